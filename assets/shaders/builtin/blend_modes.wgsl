@@ -0,0 +1,82 @@
+// Layer blend modes (operate per-channel in HDR), shared by the built-in
+// compositor and compositor hooks (#2252). `mode` is `BlendMode::as_u32`.
+
+fn blend_normal(bg: vec3f, fg: vec3f) -> vec3f {
+    return fg;
+}
+
+fn blend_add(bg: vec3f, fg: vec3f) -> vec3f {
+    return bg + fg;
+}
+
+fn blend_screen(bg: vec3f, fg: vec3f) -> vec3f {
+    return bg + fg - bg * fg;
+}
+
+fn blend_color_dodge(bg: vec3f, fg: vec3f) -> vec3f {
+    let HDR_MAX = 4.0;
+    return min(bg / max(vec3f(1.0) - fg, vec3f(0.001)), vec3f(HDR_MAX));
+}
+
+fn blend_multiply(bg: vec3f, fg: vec3f) -> vec3f {
+    return bg * fg;
+}
+
+fn blend_overlay_ch(bg: f32, fg: f32) -> f32 {
+    if bg < 0.5 {
+        return 2.0 * bg * fg;
+    } else {
+        return 1.0 - 2.0 * (1.0 - bg) * (1.0 - fg);
+    }
+}
+
+fn blend_overlay(bg: vec3f, fg: vec3f) -> vec3f {
+    return vec3f(
+        blend_overlay_ch(bg.x, fg.x),
+        blend_overlay_ch(bg.y, fg.y),
+        blend_overlay_ch(bg.z, fg.z),
+    );
+}
+
+fn blend_hard_light_ch(bg: f32, fg: f32) -> f32 {
+    if fg < 0.5 {
+        return 2.0 * bg * fg;
+    } else {
+        return 1.0 - 2.0 * (1.0 - bg) * (1.0 - fg);
+    }
+}
+
+fn blend_hard_light(bg: vec3f, fg: vec3f) -> vec3f {
+    return vec3f(
+        blend_hard_light_ch(bg.x, fg.x),
+        blend_hard_light_ch(bg.y, fg.y),
+        blend_hard_light_ch(bg.z, fg.z),
+    );
+}
+
+fn blend_difference(bg: vec3f, fg: vec3f) -> vec3f {
+    return abs(bg - fg);
+}
+
+fn blend_exclusion(bg: vec3f, fg: vec3f) -> vec3f {
+    return bg + fg - 2.0 * bg * fg;
+}
+
+fn blend_subtract(bg: vec3f, fg: vec3f) -> vec3f {
+    return max(bg - fg, vec3f(0.0));
+}
+
+fn blend(mode: u32, bg: vec3f, fg: vec3f) -> vec3f {
+    switch mode {
+        case 1u: { return blend_add(bg, fg); }
+        case 2u: { return blend_screen(bg, fg); }
+        case 3u: { return blend_color_dodge(bg, fg); }
+        case 4u: { return blend_multiply(bg, fg); }
+        case 5u: { return blend_overlay(bg, fg); }
+        case 6u: { return blend_hard_light(bg, fg); }
+        case 7u: { return blend_difference(bg, fg); }
+        case 8u: { return blend_exclusion(bg, fg); }
+        case 9u: { return blend_subtract(bg, fg); }
+        default: { return blend_normal(bg, fg); }
+    }
+}
@@ -0,0 +1,50 @@
+// Compositor hook (#2252): wraps a user WGSL file from shaders/composite/ that
+// defines
+//
+//     fn composite(uv: vec2f) -> vec4f
+//
+// It runs once per composited layer, bottom layer first, in place of the
+// built-in blend, and returns the output so far: sample_below() is everything
+// composited beneath the layer, sample_layer() the layer itself, and
+// blend_layer() the layer's own blend mode and opacity. Blend functions come
+// from blend_modes.wgsl.
+
+struct CompositeInfo {
+    // Stack index, as the layer list numbers it (0 = top)
+    layer: u32,
+    // Position among the layers composited this frame (0 = bottom)
+    slot: u32,
+    // Layers composited this frame
+    count: u32,
+    blend_mode: u32,
+    opacity: f32,
+    time: f32,
+    resolution: vec2f,
+}
+
+@group(0) @binding(0) var below_texture: texture_2d<f32>;
+@group(0) @binding(1) var below_sampler: sampler;
+@group(0) @binding(2) var layer_texture: texture_2d<f32>;
+@group(0) @binding(3) var layer_sampler: sampler;
+@group(0) @binding(4) var<uniform> info: CompositeInfo;
+
+// Explicit-level sampling, so hooks may sample inside branches.
+fn sample_below(uv: vec2f) -> vec4f {
+    return textureSampleLevel(below_texture, below_sampler, uv, 0.0);
+}
+
+fn sample_layer(uv: vec2f) -> vec4f {
+    return textureSampleLevel(layer_texture, layer_sampler, uv, 0.0);
+}
+
+// What the built-in compositor does with the layer.
+fn blend_layer(below: vec4f, layer: vec4f) -> vec4f {
+    let blended = blend(info.blend_mode, below.rgb, layer.rgb);
+    let amount = info.opacity * layer.a;
+    return vec4f(mix(below.rgb, blended, amount), max(below.a, amount));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    return composite(in.uv);
+}
@@ -0,0 +1,28 @@
+// Debug view (#2229): show one intermediate texture as raw texels. textureLoad
+// rather than a sampler, so unfilterable float formats display too.
+
+struct DebugParams {
+    channel: u32,   // 0 RGB, 1 R, 2 G, 3 B, 4 A
+    exposure: f32,
+    _pad0: f32,
+    _pad1: f32,
+}
+
+@group(0) @binding(0) var src_texture: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> params: DebugParams;
+
+@fragment
+fn fs_main(@location(0) uv: vec2f) -> @location(0) vec4f {
+    let dims = textureDimensions(src_texture);
+    let texel = min(vec2u(uv * vec2f(dims)), dims - vec2u(1u));
+    let c = textureLoad(src_texture, texel, 0);
+    var rgb = c.rgb * params.exposure;
+    switch params.channel {
+        case 1u: { rgb = vec3f(rgb.r); }
+        case 2u: { rgb = vec3f(rgb.g); }
+        case 3u: { rgb = vec3f(rgb.b); }
+        case 4u: { rgb = vec3f(c.a); }
+        default: {}
+    }
+    return vec4f(clamp(rgb, vec3f(0.0), vec3f(1.0)), 1.0);
+}
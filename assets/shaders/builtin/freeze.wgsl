@@ -0,0 +1,38 @@
+// Freeze frame (#2166): the latched composite held over or under live output.
+// params.x = hold level (1 = fully held, fades toward 0 with decay)
+// params.y = 1 to sit the held frame under live output, 0 to hold it over
+
+@group(0) @binding(0) var tex_held: texture_2d<f32>;
+@group(0) @binding(1) var samp: sampler;
+@group(0) @binding(2) var tex_live: texture_2d<f32>;
+@group(0) @binding(3) var<uniform> params: vec4f;
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) uv: vec2f,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vi: u32) -> VertexOutput {
+    // Fullscreen triangle
+    var out: VertexOutput;
+    let x = f32(i32(vi & 1u)) * 4.0 - 1.0;
+    let y = f32(i32(vi >> 1u)) * 4.0 - 1.0;
+    out.position = vec4f(x, y, 0.0, 1.0);
+    out.uv = vec2f((x + 1.0) * 0.5, (1.0 - y) * 0.5);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    let held = textureSample(tex_held, samp, in.uv);
+    let live = textureSample(tex_live, samp, in.uv);
+    let level = params.x;
+    if params.y > 0.5 {
+        // Under: the held frame shows through wherever live output is dark.
+        let cover = clamp(dot(live.rgb, vec3f(0.2126, 0.7152, 0.0722)), 0.0, 1.0);
+        let under = held * level * (1.0 - cover);
+        return vec4f(live.rgb + under.rgb, max(live.a, under.a));
+    }
+    return mix(live, held, level);
+}
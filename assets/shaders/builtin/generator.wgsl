@@ -0,0 +1,30 @@
+// Built-in generator layer (#2168): strobe, solid and gradient washes.
+// Prepended with FULLSCREEN_TRIANGLE_VS_WITH_UV. The envelope is computed on
+// the CPU; this only paints the color at the current level.
+
+struct GeneratorParams {
+    color_a: vec4f, // rgb + level in w
+    color_b: vec4f, // gradient end rgb + gradient flag in w
+    dir: vec2f,     // gradient direction (unit)
+    offset: f32,    // gradient scroll, cycles
+    aspect: f32,    // width / height
+}
+
+@group(0) @binding(0) var<uniform> g: GeneratorParams;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    var rgb = g.color_a.rgb;
+    if g.color_b.w > 0.5 {
+        // 0 → 1 corner to corner along `dir`.
+        let p = (in.uv - 0.5) * vec2f(g.aspect, 1.0);
+        let extent = abs(g.dir.x) * g.aspect + abs(g.dir.y);
+        let s = dot(p, g.dir) / extent + 0.5 + g.offset;
+        // Mirrored every other period so a scrolling gradient has no seam.
+        let t = 1.0 - abs(2.0 * fract(s * 0.5) - 1.0);
+        rgb = mix(g.color_a.rgb, g.color_b.rgb, t);
+    }
+    // Premultiplied: reads right as the only layer and in the compositor.
+    let level = g.color_a.w;
+    return vec4f(rgb * level, level);
+}
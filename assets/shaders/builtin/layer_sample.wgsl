@@ -0,0 +1,83 @@
+// Live layer → particle aux sampler (#2155).
+//
+// GPU counterpart of `image_source::sample_rgba_buffer` for a layer's HDR output:
+// every frame, each particle slot reads one jittered grid cell (or a random point)
+// of the source and writes its home position (clip space, screen-aligned so
+// particles sit on the pixels they came from), packed RGBA and luminance gradient.
+// Slots that fail the mode's test get alpha 0, which the image sims park offscreen.
+
+struct SampleUniforms {
+    max_particles: u32,
+    cols: u32,
+    rows: u32,
+    // 0 = grid, 1 = threshold (bright or edge pixels), 2 = random
+    mode: u32,
+    threshold: f32,
+    scale: f32,
+    seed: f32,
+    _pad: f32,
+}
+
+struct ParticleAux {
+    home: vec4f,
+}
+
+@group(0) @binding(0) var src_tex: texture_2d<f32>;
+@group(0) @binding(1) var src_sampler: sampler;
+@group(0) @binding(2) var<uniform> su: SampleUniforms;
+@group(0) @binding(3) var<storage, read_write> aux: array<ParticleAux>;
+
+// Same 0.4-cell jitter as the CPU grid sampler.
+const GRID_JITTER: f32 = 0.4;
+
+fn hash(n: f32) -> f32 {
+    return fract(sin(n) * 43758.5453);
+}
+
+fn luma(c: vec3f) -> f32 {
+    return dot(c, vec3f(0.299, 0.587, 0.114));
+}
+
+fn sample_rgb(uv: vec2f) -> vec3f {
+    return clamp(textureSampleLevel(src_tex, src_sampler, uv, 0.0).rgb, vec3f(0.0), vec3f(1.0));
+}
+
+@compute @workgroup_size(256)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let idx = gid.x;
+    if idx >= su.max_particles {
+        return;
+    }
+
+    var uv: vec2f;
+    if su.mode == 2u {
+        let s = f32(idx) * 1.731 + su.seed;
+        uv = vec2f(hash(s), hash(s + 17.0));
+    } else {
+        let cell = vec2f(f32(idx % su.cols), f32(idx / su.cols));
+        let j = vec2f(hash(f32(idx) * 2.17), hash(f32(idx) * 3.91)) * 2.0 - 1.0;
+        uv = (cell + 0.5 + j * GRID_JITTER) / vec2f(f32(su.cols), f32(su.rows));
+    }
+
+    let c = textureSampleLevel(src_tex, src_sampler, uv, 0.0);
+    let rgb = clamp(c.rgb, vec3f(0.0), vec3f(1.0));
+    let alpha = clamp(c.a, 0.0, 1.0);
+
+    // Central-difference luminance gradient, in the CPU sampler's 0-255 units.
+    let texel = 1.0 / vec2f(textureDimensions(src_tex));
+    let gx = luma(sample_rgb(uv + vec2f(texel.x, 0.0))) - luma(sample_rgb(uv - vec2f(texel.x, 0.0)));
+    let gy = luma(sample_rgb(uv + vec2f(0.0, texel.y))) - luma(sample_rgb(uv - vec2f(0.0, texel.y)));
+    let gradient = length(vec2f(gx, gy)) * 255.0;
+
+    var keep = alpha > 0.04;
+    if su.mode == 1u {
+        keep = keep && max(luma(rgb), gradient / 255.0) > su.threshold;
+    }
+
+    if !keep {
+        aux[idx].home = vec4f(0.0);
+        return;
+    }
+    let pos = vec2f(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0) * su.scale;
+    aux[idx].home = vec4f(pos, bitcast<f32>(pack4x8unorm(vec4f(rgb, alpha))), gradient);
+}
@@ -0,0 +1,67 @@
+// Output master grade (#2165): brightness, contrast, gamma and blackout,
+// prepended to the post composite and the post-off blit. Both then apply the
+// output's own calibration (#2237) and finish with the blue-noise dither (#2236).
+
+struct MasterParams {
+    brightness: f32,
+    contrast: f32,
+    inv_gamma: f32,
+    level: f32,        // 0 = blackout
+    dither: f32,       // spread as a fraction of full scale, 0 = off
+    srgb_target: f32,  // 1 = the target encodes sRGB on write
+    _pad0: f32,
+    _pad1: f32,
+}
+
+// One output's trims (#2237)
+struct Calibration {
+    gain: vec3f,
+    inv_gamma: f32,
+    offset: vec3f,
+    saturation: f32,
+}
+
+fn master_grade(color: vec3f, m: MasterParams) -> vec3f {
+    var c = color * m.brightness;
+    c = (c - 0.5) * m.contrast + 0.5;
+    c = pow(max(c, vec3f(0.0)), vec3f(m.inv_gamma));
+    return c * m.level;
+}
+
+// Trims the graded signal as the output would show it, clipped to 0..1:
+// saturation around Rec. 709 luma, then gain, offset and gamma. The offset is
+// scaled by the master level so blackout and fades still reach black.
+fn output_calibrate(color: vec3f, k: Calibration, m: MasterParams) -> vec3f {
+    let shown = clamp(color, vec3f(0.0), vec3f(1.0));
+    let luma = dot(shown, vec3f(0.2126, 0.7152, 0.0722));
+    var c = mix(vec3f(luma), shown, k.saturation);
+    c = c * k.gain + k.offset * m.level;
+    return clamp(pow(max(c, vec3f(0.0)), vec3f(k.inv_gamma)), vec3f(0.0), vec3f(1.0));
+}
+
+fn master_encode_srgb(c: vec3f) -> vec3f {
+    return select(1.055 * pow(c, vec3f(1.0 / 2.4)) - 0.055, c * 12.92, c <= vec3f(0.0031308));
+}
+
+fn master_decode_srgb(c: vec3f) -> vec3f {
+    return select(pow((c + 0.055) / 1.055, vec3f(2.4)), c / 12.92, c <= vec3f(0.04045));
+}
+
+// One blue-noise threshold per pixel, tiled across the output
+fn master_noise(tile: texture_2d<f32>, pos: vec4f) -> f32 {
+    return textureLoad(tile, vec2u(pos.xy) % textureDimensions(tile), 0).r;
+}
+
+// Spread `c` by up to half the dither either way before the target rounds it
+// to its steps. On an sRGB target that happens after encoding, where the
+// steps are. Black stays black so blackout and letterboxing stay clean.
+fn master_dither(c: vec3f, noise: f32, m: MasterParams) -> vec3f {
+    if m.dither <= 0.0 || max(c.r, max(c.g, c.b)) <= 0.0 {
+        return c;
+    }
+    let d = (noise - 0.5) * m.dither;
+    if m.srgb_target > 0.5 {
+        return master_decode_srgb(clamp(master_encode_srgb(c) + d, vec3f(0.0), vec3f(1.0)));
+    }
+    return clamp(c + d, vec3f(0.0), vec3f(1.0));
+}
@@ -1,18 +1,76 @@
 // Media blit shader — samples Rgba8UnormSrgb texture with letterbox transform,
 // outputs to Rgba16Float HDR target.
+//
+// projection 1/2 treat the texture as an equirectangular (360°) frame and cast a
+// ray per pixel instead: 1 = rectilinear view through a virtual camera, 2 = fisheye
+// dome master with the zenith at the centre and "front" at the top edge.
+
+const PI: f32 = 3.14159265;
 
 struct MediaUniforms {
     scale: vec2f,
     offset: vec2f,
     mirror: u32,
+    projection: u32,
+    yaw: f32,
+    pitch: f32,
+    fov: f32,
+    aspect: f32,
 }
 
 @group(0) @binding(0) var media_texture: texture_2d<f32>;
 @group(0) @binding(1) var media_sampler: sampler;
 @group(0) @binding(2) var<uniform> mu: MediaUniforms;
 
+// Pitch about X (positive looks up), then yaw about Y (positive turns right).
+fn orient(d: vec3f) -> vec3f {
+    let cp = cos(mu.pitch);
+    let sp = sin(mu.pitch);
+    let p = vec3f(d.x, d.y * cp + d.z * sp, -d.y * sp + d.z * cp);
+    let cy = cos(mu.yaw);
+    let sy = sin(mu.yaw);
+    return vec3f(p.x * cy + p.z * sy, p.y, -p.x * sy + p.z * cy);
+}
+
+fn equirect_uv(d: vec3f) -> vec2f {
+    let lon = atan2(d.x, d.z);
+    let lat = asin(clamp(d.y, -1.0, 1.0));
+    return vec2f(0.5 + lon / (2.0 * PI), 0.5 - lat / PI);
+}
+
 @fragment
 fn fs_main(@location(0) uv: vec2f) -> @location(0) vec4f {
+    if mu.projection != 0u {
+        var dir: vec3f;
+        if mu.projection == 1u {
+            let t = tan(mu.fov * 0.5);
+            let ndc = vec2f((uv.x * 2.0 - 1.0) * mu.aspect, 1.0 - uv.y * 2.0);
+            dir = normalize(vec3f(ndc * t, 1.0));
+        } else {
+            // Fit the dome circle to the shorter side.
+            var q = vec2f(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0);
+            if mu.aspect > 1.0 {
+                q.x *= mu.aspect;
+            } else {
+                q.y /= mu.aspect;
+            }
+            let r = length(q);
+            if r > 1.0 {
+                return vec4f(0.0);
+            }
+            let theta = r * mu.fov * 0.5;
+            let around = select(vec2f(0.0), q / r, r > 1e-5);
+            dir = vec3f(sin(theta) * around.x, cos(theta), sin(theta) * around.y);
+        }
+        var e = equirect_uv(orient(dir));
+        if mu.mirror != 0u {
+            e.x = 1.0 - e.x;
+        }
+        // Level 0: the per-pixel ray jumps across the longitude seam, so implicit
+        // derivatives there are meaningless.
+        return textureSampleLevel(media_texture, media_sampler, e, 0.0);
+    }
+
     var media_uv = (uv - mu.offset) / mu.scale;
     if media_uv.x < 0.0 || media_uv.x > 1.0 || media_uv.y < 0.0 || media_uv.y > 1.0 {
         return vec4f(0.0);
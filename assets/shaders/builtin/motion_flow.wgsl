@@ -0,0 +1,93 @@
+// Motion field (#2154): a coarse optical-flow estimate of a media layer's output.
+//
+// cs_luma downsamples the source into a FLOW_W x FLOW_H luma grid; cs_flow then
+// solves Lucas-Kanade over a 5x5 window of that grid against last frame's luma.
+// The flow texture holds rg = displacement in uv per frame (y down), b = mean
+// absolute frame difference, a = flow magnitude in grid cells. Per-cell totals
+// are accumulated in fixed point for the CPU-side motion energy readback.
+
+const FLOW_W: u32 = 160u;
+const FLOW_H: u32 = 90u;
+const RADIUS: i32 = 2;
+const FIXED: f32 = 1024.0;
+// Cells whose window changed less than this are treated as sensor noise.
+const NOISE_FLOOR: f32 = 0.008;
+
+@group(0) @binding(0) var src_tex: texture_2d<f32>;
+@group(0) @binding(1) var src_sampler: sampler;
+@group(0) @binding(2) var<storage, read_write> luma_cur: array<f32>;
+@group(0) @binding(3) var<storage, read> luma_prev: array<f32>;
+@group(0) @binding(4) var flow_out: texture_storage_2d<rgba16float, write>;
+// [0] sum magnitude, [1] sum flow x, [2] sum flow y, [3] sum frame difference.
+@group(0) @binding(5) var<storage, read_write> stats: array<atomic<i32>, 4>;
+
+fn luma(c: vec3f) -> f32 {
+    return dot(clamp(c, vec3f(0.0), vec3f(1.0)), vec3f(0.2126, 0.7152, 0.0722));
+}
+
+fn cell(p: vec2i) -> u32 {
+    let q = clamp(p, vec2i(0), vec2i(i32(FLOW_W) - 1, i32(FLOW_H) - 1));
+    return u32(q.y) * FLOW_W + u32(q.x);
+}
+
+@compute @workgroup_size(8, 8)
+fn cs_luma(@builtin(global_invocation_id) id: vec3u) {
+    if id.x >= FLOW_W || id.y >= FLOW_H {
+        return;
+    }
+    let size = vec2f(1.0 / f32(FLOW_W), 1.0 / f32(FLOW_H));
+    let base = (vec2f(id.xy) + 0.25) * size;
+    var sum = 0.0;
+    for (var j = 0u; j < 2u; j++) {
+        for (var i = 0u; i < 2u; i++) {
+            let uv = base + vec2f(f32(i), f32(j)) * 0.5 * size;
+            sum += luma(textureSampleLevel(src_tex, src_sampler, uv, 0.0).rgb);
+        }
+    }
+    luma_cur[id.y * FLOW_W + id.x] = sum * 0.25;
+}
+
+@compute @workgroup_size(8, 8)
+fn cs_flow(@builtin(global_invocation_id) id: vec3u) {
+    if id.x >= FLOW_W || id.y >= FLOW_H {
+        return;
+    }
+    let c = vec2i(id.xy);
+    var ixx = 0.0;
+    var ixy = 0.0;
+    var iyy = 0.0;
+    var ixt = 0.0;
+    var iyt = 0.0;
+    var diff = 0.0;
+    for (var dy = -RADIUS; dy <= RADIUS; dy++) {
+        for (var dx = -RADIUS; dx <= RADIUS; dx++) {
+            let p = c + vec2i(dx, dy);
+            let ix = (luma_cur[cell(p + vec2i(1, 0))] - luma_cur[cell(p - vec2i(1, 0))]) * 0.5;
+            let iy = (luma_cur[cell(p + vec2i(0, 1))] - luma_cur[cell(p - vec2i(0, 1))]) * 0.5;
+            let it = luma_cur[cell(p)] - luma_prev[cell(p)];
+            ixx += ix * ix;
+            ixy += ix * iy;
+            iyy += iy * iy;
+            ixt += ix * it;
+            iyt += iy * it;
+            diff += abs(it);
+        }
+    }
+    let taps = f32((2 * RADIUS + 1) * (2 * RADIUS + 1));
+    diff /= taps;
+
+    // Solve [ixx ixy; ixy iyy] v = -[ixt; iyt]. Flat or static windows stay at rest.
+    var v = vec2f(0.0);
+    let det = ixx * iyy - ixy * ixy;
+    if det > 1e-6 && diff > NOISE_FLOOR {
+        v = vec2f(ixy * iyt - iyy * ixt, ixy * ixt - ixx * iyt) / det;
+        v = clamp(v, vec2f(-4.0), vec2f(4.0));
+    }
+    let mag = length(v);
+
+    textureStore(flow_out, id.xy, vec4f(v.x / f32(FLOW_W), v.y / f32(FLOW_H), diff, mag));
+    atomicAdd(&stats[0], i32(mag * FIXED));
+    atomicAdd(&stats[1], i32(v.x * FIXED));
+    atomicAdd(&stats[2], i32(v.y * FIXED));
+    atomicAdd(&stats[3], i32(diff * FIXED));
+}
@@ -0,0 +1,15 @@
+// Live layer → obstacle map (#2160).
+//
+// Downsamples a layer's HDR output into the particle obstacle texture with
+// luminance in alpha, so bright areas of the layer become the surface the
+// obstacle collision modes (bounce / stick / flow around / contain) act on.
+// Prefixed with the fullscreen-triangle vertex shader at pipeline build.
+
+@group(0) @binding(0) var src_texture: texture_2d<f32>;
+@group(0) @binding(1) var src_sampler: sampler;
+
+@fragment
+fn fs_main(@location(0) uv: vec2f) -> @location(0) vec4f {
+    let rgb = clamp(textureSample(src_texture, src_sampler, uv).rgb, vec3f(0.0), vec3f(1.0));
+    return vec4f(rgb, dot(rgb, vec3f(0.299, 0.587, 0.114)));
+}
@@ -13,7 +13,8 @@ struct RenderUniforms {
     frame_index: u32,
     trail_length: u32,
     trail_width: f32,
-    _pad: vec2f,
+    trail_fade: f32,   // alpha falloff exponent along the ribbon
+    _pad: f32,
 }
 
 @group(0) @binding(0) var<storage, read> pos_life: array<vec4f>;
@@ -124,7 +125,7 @@ fn vs_main(
     out.trail_frac = frac;
 
     // Color: particle color with alpha tapering along trail
-    let trail_alpha = (1.0 - frac) * (1.0 - frac); // quadratic falloff
+    let trail_alpha = pow(1.0 - frac, max(ru.trail_fade, 0.01)); // 2 = quadratic
     let avg_alpha = mix(point_a.w, point_b.w, 0.5);
     out.color = vec4f(p_color.rgb, avg_alpha * trail_alpha * 0.5);
 
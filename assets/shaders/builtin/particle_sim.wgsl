@@ -100,6 +100,13 @@ fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
         let slot = emit_claim();
         if slot < u.emit_count {
             p = emit_particle(idx);
+            // Seed this slot's trail ring at the spawn point so the new
+            // ribbon never reaches back to where the previous occupant died.
+            if u.trail_length >= 2u {
+                for (var s = 0u; s < u.trail_length; s++) {
+                    trail_buffer[idx * u.trail_length + s] = vec4f(p.pos_life.xy, p.vel_size.w, 0.0);
+                }
+            }
             write_particle(idx, p);
             mark_alive(idx);
         } else {
@@ -158,6 +165,9 @@ fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
     p.color = vec4f(col, alpha);
     p.flags.x = new_age;
 
+    // Ribbon history (no-op unless the .pfx sets trail_length >= 2)
+    trail_write(idx, vec4f(pos, size, alpha));
+
     write_particle(idx, p);
     mark_alive(idx);
 }
@@ -49,14 +49,19 @@ fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
 
     let key_i = keys[i];
     let key_p = keys[partner];
+    let idx_i = indices[i];
+    let idx_p = indices[partner];
 
-    let should_swap = select(key_i < key_p, key_i > key_p, ascending);
+    // Order by (key, slot index): alive indices arrive in a different order
+    // every frame, so equal keys need a tie-break or same-size alpha sprites
+    // swap draw order and flicker (#2163).
+    let i_before_p = key_i < key_p || (key_i == key_p && idx_i < idx_p);
+    let p_before_i = key_p < key_i || (key_p == key_i && idx_p < idx_i);
+    let should_swap = select(i_before_p, p_before_i, ascending);
 
     if should_swap {
         keys[i] = key_p;
         keys[partner] = key_i;
-        let idx_i = indices[i];
-        let idx_p = indices[partner];
         indices[i] = idx_p;
         indices[partner] = idx_i;
     }
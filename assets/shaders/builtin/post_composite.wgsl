@@ -1,5 +1,7 @@
 // Post-processing composite shader.
-// Combines: scene + bloom, chromatic aberration, ACES tonemap, vignette, film grain.
+// Combines: scene + bloom, chromatic aberration, exposure and tonemap (ACES,
+// Reinhard, Hable or linear), vignette, film grain, then the output master grade,
+// the output's calibration and dither.
 
 @group(0) @binding(0) var scene_texture: texture_2d<f32>;
 @group(0) @binding(1) var scene_sampler: sampler;
@@ -13,10 +15,18 @@ struct PostParams {
     grain_intensity: f32,  // film grain (flatness-driven)
     time: f32,
     rms: f32,
-    alpha_from_luma: f32,
-    tonemap_mode: f32,     // 0 = ACES, 1 = linear passthrough (SuperSplat-faithful)
+    alpha_mode: f32,       // 0 = opaque, 1 = from brightness, 2 = scene alpha (key and fill)
+    tonemap_mode: f32,     // 0 = ACES, 1 = linear passthrough (SuperSplat-faithful), 2 = Reinhard, 3 = Hable
+    exposure: f32,         // linear multiplier
+    white_point: f32,      // HDR level mapped to full white
+    _pad0: f32,
+    _pad1: f32,
 }
 @group(0) @binding(4) var<uniform> post: PostParams;
+// Output master (#2165), from master_grade.wgsl
+@group(0) @binding(5) var<uniform> master: MasterParams;
+@group(0) @binding(6) var blue_noise: texture_2d<f32>;
+@group(0) @binding(7) var<uniform> calibration: Calibration;
 
 // ACES filmic tonemapping
 fn aces_tonemap(x: vec3f) -> vec3f {
@@ -28,6 +38,26 @@ fn aces_tonemap(x: vec3f) -> vec3f {
     return clamp((x * (a * x + b)) / (x * (c * x + d) + e), vec3f(0.0), vec3f(1.0));
 }
 
+// Extended Reinhard: reaches 1.0 at the white point
+fn reinhard_tonemap(x: vec3f, white: f32) -> vec3f {
+    return x * (1.0 + x / (white * white)) / (1.0 + x);
+}
+
+// Hable / Uncharted 2 filmic curve
+fn hable_curve(x: vec3f) -> vec3f {
+    let a = 0.15;
+    let b = 0.50;
+    let c = 0.10;
+    let d = 0.20;
+    let e = 0.02;
+    let f = 0.30;
+    return ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f;
+}
+
+fn hable_tonemap(x: vec3f, white: f32) -> vec3f {
+    return hable_curve(x * 2.0) / hable_curve(vec3f(white));
+}
+
 // Hash for film grain
 fn hash_grain(p: vec2f) -> f32 {
     var p3 = fract(vec3f(p.x, p.y, p.x) * 0.1031);
@@ -36,18 +66,16 @@ fn hash_grain(p: vec2f) -> f32 {
 }
 
 @fragment
-fn fs_main(@location(0) uv: vec2f) -> @location(0) vec4f {
+fn fs_main(@builtin(position) pos: vec4f, @location(0) uv: vec2f) -> @location(0) vec4f {
     let ca = post.ca_intensity;
 
     // Chromatic aberration: offset R and B channels
-    var color: vec3f;
+    let scene = textureSample(scene_texture, scene_sampler, uv);
+    var color = scene.rgb;
     if ca > 0.001 {
         let ca_offset = (uv - 0.5) * ca;
         color.r = textureSample(scene_texture, scene_sampler, uv + ca_offset).r;
-        color.g = textureSample(scene_texture, scene_sampler, uv).g;
         color.b = textureSample(scene_texture, scene_sampler, uv - ca_offset).b;
-    } else {
-        color = textureSample(scene_texture, scene_sampler, uv).rgb;
     }
 
     // Bloom mix (RMS modulates intensity)
@@ -55,12 +83,20 @@ fn fs_main(@location(0) uv: vec2f) -> @location(0) vec4f {
     let bloom_mix = post.bloom_intensity * (0.7 + post.rms * 0.6);
     color += bloom * bloom_mix;
 
-    // Tonemap: ACES (house look) or linear passthrough (SuperSplat-faithful,
-    // preserves raw sRGB contrast — no highlight compression / dark lift).
+    // Tonemap: ACES (house look), linear passthrough (SuperSplat-faithful,
+    // preserves raw sRGB contrast — no highlight compression / dark lift),
+    // Reinhard or Hable. ACES is scaled so the white point lands on 1.0; its
+    // own curve already clips by ~7.2, so the default leaves it unchanged.
+    color *= post.exposure;
+    let white = post.white_point;
     if post.tonemap_mode < 0.5 {
-        color = aces_tonemap(color);
-    } else {
+        color = clamp(aces_tonemap(color) / aces_tonemap(vec3f(white)), vec3f(0.0), vec3f(1.0));
+    } else if post.tonemap_mode < 1.5 {
         color = clamp(color, vec3f(0.0), vec3f(1.0));
+    } else if post.tonemap_mode < 2.5 {
+        color = clamp(reinhard_tonemap(color, white), vec3f(0.0), vec3f(1.0));
+    } else {
+        color = clamp(hable_tonemap(color, white), vec3f(0.0), vec3f(1.0));
     }
 
     // Vignette
@@ -72,8 +108,14 @@ fn fs_main(@location(0) uv: vec2f) -> @location(0) vec4f {
     let grain = (hash_grain(uv * 1000.0 + post.time * 100.0) - 0.5) * post.grain_intensity;
     color += vec3f(grain);
 
-    let final_color = clamp(color, vec3f(0.0), vec3f(1.0));
+    let graded = output_calibrate(master_grade(color, master), calibration, master);
+    let final_color = master_dither(graded, master_noise(blue_noise, pos), master);
     let brightness = max(final_color.r, max(final_color.g, final_color.b));
-    let alpha = select(1.0, clamp(brightness * 2.0, 0.0, 1.0), post.alpha_from_luma > 0.5);
+    var alpha = 1.0;
+    if post.alpha_mode > 1.5 {
+        alpha = clamp(scene.a, 0.0, 1.0);
+    } else if post.alpha_mode > 0.5 {
+        alpha = clamp(brightness * 2.0, 0.0, 1.0);
+    }
     return vec4f(final_color, alpha);
 }
@@ -0,0 +1,14 @@
+// Post-processing off: blit the scene through the output master only.
+
+@group(0) @binding(0) var src_texture: texture_2d<f32>;
+@group(0) @binding(1) var src_sampler: sampler;
+@group(0) @binding(2) var<uniform> master: MasterParams;
+@group(0) @binding(3) var blue_noise: texture_2d<f32>;
+@group(0) @binding(4) var<uniform> calibration: Calibration;
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4f, @location(0) uv: vec2f) -> @location(0) vec4f {
+    let src = textureSample(src_texture, src_sampler, uv);
+    let graded = output_calibrate(master_grade(src.rgb, master), calibration, master);
+    return vec4f(master_dither(graded, master_noise(blue_noise, pos), master), src.a);
+}
@@ -0,0 +1,16 @@
+// Per-layer trails (#2173): the layer's previous trailed output, faded by
+// params.x and kept wherever it is brighter than the current frame. Lighten
+// rather than mix, so opaque effects and media trail without dimming.
+
+@group(0) @binding(0) var prev_texture: texture_2d<f32>;
+@group(0) @binding(1) var prev_sampler: sampler;
+@group(0) @binding(2) var cur_texture: texture_2d<f32>;
+@group(0) @binding(3) var cur_sampler: sampler;
+@group(0) @binding(4) var<uniform> params: vec4f;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    let cur = textureSample(cur_texture, cur_sampler, in.uv);
+    let prev = textureSample(prev_texture, prev_sampler, in.uv) * params.x;
+    return max(cur, prev);
+}
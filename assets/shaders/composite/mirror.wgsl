@@ -0,0 +1,17 @@
+// Per-layer kaleidoscope: the bottom layer is mirrored once, and each layer
+// above it gets two more segments, slowly turning. Layers keep their blend
+// mode and opacity.
+
+const PI: f32 = 3.14159265;
+
+fn composite(uv: vec2f) -> vec4f {
+    let aspect = info.resolution.x / max(info.resolution.y, 1.0);
+    let p = (uv - 0.5) * vec2f(aspect, 1.0);
+    let segments = f32(2u + 2u * info.slot);
+    let wedge = 2.0 * PI / segments;
+    let angle = atan2(p.y, p.x) + info.time * 0.05 * f32(info.slot);
+    // Fold into one wedge, mirrored about its middle
+    let folded = abs(angle - wedge * round(angle / wedge));
+    let q = vec2f(cos(folded), sin(folded)) * length(p) / vec2f(aspect, 1.0) + 0.5;
+    return blend_layer(sample_below(uv), sample_layer(q));
+}
@@ -0,0 +1,14 @@
+// Mosaic: a grid with one whole layer per cell, bottom layer top left, like
+// a wall of monitors. Empty cells stay black.
+
+fn composite(uv: vec2f) -> vec4f {
+    let cols = u32(ceil(sqrt(f32(info.count))));
+    let rows = (info.count + cols - 1u) / cols;
+    let grid = vec2f(f32(cols), f32(rows));
+    let cell = min(floor(uv * grid), grid - 1.0);
+    if u32(cell.y) * cols + u32(cell.x) != info.slot {
+        return sample_below(uv);
+    }
+    let layer = sample_layer(uv * grid - cell);
+    return vec4f(layer.rgb * info.opacity, 1.0);
+}
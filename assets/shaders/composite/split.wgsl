@@ -0,0 +1,13 @@
+// Split screen: each layer gets a vertical strip, the bottom layer on the
+// left, showing the middle of its picture at its own opacity.
+
+fn composite(uv: vec2f) -> vec4f {
+    let n = f32(info.count);
+    let strip = min(floor(uv.x * n), n - 1.0);
+    if u32(strip) != info.slot {
+        return sample_below(uv);
+    }
+    let center = (strip + 0.5) / n;
+    let layer = sample_layer(vec2f(uv.x - center + 0.5, uv.y));
+    return vec4f(layer.rgb * info.opacity, 1.0);
+}
@@ -69,6 +69,31 @@ struct PhosphorUniforms {
     timbre_flux: f32,
     // A13b (#1801) per-band pan, sub_bass..brilliance. 0.5 = centred.
     band_pan: array<vec4f, 2>,
+    // Motion field (#2154) of the active or topmost media layer.
+    motion: f32,
+    motion_x: f32,
+    motion_y: f32,
+    frame_diff: f32,
+    // Render seed (#2174).
+    seed: f32,
+    // Second input (#2217): room mic crowd level and onset.
+    mic_rms: f32,
+    mic_onset: f32,
+    _pad_seed2: f32,
+    // Dynamics (#2218): crest factor and the impact envelope.
+    crest: f32,
+    impact: f32,
+    _pad_dynamics0: f32,
+    _pad_dynamics1: f32,
+    // Band envelopes (#2219): fast and slow followers of bass, mid and high.
+    env_bass_fast: f32,
+    env_bass_slow: f32,
+    env_mid_fast: f32,
+    env_mid_slow: f32,
+    env_high_fast: f32,
+    env_high_slow: f32,
+    _pad_env0: f32,
+    _pad_env1: f32,
 }
 
 @group(0) @binding(0) var<uniform> u: PhosphorUniforms;
@@ -153,6 +153,14 @@ struct ParticleUniforms {
     // Per-band pan, same order and convention as `pan`, for the 7 bands sub_bass..brilliance.
     // A band carrying no energy holds 0.5. Read it with band_pan(i).
     band_pan: array<vec4f, 2>,
+
+    // Force fields from the .pfx `forces` list (#2158 ABI bump, 944 -> 1088 B).
+    force_count: u32,        // point forces in use (0..8)
+    force_vortex_mask: u32,  // bit i = point force i swirls instead of pulling
+    force_curl: f32,         // curl-noise strength
+    force_curl_scale: f32,   // curl-noise spatial frequency
+    // xy = position, z = signed strength (negative repels), w = falloff radius (0 = unbounded)
+    forces: array<vec4f, 8>,
 }
 
 // Access effect param by index (mirrors fragment shader's param() function).
@@ -535,7 +543,7 @@ fn fbm_turbulence_2d(p: vec2f, octaves: u32, lacunarity: f32, persistence: f32)
 }
 
 // Apply all builtin forces to a velocity. Call from simulation shaders.
-// Applies: gravity → wind → drag → noise (FBM or legacy hash) → attraction → vortex → flow field.
+// Applies: gravity → wind → drag → noise (FBM or legacy hash) → attraction → vortex → .pfx forces → flow field.
 fn apply_builtin_forces(pos: vec2f, vel: vec2f, dt: f32) -> vec2f {
     var v = vel;
 
@@ -588,6 +596,29 @@ fn apply_builtin_forces(pos: vec2f, vel: vec2f, dt: f32) -> vec2f {
         }
     }
 
+    // .pfx `forces` list (#2158): attractors / repulsors / vortices, then curl noise
+    for (var i = 0u; i < u.force_count; i++) {
+        let f = u.forces[i];
+        let offset = f.xy - pos;
+        let dist = length(offset);
+        if dist > 0.001 {
+            var falloff = 1.0;
+            if f.w > 0.0 {
+                falloff = smoothstep(f.w, 0.0, dist);
+            }
+            let dir = offset / dist;
+            if (u.force_vortex_mask & (1u << i)) != 0u {
+                v += vec2f(dir.y, -dir.x) * f.z * falloff * dt;
+            } else {
+                v += dir * f.z * falloff * dt;
+            }
+        }
+    }
+    if u.force_curl != 0.0 {
+        let curl_pos = pos * u.force_curl_scale + vec2f(u.time * u.noise_speed);
+        v += fbm_curl_2d(curl_pos, 3u, 2.0, 0.5) * u.force_curl * dt;
+    }
+
     // Flow field (3D texture)
     v += sample_flow_field(pos);
 
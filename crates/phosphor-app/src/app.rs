@@ -1,17 +1,26 @@
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use winit::window::Window;
 
 use crate::audio::AudioSystem;
+use crate::audio::envelope::EnvelopeFollowers;
 use crate::bindings::bus::BindingBus;
 use crate::effect::EffectLoader;
 use crate::effect::format::PostProcessDef;
+use crate::effect::history::ShaderHistory;
 use crate::effect::loader::assets_dir;
+use crate::effect::post_source::PostSource;
 use crate::gpu::audio_textures::{AudioTextures, WAVEFORM_PEEK};
+use crate::gpu::capabilities::GpuCapabilities;
 use crate::gpu::compositor::Compositor;
-use crate::gpu::layer::{BlendMode, EffectLayer, Layer, LayerContent, LayerInfo, LayerStack};
+use crate::gpu::debug_view::{DebugTexture, DebugTextureInfo, DebugViewState, DebugViewer};
+use crate::gpu::error_scope::{ErrorScope, GpuError, GpuErrorThrottle};
+use crate::gpu::frame_guard::{FrameGuard, LayerTimer};
+use crate::gpu::generator::{GeneratorDef, GeneratorLayer};
+use crate::gpu::layer::{EffectLayer, Layer, LayerContent, LayerInfo, LayerStack};
+use crate::gpu::motion::MotionField;
 use crate::gpu::particle::ParticleSystem;
 use crate::gpu::pass_executor::PassExecutor;
 use crate::gpu::placeholder::PlaceholderTexture;
@@ -19,33 +28,75 @@ use crate::gpu::postprocess::PostProcessChain;
 use crate::gpu::render_target::PingPongTarget;
 use crate::gpu::shader_compiler::{CompileResult, ShaderCompiler};
 use crate::gpu::{GpuContext, ShaderPipeline, ShaderUniforms, UniformBuffer};
+use crate::installation::{LocalClock, ScheduleEvent, ScheduleWatch};
 use crate::media::MediaLayer;
 #[cfg(feature = "webcam")]
 use crate::media::WebcamBackend;
+use crate::media::types::AudioCue;
+use crate::media::watch::MediaFolderWatcher;
 use crate::midi::MidiSystem;
 use crate::midi::clock::MidiClock;
 use crate::midi::types::TriggerAction;
+use crate::monitor::{MonitorChange, OutputMonitor};
 use crate::osc::OscSystem;
+use crate::osc::address_map::{MapFormat, MapLayer, OscAddress};
 use crate::params::{ParamStore, ParamValue};
-use crate::preset::PresetStore;
-use crate::preset::loader::{MediaDecodeResult, PresetLoader};
+use crate::preset::attract::{AttractEvent, AttractWatch};
+use crate::preset::loader::{MediaDecodeResult, PresetLoader, PresetLoadingState};
+use crate::preset::session::{self, Session};
 use crate::preset::store::LayerPreset;
+use crate::preset::{PresetRef, PresetStore};
+use crate::recording::stamp::FrameStamp;
 use crate::scene::SceneStore;
 use crate::scene::timeline::{Timeline, TimelineEvent};
 use crate::scene::transition::TransitionRenderer;
 use crate::scene::types::AdvanceMode;
-use crate::settings::SettingsConfig;
+use crate::settings::{SettingsConfig, StartupMode, push_recent};
 use crate::shader::ShaderWatcher;
 use crate::ui::EguiOverlay;
-use crate::ui::panels::shader_editor::ShaderEditorState;
+use crate::ui::panels::shader_editor::{EditorFileType, ShaderEditorState};
+use crate::watchdog::{Watchdog, WatchdogAction, WatchdogEvent};
 use crate::web::WebSystem;
+use crate::web::types::{RpcError, WebAction, WebQuery, WebRequest, WsInMessage};
+
+/// How long to wait between device-loss recovery attempts (#2176).
+const DEVICE_RECOVERY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Lost or outdated frames in a row before the surface is rebuilt rather
+/// than reconfigured (#2231).
+const SURFACE_RECREATE_AFTER: u32 = 3;
 
 pub struct App {
     pub gpu: GpuContext,
     pub start_time: Instant,
     pub last_frame: Instant,
     pub frame_count: u32,
+    /// `seed` uniform outside deterministic mode, picked at launch (#2174).
+    session_seed: u32,
+    /// When device-loss recovery was last tried, to space out retries (#2176).
+    last_recovery_attempt: Option<Instant>,
+    /// Lost or outdated surface frames in a row (#2231).
+    surface_failures: u32,
+    /// The monitor the fullscreen output belongs to (#2231).
+    pub output_monitor: OutputMonitor,
+    /// Last session check, and what was last written (#2232).
+    last_session_save: Option<Instant>,
+    session_json: String,
+    /// Installation mode (#2233): opening hours, the clock they're read
+    /// from, and when to try a dead webcam again.
+    schedule: ScheduleWatch,
+    /// Controller heartbeat watchdog (#2240).
+    watchdog: Watchdog,
+    local_clock: LocalClock,
+    #[cfg_attr(not(feature = "webcam"), allow(dead_code))]
+    webcam_retry_at: Option<Instant>,
+    /// Panels and shortcuts unlocked with Ctrl+Shift+D until the next launch.
+    pub installation_unlocked: bool,
     pub shader_watcher: ShaderWatcher,
+    /// New files in the watch folders (#2246); `None` without any.
+    media_watcher: Option<MediaFolderWatcher>,
+    /// The preset crossfade (#2250) moved and its blend isn't on screen yet.
+    crossfade_pending: bool,
     pub shader_compiler: ShaderCompiler,
     pub audio: AudioSystem,
     pub egui_overlay: EguiOverlay,
@@ -66,6 +117,8 @@ pub struct App {
     // Presets
     pub preset_store: PresetStore,
     pub preset_loader: PresetLoader,
+    /// Decoded media kept in RAM across preset switches (shared with the loader).
+    pub media_cache: crate::media::cache::MediaCache,
     // Settings
     pub settings: SettingsConfig,
     // Layers
@@ -78,10 +131,27 @@ pub struct App {
     /// `ParticleSystem` (where the particle buffers are reachable).
     pub volumetric_enabled: bool,
     pub volumetric_params: crate::gpu::volumetric::VolumetricParams,
+    /// Output master (#2165): brightness / contrast / gamma and blackout,
+    /// applied after post-processing.
+    pub master: crate::gpu::master::MasterState,
+    /// Freeze frame (#2166): latched composite held over or under live output.
+    pub freeze: crate::gpu::freeze::FreezeFrame,
+    freeze_renderer: Option<crate::gpu::freeze::FreezeRenderer>,
+    /// Sustained-silence action (#2213) and the preset to return to after an
+    /// idle-preset switch.
+    silence: crate::audio::silence::SilenceWatch,
+    silence_return: Option<u64>,
+    /// Attract mode (#2239) and the stack it puts back when it stops.
+    attract: AttractWatch,
+    attract_return: Option<Session>,
+    /// Last key, click or pointer move in the window, for attract mode.
+    last_input: Instant,
     pub placeholder: PlaceholderTexture,
     /// A17 audio textures (waveform / spectrum / spectrogram) filling the reserved
     /// bind-group slots; refreshed each frame in `update` (#1468).
     pub audio_textures: AudioTextures,
+    /// Optical flow of a media layer, exposed as uniforms and the `"flow"` input (#2154).
+    pub motion: MotionField,
     /// Wall-clock of the last mel-column commit, and the EMA of the inter-commit
     /// interval — used to extrapolate a fractional scroll phase (0..1) between
     /// commits so the spectrogram terrain scrolls continuously (#1508 Strata Phase 1b).
@@ -94,6 +164,7 @@ pub struct App {
     pub ndi: crate::ndi::NdiSystem,
     // Video recording (always available — ffmpeg is a subprocess)
     pub recording: crate::recording::RecordingSystem,
+    pub screenshot: crate::recording::screenshot::Screenshot,
     // Scenes
     pub scene_store: SceneStore,
     pub timeline: Timeline,
@@ -112,12 +183,46 @@ pub struct App {
     pub morph_to_opacities: Option<Vec<f32>>,
     // Shader editor
     pub shader_editor: ShaderEditorState,
+    /// Saved and compiled snapshots of edited shaders (#2189).
+    pub shader_history: ShaderHistory,
     // Binding matrix modal
     pub binding_matrix: crate::ui::panels::binding_matrix::BindingMatrixState,
+    // Cue panel window (#2221)
+    pub cue_panel_open: bool,
+    // Performance panel (#2226)
+    pub perf_panel_open: bool,
+    /// Step of the first-run tour on screen (#2242), if it is.
+    pub tour_step: Option<usize>,
+    // Uniform inspector window (#2228)
+    pub uniforms_panel_open: bool,
+    /// Intermediate texture viewer (#2229); the pipeline is built on first use.
+    pub debug_view: DebugViewState,
+    debug_viewer: Option<DebugViewer>,
+    /// Live GPU resource counts, sampled once a second for the performance panel.
+    pub resource_monitor: crate::gpu::resources::ResourceMonitor,
+    /// Frame rate, drops, memory and audio devices for the set report (#2241).
+    pub session_stats: crate::session_stats::SessionStats,
+    /// Per-layer GPU timestamps for the frame-time guard (#2243); `None`
+    /// when the GPU can't write them between passes.
+    layer_timer: Option<LayerTimer>,
+    frame_guard: FrameGuard,
     // Quit confirmation
     pub quit_requested: bool,
     // Transient status error (displayed in status bar, auto-clears)
     pub status_error: Option<(String, Instant)>,
+    /// Holds back a GPU error that repeats every frame (#2230).
+    gpu_error_throttle: GpuErrorThrottle,
+    /// Rig file chosen in the import dialog, sent from its thread (#2203).
+    rig_import_rx: Option<crossbeam_channel::Receiver<std::path::PathBuf>>,
+    /// Primary state still to mirror while this instance is a standby (#2204).
+    follow_state: Option<serde_json::Value>,
+    /// The primary's preset when it was last acted on.
+    followed_preset: Option<String>,
+    /// Window focus came back: recheck the active shaders on disk (#2191).
+    pub rescan_shaders: bool,
+    /// Compositor hooks on disk, and why the chosen one isn't running (#2252).
+    pub composite_hooks: Vec<String>,
+    pub composite_hook_error: Option<String>,
     // Webcam capture (feature-gated)
     #[cfg(feature = "webcam")]
     pub webcam_capture: Option<WebcamBackend>,
@@ -125,13 +230,24 @@ pub struct App {
     pub webcam_devices: Vec<(u32, String)>,
     #[cfg(feature = "webcam")]
     pub webcam_device_index: u32,
+    /// Requested capture mode (resolution + fps) for the shared webcam capture.
+    #[cfg(feature = "webcam")]
+    pub webcam_mode: crate::media::types::WebcamMode,
+    /// Modes the current device advertises; empty if it couldn't be queried.
+    #[cfg(feature = "webcam")]
+    pub webcam_modes: Vec<crate::media::types::WebcamMode>,
     #[cfg(feature = "webcam")]
     pub use_ffmpeg_webcam: bool,
     // Particle source loader (background image/video decode)
     pub particle_source_loader: crate::gpu::particle::ParticleSourceLoader,
+    /// Frame-time governor over every layer's particle budget (#2156).
+    pub particle_governor: crate::gpu::particle::governor::QualityGovernor,
     /// Background Gaussian-splat scene loader (#1800): decodes .ply/.splat off
     /// the main thread; results drained in main.rs → `upload_splat_cloud`.
     pub splat_loader: crate::gpu::particle::SplatSceneLoader,
+    /// "Check for new effects" (#2180), polled by main.rs; an install
+    /// rescans the effect library.
+    pub effect_library: crate::effect::library::LibraryUpdater,
     /// In-flight Splat demo-scene download (#1800), polled by main.rs; on
     /// completion the cached file is loaded onto the active splat layer.
     pub splat_demo_download: Option<std::sync::Arc<crate::download::DownloadProgress>>,
@@ -153,6 +269,7 @@ impl App {
         // Load default effect or fall back to default shader
         let mut effect_loader = EffectLoader::new();
         effect_loader.scan_effects_directory();
+        effect_loader.gpu_caps = Some(GpuCapabilities::detect(&gpu.adapter, &gpu.device));
 
         // Prefer Phosphor as default, fall back to first effect
         let default_idx = effect_loader
@@ -168,6 +285,8 @@ impl App {
         let placeholder = PlaceholderTexture::new(&gpu.device, &gpu.queue, hdr_format);
         // A17 audio textures (waveform / spectrum / spectrogram), zero-initialized (#1468).
         let audio_textures = AudioTextures::new(&gpu.device, &gpu.queue);
+        // Motion field of a media layer (#2154).
+        let motion = MotionField::new(&gpu.device);
 
         // Build initial layer with default effect (use normalized_passes for multi-pass effects)
         let uniform_buffer = UniformBuffer::new(&gpu.device);
@@ -188,6 +307,7 @@ impl App {
                         &audio_textures,
                         &gpu.queue,
                         gpu.pipeline_cache.as_ref(),
+                        Some(&gpu.shared_pipelines),
                     ) {
                         Ok(executor) => {
                             let sources: Vec<String> = passes
@@ -350,7 +470,7 @@ impl App {
         layer_stack.layers.push(initial_layer);
 
         // Compositor
-        let compositor = Compositor::new(
+        let mut compositor = Compositor::new(
             &gpu.device,
             hdr_format,
             gpu.surface_config.width,
@@ -360,6 +480,7 @@ impl App {
         // Post-processing chain
         let post_process = PostProcessChain::new(
             &gpu.device,
+            &gpu.queue,
             gpu.format,
             hdr_format,
             gpu.surface_config.width,
@@ -369,10 +490,22 @@ impl App {
         let shader_watcher = ShaderWatcher::new()?;
         let shader_compiler = ShaderCompiler::new();
         let settings = SettingsConfig::load();
+        let composite_hook_error = compositor
+            .load_hook(&gpu.device, settings.composite_hook.as_deref())
+            .err();
+        let master_params = settings.master;
+        let dither = settings.dither;
+        let calibration = settings.calibration;
+        let freeze_params = settings.freeze;
+        let shader_auto_rollback = settings.shader_auto_rollback;
+        let shader_editor_keymap = settings.shader_editor_keymap;
+        let media_cache = crate::media::cache::MediaCache::new(settings.media_cache_mb);
         #[cfg(feature = "webcam")]
         let webcam_device_from_settings = settings.webcam_device.unwrap_or(0);
         #[cfg(feature = "webcam")]
         let use_ffmpeg_webcam = settings.use_ffmpeg_webcam;
+        #[cfg(feature = "webcam")]
+        let webcam_mode_from_settings = settings.webcam_mode.unwrap_or_default();
         let mut audio = AudioSystem::new_with_device(
             settings.audio_device.as_deref(),
             settings.band_scale,
@@ -380,11 +513,17 @@ impl App {
             Arc::new(std::sync::Mutex::new(crate::audio::TempoControl::new(
                 settings.tempo,
             ))),
+            Arc::new(crate::audio::channels::InputChannels::new(
+                settings.input_channels.clone(),
+            )),
         );
         // A9 (#1460): a setter rather than a 5th `new_with_device` param — the audio thread
         // never sees this value, so threading it through construction would touch every
         // caller for nothing.
-        audio.set_auto_reconnect(settings.auto_reconnect);
+        audio.set_auto_reconnect(settings.auto_reconnect || settings.installation.enabled);
+        audio.set_persistent_reconnect(settings.installation.enabled);
+        audio.set_mic_device(settings.mic_device.as_deref());
+        audio.envelopes.config = settings.envelopes;
         let midi = MidiSystem::new();
         let osc = OscSystem::new();
         let web = WebSystem::new();
@@ -407,6 +546,10 @@ impl App {
 
         #[cfg(feature = "profiling")]
         let gpu_profiler = crate::gpu::profiler::Profiler::new(&gpu.device);
+        let layer_timer = LayerTimer::new(&gpu.device, &gpu.queue);
+        if layer_timer.is_none() {
+            log::info!("GPU timestamps unavailable, the layer frame-time guard is off");
+        }
 
         let now = Instant::now();
         Ok(Self {
@@ -417,7 +560,20 @@ impl App {
             start_time: now,
             last_frame: now,
             frame_count: 0,
+            session_seed: crate::gpu::deterministic::clock_entropy(),
+            last_recovery_attempt: None,
+            surface_failures: 0,
+            output_monitor: OutputMonitor::default(),
+            last_session_save: None,
+            session_json: String::new(),
+            schedule: ScheduleWatch::default(),
+            watchdog: Watchdog::default(),
+            local_clock: LocalClock::default(),
+            webcam_retry_at: None,
+            installation_unlocked: false,
             shader_watcher,
+            media_watcher: start_media_watcher(&settings.watch_folders),
+            crossfade_pending: false,
             shader_compiler,
             audio,
             midi,
@@ -429,7 +585,8 @@ impl App {
             pending_web_triggers: Vec::new(),
             binding_bus,
             preset_store,
-            preset_loader: PresetLoader::new(),
+            preset_loader: PresetLoader::new(media_cache.clone()),
+            media_cache,
             scene_store,
             timeline: Timeline::new(Vec::new(), false, AdvanceMode::Manual),
             transition_renderer: None,
@@ -450,15 +607,53 @@ impl App {
             post_process,
             volumetric_enabled: false,
             volumetric_params: crate::gpu::volumetric::VolumetricParams::default(),
+            master: crate::gpu::master::MasterState {
+                params: master_params,
+                dither,
+                calibration,
+                ..Default::default()
+            },
+            freeze: crate::gpu::freeze::FreezeFrame::new(freeze_params),
+            freeze_renderer: None,
+            silence: crate::audio::silence::SilenceWatch::default(),
+            silence_return: None,
+            attract: AttractWatch::default(),
+            attract_return: None,
+            last_input: Instant::now(),
             placeholder,
             audio_textures,
+            motion,
             #[cfg(feature = "ndi")]
             ndi,
             recording,
-            shader_editor: ShaderEditorState::default(),
+            screenshot: crate::recording::screenshot::Screenshot::new(),
+            shader_editor: ShaderEditorState {
+                auto_rollback: shader_auto_rollback,
+                keymap: shader_editor_keymap,
+                ..Default::default()
+            },
+            shader_history: ShaderHistory::default(),
             binding_matrix: crate::ui::panels::binding_matrix::BindingMatrixState::new(),
+            cue_panel_open: false,
+            // The profiler's timings live in this panel, so profiling builds open it
+            perf_panel_open: cfg!(feature = "profiling"),
+            tour_step: (!settings.tour_seen).then_some(0),
+            resource_monitor: crate::gpu::resources::ResourceMonitor::default(),
+            session_stats: crate::session_stats::SessionStats::default(),
+            layer_timer,
+            frame_guard: FrameGuard::default(),
+            uniforms_panel_open: false,
+            debug_view: DebugViewState::default(),
+            debug_viewer: None,
             quit_requested: false,
             status_error: None,
+            gpu_error_throttle: GpuErrorThrottle::default(),
+            rig_import_rx: None,
+            follow_state: None,
+            followed_preset: None,
+            rescan_shaders: false,
+            composite_hooks: crate::gpu::compositor::list_hooks(),
+            composite_hook_error,
             #[cfg(feature = "webcam")]
             webcam_capture: None,
             #[cfg(feature = "webcam")]
@@ -470,9 +665,15 @@ impl App {
             #[cfg(feature = "webcam")]
             webcam_device_index: webcam_device_from_settings,
             #[cfg(feature = "webcam")]
+            webcam_mode: webcam_mode_from_settings,
+            #[cfg(feature = "webcam")]
+            webcam_modes: Vec::new(),
+            #[cfg(feature = "webcam")]
             use_ffmpeg_webcam,
             particle_source_loader: crate::gpu::particle::ParticleSourceLoader::new(),
+            particle_governor: crate::gpu::particle::governor::QualityGovernor::new(),
             splat_loader: crate::gpu::particle::SplatSceneLoader::new(),
+            effect_library: crate::effect::library::LibraryUpdater::new(),
             splat_demo_download: None,
             #[cfg(feature = "depth")]
             depth_thread: None,
@@ -503,6 +704,8 @@ impl App {
         if let Some(ref mut tr) = self.transition_renderer {
             tr.resize(&self.gpu.device, width, height, GpuContext::hdr_format());
         }
+        // The held frame no longer matches the output size.
+        self.freeze.release();
         #[cfg(feature = "ndi")]
         self.ndi.resize(&self.gpu.device, width, height);
     }
@@ -515,8 +718,44 @@ impl App {
         // the emission accumulator dumps the entire stall's budget at once
         // (#1796 live finding: every real mouse click white-flashed Tide).
         // Momentary slow-motion during a stall beats a white flash.
-        let dt = now.duration_since(self.last_frame).as_secs_f32().min(0.05);
+        let real_secs = now.duration_since(self.last_frame).as_secs_f32();
         self.last_frame = now;
+        // Deterministic mode (#2174) steps a fixed 1/fps however long the
+        // frame really took, so a run replays the same sim steps.
+        let det = self.settings.deterministic;
+        let frame_secs = if det.enabled { det.step() } else { real_secs };
+        let dt = frame_secs.min(0.05);
+        // The governor judges the real frame time, not the clamped sim step.
+        // It would change particle counts between runs, so it sits out
+        // deterministic mode.
+        if self.settings.auto_particle_quality && !det.enabled {
+            self.particle_governor.update(real_secs * 1000.0);
+        }
+        // Freeze decay runs on frame time, like a transition.
+        self.freeze.tick(frame_secs);
+        self.resource_monitor.sample(&self.gpu.device);
+        // Frames held back on purpose outside opening hours aren't drops
+        if self.idle_frame_interval().is_none() {
+            self.session_stats.frame(real_secs);
+        }
+        self.session_stats
+            .memory(self.resource_monitor.memory_bytes.max(0) as u64);
+        self.session_stats
+            .audio_device(&self.audio.device_name, self.audio.active);
+        for error in self.gpu.uncaptured_errors.drain() {
+            self.report_gpu_error(error);
+        }
+        self.check_output_monitor();
+        self.save_session(false);
+
+        // A save that failed on the writer thread (#2201)
+        if let Some((path, e)) = crate::persist::take_failures().pop() {
+            let file = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            self.status_error = Some((format!("Couldn't save {file}: {e}"), Instant::now()));
+        }
 
         // Auto-clear status error after 6 seconds
         if let Some((_, when)) = &self.status_error {
@@ -525,9 +764,25 @@ impl App {
             }
         }
 
+        // A loop export ends itself once it has its N seconds; ffmpeg
+        // finishes the file in the background.
+        if self.recording.clip_finished() {
+            self.recording.stop();
+        }
+        self.recording.reap();
+
         // Update global time uniforms
-        self.uniforms.time = now.duration_since(self.start_time).as_secs_f32();
+        self.uniforms.time = if det.enabled {
+            det.frame_time(self.frame_count)
+        } else {
+            now.duration_since(self.start_time).as_secs_f32()
+        };
         self.uniforms.delta_time = dt;
+        self.uniforms.seed = crate::gpu::deterministic::seed_uniform(if det.enabled {
+            det.seed
+        } else {
+            self.session_seed
+        });
         self.uniforms.resolution = [
             self.gpu.surface_config.width as f32,
             self.gpu.surface_config.height as f32,
@@ -603,7 +858,43 @@ impl App {
             self.uniforms.contrast_5 = features.contrast_5;
             self.uniforms.contrast_mean = features.contrast_mean;
             self.uniforms.timbre_flux = features.timbre_flux;
+            // Dynamics (#2218).
+            self.uniforms.crest = features.crest;
+            self.uniforms.impact = features.impact;
+            // Band envelopes (#2219), followed from the bands just set.
+            let env = self.audio.envelopes.update(
+                EnvelopeFollowers::inputs(&[
+                    features.sub_bass,
+                    features.bass,
+                    features.low_mid,
+                    features.mid,
+                    features.upper_mid,
+                    features.presence,
+                    features.brilliance,
+                ]),
+                dt,
+            );
+            [
+                self.uniforms.env_bass_fast,
+                self.uniforms.env_mid_fast,
+                self.uniforms.env_high_fast,
+            ] = env.fast;
+            [
+                self.uniforms.env_bass_slow,
+                self.uniforms.env_mid_slow,
+                self.uniforms.env_high_slow,
+            ] = env.slow;
         }
+        // Second input (#2217): the room mic, read on its own clock.
+        let mic = self.audio.mic_levels();
+        self.uniforms.mic_rms = mic.rms;
+        self.uniforms.mic_onset = mic.onset;
+        // Before the silence action, so a stack it switched away from comes back
+        // first and the silence action can then undo its own switch.
+        self.update_attract(dt);
+        self.update_silence(dt);
+        self.update_installation(dt);
+        self.update_watchdog(dt);
 
         // A17 (#1468): refresh the audio textures every frame. The waveform peeks the
         // freshest PCM straight from the recording ring (no audio-thread involvement);
@@ -637,6 +928,16 @@ impl App {
             None => 0.0,
         };
 
+        // Motion field (#2154): fold in last frame's readback.
+        self.motion.poll_readback();
+        if self.layer_stack.motion_source().is_none() {
+            self.motion.idle();
+        }
+        self.uniforms.motion = self.motion.stats.energy;
+        self.uniforms.motion_x = self.motion.stats.x;
+        self.uniforms.motion_y = self.motion.stats.y;
+        self.uniforms.frame_diff = self.motion.stats.frame_diff;
+
         // Watchdog: if the device died or stopped delivering data mid-session, surface it and
         // — when auto-reconnect is on (A9 #1460) — reopen it. Safe to drive from here because
         // the teardown is detached: a stalled capture thread may be blocked in a timeout-less
@@ -756,6 +1057,12 @@ impl App {
             for (name, value) in &osc_result.volumetric_params {
                 self.volumetric_params.set_param(name, *value);
             }
+            if let Some(on) = osc_result.master_blackout {
+                self.master.blackout = on;
+            }
+            for (name, value) in &osc_result.master_params {
+                self.set_master_param(name, *value);
+            }
 
             // Process scene control (outside layer borrow)
             if let Some(index) = scene_goto_cue {
@@ -869,39 +1176,101 @@ impl App {
 
             // Handle preset loads from web
             let had_preset_loads = !web_result.preset_loads.is_empty();
-            for preset_idx in web_result.preset_loads {
-                self.load_preset(preset_idx);
+            for preset in web_result.preset_loads {
+                if let Some(index) = self.preset_store.resolve(preset) {
+                    self.load_preset(index);
+                }
             }
 
-            // After preset load, broadcast full state so all clients update
-            if had_preset_loads && self.web.client_count > 0 {
-                let layer_infos = self.layer_stack.layer_infos(&self.effect_loader.effects);
-                let layer_data: Vec<_> = self
-                    .layer_stack
-                    .layers
-                    .iter()
-                    .map(|l| {
-                        (
-                            &l.param_store,
-                            l.effect_index(),
-                            l.blend_mode,
-                            l.opacity,
-                            l.enabled,
-                            l.locked,
-                        )
-                    })
-                    .collect();
-                let state_json = crate::web::state::build_full_state(
-                    &self.effect_loader.effects,
-                    &layer_infos,
-                    self.layer_stack.active_layer,
-                    &layer_data,
-                    &self.preset_store,
-                    self.post_process.enabled,
-                );
-                self.web.broadcast_json(&state_json);
+            // Media, layer, preset save, post-processing and shader editor
+            // messages, in the order they arrived (#2196)
+            let mut layers_changed = false;
+            for action in web_result.actions {
+                let changes_layers = action.changes_layers();
+                let reads_shader = action == WebAction::GetShaderSource;
+                match self.apply_web_action(action) {
+                    Ok(_) if reads_shader => {
+                        let (code, file_name) = self.shader_editor.wgsl_code();
+                        let msg = crate::web::state::build_shader_source(
+                            &self.shader_editor.effect_name,
+                            file_name,
+                            code,
+                        );
+                        self.web.broadcast_json(&msg);
+                    }
+                    Ok(_) => layers_changed |= changes_layers,
+                    Err(e) => log::debug!("Web message ignored: {}", e.message),
+                }
+            }
+
+            // Requests are carried out one by one, each answered to its sender
+            for req in web_result.requests {
+                layers_changed |= self.answer_web_request(req);
+            }
+
+            // After preset load or a layer list change, broadcast full state
+            // so all clients update
+            if (had_preset_loads || layers_changed) && self.web.client_count > 0 {
+                let state = self.web_full_state();
+                self.web.broadcast_state(state);
+            }
+        }
+        self.follow_primary();
+
+        // Drain MIDI clock bytes into MidiClock, ahead of the automation
+        // bar snap so it lands on the frame the bar starts
+        self.midi_clock_beat_crossed = self.midi.drain_clock(&mut self.midi_clock);
+
+        // Param automation (#2183): after the MIDI/OSC/WS drain so their moves
+        // count as touches, before the bus so bindings still win. The loop
+        // follows a running MIDI clock when there is one, else the beat tracker.
+        let bpm = if self.midi_clock.playing() && self.midi_clock.has_bpm() {
+            self.midi_clock.bpm() as f32
+        } else {
+            self.uniforms.bpm * 300.0
+        };
+        let bars = crate::params::automation::bars_for(dt, bpm);
+        // Lanes snap to the bar line
+        let bar_line = if self.midi_clock.playing() && self.midi_clock.has_bpm() {
+            self.midi_clock.on_bar_line(self.midi_clock_beat_crossed)
+        } else {
+            self.uniforms.downbeat > 0.5
+        };
+        let features = self.latest_audio.unwrap_or_default();
+        let mut automation_recorded = false;
+        for layer in &mut self.layer_stack.layers {
+            let effect_index = layer.effect_index();
+            let (defs, values, _) = layer.param_store.split_borrow();
+            // Audio mappings (#2186) lift last frame's offset first, so lanes
+            // and patterns see the base value rather than a hand move.
+            layer.modulation.release(values);
+            automation_recorded |= layer.automation.tick(defs, values, bars, bar_line, dt);
+            // Step patterns (#2184) follow the tracked beat and win over
+            // lanes; their steps aren't live moves for Rec to capture.
+            layer.sequencer.tick(
+                defs,
+                values,
+                self.uniforms.beat_phase,
+                self.uniforms.beat_in_bar,
+            );
+            layer.automation.settle(values, layer.sequencer.driven());
+            // Opacity envelopes (#2249) share the clock
+            layer.envelope.tick(bars, features.beat_phase);
+            // A muted layer's mappings stay released at the base value (#2206)
+            let mappings = layer.audio_mappings.as_deref().or_else(|| {
+                effect_index
+                    .and_then(|i| self.effect_loader.effects.get(i))
+                    .map(|fx| fx.audio_mappings.as_slice())
+            });
+            if let Some(mappings) = mappings.filter(|_| !layer.audio_muted) {
+                layer
+                    .modulation
+                    .apply(mappings, defs, values, |name| features.get(name), dt);
             }
         }
+        if automation_recorded {
+            self.preset_store.mark_dirty();
+        }
 
         // Evaluate binding bus (runs after MIDI/OSC/WS drain — bus overrides direct mappings)
         self.binding_bus.ingest_ws_values(&self.web.bind_values);
@@ -933,42 +1302,21 @@ impl App {
                 "Async preset decode complete, applying preset index {}",
                 result.preset_index
             );
-            let index = result.preset_index;
+            // Presets may have been saved, renamed or deleted while it decoded
+            // (#2223), so find it again by id rather than trusting the index.
+            let index = self.preset_store.index_of_id(result.preset.id);
             let preset = result.preset;
             self.apply_preset_immediately(index, &preset, result.decoded_media);
 
             // Broadcast full state to web clients after async preset load
             if self.web.client_count > 0 {
-                let layer_infos = self.layer_stack.layer_infos(&self.effect_loader.effects);
-                let layer_data: Vec<_> = self
-                    .layer_stack
-                    .layers
-                    .iter()
-                    .map(|l| {
-                        (
-                            &l.param_store,
-                            l.effect_index(),
-                            l.blend_mode,
-                            l.opacity,
-                            l.enabled,
-                            l.locked,
-                        )
-                    })
-                    .collect();
-                let state_json = crate::web::state::build_full_state(
-                    &self.effect_loader.effects,
-                    &layer_infos,
-                    self.layer_stack.active_layer,
-                    &layer_data,
-                    &self.preset_store,
-                    self.post_process.enabled,
-                );
-                self.web.broadcast_json(&state_json);
+                let state = self.web_full_state();
+                self.web.broadcast_state(state);
             }
         }
-
-        // Drain MIDI clock bytes into MidiClock
-        self.midi_clock_beat_crossed = self.midi.drain_clock(&mut self.midi_clock);
+        // After the drain, so a crossfade waiting on its preset's media
+        // blends on the frame that preset lands
+        self.update_preset_crossfade();
 
         // Auto-follow MIDI transport → timeline
         if self.midi_clock.playing()
@@ -995,6 +1343,15 @@ impl App {
             };
             let beat_event = self.timeline.feed_beat(beat_on);
             self.process_timeline_event(beat_event);
+            // Bar-quantized Go (#2221): MIDI clock has no bar of its own, so count 4/4
+            // from Start.
+            let downbeat_on = if self.midi_clock.playing() {
+                self.midi_clock_beat_crossed && self.midi_clock.beat_count() % 4 == 0
+            } else {
+                self.uniforms.downbeat > 0.5
+            };
+            let bar_event = self.timeline.feed_downbeat(downbeat_on);
+            self.process_timeline_event(bar_event);
 
             // Tick for timer-based advance
             let tick_event = self.timeline.tick(dt);
@@ -1044,48 +1401,63 @@ impl App {
                 self.timeline.cues.len(),
                 tl_progress,
             );
+            // Tempo out for lighting sync (#2205)
+            self.osc.send_tempo(&features);
+            self.midi
+                .send_tempo(features.bpm * 300.0, features.beat_phase);
 
             // Web: broadcast audio at 10Hz
             self.web.broadcast_audio(&features);
         }
 
-        // Web: update latest state for new client initial sync
-        if self.web.client_count > 0 || self.web.is_running() {
-            let layer_infos = self.layer_stack.layer_infos(&self.effect_loader.effects);
-            let layer_data: Vec<_> = self
-                .layer_stack
-                .layers
-                .iter()
-                .map(|l| {
-                    (
-                        &l.param_store,
-                        l.effect_index(),
-                        l.blend_mode,
-                        l.opacity,
-                        l.enabled,
-                        l.locked,
-                    )
-                })
-                .collect();
-            let state_json = crate::web::state::build_full_state(
-                &self.effect_loader.effects,
-                &layer_infos,
-                self.layer_stack.active_layer,
-                &layer_data,
-                &self.preset_store,
-                self.post_process.enabled,
-            );
-            self.web.update_latest_state(&state_json);
+        // Web: what changed for connected clients at 10Hz; skipped entirely
+        // with none connected
+        if self.web.state_due() {
+            let state = self.web_full_state();
+            self.web.update_latest_state(state);
         }
 
-        // Advance media playback + upload frames for media layers
+        // Advance media playback + upload frames for media layers. Clips can
+        // follow the music (#2248), unless their layer ignores audio.
+        let cue = self.latest_audio.map(|f| AudioCue {
+            onset: f.onset,
+            bass: f.bass,
+            beat: f.beat > 0.5,
+            // bpm is normalized to BPM / 300
+            beat_secs: if f.bpm > 0.0 { 0.2 / f.bpm } else { 0.5 },
+        });
         for layer in &mut self.layer_stack.layers {
+            let cue = cue.as_ref().filter(|_| !layer.audio_muted);
             if let LayerContent::Media(ref mut m) = layer.content {
-                m.advance(dt);
+                m.advance(dt, cue);
                 m.upload_frame(&self.gpu.queue);
             }
         }
 
+        // Generator envelopes follow the global clock (#2168)
+        for layer in &self.layer_stack.layers {
+            if let LayerContent::Generator(ref g) = layer.content {
+                if layer.audio_muted {
+                    g.update(&self.gpu.queue, &self.uniforms.without_audio());
+                } else {
+                    g.update(&self.gpu.queue, &self.uniforms);
+                }
+            }
+        }
+
+        // Trail history only exists while a layer's trail is set (#2173)
+        for layer in &mut self.layer_stack.layers {
+            layer.sync_trail(
+                &self.gpu.device,
+                &self.gpu.queue,
+                self.gpu.surface_config.width,
+                self.gpu.surface_config.height,
+                GpuContext::hdr_format(),
+            );
+        }
+
+        self.update_playlists();
+
         // Drain webcam frames into live media layers; detect dead capture thread
         #[cfg(feature = "webcam")]
         {
@@ -1098,6 +1470,9 @@ impl App {
                 self.status_error =
                     Some(("Webcam capture stopped unexpectedly".into(), Instant::now()));
                 self.webcam_capture = None;
+                if self.settings.installation.enabled {
+                    self.webcam_retry_at = Some(Instant::now() + crate::installation::WEBCAM_RETRY);
+                }
             }
             if let Some(ref capture) = self.webcam_capture {
                 if let Some(frame) = capture.try_recv_frame() {
@@ -1105,8 +1480,7 @@ impl App {
                     for layer in &mut self.layer_stack.layers {
                         if let LayerContent::Media(ref mut m) = layer.content {
                             if m.is_live() {
-                                m.set_live_frame(frame.data.clone());
-                                m.upload_frame(&self.gpu.queue);
+                                m.upload_live_frame(&self.gpu.queue, &frame.data);
                             }
                         }
                     }
@@ -1146,6 +1520,7 @@ impl App {
                             }
                         }
                     }
+                    capture.recycle(frame);
                 }
             }
         }
@@ -1200,22 +1575,50 @@ impl App {
         let vol_enabled = self.volumetric_enabled;
         let vol_params = self.volumetric_params;
         let vol_hdr = GpuContext::hdr_format();
+        let particle_scale = self.particle_governor.scale();
         for (layer_idx, layer) in self.layer_stack.layers.iter_mut().enumerate() {
             if let LayerContent::Effect(ref mut e) = layer.content {
-                e.uniforms = self.uniforms;
+                e.uniforms = if layer.audio_muted {
+                    self.uniforms.without_audio()
+                } else {
+                    self.uniforms
+                };
                 e.uniforms.params = layer.param_store.pack_to_buffer();
 
                 // Update particle systems
                 if let Some(ref mut ps) = e.pass_executor.particle_system {
+                    ps.update_active_count(&self.gpu.device, &self.gpu.queue, particle_scale);
                     ps.update_uniforms(
                         dt,
                         self.uniforms.time,
                         self.uniforms.resolution,
-                        self.uniforms.beat,
+                        e.uniforms.beat,
                     );
+                    // Offset by the global seed (#2174) so runs with different
+                    // seeds emit differently, and equal seeds identically.
+                    ps.uniforms.seed = (ps.uniforms.seed + self.uniforms.seed) % 65536.0;
                     // Forward first 8 effect params to compute shader
                     let p = e.uniforms.params;
                     ps.uniforms.effect_params = [p[0], p[1], p[2], p[3], p[4], p[5], p[6], p[7]];
+                    // .pfx particle settings driven by inputs (#2164), then force
+                    // fields (#2158), whose strengths follow their bound inputs
+                    let store = &layer.param_store;
+                    crate::gpu::particle::param_inputs::apply_param_inputs(
+                        &ps.def.params,
+                        &mut ps.uniforms,
+                        |name| match store.get(name) {
+                            Some(ParamValue::Float(v)) => Some(*v),
+                            _ => None,
+                        },
+                    );
+                    crate::gpu::particle::forces::pack_forces(
+                        &ps.def.forces,
+                        &mut ps.uniforms,
+                        |name| match store.get(name) {
+                            Some(ParamValue::Float(v)) => Some(*v),
+                            _ => None,
+                        },
+                    );
                     // Advance obstacle video playback
                     if ps.obstacle_source == "video" {
                         ps.advance_obstacle_video(&self.gpu.device, &self.gpu.queue, dt as f64);
@@ -1226,7 +1629,10 @@ impl App {
                     ps.uniforms.obstacle_mode = ps.obstacle_mode as u32;
                     ps.uniforms.obstacle_elasticity = ps.obstacle_elasticity;
                     ps.uniforms.obstacle_fit = ps.obstacle_fit as u32;
-                    let audio = self.latest_audio.unwrap_or_default();
+                    let audio = self
+                        .latest_audio
+                        .filter(|_| !layer.audio_muted)
+                        .unwrap_or_default();
                     ps.update_audio(&audio);
                     // Splat (#1800): camera params ride slots 8–11 and roundness
                     // slot 12 (only 0–7 reach the sim); advance the CPU
@@ -1267,6 +1673,7 @@ impl App {
         }
 
         // Apply completed background shader compilations
+        let mut active_compiled = false;
         for result in self.shader_compiler.drain_results() {
             match result {
                 CompileResult::RenderPass {
@@ -1296,6 +1703,7 @@ impl App {
                                         e.shader_sources[pass_idx] = source;
                                     }
                                     e.shader_error = None;
+                                    layer.suspended = None;
                                     log::info!("Pass {} recompiled successfully (bg)", pass_idx);
                                 }
                                 Err(err) => {
@@ -1309,6 +1717,24 @@ impl App {
                             e.shader_error = Some(err);
                         }
                     }
+                    // A save from the editor either becomes the new last-good
+                    // source or, if it broke, gets rolled back on disk (#2188).
+                    if layer_idx == self.layer_stack.active_layer {
+                        let rollback = self
+                            .shader_editor
+                            .compile_finished(e.shader_error.as_deref());
+                        if let Some((path, good)) = rollback {
+                            match std::fs::write(&path, good) {
+                                Ok(()) => {
+                                    log::warn!("Shader rolled back: {}", path.display());
+                                    // The old pipeline never stopped running.
+                                    e.shader_error = None;
+                                }
+                                Err(err) => log::error!("Shader rollback failed: {err}"),
+                            }
+                        }
+                        active_compiled |= e.shader_error.is_none();
+                    }
                 }
                 CompileResult::ComputeShader {
                     layer_idx,
@@ -1337,16 +1763,36 @@ impl App {
                 }
             }
         }
+        if active_compiled {
+            self.record_shader_snapshot(true);
+            self.lint_editor_shader();
+        }
+
+        self.check_watch_folders();
 
-        // Shader hot-reload — submit changed shaders for background compilation
+        // Shader hot-reload — submit changed shaders for background compilation.
+        // On focus regain every active shader is reread (#2191): an outside
+        // editor's save may have slipped past the watcher, and unchanged
+        // sources are skipped below.
+        let rescan = std::mem::take(&mut self.rescan_shaders);
         let changes = self.shader_watcher.drain_changes();
-        if !changes.is_empty() {
-            let lib_changed = changes
-                .iter()
-                .any(|p| p.to_string_lossy().contains("/lib/"));
+        if !changes.is_empty() || rescan {
+            let lib_changed = rescan
+                || changes
+                    .iter()
+                    .any(|p| p.to_string_lossy().contains("/lib/"));
             if lib_changed {
                 self.effect_loader.reload_library();
             }
+            let hook_dir = format!("/{}/", crate::gpu::compositor::HOOK_DIR);
+            if rescan
+                || changes
+                    .iter()
+                    .any(|p| p.to_string_lossy().contains(&hook_dir))
+            {
+                self.composite_hooks = crate::gpu::compositor::list_hooks();
+                self.apply_composite_hook();
+            }
             let hdr_format = GpuContext::hdr_format();
 
             for (layer_idx, layer) in self.layer_stack.layers.iter().enumerate() {
@@ -1389,6 +1835,11 @@ impl App {
                                     &self.gpu.device,
                                     hdr_format,
                                     pass_def.inputs.len(),
+                                    pass_def
+                                        .constants
+                                        .iter()
+                                        .map(|(name, value)| (name.clone(), *value))
+                                        .collect(),
                                 );
                             }
                         }
@@ -1401,9 +1852,10 @@ impl App {
                 // Hot-reload compute shader (background compilation)
                 if let Some(ref particle_def) = effect.particles {
                     if !particle_def.compute_shader.is_empty() {
-                        let compute_relevant = changes
-                            .iter()
-                            .any(|p| p.ends_with(&particle_def.compute_shader));
+                        let compute_relevant = rescan
+                            || changes
+                                .iter()
+                                .any(|p| p.ends_with(&particle_def.compute_shader));
                         if compute_relevant {
                             if let Some(ref ps) = e.pass_executor.particle_system {
                                 match self
@@ -1435,7 +1887,21 @@ impl App {
         }
 
         // PFX hot-reload — update effect definitions when .pfx files change
-        let pfx_changes = self.shader_watcher.drain_pfx_changes();
+        let mut pfx_changes = self.shader_watcher.drain_pfx_changes();
+        if rescan {
+            for layer in &self.layer_stack.layers {
+                let Some(path) = layer
+                    .effect_index()
+                    .and_then(|i| self.effect_loader.effects.get(i))
+                    .and_then(|e| e.source_path.clone())
+                else {
+                    continue;
+                };
+                if !pfx_changes.contains(&path) {
+                    pfx_changes.push(path);
+                }
+            }
+        }
         for pfx_path in &pfx_changes {
             let json = match std::fs::read_to_string(pfx_path) {
                 Ok(s) => s,
@@ -1540,10 +2006,12 @@ impl App {
                             .merge_from_defs(&self.effect_loader.effects[effect_idx].inputs);
                     }
                     if diff.postprocess_changed {
-                        let pp = self.effect_loader.effects[effect_idx]
-                            .postprocess
-                            .clone()
-                            .unwrap_or_default();
+                        let effect = &self.effect_loader.effects[effect_idx];
+                        let pp = self.settings.post.resolve(
+                            &effect.name,
+                            effect.postprocess.as_ref(),
+                            None,
+                        );
                         self.layer_stack.layers[layer_idx].postprocess = pp;
                         if layer_idx == self.layer_stack.active_layer {
                             self.post_process.enabled =
@@ -1568,231 +2036,88 @@ impl App {
         }
     }
 
-    /// Build a ParticleSystem from a ParticleDef, or None if the effect doesn't use particles.
-    /// Applies the particle quality multiplier to max_count and emit_rate.
-    fn build_particle_system(
+    /// Device cap on a particle system's slot count. The largest buffer is
+    /// sorted_particles_buffer = max_particles × 9 × 4 bytes (3×3 tile coverage
+    /// in the compute rasterizer scatter pass).
+    pub fn particle_count_limit(&self) -> u32 {
+        let max_binding = self.gpu.device.limits().max_storage_buffer_binding_size as u64;
+        (max_binding / (9 * 4)) as u32
+    }
+
+    /// The `.pfx` particle def as this machine builds it: `max_count` scaled by
+    /// the particle quality multiplier, or set to an explicit `count` (#2156),
+    /// then capped to the device limit. `emit_rate` follows the count.
+    pub fn scaled_particle_def(
         &self,
         particles: &crate::gpu::particle::types::ParticleDef,
-    ) -> Option<ParticleSystem> {
-        let multiplier = self.settings.particle_quality.multiplier();
+        count: Option<u32>,
+    ) -> crate::gpu::particle::types::ParticleDef {
         let mut particles = particles.clone();
         let original_count = particles.max_count;
-        particles.max_count = (particles.max_count as f32 * multiplier).round() as u32;
-        particles.emit_rate *= multiplier;
-
-        // Per-effect cap: don't scale past max_scaled_count if set
-        if particles.max_scaled_count > 0 && particles.max_count > particles.max_scaled_count {
-            let ratio = particles.max_scaled_count as f32 / particles.max_count as f32;
-            particles.max_count = particles.max_scaled_count;
-            particles.emit_rate *= ratio;
+        if let Some(count) = count {
+            particles.emit_rate *= count as f32 / original_count.max(1) as f32;
+            particles.max_count = count;
+        } else {
+            let multiplier = self.settings.particle_quality.multiplier();
+            particles.max_count = (particles.max_count as f32 * multiplier).round() as u32;
+            particles.emit_rate *= multiplier;
+
+            // Per-effect cap: don't scale past max_scaled_count if set
+            if particles.max_scaled_count > 0 && particles.max_count > particles.max_scaled_count {
+                let ratio = particles.max_scaled_count as f32 / particles.max_count as f32;
+                particles.max_count = particles.max_scaled_count;
+                particles.emit_rate *= ratio;
+            }
         }
 
         // Cap particle count to device storage buffer binding limit.
-        // The largest buffer is sorted_particles_buffer = max_particles × 9 × 4 bytes
-        // (3×3 tile coverage in compute rasterizer scatter pass).
-        let max_binding = self.gpu.device.limits().max_storage_buffer_binding_size as u64;
-        let max_from_binding = (max_binding / (9 * 4)) as u32;
+        let max_from_binding = self.particle_count_limit();
         if particles.max_count > max_from_binding {
             log::warn!(
                 "Capping particles from {} to {} (storage buffer binding limit {}MB)",
                 particles.max_count,
                 max_from_binding,
-                max_binding / (1024 * 1024),
+                self.gpu.device.limits().max_storage_buffer_binding_size / (1024 * 1024),
             );
             particles.max_count = max_from_binding;
             particles.emit_rate = particles.emit_rate.min(max_from_binding as f32);
         }
 
-        if particles.max_count != original_count {
-            log::info!(
-                "Particle quality {}: {} -> {} particles",
-                self.settings.particle_quality.display_name(),
-                original_count,
-                particles.max_count,
-            );
-        }
-        let particles = &particles;
+        particles
+    }
 
-        let hdr_format = GpuContext::hdr_format();
-        let is_image_emitter = particles.emitter.shape == "image";
-
-        // For image emitters, use the builtin image_scatter compute shader
-        let compute_source = if is_image_emitter && particles.compute_shader.is_empty() {
-            self.effect_loader.prepend_compute_libraries(include_str!(
-                "../../../assets/shaders/builtin/image_scatter.wgsl"
-            ))
-        } else if particles.compute_shader.is_empty() {
-            self.effect_loader.prepend_compute_libraries(include_str!(
-                "../../../assets/shaders/builtin/particle_sim.wgsl"
-            ))
-        } else {
-            match self
-                .effect_loader
-                .load_compute_source(&particles.compute_shader)
-            {
-                Ok(src) => src,
-                Err(e) => {
-                    log::error!(
-                        "Failed to load compute shader '{}': {e}",
-                        particles.compute_shader
-                    );
-                    return None;
-                }
+    /// Build a ParticleSystem from a ParticleDef, or None if the effect doesn't use particles.
+    /// Sizes it through `scaled_particle_def` (quality multiplier, or an explicit `count`).
+    fn build_particle_system(
+        &self,
+        particles: &crate::gpu::particle::types::ParticleDef,
+        count: Option<u32>,
+    ) -> Option<ParticleSystem> {
+        let original_count = particles.max_count;
+        let particles = &self.scaled_particle_def(particles, count);
+        if particles.max_count != original_count {
+            if count.is_some() {
+                log::info!(
+                    "Particle count: {} -> {} particles",
+                    original_count,
+                    particles.max_count,
+                );
+            } else {
+                log::info!(
+                    "Particle quality {}: {} -> {} particles",
+                    self.settings.particle_quality.display_name(),
+                    original_count,
+                    particles.max_count,
+                );
             }
-        };
+        }
 
-        let mut ps = ParticleSystem::new(
+        new_particle_system(
             &self.gpu.device,
             &self.gpu.queue,
-            hdr_format,
+            &self.effect_loader,
             particles,
-            &compute_source,
-            particles.interaction,
-        );
-        log::info!("Particle system created: {} particles", particles.max_count);
-
-        // Load image data for image emitters
-        if is_image_emitter && !particles.emitter.image.is_empty() {
-            let sample_def = particles.image_sample.clone().unwrap_or(
-                crate::gpu::particle::types::ImageSampleDef {
-                    mode: "grid".to_string(),
-                    threshold: 0.1,
-                    scale: 1.0,
-                },
-            );
-            ps.sample_def = sample_def.clone();
-            let image_path = assets_dir().join("images").join(&particles.emitter.image);
-            match crate::gpu::particle::image_source::sample_image(
-                &image_path,
-                &sample_def,
-                particles.max_count,
-            ) {
-                Ok(aux_data) => {
-                    ps.upload_aux_data(&self.gpu.device, &self.gpu.queue, &aux_data);
-                    ps.store_current_aux(aux_data.clone());
-                    ps.static_image_path = Some(image_path.to_string_lossy().to_string());
-                    log::info!(
-                        "Loaded image '{}': {} particles",
-                        particles.emitter.image,
-                        aux_data.len()
-                    );
-                }
-                Err(e) => {
-                    log::warn!("Failed to load image '{}': {e}", particles.emitter.image);
-                }
-            }
-
-            // If a video source is specified, set up video playback
-            #[cfg(feature = "video")]
-            if !particles.emitter.video.is_empty() && particles.emitter.video != "webcam" {
-                let video_path = assets_dir().join("videos").join(&particles.emitter.video);
-                if video_path.exists() {
-                    if crate::media::video::ffmpeg_available() {
-                        match crate::media::video::probe_video(&video_path) {
-                            Ok(meta) => {
-                                match crate::media::video::decode_all_frames(&video_path, &meta) {
-                                    Ok((frames, delays_ms)) => {
-                                        let path_str = video_path.to_string_lossy().to_string();
-                                        ps.set_video_source(
-                                            &self.gpu.queue,
-                                            frames,
-                                            delays_ms,
-                                            path_str,
-                                        );
-                                        log::info!(
-                                            "Particle video source: '{}'",
-                                            particles.emitter.video
-                                        );
-                                    }
-                                    Err(e) => log::warn!(
-                                        "Failed to decode particle video '{}': {e}",
-                                        particles.emitter.video
-                                    ),
-                                }
-                            }
-                            Err(e) => log::warn!(
-                                "Failed to probe particle video '{}': {e}",
-                                particles.emitter.video
-                            ),
-                        }
-                    }
-                }
-            }
-        }
-
-        // Load sprite texture if defined
-        if let Some(ref sprite_def) = particles.sprite {
-            let sprite_path = assets_dir().join("images").join(&sprite_def.texture);
-            match crate::gpu::particle::sprite::SpriteAtlas::load_with_def(
-                &self.gpu.device,
-                &self.gpu.queue,
-                &sprite_path,
-                sprite_def.cols,
-                sprite_def.rows,
-                sprite_def.animated,
-                sprite_def.frames,
-            ) {
-                Ok(atlas) => {
-                    ps.set_sprite(&self.gpu.device, atlas);
-                    log::info!("Loaded sprite atlas: {}", sprite_def.texture);
-                }
-                Err(e) => {
-                    log::warn!("Failed to load sprite '{}': {e}", sprite_def.texture);
-                }
-            }
-        }
-
-        // Set up trail rendering if trail_length specified
-        if particles.trail_length >= 2 {
-            ps.setup_trails(
-                &self.gpu.device,
-                hdr_format,
-                particles.trail_length,
-                particles.trail_width,
-            );
-            log::info!(
-                "Trail rendering enabled: {} points, width {}",
-                particles.trail_length,
-                particles.trail_width
-            );
-        }
-
-        if particles.interaction {
-            log::info!("Spatial hash enabled for particle interaction");
-        }
-
-        // Morph target loading
-        if particles.morph {
-            if let Some(ref targets) = particles.morph_targets {
-                let assets = assets_dir();
-                for (slot, target_def) in targets.iter().take(4).enumerate() {
-                    match crate::gpu::particle::morph::load_morph_target(
-                        target_def,
-                        particles.max_count,
-                        particles.initial_size,
-                        assets,
-                    ) {
-                        Ok(data) => {
-                            log::info!(
-                                "Morph target {}: '{}' ({} particles)",
-                                slot,
-                                target_def.source,
-                                data.len()
-                            );
-                            if let Some(ref mut morph) = ps.morph_state {
-                                morph.load_target(slot as u32, data);
-                            }
-                        }
-                        Err(e) => {
-                            log::warn!("Failed to load morph target {}: {e}", target_def.source);
-                        }
-                    }
-                }
-                ps.upload_morph_targets(&self.gpu.device, &self.gpu.queue);
-            }
-        }
-
-        Some(ps)
+        )
     }
 
     /// Load an effect on the active layer, as a deliberate user choice —
@@ -1812,12 +2137,31 @@ impl App {
             log::info!("Dropped {dropped} preset binding(s) targeting layer {layer_idx}");
         }
         self.load_effect_on_layer(layer_idx, index);
+        if let Some(effect) = self.effect_loader.effects.get(index) {
+            self.preset_store
+                .history
+                .record_effect(layer_idx, &effect.name);
+            if push_recent(&mut self.settings.recent_effects, &effect.name) {
+                self.settings.save();
+            }
+        }
     }
 
     /// Load an effect on a specific layer.
     ///
     /// Deliberately does not touch bindings — see `load_effect` for why.
     pub fn load_effect_on_layer(&mut self, layer_idx: usize, effect_index: usize) {
+        self.load_effect_with_particle_count(layer_idx, effect_index, None);
+    }
+
+    /// `load_effect_on_layer` with the particle buffers sized for `particle_count`
+    /// instead of the quality setting (#2156).
+    fn load_effect_with_particle_count(
+        &mut self,
+        layer_idx: usize,
+        effect_index: usize,
+        particle_count: Option<u32>,
+    ) {
         let effect = match self.effect_loader.effects.get(effect_index).cloned() {
             Some(e) => e,
             None => return,
@@ -1825,6 +2169,15 @@ impl App {
         if layer_idx >= self.layer_stack.layers.len() {
             return;
         }
+        // Leave the layer as it is rather than fail at pipeline creation (#2195).
+        if let Some(reason) = self.effect_loader.unsupported_reason(&effect) {
+            log::warn!("Not loading '{}' on this GPU: {reason}", effect.name);
+            self.status_error = Some((
+                format!("{} can't run on this GPU. {reason}.", effect.name),
+                Instant::now(),
+            ));
+            return;
+        }
 
         let hdr_format = GpuContext::hdr_format();
         let passes = effect.normalized_passes();
@@ -1841,11 +2194,23 @@ impl App {
             }
         }
 
-        // Build particle system before borrowing layer mutably (avoids borrow conflict)
+        // Build particle system before borrowing layer mutably (avoids borrow conflict).
+        // A compute or render shader that fails validation leaves the effect
+        // without particles and the error on the layer, rather than an invalid
+        // pipeline failing every frame (#2230).
+        let scope = ErrorScope::push(&self.gpu.device);
         let particle_system = effect
             .particles
             .as_ref()
-            .and_then(|pd| self.build_particle_system(pd));
+            .and_then(|pd| self.build_particle_system(pd, particle_count));
+        let particle_error = scope.pop();
+        let particle_system = match &particle_error {
+            Some(err) => {
+                log::error!("Particle system for '{}' failed: {err}", effect.name);
+                None
+            }
+            None => particle_system,
+        };
 
         // Splat scene load (#1800): kick off the background decode now that
         // the final (quality-scaled) particle budget is known. The effect
@@ -1863,9 +2228,9 @@ impl App {
             }
         }
 
-        // If layer is currently Media, convert to Effect first
-        let is_media = self.layer_stack.layers[layer_idx].is_media();
-        if is_media {
+        // If layer is currently Media or a generator, convert to Effect first
+        let needs_effect = self.layer_stack.layers[layer_idx].as_effect().is_none();
+        if needs_effect {
             let uniform_buffer = UniformBuffer::new(&self.gpu.device);
             let feedback = PingPongTarget::new_cleared(
                 &self.gpu.device,
@@ -1892,6 +2257,7 @@ impl App {
                     &self.placeholder,
                     &self.audio_textures,
                 );
+                self.layer_stack.layers[layer_idx].playlist = None;
                 self.layer_stack.layers[layer_idx].content =
                     LayerContent::Effect(Box::new(EffectLayer {
                         pass_executor,
@@ -1921,6 +2287,7 @@ impl App {
             &self.audio_textures,
             &self.gpu.queue,
             self.gpu.pipeline_cache.as_ref(),
+            Some(&self.gpu.shared_pipelines),
         );
 
         match executor_result {
@@ -1933,10 +2300,19 @@ impl App {
                 };
                 e.pass_executor = executor;
                 layer.param_store.load_from_defs(&effect.inputs);
-                e.shader_error = None;
+                // Lanes and step patterns name the old effect's params.
+                layer.automation.clear();
+                layer.sequencer.clear();
+                layer.audio_mappings = None;
+                layer.modulation.reset();
+                e.shader_error = particle_error.map(|err| format!("Particle shader: {err}"));
                 e.effect_index = Some(effect_index);
-                // Apply per-effect postprocess overrides
-                let pp = effect.postprocess.clone().unwrap_or_default();
+                layer.suspended = None;
+                // The effect's post chain, or the user's choice for it (#2234)
+                let pp =
+                    self.settings
+                        .post
+                        .resolve(&effect.name, effect.postprocess.as_ref(), None);
                 layer.postprocess = pp.clone();
                 // If this is the active layer, update global postprocess
                 if layer_idx == self.layer_stack.active_layer {
@@ -1980,6 +2356,8 @@ impl App {
                     if let Ok(content) = std::fs::read_to_string(&path) {
                         self.shader_editor.open_file(&effect.name, path, content);
                         self.shader_editor.compile_error = Some(e.clone());
+                        // Nothing to roll back to: this source never compiled.
+                        self.shader_editor.last_good = None;
                         // Load paired .pfx for tab switching
                         if let Some(ref pfx_path) = effect.source_path {
                             if let Ok(pfx_content) = std::fs::read_to_string(pfx_path) {
@@ -1987,6 +2365,8 @@ impl App {
                                     .load_paired_pfx(pfx_path.clone(), pfx_content);
                             }
                         }
+                        self.record_shader_snapshot(false);
+                        self.lint_editor_shader();
                     }
                 }
             }
@@ -1999,32 +2379,539 @@ impl App {
         }
     }
 
-    /// Add a new empty layer with the default shader.
-    pub fn add_layer(&mut self) {
-        let num = self.layer_stack.layers.len();
-        if num >= 8 {
+    /// Set a layer's particle count (#2156). Up to the allocated buffers this is
+    /// the live budget, applied next frame. Past them, the effect reloads with
+    /// buffers sized for `count` — the same rebuild a particle-quality change
+    /// does — carrying over the layer's params and particle-sim knobs.
+    pub fn set_particle_count(&mut self, layer_idx: usize, count: u32) {
+        let Some(layer) = self.layer_stack.layers.get_mut(layer_idx) else {
+            return;
+        };
+        let Some(effect_idx) = layer.effect_index() else {
+            return;
+        };
+        let values = layer.param_store.values.clone();
+        let Some(ps) = layer
+            .as_effect_mut()
+            .and_then(|e| e.pass_executor.particle_system.as_mut())
+        else {
+            return;
+        };
+        if count <= ps.max_particles || ps.budget_locked() {
+            ps.set_particle_budget(count);
             return;
         }
-        let name = format!("Layer {}", num + 1);
-        let hdr_format = GpuContext::hdr_format();
+        let old_def = ps.def.clone();
+        let old_capacity = ps.max_particles;
+        let source = ps.take_source();
 
-        let source = read_default_shader();
-
-        let uniform_buffer = UniformBuffer::new(&self.gpu.device);
-        let feedback = PingPongTarget::new_cleared(
-            &self.gpu.device,
-            &self.gpu.queue,
-            self.gpu.surface_config.width,
-            self.gpu.surface_config.height,
-            hdr_format,
-            1.0,
-        );
+        self.load_effect_with_particle_count(layer_idx, effect_idx, Some(count));
 
-        match ShaderPipeline::new(
-            &self.gpu.device,
-            hdr_format,
-            &source,
-            self.gpu.pipeline_cache.as_ref(),
+        let Some(layer) = self.layer_stack.layers.get_mut(layer_idx) else {
+            return;
+        };
+        layer.param_store.values = values;
+        layer.param_store.changed = true;
+        if let Some(ps) = layer
+            .as_effect_mut()
+            .and_then(|e| e.pass_executor.particle_system.as_mut())
+        {
+            let emit_rate = old_def.emit_rate * ps.max_particles as f32 / old_capacity as f32;
+            ps.emit_rate = emit_rate;
+            ps.def.emit_rate = emit_rate;
+            ps.burst_on_beat = old_def.burst_on_beat;
+            ps.def.burst_on_beat = old_def.burst_on_beat;
+            ps.def.lifetime = old_def.lifetime;
+            ps.def.initial_speed = old_def.initial_speed;
+            ps.def.initial_size = old_def.initial_size;
+            ps.def.drag = old_def.drag;
+            ps.def.trigger_burst = old_def.trigger_burst;
+            ps.def.trigger_burst_quantize = old_def.trigger_burst_quantize;
+            ps.restore_source(&self.gpu.device, &self.gpu.queue, source);
+            ps.set_depth_sort(&self.gpu.device, old_def.depth_sort);
+            ps.set_trails(
+                &self.gpu.device,
+                GpuContext::hdr_format(),
+                old_def.trail_length,
+                old_def.trail_width,
+                old_def.trail_fade,
+            );
+        }
+    }
+
+    /// Apply a web message that drives the app itself (#2196), the way the
+    /// matching egui panel does. The result is the reply a request gets
+    /// (#2197).
+    fn apply_web_action(&mut self, action: WebAction) -> Result<serde_json::Value, RpcError> {
+        use serde_json::{Value, json};
+        let layer_count = self.layer_stack.layers.len();
+        let check_layer = |idx: usize| {
+            if idx < layer_count {
+                Ok(())
+            } else {
+                Err(RpcError::not_found(format!("no layer {idx}")))
+            }
+        };
+        match action {
+            WebAction::MediaPlaying(_)
+            | WebAction::MediaSeek(_)
+            | WebAction::MediaSpeed(_)
+            | WebAction::MediaLoop(_)
+            | WebAction::MediaDirection(_)
+            | WebAction::WebcamMirror(_) => self.apply_web_media(&action).map(|()| Value::Null),
+            WebAction::SavePreset(name) => {
+                self.save_preset(&name);
+                self.preset_store
+                    .presets
+                    .iter()
+                    .position(|(n, _)| *n == name)
+                    .map(|index| {
+                        let id = self.preset_store.presets[index].1.id;
+                        json!({ "index": index, "preset_id": id })
+                    })
+                    .ok_or_else(|| RpcError::failed("nothing loaded to save"))
+            }
+            WebAction::AddLayer => {
+                if layer_count >= self.max_layers() {
+                    return Err(RpcError::failed("layer limit reached"));
+                }
+                self.add_layer();
+                self.preset_store.mark_dirty();
+                Ok(json!({ "index": self.layer_stack.layers.len() - 1 }))
+            }
+            WebAction::RemoveLayer(idx) => {
+                check_layer(idx)?;
+                if layer_count == 1 {
+                    return Err(RpcError::failed("can't remove the last layer"));
+                }
+                self.remove_layer(idx);
+                self.sync_active_layer();
+                self.preset_store.mark_dirty();
+                #[cfg(feature = "webcam")]
+                self.cleanup_webcam_if_unused();
+                Ok(Value::Null)
+            }
+            WebAction::MoveLayer { from, to } => {
+                check_layer(from)?;
+                check_layer(to)?;
+                self.move_layer(from, to);
+                self.sync_active_layer();
+                self.preset_store.mark_dirty();
+                Ok(Value::Null)
+            }
+            WebAction::PostProcessParam { name, value } => {
+                let active = self.layer_stack.active_layer;
+                let Some(layer) = self.layer_stack.active_mut() else {
+                    return Err(RpcError::not_found("no active layer"));
+                };
+                if layer.locked {
+                    return Err(RpcError::locked(active));
+                }
+                if !layer.postprocess.set(&name, value) {
+                    return Err(RpcError::not_found(format!(
+                        "no post-processing setting {name}"
+                    )));
+                }
+                self.post_process.enabled = layer.postprocess.enabled;
+                self.post_edited();
+                Ok(Value::Null)
+            }
+            WebAction::ShaderSource(source) => {
+                self.editor_on_active_effect()?;
+                // Someone at the editor has unsaved edits of their own
+                if self.shader_editor.open && self.shader_editor.is_dirty() {
+                    return Err(RpcError::failed("the shader editor has unsaved changes"));
+                }
+                *self.shader_editor.wgsl_code_mut() = source;
+                self.lint_editor_shader();
+                Ok(Value::Null)
+            }
+            WebAction::GetShaderSource => {
+                self.editor_on_active_effect()?;
+                let (code, file_name) = self.shader_editor.wgsl_code();
+                Ok(json!({
+                    "effect_name": self.shader_editor.effect_name,
+                    "file_name": file_name,
+                    "source": code,
+                }))
+            }
+        }
+    }
+
+    /// Transport and mirror messages for the active media layer.
+    fn apply_web_media(&mut self, action: &WebAction) -> Result<(), RpcError> {
+        use crate::media::types::PlayDirection;
+        let Some(m) = self.layer_stack.active_mut().and_then(|l| l.as_media_mut()) else {
+            return Err(RpcError::not_found("the active layer isn't a media layer"));
+        };
+        match action {
+            WebAction::MediaPlaying(playing) => m.transport.playing = *playing,
+            WebAction::MediaSeek(secs) => m.seek_to_secs(*secs),
+            WebAction::MediaSpeed(speed) => m.transport.speed = *speed,
+            WebAction::MediaLoop(looping) => m.transport.looping = *looping,
+            WebAction::MediaDirection(dir) => {
+                m.transport.direction = match *dir {
+                    1 => PlayDirection::Reverse,
+                    2 => PlayDirection::PingPong,
+                    _ => PlayDirection::Forward,
+                };
+            }
+            #[cfg(feature = "webcam")]
+            WebAction::WebcamMirror(mirror) => m.set_mirror(&self.gpu.queue, *mirror),
+            #[cfg(not(feature = "webcam"))]
+            WebAction::WebcamMirror(_) => {
+                return Err(RpcError::failed("built without webcam support"));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Carry out one web request and reply to its sender (#2197). Returns
+    /// true when clients need the full state again.
+    fn answer_web_request(&mut self, req: WebRequest) -> bool {
+        let WebRequest {
+            id,
+            message,
+            reply_to,
+        } = req;
+        let refresh = match &*message {
+            WsInMessage::LoadPreset { .. } => true,
+            WsInMessage::Action(action) => action.changes_layers(),
+            _ => false,
+        };
+        let outcome = self.apply_web_request(*message);
+        if let Err(e) = &outcome {
+            log::debug!("Web request {id} refused: {}", e.message);
+        }
+        let applied = outcome.is_ok();
+        let _ = reply_to.try_send(crate::web::state::build_reply(&id, outcome));
+        refresh && applied
+    }
+
+    fn apply_web_request(&mut self, message: WsInMessage) -> Result<serde_json::Value, RpcError> {
+        use WsInMessage as M;
+        use serde_json::{Value, json};
+        match message {
+            M::Query(query) => {
+                let state = self.web_full_state();
+                Ok(match query {
+                    WebQuery::Effects => json!({ "effects": state["effects"] }),
+                    WebQuery::Presets => json!({
+                        "presets": state["presets"],
+                        "current": state["current_preset"],
+                    }),
+                    WebQuery::Layers => json!({
+                        "layers": state["layers"],
+                        "active": state["active_layer"],
+                    }),
+                    WebQuery::State => state,
+                })
+            }
+            M::SetParam { name, value } => {
+                let active = self.layer_stack.active_layer;
+                self.apply_web_request(M::SetLayerParam {
+                    layer: active,
+                    name,
+                    value,
+                })
+            }
+            M::SetLayerParam { layer, name, value } => {
+                let target = unlocked_layer(&mut self.layer_stack, layer)?;
+                let (defs, values, changed) = target.param_store.split_borrow();
+                if crate::web::apply_param(values, changed, defs, &name, value) {
+                    Ok(Value::Null)
+                } else {
+                    Err(RpcError::not_found(format!("no param {name}")))
+                }
+            }
+            M::LoadEffect { index } => {
+                let Some(effect) = self.effect_loader.effects.get(index) else {
+                    return Err(RpcError::not_found(format!("no effect {index}")));
+                };
+                if let Some(reason) = self.effect_loader.unsupported_reason(effect) {
+                    return Err(RpcError::failed(format!("can't run on this GPU. {reason}")));
+                }
+                let active = self.layer_stack.active_layer;
+                unlocked_layer(&mut self.layer_stack, active)?;
+                self.load_effect(index);
+                Ok(json!({ "layer": active }))
+            }
+            M::SelectLayer { index } => {
+                if index >= self.layer_stack.layers.len() {
+                    return Err(RpcError::not_found(format!("no layer {index}")));
+                }
+                self.layer_stack.active_layer = index;
+                self.sync_active_layer();
+                let msg = crate::web::state::build_active_layer_changed(index);
+                self.web.broadcast_json(&msg);
+                Ok(Value::Null)
+            }
+            M::SetLayerOpacity { layer, value } => {
+                unlocked_layer(&mut self.layer_stack, layer)?.opacity = value;
+                Ok(Value::Null)
+            }
+            M::SetLayerBlend { layer, value } => {
+                unlocked_layer(&mut self.layer_stack, layer)?.blend_mode =
+                    crate::gpu::layer::BlendMode::from_u32(value);
+                Ok(Value::Null)
+            }
+            M::SetLayerEnabled { layer, value } => {
+                unlocked_layer(&mut self.layer_stack, layer)?.enabled = value;
+                Ok(Value::Null)
+            }
+            M::Trigger(action) => {
+                self.pending_web_triggers.push(action);
+                Ok(Value::Null)
+            }
+            M::LoadPreset { preset } => {
+                let Some(index) = self.preset_store.resolve(preset) else {
+                    return Err(RpcError::not_found(match preset {
+                        PresetRef::Index(index) => format!("no preset {index}"),
+                        PresetRef::Id(id) => format!("no preset with id {id}"),
+                    }));
+                };
+                self.load_preset(index);
+                Ok(Value::Null)
+            }
+            M::PostProcessEnabled(enabled) => {
+                self.post_process.enabled = enabled;
+                if let Some(layer) = self.layer_stack.active_mut() {
+                    layer.postprocess.enabled = enabled;
+                }
+                Ok(Value::Null)
+            }
+            M::Action(action) => self.apply_web_action(action),
+            // Bridge data and heartbeats are settled by the web system; requests don't nest.
+            M::BindData { .. }
+            | M::BindSchema { .. }
+            | M::BindPreview { .. }
+            | M::Heartbeat
+            | M::Request(_) => Ok(Value::Null),
+        }
+    }
+
+    /// Full state snapshot for web clients.
+    fn web_full_state(&self) -> serde_json::Value {
+        let layer_infos = self.layer_stack.layer_infos(&self.effect_loader.effects);
+        let layer_data: Vec<_> = self
+            .layer_stack
+            .layers
+            .iter()
+            .map(|l| {
+                (
+                    &l.param_store,
+                    l.effect_index(),
+                    l.blend_mode,
+                    l.opacity,
+                    l.enabled,
+                    l.locked,
+                )
+            })
+            .collect();
+        crate::web::state::build_full_state(
+            &self.effect_loader.effects,
+            &layer_infos,
+            self.layer_stack.active_layer,
+            &layer_data,
+            &self.preset_store,
+            self.post_process.enabled,
+            self.master.blackout,
+        )
+    }
+
+    /// Mirror the primary while this instance is its standby (#2204): its
+    /// preset, layers, effects, params, post-processing and blackout, plus
+    /// the triggers it sent on. Presets and effects are matched by name, so
+    /// the two machines' lists needn't be in the same order.
+    fn follow_primary(&mut self) {
+        let (state, triggers) = self.web.poll_follower();
+        self.pending_web_triggers.extend(triggers);
+        if state.is_some() {
+            self.follow_state = state;
+        }
+        // A preset with media loads in the background; mirror once it's in
+        if matches!(self.preset_loader.state, PresetLoadingState::Loading { .. }) {
+            return;
+        }
+        let Some(state) = self.follow_state.take() else {
+            return;
+        };
+
+        let current = state["current_preset"]
+            .as_u64()
+            .and_then(|i| state["presets"].get(usize::try_from(i).ok()?));
+        let preset = current.and_then(|p| p["name"].as_str()).map(str::to_string);
+        // Copied preset files carry their id (#2223), so match on it first; a
+        // name that differs between the machines still finds the same preset.
+        let preset_id = current.and_then(|p| p["id"].as_u64());
+        if preset != self.followed_preset {
+            self.followed_preset.clone_from(&preset);
+            if let Some(name) = preset {
+                let by_id = preset_id.and_then(|id| self.preset_store.index_of_id(id));
+                match by_id.or_else(|| {
+                    self.preset_store
+                        .presets
+                        .iter()
+                        .position(|(n, _)| *n == name)
+                }) {
+                    Some(index) => {
+                        self.load_preset(index);
+                        if matches!(self.preset_loader.state, PresetLoadingState::Loading { .. }) {
+                            self.follow_state = Some(state);
+                            return;
+                        }
+                    }
+                    None => log::warn!("Following: no preset named '{name}' here"),
+                }
+            }
+        }
+
+        let Some(layers) = state["layers"].as_array() else {
+            return;
+        };
+        let count = layers.len().clamp(1, self.max_layers());
+        while self.layer_stack.layers.len() < count {
+            let before = self.layer_stack.layers.len();
+            self.add_layer();
+            if self.layer_stack.layers.len() == before {
+                break;
+            }
+        }
+        while self.layer_stack.layers.len() > count {
+            self.layer_stack
+                .remove_layer(self.layer_stack.layers.len() - 1);
+        }
+
+        for (i, mirrored) in layers
+            .iter()
+            .enumerate()
+            .take(self.layer_stack.layers.len())
+        {
+            if self.layer_stack.layers[i].locked {
+                continue;
+            }
+            if let Some(name) = mirrored["effect_name"].as_str() {
+                let current = self.layer_stack.layers[i]
+                    .effect_index()
+                    .and_then(|e| self.effect_loader.effects.get(e));
+                if current.is_none_or(|e| e.name != name) {
+                    match self
+                        .effect_loader
+                        .effects
+                        .iter()
+                        .position(|e| e.name == name)
+                    {
+                        Some(index) => self.load_effect_on_layer(i, index),
+                        None => log::debug!("Following: no effect named '{name}' here"),
+                    }
+                }
+            }
+            let layer = &mut self.layer_stack.layers[i];
+            if let Some(opacity) = mirrored["opacity"].as_f64() {
+                layer.opacity = opacity as f32;
+            }
+            if let Some(blend) = mirrored["blend_mode"].as_u64() {
+                layer.blend_mode = crate::gpu::layer::BlendMode::from_u32(blend as u32);
+            }
+            if let Some(enabled) = mirrored["enabled"].as_bool() {
+                layer.enabled = enabled;
+            }
+            if let Some(muted) = mirrored["audio_muted"].as_bool() {
+                layer.audio_muted = muted;
+            }
+            let (defs, values, changed) = layer.param_store.split_borrow();
+            for param in mirrored["params"].as_array().into_iter().flatten() {
+                if let (Some(name), Some(value)) = (param["name"].as_str(), param["value"].as_f64())
+                {
+                    crate::web::apply_param(values, changed, defs, name, value as f32);
+                }
+            }
+        }
+
+        if let Some(active) = state["active_layer"]
+            .as_u64()
+            .and_then(|i| usize::try_from(i).ok())
+            && active < self.layer_stack.layers.len()
+        {
+            self.layer_stack.active_layer = active;
+        }
+        self.sync_active_layer();
+        if let Some(on) = state["postprocess_enabled"].as_bool() {
+            self.post_process.enabled = on;
+            if let Some(layer) = self.layer_stack.active_mut() {
+                layer.postprocess.enabled = on;
+            }
+        }
+        if let Some(on) = state["blackout"].as_bool() {
+            self.master.blackout = on;
+        }
+    }
+
+    /// Load the active effect's shader into the editor for a remote client
+    /// unless it's already there. Built-in effects are refused, as the panel's
+    /// Edit button refuses them, and so is replacing a buffer with unsaved
+    /// edits. The editor stays shown or hidden as it was, so the overlay
+    /// never pops up over the output.
+    fn editor_on_active_effect(&mut self) -> Result<(), RpcError> {
+        let Some(effect) = self
+            .layer_stack
+            .active()
+            .and_then(|l| l.effect_index())
+            .and_then(|i| self.effect_loader.effects.get(i))
+        else {
+            return Err(RpcError::not_found("no effect on the active layer"));
+        };
+        if EffectLoader::is_builtin(effect) {
+            return Err(RpcError::failed("built-in effects can't be edited"));
+        }
+        if self.shader_editor.file_path.is_some() && effect.name == self.shader_editor.effect_name {
+            return Ok(());
+        }
+        if self.shader_editor.is_dirty() || self.shader_editor.paired_is_dirty() {
+            return Err(RpcError::failed("the shader editor has unsaved changes"));
+        }
+        let (open, minimized) = (self.shader_editor.open, self.shader_editor.minimized);
+        self.open_shader_editor();
+        self.shader_editor.open = open;
+        self.shader_editor.minimized = minimized;
+        if self.shader_editor.file_path.is_none() {
+            return Err(RpcError::failed("no shader to edit on the active layer"));
+        }
+        Ok(())
+    }
+
+    /// Layer cap from settings (#2170).
+    pub fn max_layers(&self) -> usize {
+        self.settings.max_layers.max(1)
+    }
+
+    /// Add a new empty layer with the default shader.
+    pub fn add_layer(&mut self) {
+        let num = self.layer_stack.layers.len();
+        if num >= self.max_layers() {
+            return;
+        }
+        let name = format!("Layer {}", num + 1);
+        let hdr_format = GpuContext::hdr_format();
+
+        let source = read_default_shader();
+
+        let uniform_buffer = UniformBuffer::new(&self.gpu.device);
+        let feedback = PingPongTarget::new_cleared(
+            &self.gpu.device,
+            &self.gpu.queue,
+            self.gpu.surface_config.width,
+            self.gpu.surface_config.height,
+            hdr_format,
+            1.0,
+        );
+
+        match ShaderPipeline::new(
+            &self.gpu.device,
+            hdr_format,
+            &source,
+            self.gpu.pipeline_cache.as_ref(),
             0,
         ) {
             Ok(pipeline) => {
@@ -2078,12 +2965,12 @@ impl App {
     /// Add a new media layer from a file path.
     pub fn add_media_layer(&mut self, path: std::path::PathBuf) {
         let num = self.layer_stack.layers.len();
-        if num >= 8 {
-            log::warn!("Maximum 8 layers reached");
+        if num >= self.max_layers() {
+            log::warn!("Maximum {} layers reached", self.max_layers());
             return;
         }
 
-        match crate::media::decoder::load_media(&path) {
+        match self.media_cache.load(&path) {
             Ok(source) => {
                 let hdr_format = GpuContext::hdr_format();
                 let media_layer = MediaLayer::new(
@@ -2103,6 +2990,7 @@ impl App {
                 self.layer_stack.active_layer = self.layer_stack.layers.len() - 1;
                 self.sync_active_layer();
                 log::info!("Added media layer: {}", file_name);
+                self.remember_media(&path);
             }
             Err(e) => {
                 log::error!("Failed to load media '{}': {e}", path.display());
@@ -2111,18 +2999,43 @@ impl App {
         }
     }
 
+    /// Add a built-in generator layer (#2168) with the default strobe.
+    pub fn add_generator_layer(&mut self) {
+        let num = self.layer_stack.layers.len();
+        if num >= self.max_layers() {
+            log::warn!("Maximum {} layers reached", self.max_layers());
+            return;
+        }
+
+        let generator = GeneratorLayer::new(
+            &self.gpu.device,
+            GpuContext::hdr_format(),
+            self.gpu.surface_config.width,
+            self.gpu.surface_config.height,
+            GeneratorDef::default(),
+        );
+        let name = format!("Layer {}", num + 1);
+        self.layer_stack
+            .layers
+            .push(Layer::new_generator(name, generator));
+        self.layer_stack.active_layer = self.layer_stack.layers.len() - 1;
+        self.sync_active_layer();
+        log::info!("Added generator layer");
+    }
+
     /// Add a webcam layer. Starts capture if not already running.
     #[cfg(feature = "webcam")]
     pub fn add_webcam_layer(&mut self, device_index: u32) {
         let num = self.layer_stack.layers.len();
-        if num >= 8 {
-            log::warn!("Maximum 8 layers reached");
+        if num >= self.max_layers() {
+            log::warn!("Maximum {} layers reached", self.max_layers());
             return;
         }
 
         // Start capture if not already running
         if self.webcam_capture.is_none() {
-            match self.start_webcam(device_index, Some((1280, 720))) {
+            self.refresh_webcam_modes(device_index);
+            match self.start_webcam(device_index) {
                 Ok(capture) => {
                     self.webcam_capture = Some(capture);
                 }
@@ -2141,10 +3054,10 @@ impl App {
         let (w, h) = capture.resolution();
         let device_name = capture.device_name().to_string();
 
-        let source = crate::media::decoder::MediaSource::Live {
+        let source = std::sync::Arc::new(crate::media::decoder::MediaSource::Live {
             width: w,
             height: h,
-        };
+        });
         let hdr_format = GpuContext::hdr_format();
         let media_layer = MediaLayer::new(
             &self.gpu.device,
@@ -2187,64 +3100,569 @@ impl App {
         }
     }
 
-    /// Start webcam capture using the active backend (native or ffmpeg).
+    /// Start webcam capture in `webcam_mode` using the active backend (native or ffmpeg).
     #[cfg(feature = "webcam")]
-    pub fn start_webcam(
-        &self,
-        device_index: u32,
-        resolution: Option<(u32, u32)>,
-    ) -> Result<WebcamBackend, String> {
+    pub fn start_webcam(&self, device_index: u32) -> Result<WebcamBackend, String> {
+        let mode = Some(self.webcam_mode);
         if self.use_ffmpeg_webcam {
-            // For ffmpeg, resolve device index to device name
-            let device_name = self
-                .webcam_devices
-                .iter()
-                .find(|(idx, _)| *idx == device_index)
-                .map(|(_, name)| name.clone())
-                .unwrap_or_else(|| format!("Camera {device_index}"));
-            WebcamBackend::start_ffmpeg(&device_name, resolution)
+            WebcamBackend::start_ffmpeg(&self.webcam_device_name(device_index), mode)
+        } else {
+            WebcamBackend::start_native(device_index, mode)
+        }
+    }
+
+    /// Backend identifier for a device index (ffmpeg addresses devices by name).
+    #[cfg(feature = "webcam")]
+    fn webcam_device_name(&self, device_index: u32) -> String {
+        self.webcam_devices
+            .iter()
+            .find(|(idx, _)| *idx == device_index)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| format!("Camera {device_index}"))
+    }
+
+    /// Query the modes a device supports. Must run while the device is not
+    /// streaming — callers stop the capture first.
+    #[cfg(feature = "webcam")]
+    pub fn refresh_webcam_modes(&mut self, device_index: u32) {
+        let modes = if self.use_ffmpeg_webcam {
+            crate::media::webcam_ffmpeg::list_modes(&self.webcam_device_name(device_index))
+        } else {
+            crate::media::webcam::list_modes(device_index)
+        };
+        self.webcam_modes = modes.unwrap_or_else(|e| {
+            log::warn!("Could not list webcam modes: {e}");
+            Vec::new()
+        });
+    }
+
+    /// Restart the shared capture on `device_index` in `mode`, rebuilding every
+    /// live layer at the new frame size. On failure the previous device/mode
+    /// is restored and the error returned.
+    #[cfg(feature = "webcam")]
+    pub fn restart_webcam(
+        &mut self,
+        device_index: u32,
+        mode: crate::media::types::WebcamMode,
+    ) -> Result<(), String> {
+        let (old_idx, old_mode) = (self.webcam_device_index, self.webcam_mode);
+        // Release the device before probing or reopening it
+        self.webcam_capture = None;
+        if device_index != old_idx || self.webcam_modes.is_empty() {
+            self.refresh_webcam_modes(device_index);
+        }
+        self.webcam_mode = mode;
+        match self.start_webcam(device_index) {
+            Ok(capture) => {
+                self.webcam_capture = Some(capture);
+                self.webcam_device_index = device_index;
+                self.settings.webcam_device = Some(device_index);
+                self.settings.webcam_mode = Some(mode);
+                self.settings.save();
+                self.rebuild_live_layers();
+                Ok(())
+            }
+            Err(e) => {
+                self.webcam_mode = old_mode;
+                if device_index != old_idx {
+                    self.refresh_webcam_modes(old_idx);
+                }
+                match self.start_webcam(old_idx) {
+                    Ok(capture) => self.webcam_capture = Some(capture),
+                    Err(e2) => log::error!("Failed to restore previous webcam: {e2}"),
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Recreate live media layers to match the running capture's frame size
+    /// and device name, keeping each layer's mirror setting.
+    #[cfg(feature = "webcam")]
+    fn rebuild_live_layers(&mut self) {
+        let Some(ref capture) = self.webcam_capture else {
+            return;
+        };
+        let (w, h) = capture.resolution();
+        let device_name = capture.device_name().to_string();
+        let hdr_format = GpuContext::hdr_format();
+        for layer in &mut self.layer_stack.layers {
+            let Some(mirror) = layer.as_media().filter(|m| m.is_live()).map(|m| m.mirror) else {
+                continue;
+            };
+            let mut media_layer = MediaLayer::new(
+                &self.gpu.device,
+                &self.gpu.queue,
+                hdr_format,
+                self.gpu.surface_config.width,
+                self.gpu.surface_config.height,
+                std::sync::Arc::new(crate::media::decoder::MediaSource::Live {
+                    width: w,
+                    height: h,
+                }),
+                std::path::PathBuf::from(&device_name),
+            );
+            media_layer.set_mirror(&self.gpu.queue, mirror);
+            layer.content = LayerContent::Media(Box::new(media_layer));
+        }
+    }
+
+    /// Refresh the webcam device list using the active backend.
+    #[cfg(feature = "webcam")]
+    pub fn refresh_webcam_devices(&mut self) {
+        self.webcam_devices = if self.use_ffmpeg_webcam {
+            crate::media::webcam_ffmpeg::list_devices().unwrap_or_default()
+        } else {
+            crate::media::webcam::list_devices().unwrap_or_default()
+        };
+    }
+
+    /// Put a file or stream URL the user opened at the top of the recent
+    /// media list (#2245). Preset restores don't go through here.
+    pub fn remember_media(&mut self, path: &std::path::Path) {
+        if push_recent(&mut self.settings.recent_media, &path.to_string_lossy()) {
+            self.settings.save();
+        }
+    }
+
+    /// Replace active layer content with media from a file path.
+    /// Returns whether the media loaded.
+    pub fn load_media_on_layer(&mut self, layer_idx: usize, path: std::path::PathBuf) -> bool {
+        if layer_idx >= self.layer_stack.layers.len() {
+            return false;
+        }
+
+        match self.media_cache.load(&path) {
+            Ok(source) => {
+                let hdr_format = GpuContext::hdr_format();
+                let media_layer = MediaLayer::new(
+                    &self.gpu.device,
+                    &self.gpu.queue,
+                    hdr_format,
+                    self.gpu.surface_config.width,
+                    self.gpu.surface_config.height,
+                    source,
+                    path.clone(),
+                );
+                let file_name = media_layer.file_name.clone();
+                let layer = &mut self.layer_stack.layers[layer_idx];
+                layer.content = LayerContent::Media(Box::new(media_layer));
+                layer.param_store = ParamStore::new();
+                log::info!("Layer {}: loaded media '{}'", layer_idx, file_name);
+                true
+            }
+            Err(e) => {
+                log::error!("Failed to load media '{}': {e}", path.display());
+                false
+            }
+        }
+    }
+
+    /// Run the sustained-silence action (#2213): fade the master, or swap to
+    /// the idle preset and back when the music returns.
+    fn update_silence(&mut self, dt: f32) {
+        use crate::audio::silence::{SilenceAction, SilenceEvent};
+        let config = &self.settings.silence;
+        let event = self
+            .silence
+            .update(config, self.audio.silence_duration(), dt);
+        // Attract mode shows its presets instead of the fade
+        self.master.idle_fade = if self.attract.is_active() {
+            0.0
+        } else {
+            self.silence.fade()
+        };
+        match event {
+            Some(SilenceEvent::Began) if config.action == SilenceAction::IdlePreset => {
+                let idle = self
+                    .preset_store
+                    .presets
+                    .iter()
+                    .position(|(name, _)| *name == config.idle_preset);
+                let Some(idle) = idle else {
+                    log::warn!("Idle preset '{}' not found", config.idle_preset);
+                    return;
+                };
+                if self.preset_store.current_preset != Some(idle) {
+                    self.silence_return = self
+                        .preset_store
+                        .current_preset
+                        .and_then(|i| self.preset_store.presets.get(i))
+                        .map(|(_, p)| p.id);
+                    self.load_preset(idle);
+                }
+            }
+            Some(SilenceEvent::Ended) => {
+                // Only go back if nobody picked another preset meanwhile.
+                let idle_still_up = self.preset_store.current_name()
+                    == Some(self.settings.silence.idle_preset.as_str());
+                if let Some(previous) = self.silence_return.take()
+                    && idle_still_up
+                    && let Some(index) = self.preset_store.index_of_id(previous)
+                {
+                    self.load_preset(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Someone used the window: a key, a click or the pointer moving.
+    pub fn note_input(&mut self) {
+        self.last_input = Instant::now();
+    }
+
+    /// How long there has been neither sound nor any input — the window,
+    /// MIDI, OSC or the web remote. Zero while there's sound.
+    fn idle_duration(&self) -> Duration {
+        let silent = self.audio.silence_duration();
+        [
+            Some(self.last_input),
+            self.midi.last_activity,
+            self.osc.last_activity,
+            self.web.last_activity,
+        ]
+        .into_iter()
+        .flatten()
+        .map(|t| t.elapsed())
+        .fold(silent, Duration::min)
+    }
+
+    /// Run attract mode (#2239): save the stack and dissolve through the
+    /// attract presets while idle, and put the stack back on activity.
+    fn update_attract(&mut self, dt: f32) {
+        let allowed = matches!(
+            self.timeline.state,
+            crate::scene::timeline::PlaybackState::Idle
+        );
+        let idle_for = self.idle_duration();
+        let event = self
+            .attract
+            .update(&self.settings.attract, idle_for, allowed, dt);
+        match event {
+            Some(AttractEvent::Started) => {
+                log::info!("Attract mode started");
+                self.attract_return = Some(self.current_session());
+                self.show_attract_preset(self.attract.slot());
+            }
+            Some(AttractEvent::Next(slot)) => self.show_attract_preset(slot),
+            Some(AttractEvent::Stopped) => {
+                self.dissolve_capture_pending = None;
+                if let Some(previous) = self.attract_return.take() {
+                    self.restore_session(previous);
+                    log::info!("Attract mode stopped; stack restored");
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Dissolve to the attract preset in `slot`, by name.
+    fn show_attract_preset(&mut self, slot: usize) {
+        let Some(name) = self.settings.attract.presets.get(slot) else {
+            return;
+        };
+        let Some(index) = self
+            .preset_store
+            .presets
+            .iter()
+            .position(|(n, _)| n == name)
+        else {
+            log::warn!("Attract preset '{name}' not found");
+            return;
+        };
+        if self.transition_renderer.is_none() {
+            self.transition_renderer = Some(TransitionRenderer::new(
+                &self.gpu.device,
+                GpuContext::hdr_format(),
+            ));
+        }
+        // render() captures the outgoing frame, then loads the preset
+        self.dissolve_capture_pending = Some(index);
+        self.attract.start_fade(self.settings.attract.fade_secs);
+    }
+
+    /// How far the dissolve on screen is: a scene cue's, or attract mode's.
+    fn dissolve_progress(&self) -> Option<f32> {
+        match &self.timeline.state {
+            crate::scene::timeline::PlaybackState::Transitioning {
+                transition_type: crate::scene::types::TransitionType::Dissolve,
+                progress,
+                ..
+            } => Some(*progress),
+            _ => self.attract.dissolve_progress(),
+        }
+    }
+
+    /// Controller watchdog (#2240): when the OSC or web controller stops
+    /// pinging, black out or load the safe preset. Runs last of the master
+    /// fades, so its blackout wins.
+    fn update_watchdog(&mut self, dt: f32) {
+        let since_ping = [self.osc.last_heartbeat, self.web.last_heartbeat]
+            .into_iter()
+            .flatten()
+            .map(|t| t.elapsed())
+            .min();
+        let config = &self.settings.watchdog;
+        match self.watchdog.update(config, since_ping, dt) {
+            Some(WatchdogEvent::Tripped) => {
+                log::warn!(
+                    "No controller heartbeat for {:.0}s: {}",
+                    config.timeout_secs,
+                    config.action.display_name().to_lowercase()
+                );
+                if config.action == WatchdogAction::SafePreset {
+                    match self
+                        .preset_store
+                        .presets
+                        .iter()
+                        .position(|(name, _)| *name == config.safe_preset)
+                    {
+                        Some(index) => self.load_preset(index),
+                        None => log::warn!("Safe preset '{}' not found", config.safe_preset),
+                    }
+                }
+            }
+            Some(WatchdogEvent::Recovered) => log::info!("Controller heartbeat back"),
+            None => {}
+        }
+        self.master.idle_fade = self.master.idle_fade.max(self.watchdog.fade());
+    }
+
+    /// Installation mode (#2233): black out outside opening hours and bring
+    /// a dead webcam back. Runs after [`Self::update_silence`], whose fade it
+    /// shares the master with.
+    fn update_installation(&mut self, dt: f32) {
+        let config = &self.settings.installation;
+        let minute = if config.enabled && config.schedule {
+            self.local_clock.minute_of_day()
+        } else {
+            None
+        };
+        match self.schedule.update(config, minute, dt) {
+            Some(ScheduleEvent::Closed) => log::info!("Outside opening hours: output off"),
+            Some(ScheduleEvent::Opened) => log::info!("Opening hours: output on"),
+            None => {}
+        }
+        self.master.idle_fade = self.master.idle_fade.max(self.schedule.fade());
+
+        #[cfg(feature = "webcam")]
+        if let Some(at) = self.webcam_retry_at
+            && Instant::now() >= at
+        {
+            self.webcam_retry_at = None;
+            if self.settings.installation.enabled && self.webcam_capture.is_none() {
+                match self.start_webcam(self.webcam_device_index) {
+                    Ok(capture) => {
+                        log::info!("Webcam capture restarted");
+                        self.webcam_capture = Some(capture);
+                        self.rebuild_live_layers();
+                    }
+                    Err(e) => {
+                        log::warn!("Webcam restart failed: {e}");
+                        self.webcam_retry_at =
+                            Some(Instant::now() + crate::installation::WEBCAM_RETRY);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Installation mode is on and hasn't been unlocked this run: no panels,
+    /// no shortcuts, no cursor.
+    pub fn installation_locked(&self) -> bool {
+        self.settings.installation.enabled && !self.installation_unlocked
+    }
+
+    /// Bring the window and the watchdogs in line with the installation
+    /// setting, at launch and whenever it or the lock changes.
+    pub fn apply_installation(&mut self) {
+        let enabled = self.settings.installation.enabled;
+        self.audio
+            .set_auto_reconnect(self.settings.auto_reconnect || enabled);
+        self.audio.set_persistent_reconnect(enabled);
+        let locked = self.installation_locked();
+        self.window.set_cursor_visible(!locked);
+        if locked {
+            self.egui_overlay.visible = false;
+            self.shader_editor.open = false;
+        }
+    }
+
+    /// How long to wait before the next frame when there's no need to render
+    /// flat out: outside opening hours, once the output is dark.
+    pub fn idle_frame_interval(&self) -> Option<std::time::Duration> {
+        (self.schedule.is_dark() && !self.egui_overlay.visible)
+            .then_some(crate::installation::CLOSED_FRAME_INTERVAL)
+    }
+
+    /// Drive media-layer playlists: auto-advance on clip end or every N beats,
+    /// and swap in clips whose background decode has finished.
+    fn update_playlists(&mut self) {
+        let beat_on = if self.midi_clock.playing() {
+            self.midi_clock_beat_crossed
         } else {
-            WebcamBackend::start_native(device_index, resolution)
+            self.uniforms.beat > 0.5
+        };
+        let mut steps: Vec<usize> = Vec::new();
+        let mut ready: Vec<(usize, crate::media::playlist::ClipDecode)> = Vec::new();
+        for (i, layer) in self.layer_stack.layers.iter_mut().enumerate() {
+            let finished = layer.as_media_mut().is_some_and(|m| m.take_finished());
+            let Some(pl) = layer.playlist.as_mut() else {
+                continue;
+            };
+            if let Some(ref rx) = pl.pending {
+                match rx.try_recv() {
+                    Ok(result) => {
+                        pl.pending = None;
+                        ready.push((i, result));
+                    }
+                    Err(crossbeam_channel::TryRecvError::Disconnected) => pl.pending = None,
+                    Err(crossbeam_channel::TryRecvError::Empty) => {}
+                }
+                continue;
+            }
+            let by_beat = pl.feed_beat(beat_on);
+            let by_end = finished && pl.advance == crate::media::playlist::PlaylistAdvance::OnEnd;
+            if (by_beat || by_end) && pl.clips.len() > 1 {
+                steps.push(i);
+            }
+        }
+        for (i, (path, result)) in ready {
+            match result {
+                Ok(source) => {
+                    // Carry speed/loop and projection across clips so a reel keeps one feel.
+                    let transport = self
+                        .layer_stack
+                        .layers
+                        .get(i)
+                        .and_then(|l| l.as_media())
+                        .map(|m| {
+                            (
+                                m.transport.speed,
+                                m.transport.looping,
+                                m.transport.audio,
+                                m.projection,
+                            )
+                        });
+                    self.create_media_layer_from_source(i, source, &path);
+                    if let (Some((speed, looping, audio, projection)), Some(m)) = (
+                        transport,
+                        self.layer_stack
+                            .layers
+                            .get_mut(i)
+                            .and_then(|l| l.as_media_mut()),
+                    ) {
+                        m.transport.speed = speed;
+                        m.transport.looping = looping;
+                        m.transport.audio = audio;
+                        m.set_projection(&self.gpu.queue, projection);
+                    }
+                }
+                Err(e) => {
+                    log::error!("Playlist clip '{}' failed: {e}", path.display());
+                    self.status_error = Some((format!("Playlist: {e}"), Instant::now()));
+                }
+            }
         }
+        for i in steps {
+            self.playlist_step(i, true);
+        }
+    }
+
+    /// Step a layer's playlist forward or back and start decoding the new clip.
+    /// A locked layer keeps its clip and its place in the list.
+    pub fn playlist_step(&mut self, layer_idx: usize, forward: bool) {
+        let Some(pl) = self
+            .layer_stack
+            .layers
+            .get_mut(layer_idx)
+            .filter(|l| !l.locked)
+            .and_then(|l| l.playlist.as_mut())
+        else {
+            return;
+        };
+        let clip = if forward { pl.next() } else { pl.prev() };
+        if clip.is_some() {
+            self.load_playlist_clip(layer_idx);
+        }
+    }
+
+    /// Jump a layer's playlist to a specific clip, unless the layer is locked.
+    pub fn playlist_jump(&mut self, layer_idx: usize, clip: usize) {
+        let jumped = self
+            .layer_stack
+            .layers
+            .get_mut(layer_idx)
+            .filter(|l| !l.locked)
+            .and_then(|l| l.playlist.as_mut())
+            .and_then(|pl| pl.jump_to(clip));
+        if jumped.is_some() {
+            self.load_playlist_clip(layer_idx);
+        }
+    }
+
+    /// Decode the playlist's current clip on a background thread. The layer
+    /// keeps showing the previous clip until the decode lands.
+    pub fn load_playlist_clip(&mut self, layer_idx: usize) {
+        let Some(layer) = self.layer_stack.layers.get_mut(layer_idx) else {
+            return;
+        };
+        if layer.locked {
+            return;
+        }
+        let Some(pl) = layer.playlist.as_mut() else {
+            return;
+        };
+        let Some(path) = pl.current().map(|p| p.to_path_buf()) else {
+            return;
+        };
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        pl.pending = Some(rx);
+        let cache = self.media_cache.clone();
+        std::thread::Builder::new()
+            .name("playlist-decode".into())
+            .spawn(move || {
+                let result = cache.load(&path);
+                let _ = tx.send((path, result));
+            })
+            .ok();
     }
 
-    /// Refresh the webcam device list using the active backend.
-    #[cfg(feature = "webcam")]
-    pub fn refresh_webcam_devices(&mut self) {
-        self.webcam_devices = if self.use_ffmpeg_webcam {
-            crate::media::webcam_ffmpeg::list_devices().unwrap_or_default()
-        } else {
-            crate::media::webcam::list_devices().unwrap_or_default()
+    /// Add clips to a layer's playlist, creating the playlist (seeded with the
+    /// layer's current file) on first use.
+    pub fn playlist_add_clips(&mut self, layer_idx: usize, paths: Vec<std::path::PathBuf>) {
+        let Some(layer) = self.layer_stack.layers.get_mut(layer_idx) else {
+            return;
+        };
+        let Some(m) = layer.as_media() else {
+            return;
         };
+        if m.is_live() {
+            return;
+        }
+        let current = m.file_path.clone();
+        layer
+            .playlist
+            .get_or_insert_with(|| crate::media::playlist::MediaPlaylist::new(vec![current]))
+            .add_clips(paths);
     }
 
-    /// Replace active layer content with media from a file path.
-    pub fn load_media_on_layer(&mut self, layer_idx: usize, path: std::path::PathBuf) {
-        if layer_idx >= self.layer_stack.layers.len() {
-            return;
+    /// Remove a layer, keeping the debug view on the texture it picked (#2229).
+    pub fn remove_layer(&mut self, index: usize) {
+        let len = self.layer_stack.layers.len();
+        self.layer_stack.remove_layer(index);
+        if self.layer_stack.layers.len() < len {
+            self.debug_view.follow_layer_removal(index);
         }
+    }
 
-        match crate::media::decoder::load_media(&path) {
-            Ok(source) => {
-                let hdr_format = GpuContext::hdr_format();
-                let media_layer = MediaLayer::new(
-                    &self.gpu.device,
-                    &self.gpu.queue,
-                    hdr_format,
-                    self.gpu.surface_config.width,
-                    self.gpu.surface_config.height,
-                    source,
-                    path.clone(),
-                );
-                let file_name = media_layer.file_name.clone();
-                let layer = &mut self.layer_stack.layers[layer_idx];
-                layer.content = LayerContent::Media(Box::new(media_layer));
-                layer.param_store = ParamStore::new();
-                log::info!("Layer {}: loaded media '{}'", layer_idx, file_name);
-            }
-            Err(e) => {
-                log::error!("Failed to load media '{}': {e}", path.display());
-            }
+    /// Move a layer, keeping the debug view on the texture it picked (#2229).
+    pub fn move_layer(&mut self, from: usize, to: usize) {
+        let len = self.layer_stack.layers.len();
+        if from < len && to < len {
+            self.layer_stack.move_layer(from, to);
+            self.debug_view.follow_layer_move(from, to);
         }
     }
 
@@ -2352,10 +3770,18 @@ impl App {
                 }
             }
             "layer" => {
-                // layer.{n}.opacity or layer.{n}.blend or layer.{n}.enabled
+                // layer.{n}.opacity, layer.{n}.blend, layer.{n}.enabled,
+                // layer.{n}.playlist_next, layer.{n}.playlist_prev
                 let mut segs = rest.splitn(2, '.');
                 if let (Some(idx_str), Some(field)) = (segs.next(), segs.next()) {
                     if let Ok(idx) = idx_str.parse::<usize>() {
+                        // layer.{n}.playlist_next / playlist_prev — edge-triggered
+                        if let Some(dir) = field.strip_prefix("playlist_") {
+                            if rising {
+                                self.playlist_step(idx, dir == "next");
+                            }
+                            return;
+                        }
                         if let Some(layer) = self.layer_stack.layers.get_mut(idx) {
                             match field {
                                 "opacity" => {
@@ -2385,6 +3811,9 @@ impl App {
                         layer.opacity = clamped;
                     }
                 }
+            "global"
+                // global.preset_crossfade (#2250)
+                if rest == "preset_crossfade" => self.set_preset_crossfade(value),
             "scene" => {
                 // scene.transport.go / scene.transport.prev / scene.transport.stop
                 // Edge-triggered (#1791): fire only on the frame the output
@@ -2537,6 +3966,16 @@ impl App {
                         "contrast_5" => self.uniforms.contrast_5 = v,
                         "contrast_mean" => self.uniforms.contrast_mean = v,
                         "timbre_flux" => self.uniforms.timbre_flux = v,
+                        "crest" => self.uniforms.crest = v,
+                        "impact" => self.uniforms.impact = v,
+                        "env_bass_fast" => self.uniforms.env_bass_fast = v,
+                        "env_bass_slow" => self.uniforms.env_bass_slow = v,
+                        "env_mid_fast" => self.uniforms.env_mid_fast = v,
+                        "env_mid_slow" => self.uniforms.env_mid_slow = v,
+                        "env_high_fast" => self.uniforms.env_high_fast = v,
+                        "env_high_slow" => self.uniforms.env_high_slow = v,
+                        "mic_rms" => self.uniforms.mic_rms = v,
+                        "mic_onset" => self.uniforms.mic_onset = v,
                         "feedback_decay" => self.uniforms.feedback_decay = v,
                         "time" => self.uniforms.time = value, // time not clamped
                         _ => {}
@@ -2546,9 +3985,9 @@ impl App {
         }
     }
 
-    pub fn save_preset(&mut self, name: &str) {
-        let layer_presets: Vec<LayerPreset> = self
-            .layer_stack
+    /// The layer stack as preset entries, as `save_preset` writes them.
+    fn layer_presets(&self) -> Vec<LayerPreset> {
+        self.layer_stack
             .layers
             .iter()
             .map(|l| {
@@ -2562,10 +4001,23 @@ impl App {
                     .map(|m| m.file_path.to_string_lossy().to_string());
                 let media_speed = l.as_media().map(|m| m.transport.speed);
                 let media_looping = l.as_media().map(|m| m.transport.looping);
+                let media_audio = l
+                    .as_media()
+                    .map(|m| m.transport.audio)
+                    .filter(|a| a.mode != crate::media::types::AudioDriveMode::Off);
+                let media_projection = l
+                    .as_media()
+                    .map(|m| m.projection)
+                    .filter(|p| p.mode != crate::media::types::ProjectionMode::Flat);
+                let media_playlist = l.playlist.as_ref().map(|pl| pl.to_def());
                 let webcam_device = l
                     .as_media()
                     .filter(|m| m.is_live())
                     .map(|m| m.file_name.clone());
+                #[cfg(feature = "webcam")]
+                let webcam_mode = webcam_device.as_ref().map(|_| self.webcam_mode);
+                #[cfg(not(feature = "webcam"))]
+                let webcam_mode = None;
                 // Capture particle source info
                 let ps_ref = l
                     .as_effect()
@@ -2589,6 +4041,7 @@ impl App {
                         None
                     }
                 });
+                let particle_layer_source = ps_ref.and_then(|ps| ps.image_source.layer_index());
                 let particle_image_path = ps_ref.and_then(|ps| ps.static_image_path.clone());
                 // Splat scene (#1800): persist the absolute path; restore
                 // re-decodes in the background like media layers.
@@ -2610,23 +4063,42 @@ impl App {
                 let obstacle_depth = ps_ref
                     .filter(|ps| ps.obstacle_enabled && ps.obstacle_source == "depth")
                     .map(|_| true);
+                let obstacle_layer = ps_ref.and_then(|ps| ps.obstacle_layer_index());
                 // Capture live Lattice / particle-sim panel edits so they
                 // round-trip through the preset instead of snapping back to
                 // the effect's `.pfx` defaults on reload.
                 let lattice = ps_ref
                     .filter(|ps| ps.lattice_enabled)
                     .map(|ps| ps.lattice_params);
+                // Count only when it differs from what the quality setting would
+                // build, so a preset doesn't pin another machine's quality.
+                let default_count = l
+                    .effect_index()
+                    .and_then(|i| self.effect_loader.effects.get(i))
+                    .and_then(|e| e.particles.as_ref())
+                    .map(|pd| self.scaled_particle_def(pd, None).max_count);
                 let particle_sim = ps_ref.map(|ps| crate::preset::ParticleSimPreset {
+                    count: if Some(ps.particle_budget) == default_count {
+                        0
+                    } else {
+                        ps.particle_budget
+                    },
                     emit_rate: ps.def.emit_rate,
                     burst_on_beat: ps.def.burst_on_beat,
                     lifetime: ps.def.lifetime,
                     initial_speed: ps.def.initial_speed,
                     initial_size: ps.def.initial_size,
                     drag: ps.def.drag,
+                    trail_length: Some(ps.def.trail_length),
+                    trail_width: Some(ps.def.trail_width),
+                    trail_fade: Some(ps.def.trail_fade),
+                    trigger_burst: Some(ps.def.trigger_burst),
+                    trigger_burst_quantize: Some(ps.def.trigger_burst_quantize),
+                    depth_sort: Some(ps.def.depth_sort),
                 });
                 LayerPreset {
                     effect_name,
-                    params: l.param_store.values.clone(),
+                    params: l.modulation.unmodulated(&l.param_store.values),
                     blend_mode: l.blend_mode,
                     opacity: l.opacity,
                     enabled: l.enabled,
@@ -2636,11 +4108,16 @@ impl App {
                     media_path,
                     media_speed,
                     media_looping,
+                    media_audio,
+                    media_projection,
+                    media_playlist,
                     webcam_device,
+                    webcam_mode,
                     particle_video_path,
                     particle_video_speed,
                     particle_video_looping,
                     particle_webcam,
+                    particle_layer_source,
                     particle_image_path,
                     splat_scene_path,
                     obstacle_image_path,
@@ -2649,14 +4126,48 @@ impl App {
                     obstacle_threshold,
                     obstacle_elasticity,
                     obstacle_depth,
+                    obstacle_layer,
                     lattice,
                     particle_sim,
+                    generator: l.as_generator().map(|g| g.def),
+                    trail: l.trail,
+                    audio_muted: l.audio_muted,
+                    automation: (!l.automation.is_empty()).then(|| l.automation.clone()),
+                    sequencer: (!l.sequencer.is_empty()).then(|| l.sequencer.clone()),
+                    envelope: (!l.envelope.is_off()).then(|| l.envelope.clone()),
+                    audio_mappings: l.audio_mappings.clone(),
                 }
             })
-            .collect();
+            .collect()
+    }
+
+    /// Everything a preset holds, taken from the live state rather than disk,
+    /// so unsaved edits survive a rebuild (#2176).
+    fn snapshot_preset(&self) -> crate::preset::Preset {
+        crate::preset::Preset {
+            layers: self.layer_presets(),
+            active_layer: self.layer_stack.active_layer,
+            postprocess: self.current_postprocess(),
+            volumetric: Some(crate::preset::VolumetricPreset {
+                enabled: self.volumetric_enabled,
+                params: self.volumetric_params,
+            }),
+            master: self.master.per_preset.then_some(self.master.params),
+            id: self
+                .preset_store
+                .current_preset
+                .and_then(|i| self.preset_store.presets.get(i))
+                .map_or(0, |(_, p)| p.id),
+        }
+    }
 
+    pub fn save_preset(&mut self, name: &str) {
+        let layer_presets = self.layer_presets();
         if layer_presets.iter().all(|l| {
-            l.effect_name.is_empty() && l.media_path.is_none() && l.webcam_device.is_none()
+            l.effect_name.is_empty()
+                && l.media_path.is_none()
+                && l.webcam_device.is_none()
+                && l.generator.is_none()
         }) {
             log::warn!("No effects or media loaded, cannot save preset");
             return;
@@ -2675,6 +4186,7 @@ impl App {
             self.layer_stack.active_layer,
             &postprocess,
             volumetric,
+            self.master.per_preset.then_some(self.master.params),
         ) {
             Ok(idx) => {
                 log::info!("Saved preset '{}' at index {}", name, idx);
@@ -2688,6 +4200,511 @@ impl App {
         }
     }
 
+    /// Decode a preset's media into the RAM cache in the background, so loading
+    /// it later skips the decode.
+    pub fn preload_preset(&self, index: usize) {
+        let Some((_, preset)) = self.preset_store.presets.get(index) else {
+            return;
+        };
+        let paths: Vec<std::path::PathBuf> = preset
+            .layers
+            .iter()
+            .filter(|lp| lp.webcam_device.is_none())
+            .filter_map(|lp| lp.media_path.as_deref())
+            .map(std::path::PathBuf::from)
+            .filter(|p| p.exists())
+            .collect();
+        self.media_cache.preload(paths);
+    }
+
+    /// Preload the preset "Next Preset" would switch to.
+    pub fn preload_next_preset(&self) {
+        let num = self.preset_store.presets.len();
+        if num > 0 {
+            let current = self.preset_store.current_preset.unwrap_or(0);
+            self.preload_preset((current + 1) % num);
+        }
+    }
+
+    /// Restart the clock at frame 0 and reload the current preset so every
+    /// layer's feedback and particle state starts fresh (#2174). In
+    /// deterministic mode this is the start line for a reproducible run.
+    pub fn restart_render_clock(&mut self) {
+        self.start_time = Instant::now();
+        self.frame_count = 0;
+        self.freeze.release();
+        if let Some(index) = self.preset_store.current_preset {
+            self.load_preset(index);
+        }
+    }
+
+    /// The current machine setup as a named profile (#2177).
+    pub fn capture_profile(&self, name: &str) -> crate::settings::SettingsProfile {
+        let size = self.window.inner_size();
+        crate::settings::SettingsProfile {
+            name: name.trim().to_string(),
+            audio_device: self.settings.audio_device.clone(),
+            mic_device: self.settings.mic_device.clone(),
+            midi_port: self.midi.connected_port().map(str::to_string),
+            osc_rx_port: self.osc.config.rx_port,
+            osc_tx_host: self.osc.config.tx_host.clone(),
+            osc_tx_port: self.osc.config.tx_port,
+            monitor: self.window.current_monitor().and_then(|m| m.name()),
+            resolution: Some([size.width, size.height]),
+            fullscreen: self.window.fullscreen().is_some(),
+            theme: self.settings.theme,
+        }
+    }
+
+    /// Switch to a saved profile (#2177). Only what differs is reopened, so
+    /// re-applying the current profile doesn't drop the audio or MIDI input.
+    /// Returns false when no profile has that name.
+    pub fn apply_profile(&mut self, name: &str) -> bool {
+        let Some(profile) = self.settings.profile(name).cloned() else {
+            return false;
+        };
+
+        if self.settings.audio_device != profile.audio_device {
+            self.audio.switch_device(profile.audio_device.as_deref());
+            self.settings.audio_device = profile.audio_device.clone();
+        }
+        if self.settings.mic_device != profile.mic_device {
+            self.audio.set_mic_device(profile.mic_device.as_deref());
+            self.settings.mic_device = profile.mic_device.clone();
+        }
+
+        let mut missing = Vec::new();
+        match profile.midi_port.as_deref() {
+            Some(port) if self.midi.connected_port() != Some(port) => {
+                if self.midi.available_ports.iter().any(|p| p == port) {
+                    self.midi.connect(port);
+                } else {
+                    missing.push(format!("MIDI port '{port}'"));
+                }
+            }
+            Some(_) => {}
+            None => self.midi.disconnect(),
+        }
+
+        let osc = &mut self.osc.config;
+        if osc.rx_port != profile.osc_rx_port {
+            osc.rx_port = profile.osc_rx_port;
+            self.osc.restart_receiver();
+        }
+        let osc = &mut self.osc.config;
+        if osc.tx_host != profile.osc_tx_host || osc.tx_port != profile.osc_tx_port {
+            osc.tx_host.clone_from(&profile.osc_tx_host);
+            osc.tx_port = profile.osc_tx_port;
+            self.osc.configure_sender();
+        }
+        self.osc.config.save();
+
+        // Output window: onto the named monitor, then fullscreen or sized.
+        let monitor = profile.monitor.as_deref().and_then(|want| {
+            let found = self
+                .window
+                .available_monitors()
+                .find(|m| m.name().as_deref() == Some(want));
+            if found.is_none() {
+                missing.push(format!("monitor '{want}'"));
+            }
+            found
+        });
+        if profile.fullscreen {
+            let monitor = monitor.or_else(|| self.window.current_monitor());
+            self.output_monitor
+                .fullscreen_on(monitor.as_ref().and_then(|m| m.name()));
+            self.window
+                .set_fullscreen(Some(winit::window::Fullscreen::Borderless(monitor)));
+        } else {
+            self.window.set_fullscreen(None);
+            self.output_monitor.windowed();
+            if let Some(m) = &monitor {
+                self.window.set_outer_position(m.position());
+            }
+            if let Some([w, h]) = profile.resolution {
+                let _ = self
+                    .window
+                    .request_inner_size(winit::dpi::PhysicalSize::new(w, h));
+            }
+        }
+
+        if self.settings.theme != profile.theme {
+            self.egui_overlay.set_theme(profile.theme);
+            self.settings.theme = profile.theme;
+        }
+
+        self.settings.active_profile = Some(profile.name.clone());
+        self.settings.save();
+        if missing.is_empty() {
+            log::info!("Applied profile '{}'", profile.name);
+        } else {
+            let msg = format!(
+                "Profile '{}': {} not found",
+                profile.name,
+                missing.join(", ")
+            );
+            log::warn!("{msg}");
+            self.status_error = Some((msg, Instant::now()));
+        }
+        true
+    }
+
+    /// Save settings, keymaps, MIDI/OSC/web config and global bindings, plus
+    /// the user presets with `with_presets`, as one rig file (#2203). The
+    /// save dialog runs on its own thread so rendering doesn't stall.
+    pub fn export_rig(&self, with_presets: bool) {
+        crate::persist::flush();
+        let rig = crate::rig::Rig::collect(&crate::paths::config_dir(), with_presets);
+        let json = match crate::rig::SCHEMA.to_json(&rig) {
+            Ok(json) => json,
+            Err(e) => {
+                log::error!("Failed to build rig file: {e}");
+                return;
+            }
+        };
+        std::thread::Builder::new()
+            .name("rig-export-dialog".into())
+            .spawn(move || {
+                let ext = crate::rig::EXTENSION;
+                let dialog = rfd::FileDialog::new()
+                    .set_file_name(format!("fosfora.{ext}"))
+                    .add_filter("Fosfora rig", &[ext]);
+                if let Some(path) = dialog.save_file() {
+                    match std::fs::write(&path, json) {
+                        Ok(()) => log::info!(
+                            "Exported rig ({} files) to {}",
+                            rig.files.len(),
+                            path.display()
+                        ),
+                        Err(e) => log::error!("Failed to export rig: {e}"),
+                    }
+                }
+            })
+            .ok();
+    }
+
+    /// Ask for a rig file to import; `poll_rig_import` applies it.
+    pub fn import_rig_dialog(&mut self) {
+        if self.rig_import_rx.is_some() {
+            return;
+        }
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        self.rig_import_rx = Some(rx);
+        std::thread::Builder::new()
+            .name("rig-import-dialog".into())
+            .spawn(move || {
+                let dialog =
+                    rfd::FileDialog::new().add_filter("Fosfora rig", &[crate::rig::EXTENSION]);
+                if let Some(path) = dialog.pick_file() {
+                    let _ = tx.send(path);
+                }
+            })
+            .ok();
+    }
+
+    /// Import the rig picked in the dialog, once there is one.
+    pub fn poll_rig_import(&mut self) {
+        let Some(rx) = &self.rig_import_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(path) => {
+                self.rig_import_rx = None;
+                if let Err(e) = self.import_rig(&path) {
+                    log::error!("Failed to import rig {}: {e}", path.display());
+                    self.status_error = Some((format!("Rig import failed: {e}"), Instant::now()));
+                }
+            }
+            // Dialog cancelled
+            Err(crossbeam_channel::TryRecvError::Disconnected) => self.rig_import_rx = None,
+            Err(crossbeam_channel::TryRecvError::Empty) => {}
+        }
+    }
+
+    /// Replace this machine's setup with the rig at `path` (#2203). What was
+    /// here is first saved to `before-import.fosfora-rig` in the config folder.
+    pub fn import_rig(&mut self, path: &std::path::Path) -> Result<()> {
+        use crate::rig::{BACKUP_FILE, Rig, SCHEMA};
+        let rig: Rig = SCHEMA.parse(&std::fs::read_to_string(path)?)?;
+        if rig.files.is_empty() {
+            anyhow::bail!("the file holds no settings");
+        }
+        let dir = crate::paths::config_dir();
+        crate::persist::flush();
+        let backup = SCHEMA.to_json(&Rig::collect(&dir, true))?;
+        crate::persist::write(dir.join(BACKUP_FILE), backup);
+        let count = rig.install(&dir);
+        crate::persist::flush();
+        self.reload_config();
+        log::info!(
+            "Imported rig {} ({count} files, {} presets)",
+            path.display(),
+            rig.preset_count()
+        );
+        Ok(())
+    }
+
+    /// Pick up config files replaced on disk: theme, audio input, MIDI, OSC
+    /// and web setup, bindings and presets. Other settings, such as the
+    /// output window, apply on the next launch.
+    fn reload_config(&mut self) {
+        let settings = SettingsConfig::load();
+        if settings.theme != self.settings.theme {
+            self.egui_overlay.set_theme(settings.theme);
+        }
+        if settings.input_channels != self.settings.input_channels {
+            self.audio.set_channel_maps(settings.input_channels.clone());
+        }
+        if settings.audio_device != self.settings.audio_device {
+            self.audio.switch_device(settings.audio_device.as_deref());
+        }
+        if settings.mic_device != self.settings.mic_device {
+            self.audio.set_mic_device(settings.mic_device.as_deref());
+        }
+        self.audio.envelopes.config = settings.envelopes;
+        self.settings = settings;
+
+        self.midi.config = crate::midi::mapping::MidiConfig::load();
+        if let Some(port) = self.midi.config.port_name.clone()
+            && self.midi.connected_port() != Some(port.as_str())
+            && self.midi.available_ports.contains(&port)
+        {
+            self.midi.connect(&port);
+        }
+        self.midi.restart_clock_out();
+
+        self.osc.config = crate::osc::types::OscConfig::load();
+        self.osc.restart_receiver();
+        self.osc.configure_sender();
+
+        self.web.config = crate::web::types::WebConfig::load();
+        self.web.restart_server();
+        self.web.restart_follower();
+
+        self.ndi.config = crate::ndi::types::NdiConfig::load();
+        self.recording.config = crate::recording::types::RecordingConfig::load();
+
+        let current = self.preset_store.current_name().map(str::to_string);
+        self.preset_store.scan();
+        self.binding_bus = BindingBus::new();
+        if let Some(name) = current {
+            self.preset_store.current_preset = self
+                .preset_store
+                .presets
+                .iter()
+                .position(|(n, _)| *n == name);
+            self.binding_bus.load_preset_bindings(&name);
+        }
+    }
+
+    /// Decode a preset's media on this thread, for restores that can't wait
+    /// on the background loader.
+    fn decode_media_inline(
+        &self,
+        preset: &crate::preset::Preset,
+    ) -> std::collections::HashMap<usize, MediaDecodeResult> {
+        let mut decoded_media = std::collections::HashMap::new();
+        for (i, lp) in preset.layers.iter().enumerate() {
+            if lp.webcam_device.is_some() {
+                continue;
+            }
+            if let Some(ref media_path) = lp.media_path {
+                let result = match self.media_cache.load(std::path::Path::new(media_path)) {
+                    Ok(source) => MediaDecodeResult::Ok(source),
+                    Err(e) => MediaDecodeResult::Err(e),
+                };
+                decoded_media.insert(i, result);
+            }
+        }
+        decoded_media
+    }
+
+    /// What the output shows at launch (#2232): the default effect, the last
+    /// session or a chosen preset, then optionally fullscreen on a named
+    /// monitor. Runs once, before any `--profile`.
+    pub fn apply_startup(&mut self) {
+        let startup = self.settings.startup.clone();
+        match startup.mode {
+            StartupMode::Blank => {}
+            StartupMode::LastSession => match session::load() {
+                Some(session) => {
+                    self.restore_session(session);
+                    log::info!("Restored last session");
+                }
+                None => log::info!("No saved session, starting with the default effect"),
+            },
+            StartupMode::Preset => {
+                match startup
+                    .preset
+                    .and_then(|id| self.preset_store.index_of_id(id))
+                {
+                    Some(index) => self.load_preset(index),
+                    None => {
+                        log::warn!("Startup preset not found");
+                        self.status_error = Some((
+                            "The startup preset is gone; showing the default effect.".to_string(),
+                            Instant::now(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        if startup.fullscreen {
+            let monitor = startup.monitor.as_deref().and_then(|want| {
+                self.window
+                    .available_monitors()
+                    .find(|m| m.name().as_deref() == Some(want))
+            });
+            // A named monitor that isn't up yet, like a projector still warming
+            // up, is still where the output belongs: it moves there on connect.
+            let wanted = startup
+                .monitor
+                .or_else(|| self.window.current_monitor().and_then(|m| m.name()));
+            self.output_monitor.fullscreen_on(wanted);
+            self.window
+                .set_fullscreen(Some(winit::window::Fullscreen::Borderless(
+                    monitor.or_else(|| self.window.current_monitor()),
+                )));
+        }
+    }
+
+    /// The stack as it stands, for the session file or attract mode.
+    fn current_session(&self) -> Session {
+        Session {
+            preset: self.snapshot_preset(),
+            current: self
+                .preset_store
+                .current_preset
+                .and_then(|i| self.preset_store.presets.get(i))
+                .map(|(_, p)| p.id),
+            dirty: self.preset_store.dirty,
+        }
+    }
+
+    /// Put back the stack saved by [`Self::save_session`].
+    fn restore_session(&mut self, session: Session) {
+        let decoded_media = self.decode_media_inline(&session.preset);
+        self.restore_preset_state(&session.preset, decoded_media);
+        self.preset_store.current_preset = session
+            .current
+            .and_then(|id| self.preset_store.index_of_id(id));
+        self.preset_store.dirty = session.dirty && self.preset_store.current_preset.is_some();
+        if let Some(name) = self.preset_store.current_name().map(str::to_string) {
+            self.binding_bus.load_preset_bindings(&name);
+            self.binding_bus.preset_scope_dirty = false;
+        }
+        for layer in &mut self.layer_stack.layers {
+            layer.param_store.changed = false;
+        }
+    }
+
+    /// Keep `session.json` current while "last session" is the startup mode
+    /// (#2232). Checked every few seconds and written only when the stack
+    /// changed; `force` writes now, for quitting.
+    pub fn save_session(&mut self, force: bool) {
+        // Attract mode's presets aren't the stack to come back to
+        if self.settings.startup.mode != StartupMode::LastSession || self.attract.is_active() {
+            return;
+        }
+        let now = Instant::now();
+        if !force
+            && self
+                .last_session_save
+                .is_some_and(|t| now.duration_since(t) < session::SAVE_INTERVAL)
+        {
+            return;
+        }
+        self.last_session_save = Some(now);
+        let session = self.current_session();
+        match serde_json::to_string_pretty(&session) {
+            Ok(json) if json != self.session_json => {
+                crate::persist::write(session::path(), json.clone());
+                self.session_json = json;
+            }
+            Ok(_) => {}
+            Err(e) => log::error!("Failed to save session: {e}"),
+        }
+    }
+
+    /// Rebuild every GPU resource on a fresh device after a device loss
+    /// (#2176). The live layer stack is snapshotted as a preset first and
+    /// restored onto the new device, so unsaved edits carry over; feedback
+    /// buffers, particle state and the freeze frame start from clear.
+    pub fn recover_device(&mut self) -> Result<()> {
+        log::warn!("Recovering from GPU device loss");
+        let snapshot = self.snapshot_preset();
+        let current_preset = self.preset_store.current_preset;
+        let dirty = self.preset_store.dirty;
+
+        // On failure nothing has changed yet, so the next attempt snapshots
+        // the same stack.
+        self.gpu.recreate(self.window.clone())?;
+        // The driver may have come back on a different adapter.
+        self.effect_loader.gpu_caps =
+            Some(GpuCapabilities::detect(&self.gpu.adapter, &self.gpu.device));
+
+        // Drop everything holding old-device resources; the layers come back
+        // from the snapshot below.
+        self.layer_stack.layers.clear();
+        self.layer_stack.active_layer = 0;
+        self.freeze_renderer = None;
+        self.freeze.release();
+
+        let device = &self.gpu.device;
+        let queue = &self.gpu.queue;
+        let hdr_format = GpuContext::hdr_format();
+        let (width, height) = (
+            self.gpu.surface_config.width,
+            self.gpu.surface_config.height,
+        );
+        self.placeholder = PlaceholderTexture::new(device, queue, hdr_format);
+        self.audio_textures = AudioTextures::new(device, queue);
+        self.motion = MotionField::new(device);
+        self.compositor = Compositor::new(device, hdr_format, width, height);
+        self.composite_hook_error = self
+            .compositor
+            .load_hook(device, self.settings.composite_hook.as_deref())
+            .err();
+        let post_enabled = self.post_process.enabled;
+        self.post_process =
+            PostProcessChain::new(device, queue, self.gpu.format, hdr_format, width, height);
+        self.post_process.enabled = post_enabled;
+        if self.transition_renderer.is_some() {
+            self.transition_renderer = Some(TransitionRenderer::new(device, hdr_format));
+        }
+        self.egui_overlay
+            .recreate(device, self.gpu.format, &self.window);
+        #[cfg(feature = "ndi")]
+        self.ndi.restart(device, self.gpu.format, width, height);
+        self.recording.recreate_capture(device);
+        self.screenshot.cancel();
+        #[cfg(feature = "profiling")]
+        {
+            self.gpu_profiler = crate::gpu::profiler::Profiler::new(device);
+        }
+        self.layer_timer = LayerTimer::new(device, queue);
+        // The new device counts from zero
+        self.resource_monitor = crate::gpu::resources::ResourceMonitor::default();
+        self.debug_viewer = None;
+
+        // Media sources are CPU-side and usually still cached, so decode
+        // inline rather than leaving the output black for a background load.
+        let decoded_media = self.decode_media_inline(&snapshot);
+        self.restore_preset_state(&snapshot, decoded_media);
+
+        self.preset_store.current_preset = current_preset;
+        self.preset_store.dirty = dirty;
+        for layer in &mut self.layer_stack.layers {
+            layer.param_store.changed = false;
+        }
+        self.last_frame = Instant::now();
+        self.status_error = Some(("GPU device lost — recovered".to_string(), Instant::now()));
+        log::info!("GPU device recovered");
+        Ok(())
+    }
+
     pub fn load_preset(&mut self, index: usize) {
         let preset = match self.preset_store.load(index) {
             Some(p) => p.clone(),
@@ -2728,7 +4745,7 @@ impl App {
 
         // Scan for media layers that need decoding (skip locked, skip missing files)
         let mut media_jobs: Vec<(usize, std::path::PathBuf)> = Vec::new();
-        for (i, lp) in preset.layers.iter().enumerate() {
+        for (i, lp) in preset.layers.iter().enumerate().take(self.max_layers()) {
             // Skip locked layers
             if let Some(layer) = self.layer_stack.layers.get(i) {
                 if layer.locked {
@@ -2739,37 +4756,122 @@ impl App {
             if lp.webcam_device.is_some() {
                 continue;
             }
-            if let Some(ref media_path) = lp.media_path {
-                let path = std::path::PathBuf::from(media_path);
-                if path.exists() {
-                    media_jobs.push((i, path));
-                } else {
-                    log::warn!("Media file '{}' not found for layer {}", media_path, i);
+            if let Some(ref media_path) = lp.media_path {
+                let path = std::path::PathBuf::from(media_path);
+                if path.exists() || crate::media::decoder::is_stream_url(media_path) {
+                    media_jobs.push((i, path));
+                } else {
+                    log::warn!("Media file '{}' not found for layer {}", media_path, i);
+                }
+            }
+        }
+
+        if media_jobs.is_empty() {
+            // Fast path: no media to decode, apply immediately
+            self.apply_preset_immediately(Some(index), &preset, std::collections::HashMap::new());
+        } else {
+            // Async path: decode media in background
+            log::info!(
+                "Preset '{}' has {} media layer(s), decoding in background",
+                preset_name,
+                media_jobs.len()
+            );
+            self.preset_loader
+                .request_load(index, preset, media_jobs, preset_name);
+        }
+    }
+
+    /// Apply a preset immediately, using pre-decoded media from the HashMap.
+    /// Called directly for presets with no media (fast path) or when background
+    /// decode completes (async path). `index` is `None` when the preset was
+    /// deleted while its media decoded: it still shows, but as unsaved.
+    fn apply_preset_immediately(
+        &mut self,
+        index: Option<usize>,
+        preset: &crate::preset::Preset,
+        decoded_media: std::collections::HashMap<usize, MediaDecodeResult>,
+    ) {
+        self.restore_preset_state(preset, decoded_media);
+        self.preset_store.current_preset = index;
+        self.preset_store.dirty = false;
+        // Reset param changed flags so loading doesn't immediately mark dirty
+        for layer in &mut self.layer_stack.layers {
+            layer.param_store.changed = false;
+        }
+        if let Some((name, _)) = index.and_then(|i| self.preset_store.presets.get(i)) {
+            log::info!("Loaded preset '{}'", name);
+            let name = name.clone();
+            self.preset_store.history.record_preset(&name);
+        }
+    }
+
+    /// Move the preset crossfade (#2250). Bindings report every frame, so
+    /// only an actual move counts; otherwise edits made at rest would be
+    /// blended over straight away.
+    pub fn set_preset_crossfade(&mut self, position: f32) {
+        let position = position.clamp(0.0, 1.0);
+        let fade = &mut self.settings.preset_crossfade;
+        if (fade.position - position).abs() > 1e-4 {
+            fade.position = position;
+            self.crossfade_pending = true;
+        }
+    }
+
+    /// Put the crossfade's blend on screen: load the nearer preset when the
+    /// fader crosses the middle, then mix the shared values over it.
+    fn update_preset_crossfade(&mut self) {
+        if !self.crossfade_pending {
+            return;
+        }
+        let fade = &self.settings.preset_crossfade;
+        let (Some(a), Some(b)) = (
+            fade.a.and_then(|id| self.preset_store.index_of_id(id)),
+            fade.b.and_then(|id| self.preset_store.index_of_id(id)),
+        ) else {
+            self.crossfade_pending = false;
+            return;
+        };
+        let position = fade.position;
+        let nearer = if position < 0.5 { a } else { b };
+        if self.preset_store.current_preset != Some(nearer) {
+            let loading = matches!(
+                self.preset_loader.state,
+                crate::preset::loader::PresetLoadingState::Loading { preset_index, .. }
+                    if preset_index == nearer
+            );
+            if !loading {
+                self.load_preset(nearer);
+            }
+            // Still pending: blends once the preset is current
+            if self.preset_store.current_preset != Some(nearer) {
+                return;
+            }
+        }
+        let (Some(pa), Some(pb)) = (self.preset_store.load(a), self.preset_store.load(b)) else {
+            return;
+        };
+        let blended = crate::preset::crossfade::blend(pa, pb, position);
+        for (layer, lp) in self.layer_stack.layers.iter_mut().zip(&blended.layers) {
+            if layer.locked {
+                continue;
+            }
+            layer.opacity = lp.opacity;
+            for (name, value) in &lp.params {
+                if layer.param_store.values.contains_key(name) {
+                    layer.param_store.set(name, value.clone());
                 }
             }
+            // A blend is a performance move, not an edit to the preset
+            layer.param_store.changed = false;
         }
-
-        if media_jobs.is_empty() {
-            // Fast path: no media to decode, apply immediately
-            self.apply_preset_immediately(index, &preset, std::collections::HashMap::new());
-        } else {
-            // Async path: decode media in background
-            log::info!(
-                "Preset '{}' has {} media layer(s), decoding in background",
-                preset_name,
-                media_jobs.len()
-            );
-            self.preset_loader
-                .request_load(index, preset, media_jobs, preset_name);
-        }
+        self.crossfade_pending = false;
     }
 
-    /// Apply a preset immediately, using pre-decoded media from the HashMap.
-    /// Called directly for presets with no media (fast path) or when background
-    /// decode completes (async path).
-    fn apply_preset_immediately(
+    /// Rebuild layers, postprocess, volumetric and master from `preset`,
+    /// without touching which preset is current. Shared by preset loads and
+    /// device-loss recovery.
+    fn restore_preset_state(
         &mut self,
-        index: usize,
         preset: &crate::preset::Preset,
         mut decoded_media: std::collections::HashMap<usize, MediaDecodeResult>,
     ) {
@@ -2780,12 +4882,24 @@ impl App {
             let last = self.layer_stack.layers.len() - 1;
             self.layer_stack.layers.remove(last);
         }
-        while self.layer_stack.layers.len() < preset.layers.len() {
+        let wanted = preset.layers.len().min(self.max_layers());
+        if wanted < preset.layers.len() {
+            log::warn!(
+                "Preset has {} layers, loading the first {wanted} (layer limit)",
+                preset.layers.len()
+            );
+        }
+        while self.layer_stack.layers.len() < wanted {
+            let before = self.layer_stack.layers.len();
             self.add_layer();
+            if self.layer_stack.layers.len() == before {
+                break;
+            }
         }
 
         // Load each layer (skip locked layers)
-        for (i, lp) in preset.layers.iter().enumerate() {
+        let loaded_layers = preset.layers.len().min(self.layer_stack.layers.len());
+        for (i, lp) in preset.layers[..loaded_layers].iter().enumerate() {
             if let Some(layer) = self.layer_stack.layers.get(i) {
                 if layer.locked {
                     log::info!("Layer {} is locked, skipping preset load", i);
@@ -2813,25 +4927,23 @@ impl App {
                             .map(|(idx, _)| *idx)
                     })
                     .unwrap_or(self.webcam_device_index);
-                // Start webcam capture if not already running
-                if self.webcam_capture.is_none() {
-                    match self.start_webcam(device_idx, Some((1280, 720))) {
-                        Ok(capture) => {
-                            self.webcam_capture = Some(capture);
-                        }
-                        Err(e) => {
-                            log::error!("Failed to start webcam for preset layer {i}: {e}");
-                            self.status_error =
-                                Some((format!("Webcam failed: {e}"), Instant::now()));
-                        }
+                // (Re)start capture unless it already runs on the preset's device and mode
+                let mode = lp.webcam_mode.unwrap_or(self.webcam_mode);
+                let matches = self.webcam_capture.is_some()
+                    && device_idx == self.webcam_device_index
+                    && mode == self.webcam_mode;
+                if !matches {
+                    if let Err(e) = self.restart_webcam(device_idx, mode) {
+                        log::error!("Failed to start webcam for preset layer {i}: {e}");
+                        self.status_error = Some((format!("Webcam failed: {e}"), Instant::now()));
                     }
                 }
                 if let Some(ref capture) = self.webcam_capture {
                     let (w, h) = capture.resolution();
-                    let source = crate::media::decoder::MediaSource::Live {
+                    let source = std::sync::Arc::new(crate::media::decoder::MediaSource::Live {
                         width: w,
                         height: h,
-                    };
+                    });
                     let hdr_format = GpuContext::hdr_format();
                     let media_layer = MediaLayer::new(
                         &self.gpu.device,
@@ -2848,7 +4960,18 @@ impl App {
                 }
             }
 
-            if !is_webcam_layer {
+            if let Some(def) = lp.generator {
+                let generator = GeneratorLayer::new(
+                    &self.gpu.device,
+                    GpuContext::hdr_format(),
+                    self.gpu.surface_config.width,
+                    self.gpu.surface_config.height,
+                    def,
+                );
+                let layer = &mut self.layer_stack.layers[i];
+                layer.content = LayerContent::Generator(Box::new(generator));
+                layer.param_store = ParamStore::new();
+            } else if !is_webcam_layer {
                 if let Some(ref media_path) = lp.media_path {
                     let path = std::path::PathBuf::from(media_path);
                     // Try pre-decoded media first, fall back to sync decode
@@ -2863,7 +4986,7 @@ impl App {
                                 false
                             }
                         }
-                    } else if path.exists() {
+                    } else if path.exists() || crate::media::decoder::is_stream_url(media_path) {
                         // Fallback: sync decode (shouldn't happen in normal flow)
                         self.load_media_on_layer(i, path.clone());
                         true
@@ -2882,6 +5005,12 @@ impl App {
                                 if let Some(looping) = lp.media_looping {
                                     m.transport.looping = looping;
                                 }
+                                if let Some(audio) = lp.media_audio {
+                                    m.transport.audio = audio;
+                                }
+                                if let Some(projection) = lp.media_projection {
+                                    m.set_projection(&self.gpu.queue, projection);
+                                }
                             }
                         }
                     }
@@ -2905,6 +5034,12 @@ impl App {
                         false
                     };
 
+                    // Saved particle count (#2156); 0 = the quality setting's count.
+                    let particle_count = lp
+                        .particle_sim
+                        .as_ref()
+                        .map(|sim| sim.count)
+                        .filter(|&c| c > 0);
                     if already_loaded {
                         log::debug!(
                             "Layer {} already has '{}', skipping reload (morph-safe)",
@@ -2914,8 +5049,11 @@ impl App {
                         // Trigger particle source transition if the preset has
                         // different image source than what's currently loaded.
                         // The morph interpolation will handle param blending.
+                        if let Some(count) = particle_count {
+                            self.set_particle_count(i, count);
+                        }
                     } else if let Some(idx) = effect_idx {
-                        self.load_effect_on_layer(i, idx);
+                        self.load_effect_with_particle_count(i, idx, particle_count);
                     } else {
                         // Leaving the layer as-is meant it kept the *previous* preset's
                         // effect and then got stamped with this preset's opacity, blend
@@ -2933,6 +5071,24 @@ impl App {
                 }
             }
 
+            // Restore the media playlist, positioned on the clip the preset saved
+            if let Some(layer) = self.layer_stack.layers.get_mut(i) {
+                layer.playlist = lp
+                    .media_playlist
+                    .as_ref()
+                    .filter(|_| layer.is_media() && !is_webcam_layer)
+                    .map(crate::media::playlist::MediaPlaylist::from_def);
+                if let (Some(pl), Some(current)) = (layer.playlist.as_mut(), &lp.media_path) {
+                    if let Some(idx) = pl
+                        .clips
+                        .iter()
+                        .position(|c| c.as_os_str() == current.as_str())
+                    {
+                        pl.jump_to(idx);
+                    }
+                }
+            }
+
             // Restore particle source (video or webcam) if saved in preset
             #[cfg(feature = "video")]
             if let Some(ref video_path) = lp.particle_video_path {
@@ -2990,7 +5146,7 @@ impl App {
             if lp.particle_webcam == Some(true) {
                 // Start webcam capture if not already running
                 if self.webcam_capture.is_none() {
-                    match self.start_webcam(self.webcam_device_index, Some((1280, 720))) {
+                    match self.start_webcam(self.webcam_device_index) {
                         Ok(capture) => {
                             self.webcam_capture = Some(capture);
                         }
@@ -3017,7 +5173,8 @@ impl App {
                 // Only restore if no video/webcam source takes priority
                 if lp.particle_video_path.is_none() && lp.particle_webcam != Some(true) {
                     let path = std::path::PathBuf::from(img_path);
-                    if path.exists() {
+                    let is_text = crate::gpu::particle::vector_source::text_of(img_path).is_some();
+                    if path.exists() || is_text {
                         // Skip if the same image is already loaded
                         let already_loaded = self
                             .layer_stack
@@ -3049,10 +5206,13 @@ impl App {
                                                     crate::gpu::particle::ParticleImageSource::Static;
                                                 ps.video_path = None;
                                                 ps.static_image_path = Some(img_path.clone());
-                                                let filename = path
-                                                    .file_name()
-                                                    .map(|f| f.to_string_lossy().to_string())
-                                                    .unwrap_or_default();
+                                                let filename = if is_text {
+                                                    img_path.clone()
+                                                } else {
+                                                    path.file_name()
+                                                        .map(|f| f.to_string_lossy().to_string())
+                                                        .unwrap_or_default()
+                                                };
                                                 ps.def.emitter.image = filename;
                                                 log::info!(
                                                     "Restored particle image source for layer {i}: {img_path}"
@@ -3074,6 +5234,20 @@ impl App {
                 }
             }
 
+            // Live layer source (#2155) takes over from any image restored above,
+            // which stays remembered for switching back.
+            if let Some(source) = lp.particle_layer_source {
+                if let Some(ps) = self
+                    .layer_stack
+                    .layers
+                    .get_mut(i)
+                    .and_then(|l| l.as_effect_mut())
+                    .and_then(|e| e.pass_executor.particle_system.as_mut())
+                {
+                    ps.set_layer_source(&self.gpu.device, source);
+                }
+            }
+
             // Restore the Gaussian-splat scene (#1800) — a BACKGROUND load
             // (scenes reach ~1.5 GB, unlike the synchronous image restore
             // above); the layer renders its .pfx default (or empty) until
@@ -3153,7 +5327,7 @@ impl App {
                     // Start webcam if needed
                     #[cfg(feature = "webcam")]
                     if self.webcam_capture.is_none() {
-                        match self.start_webcam(self.webcam_device_index, Some((1280, 720))) {
+                        match self.start_webcam(self.webcam_device_index) {
                             Ok(capture) => {
                                 self.webcam_capture = Some(capture);
                             }
@@ -3206,6 +5380,32 @@ impl App {
                 }
             }
 
+            // Restore live layer obstacle (#2160)
+            if let Some(src) = lp
+                .obstacle_layer
+                .filter(|&src| src != i && lp.obstacle_image_path.is_none())
+            {
+                if let Some(ps) = self
+                    .layer_stack
+                    .layers
+                    .get_mut(i)
+                    .and_then(|l| l.as_effect_mut())
+                    .and_then(|e| e.pass_executor.particle_system.as_mut())
+                {
+                    ps.set_obstacle_layer(&self.gpu.device, src);
+                    if let Some(mode) = lp.obstacle_mode {
+                        ps.obstacle_mode = crate::gpu::particle::ObstacleMode::from_u32(mode);
+                    }
+                    if let Some(threshold) = lp.obstacle_threshold {
+                        ps.obstacle_threshold = threshold;
+                    }
+                    if let Some(elasticity) = lp.obstacle_elasticity {
+                        ps.obstacle_elasticity = elasticity;
+                    }
+                    log::info!("Restored layer {src} obstacle for layer {i}");
+                }
+            }
+
             // Cloned before the layer borrow below so the lattice rebuild can
             // reach the GPU device while `layer_stack` is mutably borrowed.
             let device = self.gpu.device.clone();
@@ -3218,6 +5418,14 @@ impl App {
                 }
                 layer.blend_mode = lp.blend_mode;
                 layer.opacity = lp.opacity;
+                layer.trail = lp.trail;
+                layer.audio_muted = lp.audio_muted;
+                layer.automation = lp.automation.clone().unwrap_or_default();
+                layer.sequencer = lp.sequencer.clone().unwrap_or_default();
+                // Loading the preset starts its envelopes, so a fade-in builds from here
+                layer.envelope = lp.envelope.clone().unwrap_or_default();
+                layer.audio_mappings = lp.audio_mappings.clone();
+                layer.modulation.reset();
                 layer.enabled = lp.enabled && !effect_missing;
                 layer.locked = lp.locked;
                 layer.pinned = lp.pinned;
@@ -3238,6 +5446,22 @@ impl App {
                         ps.def.initial_speed = sim.initial_speed;
                         ps.def.initial_size = sim.initial_size;
                         ps.def.drag = sim.drag;
+                        if let Some(n) = sim.trigger_burst {
+                            ps.def.trigger_burst = n;
+                        }
+                        if let Some(q) = sim.trigger_burst_quantize {
+                            ps.def.trigger_burst_quantize = q;
+                        }
+                        if let Some(on) = sim.depth_sort {
+                            ps.set_depth_sort(&device, on);
+                        }
+                        ps.set_trails(
+                            &device,
+                            hdr,
+                            sim.trail_length.unwrap_or(ps.def.trail_length),
+                            sim.trail_width.unwrap_or(ps.def.trail_width),
+                            sim.trail_fade.unwrap_or(ps.def.trail_fade),
+                        );
                     }
                     // Only `lattice_params` — never `lattice_defaults`, which the
                     // panel "Reset" restores from. `init_lattice` rebuilds the
@@ -3255,10 +5479,21 @@ impl App {
             .active_layer
             .min(self.layer_stack.layers.len().saturating_sub(1));
         self.sync_active_layer();
+        // The preset's chain, unless the user picked one for this effect (#2234)
+        let effect = self
+            .layer_stack
+            .active()
+            .and_then(|l| l.effect_index())
+            .and_then(|i| self.effect_loader.effects.get(i));
+        let pp = self.settings.post.resolve(
+            effect.map_or("", |e| e.name.as_str()),
+            effect.and_then(|e| e.postprocess.as_ref()),
+            Some(&preset.postprocess),
+        );
+        self.post_process.enabled = pp.enabled;
         if let Some(layer) = self.layer_stack.active_mut() {
-            layer.postprocess = preset.postprocess.clone();
+            layer.postprocess = pp;
         }
-        self.post_process.enabled = preset.postprocess.enabled;
         // Restore the global Volumetric (R3) mode. Disable when the preset has
         // no volumetric block so an earlier preset's volumetric can't bleed into
         // one saved without it. The per-frame copy onto the active layer then
@@ -3269,15 +5504,11 @@ impl App {
         } else {
             self.volumetric_enabled = false;
         }
-        self.preset_store.current_preset = Some(index);
-        self.preset_store.dirty = false;
-        // Reset param changed flags so loading doesn't immediately mark dirty
-        for layer in &mut self.layer_stack.layers {
-            layer.param_store.changed = false;
-        }
-        if let Some((name, _)) = self.preset_store.presets.get(index) {
-            log::info!("Loaded preset '{}'", name);
-        }
+        // Output master (#2165): the preset's own grade, else back to the
+        // global one. Blackout is left alone — a preset change must never
+        // bring the output back up on its own.
+        self.master.per_preset = preset.master.is_some();
+        self.master.params = preset.master.unwrap_or(self.settings.master);
     }
 
     /// Create a MediaLayer from an already-decoded MediaSource (GPU resource creation only).
@@ -3285,7 +5516,7 @@ impl App {
     fn create_media_layer_from_source(
         &mut self,
         layer_idx: usize,
-        source: crate::media::decoder::MediaSource,
+        source: std::sync::Arc<crate::media::decoder::MediaSource>,
         path: &std::path::Path,
     ) {
         if layer_idx >= self.layer_stack.layers.len() {
@@ -3318,6 +5549,70 @@ impl App {
         self.layer_stack.layer_infos(&self.effect_loader.effects)
     }
 
+    /// Name of the effect on the active layer; `None` for media, generator
+    /// and empty layers.
+    fn active_effect_name(&self) -> Option<String> {
+        let index = self.layer_stack.active()?.effect_index()?;
+        Some(self.effect_loader.effects.get(index)?.name.clone())
+    }
+
+    /// Where the active layer's effect takes its post chain from (#2234).
+    pub fn set_post_source(&mut self, source: PostSource) {
+        let Some(name) = self.active_effect_name() else {
+            return;
+        };
+        let current = self.current_postprocess();
+        self.settings.post.set_source(&name, source, &current);
+        self.settings.save();
+        self.resolve_post_chains(Some(&name));
+    }
+
+    /// The active layer's post chain was edited: keep the edit in settings
+    /// when the chain lives there, and pass it on to the other layers that
+    /// share it.
+    pub fn post_edited(&mut self) {
+        let Some(name) = self.active_effect_name() else {
+            return;
+        };
+        let chain = self.current_postprocess();
+        if self.settings.post.record_edit(&name, &chain) {
+            self.settings.save();
+            self.resolve_post_chains(None);
+        }
+    }
+
+    /// Make the active layer's chain the default.
+    pub fn save_post_default(&mut self) {
+        self.settings.post.default = self.current_postprocess();
+        self.settings.save();
+        self.resolve_post_chains(None);
+    }
+
+    /// Re-resolve the post chain of every layer showing `effect`, or with
+    /// `None`, of every layer whose effect takes its chain from settings.
+    /// Layers on their effect's own chain otherwise keep it, edits and all.
+    fn resolve_post_chains(&mut self, effect: Option<&str>) {
+        for layer in &mut self.layer_stack.layers {
+            let Some(def) = layer
+                .effect_index()
+                .and_then(|idx| self.effect_loader.effects.get(idx))
+            else {
+                continue;
+            };
+            let due = match effect {
+                Some(name) => def.name == name,
+                None => self.settings.post.source(&def.name) != PostSource::Effect,
+            };
+            if due {
+                layer.postprocess =
+                    self.settings
+                        .post
+                        .resolve(&def.name, def.postprocess.as_ref(), None);
+            }
+        }
+        self.post_process.enabled = self.current_postprocess().enabled;
+    }
+
     /// Get the current postprocess def from active layer.
     pub fn current_postprocess(&self) -> PostProcessDef {
         self.layer_stack
@@ -3336,6 +5631,7 @@ impl App {
         self.scene_store.current_scene = Some(index);
 
         self.timeline = Timeline::new(scene.cues.clone(), scene.loop_mode, scene.advance_mode);
+        self.timeline.quantize = scene.quantize;
 
         // Start at cue 0
         let event = self.timeline.start(0);
@@ -3359,6 +5655,7 @@ impl App {
                     cues: self.timeline.cues.clone(),
                     loop_mode: self.timeline.loop_mode,
                     advance_mode: self.timeline.advance_mode,
+                    quantize: self.timeline.quantize,
                 };
                 if let Err(e) = self.scene_store.save(&name, set) {
                     log::error!("Failed to autosave scene: {e}");
@@ -3367,23 +5664,32 @@ impl App {
         }
     }
 
+    /// The preset a cue loads: by id when it has one (#2223), else by name.
+    fn cue_preset_index(&self, cue: &crate::scene::types::SceneCue) -> Option<usize> {
+        cue.preset_id
+            .and_then(|id| self.preset_store.index_of_id(id))
+            .or_else(|| {
+                self.preset_store
+                    .presets
+                    .iter()
+                    .position(|(name, _)| *name == cue.preset_name)
+            })
+    }
+
     /// Process a timeline event (load cue, begin transition, etc.).
     pub fn process_timeline_event(&mut self, event: TimelineEvent) {
         match event {
             TimelineEvent::None => {}
             TimelineEvent::LoadCue { cue_index } => {
-                // Look up the preset by name and load it
                 if let Some(cue) = self.timeline.cues.get(cue_index) {
-                    let preset_name = cue.preset_name.clone();
-                    let preset_idx = self
-                        .preset_store
-                        .presets
-                        .iter()
-                        .position(|(name, _)| name == &preset_name);
-                    if let Some(idx) = preset_idx {
+                    if let Some(idx) = self.cue_preset_index(cue) {
                         self.load_preset(idx);
                     } else {
-                        log::warn!("Preset '{}' not found for cue {}", preset_name, cue_index);
+                        log::warn!(
+                            "Preset '{}' not found for cue {}",
+                            cue.preset_name,
+                            cue_index
+                        );
                     }
                 }
             }
@@ -3404,12 +5710,11 @@ impl App {
                         }
                         // Defer preset load until render() captures the outgoing frame.
                         // render() will: capture snapshot → load preset → crossfade.
-                        let preset_idx = self.timeline.cues.get(to_cue).and_then(|cue| {
-                            self.preset_store
-                                .presets
-                                .iter()
-                                .position(|(name, _)| name == &cue.preset_name)
-                        });
+                        let preset_idx = self
+                            .timeline
+                            .cues
+                            .get(to_cue)
+                            .and_then(|cue| self.cue_preset_index(cue));
                         self.dissolve_capture_pending = preset_idx;
                     }
                     crate::scene::types::TransitionType::ParamMorph => {
@@ -3426,16 +5731,13 @@ impl App {
                         self.morph_from_opacities = Some(from_opacities);
 
                         // Load target preset
-                        if let Some(cue) = self.timeline.cues.get(to_cue) {
-                            let preset_name = cue.preset_name.clone();
-                            let preset_idx = self
-                                .preset_store
-                                .presets
-                                .iter()
-                                .position(|(name, _)| name == &preset_name);
-                            if let Some(idx) = preset_idx {
-                                self.load_preset(idx);
-                            }
+                        if let Some(idx) = self
+                            .timeline
+                            .cues
+                            .get(to_cue)
+                            .and_then(|cue| self.cue_preset_index(cue))
+                        {
+                            self.load_preset(idx);
                         }
 
                         // Snapshot target (incoming) params after preset load
@@ -3503,6 +5805,14 @@ impl App {
         }
     }
 
+    /// What the debug view panel can pick from (#2229).
+    pub fn debug_texture_list(&self) -> Vec<DebugTextureInfo> {
+        debug_textures(&self.layer_stack, &self.motion)
+            .iter()
+            .map(|(layer, texture)| DebugTextureInfo::new(*layer, texture))
+            .collect()
+    }
+
     /// Build SceneInfo snapshot for UI.
     pub fn scene_info(&self) -> crate::ui::panels::scene_panel::SceneInfo {
         let scene_store_names: Vec<String> = self
@@ -3526,11 +5836,14 @@ impl App {
             .timeline
             .cues
             .iter()
-            .map(|c| crate::ui::panels::scene_panel::CueDisplayInfo {
+            .enumerate()
+            .map(|(i, c)| crate::ui::panels::scene_panel::CueDisplayInfo {
                 preset_name: c.display_name().to_string(),
                 transition: c.transition,
                 transition_secs: c.transition_secs,
                 hold_secs: c.hold_secs,
+                number: c.display_number(i),
+                notes: c.notes.clone(),
             })
             .collect();
         crate::ui::panels::scene_panel::SceneInfo {
@@ -3542,17 +5855,353 @@ impl App {
         }
     }
 
+    /// F12 / `Screenshot` trigger (#2252): save the next rendered output as
+    /// a PNG in the recording config's `screenshot_dir`.
+    pub fn capture_screenshot(&mut self) {
+        self.screenshot.request();
+    }
+
+    /// Start an "Export loop" capture of the composite. A recording already in progress
+    /// is left alone — the capture target is shared, and cutting a set short to grab a
+    /// GIF is never what was meant.
+    pub fn export_loop(&mut self) {
+        if self.recording.is_recording() {
+            self.status_error = Some((
+                "Stop the recording before exporting a loop".to_string(),
+                Instant::now(),
+            ));
+            return;
+        }
+        if let Err(e) = self.recording.start_clip(
+            &self.gpu.device,
+            self.gpu.format,
+            self.gpu.surface_config.width,
+            self.gpu.surface_config.height,
+        ) {
+            log::error!("Failed to start loop export: {e}");
+            self.status_error = Some((format!("Loop export failed: {e}"), Instant::now()));
+        }
+    }
+
+    /// Output master edit (#2165) from the panel or OSC. A preset that carries
+    /// its own grade becomes unsaved; otherwise the global setting follows.
+    pub fn set_master_params(&mut self, params: crate::gpu::master::MasterParams) {
+        if params == self.master.params {
+            return;
+        }
+        self.master.params = params;
+        if self.master.per_preset {
+            self.preset_store.mark_dirty();
+        } else {
+            self.settings.master = params;
+            self.settings.save();
+        }
+    }
+
+    /// Output dither (#2236): kept in settings, never in presets.
+    pub fn set_dither(&mut self, dither: crate::gpu::master::DitherParams) {
+        if dither == self.master.dither {
+            return;
+        }
+        self.master.dither = dither;
+        self.settings.dither = dither;
+        self.settings.save();
+    }
+
+    /// Per-output calibration (#2237), kept in settings with the dither.
+    pub fn set_calibration(&mut self, calibration: crate::gpu::calibration::OutputCalibration) {
+        if calibration == self.master.calibration {
+            return;
+        }
+        self.master.calibration = calibration;
+        self.settings.calibration = calibration;
+        self.settings.save();
+    }
+
+    pub fn set_master_param(&mut self, name: &str, value: f32) {
+        let mut params = self.master.params;
+        params.set_param(name, value);
+        self.set_master_params(params);
+    }
+
+    /// Give the current preset its own master grade, or hand it back to the
+    /// global one (whose values return at once).
+    pub fn set_master_per_preset(&mut self, on: bool) {
+        if on == self.master.per_preset {
+            return;
+        }
+        self.master.per_preset = on;
+        if !on {
+            self.master.params = self.settings.master;
+        }
+        self.preset_store.mark_dirty();
+    }
+
+    /// `ParticleBurst` trigger (#2162): queue each enabled particle layer's
+    /// trigger burst. Quantized layers hold theirs for the next beat.
+    pub fn particle_burst(&mut self) {
+        for layer in self.layer_stack.layers.iter_mut().filter(|l| l.enabled) {
+            if let Some(ps) = layer
+                .as_effect_mut()
+                .and_then(|e| e.pass_executor.particle_system.as_mut())
+            {
+                ps.queue_burst();
+            }
+        }
+    }
+
+    /// Build the compositor hook chosen in the settings (#2252). A hook that
+    /// can't be read or compiled leaves the compositing that was running.
+    pub fn apply_composite_hook(&mut self) {
+        self.composite_hook_error = self
+            .compositor
+            .load_hook(&self.gpu.device, self.settings.composite_hook.as_deref())
+            .err();
+    }
+
+    /// `SurpriseMe` trigger (#2251): replace the stack with a random one built
+    /// from the effects this GPU runs. Locked layers stay; the result is
+    /// unsaved until stored as a preset.
+    pub fn surprise_me(&mut self) {
+        let effects: Vec<&crate::effect::format::PfxEffect> = self
+            .effect_loader
+            .effects
+            .iter()
+            .filter(|e| !e.hidden && self.effect_loader.unsupported_reason(e).is_none())
+            .collect();
+        let mut config = self.settings.surprise.clone();
+        config.layers = config.layers.min(self.max_layers());
+        let seed = crate::gpu::deterministic::clock_entropy();
+        let Some(preset) =
+            crate::preset::surprise::surprise(&self.snapshot_preset(), &effects, &config, seed)
+        else {
+            log::warn!("Surprise me: no effects to pick from");
+            return;
+        };
+        self.apply_preset_immediately(None, &preset, std::collections::HashMap::new());
+    }
+
     pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        // Check for GPU device loss
+        // GPU device loss (#2176): rebuild on a fresh device, retrying every
+        // couple of seconds while the driver is still resetting.
         if self
             .gpu
             .device_lost
             .load(std::sync::atomic::Ordering::Relaxed)
         {
-            log::error!("GPU device lost — cannot render");
-            return Err(wgpu::SurfaceError::Lost);
+            if self
+                .last_recovery_attempt
+                .is_some_and(|t| t.elapsed() < DEVICE_RECOVERY_INTERVAL)
+            {
+                return Err(wgpu::SurfaceError::Lost);
+            }
+            self.last_recovery_attempt = Some(Instant::now());
+            if let Err(e) = self.recover_device() {
+                log::error!("GPU device recovery failed: {e:#}");
+                return Err(wgpu::SurfaceError::Lost);
+            }
+            self.last_recovery_attempt = None;
+        }
+
+        // Screenshots (#2252) captured last frame are written off-thread
+        for saved in self
+            .screenshot
+            .poll(&self.gpu.device, &self.recording.config.screenshot_dir)
+        {
+            match saved {
+                Ok(path) => log::info!("Screenshot: {}", path.display()),
+                Err(e) => {
+                    log::error!("Screenshot failed: {e}");
+                    self.status_error = Some((format!("Screenshot failed: {e}"), Instant::now()));
+                }
+            }
+        }
+
+        // Errors from this frame's encoding and submit (#2230), e.g. a user
+        // shader reading a texture in the wrong format. Skipped, not waited
+        // for, while the background compiler holds the scope stack.
+        let scope = ErrorScope::try_push(&self.gpu.device);
+        let result = self.render_frame();
+        if let Some(error) = scope.and_then(ErrorScope::pop) {
+            self.report_gpu_error(error);
+        }
+        if result.is_ok() {
+            self.surface_failures = 0;
+        }
+        result
+    }
+
+    /// The surface went stale (#2231): its monitor was unplugged or changed
+    /// resolution. Reconfigure at the window's current size, and if frames
+    /// keep failing, give the window a new surface.
+    pub fn recover_surface(&mut self) {
+        self.surface_failures += 1;
+        if self.surface_failures >= SURFACE_RECREATE_AFTER {
+            self.surface_failures = 0;
+            match self.gpu.recreate_surface(self.window.clone()) {
+                Ok(()) => log::info!("Output surface recreated"),
+                Err(e) => log::error!("Output surface recreation failed: {e:#}"),
+            }
+        }
+        let size = self.window.inner_size();
+        self.resize(size.width, size.height);
+    }
+
+    /// F: borderless fullscreen on the window's monitor, or back to a window.
+    pub fn toggle_fullscreen(&mut self) {
+        if self.window.fullscreen().is_some() {
+            self.window.set_fullscreen(None);
+            self.output_monitor.windowed();
+        } else {
+            let monitor = self.window.current_monitor();
+            self.output_monitor
+                .fullscreen_on(monitor.as_ref().and_then(|m| m.name()));
+            self.window
+                .set_fullscreen(Some(winit::window::Fullscreen::Borderless(monitor)));
+        }
+    }
+
+    /// Follow the fullscreen output's monitor through an unplug (#2231): say
+    /// when it goes, and put the output back on it fullscreen when it returns.
+    fn check_output_monitor(&mut self) {
+        let now = Instant::now();
+        if self.output_monitor.wanted().is_none() || !self.output_monitor.due(now) {
+            return;
+        }
+        let monitors: Vec<_> = self.window.available_monitors().collect();
+        let names: Vec<String> = monitors.iter().filter_map(|m| m.name()).collect();
+        match self.output_monitor.check(names.iter().map(String::as_str)) {
+            Some(MonitorChange::Lost(name)) => {
+                log::warn!("Output monitor '{name}' disconnected");
+                self.status_error = Some((
+                    format!(
+                        "Output monitor '{name}' disconnected. The output goes back when it reconnects."
+                    ),
+                    now,
+                ));
+            }
+            Some(MonitorChange::Restored(name)) => {
+                log::info!("Output monitor '{name}' reconnected");
+                let monitor = monitors
+                    .into_iter()
+                    .find(|m| m.name().as_deref() == Some(name.as_str()));
+                self.window
+                    .set_fullscreen(Some(winit::window::Fullscreen::Borderless(monitor)));
+                self.status_error = Some((format!("Output back on '{name}'"), now));
+            }
+            // Fullscreen left some other way, e.g. a window manager shortcut
+            None if self.window.fullscreen().is_none() && !self.output_monitor.is_missing() => {
+                self.output_monitor.windowed();
+            }
+            None => {}
+        }
+    }
+
+    /// Replace the watch folders (#2246) and start watching the new set.
+    pub fn set_watch_folders(&mut self, folders: Vec<std::path::PathBuf>) {
+        self.media_watcher = None;
+        self.media_watcher = start_media_watcher(&folders);
+        self.settings.watch_folders = folders;
+        self.settings.save();
+    }
+
+    /// Put media that appeared in a watch folder (#2246) at the top of the
+    /// recent list, and say so.
+    fn check_watch_folders(&mut self) {
+        let Some(watcher) = self.media_watcher.as_ref() else {
+            return;
+        };
+        let new = watcher.drain_new();
+        let Some(last) = new.last() else {
+            return;
+        };
+        for path in &new {
+            log::info!("New media in watch folder: {}", path.display());
+            push_recent(&mut self.settings.recent_media, &path.to_string_lossy());
+        }
+        self.settings.save();
+        let what = if new.len() == 1 {
+            last.file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        } else {
+            format!("{} files", new.len())
+        };
+        self.status_error = Some((
+            format!("New media: {what} (right-click + Media to add)"),
+            Instant::now(),
+        ));
+    }
+
+    /// Suspend the layers the frame-time guard (#2243) caught over budget.
+    /// Timings arrive a frame or two late, from the last landed readback.
+    fn check_frame_guard(&mut self) {
+        let Some(timings) = self.layer_timer.as_mut().and_then(LayerTimer::poll) else {
+            return;
+        };
+        let config = &self.settings.frame_guard;
+        if !config.enabled {
+            return;
+        }
+        let over = self
+            .frame_guard
+            .observe(config, self.layer_stack.layers.len(), &timings);
+        for (i, ms) in over {
+            let Some(layer) = self.layer_stack.layers.get_mut(i) else {
+                continue;
+            };
+            let reason = format!(
+                "{ms:.0} ms of GPU time a frame, over the {:.0} ms budget",
+                config.budget_ms
+            );
+            log::warn!("Layer {} suspended: {reason}", i + 1);
+            self.status_error = Some((
+                format!("Layer {} suspended: {reason}", i + 1),
+                Instant::now(),
+            ));
+            layer.suspended = Some(reason);
+        }
+    }
+
+    /// Show a GPU error in the status bar (#2230). When wgpu names one of an
+    /// effect's passes, the error also goes on that layer, where the param
+    /// panel and shader editor show it. The active layer is checked first,
+    /// since pass names repeat across effects.
+    fn report_gpu_error(&mut self, error: GpuError) {
+        if !self
+            .gpu_error_throttle
+            .should_report(&error, Instant::now())
+        {
+            return;
+        }
+        log::error!("GPU error: {error}");
+        self.status_error = Some((error.status_text(), Instant::now()));
+        let active = self.layer_stack.active_layer;
+        let order = std::iter::once(active)
+            .chain((0..self.layer_stack.layers.len()).filter(|&i| i != active));
+        for i in order {
+            let Some(effect) = self
+                .layer_stack
+                .layers
+                .get_mut(i)
+                .and_then(|l| l.as_effect_mut())
+            else {
+                continue;
+            };
+            if effect
+                .pass_executor
+                .pass_names()
+                .any(|name| error.mentions_label(name))
+            {
+                effect
+                    .shader_error
+                    .get_or_insert_with(|| format!("GPU error: {error}"));
+                break;
+            }
         }
+    }
 
+    fn render_frame(&mut self) -> Result<(), wgpu::SurfaceError> {
         let output = self.gpu.surface.get_current_texture()?;
         let surface_view = output
             .texture
@@ -3565,12 +6214,11 @@ impl App {
                 label: Some("phosphor-encoder"),
             });
 
-        // Execute all enabled layers
-        let mut enabled_layers: Vec<usize> = Vec::with_capacity(self.layer_stack.layers.len());
-        for (i, l) in self.layer_stack.layers.iter().enumerate() {
-            if l.enabled {
-                enabled_layers.push(i);
-            }
+        // Stacks past the default cap need more compositor passes (#2170)
+        self.compositor
+            .reserve(&self.gpu.device, self.layer_stack.layers.len());
+        if self.debug_view.active() && self.debug_viewer.is_none() {
+            self.debug_viewer = Some(DebugViewer::new(&self.gpu.device, self.gpu.format));
         }
 
         // Poll particle counter readback from previous frame (non-blocking)
@@ -3582,41 +6230,85 @@ impl App {
                 }
             }
         }
+        self.check_frame_guard();
+        let guard_on = self.settings.frame_guard.enabled;
+
+        // Motion field (#2154): analyse the tracked media layer's last output before
+        // any layer samples the flow this frame.
+        let motion_source = self.layer_stack.motion_source();
+        if let Some(i) = motion_source {
+            self.motion.dispatch(
+                &self.gpu.device,
+                &mut encoder,
+                self.layer_stack.layers[i].previous_output(),
+            );
+        }
+
+        // Layer-fed particle emitters (#2155): refresh home positions from the
+        // source layer's last output before the particle sims step.
+        for (i, layer) in self.layer_stack.layers.iter().enumerate() {
+            let Some(ps) = layer
+                .as_effect()
+                .and_then(|e| e.pass_executor.particle_system.as_ref())
+            else {
+                continue;
+            };
+            let Some(src) = ps
+                .image_source
+                .layer_index()
+                .filter(|&s| s != i)
+                .and_then(|s| self.layer_stack.layers.get(s))
+            else {
+                continue;
+            };
+            ps.sample_layer(
+                &self.gpu.device,
+                &self.gpu.queue,
+                &mut encoder,
+                src.previous_output(),
+                self.uniforms.time,
+            );
+        }
+        // Layer-fed obstacles (#2160): same one-frame-behind source, drawn
+        // into the obstacle map the collision modes read.
+        for (i, layer) in self.layer_stack.layers.iter().enumerate() {
+            let Some(ps) = layer
+                .as_effect()
+                .and_then(|e| e.pass_executor.particle_system.as_ref())
+            else {
+                continue;
+            };
+            let Some(src) = ps
+                .obstacle_layer_index()
+                .filter(|&s| s != i)
+                .and_then(|s| self.layer_stack.layers.get(s))
+            else {
+                continue;
+            };
+            ps.sample_obstacle_layer(&self.gpu.device, &mut encoder, src.previous_output());
+        }
+        let flow = motion_source.map(|_| &self.motion.flow);
 
         // Compute the HDR source from layer execution + compositing.
-        let (source, postprocess) = if enabled_layers.is_empty() {
-            (
-                self.compositor.accumulator.write_target()
-                    as &crate::gpu::render_target::RenderTarget,
+        self.compositor.hook_time = self.uniforms.time;
+        let (composited, ran_layers) = self.layer_stack.execute_and_composite(
+            &self.gpu.device,
+            &self.gpu.queue,
+            &mut encoder,
+            &self.compositor,
+            flow,
+            &self.effect_loader.effects,
+            self.layer_timer.as_ref().filter(|_| guard_on),
+        );
+        if let Some(timer) = self.layer_timer.as_mut().filter(|_| guard_on) {
+            timer.resolve(&mut encoder, &ran_layers);
+        }
+        let (source, postprocess) = match composited {
+            Some(target) => (target, self.current_postprocess()),
+            None => (
+                self.compositor.clear_output(&self.gpu.device, &mut encoder),
                 PostProcessDef::default(),
-            )
-        } else if enabled_layers.len() == 1
-            && self.layer_stack.layers[enabled_layers[0]].opacity >= 1.0
-        {
-            // Single-layer fast path: skip compositing entirely (only when fully opaque)
-            let idx = enabled_layers[0];
-            let target = self.layer_stack.layers[idx].execute(&mut encoder, &self.gpu.queue);
-            (target, self.current_postprocess())
-        } else {
-            // Multi-layer: render each layer, then composite
-            let mut layer_outputs: Vec<(&crate::gpu::render_target::RenderTarget, BlendMode, f32)> =
-                Vec::with_capacity(enabled_layers.len());
-            for &idx in &enabled_layers {
-                let target = self.layer_stack.layers[idx].execute(&mut encoder, &self.gpu.queue);
-                let blend = self.layer_stack.layers[idx].blend_mode;
-                let opacity = self.layer_stack.layers[idx].opacity;
-                layer_outputs.push((target, blend, opacity));
-            }
-            // Reverse so top-of-UI-list renders visually on top
-            layer_outputs.reverse();
-
-            let composited = self.compositor.composite(
-                &self.gpu.device,
-                &self.gpu.queue,
-                &mut encoder,
-                &layer_outputs,
-            );
-            (composited, self.current_postprocess())
+            ),
         };
 
         // Dissolve capture: on the first frame of a dissolve, capture outgoing then load incoming.
@@ -3639,59 +6331,36 @@ impl App {
                 .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                     label: Some("phosphor-encoder-dissolve"),
                 });
-            let mut enabled_layers2: Vec<usize> = Vec::with_capacity(self.layer_stack.layers.len());
-            for (i, l) in self.layer_stack.layers.iter().enumerate() {
-                if l.enabled {
-                    enabled_layers2.push(i);
-                }
+            let flow = self.layer_stack.motion_source().map(|_| &self.motion.flow);
+            let (composited, ran_layers2) = self.layer_stack.execute_and_composite(
+                &self.gpu.device,
+                &self.gpu.queue,
+                &mut encoder,
+                &self.compositor,
+                flow,
+                &self.effect_loader.effects,
+                self.layer_timer.as_ref().filter(|_| guard_on),
+            );
+            if let Some(timer) = self.layer_timer.as_mut().filter(|_| guard_on) {
+                timer.resolve(&mut encoder, &ran_layers2);
             }
-            let (new_source, new_pp) = if enabled_layers2.is_empty() {
-                (
-                    self.compositor.accumulator.write_target()
-                        as &crate::gpu::render_target::RenderTarget,
+            let (new_source, new_pp) = match composited {
+                Some(target) => (target, self.current_postprocess()),
+                None => (
+                    self.compositor.clear_output(&self.gpu.device, &mut encoder),
                     PostProcessDef::default(),
-                )
-            } else if enabled_layers2.len() == 1
-                && self.layer_stack.layers[enabled_layers2[0]].opacity >= 1.0
-            {
-                let idx = enabled_layers2[0];
-                let target = self.layer_stack.layers[idx].execute(&mut encoder, &self.gpu.queue);
-                (target, self.current_postprocess())
-            } else {
-                let mut layer_outputs2: Vec<(
-                    &crate::gpu::render_target::RenderTarget,
-                    BlendMode,
-                    f32,
-                )> = Vec::with_capacity(enabled_layers2.len());
-                for &idx in &enabled_layers2 {
-                    let target =
-                        self.layer_stack.layers[idx].execute(&mut encoder, &self.gpu.queue);
-                    let blend = self.layer_stack.layers[idx].blend_mode;
-                    let opacity = self.layer_stack.layers[idx].opacity;
-                    layer_outputs2.push((target, blend, opacity));
-                }
-                layer_outputs2.reverse();
-                let composited = self.compositor.composite(
-                    &self.gpu.device,
-                    &self.gpu.queue,
-                    &mut encoder,
-                    &layer_outputs2,
-                );
-                (composited, self.current_postprocess())
+                ),
             };
             // Crossfade snapshot (outgoing) + new_source (incoming)
             let source = if let Some(ref tr) = self.transition_renderer {
                 if tr.has_snapshot() {
-                    if let crate::scene::timeline::PlaybackState::Transitioning {
-                        progress, ..
-                    } = &self.timeline.state
-                    {
+                    if let Some(progress) = self.dissolve_progress() {
                         tr.crossfade(
                             &self.gpu.device,
                             &self.gpu.queue,
                             &mut encoder,
                             new_source,
-                            *progress,
+                            progress,
                         )
                         .unwrap_or(new_source)
                     } else {
@@ -3703,6 +6372,13 @@ impl App {
             } else {
                 new_source
             };
+            let source = self.freeze.render(
+                &mut self.freeze_renderer,
+                &self.gpu.device,
+                &self.gpu.queue,
+                &mut encoder,
+                source,
+            );
             // Post-process → surface
             self.post_process.render(
                 &self.gpu.device,
@@ -3718,20 +6394,28 @@ impl App {
                 {
                     #[cfg(feature = "ndi")]
                     {
-                        self.ndi.config.alpha_from_luma
+                        self.ndi.config.alpha_mode()
                     }
                     #[cfg(not(feature = "ndi"))]
                     {
-                        false
+                        crate::gpu::postprocess::AlphaMode::Opaque
                     }
                 },
+                &self.master.uniforms(),
+                &self.master.calibration,
             );
 
             // NDI capture
+            let stamp = FrameStamp::now(self.latest_audio.as_ref());
             #[cfg(feature = "ndi")]
             if self.ndi.is_running() {
-                self.ndi
-                    .capture_frame(&self.gpu.device, &mut encoder, &self.post_process, source);
+                self.ndi.capture_frame(
+                    &self.gpu.device,
+                    &mut encoder,
+                    &self.post_process,
+                    source,
+                    stamp,
+                );
             }
 
             // Recording capture
@@ -3741,12 +6425,22 @@ impl App {
                     &mut encoder,
                     &self.post_process,
                     source,
+                    stamp,
                 );
             }
+            self.screenshot.capture(
+                &self.gpu.device,
+                &mut encoder,
+                &self.post_process,
+                source,
+                self.gpu.format,
+                self.gpu.surface_config.width,
+                self.gpu.surface_config.height,
+            );
 
-            // Flip ping-pong for all layers
-            for layer in &mut self.layer_stack.layers {
-                layer.flip();
+            // Flip ping-pong for the layers that ran; skipped ones keep their state
+            for i in ran_layers2 {
+                self.layer_stack.layers[i].flip();
             }
             self.frame_count = self.frame_count.wrapping_add(1);
 
@@ -3766,6 +6460,11 @@ impl App {
             #[cfg(feature = "profiling")]
             self.gpu_profiler.end_frame(&self.gpu.queue);
 
+            self.motion.request_readback();
+            if let Some(timer) = &mut self.layer_timer {
+                timer.request_readback();
+            }
+
             // Request particle counter readback (async, read next frame)
             for layer in &self.layer_stack.layers {
                 if let Some(effect) = layer.as_effect() {
@@ -3784,18 +6483,14 @@ impl App {
             if self.recording.is_recording() {
                 self.recording.post_submit();
             }
+            self.screenshot.post_submit();
 
             output.present();
             return Ok(());
         }
 
-        // Dissolve crossfade: if transitioning with dissolve, blend snapshot + current
-        let source = if let crate::scene::timeline::PlaybackState::Transitioning {
-            transition_type: crate::scene::types::TransitionType::Dissolve,
-            progress,
-            ..
-        } = &self.timeline.state
-        {
+        // Dissolve crossfade: if a cue or attract mode is dissolving, blend snapshot + current
+        let source = if let Some(progress) = self.dissolve_progress() {
             if let Some(ref tr) = self.transition_renderer {
                 if tr.has_snapshot() {
                     tr.crossfade(
@@ -3803,7 +6498,7 @@ impl App {
                         &self.gpu.queue,
                         &mut encoder,
                         source,
-                        *progress,
+                        progress,
                     )
                     .unwrap_or(source)
                 } else {
@@ -3816,6 +6511,16 @@ impl App {
             source
         };
 
+        // Freeze frame (#2166): the held composite over or under live output,
+        // ahead of post so captures and the output master see it too.
+        let source = self.freeze.render(
+            &mut self.freeze_renderer,
+            &self.gpu.device,
+            &self.gpu.queue,
+            &mut encoder,
+            source,
+        );
+
         // Post-process → surface
         self.post_process.render(
             &self.gpu.device,
@@ -3831,20 +6536,56 @@ impl App {
             {
                 #[cfg(feature = "ndi")]
                 {
-                    self.ndi.config.alpha_from_luma
+                    self.ndi.config.alpha_mode()
                 }
                 #[cfg(not(feature = "ndi"))]
                 {
-                    false
+                    crate::gpu::postprocess::AlphaMode::Opaque
                 }
             },
+            &self.master.uniforms(),
+            &self.master.calibration,
         );
 
-        // NDI capture: render composite to capture texture + copy to staging
+        // Debug view (#2229): the picked texture over the output, under the UI.
+        // Window only; NDI and recordings capture `source`, not the surface.
+        // Hidden with the overlay, so hiding the UI for a show hides it too.
+        if let (true, true, Some(viewer)) = (
+            self.egui_overlay.visible,
+            self.debug_view.active(),
+            &self.debug_viewer,
+        ) && let Some(texture) = self.debug_view.selection.as_ref().and_then(|sel| {
+            debug_textures(&self.layer_stack, &self.motion)
+                .into_iter()
+                .find(|(layer, t)| *layer == sel.layer && t.label == sel.label)
+                .map(|(_, t)| t)
+        }) {
+            viewer.draw(
+                &self.gpu.device,
+                &self.gpu.queue,
+                &mut encoder,
+                &texture,
+                &surface_view,
+                (
+                    self.gpu.surface_config.width,
+                    self.gpu.surface_config.height,
+                ),
+                &self.debug_view,
+            );
+        }
+
+        // NDI capture: render composite to capture texture + copy to staging.
+        // Both outputs stamp the frame with its render time and beat (#2211).
+        let stamp = FrameStamp::now(self.latest_audio.as_ref());
         #[cfg(feature = "ndi")]
         if self.ndi.is_running() {
-            self.ndi
-                .capture_frame(&self.gpu.device, &mut encoder, &self.post_process, source);
+            self.ndi.capture_frame(
+                &self.gpu.device,
+                &mut encoder,
+                &self.post_process,
+                source,
+                stamp,
+            );
         }
 
         // Recording capture
@@ -3854,12 +6595,24 @@ impl App {
                 &mut encoder,
                 &self.post_process,
                 source,
+                stamp,
             );
         }
 
-        // Flip ping-pong for all layers
-        for layer in &mut self.layer_stack.layers {
-            layer.flip();
+        // Screenshot (#2252): the same output, once, at surface resolution
+        self.screenshot.capture(
+            &self.gpu.device,
+            &mut encoder,
+            &self.post_process,
+            source,
+            self.gpu.format,
+            self.gpu.surface_config.width,
+            self.gpu.surface_config.height,
+        );
+
+        // Flip ping-pong for the layers that ran; skipped ones keep their state
+        for i in ran_layers {
+            self.layer_stack.layers[i].flip();
         }
         self.frame_count = self.frame_count.wrapping_add(1);
 
@@ -3888,6 +6641,10 @@ impl App {
         // and never fired, so growth rules just filled the domain and parked on a
         // sphere. Requesting it here (alongside the counter) is what makes the
         // reseed work at all.
+        self.motion.request_readback();
+        if let Some(timer) = &mut self.layer_timer {
+            timer.request_readback();
+        }
         for layer in &self.layer_stack.layers {
             if let Some(effect) = layer.as_effect() {
                 if let Some(ps) = &effect.pass_executor.particle_system {
@@ -3910,12 +6667,269 @@ impl App {
         if self.recording.is_recording() {
             self.recording.post_submit();
         }
+        self.screenshot.post_submit();
 
         output.present();
 
         Ok(())
     }
 
+    /// Rescan the effects directory after files changed underneath it
+    /// (#2180). Indices move when effects are added, so layers and the
+    /// current selection are re-pointed by name.
+    pub fn rescan_effects(&mut self) {
+        let name_of = |loader: &EffectLoader, idx: Option<usize>| {
+            idx.and_then(|i| loader.effects.get(i))
+                .map(|e| e.name.clone())
+        };
+        let layer_names: Vec<Option<String>> = self
+            .layer_stack
+            .layers
+            .iter()
+            .map(|l| name_of(&self.effect_loader, l.effect_index()))
+            .collect();
+        let current = name_of(&self.effect_loader, self.effect_loader.current_effect);
+
+        self.effect_loader.scan_effects_directory();
+
+        let index_of = |loader: &EffectLoader, name: Option<String>| {
+            name.and_then(|n| loader.effects.iter().position(|e| e.name == n))
+        };
+        for (layer, name) in self.layer_stack.layers.iter_mut().zip(layer_names) {
+            if let Some(effect) = layer.as_effect_mut() {
+                if effect.effect_index.is_some() {
+                    effect.effect_index = index_of(&self.effect_loader, name);
+                }
+            }
+        }
+        self.effect_loader.current_effect = index_of(&self.effect_loader, current);
+    }
+
+    /// Open the shader editor on the active layer's first pass, with its
+    /// .pfx in the second tab.
+    pub fn open_shader_editor(&mut self) {
+        let Some(idx) = self.layer_stack.active().and_then(|l| l.effect_index()) else {
+            return;
+        };
+        let Some(effect) = self.effect_loader.effects.get(idx).cloned() else {
+            return;
+        };
+        let passes = effect.normalized_passes();
+        let Some(pass) = passes.first() else {
+            return;
+        };
+        let path = self.effect_loader.resolve_shader_path(&pass.shader);
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            self.shader_editor.open_file(&effect.name, path, content);
+            // Load paired .pfx file for tab switching
+            if let Some(ref pfx_path) = effect.source_path {
+                if let Ok(pfx_content) = std::fs::read_to_string(pfx_path) {
+                    self.shader_editor
+                        .load_paired_pfx(pfx_path.clone(), pfx_content);
+                }
+            }
+            // The file as opened is the first thing a bad edit can lose.
+            self.record_shader_snapshot(false);
+            self.lint_editor_shader();
+        } else {
+            log::error!("Could not read shader: {}", path.display());
+        }
+    }
+
+    /// Open one of the active effect's shaders in the configured outside
+    /// editor (#2191). `shader` is relative to the shaders directory.
+    pub fn open_in_external_editor(&mut self, shader: &str) {
+        let path = self.effect_loader.resolve_shader_path(shader);
+        if let Err(e) = crate::shader::external_editor::open(&self.settings.external_editor, &path)
+        {
+            log::error!("Could not open {} in an editor: {e}", path.display());
+            self.status_error = Some((format!("Editor failed to start: {e}"), Instant::now()));
+        }
+    }
+
+    /// Every OSC address the app answers with the current layers (#2198).
+    pub fn osc_address_map(&self) -> Vec<OscAddress> {
+        let layers: Vec<MapLayer<'_>> = self
+            .layer_stack
+            .layers
+            .iter()
+            .map(|l| MapLayer {
+                name: l.custom_name.as_deref().unwrap_or(&l.name),
+                params: &l.param_store.defs,
+                particles: l.has_particles(),
+            })
+            .collect();
+        crate::osc::address_map::address_map(
+            &self.osc.config,
+            &layers,
+            self.layer_stack.active_layer,
+        )
+    }
+
+    /// Ask where to save the OSC address map, then write it there. The
+    /// dialog runs on its own thread so rendering doesn't stall.
+    pub fn save_osc_address_map(&self, format: MapFormat) {
+        let map = match &self.osc.address_map {
+            Some(map) => map.clone(),
+            None => self.osc_address_map(),
+        };
+        let text = match format {
+            MapFormat::Markdown => {
+                crate::osc::address_map::to_markdown(&map, self.osc.config.rx_port)
+            }
+            MapFormat::Csv => crate::osc::address_map::to_csv(&map),
+        };
+        std::thread::Builder::new()
+            .name("osc-map-dialog".into())
+            .spawn(move || {
+                let ext = format.extension();
+                let dialog = rfd::FileDialog::new()
+                    .set_file_name(format!("fosfora-osc.{ext}"))
+                    .add_filter(ext.to_uppercase(), &[ext]);
+                if let Some(path) = dialog.save_file() {
+                    match std::fs::write(&path, text) {
+                        Ok(()) => log::info!("Saved OSC address map to {}", path.display()),
+                        Err(e) => log::error!("Failed to save OSC address map: {e}"),
+                    }
+                }
+            })
+            .ok();
+    }
+
+    /// Show or hide the shader editor (#2188). Hiding keeps unsaved edits;
+    /// showing again resumes them if the active layer still plays that effect.
+    pub fn toggle_shader_editor(&mut self) {
+        if self.shader_editor.open {
+            self.shader_editor.open = false;
+            return;
+        }
+        let active_effect = self
+            .layer_stack
+            .active()
+            .and_then(|l| l.effect_index())
+            .and_then(|i| self.effect_loader.effects.get(i))
+            .map(|e| e.name.as_str());
+        if self.shader_editor.file_path.is_some()
+            && active_effect == Some(self.shader_editor.effect_name.as_str())
+        {
+            self.shader_editor.open = true;
+        } else {
+            self.open_shader_editor();
+        }
+    }
+
+    /// Snapshot the editor's shader file into its version history (#2189)
+    /// and refresh the editor's list. `compiled` only counts when the
+    /// active layer is playing the effect the editor has open.
+    fn record_shader_snapshot(&mut self, compiled: bool) {
+        let Some(path) = self
+            .shader_editor
+            .wgsl_path()
+            .map(std::path::Path::to_path_buf)
+        else {
+            return;
+        };
+        if compiled {
+            let active_effect = self
+                .layer_stack
+                .active()
+                .and_then(|l| l.effect_index())
+                .and_then(|i| self.effect_loader.effects.get(i))
+                .map(|e| e.name.as_str());
+            if active_effect != Some(self.shader_editor.effect_name.as_str()) {
+                return;
+            }
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return;
+        };
+        self.shader_history.record(&path, &content, compiled);
+        self.shader_editor.history = self.shader_history.list(&path);
+    }
+
+    /// Refresh the editor's warnings and performance hints (#2192) from the
+    /// saved WGSL, wrapped the way its effect compiles it.
+    fn lint_editor_shader(&mut self) {
+        let Some(path) = self
+            .shader_editor
+            .wgsl_path()
+            .map(std::path::Path::to_path_buf)
+        else {
+            return;
+        };
+        let Ok(user) = std::fs::read_to_string(&path) else {
+            return;
+        };
+        let loader = &self.effect_loader;
+        let effect = loader
+            .effects
+            .iter()
+            .find(|e| e.name == self.shader_editor.effect_name);
+        let is_compute = effect.and_then(|e| e.particles.as_ref()).is_some_and(|p| {
+            !p.compute_shader.is_empty() && loader.resolve_shader_path(&p.compute_shader) == path
+        });
+        let source = if is_compute {
+            loader.prepend_compute_libraries(&user)
+        } else {
+            let inputs = effect
+                .map(|e| e.normalized_passes())
+                .unwrap_or_default()
+                .into_iter()
+                .find(|p| loader.resolve_shader_path(&p.shader) == path)
+                .map_or(0, |p| p.inputs.len());
+            loader.prepend_library_with_inputs(&user, inputs)
+        };
+        // The user's source is always the tail of what compiles.
+        self.shader_editor.diagnostics =
+            crate::shader::lint::lint(&source, source.len() - user.len());
+    }
+
+    /// Write the editor's dirty tabs to disk; hot reload compiles the shader.
+    pub fn save_shader_editor(&mut self) {
+        let ed = &mut self.shader_editor;
+        let mut wgsl_written = false;
+        // Save the active tab
+        if let Some(ref path) = ed.file_path {
+            match std::fs::write(path, &ed.code) {
+                Ok(()) => {
+                    ed.disk_content = ed.code.clone();
+                    log::info!("Saved shader: {}", path.display());
+                    if ed.file_type == EditorFileType::Wgsl {
+                        ed.wgsl_saved();
+                        wgsl_written = true;
+                    }
+                }
+                Err(e) => {
+                    log::error!("Failed to save shader: {e}");
+                    self.status_error = Some((format!("Save failed: {e}"), Instant::now()));
+                }
+            }
+        }
+        // Also save the paired tab if it has unsaved changes
+        if ed.paired_is_dirty() {
+            if let Some(ref paired_path) = ed.paired_path {
+                match std::fs::write(paired_path, &ed.paired_content) {
+                    Ok(()) => {
+                        ed.paired_disk_content = ed.paired_content.clone();
+                        log::info!("Saved paired file: {}", paired_path.display());
+                        if ed.file_type == EditorFileType::Pfx {
+                            ed.wgsl_saved();
+                            wgsl_written = true;
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Failed to save paired file: {e}");
+                        self.status_error = Some((format!("Save failed: {e}"), Instant::now()));
+                    }
+                }
+            }
+        }
+        if wgsl_written {
+            self.record_shader_snapshot(false);
+            self.lint_editor_shader();
+        }
+    }
+
     /// Create a new effect from template (.pfx + .wgsl), scan, load, and open in editor.
     pub fn copy_builtin_effect(&mut self, new_name: &str) -> Result<()> {
         let idx = self
@@ -3923,34 +6937,24 @@ impl App {
             .current_effect
             .ok_or_else(|| anyhow::anyhow!("No effect selected"))?;
 
-        let (_pfx_path, wgsl_path) = self.effect_loader.copy_builtin_effect(idx, new_name)?;
-
-        // Rescan effects
-        self.effect_loader.scan_effects_directory();
-
-        // Find and load the new effect
-        let new_idx = self
-            .effect_loader
-            .effects
-            .iter()
-            .position(|e| e.name == new_name);
-        if let Some(new_idx) = new_idx {
-            self.load_effect(new_idx);
-        }
+        let (new_idx, wgsl_path) = self.effect_loader.copy_builtin_effect(idx, new_name)?;
+        self.load_effect(new_idx);
 
         // Open in editor
         if wgsl_path.exists() {
             let content = std::fs::read_to_string(&wgsl_path)?;
             self.shader_editor.open_file(new_name, wgsl_path, content);
-            // Load paired .pfx for tab switching
-            if let Some(new_idx) = new_idx {
-                if let Some(ref pfx_path) = self.effect_loader.effects[new_idx].source_path {
-                    if let Ok(pfx_content) = std::fs::read_to_string(pfx_path) {
-                        self.shader_editor
-                            .load_paired_pfx(pfx_path.clone(), pfx_content);
-                    }
-                }
+            // Load paired .pfx for tab switching; it may still be queued for
+            // the writer, so take it from the loaded effect, not the disk
+            let effect = &self.effect_loader.effects[new_idx];
+            if let (Some(pfx_path), Ok(pfx_content)) = (
+                effect.source_path.clone(),
+                serde_json::to_string_pretty(effect),
+            ) {
+                self.shader_editor.load_paired_pfx(pfx_path, pfx_content);
             }
+            self.record_shader_snapshot(false);
+            self.lint_editor_shader();
         }
 
         Ok(())
@@ -4084,6 +7088,8 @@ fn fs_main(@builtin(position) frag_coord: vec4f) -> @location(0) vec4f {{
             if let Ok(pfx_content) = std::fs::read_to_string(&pfx_path) {
                 self.shader_editor.load_paired_pfx(pfx_path, pfx_content);
             }
+            self.record_shader_snapshot(false);
+            self.lint_editor_shader();
         }
 
         Ok(())
@@ -4096,9 +7102,238 @@ impl ShaderUniforms {
     }
 }
 
+/// Watch `folders` for new media (#2246), or `None` if there are none or the
+/// watcher can't start.
+fn start_media_watcher(folders: &[std::path::PathBuf]) -> Option<MediaFolderWatcher> {
+    if folders.is_empty() {
+        return None;
+    }
+    MediaFolderWatcher::new(folders)
+        .inspect_err(|e| log::warn!("Watch folders unavailable: {e}"))
+        .ok()
+}
+
+/// Layer `idx` if it exists and isn't locked, for web requests (#2197).
+fn unlocked_layer(stack: &mut LayerStack, idx: usize) -> Result<&mut Layer, RpcError> {
+    let layer = stack
+        .layers
+        .get_mut(idx)
+        .ok_or_else(|| RpcError::not_found(format!("no layer {idx}")))?;
+    if layer.locked {
+        return Err(RpcError::locked(idx));
+    }
+    Ok(layer)
+}
+
 /// Read default.wgsl from assets dir, falling back to embedded copy.
 fn read_default_shader() -> String {
     let path = assets_dir().join("shaders/default.wgsl");
     std::fs::read_to_string(&path)
         .unwrap_or_else(|_| include_str!("../../../assets/shaders/default.wgsl").to_string())
 }
+
+/// Everything the debug view can show (#2229): each layer's textures, then
+/// the motion field while a media layer feeds it.
+fn debug_textures<'a>(
+    stack: &'a LayerStack,
+    motion: &'a MotionField,
+) -> Vec<(Option<usize>, DebugTexture<'a>)> {
+    let mut out = stack.debug_textures();
+    if stack.motion_source().is_some() {
+        out.push((None, DebugTexture::of_target("motion field", &motion.flow)));
+    }
+    out
+}
+
+/// Build the particle system for an already-sized `ParticleDef`: its compute
+/// shader, image, video, sprite, trail and morph sources. Shared by the app and
+/// the headless benchmark (#2225).
+pub(crate) fn new_particle_system(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    loader: &EffectLoader,
+    particles: &crate::gpu::particle::types::ParticleDef,
+) -> Option<ParticleSystem> {
+    let before = crate::gpu::resources::ResourceCounts::read(device);
+    let hdr_format = GpuContext::hdr_format();
+    let is_image_emitter = particles.emitter.shape == "image";
+
+    // For image emitters, use the builtin image_scatter compute shader
+    let compute_source = if is_image_emitter && particles.compute_shader.is_empty() {
+        loader.prepend_compute_libraries(include_str!(
+            "../../../assets/shaders/builtin/image_scatter.wgsl"
+        ))
+    } else if particles.compute_shader.is_empty() {
+        loader.prepend_compute_libraries(include_str!(
+            "../../../assets/shaders/builtin/particle_sim.wgsl"
+        ))
+    } else {
+        match loader.load_compute_source(&particles.compute_shader) {
+            Ok(src) => src,
+            Err(e) => {
+                log::error!(
+                    "Failed to load compute shader '{}': {e}",
+                    particles.compute_shader
+                );
+                return None;
+            }
+        }
+    };
+
+    let mut ps = ParticleSystem::new(
+        device,
+        queue,
+        hdr_format,
+        particles,
+        &compute_source,
+        particles.interaction,
+    );
+    log::info!("Particle system created: {} particles", particles.max_count);
+
+    // Load image data for image emitters
+    if is_image_emitter && !particles.emitter.image.is_empty() {
+        let sample_def =
+            particles
+                .image_sample
+                .clone()
+                .unwrap_or(crate::gpu::particle::types::ImageSampleDef {
+                    mode: "grid".to_string(),
+                    threshold: 0.1,
+                    scale: 1.0,
+                });
+        ps.sample_def = sample_def.clone();
+        // `text:` sources (#2161) are rendered, not looked up in assets
+        let image_path =
+            if crate::gpu::particle::vector_source::text_of(&particles.emitter.image).is_some() {
+                std::path::PathBuf::from(&particles.emitter.image)
+            } else {
+                assets_dir().join("images").join(&particles.emitter.image)
+            };
+        match crate::gpu::particle::image_source::sample_image(
+            &image_path,
+            &sample_def,
+            particles.max_count,
+        ) {
+            Ok(aux_data) => {
+                ps.upload_aux_data(device, queue, &aux_data);
+                ps.store_current_aux(aux_data.clone());
+                ps.static_image_path = Some(image_path.to_string_lossy().to_string());
+                log::info!(
+                    "Loaded image '{}': {} particles",
+                    particles.emitter.image,
+                    aux_data.len()
+                );
+            }
+            Err(e) => {
+                log::warn!("Failed to load image '{}': {e}", particles.emitter.image);
+            }
+        }
+
+        // If a video source is specified, set up video playback
+        #[cfg(feature = "video")]
+        if !particles.emitter.video.is_empty() && particles.emitter.video != "webcam" {
+            let video_path = assets_dir().join("videos").join(&particles.emitter.video);
+            if video_path.exists() {
+                if crate::media::video::ffmpeg_available() {
+                    match crate::media::video::probe_video(&video_path) {
+                        Ok(meta) => {
+                            match crate::media::video::decode_all_frames(&video_path, &meta) {
+                                Ok((frames, delays_ms)) => {
+                                    let path_str = video_path.to_string_lossy().to_string();
+                                    ps.set_video_source(queue, frames, delays_ms, path_str);
+                                    log::info!(
+                                        "Particle video source: '{}'",
+                                        particles.emitter.video
+                                    );
+                                }
+                                Err(e) => log::warn!(
+                                    "Failed to decode particle video '{}': {e}",
+                                    particles.emitter.video
+                                ),
+                            }
+                        }
+                        Err(e) => log::warn!(
+                            "Failed to probe particle video '{}': {e}",
+                            particles.emitter.video
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    // Load sprite texture if defined
+    if let Some(ref sprite_def) = particles.sprite {
+        let sprite_path = assets_dir().join("images").join(&sprite_def.texture);
+        match crate::gpu::particle::sprite::SpriteAtlas::load_with_def(
+            device,
+            queue,
+            &sprite_path,
+            sprite_def.cols,
+            sprite_def.rows,
+            sprite_def.animated,
+            sprite_def.frames,
+        ) {
+            Ok(atlas) => {
+                ps.set_sprite(device, atlas);
+                log::info!("Loaded sprite atlas: {}", sprite_def.texture);
+            }
+            Err(e) => {
+                log::warn!("Failed to load sprite '{}': {e}", sprite_def.texture);
+            }
+        }
+    }
+
+    // Set up trail rendering if trail_length specified
+    if particles.trail_length >= 2 {
+        ps.setup_trails(
+            device,
+            hdr_format,
+            particles.trail_length,
+            particles.trail_width,
+        );
+        log::info!(
+            "Trail rendering enabled: {} points, width {}",
+            particles.trail_length,
+            particles.trail_width
+        );
+    }
+
+    if particles.interaction {
+        log::info!("Spatial hash enabled for particle interaction");
+    }
+
+    // Morph target loading
+    if particles.morph {
+        if let Some(ref targets) = particles.morph_targets {
+            let assets = assets_dir();
+            for (slot, target_def) in targets.iter().take(4).enumerate() {
+                match crate::gpu::particle::morph::load_morph_target(
+                    target_def,
+                    particles.max_count,
+                    particles.initial_size,
+                    assets,
+                ) {
+                    Ok(data) => {
+                        log::info!(
+                            "Morph target {}: '{}' ({} particles)",
+                            slot,
+                            target_def.source,
+                            data.len()
+                        );
+                        if let Some(ref mut morph) = ps.morph_state {
+                            morph.load_target(slot as u32, data);
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to load morph target {}: {e}", target_def.source);
+                    }
+                }
+            }
+            ps.upload_morph_targets(device, queue);
+        }
+    }
+
+    ps.track_resources(device, before);
+    Some(ps)
+}
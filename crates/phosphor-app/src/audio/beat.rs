@@ -29,6 +29,15 @@ const AUTO_PRIOR_MAX_BPM: f64 = 200.0;
 const MIN_PRIOR_SIGMA: f64 = 0.05;
 const MAX_PRIOR_SIGMA: f64 = 4.0;
 
+/// Bounds on the detector knobs (#2214). The search window must stay wide enough to hold
+/// a few lags at the estimator's frame rate; the onset and Kalman scales outside these
+/// ranges either fire on everything or never move.
+const MIN_BPM_SPAN: f64 = 10.0;
+const MIN_ONSET_THRESHOLD: f32 = 0.5;
+const MAX_ONSET_THRESHOLD: f32 = 6.0;
+const MIN_KALMAN_AGGRESSIVENESS: f64 = 0.1;
+const MAX_KALMAN_AGGRESSIVENESS: f64 = 10.0;
+
 /// Prior centre in log2 space, clamped to the range the estimator can actually report.
 fn prior_center_log2(bpm: f32) -> f64 {
     (bpm as f64).clamp(BPM_MIN, BPM_MAX).log2()
@@ -37,7 +46,11 @@ fn prior_center_log2(bpm: f32) -> f64 {
 /// User-tunable tempo prior (A7 #1458). The estimator scores metrical-ratio candidates
 /// with a log-Gaussian centred on `prior_center_bpm`, so this is what decides whether a
 /// 172 BPM DnB track reads as 172 or folds to 86.
+///
+/// `#[serde(default)]` so settings saved before the detector knobs (#2214) existed load
+/// with today's values for them.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TempoConfig {
     /// Centre of the log-Gaussian tempo prior, in BPM.
     pub prior_center_bpm: f32,
@@ -47,6 +60,16 @@ pub struct TempoConfig {
     /// actually locking onto. The audio thread publishes the adapted value back into the
     /// shared config, so the UI reads it live and it freezes in place when auto is off.
     pub auto_prior: bool,
+    /// Onset threshold, in median absolute deviations of the flux above its median
+    /// (#2214). Lower catches softer hits; higher ignores busy hats and ghost notes.
+    pub onset_threshold: f32,
+    /// Window the estimator searches, in BPM (#2214). Readings outside it are dropped,
+    /// and octave shifts or taps that would leave it are rejected.
+    pub bpm_min: f32,
+    pub bpm_max: f32,
+    /// Scale on the Kalman filter's process noise (#2214): above 1 follows a tempo change
+    /// sooner, below 1 holds a steadier reading through fills and breakdowns.
+    pub kalman_aggressiveness: f32,
 }
 
 impl Default for TempoConfig {
@@ -57,16 +80,42 @@ impl Default for TempoConfig {
             prior_center_bpm: 150.0,
             prior_sigma: 1.0,
             auto_prior: false,
+            onset_threshold: 2.0,
+            bpm_min: BPM_MIN as f32,
+            bpm_max: BPM_MAX as f32,
+            kalman_aggressiveness: 1.0,
         }
     }
 }
 
-/// Genre presets for the tempo prior (A7 #1458).
+impl TempoConfig {
+    /// The search window, clamped to what the estimator can report and kept at least
+    /// `MIN_BPM_SPAN` wide — `settings.json` is hand-editable.
+    fn bpm_range(&self) -> (f32, f32) {
+        let lo = (self.bpm_min as f64).clamp(BPM_MIN, BPM_MAX - MIN_BPM_SPAN);
+        let hi = (self.bpm_max as f64).clamp(lo + MIN_BPM_SPAN, BPM_MAX);
+        (lo as f32, hi as f32)
+    }
+
+    fn onset_threshold(&self) -> f32 {
+        self.onset_threshold
+            .clamp(MIN_ONSET_THRESHOLD, MAX_ONSET_THRESHOLD)
+    }
+
+    fn kalman_aggressiveness(&self) -> f64 {
+        (self.kalman_aggressiveness as f64)
+            .clamp(MIN_KALMAN_AGGRESSIVENESS, MAX_KALMAN_AGGRESSIVENESS)
+    }
+}
+
+/// Genre presets for the tempo prior (A7 #1458) and, since #2214, the rest of the
+/// detector: a preset with a narrow BPM window can't fold a track out of its genre.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TempoPreset {
     Neutral,
     Wide,
     House,
+    Techno,
     DrumAndBass,
     HipHop,
     Ambient,
@@ -77,20 +126,59 @@ impl TempoPreset {
         TempoPreset::Neutral,
         TempoPreset::Wide,
         TempoPreset::House,
+        TempoPreset::Techno,
         TempoPreset::DrumAndBass,
         TempoPreset::HipHop,
         TempoPreset::Ambient,
     ];
 
-    /// (centre BPM, sigma in octaves).
-    pub fn values(self) -> (f32, f32) {
+    /// The preset's detector settings. `auto_prior` is left off; it is the user's call,
+    /// not the genre's.
+    pub fn config(self) -> TempoConfig {
+        let base = TempoConfig::default();
         match self {
-            Self::Neutral => (150.0, 1.0),
-            Self::Wide => (140.0, 1.2),
-            Self::House => (127.0, 0.35),
-            Self::DrumAndBass => (172.0, 0.3),
-            Self::HipHop => (90.0, 0.4),
-            Self::Ambient => (70.0, 0.6),
+            Self::Neutral => base,
+            Self::Wide => TempoConfig {
+                prior_center_bpm: 140.0,
+                prior_sigma: 1.2,
+                ..base
+            },
+            Self::House => TempoConfig {
+                prior_center_bpm: 127.0,
+                prior_sigma: 0.35,
+                ..base
+            },
+            // Four-on-the-floor barely moves: a calm filter rides out breakdowns.
+            Self::Techno => TempoConfig {
+                prior_center_bpm: 130.0,
+                prior_sigma: 0.3,
+                bpm_min: 120.0,
+                bpm_max: 140.0,
+                kalman_aggressiveness: 0.5,
+                ..base
+            },
+            // Breakbeats are dense; a higher threshold keeps ghost notes out of the onsets.
+            Self::DrumAndBass => TempoConfig {
+                prior_center_bpm: 172.0,
+                prior_sigma: 0.3,
+                bpm_min: 160.0,
+                bpm_max: 180.0,
+                onset_threshold: 2.5,
+                ..base
+            },
+            Self::HipHop => TempoConfig {
+                prior_center_bpm: 90.0,
+                prior_sigma: 0.4,
+                ..base
+            },
+            // Soft attacks need a lower threshold, and what pulse there is drifts slowly.
+            Self::Ambient => TempoConfig {
+                prior_center_bpm: 70.0,
+                prior_sigma: 0.6,
+                onset_threshold: 1.5,
+                kalman_aggressiveness: 0.5,
+                ..base
+            },
         }
     }
 
@@ -99,7 +187,8 @@ impl TempoPreset {
             Self::Neutral => "Neutral \u{00b7} 150",
             Self::Wide => "Wide \u{00b7} 140",
             Self::House => "House \u{00b7} 127",
-            Self::DrumAndBass => "Drum & Bass \u{00b7} 172",
+            Self::Techno => "Techno \u{00b7} 120\u{2013}140",
+            Self::DrumAndBass => "Drum & Bass \u{00b7} 160\u{2013}180",
             Self::HipHop => "Hip-hop \u{00b7} 90",
             Self::Ambient => "Ambient \u{00b7} 70",
         }
@@ -110,8 +199,11 @@ impl TempoPreset {
     /// drift out of sync with the values it names.
     pub fn from_config(cfg: &TempoConfig) -> Option<TempoPreset> {
         Self::ALL.iter().copied().find(|p| {
-            let (c, s) = p.values();
-            cfg.prior_center_bpm == c && cfg.prior_sigma == s
+            let preset = TempoConfig {
+                auto_prior: cfg.auto_prior,
+                ..p.config()
+            };
+            preset == *cfg
         })
     }
 }
@@ -276,7 +368,8 @@ impl CircularBuffer {
 /// registering flux, which suppresses the phantom onsets that plain flux fires on vibrato
 /// and pitch slides. The bands are contiguous and log-spaced, so they cover 250–500 Hz —
 /// the snare/tom/male-vocal gap the old four-band detector left open.
-const N_ONSET_BANDS: usize = 64;
+/// Log-spaced onset bands; also the width of a recorded analysis snapshot (#2215).
+pub const N_ONSET_BANDS: usize = 64;
 const ONSET_F_MIN: f32 = 20.0;
 const ONSET_F_MAX: f32 = 16000.0;
 /// Frequency max-filter half-width, in bands, on the reference frame.
@@ -311,13 +404,31 @@ struct OnsetDetector {
 
 impl OnsetDetector {
     fn new(sample_rate: f32, history_size: usize, long_term_size: usize) -> Self {
+        // Each band's partition is decided by its geometric-centre frequency, which doesn't
+        // depend on the FFT size — so it is fixed here, and a replay that never sees a
+        // spectrum (#2215) partitions exactly like the live detector.
+        let mut band_partition = Vec::with_capacity(N_ONSET_BANDS);
+        let mut partition_counts = [0; 3];
+        for b in 0..N_ONSET_BANDS {
+            let (f_lo, f_hi) = onset_band_edges(b);
+            let centre = (f_lo * f_hi).sqrt();
+            let part = if centre < ONSET_LOW_HZ {
+                0u8
+            } else if centre < ONSET_HIGH_HZ {
+                1u8
+            } else {
+                2u8
+            };
+            band_partition.push(part);
+            partition_counts[part as usize] += 1;
+        }
         Self {
             sample_rate,
             threshold_mult: 2.0,
             threshold_ceiling: 0.5,
             band_bins: Vec::new(),
-            band_partition: Vec::new(),
-            partition_counts: [0; 3],
+            band_partition,
+            partition_counts,
             prev_log: Vec::new(),
             onset_history: CircularBuffer::new(history_size),
             long_term_history: CircularBuffer::new(long_term_size),
@@ -327,33 +438,19 @@ impl OnsetDetector {
 
     /// Build the log-spaced filterbank the first time we see the spectrum length. Each band
     /// spans at least one FFT bin (adjacent low bands may overlap a shared bin, which is
-    /// harmless); its partition is decided by the band's geometric-centre frequency.
+    /// harmless).
     fn ensure_bands(&mut self, num_bins: usize) {
         if self.band_bins.len() == N_ONSET_BANDS {
             return;
         }
         let bin_hz = self.sample_rate / ((num_bins - 1) * 2) as f32;
-        let ratio = (ONSET_F_MAX / ONSET_F_MIN).powf(1.0 / N_ONSET_BANDS as f32);
         self.band_bins.clear();
-        self.band_partition.clear();
-        self.partition_counts = [0; 3];
         for b in 0..N_ONSET_BANDS {
-            let f_lo = ONSET_F_MIN * ratio.powi(b as i32);
-            let f_hi = ONSET_F_MIN * ratio.powi(b as i32 + 1);
+            let (f_lo, f_hi) = onset_band_edges(b);
             let lo = (f_lo / bin_hz).floor() as usize;
             let hi = ((f_hi / bin_hz).ceil() as usize).max(lo + 1).min(num_bins);
             self.band_bins
                 .push((lo.min(num_bins.saturating_sub(1)), hi));
-            let centre = (f_lo * f_hi).sqrt();
-            let part = if centre < ONSET_LOW_HZ {
-                0u8
-            } else if centre < ONSET_HIGH_HZ {
-                1u8
-            } else {
-                2u8
-            };
-            self.band_partition.push(part);
-            self.partition_counts[part as usize] += 1;
         }
         self.prev_log.clear();
     }
@@ -389,7 +486,27 @@ impl OnsetDetector {
             }
             cur[b] = (e + 1e-10).ln();
         }
+        self.superflux(cur)
+    }
+
+    /// [`Self::process`] from per-band log magnitudes recorded earlier (#2215), in place of
+    /// the spectrum they were computed from.
+    fn process_log_bands(&mut self, log_bands: &[f32], loud_silent: bool) -> (bool, f32, f64) {
+        if loud_silent {
+            self.silent_frames += 1;
+            return (false, 0.0, 0.0);
+        }
+        self.silent_frames = 0;
+        let mut cur = vec![0.0f64; N_ONSET_BANDS];
+        for (c, &v) in cur.iter_mut().zip(log_bands) {
+            *c = v as f64;
+        }
+        self.superflux(cur)
+    }
 
+    /// The onset stage past the filterbank: flux of `cur` against the previous frame, the
+    /// adaptive threshold, and the normalized strength.
+    fn superflux(&mut self, cur: Vec<f64>) -> (bool, f32, f64) {
         // SuperFlux: half-wave-rectified difference against the frequency-max-filtered
         // reference frame, accumulated per partition.
         let mut part = [0.0f64; 3];
@@ -454,6 +571,15 @@ impl OnsetDetector {
     }
 }
 
+/// Lower and upper edge of onset band `b`, in Hz.
+fn onset_band_edges(b: usize) -> (f32, f32) {
+    let ratio = (ONSET_F_MAX / ONSET_F_MIN).powf(1.0 / N_ONSET_BANDS as f32);
+    (
+        ONSET_F_MIN * ratio.powi(b as i32),
+        ONSET_F_MIN * ratio.powi(b as i32 + 1),
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Kalman filter for BPM tracking in log2-BPM space
 // ---------------------------------------------------------------------------
@@ -466,6 +592,8 @@ struct KalmanBpm {
     diverge_count: u32, // consecutive divergent frames
     snap_count: u32,    // consecutive octave-snapped frames
     initialized: bool,
+    /// Scale on `q` (#2214); see [`TempoConfig::kalman_aggressiveness`].
+    aggressiveness: f64,
 }
 
 impl KalmanBpm {
@@ -478,6 +606,7 @@ impl KalmanBpm {
             diverge_count: 0,
             snap_count: 0,
             initialized: false,
+            aggressiveness: 1.0,
         }
     }
 
@@ -579,7 +708,8 @@ impl KalmanBpm {
 
         // Adaptive noise: R = f(confidence), Q = f(stability)
         self.r = 0.01 + (1.0 - confidence) * 0.5;
-        self.q = if self.diverge_count > 0 { 0.1 } else { 0.001 };
+        let q = if self.diverge_count > 0 { 0.1 } else { 0.001 };
+        self.q = q * self.aggressiveness;
 
         // Kalman predict (constant model: state unchanged)
         self.variance += self.q;
@@ -642,7 +772,7 @@ impl TempoEstimator {
         let fft_inverse = planner.plan_fft_inverse(fft_size);
 
         Self {
-            bpm_range: (BPM_MIN as f32, BPM_MAX as f32),
+            bpm_range: config.bpm_range(),
             onset_history: CircularBuffer::new(history_size),
             frame_rate,
             frame_time,
@@ -657,7 +787,10 @@ impl TempoEstimator {
             prior_sigma: (config.prior_sigma as f64).clamp(MIN_PRIOR_SIGMA, MAX_PRIOR_SIGMA),
             auto_prior: config.auto_prior,
             octave_offset: 0,
-            kalman: KalmanBpm::new(),
+            kalman: KalmanBpm {
+                aggressiveness: config.kalman_aggressiveness(),
+                ..KalmanBpm::new()
+            },
         }
     }
 
@@ -670,6 +803,8 @@ impl TempoEstimator {
         // `settings.json` is hand-editable, and a centre of 0 would make every candidate weight
         // exp(-inf) = 0 — the prior would silently stop discriminating instead of failing loudly.
         self.prior_sigma = (config.prior_sigma as f64).clamp(MIN_PRIOR_SIGMA, MAX_PRIOR_SIGMA);
+        self.bpm_range = config.bpm_range();
+        self.kalman.aggressiveness = config.kalman_aggressiveness();
         if !config.auto_prior {
             self.prior_center_log2 = prior_center_log2(config.prior_center_bpm);
         }
@@ -1237,6 +1372,7 @@ impl BeatScheduler {
 // ---------------------------------------------------------------------------
 
 /// Result from beat detection for one frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct BeatResult {
     pub onset_strength: f32,
     pub beat: f32,
@@ -1271,8 +1407,11 @@ impl BeatDetector {
         let history_size = (0.5 * frame_rate) as usize; // ~0.5s
         let long_term_size = (4.0 * frame_rate) as usize; // ~4s
 
+        let mut onset_detector = OnsetDetector::new(sample_rate, history_size, long_term_size);
+        onset_detector.threshold_mult = tempo.onset_threshold();
+
         Self {
-            onset_detector: OnsetDetector::new(sample_rate, history_size, long_term_size),
+            onset_detector,
             tempo_estimator: TempoEstimator::new(8.0, frame_rate, tempo),
             beat_scheduler: BeatScheduler::new(),
             held_onset: 0.0,
@@ -1285,6 +1424,7 @@ impl BeatDetector {
 
     /// Apply live tempo config (A7 #1458), snapshotted from the shared `TempoControl`.
     pub fn set_tempo_config(&mut self, config: TempoConfig) {
+        self.onset_detector.threshold_mult = config.onset_threshold();
         self.tempo_estimator.set_config(config);
     }
 
@@ -1318,6 +1458,41 @@ impl BeatDetector {
         high_spectrum: &[f32],
         timestamp: f64,
         loud_silent: bool,
+    ) -> BeatResult {
+        // Stage 1: Onset detection
+        let onset =
+            self.onset_detector
+                .process(bass_spectrum, mid_spectrum, high_spectrum, loud_silent);
+        self.track(onset, timestamp, loud_silent)
+    }
+
+    /// Replay one frame from an analysis snapshot (#2215): `log_bands` are the per-band log
+    /// magnitudes [`Self::onset_log_bands`] reported live, which is everything the pipeline
+    /// reads from the spectrum.
+    pub fn process_log_bands(
+        &mut self,
+        log_bands: &[f32],
+        timestamp: f64,
+        loud_silent: bool,
+    ) -> BeatResult {
+        let onset = self
+            .onset_detector
+            .process_log_bands(log_bands, loud_silent);
+        self.track(onset, timestamp, loud_silent)
+    }
+
+    /// The last frame's per-band log magnitudes, for an analysis snapshot (#2215). Stale
+    /// (the last non-silent frame's) while `loud_silent` holds; empty before the first.
+    pub fn onset_log_bands(&self) -> &[f64] {
+        &self.onset_detector.prev_log
+    }
+
+    /// Stages 2 and 3, given the onset stage's `(is_onset, strength, flux)`.
+    fn track(
+        &mut self,
+        (is_onset, onset_strength, combined_flux): (bool, f32, f64),
+        timestamp: f64,
+        loud_silent: bool,
     ) -> BeatResult {
         let dt = if self.last_timestamp > 0.0 {
             (timestamp - self.last_timestamp).max(0.0)
@@ -1326,11 +1501,6 @@ impl BeatDetector {
         };
         self.last_timestamp = timestamp;
 
-        // Stage 1: Onset detection
-        let (is_onset, onset_strength, combined_flux) =
-            self.onset_detector
-                .process(bass_spectrum, mid_spectrum, high_spectrum, loud_silent);
-
         // Apply onset cooldown
         let mut onset_gated = is_onset;
         if is_onset && (timestamp - self.last_onset_time) < self.onset_cooldown {
@@ -1765,18 +1935,23 @@ mod tests {
         assert_eq!(c.prior_center_bpm, 150.0);
         assert_eq!(c.prior_sigma, 1.0);
         assert!(!c.auto_prior);
+        // #2214 knobs: the old hardcoded threshold, full range and unscaled filter.
+        assert_eq!(c.onset_threshold, 2.0);
+        assert_eq!(c.bpm_range(), (BPM_MIN as f32, BPM_MAX as f32));
+        assert_eq!(c.kalman_aggressiveness, 1.0);
     }
 
     #[test]
     fn tempo_preset_round_trips_through_config() {
         for &p in TempoPreset::ALL {
-            let (center, sigma) = p.values();
-            let cfg = TempoConfig {
-                prior_center_bpm: center,
-                prior_sigma: sigma,
-                auto_prior: false,
-            };
+            let cfg = p.config();
             assert_eq!(TempoPreset::from_config(&cfg), Some(p));
+            // Auto mode is orthogonal to the preset.
+            let auto = TempoConfig {
+                auto_prior: true,
+                ..cfg
+            };
+            assert_eq!(TempoPreset::from_config(&auto), Some(p));
         }
     }
 
@@ -1785,7 +1960,13 @@ mod tests {
         let cfg = TempoConfig {
             prior_center_bpm: 133.0,
             prior_sigma: 0.5,
-            auto_prior: false,
+            ..TempoConfig::default()
+        };
+        assert_eq!(TempoPreset::from_config(&cfg), None);
+        // A preset with one detector knob moved is hand-tuned too.
+        let cfg = TempoConfig {
+            bpm_max: 150.0,
+            ..TempoPreset::Techno.config()
         };
         assert_eq!(TempoPreset::from_config(&cfg), None);
     }
@@ -1807,14 +1988,15 @@ mod tests {
             "default prior should hold 172, got {default_bpm}"
         );
 
-        let (center, sigma) = TempoPreset::Ambient.values();
+        let ambient = TempoPreset::Ambient.config();
+        let center = ambient.prior_center_bpm;
         let low_bpm = run_bpm_convergence_with(
             172.0,
             10.0,
             TempoConfig {
                 prior_center_bpm: center,
-                prior_sigma: sigma,
-                auto_prior: false,
+                prior_sigma: ambient.prior_sigma,
+                ..TempoConfig::default()
             },
         );
         assert!(
@@ -1945,6 +2127,7 @@ mod tests {
                 prior_center_bpm: 150.0,
                 prior_sigma: 0.4,
                 auto_prior: true,
+                ..TempoConfig::default()
             },
         );
         let start = est.prior_center_bpm();
@@ -1979,8 +2162,18 @@ mod tests {
         est.set_config(TempoConfig {
             prior_center_bpm: 0.0,
             prior_sigma: 0.0,
-            auto_prior: false,
+            bpm_min: 500.0,
+            bpm_max: 0.0,
+            kalman_aggressiveness: 0.0,
+            ..TempoConfig::default()
         });
+        let (lo, hi) = est.bpm_range;
+        assert!(lo >= BPM_MIN as f32 && hi <= BPM_MAX as f32);
+        assert!(
+            hi - lo >= MIN_BPM_SPAN as f32,
+            "window collapsed: {lo}..{hi}"
+        );
+        assert!(est.kalman.aggressiveness >= MIN_KALMAN_AGGRESSIVENESS);
         assert!(
             est.prior_center_bpm().is_finite() && est.prior_center_bpm() >= BPM_MIN as f32,
             "centre must stay finite and in range, got {}",
@@ -1999,8 +2192,77 @@ mod tests {
             prior_center_bpm: 70.0,
             prior_sigma: 0.5,
             auto_prior: true,
+            ..TempoConfig::default()
         });
         assert_eq!(est.prior_center_bpm(), 150.0);
         assert_eq!(est.prior_sigma, 0.5, "sigma must still track the config");
     }
+
+    #[test]
+    fn detector_knobs_reach_every_stage() {
+        let cfg = TempoPreset::DrumAndBass.config();
+        let mut det = BeatDetector::new(44100.0, cfg);
+        assert_eq!(det.onset_detector.threshold_mult, 2.5);
+        assert_eq!(det.tempo_estimator.bpm_range, (160.0, 180.0));
+
+        det.set_tempo_config(TempoPreset::Techno.config());
+        assert_eq!(det.onset_detector.threshold_mult, 2.0);
+        assert_eq!(det.tempo_estimator.bpm_range, (120.0, 140.0));
+        assert_eq!(det.tempo_estimator.kalman.aggressiveness, 0.5);
+    }
+
+    #[test]
+    fn aggressive_kalman_follows_a_tempo_change_sooner() {
+        let track = |aggressiveness: f64| {
+            let mut k = KalmanBpm {
+                aggressiveness,
+                ..KalmanBpm::new()
+            };
+            for _ in 0..60 {
+                k.update(120.0, 0.8);
+            }
+            // A 5% nudge: under the divergence threshold, so only the gain decides.
+            let mut bpm = 0.0;
+            for _ in 0..3 {
+                bpm = k.update(126.0, 0.8);
+            }
+            bpm
+        };
+        let calm = track(0.2);
+        let eager = track(5.0);
+        assert!(calm < eager, "calm {calm} should lag eager {eager}");
+        assert!(eager > 122.0, "eager filter barely moved: {eager}");
+    }
+
+    #[test]
+    fn log_band_replay_matches_the_live_detector() {
+        // What an analysis snapshot (#2215) records is enough to re-run the pipeline: feeding
+        // the recorded bands back must land the same beats as the spectrum did.
+        let sample_rate = 44100.0;
+        let mut live = BeatDetector::new(sample_rate, TempoConfig::default());
+        let mut replay = BeatDetector::new(sample_rate, TempoConfig::default());
+        let dt = crate::audio::ANALYSIS_HOP as f64 / sample_rate as f64;
+        let (mid, high) = (vec![0.001f32; 513], vec![0.001f32; 257]);
+        let mut last = BeatResult::default();
+        let (mut live_beats, mut replay_beats) = (0, 0);
+        for frame in 0..1200 {
+            let t = frame as f64 * dt;
+            let mut bass = vec![0.001f32; 2049];
+            if frame % 43 == 0 {
+                bass[1..12].fill(2.0);
+            }
+            // A stretch of silence, which the replay must gate the same way.
+            let silent = (500..560).contains(&frame);
+            let a = live.process(&bass, &mid, &high, t, silent);
+            let bands: Vec<f32> = live.onset_log_bands().iter().map(|&v| v as f32).collect();
+            let b = replay.process_log_bands(&bands, t, silent);
+            live_beats += usize::from(a.beat > 0.5);
+            replay_beats += usize::from(b.beat > 0.5);
+            assert_eq!(a.beat, b.beat, "beat differs at frame {frame}");
+            last = b;
+        }
+        assert!(live_beats > 20);
+        assert_eq!(live_beats, replay_beats);
+        assert!(last.bpm > 100.0, "replay lost the tempo: {}", last.bpm);
+    }
 }
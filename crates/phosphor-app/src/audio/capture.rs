@@ -5,6 +5,8 @@ use anyhow::Result;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Sample, SampleFormat, Stream};
 
+use super::channels::{ChannelMixer, InputChannels, MAX_CHANNELS};
+
 /// Suppress noisy ALSA/JACK stderr messages during device enumeration.
 /// ALSA/JACK C libraries print errors for missing JACK server, OSS devices, etc.
 #[cfg(target_os = "linux")]
@@ -206,6 +208,10 @@ pub struct AudioCapture {
     /// A9 (#1460): set by cpal's error callback when the device goes away. See
     /// [`err_callback`].
     pub capture_failed: Arc<AtomicBool>,
+    /// Channels the stream was opened with, and the most the device offers at
+    /// this rate (#2216).
+    pub channels: usize,
+    pub max_channels: usize,
 }
 
 /// A9 (#1460): build cpal's stream error callback, publishing device loss to the watchdog.
@@ -231,10 +237,13 @@ fn err_callback(capture_failed: Arc<AtomicBool>) -> impl FnMut(cpal::StreamError
 impl AudioCapture {
     #[allow(dead_code)]
     pub fn new() -> Result<Self> {
-        Self::new_with_device(None)
+        Self::new_with_device(None, &Arc::new(InputChannels::default()))
     }
 
-    pub fn new_with_device(device_name: Option<&str>) -> Result<Self> {
+    pub fn new_with_device(
+        device_name: Option<&str>,
+        input_channels: &Arc<InputChannels>,
+    ) -> Result<Self> {
         let host = cpal::default_host();
         let device = if let Some(name) = device_name {
             // Try to find device by name
@@ -265,8 +274,41 @@ impl AudioCapture {
             .unwrap_or_else(|_| "Unknown".into());
         log::info!("Audio capture device: {device_name}");
 
-        let config = device.default_input_config()?;
+        let mut config = device.default_input_config()?;
         let sample_rate = config.sample_rate();
+        let format = config.sample_format();
+        // #2216: the default config is often just the first pair; open wider when the
+        // routing reaches further, at the same rate and format.
+        let supported: Vec<_> = device
+            .supported_input_configs()
+            .map(|c| c.collect())
+            .unwrap_or_default();
+        let at_rate = |c: &cpal::SupportedStreamConfigRange| {
+            c.sample_format() == format
+                && (c.min_sample_rate()..=c.max_sample_rate()).contains(&sample_rate)
+        };
+        let max_channels = supported
+            .iter()
+            .filter(|c| at_rate(c))
+            .map(|c| c.channels() as usize)
+            .max()
+            .unwrap_or(0)
+            .max(config.channels() as usize)
+            .min(MAX_CHANNELS);
+        let needed = input_channels.map_for(&device_name).channels_needed();
+        if needed > config.channels() as usize {
+            match supported
+                .iter()
+                .filter(|c| at_rate(c) && c.channels() as usize >= needed)
+                .min_by_key(|c| c.channels())
+            {
+                Some(wider) => config = wider.clone().with_sample_rate(sample_rate),
+                None => log::warn!(
+                    "Audio device offers no {needed}-channel config; routing is limited to {}",
+                    config.channels()
+                ),
+            }
+        }
         let channels = config.channels() as usize;
         log::info!(
             "Audio config: {sample_rate}Hz, {channels}ch, {:?}",
@@ -279,15 +321,18 @@ impl AudioCapture {
 
         let sample_format = config.sample_format();
         let stream_config: cpal::StreamConfig = config.into();
+        let mixer = || ChannelMixer::new(input_channels.clone(), &device_name, channels);
 
         let stream = match sample_format {
             SampleFormat::I16 => {
                 let ring_clone = ring.clone();
                 let cb_clone = callback_count.clone();
+                let mut mixer = mixer();
+                let mut scratch = Scratch::new(channels);
                 device.build_input_stream(
                     &stream_config,
                     move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                        push_samples(&ring_clone, &cb_clone, data, channels);
+                        push_samples(&ring_clone, &cb_clone, data, &mut mixer, &mut scratch);
                     },
                     err_callback(capture_failed.clone()),
                     None,
@@ -296,10 +341,12 @@ impl AudioCapture {
             SampleFormat::I32 => {
                 let ring_clone = ring.clone();
                 let cb_clone = callback_count.clone();
+                let mut mixer = mixer();
+                let mut scratch = Scratch::new(channels);
                 device.build_input_stream(
                     &stream_config,
                     move |data: &[i32], _: &cpal::InputCallbackInfo| {
-                        push_samples(&ring_clone, &cb_clone, data, channels);
+                        push_samples(&ring_clone, &cb_clone, data, &mut mixer, &mut scratch);
                     },
                     err_callback(capture_failed.clone()),
                     None,
@@ -308,10 +355,12 @@ impl AudioCapture {
             _ => {
                 let ring_clone = ring.clone();
                 let cb_clone = callback_count.clone();
+                let mut mixer = mixer();
+                let mut scratch = Scratch::new(channels);
                 device.build_input_stream(
                     &stream_config,
                     move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                        push_samples(&ring_clone, &cb_clone, data, channels);
+                        push_samples(&ring_clone, &cb_clone, data, &mut mixer, &mut scratch);
                     },
                     err_callback(capture_failed.clone()),
                     None,
@@ -329,6 +378,8 @@ impl AudioCapture {
             device_name,
             callback_count,
             capture_failed,
+            channels,
+            max_channels,
         })
     }
 
@@ -354,15 +405,17 @@ impl AudioCapture {
 
 /// Convert any cpal sample type to f32 and push interleaved `L,R` stereo to the ring buffer.
 ///
-/// A13 (#1464): the ring carries native stereo (front L/R pair) so the analysis thread can measure
-/// pan/width/correlation; it derives the mono mix downstream. For >2 channels we take channels 0/1
-/// as L/R; a mono source is duplicated to both. The push is always even-length, which the ring's
-/// L/R parity invariant relies on (see [`RingBuffer::read`]).
+/// A13 (#1464): the ring carries native stereo so the analysis thread can measure
+/// pan/width/correlation; it derives the mono mix downstream. Which device channels make up
+/// that pair is the [`ChannelMixer`]'s call (#2216) — by default channels 0/1 as L/R, a mono
+/// source duplicated to both. The push is always even-length, which the ring's L/R parity
+/// invariant relies on (see [`RingBuffer::read`]).
 fn push_samples<T: Sample>(
     ring: &RingBuffer,
     callback_count: &AtomicU64,
     data: &[T],
-    channels: usize,
+    mixer: &mut ChannelMixer,
+    scratch: &mut Scratch,
 ) where
     f32: cpal::FromSample<T>,
 {
@@ -370,21 +423,34 @@ fn push_samples<T: Sample>(
     if count == 0 {
         log::info!("Audio callback fired (first data: {} samples)", data.len());
     }
-    let conv = |s: T| <f32 as cpal::FromSample<T>>::from_sample_(s);
-    let mut stereo: Vec<f32> = Vec::with_capacity(data.len() / channels.max(1) * 2);
-    if channels == 1 {
-        for &s in data {
-            let v = conv(s);
-            stereo.push(v);
-            stereo.push(v);
-        }
-    } else {
-        for frame in data.chunks(channels) {
-            stereo.push(conv(frame[0]));
-            stereo.push(conv(frame[1]));
+    scratch.samples.clear();
+    scratch.samples.extend(
+        data.iter()
+            .map(|&s| <f32 as cpal::FromSample<T>>::from_sample_(s)),
+    );
+    scratch.stereo.clear();
+    mixer.mix(&scratch.samples, &mut scratch.stereo);
+    ring.push(&scratch.stereo);
+}
+
+/// Callback frames [`Scratch`] is sized for up front. Drivers rarely deliver
+/// more; if one does, the buffers grow once and keep that size.
+const SCRATCH_FRAMES: usize = 4096;
+
+/// Conversion and mix buffers owned by one stream's callback, reused every
+/// call so the audio thread doesn't allocate.
+struct Scratch {
+    samples: Vec<f32>,
+    stereo: Vec<f32>,
+}
+
+impl Scratch {
+    fn new(channels: usize) -> Self {
+        Self {
+            samples: Vec::with_capacity(SCRATCH_FRAMES * channels.max(1)),
+            stereo: Vec::with_capacity(SCRATCH_FRAMES * 2),
         }
     }
-    ring.push(&stereo);
 }
 
 #[cfg(test)]
@@ -0,0 +1,340 @@
+//! Input channel routing (#2216): which channels of a multi-channel device feed
+//! analysis, and how they fold down to the stereo pair the pipeline works on.
+//! An audio interface or Dante card may carry the booth feed on 3-4 and a
+//! room mic on 7; the default (the first two channels) is rarely right there.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// Highest channel a route can name, to keep a typo from asking a device
+/// for thousands of channels.
+pub const MAX_CHANNELS: usize = 64;
+
+/// Which side of the analysed stereo pair a channel feeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ChannelSide {
+    /// Both sides, as a mono source would.
+    #[default]
+    Both,
+    Left,
+    Right,
+}
+
+impl ChannelSide {
+    pub const ALL: &[ChannelSide] = &[ChannelSide::Both, ChannelSide::Left, ChannelSide::Right];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            ChannelSide::Both => "L+R",
+            ChannelSide::Left => "L",
+            ChannelSide::Right => "R",
+        }
+    }
+}
+
+/// One device channel feeding analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChannelRoute {
+    /// 0-based; the UI shows it 1-based, as interfaces label their inputs.
+    pub channel: u16,
+    pub side: ChannelSide,
+    pub gain_db: f32,
+}
+
+impl Default for ChannelRoute {
+    fn default() -> Self {
+        Self {
+            channel: 0,
+            side: ChannelSide::Both,
+            gain_db: 0.0,
+        }
+    }
+}
+
+/// The routing for one device. Empty is the default: the first two channels
+/// as left and right, or a mono device on both.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChannelMap {
+    pub routes: Vec<ChannelRoute>,
+}
+
+impl ChannelMap {
+    pub fn is_default(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// How many channels the device must be opened with to feed every route.
+    pub fn channels_needed(&self) -> usize {
+        self.routes
+            .iter()
+            .map(|r| usize::from(r.channel) + 1)
+            .max()
+            .unwrap_or(0)
+            .min(MAX_CHANNELS)
+    }
+
+    /// Per-channel weights into left and right for a stream of `channels`.
+    /// Channels feeding the same side are summed; a side nothing feeds
+    /// mirrors the other, so a single routed channel still reads as centred.
+    fn taps(&self, channels: usize) -> Vec<Tap> {
+        if self.is_default() {
+            return match channels {
+                0 => Vec::new(),
+                1 => vec![Tap::new(0, 1.0, 1.0)],
+                _ => vec![Tap::new(0, 1.0, 0.0), Tap::new(1, 0.0, 1.0)],
+            };
+        }
+        let mut taps: Vec<Tap> = Vec::new();
+        for route in &self.routes {
+            let channel = usize::from(route.channel);
+            if channel >= channels {
+                continue;
+            }
+            let gain = 10f32.powf(route.gain_db / 20.0);
+            let (left, right) = match route.side {
+                ChannelSide::Both => (gain, gain),
+                ChannelSide::Left => (gain, 0.0),
+                ChannelSide::Right => (0.0, gain),
+            };
+            match taps.iter_mut().find(|t| t.channel == channel) {
+                Some(tap) => {
+                    tap.left += left;
+                    tap.right += right;
+                }
+                None => taps.push(Tap::new(channel, left, right)),
+            }
+        }
+        let fed_left = taps.iter().any(|t| t.left != 0.0);
+        let fed_right = taps.iter().any(|t| t.right != 0.0);
+        for tap in &mut taps {
+            if !fed_left {
+                tap.left = tap.right;
+            }
+            if !fed_right {
+                tap.right = tap.left;
+            }
+        }
+        taps
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Tap {
+    channel: usize,
+    left: f32,
+    right: f32,
+}
+
+impl Tap {
+    fn new(channel: usize, left: f32, right: f32) -> Self {
+        Self {
+            channel,
+            left,
+            right,
+        }
+    }
+}
+
+/// Routing for every device, by name; shared between the UI and the capture
+/// callbacks. Saved with the settings.
+#[derive(Debug, Default)]
+pub struct InputChannels {
+    maps: Mutex<BTreeMap<String, ChannelMap>>,
+    /// Bumped on every change so a callback only takes the lock when it has to.
+    generation: AtomicU64,
+}
+
+impl InputChannels {
+    pub fn new(maps: BTreeMap<String, ChannelMap>) -> Self {
+        Self {
+            maps: Mutex::new(maps),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    pub fn map_for(&self, device: &str) -> ChannelMap {
+        self.lock().get(device).cloned().unwrap_or_default()
+    }
+
+    /// Replace a device's routing; the default one is not stored.
+    pub fn set_map(&self, device: &str, map: ChannelMap) {
+        {
+            let mut maps = self.lock();
+            if map.is_default() {
+                maps.remove(device);
+            } else {
+                maps.insert(device.to_string(), map);
+            }
+        }
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    /// Replace every device's routing, as from a reloaded settings file.
+    pub fn set_maps(&self, maps: BTreeMap<String, ChannelMap>) {
+        *self.lock() = maps;
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    /// Every stored routing, for the settings file.
+    pub fn maps(&self) -> BTreeMap<String, ChannelMap> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, ChannelMap>> {
+        self.maps.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Folds interleaved device frames down to `L,R` pairs for one open stream.
+/// Lives in the capture callback: it re-reads the routing only after a
+/// change, and then with `try_lock`, so the audio callback never waits on
+/// the UI.
+pub struct ChannelMixer {
+    shared: std::sync::Arc<InputChannels>,
+    device: String,
+    channels: usize,
+    generation: u64,
+    taps: Vec<Tap>,
+}
+
+impl ChannelMixer {
+    pub fn new(shared: std::sync::Arc<InputChannels>, device: &str, channels: usize) -> Self {
+        let generation = shared.generation.load(Ordering::Acquire);
+        let taps = shared.map_for(device).taps(channels);
+        Self {
+            shared,
+            device: device.to_string(),
+            channels,
+            generation,
+            taps,
+        }
+    }
+
+    /// Append one `L,R` pair per whole frame of `data`.
+    pub fn mix(&mut self, data: &[f32], out: &mut Vec<f32>) {
+        self.refresh();
+        if self.channels == 0 {
+            return;
+        }
+        for frame in data.chunks_exact(self.channels) {
+            let (mut l, mut r) = (0.0, 0.0);
+            for tap in &self.taps {
+                let s = frame[tap.channel];
+                l += s * tap.left;
+                r += s * tap.right;
+            }
+            out.push(l);
+            out.push(r);
+        }
+    }
+
+    fn refresh(&mut self) {
+        let generation = self.shared.generation.load(Ordering::Acquire);
+        if generation == self.generation {
+            return;
+        }
+        if let Ok(maps) = self.shared.maps.try_lock() {
+            self.taps = maps
+                .get(&self.device)
+                .cloned()
+                .unwrap_or_default()
+                .taps(self.channels);
+            self.generation = generation;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn route(channel: u16, side: ChannelSide, gain_db: f32) -> ChannelRoute {
+        ChannelRoute {
+            channel,
+            side,
+            gain_db,
+        }
+    }
+
+    fn mix(shared: &Arc<InputChannels>, channels: usize, data: &[f32]) -> Vec<f32> {
+        let mut out = Vec::new();
+        ChannelMixer::new(shared.clone(), "card", channels).mix(data, &mut out);
+        out
+    }
+
+    #[test]
+    fn default_takes_the_front_pair_or_duplicates_mono() {
+        let shared = Arc::new(InputChannels::default());
+        assert_eq!(mix(&shared, 4, &[0.1, 0.2, 0.3, 0.4]), vec![0.1, 0.2]);
+        assert_eq!(mix(&shared, 1, &[0.5, 0.25]), vec![0.5, 0.5, 0.25, 0.25]);
+    }
+
+    #[test]
+    fn routes_pick_channels_with_gain() {
+        let shared = Arc::new(InputChannels::default());
+        shared.set_map(
+            "card",
+            ChannelMap {
+                routes: vec![
+                    route(2, ChannelSide::Left, 0.0),
+                    route(3, ChannelSide::Right, -6.0),
+                ],
+            },
+        );
+        let out = mix(&shared, 4, &[9.0, 9.0, 0.5, 1.0]);
+        assert_eq!(out[0], 0.5);
+        assert!((out[1] - 0.501).abs() < 1e-3);
+    }
+
+    #[test]
+    fn one_sided_routing_mirrors_and_same_side_sums() {
+        let shared = Arc::new(InputChannels::default());
+        shared.set_map(
+            "card",
+            ChannelMap {
+                routes: vec![
+                    route(0, ChannelSide::Left, 0.0),
+                    route(5, ChannelSide::Left, 0.0),
+                ],
+            },
+        );
+        assert_eq!(
+            mix(&shared, 6, &[0.25, 0.0, 0.0, 0.0, 0.0, 0.5]),
+            vec![0.75, 0.75]
+        );
+        // A route past the open stream's channels is skipped, not out of bounds.
+        assert_eq!(mix(&shared, 2, &[0.25, 1.0]), vec![0.25, 0.25]);
+    }
+
+    #[test]
+    fn mixer_picks_up_changes_and_default_is_not_stored() {
+        let shared = Arc::new(InputChannels::default());
+        let mut mixer = ChannelMixer::new(shared.clone(), "card", 2);
+        shared.set_map(
+            "card",
+            ChannelMap {
+                routes: vec![route(1, ChannelSide::Both, 0.0)],
+            },
+        );
+        let mut out = Vec::new();
+        mixer.mix(&[0.1, 0.9], &mut out);
+        assert_eq!(out, vec![0.9, 0.9]);
+
+        shared.set_map("card", ChannelMap::default());
+        assert!(shared.maps().is_empty());
+        assert_eq!(
+            ChannelMap {
+                routes: vec![route(7, ChannelSide::Both, 0.0)]
+            }
+            .channels_needed(),
+            8
+        );
+    }
+}
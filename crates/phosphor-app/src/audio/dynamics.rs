@@ -0,0 +1,326 @@
+//! Dynamics (#2218): crest factor, and an impact detector for the moment a quiet
+//! passage slams back in.
+//!
+//! - `crest`  — peak-to-RMS ratio over the last ~0.75 s, 3..23 dB mapped to 0..1. Low on a
+//!   brick-walled master or a held pad, high on sparse, punchy material.
+//! - `impact` — jumps to 1 when broadband energy returns after a quiet bar, then decays over
+//!   ~half a second. The A18 `drop` (structure.rs) only fires at the end of a detected
+//!   build-up; this needs no build-up, so it also catches a band coming back after a stop or
+//!   a DJ cutting the bass for a bar. "Broadband" means at least two of three crude bands
+//!   (below 200 Hz, 200 Hz–2 kHz, above 2 kHz) jumped together, so a lone kick or a cymbal
+//!   swell after a breakdown doesn't count.
+//!
+//! Both are producer-normalized (Passthrough), and `impact` is a decaying envelope rather
+//! than a 1-frame pulse, so a render frame that skips an analysis hop still sees it.
+//!
+//! Short-term loudness isn't measured again here: A10's `loudness_s` (loudness.rs) is already
+//! K-weighted LUFS over 3 s, with its own uniform and `audio.loudness_s` source.
+
+use std::collections::VecDeque;
+
+/// Crest factor mapped to 0.0 here (a pure sine is 3 dB).
+const CREST_MIN_DB: f32 = 3.0;
+/// Crest factor mapped to 1.0 here.
+const CREST_MAX_DB: f32 = 23.0;
+/// Window the crest peak and RMS are taken over.
+const CREST_WINDOW_SECS: f32 = 0.75;
+/// Band split points for the broadband test.
+const LOW_SPLIT_HZ: f32 = 200.0;
+const HIGH_SPLIT_HZ: f32 = 2000.0;
+/// Time constant of the per-band level followers the jump is read from.
+const LEVEL_SECS: f32 = 0.05;
+/// Bar length used until the downbeat tracker has one, and the longest bar considered.
+const DEFAULT_BAR_SECS: f32 = 2.0;
+const MAX_BAR_SECS: f32 = 4.0;
+/// The quiet bar ends this long before the current hop, so the leading edge of the jump
+/// itself doesn't lift the bar's average.
+const GUARD_SECS: f32 = 0.15;
+/// The bar counts as quiet when its average sits this far below the recent loudest level.
+const QUIET_DB: f32 = 10.0;
+/// The return must come back to within this much of the recent loudest level...
+const RETURN_DB: f32 = 6.0;
+/// ...with at least `MIN_JUMPED_BANDS` bands this much above their quiet-bar average.
+const JUMP_DB: f32 = 10.0;
+const MIN_JUMPED_BANDS: usize = 2;
+/// How fast the recent loudest level forgets a peak.
+const CEILING_RELEASE_DB_PER_SEC: f32 = 0.3;
+/// Minimum time between impacts.
+const REFRACTORY_SECS: f32 = 4.0;
+/// Time constant of `impact`'s fall from 1.
+const IMPACT_DECAY_SECS: f32 = 0.5;
+/// Floor for dB conversions of silence.
+const FLOOR_DB: f32 = -120.0;
+
+/// One hop's dynamics features.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Dynamics {
+    pub crest: f32,
+    pub impact: f32,
+}
+
+/// Per-hop band levels kept for the quiet-bar test.
+#[derive(Debug, Clone, Copy)]
+struct HopLevels {
+    bands_db: [f32; 3],
+    total_db: f32,
+    silent: bool,
+}
+
+pub struct DynamicsAnalyzer {
+    dt: f32,
+    /// One-pole lowpass coefficients and states at the two split points.
+    low_coef: f32,
+    high_coef: f32,
+    low_state: f32,
+    high_state: f32,
+    /// Per-hop `(peak, mean square)` over the crest window.
+    crest_window: VecDeque<(f32, f32)>,
+    crest_hops: usize,
+    /// Smoothed per-band power.
+    band_power: [f32; 3],
+    history: VecDeque<HopLevels>,
+    history_hops: usize,
+    guard_hops: usize,
+    ceiling_db: f32,
+    refractory: f32,
+    out: Dynamics,
+}
+
+impl DynamicsAnalyzer {
+    pub fn new(sample_rate: f32) -> Self {
+        let sample_rate = sample_rate.max(1.0);
+        let dt = super::ANALYSIS_HOP as f32 / sample_rate;
+        let one_pole = |hz: f32| 1.0 - (-std::f32::consts::TAU * hz / sample_rate).exp();
+        let hops = |secs: f32| (secs / dt).ceil() as usize;
+        Self {
+            dt,
+            low_coef: one_pole(LOW_SPLIT_HZ),
+            high_coef: one_pole(HIGH_SPLIT_HZ),
+            low_state: 0.0,
+            high_state: 0.0,
+            crest_window: VecDeque::with_capacity(hops(CREST_WINDOW_SECS) + 1),
+            crest_hops: hops(CREST_WINDOW_SECS),
+            band_power: [0.0; 3],
+            history: VecDeque::with_capacity(hops(MAX_BAR_SECS + GUARD_SECS) + 1),
+            history_hops: hops(MAX_BAR_SECS + GUARD_SECS),
+            guard_hops: hops(GUARD_SECS),
+            ceiling_db: FLOOR_DB,
+            refractory: 0.0,
+            out: Dynamics::default(),
+        }
+    }
+
+    /// Analyse one mono hop. `silent` is the A10 perceptual silence flag and `bar_secs`
+    /// the downbeat tracker's bar length (0 while it has none).
+    pub fn process(&mut self, hop: &[f32], silent: bool, bar_secs: f32) -> Dynamics {
+        if hop.is_empty() {
+            return self.out;
+        }
+        let n = hop.len() as f32;
+        let mut peak = 0.0f32;
+        let mut mean_sq = 0.0f32;
+        let mut power = [0.0f32; 3];
+        for &x in hop {
+            self.low_state += self.low_coef * (x - self.low_state);
+            self.high_state += self.high_coef * (x - self.high_state);
+            let split = [
+                self.low_state,
+                self.high_state - self.low_state,
+                x - self.high_state,
+            ];
+            for (p, s) in power.iter_mut().zip(split) {
+                *p += s * s;
+            }
+            peak = peak.max(x.abs());
+            mean_sq += x * x;
+        }
+        mean_sq /= n;
+
+        // Crest: held through silence rather than read off the noise floor.
+        self.crest_window.push_back((peak, mean_sq));
+        while self.crest_window.len() > self.crest_hops {
+            self.crest_window.pop_front();
+        }
+        if !silent {
+            let (peak, sum_sq) = self
+                .crest_window
+                .iter()
+                .fold((0.0f32, 0.0f32), |(p, s), &(hp, hs)| (p.max(hp), s + hs));
+            let rms = (sum_sq / self.crest_window.len() as f32).sqrt();
+            if rms > 0.0 {
+                let crest_db = 20.0 * (peak / rms).log10();
+                self.out.crest =
+                    ((crest_db - CREST_MIN_DB) / (CREST_MAX_DB - CREST_MIN_DB)).clamp(0.0, 1.0);
+            }
+        }
+
+        let follow = 1.0 - (-self.dt / LEVEL_SECS).exp();
+        for (level, p) in self.band_power.iter_mut().zip(power) {
+            *level += (p / n - *level) * follow;
+        }
+        let bands_db = self.band_power.map(to_db);
+        let total_db = to_db(self.band_power.iter().sum());
+
+        self.refractory = (self.refractory - self.dt).max(0.0);
+        self.out.impact *= (-self.dt / IMPACT_DECAY_SECS).exp();
+        if self.refractory == 0.0 && self.is_impact(&bands_db, total_db, bar_secs) {
+            self.out.impact = 1.0;
+            self.refractory = REFRACTORY_SECS;
+        }
+
+        self.ceiling_db = total_db.max(self.ceiling_db - CEILING_RELEASE_DB_PER_SEC * self.dt);
+        self.history.push_back(HopLevels {
+            bands_db,
+            total_db,
+            silent,
+        });
+        while self.history.len() > self.history_hops {
+            self.history.pop_front();
+        }
+        self.out
+    }
+
+    /// Whether the current levels are a broadband return after a quiet bar. The bar must
+    /// be quiet but not silent: music resuming after a gap between tracks isn't an impact.
+    fn is_impact(&self, bands_db: &[f32; 3], total_db: f32, bar_secs: f32) -> bool {
+        if total_db < self.ceiling_db - RETURN_DB {
+            return false;
+        }
+        let bar_secs = if bar_secs > 0.0 {
+            bar_secs.min(MAX_BAR_SECS)
+        } else {
+            DEFAULT_BAR_SECS
+        };
+        let bar_hops = (bar_secs / self.dt).round() as usize;
+        let Some(end) = self.history.len().checked_sub(self.guard_hops) else {
+            return false;
+        };
+        let Some(start) = end.checked_sub(bar_hops) else {
+            return false;
+        };
+        let bar = self.history.range(start..end);
+        let mut mean_bands = [0.0f32; 3];
+        let mut mean_total = 0.0f32;
+        for hop in bar {
+            if hop.silent {
+                return false;
+            }
+            for (m, b) in mean_bands.iter_mut().zip(hop.bands_db) {
+                *m += b;
+            }
+            mean_total += hop.total_db;
+        }
+        let count = bar_hops.max(1) as f32;
+        if mean_total / count > self.ceiling_db - QUIET_DB {
+            return false;
+        }
+        let jumped = bands_db
+            .iter()
+            .zip(mean_bands)
+            .filter(|&(now, sum)| now - sum / count >= JUMP_DB)
+            .count();
+        jumped >= MIN_JUMPED_BANDS
+    }
+}
+
+fn to_db(power: f32) -> f32 {
+    (10.0 * power.log10()).max(FLOOR_DB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: f32 = 48_000.0;
+    const HOP: usize = super::super::ANALYSIS_HOP;
+
+    /// A hop of a mid tone, with a bass and a high tone on top when `bass`/`highs`.
+    fn hop(index: usize, amplitude: f32, bass: bool, highs: bool) -> Vec<f32> {
+        (0..HOP)
+            .map(|i| {
+                let t = (index * HOP + i) as f32 / RATE;
+                let mut s = 0.0;
+                if bass {
+                    s += (std::f32::consts::TAU * 80.0 * t).sin();
+                }
+                s += 0.5 * (std::f32::consts::TAU * 700.0 * t).sin();
+                if highs {
+                    s += 0.5 * (std::f32::consts::TAU * 5000.0 * t).sin();
+                }
+                amplitude * s
+            })
+            .collect()
+    }
+
+    fn run(
+        d: &mut DynamicsAnalyzer,
+        from: usize,
+        secs: f32,
+        amplitude: f32,
+        full: bool,
+    ) -> (usize, f32) {
+        let hops = (secs * RATE / HOP as f32) as usize;
+        let mut peak_impact = 0.0f32;
+        for i in from..from + hops {
+            let out = d.process(&hop(i, amplitude, full, full), false, 2.0);
+            peak_impact = peak_impact.max(out.impact);
+        }
+        (from + hops, peak_impact)
+    }
+
+    #[test]
+    fn impact_fires_on_a_return_after_a_quiet_bar_only() {
+        let mut d = DynamicsAnalyzer::new(RATE);
+        let (i, steady) = run(&mut d, 0, 6.0, 0.3, true);
+        assert_eq!(steady, 0.0, "a steady groove is no impact");
+
+        // Breakdown: bass and highs cut, mids way down, for over a bar.
+        let (i, quiet) = run(&mut d, i, 3.0, 0.03, false);
+        assert_eq!(quiet, 0.0);
+        let (i, slam) = run(&mut d, i, 0.3, 0.3, true);
+        assert!(slam > 0.9, "full band back after a quiet bar: {slam}");
+
+        // Refractory, then decay: the held groove doesn't re-trigger.
+        let (_, held) = run(&mut d, i, 2.0, 0.3, true);
+        let last = d.process(&hop(0, 0.3, true, true), false, 2.0).impact;
+        assert!(held <= slam && last < 0.05, "{held} {last}");
+    }
+
+    #[test]
+    fn a_gap_of_silence_is_not_an_impact() {
+        let mut d = DynamicsAnalyzer::new(RATE);
+        let (i, _) = run(&mut d, 0, 4.0, 0.3, true);
+        let hops = (3.0 * RATE / HOP as f32) as usize;
+        for _ in 0..hops {
+            d.process(&[0.0; HOP], true, 2.0);
+        }
+        let (_, back) = run(&mut d, i, 0.5, 0.3, true);
+        assert_eq!(back, 0.0);
+    }
+
+    #[test]
+    fn crest_orders_sine_below_clicks() {
+        let mut d = DynamicsAnalyzer::new(RATE);
+        let sine: Vec<f32> = (0..HOP).map(|i| 0.5 * (i as f32 * 0.05).sin()).collect();
+        let mut out = Dynamics::default();
+        for _ in 0..100 {
+            out = d.process(&sine, false, 0.0);
+        }
+        assert!(out.crest < 0.05, "a sine is ~3 dB: {}", out.crest);
+
+        let mut clicks = vec![0.01f32; HOP];
+        clicks[0] = 0.8;
+        for _ in 0..100 {
+            out = d.process(&clicks, false, 0.0);
+        }
+        assert!(
+            out.crest > 0.8,
+            "sparse clicks are very peaky: {}",
+            out.crest
+        );
+
+        // Held through silence.
+        let held = d.process(&[0.0; HOP], true, 0.0).crest;
+        assert_eq!(held, out.crest);
+    }
+}
@@ -0,0 +1,149 @@
+//! Envelope followers (#2219): a fast and a slow version of the bass, mid and high
+//! levels, with attack and release set in the audio panel. Shaders read them as
+//! `env_bass_fast` … `env_high_slow` instead of each rolling its own smoothing, and
+//! pick the punchy or the smooth one.
+//!
+//! They follow the already-normalized bands on the render thread, once per frame, so a
+//! change of times takes effect on the next frame and needs no pipeline rebuild.
+
+use serde::{Deserialize, Serialize};
+
+/// Attack and release of one follower, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EnvelopeTimes {
+    pub attack_ms: f32,
+    pub release_ms: f32,
+}
+
+impl EnvelopeTimes {
+    const fn new(attack_ms: f32, release_ms: f32) -> Self {
+        Self {
+            attack_ms,
+            release_ms,
+        }
+    }
+}
+
+/// The fast and slow followers of one band.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BandEnvelope {
+    pub fast: EnvelopeTimes,
+    pub slow: EnvelopeTimes,
+}
+
+/// Follower times for the three bands. Saved with the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvelopeConfig {
+    pub bass: BandEnvelope,
+    pub mid: BandEnvelope,
+    pub high: BandEnvelope,
+}
+
+impl Default for EnvelopeConfig {
+    fn default() -> Self {
+        Self {
+            bass: BandEnvelope {
+                fast: EnvelopeTimes::new(5.0, 120.0),
+                slow: EnvelopeTimes::new(200.0, 800.0),
+            },
+            mid: BandEnvelope {
+                fast: EnvelopeTimes::new(5.0, 90.0),
+                slow: EnvelopeTimes::new(150.0, 600.0),
+            },
+            high: BandEnvelope {
+                fast: EnvelopeTimes::new(2.0, 60.0),
+                slow: EnvelopeTimes::new(100.0, 500.0),
+            },
+        }
+    }
+}
+
+impl EnvelopeConfig {
+    pub fn bands(&self) -> [&BandEnvelope; 3] {
+        [&self.bass, &self.mid, &self.high]
+    }
+
+    pub fn bands_mut(&mut self) -> [&mut BandEnvelope; 3] {
+        [&mut self.bass, &mut self.mid, &mut self.high]
+    }
+}
+
+/// Follower outputs, 0-1, in `[bass, mid, high]` order.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Envelopes {
+    pub fast: [f32; 3],
+    pub slow: [f32; 3],
+}
+
+/// The followers' state and the times they run with.
+#[derive(Debug, Default)]
+pub struct EnvelopeFollowers {
+    pub config: EnvelopeConfig,
+    out: Envelopes,
+}
+
+impl EnvelopeFollowers {
+    /// The three follower inputs from the seven normalized bands: bass is sub-bass and
+    /// bass, mid the three middle bands, high presence and brilliance.
+    pub fn inputs(bands: &[f32; 7]) -> [f32; 3] {
+        [
+            (bands[0] + bands[1]) * 0.5,
+            (bands[2] + bands[3] + bands[4]) / 3.0,
+            (bands[5] + bands[6]) * 0.5,
+        ]
+    }
+
+    /// Advance every follower by `dt` seconds towards `inputs`.
+    pub fn update(&mut self, inputs: [f32; 3], dt: f32) -> Envelopes {
+        for (i, band) in self.config.bands().into_iter().enumerate() {
+            let target = inputs[i].clamp(0.0, 1.0);
+            self.out.fast[i] = follow(self.out.fast[i], target, band.fast, dt);
+            self.out.slow[i] = follow(self.out.slow[i], target, band.slow, dt);
+        }
+        self.out
+    }
+}
+
+/// One step of an attack/release follower. A zero time snaps straight to the target.
+fn follow(current: f32, target: f32, times: EnvelopeTimes, dt: f32) -> f32 {
+    let ms = if target > current {
+        times.attack_ms
+    } else {
+        times.release_ms
+    };
+    if ms <= 0.0 {
+        return target;
+    }
+    current + (target - current) * (1.0 - (-dt * 1000.0 / ms).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fast_tracks_a_hit_that_slow_only_leans_towards() {
+        let mut env = EnvelopeFollowers::default();
+        let dt = 1.0 / 120.0;
+        let mut out = Envelopes::default();
+        // A 50 ms bass hit.
+        for _ in 0..6 {
+            out = env.update([1.0, 0.0, 0.0], dt);
+        }
+        assert!(out.fast[0] > 0.95, "{out:?}");
+        assert!(out.slow[0] < 0.3, "{out:?}");
+        assert_eq!(out.fast[1], 0.0);
+
+        // Release: after 300 ms the fast one has mostly let go, the slow one hasn't.
+        for _ in 0..36 {
+            out = env.update([0.0; 3], dt);
+        }
+        assert!(out.fast[0] < 0.15, "{out:?}");
+        assert!(out.slow[0] > out.fast[0], "{out:?}");
+
+        // Zero times follow the input exactly.
+        env.config.mid.fast = EnvelopeTimes::new(0.0, 0.0);
+        assert_eq!(env.update([0.0, 0.7, 0.0], dt).fast[1], 0.7);
+    }
+}
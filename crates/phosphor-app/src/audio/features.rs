@@ -1,6 +1,6 @@
 use bytemuck::{Pod, Zeroable};
 
-/// 81 audio features, all normalized to 0.0-1.0 range.
+/// 83 audio features, all normalized to 0.0-1.0 range.
 /// Multi-resolution FFT bands + spectral shape + beat detection + MFCC + chroma,
 /// plus a reserved tail laid out by two batched shader-ABI bumps: v2 (#1505 —
 /// loudness / key / downbeat / stereo / structure) and v3 (#1629 — hpss / pitch /
@@ -103,9 +103,13 @@ pub struct AudioFeatures {
     pub band_pan_upper_mid: f32,
     pub band_pan_presence: f32,
     pub band_pan_brilliance: f32,
+
+    // Dynamics (#2218), appended like the block above.
+    pub crest: f32,  // peak-to-RMS over ~0.75 s, 3-23 dB mapped 0-1
+    pub impact: f32, // 1.0 when the full mix slams back after a quiet bar, then decays
 }
 
-pub const NUM_FEATURES: usize = 81;
+pub const NUM_FEATURES: usize = 83;
 
 impl AudioFeatures {
     pub fn as_slice(&self) -> &[f32; NUM_FEATURES] {
@@ -115,6 +119,14 @@ impl AudioFeatures {
     pub fn as_slice_mut(&mut self) -> &mut [f32; NUM_FEATURES] {
         bytemuck::cast_mut(self)
     }
+
+    /// Look a feature up by its schema name (`"bass"`, `"mfcc.3"`, …).
+    pub fn get(&self, name: &str) -> Option<f32> {
+        super::schema::FEATURES
+            .iter()
+            .position(|f| f.name == name)
+            .map(|i| self.as_slice()[i])
+    }
 }
 
 impl Default for AudioFeatures {
@@ -140,7 +152,18 @@ mod tests {
     #[test]
     fn as_slice_len() {
         let f = AudioFeatures::default();
-        assert_eq!(f.as_slice().len(), 81);
+        assert_eq!(f.as_slice().len(), 83);
+    }
+
+    #[test]
+    fn get_by_name() {
+        let f = AudioFeatures {
+            bass: 0.25,
+            ..Default::default()
+        };
+        assert_eq!(f.get("bass"), Some(0.25));
+        assert_eq!(f.get("rms"), Some(0.0));
+        assert_eq!(f.get("nope"), None);
     }
 
     #[test]
@@ -158,6 +181,7 @@ mod tests {
             drop: 0.44,
             timbre_flux: 0.99,
             band_pan_brilliance: 0.77,
+            impact: 0.66,
             ..Default::default()
         };
         let s = f.as_slice();
@@ -168,13 +192,16 @@ mod tests {
         assert!((s[60] - 0.44).abs() < 1e-6);
         // `timbre_flux` kept index 73 across the A13b append — the point of appending
         assert!((s[73] - 0.99).abs() < 1e-6);
-        // `band_pan_brilliance` is the new last slot (index 80)
+        // `band_pan_brilliance` kept index 80 across the dynamics append (#2218)
         assert!((s[80] - 0.77).abs() < 1e-6);
+        // `impact` is the new last slot (index 82)
+        assert!((s[82] - 0.66).abs() < 1e-6);
     }
 
     #[test]
-    fn size_is_324_bytes() {
-        // 81 f32 features (296 bytes / 74 before the A13b per-band pan append)
-        assert_eq!(std::mem::size_of::<AudioFeatures>(), 324);
+    fn size_is_332_bytes() {
+        // 83 f32 features (324 bytes / 81 before the #2218 dynamics pair, 296 bytes / 74
+        // before the A13b per-band pan append)
+        assert_eq!(std::mem::size_of::<AudioFeatures>(), 332);
     }
 }
@@ -0,0 +1,222 @@
+//! Second input (#2217): a room mic captured alongside the music, analysed
+//! only for how loud the crowd is and when it jumps, so an effect can answer
+//! applause without the music's beat or FFT being touched. It feeds the
+//! `mic_rms` / `mic_onset` uniforms.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+
+use super::capture::AudioCapture;
+use super::channels::InputChannels;
+
+/// Samples per analysis step, the same hop as the music analysis.
+const MIC_HOP: usize = super::ANALYSIS_HOP;
+/// Span below the recent loudest level that `mic_rms` spreads over.
+const RANGE_DB: f32 = 40.0;
+/// How fast the loudest level forgets a peak, so one big cheer doesn't hold
+/// the rest of the night down.
+const CEILING_RELEASE_DB_PER_SEC: f32 = 0.5;
+/// The ceiling never drops below this (dBFS), or a murmuring room would read
+/// as a full-scale crowd before anyone has cheered.
+const MIN_CEILING_DB: f32 = -20.0;
+/// Rise over the background level before `mic_onset` starts to register, and
+/// the rise that reads as 1.
+const ONSET_START_DB: f32 = 3.0;
+const ONSET_FULL_DB: f32 = 15.0;
+/// Time constants: the background the onset is measured against, the onset's
+/// fall, and the level's attack and release.
+const BACKGROUND_SECS: f32 = 1.5;
+const ONSET_DECAY_SECS: f32 = 0.25;
+const ATTACK_SECS: f32 = 0.03;
+const RELEASE_SECS: f32 = 0.4;
+
+/// What the mic contributes to the frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MicLevels {
+    /// 0-1 crowd level, ranged against the loudest it has been recently.
+    pub rms: f32,
+    /// 0-1, jumps when the level rises sharply (a cheer, a clap) and falls back.
+    pub onset: f32,
+}
+
+/// Level and onset from mono hops of the mic signal.
+#[derive(Debug)]
+pub struct MicAnalyzer {
+    dt: f32,
+    ceiling_db: f32,
+    background_db: Option<f32>,
+    levels: MicLevels,
+}
+
+impl MicAnalyzer {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            dt: MIC_HOP as f32 / sample_rate.max(1.0),
+            ceiling_db: MIN_CEILING_DB,
+            background_db: None,
+            levels: MicLevels::default(),
+        }
+    }
+
+    /// Analyse one hop of mono samples.
+    pub fn process(&mut self, hop: &[f32]) -> MicLevels {
+        if hop.is_empty() {
+            return self.levels;
+        }
+        let mean_sq = hop.iter().map(|s| s * s).sum::<f32>() / hop.len() as f32;
+        let db = 10.0 * mean_sq.max(1e-12).log10();
+
+        self.ceiling_db = db
+            .max(self.ceiling_db - CEILING_RELEASE_DB_PER_SEC * self.dt)
+            .max(MIN_CEILING_DB);
+        let target = ((db - (self.ceiling_db - RANGE_DB)) / RANGE_DB).clamp(0.0, 1.0);
+        let tau = if target > self.levels.rms {
+            ATTACK_SECS
+        } else {
+            RELEASE_SECS
+        };
+        self.levels.rms += (target - self.levels.rms) * smoothing(self.dt, tau);
+
+        let background = self.background_db.get_or_insert(db);
+        let rise = db - *background;
+        *background += (db - *background) * smoothing(self.dt, BACKGROUND_SECS);
+        let hit = ((rise - ONSET_START_DB) / (ONSET_FULL_DB - ONSET_START_DB)).clamp(0.0, 1.0);
+        self.levels.onset = hit.max(self.levels.onset * (-self.dt / ONSET_DECAY_SECS).exp());
+
+        self.levels
+    }
+}
+
+/// EMA coefficient for a step of `dt` towards a time constant of `tau`.
+fn smoothing(dt: f32, tau: f32) -> f32 {
+    1.0 - (-dt / tau).exp()
+}
+
+/// An open second input and the thread analysing it.
+pub struct MicInput {
+    pub device_name: String,
+    levels: Arc<Mutex<MicLevels>>,
+    failed: Arc<AtomicBool>,
+    shutdown: Arc<AtomicBool>,
+    thread_handle: Option<thread::JoinHandle<()>>,
+    _capture: AudioCapture,
+}
+
+impl MicInput {
+    /// Open `device` for the mic. Unlike the main input there is no fallback
+    /// to the default device: that is usually the music itself.
+    pub fn open(device: &str, input_channels: &Arc<InputChannels>) -> Result<Self> {
+        let capture = AudioCapture::new_with_device(Some(device), input_channels)?;
+        if capture.device_name != device {
+            bail!("device '{device}' not found");
+        }
+        let levels = Arc::new(Mutex::new(MicLevels::default()));
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let failed = capture.capture_failed.clone();
+
+        let ring = capture.ring.clone();
+        let sample_rate = capture.sample_rate as f32;
+        let levels_thread = levels.clone();
+        let shutdown_thread = shutdown.clone();
+        let thread_handle =
+            thread::Builder::new()
+                .name("phosphor-mic".into())
+                .spawn(move || {
+                    let mut analyzer = MicAnalyzer::new(sample_rate);
+                    let mut read_buf = vec![0.0f32; 8192];
+                    let mut fifo: Vec<f32> = Vec::with_capacity(read_buf.len() / 2 + MIC_HOP);
+                    while !shutdown_thread.load(Ordering::Acquire) {
+                        thread::sleep(Duration::from_millis(10));
+                        let n = ring.read(&mut read_buf);
+                        fifo.extend(
+                            read_buf[..n]
+                                .chunks_exact(2)
+                                .map(|lr| (lr[0] + lr[1]) * 0.5),
+                        );
+                        let mut start = 0;
+                        while fifo.len() - start >= MIC_HOP {
+                            let out = analyzer.process(&fifo[start..start + MIC_HOP]);
+                            *levels_thread.lock().unwrap_or_else(|e| e.into_inner()) = out;
+                            start += MIC_HOP;
+                        }
+                        fifo.drain(..start);
+                    }
+                })?;
+
+        log::info!("Mic input: {device}");
+        Ok(Self {
+            device_name: capture.device_name.clone(),
+            levels,
+            failed,
+            shutdown,
+            thread_handle: Some(thread_handle),
+            _capture: capture,
+        })
+    }
+
+    pub fn levels(&self) -> MicLevels {
+        *self.levels.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The device went away under the stream.
+    pub fn failed(&self) -> bool {
+        self.failed.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for MicInput {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Release);
+        if let Some(handle) = self.thread_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: f32 = 48_000.0;
+
+    fn tone(amplitude: f32) -> Vec<f32> {
+        (0..MIC_HOP)
+            .map(|i| amplitude * (i as f32 * 0.07).sin())
+            .collect()
+    }
+
+    fn run(analyzer: &mut MicAnalyzer, amplitude: f32, secs: f32) -> MicLevels {
+        let hops = (secs * RATE / MIC_HOP as f32) as usize;
+        let hop = tone(amplitude);
+        (0..hops).fold(MicLevels::default(), |_, _| analyzer.process(&hop))
+    }
+
+    #[test]
+    fn a_cheer_reads_louder_than_the_room_and_fires_an_onset() {
+        let mut mic = MicAnalyzer::new(RATE);
+        let room = run(&mut mic, 0.01, 5.0);
+        assert!(room.onset < 0.05, "steady room noise is no onset: {room:?}");
+
+        let cheer = run(&mut mic, 0.3, 0.1);
+        assert!(cheer.onset > 0.9, "{cheer:?}");
+        assert!(cheer.rms > room.rms + 0.3, "{room:?} -> {cheer:?}");
+
+        let held = run(&mut mic, 0.3, 4.0);
+        assert!(
+            held.onset < 0.1,
+            "a held level stops counting as a jump: {held:?}"
+        );
+        assert!(held.rms > 0.9);
+    }
+
+    #[test]
+    fn silence_stays_at_zero() {
+        let mut mic = MicAnalyzer::new(RATE);
+        let quiet = run(&mut mic, 0.0, 2.0);
+        assert_eq!(quiet, MicLevels::default());
+    }
+}
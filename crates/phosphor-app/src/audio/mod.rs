@@ -1,13 +1,17 @@
 pub mod analyzer;
 pub mod beat;
 pub mod capture;
+pub mod channels;
 pub mod chroma;
 pub mod downbeat;
+pub mod dynamics;
+pub mod envelope;
 pub mod features;
 pub mod hpss;
 pub mod interp;
 pub mod key;
 pub mod loudness;
+pub mod mic;
 pub mod normalizer;
 pub mod pitch;
 #[cfg(target_os = "linux")]
@@ -15,7 +19,9 @@ pub mod pulse_capture;
 pub mod ranging;
 pub mod reconnect;
 pub mod schema;
+pub mod silence;
 pub mod smoother;
+pub mod snapshot;
 pub mod stereo;
 pub mod structure;
 pub mod timbre;
@@ -60,8 +66,47 @@ pub struct AudioFrame {
     /// the property the render side leans on. It is deliberately not `meter`: that changes on
     /// any beat, mid-bar, and re-deriving the rate from it would fight the phase it chases.
     pub bar_duration: f64,
+    /// Raw input level of this hop's L/R samples, for the status bar meter (#2212).
+    pub level: InputLevel,
 }
 
+/// Sample peak at or above this counts as clipping (about -0.1 dBFS).
+pub const CLIP_LEVEL: f32 = 0.989;
+
+/// Input level straight off the capture ring (#2212), as linear full-scale
+/// amplitude (1.0 = 0 dBFS). The features can't stand in for it: `rms` is
+/// rescaled to its own recent range, so quiet routing and loud music read alike.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct InputLevel {
+    pub peak: f32,
+    pub rms: f32,
+}
+
+impl InputLevel {
+    pub fn measure(samples: &[f32]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        let sum_sq: f32 = samples.iter().map(|s| s * s).sum();
+        Self {
+            peak,
+            rms: (sum_sq / samples.len() as f32).sqrt(),
+        }
+    }
+
+    /// Whether the input hit full scale.
+    pub fn clipped(&self) -> bool {
+        self.peak >= CLIP_LEVEL
+    }
+}
+
+/// Linear amplitude in dBFS, floored at -96 (16-bit silence).
+pub fn to_dbfs(linear: f32) -> f32 {
+    (20.0 * linear.max(1e-9).log10()).max(-96.0)
+}
+
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -73,12 +118,14 @@ use self::analyzer::FftAnalyzer;
 use self::beat::BeatDetector;
 pub use self::beat::{TempoCommand, TempoConfig, TempoControl, TempoPreset};
 use self::capture::{AudioCapture, RingBuffer};
+use self::channels::{ChannelMap, InputChannels};
 use self::downbeat::DownbeatTracker;
+use self::dynamics::DynamicsAnalyzer;
 use self::hpss::HpssAnalyzer;
 use self::interp::FeatureInterpolator;
 use self::key::KeyDetector;
 use self::loudness::LoudnessMeter;
-use self::normalizer::FeatureNormalizer;
+use self::normalizer::{FeatureNormalizer, GainControl};
 use self::pitch::PitchAnalyzer;
 use self::smoother::FeatureSmoother;
 use self::stereo::StereoAnalyzer;
@@ -115,6 +162,10 @@ struct OpenedBackend {
     /// merely idle. This is where the per-backend policy lives, so nothing downstream of
     /// `open_backend` has to know which platform it is on.
     silence_delivers_data: bool,
+    /// Channels the stream was opened with, and the most the device offers (#2216). The
+    /// native loopback backends are always a stereo pair.
+    channels: usize,
+    max_channels: usize,
 }
 
 /// How long `callback_count` may stay frozen before the watchdog calls it a stall.
@@ -153,7 +204,10 @@ enum Teardown {
 
 /// Try native loopback first (PulseAudio on Linux, WASAPI on Windows), then cpal.
 /// When a specific device is requested, skip native loopback and go straight to cpal.
-fn open_backend(device_name: Option<&str>) -> Result<OpenedBackend, String> {
+fn open_backend(
+    device_name: Option<&str>,
+    input_channels: &Arc<InputChannels>,
+) -> Result<OpenedBackend, String> {
     if device_name.is_none() {
         #[cfg(target_os = "linux")]
         {
@@ -171,6 +225,8 @@ fn open_backend(device_name: Option<&str>) -> Result<OpenedBackend, String> {
                         // indistinguishable from death by callback count alone. Read errors
                         // are this backend's real death signal.
                         silence_delivers_data: false,
+                        channels: 2,
+                        max_channels: 2,
                     });
                 }
                 Err(e) => {
@@ -195,6 +251,8 @@ fn open_backend(device_name: Option<&str>) -> Result<OpenedBackend, String> {
                         // frozen callback count is just as likely to be a quiet passage as a
                         // dead endpoint. COM errors are this backend's real death signal.
                         silence_delivers_data: false,
+                        channels: 2,
+                        max_channels: 2,
                     });
                 }
                 Err(e) => {
@@ -204,8 +262,10 @@ fn open_backend(device_name: Option<&str>) -> Result<OpenedBackend, String> {
         }
     }
 
-    match AudioCapture::new_with_device(device_name) {
+    match AudioCapture::new_with_device(device_name, input_channels) {
         Ok(capture) => Ok(OpenedBackend {
+            channels: capture.channels,
+            max_channels: capture.max_channels,
             ring: capture.ring.clone(),
             sample_rate: capture.sample_rate as f32,
             device_name: capture.device_name.clone(),
@@ -241,6 +301,9 @@ pub struct AudioSystem {
     /// Newest delta-MFCC slopes received (A16 `audio.dmfcc.N`, #1467). Held between polls like
     /// `latest_mel` so the binding bus can expose the sources; bindings-only (not in the ABI).
     latest_dmfcc: [f32; 13],
+    /// Input level since the last poll (#2212): the highest peak among the
+    /// frames drained, so a clip between polls still shows, and the newest rms.
+    latest_level: InputLevel,
     pub device_name: String,
     pub active: bool,
     pub last_error: Option<String>,
@@ -272,6 +335,29 @@ pub struct AudioSystem {
     /// shared with the audio thread, snapshotted once per hop, threaded through
     /// `switch_device`. The mailbox half carries UI/MIDI/OSC overrides to the detector.
     tempo: Arc<Mutex<TempoControl>>,
+    /// Normalizer freeze + reset (#2213). Same shape as `tempo`, and likewise
+    /// threaded through `switch_device` so a freeze survives a device change.
+    gain: Arc<Mutex<GainControl>>,
+    /// Analysis snapshot start/stop (#2215). Shared with the audio thread like `gain`;
+    /// a device switch ends the snapshot rather than truncating it.
+    snapshot: Arc<Mutex<snapshot::SnapshotControl>>,
+    /// Per-device channel routing (#2216), read by the capture callback. Threaded through
+    /// `switch_device` and into reopen workers so every backend opened sees the same maps.
+    input_channels: Arc<InputChannels>,
+    /// Channels the current stream carries, and the most the device could (#2216).
+    pub channel_count: usize,
+    pub max_channel_count: usize,
+    /// Second input for crowd level (#2217). Independent of the main pipeline, so a device
+    /// switch or reconnect leaves it running.
+    mic: Option<mic::MicInput>,
+    /// Why the mic input isn't running, when one was asked for.
+    pub mic_error: Option<String>,
+    /// Fast/slow band envelopes for the `env_*` uniforms (#2219), run on the render thread.
+    /// The audio panel edits their times in place.
+    pub envelopes: envelope::EnvelopeFollowers,
+    /// Since when the input has been silent (the A10 perceptual gate, or no
+    /// frames at all), for the sustained-silence action (#2213).
+    silent_since: Option<Instant>,
     /// Beat taps for tap tempo (A7 #1458). Held as `Instant`s rather than offsets from
     /// `started_at`, which `switch_device` resets — a reset clock mid-sequence would turn
     /// the stored taps into garbage intervals.
@@ -342,6 +428,7 @@ impl AudioSystem {
             BandScale::default(),
             Arc::new(Mutex::new(StructureConfig::default())),
             Arc::new(Mutex::new(TempoControl::default())),
+            Arc::new(InputChannels::default()),
         )
     }
 
@@ -350,13 +437,17 @@ impl AudioSystem {
         band_scale: BandScale,
         tuning: Arc<Mutex<StructureConfig>>,
         tempo: Arc<Mutex<TempoControl>>,
+        input_channels: Arc<InputChannels>,
     ) -> Self {
         Self::from_opened(
-            open_backend(device_name),
+            open_backend(device_name, &input_channels),
             device_name,
             band_scale,
             tuning,
             tempo,
+            Arc::new(Mutex::new(GainControl::default())),
+            Arc::new(Mutex::new(snapshot::SnapshotControl::default())),
+            input_channels,
             Arc::new(RingBuffer::new()),
         )
     }
@@ -378,6 +469,9 @@ impl AudioSystem {
         band_scale: BandScale,
         tuning: Arc<Mutex<StructureConfig>>,
         tempo: Arc<Mutex<TempoControl>>,
+        gain: Arc<Mutex<GainControl>>,
+        snapshot: Arc<Mutex<snapshot::SnapshotControl>>,
+        input_channels: Arc<InputChannels>,
         recording_ring: Arc<RingBuffer>,
     ) -> Self {
         let (tx, rx): (Sender<AudioFrame>, Receiver<AudioFrame>) = crossbeam_channel::bounded(4);
@@ -398,6 +492,8 @@ impl AudioSystem {
                 let drops = drop_counter.clone();
                 let tuning_thread = tuning.clone();
                 let tempo_thread = tempo.clone();
+                let gain_thread = gain.clone();
+                let snapshot_thread = snapshot.clone();
 
                 let thread_handle = thread::Builder::new()
                     .name("phosphor-audio".into())
@@ -414,6 +510,8 @@ impl AudioSystem {
                             band_scale,
                             tuning_thread,
                             tempo_thread,
+                            gain_thread,
+                            snapshot_thread,
                         );
                     })
                     .expect("Failed to spawn audio thread");
@@ -426,6 +524,7 @@ impl AudioSystem {
                     pending_mel: Vec::new(),
                     latest_mel: Vec::new(),
                     latest_dmfcc: [0.0; 13],
+                    latest_level: InputLevel::default(),
                     device_name: opened.device_name,
                     active: true,
                     last_error: None,
@@ -447,6 +546,15 @@ impl AudioSystem {
                     band_scale,
                     tuning,
                     tempo,
+                    gain,
+                    snapshot,
+                    input_channels,
+                    channel_count: opened.channels,
+                    max_channel_count: opened.max_channels,
+                    mic: None,
+                    mic_error: None,
+                    envelopes: envelope::EnvelopeFollowers::default(),
+                    silent_since: None,
                     tap_times: Vec::new(),
                     beat_counter,
                     beats_seen: 0,
@@ -486,6 +594,7 @@ impl AudioSystem {
                     pending_mel: Vec::new(),
                     latest_mel: Vec::new(),
                     latest_dmfcc: [0.0; 13],
+                    latest_level: InputLevel::default(),
                     device_name: requested.unwrap_or("Default").to_string(),
                     active: false,
                     last_error: Some(e),
@@ -509,6 +618,15 @@ impl AudioSystem {
                     band_scale,
                     tuning,
                     tempo,
+                    gain,
+                    snapshot,
+                    input_channels,
+                    channel_count: 0,
+                    max_channel_count: 0,
+                    mic: None,
+                    mic_error: None,
+                    envelopes: envelope::EnvelopeFollowers::default(),
+                    silent_since: None,
                     tap_times: Vec::new(),
                     beat_counter,
                     beats_seen: 0,
@@ -544,7 +662,8 @@ impl AudioSystem {
         // backend it opened on its own thread), and forget the backoff.
         self.pending_open = None;
         self.reopen_target = None;
-        self.adopt(device_name, open_backend(device_name), Teardown::Blocking);
+        let opened = open_backend(device_name, &self.input_channels);
+        self.adopt(device_name, opened, Teardown::Blocking);
         self.reconnect.reset();
     }
 
@@ -588,6 +707,9 @@ impl AudioSystem {
             self.band_scale,
             self.tuning.clone(),
             self.tempo.clone(),
+            self.gain.clone(),
+            self.snapshot.clone(),
+            self.input_channels.clone(),
             self.recording_ring.clone(),
         );
         self.receiver = std::mem::replace(&mut new.receiver, crossbeam_channel::bounded(1).1);
@@ -602,6 +724,7 @@ impl AudioSystem {
         self.pending_mel.clear();
         self.latest_mel.clear();
         self.latest_dmfcc = [0.0; 13];
+        self.latest_level = InputLevel::default();
         self.device_name = std::mem::take(&mut new.device_name);
         self.active = new.active;
         self.last_error = new.last_error.take();
@@ -619,6 +742,8 @@ impl AudioSystem {
         // ours, so the new audio thread already writes to the same ring an in-progress
         // recording is draining.
         self.sample_rate = new.sample_rate;
+        self.channel_count = new.channel_count;
+        self.max_channel_count = new.max_channel_count;
         self.beat_counter = std::mem::replace(&mut new.beat_counter, Arc::new(AtomicU32::new(0)));
         self.beats_seen = self.beat_counter.load(Ordering::Relaxed);
         self.downbeat_counter =
@@ -663,6 +788,51 @@ impl AudioSystem {
             .push(cmd);
     }
 
+    /// Forget the normalizer's learned ranges (#2213), e.g. after the FOH
+    /// engineer changes the feed's level. Also releases a freeze.
+    pub fn reset_normalization(&self) {
+        self.gain
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .request_reset();
+    }
+
+    /// Lock the normalizer's gains (#2213), so a quiet breakdown reads quiet.
+    pub fn set_normalization_frozen(&self, frozen: bool) {
+        self.gain.lock().unwrap_or_else(|e| e.into_inner()).frozen = frozen;
+    }
+
+    pub fn normalization_frozen(&self) -> bool {
+        self.gain.lock().unwrap_or_else(|e| e.into_inner()).frozen
+    }
+
+    /// Start recording an analysis snapshot to `path` (#2215); the audio thread opens
+    /// it on its next hop.
+    pub fn start_snapshot(&self, path: PathBuf) {
+        self.snapshot
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .target = Some(path);
+    }
+
+    pub fn stop_snapshot(&self) {
+        self.snapshot
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .target = None;
+    }
+
+    /// The file being recorded and its frame count, or why the last one stopped.
+    pub fn snapshot_status(&self) -> (Option<PathBuf>, u64, Option<String>) {
+        let s = self.snapshot.lock().unwrap_or_else(|e| e.into_inner());
+        (s.target.clone(), s.frames, s.error.clone())
+    }
+
+    /// How long the input has been silent, zero while there's sound (#2213).
+    pub fn silence_duration(&self) -> Duration {
+        self.silent_since.map_or(Duration::ZERO, |t| t.elapsed())
+    }
+
     /// Register one beat tap (A7 #1458), sending the averaged tempo to the detector once
     /// enough taps have landed. Owned here so the panel's Tap button and the MIDI/OSC tap
     /// trigger feed a single sequence — tapping across both still averages correctly.
@@ -703,6 +873,78 @@ impl AudioSystem {
         self.switch_device(device.as_deref());
     }
 
+    /// Open `device` as the mic input, or close it with `None` (#2217).
+    pub fn set_mic_device(&mut self, device: Option<&str>) {
+        self.mic = None;
+        self.mic_error = None;
+        let Some(device) = device else {
+            return;
+        };
+        match mic::MicInput::open(device, &self.input_channels) {
+            Ok(input) => self.mic = Some(input),
+            Err(e) => {
+                log::warn!("Mic input unavailable: {e}");
+                self.mic_error = Some(format!("{e}"));
+            }
+        }
+    }
+
+    /// The open mic device, if any.
+    pub fn mic_device(&self) -> Option<&str> {
+        self.mic.as_ref().map(|m| m.device_name.as_str())
+    }
+
+    /// Crowd level and onset from the mic; zero without one. A mic whose device went away
+    /// is closed and reported in `mic_error`.
+    pub fn mic_levels(&mut self) -> mic::MicLevels {
+        if self.mic.as_ref().is_some_and(mic::MicInput::failed) {
+            let name = self.mic.take().map(|m| m.device_name).unwrap_or_default();
+            log::warn!("Mic input '{name}' disconnected");
+            self.mic_error = Some(format!("'{name}' disconnected"));
+        }
+        self.mic
+            .as_ref()
+            .map(mic::MicInput::levels)
+            .unwrap_or_default()
+    }
+
+    /// Channel routing for the current device (#2216).
+    pub fn channel_map(&self) -> ChannelMap {
+        self.input_channels.map_for(&self.device_name)
+    }
+
+    /// Route the current device's channels (#2216). Takes effect within a callback, unless
+    /// the routing reaches past the channels the stream was opened with: then the device is
+    /// reopened wider, like a band-scale change.
+    pub fn set_channel_map(&mut self, map: ChannelMap) {
+        let reopen = !self.using_native_backend
+            && self.active
+            && map.channels_needed() > self.channel_count
+            && self.channel_count < self.max_channel_count;
+        self.input_channels.set_map(&self.device_name, map);
+        if reopen {
+            let device = self.current_target();
+            self.switch_device(device.as_deref());
+        }
+    }
+
+    /// Replace every device's routing (a reloaded settings file). Reaching past the open
+    /// stream's channels waits for the next device open.
+    pub fn set_channel_maps(&mut self, maps: std::collections::BTreeMap<String, ChannelMap>) {
+        self.input_channels.set_maps(maps);
+    }
+
+    /// Every device's routing, for the settings file.
+    pub fn channel_maps(&self) -> std::collections::BTreeMap<String, ChannelMap> {
+        self.input_channels.maps()
+    }
+
+    /// Whether the current input can be routed: a named multi-channel device, not the
+    /// native loopback.
+    pub fn channel_routing_available(&self) -> bool {
+        self.active && !self.using_native_backend && self.max_channel_count > 2
+    }
+
     /// How to reopen whatever we are listening to now: `None` for the native loopback backend
     /// (which `open_backend` only tries when no device is named), else the cpal device name.
     fn current_target(&self) -> Option<String> {
@@ -753,7 +995,13 @@ impl AudioSystem {
         self.last_poll_at = now;
 
         let mut got_frame = false;
+        let mut peak = 0.0f32;
         while let Ok(frame) = self.receiver.try_recv() {
+            if !frame.phase_frozen {
+                self.silent_since = None;
+            } else if self.silent_since.is_none() {
+                self.silent_since = Some(now);
+            }
             self.latest = Some(frame.features);
             self.interp.push(
                 frame.timestamp,
@@ -772,6 +1020,11 @@ impl AudioSystem {
             // A16 (#1467): newest delta-MFCC slopes for the `audio.dmfcc.N` binding sources.
             self.latest_dmfcc = frame.dmfcc;
             self.pending_mel.push(frame.mel);
+            peak = peak.max(frame.level.peak);
+            self.latest_level = InputLevel {
+                peak,
+                rms: frame.level.rms,
+            };
             got_frame = true;
         }
 
@@ -781,6 +1034,9 @@ impl AudioSystem {
             // No frames for a while (device stalled or removed): decay the held
             // features toward silence instead of freezing on the last loud frame.
             // Compounds per poll, so visuals settle to zero in roughly a second.
+            // The meter drops straight to silence, and so does the silence clock.
+            self.latest_level = InputLevel::default();
+            self.silent_since.get_or_insert(self.last_frame_at);
             if let Some(ref mut latest) = self.latest {
                 let k = (-poll_dt / 0.3).exp();
                 decay_features(latest, k);
@@ -867,6 +1123,12 @@ impl AudioSystem {
         &self.latest_dmfcc
     }
 
+    /// Raw input level for the status bar meter (#2212). Call after
+    /// `latest_features` each frame; zero while no audio arrives.
+    pub fn input_level(&self) -> InputLevel {
+        self.latest_level
+    }
+
     /// Take the mel-spectrogram columns accumulated since the last call (oldest first),
     /// leaving the pending buffer empty (A17 `audio_spectrogram`, #1468). The render
     /// thread scrolls these into the spectrogram texture.
@@ -879,6 +1141,12 @@ impl AudioSystem {
         self.reconnect.set_enabled(enabled);
     }
 
+    /// Installation mode (#2233): keep retrying a lost device after the
+    /// regular attempts run out.
+    pub fn set_persistent_reconnect(&mut self, persistent: bool) {
+        self.reconnect.set_persistent(persistent);
+    }
+
     /// What the status bar's AUD dot should show (A9 #1460).
     pub fn indicator(&self) -> AudioIndicator {
         if self.reconnect.is_reconnecting() {
@@ -917,10 +1185,11 @@ impl AudioSystem {
 
         let (tx, rx) = crossbeam_channel::bounded(1);
         self.pending_open = Some(rx);
+        let input_channels = self.input_channels.clone();
         thread::Builder::new()
             .name("phosphor-audio-reopen".into())
             .spawn(move || {
-                let _ = tx.send(open_backend(target.as_deref()));
+                let _ = tx.send(open_backend(target.as_deref(), &input_channels));
             })
             // Spawn failure drops `tx`, so `rx` disconnects and `poll_health` fails the
             // attempt on its next tick — no special case needed.
@@ -1164,6 +1433,8 @@ fn audio_thread(
     band_scale: BandScale,
     tuning: Arc<Mutex<StructureConfig>>,
     tempo: Arc<Mutex<TempoControl>>,
+    gain: Arc<Mutex<GainControl>>,
+    snapshot: Arc<Mutex<snapshot::SnapshotControl>>,
 ) {
     let mut analyzer = FftAnalyzer::new(sample_rate, band_scale);
     let mut normalizer = FeatureNormalizer::new();
@@ -1180,6 +1451,8 @@ fn audio_thread(
     let mut hpss_analyzer = HpssAnalyzer::new();
     let mut pitch_analyzer = PitchAnalyzer::new(sample_rate);
     let mut dmfcc_analyzer = DeltaMfccAnalyzer::new();
+    let mut dynamics_analyzer = DynamicsAnalyzer::new(sample_rate);
+    let mut snapshot_recorder = snapshot::SnapshotRecorder::default();
     // A13 (#1464): the capture ring yields interleaved L,R. `read_buf` reads it raw; `mono_scratch`
     // holds the mono mix derived from it (fed to the recording mirror + FFT, exactly as before).
     let mut read_buf = vec![0.0f32; 8192]; // 4096 stereo frames; larger for the 4096-pt FFT
@@ -1199,6 +1472,7 @@ fn audio_thread(
     loop {
         if shutdown.load(Ordering::Acquire) {
             log::info!("Audio thread shutting down");
+            snapshot_recorder.shutdown(&snapshot);
             break;
         }
         thread::sleep(Duration::from_millis(10));
@@ -1333,7 +1607,15 @@ fn audio_thread(
             let pre_norm = raw;
 
             // A2 (#1453): per-feature normalization (gated percentile / fixed-range /
-            // z-score / passthrough), silence-gated on the A10 perceptual flag.
+            // z-score / passthrough), silence-gated on the A10 perceptual flag. The
+            // freeze and reset controls (#2213) are picked up once per hop.
+            {
+                let mut g = gain.lock().unwrap_or_else(|e| e.into_inner());
+                if g.take_reset() {
+                    normalizer.reset();
+                }
+                normalizer.set_frozen(g.frozen);
+            }
             raw = normalizer.normalize(&raw, loud_silent);
 
             // A7 (#1458): snapshot the shared tempo config and drain the command mailbox
@@ -1366,6 +1648,29 @@ fn audio_thread(
             raw.bpm = beat_result.bpm / 300.0; // normalize to 0-1
             raw.beat_strength = beat_result.beat_strength;
 
+            // #2215: the beat pipeline's input and verdict, for offline tuning.
+            snapshot_recorder.hop(
+                &snapshot,
+                || snapshot::SnapshotHeader {
+                    sample_rate,
+                    hop: ANALYSIS_HOP,
+                    tempo: tempo_cfg,
+                },
+                || {
+                    let mut log_bands = [0.0; beat::N_ONSET_BANDS];
+                    for (out, &v) in log_bands.iter_mut().zip(beat_detector.onset_log_bands()) {
+                        *out = v as f32;
+                    }
+                    snapshot::SnapshotFrame {
+                        timestamp,
+                        loud_silent,
+                        log_bands,
+                        beat: beat_result,
+                        raw: pre_norm,
+                    }
+                },
+            );
+
             // Count beats in an atomic so the consumer can't miss a 1-frame pulse
             // when the channel overflows or it drains multiple frames at once.
             if beat_result.beat > 0.5 {
@@ -1405,6 +1710,12 @@ fn audio_thread(
                 drop_counter.fetch_add(1, Ordering::Relaxed);
             }
 
+            // #2218: crest factor and the impact envelope, on the bar length the downbeat
+            // tracker just settled. Both are Passthrough, so they can land after normalize().
+            let dynamics = dynamics_analyzer.process(hop, loud_silent, db.bar_duration as f32);
+            raw.crest = dynamics.crest;
+            raw.impact = dynamics.impact;
+
             // Smoothing (per-feature asymmetric EMA; beat/beat_phase pass through)
             let smoothed = smoother.smooth(&raw, dt);
 
@@ -1426,6 +1737,7 @@ fn audio_thread(
                 // A8b (#1554): the tracker's own bar-clock denominator, so the render side
                 // advances `bar_phase` on the same rate that produced the phase above.
                 bar_duration: db.bar_duration,
+                level: InputLevel::measure(hop_stereo),
             };
 
             // Non-blocking send; drop if main thread is behind
@@ -1448,6 +1760,25 @@ mod tests {
         (a - b).abs() < eps
     }
 
+    #[test]
+    fn input_level_reads_raw_samples() {
+        assert_eq!(InputLevel::measure(&[]), InputLevel::default());
+
+        // Full-scale square wave: peak and rms both 1.0, and it clips
+        let square = InputLevel::measure(&[1.0, -1.0, 1.0, -1.0]);
+        assert!(approx_eq(square.peak, 1.0, 1e-6));
+        assert!(approx_eq(square.rms, 1.0, 1e-6));
+        assert!(square.clipped());
+
+        let quiet = InputLevel::measure(&[0.1, -0.05, 0.0, 0.05]);
+        assert!(approx_eq(quiet.peak, 0.1, 1e-6));
+        assert!(!quiet.clipped());
+
+        assert!(approx_eq(to_dbfs(1.0), 0.0, 1e-4));
+        assert!(approx_eq(to_dbfs(0.5), -6.02, 0.01));
+        assert_eq!(to_dbfs(0.0), -96.0);
+    }
+
     #[test]
     fn decay_scales_everything_except_holds() {
         let mut f = AudioFeatures::default();
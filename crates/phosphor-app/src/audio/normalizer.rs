@@ -48,6 +48,31 @@ pub struct FeatureNormalizer {
     z_var: [f32; NUM_FEATURES],
     /// Last emitted value per slot — FixedRange holds this through a silence gate.
     fixed_last: [f32; NUM_FEATURES],
+    /// Gains locked (#2213): Adaptive and ZScore slots keep mapping through the
+    /// ranges they had, without learning from new input — so a quiet breakdown
+    /// reads quiet instead of being stretched back up to full scale.
+    frozen: bool,
+}
+
+/// Live gain-staging controls for the normalizer (#2213), shared with the
+/// audio thread behind a mutex it locks once per hop, like `TempoControl`.
+#[derive(Debug, Default)]
+pub struct GainControl {
+    pub frozen: bool,
+    reset_pending: bool,
+}
+
+impl GainControl {
+    /// Ask the audio thread to forget every learned range on its next hop.
+    /// Unfreezes too, since an empty range has nothing to hold.
+    pub fn request_reset(&mut self) {
+        self.reset_pending = true;
+        self.frozen = false;
+    }
+
+    pub fn take_reset(&mut self) -> bool {
+        std::mem::take(&mut self.reset_pending)
+    }
 }
 
 impl FeatureNormalizer {
@@ -59,9 +84,20 @@ impl FeatureNormalizer {
             z_mean: [0.0; NUM_FEATURES],
             z_var: [1.0; NUM_FEATURES],
             fixed_last: [0.0; NUM_FEATURES],
+            frozen: false,
         }
     }
 
+    /// Forget every learned range and running stat, as at startup; gains
+    /// re-learn from the next frame. Also unfreezes: there is nothing left to hold.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+
     /// Normalize all features to 0..1 per their [`NormPolicy`]. `loud_silent` is the A10
     /// perceptual silence gate (`LoudnessMeter::is_silent`): when set, energy features read
     /// 0 with their adaptation frozen, FixedRange features hold their last value, and
@@ -81,7 +117,9 @@ impl FeatureNormalizer {
                         // Freeze the window (don't push silence) and read 0.
                         0.0
                     } else {
-                        self.windows[i].push(v);
+                        if !self.frozen {
+                            self.windows[i].push(v);
+                        }
                         let (p_lo, p_hi) = self.windows[i].range(P_LO, P_HI);
                         soft_norm(v, p_lo, p_hi)
                     }
@@ -105,9 +143,11 @@ impl FeatureNormalizer {
                         let var = self.z_var[i];
                         let z = (v - mean) / (var + 1e-6).sqrt();
                         // Update running stats (EMA mean + EWMA variance).
-                        let delta = v - mean;
-                        self.z_mean[i] = mean + Z_ALPHA * delta;
-                        self.z_var[i] = (1.0 - Z_ALPHA) * (var + Z_ALPHA * delta * delta);
+                        if !self.frozen {
+                            let delta = v - mean;
+                            self.z_mean[i] = mean + Z_ALPHA * delta;
+                            self.z_var[i] = (1.0 - Z_ALPHA) * (var + Z_ALPHA * delta * delta);
+                        }
                         0.5 + 0.5 * (z / Z_SOFT).tanh()
                     }
                 }
@@ -290,4 +330,73 @@ mod tests {
             assert!(approx_eq(out.chroma[3], 0.8, 1e-6));
         }
     }
+
+    /// A sawtooth 0..1 history so the rms window spans the whole range.
+    fn warm_up(norm: &mut FeatureNormalizer) {
+        for i in 0..ADAPTIVE_WINDOW {
+            let raw = AudioFeatures {
+                rms: (i % 100) as f32 / 100.0,
+                ..Default::default()
+            };
+            norm.normalize(&raw, false);
+        }
+    }
+
+    fn rms_reading(norm: &mut FeatureNormalizer, rms: f32) -> f32 {
+        norm.normalize(
+            &AudioFeatures {
+                rms,
+                ..Default::default()
+            },
+            false,
+        )
+        .rms
+    }
+
+    #[test]
+    fn frozen_gains_keep_a_quiet_passage_quiet() {
+        let mut norm = FeatureNormalizer::new();
+        warm_up(&mut norm);
+        norm.set_frozen(true);
+        // A long breakdown around 0.1 would normally become the whole range
+        let breakdown = |i: usize| if i % 2 == 0 { 0.08 } else { 0.12 };
+        for i in 0..ADAPTIVE_WINDOW * 2 {
+            rms_reading(&mut norm, breakdown(i));
+        }
+        let quiet = rms_reading(&mut norm, 0.12);
+        assert!(
+            quiet < 0.2,
+            "frozen range should keep 0.12 low, got {quiet}"
+        );
+
+        // Unfrozen, the window fills with the breakdown and re-ranges around it
+        norm.set_frozen(false);
+        for i in 0..ADAPTIVE_WINDOW {
+            rms_reading(&mut norm, breakdown(i));
+        }
+        assert!(rms_reading(&mut norm, 0.12) > 0.8);
+    }
+
+    #[test]
+    fn gain_control_reset_is_one_shot() {
+        let mut gain = GainControl {
+            frozen: true,
+            ..Default::default()
+        };
+        gain.request_reset();
+        assert!(!gain.frozen);
+        assert!(gain.take_reset());
+        assert!(!gain.take_reset());
+    }
+
+    #[test]
+    fn reset_forgets_ranges_and_unfreezes() {
+        let mut norm = FeatureNormalizer::new();
+        warm_up(&mut norm);
+        norm.set_frozen(true);
+        norm.reset();
+        assert!(!norm.frozen);
+        // Nothing learned yet: an empty window reads flat
+        assert_eq!(rms_reading(&mut norm, 0.9), 0.0);
+    }
 }
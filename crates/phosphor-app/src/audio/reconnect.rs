@@ -22,6 +22,10 @@ const BACKOFF: [Duration; 4] = [
 /// Attempts per stall episode before giving up and leaving it to the user.
 pub const MAX_ATTEMPTS: u32 = 5;
 
+/// In installation mode (#2233) an exhausted episode starts over after this
+/// long, instead of waiting for someone to pick the device again.
+const PERSISTENT_RETRY: Duration = Duration::from_secs(60);
+
 /// How long a freshly opened backend has to produce its first callback before the attempt is
 /// counted as failed. Matches the existing startup check in [`super::AudioSystem::latest_features`].
 const PROBE: Duration = Duration::from_secs(5);
@@ -111,8 +115,10 @@ enum Phase {
     Waiting {
         at: Instant,
     },
-    /// [`MAX_ATTEMPTS`] spent. Cleared by a recovered callback or a manual switch.
-    Exhausted,
+    /// [`MAX_ATTEMPTS`] spent at `since`. Cleared by a recovered callback or a manual switch.
+    Exhausted {
+        since: Instant,
+    },
 }
 
 /// Backoff and attempt bookkeeping for one stall episode.
@@ -121,6 +127,8 @@ enum Phase {
 /// `band_scale`, it belongs to the system rather than to any one backend.
 pub struct ReconnectState {
     enabled: bool,
+    /// Never give up for good: see [`PERSISTENT_RETRY`].
+    persistent: bool,
     phase: Phase,
     attempt: u32,
     /// Set when [`Self::fail`] exhausts the episode, drained by the next [`Self::poll`] so
@@ -132,6 +140,7 @@ impl ReconnectState {
     pub fn new(enabled: bool) -> Self {
         Self {
             enabled,
+            persistent: false,
             phase: Phase::Healthy,
             attempt: 0,
             giveup_pending: false,
@@ -144,6 +153,11 @@ impl ReconnectState {
         self.reset();
     }
 
+    /// Keep retrying an exhausted episode every [`PERSISTENT_RETRY`] (#2233).
+    pub fn set_persistent(&mut self, persistent: bool) {
+        self.persistent = persistent;
+    }
+
     /// Whether auto-reconnect is on. [`Self::poll`] gates on this itself, but A9b's
     /// default-sink trigger (#1617) reaches [`Self::note_reopen_started`] without going
     /// through `poll`, so it has to ask.
@@ -251,13 +265,21 @@ impl ReconnectState {
                     ReconnectAction::Idle
                 }
             }
-            Phase::Exhausted => ReconnectAction::Idle,
+            Phase::Exhausted { since } => {
+                if self.persistent && now >= since + PERSISTENT_RETRY {
+                    self.attempt = 1;
+                    self.phase = Phase::Opening;
+                    ReconnectAction::Reopen { attempt: 1 }
+                } else {
+                    ReconnectAction::Idle
+                }
+            }
         }
     }
 
     fn fail(&mut self, now: Instant) {
         if self.attempt >= MAX_ATTEMPTS {
-            self.phase = Phase::Exhausted;
+            self.phase = Phase::Exhausted { since: now };
             self.giveup_pending = true;
         } else {
             // `attempt` is 1-based and capped at MAX_ATTEMPTS above, so this indexes 0..=3.
@@ -280,7 +302,7 @@ impl ReconnectState {
     }
 
     pub fn is_exhausted(&self) -> bool {
-        matches!(self.phase, Phase::Exhausted)
+        matches!(self.phase, Phase::Exhausted { .. })
     }
 }
 
@@ -452,6 +474,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn persistent_starts_over_after_giving_up() {
+        let mut s = ReconnectState::new(true);
+        s.set_persistent(true);
+        let t0 = Instant::now();
+        let t = exhaust(&mut s, t0);
+        assert_eq!(s.poll(t, dead()), ReconnectAction::GiveUp);
+        assert_eq!(
+            s.poll(t + PERSISTENT_RETRY - Duration::from_secs(1), dead()),
+            ReconnectAction::Idle
+        );
+        assert_eq!(
+            s.poll(t + PERSISTENT_RETRY, dead()),
+            ReconnectAction::Reopen { attempt: 1 }
+        );
+        assert!(s.is_reconnecting());
+    }
+
     #[test]
     fn probe_succeeds_on_first_callback() {
         let mut s = ReconnectState::new(true);
@@ -96,9 +96,8 @@ impl SmoothParams {
 #[derive(Debug, Clone, Copy)]
 pub struct FeatureDef {
     /// Canonical name (the struct field for scalars; `mfcc.0` / `chroma.0` for
-    /// array members). Consumed by the layout-guard test today; reserved for
-    /// deriving binding source ids (see `bindings::sources`) in a later pass.
-    #[allow(dead_code)]
+    /// array members). Checked by the layout-guard test and used for
+    /// by-name lookups such as effect audio mappings (#2186).
     pub name: &'static str,
     pub norm: NormPolicy,
     pub smooth: SmoothParams,
@@ -437,6 +436,12 @@ pub const FEATURES: [FeatureDef; NUM_FEATURES] = [
         SmoothParams::ar(0.08, 0.08),
         Scale,
     ),
+    // Dynamics (#2218) — detector-owned, both already 0..1. `crest` is held by its producer
+    // through silence and lightly smoothed. `impact` is an envelope the producer already
+    // decays, so it bypasses the EMA; it lerps like any continuous value (which a 1-frame
+    // trigger such as `drop` could not) and is forced to 0 on a stalled device.
+    def("crest", Passthrough, SmoothParams::ar(0.05, 0.2), Scale),
+    def("impact", Passthrough, SmoothParams::bypass(), ForceZero),
 ];
 
 /// Terse constructor so the table above reads as one row per feature. Interpolates
@@ -534,6 +539,10 @@ mod tests {
         // A13b appended after the v3 tail, so every index above is unmoved.
         assert_eq!(FEATURES[74].name, "band_pan_sub_bass");
         assert_eq!(FEATURES[80].name, "band_pan_brilliance");
+        // #2218 appended after A13b.
+        assert_eq!(f.crest, 81.0);
+        assert_eq!(FEATURES[81].name, "crest");
+        assert_eq!(FEATURES[82].name, "impact");
     }
 
     /// Every BIPOLAR feature — one centred at 0.5 encoding a position rather than an amount —
@@ -577,7 +586,8 @@ mod tests {
     ///   producer-remapped to 0..1); the A18 structure block (58..=60); and most of the v3 (#1629)
     ///   reserved tail — `harmonic_ratio` (63, a level-invariant balance), A15 pitch (64..=65),
     ///   A16 contrast (66..=72) — all producer-scaled to 0..1; and the A13b per-band pan block
-    ///   (74..=80), which the StereoAnalyzer remaps to 0..1 and holds at 0.5 for an empty band.
+    ///   (74..=80), which the StereoAnalyzer remaps to 0..1 and holds at 0.5 for an empty band;
+    ///   and the #2218 dynamics pair `crest` / `impact` (81, 82).
     /// - **Adaptive** (gated percentile ranging): the energy-like features — the 7 bands, rms (7),
     ///   flux (10), the A14 HPSS energies `percussive_energy` / `harmonic_energy` (61, 62), and the
     ///   A16 `timbre_flux` (73) — raw levels of unknown absolute scale.
@@ -587,7 +597,7 @@ mod tests {
             let expected = match i {
                 9 | 11 | 12 | 13 | 14 => FixedRange,
                 20..=32 => ZScore,
-                8 | 15..=19 | 33..=60 | 63..=72 | 74..=80 => Passthrough,
+                8 | 15..=19 | 33..=60 | 63..=72 | 74..=82 => Passthrough,
                 _ => Adaptive,
             };
             assert_eq!(
@@ -599,14 +609,15 @@ mod tests {
     }
 
     /// Decay exemptions: `bpm`, the categorical key fields, and the A15 `pitch` estimate hold
-    /// their last value on a stalled device (no sweep toward the lowest note), the `beat` and
-    /// `downbeat` triggers are forced to zero, everything else scales toward silence.
+    /// their last value on a stalled device (no sweep toward the lowest note), the `beat`,
+    /// `downbeat` and `drop` triggers and the `impact` envelope are forced to zero, everything
+    /// else scales toward silence.
     #[test]
     fn decay_exemptions() {
         for (i, def) in FEATURES.iter().enumerate() {
             let expected = match def.name {
                 "bpm" | "key_class" | "key_is_minor" | "pitch" => Hold,
-                "beat" | "downbeat" | "drop" => ForceZero,
+                "beat" | "downbeat" | "drop" | "impact" => ForceZero,
                 _ => Scale,
             };
             assert_eq!(
@@ -0,0 +1,169 @@
+//! Sustained-silence action (#2213): after the input has been silent for a
+//! while — a set break, a dropped feed — fade the output out or switch to an
+//! idle preset, and undo it when the music comes back.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Seconds the output takes to fade out once the action fires.
+const FADE_OUT_SECS: f32 = 3.0;
+/// Seconds to come back up when sound returns; short, so the first beat shows.
+const FADE_IN_SECS: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SilenceAction {
+    #[default]
+    Off,
+    /// Fade the master output to black.
+    Fade,
+    /// Load the idle preset, and the previous one again when sound returns.
+    IdlePreset,
+}
+
+impl SilenceAction {
+    pub const ALL: &[SilenceAction] = &[
+        SilenceAction::Off,
+        SilenceAction::Fade,
+        SilenceAction::IdlePreset,
+    ];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            SilenceAction::Off => "Off",
+            SilenceAction::Fade => "Fade out",
+            SilenceAction::IdlePreset => "Idle preset",
+        }
+    }
+}
+
+/// Saved with the settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SilenceConfig {
+    pub action: SilenceAction,
+    /// Seconds of silence before the action fires.
+    pub after_secs: f32,
+    /// Preset name for [`SilenceAction::IdlePreset`].
+    pub idle_preset: String,
+}
+
+impl Default for SilenceConfig {
+    fn default() -> Self {
+        Self {
+            action: SilenceAction::Off,
+            after_secs: 10.0,
+            idle_preset: String::new(),
+        }
+    }
+}
+
+/// Edges of a sustained silence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SilenceEvent {
+    Began,
+    Ended,
+}
+
+/// Tracks whether the action is engaged, and the fade it drives.
+#[derive(Debug, Default)]
+pub struct SilenceWatch {
+    engaged: bool,
+    /// 0 = full output, 1 = faded out.
+    fade: f32,
+}
+
+impl SilenceWatch {
+    /// Advance one frame of `dt` seconds, given how long the input has been
+    /// silent. Reports the frame the action should fire or be undone.
+    pub fn update(
+        &mut self,
+        config: &SilenceConfig,
+        silent_for: Duration,
+        dt: f32,
+    ) -> Option<SilenceEvent> {
+        let silent = config.action != SilenceAction::Off
+            && silent_for.as_secs_f32() >= config.after_secs.max(0.0)
+            && !silent_for.is_zero();
+        let event = match (self.engaged, silent) {
+            (false, true) => Some(SilenceEvent::Began),
+            (true, false) => Some(SilenceEvent::Ended),
+            _ => None,
+        };
+        self.engaged = silent;
+
+        self.fade = if self.engaged && config.action == SilenceAction::Fade {
+            (self.fade + dt / FADE_OUT_SECS).min(1.0)
+        } else {
+            (self.fade - dt / FADE_IN_SECS).max(0.0)
+        };
+        event
+    }
+
+    /// How far the output is faded, 0 (not at all) to 1 (black).
+    pub fn fade(&self) -> f32 {
+        self.fade
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fade_config() -> SilenceConfig {
+        SilenceConfig {
+            action: SilenceAction::Fade,
+            after_secs: 5.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fires_once_after_the_wait_and_undoes_on_sound() {
+        let cfg = fade_config();
+        let mut watch = SilenceWatch::default();
+        assert_eq!(watch.update(&cfg, Duration::from_secs(4), 0.1), None);
+        assert_eq!(
+            watch.update(&cfg, Duration::from_secs(5), 0.1),
+            Some(SilenceEvent::Began)
+        );
+        assert_eq!(watch.update(&cfg, Duration::from_secs(6), 0.1), None);
+        assert_eq!(
+            watch.update(&cfg, Duration::ZERO, 0.1),
+            Some(SilenceEvent::Ended)
+        );
+        assert_eq!(watch.update(&cfg, Duration::ZERO, 0.1), None);
+    }
+
+    #[test]
+    fn fade_ramps_out_slowly_and_back_quickly() {
+        let cfg = fade_config();
+        let mut watch = SilenceWatch::default();
+        let silent = Duration::from_secs(10);
+        watch.update(&cfg, silent, 1.5);
+        assert!((watch.fade() - 0.5).abs() < 1e-6);
+        watch.update(&cfg, silent, 5.0);
+        assert_eq!(watch.fade(), 1.0);
+        watch.update(&cfg, Duration::ZERO, 0.25);
+        assert!((watch.fade() - 0.5).abs() < 1e-6);
+        watch.update(&cfg, Duration::ZERO, 1.0);
+        assert_eq!(watch.fade(), 0.0);
+    }
+
+    #[test]
+    fn off_and_idle_preset_never_fade() {
+        let mut watch = SilenceWatch::default();
+        let off = SilenceConfig::default();
+        assert_eq!(watch.update(&off, Duration::from_secs(60), 1.0), None);
+
+        let idle = SilenceConfig {
+            action: SilenceAction::IdlePreset,
+            ..fade_config()
+        };
+        assert_eq!(
+            watch.update(&idle, Duration::from_secs(60), 1.0),
+            Some(SilenceEvent::Began)
+        );
+        assert_eq!(watch.fade(), 0.0);
+    }
+}
@@ -0,0 +1,596 @@
+//! Analysis snapshots (#2215): record what the beat pipeline heard and decided during a
+//! set, then replay it offline with different detector settings.
+//!
+//! A snapshot is a magic line, a JSON header, and one fixed-size little-endian record per
+//! analysis hop: the timestamp, the silence gate, the 64 onset-band log magnitudes (all
+//! the beat pipeline reads from the spectrum), the live beat result, and the features as
+//! they were before adaptive normalization. That comes to about 50 KB per second of audio.
+//!
+//! `phosphor --replay-beats <file>` re-runs [`BeatDetector`] over the recorded bands, with
+//! the tempo settings the set was recorded under or with overrides, and reports how its
+//! beats and tempo compare with the live decisions. Taps and octave shifts made during
+//! the set aren't recorded, so a replay always follows the detector's own reading.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use super::beat::{BeatDetector, BeatResult, N_ONSET_BANDS, TempoConfig, TempoPreset};
+use super::features::{AudioFeatures, NUM_FEATURES};
+
+/// Bumped whenever the frame layout changes (v2: the #2218 dynamics features), so an
+/// older file is refused rather than misread.
+const MAGIC: &[u8; 8] = b"PHSNAP2\n";
+pub const EXTENSION: &str = "phsnap";
+const FRAME_BYTES: usize = 8 + 1 + 4 * (N_ONSET_BANDS + 5 + NUM_FEATURES);
+/// A recorded and a replayed beat this close together count as the same beat.
+const MATCH_WINDOW: f64 = 0.05;
+/// Tempo readings within this fraction of each other agree.
+const BPM_AGREEMENT: f32 = 0.02;
+
+/// Written once at the top of a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    pub sample_rate: f32,
+    /// Samples per analysis hop; a replay needs the same frame rate.
+    pub hop: usize,
+    /// Detector settings when recording started.
+    pub tempo: TempoConfig,
+}
+
+/// One analysis hop.
+#[derive(Debug, Clone, Copy)]
+pub struct SnapshotFrame {
+    pub timestamp: f64,
+    pub loud_silent: bool,
+    pub log_bands: [f32; N_ONSET_BANDS],
+    /// What the live detector decided.
+    pub beat: BeatResult,
+    /// Features before adaptive normalization.
+    pub raw: AudioFeatures,
+}
+
+impl SnapshotFrame {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.timestamp.to_le_bytes());
+        out.push(u8::from(self.loud_silent));
+        let b = &self.beat;
+        let beat = [
+            b.onset_strength,
+            b.beat,
+            b.beat_phase,
+            b.bpm,
+            b.beat_strength,
+        ];
+        for v in self
+            .log_bands
+            .iter()
+            .chain(&beat)
+            .chain(self.raw.as_slice())
+        {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+
+    fn decode(bytes: &[u8; FRAME_BYTES]) -> Self {
+        let timestamp = f64::from_le_bytes(bytes[..8].try_into().expect("8 bytes"));
+        let mut floats = bytes[9..]
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes(c.try_into().expect("4 bytes")));
+        let mut log_bands = [0.0; N_ONSET_BANDS];
+        log_bands.fill_with(|| floats.next().unwrap_or_default());
+        let mut next = || floats.next().unwrap_or_default();
+        let beat = BeatResult {
+            onset_strength: next(),
+            beat: next(),
+            beat_phase: next(),
+            bpm: next(),
+            beat_strength: next(),
+        };
+        let mut raw = AudioFeatures::default();
+        raw.as_slice_mut().fill_with(next);
+        Self {
+            timestamp,
+            loud_silent: bytes[8] != 0,
+            log_bands,
+            beat,
+            raw,
+        }
+    }
+}
+
+fn write_header(out: &mut impl Write, header: &SnapshotHeader) -> Result<()> {
+    let json = serde_json::to_vec(header)?;
+    out.write_all(MAGIC)?;
+    out.write_all(&(json.len() as u32).to_le_bytes())?;
+    out.write_all(&json)?;
+    Ok(())
+}
+
+/// Appends frames to a snapshot file.
+pub struct SnapshotWriter {
+    out: BufWriter<File>,
+    scratch: Vec<u8>,
+}
+
+impl SnapshotWriter {
+    pub fn create(path: &Path, header: &SnapshotHeader) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        write_header(&mut out, header)?;
+        Ok(Self {
+            out,
+            scratch: Vec::with_capacity(FRAME_BYTES),
+        })
+    }
+
+    pub fn write(&mut self, frame: &SnapshotFrame) -> std::io::Result<()> {
+        self.scratch.clear();
+        frame.encode(&mut self.scratch);
+        self.out.write_all(&self.scratch)
+    }
+
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Reads a snapshot back, a frame at a time — a long set doesn't fit in memory whole.
+pub struct SnapshotReader<R> {
+    pub header: SnapshotHeader,
+    input: R,
+}
+
+impl SnapshotReader<BufReader<File>> {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read> SnapshotReader<R> {
+    pub fn new(mut input: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            if magic.starts_with(b"PHSNAP") {
+                bail!("analysis snapshot from another version of Fosfora; record it again");
+            }
+            bail!("not an analysis snapshot");
+        }
+        let mut len = [0u8; 4];
+        input.read_exact(&mut len)?;
+        let mut json = vec![0u8; u32::from_le_bytes(len) as usize];
+        input.read_exact(&mut json)?;
+        let header = serde_json::from_slice(&json).context("snapshot header")?;
+        Ok(Self { header, input })
+    }
+
+    /// The next frame, or `None` at the end. A partial last record — the app quit
+    /// mid-write — also ends the snapshot.
+    pub fn next_frame(&mut self) -> Result<Option<SnapshotFrame>> {
+        let mut bytes = [0u8; FRAME_BYTES];
+        match self.input.read_exact(&mut bytes) {
+            Ok(()) => Ok(Some(SnapshotFrame::decode(&bytes))),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Start/stop for a snapshot, shared between the UI and the audio thread, which follows
+/// it once per hop.
+#[derive(Debug, Default)]
+pub struct SnapshotControl {
+    /// File being recorded; `None` when idle.
+    pub target: Option<PathBuf>,
+    /// Frames written to `target` so far.
+    pub frames: u64,
+    /// Why the last snapshot stopped on its own, if it did.
+    pub error: Option<String>,
+}
+
+/// The audio thread's side: the open file, if any.
+#[derive(Default)]
+pub struct SnapshotRecorder {
+    writer: Option<(PathBuf, SnapshotWriter)>,
+}
+
+impl SnapshotRecorder {
+    /// Follow the control's start/stop, then append this hop if recording. `header` and
+    /// `frame` are only built when they're needed.
+    pub fn hop(
+        &mut self,
+        control: &Mutex<SnapshotControl>,
+        header: impl FnOnce() -> SnapshotHeader,
+        frame: impl FnOnce() -> SnapshotFrame,
+    ) {
+        let mut ctl = control.lock().unwrap_or_else(|e| e.into_inner());
+        if self.writer.as_ref().map(|(path, _)| path) != ctl.target.as_ref() {
+            self.close();
+            if let Some(path) = ctl.target.clone() {
+                match SnapshotWriter::create(&path, &header()) {
+                    Ok(writer) => {
+                        log::info!("Analysis snapshot started: {}", path.display());
+                        ctl.frames = 0;
+                        ctl.error = None;
+                        self.writer = Some((path, writer));
+                    }
+                    Err(e) => {
+                        log::error!("Analysis snapshot failed: {e:#}");
+                        ctl.error = Some(format!("{e:#}"));
+                        ctl.target = None;
+                    }
+                }
+            }
+        }
+        let Some((_, writer)) = self.writer.as_mut() else {
+            return;
+        };
+        if let Err(e) = writer.write(&frame()) {
+            log::error!("Analysis snapshot write failed: {e}");
+            ctl.error = Some(e.to_string());
+            ctl.target = None;
+            self.close();
+        } else {
+            ctl.frames += 1;
+        }
+    }
+
+    /// Stop when the audio thread exits. The next thread (after a device switch) would
+    /// otherwise reopen the same path and truncate what was recorded.
+    pub fn shutdown(&mut self, control: &Mutex<SnapshotControl>) {
+        if self.writer.is_some() {
+            let mut ctl = control.lock().unwrap_or_else(|e| e.into_inner());
+            ctl.target = None;
+            ctl.error = Some("stopped: the audio device changed".to_string());
+            self.close();
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some((path, writer)) = self.writer.take() {
+            match writer.finish() {
+                Ok(()) => log::info!("Analysis snapshot saved: {}", path.display()),
+                Err(e) => log::error!("Failed to finish analysis snapshot: {e}"),
+            }
+        }
+    }
+}
+
+/// Command-line options for `--replay-beats`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReplayOptions {
+    pub path: PathBuf,
+    pub preset: Option<TempoPreset>,
+    pub onset_threshold: Option<f32>,
+    pub bpm_min: Option<f32>,
+    pub bpm_max: Option<f32>,
+    pub tracking: Option<f32>,
+    pub prior_center: Option<f32>,
+    pub prior_sigma: Option<f32>,
+    /// Write a per-frame comparison here.
+    pub csv: Option<PathBuf>,
+}
+
+impl ReplayOptions {
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut opts = Self::default();
+        let mut path = None;
+        let mut it = args.iter();
+        while let Some(arg) = it.next() {
+            let mut value = |flag: &str| {
+                it.next()
+                    .cloned()
+                    .ok_or_else(|| anyhow!("{flag} needs a value"))
+            };
+            match arg.as_str() {
+                "--replay-beats" => path = Some(PathBuf::from(value(arg)?)),
+                "--preset" => {
+                    let name = value(arg)?.to_lowercase();
+                    let preset = TempoPreset::ALL
+                        .iter()
+                        .copied()
+                        .find(|p| p.display_name().to_lowercase().starts_with(&name))
+                        .ok_or_else(|| anyhow!("unknown tempo preset '{name}'"))?;
+                    opts.preset = Some(preset);
+                }
+                "--onset-threshold" => opts.onset_threshold = Some(number(arg, &value(arg)?)?),
+                "--bpm-min" => opts.bpm_min = Some(number(arg, &value(arg)?)?),
+                "--bpm-max" => opts.bpm_max = Some(number(arg, &value(arg)?)?),
+                "--tracking" => opts.tracking = Some(number(arg, &value(arg)?)?),
+                "--prior-center" => opts.prior_center = Some(number(arg, &value(arg)?)?),
+                "--prior-sigma" => opts.prior_sigma = Some(number(arg, &value(arg)?)?),
+                "--csv" => opts.csv = Some(PathBuf::from(value(arg)?)),
+                other => bail!("unknown --replay-beats option '{other}'"),
+            }
+        }
+        opts.path = path.ok_or_else(|| anyhow!("--replay-beats needs a snapshot file"))?;
+        Ok(opts)
+    }
+
+    /// The settings to replay with: the recorded ones, then the preset, then each knob.
+    pub fn tempo(&self, recorded: TempoConfig) -> TempoConfig {
+        let mut cfg = self.preset.map_or(recorded, TempoPreset::config);
+        let knobs = [
+            (self.onset_threshold, &mut cfg.onset_threshold),
+            (self.bpm_min, &mut cfg.bpm_min),
+            (self.bpm_max, &mut cfg.bpm_max),
+            (self.tracking, &mut cfg.kalman_aggressiveness),
+            (self.prior_center, &mut cfg.prior_center_bpm),
+            (self.prior_sigma, &mut cfg.prior_sigma),
+        ];
+        for (value, field) in knobs {
+            if let Some(v) = value {
+                *field = v;
+            }
+        }
+        // The replay has no UI to publish an adapted prior to; hold the given centre.
+        cfg.auto_prior = false;
+        cfg
+    }
+}
+
+fn number(flag: &str, value: &str) -> Result<f32> {
+    value.parse().with_context(|| format!("{flag} {value}"))
+}
+
+/// How a replay compares with the live run.
+#[derive(Debug, Default)]
+pub struct ReplayReport {
+    frames: u64,
+    seconds: f64,
+    recorded_beats: Vec<f64>,
+    replayed_beats: Vec<f64>,
+    /// Frames where both runs had a tempo, and where those agreed.
+    tempo_frames: u64,
+    tempo_agreed: u64,
+    recorded_bpm_sum: f64,
+    replayed_bpm_sum: f64,
+}
+
+impl ReplayReport {
+    fn add(&mut self, timestamp: f64, recorded: &BeatResult, replayed: &BeatResult) {
+        self.frames += 1;
+        self.seconds = timestamp;
+        if recorded.beat > 0.5 {
+            self.recorded_beats.push(timestamp);
+        }
+        if replayed.beat > 0.5 {
+            self.replayed_beats.push(timestamp);
+        }
+        if recorded.bpm > 0.0 && replayed.bpm > 0.0 {
+            self.tempo_frames += 1;
+            self.recorded_bpm_sum += recorded.bpm as f64;
+            self.replayed_bpm_sum += replayed.bpm as f64;
+            if (replayed.bpm / recorded.bpm - 1.0).abs() <= BPM_AGREEMENT {
+                self.tempo_agreed += 1;
+            }
+        }
+    }
+
+    /// Beats both runs placed within `MATCH_WINDOW` of each other, each used once.
+    pub fn matched_beats(&self) -> usize {
+        let (a, b) = (&self.recorded_beats, &self.replayed_beats);
+        let (mut i, mut j, mut matched) = (0, 0, 0);
+        while i < a.len() && j < b.len() {
+            if (a[i] - b[j]).abs() <= MATCH_WINDOW {
+                matched += 1;
+                i += 1;
+                j += 1;
+            } else if a[i] < b[j] {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        matched
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:.1} s, {} frames", self.seconds, self.frames)?;
+        writeln!(
+            f,
+            "beats: {} recorded, {} replayed, {} matched within {:.0} ms",
+            self.recorded_beats.len(),
+            self.replayed_beats.len(),
+            self.matched_beats(),
+            MATCH_WINDOW * 1000.0
+        )?;
+        if self.tempo_frames == 0 {
+            return write!(f, "tempo: no frames where both runs had one");
+        }
+        let n = self.tempo_frames as f64;
+        write!(
+            f,
+            "tempo: {:.1} BPM recorded, {:.1} replayed, within {:.0}% on {:.1}% of frames",
+            self.recorded_bpm_sum / n,
+            self.replayed_bpm_sum / n,
+            BPM_AGREEMENT * 100.0,
+            self.tempo_agreed as f64 / n * 100.0
+        )
+    }
+}
+
+/// Replay a snapshot through a fresh detector.
+pub fn run_replay(opts: &ReplayOptions) -> Result<ReplayReport> {
+    let mut reader = SnapshotReader::open(&opts.path)?;
+    let header = reader.header.clone();
+    if header.hop != super::ANALYSIS_HOP {
+        bail!(
+            "snapshot was analysed with a {}-sample hop, this build uses {}",
+            header.hop,
+            super::ANALYSIS_HOP
+        );
+    }
+    let mut detector = BeatDetector::new(header.sample_rate, opts.tempo(header.tempo));
+    let mut csv = match &opts.csv {
+        Some(path) => {
+            let file =
+                File::create(path).with_context(|| format!("creating {}", path.display()))?;
+            let mut out = BufWriter::new(file);
+            writeln!(
+                out,
+                "seconds,silent,rec_onset,rec_beat,rec_bpm,new_onset,new_beat,new_bpm"
+            )?;
+            Some(out)
+        }
+        None => None,
+    };
+    let mut report = ReplayReport::default();
+    while let Some(frame) = reader.next_frame()? {
+        let replayed =
+            detector.process_log_bands(&frame.log_bands, frame.timestamp, frame.loud_silent);
+        report.add(frame.timestamp, &frame.beat, &replayed);
+        if let Some(out) = csv.as_mut() {
+            let rec = &frame.beat;
+            writeln!(
+                out,
+                "{:.4},{},{:.4},{},{:.2},{:.4},{},{:.2}",
+                frame.timestamp,
+                u8::from(frame.loud_silent),
+                rec.onset_strength,
+                u8::from(rec.beat > 0.5),
+                rec.bpm,
+                replayed.onset_strength,
+                u8::from(replayed.beat > 0.5),
+                replayed.bpm,
+            )?;
+        }
+    }
+    if let Some(mut out) = csv {
+        out.flush()?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(timestamp: f64, beat: bool) -> SnapshotFrame {
+        let mut raw = AudioFeatures {
+            bass: 0.25,
+            ..Default::default()
+        };
+        raw.mfcc[12] = -3.5;
+        let mut log_bands = [0.0; N_ONSET_BANDS];
+        log_bands[3] = -1.5;
+        SnapshotFrame {
+            timestamp,
+            loud_silent: !beat,
+            log_bands,
+            beat: BeatResult {
+                beat: f32::from(u8::from(beat)),
+                bpm: 128.0,
+                ..Default::default()
+            },
+            raw,
+        }
+    }
+
+    fn header() -> SnapshotHeader {
+        SnapshotHeader {
+            sample_rate: 48000.0,
+            hop: 512,
+            tempo: TempoPreset::Techno.config(),
+        }
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let mut bytes = Vec::new();
+        write_header(&mut bytes, &header()).unwrap();
+        frame(0.5, true).encode(&mut bytes);
+        frame(0.6, false).encode(&mut bytes);
+
+        let mut reader = SnapshotReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.header, header());
+        let a = reader.next_frame().unwrap().unwrap();
+        assert_eq!(a.timestamp, 0.5);
+        assert!(!a.loud_silent);
+        assert_eq!(a.log_bands, frame(0.5, true).log_bands);
+        assert_eq!(a.beat, frame(0.5, true).beat);
+        assert_eq!(a.raw.as_slice(), frame(0.5, true).raw.as_slice());
+        assert!(reader.next_frame().unwrap().unwrap().loud_silent);
+        assert!(reader.next_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn a_torn_last_frame_ends_the_snapshot() {
+        let mut bytes = Vec::new();
+        write_header(&mut bytes, &header()).unwrap();
+        frame(0.5, true).encode(&mut bytes);
+        frame(0.6, true).encode(&mut bytes);
+        bytes.truncate(bytes.len() - 10);
+        let mut reader = SnapshotReader::new(bytes.as_slice()).unwrap();
+        assert!(reader.next_frame().unwrap().is_some());
+        assert!(reader.next_frame().unwrap().is_none());
+        assert!(SnapshotReader::new(&b"not a snapshot file"[..]).is_err());
+    }
+
+    #[test]
+    fn beats_match_within_the_window() {
+        let mut report = ReplayReport::default();
+        let beat = |on: bool, bpm: f32| BeatResult {
+            beat: f32::from(u8::from(on)),
+            bpm,
+            ..Default::default()
+        };
+        for (t, rec, new) in [
+            (1.00, true, false),
+            (1.02, false, true),
+            (1.50, true, true),
+            (2.00, true, false),
+            (2.20, false, true),
+        ] {
+            report.add(
+                t,
+                &beat(rec, 120.0),
+                &beat(new, if new { 121.0 } else { 130.0 }),
+            );
+        }
+        assert_eq!(report.matched_beats(), 2);
+        assert_eq!(report.tempo_agreed, 3);
+        let text = report.to_string();
+        assert!(text.contains("3 recorded, 3 replayed, 2 matched"), "{text}");
+    }
+
+    #[test]
+    fn options_layer_preset_and_knobs_over_the_recording() {
+        let args: Vec<String> = [
+            "--replay-beats",
+            "set.phsnap",
+            "--preset",
+            "Techno",
+            "--bpm-max",
+            "150",
+        ]
+        .map(String::from)
+        .to_vec();
+        let opts = ReplayOptions::from_args(&args).unwrap();
+        assert_eq!(opts.path, PathBuf::from("set.phsnap"));
+        let cfg = opts.tempo(TempoConfig {
+            auto_prior: true,
+            ..TempoConfig::default()
+        });
+        assert_eq!(cfg.bpm_min, 120.0);
+        assert_eq!(cfg.bpm_max, 150.0);
+        assert!(!cfg.auto_prior);
+
+        let bare = ReplayOptions::from_args(&["--replay-beats".into(), "x".into()]).unwrap();
+        assert_eq!(bare.tempo(header().tempo), header().tempo);
+        assert!(ReplayOptions::from_args(&["--preset".into(), "polka".into()]).is_err());
+        assert!(ReplayOptions::from_args(&[]).is_err());
+    }
+}
@@ -0,0 +1,628 @@
+//! Benchmark mode (#2225).
+//!
+//! `phosphor --benchmark` renders every bundled effect headless at a fixed
+//! resolution for a few seconds each — the same deterministic clock, seed and
+//! synthetic audio as the golden images (#2175) — and reports the average,
+//! 99th-percentile and worst frame times plus the video memory each effect
+//! allocated. The report is written as markdown for reading and JSON for
+//! comparing: `--baseline <old.json>` adds the change against an earlier run
+//! and fails when any effect got slower than `--max-regression` allows, so two
+//! GPUs or two releases can be put side by side.
+//!
+//! Unlike the golden images, particle simulations run here: they are most of
+//! what a heavy effect costs. Frames are timed from submit until the GPU is
+//! done with them, one at a time, so the numbers are GPU time plus a little
+//! driver overhead rather than what a pipelined frame loop would show.
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::effect::EffectLoader;
+use crate::effect::format::PfxEffect;
+use crate::golden::{self, FPS, Headless, SEED};
+use crate::gpu::GpuContext;
+use crate::gpu::deterministic::{DeterministicParams, seed_uniform};
+use crate::gpu::pass_executor::PassExecutor;
+use crate::gpu::uniforms::{ShaderUniforms, UniformBuffer};
+use crate::params::ParamStore;
+
+const DEFAULT_SECONDS: f32 = 5.0;
+const DEFAULT_SIZE: (u32, u32) = (1920, 1080);
+/// Frames rendered before timing starts, so pipeline compilation and first
+/// allocations don't land in the numbers.
+const WARMUP_FRAMES: u32 = 30;
+/// Slowdown in average frame time, in percent, before a baseline comparison fails.
+const DEFAULT_MAX_REGRESSION: f32 = 10.0;
+
+/// Command-line options for `--benchmark`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkOptions {
+    /// Timed seconds per effect, after the warmup.
+    pub seconds: f32,
+    pub width: u32,
+    pub height: u32,
+    /// Only effects whose name or file contains this (case-insensitive).
+    pub only: Option<String>,
+    /// Include the user's own effects, not just the bundled ones.
+    pub user_effects: bool,
+    /// Markdown report; the JSON goes beside it.
+    pub out: PathBuf,
+    pub baseline: Option<PathBuf>,
+    pub max_regression: f32,
+}
+
+impl BenchmarkOptions {
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut opts = Self {
+            seconds: DEFAULT_SECONDS,
+            width: DEFAULT_SIZE.0,
+            height: DEFAULT_SIZE.1,
+            only: None,
+            user_effects: false,
+            out: PathBuf::from("phosphor-benchmark.md"),
+            baseline: None,
+            max_regression: DEFAULT_MAX_REGRESSION,
+        };
+        let mut it = args.iter();
+        while let Some(arg) = it.next() {
+            let mut value = |flag: &str| {
+                it.next()
+                    .cloned()
+                    .ok_or_else(|| anyhow!("{flag} needs a value"))
+            };
+            match arg.as_str() {
+                "--benchmark" => {}
+                "--seconds" => {
+                    let s: f32 = value(arg)?.parse().context("--seconds")?;
+                    opts.seconds = s.max(0.1);
+                }
+                "--resolution" => {
+                    let v = value(arg)?;
+                    (opts.width, opts.height) = parse_resolution(&v)
+                        .ok_or_else(|| anyhow!("--resolution wants WIDTHxHEIGHT, got '{v}'"))?;
+                }
+                "--only" => opts.only = Some(value(arg)?.to_lowercase()),
+                "--user-effects" => opts.user_effects = true,
+                "--out" => opts.out = PathBuf::from(value(arg)?),
+                "--baseline" => opts.baseline = Some(PathBuf::from(value(arg)?)),
+                "--max-regression" => {
+                    opts.max_regression = value(arg)?.parse().context("--max-regression")?;
+                }
+                other => bail!("unknown --benchmark option '{other}'"),
+            }
+        }
+        Ok(opts)
+    }
+}
+
+fn parse_resolution(s: &str) -> Option<(u32, u32)> {
+    let (w, h) = s.split_once(['x', 'X'])?;
+    let (w, h) = (w.trim().parse().ok()?, h.trim().parse().ok()?);
+    (w > 0 && h > 0 && w <= 8192 && h <= 8192).then_some((w, h))
+}
+
+/// Frame time statistics of one effect, in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FrameStats {
+    pub frames: u32,
+    pub avg_ms: f32,
+    pub p99_ms: f32,
+    pub max_ms: f32,
+}
+
+impl FrameStats {
+    fn from_times(times: &mut [f32]) -> Self {
+        if times.is_empty() {
+            return Self::default();
+        }
+        times.sort_by(f32::total_cmp);
+        let n = times.len();
+        // Nearest rank: the smallest time at least 99% of frames stay within.
+        let p99 = times[(n * 99).div_ceil(100) - 1];
+        Self {
+            frames: n as u32,
+            avg_ms: times.iter().sum::<f32>() / n as f32,
+            p99_ms: p99,
+            max_ms: times[n - 1],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EffectResult {
+    /// `.pfx` file stem, which stays put across renames.
+    pub key: String,
+    pub name: String,
+    /// Particle slots, for effects with a simulation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub particles: Option<u32>,
+    #[serde(default)]
+    pub stats: FrameStats,
+    /// Video memory the effect allocated, when the backend reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vram_mb: Option<f32>,
+    /// Why the effect didn't run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+}
+
+/// One benchmark run, as saved to JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub version: String,
+    pub adapter: String,
+    pub backend: String,
+    pub driver: String,
+    pub width: u32,
+    pub height: u32,
+    pub seconds: f32,
+    pub effects: Vec<EffectResult>,
+}
+
+impl BenchmarkReport {
+    /// Effects whose average frame time rose more than `max_pct` percent over
+    /// `baseline`, with the rise.
+    pub fn regressions<'a>(&'a self, baseline: &Self, max_pct: f32) -> Vec<(&'a str, f32)> {
+        self.effects
+            .iter()
+            .filter_map(|e| {
+                let pct = change_pct(e, baseline)?;
+                (pct > max_pct).then_some((e.key.as_str(), pct))
+            })
+            .collect()
+    }
+
+    pub fn to_markdown(&self, baseline: Option<&Self>) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Fosfora benchmark\n");
+        let _ = writeln!(
+            out,
+            "Fosfora {} · {} ({}, driver {}) · {}x{} · {}s per effect\n",
+            self.version,
+            self.adapter,
+            self.backend,
+            if self.driver.is_empty() {
+                "unknown"
+            } else {
+                &self.driver
+            },
+            self.width,
+            self.height,
+            self.seconds
+        );
+        if let Some(b) = baseline {
+            let _ = writeln!(
+                out,
+                "Compared with Fosfora {} on {} at {}x{}.\n",
+                b.version, b.adapter, b.width, b.height
+            );
+        }
+
+        let ran: Vec<&EffectResult> = self
+            .effects
+            .iter()
+            .filter(|e| e.skipped.is_none())
+            .collect();
+        if !ran.is_empty() {
+            let mean = ran.iter().map(|e| e.stats.avg_ms).sum::<f32>() / ran.len() as f32;
+            let worst = ran
+                .iter()
+                .max_by(|a, b| a.stats.p99_ms.total_cmp(&b.stats.p99_ms))
+                .map_or("", |e| e.name.as_str());
+            let _ = writeln!(
+                out,
+                "{} effects · mean of averages {mean:.2} ms · worst 99th percentile: {worst}\n",
+                ran.len()
+            );
+        }
+
+        out.push_str("| Effect | Avg ms | 99% ms | Max ms | FPS | VRAM MB |");
+        if baseline.is_some() {
+            out.push_str(" vs baseline |");
+        }
+        out.push_str("\n|---|---|---|---|---|---|");
+        if baseline.is_some() {
+            out.push_str("---|");
+        }
+        out.push('\n');
+        for e in &self.effects {
+            let name = match e.particles {
+                Some(n) => format!("{} ({n} particles)", e.name),
+                None => e.name.clone(),
+            }
+            .replace('|', "\\|");
+            if let Some(reason) = &e.skipped {
+                let _ = write!(
+                    out,
+                    "| {name} | skipped: {} | | | | |",
+                    reason.replace('|', "/")
+                );
+                if baseline.is_some() {
+                    out.push_str(" |");
+                }
+                out.push('\n');
+                continue;
+            }
+            let s = e.stats;
+            let fps = if s.avg_ms > 0.0 {
+                1000.0 / s.avg_ms
+            } else {
+                0.0
+            };
+            let vram = e
+                .vram_mb
+                .map_or_else(|| "n/a".to_string(), |mb| format!("{mb:.0}"));
+            let _ = write!(
+                out,
+                "| {name} | {:.2} | {:.2} | {:.2} | {fps:.0} | {vram} |",
+                s.avg_ms, s.p99_ms, s.max_ms
+            );
+            if let Some(b) = baseline {
+                match change_pct(e, b) {
+                    Some(pct) => {
+                        let _ = write!(out, " {pct:+.1}% |");
+                    }
+                    None => out.push_str(" new |"),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Percent change of `e`'s average frame time over the same effect in `baseline`.
+fn change_pct(e: &EffectResult, baseline: &BenchmarkReport) -> Option<f32> {
+    if e.skipped.is_some() {
+        return None;
+    }
+    let before = baseline
+        .effects
+        .iter()
+        .find(|b| b.key == e.key && b.skipped.is_none())?;
+    (before.stats.avg_ms > 0.0).then(|| (e.stats.avg_ms / before.stats.avg_ms - 1.0) * 100.0)
+}
+
+/// Run the benchmark and write the reports; `Ok(false)` when a baseline was
+/// given and an effect regressed past the limit.
+pub fn run(opts: &BenchmarkOptions) -> Result<bool> {
+    let baseline: Option<BenchmarkReport> = match &opts.baseline {
+        Some(path) => {
+            let json = std::fs::read_to_string(path)
+                .with_context(|| format!("reading {}", path.display()))?;
+            Some(
+                serde_json::from_str(&json)
+                    .with_context(|| format!("parsing {}", path.display()))?,
+            )
+        }
+        None => None,
+    };
+
+    let gpu = Headless::new()?;
+    let mut loader = EffectLoader::new();
+    loader.gpu_caps = Some(gpu.caps);
+    loader.scan_effects_directory();
+    let effects: Vec<&PfxEffect> = loader
+        .effects
+        .iter()
+        .filter(|e| opts.user_effects || EffectLoader::is_builtin(e))
+        .filter(|e| {
+            opts.only.as_ref().is_none_or(|f| {
+                e.name.to_lowercase().contains(f.as_str())
+                    || golden::effect_key(e).contains(f.as_str())
+            })
+        })
+        .collect();
+    if effects.is_empty() {
+        bail!("no effects matched");
+    }
+
+    println!(
+        "Benchmark: {} effects, {}x{}, {}s each",
+        effects.len(),
+        opts.width,
+        opts.height,
+        opts.seconds
+    );
+    let mut report = BenchmarkReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        adapter: gpu.info.name.clone(),
+        backend: format!("{:?}", gpu.info.backend),
+        driver: [gpu.info.driver.as_str(), gpu.info.driver_info.as_str()]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" "),
+        width: opts.width,
+        height: opts.height,
+        seconds: opts.seconds,
+        effects: Vec::new(),
+    };
+    for effect in &effects {
+        let key = golden::effect_key(effect);
+        let mut result = EffectResult {
+            key: key.clone(),
+            name: effect.name.clone(),
+            particles: None,
+            stats: FrameStats::default(),
+            vram_mb: None,
+            skipped: loader.unsupported_reason(effect),
+        };
+        if result.skipped.is_none()
+            && let Err(e) = bench_effect(&gpu, &loader, effect, opts, &mut result)
+        {
+            result.skipped = Some(format!("{e:#}"));
+        }
+        match &result.skipped {
+            Some(reason) => println!("  skipped  {key} — {reason}"),
+            None => println!(
+                "  {:>7.2} ms avg  {:>7.2} ms 99%  {key}",
+                result.stats.avg_ms, result.stats.p99_ms
+            ),
+        }
+        report.effects.push(result);
+    }
+
+    let markdown = report.to_markdown(baseline.as_ref());
+    std::fs::write(&opts.out, &markdown)
+        .with_context(|| format!("writing {}", opts.out.display()))?;
+    let json_path = opts.out.with_extension("json");
+    std::fs::write(&json_path, serde_json::to_string_pretty(&report)? + "\n")
+        .with_context(|| format!("writing {}", json_path.display()))?;
+    println!(
+        "Report: {} (and {})",
+        opts.out.display(),
+        json_path.display()
+    );
+
+    let Some(baseline) = &baseline else {
+        return Ok(true);
+    };
+    let regressions = report.regressions(baseline, opts.max_regression);
+    for (key, pct) in &regressions {
+        println!(
+            "  SLOWER   {key} — {pct:+.1}% (limit {:.1}%)",
+            opts.max_regression
+        );
+    }
+    Ok(regressions.is_empty())
+}
+
+/// Warm up and time one effect, filling in `result`.
+fn bench_effect(
+    gpu: &Headless,
+    loader: &EffectLoader,
+    effect: &PfxEffect,
+    opts: &BenchmarkOptions,
+    result: &mut EffectResult,
+) -> Result<()> {
+    let device = &gpu.device;
+    let passes = effect.normalized_passes();
+    if passes.is_empty() {
+        bail!("no shader or passes defined");
+    }
+    let vram_before = allocated_bytes(gpu);
+
+    let ubuf = UniformBuffer::new(device);
+    let mut executor = PassExecutor::new(
+        device,
+        GpuContext::hdr_format(),
+        opts.width,
+        opts.height,
+        &passes,
+        loader,
+        &ubuf,
+        &gpu.placeholder,
+        &gpu.audio,
+        &gpu.queue,
+        None,
+        None,
+    )
+    .map_err(|e| anyhow!(e))?;
+
+    if let Some(def) = &effect.particles {
+        let mut def = def.clone();
+        // The same cap the app applies for the device's binding limit.
+        let limit = (device.limits().max_storage_buffer_binding_size as u64 / (9 * 4)) as u32;
+        if def.max_count > limit {
+            def.emit_rate *= limit as f32 / def.max_count as f32;
+            def.max_count = limit;
+        }
+        let mut ps = crate::app::new_particle_system(device, &gpu.queue, loader, &def)
+            .ok_or_else(|| anyhow!("particle system failed to build"))?;
+        ps.resize_compute_raster(device, opts.width, opts.height);
+        ps.resize_wboit(device, opts.width, opts.height);
+        result.particles = Some(def.max_count);
+        executor.particle_system = Some(ps);
+    }
+
+    let mut store = ParamStore::new();
+    store.load_from_defs(&effect.inputs);
+    let clock = DeterministicParams {
+        enabled: true,
+        seed: SEED,
+        fps: FPS,
+    };
+    let resolution = [opts.width as f32, opts.height as f32];
+    let mut u = ShaderUniforms::zeroed();
+    u.resolution = resolution;
+    u.feedback_decay = 0.88;
+    u.seed = seed_uniform(clock.seed);
+    u.params = store.pack_to_buffer();
+    u.delta_time = clock.step();
+
+    let budget = Duration::from_secs_f32(opts.seconds);
+    let mut times: Vec<f32> = Vec::new();
+    let mut vram_peak = None;
+    let mut timed_since: Option<Instant> = None;
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    for frame in 0.. {
+        if frame == WARMUP_FRAMES {
+            timed_since = Some(Instant::now());
+        }
+        if timed_since.is_some_and(|t| t.elapsed() >= budget) {
+            break;
+        }
+        u.time = clock.frame_time(frame);
+        u.frame_index = frame as f32;
+        golden::synthetic_audio(&mut u, frame);
+        if let Some(ps) = executor.particle_system.as_mut() {
+            ps.update_uniforms(u.delta_time, u.time, resolution, u.beat);
+            ps.uniforms.seed = (ps.uniforms.seed + u.seed) % 65536.0;
+        }
+
+        let start = Instant::now();
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("benchmark-frame"),
+        });
+        executor.execute(&mut encoder, &ubuf, &gpu.queue, &u);
+        gpu.queue.submit([encoder.finish()]);
+        gpu.wait();
+        let ms = start.elapsed().as_secs_f32() * 1000.0;
+        executor.flip();
+
+        if timed_since.is_some() {
+            times.push(ms);
+        }
+        if frame == WARMUP_FRAMES {
+            vram_peak = allocated_bytes(gpu);
+        }
+    }
+    if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+        bail!("{error}");
+    }
+
+    let vram_after = allocated_bytes(gpu).max(vram_peak);
+    result.vram_mb = vram_before
+        .zip(vram_after)
+        .map(|(before, after)| after.saturating_sub(before) as f32 / (1024.0 * 1024.0));
+    result.stats = FrameStats::from_times(&mut times);
+    Ok(())
+}
+
+/// Bytes the device has allocated, on backends whose allocator reports it
+/// (Vulkan and DX12; not Metal or GL).
+fn allocated_bytes(gpu: &Headless) -> Option<u64> {
+    gpu.device
+        .generate_allocator_report()
+        .map(|r| r.total_allocated_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    fn result(key: &str, avg_ms: f32) -> EffectResult {
+        EffectResult {
+            key: key.to_string(),
+            name: key.to_string(),
+            particles: None,
+            stats: FrameStats {
+                frames: 100,
+                avg_ms,
+                p99_ms: avg_ms * 2.0,
+                max_ms: avg_ms * 3.0,
+            },
+            vram_mb: None,
+            skipped: None,
+        }
+    }
+
+    fn report(effects: Vec<EffectResult>) -> BenchmarkReport {
+        BenchmarkReport {
+            version: "1.0.0".to_string(),
+            adapter: "Test GPU".to_string(),
+            backend: "Vulkan".to_string(),
+            driver: String::new(),
+            width: 1920,
+            height: 1080,
+            seconds: 5.0,
+            effects,
+        }
+    }
+
+    #[test]
+    fn options_parse() {
+        let o = BenchmarkOptions::from_args(&args(
+            "--benchmark --seconds 2 --resolution 1280x720 --only Aurora --out /tmp/b.md \
+             --baseline old.json --max-regression 5",
+        ))
+        .unwrap();
+        assert_eq!(o.seconds, 2.0);
+        assert_eq!((o.width, o.height), (1280, 720));
+        assert_eq!(o.only.as_deref(), Some("aurora"));
+        assert_eq!(o.out, PathBuf::from("/tmp/b.md"));
+        assert_eq!(o.baseline, Some(PathBuf::from("old.json")));
+        assert_eq!(o.max_regression, 5.0);
+
+        let d = BenchmarkOptions::from_args(&args("--benchmark")).unwrap();
+        assert_eq!((d.width, d.height), DEFAULT_SIZE);
+        assert!(!d.user_effects);
+        assert!(BenchmarkOptions::from_args(&args("--benchmark --resolution 1920")).is_err());
+        assert!(BenchmarkOptions::from_args(&args("--benchmark --resolution 0x10")).is_err());
+        assert!(BenchmarkOptions::from_args(&args("--benchmark --fast")).is_err());
+    }
+
+    #[test]
+    fn stats_use_nearest_rank_percentile() {
+        let mut times: Vec<f32> = (1..=200).map(|i| i as f32).rev().collect();
+        let s = FrameStats::from_times(&mut times);
+        assert_eq!(s.frames, 200);
+        assert_eq!(s.avg_ms, 100.5);
+        assert_eq!(s.p99_ms, 198.0);
+        assert_eq!(s.max_ms, 200.0);
+
+        let mut one = [4.0];
+        assert_eq!(FrameStats::from_times(&mut one).p99_ms, 4.0);
+        assert_eq!(FrameStats::from_times(&mut []), FrameStats::default());
+    }
+
+    #[test]
+    fn baseline_flags_slower_effects() {
+        let before = report(vec![result("aurora", 2.0), result("ember", 4.0)]);
+        let now = report(vec![
+            result("aurora", 2.1),
+            result("ember", 5.0),
+            result("nebula", 1.0),
+        ]);
+        let slower = now.regressions(&before, 10.0);
+        assert_eq!(slower.len(), 1);
+        assert_eq!(slower[0].0, "ember");
+        assert!((slower[0].1 - 25.0).abs() < 1e-3);
+
+        let md = now.to_markdown(Some(&before));
+        assert!(
+            md.contains("| ember | 5.00 | 10.00 | 15.00 | 200 | n/a | +25.0% |"),
+            "{md}"
+        );
+        assert!(
+            md.contains("| nebula | 1.00 | 2.00 | 3.00 | 1000 | n/a | new |"),
+            "{md}"
+        );
+    }
+
+    #[test]
+    fn report_round_trips_through_json() {
+        let mut r = report(vec![result("aurora", 2.0)]);
+        r.effects[0].vram_mb = Some(64.0);
+        r.effects.push(EffectResult {
+            skipped: Some("Needs compute shaders".to_string()),
+            ..result("swarm", 0.0)
+        });
+        let json = serde_json::to_string(&r).unwrap();
+        assert_eq!(serde_json::from_str::<BenchmarkReport>(&json).unwrap(), r);
+        assert!(
+            r.to_markdown(None)
+                .contains("| swarm | skipped: Needs compute shaders |")
+        );
+    }
+}
@@ -11,9 +11,7 @@ pub struct BindingsFile {
 }
 
 fn config_dir() -> PathBuf {
-    dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("phosphor")
+    crate::paths::config_dir()
 }
 
 fn global_path() -> PathBuf {
@@ -79,26 +77,13 @@ fn save_to_path(_path: &PathBuf, _bindings: &[Binding]) {}
 
 #[cfg(not(test))]
 fn save_to_path(path: &PathBuf, bindings: &[Binding]) {
-    if let Some(parent) = path.parent() {
-        if let Err(e) = std::fs::create_dir_all(parent) {
-            log::error!("Failed to create bindings dir: {e}");
-            return;
-        }
-    }
-
     let file = BindingsFile {
         version: 1,
         bindings: bindings.to_vec(),
     };
 
     match serde_json::to_string_pretty(&file) {
-        Ok(json) => {
-            if let Err(e) = std::fs::write(path, json) {
-                log::error!("Failed to write bindings: {e}");
-            } else {
-                log::debug!("Saved {} bindings to {}", bindings.len(), path.display());
-            }
-        }
+        Ok(json) => crate::persist::write(path.clone(), json),
         Err(e) => log::error!("Failed to serialize bindings: {e}"),
     }
 }
@@ -133,6 +133,17 @@ pub fn collect_audio(features: &AudioFeatures) -> SourceSnapshot {
         map.insert(key.to_string(), (val, raw(val)));
     }
 
+    // Dynamics (#2218). `impact` rises to 1 and decays, so a binding to a scene trigger
+    // fires once per impact.
+    map.insert(
+        "audio.crest".to_string(),
+        (features.crest, raw(features.crest)),
+    );
+    map.insert(
+        "audio.impact".to_string(),
+        (features.impact, raw(features.impact)),
+    );
+
     map
 }
 
@@ -258,8 +269,9 @@ mod tests {
     fn test_collect_audio() {
         let features = AudioFeatures::default();
         let snap = collect_audio(&features);
-        // 7 bands + 13 scalars + 13 mfcc + 12 chroma + 1 dominant + 1 key_hue + 28 reserved = 75
-        assert_eq!(snap.len(), 75);
+        // 7 bands + 13 scalars + 13 mfcc + 12 chroma + 1 dominant + 1 key_hue + 28 reserved
+        // + 2 dynamics = 77
+        assert_eq!(snap.len(), 77);
         assert!(snap.contains_key("audio.kick"));
         assert!(snap.contains_key("audio.band.0"));
         assert!(snap.contains_key("audio.mfcc.12"));
@@ -278,6 +290,9 @@ mod tests {
         assert!(snap.contains_key("audio.pitch"));
         assert!(snap.contains_key("audio.contrast_0"));
         assert!(snap.contains_key("audio.timbre_flux"));
+        // Dynamics (#2218)
+        assert!(snap.contains_key("audio.crest"));
+        assert!(snap.contains_key("audio.impact"));
     }
 
     #[test]
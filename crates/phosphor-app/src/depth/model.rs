@@ -81,8 +81,7 @@ pub fn ort_available() -> bool {
 
 /// Returns the directory where models and runtime are stored.
 pub fn model_dir() -> PathBuf {
-    let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-    config_dir.join("phosphor").join("models")
+    crate::paths::config_dir().join("models")
 }
 
 /// Returns the full path to the MiDaS model file.
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
@@ -22,10 +23,14 @@ pub enum EffectType {
 pub struct PassDef {
     pub name: String,
     pub shader: String,
+    /// Render target size relative to the layer, e.g. 0.5 for a blur buffer.
+    /// Clamped to [`MIN_PASS_SCALE`]..=[`MAX_PASS_SCALE`] by [`PassDef::target_scale`].
     #[serde(default = "default_scale")]
     pub scale: f32,
     /// Names of earlier passes whose **current-frame** outputs this pass samples as
     /// `input0..` (in declared order). Forward/unknown references are a hard error.
+    /// An entry may instead name another layer (`"layer:N"` / `"media"`, see
+    /// [`LayerSource`]) so the effect can process what is beneath it.
     #[serde(default)]
     pub inputs: Vec<String>,
     /// Names of feedback passes whose **previous-frame** outputs this pass samples,
@@ -44,6 +49,74 @@ pub struct PassDef {
     /// Defaults to true (matches legacy single-shader behavior); set false to disable.
     #[serde(default = "default_true")]
     pub feedback: bool,
+    /// Values for this pass's WGSL `override` constants, by name, so one shader
+    /// can serve several passes (a blur's direction and radius, say) without
+    /// spending uniforms on them (#2193). Applied when the pipeline is built.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub constants: BTreeMap<String, f64>,
+    /// Named buffer this pass renders into (#2194); defaults to the pass's own
+    /// name. Passes sharing a buffer write it in turn, and `inputs` naming the
+    /// buffer read its latest write.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Keep the buffer's contents across frames. Each writer's `feedback(uv)`
+    /// then reads the buffer as the previous writer left it (last frame's final
+    /// write, for the first writer), and a pass reading the buffer before any
+    /// writer has run this frame gets last frame's. Implies `feedback`.
+    #[serde(default)]
+    pub persistent: bool,
+}
+
+/// Smallest and largest [`PassDef::scale`] a pass target is built at.
+pub const MIN_PASS_SCALE: f32 = 1.0 / 16.0;
+pub const MAX_PASS_SCALE: f32 = 2.0;
+
+impl PassDef {
+    /// `scale`, clamped to a sane range; a non-finite scale falls back to 1.
+    pub fn target_scale(&self) -> f32 {
+        if self.scale.is_finite() {
+            self.scale.clamp(MIN_PASS_SCALE, MAX_PASS_SCALE)
+        } else {
+            1.0
+        }
+    }
+
+    /// Name of the buffer this pass writes: `target`, or the pass name.
+    pub fn buffer(&self) -> &str {
+        self.target.as_deref().unwrap_or(&self.name)
+    }
+
+    /// `constants` in the form wgpu's pipeline compilation options take.
+    pub fn pipeline_constants(&self) -> Vec<(&str, f64)> {
+        self.constants
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+            .collect()
+    }
+}
+
+/// A pass input that samples another layer's output rather than a pass of this
+/// effect. Parsed from a `PassDef.inputs` entry; pass names take precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerSource {
+    /// `"layer:N"`: the layer at position N in the layer list, top = 1. Stored 0-based.
+    Layer(usize),
+    /// `"media"`: the nearest enabled media layer beneath the consuming layer.
+    Media,
+    /// `"flow"`: the motion field of the tracked media layer (see `gpu::motion`).
+    Flow,
+}
+
+impl LayerSource {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "media" => return Some(LayerSource::Media),
+            "flow" => return Some(LayerSource::Flow),
+            _ => {}
+        }
+        let n: usize = name.strip_prefix("layer:")?.trim().parse().ok()?;
+        n.checked_sub(1).map(LayerSource::Layer)
+    }
 }
 
 fn default_scale() -> f32 {
@@ -77,10 +150,29 @@ pub struct PostProcessDef {
     pub vignette_enabled: bool,
     #[serde(default = "default_true")]
     pub grain_enabled: bool,
-    /// Tonemap operator: "aces" (default, Phosphor house look) or "linear"
-    /// (raw passthrough clamp, matching SuperSplat for the Splat effect).
+    /// Tonemap operator: "aces" (default, Phosphor house look), "reinhard",
+    /// "hable" or "linear" (raw passthrough clamp, matching SuperSplat for the
+    /// Splat effect). See [`TONEMAPPERS`].
     #[serde(default = "default_tonemap")]
     pub tonemap: String,
+    /// Exposure in stops, applied before the tonemapper (#2235).
+    #[serde(default)]
+    pub exposure: f32,
+    /// HDR level the tonemapper maps to full white (#2235). Ignored by "linear".
+    #[serde(default = "default_white_point")]
+    pub white_point: f32,
+}
+
+/// Tonemap operators by `PostProcessDef::tonemap` name, with display names.
+pub const TONEMAPPERS: &[(&str, &str)] = &[
+    ("aces", "ACES"),
+    ("reinhard", "Reinhard"),
+    ("hable", "Hable"),
+    ("linear", "Linear"),
+];
+
+fn default_white_point() -> f32 {
+    11.2
 }
 
 fn default_true() -> bool {
@@ -121,15 +213,83 @@ impl Default for PostProcessDef {
             vignette_enabled: true,
             grain_enabled: true,
             tonemap: "aces".to_string(),
+            exposure: 0.0,
+            white_point: default_white_point(),
+        }
+    }
+}
+
+impl PostProcessDef {
+    /// Set a setting by field name, as remote control surfaces address them
+    /// (#2196). Amounts are clamped to the post-processing panel's ranges and
+    /// switches are on above 0.5. Returns false for an unknown name.
+    pub fn set(&mut self, name: &str, value: f32) -> bool {
+        let on = value > 0.5;
+        match name {
+            "enabled" => self.enabled = on,
+            "bloom_enabled" => self.bloom_enabled = on,
+            "ca_enabled" => self.ca_enabled = on,
+            "vignette_enabled" => self.vignette_enabled = on,
+            "grain_enabled" => self.grain_enabled = on,
+            "bloom_threshold" => self.bloom_threshold = value.clamp(0.0, 1.5),
+            "bloom_intensity" => self.bloom_intensity = value.clamp(0.0, 1.0),
+            "vignette" => self.vignette = value.clamp(0.0, 1.0),
+            "ca_intensity" => self.ca_intensity = value.clamp(0.0, 1.0),
+            "grain_intensity" => self.grain_intensity = value.clamp(0.0, 1.0),
+            "exposure" => self.exposure = value.clamp(-4.0, 4.0),
+            "white_point" => self.white_point = value.clamp(1.0, 20.0),
+            _ => return false,
         }
+        true
     }
 }
 
 /// Describes which audio feature drives which visual aspect of an effect.
+/// With `param` set the mapping also drives that param at runtime (#2186);
+/// without it the mapping only documents what the shader does.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AudioMapping {
     pub feature: String,
+    #[serde(default)]
     pub target: String,
+    /// Float param the feature is added to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub param: Option<String>,
+    /// Share of the param's range a full-scale feature adds (-1..1).
+    #[serde(default = "default_scale", skip_serializing_if = "is_one")]
+    pub amount: f32,
+    /// Envelope rise time in seconds (#2187); 0 = follow rises instantly.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub attack: f32,
+    /// Envelope fall time in seconds; a slow release turns hits into swells.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub release: f32,
+}
+
+impl AudioMapping {
+    /// A mapping that drives `param` from `feature` at full amount.
+    pub fn new(feature: &str, param: &str) -> Self {
+        Self {
+            feature: feature.to_string(),
+            target: String::new(),
+            param: Some(param.to_string()),
+            amount: 1.0,
+            attack: 0.0,
+            release: 0.0,
+        }
+    }
+}
+
+fn is_one(v: &f32) -> bool {
+    *v == 1.0
+}
+
+fn is_zero(v: &f32) -> bool {
+    *v == 0.0
+}
+
+fn is_false(v: &bool) -> bool {
+    !*v
 }
 
 /// A .pfx effect definition (JSON format).
@@ -154,22 +314,62 @@ pub struct PfxEffect {
     /// GPU particle system definition.
     #[serde(default)]
     pub particles: Option<ParticleDef>,
-    /// Audio feature → visual target mappings (read-only display in UI).
+    /// Audio feature → visual target mappings. Editable in the UI (#2186).
     #[serde(default)]
     pub audio_mappings: Vec<AudioMapping>,
     /// If true, effect is hidden from UI (not shown in effects panel or next/prev cycling).
     #[serde(default)]
     pub hidden: bool,
+    /// The final pass always writes alpha 1 (#2209), so a layer showing the
+    /// effect in full with Normal blend hides every layer beneath it, and
+    /// those aren't rendered.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub opaque: bool,
     /// Explicit effect type override (shader/particle/feedback).
     /// If absent, auto-detected: no particles → Shader, particles → Particle.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub effect_type: Option<EffectType>,
+    /// GPU features the effect needs (#2195). Effects the GPU can't run are
+    /// marked in the UI instead of failing at pipeline creation.
+    #[serde(default, skip_serializing_if = "EffectRequirements::is_empty")]
+    pub requires: EffectRequirements,
+    /// Free-form labels, e.g. "background" or "glitch", that the scene
+    /// randomizer (#2251) draws each layer's effect from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     /// Path to the .pfx file on disk (not serialized).
     #[serde(skip)]
     pub source_path: Option<PathBuf>,
 }
 
+/// GPU features an effect declares it needs (#2195).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EffectRequirements {
+    pub compute: bool,
+    pub storage_textures: bool,
+    /// Filtered sampling of 32-bit float textures.
+    pub float_filtering: bool,
+    /// Video memory in MB, checked where the driver reports it.
+    pub min_vram_mb: u32,
+}
+
+impl EffectRequirements {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 impl PfxEffect {
+    /// `requires`, plus what the effect needs without saying: particles run
+    /// in compute shaders.
+    pub fn requirements(&self) -> EffectRequirements {
+        EffectRequirements {
+            compute: self.requires.compute || self.particles.is_some(),
+            ..self.requires.clone()
+        }
+    }
+
     /// Returns the effect type: explicit if set, otherwise auto-detected.
     pub fn effect_type(&self) -> EffectType {
         if let Some(et) = self.effect_type {
@@ -182,6 +382,17 @@ impl PfxEffect {
         }
     }
 
+    /// True if `tag` is one of the effect's tags or its type ("shader",
+    /// "particle", "feedback"), ignoring case.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let kind = match self.effect_type() {
+            EffectType::Shader => "shader",
+            EffectType::Particle => "particle",
+            EffectType::Feedback => "feedback",
+        };
+        kind.eq_ignore_ascii_case(tag) || self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
     /// Normalize: if `passes` is empty but `shader` is set, create a single-pass definition.
     /// Single-pass effects get feedback enabled by default (matches legacy behavior).
     pub fn normalized_passes(&self) -> Vec<PassDef> {
@@ -197,6 +408,9 @@ impl PfxEffect {
                 prev_inputs: vec![],
                 iterations: 1,
                 feedback: true,
+                constants: BTreeMap::new(),
+                target: None,
+                persistent: false,
             }]
         } else {
             vec![]
@@ -210,7 +424,9 @@ impl PfxEffect {
                 || self.author != other.author
                 || self.description != other.description
                 || self.hidden != other.hidden
-                || self.audio_mappings != other.audio_mappings,
+                || self.opaque != other.opaque
+                || self.audio_mappings != other.audio_mappings
+                || self.requires != other.requires,
             inputs_changed: self.inputs != other.inputs,
             postprocess_changed: self.postprocess != other.postprocess,
             passes_changed: self.normalized_passes() != other.normalized_passes(),
@@ -253,6 +469,17 @@ mod tests {
         (a - b).abs() < eps
     }
 
+    #[test]
+    fn layer_source_parse() {
+        assert_eq!(LayerSource::parse("media"), Some(LayerSource::Media));
+        assert_eq!(LayerSource::parse("flow"), Some(LayerSource::Flow));
+        assert_eq!(LayerSource::parse("layer:1"), Some(LayerSource::Layer(0)));
+        assert_eq!(LayerSource::parse("layer:3"), Some(LayerSource::Layer(2)));
+        assert_eq!(LayerSource::parse("layer:0"), None);
+        assert_eq!(LayerSource::parse("layer:x"), None);
+        assert_eq!(LayerSource::parse("bg"), None);
+    }
+
     #[test]
     fn normalized_passes_from_single_shader() {
         let effect = PfxEffect {
@@ -266,7 +493,10 @@ mod tests {
             particles: None,
             audio_mappings: vec![],
             hidden: false,
+            opaque: false,
             effect_type: None,
+            requires: EffectRequirements::default(),
+            tags: vec![],
             source_path: None,
         };
         let passes = effect.normalized_passes();
@@ -290,7 +520,10 @@ mod tests {
             particles: None,
             audio_mappings: vec![],
             hidden: false,
+            opaque: false,
             effect_type: None,
+            requires: EffectRequirements::default(),
+            tags: vec![],
             source_path: None,
         };
         assert!(effect.normalized_passes().is_empty());
@@ -306,6 +539,9 @@ mod tests {
             prev_inputs: vec![],
             iterations: 1,
             feedback: false,
+            constants: BTreeMap::new(),
+            target: None,
+            persistent: false,
         };
         let effect = PfxEffect {
             name: "test".into(),
@@ -318,7 +554,10 @@ mod tests {
             particles: None,
             audio_mappings: vec![],
             hidden: false,
+            opaque: false,
             effect_type: None,
+            requires: EffectRequirements::default(),
+            tags: vec![],
             source_path: None,
         };
         let passes = effect.normalized_passes();
@@ -349,6 +588,58 @@ mod tests {
         assert!(effect.particles.is_none());
     }
 
+    #[test]
+    fn postprocess_set_by_name_clamps() {
+        let mut pp = PostProcessDef::default();
+        assert!(pp.set("bloom_threshold", 3.0));
+        assert!(pp.set("vignette", -1.0));
+        assert!(pp.set("grain_enabled", 0.0));
+        assert!(!pp.set("tonemap", 1.0));
+        assert!((pp.bloom_threshold - 1.5).abs() < f32::EPSILON);
+        assert!(pp.vignette.abs() < f32::EPSILON);
+        assert!(!pp.grain_enabled);
+        assert_eq!(pp.tonemap, "aces");
+    }
+
+    #[test]
+    fn exposure_and_white_point_default_for_old_files() {
+        let pp: PostProcessDef = serde_json::from_str(r#"{"tonemap":"linear"}"#).unwrap();
+        assert_eq!(pp.exposure, 0.0);
+        assert_eq!(pp.white_point, PostProcessDef::default().white_point);
+        let mut pp = pp;
+        assert!(pp.set("exposure", 9.0));
+        assert!(pp.set("white_point", 0.0));
+        assert_eq!((pp.exposure, pp.white_point), (4.0, 1.0));
+    }
+
+    #[test]
+    fn requirements_parse_and_particles_imply_compute() {
+        let plain: PfxEffect = serde_json::from_str(r#"{"name":"a","shader":"a.wgsl"}"#).unwrap();
+        assert!(plain.requirements().is_empty());
+        assert!(!serde_json::to_string(&plain).unwrap().contains("requires"));
+
+        let heavy: PfxEffect = serde_json::from_str(
+            r#"{"name":"b","shader":"b.wgsl","requires":{"float_filtering":true,"min_vram_mb":2048},
+                "particles":{"max_count":1000}}"#,
+        )
+        .unwrap();
+        let req = heavy.requirements();
+        assert!(req.compute && req.float_filtering && !req.storage_textures);
+        assert_eq!(req.min_vram_mb, 2048);
+    }
+
+    #[test]
+    fn opaque_is_off_unless_declared() {
+        let plain: PfxEffect = serde_json::from_str(r#"{"name":"a","shader":"a.wgsl"}"#).unwrap();
+        assert!(!plain.opaque);
+        assert!(!serde_json::to_string(&plain).unwrap().contains("opaque"));
+
+        let solid: PfxEffect =
+            serde_json::from_str(r#"{"name":"b","shader":"b.wgsl","opaque":true}"#).unwrap();
+        assert!(solid.opaque);
+        assert!(plain.diff(&solid).metadata_changed);
+    }
+
     #[test]
     fn pfx_effect_serde_with_passes() {
         let json = r#"{"name":"multi","shader":"","passes":[{"name":"p1","shader":"a.wgsl"},{"name":"p2","shader":"b.wgsl","feedback":false}]}"#;
@@ -367,6 +658,28 @@ mod tests {
         assert!(pass.inputs.is_empty());
         assert!(pass.prev_inputs.is_empty());
         assert_eq!(pass.iterations, 1);
+        assert!(pass.constants.is_empty());
+        assert_eq!(pass.buffer(), "test");
+        assert!(!pass.persistent);
+    }
+
+    #[test]
+    fn pass_def_constants_and_scale_clamp() {
+        let json = r#"{"name":"blur_h","shader":"blur.wgsl","scale":0.01,
+            "constants":{"RADIUS":6,"HORIZONTAL":1}}"#;
+        let pass: PassDef = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            pass.pipeline_constants(),
+            vec![("HORIZONTAL", 1.0), ("RADIUS", 6.0)]
+        );
+        assert!(approx_eq(pass.target_scale(), MIN_PASS_SCALE, 1e-6));
+        // Changing a constant rebuilds the pipeline like any other pass change.
+        let mut other = pass.clone();
+        other.constants.insert("RADIUS".into(), 3.0);
+        assert_ne!(pass, other);
+        // An empty map stays out of saved files.
+        let plain: PassDef = serde_json::from_str(r#"{"name":"a","shader":"a.wgsl"}"#).unwrap();
+        assert!(!serde_json::to_string(&plain).unwrap().contains("constants"));
     }
 
     #[test]
@@ -414,11 +727,44 @@ mod tests {
             particles: None,
             audio_mappings: vec![],
             hidden: false,
+            opaque: false,
             effect_type: None,
+            requires: EffectRequirements::default(),
+            tags: vec![],
             source_path: None,
         }
     }
 
+    #[test]
+    fn has_tag_matches_tags_and_type() {
+        let mut effect = make_effect("Tagged", "main.wgsl");
+        effect.tags = vec!["Background".into()];
+        assert!(effect.has_tag("background"));
+        assert!(effect.has_tag("Shader"));
+        assert!(!effect.has_tag("particle"));
+        // Untagged effects don't write the field
+        let json = serde_json::to_string(&make_effect("Plain", "main.wgsl")).unwrap();
+        assert!(!json.contains("tags"));
+    }
+
+    #[test]
+    fn audio_mapping_defaults_keep_old_files_unchanged() {
+        let m: AudioMapping =
+            serde_json::from_str(r#"{ "feature": "bass", "target": "zoom pulse" }"#).unwrap();
+        assert_eq!(m.param, None);
+        assert_eq!(m.amount, 1.0);
+        assert_eq!((m.attack, m.release), (0.0, 0.0));
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(json, r#"{"feature":"bass","target":"zoom pulse"}"#);
+
+        let mut driven = AudioMapping::new("kick", "warp");
+        driven.amount = 0.5;
+        driven.release = 0.8;
+        let back: AudioMapping =
+            serde_json::from_str(&serde_json::to_string(&driven).unwrap()).unwrap();
+        assert_eq!(back, driven);
+    }
+
     #[test]
     fn diff_identical_effects_is_empty() {
         let a = make_effect("test", "t.wgsl");
@@ -449,6 +795,9 @@ mod tests {
             default: 0.5,
             min: 0.0,
             max: 1.0,
+            group: None,
+            description: None,
+            unit: None,
         }];
         let diff = a.diff(&b);
         assert!(!diff.metadata_changed);
@@ -495,6 +844,8 @@ mod tests {
         b.inputs = vec![ParamDef::Bool {
             name: "flag".into(),
             default: false,
+            group: None,
+            description: None,
         }];
         let diff = a.diff(&b);
         assert!(diff.metadata_changed);
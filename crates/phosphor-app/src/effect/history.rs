@@ -0,0 +1,280 @@
+//! Shader version history (#2189): a rolling set of timestamped snapshots
+//! per shader file, taken on every editor save and every successful
+//! compile, so a destructive live edit can be diffed and undone.
+//!
+//! Snapshots live under `<config>/shader-history/<shader file name>/` as
+//! `<unix millis>-saved.wgsl` or `<unix millis>-compiled.wgsl`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Snapshots kept per shader; the oldest go first.
+pub const MAX_SNAPSHOTS: usize = 40;
+
+/// Above this many lines on either side (the table grows with the product),
+/// [`line_diff`] shows a whole-file replacement instead of building its table.
+const MAX_DIFF_LINES: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    /// Unix time in milliseconds.
+    pub taken_ms: u64,
+    /// True once this source is known to compile.
+    pub compiled: bool,
+}
+
+impl Snapshot {
+    fn parse(path: PathBuf) -> Option<Self> {
+        let stem = path.file_stem()?.to_str()?;
+        let (millis, kind) = stem.split_once('-')?;
+        let compiled = match kind {
+            "compiled" => true,
+            "saved" => false,
+            _ => return None,
+        };
+        Some(Self {
+            taken_ms: millis.parse().ok()?,
+            compiled,
+            path,
+        })
+    }
+
+    pub fn read(&self) -> std::io::Result<String> {
+        std::fs::read_to_string(&self.path)
+    }
+}
+
+/// Snapshot store rooted at one directory. A shader's folder is read the
+/// first time it's asked about; after that its list is kept here and
+/// changes reach disk through the file writer, off the render thread.
+#[derive(Debug, Clone)]
+pub struct ShaderHistory {
+    root: PathBuf,
+    /// Per shader folder: its snapshots, newest first, and the newest one's
+    /// content.
+    known: HashMap<PathBuf, (Vec<Snapshot>, Option<String>)>,
+}
+
+impl Default for ShaderHistory {
+    fn default() -> Self {
+        Self::new(crate::paths::config_dir().join("shader-history"))
+    }
+}
+
+impl ShaderHistory {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            known: HashMap::new(),
+        }
+    }
+
+    fn dir_for(&self, shader: &Path) -> PathBuf {
+        let name = shader
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        self.root.join(name)
+    }
+
+    fn known(&mut self, shader: &Path) -> (PathBuf, &mut (Vec<Snapshot>, Option<String>)) {
+        let dir = self.dir_for(shader);
+        let known = self.known.entry(dir.clone()).or_insert_with(|| scan(&dir));
+        (dir, known)
+    }
+
+    /// Snapshots of `shader`, newest first.
+    pub fn list(&mut self, shader: &Path) -> Vec<Snapshot> {
+        self.known(shader).1.0.clone()
+    }
+
+    /// Snapshot `content`. Identical to the newest snapshot, it only upgrades
+    /// that one to compiled rather than adding a copy. Trims to
+    /// [`MAX_SNAPSHOTS`]. Nothing is recorded while another instance owns
+    /// the config dir (#2224), so the list never shows unwritten snapshots.
+    pub fn record(&mut self, shader: &Path, content: &str, compiled: bool) {
+        if crate::persist::read_only() {
+            return;
+        }
+        let (dir, (snaps, newest)) = self.known(shader);
+        if newest.as_deref() == Some(content) {
+            if let Some(top) = snaps.first_mut()
+                && compiled
+                && !top.compiled
+            {
+                let path = dir.join(snapshot_name(top.taken_ms, true));
+                let from = std::mem::replace(&mut top.path, path.clone());
+                top.compiled = true;
+                crate::persist::replace(path, content, from);
+            }
+            return;
+        }
+        // Never reuse the newest timestamp, so two quick saves stay ordered.
+        let now = now_ms().max(snaps.first().map_or(0, |s| s.taken_ms + 1));
+        let path = dir.join(snapshot_name(now, compiled));
+        crate::persist::write(path.clone(), content);
+        snaps.insert(
+            0,
+            Snapshot {
+                path,
+                taken_ms: now,
+                compiled,
+            },
+        );
+        if snaps.len() > MAX_SNAPSHOTS {
+            for old in snaps.split_off(MAX_SNAPSHOTS) {
+                crate::persist::remove(old.path);
+            }
+        }
+        *newest = Some(content.to_string());
+    }
+}
+
+/// The snapshots in `dir`, newest first, and the newest one's content.
+fn scan(dir: &Path) -> (Vec<Snapshot>, Option<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (Vec::new(), None);
+    };
+    let mut snaps: Vec<Snapshot> = entries
+        .filter_map(|e| Snapshot::parse(e.ok()?.path()))
+        .collect();
+    snaps.sort_by_key(|s| std::cmp::Reverse(s.taken_ms));
+    let newest = snaps.first().and_then(|s| s.read().ok());
+    (snaps, newest)
+}
+
+fn snapshot_name(taken_ms: u64, compiled: bool) -> String {
+    let kind = if compiled { "compiled" } else { "saved" };
+    format!("{taken_ms}-{kind}.wgsl")
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// "12s ago", "5m ago", "3h ago", "2d ago".
+pub fn age_label(taken_ms: u64, now_ms: u64) -> String {
+    let secs = now_ms.saturating_sub(taken_ms) / 1000;
+    match secs {
+        0..60 => format!("{secs}s ago"),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+/// One line of a [`line_diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff from `old` to `new` by longest common subsequence.
+pub fn line_diff<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    if a.len() > MAX_DIFF_LINES || b.len() > MAX_DIFF_LINES {
+        return a
+            .iter()
+            .map(|l| DiffLine::Removed(l))
+            .chain(b.iter().map(|l| DiffLine::Added(l)))
+            .collect();
+    }
+
+    // lcs[i][j] = LCS length of a[i..] and b[j..].
+    let w = b.len() + 1;
+    let mut lcs = vec![0u32; (a.len() + 1) * w];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * w + j] = if a[i] == b[j] {
+                lcs[(i + 1) * w + j + 1] + 1
+            } else {
+                lcs[(i + 1) * w + j].max(lcs[i * w + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::with_capacity(a.len().max(b.len()));
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            out.push(DiffLine::Same(a[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * w + j] >= lcs[i * w + j + 1] {
+            out.push(DiffLine::Removed(a[i]));
+            i += 1;
+        } else {
+            out.push(DiffLine::Added(b[j]));
+            j += 1;
+        }
+    }
+    out.extend(a[i..].iter().map(|l| DiffLine::Removed(l)));
+    out.extend(b[j..].iter().map(|l| DiffLine::Added(l)));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_dedupes_upgrades_and_trims() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut h = ShaderHistory::new(tmp.path().to_path_buf());
+        let shader = Path::new("/fx/aurora.wgsl");
+
+        h.record(shader, "a", false);
+        h.record(shader, "a", false);
+        assert_eq!(h.list(shader).len(), 1);
+        h.record(shader, "a", true);
+        let snaps = h.list(shader);
+        assert_eq!(snaps.len(), 1);
+        assert!(snaps[0].compiled);
+
+        h.record(shader, "b", false);
+        let snaps = h.list(shader);
+        assert!(!snaps[0].compiled);
+        crate::persist::flush();
+        assert_eq!(snaps[0].read().unwrap(), "b");
+
+        for n in 0..MAX_SNAPSHOTS + 5 {
+            h.record(shader, &n.to_string(), false);
+        }
+        crate::persist::flush();
+        // What's on disk matches what was kept in memory
+        let snaps = ShaderHistory::new(tmp.path().to_path_buf()).list(shader);
+        assert_eq!(snaps, h.list(shader));
+        assert_eq!(snaps.len(), MAX_SNAPSHOTS);
+        assert_eq!(snaps[0].read().unwrap(), (MAX_SNAPSHOTS + 4).to_string());
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        let d = line_diff("a\nb\nc", "a\nx\nc\nd");
+        assert_eq!(
+            d,
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Added("x"),
+                DiffLine::Same("c"),
+                DiffLine::Added("d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn age_labels() {
+        assert_eq!(age_label(0, 5_000), "5s ago");
+        assert_eq!(age_label(0, 120_000), "2m ago");
+        assert_eq!(age_label(0, 7_200_000), "2h ago");
+        assert_eq!(age_label(10, 0), "0s ago");
+    }
+}
@@ -0,0 +1,406 @@
+//! Effect library updates (#2180): fetch a signed manifest of effect packs,
+//! show which are new or changed against the installed assets, and install
+//! a pack's files into the assets dir — all off the UI thread.
+//!
+//! The manifest is JSON at `manifest_url`; its detached Ed25519 signature
+//! is hex at `<manifest_url>.sig`. Every pack file carries a SHA-256 the
+//! signature covers, so a file is only written if it matches.
+
+use std::path::{Component, Path, PathBuf};
+use std::thread;
+
+use anyhow::{Context, Result};
+use crossbeam_channel::{Receiver, TryRecvError, bounded};
+use serde::{Deserialize, Serialize};
+
+use super::loader::assets_dir;
+
+/// Asset folders a pack may write into.
+const PACK_DIRS: &[&str] = &["effects", "shaders", "images"];
+
+/// Where to look for effect packs; kept in settings. Both empty by default:
+/// the action stays off until a feed is configured.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LibraryConfig {
+    pub manifest_url: String,
+    /// Hex Ed25519 public key the manifest must be signed with.
+    pub public_key: String,
+}
+
+impl LibraryConfig {
+    pub fn is_configured(&self) -> bool {
+        !self.manifest_url.trim().is_empty() && !self.public_key.trim().is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Manifest {
+    #[serde(default)]
+    pub packs: Vec<PackEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackEntry {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    pub files: Vec<PackFile>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackFile {
+    /// Relative to the assets dir, e.g. `effects/aurora.pfx`.
+    pub path: String,
+    pub url: String,
+    /// Hex SHA-256 of the file contents.
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackState {
+    /// None of its files are installed.
+    New,
+    /// Some files are missing or differ from the manifest.
+    Update,
+    Installed,
+}
+
+impl PackState {
+    pub fn label(self) -> &'static str {
+        match self {
+            PackState::New => "New",
+            PackState::Update => "Update",
+            PackState::Installed => "Installed",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PackStatus {
+    pub entry: PackEntry,
+    pub state: PackState,
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum LibraryState {
+    #[default]
+    Idle,
+    Checking,
+    Ready(Vec<PackStatus>),
+    /// Installing the named pack; the list stays visible meanwhile.
+    Installing(String, Vec<PackStatus>),
+    Error(String),
+}
+
+enum LibraryMsg {
+    Checked(Result<Vec<PackStatus>, String>),
+    Installed(String, Result<Vec<PackStatus>, String>),
+}
+
+/// Background checker/installer, polled once per frame.
+pub struct LibraryUpdater {
+    pub state: LibraryState,
+    rx: Option<Receiver<LibraryMsg>>,
+}
+
+impl LibraryUpdater {
+    pub fn new() -> Self {
+        Self {
+            state: LibraryState::Idle,
+            rx: None,
+        }
+    }
+
+    pub fn is_busy(&self) -> bool {
+        matches!(
+            self.state,
+            LibraryState::Checking | LibraryState::Installing(..)
+        )
+    }
+
+    /// Fetch and verify the manifest, then compare it with the assets dir.
+    pub fn check(&mut self, config: &LibraryConfig) {
+        if self.is_busy() {
+            return;
+        }
+        let config = config.clone();
+        let (tx, rx) = bounded(1);
+        self.rx = Some(rx);
+        self.state = LibraryState::Checking;
+        thread::Builder::new()
+            .name("effect-library-check".into())
+            .spawn(move || {
+                let result = fetch_manifest(&config)
+                    .map(|m| statuses(m.packs, assets_dir()))
+                    .map_err(|e| format!("{e:#}"));
+                let _ = tx.send(LibraryMsg::Checked(result));
+            })
+            .expect("failed to spawn effect library thread");
+    }
+
+    /// Download and install one pack from the last check.
+    pub fn install(&mut self, pack: &str) {
+        let LibraryState::Ready(list) = &self.state else {
+            return;
+        };
+        let list = list.clone();
+        let Some(entry) = list
+            .iter()
+            .find(|p| p.entry.name == pack)
+            .map(|p| p.entry.clone())
+        else {
+            return;
+        };
+        let name = entry.name.clone();
+        let (tx, rx) = bounded(1);
+        self.rx = Some(rx);
+        self.state = LibraryState::Installing(name.clone(), list.clone());
+        thread::Builder::new()
+            .name("effect-library-install".into())
+            .spawn(move || {
+                let assets = assets_dir();
+                let result = install_pack(&entry, assets)
+                    .map(|()| statuses(list.into_iter().map(|p| p.entry).collect(), assets))
+                    .map_err(|e| format!("{e:#}"));
+                let _ = tx.send(LibraryMsg::Installed(name, result));
+            })
+            .expect("failed to spawn effect library thread");
+    }
+
+    /// Take a finished check or install. Returns the pack name once an
+    /// install lands, so the caller can rescan effects.
+    pub fn poll(&mut self) -> Option<String> {
+        let rx = self.rx.as_ref()?;
+        let msg = match rx.try_recv() {
+            Ok(msg) => msg,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => {
+                self.rx = None;
+                self.state = LibraryState::Error("Library update stopped".to_string());
+                return None;
+            }
+        };
+        self.rx = None;
+        match msg {
+            LibraryMsg::Checked(Ok(list)) => {
+                self.state = LibraryState::Ready(list);
+                None
+            }
+            LibraryMsg::Installed(name, Ok(list)) => {
+                log::info!("Installed effect pack '{name}'");
+                self.state = LibraryState::Ready(list);
+                Some(name)
+            }
+            LibraryMsg::Checked(Err(e)) | LibraryMsg::Installed(_, Err(e)) => {
+                log::warn!("Effect library: {e}");
+                self.state = LibraryState::Error(e);
+                None
+            }
+        }
+    }
+}
+
+fn fetch_manifest(config: &LibraryConfig) -> Result<Manifest> {
+    let url = config.manifest_url.trim();
+    let body = ureq::get(url)
+        .call()
+        .context("fetching manifest")?
+        .body_mut()
+        .read_to_vec()?;
+    let sig = ureq::get(&format!("{url}.sig"))
+        .call()
+        .context("fetching manifest signature")?
+        .body_mut()
+        .read_to_string()?;
+    verify_manifest(&body, &sig, &config.public_key)
+}
+
+/// Check the signature, then parse. Nothing in an unverified manifest is
+/// looked at.
+fn verify_manifest(body: &[u8], sig_hex: &str, key_hex: &str) -> Result<Manifest> {
+    let key = decode_hex(key_hex).context("public key is not valid hex")?;
+    let sig = decode_hex(sig_hex).context("signature is not valid hex")?;
+    ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, key)
+        .verify(body, &sig)
+        .map_err(|_| anyhow::anyhow!("manifest signature does not match the public key"))?;
+    Ok(serde_json::from_slice(body)?)
+}
+
+fn statuses(packs: Vec<PackEntry>, assets: &Path) -> Vec<PackStatus> {
+    packs
+        .into_iter()
+        .map(|entry| PackStatus {
+            state: pack_state(&entry, assets),
+            entry,
+        })
+        .collect()
+}
+
+fn pack_state(entry: &PackEntry, assets: &Path) -> PackState {
+    let mut present = 0;
+    let mut current = 0;
+    for file in &entry.files {
+        let Some(path) = pack_path(assets, &file.path) else {
+            continue;
+        };
+        if let Ok(bytes) = std::fs::read(&path) {
+            present += 1;
+            if sha256_hex(&bytes).eq_ignore_ascii_case(file.sha256.trim()) {
+                current += 1;
+            }
+        }
+    }
+    if current == entry.files.len() {
+        PackState::Installed
+    } else if present == 0 {
+        PackState::New
+    } else {
+        PackState::Update
+    }
+}
+
+/// Download every file and check its hash before writing any, so a bad
+/// file leaves the installed pack untouched.
+fn install_pack(entry: &PackEntry, assets: &Path) -> Result<()> {
+    let mut files = Vec::with_capacity(entry.files.len());
+    for file in &entry.files {
+        let path = pack_path(assets, &file.path)
+            .with_context(|| format!("'{}' is outside the effect folders", file.path))?;
+        let bytes = ureq::get(&file.url)
+            .call()
+            .with_context(|| format!("downloading {}", file.path))?
+            .body_mut()
+            .read_to_vec()?;
+        if !sha256_hex(&bytes).eq_ignore_ascii_case(file.sha256.trim()) {
+            anyhow::bail!("{} does not match its manifest hash", file.path);
+        }
+        files.push((path, bytes));
+    }
+    for (path, bytes) in files {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(&tmp, &path)?;
+    }
+    Ok(())
+}
+
+/// A manifest path under the assets dir, or `None` if it would land outside
+/// the pack folders.
+fn pack_path(assets: &Path, rel: &str) -> Option<PathBuf> {
+    let rel = Path::new(rel);
+    let mut components = rel.components();
+    let first = match components.next()? {
+        Component::Normal(c) => c.to_str()?,
+        _ => return None,
+    };
+    if !PACK_DIRS.contains(&first) || !components.all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    // A bare folder name isn't a file.
+    (rel.components().count() > 1).then(|| assets.join(rel))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    encode_hex(ring::digest::digest(&ring::digest::SHA256, bytes).as_ref())
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{b:02x}");
+        s
+    })
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    let s = s.trim();
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::KeyPair;
+
+    fn keypair() -> ring::signature::Ed25519KeyPair {
+        ring::signature::Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap()
+    }
+
+    #[test]
+    fn signed_manifest_verifies_and_tampering_fails() {
+        let kp = keypair();
+        let body = br#"{"packs":[{"name":"Neon","files":[]}]}"#;
+        let sig = encode_hex(kp.sign(body).as_ref());
+        let key = encode_hex(kp.public_key().as_ref());
+
+        let m = verify_manifest(body, &sig, &key).unwrap();
+        assert_eq!(m.packs[0].name, "Neon");
+
+        let tampered = br#"{"packs":[{"name":"Evil","files":[]}]}"#;
+        assert!(verify_manifest(tampered, &sig, &key).is_err());
+        assert!(verify_manifest(body, &sig, "zz").is_err());
+    }
+
+    #[test]
+    fn pack_paths_stay_in_pack_folders() {
+        let assets = Path::new("/a");
+        assert_eq!(
+            pack_path(assets, "effects/x.pfx"),
+            Some(PathBuf::from("/a/effects/x.pfx"))
+        );
+        assert!(pack_path(assets, "shaders/lib/y.wgsl").is_some());
+        assert!(pack_path(assets, "effects/../../etc/passwd").is_none());
+        assert!(pack_path(assets, "/etc/passwd").is_none());
+        assert!(pack_path(assets, "presets/p.json").is_none());
+        assert!(pack_path(assets, "effects").is_none());
+    }
+
+    #[test]
+    fn pack_state_compares_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = |path: &str, body: &[u8]| PackFile {
+            path: path.to_string(),
+            url: String::new(),
+            sha256: sha256_hex(body),
+        };
+        let entry = PackEntry {
+            name: "P".to_string(),
+            version: String::new(),
+            description: String::new(),
+            files: vec![file("effects/a.pfx", b"a"), file("shaders/a.wgsl", b"b")],
+        };
+        assert_eq!(pack_state(&entry, dir.path()), PackState::New);
+
+        std::fs::create_dir_all(dir.path().join("effects")).unwrap();
+        std::fs::write(dir.path().join("effects/a.pfx"), b"a").unwrap();
+        assert_eq!(pack_state(&entry, dir.path()), PackState::Update);
+
+        std::fs::create_dir_all(dir.path().join("shaders")).unwrap();
+        std::fs::write(dir.path().join("shaders/a.wgsl"), b"b").unwrap();
+        assert_eq!(pack_state(&entry, dir.path()), PackState::Installed);
+    }
+
+    #[test]
+    fn sha256_and_hex_roundtrip() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(decode_hex("00ff10"), Some(vec![0, 255, 16]));
+        assert_eq!(encode_hex(&[0, 255, 16]), "00ff10");
+        assert_eq!(decode_hex("0"), None);
+        assert_eq!(decode_hex("gg"), None);
+    }
+}
@@ -4,11 +4,22 @@ use std::sync::OnceLock;
 use anyhow::Result;
 
 use super::format::PfxEffect;
+use crate::gpu::capabilities::GpuCapabilities;
 
 /// Resolve the assets directory once (CWD-relative → exe-relative → macOS bundle).
+/// Portable mode (#2178) checks exe-relative first, so the copy travelling
+/// with the executable wins over whatever the working directory holds.
 pub fn assets_dir() -> &'static Path {
     static DIR: OnceLock<PathBuf> = OnceLock::new();
     DIR.get_or_init(|| {
+        if let Some(root) = crate::paths::portable_root() {
+            let beside = root.join("assets");
+            if beside.join("effects").is_dir() {
+                log::info!("Assets: portable ({})", beside.display());
+                return beside;
+            }
+        }
+
         // 1. CWD-relative (dev workflow)
         let cwd = PathBuf::from("assets");
         if cwd.join("effects").is_dir() {
@@ -128,6 +139,31 @@ struct PhosphorUniforms {
     // 0.5 = centred, 0 = hard left, 1 = hard right; a band with no energy holds 0.5.
     // Same band order as sub_bass..brilliance above. Read it with band_pan(i).
     band_pan: array<vec4f, 2>,
+    // Motion field (#2154) of the active or topmost media layer; 0.0 without one.
+    motion: f32,            // 0-1 amount of movement
+    motion_x: f32,          // mean flow direction, -1..1 (right = +)
+    motion_y: f32,          // mean flow direction, -1..1 (down = +)
+    frame_diff: f32,        // mean absolute brightness change, 0-1
+    // Render seed (#2174): fixed in deterministic mode, so seed noise from it.
+    seed: f32,              // 0..65535
+    // Second input (#2217): a room mic, for crowd reactions; 0.0 with none open.
+    mic_rms: f32,           // 0-1 crowd level
+    mic_onset: f32,         // 0-1, jumps on a cheer or clap
+    _pad_seed2: f32,
+    // Dynamics (#2218): crest factor and the impact envelope.
+    crest: f32,             // 0-1, low on squashed or sustained sound, high on punchy
+    impact: f32,            // 1 when the full mix slams back after a quiet bar, then decays
+    _pad_dynamics0: f32,
+    _pad_dynamics1: f32,
+    // Band envelopes (#2219): attack/release followers, timed in the audio panel.
+    env_bass_fast: f32,     // punchy bass (sub_bass + bass)
+    env_bass_slow: f32,     // smooth bass
+    env_mid_fast: f32,      // punchy mids (low_mid..upper_mid)
+    env_mid_slow: f32,      // smooth mids
+    env_high_fast: f32,     // punchy highs (presence + brilliance)
+    env_high_slow: f32,     // smooth highs
+    _pad_env0: f32,
+    _pad_env1: f32,
 }
 
 @group(0) @binding(0) var<uniform> u: PhosphorUniforms;
@@ -203,6 +239,9 @@ pub struct EffectLoader {
     /// Spatial hash grid dimensions, patched into particle_lib SH_GRID_W/H constants.
     /// Updated when a particle system with interaction is created.
     pub grid_dims: (u32, u32),
+    /// What the GPU supports, for effects' `requires` (#2195). `None` until the
+    /// app has a device, and in tests.
+    pub gpu_caps: Option<GpuCapabilities>,
 }
 
 impl EffectLoader {
@@ -242,6 +281,7 @@ impl EffectLoader {
             lib_source,
             particle_lib_source,
             grid_dims: (40, 40),
+            gpu_caps: None,
         }
     }
 
@@ -298,6 +338,14 @@ impl EffectLoader {
                     Ok(mut effect) => {
                         let path = entry.path().canonicalize().unwrap_or_else(|_| entry.path());
                         log::info!("Found effect: {} ({})", effect.name, path.display());
+                        if let Some(pd) = &effect.particles {
+                            for problem in crate::gpu::particle::param_inputs::binding_problems(
+                                &pd.params,
+                                &effect.inputs,
+                            ) {
+                                log::warn!("{}: particle params: {problem}", effect.name);
+                            }
+                        }
                         effect.source_path = Some(path);
                         self.effects.push(effect);
                     }
@@ -379,6 +427,11 @@ impl EffectLoader {
         }
     }
 
+    /// Why `effect` can't run on this GPU, if it can't (#2195).
+    pub fn unsupported_reason(&self, effect: &PfxEffect) -> Option<String> {
+        self.gpu_caps.as_ref()?.unmet(&effect.requirements())
+    }
+
     /// Returns true if the effect is a built-in (shipped) effect.
     pub fn is_builtin(effect: &PfxEffect) -> bool {
         effect.author == "Fosfora"
@@ -393,6 +446,7 @@ impl EffectLoader {
             lib_source: lib_source.to_string(),
             particle_lib_source: String::new(),
             grid_dims: (40, 40),
+            gpu_caps: None,
         }
     }
 
@@ -450,9 +504,15 @@ impl EffectLoader {
         Ok(name)
     }
 
-    /// Copy a built-in effect to a new user effect with the given name.
-    /// Returns (pfx_path, first_wgsl_path) so the caller can load + open editor.
-    pub fn copy_builtin_effect(&self, index: usize, new_name: &str) -> Result<(PathBuf, PathBuf)> {
+    /// Copy a built-in effect to a new user effect with the given name. The
+    /// .pfx goes through the persist writer (#2201) and the copy is added to
+    /// `effects` straight away. Returns (new effect index, first_wgsl_path) so
+    /// the caller can load + open editor.
+    pub fn copy_builtin_effect(
+        &mut self,
+        index: usize,
+        new_name: &str,
+    ) -> Result<(usize, PathBuf)> {
         let effect = self
             .effects
             .get(index)
@@ -485,7 +545,10 @@ impl EffectLoader {
         let effects_dir = assets_dir().join("effects");
         let shaders_dir = assets_dir().join("shaders");
         let new_pfx_path = effects_dir.join(format!("{snake}.pfx"));
-        if new_pfx_path.exists() {
+        let listed = |e: &PfxEffect| {
+            e.source_path.as_deref().and_then(Path::file_name) == new_pfx_path.file_name()
+        };
+        if new_pfx_path.exists() || self.effects.iter().any(listed) {
             anyhow::bail!("Effect '{}' already exists", new_name);
         }
 
@@ -585,14 +648,18 @@ impl EffectLoader {
         }
 
         let pfx_json = serde_json::to_string_pretty(&new_effect)?;
-        std::fs::write(&new_pfx_path, pfx_json)?;
+        crate::persist::write(new_pfx_path.clone(), pfx_json);
         log::info!(
             "Created effect copy: {} -> {}",
             effect.name,
             new_pfx_path.display()
         );
 
-        Ok((new_pfx_path, first_wgsl))
+        // Appended, so the effect indices layers hold stay valid; the next
+        // rescan sorts it in.
+        new_effect.source_path = Some(new_pfx_path);
+        self.effects.push(new_effect);
+        Ok((self.effects.len() - 1, first_wgsl))
     }
 }
 
@@ -2494,9 +2561,11 @@ fn cs_main() {
     for (var i = 0u; i < 7u; i = i + 1u) {
         out[6u + i] = band_pan(i);
     }
+    out[13] = u.force_curl;      // #2158 force header
+    out[14] = u.forces[7].z;     // last point-force slot — the struct's tail
 }
 "#;
-        const N: usize = 13;
+        const N: usize = 15;
 
         let _guard = gpu_guard();
         let (device, queue) = test_gpu();
@@ -2510,6 +2579,8 @@ fn cs_main() {
         u.stereo_width = 0.75;
         u.stereo_corr = 0.125;
         u.band_pan = [0.11, 0.22, 0.33, 0.44, 0.55, 0.66, 0.77, 0.0];
+        u.force_curl = 0.625;
+        u.forces[7] = [0.0, 0.0, 0.875, 0.0];
 
         let ubuf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("probe-uniforms"),
@@ -2605,5 +2676,7 @@ fn cs_main() {
                 u.band_pan[i]
             );
         }
+        assert_eq!(got[13], u.force_curl, "force_curl drifted");
+        assert_eq!(got[14], u.forces[7][2], "forces[7].z drifted");
     }
 }
@@ -1,4 +1,7 @@
 pub mod format;
+pub mod history;
+pub mod library;
 pub mod loader;
+pub mod post_source;
 
 pub use loader::EffectLoader;
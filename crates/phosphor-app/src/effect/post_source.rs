@@ -0,0 +1,153 @@
+//! Where a layer's post-processing chain comes from (#2234). Saved with the
+//! settings rather than in presets: a default chain of your own, and per
+//! effect a choice between the effect's chain, that default, or a custom
+//! chain kept for that effect alone.
+//!
+//! Precedence, first match wins:
+//! 1. The effect is set to "My default" or "Custom": that chain.
+//! 2. A preset is being loaded: the chain saved in the preset.
+//! 3. The effect's own `postprocess` block.
+//! 4. Your default chain.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::format::PostProcessDef;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PostSource {
+    /// The effect's block, or the preset's chain when one is loaded.
+    #[default]
+    Effect,
+    /// The user's default chain.
+    Default,
+    /// A chain kept for this effect only.
+    Custom,
+}
+
+impl PostSource {
+    pub const ALL: &[PostSource] = &[PostSource::Effect, PostSource::Default, PostSource::Custom];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            PostSource::Effect => "Effect's post",
+            PostSource::Default => "My default",
+            PostSource::Custom => "Custom",
+        }
+    }
+}
+
+/// Saved with the settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct PostSettings {
+    /// The user's default chain.
+    pub default: PostProcessDef,
+    /// Per effect name; effects missing here use [`PostSource::Effect`].
+    pub sources: BTreeMap<String, PostSource>,
+    /// Custom chains by effect name, kept when the effect switches away so
+    /// switching back restores them.
+    pub custom: BTreeMap<String, PostProcessDef>,
+}
+
+impl PostSettings {
+    pub fn source(&self, effect: &str) -> PostSource {
+        self.sources.get(effect).copied().unwrap_or_default()
+    }
+
+    /// The chain for `effect`, given its own block and the chain of a preset
+    /// being loaded, in the order set out at the top of this module.
+    pub fn resolve(
+        &self,
+        effect: &str,
+        effect_post: Option<&PostProcessDef>,
+        preset_post: Option<&PostProcessDef>,
+    ) -> PostProcessDef {
+        match self.source(effect) {
+            PostSource::Default => self.default.clone(),
+            PostSource::Custom => self.custom.get(effect).unwrap_or(&self.default).clone(),
+            PostSource::Effect => preset_post.or(effect_post).unwrap_or(&self.default).clone(),
+        }
+    }
+
+    /// Switch `effect` to `source`. A first switch to Custom starts from
+    /// `current`, the chain on screen.
+    pub fn set_source(&mut self, effect: &str, source: PostSource, current: &PostProcessDef) {
+        if source == PostSource::Custom && !self.custom.contains_key(effect) {
+            self.custom.insert(effect.to_string(), current.clone());
+        }
+        if source == PostSource::Effect {
+            self.sources.remove(effect);
+        } else {
+            self.sources.insert(effect.to_string(), source);
+        }
+    }
+
+    /// Keep an edit to `effect`'s chain where its source lives. Returns true
+    /// when that's these settings; edits to an effect's own chain stay on the
+    /// layer and travel with presets, as before.
+    pub fn record_edit(&mut self, effect: &str, chain: &PostProcessDef) -> bool {
+        match self.source(effect) {
+            PostSource::Effect => false,
+            PostSource::Default => {
+                self.default = chain.clone();
+                true
+            }
+            PostSource::Custom => {
+                self.custom.insert(effect.to_string(), chain.clone());
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(vignette: f32) -> PostProcessDef {
+        PostProcessDef {
+            vignette,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn precedence_is_choice_then_preset_then_effect_then_default() {
+        let mut post = PostSettings {
+            default: chain(0.1),
+            ..Default::default()
+        };
+        let (effect, preset) = (chain(0.2), chain(0.3));
+        assert_eq!(post.resolve("a", None, None), chain(0.1));
+        assert_eq!(post.resolve("a", Some(&effect), None), effect);
+        assert_eq!(post.resolve("a", Some(&effect), Some(&preset)), preset);
+
+        post.set_source("a", PostSource::Default, &effect);
+        assert_eq!(post.resolve("a", Some(&effect), Some(&preset)), chain(0.1));
+        assert_eq!(post.resolve("b", Some(&effect), None), effect);
+
+        post.set_source("a", PostSource::Custom, &chain(0.4));
+        assert_eq!(post.resolve("a", Some(&effect), Some(&preset)), chain(0.4));
+    }
+
+    #[test]
+    fn edits_go_where_the_chain_came_from() {
+        let mut post = PostSettings::default();
+        assert!(!post.record_edit("a", &chain(0.5)));
+        assert_eq!(post, PostSettings::default());
+
+        post.set_source("a", PostSource::Custom, &chain(0.2));
+        assert!(post.record_edit("a", &chain(0.6)));
+        post.set_source("a", PostSource::Effect, &chain(0.0));
+        assert!(post.sources.is_empty());
+        // The custom chain waits for the effect to switch back
+        post.set_source("a", PostSource::Custom, &chain(0.0));
+        assert_eq!(post.resolve("a", None, None), chain(0.6));
+
+        post.set_source("b", PostSource::Default, &chain(0.0));
+        assert!(post.record_edit("b", &chain(0.7)));
+        assert_eq!(post.default, chain(0.7));
+    }
+}
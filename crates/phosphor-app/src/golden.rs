@@ -0,0 +1,659 @@
+//! Golden-image snapshots of the bundled effects (#2175).
+//!
+//! `phosphor --golden` renders every built-in effect headless for a fixed
+//! number of frames — deterministic clock and seed (#2174), synthetic audio,
+//! a small fixed resolution — and compares the last frame against the PNG
+//! references in `tests/golden/`. Any effect whose output drifted past the
+//! tolerance fails the run, so a wgpu/naga upgrade or a shared-library edit
+//! that changes how a shader renders shows up before release.
+//! `--golden --bless` rewrites the references. An effect with no reference
+//! yet has one written on its first run and passes, so a fresh checkout or a
+//! new effect starts from a baseline instead of failing.
+//!
+//! Only the effect's fragment passes are rendered: particle simulations
+//! resolve their atomics in a different order every run, so a particle
+//! effect's snapshot covers its background passes alone. Post-processing is
+//! left out too; it is shared code, not the effect's.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use wgpu::{Device, Queue, TextureFormat};
+
+use crate::effect::EffectLoader;
+use crate::effect::format::PfxEffect;
+use crate::gpu::audio_textures::AudioTextures;
+use crate::gpu::capabilities::GpuCapabilities;
+use crate::gpu::context::GpuContext;
+use crate::gpu::deterministic::{DeterministicParams, seed_uniform};
+use crate::gpu::frame_capture::FrameCapture;
+use crate::gpu::fullscreen_quad::FULLSCREEN_TRIANGLE_VS;
+use crate::gpu::pass_executor::PassExecutor;
+use crate::gpu::placeholder::PlaceholderTexture;
+use crate::gpu::render_target::RenderTarget;
+use crate::gpu::uniforms::{ShaderUniforms, UniformBuffer};
+use crate::params::ParamStore;
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 90;
+pub(crate) const FPS: u32 = 60;
+pub(crate) const SEED: u32 = 1;
+const DEFAULT_FRAMES: u32 = 60;
+/// Mean absolute difference, in 8-bit levels, a frame may drift before it fails.
+const DEFAULT_TOLERANCE: f32 = 1.0;
+const CAPTURE_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+const MANIFEST: &str = "manifest.json";
+/// How long to wait for the last frame's readback before giving up on a
+/// hung device.
+const READBACK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Command-line options for `--golden`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldenOptions {
+    /// Write the references instead of comparing against them.
+    pub bless: bool,
+    /// Frames to render; defaults to what the references were blessed with.
+    pub frames: Option<u32>,
+    /// Only effects whose name or file contains this (case-insensitive).
+    pub only: Option<String>,
+    pub tolerance: f32,
+    pub dir: PathBuf,
+}
+
+impl GoldenOptions {
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut opts = Self {
+            bless: false,
+            frames: None,
+            only: None,
+            tolerance: DEFAULT_TOLERANCE,
+            dir: PathBuf::from("tests/golden"),
+        };
+        let mut it = args.iter();
+        while let Some(arg) = it.next() {
+            let mut value = |flag: &str| {
+                it.next()
+                    .cloned()
+                    .ok_or_else(|| anyhow!("{flag} needs a value"))
+            };
+            match arg.as_str() {
+                "--golden" => {}
+                "--bless" => opts.bless = true,
+                "--frames" => {
+                    let n: u32 = value(arg)?.parse().context("--frames")?;
+                    opts.frames = Some(n.max(1));
+                }
+                "--only" => opts.only = Some(value(arg)?.to_lowercase()),
+                "--tolerance" => opts.tolerance = value(arg)?.parse().context("--tolerance")?,
+                "--golden-dir" => opts.dir = PathBuf::from(value(arg)?),
+                other => bail!("unknown --golden option '{other}'"),
+            }
+        }
+        Ok(opts)
+    }
+}
+
+/// Render settings the references were made with, saved beside them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Manifest {
+    width: u32,
+    height: u32,
+    frames: u32,
+    fps: u32,
+    seed: u32,
+    /// Effect key → FNV-1a hash of its reference pixels.
+    effects: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Outcome {
+    Match,
+    /// Pixels differ, but the mean difference is within tolerance.
+    Close(f32),
+    Drift(f32),
+    /// No reference yet; this run's frame became it.
+    New,
+    Blessed,
+    Failed(String),
+}
+
+/// Run the snapshot check; `Ok(false)` when any effect failed.
+pub fn run(opts: &GoldenOptions) -> Result<bool> {
+    let manifest_path = opts.dir.join(MANIFEST);
+    let stored: Option<Manifest> = std::fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok());
+
+    let frames = match (&stored, opts.frames) {
+        (Some(m), None) => m.frames,
+        (None, None) => DEFAULT_FRAMES,
+        (_, Some(n)) => n,
+    };
+    if let Some(m) = &stored {
+        if !opts.bless && (m.frames, m.width, m.height) != (frames, WIDTH, HEIGHT) {
+            bail!(
+                "references are {}x{} at {} frames; re-bless or pass --frames {}",
+                m.width,
+                m.height,
+                m.frames,
+                m.frames
+            );
+        }
+    }
+
+    let mut loader = EffectLoader::new();
+    loader.scan_effects_directory();
+    let effects: Vec<&PfxEffect> = loader
+        .effects
+        .iter()
+        .filter(|e| EffectLoader::is_builtin(e))
+        .filter(|e| {
+            opts.only.as_ref().is_none_or(|f| {
+                e.name.to_lowercase().contains(f.as_str()) || effect_key(e).contains(f.as_str())
+            })
+        })
+        .collect();
+    if effects.is_empty() {
+        bail!("no bundled effects matched");
+    }
+
+    let gpu = Headless::new()?;
+    std::fs::create_dir_all(&opts.dir)
+        .with_context(|| format!("creating {}", opts.dir.display()))?;
+    let actual_dir = std::env::temp_dir().join("phosphor-golden");
+
+    let mut manifest = Manifest {
+        width: WIDTH,
+        height: HEIGHT,
+        frames,
+        fps: FPS,
+        seed: SEED,
+        effects: BTreeMap::new(),
+    };
+    // Blessing a subset keeps the other references if they were made alike.
+    if let Some(m) = &stored {
+        if (m.width, m.height, m.frames, m.fps, m.seed) == (WIDTH, HEIGHT, frames, FPS, SEED) {
+            manifest.effects = m.effects.clone();
+        }
+    }
+
+    println!(
+        "Golden images: {} effects, {WIDTH}x{HEIGHT}, {frames} frames, seed {SEED}",
+        effects.len()
+    );
+    let mut failed = 0;
+    let mut written = false;
+    for effect in &effects {
+        let key = effect_key(effect);
+        let reference = opts.dir.join(format!("{key}.png"));
+        let outcome = match render_effect(&gpu, &loader, effect, frames) {
+            Err(e) => Outcome::Failed(format!("{e:#}")),
+            Ok(pixels) => {
+                let hash = format!("{:016x}", fnv1a64(&pixels));
+                if opts.bless || !reference.exists() {
+                    image::save_buffer(
+                        &reference,
+                        &pixels,
+                        WIDTH,
+                        HEIGHT,
+                        image::ExtendedColorType::Rgba8,
+                    )
+                    .with_context(|| format!("writing {}", reference.display()))?;
+                    manifest.effects.insert(key.clone(), hash);
+                    written = true;
+                    if opts.bless {
+                        Outcome::Blessed
+                    } else {
+                        Outcome::New
+                    }
+                } else if stored
+                    .as_ref()
+                    .and_then(|m| m.effects.get(&key))
+                    .is_some_and(|h| *h == hash)
+                {
+                    Outcome::Match
+                } else {
+                    let outcome = compare(&reference, &pixels, opts.tolerance);
+                    if matches!(outcome, Outcome::Drift(_)) {
+                        std::fs::create_dir_all(&actual_dir)?;
+                        let path = actual_dir.join(format!("{key}.png"));
+                        image::save_buffer(
+                            &path,
+                            &pixels,
+                            WIDTH,
+                            HEIGHT,
+                            image::ExtendedColorType::Rgba8,
+                        )?;
+                    }
+                    outcome
+                }
+            }
+        };
+        let particles = if effect.particles.is_some() {
+            " (passes only)"
+        } else {
+            ""
+        };
+        match &outcome {
+            Outcome::Match => println!("  ok       {key}{particles}"),
+            Outcome::Close(d) => println!("  ok       {key}{particles} — mean diff {d:.2}"),
+            Outcome::Blessed => println!("  blessed  {key}{particles}"),
+            Outcome::Drift(d) => println!(
+                "  DRIFT    {key} — mean diff {d:.2} > {:.2}, actual in {}",
+                opts.tolerance,
+                actual_dir.display()
+            ),
+            Outcome::New => println!("  new      {key}{particles} — reference written"),
+            Outcome::Failed(e) => println!("  FAILED   {key} — {e}"),
+        }
+        if matches!(outcome, Outcome::Drift(_) | Outcome::Failed(_)) {
+            failed += 1;
+        }
+    }
+
+    if written {
+        let json = serde_json::to_string_pretty(&manifest)?;
+        std::fs::write(&manifest_path, json + "\n")
+            .with_context(|| format!("writing {}", manifest_path.display()))?;
+    }
+    println!(
+        "{} of {} effects passed",
+        effects.len() - failed,
+        effects.len()
+    );
+    Ok(failed == 0)
+}
+
+/// Reference file stem: the `.pfx` file name, which stays put when the
+/// display name is reworded.
+pub(crate) fn effect_key(effect: &PfxEffect) -> String {
+    effect
+        .source_path
+        .as_deref()
+        .and_then(Path::file_stem)
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_else(|| effect.name.to_lowercase().replace(' ', "_"))
+}
+
+fn compare(reference: &Path, pixels: &[u8], tolerance: f32) -> Outcome {
+    let img = match image::open(reference) {
+        Ok(img) => img,
+        Err(e) => return Outcome::Failed(format!("reading reference: {e}")),
+    };
+    let img = img.to_rgba8();
+    if img.dimensions() != (WIDTH, HEIGHT) {
+        return Outcome::Failed(format!(
+            "reference is {}x{}; re-bless",
+            img.width(),
+            img.height()
+        ));
+    }
+    let diff = mean_abs_diff(img.as_raw(), pixels);
+    if diff == 0.0 {
+        Outcome::Match
+    } else if diff <= tolerance {
+        Outcome::Close(diff)
+    } else {
+        Outcome::Drift(diff)
+    }
+}
+
+/// Mean absolute RGB difference in 8-bit levels; alpha is never displayed.
+fn mean_abs_diff(a: &[u8], b: &[u8]) -> f32 {
+    let mut sum = 0u64;
+    let mut n = 0u64;
+    for (pa, pb) in a.chunks_exact(4).zip(b.chunks_exact(4)) {
+        for c in 0..3 {
+            sum += u64::from(pa[c].abs_diff(pb[c]));
+            n += 1;
+        }
+    }
+    if n == 0 { 0.0 } else { sum as f32 / n as f32 }
+}
+
+/// FNV-1a, chosen over `DefaultHasher` because its output must not change
+/// between Rust releases.
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Synthetic audio for frame `frame`: a 120 BPM pulse with slow band swells,
+/// so audio-reactive code paths run without a capture device.
+pub(crate) fn synthetic_audio(u: &mut ShaderUniforms, frame: u32) {
+    const BEAT_FRAMES: u32 = FPS / 2;
+    let t = frame as f32 / FPS as f32;
+    let phase = (frame % BEAT_FRAMES) as f32 / BEAT_FRAMES as f32;
+    let swell = |hz: f32, off: f32| 0.5 + 0.5 * (t * hz * std::f32::consts::TAU + off).sin();
+    u.sub_bass = swell(0.25, 0.0);
+    u.bass = swell(0.5, 0.5);
+    u.low_mid = swell(0.7, 1.0);
+    u.mid = swell(0.9, 1.5);
+    u.upper_mid = swell(1.1, 2.0);
+    u.presence = swell(1.3, 2.5);
+    u.brilliance = swell(1.7, 3.0);
+    u.rms = 0.5 * (u.bass + u.mid);
+    u.centroid = swell(0.2, 0.0);
+    u.beat_phase = phase;
+    u.bpm = 120.0 / 300.0;
+    u.beat_strength = 0.8;
+    let hit = if frame % BEAT_FRAMES == 0 { 1.0 } else { 0.0 };
+    u.beat = hit;
+    u.onset = hit;
+    u.kick = (1.0 - phase).powi(4);
+}
+
+/// A windowless device plus the shared resources every effect binds. Also
+/// what `--benchmark` (#2225) renders with.
+pub(crate) struct Headless {
+    pub(crate) device: Device,
+    pub(crate) queue: Queue,
+    pub(crate) info: wgpu::AdapterInfo,
+    pub(crate) caps: GpuCapabilities,
+    pub(crate) placeholder: PlaceholderTexture,
+    pub(crate) audio: AudioTextures,
+    blit: wgpu::RenderPipeline,
+    blit_bgl: wgpu::BindGroupLayout,
+}
+
+impl Headless {
+    pub(crate) fn new() -> Result<Self> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        }))
+        .context("no GPU adapter")?;
+        println!("Adapter: {}", adapter.get_info().name);
+        let limits = adapter.limits();
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+                label: Some("headless-device"),
+                // For effects that declare `float_filtering` (#2195).
+                required_features: adapter.features() & wgpu::Features::FLOAT32_FILTERABLE,
+                // The same limits the app asks for, so effects that load there load here.
+                required_limits: wgpu::Limits {
+                    max_storage_buffers_per_shader_stage: 16,
+                    max_bind_groups: 5,
+                    max_storage_buffer_binding_size: limits.max_storage_buffer_binding_size,
+                    max_buffer_size: limits.max_buffer_size,
+                    ..wgpu::Limits::default()
+                },
+                experimental_features: wgpu::ExperimentalFeatures::default(),
+                memory_hints: wgpu::MemoryHints::Performance,
+                trace: wgpu::Trace::Off,
+            }))?;
+        let caps = GpuCapabilities::detect(&adapter, &device);
+        let placeholder = PlaceholderTexture::new(&device, &queue, GpuContext::hdr_format());
+        let audio = AudioTextures::new(&device, &queue);
+        let (blit, blit_bgl) = blit_pipeline(&device);
+        Ok(Self {
+            device,
+            queue,
+            info: adapter.get_info(),
+            caps,
+            placeholder,
+            audio,
+            blit,
+            blit_bgl,
+        })
+    }
+
+    pub(crate) fn wait(&self) {
+        let _ = self.device.poll(wgpu::PollType::Wait {
+            submission_index: None,
+            timeout: None,
+        });
+    }
+}
+
+/// Render `frames` frames of `effect` and read back the last, as RGBA8.
+fn render_effect(
+    gpu: &Headless,
+    loader: &EffectLoader,
+    effect: &PfxEffect,
+    frames: u32,
+) -> Result<Vec<u8>> {
+    let device = &gpu.device;
+    let passes = effect.normalized_passes();
+    if passes.is_empty() {
+        bail!("no shader or passes defined");
+    }
+    let ubuf = UniformBuffer::new(device);
+    let mut executor = PassExecutor::new(
+        device,
+        GpuContext::hdr_format(),
+        WIDTH,
+        HEIGHT,
+        &passes,
+        loader,
+        &ubuf,
+        &gpu.placeholder,
+        &gpu.audio,
+        &gpu.queue,
+        None,
+        None,
+    )
+    .map_err(|e| anyhow!(e))?;
+
+    let mut store = ParamStore::new();
+    store.load_from_defs(&effect.inputs);
+    let clock = DeterministicParams {
+        enabled: true,
+        seed: SEED,
+        fps: FPS,
+    };
+    let mut u = ShaderUniforms::zeroed();
+    u.resolution = [WIDTH as f32, HEIGHT as f32];
+    u.feedback_decay = 0.88;
+    u.seed = seed_uniform(clock.seed);
+    u.params = store.pack_to_buffer();
+    u.delta_time = clock.step();
+
+    let mut capture = FrameCapture::new(device, WIDTH, HEIGHT, CAPTURE_FORMAT, "golden-capture");
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    for frame in 0..frames {
+        u.time = clock.frame_time(frame);
+        u.frame_index = frame as f32;
+        synthetic_audio(&mut u, frame);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("golden-frame"),
+        });
+        let output = executor.execute(&mut encoder, &ubuf, &gpu.queue, &u);
+        if frame + 1 == frames {
+            blit(gpu, &mut encoder, output, &capture);
+            capture.copy_to_staging(&mut encoder);
+        }
+        gpu.queue.submit([encoder.finish()]);
+        executor.flip();
+    }
+    if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+        bail!("{error}");
+    }
+
+    capture.request_map();
+    let deadline = std::time::Instant::now() + READBACK_TIMEOUT;
+    loop {
+        let _ = device.poll(wgpu::PollType::Wait {
+            submission_index: None,
+            timeout: Some(READBACK_TIMEOUT),
+        });
+        if let Some(data) = capture.take_mapped_data(device) {
+            return Ok(data);
+        }
+        if std::time::Instant::now() >= deadline {
+            bail!(
+                "frame readback timed out after {}s",
+                READBACK_TIMEOUT.as_secs()
+            );
+        }
+    }
+}
+
+/// Copy an HDR effect target into the 8-bit capture texture (values clamp).
+fn blit(
+    gpu: &Headless,
+    encoder: &mut wgpu::CommandEncoder,
+    source: &RenderTarget,
+    capture: &FrameCapture,
+) {
+    let bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("golden-blit-bg"),
+        layout: &gpu.blit_bgl,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: wgpu::BindingResource::TextureView(&source.view),
+        }],
+    });
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("golden-blit"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: &capture.view,
+            depth_slice: None,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    pass.set_pipeline(&gpu.blit);
+    pass.set_bind_group(0, &bind_group, &[]);
+    pass.draw(0..3, 0..1);
+}
+
+fn blit_pipeline(device: &Device) -> (wgpu::RenderPipeline, wgpu::BindGroupLayout) {
+    let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("golden-blit-bgl"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        }],
+    });
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("golden-blit-layout"),
+        bind_group_layouts: &[&bgl],
+        push_constant_ranges: &[],
+    });
+    let src = format!(
+        "{FULLSCREEN_TRIANGLE_VS}\n\
+         @group(0) @binding(0) var src_tex: texture_2d<f32>;\n\
+         @fragment fn fs_main(@builtin(position) pos: vec4f) -> @location(0) vec4f {{\n\
+             return textureLoad(src_tex, vec2i(pos.xy), 0);\n\
+         }}"
+    );
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("golden-blit"),
+        source: wgpu::ShaderSource::Wgsl(src.into()),
+    });
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("golden-blit-pipeline"),
+        layout: Some(&layout),
+        vertex: wgpu::VertexState {
+            module: &module,
+            entry_point: Some("vs_main"),
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &module,
+            entry_point: Some("fs_main"),
+            targets: &[Some(wgpu::ColorTargetState {
+                format: CAPTURE_FORMAT,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: Default::default(),
+        multiview: None,
+        cache: None,
+    });
+    (pipeline, bgl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn options_parse() {
+        let o = GoldenOptions::from_args(&args(
+            "--golden --bless --frames 30 --only Aurora --tolerance 2.5 --golden-dir /tmp/g",
+        ))
+        .unwrap();
+        assert!(o.bless);
+        assert_eq!(o.frames, Some(30));
+        assert_eq!(o.only.as_deref(), Some("aurora"));
+        assert_eq!(o.tolerance, 2.5);
+        assert_eq!(o.dir, PathBuf::from("/tmp/g"));
+
+        let d = GoldenOptions::from_args(&args("--golden")).unwrap();
+        assert!(!d.bless);
+        assert_eq!(d.frames, None);
+        assert_eq!(d.dir, PathBuf::from("tests/golden"));
+    }
+
+    #[test]
+    fn options_reject_unknown_and_missing_values() {
+        assert!(GoldenOptions::from_args(&args("--golden --blss")).is_err());
+        assert!(GoldenOptions::from_args(&args("--golden --frames")).is_err());
+        assert!(GoldenOptions::from_args(&args("--golden --frames many")).is_err());
+    }
+
+    #[test]
+    fn fnv_is_stable() {
+        // Reference values for FNV-1a 64; the manifest depends on them.
+        assert_eq!(fnv1a64(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a64(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn mean_diff_ignores_alpha() {
+        let a = [10, 20, 30, 255, 0, 0, 0, 0];
+        let b = [13, 20, 30, 0, 0, 0, 6, 255];
+        // (3 + 6) / 6 channels
+        assert!((mean_abs_diff(&a, &b) - 1.5).abs() < 1e-6);
+        assert_eq!(mean_abs_diff(&a, &a), 0.0);
+    }
+
+    #[test]
+    fn synthetic_audio_is_a_steady_pulse() {
+        let mut u = ShaderUniforms::zeroed();
+        synthetic_audio(&mut u, 0);
+        assert_eq!(u.beat, 1.0);
+        synthetic_audio(&mut u, 15);
+        assert_eq!(u.beat, 0.0);
+        assert!((u.beat_phase - 0.5).abs() < 1e-6);
+        synthetic_audio(&mut u, 30);
+        assert_eq!(u.beat, 1.0);
+    }
+
+    #[test]
+    fn effect_key_prefers_file_stem() {
+        let mut e: PfxEffect = serde_json::from_str(r#"{"name": "Deep Field"}"#).unwrap();
+        assert_eq!(effect_key(&e), "deep_field");
+        e.source_path = Some(PathBuf::from("/x/assets/effects/Drift.pfx"));
+        assert_eq!(effect_key(&e), "drift");
+    }
+}
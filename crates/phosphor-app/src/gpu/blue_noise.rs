@@ -0,0 +1,194 @@
+//! Blue-noise tile for output dithering (#2236). Blue noise has no low
+//! frequencies, so the dither reads as a fine even grain instead of the
+//! blotches white noise leaves in dark gradients.
+//!
+//! The tile is built once with void-and-cluster (Ulichney 1993) from a fixed
+//! seed, so every run and every deterministic render (#2174) dithers alike.
+
+use std::sync::OnceLock;
+
+use wgpu::{Device, Queue, Texture, TextureView};
+
+/// Tile edge in pixels; the shader wraps it across the output.
+pub const SIZE: usize = 64;
+/// Spread of the energy filter, in pixels.
+const SIGMA: f32 = 1.5;
+/// The filter is cut off where its weight is negligible.
+const RADIUS: i32 = 6;
+/// Share of pixels in the initial binary pattern.
+const INITIAL_DENSITY: usize = 10;
+
+/// Thresholds in 0..=255, row-major, `size * size` of them.
+pub fn generate(size: usize, seed: u64) -> Vec<u8> {
+    let n = size * size;
+    let mut field = Field::new(size);
+    let mut rng = seed | 1;
+
+    // Initial pattern: a few random pixels, relaxed until the tightest
+    // cluster and the largest void are the same pixel.
+    let ones = (n / INITIAL_DENSITY).max(1);
+    let mut placed = 0;
+    while placed < ones {
+        rng ^= rng << 13;
+        rng ^= rng >> 7;
+        rng ^= rng << 17;
+        let i = (rng % n as u64) as usize;
+        if !field.on[i] {
+            field.set(i, true);
+            placed += 1;
+        }
+    }
+    for _ in 0..n {
+        let cluster = field.tightest_cluster();
+        field.set(cluster, false);
+        let void = field.largest_void();
+        field.set(void, true);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut rank = vec![0; n];
+    // Rank the initial pixels by taking clusters away from a copy...
+    let mut thinning = field.clone();
+    for r in (0..ones).rev() {
+        let cluster = thinning.tightest_cluster();
+        thinning.set(cluster, false);
+        rank[cluster] = r;
+    }
+    // ...and the rest by filling the largest void each time.
+    for r in ones..n {
+        let void = field.largest_void();
+        field.set(void, true);
+        rank[void] = r;
+    }
+    rank.into_iter().map(|r| (r * 256 / n) as u8).collect()
+}
+
+/// A binary pattern on a torus and its Gaussian-filtered energy.
+#[derive(Clone)]
+struct Field {
+    size: usize,
+    on: Vec<bool>,
+    energy: Vec<f32>,
+    kernel: Vec<(i32, i32, f32)>,
+}
+
+impl Field {
+    fn new(size: usize) -> Self {
+        let mut kernel = Vec::new();
+        for dy in -RADIUS..=RADIUS {
+            for dx in -RADIUS..=RADIUS {
+                let d2 = (dx * dx + dy * dy) as f32;
+                kernel.push((dx, dy, (-d2 / (2.0 * SIGMA * SIGMA)).exp()));
+            }
+        }
+        Self {
+            size,
+            on: vec![false; size * size],
+            energy: vec![0.0; size * size],
+            kernel,
+        }
+    }
+
+    fn set(&mut self, i: usize, on: bool) {
+        self.on[i] = on;
+        let sign = if on { 1.0 } else { -1.0 };
+        let s = self.size as i32;
+        let (x, y) = ((i % self.size) as i32, (i / self.size) as i32);
+        for &(dx, dy, w) in &self.kernel {
+            let j = ((y + dy).rem_euclid(s) * s + (x + dx).rem_euclid(s)) as usize;
+            self.energy[j] += sign * w;
+        }
+    }
+
+    /// The set pixel with the most set neighbours.
+    fn tightest_cluster(&self) -> usize {
+        self.pick(true, |a, b| a > b)
+    }
+
+    /// The unset pixel furthest from any set one.
+    fn largest_void(&self) -> usize {
+        self.pick(false, |a, b| a < b)
+    }
+
+    fn pick(&self, on: bool, better: impl Fn(f32, f32) -> bool) -> usize {
+        let mut best = None;
+        for (i, &e) in self.energy.iter().enumerate() {
+            if self.on[i] == on && best.is_none_or(|(_, b)| better(e, b)) {
+                best = Some((i, e));
+            }
+        }
+        best.map_or(0, |(i, _)| i)
+    }
+}
+
+/// The shared tile as an `R8Unorm` texture, generated on first use.
+pub fn create_texture(device: &Device, queue: &Queue) -> (Texture, TextureView) {
+    static TILE: OnceLock<Vec<u8>> = OnceLock::new();
+    let tile = TILE.get_or_init(|| generate(SIZE, 0x5eed_b10e));
+    let extent = wgpu::Extent3d {
+        width: SIZE as u32,
+        height: SIZE as u32,
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("blue-noise"),
+        size: extent,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::R8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        tile,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(SIZE as u32),
+            rows_per_image: None,
+        },
+        extent,
+    );
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_level_is_used_equally() {
+        let tile = generate(SIZE, 1);
+        let mut counts = [0usize; 256];
+        for &v in &tile {
+            counts[v as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| c == SIZE * SIZE / 256));
+    }
+
+    #[test]
+    fn neighbours_differ_more_than_white_noise() {
+        // Uniform white noise averages 256/3 ≈ 85 between neighbours; blue
+        // noise pushes neighbouring thresholds apart.
+        let tile = generate(SIZE, 1);
+        let mut total = 0u32;
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let a = tile[y * SIZE + x] as i32;
+                let b = tile[y * SIZE + (x + 1) % SIZE] as i32;
+                total += a.abs_diff(b);
+            }
+        }
+        let mean = total as f32 / (SIZE * SIZE) as f32;
+        assert!(mean > 95.0, "mean neighbour difference {mean}");
+    }
+}
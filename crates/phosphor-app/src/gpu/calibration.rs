@@ -0,0 +1,124 @@
+//! Per-output colour calibration (#2237): gamma, RGB gain and offset, and
+//! saturation trims for one physical output, so a venue projector with a
+//! green cast can be corrected without touching NDI or recordings. Applied
+//! after the output master, before the dither, in `master_grade.wgsl`.
+
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+/// Where a final pass is headed. Each gets its own calibration uniform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputTarget {
+    Window,
+    Ndi,
+    /// Recordings and clips keep the uncalibrated image.
+    Recording,
+}
+
+/// Trims for one output. Identity by default.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Calibration {
+    /// 1 = unchanged; above 1 lifts the shadows.
+    pub gamma: f32,
+    /// Per-channel multiplier.
+    pub gain: [f32; 3],
+    /// Per-channel lift, faded out with blackout and the master level.
+    pub offset: [f32; 3],
+    /// 0 = greyscale, 1 = unchanged.
+    pub saturation: f32,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self {
+            gamma: 1.0,
+            gain: [1.0; 3],
+            offset: [0.0; 3],
+            saturation: 1.0,
+        }
+    }
+}
+
+impl Calibration {
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn build_uniforms(&self) -> CalibrationUniforms {
+        CalibrationUniforms {
+            gain: self.gain.map(|g| g.clamp(0.0, 4.0)),
+            inv_gamma: 1.0 / self.gamma.clamp(0.1, 4.0),
+            offset: self.offset.map(|o| o.clamp(-1.0, 1.0)),
+            saturation: self.saturation.clamp(0.0, 4.0),
+        }
+    }
+}
+
+/// Saved with the settings: one calibration per physical output.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputCalibration {
+    pub window: Calibration,
+    pub ndi: Calibration,
+}
+
+impl OutputCalibration {
+    /// This target's trims; recordings always get the identity.
+    pub fn for_target(&self, target: OutputTarget) -> Calibration {
+        match target {
+            OutputTarget::Window => self.window,
+            OutputTarget::Ndi => self.ndi,
+            OutputTarget::Recording => Calibration::default(),
+        }
+    }
+}
+
+/// Matches `Calibration` in `master_grade.wgsl`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Pod, Zeroable)]
+pub struct CalibrationUniforms {
+    pub gain: [f32; 3],
+    pub inv_gamma: f32,
+    pub offset: [f32; 3],
+    pub saturation: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recordings_stay_uncalibrated() {
+        let cal = OutputCalibration {
+            window: Calibration {
+                gain: [1.0, 0.9, 1.0],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(cal.for_target(OutputTarget::Window).gain[1], 0.9);
+        assert!(cal.for_target(OutputTarget::Ndi).is_identity());
+        assert!(cal.for_target(OutputTarget::Recording).is_identity());
+    }
+
+    #[test]
+    fn uniforms_fill_two_vec4s() {
+        assert_eq!(std::mem::size_of::<CalibrationUniforms>(), 32);
+        let u = Calibration {
+            gamma: 2.0,
+            ..Default::default()
+        }
+        .build_uniforms();
+        assert_eq!(u.inv_gamma, 0.5);
+    }
+
+    #[test]
+    fn partial_json_fills_defaults() {
+        let cal: OutputCalibration =
+            serde_json::from_str(r#"{ "window": { "saturation": 0.8 } }"#).unwrap();
+        assert_eq!(cal.window.saturation, 0.8);
+        assert_eq!(cal.window.gain, [1.0; 3]);
+        assert!(cal.ndi.is_identity());
+    }
+}
@@ -0,0 +1,121 @@
+//! What the GPU can do, checked against each effect's declared requirements
+//! (#2195) so effects a weaker GPU can't run are marked in the UI instead of
+//! failing at pipeline creation.
+
+use wgpu::{Adapter, Device};
+
+use crate::effect::format::EffectRequirements;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GpuCapabilities {
+    pub compute: bool,
+    pub storage_textures: bool,
+    pub float_filtering: bool,
+    /// Video memory in MB, when the driver reports it. wgpu doesn't, so this
+    /// is only known on Linux drivers that publish it in sysfs (amdgpu).
+    pub vram_mb: Option<u32>,
+}
+
+impl GpuCapabilities {
+    /// Read from the adapter, and from `device` for features that must have
+    /// been enabled on it.
+    pub fn detect(adapter: &Adapter, device: &Device) -> Self {
+        let downlevel = adapter.get_downlevel_capabilities();
+        let compute = downlevel
+            .flags
+            .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS);
+        Self {
+            compute,
+            storage_textures: compute && adapter.limits().max_storage_textures_per_shader_stage > 0,
+            float_filtering: device
+                .features()
+                .contains(wgpu::Features::FLOAT32_FILTERABLE),
+            vram_mb: vram_mb(&adapter.get_info()),
+        }
+    }
+
+    /// What `req` asks for that this GPU lacks, as a sentence for the UI, or
+    /// `None` when the effect can run. Unknown VRAM never blocks an effect.
+    pub fn unmet(&self, req: &EffectRequirements) -> Option<String> {
+        let mut missing = Vec::new();
+        if req.compute && !self.compute {
+            missing.push("compute shaders".to_string());
+        }
+        if req.storage_textures && !self.storage_textures {
+            missing.push("storage textures".to_string());
+        }
+        if req.float_filtering && !self.float_filtering {
+            missing.push("filterable float textures".to_string());
+        }
+        if let Some(vram) = self.vram_mb
+            && req.min_vram_mb > vram
+        {
+            missing.push(format!(
+                "{} MB of video memory (this GPU has {vram} MB)",
+                req.min_vram_mb
+            ));
+        }
+        if missing.is_empty() {
+            None
+        } else {
+            Some(format!("Needs {}", missing.join(", ")))
+        }
+    }
+}
+
+/// Total VRAM of the card whose PCI device id matches the adapter's.
+#[cfg(target_os = "linux")]
+fn vram_mb(info: &wgpu::AdapterInfo) -> Option<u32> {
+    let cards = std::fs::read_dir("/sys/class/drm").ok()?;
+    for card in cards.flatten() {
+        let device = card.path().join("device");
+        let id = std::fs::read_to_string(device.join("device")).unwrap_or_default();
+        let id = u32::from_str_radix(id.trim().trim_start_matches("0x"), 16);
+        if id != Ok(info.device) {
+            continue;
+        }
+        let bytes = std::fs::read_to_string(device.join("mem_info_vram_total")).ok()?;
+        let bytes: u64 = bytes.trim().parse().ok()?;
+        return u32::try_from(bytes / (1024 * 1024)).ok();
+    }
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+fn vram_mb(_info: &wgpu::AdapterInfo) -> Option<u32> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unmet_lists_what_is_missing() {
+        let weak = GpuCapabilities {
+            compute: false,
+            storage_textures: false,
+            float_filtering: true,
+            vram_mb: Some(1024),
+        };
+        let req = EffectRequirements {
+            compute: true,
+            float_filtering: true,
+            min_vram_mb: 2048,
+            ..Default::default()
+        };
+        assert_eq!(
+            weak.unmet(&req).as_deref(),
+            Some("Needs compute shaders, 2048 MB of video memory (this GPU has 1024 MB)")
+        );
+        assert_eq!(weak.unmet(&EffectRequirements::default()), None);
+
+        // Unknown VRAM doesn't block.
+        let unknown = GpuCapabilities {
+            compute: true,
+            vram_mb: None,
+            ..weak
+        };
+        assert_eq!(unknown.unmet(&req), None);
+    }
+}
@@ -7,12 +7,16 @@ use wgpu::{
     TextureSampleType, TextureViewDimension, VertexState,
 };
 
+use super::error_scope::ErrorScope;
 use super::fullscreen_quad::FULLSCREEN_TRIANGLE_VS_WITH_UV;
-use super::layer::BlendMode;
+use super::layer::{BlendMode, DEFAULT_MAX_LAYERS};
 use super::render_target::{PingPongTarget, RenderTarget};
 
+const BLEND_MODES_LIB: &str = include_str!("../../../../assets/shaders/builtin/blend_modes.wgsl");
 const COMPOSITE_FS: &str = include_str!("../../../../assets/shaders/builtin/composite.wgsl");
+const HOOK_PRELUDE: &str = include_str!("../../../../assets/shaders/builtin/composite_hook.wgsl");
 const BLIT_FS: &str = include_str!("../../../../assets/shaders/builtin/blit.wgsl");
+const TRAIL_FS: &str = include_str!("../../../../assets/shaders/builtin/trail.wgsl");
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
@@ -23,13 +27,106 @@ struct CompositeUniforms {
     _pad1: f32,
 }
 
+/// What a compositor hook (#2252) knows about the layer it is compositing;
+/// mirrors `CompositeInfo` in composite_hook.wgsl.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct HookUniforms {
+    layer: u32,
+    slot: u32,
+    count: u32,
+    blend_mode: u32,
+    opacity: f32,
+    time: f32,
+    resolution: [f32; 2],
+}
+
+/// Folder under `assets/shaders` holding compositor hooks (#2252).
+pub const HOOK_DIR: &str = "composite";
+
+fn hook_dir() -> std::path::PathBuf {
+    crate::effect::loader::assets_dir()
+        .join("shaders")
+        .join(HOOK_DIR)
+}
+
+/// Compositor hooks found in [`HOOK_DIR`], by file stem, sorted.
+pub fn list_hooks() -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(hook_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "wgsl"))
+        .filter_map(|p| Some(p.file_stem()?.to_str()?.to_string()))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Full shader for a hook file's source: the blend functions, the prelude
+/// with its bindings and helpers, then the user's `composite` function.
+pub fn hook_source(user: &str) -> String {
+    format!("{FULLSCREEN_TRIANGLE_VS_WITH_UV}\n{BLEND_MODES_LIB}\n{HOOK_PRELUDE}\n{user}")
+}
+
+/// Per-layer trail history (#2173): the layer's last trailed output plus its
+/// amount uniform. Only exists while the layer's trail is above zero.
+pub struct LayerTrail {
+    target: PingPongTarget,
+    uniform_buffer: wgpu::Buffer,
+}
+
+impl LayerTrail {
+    pub fn new(
+        device: &Device,
+        queue: &Queue,
+        width: u32,
+        height: u32,
+        hdr_format: TextureFormat,
+    ) -> Self {
+        let target = PingPongTarget::new_cleared(device, queue, width, height, hdr_format, 1.0);
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("layer-trail-uniforms"),
+            size: std::mem::size_of::<CompositeUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Self {
+            target,
+            uniform_buffer,
+        }
+    }
+
+    /// Last frame's trailed output.
+    pub fn previous(&self) -> &RenderTarget {
+        self.target.read_target()
+    }
+
+    pub fn flip(&mut self) {
+        self.target.flip();
+    }
+
+    pub fn resize(&mut self, device: &Device, queue: &Queue, width: u32, height: u32) {
+        self.target.resize_cleared(device, queue, width, height);
+    }
+}
+
 /// GPU compositor that blends multiple layer outputs together.
 pub struct Compositor {
     composite_pipeline: RenderPipeline,
     blit_pipeline: RenderPipeline,
+    trail_pipeline: RenderPipeline,
     composite_bgl: BindGroupLayout,
     blit_bgl: BindGroupLayout,
     uniform_buffers: Vec<wgpu::Buffer>,
+    /// User compositor hook (#2252); `None` runs the built-in blend modes.
+    hook_pipeline: Option<RenderPipeline>,
+    hook_bgl: BindGroupLayout,
+    hook_buffers: Vec<wgpu::Buffer>,
+    /// Seconds, for animated hooks; set by the app each frame.
+    pub hook_time: f32,
+    hdr_format: TextureFormat,
     /// Ping-pong accumulator for sequential compositing.
     pub accumulator: PingPongTarget,
 }
@@ -51,9 +148,20 @@ impl Compositor {
             device,
             "compositor-composite",
             &composite_bgl,
-            COMPOSITE_FS,
+            &format!("{BLEND_MODES_LIB}\n{COMPOSITE_FS}"),
             hdr_format,
         );
+        // Hook passes bind the same textures, with the larger hook uniforms
+        let hook_bgl = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("compositor-hook-bgl"),
+            entries: &[
+                tex_entry(0),
+                sampler_entry(1),
+                tex_entry(2),
+                sampler_entry(3),
+                uniform_entry(4, std::mem::size_of::<HookUniforms>()),
+            ],
+        });
 
         // Blit pipeline: copy first layer to accumulator
         let blit_bgl = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
@@ -63,16 +171,22 @@ impl Compositor {
         let blit_pipeline =
             create_fs_pipeline(device, "compositor-blit", &blit_bgl, BLIT_FS, hdr_format);
 
-        // One uniform buffer per composite pass (max 8: 1 for first-layer opacity + 7 for layers[1..])
-        let uniform_buffers: Vec<wgpu::Buffer> = (0..8)
-            .map(|i| {
-                device.create_buffer(&wgpu::BufferDescriptor {
-                    label: Some(&format!("compositor-uniforms-{i}")),
-                    size: std::mem::size_of::<CompositeUniforms>() as u64,
-                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-                    mapped_at_creation: false,
-                })
-            })
+        // Trail pass: previous + current + amount, the same shape as a composite
+        let trail_pipeline = create_fs_pipeline(
+            device,
+            "compositor-trail",
+            &composite_bgl,
+            TRAIL_FS,
+            hdr_format,
+        );
+
+        // One uniform buffer per composite pass (1 for first-layer opacity + 1 per
+        // layer after it); `reserve` grows the set past the default layer cap.
+        let uniform_buffers: Vec<wgpu::Buffer> = (0..DEFAULT_MAX_LAYERS)
+            .map(|i| create_uniform_buffer(device, i))
+            .collect();
+        let hook_buffers: Vec<wgpu::Buffer> = (0..DEFAULT_MAX_LAYERS)
+            .map(|i| create_hook_buffer(device, i))
             .collect();
 
         let accumulator = PingPongTarget::new(device, width, height, hdr_format, 1.0);
@@ -80,13 +194,150 @@ impl Compositor {
         Self {
             composite_pipeline,
             blit_pipeline,
+            trail_pipeline,
             composite_bgl,
             blit_bgl,
             uniform_buffers,
+            hook_pipeline: None,
+            hook_bgl,
+            hook_buffers,
+            hook_time: 0.0,
+            hdr_format,
             accumulator,
         }
     }
 
+    /// Make sure `composite` can take `layers` inputs (#2170).
+    pub fn reserve(&mut self, device: &Device, layers: usize) {
+        for i in self.uniform_buffers.len()..layers {
+            self.uniform_buffers.push(create_uniform_buffer(device, i));
+        }
+        for i in self.hook_buffers.len()..layers {
+            self.hook_buffers.push(create_hook_buffer(device, i));
+        }
+    }
+
+    /// Run a compositor hook (#2252) built from `user` WGSL in place of the
+    /// built-in blend, or go back to the built-in one with `None`. A hook
+    /// that fails to compile leaves the current compositing as it is.
+    pub fn set_hook(&mut self, device: &Device, user: Option<&str>) -> Result<(), String> {
+        let Some(user) = user else {
+            self.hook_pipeline = None;
+            return Ok(());
+        };
+        let scope = ErrorScope::push(device);
+        let pipeline = create_pipeline_from_source(
+            device,
+            "compositor-hook",
+            &self.hook_bgl,
+            &hook_source(user),
+            self.hdr_format,
+        );
+        if let Some(error) = scope.pop() {
+            return Err(error.summary());
+        }
+        self.hook_pipeline = Some(pipeline);
+        Ok(())
+    }
+
+    /// [`Self::set_hook`] with the hook file `name` from [`HOOK_DIR`].
+    pub fn load_hook(&mut self, device: &Device, name: Option<&str>) -> Result<(), String> {
+        let Some(name) = name else {
+            return self.set_hook(device, None);
+        };
+        let path = hook_dir().join(format!("{name}.wgsl"));
+        let source =
+            std::fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        self.set_hook(device, Some(&source))
+            .map_err(|e| format!("{name}: {e}"))
+    }
+
+    pub fn has_hook(&self) -> bool {
+        self.hook_pipeline.is_some()
+    }
+
+    /// Composite through the hook: every layer, the bottom one included,
+    /// runs the hook over what is beneath it, starting from transparent
+    /// black. `layers` are (stack index, output, blend mode, opacity),
+    /// bottom first. Call only with a hook set.
+    pub fn composite_hooked<'a>(
+        &'a self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        layers: &[(usize, &RenderTarget, BlendMode, f32)],
+    ) -> &'a RenderTarget {
+        assert!(!layers.is_empty());
+        let pipeline = self
+            .hook_pipeline
+            .as_ref()
+            .expect("composite_hooked needs a hook");
+
+        let mut read_idx = 1 - self.accumulator.current;
+        self.blit_into(device, encoder, None, &self.accumulator.targets[read_idx]);
+        let resolution = {
+            let target = &self.accumulator.targets[read_idx];
+            [target.width as f32, target.height as f32]
+        };
+        for (slot, &(layer, fg, blend_mode, opacity)) in layers.iter().enumerate() {
+            let uniforms = HookUniforms {
+                layer: layer as u32,
+                slot: slot as u32,
+                count: layers.len() as u32,
+                blend_mode: blend_mode.as_u32(),
+                opacity,
+                time: self.hook_time,
+                resolution,
+            };
+            queue.write_buffer(&self.hook_buffers[slot], 0, bytemuck::bytes_of(&uniforms));
+
+            let write_idx = 1 - read_idx;
+            let bg_target = &self.accumulator.targets[read_idx];
+            let bind_group = device.create_bind_group(&BindGroupDescriptor {
+                label: Some("compositor-hook-bg"),
+                layout: &self.hook_bgl,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&bg_target.view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(&bg_target.sampler),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: BindingResource::TextureView(&fg.view),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: BindingResource::Sampler(&fg.sampler),
+                    },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: self.hook_buffers[slot].as_entire_binding(),
+                    },
+                ],
+            });
+            run_fullscreen_pass(
+                encoder,
+                "compositor-hook",
+                pipeline,
+                &bind_group,
+                &self.accumulator.targets[write_idx].view,
+            );
+            read_idx = write_idx;
+        }
+        &self.accumulator.targets[read_idx]
+    }
+
+    /// Clear the accumulator and return it: the output when no layer is visible.
+    pub fn clear_output(&self, device: &Device, encoder: &mut CommandEncoder) -> &RenderTarget {
+        let target = self.accumulator.write_target();
+        self.blit_into(device, encoder, None, target);
+        target
+    }
+
     /// Composite multiple layer outputs into a single HDR result.
     /// Returns a reference to the final composited render target.
     ///
@@ -270,6 +521,108 @@ impl Compositor {
         &self.accumulator.targets[read_idx]
     }
 
+    /// Fold `current` into the layer's trail history at `amount` (0..1) and
+    /// return the trailed frame, which stands in for the layer's output.
+    pub fn apply_trail<'a>(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        current: &RenderTarget,
+        trail: &'a LayerTrail,
+        amount: f32,
+    ) -> &'a RenderTarget {
+        let params: [f32; 4] = [amount, 0.0, 0.0, 0.0];
+        queue.write_buffer(&trail.uniform_buffer, 0, bytemuck::bytes_of(&params));
+
+        let prev = trail.previous();
+        let out = trail.target.write_target();
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("compositor-trail-bg"),
+            layout: &self.composite_bgl,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&prev.view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&prev.sampler),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::TextureView(&current.view),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindingResource::Sampler(&current.sampler),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: trail.uniform_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        run_fullscreen_pass(
+            encoder,
+            "compositor-trail",
+            &self.trail_pipeline,
+            &bind_group,
+            &out.view,
+        );
+        out
+    }
+
+    /// Copy `src` into `dst` (same HDR format), or clear `dst` to transparent
+    /// black when there is no source. Feeds effect layers that sample other layers.
+    pub fn blit_into(
+        &self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        src: Option<&RenderTarget>,
+        dst: &RenderTarget,
+    ) {
+        let Some(src) = src else {
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("compositor-clear"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &dst.view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            return;
+        };
+        let blit_bg = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("compositor-blit-into-bg"),
+            layout: &self.blit_bgl,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&src.view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&src.sampler),
+                },
+            ],
+        });
+        run_fullscreen_pass(
+            encoder,
+            "compositor-blit-into",
+            &self.blit_pipeline,
+            &blit_bg,
+            &dst.view,
+        );
+    }
+
     pub fn resize(&mut self, device: &Device, width: u32, height: u32) {
         self.accumulator.resize(device, width, height);
     }
@@ -277,6 +630,24 @@ impl Compositor {
 
 // --- Helper functions (same pattern as postprocess.rs) ---
 
+fn create_uniform_buffer(device: &Device, index: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(&format!("compositor-uniforms-{index}")),
+        size: std::mem::size_of::<CompositeUniforms>() as u64,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+fn create_hook_buffer(device: &Device, index: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(&format!("compositor-hook-uniforms-{index}")),
+        size: std::mem::size_of::<HookUniforms>() as u64,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
 fn tex_entry(binding: u32) -> BindGroupLayoutEntry {
     BindGroupLayoutEntry {
         binding,
@@ -320,6 +691,17 @@ fn create_fs_pipeline(
     target_format: TextureFormat,
 ) -> RenderPipeline {
     let full_source = format!("{FULLSCREEN_TRIANGLE_VS_WITH_UV}\n{fragment_src}");
+    create_pipeline_from_source(device, label, bgl, &full_source, target_format)
+}
+
+/// [`create_fs_pipeline`] for a source that already has its vertex stage.
+fn create_pipeline_from_source(
+    device: &Device,
+    label: &str,
+    bgl: &BindGroupLayout,
+    full_source: &str,
+    target_format: TextureFormat,
+) -> RenderPipeline {
     let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some(label),
         source: wgpu::ShaderSource::Wgsl(full_source.into()),
@@ -384,3 +766,37 @@ fn run_fullscreen_pass(
     pass.set_bind_group(0, bind_group, &[]);
     pass.draw(0..3, 0..1);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(src: &str) {
+        let module = wgpu::naga::front::wgsl::parse_str(src).expect("shader parses");
+        wgpu::naga::valid::Validator::new(
+            wgpu::naga::valid::ValidationFlags::all(),
+            wgpu::naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .expect("shader validates");
+    }
+
+    #[test]
+    fn composite_and_example_hooks_validate() {
+        validate(&format!(
+            "{FULLSCREEN_TRIANGLE_VS_WITH_UV}\n{BLEND_MODES_LIB}\n{COMPOSITE_FS}"
+        ));
+        for hook in [
+            include_str!("../../../../assets/shaders/composite/split.wgsl"),
+            include_str!("../../../../assets/shaders/composite/mirror.wgsl"),
+            include_str!("../../../../assets/shaders/composite/mosaic.wgsl"),
+        ] {
+            validate(&hook_source(hook));
+        }
+    }
+
+    #[test]
+    fn hook_uniforms_fill_two_vec4s() {
+        assert_eq!(std::mem::size_of::<HookUniforms>(), 32);
+    }
+}
@@ -7,15 +7,15 @@ use wgpu::{
 };
 use winit::window::Window;
 
+use super::error_scope::{GpuError, GpuErrorLog};
+
 /// Path for persisted pipeline cache data.
 fn pipeline_cache_path() -> Option<std::path::PathBuf> {
-    dirs::config_dir().map(|d| d.join("phosphor").join("pipeline_cache.bin"))
+    Some(crate::paths::config_dir().join("pipeline_cache.bin"))
 }
 
 pub struct GpuContext {
-    #[allow(dead_code)]
     pub instance: Instance,
-    #[allow(dead_code)]
     pub adapter: Adapter,
     pub device: Device,
     pub queue: Queue,
@@ -24,12 +24,53 @@ pub struct GpuContext {
     pub format: TextureFormat,
     /// Pipeline cache for faster shader compilation on subsequent launches.
     pub pipeline_cache: Option<wgpu::PipelineCache>,
+    /// Effect pipelines shared between layers (#2208). Lives with the device
+    /// so a recreated device starts empty.
+    pub shared_pipelines: super::SharedPipelines,
     /// Set to true when the GPU device is lost (driver crash/reset).
     pub device_lost: Arc<std::sync::atomic::AtomicBool>,
+    /// Errors raised outside any error scope, for the status bar (#2230).
+    pub uncaptured_errors: GpuErrorLog,
 }
 
 impl GpuContext {
     pub fn new(window: Arc<Window>) -> Result<Self> {
+        let ctx = Self::create(window)?;
+        ctx.surface.configure(&ctx.device, &ctx.surface_config);
+        Ok(ctx)
+    }
+
+    /// Replace a lost device with a fresh instance, adapter, device and
+    /// surface (#2176). The old context is dropped before the new surface is
+    /// configured, since a window can only back one swapchain at a time.
+    pub fn recreate(&mut self, window: Arc<Window>) -> Result<()> {
+        let fresh = Self::create(window)?;
+        *self = fresh;
+        self.surface.configure(&self.device, &self.surface_config);
+        Ok(())
+    }
+
+    /// Give the window a new surface on the same device (#2231), for when the
+    /// old one stays lost after its monitor was unplugged. The old surface is
+    /// dropped before the new one is configured.
+    pub fn recreate_surface(&mut self, window: Arc<Window>) -> Result<()> {
+        let size = window.inner_size();
+        let surface = self.instance.create_surface(window)?;
+        let capabilities = surface.get_capabilities(&self.adapter);
+        anyhow::ensure!(
+            capabilities.formats.contains(&self.format),
+            "the new surface doesn't support {:?}",
+            self.format
+        );
+        self.surface = surface;
+        self.surface_config.width = size.width.max(1);
+        self.surface_config.height = size.height.max(1);
+        self.surface.configure(&self.device, &self.surface_config);
+        Ok(())
+    }
+
+    /// Everything `new` does except configuring the surface.
+    fn create(window: Arc<Window>) -> Result<Self> {
         let instance = Instance::new(&InstanceDescriptor::default());
 
         let surface = instance.create_surface(window.clone())?;
@@ -47,16 +88,22 @@ impl GpuContext {
         if supported.contains(wgpu::Features::PIPELINE_CACHE) {
             required_features |= wgpu::Features::PIPELINE_CACHE;
         }
+        // For effects that declare `float_filtering` (#2195).
+        if supported.contains(wgpu::Features::FLOAT32_FILTERABLE) {
+            required_features |= wgpu::Features::FLOAT32_FILTERABLE;
+        }
+
+        // Timestamps between passes time each layer for the frame-time guard (#2243)
+        if supported.contains(wgpu::Features::TIMESTAMP_QUERY) {
+            required_features |= wgpu::Features::TIMESTAMP_QUERY;
+        }
+        if supported.contains(wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS) {
+            required_features |= wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS;
+        }
 
         #[cfg(feature = "profiling")]
         {
-            // Request timestamp queries if the adapter supports them
-            if supported.contains(wgpu::Features::TIMESTAMP_QUERY) {
-                required_features |= wgpu::Features::TIMESTAMP_QUERY;
-            }
-            if supported.contains(wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS) {
-                required_features |= wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS;
-            }
+            // The profiler also times scopes inside passes
             if supported.contains(wgpu::Features::TIMESTAMP_QUERY_INSIDE_PASSES) {
                 required_features |= wgpu::Features::TIMESTAMP_QUERY_INSIDE_PASSES;
             }
@@ -83,13 +130,22 @@ impl GpuContext {
         {
             let lost = device_lost.clone();
             device.set_device_lost_callback(move |reason, msg| {
+                // `Destroyed` is our own release, e.g. the old device after a recovery.
+                if reason == wgpu::DeviceLostReason::Destroyed {
+                    return;
+                }
                 log::error!("GPU device lost ({reason:?}): {msg}");
                 lost.store(true, std::sync::atomic::Ordering::SeqCst);
             });
         }
-        device.on_uncaptured_error(Arc::new(|error| {
-            log::error!("Uncaptured GPU error: {error}");
-        }));
+        let uncaptured_errors = GpuErrorLog::default();
+        {
+            let sink = uncaptured_errors.clone();
+            device.on_uncaptured_error(Arc::new(move |error| {
+                log::error!("Uncaptured GPU error: {error}");
+                sink.push(GpuError::new(&error));
+            }));
+        }
 
         // Create pipeline cache (load from disk if available). A stale or foreign cache
         // (GPU swap, driver update, corruption) must NOT be fatal. In wgpu 27, `fallback:
@@ -156,7 +212,6 @@ impl GpuContext {
             alpha_mode: capabilities.alpha_modes[0],
             view_formats: vec![],
         };
-        surface.configure(&device, &surface_config);
 
         log::info!(
             "GPU initialized: {} ({:?}), present mode: {:?} (available: {:?})",
@@ -175,7 +230,9 @@ impl GpuContext {
             surface_config,
             format,
             pipeline_cache,
+            shared_pipelines: super::SharedPipelines::default(),
             device_lost,
+            uncaptured_errors,
         })
     }
 
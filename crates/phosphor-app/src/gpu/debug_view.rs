@@ -0,0 +1,395 @@
+//! Debug view (#2229): draw any intermediate texture — a pass output, a
+//! feedback buffer, a particle aux texture — over the output, fullscreen or
+//! as a picture-in-picture. A RenderDoc-lite for effect authors.
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::{CommandEncoder, Device, Queue, RenderPipeline, Texture, TextureFormat, TextureView};
+
+use super::fullscreen_quad::FULLSCREEN_TRIANGLE_VS_WITH_UV;
+use super::render_target::RenderTarget;
+
+const DEBUG_VIEW_FS: &str = include_str!("../../../../assets/shaders/builtin/debug_view.wgsl");
+
+/// Picture-in-picture width as a fraction of the output's.
+const PIP_FRACTION: f32 = 0.3;
+/// Gap between the picture-in-picture and the output's edge, in pixels. The
+/// bottom one clears the status bar.
+const PIP_MARGIN: f32 = 12.0;
+const PIP_BOTTOM_MARGIN: f32 = 36.0;
+
+/// A texture the debug view can show.
+pub struct DebugTexture<'a> {
+    pub label: String,
+    pub view: &'a TextureView,
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+}
+
+impl<'a> DebugTexture<'a> {
+    pub fn of_target(label: impl Into<String>, target: &'a RenderTarget) -> Self {
+        Self {
+            label: label.into(),
+            view: &target.view,
+            width: target.width,
+            height: target.height,
+            format: target.format,
+        }
+    }
+
+    pub fn of_texture(label: impl Into<String>, texture: &Texture, view: &'a TextureView) -> Self {
+        Self {
+            label: label.into(),
+            view,
+            width: texture.width(),
+            height: texture.height(),
+            format: texture.format(),
+        }
+    }
+}
+
+/// A [`DebugTexture`] without its view, for listing in the panel.
+#[derive(Debug, Clone)]
+pub struct DebugTextureInfo {
+    pub layer: Option<usize>,
+    pub label: String,
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+}
+
+impl DebugTextureInfo {
+    pub fn new(layer: Option<usize>, texture: &DebugTexture) -> Self {
+        Self {
+            layer,
+            label: texture.label.clone(),
+            width: texture.width,
+            height: texture.height,
+            format: texture.format,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DebugLayout {
+    #[default]
+    PictureInPicture,
+    Fullscreen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DebugChannel {
+    #[default]
+    Rgb,
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+
+impl DebugChannel {
+    pub const ALL: [DebugChannel; 5] = [
+        DebugChannel::Rgb,
+        DebugChannel::Red,
+        DebugChannel::Green,
+        DebugChannel::Blue,
+        DebugChannel::Alpha,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            DebugChannel::Rgb => "RGB",
+            DebugChannel::Red => "R",
+            DebugChannel::Green => "G",
+            DebugChannel::Blue => "B",
+            DebugChannel::Alpha => "A",
+        }
+    }
+}
+
+/// A texture picked in the debug view panel. `layer` is `None` for textures
+/// outside the layer stack; `label` matches a [`DebugTexture`] label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugSelection {
+    pub layer: Option<usize>,
+    pub label: String,
+}
+
+/// What the debug view shows. Only drawn while its panel is open, so a
+/// forgotten view can't stay over a show's output.
+#[derive(Debug, Clone)]
+pub struct DebugViewState {
+    pub open: bool,
+    pub selection: Option<DebugSelection>,
+    pub layout: DebugLayout,
+    pub channel: DebugChannel,
+    /// Multiplier before clamping, for reading HDR or very dim buffers.
+    pub exposure: f32,
+}
+
+impl Default for DebugViewState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            selection: None,
+            layout: DebugLayout::default(),
+            channel: DebugChannel::default(),
+            exposure: 1.0,
+        }
+    }
+}
+
+impl DebugViewState {
+    pub fn active(&self) -> bool {
+        self.open && self.selection.is_some()
+    }
+
+    /// Keep the selection on the same layer after a reorder.
+    pub fn follow_layer_move(&mut self, from: usize, to: usize) {
+        if let Some(layer) = self.selection.as_mut().and_then(|s| s.layer.as_mut()) {
+            *layer = crate::gpu::layer::adjusted_active_after_move(*layer, from, to);
+        }
+    }
+
+    /// Keep the selection on the same layer after a removal, dropping it if
+    /// its layer was the one removed.
+    pub fn follow_layer_removal(&mut self, removed: usize) {
+        if let Some(layer) = self.selection.as_ref().and_then(|s| s.layer) {
+            match crate::gpu::layer::index_after_remove(layer, removed) {
+                Some(index) => self.selection.as_mut().unwrap().layer = Some(index),
+                None => self.selection = None,
+            }
+        }
+    }
+}
+
+/// Where on a `dst`-sized output the `src`-sized texture goes, as
+/// (x, y, width, height) in pixels, keeping its aspect ratio.
+pub fn viewport(layout: DebugLayout, src: (u32, u32), dst: (u32, u32)) -> [f32; 4] {
+    let (sw, sh) = (src.0.max(1) as f32, src.1.max(1) as f32);
+    let (dw, dh) = (dst.0 as f32, dst.1 as f32);
+    match layout {
+        DebugLayout::Fullscreen => {
+            let scale = (dw / sw).min(dh / sh);
+            let (w, h) = (sw * scale, sh * scale);
+            [(dw - w) * 0.5, (dh - h) * 0.5, w, h]
+        }
+        DebugLayout::PictureInPicture => {
+            let w = (dw * PIP_FRACTION).min(dw - 2.0 * PIP_MARGIN).max(1.0);
+            let h = (w * sh / sw).min(dh * 0.5).max(1.0);
+            let w = h * sw / sh;
+            let x = (dw - w - PIP_MARGIN).max(0.0);
+            let y = (dh - h - PIP_BOTTOM_MARGIN).max(0.0);
+            [x, y, w, h]
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct DebugParams {
+    channel: u32,
+    exposure: f32,
+    _pad: [f32; 2],
+}
+
+/// Draws a [`DebugTexture`] onto the surface.
+pub struct DebugViewer {
+    pipeline: RenderPipeline,
+    bgl: wgpu::BindGroupLayout,
+    params_buffer: wgpu::Buffer,
+}
+
+impl DebugViewer {
+    pub fn new(device: &Device, surface_format: TextureFormat) -> Self {
+        let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("debug-view-bgl"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        // Unfilterable accepts every float format, R32Float included
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("debug-view"),
+            source: wgpu::ShaderSource::Wgsl(
+                format!("{FULLSCREEN_TRIANGLE_VS_WITH_UV}\n{DEBUG_VIEW_FS}").into(),
+            ),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("debug-view-layout"),
+            bind_group_layouts: &[&bgl],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("debug-view-pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        let params_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("debug-view-params"),
+            size: std::mem::size_of::<DebugParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Self {
+            pipeline,
+            bgl,
+            params_buffer,
+        }
+    }
+
+    /// Draw `texture` onto `target` (a `target_size` surface). Fullscreen
+    /// clears around it; picture-in-picture keeps the output underneath.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        texture: &DebugTexture,
+        target: &TextureView,
+        target_size: (u32, u32),
+        state: &DebugViewState,
+    ) {
+        let params = DebugParams {
+            channel: DebugChannel::ALL
+                .iter()
+                .position(|c| *c == state.channel)
+                .unwrap_or(0) as u32,
+            exposure: state.exposure,
+            _pad: [0.0; 2],
+        };
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("debug-view-bg"),
+            layout: &self.bgl,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(texture.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.params_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        let load = match state.layout {
+            DebugLayout::Fullscreen => wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+            DebugLayout::PictureInPicture => wgpu::LoadOp::Load,
+        };
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("debug-view"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let [x, y, w, h] = viewport(state.layout, (texture.width, texture.height), target_size);
+        pass.set_viewport(x, y, w, h, 0.0, 1.0);
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shader_validates() {
+        let src = format!("{FULLSCREEN_TRIANGLE_VS_WITH_UV}\n{DEBUG_VIEW_FS}");
+        let module = wgpu::naga::front::wgsl::parse_str(&src).expect("shader parses");
+        wgpu::naga::valid::Validator::new(
+            wgpu::naga::valid::ValidationFlags::all(),
+            wgpu::naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .expect("shader validates");
+    }
+
+    #[test]
+    fn fullscreen_letterboxes_to_the_texture_aspect() {
+        // A square texture on a 16:9 output: full height, centred
+        let [x, y, w, h] = viewport(DebugLayout::Fullscreen, (512, 512), (1920, 1080));
+        assert_eq!([w, h], [1080.0, 1080.0]);
+        assert_eq!([x, y], [420.0, 0.0]);
+    }
+
+    #[test]
+    fn picture_in_picture_sits_bottom_right_inside_the_output() {
+        let [x, y, w, h] = viewport(DebugLayout::PictureInPicture, (1920, 1080), (1920, 1080));
+        assert!((w - 1920.0 * PIP_FRACTION).abs() < 0.01);
+        assert!((w / h - 1920.0 / 1080.0).abs() < 0.01);
+        assert!((x + w - (1920.0 - PIP_MARGIN)).abs() < 0.01);
+        assert!((y + h - (1080.0 - PIP_BOTTOM_MARGIN)).abs() < 0.01);
+        // A tall texture is capped at half the output's height
+        let [_, _, _, h] = viewport(DebugLayout::PictureInPicture, (64, 1024), (1920, 1080));
+        assert!(h <= 540.0);
+    }
+
+    #[test]
+    fn selection_follows_its_layer() {
+        let mut state = DebugViewState {
+            selection: Some(DebugSelection {
+                layer: Some(1),
+                label: "output".into(),
+            }),
+            ..Default::default()
+        };
+        state.follow_layer_move(1, 3);
+        assert_eq!(state.selection.as_ref().unwrap().layer, Some(3));
+        state.follow_layer_removal(0);
+        assert_eq!(state.selection.as_ref().unwrap().layer, Some(2));
+        state.follow_layer_removal(2);
+        assert_eq!(state.selection, None);
+    }
+}
@@ -0,0 +1,98 @@
+//! Deterministic rendering (#2174): a fixed-step frame clock and a fixed
+//! global seed, so recordings and A/B comparisons repeat frame for frame.
+//! Everything driven by `time`, `delta_time` and `seed` replays exactly;
+//! live audio input and wall-clock media playback still differ per run.
+
+use serde::{Deserialize, Serialize};
+
+/// Frame rates offered for the fixed step.
+pub const FPS_CHOICES: &[u32] = &[24, 25, 30, 50, 60];
+
+/// Deterministic mode settings, kept in settings.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeterministicParams {
+    pub enabled: bool,
+    /// Global seed; shaders see its low 16 bits as the `seed` uniform.
+    pub seed: u32,
+    /// Simulated frames per second, whatever the real frame rate is.
+    pub fps: u32,
+}
+
+impl Default for DeterministicParams {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            seed: 0,
+            fps: 60,
+        }
+    }
+}
+
+impl DeterministicParams {
+    /// The fixed time step in seconds.
+    pub fn step(&self) -> f32 {
+        1.0 / self.fps.max(1) as f32
+    }
+
+    /// Shader time at `frame`. Divided in f64 so late frames land on the
+    /// same value as a fresh computation rather than an accumulated sum.
+    pub fn frame_time(&self, frame: u32) -> f32 {
+        (f64::from(frame) / f64::from(self.fps.max(1))) as f32
+    }
+}
+
+/// `seed` uniform value for a seed; 16 bits keep it exact in an f32.
+pub fn seed_uniform(seed: u32) -> f32 {
+    (seed & 0xffff) as f32
+}
+
+/// Fresh bits from the wall clock, different on every call. Seeds the
+/// live session's `seed` and anything meant to vary run to run, so it
+/// never replays, deterministic mode or not.
+pub fn clock_entropy() -> u32 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_time_is_frame_over_fps() {
+        let p = DeterministicParams {
+            fps: 30,
+            ..Default::default()
+        };
+        assert_eq!(p.frame_time(0), 0.0);
+        assert_eq!(p.frame_time(90), 3.0);
+        assert!((p.step() - 1.0 / 30.0).abs() < 1e-9);
+        // Late frames land on frame / fps, with no drift from summed steps.
+        assert!((p.frame_time(123_456) - 4115.2).abs() < 1e-3);
+    }
+
+    #[test]
+    fn zero_fps_does_not_divide_by_zero() {
+        let p = DeterministicParams {
+            fps: 0,
+            ..Default::default()
+        };
+        assert_eq!(p.step(), 1.0);
+        assert_eq!(p.frame_time(5), 5.0);
+    }
+
+    #[test]
+    fn seed_uniform_keeps_low_bits() {
+        assert_eq!(seed_uniform(42), 42.0);
+        assert_eq!(seed_uniform(0x1_0005), 5.0);
+    }
+
+    #[test]
+    fn params_default_from_empty_json() {
+        let p: DeterministicParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(p, DeterministicParams::default());
+        assert!(!p.enabled);
+    }
+}
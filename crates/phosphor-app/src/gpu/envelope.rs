@@ -0,0 +1,198 @@
+//! Opacity envelopes (#2249): a layer's opacity shaped over bars and beats —
+//! fade in over 4 bars, pulse on the beat, fade out after 16 — so builds and
+//! drops can be set up ahead of time and saved with the preset. The envelope
+//! scales the layer's opacity at composite time; the opacity slider keeps
+//! the base value.
+
+use serde::{Deserialize, Serialize};
+
+/// The envelope's shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EnvelopeShape {
+    #[default]
+    Off,
+    /// Rise from clear to full over `bars`, after `after_bars`.
+    FadeIn,
+    /// Hold full for `after_bars`, then fall to clear over `bars`.
+    FadeOut,
+    /// Full on each beat, dipping by `depth` before the next.
+    Pulse,
+    /// A smooth swell and dip by `depth`, once every `bars`.
+    Lfo,
+}
+
+impl EnvelopeShape {
+    pub const ALL: &[EnvelopeShape] = &[
+        EnvelopeShape::Off,
+        EnvelopeShape::FadeIn,
+        EnvelopeShape::FadeOut,
+        EnvelopeShape::Pulse,
+        EnvelopeShape::Lfo,
+    ];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            EnvelopeShape::Off => "Off",
+            EnvelopeShape::FadeIn => "Fade in",
+            EnvelopeShape::FadeOut => "Fade out",
+            EnvelopeShape::Pulse => "Pulse",
+            EnvelopeShape::Lfo => "LFO",
+        }
+    }
+
+    /// Whether `bars`/`after_bars` mean anything for this shape.
+    pub fn uses_bars(self) -> bool {
+        matches!(
+            self,
+            EnvelopeShape::FadeIn | EnvelopeShape::FadeOut | EnvelopeShape::Lfo
+        )
+    }
+
+    /// Whether `depth` means anything for this shape.
+    pub fn uses_depth(self) -> bool {
+        matches!(self, EnvelopeShape::Pulse | EnvelopeShape::Lfo)
+    }
+}
+
+/// Shortest fade or LFO period, in bars.
+const MIN_BARS: f32 = 0.25;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpacityEnvelope {
+    pub shape: EnvelopeShape,
+    /// Fade length, or LFO period.
+    pub bars: f32,
+    /// Bars before a fade starts.
+    pub after_bars: f32,
+    /// How far a pulse or LFO dips, 0..1.
+    pub depth: f32,
+    /// Bars since the envelope (re)started.
+    #[serde(skip)]
+    pub elapsed_bars: f64,
+    /// Where the beat tracker is within the current beat, 0..1.
+    #[serde(skip)]
+    pub beat_phase: f32,
+}
+
+impl Default for OpacityEnvelope {
+    fn default() -> Self {
+        Self {
+            shape: EnvelopeShape::Off,
+            bars: 4.0,
+            after_bars: 0.0,
+            depth: 0.8,
+            elapsed_bars: 0.0,
+            beat_phase: 0.0,
+        }
+    }
+}
+
+impl OpacityEnvelope {
+    pub fn is_off(&self) -> bool {
+        self.shape == EnvelopeShape::Off
+    }
+
+    /// Start the envelope over, e.g. to fire a fade again at a drop.
+    pub fn restart(&mut self) {
+        self.elapsed_bars = 0.0;
+    }
+
+    /// Take the settings from `other`, an edited copy. The clock keeps
+    /// running unless the shape changed.
+    pub fn apply(&mut self, other: &OpacityEnvelope) {
+        if other.shape != self.shape {
+            self.restart();
+        }
+        self.shape = other.shape;
+        self.bars = other.bars;
+        self.after_bars = other.after_bars;
+        self.depth = other.depth;
+    }
+
+    /// Advance the clock, on the same beat clock as automation lanes.
+    pub fn tick(&mut self, bars_elapsed: f32, beat_phase: f32) {
+        if self.is_off() {
+            return;
+        }
+        self.elapsed_bars += f64::from(bars_elapsed);
+        self.beat_phase = beat_phase;
+    }
+
+    /// What the layer's opacity is multiplied by right now, 0..1.
+    pub fn gain(&self) -> f32 {
+        let len = f64::from(self.bars.max(MIN_BARS));
+        let into_fade =
+            || ((self.elapsed_bars - f64::from(self.after_bars.max(0.0))) / len).clamp(0.0, 1.0);
+        let depth = self.depth.clamp(0.0, 1.0);
+        match self.shape {
+            EnvelopeShape::Off => 1.0,
+            EnvelopeShape::FadeIn => into_fade() as f32,
+            EnvelopeShape::FadeOut => 1.0 - into_fade() as f32,
+            EnvelopeShape::Pulse => 1.0 - depth * self.beat_phase.clamp(0.0, 1.0),
+            EnvelopeShape::Lfo => {
+                let cycle = (self.elapsed_bars / len).fract() as f32;
+                let dip = 0.5 - 0.5 * (cycle * std::f32::consts::TAU).cos();
+                1.0 - depth * dip
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(shape: EnvelopeShape, bars: f32, after_bars: f32) -> OpacityEnvelope {
+        OpacityEnvelope {
+            shape,
+            bars,
+            after_bars,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fades_follow_the_bar_clock() {
+        let mut fade_in = envelope(EnvelopeShape::FadeIn, 4.0, 0.0);
+        assert_eq!(fade_in.gain(), 0.0);
+        fade_in.tick(1.0, 0.0);
+        assert!((fade_in.gain() - 0.25).abs() < 1e-6);
+        fade_in.tick(8.0, 0.0);
+        assert_eq!(fade_in.gain(), 1.0);
+
+        let mut fade_out = envelope(EnvelopeShape::FadeOut, 2.0, 16.0);
+        fade_out.tick(16.0, 0.0);
+        assert_eq!(fade_out.gain(), 1.0);
+        fade_out.tick(1.0, 0.0);
+        assert!((fade_out.gain() - 0.5).abs() < 1e-6);
+        fade_out.restart();
+        assert_eq!(fade_out.gain(), 1.0);
+    }
+
+    #[test]
+    fn pulse_and_lfo_dip_by_depth() {
+        let mut pulse = envelope(EnvelopeShape::Pulse, 4.0, 0.0);
+        pulse.depth = 0.5;
+        pulse.tick(0.0, 0.0);
+        assert_eq!(pulse.gain(), 1.0);
+        pulse.tick(0.0, 1.0);
+        assert!((pulse.gain() - 0.5).abs() < 1e-6);
+
+        let mut lfo = envelope(EnvelopeShape::Lfo, 2.0, 0.0);
+        lfo.depth = 1.0;
+        assert!((lfo.gain() - 1.0).abs() < 1e-6);
+        lfo.tick(1.0, 0.0);
+        assert!(lfo.gain().abs() < 1e-6);
+    }
+
+    #[test]
+    fn editing_keeps_the_clock_unless_the_shape_changes() {
+        let mut env = envelope(EnvelopeShape::FadeIn, 4.0, 0.0);
+        env.tick(2.0, 0.0);
+        env.apply(&envelope(EnvelopeShape::FadeIn, 8.0, 0.0));
+        assert_eq!(env.elapsed_bars, 2.0);
+        env.apply(&envelope(EnvelopeShape::FadeOut, 8.0, 0.0));
+        assert_eq!(env.elapsed_bars, 0.0);
+    }
+}
@@ -0,0 +1,317 @@
+//! wgpu error scopes (#2230): validation and out-of-memory errors become
+//! values the app can show instead of a log line, so a user shader binding
+//! the wrong texture format or a device that runs out of memory mid-show
+//! leaves a status message and keeps the process alive.
+//!
+//! wgpu keeps one scope stack per device, shared by every thread, and a pop
+//! takes whichever scope is on top. The background shader compiler and the
+//! render loop both open scopes, so an [`ErrorScope`] also holds a
+//! process-wide lock (re-entrant, so scopes still nest on one thread) until
+//! it is popped. The frame's scope is only tried: rather than wait out a
+//! background compile, that frame goes unscoped and its errors reach the
+//! uncaptured handler, which feeds [`GpuErrorLog`].
+
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+use wgpu::Device;
+
+/// Uncaptured errors kept between drains; a broken frame repeats its error
+/// every frame, so older ones are dropped.
+const MAX_PENDING: usize = 16;
+/// The same error isn't shown again within this window.
+const REPEAT_INTERVAL: Duration = Duration::from_secs(10);
+/// Status bar messages are cut to this many characters.
+const SUMMARY_LEN: usize = 160;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuErrorKind {
+    Validation,
+    OutOfMemory,
+    Internal,
+}
+
+/// A wgpu error, kept as text so it can cross threads and be shown later.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuError {
+    pub kind: GpuErrorKind,
+    /// wgpu's full description, with its chain of "Caused by" contexts.
+    pub message: String,
+}
+
+impl GpuError {
+    pub fn new(error: &wgpu::Error) -> Self {
+        let (kind, message) = match error {
+            wgpu::Error::Validation { description, .. } => {
+                (GpuErrorKind::Validation, description.clone())
+            }
+            wgpu::Error::OutOfMemory { .. } => (GpuErrorKind::OutOfMemory, error.to_string()),
+            wgpu::Error::Internal { description, .. } => {
+                (GpuErrorKind::Internal, description.clone())
+            }
+        };
+        Self { kind, message }
+    }
+
+    /// The innermost cause, which names what was actually wrong, on one line.
+    pub fn summary(&self) -> String {
+        let line = self
+            .message
+            .lines()
+            .map(str::trim)
+            .rfind(|l| !l.is_empty() && !l.starts_with("note:") && *l != "Caused by:")
+            .unwrap_or("");
+        if line.chars().count() > SUMMARY_LEN {
+            let cut: String = line.chars().take(SUMMARY_LEN - 1).collect();
+            format!("{cut}…")
+        } else {
+            line.to_string()
+        }
+    }
+
+    /// For the status bar.
+    pub fn status_text(&self) -> String {
+        match self.kind {
+            GpuErrorKind::Validation => format!("GPU error: {}", self.summary()),
+            GpuErrorKind::OutOfMemory => {
+                "GPU out of memory. Lower particle counts, layers or resolution.".to_string()
+            }
+            GpuErrorKind::Internal => format!("GPU driver error: {}", self.summary()),
+        }
+    }
+
+    /// Whether wgpu named a resource labelled `label` in this error.
+    pub fn mentions_label(&self, label: &str) -> bool {
+        !label.is_empty() && self.message.contains(&format!("'{label}'"))
+    }
+}
+
+impl fmt::Display for GpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Validation, out-of-memory and internal scopes pushed together. Pop with
+/// [`ErrorScope::pop`]; dropping it unpopped discards what it caught.
+#[must_use = "an error scope catches nothing useful unless it is popped"]
+pub struct ErrorScope {
+    device: Device,
+    open: bool,
+}
+
+const FILTERS: [wgpu::ErrorFilter; 3] = [
+    wgpu::ErrorFilter::Internal,
+    wgpu::ErrorFilter::OutOfMemory,
+    wgpu::ErrorFilter::Validation,
+];
+
+impl ErrorScope {
+    pub fn push(device: &Device) -> Self {
+        scope_lock::acquire();
+        Self::open(device)
+    }
+
+    /// [`Self::push`], or `None` while another thread has a scope open.
+    pub fn try_push(device: &Device) -> Option<Self> {
+        scope_lock::try_acquire().then(|| Self::open(device))
+    }
+
+    fn open(device: &Device) -> Self {
+        for filter in FILTERS {
+            device.push_error_scope(filter);
+        }
+        Self {
+            device: device.clone(),
+            open: true,
+        }
+    }
+
+    /// The first error caught since the push, validation errors first.
+    pub fn pop(mut self) -> Option<GpuError> {
+        self.close()
+    }
+
+    fn close(&mut self) -> Option<GpuError> {
+        if !std::mem::take(&mut self.open) {
+            return None;
+        }
+        // Native wgpu resolves these immediately
+        let mut first = None;
+        for _ in FILTERS {
+            if let Some(error) = pollster::block_on(self.device.pop_error_scope()) {
+                first.get_or_insert_with(|| GpuError::new(&error));
+            }
+        }
+        scope_lock::release();
+        first
+    }
+}
+
+impl Drop for ErrorScope {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Errors no scope caught, filled by the device's uncaptured-error handler
+/// and drained by the app each frame.
+#[derive(Clone, Default)]
+pub struct GpuErrorLog(Arc<Mutex<Vec<GpuError>>>);
+
+impl GpuErrorLog {
+    pub fn push(&self, error: GpuError) {
+        let mut pending = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if pending.len() >= MAX_PENDING {
+            pending.remove(0);
+        }
+        pending.push(error);
+    }
+
+    pub fn drain(&self) -> Vec<GpuError> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Keeps an error that repeats every frame from being re-reported every frame.
+#[derive(Default)]
+pub struct GpuErrorThrottle {
+    last: Option<(String, Instant)>,
+}
+
+impl GpuErrorThrottle {
+    /// Whether `error` should be reported now: it differs from the last one,
+    /// or the last report is old enough to show again.
+    pub fn should_report(&mut self, error: &GpuError, now: Instant) -> bool {
+        if let Some((message, when)) = &self.last
+            && *message == error.message
+            && now.duration_since(*when) < REPEAT_INTERVAL
+        {
+            return false;
+        }
+        self.last = Some((error.message.clone(), now));
+        true
+    }
+}
+
+/// The process-wide lock behind [`ErrorScope`]. Re-entrant per thread so a
+/// pipeline built inside the frame's scope can open its own.
+mod scope_lock {
+    use super::*;
+
+    static OWNER: Mutex<Option<(ThreadId, usize)>> = Mutex::new(None);
+    static FREED: Condvar = Condvar::new();
+
+    pub(super) fn acquire() {
+        let mut owner = OWNER.lock().unwrap_or_else(|e| e.into_inner());
+        while !take(&mut owner) {
+            owner = FREED.wait(owner).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    pub(super) fn try_acquire() -> bool {
+        take(&mut OWNER.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    fn take(owner: &mut Option<(ThreadId, usize)>) -> bool {
+        let me = thread::current().id();
+        match owner {
+            None => {
+                *owner = Some((me, 1));
+                true
+            }
+            Some((id, depth)) if *id == me => {
+                *depth += 1;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    pub(super) fn release() {
+        let mut owner = OWNER.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, depth)) = owner.as_mut() {
+            *depth -= 1;
+            if *depth == 0 {
+                *owner = None;
+                FREED.notify_all();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validation(message: &str) -> GpuError {
+        GpuError {
+            kind: GpuErrorKind::Validation,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn summary_is_the_innermost_cause() {
+        let err = validation(
+            "Validation Error\n\nCaused by:\n  In Device::create_render_pipeline, label = 'main'\n    \
+             Error matching ShaderStages(FRAGMENT) shader requirements against the pipeline\n      \
+             Texture class Sampled doesn't match the shader Storage\n",
+        );
+        assert_eq!(
+            err.summary(),
+            "Texture class Sampled doesn't match the shader Storage"
+        );
+        assert!(err.mentions_label("main"));
+        assert!(!err.mentions_label("blur"));
+        assert!(!err.mentions_label(""));
+    }
+
+    #[test]
+    fn long_summaries_are_cut() {
+        let err = validation(&"x".repeat(400));
+        assert_eq!(err.summary().chars().count(), SUMMARY_LEN);
+    }
+
+    #[test]
+    fn throttle_holds_back_repeats() {
+        let mut throttle = GpuErrorThrottle::default();
+        let t0 = Instant::now();
+        let a = validation("a");
+        assert!(throttle.should_report(&a, t0));
+        assert!(!throttle.should_report(&a, t0 + Duration::from_secs(1)));
+        assert!(throttle.should_report(&validation("b"), t0 + Duration::from_secs(2)));
+        assert!(throttle.should_report(&a, t0 + Duration::from_secs(3)));
+        assert!(throttle.should_report(&a, t0 + REPEAT_INTERVAL + Duration::from_secs(4)));
+    }
+
+    #[test]
+    fn log_keeps_only_the_newest() {
+        let log = GpuErrorLog::default();
+        for i in 0..MAX_PENDING + 4 {
+            log.push(validation(&i.to_string()));
+        }
+        let drained = log.drain();
+        assert_eq!(drained.len(), MAX_PENDING);
+        assert_eq!(drained[0].message, "4");
+        assert!(log.drain().is_empty());
+    }
+
+    #[test]
+    fn scope_lock_nests_on_one_thread() {
+        scope_lock::acquire();
+        assert!(scope_lock::try_acquire());
+        assert!(!thread::spawn(scope_lock::try_acquire).join().unwrap());
+        scope_lock::release();
+        scope_lock::release();
+        // Free again: another thread can take it
+        thread::spawn(|| {
+            scope_lock::acquire();
+            scope_lock::release();
+        })
+        .join()
+        .unwrap();
+    }
+}
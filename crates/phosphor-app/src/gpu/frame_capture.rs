@@ -13,7 +13,6 @@ pub struct FrameCapture {
     pub view: TextureView,
     pub width: u32,
     pub height: u32,
-    #[allow(dead_code)]
     pub format: TextureFormat,
     staging: [Buffer; 2],
     /// Bytes per row, padded to wgpu's COPY_BYTES_PER_ROW_ALIGNMENT (256).
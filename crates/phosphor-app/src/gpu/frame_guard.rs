@@ -0,0 +1,240 @@
+//! Per-layer GPU time guard (#2243). Each layer's work is bracketed with
+//! timestamp queries; a layer whose GPU time stays over the budget for a run
+//! of frames — a user shader stuck in a near-endless loop — is suspended, so
+//! one runaway effect can't drag the whole app down. Suspension is runtime
+//! only: the layer keeps its enabled flag and comes back with Resume, a new
+//! effect, or a successful recompile.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use super::layer::MAX_LAYERS_CHOICES;
+
+/// Layers past this many aren't timed.
+const TIMED_LAYERS: usize = MAX_LAYERS_CHOICES[MAX_LAYERS_CHOICES.len() - 1];
+/// Each layer's pair of timestamps resolves to its own aligned slot.
+const SLOT: u64 = wgpu::QUERY_RESOLVE_BUFFER_ALIGNMENT;
+
+/// Saved with the settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrameGuardConfig {
+    pub enabled: bool,
+    /// GPU milliseconds one layer may take per frame.
+    pub budget_ms: f32,
+    /// Consecutive frames over the budget before the layer is suspended.
+    pub frames: u32,
+}
+
+impl Default for FrameGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            budget_ms: 25.0,
+            frames: 30,
+        }
+    }
+}
+
+/// Counts each layer's consecutive frames over the budget.
+#[derive(Debug, Default)]
+pub struct FrameGuard {
+    over: Vec<u32>,
+}
+
+impl FrameGuard {
+    /// Take one frame's `(layer, ms)` timings. Returns the layers to suspend
+    /// now, with their last time. A layer missing from `timings` didn't run,
+    /// and starts counting again from zero.
+    pub fn observe(
+        &mut self,
+        config: &FrameGuardConfig,
+        layer_count: usize,
+        timings: &[(usize, f32)],
+    ) -> Vec<(usize, f32)> {
+        let mut over = vec![0; layer_count];
+        let mut suspend = Vec::new();
+        for &(layer, ms) in timings {
+            if layer >= layer_count || ms <= config.budget_ms {
+                continue;
+            }
+            let frames = self.over.get(layer).copied().unwrap_or(0) + 1;
+            if frames >= config.frames.max(1) {
+                suspend.push((layer, ms));
+            } else {
+                over[layer] = frames;
+            }
+        }
+        self.over = over;
+        suspend
+    }
+}
+
+/// Timestamp queries around each layer, read back a frame or two later in
+/// the same way as the particle counters.
+pub struct LayerTimer {
+    queries: wgpu::QuerySet,
+    resolve: wgpu::Buffer,
+    readback: wgpu::Buffer,
+    /// Layers resolved this frame, waiting for the readback request.
+    copied: Vec<usize>,
+    /// Layers whose timestamps are in the mapped readback buffer.
+    mapped: Vec<usize>,
+    map_pending: Arc<AtomicBool>,
+    map_ready: Arc<AtomicBool>,
+    /// Nanoseconds per timestamp tick.
+    period: f32,
+}
+
+impl LayerTimer {
+    /// `None` when the device can't write timestamps between passes.
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        let needed =
+            wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS;
+        if !device.features().contains(needed) {
+            return None;
+        }
+        let size = SLOT * TIMED_LAYERS as u64;
+        Some(Self {
+            queries: device.create_query_set(&wgpu::QuerySetDescriptor {
+                label: Some("layer-timer"),
+                ty: wgpu::QueryType::Timestamp,
+                count: (TIMED_LAYERS * 2) as u32,
+            }),
+            resolve: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("layer-timer-resolve"),
+                size,
+                usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+            readback: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("layer-timer-readback"),
+                size,
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+            copied: Vec::new(),
+            mapped: Vec::new(),
+            map_pending: Arc::new(AtomicBool::new(false)),
+            map_ready: Arc::new(AtomicBool::new(false)),
+            period: queue.get_timestamp_period(),
+        })
+    }
+
+    /// Mark the start of `layer`'s work.
+    pub fn begin(&self, encoder: &mut wgpu::CommandEncoder, layer: usize) {
+        if layer < TIMED_LAYERS {
+            encoder.write_timestamp(&self.queries, layer as u32 * 2);
+        }
+    }
+
+    /// Mark the end of `layer`'s work.
+    pub fn end(&self, encoder: &mut wgpu::CommandEncoder, layer: usize) {
+        if layer < TIMED_LAYERS {
+            encoder.write_timestamp(&self.queries, layer as u32 * 2 + 1);
+        }
+    }
+
+    /// Resolve the timestamps of the layers that `ran` in this encoder and
+    /// copy them for readback. Skipped while the last readback is mapped.
+    pub fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder, ran: &[usize]) {
+        if self.map_pending.load(Ordering::Relaxed) {
+            return;
+        }
+        self.copied = ran.iter().copied().filter(|&i| i < TIMED_LAYERS).collect();
+        for &i in &self.copied {
+            let first = i as u32 * 2;
+            encoder.resolve_query_set(
+                &self.queries,
+                first..first + 2,
+                &self.resolve,
+                SLOT * i as u64,
+            );
+        }
+        if !self.copied.is_empty() {
+            encoder.copy_buffer_to_buffer(&self.resolve, 0, &self.readback, 0, self.resolve.size());
+        }
+    }
+
+    /// Request the async map of this frame's copy. Call after submit.
+    pub fn request_readback(&mut self) {
+        if self.copied.is_empty() || self.map_pending.load(Ordering::Relaxed) {
+            return;
+        }
+        self.mapped = std::mem::take(&mut self.copied);
+        // Set pending before map_async: wgpu treats the buffer as mapped at once
+        self.map_pending.store(true, Ordering::Release);
+        let pending = self.map_pending.clone();
+        let ready = self.map_ready.clone();
+        self.readback
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                if result.is_ok() {
+                    ready.store(true, Ordering::Release);
+                } else {
+                    pending.store(false, Ordering::Release);
+                }
+            });
+    }
+
+    /// `(layer, ms)` for each timed layer, once a readback has landed.
+    pub fn poll(&mut self) -> Option<Vec<(usize, f32)>> {
+        if !self.map_ready.load(Ordering::Acquire) {
+            return None;
+        }
+        let timings = {
+            let view = self.readback.slice(..).get_mapped_range();
+            self.mapped
+                .iter()
+                .filter_map(|&i| {
+                    let at = i * SLOT as usize;
+                    let stamps: &[u64] = bytemuck::cast_slice(&view[at..at + 16]);
+                    let ticks = stamps[1].checked_sub(stamps[0])?;
+                    Some((i, ticks as f32 * self.period / 1_000_000.0))
+                })
+                .collect()
+        };
+        self.readback.unmap();
+        self.map_ready.store(false, Ordering::Release);
+        self.map_pending.store(false, Ordering::Release);
+        Some(timings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> FrameGuardConfig {
+        FrameGuardConfig {
+            enabled: true,
+            budget_ms: 10.0,
+            frames: 3,
+        }
+    }
+
+    #[test]
+    fn suspends_after_consecutive_frames_over_budget() {
+        let mut guard = FrameGuard::default();
+        let cfg = config();
+        assert!(guard.observe(&cfg, 2, &[(0, 2.0), (1, 40.0)]).is_empty());
+        assert!(guard.observe(&cfg, 2, &[(0, 2.0), (1, 40.0)]).is_empty());
+        assert_eq!(guard.observe(&cfg, 2, &[(0, 2.0), (1, 45.0)]), [(1, 45.0)]);
+    }
+
+    #[test]
+    fn a_frame_under_budget_or_not_run_starts_over() {
+        let mut guard = FrameGuard::default();
+        let cfg = config();
+        guard.observe(&cfg, 1, &[(0, 40.0)]);
+        guard.observe(&cfg, 1, &[(0, 40.0)]);
+        guard.observe(&cfg, 1, &[(0, 4.0)]);
+        guard.observe(&cfg, 1, &[(0, 40.0)]);
+        guard.observe(&cfg, 1, &[]);
+        assert!(guard.observe(&cfg, 1, &[(0, 40.0)]).is_empty());
+        assert!(guard.observe(&cfg, 1, &[(0, 40.0)]).is_empty());
+        assert_eq!(guard.observe(&cfg, 1, &[(0, 40.0)]).len(), 1);
+    }
+}
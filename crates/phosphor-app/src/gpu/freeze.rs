@@ -0,0 +1,432 @@
+//! Freeze frame (#2166): the `FreezeFrame` trigger latches the composite into
+//! a hold target and shows it in place of (or under) live output until it is
+//! released or decays away. It sits after the dissolve crossfade and before
+//! post-processing, so bloom and the output master still apply on top.
+
+use serde::{Deserialize, Serialize};
+use wgpu::{
+    BindGroupLayout, CommandEncoder, Device, Queue, RenderPipeline, Sampler, TextureFormat,
+};
+
+use super::render_target::RenderTarget;
+
+/// Freeze behaviour, kept in settings.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FreezeParams {
+    /// Seconds for the held frame to fade back to live; 0 holds until released.
+    pub decay: f32,
+    /// Show live output on top of the held frame instead of behind it.
+    pub under: bool,
+}
+
+/// Host-side freeze state, ticked once per frame.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct FreezeFrame {
+    pub params: FreezeParams,
+    active: bool,
+    latch_pending: bool,
+    age: f32,
+}
+
+impl FreezeFrame {
+    pub fn new(params: FreezeParams) -> Self {
+        Self {
+            params,
+            ..Default::default()
+        }
+    }
+
+    /// The trigger: a held freeze is released; otherwise (re)latch the next
+    /// frame. With decay on, repeated hits re-latch — a stutter.
+    pub fn trigger(&mut self) {
+        if self.active && self.params.decay <= 0.0 {
+            self.release();
+        } else {
+            self.active = true;
+            self.latch_pending = true;
+            self.age = 0.0;
+        }
+    }
+
+    pub fn release(&mut self) {
+        self.active = false;
+        self.latch_pending = false;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Whether this frame's composite must be copied into the hold target.
+    fn take_latch(&mut self) -> bool {
+        std::mem::take(&mut self.latch_pending)
+    }
+
+    /// How much of the held frame shows, 1 → 0 over `decay`.
+    pub fn level(&self) -> f32 {
+        if !self.active {
+            0.0
+        } else if self.params.decay <= 0.0 {
+            1.0
+        } else {
+            (1.0 - self.age / self.params.decay).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Advance the decay; a fully faded freeze releases itself.
+    pub fn tick(&mut self, dt: f32) {
+        if !self.active || self.latch_pending {
+            return;
+        }
+        self.age += dt;
+        if self.params.decay > 0.0 && self.age >= self.params.decay {
+            self.release();
+        }
+    }
+}
+
+/// GPU side: the hold target and the pass that blends it with live output.
+/// Lazily allocated — only created when the first freeze is triggered.
+pub struct FreezeRenderer {
+    pipeline: RenderPipeline,
+    bgl: BindGroupLayout,
+    sampler: Sampler,
+    uniform_buffer: wgpu::Buffer,
+    /// Never written: wgpu zero-fills it, and level 0 passes live through.
+    /// Separate from `uniform_buffer` because both passes share one submit.
+    latch_uniform: wgpu::Buffer,
+    /// Latched composite.
+    held: Option<RenderTarget>,
+    /// Held + live blend, fed to post-processing.
+    output: Option<RenderTarget>,
+}
+
+impl FreezeRenderer {
+    pub fn new(device: &Device, hdr_format: TextureFormat) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("freeze"),
+            source: wgpu::ShaderSource::Wgsl(
+                include_str!("../../../../assets/shaders/builtin/freeze.wgsl").into(),
+            ),
+        });
+
+        let tex_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("freeze_bgl"),
+            entries: &[
+                // binding 0: held frame
+                tex_entry(0),
+                // binding 1: sampler
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                // binding 2: live composite
+                tex_entry(2),
+                // binding 3: uniform (level, under)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("freeze_layout"),
+            bind_group_layouts: &[&bgl],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("freeze_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: hdr_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: Default::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("freeze_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("freeze_uniform"),
+            size: 16, // vec4f (level, under, padding)
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let latch_uniform = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("freeze_latch_uniform"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline,
+            bgl,
+            sampler,
+            uniform_buffer,
+            latch_uniform,
+            held: None,
+            output: None,
+        }
+    }
+
+    fn ensure_targets(&mut self, device: &Device, width: u32, height: u32, format: TextureFormat) {
+        let needs = |t: &Option<RenderTarget>| {
+            t.as_ref()
+                .map_or(true, |r| r.width != width || r.height != height)
+        };
+        if needs(&self.held) {
+            self.held = Some(RenderTarget::new(
+                device,
+                width,
+                height,
+                format,
+                1.0,
+                "freeze-held",
+            ));
+        }
+        if needs(&self.output) {
+            self.output = Some(RenderTarget::new(
+                device,
+                width,
+                height,
+                format,
+                1.0,
+                "freeze-output",
+            ));
+        }
+    }
+
+    fn run(
+        &self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        held: &RenderTarget,
+        live: &RenderTarget,
+        uniform: &wgpu::Buffer,
+        target: &RenderTarget,
+    ) {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("freeze_bg"),
+            layout: &self.bgl,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&held.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&live.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: uniform.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("freeze_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            ..Default::default()
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+
+    /// Copy `source` into the hold target.
+    fn latch(&mut self, device: &Device, encoder: &mut CommandEncoder, source: &RenderTarget) {
+        self.ensure_targets(device, source.width, source.height, source.format);
+        let held = self
+            .held
+            .as_ref()
+            .expect("held allocated by ensure_targets");
+        self.run(device, encoder, source, source, &self.latch_uniform, held);
+    }
+
+    /// Blend the held frame with `live`. Returns `None` before the first latch
+    /// or after a resize dropped the held frame.
+    fn apply<'a>(
+        &'a self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        live: &RenderTarget,
+        level: f32,
+        under: bool,
+    ) -> Option<&'a RenderTarget> {
+        let held = self.held.as_ref()?;
+        let output = self.output.as_ref()?;
+        if held.width != live.width || held.height != live.height {
+            return None;
+        }
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[level, if under { 1.0 } else { 0.0 }, 0.0f32, 0.0f32]),
+        );
+        self.run(device, encoder, held, live, &self.uniform_buffer, output);
+        Some(output)
+    }
+}
+
+impl FreezeFrame {
+    /// This frame's freeze stage: latch `source` if the trigger just fired,
+    /// then return the held/live blend, or `source` itself when idle.
+    pub fn render<'a>(
+        &mut self,
+        renderer: &'a mut Option<FreezeRenderer>,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        source: &'a RenderTarget,
+    ) -> &'a RenderTarget {
+        if !self.active {
+            return source;
+        }
+        let r = renderer.get_or_insert_with(|| FreezeRenderer::new(device, source.format));
+        if self.take_latch() {
+            r.latch(device, encoder, source);
+        }
+        let r: &'a FreezeRenderer = r;
+        r.apply(
+            device,
+            queue,
+            encoder,
+            source,
+            self.level(),
+            self.params.under,
+        )
+        .unwrap_or(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn freeze(decay: f32) -> FreezeFrame {
+        FreezeFrame::new(FreezeParams {
+            decay,
+            under: false,
+        })
+    }
+
+    #[test]
+    fn hold_toggles_and_latches_once() {
+        let mut f = freeze(0.0);
+        assert_eq!(f.level(), 0.0);
+        f.trigger();
+        assert!(f.is_active());
+        assert!(f.take_latch());
+        assert!(!f.take_latch());
+        for _ in 0..600 {
+            f.tick(1.0 / 60.0);
+        }
+        assert_eq!(f.level(), 1.0);
+        f.trigger();
+        assert!(!f.is_active());
+        assert_eq!(f.level(), 0.0);
+    }
+
+    #[test]
+    fn decay_fades_out_and_releases() {
+        let mut f = freeze(1.0);
+        f.trigger();
+        f.take_latch();
+        f.tick(0.25);
+        assert!((f.level() - 0.75).abs() < 1e-6);
+        f.tick(1.0);
+        assert!(!f.is_active());
+        assert_eq!(f.level(), 0.0);
+    }
+
+    #[test]
+    fn decay_retrigger_relatches() {
+        let mut f = freeze(1.0);
+        f.trigger();
+        f.take_latch();
+        f.tick(0.5);
+        f.trigger();
+        assert!(f.is_active());
+        assert!(f.take_latch());
+        assert_eq!(f.level(), 1.0);
+    }
+
+    #[test]
+    fn decay_waits_for_the_latch() {
+        let mut f = freeze(0.5);
+        f.trigger();
+        f.tick(1.0);
+        assert!(f.is_active());
+        assert_eq!(f.level(), 1.0);
+    }
+
+    #[test]
+    fn freeze_shader_validates() {
+        let src = include_str!("../../../../assets/shaders/builtin/freeze.wgsl");
+        let module = wgpu::naga::front::wgsl::parse_str(src).expect("shader parses");
+        wgpu::naga::valid::Validator::new(
+            wgpu::naga::valid::ValidationFlags::all(),
+            wgpu::naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .expect("shader validates");
+    }
+}
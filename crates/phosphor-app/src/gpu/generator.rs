@@ -0,0 +1,347 @@
+//! Built-in generator layers (#2168): strobe, solid and gradient washes
+//! without a shader file. The envelope (beat-locked or free-running) is
+//! computed here each frame; `generator.wgsl` only paints the color.
+
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+use wgpu::{BindGroup, Device, Queue, RenderPipeline, TextureFormat};
+
+use super::fullscreen_quad::FULLSCREEN_TRIANGLE_VS_WITH_UV;
+use super::render_target::RenderTarget;
+use super::resources::{ResourceCounts, Subsystem, Tracked};
+use super::uniforms::ShaderUniforms;
+
+const GENERATOR_FS: &str = include_str!("../../../../assets/shaders/builtin/generator.wgsl");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneratorKind {
+    /// Hard on/off flashes.
+    #[default]
+    Strobe,
+    /// One color; with beat sync it flashes and decays each cycle.
+    Solid,
+    /// Two-color linear wash that can scroll; flashes like `Solid`.
+    Gradient,
+}
+
+impl GeneratorKind {
+    pub const ALL: &[GeneratorKind] = &[
+        GeneratorKind::Strobe,
+        GeneratorKind::Solid,
+        GeneratorKind::Gradient,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            GeneratorKind::Strobe => "Strobe",
+            GeneratorKind::Solid => "Solid",
+            GeneratorKind::Gradient => "Gradient",
+        }
+    }
+}
+
+/// Beat-sync rates offered in the panel, as (cycles per beat, label).
+/// Rates under one beat follow the bar clock and assume 4/4.
+pub const BEAT_RATES: &[(f32, &str)] = &[
+    (0.25, "1 bar"),
+    (0.5, "1/2 bar"),
+    (1.0, "1 beat"),
+    (2.0, "1/2 beat"),
+    (4.0, "1/4 beat"),
+];
+
+/// A generator layer's settings; saved with the preset.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneratorDef {
+    pub kind: GeneratorKind,
+    pub color: [f32; 3],
+    /// Gradient end color.
+    pub color_b: [f32; 3],
+    /// Cycles per beat with `beat_sync`, otherwise per second.
+    pub rate: f32,
+    /// Lit fraction of each cycle: the strobe's on-time, or how long a
+    /// solid/gradient flash takes to decay. 1 = always on.
+    pub duty: f32,
+    pub beat_sync: bool,
+    /// Gradient direction in degrees; 0 = left to right, 90 = top to bottom.
+    pub angle: f32,
+    /// Gradient scroll speed in cycles per second.
+    pub scroll: f32,
+    pub intensity: f32,
+}
+
+impl Default for GeneratorDef {
+    fn default() -> Self {
+        Self {
+            kind: GeneratorKind::Strobe,
+            color: [1.0, 1.0, 1.0],
+            color_b: [0.1, 0.2, 0.9],
+            rate: 1.0,
+            duty: 0.15,
+            beat_sync: true,
+            angle: 0.0,
+            scroll: 0.0,
+            intensity: 1.0,
+        }
+    }
+}
+
+impl GeneratorDef {
+    /// Position in the current cycle, 0..1.
+    pub fn cycle_phase(&self, time: f32, beat_phase: f32, bar_phase: f32) -> f32 {
+        let rate = self.rate.max(0.01);
+        let pos = if !self.beat_sync {
+            time * rate
+        } else if rate >= 1.0 {
+            beat_phase * rate
+        } else {
+            bar_phase * 4.0 * rate
+        };
+        pos.rem_euclid(1.0)
+    }
+
+    /// Output level at `phase`: a hard gate for the strobe, a quadratic
+    /// flash decay for the washes (steady once `duty` reaches 1).
+    pub fn level(&self, phase: f32) -> f32 {
+        let duty = self.duty.clamp(0.0, 1.0);
+        let env = match self.kind {
+            GeneratorKind::Strobe => {
+                if phase < duty {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            GeneratorKind::Solid | GeneratorKind::Gradient => {
+                if duty >= 1.0 {
+                    1.0
+                } else if phase < duty {
+                    let t = 1.0 - phase / duty;
+                    t * t
+                } else {
+                    0.0
+                }
+            }
+        };
+        env * self.intensity.max(0.0)
+    }
+}
+
+/// Matches `GeneratorParams` in `generator.wgsl`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct GeneratorUniforms {
+    color_a: [f32; 4],
+    color_b: [f32; 4],
+    dir: [f32; 2],
+    offset: f32,
+    aspect: f32,
+}
+
+/// GPU side of a generator layer: one fullscreen pass into its own target.
+pub struct GeneratorLayer {
+    pub def: GeneratorDef,
+    pub output_target: RenderTarget,
+    pipeline: RenderPipeline,
+    bind_group: BindGroup,
+    uniform_buffer: wgpu::Buffer,
+    _tracked: Tracked,
+}
+
+impl GeneratorLayer {
+    pub fn new(
+        device: &Device,
+        hdr_format: TextureFormat,
+        width: u32,
+        height: u32,
+        def: GeneratorDef,
+    ) -> Self {
+        let before = ResourceCounts::read(device);
+        let output_target =
+            RenderTarget::new(device, width, height, hdr_format, 1.0, "generator-output");
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("generator-uniforms"),
+            size: std::mem::size_of::<GeneratorUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("generator-bgl"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: std::num::NonZeroU64::new(
+                        std::mem::size_of::<GeneratorUniforms>() as u64,
+                    ),
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("generator-bg"),
+            layout: &bgl,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let full_source = format!("{FULLSCREEN_TRIANGLE_VS_WITH_UV}\n{GENERATOR_FS}");
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("generator"),
+            source: wgpu::ShaderSource::Wgsl(full_source.into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("generator-layout"),
+            bind_group_layouts: &[&bgl],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("generator-pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: hdr_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: Default::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            def,
+            output_target,
+            pipeline,
+            bind_group,
+            uniform_buffer,
+            _tracked: Tracked::since(Subsystem::Generators, device, before),
+        }
+    }
+
+    /// Upload this frame's color and envelope level.
+    pub fn update(&self, queue: &Queue, u: &ShaderUniforms) {
+        let d = &self.def;
+        let level = d.level(d.cycle_phase(u.time, u.beat_phase, u.bar_phase));
+        let gradient = d.kind == GeneratorKind::Gradient;
+        let angle = d.angle.to_radians();
+        let uniforms = GeneratorUniforms {
+            color_a: [d.color[0], d.color[1], d.color[2], level],
+            color_b: [
+                d.color_b[0],
+                d.color_b[1],
+                d.color_b[2],
+                if gradient { 1.0 } else { 0.0 },
+            ],
+            dir: [angle.cos(), angle.sin()],
+            offset: (u.time * d.scroll).rem_euclid(2.0),
+            aspect: self.output_target.width as f32 / self.output_target.height.max(1) as f32,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+    }
+
+    pub fn execute(&self, encoder: &mut wgpu::CommandEncoder) -> &RenderTarget {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("generator"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.output_target.view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.draw(0..3, 0..1);
+        &self.output_target
+    }
+
+    pub fn resize(&mut self, device: &Device, width: u32, height: u32) {
+        self.output_target.resize(device, width, height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strobe_gates_on_duty() {
+        let d = GeneratorDef::default();
+        assert_eq!(d.level(0.0), 1.0);
+        assert_eq!(d.level(0.1), 1.0);
+        assert_eq!(d.level(0.5), 0.0);
+    }
+
+    #[test]
+    fn wash_flash_decays_and_full_duty_holds() {
+        let mut d = GeneratorDef {
+            kind: GeneratorKind::Solid,
+            duty: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(d.level(0.0), 1.0);
+        assert!((d.level(0.25) - 0.25).abs() < 1e-6);
+        assert_eq!(d.level(0.75), 0.0);
+        d.duty = 1.0;
+        assert_eq!(d.level(0.9), 1.0);
+    }
+
+    #[test]
+    fn beat_rates_follow_beat_or_bar_clock() {
+        let mut d = GeneratorDef {
+            rate: 2.0,
+            ..Default::default()
+        };
+        assert!((d.cycle_phase(0.0, 0.75, 0.0) - 0.5).abs() < 1e-6);
+        // Once per bar: bar phase drives it, beat phase is ignored.
+        d.rate = 0.25;
+        assert!((d.cycle_phase(0.0, 0.9, 0.3) - 0.3).abs() < 1e-6);
+        d.beat_sync = false;
+        d.rate = 4.0;
+        assert!((d.cycle_phase(1.125, 0.0, 0.0) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn uniforms_match_shader_struct() {
+        assert_eq!(std::mem::size_of::<GeneratorUniforms>(), 48);
+    }
+
+    #[test]
+    fn generator_shader_validates() {
+        let src = format!("{FULLSCREEN_TRIANGLE_VS_WITH_UV}\n{GENERATOR_FS}");
+        let module = wgpu::naga::front::wgsl::parse_str(&src).expect("shader parses");
+        wgpu::naga::valid::Validator::new(
+            wgpu::naga::valid::ValidationFlags::all(),
+            wgpu::naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .expect("shader validates");
+    }
+}
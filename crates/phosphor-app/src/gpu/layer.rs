@@ -1,14 +1,20 @@
 use serde::{Deserialize, Serialize};
 
-use crate::effect::format::PostProcessDef;
+use crate::effect::format::{AudioMapping, LayerSource, PfxEffect, PostProcessDef};
 use crate::gpu::ShaderUniforms;
 use crate::gpu::audio_textures::AudioTextures;
-use crate::gpu::pass_executor::PassExecutor;
+use crate::gpu::compositor::{Compositor, LayerTrail};
+use crate::gpu::debug_view::DebugTexture;
+use crate::gpu::envelope::OpacityEnvelope;
+use crate::gpu::frame_guard::LayerTimer;
+use crate::gpu::generator::GeneratorLayer;
+use crate::gpu::pass_executor::{ExternalInput, PassExecutor};
 use crate::gpu::placeholder::PlaceholderTexture;
 use crate::gpu::render_target::RenderTarget;
 use crate::gpu::uniforms::UniformBuffer;
 use crate::media::MediaLayer;
-use crate::params::ParamStore;
+use crate::media::playlist::MediaPlaylist;
+use crate::params::{AudioModulation, ParamAutomation, ParamStore, StepSequencer};
 
 /// Blend mode for compositing layers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -125,6 +131,8 @@ pub struct EffectLayer {
 pub enum LayerContent {
     Effect(Box<EffectLayer>),
     Media(Box<MediaLayer>),
+    /// Built-in strobe / solid / gradient wash (#2168).
+    Generator(Box<GeneratorLayer>),
 }
 
 /// A single compositing layer. Owns its own rendering pipeline and parameters.
@@ -139,6 +147,31 @@ pub struct Layer {
     pub locked: bool,
     pub pinned: bool,
     pub postprocess: PostProcessDef,
+    /// Clip reel for media layers. Lives here rather than on `MediaLayer`
+    /// because every clip change replaces the layer's media content.
+    pub playlist: Option<MediaPlaylist>,
+    /// Trail persistence (#2173): how much of the previous frame survives,
+    /// 0 = off. Works on any content, so it lives on the layer.
+    pub trail: f32,
+    /// Trail history, allocated by `sync_trail` while `trail` is above zero.
+    pub trail_history: Option<LayerTrail>,
+    /// Recorded param automation lanes (#2183).
+    pub automation: ParamAutomation,
+    /// Beat-synced step patterns (#2184).
+    pub sequencer: StepSequencer,
+    /// Edited audio mappings (#2186), saved with the preset. `None` follows
+    /// the effect's own `.pfx` mappings.
+    pub audio_mappings: Option<Vec<AudioMapping>>,
+    /// Runtime state for whichever audio mappings are in effect.
+    pub modulation: AudioModulation,
+    /// Audio mute (#2206): the layer sees silence, in its shader uniforms,
+    /// particles, generator envelope and audio mappings alike.
+    pub audio_muted: bool,
+    /// Why the frame-time guard stopped running this layer (#2243). Runtime
+    /// only: the layer stays enabled in the preset.
+    pub suspended: Option<String>,
+    /// Beat-synced opacity envelope (#2249), saved with the preset.
+    pub envelope: OpacityEnvelope,
 }
 
 impl Layer {
@@ -155,6 +188,16 @@ impl Layer {
             locked: false,
             pinned: false,
             postprocess: PostProcessDef::default(),
+            playlist: None,
+            trail: 0.0,
+            trail_history: None,
+            automation: ParamAutomation::default(),
+            sequencer: StepSequencer::default(),
+            audio_mappings: None,
+            modulation: AudioModulation::default(),
+            audio_muted: false,
+            suspended: None,
+            envelope: OpacityEnvelope::default(),
         }
     }
 
@@ -171,9 +214,69 @@ impl Layer {
             locked: false,
             pinned: false,
             postprocess: PostProcessDef::default(),
+            playlist: None,
+            trail: 0.0,
+            trail_history: None,
+            automation: ParamAutomation::default(),
+            sequencer: StepSequencer::default(),
+            audio_mappings: None,
+            modulation: AudioModulation::default(),
+            audio_muted: false,
+            suspended: None,
+            envelope: OpacityEnvelope::default(),
         }
     }
 
+    /// Create a new Generator layer.
+    pub fn new_generator(name: String, generator: GeneratorLayer) -> Self {
+        Self {
+            name,
+            custom_name: None,
+            param_store: ParamStore::new(),
+            content: LayerContent::Generator(Box::new(generator)),
+            blend_mode: BlendMode::Normal,
+            opacity: 1.0,
+            enabled: true,
+            locked: false,
+            pinned: false,
+            postprocess: PostProcessDef::default(),
+            playlist: None,
+            trail: 0.0,
+            trail_history: None,
+            automation: ParamAutomation::default(),
+            sequencer: StepSequencer::default(),
+            audio_mappings: None,
+            modulation: AudioModulation::default(),
+            audio_muted: false,
+            suspended: None,
+            envelope: OpacityEnvelope::default(),
+        }
+    }
+
+    /// Opacity as composited: the slider's value scaled by the envelope (#2249).
+    pub fn shown_opacity(&self) -> f32 {
+        self.opacity * self.envelope.gain()
+    }
+
+    /// Whether this layer hides everything beneath it (#2209): enabled, at
+    /// full opacity with Normal blend, and running an effect declared
+    /// `opaque`. Media and generators can leave areas transparent, so they
+    /// never do.
+    pub fn covers_below(&self, effects: &[PfxEffect]) -> bool {
+        self.is_running()
+            && self.shown_opacity() >= 1.0
+            && self.blend_mode == BlendMode::Normal
+            && self
+                .effect_index()
+                .and_then(|i| effects.get(i))
+                .is_some_and(|e| e.opaque)
+    }
+
+    /// Enabled and not suspended by the frame-time guard (#2243).
+    pub fn is_running(&self) -> bool {
+        self.enabled && self.suspended.is_none()
+    }
+
     /// Get the effect content, if this is an Effect layer.
     pub fn as_effect(&self) -> Option<&EffectLayer> {
         match &self.content {
@@ -206,6 +309,22 @@ impl Layer {
         }
     }
 
+    /// Get the generator content, if this is a Generator layer.
+    pub fn as_generator(&self) -> Option<&GeneratorLayer> {
+        match &self.content {
+            LayerContent::Generator(g) => Some(g),
+            _ => None,
+        }
+    }
+
+    /// Get mutable generator content, if this is a Generator layer.
+    pub fn as_generator_mut(&mut self) -> Option<&mut GeneratorLayer> {
+        match &mut self.content {
+            LayerContent::Generator(g) => Some(g),
+            _ => None,
+        }
+    }
+
     /// Check if this is a media layer.
     pub fn is_media(&self) -> bool {
         matches!(&self.content, LayerContent::Media(_))
@@ -227,6 +346,27 @@ impl Layer {
             .map_or(false, |e| e.pass_executor.particle_system.is_some())
     }
 
+    /// Allocate the trail history when a trail is set, and free it when not.
+    pub fn sync_trail(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        width: u32,
+        height: u32,
+        hdr_format: wgpu::TextureFormat,
+    ) {
+        if self.trail <= 0.0 {
+            self.trail_history = None;
+        } else if self.trail_history.is_none() {
+            self.trail_history = Some(LayerTrail::new(device, queue, width, height, hdr_format));
+        }
+    }
+
+    /// The trail history, while a trail is active.
+    fn active_trail(&self) -> Option<&LayerTrail> {
+        self.trail_history.as_ref().filter(|_| self.trail > 0.0)
+    }
+
     /// Execute this layer's render passes. Returns the final HDR target.
     pub fn execute(
         &self,
@@ -239,14 +379,53 @@ impl Layer {
                     .execute(encoder, &e.uniform_buffer, queue, &e.uniforms)
             }
             LayerContent::Media(m) => m.execute(encoder),
+            LayerContent::Generator(g) => g.execute(encoder),
+        }
+    }
+
+    /// Other layers this layer samples (effect `"layer:N"` / `"media"` inputs).
+    pub fn external_inputs(&self) -> &[ExternalInput] {
+        match &self.content {
+            LayerContent::Effect(e) => e.pass_executor.external_inputs(),
+            LayerContent::Media(_) | LayerContent::Generator(_) => &[],
+        }
+    }
+
+    /// The output this layer completed most recently, without rendering.
+    pub fn previous_output(&self) -> &RenderTarget {
+        if let Some(trail) = self.active_trail() {
+            return trail.previous();
+        }
+        match &self.content {
+            LayerContent::Effect(e) => e.pass_executor.previous_output(),
+            LayerContent::Media(m) => &m.output_target,
+            LayerContent::Generator(g) => &g.output_target,
         }
     }
 
+    /// Textures this layer renders through, for the debug view (#2229).
+    pub fn debug_textures(&self) -> Vec<DebugTexture<'_>> {
+        let mut out = match &self.content {
+            LayerContent::Effect(e) => e.pass_executor.debug_textures(),
+            LayerContent::Media(m) => vec![DebugTexture::of_target("output", &m.output_target)],
+            LayerContent::Generator(g) => {
+                vec![DebugTexture::of_target("output", &g.output_target)]
+            }
+        };
+        if let Some(trail) = self.active_trail() {
+            out.push(DebugTexture::of_target("trail", trail.previous()));
+        }
+        out
+    }
+
     /// Flip ping-pong targets for next frame.
     pub fn flip(&mut self) {
         match &mut self.content {
             LayerContent::Effect(e) => e.pass_executor.flip(),
-            LayerContent::Media(_) => {} // no ping-pong for media
+            LayerContent::Media(_) | LayerContent::Generator(_) => {} // no ping-pong
+        }
+        if let Some(ref mut trail) = self.trail_history {
+            trail.flip();
         }
     }
 
@@ -275,6 +454,10 @@ impl Layer {
             LayerContent::Media(_) => {
                 // Media resize handled separately (needs queue for uniform upload)
             }
+            LayerContent::Generator(g) => g.resize(device, width, height),
+        }
+        if let Some(ref mut trail) = self.trail_history {
+            trail.resize(device, queue, width, height);
         }
     }
 
@@ -315,8 +498,24 @@ pub struct LayerInfo {
     #[allow(dead_code)]
     pub media_is_video: bool,
     pub media_is_live: bool,
+    /// Generator kind name (#2168); `None` for effect and media layers.
+    pub generator: Option<&'static str>,
+    /// Trail persistence (#2173), 0 = off.
+    pub trail: f32,
+    /// Ignoring the audio (#2206).
+    pub audio_muted: bool,
+    /// Stopped by the frame-time guard (#2243), and why.
+    pub suspended: Option<String>,
+    /// Opacity envelope (#2249) and what it scales the opacity by now.
+    pub envelope: OpacityEnvelope,
+    pub envelope_gain: f32,
 }
 
+/// Layer cap when settings don't say otherwise (#2170).
+pub const DEFAULT_MAX_LAYERS: usize = 8;
+/// Layer caps offered in the settings panel.
+pub const MAX_LAYERS_CHOICES: &[usize] = &[8, 12, 16, 24, 32];
+
 /// Manages an ordered stack of layers.
 pub struct LayerStack {
     pub layers: Vec<Layer>,
@@ -339,6 +538,7 @@ impl LayerStack {
         self.layers.remove(index);
         self.active_layer =
             adjusted_active_after_remove(self.active_layer, index, self.layers.len());
+        self.remap_particle_layers(|i| index_after_remove(i, index));
     }
 
     /// Move a layer from `from` to `to` position.
@@ -349,6 +549,37 @@ impl LayerStack {
         let layer = self.layers.remove(from);
         self.layers.insert(to, layer);
         self.active_layer = adjusted_active_after_move(self.active_layer, from, to);
+        self.remap_particle_layers(|i| Some(adjusted_active_after_move(i, from, to)));
+    }
+
+    /// Keep particle systems that read other layers (#2155, #2160) on the
+    /// same layers after a reorder or removal.
+    fn remap_particle_layers(&mut self, remap: impl Fn(usize) -> Option<usize>) {
+        for ps in self
+            .layers
+            .iter_mut()
+            .filter_map(|l| l.as_effect_mut())
+            .filter_map(|e| e.pass_executor.particle_system.as_mut())
+        {
+            ps.remap_layer_source(&remap);
+            ps.remap_obstacle_layer(&remap);
+        }
+    }
+
+    /// The layers other than the active one, named for a picker.
+    pub fn layer_choices(&self) -> Vec<(usize, String)> {
+        self.layers
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != self.active_layer)
+            .map(|(i, l)| {
+                let name = l
+                    .custom_name
+                    .clone()
+                    .unwrap_or_else(|| format!("{} {}", i + 1, l.name));
+                (i, name)
+            })
+            .collect()
     }
 
     pub fn active(&self) -> Option<&Layer> {
@@ -395,11 +626,198 @@ impl LayerStack {
                     media_is_animated,
                     media_is_video,
                     media_is_live,
+                    generator: l.as_generator().map(|g| g.def.kind.display_name()),
+                    trail: l.trail,
+                    audio_muted: l.audio_muted,
+                    suspended: l.suspended.clone(),
+                    envelope: l.envelope.clone(),
+                    envelope_gain: l.envelope.gain(),
                 }
             })
             .collect()
     }
 
+    /// The enabled layers worth running this frame (#2170): the visible ones
+    /// (opacity above zero and not hidden under a covering layer, #2209), the
+    /// motion field's source, and everything they sample — effect inputs plus
+    /// layer-fed emitters and obstacles. A faded-out or covered layer nothing
+    /// reads is skipped. Stack order. `hooked` is set while a compositor hook
+    /// (#2252) runs, which may show any layer, so nothing counts as covered.
+    pub fn layers_to_run(&self, effects: &[PfxEffect], hooked: bool) -> Vec<usize> {
+        let flags: Vec<(bool, bool)> = self
+            .layers
+            .iter()
+            .map(|l| (l.is_running(), l.is_media()))
+            .collect();
+        let reads = |i: usize| -> Vec<usize> {
+            let layer = &self.layers[i];
+            let mut sources: Vec<usize> = layer
+                .external_inputs()
+                .iter()
+                .filter_map(|x| resolve_layer_source(x.source, i, &flags))
+                .collect();
+            if let Some(ps) = layer
+                .as_effect()
+                .and_then(|e| e.pass_executor.particle_system.as_ref())
+            {
+                sources.extend(ps.image_source.layer_index());
+                sources.extend(ps.obstacle_layer_index());
+            }
+            sources
+        };
+        let shown = self.shown_depth(effects, hooked);
+        let roots: Vec<usize> = self
+            .layers
+            .iter()
+            .enumerate()
+            .take(shown)
+            .filter(|(_, l)| l.is_running() && l.shown_opacity() > 0.0)
+            .map(|(i, _)| i)
+            .chain(self.motion_source())
+            .collect();
+        let enabled: Vec<bool> = self.layers.iter().map(Layer::is_running).collect();
+        needed_layers(&roots, &enabled, &reads)
+    }
+
+    /// How many layers, from the top, can show in the output: down to and
+    /// including the first that covers everything beneath it (#2209), or all
+    /// of them under a compositor hook.
+    fn shown_depth(&self, effects: &[PfxEffect], hooked: bool) -> usize {
+        shown_depth(self.layers.iter().map(|l| l.covers_below(effects)), hooked)
+    }
+
+    /// Run [`layers_to_run`](Self::layers_to_run) and composite the visible
+    /// ones. Returns the HDR result (`None` when no layer is visible) and the
+    /// layers that ran, which are the ones to flip at the end of the frame.
+    /// `timer` times each layer for the frame-time guard (#2243).
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_and_composite<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        compositor: &'a Compositor,
+        flow: Option<&RenderTarget>,
+        effects: &[PfxEffect],
+        timer: Option<&LayerTimer>,
+    ) -> (Option<&'a RenderTarget>, Vec<usize>) {
+        let hooked = compositor.has_hook();
+        let run = self.layers_to_run(effects, hooked);
+        let targets = self.execute_enabled(device, queue, encoder, compositor, &run, flow, timer);
+        // A covered layer that ran because something samples it stays out
+        let shown = self.shown_depth(effects, hooked);
+        // Reverse so top-of-UI-list renders visually on top
+        let visible: Vec<(usize, &RenderTarget, BlendMode, f32)> = run
+            .iter()
+            .zip(targets)
+            .filter(|&(&i, _)| i < shown)
+            .rev()
+            .map(|(&i, target)| {
+                let layer = &self.layers[i];
+                (i, target, layer.blend_mode, layer.shown_opacity())
+            })
+            .filter(|&(_, _, _, opacity)| opacity > 0.0)
+            .collect();
+        let output = match visible.as_slice() {
+            [] => None,
+            // A compositor hook (#2252) sees every layer, a lone one included
+            _ if compositor.has_hook() => {
+                Some(compositor.composite_hooked(device, queue, encoder, &visible))
+            }
+            // Single-layer fast path: skip compositing entirely (only when fully opaque)
+            [(_, target, _, opacity)] if *opacity >= 1.0 => Some(*target),
+            _ => {
+                let layers: Vec<(&RenderTarget, BlendMode, f32)> =
+                    visible.iter().map(|&(_, t, b, o)| (t, b, o)).collect();
+                Some(compositor.composite(device, queue, encoder, &layers))
+            }
+        };
+        (output, run)
+    }
+
+    /// Execute the `enabled` layers, each after any layer it samples, copying
+    /// sources into the consumers' input targets first. `flow` feeds `"flow"`
+    /// inputs. Returns the outputs in `enabled` order, ready for compositing.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_enabled(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        compositor: &Compositor,
+        enabled: &[usize],
+        flow: Option<&RenderTarget>,
+        timer: Option<&LayerTimer>,
+    ) -> Vec<&RenderTarget> {
+        let flags: Vec<(bool, bool)> = self
+            .layers
+            .iter()
+            .map(|l| (l.is_running(), l.is_media()))
+            .collect();
+        let sources_of = |i: usize| -> Vec<usize> {
+            self.layers[i]
+                .external_inputs()
+                .iter()
+                .filter_map(|x| resolve_layer_source(x.source, i, &flags))
+                .collect()
+        };
+
+        let mut outputs: Vec<Option<&RenderTarget>> = vec![None; self.layers.len()];
+        for idx in execution_order(enabled, &sources_of) {
+            let layer = &self.layers[idx];
+            if let Some(timer) = timer {
+                timer.begin(encoder, idx);
+            }
+            for ext in layer.external_inputs() {
+                // A source not yet run this frame (dependency cycle) gives last frame.
+                let src = match ext.source {
+                    LayerSource::Flow => flow,
+                    source => resolve_layer_source(source, idx, &flags)
+                        .map(|s| outputs[s].unwrap_or_else(|| self.layers[s].previous_output())),
+                };
+                compositor.blit_into(device, encoder, src, &ext.target);
+            }
+            let output = layer.execute(encoder, queue);
+            outputs[idx] = Some(match layer.active_trail() {
+                Some(trail) => {
+                    compositor.apply_trail(device, queue, encoder, output, trail, layer.trail)
+                }
+                None => output,
+            });
+            if let Some(timer) = timer {
+                timer.end(encoder, idx);
+            }
+        }
+        enabled
+            .iter()
+            .map(|&i| outputs[i].expect("every enabled layer executed"))
+            .collect()
+    }
+
+    /// Every layer's textures for the debug view (#2229), tagged with its index.
+    pub fn debug_textures(&self) -> Vec<(Option<usize>, DebugTexture<'_>)> {
+        self.layers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, layer)| {
+                layer
+                    .debug_textures()
+                    .into_iter()
+                    .map(move |t| (Some(i), t))
+            })
+            .collect()
+    }
+
+    /// The media layer the motion field tracks: the active layer if it is an
+    /// enabled media layer, otherwise the topmost enabled one.
+    pub fn motion_source(&self) -> Option<usize> {
+        let usable = |l: &Layer| l.is_running() && l.is_media();
+        if self.active().is_some_and(usable) {
+            return Some(self.active_layer);
+        }
+        self.layers.iter().position(usable)
+    }
+
     /// Number of enabled layers.
     #[allow(dead_code)]
     pub fn enabled_count(&self) -> usize {
@@ -407,6 +825,99 @@ impl LayerStack {
     }
 }
 
+/// How many layers from the top show, given whether each (top first) hides
+/// everything beneath it. A compositor hook (`hooked`) places layers itself,
+/// so an opaque one covers nothing.
+fn shown_depth(covers: impl Iterator<Item = bool>, hooked: bool) -> usize {
+    let mut depth = 0;
+    for covers in covers {
+        depth += 1;
+        if covers && !hooked {
+            return depth;
+        }
+    }
+    depth
+}
+
+/// Which layer a `LayerSource` names for the layer at `consumer`, given each
+/// layer's `(enabled, is_media)`. Disabled layers, the consumer itself, and
+/// out-of-range positions resolve to `None` (the input reads black).
+pub(crate) fn resolve_layer_source(
+    source: LayerSource,
+    consumer: usize,
+    layers: &[(bool, bool)],
+) -> Option<usize> {
+    match source {
+        LayerSource::Layer(n) => {
+            (n != consumer && layers.get(n).is_some_and(|&(enabled, _)| enabled)).then_some(n)
+        }
+        LayerSource::Media => layers
+            .iter()
+            .enumerate()
+            .skip(consumer + 1)
+            .find(|&(_, &(enabled, is_media))| enabled && is_media)
+            .map(|(i, _)| i),
+        LayerSource::Flow => None,
+    }
+}
+
+/// Indices whose `enabled` flag is set and that are a root or are read,
+/// directly or through other needed layers, by one. Sorted ascending.
+pub(crate) fn needed_layers(
+    roots: &[usize],
+    enabled: &[bool],
+    reads: &dyn Fn(usize) -> Vec<usize>,
+) -> Vec<usize> {
+    let is_enabled = |i: usize| enabled.get(i).copied().unwrap_or(false);
+    let mut needed = vec![false; enabled.len()];
+    let mut pending: Vec<usize> = roots.iter().copied().filter(|&i| is_enabled(i)).collect();
+    while let Some(i) = pending.pop() {
+        if needed[i] {
+            continue;
+        }
+        needed[i] = true;
+        pending.extend(
+            reads(i)
+                .into_iter()
+                .filter(|&s| is_enabled(s) && !needed[s]),
+        );
+    }
+    (0..enabled.len()).filter(|&i| needed[i]).collect()
+}
+
+/// Order `enabled` so each layer runs after the layers it samples. Independent
+/// layers keep stack order; an edge that would close a cycle is ignored, so that
+/// read sees the source's previous frame.
+pub(crate) fn execution_order(
+    enabled: &[usize],
+    sources_of: &dyn Fn(usize) -> Vec<usize>,
+) -> Vec<usize> {
+    fn visit(
+        i: usize,
+        enabled: &[usize],
+        sources_of: &dyn Fn(usize) -> Vec<usize>,
+        seen: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+    ) {
+        seen.push(i);
+        for s in sources_of(i) {
+            if enabled.contains(&s) && !seen.contains(&s) {
+                visit(s, enabled, sources_of, seen, order);
+            }
+        }
+        order.push(i);
+    }
+
+    let mut seen = Vec::with_capacity(enabled.len());
+    let mut order = Vec::with_capacity(enabled.len());
+    for &i in enabled {
+        if !seen.contains(&i) {
+            visit(i, enabled, sources_of, &mut seen, &mut order);
+        }
+    }
+    order
+}
+
 /// Compute adjusted active layer index after removing a layer.
 pub(crate) fn adjusted_active_after_remove(
     active: usize,
@@ -420,6 +931,16 @@ pub(crate) fn adjusted_active_after_remove(
     }
 }
 
+/// Where the layer at `index` sits after the layer at `removed` is taken out,
+/// or `None` if it was the one removed.
+pub(crate) fn index_after_remove(index: usize, removed: usize) -> Option<usize> {
+    match index.cmp(&removed) {
+        std::cmp::Ordering::Less => Some(index),
+        std::cmp::Ordering::Equal => None,
+        std::cmp::Ordering::Greater => Some(index - 1),
+    }
+}
+
 /// Compute adjusted active layer index after moving a layer from `from` to `to`.
 pub(crate) fn adjusted_active_after_move(active: usize, from: usize, to: usize) -> usize {
     if active == from {
@@ -437,6 +958,103 @@ pub(crate) fn adjusted_active_after_move(active: usize, from: usize, to: usize)
 mod tests {
     use super::*;
 
+    #[test]
+    fn resolve_layer_source_by_position() {
+        let layers = [(true, false), (true, false), (false, true)];
+        assert_eq!(
+            resolve_layer_source(LayerSource::Layer(1), 0, &layers),
+            Some(1)
+        );
+        // Self, disabled, and out-of-range sources read black.
+        assert_eq!(
+            resolve_layer_source(LayerSource::Layer(0), 0, &layers),
+            None
+        );
+        assert_eq!(
+            resolve_layer_source(LayerSource::Layer(2), 0, &layers),
+            None
+        );
+        assert_eq!(
+            resolve_layer_source(LayerSource::Layer(5), 0, &layers),
+            None
+        );
+    }
+
+    #[test]
+    fn resolve_media_picks_nearest_enabled_beneath() {
+        let layers = [
+            (true, true),
+            (true, false),
+            (false, true),
+            (true, true),
+            (true, true),
+        ];
+        assert_eq!(
+            resolve_layer_source(LayerSource::Media, 1, &layers),
+            Some(3)
+        );
+        assert_eq!(
+            resolve_layer_source(LayerSource::Media, 3, &layers),
+            Some(4)
+        );
+        // Media above the consumer is never picked.
+        assert_eq!(resolve_layer_source(LayerSource::Media, 4, &layers), None);
+    }
+
+    #[test]
+    fn execution_order_runs_sources_first() {
+        // Layer 0 samples 2; layer 1 is independent.
+        let deps = |i: usize| if i == 0 { vec![2] } else { vec![] };
+        assert_eq!(execution_order(&[0, 1, 2], &deps), vec![2, 0, 1]);
+        // Without dependencies, stack order is kept.
+        assert_eq!(execution_order(&[0, 1, 2], &|_| vec![]), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn execution_order_breaks_cycles_and_skips_disabled() {
+        let deps = |i: usize| match i {
+            0 => vec![1],
+            1 => vec![0],
+            _ => vec![3],
+        };
+        let order = execution_order(&[0, 1, 2], &deps);
+        assert_eq!(order, vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn shown_depth_stops_at_the_first_covering_layer() {
+        assert_eq!(shown_depth([false, true, false].into_iter(), false), 2);
+        assert_eq!(shown_depth([true, true].into_iter(), false), 1);
+        assert_eq!(shown_depth([false, false].into_iter(), false), 2);
+        assert_eq!(shown_depth(std::iter::empty(), false), 0);
+    }
+
+    #[test]
+    fn hooked_compositing_shows_layers_under_an_opaque_top() {
+        // An opaque top layer hides the rest, unless a hook lays them out
+        assert_eq!(shown_depth([true, false, false].into_iter(), false), 1);
+        assert_eq!(shown_depth([true, false, false].into_iter(), true), 3);
+        assert_eq!(shown_depth([false, true, true].into_iter(), true), 3);
+    }
+
+    #[test]
+    fn needed_layers_skips_unread_invisible_layers() {
+        // 0 is visible and samples 2, which samples 3; 1 is faded out and unread.
+        let reads = |i: usize| match i {
+            0 => vec![2],
+            2 => vec![3],
+            _ => vec![],
+        };
+        let enabled = [true; 4];
+        assert_eq!(needed_layers(&[0], &enabled, &reads), vec![0, 2, 3]);
+        // A disabled source is never run, nor is what only it reads.
+        let enabled = [true, true, false, true];
+        assert_eq!(needed_layers(&[0], &enabled, &reads), vec![0]);
+        // Cycles terminate; out-of-range roots are ignored.
+        let cyclic = |i: usize| vec![1 - i];
+        assert_eq!(needed_layers(&[0, 9], &[true, true], &cyclic), vec![0, 1]);
+    }
+
     #[test]
     fn blend_mode_all_count() {
         assert_eq!(BlendMode::ALL.len(), 10);
@@ -571,6 +1189,13 @@ mod tests {
         assert_eq!(adjusted_active_after_remove(2, 2, 2), 1);
     }
 
+    #[test]
+    fn index_after_remove_shifts_layers_below() {
+        assert_eq!(index_after_remove(0, 2), Some(0));
+        assert_eq!(index_after_remove(2, 2), None);
+        assert_eq!(index_after_remove(3, 2), Some(2));
+    }
+
     #[test]
     fn adjusted_active_after_move_boundary_from_zero() {
         // active=0, move from=0 to=3 -> active follows = 3
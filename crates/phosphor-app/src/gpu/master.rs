@@ -0,0 +1,193 @@
+//! Output master (#2165): a final brightness / contrast / gamma grade and a
+//! hard blackout, applied after post-processing to the surface and to every
+//! capture (NDI, recording). Both the post composite and the bypass blit run
+//! `master_grade.wgsl`, so the master works with post-processing off too.
+//! The same stage dithers the output (#2236) against 8-bit banding.
+
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+use super::calibration::OutputCalibration;
+
+/// Master grade (host-side). The app keeps a global copy in settings; a preset
+/// that opts in carries its own.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MasterParams {
+    /// Output gain, 1 = unchanged.
+    pub brightness: f32,
+    /// Contrast around mid-grey, 1 = unchanged.
+    pub contrast: f32,
+    /// Display gamma, 1 = unchanged; above 1 lifts the shadows.
+    pub gamma: f32,
+}
+
+impl Default for MasterParams {
+    fn default() -> Self {
+        Self {
+            brightness: 1.0,
+            contrast: 1.0,
+            gamma: 1.0,
+        }
+    }
+}
+
+impl MasterParams {
+    /// Names [`Self::set_param`] accepts.
+    pub const PARAM_NAMES: &[&str] = &["brightness", "contrast", "gamma"];
+
+    /// Set a named parameter (from OSC `/phosphor/master/{name}` or UI).
+    pub fn set_param(&mut self, name: &str, value: f32) {
+        match name {
+            "brightness" => self.brightness = value.clamp(0.0, 4.0),
+            "contrast" => self.contrast = value.clamp(0.0, 4.0),
+            "gamma" => self.gamma = value.clamp(0.1, 4.0),
+            _ => log::warn!("unknown master param: {name}"),
+        }
+    }
+
+    pub fn build_uniforms(&self, blackout: bool) -> MasterUniforms {
+        MasterUniforms {
+            brightness: self.brightness.max(0.0),
+            contrast: self.contrast.max(0.0),
+            inv_gamma: 1.0 / self.gamma.max(0.1),
+            level: if blackout { 0.0 } else { 1.0 },
+            ..Default::default()
+        }
+    }
+}
+
+/// Blue-noise dither on the output (#2236). A property of the display, so it
+/// lives in settings rather than presets.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DitherParams {
+    pub enabled: bool,
+    /// Noise spread in output steps; 1 covers one 8-bit level.
+    pub strength: f32,
+}
+
+impl Default for DitherParams {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strength: 1.0,
+        }
+    }
+}
+
+impl DitherParams {
+    /// Spread in output steps, 0 when off.
+    pub fn steps(&self) -> f32 {
+        if self.enabled {
+            self.strength.clamp(0.0, 4.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Live master state owned by the app.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct MasterState {
+    pub params: MasterParams,
+    /// Hard blackout: output is black until released. Never saved.
+    pub blackout: bool,
+    /// `params` belong to the current preset rather than the global setting.
+    pub per_preset: bool,
+    /// Silence fade (#2213), 0 = none to 1 = black. Driven every frame, never saved.
+    pub idle_fade: f32,
+    /// Output dither (#2236), from settings.
+    pub dither: DitherParams,
+    /// Per-output trims (#2237), from settings.
+    pub calibration: OutputCalibration,
+}
+
+impl MasterState {
+    /// This frame's uniforms: the grade, with blackout and the silence fade.
+    pub fn uniforms(&self) -> MasterUniforms {
+        let mut u = self.params.build_uniforms(self.blackout);
+        u.level *= 1.0 - self.idle_fade.clamp(0.0, 1.0);
+        u.dither = self.dither.steps();
+        u
+    }
+}
+
+/// Matches `MasterParams` in `master_grade.wgsl`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Pod, Zeroable)]
+pub struct MasterUniforms {
+    pub brightness: f32,
+    pub contrast: f32,
+    pub inv_gamma: f32,
+    /// 0 during blackout, otherwise 1.
+    pub level: f32,
+    /// Dither spread. Set here in output steps; the post chain turns it into
+    /// a fraction of full scale for the target format.
+    pub dither: f32,
+    /// 1 when the target stores sRGB, so the dither is spread in encoded steps.
+    pub srgb_target: f32,
+    pub _pad: [f32; 2],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_identity() {
+        let u = MasterParams::default().build_uniforms(false);
+        assert_eq!(
+            u,
+            MasterUniforms {
+                brightness: 1.0,
+                contrast: 1.0,
+                inv_gamma: 1.0,
+                level: 1.0,
+                ..Default::default()
+            }
+        );
+        assert_eq!(MasterParams::default().build_uniforms(true).level, 0.0);
+    }
+
+    #[test]
+    fn idle_fade_dims_the_level() {
+        let mut m = MasterState {
+            idle_fade: 0.25,
+            ..Default::default()
+        };
+        assert_eq!(m.uniforms().level, 0.75);
+        m.blackout = true;
+        assert_eq!(m.uniforms().level, 0.0);
+    }
+
+    #[test]
+    fn dither_is_off_until_enabled() {
+        let mut m = MasterState::default();
+        assert_eq!(m.uniforms().dither, 0.0);
+        m.dither.enabled = true;
+        assert_eq!(m.uniforms().dither, 1.0);
+        m.dither.strength = 10.0;
+        assert_eq!(m.uniforms().dither, 4.0);
+    }
+
+    #[test]
+    fn set_param_clamps_to_safe_ranges() {
+        let mut p = MasterParams::default();
+        p.set_param("brightness", -1.0);
+        p.set_param("contrast", 1.5);
+        p.set_param("gamma", 0.0);
+        assert_eq!(p.brightness, 0.0);
+        assert_eq!(p.contrast, 1.5);
+        assert_eq!(p.gamma, 0.1);
+        p.set_param("bogus", 9.0);
+        assert_eq!(p.contrast, 1.5);
+    }
+
+    #[test]
+    fn partial_json_fills_defaults() {
+        let p: MasterParams = serde_json::from_str(r#"{ "gamma": 2.2 }"#).unwrap();
+        assert_eq!(p.gamma, 2.2);
+        assert_eq!(p.brightness, 1.0);
+    }
+}
@@ -1,11 +1,23 @@
 pub mod audio_textures;
+pub mod blue_noise;
+pub mod calibration;
+pub mod capabilities;
 pub mod compositor;
 pub mod context;
+pub mod debug_view;
+pub mod deterministic;
+pub mod envelope;
+pub mod error_scope;
 pub mod frame_capture;
+pub mod frame_guard;
+pub mod freeze;
 pub mod fullscreen_quad;
+pub mod generator;
 pub mod half;
 pub mod lattice;
 pub mod layer;
+pub mod master;
+pub mod motion;
 pub mod particle;
 pub mod pass_executor;
 pub mod pipeline;
@@ -14,6 +26,7 @@ pub mod postprocess;
 #[cfg(feature = "profiling")]
 pub mod profiler;
 pub mod render_target;
+pub mod resources;
 pub mod shader_compiler;
 #[cfg(test)]
 pub mod test_gpu;
@@ -22,5 +35,5 @@ pub mod uniforms;
 pub mod volumetric;
 
 pub use context::GpuContext;
-pub use pipeline::ShaderPipeline;
+pub use pipeline::{ShaderPipeline, SharedPipelines};
 pub use uniforms::{ShaderUniforms, UniformBuffer};
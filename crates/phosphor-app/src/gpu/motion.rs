@@ -0,0 +1,387 @@
+//! Motion field (#2154): coarse optical flow of a media layer, so effects can react
+//! to movement on camera as well as to audio.
+//!
+//! Each frame the source layer's last output is reduced to a `FLOW_W`×`FLOW_H` luma
+//! grid and compared with the previous grid (Lucas-Kanade over a 5×5 window) in
+//! `motion_flow.wgsl`. Effects get the result two ways: the `motion` / `motion_x` /
+//! `motion_y` / `frame_diff` uniforms, read back from per-cell totals with one frame
+//! of latency (same non-blocking map pattern as the particle counter), and the flow
+//! texture itself via a `"flow"` pass input.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use wgpu::{
+    BindGroupLayout, BindGroupLayoutEntry, BindingType, BufferBindingType, CommandEncoder,
+    ComputePipeline, Device, PipelineCompilationOptions, PipelineLayoutDescriptor, ShaderStages,
+};
+
+use super::render_target::RenderTarget;
+
+const FLOW_SHADER: &str = include_str!("../../../../assets/shaders/builtin/motion_flow.wgsl");
+
+/// Flow grid size. Must match `FLOW_W` / `FLOW_H` in `motion_flow.wgsl`.
+const FLOW_W: u32 = 160;
+const FLOW_H: u32 = 90;
+/// Fixed-point scale of the shader's atomic totals.
+const FIXED: f32 = 1024.0;
+/// Per-frame smoothing weight for the reported values.
+const SMOOTHING: f32 = 0.35;
+
+/// Motion summary for one frame, as exposed to shaders.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MotionStats {
+    /// 0–1 amount of movement in the frame.
+    pub energy: f32,
+    /// Mean flow direction in grid cells per frame, -1..1 (x right, y down).
+    pub x: f32,
+    pub y: f32,
+    /// Mean absolute luma change per cell, 0–1.
+    pub frame_diff: f32,
+}
+
+impl MotionStats {
+    /// Convert the shader's fixed-point totals into per-cell means. Energy saturates
+    /// smoothly: a fifth of the frame moving two cells per frame reads about 0.7.
+    pub fn from_sums(sums: [i32; 4]) -> Self {
+        let cells = (FLOW_W * FLOW_H) as f32;
+        let mean = |s: i32| s as f32 / FIXED / cells;
+        Self {
+            energy: 1.0 - (-mean(sums[0]) * 3.0).exp(),
+            x: mean(sums[1]).clamp(-1.0, 1.0),
+            y: mean(sums[2]).clamp(-1.0, 1.0),
+            frame_diff: mean(sums[3]).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Ease toward `target` so single noisy frames don't flicker.
+    pub fn smoothed_toward(self, target: Self) -> Self {
+        let lerp = |a: f32, b: f32| a + (b - a) * SMOOTHING;
+        Self {
+            energy: lerp(self.energy, target.energy),
+            x: lerp(self.x, target.x),
+            y: lerp(self.y, target.y),
+            frame_diff: lerp(self.frame_diff, target.frame_diff),
+        }
+    }
+}
+
+pub struct MotionField {
+    bgl: BindGroupLayout,
+    luma_pipeline: ComputePipeline,
+    flow_pipeline: ComputePipeline,
+    luma: [wgpu::Buffer; 2],
+    /// Which `luma` buffer receives this frame's grid; the other holds last frame's.
+    current: usize,
+    stats_buffer: wgpu::Buffer,
+    readback: wgpu::Buffer,
+    map_pending: Arc<AtomicBool>,
+    map_ready: Arc<AtomicBool>,
+    /// Whether a dispatch went out this frame (a readback is worth requesting).
+    dispatched: bool,
+    /// Flow texture, `Rgba16Float`: rg = uv displacement per frame, b = frame
+    /// difference, a = magnitude in cells.
+    pub flow: RenderTarget,
+    pub stats: MotionStats,
+}
+
+impl MotionField {
+    pub fn new(device: &Device) -> Self {
+        let storage = |binding: u32, read_only: bool| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::COMPUTE,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("motion-flow-bgl"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                storage(2, false),
+                storage(3, true),
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: wgpu::TextureFormat::Rgba16Float,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                storage(5, false),
+            ],
+        });
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("motion-flow"),
+            source: wgpu::ShaderSource::Wgsl(FLOW_SHADER.into()),
+        });
+        let layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("motion-flow-layout"),
+            bind_group_layouts: &[&bgl],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry: &str| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(entry),
+                layout: Some(&layout),
+                module: &module,
+                entry_point: Some(entry),
+                compilation_options: PipelineCompilationOptions::default(),
+                cache: None,
+            })
+        };
+        let luma_pipeline = pipeline("cs_luma");
+        let flow_pipeline = pipeline("cs_flow");
+
+        let luma_buffer = |label: &str| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: (FLOW_W * FLOW_H * 4) as u64,
+                usage: wgpu::BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            })
+        };
+        let luma = [luma_buffer("motion-luma-0"), luma_buffer("motion-luma-1")];
+        let stats_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("motion-stats"),
+            size: 16,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("motion-stats-readback"),
+            size: 16,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            bgl,
+            luma_pipeline,
+            flow_pipeline,
+            luma,
+            current: 0,
+            stats_buffer,
+            readback,
+            map_pending: Arc::new(AtomicBool::new(false)),
+            map_ready: Arc::new(AtomicBool::new(false)),
+            dispatched: false,
+            flow: create_flow_target(device),
+            stats: MotionStats::default(),
+        }
+    }
+
+    /// Analyse `source` (a layer's HDR output) against last frame's grid.
+    pub fn dispatch(
+        &mut self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        source: &RenderTarget,
+    ) {
+        self.current = 1 - self.current;
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("motion-flow-bg"),
+            layout: &self.bgl,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&source.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&source.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.luma[self.current].as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.luma[1 - self.current].as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::TextureView(&self.flow.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: self.stats_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        encoder.clear_buffer(&self.stats_buffer, 0, None);
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("motion-flow"),
+                timestamp_writes: None,
+            });
+            pass.set_bind_group(0, &bind_group, &[]);
+            let groups = (FLOW_W.div_ceil(8), FLOW_H.div_ceil(8));
+            pass.set_pipeline(&self.luma_pipeline);
+            pass.dispatch_workgroups(groups.0, groups.1, 1);
+            pass.set_pipeline(&self.flow_pipeline);
+            pass.dispatch_workgroups(groups.0, groups.1, 1);
+        }
+
+        // Skip the copy while the readback is still mapped (submit would fail).
+        self.dispatched = !self.map_pending.load(Ordering::Relaxed);
+        if self.dispatched {
+            encoder.copy_buffer_to_buffer(&self.stats_buffer, 0, &self.readback, 0, 16);
+        }
+    }
+
+    /// No source this frame: let the reported motion fall back to rest.
+    pub fn idle(&mut self) {
+        self.stats = self.stats.smoothed_toward(MotionStats::default());
+    }
+
+    /// Request async map of the stats readback. Call once per frame after submit.
+    pub fn request_readback(&mut self) {
+        if !std::mem::take(&mut self.dispatched) || self.map_pending.load(Ordering::Relaxed) {
+            return;
+        }
+        self.map_pending.store(true, Ordering::Release);
+        let pending = self.map_pending.clone();
+        let ready = self.map_ready.clone();
+        self.readback
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                if result.is_ok() {
+                    ready.store(true, Ordering::Release);
+                } else {
+                    pending.store(false, Ordering::Release);
+                }
+            });
+    }
+
+    /// Fold a completed readback into `stats`. Call once per frame before use.
+    pub fn poll_readback(&mut self) {
+        if !self.map_ready.load(Ordering::Acquire) {
+            return;
+        }
+        let sums = {
+            let view = self.readback.slice(..).get_mapped_range();
+            let data: &[i32] = bytemuck::cast_slice(&view);
+            [data[0], data[1], data[2], data[3]]
+        };
+        self.readback.unmap();
+        self.map_ready.store(false, Ordering::Release);
+        self.map_pending.store(false, Ordering::Release);
+        self.stats = self.stats.smoothed_toward(MotionStats::from_sums(sums));
+    }
+}
+
+fn create_flow_target(device: &Device) -> RenderTarget {
+    let format = wgpu::TextureFormat::Rgba16Float;
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("motion-flow"),
+        size: wgpu::Extent3d {
+            width: FLOW_W,
+            height: FLOW_H,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("motion-flow-sampler"),
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        ..Default::default()
+    });
+    RenderTarget {
+        texture,
+        view,
+        sampler,
+        format,
+        width: FLOW_W,
+        height: FLOW_H,
+        scale: 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_from_zero_sums_are_at_rest() {
+        assert_eq!(MotionStats::from_sums([0; 4]), MotionStats::default());
+    }
+
+    #[test]
+    fn stats_from_sums_are_per_cell_means() {
+        let cells = (FLOW_W * FLOW_H) as f32;
+        let total = |mean: f32| (mean * cells * FIXED) as i32;
+        let s = MotionStats::from_sums([total(0.4), total(0.25), total(-0.5), total(0.1)]);
+        assert!((s.energy - (1.0 - (-1.2f32).exp())).abs() < 1e-3);
+        assert!((s.x - 0.25).abs() < 1e-3);
+        assert!((s.y + 0.5).abs() < 1e-3);
+        assert!((s.frame_diff - 0.1).abs() < 1e-3);
+    }
+
+    #[test]
+    fn stats_direction_is_clamped() {
+        let s = MotionStats::from_sums([0, i32::MAX, i32::MIN, 0]);
+        assert_eq!((s.x, s.y), (1.0, -1.0));
+    }
+
+    #[test]
+    fn smoothing_moves_partway() {
+        let target = MotionStats {
+            energy: 1.0,
+            ..Default::default()
+        };
+        let s = MotionStats::default().smoothed_toward(target);
+        assert!(s.energy > 0.0 && s.energy < 1.0);
+    }
+
+    #[test]
+    fn flow_grid_matches_shader() {
+        assert!(FLOW_SHADER.contains(&format!("const FLOW_W: u32 = {FLOW_W}u;")));
+        assert!(FLOW_SHADER.contains(&format!("const FLOW_H: u32 = {FLOW_H}u;")));
+        assert!(FLOW_SHADER.contains(&format!("const FIXED: f32 = {FIXED:.1};")));
+    }
+
+    #[test]
+    fn flow_shader_validates() {
+        let module = wgpu::naga::front::wgsl::parse_str(FLOW_SHADER).expect("parse");
+        wgpu::naga::valid::Validator::new(
+            wgpu::naga::valid::ValidationFlags::all(),
+            wgpu::naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .expect("validate");
+    }
+}
@@ -0,0 +1,98 @@
+//! On-demand particle bursts (#2162).
+//!
+//! The `ParticleBurst` trigger queues a burst here instead of touching the
+//! continuous emit rate. `ParticleSystem::update_uniforms` drains the queue
+//! into the frame's emission count: unquantized bursts on the next frame,
+//! quantized ones on the next detected beat.
+
+/// A quantized burst stops waiting after this long without a beat, so one
+/// fired in silence or a breakdown still lands.
+const MAX_BEAT_WAIT_SECS: f32 = 2.0;
+
+struct PendingBurst {
+    count: u32,
+    on_beat: bool,
+    waited: f32,
+}
+
+#[derive(Default)]
+pub struct BurstQueue {
+    pending: Vec<PendingBurst>,
+}
+
+impl BurstQueue {
+    pub fn push(&mut self, count: u32, on_beat: bool) {
+        if count > 0 {
+            self.pending.push(PendingBurst {
+                count,
+                on_beat,
+                waited: 0.0,
+            });
+        }
+    }
+
+    /// Particles due this frame. `beat` is this frame's beat trigger.
+    pub fn drain(&mut self, beat: bool, dt: f32) -> u32 {
+        let mut due = 0u32;
+        self.pending.retain_mut(|b| {
+            b.waited += dt;
+            if !b.on_beat || beat || b.waited >= MAX_BEAT_WAIT_SECS {
+                due = due.saturating_add(b.count);
+                false
+            } else {
+                true
+            }
+        });
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn immediate_burst_fires_next_frame() {
+        let mut q = BurstQueue::default();
+        q.push(500, false);
+        assert_eq!(q.drain(false, 0.016), 500);
+        assert_eq!(q.drain(false, 0.016), 0);
+    }
+
+    #[test]
+    fn quantized_burst_waits_for_the_beat() {
+        let mut q = BurstQueue::default();
+        q.push(500, true);
+        q.push(100, false);
+        assert_eq!(q.drain(false, 0.016), 100);
+        assert_eq!(q.drain(false, 0.016), 0);
+        assert_eq!(q.drain(true, 0.016), 500);
+        assert_eq!(q.drain(true, 0.016), 0);
+    }
+
+    #[test]
+    fn quantized_bursts_stack_on_one_beat() {
+        let mut q = BurstQueue::default();
+        q.push(200, true);
+        q.push(300, true);
+        assert_eq!(q.drain(true, 0.016), 500);
+    }
+
+    #[test]
+    fn quantized_burst_gives_up_waiting_in_silence() {
+        let mut q = BurstQueue::default();
+        q.push(500, true);
+        let mut fired = 0;
+        for _ in 0..200 {
+            fired += q.drain(false, 1.0 / 60.0);
+        }
+        assert_eq!(fired, 500);
+    }
+
+    #[test]
+    fn zero_count_is_ignored() {
+        let mut q = BurstQueue::default();
+        q.push(0, false);
+        assert!(q.pending.is_empty());
+    }
+}
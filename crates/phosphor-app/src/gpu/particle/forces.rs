@@ -0,0 +1,158 @@
+//! `.pfx` force fields (#2158).
+//!
+//! A `forces` list is resolved on the CPU every frame: each entry's strength is
+//! multiplied by its bound input (if any), point forces are packed into the
+//! uniform force block, and field forces fold into the global gravity / drag
+//! terms `update_uniforms` just wrote. `apply_builtin_forces` in
+//! `particle_lib.wgsl` evaluates the result.
+
+use super::types::{ForceDef, ForceKind, MAX_POINT_FORCES, ParticleUniforms};
+
+/// Curl-noise frequency when a `curl` entry leaves `radius` at 0. Matches the
+/// `pos * 3.0` the legacy noise path samples at.
+const DEFAULT_CURL_SCALE: f32 = 3.0;
+
+/// Write `forces` into `u`. `param` looks up the live value of a named float
+/// input; an unknown name leaves the strength unscaled.
+///
+/// Must run after `ParticleSystem::update_uniforms`, which resets gravity and
+/// drag from the def each frame — that reset is what keeps this additive.
+pub fn pack_forces(
+    forces: &[ForceDef],
+    u: &mut ParticleUniforms,
+    param: impl Fn(&str) -> Option<f32>,
+) {
+    u.force_count = 0;
+    u.force_vortex_mask = 0;
+    u.force_curl = 0.0;
+    u.force_curl_scale = DEFAULT_CURL_SCALE;
+    u.forces = [[0.0; 4]; MAX_POINT_FORCES];
+
+    for f in forces {
+        let strength = if f.param.is_empty() {
+            f.strength
+        } else {
+            f.strength * param(&f.param).unwrap_or(1.0)
+        };
+        match f.kind {
+            ForceKind::Attractor | ForceKind::Repulsor | ForceKind::Vortex => {
+                let i = u.force_count as usize;
+                if i >= MAX_POINT_FORCES {
+                    continue;
+                }
+                let signed = if f.kind == ForceKind::Repulsor {
+                    -strength
+                } else {
+                    strength
+                };
+                u.forces[i] = [f.position[0], f.position[1], signed, f.radius.max(0.0)];
+                if f.kind == ForceKind::Vortex {
+                    u.force_vortex_mask |= 1 << i;
+                }
+                u.force_count += 1;
+            }
+            ForceKind::Curl => {
+                u.force_curl += strength;
+                // `radius` is the swirl size; the shader wants a frequency
+                if f.radius > 0.0 {
+                    u.force_curl_scale = 1.0 / f.radius;
+                }
+            }
+            ForceKind::Gravity => {
+                u.gravity[0] += f.direction[0] * strength;
+                u.gravity[1] += f.direction[1] * strength;
+            }
+            ForceKind::Drag => {
+                u.drag *= (1.0 - strength).clamp(0.0, 1.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn force(json: &str) -> ForceDef {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn zeroed() -> ParticleUniforms {
+        bytemuck::Zeroable::zeroed()
+    }
+
+    #[test]
+    fn point_forces_pack_in_order_with_signs() {
+        let forces = [
+            force(r#"{"type": "attractor", "position": [0.5, 0.0], "strength": 2.0}"#),
+            force(r#"{"type": "repulsor", "strength": 1.0, "radius": 0.3}"#),
+            force(r#"{"type": "vortex", "position": [0.0, 0.5], "strength": 3.0}"#),
+        ];
+        let mut u = zeroed();
+        pack_forces(&forces, &mut u, |_| None);
+        assert_eq!(u.force_count, 3);
+        assert_eq!(u.forces[0], [0.5, 0.0, 2.0, 0.0]);
+        assert_eq!(u.forces[1], [0.0, 0.0, -1.0, 0.3]);
+        assert_eq!(u.force_vortex_mask, 0b100);
+    }
+
+    #[test]
+    fn bound_param_scales_strength() {
+        let forces = [force(
+            r#"{"type": "attractor", "strength": 2.0, "param": "pull"}"#,
+        )];
+        let mut u = zeroed();
+        pack_forces(&forces, &mut u, |name| (name == "pull").then_some(0.25));
+        assert_eq!(u.forces[0][2], 0.5);
+
+        // A name the effect doesn't declare leaves the strength as written.
+        let forces = [force(
+            r#"{"type": "attractor", "strength": 2.0, "param": "nope"}"#,
+        )];
+        pack_forces(&forces, &mut u, |_| None);
+        assert_eq!(u.forces[0][2], 2.0);
+    }
+
+    #[test]
+    fn field_forces_fold_into_globals() {
+        let forces = [
+            force(r#"{"type": "gravity", "strength": 0.5}"#),
+            force(r#"{"type": "drag", "strength": 0.5}"#),
+            force(r#"{"type": "curl", "strength": 0.4, "radius": 0.25}"#),
+        ];
+        let mut u = zeroed();
+        u.gravity = [0.1, 0.0];
+        u.drag = 0.98;
+        pack_forces(&forces, &mut u, |_| None);
+        assert_eq!(u.gravity, [0.1, -0.5]);
+        assert_eq!(u.drag, 0.49);
+        assert_eq!(u.force_curl, 0.4);
+        assert_eq!(u.force_curl_scale, 4.0);
+        assert_eq!(u.force_count, 0);
+    }
+
+    #[test]
+    fn point_forces_past_the_block_are_dropped() {
+        let forces: Vec<ForceDef> = (0..MAX_POINT_FORCES + 2)
+            .map(|_| force(r#"{"type": "vortex", "strength": 1.0}"#))
+            .collect();
+        let mut u = zeroed();
+        pack_forces(&forces, &mut u, |_| None);
+        assert_eq!(u.force_count as usize, MAX_POINT_FORCES);
+        assert_eq!(u.force_vortex_mask, (1 << MAX_POINT_FORCES) - 1);
+    }
+
+    #[test]
+    fn repacking_clears_the_previous_frame() {
+        let mut u = zeroed();
+        pack_forces(
+            &[force(r#"{"type": "vortex", "strength": 1.0}"#)],
+            &mut u,
+            |_| None,
+        );
+        pack_forces(&[], &mut u, |_| None);
+        assert_eq!(u.force_count, 0);
+        assert_eq!(u.force_vortex_mask, 0);
+        assert_eq!(u.forces[0], [0.0; 4]);
+    }
+}
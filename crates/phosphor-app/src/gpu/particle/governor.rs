@@ -0,0 +1,151 @@
+//! Automatic particle quality governor (#2156).
+//!
+//! Watches frame time and scales every layer's live particle budget down when
+//! frames run over budget, then eases it back up once they have been steady for
+//! a while. Only the live budget moves — buffers are never reallocated — so a
+//! step is free and takes effect on the next frame.
+
+/// Frame budget the governor defends (60 fps).
+pub const FRAME_BUDGET_MS: f32 = 1000.0 / 60.0;
+/// Smoothed frame time above `FRAME_BUDGET_MS ×` this sheds particles.
+const OVER_BUDGET: f32 = 1.2;
+/// Smoothed frame time under `FRAME_BUDGET_MS ×` this counts as steady. Loose
+/// enough that a vsync-locked frame (exactly on budget) still recovers.
+const STEADY: f32 = 1.05;
+/// Scale never drops below this — a dim effect beats an empty one.
+pub const MIN_SCALE: f32 = 0.1;
+const STEP_DOWN: f32 = 0.8;
+const STEP_UP: f32 = 1.1;
+/// Over-budget seconds required before shedding, so a short burst of slow
+/// frames doesn't cost quality.
+const SHED_AFTER_SECS: f32 = 0.5;
+/// Seconds after any step before judging again — the new load needs a few
+/// frames to show up in the smoothed time.
+const SETTLE_SECS: f32 = 0.5;
+/// Steady seconds required before stepping back up.
+const RECOVER_SECS: f32 = 3.0;
+/// Single-frame ceiling: a file dialog or effect swap stalls one frame for
+/// hundreds of ms and shouldn't read as sustained load.
+const MAX_FRAME_MS: f32 = 100.0;
+
+pub struct QualityGovernor {
+    scale: f32,
+    smoothed_ms: f32,
+    settle_secs: f32,
+    over_secs: f32,
+    steady_secs: f32,
+}
+
+impl QualityGovernor {
+    pub fn new() -> Self {
+        Self {
+            scale: 1.0,
+            smoothed_ms: FRAME_BUDGET_MS,
+            settle_secs: 0.0,
+            over_secs: 0.0,
+            steady_secs: 0.0,
+        }
+    }
+
+    /// Current budget multiplier in `[MIN_SCALE, 1]`.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Back to full budget (governor switched off, or a new effect loaded).
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Feed one frame's wall time. Returns true if the scale changed.
+    pub fn update(&mut self, frame_ms: f32) -> bool {
+        let frame_ms = frame_ms.clamp(0.0, MAX_FRAME_MS);
+        let secs = frame_ms / 1000.0;
+        self.smoothed_ms += (frame_ms - self.smoothed_ms) * 0.1;
+
+        if self.settle_secs > 0.0 {
+            self.settle_secs -= secs;
+            return false;
+        }
+
+        if self.smoothed_ms > FRAME_BUDGET_MS * OVER_BUDGET {
+            self.steady_secs = 0.0;
+            self.over_secs += secs;
+            if self.over_secs >= SHED_AFTER_SECS && self.scale > MIN_SCALE {
+                self.scale = (self.scale * STEP_DOWN).max(MIN_SCALE);
+                self.over_secs = 0.0;
+                self.settle_secs = SETTLE_SECS;
+                return true;
+            }
+        } else if self.smoothed_ms <= FRAME_BUDGET_MS * STEADY {
+            self.over_secs = 0.0;
+            self.steady_secs += secs;
+            if self.steady_secs >= RECOVER_SECS && self.scale < 1.0 {
+                self.scale = (self.scale * STEP_UP).min(1.0);
+                self.steady_secs = 0.0;
+                self.settle_secs = SETTLE_SECS;
+                return true;
+            }
+        } else {
+            // Between steady and over: hold.
+            self.over_secs = 0.0;
+            self.steady_secs = 0.0;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(g: &mut QualityGovernor, frame_ms: f32, secs: f32) {
+        let frames = (secs * 1000.0 / frame_ms) as usize;
+        for _ in 0..frames {
+            g.update(frame_ms);
+        }
+    }
+
+    #[test]
+    fn steady_frames_keep_full_quality() {
+        let mut g = QualityGovernor::new();
+        run(&mut g, FRAME_BUDGET_MS, 10.0);
+        assert_eq!(g.scale(), 1.0);
+    }
+
+    #[test]
+    fn sustained_overload_sheds_down_to_floor() {
+        let mut g = QualityGovernor::new();
+        run(&mut g, 40.0, 2.0);
+        assert!(g.scale() < 1.0);
+        run(&mut g, 40.0, 60.0);
+        assert_eq!(g.scale(), MIN_SCALE);
+    }
+
+    #[test]
+    fn single_stall_does_not_shed() {
+        let mut g = QualityGovernor::new();
+        run(&mut g, FRAME_BUDGET_MS, 1.0);
+        g.update(500.0);
+        run(&mut g, FRAME_BUDGET_MS, 1.0);
+        assert_eq!(g.scale(), 1.0);
+    }
+
+    #[test]
+    fn recovers_at_vsync_after_overload() {
+        let mut g = QualityGovernor::new();
+        run(&mut g, 30.0, 3.0);
+        let shed = g.scale();
+        assert!(shed < 1.0);
+        run(&mut g, FRAME_BUDGET_MS, 120.0);
+        assert_eq!(g.scale(), 1.0);
+    }
+
+    #[test]
+    fn reset_restores_full_scale() {
+        let mut g = QualityGovernor::new();
+        run(&mut g, 40.0, 5.0);
+        g.reset();
+        assert_eq!(g.scale(), 1.0);
+    }
+}
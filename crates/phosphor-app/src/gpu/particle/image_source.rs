@@ -1,6 +1,9 @@
 use std::path::Path;
 
+use bytemuck::{Pod, Zeroable};
+
 use super::types::{ImageSampleDef, ParticleAux};
+use crate::gpu::render_target::RenderTarget;
 
 /// Maximum image dimension after resize.
 /// 2048 supports up to ~1M particles with grid sampling (step=2 at 2048²).
@@ -88,7 +91,10 @@ pub fn sample_image(
     sample_def: &ImageSampleDef,
     max_particles: u32,
 ) -> Result<Vec<ParticleAux>, String> {
-    let img = image::open(path).map_err(|e| format!("Failed to load image: {e}"))?;
+    let img = match super::vector_source::load(path) {
+        Some(rendered) => image::DynamicImage::ImageRgba8(rendered?),
+        None => image::open(path).map_err(|e| format!("Failed to load image: {e}"))?,
+    };
 
     // Resize to cap dimensions while preserving aspect ratio
     let img = if img.width() > MAX_DIM || img.height() > MAX_DIM {
@@ -341,6 +347,193 @@ pub fn sample_rgba_buffer(
         .collect()
 }
 
+/// Evenly spaced subset of `count` records from `data` — how a live particle
+/// budget below the sampled count thins an image instead of cropping it (#2156).
+pub fn thin_aux(data: &[ParticleAux], count: usize) -> Vec<ParticleAux> {
+    if count >= data.len() {
+        return data.to_vec();
+    }
+    (0..count)
+        .map(|i| data[(i as u64 * data.len() as u64 / count as u64) as usize])
+        .collect()
+}
+
+/// Grid of `cols × rows ≥ max_particles` cells with roughly square cells on a
+/// source of the given aspect ratio. Shared slot layout of the live layer sampler.
+pub fn layer_grid(max_particles: u32, aspect: f32) -> (u32, u32) {
+    let n = max_particles.max(1);
+    let cols = ((n as f32 * aspect.max(1e-3)).sqrt().ceil() as u32).clamp(1, n);
+    (cols, n.div_ceil(cols))
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct LayerSampleUniforms {
+    max_particles: u32,
+    cols: u32,
+    rows: u32,
+    mode: u32,
+    threshold: f32,
+    scale: f32,
+    seed: f32,
+    _pad: f32,
+}
+
+/// GPU sampler that refreshes particle aux data from a live layer texture each
+/// frame (#2155) — the video/webcam path without a CPU readback. Positions are
+/// screen-aligned, so particles sit on the pixels they were sampled from.
+pub struct LayerSampler {
+    pipeline: wgpu::ComputePipeline,
+    bgl: wgpu::BindGroupLayout,
+    uniform_buffer: wgpu::Buffer,
+}
+
+impl LayerSampler {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let compute = wgpu::ShaderStages::COMPUTE;
+        let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("particle-layer-sample-bgl"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: compute,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: compute,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: compute,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: compute,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("particle-layer-sample"),
+            source: wgpu::ShaderSource::Wgsl(LAYER_SAMPLE_SHADER.into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("particle-layer-sample-layout"),
+            bind_group_layouts: &[&bgl],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("particle-layer-sample-pipeline"),
+            layout: Some(&layout),
+            module: &module,
+            entry_point: Some("cs_main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("particle-layer-sample-uniforms"),
+            size: std::mem::size_of::<LayerSampleUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Self {
+            pipeline,
+            bgl,
+            uniform_buffer,
+        }
+    }
+
+    /// Rewrite the first `max_particles` records of `aux` from `source`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn dispatch(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &RenderTarget,
+        aux: &wgpu::Buffer,
+        sample_def: &ImageSampleDef,
+        max_particles: u32,
+        time: f32,
+    ) {
+        let capacity = (aux.size() / std::mem::size_of::<ParticleAux>() as u64) as u32;
+        let count = max_particles.min(capacity);
+        if count == 0 {
+            return;
+        }
+        let (cols, rows) = layer_grid(count, source.width as f32 / source.height.max(1) as f32);
+        let mode = match sample_def.mode.as_str() {
+            "threshold" => 1,
+            "random" => 2,
+            _ => 0,
+        };
+        let uniforms = LayerSampleUniforms {
+            max_particles: count,
+            cols,
+            rows,
+            mode,
+            threshold: sample_def.threshold,
+            scale: sample_def.scale,
+            // Random mode reshuffles slowly rather than every frame.
+            seed: (time * 4.0).floor(),
+            _pad: 0.0,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("particle-layer-sample-bg"),
+            layout: &self.bgl,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&source.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&source.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: aux.as_entire_binding(),
+                },
+            ],
+        });
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("particle-layer-sample"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(count.div_ceil(256), 1, 1);
+    }
+}
+
+const LAYER_SAMPLE_SHADER: &str =
+    include_str!("../../../../../assets/shaders/builtin/layer_sample.wgsl");
+
 /// Pack RGBA bytes into a single f32 via bitcast from u32.
 fn pack_rgba(r: u8, g: u8, b: u8, a: u8) -> f32 {
     let packed = (r as u32) | ((g as u32) << 8) | ((b as u32) << 16) | ((a as u32) << 24);
@@ -351,6 +544,56 @@ fn pack_rgba(r: u8, g: u8, b: u8, a: u8) -> f32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn thin_aux_spans_the_whole_source() {
+        let data: Vec<ParticleAux> = (0..1000)
+            .map(|i| ParticleAux {
+                home: [i as f32, 0.0, 0.0, 0.0],
+            })
+            .collect();
+        let thinned = thin_aux(&data, 100);
+        assert_eq!(thinned.len(), 100);
+        assert_eq!(thinned[0].home[0], 0.0);
+        assert_eq!(thinned[99].home[0], 990.0);
+        assert!(thinned.windows(2).all(|w| w[1].home[0] > w[0].home[0]));
+    }
+
+    #[test]
+    fn thin_aux_never_grows() {
+        let data = vec![ParticleAux { home: [1.0; 4] }; 10];
+        assert_eq!(thin_aux(&data, 50).len(), 10);
+        assert!(thin_aux(&data, 0).is_empty());
+    }
+
+    #[test]
+    fn layer_grid_covers_every_slot() {
+        for (max, aspect) in [(1, 1.0), (1000, 16.0 / 9.0), (50_000, 1.0), (7, 0.5)] {
+            let (cols, rows) = layer_grid(max, aspect);
+            assert!(cols * rows >= max, "{max} @ {aspect}: {cols}x{rows}");
+            assert!(
+                cols * (rows - 1) < max,
+                "{max} @ {aspect}: a whole spare row"
+            );
+        }
+    }
+
+    #[test]
+    fn layer_grid_follows_aspect() {
+        let (cols, rows) = layer_grid(16 * 9 * 100, 16.0 / 9.0);
+        assert_eq!((cols, rows), (160, 90));
+    }
+
+    #[test]
+    fn layer_sample_shader_validates() {
+        let module = wgpu::naga::front::wgsl::parse_str(LAYER_SAMPLE_SHADER).expect("parse");
+        wgpu::naga::valid::Validator::new(
+            wgpu::naga::valid::ValidationFlags::all(),
+            wgpu::naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .expect("validate");
+    }
+
     #[test]
     fn sample_rgba_buffer_empty() {
         let def = ImageSampleDef {
@@ -1,9 +1,13 @@
+pub mod burst;
 pub mod compute_raster;
 pub mod emitter;
 pub mod flow_field;
+pub mod forces;
+pub mod governor;
 pub mod image_source;
 pub mod morph;
 pub mod obstacle;
+pub mod param_inputs;
 pub mod source_loader;
 pub mod spatial_hash;
 pub mod splat;
@@ -14,6 +18,7 @@ pub mod symbiosis;
 pub mod system;
 pub mod text_source;
 pub mod types;
+pub mod vector_source;
 
 pub use source_loader::{
     ParticleSourceLoader, ParticleSourceResult, builtin_raster_images, builtin_raster_path,
@@ -1,4 +1,11 @@
-use wgpu::{Device, Queue};
+use wgpu::{CommandEncoder, Device, Queue};
+
+use crate::gpu::fullscreen_quad::FULLSCREEN_TRIANGLE_VS_WITH_UV;
+use crate::gpu::render_target::RenderTarget;
+
+/// Size of the obstacle map a live layer is drawn into (#2160).
+pub const LAYER_OBSTACLE_WIDTH: u32 = 480;
+pub const LAYER_OBSTACLE_HEIGHT: u32 = 270;
 
 /// 2D obstacle texture for particle collision.
 /// Stores alpha-channel shape data. Particles test alpha against a threshold
@@ -130,6 +137,47 @@ impl ObstacleTexture {
         }
     }
 
+    /// Render-target obstacle map that a layer's output is drawn into every
+    /// frame (#2160). Fixed size: the map is fitted with `ObstacleFit::Stretch`
+    /// so it lines up with the layer whatever the viewport, and collision only
+    /// needs coarse shapes. Keeps `COPY_DST` so a later webcam/depth frame of
+    /// the same size can still `update` it in place.
+    pub fn layer_target(device: &Device) -> Self {
+        let (width, height) = (LAYER_OBSTACLE_WIDTH, LAYER_OBSTACLE_HEIGHT);
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("obstacle-layer-texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("obstacle-layer-sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        Self {
+            texture,
+            view,
+            sampler,
+            width,
+            height,
+        }
+    }
+
     /// Update texture data in-place (for webcam per-frame updates).
     /// If dimensions match, reuses existing texture. Otherwise recreates.
     pub fn update(&mut self, device: &Device, queue: &Queue, data: &[u8], w: u32, h: u32) {
@@ -162,6 +210,121 @@ impl ObstacleTexture {
     }
 }
 
+/// Draws a layer's output into a [`ObstacleTexture::layer_target`] with
+/// luminance in alpha (#2160), so bright areas of the layer become the surface
+/// particles collide with. Source is the layer's previous frame, one frame
+/// behind like the live-layer emitter.
+pub struct LayerObstacle {
+    pipeline: wgpu::RenderPipeline,
+    bgl: wgpu::BindGroupLayout,
+}
+
+impl LayerObstacle {
+    pub fn new(device: &Device) -> Self {
+        let bgl = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("obstacle-layer-bgl"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let fs = include_str!("../../../../../assets/shaders/builtin/obstacle_luma.wgsl");
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("obstacle-layer"),
+            source: wgpu::ShaderSource::Wgsl(
+                format!("{FULLSCREEN_TRIANGLE_VS_WITH_UV}\n{fs}").into(),
+            ),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("obstacle-layer-layout"),
+            bind_group_layouts: &[&bgl],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("obstacle-layer-pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        Self { pipeline, bgl }
+    }
+
+    /// Record the layer → obstacle pass into `encoder`.
+    pub fn render(
+        &self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        source: &RenderTarget,
+        target: &ObstacleTexture,
+    ) {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("obstacle-layer-bg"),
+            layout: &self.bgl,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&source.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&source.sampler),
+                },
+            ],
+        });
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("obstacle-layer"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.draw(0..3, 0..1);
+    }
+}
+
 /// If the image has no meaningful alpha (all pixels ≥ 250), replace alpha
 /// with luminance so opaque images (JPEG, opaque PNG) work as obstacles
 /// based on their brightness.
@@ -0,0 +1,145 @@
+//! Particle constants driven by effect inputs (#2164).
+//!
+//! A `.pfx` particle block's `params` map names a particle setting and the
+//! float input that drives it: `{ "initial_speed": "speed" }`. The input shows
+//! in the param panel and takes MIDI/OSC/audio bindings like any other; each
+//! frame its live value replaces the `.pfx` constant in the sim uniforms.
+
+use std::collections::BTreeMap;
+
+use super::types::ParticleUniforms;
+use crate::params::ParamDef;
+
+/// Particle settings a `params` entry can drive.
+pub const BINDABLE: &[&str] = &[
+    "lifetime",
+    "initial_speed",
+    "initial_size",
+    "size_end",
+    "drag",
+    "turbulence",
+    "attraction_strength",
+    "emitter_radius",
+    "emitter_angle",
+    "emitter_spread",
+    "speed_variance",
+    "life_variance",
+    "size_variance",
+    "flow_strength",
+];
+
+fn field<'a>(u: &'a mut ParticleUniforms, name: &str) -> Option<&'a mut f32> {
+    Some(match name {
+        "lifetime" => &mut u.lifetime,
+        "initial_speed" => &mut u.initial_speed,
+        "initial_size" => &mut u.initial_size,
+        "size_end" => &mut u.size_end,
+        "drag" => &mut u.drag,
+        "turbulence" => &mut u.turbulence,
+        "attraction_strength" => &mut u.attraction_strength,
+        "emitter_radius" => &mut u.emitter_radius,
+        "emitter_angle" => &mut u.emitter_angle,
+        "emitter_spread" => &mut u.emitter_spread,
+        "speed_variance" => &mut u.speed_variance,
+        "life_variance" => &mut u.life_variance,
+        "size_variance" => &mut u.size_variance,
+        "flow_strength" => &mut u.flow_strength,
+        _ => return None,
+    })
+}
+
+/// Overwrite each bound setting in `u` with its input's live value. `param`
+/// looks up a named float input; an unknown input leaves the `.pfx` value.
+///
+/// Must run after `ParticleSystem::update_uniforms`, which rewrites these from
+/// the def each frame, and before `forces::pack_forces`, which scales `drag`.
+pub fn apply_param_inputs(
+    params: &BTreeMap<String, String>,
+    u: &mut ParticleUniforms,
+    param: impl Fn(&str) -> Option<f32>,
+) {
+    for (setting, input) in params {
+        if let (Some(slot), Some(v)) = (field(u, setting), param(input)) {
+            *slot = v;
+        }
+    }
+}
+
+/// Load-time problems with a `params` map: unknown settings and names that
+/// aren't float inputs of the effect.
+pub fn binding_problems(params: &BTreeMap<String, String>, inputs: &[ParamDef]) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (setting, input) in params {
+        if !BINDABLE.contains(&setting.as_str()) {
+            problems.push(format!("\"{setting}\" is not a bindable particle setting"));
+        }
+        let is_float_input = inputs
+            .iter()
+            .any(|d| matches!(d, ParamDef::Float { name, .. } if name == input));
+        if !is_float_input {
+            problems.push(format!(
+                "\"{setting}\" is bound to \"{input}\", which is not a float input"
+            ));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn every_bindable_setting_has_a_uniform() {
+        let mut u: ParticleUniforms = bytemuck::Zeroable::zeroed();
+        for name in BINDABLE {
+            assert!(field(&mut u, name).is_some(), "{name}");
+        }
+    }
+
+    #[test]
+    fn bound_inputs_replace_pfx_values() {
+        let mut u: ParticleUniforms = bytemuck::Zeroable::zeroed();
+        u.initial_speed = 0.5;
+        u.lifetime = 3.0;
+        u.drag = 0.99;
+        let params = map(&[("initial_speed", "speed"), ("lifetime", "missing")]);
+        apply_param_inputs(&params, &mut u, |name| (name == "speed").then_some(1.25));
+        assert_eq!(u.initial_speed, 1.25);
+        assert_eq!(u.lifetime, 3.0);
+        assert_eq!(u.drag, 0.99);
+    }
+
+    #[test]
+    fn problems_name_bad_settings_and_inputs() {
+        let inputs = vec![
+            ParamDef::Float {
+                name: "speed".into(),
+                default: 0.5,
+                min: 0.0,
+                max: 2.0,
+                group: None,
+                description: None,
+                unit: None,
+            },
+            ParamDef::Bool {
+                name: "on".into(),
+                default: true,
+                group: None,
+                description: None,
+            },
+        ];
+        assert!(binding_problems(&map(&[("initial_speed", "speed")]), &inputs).is_empty());
+        let problems = binding_problems(&map(&[("warp", "speed"), ("drag", "on")]), &inputs);
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("\"drag\""));
+        assert!(problems[1].contains("\"warp\""));
+    }
+}
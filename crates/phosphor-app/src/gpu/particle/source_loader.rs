@@ -106,6 +106,13 @@ impl ParticleSourceLoader {
             .expect("failed to spawn particle source loader thread");
     }
 
+    /// Start rendering `text` as the particle source (#2161). Runs through the
+    /// image path as a `text:` pseudo-path, which is also what gets saved.
+    pub fn load_text(&mut self, text: &str) {
+        self.load_image(format!("{}{text}", super::vector_source::TEXT_PREFIX).into());
+        self.loading_name = format!("\"{text}\"");
+    }
+
     /// Start loading a video file in the background.
     #[cfg(feature = "video")]
     #[allow(dead_code)]
@@ -146,7 +153,7 @@ impl ParticleSourceLoader {
             .spawn(move || {
                 let dialog = rfd::FileDialog::new()
                     .set_title("Load Image for Particle Source")
-                    .add_filter("Images", &["png", "jpg", "jpeg", "webp", "gif"]);
+                    .add_filter("Images", &["png", "jpg", "jpeg", "webp", "gif", "svg"]);
                 if let Some(path) = dialog.pick_file() {
                     let result = load_image_sync(&path);
                     let _ = tx.send((load_gen, result));
@@ -207,6 +214,22 @@ impl ParticleSourceLoader {
 
 /// Synchronous image loading (runs on background thread).
 fn load_image_sync(path: &std::path::Path) -> ParticleSourceResult {
+    // Text and SVG logos (#2161) render to a bitmap instead of decoding one
+    if let Some(rendered) = super::vector_source::load(path) {
+        return match rendered {
+            Ok(img) => {
+                let (width, height) = img.dimensions();
+                ParticleSourceResult::Image {
+                    path: path.to_string_lossy().to_string(),
+                    data: img.into_raw(),
+                    width,
+                    height,
+                }
+            }
+            Err(e) => ParticleSourceResult::Error(e),
+        };
+    }
+
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
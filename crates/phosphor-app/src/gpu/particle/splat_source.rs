@@ -186,10 +186,7 @@ pub fn download_demo_scene(name: &str) -> Arc<crate::download::DownloadProgress>
 /// Where downloaded demo scenes live: `~/.config/phosphor/splats/`
 /// (mirrors `depth::model::model_dir`).
 pub fn splat_dir() -> PathBuf {
-    dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("phosphor")
-        .join("splats")
+    crate::paths::config_dir().join("splats")
 }
 
 /// Map a .pfx `splat.source` string to a filesystem path (no existence check):
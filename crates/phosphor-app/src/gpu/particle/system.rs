@@ -8,16 +8,19 @@ use wgpu::{
 
 use super::compute_raster::ComputeRasterizer;
 use super::flow_field::FlowFieldTexture;
-use super::obstacle::ObstacleTexture;
+use super::obstacle::{LayerObstacle, ObstacleTexture};
 use super::spatial_hash::SpatialHashGrid;
 use super::splat::{SplatDriver, SplatShRec, SplatStatic};
 use super::splat_source::SplatCloud;
 use super::sprite::SpriteAtlas;
 use super::types::{
-    ImageSampleDef, ParticleAux, ParticleDef, ParticleImageSource, ParticleRenderUniforms,
-    ParticleUniforms, RDUniforms, SourceTransition, TrailFieldUniforms,
+    CarriedSource, ImageSampleDef, ParticleAux, ParticleDef, ParticleImageSource,
+    ParticleRenderUniforms, ParticleUniforms, RDUniforms, SourceTransition, TrailFieldUniforms,
 };
+use crate::gpu::debug_view::DebugTexture;
 use crate::gpu::lattice::{LatticeParams, LatticeSim, LatticeUniforms, lattice_step_budget};
+use crate::gpu::render_target::RenderTarget;
+use crate::gpu::resources::{ResourceCounts, Subsystem, Tracked};
 use crate::gpu::volumetric::{VolumetricParams, VolumetricRenderer, VolumetricUniforms};
 
 const WORKGROUP_SIZE: u32 = 256;
@@ -26,6 +29,11 @@ const WORKGROUP_SIZE: u32 = 256;
 /// alive/dead index lists, and indirect draw for GPU-driven rendering.
 pub struct ParticleSystem {
     pub max_particles: u32,
+    /// Live particle budget (#2156), at most `max_particles`: the sim only walks
+    /// the first `active_particles` slots, so lowering it needs no reallocation.
+    pub particle_budget: u32,
+    /// `particle_budget` after the quality governor's scale — this frame's count.
+    active_particles: u32,
     pub uniforms: ParticleUniforms,
     pub render_uniforms: ParticleRenderUniforms,
     pub alive_count: u32,
@@ -53,6 +61,8 @@ pub struct ParticleSystem {
     // Auxiliary data buffer (home positions, packed colors for image decomposition)
     aux_buffer: wgpu::Buffer,
     pub has_aux_data: bool,
+    /// GPU sampler for `ParticleImageSource::Layer`, created on first use.
+    layer_sampler: Option<super::image_source::LayerSampler>,
 
     // Uniform buffers
     uniform_buffer: wgpu::Buffer,
@@ -97,6 +107,10 @@ pub struct ParticleSystem {
     pub obstacle_source: String,
     /// Path to obstacle image/video file (for preset save/load)
     pub obstacle_image_path: Option<String>,
+    /// Layer whose brightness is the obstacle when `obstacle_source` is
+    /// "layer" (#2160), plus the pass that draws it in (built on first use).
+    pub obstacle_layer: Option<usize>,
+    layer_obstacle: Option<LayerObstacle>,
 
     // Obstacle video playback
     obstacle_video_frames: Vec<crate::media::types::DecodedFrame>,
@@ -111,6 +125,7 @@ pub struct ParticleSystem {
     trail_buffer: Option<wgpu::Buffer>,
     trail_length: u32,
     trail_width: f32,
+    trail_fade: f32,
     frame_index: u32,
     trail_render_pipeline: Option<RenderPipeline>,
     trail_render_bgl: Option<BindGroupLayout>,
@@ -136,7 +151,6 @@ pub struct ParticleSystem {
 
     // Reaction-diffusion (R-D compute on own pipeline; particle sampling via group 4)
     rd_textures: Option<[wgpu::Texture; 2]>,
-    #[allow(dead_code)]
     rd_views: Option<[wgpu::TextureView; 2]>,
     #[allow(dead_code)]
     rd_sampler: Option<wgpu::Sampler>,
@@ -281,6 +295,8 @@ pub struct ParticleSystem {
 
     // Emission accumulator (fractional particles per frame)
     emit_accumulator: f32,
+    /// Triggered bursts waiting to emit (#2162).
+    burst_queue: super::burst::BurstQueue,
     pub emit_rate: f32,
     pub burst_on_beat: u32,
     pub def: ParticleDef,
@@ -297,6 +313,8 @@ pub struct ParticleSystem {
     pub static_image_path: Option<String>,
     /// Cached aux data for the current static source (used as transition "from").
     pub current_aux: Vec<ParticleAux>,
+    /// What building this system created, credited to Particles (#2226).
+    _tracked: Option<Tracked>,
 }
 
 impl ParticleSystem {
@@ -418,18 +436,20 @@ impl ParticleSystem {
             mapped_at_creation: false,
         });
 
-        // Alive index buffers (ping-pong): max_particles * 4 bytes each
+        // Alive index buffers (ping-pong), padded to the depth sort's power of two
+        // so its tail swaps stay in bounds for any count (#2163)
         let alive_index_size = max_particles as u64 * 4;
+        let sortable_index_size = next_power_of_2(max_particles) as u64 * 4;
         let alive_index_buffers = [
             device.create_buffer(&wgpu::BufferDescriptor {
                 label: Some("alive-indices-a"),
-                size: alive_index_size,
+                size: sortable_index_size,
                 usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
                 mapped_at_creation: false,
             }),
             device.create_buffer(&wgpu::BufferDescriptor {
                 label: Some("alive-indices-b"),
-                size: alive_index_size,
+                size: sortable_index_size,
                 usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
                 mapped_at_creation: false,
             }),
@@ -980,24 +1000,7 @@ impl ParticleSystem {
         );
 
         // --- Depth sort (optional) ---
-        // Auto-disable above 65K: bitonic sort is O(n log²n) dispatches — at 1M (2^20)
-        // that's 210 dispatches/frame which is too expensive.
-        const MAX_SORT_PARTICLES: u32 = 65536;
-        let depth_sort_enabled = if def.depth_sort && max_particles > MAX_SORT_PARTICLES {
-            log::warn!(
-                "Depth sort auto-disabled: {} particles exceeds {} limit (would need {} dispatches/frame)",
-                max_particles,
-                MAX_SORT_PARTICLES,
-                {
-                    let n = next_power_of_2(max_particles);
-                    let log_n = (n as f32).log2() as u32;
-                    log_n * (log_n + 1) / 2
-                },
-            );
-            false
-        } else {
-            def.depth_sort
-        };
+        let depth_sort_enabled = def.depth_sort && depth_sort_fits(max_particles);
         let (
             sort_key_buffer,
             sort_params_buffer,
@@ -1199,6 +1202,8 @@ impl ParticleSystem {
 
         Self {
             max_particles,
+            particle_budget: max_particles,
+            active_particles: max_particles,
             uniforms: bytemuck::Zeroable::zeroed(),
             render_uniforms: bytemuck::Zeroable::zeroed(),
             alive_count: 0,
@@ -1213,6 +1218,7 @@ impl ParticleSystem {
             indirect_args_buffer,
             aux_buffer,
             has_aux_data: false,
+            layer_sampler: None,
             uniform_buffer,
             render_uniform_buffer,
             compute_pipeline,
@@ -1239,6 +1245,8 @@ impl ParticleSystem {
             obstacle_elasticity: 0.7,
             obstacle_source: String::new(),
             obstacle_image_path: None,
+            obstacle_layer: None,
+            layer_obstacle: None,
             obstacle_video_frames: Vec::new(),
             obstacle_video_delays_ms: Vec::new(),
             obstacle_video_frame: 0,
@@ -1249,6 +1257,7 @@ impl ParticleSystem {
             trail_buffer: None,
             trail_length: 0,
             trail_width: 0.005,
+            trail_fade: def.trail_fade,
             frame_index: 0,
             trail_render_pipeline: None,
             trail_render_bgl: None,
@@ -1361,6 +1370,7 @@ impl ParticleSystem {
                 None
             },
             emit_accumulator: 0.0,
+            burst_queue: Default::default(),
             emit_rate: def.emit_rate,
             burst_on_beat: def.burst_on_beat,
             def: def.clone(),
@@ -1375,9 +1385,16 @@ impl ParticleSystem {
             video_path: None,
             static_image_path: None,
             current_aux: Vec::new(),
+            _tracked: None,
         }
     }
 
+    /// Credit what the device gained since `before` — this system with its
+    /// sprite, trail and morph resources — to Particles.
+    pub fn track_resources(&mut self, device: &Device, before: ResourceCounts) {
+        self._tracked = Some(Tracked::since(Subsystem::Particles, device, before));
+    }
+
     /// Build the list of bind group layouts for compute pipeline creation.
     /// Groups: 0=core, 1=flow field, 2=trails OR splat static (mutually
     /// exclusive, #1800), 3=spatial hash, 4=R-D (if enabled).
@@ -1459,22 +1476,145 @@ impl ParticleSystem {
         self.compute_pipeline = pipeline;
     }
 
+    /// Whether the slot count is pinned to the buffers (#2156). Splat scenes and
+    /// morph targets lay their data out per slot at `max_particles`, so trimming
+    /// the walked range would cut off part of the scene rather than thin it.
+    pub fn budget_locked(&self) -> bool {
+        self.def.splat.is_some() || self.morph_state.is_some()
+    }
+
+    /// Particles simulated this frame: the budget after the governor's scale.
+    pub fn active_particles(&self) -> u32 {
+        self.active_particles
+    }
+
+    /// Set the live budget (#2156), clamped to the allocated buffers. Takes
+    /// effect at the next `update_active_count`, i.e. between frames.
+    pub fn set_particle_budget(&mut self, count: u32) {
+        self.particle_budget = count.clamp(1, self.max_particles.max(1));
+    }
+
+    /// Apply the budget × governor `scale` before this frame's sim. Slots that
+    /// come back into range are cleared so stale particles don't resume
+    /// mid-life, and image sources re-upload so the picture is thinned rather
+    /// than cropped. No-op when the count is unchanged.
+    pub fn update_active_count(&mut self, device: &Device, queue: &Queue, scale: f32) {
+        let target = if self.budget_locked() {
+            self.max_particles
+        } else {
+            ((self.particle_budget as f32 * scale).round() as u32)
+                .clamp(1, self.max_particles.max(1))
+        };
+        if target == self.active_particles {
+            return;
+        }
+        let previous = self.active_particles;
+        self.active_particles = target;
+
+        if target > previous {
+            let stride = super::types::PARTICLE_COMPONENT_STRIDE;
+            let offset = previous as u64 * stride;
+            let size = (target - previous) as u64 * stride;
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("particle-budget-clear"),
+            });
+            for buf in &self.pos_life_buffers {
+                encoder.clear_buffer(buf, offset, Some(size));
+            }
+            queue.submit(std::iter::once(encoder.finish()));
+        }
+
+        // The layer sampler regrids on the GPU every frame; everything else
+        // re-uploads the full-resolution sample through the thinning path.
+        if self.image_source.layer_index().is_none() && !self.current_aux.is_empty() {
+            self.update_aux_in_place(queue, &self.current_aux);
+        }
+    }
+
+    /// `data` thinned to the live budget when it holds more records than the
+    /// sim walks (one per slot); otherwise `data` as-is.
+    fn budgeted_aux<'a>(&self, data: &'a [ParticleAux]) -> std::borrow::Cow<'a, [ParticleAux]> {
+        let active = self.active_particles as usize;
+        if self.budget_locked() || data.len() <= active {
+            std::borrow::Cow::Borrowed(data)
+        } else {
+            std::borrow::Cow::Owned(super::image_source::thin_aux(data, active))
+        }
+    }
+
+    /// Turn the alpha depth sort on or off (#2163), building its passes on
+    /// first use. The request sticks in the def, but above the sort's particle
+    /// cap it stays off; returns whether the sort is now running.
+    pub fn set_depth_sort(&mut self, device: &Device, enabled: bool) -> bool {
+        self.def.depth_sort = enabled;
+        let enabled = enabled && depth_sort_fits(self.max_particles);
+        if !enabled {
+            self.sort_pipeline = None;
+            self.sort_bind_groups = None;
+            self.sort_keygen_pipeline = None;
+            self.sort_keygen_bind_groups = None;
+            self.sort_params_buffer = None;
+            self.sort_key_buffer = None;
+            self.sort_passes.clear();
+            self.sort_n = 0;
+        } else if self.sort_pipeline.is_none() {
+            (
+                self.sort_key_buffer,
+                self.sort_params_buffer,
+                self.sort_keygen_pipeline,
+                self.sort_keygen_bind_groups,
+                self.sort_pipeline,
+                self.sort_bind_groups,
+                self.sort_passes,
+                self.sort_n,
+            ) = create_sort_resources(
+                device,
+                self.max_particles,
+                &self.counter_buffer,
+                &self.vel_size_buffers,
+                &self.alive_index_buffers,
+            );
+        }
+        enabled
+    }
+
+    /// Whether alive particles are drawn back-to-front this frame.
+    pub fn depth_sort_active(&self) -> bool {
+        self.sort_pipeline.is_some()
+    }
+
+    /// Whether this system is small enough to depth sort at all.
+    pub fn depth_sort_supported(&self) -> bool {
+        self.max_particles <= MAX_SORT_PARTICLES
+    }
+
+    /// Queue the def's trigger burst, held for the next beat when quantized.
+    pub fn queue_burst(&mut self) {
+        self.burst_queue
+            .push(self.def.trigger_burst, self.def.trigger_burst_quantize);
+    }
+
     /// Update uniforms from app state. Call before dispatch().
     pub fn update_uniforms(&mut self, dt: f32, time: f32, resolution: [f32; 2], beat: f32) {
-        // Accumulate emissions
-        self.emit_accumulator += self.emit_rate * dt;
+        // Accumulate emissions — scaled with the live budget so a trimmed
+        // system keeps the same turnover instead of saturating its slots
+        let budget_fraction = self.active_particles as f32 / self.max_particles.max(1) as f32;
+        self.emit_accumulator += self.emit_rate * budget_fraction * dt;
 
         // Beat burst — use dedicated beat trigger instead of onset threshold
         if beat > 0.5 && self.burst_on_beat > 0 {
-            self.emit_accumulator += self.burst_on_beat as f32;
+            self.emit_accumulator += self.burst_on_beat as f32 * budget_fraction;
         }
+        // Triggered bursts (#2162), quantized ones landing on this beat
+        let queued = self.burst_queue.drain(beat > 0.5, dt);
+        self.emit_accumulator += queued as f32 * budget_fraction;
 
         let emit_count = self.emit_accumulator as u32;
         self.emit_accumulator -= emit_count as f32;
 
         self.uniforms.delta_time = dt;
         self.uniforms.time = time;
-        self.uniforms.max_particles = self.max_particles;
+        self.uniforms.max_particles = self.active_particles;
         self.uniforms.emit_count = emit_count;
 
         // Track previous emitter position for velocity inheritance
@@ -1572,6 +1712,7 @@ impl ParticleSystem {
         self.render_uniforms.frame_index = self.frame_index;
         self.render_uniforms.trail_length = self.trail_length;
         self.render_uniforms.trail_width = self.trail_width;
+        self.render_uniforms.trail_fade = self.trail_fade;
         // Same counter into the compute uniforms: trail_write's ring slot and
         // the trail renderer's head must agree for the same frame.
         self.uniforms.frame_index = self.frame_index;
@@ -1633,7 +1774,7 @@ impl ParticleSystem {
         // Upload uniforms
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&self.uniforms));
 
-        let workgroups = self.max_particles.div_ceil(WORKGROUP_SIZE);
+        let workgroups = self.active_particles.div_ceil(WORKGROUP_SIZE);
 
         // 0a. Reaction-diffusion compute (if present) — step R-D before particles
         if let (Some(rd_pipeline), Some(rd_bgs), Some(rd_ubuf)) = (
@@ -1956,6 +2097,46 @@ impl ParticleSystem {
         self.counter_map_pending.store(false, Ordering::Release);
     }
 
+    /// The 2D aux textures worth inspecting in the debug view (#2229).
+    pub fn debug_textures(&self) -> Vec<DebugTexture<'_>> {
+        let mut out = Vec::new();
+        if self.obstacle_enabled {
+            out.push(DebugTexture::of_texture(
+                "particles: obstacle map",
+                &self.obstacle.texture,
+                &self.obstacle.view,
+            ));
+        }
+        if let Some(ref sprite) = self.sprite {
+            out.push(DebugTexture::of_texture(
+                "particles: sprite atlas",
+                &sprite.texture,
+                &sprite.view,
+            ));
+        }
+        if let (Some(textures), Some(views)) = (&self.rd_textures, &self.rd_views) {
+            for (i, (texture, view)) in textures.iter().zip(views).enumerate() {
+                let label = format!("particles: reaction-diffusion {}", ["A", "B"][i]);
+                out.push(DebugTexture::of_texture(label, texture, view));
+            }
+        }
+        if let (Some(texture), Some(view)) = (&self.wboit_accum_texture, &self.wboit_accum_view) {
+            out.push(DebugTexture::of_texture(
+                "particles: WBOIT accumulation",
+                texture,
+                view,
+            ));
+        }
+        if let (Some(texture), Some(view)) = (&self.wboit_reveal_texture, &self.wboit_reveal_view) {
+            out.push(DebugTexture::of_texture(
+                "particles: WBOIT revealage",
+                texture,
+                view,
+            ));
+        }
+        out
+    }
+
     /// Render particles into the given target using indirect draw.
     pub fn render(&self, encoder: &mut CommandEncoder, queue: &Queue, target: &TextureView) {
         // Lattice / Volumetric path: ray march the density volume (built in
@@ -2161,7 +2342,11 @@ impl ParticleSystem {
             mapped_at_creation: false,
         });
         if !data.is_empty() {
-            queue.write_buffer(&self.aux_buffer, 0, bytemuck::cast_slice(data));
+            queue.write_buffer(
+                &self.aux_buffer,
+                0,
+                bytemuck::cast_slice(&self.budgeted_aux(data)),
+            );
         }
         self.has_aux_data = !data.is_empty();
 
@@ -2188,9 +2373,10 @@ impl ParticleSystem {
         if data.is_empty() {
             return;
         }
-        let byte_len = std::mem::size_of_val(data) as u64;
+        let data = self.budgeted_aux(data);
+        let byte_len = std::mem::size_of_val(data.as_ref()) as u64;
         if byte_len <= self.aux_buffer.size() {
-            queue.write_buffer(&self.aux_buffer, 0, bytemuck::cast_slice(data));
+            queue.write_buffer(&self.aux_buffer, 0, bytemuck::cast_slice(&data));
         } else {
             log::warn!(
                 "Aux buffer too small: need {} bytes, have {} bytes ({} vs {} particles)",
@@ -2379,6 +2565,111 @@ impl ParticleSystem {
         self.image_source = ParticleImageSource::Webcam { width, height };
     }
 
+    /// Emit from another layer's live output (#2155). Aux is rewritten on the GPU
+    /// every frame by `sample_layer`, so there is no CPU-side transition; the
+    /// static image path is kept so switching back restores it.
+    pub fn set_layer_source(&mut self, device: &Device, index: usize) {
+        if self.layer_sampler.is_none() {
+            self.layer_sampler = Some(super::image_source::LayerSampler::new(device));
+        }
+        self.source_transition = None;
+        self.has_aux_data = true;
+        self.video_path = None;
+        self.image_source = ParticleImageSource::Layer { index };
+    }
+
+    /// Give up the emission source so a rebuilt system can `restore_source` it.
+    pub fn take_source(&mut self) -> CarriedSource {
+        CarriedSource {
+            image_source: std::mem::replace(&mut self.image_source, ParticleImageSource::Static),
+            video_path: self.video_path.take(),
+            static_image_path: self.static_image_path.take(),
+            emitter_image: std::mem::take(&mut self.def.emitter.image),
+            sample_def: self.sample_def.clone(),
+        }
+    }
+
+    /// Emit from a source taken off the system this one replaces, re-sampled
+    /// at this system's capacity so the extra particles get homes too.
+    pub fn restore_source(&mut self, device: &Device, queue: &Queue, carried: CarriedSource) {
+        self.sample_def = carried.sample_def;
+        self.def.emitter.image = carried.emitter_image;
+        if let Some(path) = carried.static_image_path {
+            match super::image_source::sample_image(
+                std::path::Path::new(&path),
+                &self.sample_def,
+                self.max_particles,
+            ) {
+                Ok(aux) => {
+                    self.upload_aux_data(device, queue, &aux);
+                    self.store_current_aux(aux);
+                }
+                Err(e) => log::warn!("Failed to re-sample particle image '{path}': {e}"),
+            }
+            self.static_image_path = Some(path);
+        }
+        self.video_path = carried.video_path;
+        match carried.image_source {
+            ParticleImageSource::Static => {}
+            #[cfg(feature = "video")]
+            video @ ParticleImageSource::Video { .. } => {
+                if let Some(frame) = video.current_frame_data() {
+                    let aux = super::image_source::sample_rgba_buffer(
+                        &frame.data,
+                        frame.width,
+                        frame.height,
+                        &self.sample_def,
+                        self.max_particles,
+                    );
+                    self.upload_aux_data(device, queue, &aux);
+                    self.store_current_aux(aux);
+                }
+                self.image_source = video;
+            }
+            #[cfg(feature = "webcam")]
+            webcam @ ParticleImageSource::Webcam { .. } => {
+                self.has_aux_data = true;
+                self.image_source = webcam;
+            }
+            ParticleImageSource::Layer { index } => self.set_layer_source(device, index),
+        }
+    }
+
+    /// Follow the emitting layer through a reorder or removal of the layer
+    /// stack. `remap` gives a layer's new index, or `None` if it was removed,
+    /// in which case emission stops tracking it and holds its last frame.
+    pub fn remap_layer_source(&mut self, remap: impl Fn(usize) -> Option<usize>) {
+        if let ParticleImageSource::Layer { index } = self.image_source {
+            self.image_source = match remap(index) {
+                Some(index) => ParticleImageSource::Layer { index },
+                None => ParticleImageSource::Static,
+            };
+        }
+    }
+
+    /// Refresh aux from `source` (the emitting layer's last output).
+    pub fn sample_layer(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        source: &RenderTarget,
+        time: f32,
+    ) {
+        if let Some(sampler) = &self.layer_sampler {
+            sampler.dispatch(
+                device,
+                queue,
+                encoder,
+                source,
+                &self.aux_buffer,
+                &self.sample_def,
+                self.active_particles,
+                time,
+            );
+        }
+    }
+
     /// Update aux data from a webcam frame. Called per-frame from the webcam drain loop.
     #[allow(dead_code)]
     pub fn update_webcam_frame(&mut self, queue: &Queue, data: &[u8], width: u32, height: u32) {
@@ -2580,9 +2871,60 @@ impl ParticleSystem {
         self.obstacle_enabled = true;
         self.obstacle_source = "image".to_string();
         self.obstacle_image_path = path;
+        self.obstacle_layer = None;
+        self.rebuild_flow_field_bind_group(device);
+    }
+
+    /// Collide with the bright areas of another layer (#2160). The layer's
+    /// output is redrawn into the obstacle map every frame by
+    /// `sample_obstacle_layer`; the fit switches to Stretch so the map sits
+    /// exactly on the layer it came from.
+    pub fn set_obstacle_layer(&mut self, device: &Device, index: usize) {
+        if self.layer_obstacle.is_none() {
+            self.layer_obstacle = Some(LayerObstacle::new(device));
+        }
+        self.obstacle = ObstacleTexture::layer_target(device);
+        self.obstacle_enabled = true;
+        self.obstacle_source = "layer".to_string();
+        self.obstacle_image_path = None;
+        self.obstacle_layer = Some(index);
+        self.obstacle_fit = super::types::ObstacleFit::Stretch;
+        self.obstacle_video_frames.clear();
+        self.obstacle_video_delays_ms.clear();
         self.rebuild_flow_field_bind_group(device);
     }
 
+    /// Follow the obstacle layer through a reorder or removal of the layer
+    /// stack, as `remap_layer_source` does. A removed layer turns the
+    /// obstacle off rather than colliding with whatever slid into its place.
+    pub fn remap_obstacle_layer(&mut self, remap: impl Fn(usize) -> Option<usize>) {
+        if let Some(index) = self.obstacle_layer {
+            self.obstacle_layer = remap(index);
+            if self.obstacle_layer.is_none() {
+                self.obstacle_enabled = false;
+                self.obstacle_source.clear();
+            }
+        }
+    }
+
+    /// Layer to draw into the obstacle map this frame, if the obstacle is live.
+    pub fn obstacle_layer_index(&self) -> Option<usize> {
+        self.obstacle_layer
+            .filter(|_| self.obstacle_enabled && self.obstacle_source == "layer")
+    }
+
+    /// Redraw the obstacle map from `source` (the layer's last output).
+    pub fn sample_obstacle_layer(
+        &self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        source: &RenderTarget,
+    ) {
+        if let Some(layer_obstacle) = &self.layer_obstacle {
+            layer_obstacle.render(device, encoder, source, &self.obstacle);
+        }
+    }
+
     /// Update obstacle texture from webcam frame data (per-frame).
     #[allow(dead_code)]
     pub fn update_obstacle_webcam(
@@ -2628,6 +2970,7 @@ impl ParticleSystem {
         self.obstacle_enabled = true;
         self.obstacle_source = "video".to_string();
         self.obstacle_image_path = Some(path);
+        self.obstacle_layer = None;
     }
 
     /// Convert RGBA data so alpha = luminance. Videos from ffmpeg have alpha=1.0
@@ -2690,6 +3033,7 @@ impl ParticleSystem {
         self.obstacle_enabled = false;
         self.obstacle_source.clear();
         self.obstacle_image_path = None;
+        self.obstacle_layer = None;
         self.obstacle_video_frames.clear();
         self.obstacle_video_delays_ms.clear();
         self.obstacle_video_frame = 0;
@@ -2965,6 +3309,33 @@ impl ParticleSystem {
         self.trail_prepare_indirect_bind_group = Some(trail_prepare_bg);
     }
 
+    /// Apply a trail edit from the particle panel or a preset (#2159). Width
+    /// and fade are render uniforms and take effect next frame; a new length
+    /// reallocates the trail ring, so callers send it on slider release. The
+    /// def is updated too, so the edit saves with a user effect. Splat effects
+    /// own bind group 2 and stay trail-free.
+    pub fn set_trails(
+        &mut self,
+        device: &Device,
+        hdr_format: TextureFormat,
+        length: u32,
+        width: f32,
+        fade: f32,
+    ) {
+        self.trail_width = width;
+        self.trail_fade = fade;
+        self.def.trail_width = width;
+        self.def.trail_fade = fade;
+        if self.def.splat.is_some() {
+            return;
+        }
+        let length = if length < 2 { 0 } else { length };
+        if length != self.def.trail_length {
+            self.def.trail_length = length;
+            self.setup_trails(device, hdr_format, length, width);
+        }
+    }
+
     /// Flip ping-pong buffers for next frame.
     pub fn flip(&mut self) {
         self.current = 1 - self.current;
@@ -3820,6 +4191,24 @@ fn cs_main() {{
     (pipeline, bg)
 }
 
+/// Above 65K the bitonic sort is too expensive: O(n log²n) work over
+/// log n (log n + 1) / 2 dispatches — 210 per frame at 1M (2^20).
+const MAX_SORT_PARTICLES: u32 = 65536;
+
+/// Whether a system of `max_particles` can be depth sorted; logs why not.
+fn depth_sort_fits(max_particles: u32) -> bool {
+    if max_particles <= MAX_SORT_PARTICLES {
+        return true;
+    }
+    log::warn!(
+        "Depth sort auto-disabled: {} particles exceeds {} limit (would need {} dispatches/frame)",
+        max_particles,
+        MAX_SORT_PARTICLES,
+        bitonic_sort_passes(next_power_of_2(max_particles)).len(),
+    );
+    false
+}
+
 /// Compute bitonic sort pass parameters: (block_size, sub_block_size) for each step.
 fn bitonic_sort_passes(n: u32) -> Vec<(u32, u32)> {
     let mut passes = Vec::new();
@@ -4985,3 +5374,29 @@ mod trail_binding_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod depth_sort_tests {
+    use super::*;
+
+    #[test]
+    fn sort_pads_to_a_power_of_two() {
+        assert_eq!(next_power_of_2(1), 1);
+        assert_eq!(next_power_of_2(10_000), 16_384);
+        assert_eq!(next_power_of_2(65_536), 65_536);
+    }
+
+    #[test]
+    fn sort_passes_cover_every_merge_step() {
+        // log n (log n + 1) / 2 steps, the last one comparing neighbours.
+        let passes = bitonic_sort_passes(1 << 16);
+        assert_eq!(passes.len(), 16 * 17 / 2);
+        assert_eq!(passes.last(), Some(&(1 << 16, 1)));
+    }
+
+    #[test]
+    fn sort_is_capped_at_65k() {
+        assert!(depth_sort_fits(MAX_SORT_PARTICLES));
+        assert!(!depth_sort_fits(MAX_SORT_PARTICLES + 1));
+    }
+}
@@ -14,17 +14,44 @@ pub fn render_text_to_particles(
         return Vec::new();
     }
 
+    let Some((rgba, bitmap_width, bitmap_height)) = rasterize_text(text, px_size(text)) else {
+        return Vec::new();
+    };
+
+    let sample_def = ImageSampleDef {
+        mode: "grid".to_string(),
+        threshold: 0.1,
+        scale: 1.0,
+    };
+
+    let _ = particle_size; // density handled by image sampler
+    image_source::sample_rgba_buffer(
+        &rgba,
+        bitmap_width,
+        bitmap_height,
+        &sample_def,
+        max_particles,
+    )
+}
+
+/// Glyph size that keeps the rendered string roughly 1024 px wide.
+fn px_size(text: &str) -> f32 {
+    let char_count = text.chars().count().max(1) as f32;
+    (1024.0 / char_count * 0.6).clamp(32.0, 256.0)
+}
+
+/// Rasterize `text` at `px_size` into an RGBA bitmap (white, alpha =
+/// coverage) with a 2 px margin. Returns `(rgba, width, height)`, or `None`
+/// if the embedded font fails to parse.
+pub fn rasterize_text(text: &str, px_size: f32) -> Option<(Vec<u8>, u32, u32)> {
     let font = match fontdue::Font::from_bytes(FONT_DATA, fontdue::FontSettings::default()) {
         Ok(f) => f,
         Err(e) => {
             log::error!("Failed to parse font: {}", e);
-            return Vec::new();
+            return None;
         }
     };
 
-    let char_count = text.chars().count().max(1) as f32;
-    let px_size = (1024.0 / char_count * 0.6).clamp(32.0, 256.0);
-
     // Rasterize each glyph and compute total width
     let mut glyphs: Vec<(fontdue::Metrics, Vec<u8>)> = Vec::new();
     let mut total_width: i32 = 0;
@@ -89,18 +116,5 @@ pub fn render_text_to_particles(
         prev_char = Some(ch);
     }
 
-    let sample_def = ImageSampleDef {
-        mode: "grid".to_string(),
-        threshold: 0.1,
-        scale: 1.0,
-    };
-
-    let _ = particle_size; // density handled by image sampler
-    image_source::sample_rgba_buffer(
-        &rgba,
-        bitmap_width,
-        bitmap_height,
-        &sample_def,
-        max_particles,
-    )
+    Some((rgba, bitmap_width, bitmap_height))
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use bytemuck::{Pod, Zeroable};
 use serde::{Deserialize, Serialize};
 
@@ -21,7 +23,7 @@ pub struct Particle {
     pub flags: [f32; 4],
 }
 
-/// Particle simulation uniforms: 944 bytes.
+/// Particle simulation uniforms: 1088 bytes.
 /// Separate from the main 432-byte ShaderUniforms — the two carry overlapping but not
 /// identical feature sets, and each has its own WGSL mirror that must be kept in step
 /// (see `particle_uniforms_wgsl_layout_matches_rust`).
@@ -213,9 +215,25 @@ pub struct ParticleUniforms {
     /// padding for the vec4 stride. Declared `array<vec4f, 2>` in WGSL — uniform-address-space
     /// arrays need a 16-byte element stride, as `mfcc`/`chroma` already do. Index via `band_pan()`.
     pub band_pan: [f32; 8],
-    // Total = 944 bytes
+    // 944 bytes above
+
+    // Force fields from the `.pfx` `forces` list (#2158), resolved on the CPU each frame
+    // by `forces::pack_forces` so param-scaled strengths follow audio bindings live.
+    // Appended as fresh 16-byte blocks so every existing offset stays stable (#1505 precedent).
+    pub force_count: u32,       // point forces in use, 0..=MAX_POINT_FORCES
+    pub force_vortex_mask: u32, // bit i set = point force i swirls instead of pulling
+    pub force_curl: f32,        // summed strength of `curl` entries
+    pub force_curl_scale: f32,  // curl-noise spatial frequency
+    /// Point forces: xy = position, z = signed strength (negative repels),
+    /// w = falloff radius (0 = unbounded, like `attraction_strength`).
+    pub forces: [[f32; 4]; MAX_POINT_FORCES],
+    // Total = 1088 bytes
 }
 
+/// Point forces (attractors, repulsors, vortices) the uniform block carries.
+/// Entries past this in a `.pfx` `forces` list are ignored.
+pub const MAX_POINT_FORCES: usize = 8;
+
 /// Obstacle collision mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ObstacleMode {
@@ -314,6 +332,8 @@ pub enum ParticleImageSource {
     #[cfg(feature = "webcam")]
     #[allow(dead_code)]
     Webcam { width: u32, height: u32 },
+    /// Another layer's live output — re-sampled on the GPU every frame.
+    Layer { index: usize },
 }
 
 impl ParticleImageSource {
@@ -400,6 +420,14 @@ impl ParticleImageSource {
         false
     }
 
+    /// Index of the layer this source samples, if any.
+    pub fn layer_index(&self) -> Option<usize> {
+        match self {
+            ParticleImageSource::Layer { index } => Some(*index),
+            _ => None,
+        }
+    }
+
     /// Get video playback speed (1.0 if not video).
     pub fn video_speed(&self) -> Option<f32> {
         #[cfg(feature = "video")]
@@ -469,6 +497,16 @@ impl ParticleImageSource {
     }
 }
 
+/// What a particle system emits from, handed to its replacement when a
+/// capacity change rebuilds it (#2156).
+pub struct CarriedSource {
+    pub image_source: ParticleImageSource,
+    pub video_path: Option<String>,
+    pub static_image_path: Option<String>,
+    pub emitter_image: String,
+    pub sample_def: ImageSampleDef,
+}
+
 /// Smooth transition between particle source positions.
 pub struct SourceTransition {
     pub from_aux: Vec<ParticleAux>,
@@ -522,7 +560,9 @@ pub struct ParticleRenderUniforms {
     /// Trail params
     pub trail_length: u32,
     pub trail_width: f32,
-    pub _pad: [f32; 2],
+    /// Alpha falloff exponent along the ribbon, head to tail (#2159)
+    pub trail_fade: f32,
+    pub _pad: f32,
 }
 
 /// Sprite atlas definition for textured particles.
@@ -702,6 +742,52 @@ fn default_far_clip() -> f32 {
     10.0
 }
 
+/// Kind of a `.pfx` `forces` entry (#2158).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForceKind {
+    /// Pulls toward `position`.
+    Attractor,
+    /// Pushes away from `position`.
+    Repulsor,
+    /// Swirls counter-clockwise around `position` (negative strength = clockwise).
+    Vortex,
+    /// Divergence-free curl noise over the whole field.
+    Curl,
+    /// Constant acceleration along `direction`, added to `gravity`.
+    Gravity,
+    /// Extra velocity loss: `strength` is the fraction shed per 1/60 s, on top of `drag`.
+    Drag,
+}
+
+/// One entry of a `.pfx` `forces` list, evaluated by the builtin sim (and any
+/// custom sim that calls `apply_builtin_forces`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForceDef {
+    #[serde(rename = "type")]
+    pub kind: ForceKind,
+    /// Center for attractor / repulsor / vortex
+    #[serde(default)]
+    pub position: [f32; 2],
+    /// Unit direction for gravity
+    #[serde(default = "default_force_direction")]
+    pub direction: [f32; 2],
+    #[serde(default)]
+    pub strength: f32,
+    /// Falloff radius for point forces (0 = unbounded); swirl size for curl,
+    /// in clip-space units (0 = 1/3)
+    #[serde(default)]
+    pub radius: f32,
+    /// Name of a float input whose live value multiplies `strength`, so a
+    /// binding can drive the force. Empty = fixed strength.
+    #[serde(default)]
+    pub param: String,
+}
+
+fn default_force_direction() -> [f32; 2] {
+    [0.0, -1.0]
+}
+
 /// .pfx particle definition (JSON).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ParticleDef {
@@ -736,6 +822,12 @@ pub struct ParticleDef {
     pub emit_rate: f32,
     #[serde(default)]
     pub burst_on_beat: u32,
+    /// Particles the `ParticleBurst` trigger emits (#2162); 0 ignores it.
+    #[serde(default = "default_trigger_burst")]
+    pub trigger_burst: u32,
+    /// Hold a triggered burst until the next beat.
+    #[serde(default)]
+    pub trigger_burst_quantize: bool,
     /// Sprite texture definition (optional)
     #[serde(default)]
     pub sprite: Option<SpriteDef>,
@@ -765,6 +857,10 @@ pub struct ParticleDef {
     /// Trail ribbon width in screen units
     #[serde(default = "default_trail_width")]
     pub trail_width: f32,
+    /// Trail alpha falloff exponent, head to tail: 1 = linear, 2 = quadratic
+    /// (default), higher = shorter-looking streaks
+    #[serde(default = "default_trail_fade")]
+    pub trail_fade: f32,
     /// Enable spatial hash grid for particle-particle interaction
     #[serde(default)]
     pub interaction: bool,
@@ -807,6 +903,14 @@ pub struct ParticleDef {
     /// Noise animation speed
     #[serde(default = "default_noise_speed")]
     pub noise_speed: f32,
+    /// Extra force sources — attractors, repulsors, vortices, curl noise,
+    /// gravity, drag — with optionally param-driven strengths (#2158)
+    #[serde(default)]
+    pub forces: Vec<ForceDef>,
+    /// Settings driven by the effect's float `inputs` (#2164): setting name →
+    /// input name, e.g. `{"initial_speed": "speed"}`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
 
     // --- Phase 2: Emitter enhancements ---
     /// Cone emission direction (radians)
@@ -915,6 +1019,12 @@ fn default_flow_speed() -> f32 {
 fn default_trail_width() -> f32 {
     0.005
 }
+fn default_trail_fade() -> f32 {
+    2.0
+}
+fn default_trigger_burst() -> u32 {
+    1000
+}
 fn default_vortex_radius() -> f32 {
     1.0
 }
@@ -967,9 +1077,10 @@ mod tests {
     }
 
     #[test]
-    fn particle_uniforms_size_944() {
-        // 896 through the Splat block, + 16 (A13 stereo) + 32 (A13b band_pan) for #1801.
-        assert_eq!(std::mem::size_of::<ParticleUniforms>(), 944);
+    fn particle_uniforms_size_1088() {
+        // 896 through the Splat block, + 16 (A13 stereo) + 32 (A13b band_pan) for #1801,
+        // + 16 (force header) + 128 (8 point forces) for #2158.
+        assert_eq!(std::mem::size_of::<ParticleUniforms>(), 1088);
     }
 
     #[test]
@@ -1024,6 +1135,12 @@ mod tests {
         assert_eq!(def.spin_speed, 0.0);
         assert!(!def.depth_sort);
         assert_eq!(def.render_mode, "billboard");
+        // Trails off; the fade default keeps the original quadratic taper (#2159)
+        assert_eq!(def.trail_length, 0);
+        assert!((def.trail_fade - 2.0).abs() < 1e-6);
+        assert!(def.forces.is_empty());
+        assert_eq!(def.trigger_burst, 1000);
+        assert!(!def.trigger_burst_quantize);
     }
 
     #[test]
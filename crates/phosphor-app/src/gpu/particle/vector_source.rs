@@ -0,0 +1,277 @@
+//! Text and SVG logo sources for image emitters (#2161).
+//!
+//! Both resolve to an RGBA bitmap that goes through the regular image sampler,
+//! so they work wherever a raster image does: `.pfx` `emitter.image`, the
+//! Particles panel and presets. A text source travels as a `text:` pseudo-path
+//! (`"text:PHOSPHOR"`), the same spelling morph targets use.
+//!
+//! Text is rasterized small and rebuilt from a signed distance field, so a
+//! short word can fill the frame with clean edges without rasterizing glyphs
+//! hundreds of pixels tall. SVGs are rendered straight from their vectors.
+
+use std::path::Path;
+
+use image::RgbaImage;
+
+use super::text_source;
+
+/// Prefix marking an emitter image that is a text string.
+pub const TEXT_PREFIX: &str = "text:";
+
+/// Glyph size the distance field is built from.
+const SDF_GLYPH_PX: f32 = 64.0;
+/// Width text is rebuilt at from its distance field.
+const TEXT_WIDTH: u32 = 1536;
+/// Upscale ceiling, so a single letter doesn't balloon past what the sampler
+/// needs.
+const MAX_SDF_UPSCALE: u32 = 8;
+/// Longest side an SVG is rendered at.
+const SVG_DIM: u32 = 1024;
+
+/// The text of a `text:` pseudo-path, or `None` for a file path.
+pub fn text_of(path: &str) -> Option<&str> {
+    path.strip_prefix(TEXT_PREFIX)
+}
+
+pub fn is_svg(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
+}
+
+/// Render a text or SVG source. `None` means `path` is neither and should be
+/// decoded as a raster image.
+pub fn load(path: &Path) -> Option<Result<RgbaImage, String>> {
+    if let Some(text) = path.to_str().and_then(text_of) {
+        return Some(render_text(text));
+    }
+    if is_svg(path) {
+        let rendered = std::fs::read(path)
+            .map_err(|e| format!("Failed to read SVG: {e}"))
+            .and_then(|data| render_svg(&data));
+        return Some(rendered);
+    }
+    None
+}
+
+/// White text, alpha = coverage, rebuilt from a 64 px distance field.
+pub fn render_text(text: &str) -> Result<RgbaImage, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Text source is empty".to_string());
+    }
+    let (glyphs, w, h) =
+        text_source::rasterize_text(text, SDF_GLYPH_PX).ok_or("Failed to rasterize text")?;
+    let coverage: Vec<u8> = glyphs.chunks_exact(4).map(|px| px[3]).collect();
+    if !coverage.iter().any(|&c| c >= 128) {
+        return Err(format!("\"{text}\" has no visible glyphs"));
+    }
+    let sdf = signed_distance(&coverage, w, h);
+    let scale = (TEXT_WIDTH / w).clamp(1, MAX_SDF_UPSCALE);
+    Ok(sdf_to_image(&sdf, w, h, scale))
+}
+
+/// Render SVG data with its longest side at `SVG_DIM`, in straight (not
+/// premultiplied) RGBA. SVG `<text>` needs system fonts and is not drawn —
+/// logos should have their lettering converted to paths.
+pub fn render_svg(data: &[u8]) -> Result<RgbaImage, String> {
+    use resvg::{tiny_skia, usvg};
+
+    let tree = usvg::Tree::from_data(data, &usvg::Options::default())
+        .map_err(|e| format!("Failed to parse SVG: {e}"))?;
+    let size = tree.size();
+    let fit = SVG_DIM as f32 / size.width().max(size.height());
+    let w = ((size.width() * fit).round() as u32).max(1);
+    let h = ((size.height() * fit).round() as u32).max(1);
+    let mut pixmap = tiny_skia::Pixmap::new(w, h).ok_or("SVG has no drawable area")?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(fit, fit),
+        &mut pixmap.as_mut(),
+    );
+
+    let mut rgba = pixmap.take();
+    for px in rgba.chunks_exact_mut(4) {
+        let a = px[3] as u16;
+        if a > 0 && a < 255 {
+            for c in &mut px[..3] {
+                *c = ((*c as u16 * 255 + a / 2) / a).min(255) as u8;
+            }
+        }
+    }
+    // Particles take the logo's colours; one drawn in black would emit
+    // invisible particles on a dark stage, so those are drawn white instead.
+    let brightest = rgba
+        .chunks_exact(4)
+        .filter(|px| px[3] >= 10)
+        .map(|px| px[0].max(px[1]).max(px[2]))
+        .max()
+        .unwrap_or(0);
+    if brightest < 32 {
+        for px in rgba.chunks_exact_mut(4) {
+            px[..3].fill(255);
+        }
+    }
+    RgbaImage::from_raw(w, h, rgba).ok_or_else(|| "SVG render size mismatch".to_string())
+}
+
+/// Signed distance in pixels to the shape's edge (negative inside), from a
+/// two-pass chamfer transform of the half-coverage mask.
+fn signed_distance(coverage: &[u8], w: u32, h: u32) -> Vec<f32> {
+    let inside: Vec<bool> = coverage.iter().map(|&c| c >= 128).collect();
+    let to_inside = chamfer(&inside, w, h, true);
+    let to_outside = chamfer(&inside, w, h, false);
+    // The edge lies halfway between the nearest pixels on either side.
+    inside
+        .iter()
+        .zip(to_inside.iter().zip(&to_outside))
+        .map(|(&is_in, (&d_in, &d_out))| if is_in { 0.5 - d_out } else { d_in - 0.5 })
+        .collect()
+}
+
+/// Distance from every pixel to the nearest pixel whose mask equals `target`.
+fn chamfer(mask: &[bool], w: u32, h: u32, target: bool) -> Vec<f32> {
+    const DIAG: f32 = std::f32::consts::SQRT_2;
+    let (w, h) = (w as usize, h as usize);
+    let far = (w + h) as f32;
+    let mut d: Vec<f32> = mask
+        .iter()
+        .map(|&m| if m == target { 0.0 } else { far })
+        .collect();
+
+    for y in 0..h {
+        for x in 0..w {
+            let i = y * w + x;
+            let mut v = d[i];
+            if x > 0 {
+                v = v.min(d[i - 1] + 1.0);
+            }
+            if y > 0 {
+                v = v.min(d[i - w] + 1.0);
+                if x > 0 {
+                    v = v.min(d[i - w - 1] + DIAG);
+                }
+                if x + 1 < w {
+                    v = v.min(d[i - w + 1] + DIAG);
+                }
+            }
+            d[i] = v;
+        }
+    }
+    for y in (0..h).rev() {
+        for x in (0..w).rev() {
+            let i = y * w + x;
+            let mut v = d[i];
+            if x + 1 < w {
+                v = v.min(d[i + 1] + 1.0);
+            }
+            if y + 1 < h {
+                v = v.min(d[i + w] + 1.0);
+                if x + 1 < w {
+                    v = v.min(d[i + w + 1] + DIAG);
+                }
+                if x > 0 {
+                    v = v.min(d[i + w - 1] + DIAG);
+                }
+            }
+            d[i] = v;
+        }
+    }
+    d
+}
+
+/// Rebuild the shape at `scale`× from its distance field: bilinear-filtered
+/// distance, antialiased over one output pixel.
+fn sdf_to_image(sdf: &[f32], w: u32, h: u32, scale: u32) -> RgbaImage {
+    let s = scale as f32;
+    RgbaImage::from_fn(w * scale, h * scale, |ox, oy| {
+        // Output pixel centre in source pixel coordinates
+        let sx = (ox as f32 + 0.5) / s - 0.5;
+        let sy = (oy as f32 + 0.5) / s - 0.5;
+        let d = bilinear(sdf, w, h, sx, sy);
+        let a = ((0.5 - d * s).clamp(0.0, 1.0) * 255.0).round() as u8;
+        image::Rgba([255, 255, 255, a])
+    })
+}
+
+fn bilinear(v: &[f32], w: u32, h: u32, x: f32, y: f32) -> f32 {
+    let x = x.clamp(0.0, (w - 1) as f32);
+    let y = y.clamp(0.0, (h - 1) as f32);
+    let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+    let (x1, y1) = ((x0 + 1).min(w - 1), (y0 + 1).min(h - 1));
+    let (tx, ty) = (x - x0 as f32, y - y0 as f32);
+    let at = |x: u32, y: u32| v[(y * w + x) as usize];
+    let top = at(x0, y0) + (at(x1, y0) - at(x0, y0)) * tx;
+    let bottom = at(x0, y1) + (at(x1, y1) - at(x0, y1)) * tx;
+    top + (bottom - top) * ty
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_prefix_round_trips() {
+        assert_eq!(text_of("text:HELLO"), Some("HELLO"));
+        assert_eq!(text_of("text:AC/DC"), Some("AC/DC"));
+        assert_eq!(text_of("/images/text.png"), None);
+        assert!(is_svg(Path::new("/logos/client.SVG")));
+        assert!(!is_svg(Path::new("/logos/client.png")));
+    }
+
+    #[test]
+    fn distance_is_signed_across_the_edge() {
+        // 8×8 with a filled 4×4 square in the middle.
+        let coverage: Vec<u8> = (0..64)
+            .map(|i| {
+                let (x, y) = (i % 8, i / 8);
+                if (2..6).contains(&x) && (2..6).contains(&y) {
+                    255
+                } else {
+                    0
+                }
+            })
+            .collect();
+        let sdf = signed_distance(&coverage, 8, 8);
+        assert_eq!(sdf[2 * 8 + 2], -0.5);
+        assert_eq!(sdf[2 * 8 + 1], 0.5);
+        assert_eq!(sdf[4 * 8 + 4], -1.5);
+        assert_eq!(sdf[4 * 8], 1.5);
+    }
+
+    #[test]
+    fn text_renders_wide_and_upscaled() {
+        let img = render_text("HI").unwrap();
+        let (w, h) = img.dimensions();
+        assert!(w > h);
+        assert!(w > 64 * 2);
+        assert!(img.pixels().any(|p| p[3] == 255));
+        assert!(img.pixels().any(|p| p[3] == 0));
+    }
+
+    #[test]
+    fn blank_text_is_an_error() {
+        assert!(render_text("   ").is_err());
+        assert!(load(Path::new("text: ")).unwrap().is_err());
+    }
+
+    #[test]
+    fn svg_renders_at_fit_size_with_straight_colour() {
+        let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="100">
+            <rect x="0" y="0" width="100" height="100" fill="#ff0000" fill-opacity="0.5"/>
+        </svg>"##;
+        let img = render_svg(svg).unwrap();
+        assert_eq!(img.dimensions(), (SVG_DIM, SVG_DIM / 2));
+        let p = img.get_pixel(10, 10);
+        assert!(p[0] >= 250 && (120..=135).contains(&p[3]));
+        assert_eq!(img.get_pixel(SVG_DIM - 10, 10)[3], 0);
+    }
+
+    #[test]
+    fn black_logo_is_drawn_white() {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10">
+            <rect width="10" height="10" fill="black"/>
+        </svg>"#;
+        let img = render_svg(svg).unwrap();
+        assert_eq!(img.get_pixel(5, 5).0, [255, 255, 255, 255]);
+    }
+}
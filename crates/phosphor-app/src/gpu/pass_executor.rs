@@ -1,29 +1,45 @@
+use std::sync::Arc;
+
 use wgpu::{CommandEncoder, Device, Queue, Sampler, TextureFormat, TextureView};
 
 use crate::effect::EffectLoader;
-use crate::effect::format::PassDef;
+use crate::effect::format::{LayerSource, PassDef};
 
-use super::ShaderPipeline;
 use super::audio_textures::AudioTextures;
+use super::debug_view::DebugTexture;
 use super::particle::ParticleSystem;
 use super::placeholder::PlaceholderTexture;
 use super::render_target::{PingPongTarget, RenderTarget};
+use super::resources::{ResourceCounts, Subsystem, Tracked};
 use super::uniforms::UniformBuffer;
+use super::{ShaderPipeline, SharedPipelines};
 
 /// One resolved pass-graph input: which pass supplies it, and whether we sample
 /// that pass's *previous* frame (`prev`, from `PassDef.prev_inputs`) or its
 /// *current* frame (`PassDef.inputs`). Current inputs come first in the WGSL
 /// `input0..` numbering, then prev inputs (matching declaration order).
-#[derive(Clone, Copy)]
-struct InputSrc {
-    pass: usize,
-    prev: bool,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputSrc {
+    Pass {
+        pass: usize,
+        prev: bool,
+    },
+    /// Index into `PassExecutor::externals` — another layer's output.
+    External(usize),
+}
+
+/// Another layer's output sampled by this effect (`"layer:N"` / `"media"` inputs).
+/// The layer stack copies the source into `target` each frame before `execute`.
+pub struct ExternalInput {
+    pub source: LayerSource,
+    pub target: RenderTarget,
 }
 
 /// A compiled pass: pipeline + render target + bind groups.
 struct CompiledPass {
     name: String,
-    pipeline: ShaderPipeline,
+    /// Shared with other layers running the same shader (#2208).
+    pipeline: Arc<ShaderPipeline>,
     /// Ping-pong target for this pass (feedback-capable).
     target: PingPongTarget,
     /// Bind groups indexed by the executor's global flip parity (#1481), not by
@@ -31,6 +47,10 @@ struct CompiledPass {
     /// feedback input at the right parity.
     bind_groups: [wgpu::BindGroup; 2],
     has_feedback: bool,
+    /// What `feedback(uv)` samples: this pass's own previous frame, or a
+    /// neighbouring writer of its persistent buffer (#2194). `None` binds the
+    /// placeholder.
+    feedback_src: Option<InputSrc>,
     /// Prior passes this pass samples as `input0..inputN-1` (current + prev frame).
     input_srcs: Vec<InputSrc>,
     /// Per-frame draw count. `>1` ping-pongs this pass's own target between draws
@@ -46,6 +66,7 @@ struct PassView<'a> {
     layout: &'a wgpu::BindGroupLayout,
     target: &'a PingPongTarget,
     has_feedback: bool,
+    feedback_src: Option<InputSrc>,
     input_srcs: &'a [InputSrc],
 }
 
@@ -53,9 +74,10 @@ struct PassView<'a> {
 /// (which need every pass's target to be resolvable). Construction two-phase.
 struct PreparedPass {
     name: String,
-    pipeline: ShaderPipeline,
+    pipeline: Arc<ShaderPipeline>,
     target: PingPongTarget,
     has_feedback: bool,
+    feedback_src: Option<InputSrc>,
     input_srcs: Vec<InputSrc>,
     iterations: u32,
 }
@@ -68,10 +90,14 @@ pub struct PassExecutor {
     /// feedback pass's `target.current` equals this value; bind groups are indexed
     /// by it so cross-pass reads land on the correct target every frame (#1481).
     flip_parity: usize,
+    externals: Vec<ExternalInput>,
+    /// What building this effect created, credited to Effects (#2226).
+    _tracked: Option<Tracked>,
 }
 
 impl PassExecutor {
-    /// Build a PassExecutor from a list of PassDefs.
+    /// Build a PassExecutor from a list of PassDefs. With `shared`, passes
+    /// reuse pipelines other layers already compiled for the same shader.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         device: &Device,
@@ -85,31 +111,52 @@ impl PassExecutor {
         audio: &AudioTextures,
         queue: &Queue,
         pipeline_cache: Option<&wgpu::PipelineCache>,
+        shared: Option<&SharedPipelines>,
     ) -> Result<Self, String> {
+        let before = ResourceCounts::read(device);
         // Phase 1: resolve inputs, compile pipelines, create targets.
         let mut prepared: Vec<PreparedPass> = Vec::with_capacity(pass_defs.len());
+        let mut externals: Vec<ExternalInput> = Vec::new();
+        let buffers = plan_buffers(pass_defs)?;
 
         for (idx, def) in pass_defs.iter().enumerate() {
             // Resolve inputs into `input0..` order: current-frame inputs first,
             // then previous-frame inputs.
             let mut input_srcs = Vec::with_capacity(def.inputs.len() + def.prev_inputs.len());
 
-            // `inputs`: current-frame output of an EARLIER pass. Forward/unknown
-            // references are a hard error — that half of the graph is a DAG.
+            // `inputs`: current-frame output of an EARLIER pass or buffer write, a
+            // persistent buffer's last frame, or another layer. Other forward/unknown
+            // references are a hard error — that half of the graph is a DAG. Passes
+            // sharing a layer source share one external target.
             for name in &def.inputs {
-                let src = pass_defs[..idx]
-                    .iter()
-                    .position(|p| &p.name == name)
-                    .ok_or_else(|| {
-                        format!(
-                            "Pass '{}' input '{name}' does not name an earlier pass",
-                            def.name
-                        )
-                    })?;
-                input_srcs.push(InputSrc {
-                    pass: src,
-                    prev: false,
-                });
+                if let Some(src) = current_input(pass_defs, &buffers, idx, name) {
+                    input_srcs.push(src);
+                    continue;
+                }
+                let source = LayerSource::parse(name).ok_or_else(|| {
+                    format!(
+                        "Pass '{}' input '{name}' does not name an earlier pass or a layer",
+                        def.name
+                    )
+                })?;
+                let slot = match externals.iter().position(|e| e.source == source) {
+                    Some(slot) => slot,
+                    None => {
+                        externals.push(ExternalInput {
+                            source,
+                            target: RenderTarget::new(
+                                device,
+                                width,
+                                height,
+                                hdr_format,
+                                1.0,
+                                "pass-external-input",
+                            ),
+                        });
+                        externals.len() - 1
+                    }
+                };
+                input_srcs.push(InputSrc::External(slot));
             }
 
             // `prev_inputs`: previous-frame output of ANY feedback pass (later refs
@@ -121,16 +168,20 @@ impl PassExecutor {
                 let src = pass_defs
                     .iter()
                     .position(|p| &p.name == name)
+                    .or_else(|| pass_defs.iter().rposition(|p| p.buffer() == name))
                     .ok_or_else(|| {
-                        format!("Pass '{}' prev_input '{name}' names no pass", def.name)
+                        format!(
+                            "Pass '{}' prev_input '{name}' names no pass or buffer",
+                            def.name
+                        )
                     })?;
-                if !pass_defs[src].feedback {
+                if !buffers[src].has_feedback {
                     return Err(format!(
-                        "Pass '{}' prev_input '{name}' must name a feedback pass",
+                        "Pass '{}' prev_input '{name}' must name a feedback pass or persistent buffer",
                         def.name
                     ));
                 }
-                input_srcs.push(InputSrc {
+                input_srcs.push(InputSrc::Pass {
                     pass: src,
                     prev: true,
                 });
@@ -141,19 +192,40 @@ impl PassExecutor {
                 .load_effect_source_with_inputs(&def.shader, input_count)
                 .map_err(|e| format!("Failed to load shader '{}': {e}", def.shader))?;
 
-            let pipeline =
-                ShaderPipeline::new(device, hdr_format, &source, pipeline_cache, input_count)
-                    .map_err(|e| format!("Failed to compile shader '{}': {e}", def.shader))?;
+            let constants = def.pipeline_constants();
+            let pipeline = match shared {
+                Some(shared) => shared.get_or_create(
+                    device,
+                    hdr_format,
+                    &source,
+                    pipeline_cache,
+                    input_count,
+                    &constants,
+                ),
+                None => ShaderPipeline::with_constants(
+                    device,
+                    hdr_format,
+                    &source,
+                    pipeline_cache,
+                    input_count,
+                    &constants,
+                )
+                .map(Arc::new),
+            }
+            .map_err(|e| format!("Failed to compile shader '{}': {e}", def.shader))?;
 
             // Clear feedback targets to prevent NaN/garbage from uninitialized GPU memory
-            let target = if def.feedback {
-                PingPongTarget::new_cleared(device, queue, width, height, hdr_format, def.scale)
+            let scale = def.target_scale();
+            let has_feedback = buffers[idx].has_feedback;
+            let target = if has_feedback {
+                PingPongTarget::new_cleared(device, queue, width, height, hdr_format, scale)
             } else {
-                PingPongTarget::new(device, width, height, hdr_format, def.scale)
+                // Never flipped, so the second side would be dead VRAM (#2170)
+                PingPongTarget::new_single(device, width, height, hdr_format, scale)
             };
 
             // Iterations only ping-pong a feedback target; ignore on non-feedback passes.
-            let iterations = if def.feedback {
+            let iterations = if has_feedback {
                 def.iterations.max(1)
             } else {
                 1
@@ -163,7 +235,8 @@ impl PassExecutor {
                 name: def.name.clone(),
                 pipeline,
                 target,
-                has_feedback: def.feedback,
+                has_feedback,
+                feedback_src: buffers[idx].feedback_src,
                 input_srcs,
                 iterations,
             });
@@ -176,11 +249,22 @@ impl PassExecutor {
                 layout: &p.pipeline.bind_group_layout,
                 target: &p.target,
                 has_feedback: p.has_feedback,
+                feedback_src: p.feedback_src,
                 input_srcs: &p.input_srcs,
             })
             .collect();
         let bind_groups: Vec<[wgpu::BindGroup; 2]> = (0..views.len())
-            .map(|i| build_bind_groups(&views, i, device, uniform_buffer, placeholder, audio))
+            .map(|i| {
+                build_bind_groups(
+                    &views,
+                    &externals,
+                    i,
+                    device,
+                    uniform_buffer,
+                    placeholder,
+                    audio,
+                )
+            })
             .collect();
         drop(views);
 
@@ -193,6 +277,7 @@ impl PassExecutor {
                 target: p.target,
                 bind_groups: bg,
                 has_feedback: p.has_feedback,
+                feedback_src: p.feedback_src,
                 input_srcs: p.input_srcs,
                 iterations: p.iterations,
             })
@@ -202,6 +287,8 @@ impl PassExecutor {
             passes,
             particle_system: None,
             flip_parity: 0,
+            externals,
+            _tracked: Some(Tracked::since(Subsystem::Effects, device, before)),
         })
     }
 
@@ -219,24 +306,81 @@ impl PassExecutor {
                 layout: &pipeline.bind_group_layout,
                 target: &feedback,
                 has_feedback: true, // always enable feedback for single-pass mode
+                feedback_src: Some(InputSrc::Pass {
+                    pass: 0,
+                    prev: true,
+                }),
                 input_srcs: &[],
             }];
-            build_bind_groups(&views, 0, device, uniform_buffer, placeholder, audio)
+            build_bind_groups(&views, &[], 0, device, uniform_buffer, placeholder, audio)
         };
 
         Self {
             passes: vec![CompiledPass {
                 name: "main".to_string(),
-                pipeline,
+                pipeline: Arc::new(pipeline),
                 target: feedback,
                 bind_groups,
                 has_feedback: true,
+                feedback_src: Some(InputSrc::Pass {
+                    pass: 0,
+                    prev: true,
+                }),
                 input_srcs: Vec::new(),
                 iterations: 1,
             }],
             particle_system: None,
             flip_parity: 0,
+            externals: Vec::new(),
+            _tracked: None,
+        }
+    }
+
+    /// Layer outputs this effect samples, in first-use order.
+    pub fn external_inputs(&self) -> &[ExternalInput] {
+        &self.externals
+    }
+
+    /// The final pass's most recently completed output: before `execute` this is
+    /// last frame's result (a layer sampled across a dependency cycle reads this).
+    pub fn previous_output(&self) -> &RenderTarget {
+        let last = self
+            .passes
+            .last()
+            .expect("pipeline always has at least one pass");
+        if last.has_feedback {
+            last.target.read_target()
+        } else {
+            last.target.write_target()
+        }
+    }
+
+    /// Pass names, which label their render passes; a GPU error naming one
+    /// is blamed on this effect (#2230).
+    pub fn pass_names(&self) -> impl Iterator<Item = &str> {
+        self.passes.iter().map(|p| p.name.as_str())
+    }
+
+    /// Every pass's output for the debug view (#2229): this frame's, and the
+    /// previous frame's for feedback passes. Particle textures follow.
+    pub fn debug_textures(&self) -> Vec<DebugTexture<'_>> {
+        let mut out = Vec::new();
+        for pass in &self.passes {
+            out.push(DebugTexture::of_target(
+                pass.name.clone(),
+                pass.target.write_target(),
+            ));
+            if pass.has_feedback {
+                out.push(DebugTexture::of_target(
+                    format!("{} (previous frame)", pass.name),
+                    pass.target.read_target(),
+                ));
+            }
+        }
+        if let Some(ref ps) = self.particle_system {
+            out.extend(ps.debug_textures());
         }
+        out
     }
 
     /// Execute all passes. Returns a reference to the final pass's write target.
@@ -354,6 +498,9 @@ impl PassExecutor {
                 pass.target.resize(device, width, height);
             }
         }
+        for ext in &mut self.externals {
+            ext.target.resize(device, width, height);
+        }
         // Phase 2: rebuild all bind groups against the new targets (a pass may
         // sample another pass's just-recreated target).
         self.rebuild_all_bind_groups(device, uniform_buffer, placeholder, audio);
@@ -383,11 +530,22 @@ impl PassExecutor {
                     layout: &p.pipeline.bind_group_layout,
                     target: &p.target,
                     has_feedback: p.has_feedback,
+                    feedback_src: p.feedback_src,
                     input_srcs: &p.input_srcs,
                 })
                 .collect();
             (0..views.len())
-                .map(|i| build_bind_groups(&views, i, device, uniform_buffer, placeholder, audio))
+                .map(|i| {
+                    build_bind_groups(
+                        &views,
+                        &self.externals,
+                        i,
+                        device,
+                        uniform_buffer,
+                        placeholder,
+                        audio,
+                    )
+                })
                 .collect()
         };
         for (pass, bg) in self.passes.iter_mut().zip(new_groups) {
@@ -415,8 +573,9 @@ impl PassExecutor {
             return Err(format!("Pass index {pass_index} out of range"));
         }
         // recreate_pipeline reuses the existing layout (same input_count), so the
-        // rebuilt bind groups stay valid.
-        self.passes[pass_index].pipeline.recreate_pipeline(
+        // rebuilt bind groups stay valid. A pipeline other layers share is
+        // copied first, leaving theirs alone.
+        Arc::make_mut(&mut self.passes[pass_index].pipeline).recreate_pipeline(
             device,
             hdr_format,
             source,
@@ -441,20 +600,107 @@ impl PassExecutor {
             return Err(format!("Pass index {pass_index} out of range"));
         }
         // Install the new pipeline first so the rebuild reads its layout.
-        self.passes[pass_index].pipeline = pipeline;
+        self.passes[pass_index].pipeline = Arc::new(pipeline);
         self.rebuild_all_bind_groups(device, uniform_buffer, placeholder, audio);
         Ok(())
     }
 }
 
+/// How one pass takes part in the named-buffer graph (#2194).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PassBuffer {
+    /// Keeps a previous frame: `feedback: true`, or writes a persistent buffer.
+    has_feedback: bool,
+    feedback_src: Option<InputSrc>,
+    /// Writes a buffer that keeps its contents across frames.
+    persistent: bool,
+}
+
+/// Work out each pass's buffer role. Writers of a persistent buffer form a chain:
+/// each one's `feedback(uv)` reads the writer before it this frame, and the first
+/// reads the last writer's previous frame, so the buffer carries over. An
+/// iterated pass ping-pongs its own target, so it can't share one.
+fn plan_buffers(pass_defs: &[PassDef]) -> Result<Vec<PassBuffer>, String> {
+    pass_defs
+        .iter()
+        .enumerate()
+        .map(|(idx, def)| {
+            let writers: Vec<usize> = pass_defs
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| p.buffer() == def.buffer())
+                .map(|(i, _)| i)
+                .collect();
+            if writers.len() > 1 && def.iterations > 1 {
+                return Err(format!(
+                    "Pass '{}' has iterations, so it can't share buffer '{}' with other passes",
+                    def.name,
+                    def.buffer()
+                ));
+            }
+            let persistent = writers.iter().any(|&w| pass_defs[w].persistent);
+            let has_feedback = def.feedback || persistent;
+            let feedback_src = match writers.iter().position(|&w| w == idx) {
+                Some(k) if persistent && writers.len() > 1 => Some(if k == 0 {
+                    InputSrc::Pass {
+                        pass: writers[writers.len() - 1],
+                        prev: true,
+                    }
+                } else {
+                    InputSrc::Pass {
+                        pass: writers[k - 1],
+                        prev: false,
+                    }
+                }),
+                _ => has_feedback.then_some(InputSrc::Pass {
+                    pass: idx,
+                    prev: true,
+                }),
+            };
+            Ok(PassBuffer {
+                has_feedback,
+                feedback_src,
+                persistent,
+            })
+        })
+        .collect()
+}
+
+/// Resolve an `inputs` entry of pass `idx` against the passes: an earlier pass
+/// by name, else the latest earlier write to a buffer of that name, else — for
+/// a persistent buffer nothing has written yet this frame — its last frame.
+fn current_input(
+    pass_defs: &[PassDef],
+    buffers: &[PassBuffer],
+    idx: usize,
+    name: &str,
+) -> Option<InputSrc> {
+    let earlier = &pass_defs[..idx];
+    if let Some(pass) = earlier
+        .iter()
+        .position(|p| p.name == name)
+        .or_else(|| earlier.iter().rposition(|p| p.buffer() == name))
+    {
+        return Some(InputSrc::Pass { pass, prev: false });
+    }
+    let last = pass_defs.iter().rposition(|p| p.buffer() == name)?;
+    buffers[last].persistent.then_some(InputSrc::Pass {
+        pass: last,
+        prev: true,
+    })
+}
+
 /// Build the `[BindGroup; 2]` (one per global flip parity) for `views[i]`.
 ///
 /// For parity `g`, the group binds: this pass's own previous frame (feedback →
-/// the *other* target `targets[1-g]`; non-feedback → the 1x1 placeholder), the
+/// the *other* target `targets[1-g]`, or another writer of a persistent buffer;
+/// non-feedback → the 1x1 placeholder), the
 /// three A17 audio textures + sampler, then each declared input pass `P` at
 /// `P.targets[P.has_feedback ? g : 0]` — i.e. the target `P` writes this frame.
+/// Layer inputs bind their external copy at both parities.
 fn build_bind_groups(
     views: &[PassView],
+    externals: &[ExternalInput],
     i: usize,
     device: &Device,
     uniform_buffer: &UniformBuffer,
@@ -469,34 +715,37 @@ fn build_bind_groups(
     let audio_sampler = &audio.sampler;
 
     let make = |g: usize| -> wgpu::BindGroup {
-        // Own previous frame.
-        let (prev_view, prev_sampler): (&TextureView, &Sampler) = if view.has_feedback {
-            let other = &view.target.targets[1 - g];
-            (&other.view, &other.sampler)
-        } else {
-            (&placeholder.view, &placeholder.sampler)
+        // Current-frame sources read the target the source writes THIS frame
+        // (targets[g] if feedback, else targets[0]); prev-frame sources read the
+        // source feedback pass's OTHER target, targets[1-g], which still holds last
+        // frame's output when this pass executes (#1481).
+        let resolve = |src: InputSrc| -> (&TextureView, &Sampler) {
+            let rt = match src {
+                InputSrc::Pass { pass, prev } => {
+                    let sp = &views[pass];
+                    let ti = if prev {
+                        1 - g
+                    } else if sp.has_feedback {
+                        g
+                    } else {
+                        0
+                    };
+                    &sp.target.targets[ti]
+                }
+                InputSrc::External(slot) => &externals[slot].target,
+            };
+            (&rt.view, &rt.sampler)
         };
 
-        // Declared inputs → each source pass's target. Current-frame inputs read the
-        // target the source writes THIS frame (targets[g] if feedback, else targets[0]);
-        // prev-frame inputs read the source feedback pass's OTHER target, targets[1-g],
-        // which still holds last frame's output when this pass executes (#1481).
-        let input_refs: Vec<(&TextureView, &Sampler)> = view
-            .input_srcs
-            .iter()
-            .map(|&src| {
-                let sp = &views[src.pass];
-                let ti = if src.prev {
-                    1 - g
-                } else if sp.has_feedback {
-                    g
-                } else {
-                    0
-                };
-                let rt = &sp.target.targets[ti];
-                (&rt.view, &rt.sampler)
-            })
-            .collect();
+        // Own previous frame, or the persistent buffer's previous write (#2194).
+        let (prev_view, prev_sampler) = match view.feedback_src {
+            Some(src) => resolve(src),
+            None => (&placeholder.view, &placeholder.sampler),
+        };
+
+        // Declared inputs → each source pass's target.
+        let input_refs: Vec<(&TextureView, &Sampler)> =
+            view.input_srcs.iter().map(|&src| resolve(src)).collect();
 
         uniform_buffer.create_bind_group(
             device,
@@ -627,18 +876,23 @@ mod tests {
     ) -> PassExecutor {
         let prepared: Vec<PreparedPass> = specs
             .into_iter()
+            .enumerate()
             .map(
-                |(name, pipeline, feedback, input_srcs, iterations, scale)| {
+                |(i, (name, pipeline, feedback, input_srcs, iterations, scale))| {
                     let target = if feedback {
                         PingPongTarget::new_cleared(device, queue, w, h, fmt, scale)
                     } else {
-                        PingPongTarget::new(device, w, h, fmt, scale)
+                        PingPongTarget::new_single(device, w, h, fmt, scale)
                     };
                     PreparedPass {
                         name: name.to_string(),
-                        pipeline,
+                        pipeline: Arc::new(pipeline),
                         target,
                         has_feedback: feedback,
+                        feedback_src: feedback.then_some(InputSrc::Pass {
+                            pass: i,
+                            prev: true,
+                        }),
                         input_srcs,
                         iterations,
                     }
@@ -652,11 +906,12 @@ mod tests {
                     layout: &p.pipeline.bind_group_layout,
                     target: &p.target,
                     has_feedback: p.has_feedback,
+                    feedback_src: p.feedback_src,
                     input_srcs: &p.input_srcs,
                 })
                 .collect();
             (0..views.len())
-                .map(|i| build_bind_groups(&views, i, device, ubuf, placeholder, audio))
+                .map(|i| build_bind_groups(&views, &[], i, device, ubuf, placeholder, audio))
                 .collect()
         };
         let passes = prepared
@@ -668,6 +923,7 @@ mod tests {
                 target: p.target,
                 bind_groups: bg,
                 has_feedback: p.has_feedback,
+                feedback_src: p.feedback_src,
                 input_srcs: p.input_srcs,
                 iterations: p.iterations,
             })
@@ -676,9 +932,49 @@ mod tests {
             passes,
             particle_system: None,
             flip_parity: 0,
+            externals: Vec::new(),
+            _tracked: None,
         }
     }
 
+    fn buffer_pass(json: &str) -> PassDef {
+        serde_json::from_str(json).unwrap()
+    }
+
+    // Writers of a persistent buffer chain their feedback through each other and
+    // wrap around to last frame's final write; readers see the latest write, or
+    // last frame's before any writer has run (#2194).
+    #[test]
+    fn persistent_buffer_chains_writers() {
+        let passes = [
+            buffer_pass(
+                r#"{"name":"read","shader":"r.wgsl","feedback":false,"inputs":["velocity"]}"#,
+            ),
+            buffer_pass(
+                r#"{"name":"advect","shader":"a.wgsl","feedback":false,"target":"velocity","persistent":true}"#,
+            ),
+            buffer_pass(r#"{"name":"relax","shader":"b.wgsl","target":"velocity"}"#),
+            buffer_pass(r#"{"name":"show","shader":"s.wgsl","feedback":false}"#),
+        ];
+        let plan = plan_buffers(&passes).unwrap();
+        let src = |pass, prev| Some(InputSrc::Pass { pass, prev });
+        assert!(plan[1].has_feedback && plan[2].has_feedback);
+        assert_eq!(plan[1].feedback_src, src(2, true));
+        assert_eq!(plan[2].feedback_src, src(1, false));
+        assert_eq!(plan[3].feedback_src, None);
+        assert_eq!(current_input(&passes, &plan, 0, "velocity"), src(2, true));
+        assert_eq!(current_input(&passes, &plan, 2, "velocity"), src(1, false));
+        assert_eq!(current_input(&passes, &plan, 3, "velocity"), src(2, false));
+        // A pass's own name still picks that pass, not the buffer's latest write.
+        assert_eq!(current_input(&passes, &plan, 3, "advect"), src(1, false));
+        assert_eq!(current_input(&passes, &plan, 0, "show"), None);
+
+        // An iterated pass ping-pongs its own target, so it can't share one.
+        let mut iterated = passes.clone();
+        iterated[2].iterations = 8;
+        assert!(plan_buffers(&iterated).unwrap_err().contains("relax"));
+    }
+
     // An input naming a pass that was not declared earlier is a hard error, caught
     // before any shader is loaded (so `shader` never has to resolve on disk).
     #[test]
@@ -699,6 +995,9 @@ mod tests {
             prev_inputs: vec![],
             iterations: 1,
             feedback: false,
+            constants: Default::default(),
+            target: None,
+            persistent: false,
         }];
         let res = PassExecutor::new(
             &device,
@@ -712,6 +1011,7 @@ mod tests {
             &audio,
             &queue,
             None,
+            None,
         );
         let err = res.err().expect("unknown input must be rejected");
         assert!(
@@ -772,7 +1072,7 @@ mod tests {
                     "B",
                     pipe_b,
                     false,
-                    vec![InputSrc {
+                    vec![InputSrc::Pass {
                         pass: 0,
                         prev: false,
                     }],
@@ -1001,7 +1301,7 @@ mod tests {
                     "reader",
                     pipe_reader,
                     false,
-                    vec![InputSrc {
+                    vec![InputSrc::Pass {
                         pass: 1,
                         prev: true,
                     }],
@@ -1160,7 +1460,7 @@ mod tests {
         let pipe_dye = mk(include_str!("../../../../assets/shaders/sumi_dye.wgsl"), 1);
 
         // Same wiring as sumi.pfx: passes 0..3 = divergence, pressure, velocity, dye.
-        let src = |pass: usize, prev: bool| InputSrc { pass, prev };
+        let src = |pass: usize, prev: bool| InputSrc::Pass { pass, prev };
         let mut executor = assemble(
             &device,
             &queue,
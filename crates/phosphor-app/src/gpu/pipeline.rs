@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
 use anyhow::Result;
 use wgpu::{
     BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType,
@@ -7,11 +11,16 @@ use wgpu::{
     TextureViewDimension, VertexState,
 };
 
+use super::error_scope::ErrorScope;
 use super::fullscreen_quad::FULLSCREEN_TRIANGLE_VS;
 
+#[derive(Clone)]
 pub struct ShaderPipeline {
     pub pipeline: RenderPipeline,
     pub bind_group_layout: BindGroupLayout,
+    /// WGSL `override` values the pipeline was built with (#2193), kept so
+    /// `recreate_pipeline` applies them again.
+    constants: Vec<(String, f64)>,
 }
 
 impl ShaderPipeline {
@@ -25,29 +34,52 @@ impl ShaderPipeline {
         fragment_source: &str,
         cache: Option<&wgpu::PipelineCache>,
         input_count: usize,
+    ) -> Result<Self> {
+        Self::with_constants(device, format, fragment_source, cache, input_count, &[])
+    }
+
+    /// [`Self::new`], also setting the shader's `override` constants by name
+    /// (a `.pfx` pass's `constants`, #2193).
+    pub fn with_constants(
+        device: &Device,
+        format: TextureFormat,
+        fragment_source: &str,
+        cache: Option<&wgpu::PipelineCache>,
+        input_count: usize,
+        constants: &[(&str, f64)],
     ) -> Result<Self> {
         let bind_group_layout = Self::create_bind_group_layout(device, input_count);
 
         // Combine vertex + fragment into one module
         let full_source = format!("{}\n{}", FULLSCREEN_TRIANGLE_VS, fragment_source);
 
-        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let scope = ErrorScope::push(device);
 
         let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("phosphor-shader"),
             source: wgpu::ShaderSource::Wgsl(full_source.into()),
         });
 
-        let pipeline =
-            Self::create_pipeline(device, format, &bind_group_layout, &shader_module, cache);
+        let pipeline = Self::create_pipeline(
+            device,
+            format,
+            &bind_group_layout,
+            &shader_module,
+            cache,
+            constants,
+        );
 
-        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+        if let Some(error) = scope.pop() {
             return Err(anyhow::anyhow!("{error}"));
         }
 
         Ok(Self {
             pipeline,
             bind_group_layout,
+            constants: constants
+                .iter()
+                .map(|(name, value)| ((*name).to_string(), *value))
+                .collect(),
         })
     }
 
@@ -61,25 +93,31 @@ impl ShaderPipeline {
     ) -> Result<(), String> {
         let full_source = format!("{}\n{}", FULLSCREEN_TRIANGLE_VS, fragment_source);
 
-        // Push an error scope to catch shader compilation and pipeline creation errors
-        // instead of letting wgpu panic with the default error handler.
-        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        // Catch shader compilation and pipeline creation errors instead of
+        // letting wgpu panic with the default error handler.
+        let scope = ErrorScope::push(device);
 
         let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("phosphor-shader"),
             source: wgpu::ShaderSource::Wgsl(full_source.into()),
         });
 
+        let constants: Vec<(&str, f64)> = self
+            .constants
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+            .collect();
         let pipeline = Self::create_pipeline(
             device,
             format,
             &self.bind_group_layout,
             &shader_module,
             cache,
+            &constants,
         );
 
         // Check if any validation errors occurred during shader/pipeline creation.
-        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+        if let Some(error) = scope.pop() {
             return Err(format!("{error}"));
         }
 
@@ -198,6 +236,7 @@ impl ShaderPipeline {
         bind_group_layout: &BindGroupLayout,
         shader_module: &ShaderModule,
         cache: Option<&wgpu::PipelineCache>,
+        constants: &[(&str, f64)],
     ) -> RenderPipeline {
         let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("phosphor-pipeline-layout"),
@@ -212,7 +251,11 @@ impl ShaderPipeline {
                 module: shader_module,
                 entry_point: Some("vs_main"),
                 buffers: &[],
-                compilation_options: PipelineCompilationOptions::default(),
+                // Both stages share the module, so both need every override set.
+                compilation_options: PipelineCompilationOptions {
+                    constants,
+                    ..Default::default()
+                },
             },
             fragment: Some(FragmentState {
                 module: shader_module,
@@ -222,7 +265,10 @@ impl ShaderPipeline {
                     blend: None,
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
-                compilation_options: PipelineCompilationOptions::default(),
+                compilation_options: PipelineCompilationOptions {
+                    constants,
+                    ..Default::default()
+                },
             }),
             primitive: PrimitiveState::default(),
             depth_stencil: None,
@@ -232,3 +278,107 @@ impl ShaderPipeline {
         })
     }
 }
+
+/// Pipelines shared by every layer running the same shader (#2208). Layers
+/// on one effect compile each pass once and differ only in their targets and
+/// bind groups. An entry is dropped on the next lookup after the last layer
+/// holding it lets go.
+#[derive(Default)]
+pub struct SharedPipelines {
+    entries: Mutex<HashMap<u64, Arc<ShaderPipeline>>>,
+}
+
+impl SharedPipelines {
+    /// The pipeline for this shader, compiling it on first use. The arguments
+    /// are [`ShaderPipeline::with_constants`]'s, and all of them are part of
+    /// the key.
+    pub fn get_or_create(
+        &self,
+        device: &Device,
+        format: TextureFormat,
+        fragment_source: &str,
+        cache: Option<&wgpu::PipelineCache>,
+        input_count: usize,
+        constants: &[(&str, f64)],
+    ) -> Result<Arc<ShaderPipeline>> {
+        let key = pipeline_key(format, fragment_source, input_count, constants);
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, pipeline| Arc::strong_count(pipeline) > 1);
+        if let Some(pipeline) = entries.get(&key) {
+            return Ok(pipeline.clone());
+        }
+        let pipeline = Arc::new(ShaderPipeline::with_constants(
+            device,
+            format,
+            fragment_source,
+            cache,
+            input_count,
+            constants,
+        )?);
+        entries.insert(key, pipeline.clone());
+        Ok(pipeline)
+    }
+}
+
+/// Hash of everything that goes into a pipeline.
+fn pipeline_key(
+    format: TextureFormat,
+    fragment_source: &str,
+    input_count: usize,
+    constants: &[(&str, f64)],
+) -> u64 {
+    let mut hasher = std::hash::DefaultHasher::new();
+    format.hash(&mut hasher);
+    fragment_source.hash(&mut hasher);
+    input_count.hash(&mut hasher);
+    for (name, value) in constants {
+        name.hash(&mut hasher);
+        value.to_bits().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::test_gpu::{gpu_guard, test_gpu};
+
+    const FMT: TextureFormat = TextureFormat::Rgba16Float;
+    const FRAG: &str = "@fragment fn fs_main() -> @location(0) vec4f { return vec4f(1.0); }";
+
+    #[test]
+    fn key_covers_everything_that_shapes_a_pipeline() {
+        let base = pipeline_key(FMT, FRAG, 0, &[("steps", 4.0)]);
+        assert_eq!(base, pipeline_key(FMT, FRAG, 0, &[("steps", 4.0)]));
+        assert_ne!(base, pipeline_key(FMT, "// edited", 0, &[("steps", 4.0)]));
+        assert_ne!(base, pipeline_key(FMT, FRAG, 1, &[("steps", 4.0)]));
+        assert_ne!(base, pipeline_key(FMT, FRAG, 0, &[("steps", 8.0)]));
+        assert_ne!(
+            base,
+            pipeline_key(TextureFormat::Rgba8Unorm, FRAG, 0, &[("steps", 4.0)])
+        );
+    }
+
+    #[test]
+    #[ignore = "requires a wgpu adapter"]
+    fn same_shader_compiles_once() {
+        let _guard = gpu_guard();
+        let (device, _queue) = test_gpu();
+        let shared = SharedPipelines::default();
+        let a = shared
+            .get_or_create(&device, FMT, FRAG, None, 0, &[])
+            .unwrap();
+        let b = shared
+            .get_or_create(&device, FMT, FRAG, None, 0, &[])
+            .unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+
+        // Once no layer holds it, the entry goes at the next lookup
+        drop((a, b));
+        let c = shared
+            .get_or_create(&device, FMT, &format!("{FRAG}\n"), None, 0, &[])
+            .unwrap();
+        assert_eq!(shared.entries.lock().unwrap().len(), 1);
+        drop(c);
+    }
+}
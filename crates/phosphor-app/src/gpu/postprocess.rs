@@ -9,15 +9,20 @@ use wgpu::{
 
 use crate::effect::format::PostProcessDef;
 
+use super::blue_noise;
+use super::calibration::{CalibrationUniforms, OutputCalibration, OutputTarget};
 use super::fullscreen_quad::FULLSCREEN_TRIANGLE_VS_WITH_UV;
+use super::master::MasterUniforms;
 use super::render_target::RenderTarget;
+use super::resources::{ResourceCounts, Subsystem, Tracked};
 
 const BLOOM_EXTRACT_FS: &str =
     include_str!("../../../../assets/shaders/builtin/bloom_extract.wgsl");
 const BLOOM_BLUR_FS: &str = include_str!("../../../../assets/shaders/builtin/bloom_blur.wgsl");
 const POST_COMPOSITE_FS: &str =
     include_str!("../../../../assets/shaders/builtin/post_composite.wgsl");
-const BLIT_FS: &str = include_str!("../../../../assets/shaders/builtin/blit.wgsl");
+const MASTER_GRADE_LIB: &str = include_str!("../../../../assets/shaders/builtin/master_grade.wgsl");
+const POST_MASTER_FS: &str = include_str!("../../../../assets/shaders/builtin/post_master.wgsl");
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
@@ -44,8 +49,54 @@ struct PostParams {
     grain_intensity: f32,
     time: f32,
     rms: f32,
-    alpha_from_luma: f32,
-    tonemap_mode: f32, // 0 = ACES (house look), 1 = linear passthrough (SuperSplat-faithful)
+    alpha_mode: f32,   // see `AlphaMode`
+    tonemap_mode: f32, // see `tonemap_mode`
+    /// Linear multiplier from the exposure in stops (#2235).
+    exposure: f32,
+    white_point: f32,
+    _pad: [f32; 2],
+}
+
+/// What the composite writes to alpha, for NDI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlphaMode {
+    Opaque,
+    /// Brightness as alpha, for receivers without a keyer.
+    Luma,
+    /// The layers' own alpha, for key and fill output (#2238).
+    Scene,
+}
+
+impl AlphaMode {
+    fn shader_index(self) -> f32 {
+        match self {
+            AlphaMode::Opaque => 0.0,
+            AlphaMode::Luma => 1.0,
+            AlphaMode::Scene => 2.0,
+        }
+    }
+}
+
+/// Shader index of a `PostProcessDef::tonemap` name: 0 ACES (house look),
+/// 1 linear passthrough (SuperSplat-faithful), 2 Reinhard, 3 Hable. Unknown
+/// names get ACES.
+fn tonemap_mode(name: &str) -> f32 {
+    match name {
+        "linear" => 1.0,
+        "reinhard" => 2.0,
+        "hable" => 3.0,
+        _ => 0.0,
+    }
+}
+
+/// One output step as a fraction of full scale, for spreading the dither
+/// (#2236). Float targets don't band, so they get none.
+fn dither_step(format: TextureFormat) -> f32 {
+    match format {
+        TextureFormat::Rgb10a2Unorm => 1.0 / 1023.0,
+        TextureFormat::Rgba16Float | TextureFormat::Rgba32Float => 0.0,
+        _ => 1.0 / 255.0,
+    }
 }
 
 pub struct PostProcessChain {
@@ -69,21 +120,28 @@ pub struct PostProcessChain {
     blur_h_params_buffer: wgpu::Buffer,
     blur_v_params_buffer: wgpu::Buffer,
     post_params_buffer: wgpu::Buffer,
-    // Stored for potential resize rebuilds
-    #[allow(dead_code)]
+    master_params_buffer: wgpu::Buffer,
+    /// One per [`OutputTarget`], since captures render in the same submission (#2237)
+    calibration_buffers: [wgpu::Buffer; 3],
+    // Dither tile (#2236)
+    _blue_noise: wgpu::Texture,
+    blue_noise_view: TextureView,
     surface_format: TextureFormat,
     #[allow(dead_code)]
     hdr_format: TextureFormat,
+    _tracked: Tracked,
 }
 
 impl PostProcessChain {
     pub fn new(
         device: &Device,
+        queue: &Queue,
         surface_format: TextureFormat,
         hdr_format: TextureFormat,
         width: u32,
         height: u32,
     ) -> Self {
+        let before = ResourceCounts::read(device);
         // Quarter-res bloom targets
         let bloom_extract_target =
             RenderTarget::new(device, width, height, hdr_format, 0.25, "bloom-extract");
@@ -130,23 +188,37 @@ impl PostProcessChain {
                 tex_entry(2),     // bloom
                 sampler_entry(3), // bloom sampler
                 uniform_entry(4, std::mem::size_of::<PostParams>()),
+                uniform_entry(5, std::mem::size_of::<MasterUniforms>()),
+                tex_entry(6), // blue noise
+                uniform_entry(7, std::mem::size_of::<CalibrationUniforms>()),
             ],
         });
         let composite_pipeline = create_fs_pipeline(
             device,
             "post-composite",
             &composite_bgl,
-            POST_COMPOSITE_FS,
+            &format!("{MASTER_GRADE_LIB}\n{POST_COMPOSITE_FS}"),
             surface_format,
         );
 
-        // --- Simple blit pipeline (fallback when post-processing disabled) ---
+        // --- Blit pipeline (post-processing disabled): output master only ---
         let blit_bgl = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some("post-blit-bgl"),
-            entries: &[tex_entry(0), sampler_entry(1)],
+            entries: &[
+                tex_entry(0),
+                sampler_entry(1),
+                uniform_entry(2, std::mem::size_of::<MasterUniforms>()),
+                tex_entry(3), // blue noise
+                uniform_entry(4, std::mem::size_of::<CalibrationUniforms>()),
+            ],
         });
-        let blit_pipeline =
-            create_fs_pipeline(device, "post-blit", &blit_bgl, BLIT_FS, surface_format);
+        let blit_pipeline = create_fs_pipeline(
+            device,
+            "post-blit",
+            &blit_bgl,
+            &format!("{MASTER_GRADE_LIB}\n{POST_MASTER_FS}"),
+            surface_format,
+        );
 
         // Uniform buffers
         let bloom_params_buffer =
@@ -157,6 +229,16 @@ impl PostProcessChain {
             create_uniform_buffer(device, "blur-v-params", std::mem::size_of::<BlurParams>());
         let post_params_buffer =
             create_uniform_buffer(device, "post-params", std::mem::size_of::<PostParams>());
+        let master_params_buffer = create_uniform_buffer(
+            device,
+            "master-params",
+            std::mem::size_of::<MasterUniforms>(),
+        );
+        let calibration_buffers =
+            ["calibration-window", "calibration-ndi", "calibration-rec"].map(|label| {
+                create_uniform_buffer(device, label, std::mem::size_of::<CalibrationUniforms>())
+            });
+        let (blue_noise, blue_noise_view) = blue_noise::create_texture(device, queue);
 
         Self {
             enabled: true,
@@ -175,8 +257,13 @@ impl PostProcessChain {
             blur_h_params_buffer,
             blur_v_params_buffer,
             post_params_buffer,
+            master_params_buffer,
+            calibration_buffers,
+            _blue_noise: blue_noise,
+            blue_noise_view,
             surface_format,
             hdr_format,
+            _tracked: Tracked::since(Subsystem::PostProcess, device, before),
         }
     }
 
@@ -200,10 +287,34 @@ impl PostProcessChain {
         onset: f32,
         flatness: f32,
         overrides: &PostProcessDef,
-        alpha_from_luma: bool,
+        alpha_mode: AlphaMode,
+        master: &MasterUniforms,
+        calibration: &OutputCalibration,
     ) {
+        let master = MasterUniforms {
+            dither: master.dither * dither_step(self.surface_format),
+            srgb_target: if self.surface_format.is_srgb() {
+                1.0
+            } else {
+                0.0
+            },
+            ..*master
+        };
+        queue.write_buffer(&self.master_params_buffer, 0, bytemuck::bytes_of(&master));
+        for target in [
+            OutputTarget::Window,
+            OutputTarget::Ndi,
+            OutputTarget::Recording,
+        ] {
+            let uniforms = calibration.for_target(target).build_uniforms();
+            queue.write_buffer(
+                self.calibration_buffer(target),
+                0,
+                bytemuck::bytes_of(&uniforms),
+            );
+        }
         if !self.enabled {
-            // Simple blit fallback
+            // Blit fallback, still through the output master
             let bg = device.create_bind_group(&BindGroupDescriptor {
                 label: Some("post-blit-bg"),
                 layout: &self.blit_bgl,
@@ -216,6 +327,20 @@ impl PostProcessChain {
                         binding: 1,
                         resource: BindingResource::Sampler(&source.sampler),
                     },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: self.master_params_buffer.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: BindingResource::TextureView(&self.blue_noise_view),
+                    },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: self
+                            .calibration_buffer(OutputTarget::Window)
+                            .as_entire_binding(),
+                    },
                 ],
             });
             run_fullscreen_pass(encoder, "post-blit", &self.blit_pipeline, &bg, surface_view);
@@ -282,12 +407,11 @@ impl PostProcessChain {
             },
             time,
             rms,
-            alpha_from_luma: if alpha_from_luma { 1.0 } else { 0.0 },
-            tonemap_mode: if overrides.tonemap == "linear" {
-                1.0
-            } else {
-                0.0
-            },
+            alpha_mode: alpha_mode.shader_index(),
+            tonemap_mode: tonemap_mode(&overrides.tonemap),
+            exposure: overrides.exposure.exp2(),
+            white_point: overrides.white_point.max(1.0),
+            _pad: [0.0; 2],
         };
         queue.write_buffer(
             &self.post_params_buffer,
@@ -411,6 +535,20 @@ impl PostProcessChain {
                         binding: 4,
                         resource: self.post_params_buffer.as_entire_binding(),
                     },
+                    BindGroupEntry {
+                        binding: 5,
+                        resource: self.master_params_buffer.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 6,
+                        resource: BindingResource::TextureView(&self.blue_noise_view),
+                    },
+                    BindGroupEntry {
+                        binding: 7,
+                        resource: self
+                            .calibration_buffer(OutputTarget::Window)
+                            .as_entire_binding(),
+                    },
                 ],
             });
             run_fullscreen_pass(
@@ -423,8 +561,17 @@ impl PostProcessChain {
         }
     }
 
+    fn calibration_buffer(&self, target: OutputTarget) -> &wgpu::Buffer {
+        match target {
+            OutputTarget::Window => &self.calibration_buffers[0],
+            OutputTarget::Ndi => &self.calibration_buffers[1],
+            OutputTarget::Recording => &self.calibration_buffers[2],
+        }
+    }
+
     /// Render the final composite (or blit) to a secondary capture target.
-    /// Reuses existing bloom results and uniform buffers — only runs the final pass.
+    /// Reuses existing bloom results and uniform buffers — only runs the final
+    /// pass, with `target`'s calibration.
     #[allow(dead_code)]
     pub fn render_composite_to(
         &self,
@@ -432,6 +579,7 @@ impl PostProcessChain {
         encoder: &mut CommandEncoder,
         source: &RenderTarget,
         capture_view: &TextureView,
+        target: OutputTarget,
     ) {
         if !self.enabled {
             let bg = device.create_bind_group(&BindGroupDescriptor {
@@ -446,6 +594,18 @@ impl PostProcessChain {
                         binding: 1,
                         resource: BindingResource::Sampler(&source.sampler),
                     },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: self.master_params_buffer.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: BindingResource::TextureView(&self.blue_noise_view),
+                    },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: self.calibration_buffer(target).as_entire_binding(),
+                    },
                 ],
             });
             run_fullscreen_pass(encoder, "ndi-blit", &self.blit_pipeline, &bg, capture_view);
@@ -476,6 +636,18 @@ impl PostProcessChain {
                     binding: 4,
                     resource: self.post_params_buffer.as_entire_binding(),
                 },
+                BindGroupEntry {
+                    binding: 5,
+                    resource: self.master_params_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 6,
+                    resource: BindingResource::TextureView(&self.blue_noise_view),
+                },
+                BindGroupEntry {
+                    binding: 7,
+                    resource: self.calibration_buffer(target).as_entire_binding(),
+                },
             ],
         });
         run_fullscreen_pass(
@@ -606,3 +778,45 @@ fn run_fullscreen_pass(
     pass.set_bind_group(0, bind_group, &[]);
     pass.draw(0..3, 0..1);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(fragment: &str) {
+        let src = format!("{FULLSCREEN_TRIANGLE_VS_WITH_UV}\n{MASTER_GRADE_LIB}\n{fragment}");
+        let module = wgpu::naga::front::wgsl::parse_str(&src).expect("shader parses");
+        wgpu::naga::valid::Validator::new(
+            wgpu::naga::valid::ValidationFlags::all(),
+            wgpu::naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .expect("shader validates");
+    }
+
+    #[test]
+    fn master_graded_shaders_validate() {
+        validate(POST_COMPOSITE_FS);
+        validate(POST_MASTER_FS);
+    }
+
+    #[test]
+    fn post_params_fill_three_vec4s() {
+        assert_eq!(std::mem::size_of::<PostParams>(), 48);
+        assert_eq!(tonemap_mode("linear"), 1.0);
+        assert_eq!(tonemap_mode("hable"), 3.0);
+        assert_eq!(tonemap_mode("filmic"), 0.0);
+    }
+
+    #[test]
+    fn master_uniforms_fill_two_vec4s() {
+        assert_eq!(std::mem::size_of::<MasterUniforms>(), 32);
+    }
+
+    #[test]
+    fn dither_steps_follow_the_target_depth() {
+        assert_eq!(dither_step(TextureFormat::Bgra8UnormSrgb), 1.0 / 255.0);
+        assert_eq!(dither_step(TextureFormat::Rgb10a2Unorm), 1.0 / 1023.0);
+        assert_eq!(dither_step(TextureFormat::Rgba16Float), 0.0);
+    }
+}
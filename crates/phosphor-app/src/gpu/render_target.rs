@@ -100,6 +100,24 @@ impl PingPongTarget {
         }
     }
 
+    /// A target that never flips (#2170): only `targets[0]` is full size. The
+    /// spare is a 1×1 stand-in (scale 0 keeps it 1×1 across resizes), so
+    /// non-feedback passes don't pay for a second full-resolution texture.
+    pub fn new_single(
+        device: &Device,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+        scale: f32,
+    ) -> Self {
+        let a = RenderTarget::new(device, width, height, format, scale, "pass-target");
+        let b = RenderTarget::new(device, 1, 1, format, 0.0, "pass-target-spare");
+        Self {
+            targets: [a, b],
+            current: 0,
+        }
+    }
+
     /// Create a new PingPongTarget and clear both sides to transparent black.
     /// Prevents NaN/garbage in uninitialized feedback textures from causing blowout.
     pub fn new_cleared(
@@ -0,0 +1,385 @@
+//! GPU resource tracking (#2226): what each subsystem created and released,
+//! and whether the device's live resource counts keep climbing, so a long
+//! session of effect swaps can show a leak before the GPU runs out.
+//!
+//! Live totals come from wgpu's internal counters (the `counters` feature).
+//! Subsystems are credited by counting what the device gained while one of
+//! their objects was built; a [`Tracked`] guard kept in the object hands the
+//! same counts back when it drops. wgpu frees resources a frame or two after
+//! their last handle goes, so totals and the per-subsystem ledger only agree
+//! between loads, and growth is judged on the floor of the counts over whole
+//! minutes rather than on any one sample.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use wgpu::Device;
+
+/// Who built a set of GPU resources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Effects,
+    Particles,
+    Media,
+    Generators,
+    PostProcess,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 5] = [
+        Subsystem::Effects,
+        Subsystem::Particles,
+        Subsystem::Media,
+        Subsystem::Generators,
+        Subsystem::PostProcess,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Subsystem::Effects => "Effects",
+            Subsystem::Particles => "Particles",
+            Subsystem::Media => "Media",
+            Subsystem::Generators => "Generators",
+            Subsystem::PostProcess => "Post-processing",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Counts of the resource kinds worth watching.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceCounts {
+    pub textures: i64,
+    pub buffers: i64,
+    pub pipelines: i64,
+    pub bind_groups: i64,
+}
+
+impl ResourceCounts {
+    pub const KINDS: [&'static str; 4] = ["Textures", "Buffers", "Pipelines", "Bind groups"];
+
+    const ZERO: Self = Self {
+        textures: 0,
+        buffers: 0,
+        pipelines: 0,
+        bind_groups: 0,
+    };
+
+    /// What `device` holds right now. All zero when the backend keeps no counters.
+    pub fn read(device: &Device) -> Self {
+        let hal = device.get_internal_counters().hal;
+        Self {
+            textures: hal.textures.read() as i64,
+            buffers: hal.buffers.read() as i64,
+            pipelines: (hal.render_pipelines.read() + hal.compute_pipelines.read()) as i64,
+            bind_groups: hal.bind_groups.read() as i64,
+        }
+    }
+
+    pub fn values(&self) -> [i64; 4] {
+        [
+            self.textures,
+            self.buffers,
+            self.pipelines,
+            self.bind_groups,
+        ]
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.values() == [0; 4]
+    }
+
+    fn zip(self, other: Self, f: impl Fn(i64, i64) -> i64) -> Self {
+        Self {
+            textures: f(self.textures, other.textures),
+            buffers: f(self.buffers, other.buffers),
+            pipelines: f(self.pipelines, other.pipelines),
+            bind_groups: f(self.bind_groups, other.bind_groups),
+        }
+    }
+
+    /// `self - other`, kind by kind, never below zero.
+    pub fn saturating_sub(self, other: Self) -> Self {
+        self.zip(other, |a, b| (a - b).max(0))
+    }
+
+    fn add(self, other: Self) -> Self {
+        self.zip(other, |a, b| a + b)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Ledger {
+    created: ResourceCounts,
+    released: ResourceCounts,
+    owners: usize,
+}
+
+impl Ledger {
+    const EMPTY: Self = Self {
+        created: ResourceCounts::ZERO,
+        released: ResourceCounts::ZERO,
+        owners: 0,
+    };
+}
+
+static LEDGERS: Mutex<[Ledger; Subsystem::ALL.len()]> =
+    Mutex::new([Ledger::EMPTY; Subsystem::ALL.len()]);
+
+fn with_ledger<R>(subsystem: Subsystem, f: impl FnOnce(&mut Ledger) -> R) -> R {
+    let mut ledgers = LEDGERS.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut ledgers[subsystem.index()])
+}
+
+/// Credits a subsystem with the resources one of its objects created, and
+/// releases them again when the object (and this guard with it) drops.
+#[derive(Debug)]
+pub struct Tracked {
+    subsystem: Subsystem,
+    counts: ResourceCounts,
+}
+
+impl Tracked {
+    /// Credit `subsystem` with what `device` gained since `before` was read.
+    pub fn since(subsystem: Subsystem, device: &Device, before: ResourceCounts) -> Self {
+        Self::new(
+            subsystem,
+            ResourceCounts::read(device).saturating_sub(before),
+        )
+    }
+
+    fn new(subsystem: Subsystem, counts: ResourceCounts) -> Self {
+        with_ledger(subsystem, |l| {
+            l.created = l.created.add(counts);
+            l.owners += 1;
+        });
+        Self { subsystem, counts }
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        with_ledger(self.subsystem, |l| {
+            l.released = l.released.add(self.counts);
+            l.owners = l.owners.saturating_sub(1);
+        });
+    }
+}
+
+/// One subsystem's totals since startup.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SubsystemUsage {
+    pub subsystem: Subsystem,
+    /// Objects alive now (layers, particle systems, chains…).
+    pub owners: usize,
+    pub created: ResourceCounts,
+    pub released: ResourceCounts,
+}
+
+impl SubsystemUsage {
+    /// What its live objects hold.
+    pub fn held(&self) -> ResourceCounts {
+        self.created.saturating_sub(self.released)
+    }
+}
+
+/// Every subsystem's totals.
+pub fn usage() -> Vec<SubsystemUsage> {
+    let ledgers = LEDGERS.lock().unwrap_or_else(|e| e.into_inner());
+    Subsystem::ALL
+        .iter()
+        .map(|&subsystem| {
+            let l = ledgers[subsystem.index()];
+            SubsystemUsage {
+                subsystem,
+                owners: l.owners,
+                created: l.created,
+                released: l.released,
+            }
+        })
+        .collect()
+}
+
+const SAMPLE_EVERY: Duration = Duration::from_secs(1);
+/// Length of one window whose lowest count is compared with the next.
+const WINDOW_SECS: f64 = 60.0;
+/// Consecutive windows whose floors must each be higher to flag growth.
+const RISING_WINDOWS: usize = 4;
+/// A rise smaller than this across the windows is noise, not a leak.
+const MIN_RISE: i64 = 4;
+
+/// Samples the device's live counts once a second and spots kinds that keep growing.
+#[derive(Debug)]
+pub struct ResourceMonitor {
+    started: Instant,
+    last_sample: Option<Instant>,
+    /// (seconds since start, live counts), oldest first.
+    samples: VecDeque<(f64, ResourceCounts)>,
+    first: Option<ResourceCounts>,
+    /// Bytes of buffer and texture memory, when the backend reports them.
+    pub memory_bytes: i64,
+}
+
+impl Default for ResourceMonitor {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            last_sample: None,
+            samples: VecDeque::new(),
+            first: None,
+            memory_bytes: 0,
+        }
+    }
+}
+
+impl ResourceMonitor {
+    /// Take a sample if a second has passed since the last.
+    pub fn sample(&mut self, device: &Device) {
+        let now = Instant::now();
+        if self
+            .last_sample
+            .is_some_and(|t| now.duration_since(t) < SAMPLE_EVERY)
+        {
+            return;
+        }
+        self.last_sample = Some(now);
+        let hal = device.get_internal_counters().hal;
+        self.memory_bytes = (hal.buffer_memory.read() + hal.texture_memory.read()) as i64;
+        self.push(
+            now.duration_since(self.started).as_secs_f64(),
+            ResourceCounts::read(device),
+        );
+    }
+
+    fn push(&mut self, at: f64, counts: ResourceCounts) {
+        self.first.get_or_insert(counts);
+        self.samples.push_back((at, counts));
+        let keep = WINDOW_SECS * RISING_WINDOWS as f64;
+        while self.samples.front().is_some_and(|(t, _)| at - t > keep) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// The latest live counts.
+    pub fn live(&self) -> Option<ResourceCounts> {
+        self.samples.back().map(|(_, c)| *c)
+    }
+
+    /// Live counts now against the first sample.
+    pub fn since_start(&self) -> Option<ResourceCounts> {
+        Some(self.live()?.zip(self.first?, |now, first| now - first))
+    }
+
+    /// Whether the backend reports counts at all.
+    pub fn has_counters(&self) -> bool {
+        self.live().is_some_and(|c| !c.is_zero())
+    }
+
+    /// Per kind (in [`ResourceCounts::KINDS`] order), whether it has kept
+    /// growing: each of the last few minutes' lowest count above the one before.
+    pub fn growing(&self) -> [bool; 4] {
+        let samples: Vec<(f64, ResourceCounts)> = self.samples.iter().copied().collect();
+        std::array::from_fn(|kind| {
+            let series: Vec<(f64, i64)> = samples
+                .iter()
+                .map(|(t, c)| (*t, c.values()[kind]))
+                .collect();
+            rising_floor(&series)
+        })
+    }
+
+    /// Seconds of history the growth check can see.
+    pub fn history_secs(&self) -> f64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some((a, _)), Some((b, _))) => b - a,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Whether the lowest value in each of the last `RISING_WINDOWS` windows is
+/// higher than in the window before, by `MIN_RISE` overall. Needs the full
+/// span of history; a load spike never raises a floor, only what stays does.
+fn rising_floor(series: &[(f64, i64)]) -> bool {
+    let Some(&(end, _)) = series.last() else {
+        return false;
+    };
+    let start = end - WINDOW_SECS * RISING_WINDOWS as f64;
+    if series[0].0 > start + SAMPLE_EVERY.as_secs_f64() {
+        return false;
+    }
+    let mut floors = [i64::MAX; RISING_WINDOWS];
+    for &(t, v) in series {
+        if t < start {
+            continue;
+        }
+        let w = (((t - start) / WINDOW_SECS) as usize).min(RISING_WINDOWS - 1);
+        floors[w] = floors[w].min(v);
+    }
+    if floors.contains(&i64::MAX) {
+        return false;
+    }
+    floors.windows(2).all(|w| w[1] > w[0]) && floors[RISING_WINDOWS - 1] - floors[0] >= MIN_RISE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(f: impl Fn(f64) -> i64) -> Vec<(f64, i64)> {
+        (0..=240).map(|s| (s as f64, f(s as f64))).collect()
+    }
+
+    #[test]
+    fn a_climbing_floor_is_flagged_but_spikes_and_plateaus_are_not() {
+        // Every effect swap (each 30 s) leaves two textures behind.
+        assert!(rising_floor(&minutes(|t| 40 + 2 * (t / 30.0) as i64)));
+        // Loads spike and come back down.
+        assert!(!rising_floor(&minutes(|t| {
+            if (t as i64) % 30 < 3 { 80 } else { 40 }
+        })));
+        // Grew once, then held.
+        assert!(!rising_floor(&minutes(|t| if t < 90.0 { 40 } else { 60 })));
+        // Too little history.
+        assert!(!rising_floor(&minutes(|t| t as i64)[..100]));
+        // Rising, but by less than the noise floor.
+        assert!(!rising_floor(&minutes(|t| 40 + (t / 60.0) as i64)));
+    }
+
+    #[test]
+    fn guards_credit_and_release_their_subsystem() {
+        let counts = ResourceCounts {
+            textures: 3,
+            buffers: 2,
+            pipelines: 1,
+            bind_groups: 4,
+        };
+        let held = |s: Subsystem| usage()[s.index()].held();
+        let before = held(Subsystem::Generators);
+        let guard = Tracked::new(Subsystem::Generators, counts);
+        assert_eq!(held(Subsystem::Generators).saturating_sub(before), counts);
+        drop(guard);
+        assert_eq!(held(Subsystem::Generators), before);
+    }
+
+    #[test]
+    fn monitor_keeps_four_minutes_and_reports_the_change() {
+        let mut m = ResourceMonitor::default();
+        let at = |n: i64| ResourceCounts {
+            textures: n,
+            ..Default::default()
+        };
+        for s in 0..600 {
+            m.push(s as f64, at(10 + s / 100));
+        }
+        assert!(m.history_secs() <= 240.0);
+        assert_eq!(m.live(), Some(at(15)));
+        assert_eq!(m.since_start().unwrap().textures, 5);
+        assert!(m.has_counters());
+        assert!(!ResourceMonitor::default().has_counters());
+    }
+}
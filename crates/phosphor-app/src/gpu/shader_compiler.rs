@@ -6,6 +6,7 @@ use wgpu::{
 };
 
 use super::ShaderPipeline;
+use super::error_scope::ErrorScope;
 
 /// A request to compile a shader on a background thread.
 pub enum CompileRequest {
@@ -18,6 +19,8 @@ pub enum CompileRequest {
         format: TextureFormat,
         /// Multi-pass graph input count for this pass's bind-group layout (#1481).
         input_count: usize,
+        /// The pass's `override` constants (#2193).
+        constants: Vec<(String, f64)>,
     },
     /// Compile a compute shader into a compute pipeline.
     ComputeShader {
@@ -79,6 +82,7 @@ impl ShaderCompiler {
         device: &Device,
         format: TextureFormat,
         input_count: usize,
+        constants: Vec<(String, f64)>,
     ) {
         let _ = self.request_tx.send(CompileRequest::RenderPass {
             layer_idx,
@@ -87,6 +91,7 @@ impl ShaderCompiler {
             device: device.clone(),
             format,
             input_count,
+            constants,
         });
     }
 
@@ -121,9 +126,19 @@ impl ShaderCompiler {
                     device,
                     format,
                     input_count,
+                    constants,
                 } => {
-                    let result = ShaderPipeline::new(&device, format, &source, None, input_count)
-                        .map_err(|e| e.to_string());
+                    let constants: Vec<(&str, f64)> =
+                        constants.iter().map(|(n, v)| (n.as_str(), *v)).collect();
+                    let result = ShaderPipeline::with_constants(
+                        &device,
+                        format,
+                        &source,
+                        None,
+                        input_count,
+                        &constants,
+                    )
+                    .map_err(|e| e.to_string());
                     let _ = tx.send(CompileResult::RenderPass {
                         layer_idx,
                         pass_idx,
@@ -155,7 +170,7 @@ fn compile_compute_pipeline(
     source: &str,
     bind_group_layouts: &[BindGroupLayout],
 ) -> Result<ComputePipeline, String> {
-    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let scope = ErrorScope::push(device);
 
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
         label: Some("particle-compute-hotreload"),
@@ -178,7 +193,7 @@ fn compile_compute_pipeline(
         cache: None,
     });
 
-    if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+    if let Some(error) = scope.pop() {
         return Err(format!("{error}"));
     }
 
@@ -120,6 +120,69 @@ pub struct ShaderUniforms {
     // helper rather than by hand.
     pub band_pan: [f32; 8],
     // 32 bytes (432 total)
+
+    // ---- Motion field (#2154) ----
+    // Movement in the tracked media layer, from `gpu::motion`. 0.0 with no media layer.
+    pub motion: f32,
+    pub motion_x: f32,
+    pub motion_y: f32,
+    pub frame_diff: f32,
+    // 16 bytes (448 total)
+
+    // ---- Render seed (#2174) ----
+    // Global random seed, 0..65535 as a float. Fixed while deterministic mode is on so
+    // noise seeded from it repeats run-to-run; padded to keep the struct 16-byte aligned.
+    pub seed: f32,
+    // ---- Second input (#2217) ----
+    // Crowd level and onset from the mic input, in two of the seed's pad slots. 0.0 with
+    // no mic open.
+    pub mic_rms: f32,
+    pub mic_onset: f32,
+    pub _pad_seed: f32,
+    // 16 bytes (464 total)
+
+    // ---- Dynamics (#2218) ----
+    // Crest factor and the impact envelope from `audio::dynamics`, plus two pads.
+    pub crest: f32,
+    pub impact: f32,
+    pub _pad_dynamics: [f32; 2],
+    // 16 bytes (480 total)
+
+    // ---- Band envelopes (#2219) ----
+    // Fast and slow attack/release followers of bass, mid and high, timed in the audio
+    // panel, plus two pads.
+    pub env_bass_fast: f32,
+    pub env_bass_slow: f32,
+    pub env_mid_fast: f32,
+    pub env_mid_slow: f32,
+    pub env_high_fast: f32,
+    pub env_high_slow: f32,
+    pub _pad_env: [f32; 2],
+    // 32 bytes (512 total)
+}
+
+impl ShaderUniforms {
+    /// A copy with every audio feature zeroed, for a layer muted from the
+    /// audio (#2206). Clock, resolution, params, feedback, motion and the
+    /// seed carry over, so the effect keeps animating, just not to the music.
+    #[must_use]
+    pub fn without_audio(&self) -> Self {
+        Self {
+            time: self.time,
+            delta_time: self.delta_time,
+            resolution: self.resolution,
+            params: self.params,
+            feedback_decay: self.feedback_decay,
+            frame_index: self.frame_index,
+            scroll_phase: self.scroll_phase,
+            motion: self.motion,
+            motion_x: self.motion_x,
+            motion_y: self.motion_y,
+            frame_diff: self.frame_diff,
+            seed: self.seed,
+            ..Zeroable::zeroed()
+        }
+    }
 }
 
 pub struct UniformBuffer {
@@ -219,13 +282,15 @@ mod tests {
     use super::*;
 
     #[test]
-    fn shader_uniforms_size_432() {
+    fn shader_uniforms_size_512() {
         // 288 (through chroma) + 28 reserved audio scalars = 400, then the A13b per-band pan
         // block (#1801) appends 8 slots = 432. The #1629 "v3" bump added 13 scalars
         // (A14/A15/A16), absorbing the single pad the #1505 "v2" bump left at 352. Must stay a
         // multiple of 16 for the array<vec4f> members and match the WGSL PhosphorUniforms
-        // struct byte-for-byte.
-        assert_eq!(std::mem::size_of::<ShaderUniforms>(), 432);
+        // struct byte-for-byte. The #2154 motion block appends 4 scalars = 448, and the
+        // #2174 seed, the #2217 mic pair and a pad = 464, the #2218 dynamics block = 480, and
+        // the #2219 band envelopes = 512.
+        assert_eq!(std::mem::size_of::<ShaderUniforms>(), 512);
     }
 
     #[test]
@@ -240,4 +305,33 @@ mod tests {
         assert_eq!(u.frame_index, 0.0);
         assert_eq!(u.params, [0.0; 16]);
     }
+
+    #[test]
+    fn without_audio_keeps_the_clock_and_params() {
+        let mut u: ShaderUniforms = bytemuck::Zeroable::zeroed();
+        u.time = 12.5;
+        u.resolution = [1920.0, 1080.0];
+        u.params[3] = 0.7;
+        u.motion = 0.4;
+        u.seed = 42.0;
+        u.bass = 0.9;
+        u.beat = 1.0;
+        u.bpm = 0.4;
+        u.chroma[2] = 0.5;
+        u.band_pan[0] = -0.3;
+        u.timbre_flux = 0.2;
+
+        let muted = u.without_audio();
+        assert_eq!(muted.time, 12.5);
+        assert_eq!(muted.resolution, [1920.0, 1080.0]);
+        assert_eq!(muted.params[3], 0.7);
+        assert_eq!(muted.motion, 0.4);
+        assert_eq!(muted.seed, 42.0);
+        assert_eq!(muted.bass, 0.0);
+        assert_eq!(muted.beat, 0.0);
+        assert_eq!(muted.bpm, 0.0);
+        assert_eq!(muted.chroma, [0.0; 12]);
+        assert_eq!(muted.band_pan, [0.0; 8]);
+        assert_eq!(muted.timbre_flux, 0.0);
+    }
 }
@@ -133,6 +133,28 @@ impl Default for VolumetricParams {
 }
 
 impl VolumetricParams {
+    /// Names [`Self::set_param`] accepts, less the `jitter` alias.
+    pub const PARAM_NAMES: &[&str] = &[
+        "march_steps",
+        "absorption",
+        "detail_scale",
+        "detail_strength",
+        "density_threshold",
+        "volume_depth",
+        "density_scale",
+        "density_gain",
+        "cam_yaw",
+        "cam_pitch",
+        "cam_distance",
+        "cam_orbit_speed",
+        "fov",
+        "palette_hue",
+        "emission_gain",
+        "env_shape",
+        "jitter_amp",
+        "age_influence",
+    ];
+
     /// Set a named parameter (from OSC `/phosphor/volumetric/{name}` or UI).
     pub fn set_param(&mut self, name: &str, value: f32) {
         match name {
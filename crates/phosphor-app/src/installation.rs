@@ -0,0 +1,282 @@
+//! Installation mode (#2233): for a machine that runs a show unattended for
+//! weeks. The panels and keyboard shortcuts are locked away from visitors,
+//! capture devices that die are retried for as long as it takes, and an
+//! optional daily schedule blacks the output out (and renders at a trickle)
+//! outside opening hours.
+//!
+//! Wall-clock hours need the local UTC offset, which std doesn't expose. As
+//! with recording timestamps, it is asked of the OS — `date` or PowerShell —
+//! on a worker thread every few minutes, so a DST change lands within one.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+/// Seconds the output takes to fade out at closing time.
+const FADE_OUT_SECS: f32 = 10.0;
+/// Seconds to fade back in at opening time.
+const FADE_IN_SECS: f32 = 3.0;
+/// How often the local UTC offset is looked up again.
+const OFFSET_REFRESH: Duration = Duration::from_secs(600);
+/// Gap between attempts to restart a webcam that stopped.
+pub const WEBCAM_RETRY: Duration = Duration::from_secs(10);
+/// Frame interval once the output is fully dark outside opening hours.
+pub const CLOSED_FRAME_INTERVAL: Duration = Duration::from_secs(1);
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// Saved with the settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstallationConfig {
+    pub enabled: bool,
+    /// Black the output out outside `[open_at, close_at)`.
+    pub schedule: bool,
+    /// Minutes after local midnight.
+    pub open_at: u16,
+    /// Minutes after local midnight; earlier than `open_at` for hours that
+    /// run past midnight.
+    pub close_at: u16,
+}
+
+impl Default for InstallationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schedule: false,
+            open_at: 9 * 60,
+            close_at: 22 * 60,
+        }
+    }
+}
+
+impl InstallationConfig {
+    /// Whether `minute` (after local midnight) falls in opening hours. Equal
+    /// open and close times mean open around the clock.
+    pub fn is_open_at(&self, minute: u16) -> bool {
+        let (open, close) = (self.open_at, self.close_at);
+        if open == close {
+            true
+        } else if open < close {
+            (open..close).contains(&minute)
+        } else {
+            minute >= open || minute < close
+        }
+    }
+}
+
+/// `HH:MM` for a minute of the day.
+pub fn format_minute(minute: u16) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+/// Edges of the opening hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleEvent {
+    Closed,
+    Opened,
+}
+
+/// Tracks whether the schedule has the output closed, and the fade it drives.
+#[derive(Debug, Default)]
+pub struct ScheduleWatch {
+    closed: bool,
+    /// 0 = full output, 1 = blacked out.
+    fade: f32,
+}
+
+impl ScheduleWatch {
+    /// Advance one frame of `dt` seconds at local time `minute`. An unknown
+    /// time (`None`) keeps the output open. Reports the frame the output
+    /// closes or opens.
+    pub fn update(
+        &mut self,
+        config: &InstallationConfig,
+        minute: Option<u16>,
+        dt: f32,
+    ) -> Option<ScheduleEvent> {
+        let closed =
+            config.enabled && config.schedule && minute.is_some_and(|m| !config.is_open_at(m));
+        let event = match (self.closed, closed) {
+            (false, true) => Some(ScheduleEvent::Closed),
+            (true, false) => Some(ScheduleEvent::Opened),
+            _ => None,
+        };
+        self.closed = closed;
+        self.fade = if closed {
+            (self.fade + dt / FADE_OUT_SECS).min(1.0)
+        } else {
+            (self.fade - dt / FADE_IN_SECS).max(0.0)
+        };
+        event
+    }
+
+    /// How far the output is faded, 0 (not at all) to 1 (black).
+    pub fn fade(&self) -> f32 {
+        self.fade
+    }
+
+    /// Closed and fully dark, so frames can be few and far between.
+    pub fn is_dark(&self) -> bool {
+        self.closed && self.fade >= 1.0
+    }
+}
+
+/// Local time of day, from the system clock and a periodically refreshed
+/// UTC offset.
+#[derive(Default)]
+pub struct LocalClock {
+    /// Seconds east of UTC; `None` until the first lookup lands.
+    offset: Arc<Mutex<Option<i32>>>,
+    last_lookup: Option<Instant>,
+}
+
+impl LocalClock {
+    /// Minutes after local midnight, or `None` while the offset is unknown.
+    pub fn minute_of_day(&mut self) -> Option<u16> {
+        let now = Instant::now();
+        if self
+            .last_lookup
+            .is_none_or(|t| now.duration_since(t) >= OFFSET_REFRESH)
+        {
+            self.last_lookup = Some(now);
+            let cell = self.offset.clone();
+            thread::Builder::new()
+                .name("phosphor-utc-offset".into())
+                .spawn(move || {
+                    let found = lookup_utc_offset();
+                    let mut offset = cell.lock().unwrap_or_else(|e| e.into_inner());
+                    match found {
+                        Some(found) => *offset = Some(found),
+                        // Keep a previous answer; with none at all, fall back to UTC
+                        None if offset.is_none() => {
+                            log::warn!("Could not read the local time zone; schedule uses UTC");
+                            *offset = Some(0);
+                        }
+                        None => {}
+                    }
+                })
+                .ok();
+        }
+        let offset = (*self.offset.lock().unwrap_or_else(|e| e.into_inner()))?;
+        let unix = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .ok()?
+            .as_secs() as i64;
+        let minute = (unix + i64::from(offset)).div_euclid(60) % i64::from(MINUTES_PER_DAY);
+        Some(minute as u16)
+    }
+}
+
+#[cfg(not(windows))]
+fn lookup_utc_offset() -> Option<i32> {
+    let output = std::process::Command::new("date")
+        .arg("+%z")
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| parse_utc_offset(String::from_utf8_lossy(&output.stdout).trim()))?
+}
+
+#[cfg(windows)]
+fn lookup_utc_offset() -> Option<i32> {
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", "Get-Date -Format zzz"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| parse_utc_offset(String::from_utf8_lossy(&output.stdout).trim()))?
+}
+
+/// Seconds east of UTC from `+0130` (`date +%z`) or `+01:30` (PowerShell).
+fn parse_utc_offset(text: &str) -> Option<i32> {
+    let (sign, digits) = match text.as_bytes().first()? {
+        b'+' => (1, &text[1..]),
+        b'-' => (-1, &text[1..]),
+        _ => return None,
+    };
+    let digits = digits.replace(':', "");
+    if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i32 = digits[..2].parse().ok()?;
+    let minutes: i32 = digits[2..].parse().ok()?;
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduled(open_at: u16, close_at: u16) -> InstallationConfig {
+        InstallationConfig {
+            enabled: true,
+            schedule: true,
+            open_at,
+            close_at,
+        }
+    }
+
+    #[test]
+    fn opening_hours_can_run_past_midnight() {
+        let day = scheduled(9 * 60, 22 * 60);
+        assert!(!day.is_open_at(8 * 60 + 59));
+        assert!(day.is_open_at(9 * 60));
+        assert!(!day.is_open_at(22 * 60));
+
+        let night = scheduled(20 * 60, 2 * 60);
+        assert!(night.is_open_at(23 * 60));
+        assert!(night.is_open_at(60));
+        assert!(!night.is_open_at(2 * 60));
+        assert!(!night.is_open_at(12 * 60));
+
+        assert!(scheduled(0, 0).is_open_at(12 * 60));
+    }
+
+    #[test]
+    fn closing_fades_out_and_reports_each_edge_once() {
+        let cfg = scheduled(9 * 60, 22 * 60);
+        let mut watch = ScheduleWatch::default();
+        assert_eq!(watch.update(&cfg, Some(12 * 60), 1.0), None);
+        assert_eq!(
+            watch.update(&cfg, Some(22 * 60), 1.0),
+            Some(ScheduleEvent::Closed)
+        );
+        assert!(!watch.is_dark());
+        assert_eq!(watch.update(&cfg, Some(22 * 60), FADE_OUT_SECS), None);
+        assert!(watch.is_dark());
+        assert_eq!(
+            watch.update(&cfg, Some(9 * 60), 0.0),
+            Some(ScheduleEvent::Opened)
+        );
+        watch.update(&cfg, Some(9 * 60), FADE_IN_SECS);
+        assert_eq!(watch.fade(), 0.0);
+    }
+
+    #[test]
+    fn unknown_time_or_no_schedule_stays_open() {
+        let mut watch = ScheduleWatch::default();
+        assert_eq!(watch.update(&scheduled(9 * 60, 10 * 60), None, 1.0), None);
+        let unscheduled = InstallationConfig {
+            schedule: false,
+            ..scheduled(9 * 60, 10 * 60)
+        };
+        assert_eq!(watch.update(&unscheduled, Some(0), 1.0), None);
+        assert_eq!(watch.fade(), 0.0);
+    }
+
+    #[test]
+    fn utc_offsets_parse_in_both_spellings() {
+        assert_eq!(parse_utc_offset("+0130"), Some(5400));
+        assert_eq!(parse_utc_offset("-05:00"), Some(-18000));
+        assert_eq!(parse_utc_offset("+0000"), Some(0));
+        assert_eq!(parse_utc_offset("CET"), None);
+        assert_eq!(parse_utc_offset("+1"), None);
+    }
+}
@@ -1,23 +1,33 @@
 mod app;
 mod audio;
+mod benchmark;
 mod bindings;
 #[cfg(feature = "depth")]
 mod depth;
 mod download;
 mod effect;
+mod golden;
 mod gpu;
+mod installation;
 mod media;
 mod midi;
+mod monitor;
 #[cfg(feature = "ndi")]
 mod ndi;
 mod osc;
 mod params;
+mod paths;
+mod persist;
 mod preset;
 mod recording;
+mod rig;
 mod scene;
+mod schema;
+mod session_stats;
 mod settings;
 mod shader;
 mod ui;
+mod watchdog;
 mod web;
 
 use std::path::PathBuf;
@@ -26,32 +36,59 @@ use std::sync::Arc;
 use anyhow::Result;
 use crossbeam_channel::Receiver;
 use winit::application::ApplicationHandler;
-use winit::event::{ElementState, KeyEvent, WindowEvent};
-use winit::event_loop::{ActiveEventLoop, EventLoop};
-use winit::keyboard::{KeyCode, PhysicalKey};
-use winit::window::{Fullscreen, Icon, Window, WindowAttributes, WindowId};
+use winit::event::{ElementState, KeyEvent, StartCause, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
+use winit::keyboard::{KeyCode, ModifiersState, PhysicalKey};
+use winit::window::{Icon, Window, WindowAttributes, WindowId};
 
 use app::App;
 use effect::loader::EffectLoader;
+use gpu::envelope::OpacityEnvelope;
 use gpu::layer::BlendMode;
+use media::decoder::{IMAGE_EXTENSIONS, media_extensions, video_extensions};
+use media::thumbs::ThumbCache;
+use ui::file_browser::{self, FileBrowser, FileDialogKind};
 
 struct PhosphorApp {
     app: Option<App>,
     window: Option<Arc<Window>>,
     file_dialog_rx: Option<Receiver<PathBuf>>,
     obstacle_dialog_rx: Option<Receiver<PathBuf>>,
+    /// Multi-select dialog for adding clips to a media playlist: (layer, paths).
+    playlist_dialog_rx: Option<Receiver<(usize, Vec<PathBuf>)>>,
+    /// The built-in browser (#2244), when it stands in for the native dialog.
+    /// Its picks arrive on the receivers above like the native dialog's.
+    file_browser: Option<FileBrowser>,
+    /// Folder the built-in browser was last left in.
+    browse_dir: Option<PathBuf>,
+    /// The built-in browser's thumbnails (#2247), kept between openings.
+    thumbs: ThumbCache,
     /// Debounced param save: (effect_index, last_change_time)
     param_save_pending: Option<(usize, std::time::Instant)>,
+    /// `--profile <name>`, applied once the app is up (#2177).
+    startup_profile: Option<String>,
+    /// Pid of the instance owning the config dir when this one runs
+    /// read-only beside it (#2224).
+    config_owner: Option<u32>,
+    /// Held modifier keys, for the installation unlock combo (#2233).
+    modifiers: ModifiersState,
 }
 
 impl PhosphorApp {
-    fn new() -> Self {
+    fn new(startup_profile: Option<String>) -> Self {
         Self {
             app: None,
             window: None,
             file_dialog_rx: None,
             obstacle_dialog_rx: None,
+            playlist_dialog_rx: None,
+            file_browser: None,
+            browse_dir: None,
+            thumbs: ThumbCache::new(paths::config_dir().join("thumbnails")),
             param_save_pending: None,
+            startup_profile,
+            config_owner: None,
+            modifiers: ModifiersState::empty(),
         }
     }
 }
@@ -62,8 +99,13 @@ impl ApplicationHandler for PhosphorApp {
             return;
         }
 
+        let title = if persist::read_only() {
+            "Fosfora (read-only)"
+        } else {
+            "Fosfora"
+        };
         let mut attrs = WindowAttributes::default()
-            .with_title("Fosfora")
+            .with_title(title)
             .with_inner_size(winit::dpi::LogicalSize::new(1920, 1080));
 
         // Center window on primary monitor via initial position hint.
@@ -99,7 +141,39 @@ impl ApplicationHandler for PhosphorApp {
         self.window = Some(window.clone());
 
         match App::new(window) {
-            Ok(app) => {
+            Ok(mut app) => {
+                app.apply_startup();
+                app.apply_installation();
+                if let Some(name) = self.startup_profile.take() {
+                    if !app.apply_profile(&name) {
+                        let known: Vec<&str> = app
+                            .settings
+                            .profiles
+                            .iter()
+                            .map(|p| p.name.as_str())
+                            .collect();
+                        log::warn!(
+                            "--profile: no profile named '{name}' (saved: {})",
+                            if known.is_empty() {
+                                "none".to_string()
+                            } else {
+                                known.join(", ")
+                            }
+                        );
+                    }
+                }
+                if persist::read_only() {
+                    let owner = self
+                        .config_owner
+                        .map_or_else(String::new, |pid| format!(" (pid {pid})"));
+                    app.status_error = Some((
+                        format!(
+                            "Another instance{owner} uses this config — nothing will be saved. \
+                             Launch with --config-dir to keep separate settings."
+                        ),
+                        std::time::Instant::now(),
+                    ));
+                }
                 self.app = Some(app);
                 log::info!("Fosfora initialized");
             }
@@ -110,6 +184,14 @@ impl ApplicationHandler for PhosphorApp {
         }
     }
 
+    fn new_events(&mut self, _event_loop: &ActiveEventLoop, cause: StartCause) {
+        if let StartCause::ResumeTimeReached { .. } = cause
+            && let Some(app) = &self.app
+        {
+            app.window.request_redraw();
+        }
+    }
+
     fn window_event(
         &mut self,
         event_loop: &ActiveEventLoop,
@@ -123,13 +205,31 @@ impl ApplicationHandler for PhosphorApp {
         // Let egui handle events first
         let egui_consumed = app.egui_overlay.handle_event(&app.window, &event);
 
+        // Someone's at the controls: attract mode (#2239) stops or stays away
+        if matches!(
+            event,
+            WindowEvent::KeyboardInput { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::CursorMoved { .. }
+        ) {
+            app.note_input();
+        }
+
         match event {
             WindowEvent::CloseRequested => {
                 app.quit_requested = true;
             }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+            }
             WindowEvent::Resized(size) => {
                 app.resize(size.width, size.height);
             }
+            // Back from an outside editor: pick up saves the watcher missed (#2191)
+            WindowEvent::Focused(true) => {
+                app.rescan_shaders = true;
+            }
             WindowEvent::KeyboardInput {
                 event:
                     KeyEvent {
@@ -139,22 +239,46 @@ impl ApplicationHandler for PhosphorApp {
                     },
                 ..
             } if !egui_consumed || !app.egui_overlay.wants_keyboard() => {
+                // Installation mode (#2233): Ctrl+Shift+D unlocks the panels and
+                // shortcuts until the next launch, and locks them again.
+                if app.settings.installation.enabled
+                    && key == KeyCode::KeyD
+                    && self.modifiers.control_key()
+                    && self.modifiers.shift_key()
+                {
+                    app.installation_unlocked = !app.installation_unlocked;
+                    app.apply_installation();
+                    if app.installation_unlocked && !app.egui_overlay.visible {
+                        app.egui_overlay.toggle_visible();
+                    }
+                    return;
+                }
+                if app.installation_locked() {
+                    return;
+                }
                 match key {
                     KeyCode::Escape => {
-                        // Close binding matrix first, then shader editor, then quit
-                        if app.binding_matrix.open {
+                        // Close the file browser or binding matrix first, then the cue,
+                        // performance, uniform and debug view panels, then shader editor,
+                        // then quit
+                        if let Some(browser) = self.file_browser.take() {
+                            self.browse_dir = Some(browser.dir().to_path_buf());
+                        } else if app.binding_matrix.open {
                             app.binding_matrix.open = false;
+                        } else if app.cue_panel_open {
+                            app.cue_panel_open = false;
+                        } else if app.perf_panel_open {
+                            app.perf_panel_open = false;
+                        } else if app.uniforms_panel_open {
+                            app.uniforms_panel_open = false;
+                        } else if app.debug_view.open {
+                            app.debug_view.open = false;
                         } else if !app.shader_editor.open {
                             app.quit_requested = true;
                         }
                     }
                     KeyCode::KeyF => {
-                        let window = &app.window;
-                        if window.fullscreen().is_some() {
-                            window.set_fullscreen(None);
-                        } else {
-                            window.set_fullscreen(Some(Fullscreen::Borderless(None)));
-                        }
+                        app.toggle_fullscreen();
                     }
                     KeyCode::KeyD => {
                         app.egui_overlay.toggle_visible();
@@ -177,6 +301,42 @@ impl ApplicationHandler for PhosphorApp {
                         if !app.shader_editor.open => {
                             app.binding_matrix.open = !app.binding_matrix.open;
                         }
+                    KeyCode::KeyC
+                        if !app.shader_editor.open => {
+                            app.cue_panel_open = !app.cue_panel_open;
+                        }
+                    KeyCode::KeyU
+                        if !app.shader_editor.open => {
+                            app.uniforms_panel_open = !app.uniforms_panel_open;
+                        }
+                    KeyCode::KeyV
+                        if !app.shader_editor.open => {
+                            app.debug_view.open = !app.debug_view.open;
+                        }
+                    KeyCode::KeyP => {
+                        app.particle_burst();
+                        app.web
+                            .broadcast_trigger(crate::midi::types::TriggerAction::ParticleBurst);
+                    }
+                    KeyCode::KeyK => {
+                        app.master.blackout = !app.master.blackout;
+                    }
+                    KeyCode::KeyR
+                        // Start/stop recording the output (#2251), as the
+                        // panel's Record button does
+                        if !app.shader_editor.open => {
+                            app.egui_overlay.context().data_mut(|d| {
+                                d.insert_temp(egui::Id::new("recording_toggle"), true);
+                            });
+                        }
+                    KeyCode::F12 => {
+                        app.capture_screenshot();
+                    }
+                    KeyCode::KeyH => {
+                        app.freeze.trigger();
+                        app.web
+                            .broadcast_trigger(crate::midi::types::TriggerAction::FreezeFrame);
+                    }
                     KeyCode::BracketLeft => {
                         // Previous layer
                         let num = app.layer_stack.layers.len();
@@ -196,7 +356,15 @@ impl ApplicationHandler for PhosphorApp {
                             app.sync_active_layer();
                         }
                     }
-                    _ => {}
+                    _ => {
+                        // Number keys assigned to a preset (#2222)
+                        if let Some(id) = digit_key(key)
+                            .and_then(|d| app.settings.preset_keys.get(&d).copied())
+                            && let Some(idx) = app.preset_store.index_of_id(id)
+                        {
+                            app.load_preset(idx);
+                        }
+                    }
                 }
             }
             WindowEvent::RedrawRequested => {
@@ -207,7 +375,9 @@ impl ApplicationHandler for PhosphorApp {
                 let active_layer = app.layer_stack.active_layer;
 
                 // Auto-show panels after startup delay
-                app.egui_overlay.update_auto_show();
+                if !app.installation_locked() {
+                    app.egui_overlay.update_auto_show();
+                }
 
                 // Prepare egui frame
                 app.egui_overlay.begin_frame(&app.window);
@@ -228,6 +398,8 @@ impl ApplicationHandler for PhosphorApp {
                                 )
                             } else if ps.image_source.is_webcam() {
                                 ("webcam".to_string(), "webcam".to_string())
+                            } else if ps.image_source.layer_index().is_some() {
+                                ("layer".to_string(), String::new())
                             } else {
                                 ("static".to_string(), ps.def.emitter.image.clone())
                             };
@@ -254,18 +426,32 @@ impl ApplicationHandler for PhosphorApp {
                             crate::ui::panels::particle_panel::ParticleInfo {
                                 alive_count: ps.alive_count,
                                 max_count: ps.max_particles,
+                                particle_budget: ps.particle_budget,
+                                active_count: ps.active_particles(),
+                                count_ceiling: ps.max_particles, // set below
+                                budget_locked: ps.budget_locked(),
+                                auto_quality: app.settings.auto_particle_quality,
+                                quality_scale: app.particle_governor.scale(),
                                 emit_rate: ps.emit_rate,
                                 burst_on_beat: ps.burst_on_beat,
+                                trigger_burst: ps.def.trigger_burst,
+                                trigger_burst_quantize: ps.def.trigger_burst_quantize,
                                 lifetime: ps.def.lifetime,
                                 initial_speed: ps.def.initial_speed,
                                 initial_size: ps.def.initial_size,
                                 size_end: ps.def.size_end,
                                 drag: ps.def.drag,
                                 attraction_strength: ps.def.attraction_strength,
+                                bound_params: ps.def.params.keys().cloned().collect(),
                                 blend_mode: ps.blend_mode.clone(),
                                 has_flow_field: ps.def.flow_field,
                                 has_trails: ps.def.trail_length >= 2,
                                 trail_length: ps.def.trail_length,
+                                trail_width: ps.def.trail_width,
+                                trail_fade: ps.def.trail_fade,
+                                trails_supported: ps.def.splat.is_none(),
+                                depth_sort: ps.depth_sort_active(),
+                                depth_sort_supported: ps.depth_sort_supported(),
                                 has_interaction: ps.def.interaction,
                                 has_sprite: ps.sprite.is_some(),
                                 is_compute_raster: ps.is_compute_raster(),
@@ -283,6 +469,8 @@ impl ApplicationHandler for PhosphorApp {
                                 source_loading: false, // set below
                                 source_loading_name: String::new(),
                                 builtin_images: Vec::new(), // set below
+                                layer_source: ps.image_source.layer_index(),
+                                layer_choices: Vec::new(), // set below
                                 has_splat: ps.def.splat.is_some(),
                                 splat_sorted: ps.is_splat_sorted(),
                                 splat_sh_degree: ps.splat_sh_degree(),
@@ -391,9 +579,27 @@ impl ApplicationHandler for PhosphorApp {
                     if let Some(ref mut pi) = particle_info {
                         pi.source_loading = app.particle_source_loader.loading;
                         pi.source_loading_name = app.particle_source_loader.loading_name.clone();
+                        // Count slider tops out at the Max-quality count (#2156),
+                        // or the effect's own ceiling, within the device limit.
+                        if let Some(pd) = app
+                            .layer_stack
+                            .active()
+                            .and_then(|l| l.effect_index())
+                            .and_then(|i| app.effect_loader.effects.get(i))
+                            .and_then(|e| e.particles.as_ref())
+                        {
+                            let multiplier = crate::settings::ParticleQuality::Max.multiplier();
+                            let mut ceiling = (pd.max_count as f32 * multiplier) as u32;
+                            if pd.max_scaled_count > 0 {
+                                ceiling = ceiling.min(pd.max_scaled_count);
+                            }
+                            pi.count_ceiling =
+                                ceiling.max(pi.max_count).min(app.particle_count_limit());
+                        }
                         if pi.has_image_source {
                             pi.builtin_images =
                                 crate::gpu::particle::builtin_raster_images().to_vec();
+                            pi.layer_choices = app.layer_stack.layer_choices();
                         }
                         if pi.has_splat {
                             use crate::gpu::particle::splat_source;
@@ -449,6 +655,8 @@ impl ApplicationHandler for PhosphorApp {
                                     }
                                 };
                                 let depth_available = cfg!(feature = "depth");
+                                // Other layers a live layer obstacle can read (#2160)
+                                let layer_choices = app.layer_stack.layer_choices();
                                 let depth_model_downloaded = {
                                     #[cfg(feature = "depth")]
                                     {
@@ -512,6 +720,8 @@ impl ApplicationHandler for PhosphorApp {
                                         webcam_device_index: app.webcam_device_index,
                                         #[cfg(not(feature = "webcam"))]
                                         webcam_device_index: 0,
+                                        layer: ps.obstacle_layer_index(),
+                                        layer_choices,
                                     }
                                 } else {
                                     crate::ui::panels::obstacle_panel::ObstacleInfo {
@@ -537,6 +747,8 @@ impl ApplicationHandler for PhosphorApp {
                                         webcam_device_index: app.webcam_device_index,
                                         #[cfg(not(feature = "webcam"))]
                                         webcam_device_index: 0,
+                                        layer: None,
+                                        layer_choices,
                                     }
                                 }
                             });
@@ -564,6 +776,35 @@ impl ApplicationHandler for PhosphorApp {
                                 current_frame: m.current_frame,
                                 video_position_secs: m.position_secs(),
                                 video_duration_secs: m.duration_secs(),
+                                projection: m.projection,
+                                audio: m.transport.audio,
+                                #[cfg(feature = "video")]
+                                stream: m.stream_status().map(|(status, reconnects)| {
+                                    crate::ui::panels::media_panel::StreamInfo {
+                                        status: status.display_name(),
+                                        live: status == crate::media::stream::StreamStatus::Playing,
+                                        reconnects,
+                                    }
+                                }),
+                                #[cfg(not(feature = "video"))]
+                                stream: None,
+                                playlist: l.playlist.as_ref().map(|pl| {
+                                    crate::ui::panels::media_panel::PlaylistInfo {
+                                        clip_names: pl
+                                            .clips
+                                            .iter()
+                                            .map(|c| {
+                                                c.file_name()
+                                                    .map(|n| n.to_string_lossy().to_string())
+                                                    .unwrap_or_default()
+                                            })
+                                            .collect(),
+                                        current: pl.current_index(),
+                                        advance: pl.advance,
+                                        shuffle: pl.shuffle(),
+                                        loading: pl.pending.is_some(),
+                                    }
+                                }),
                             }
                         })
                     });
@@ -588,6 +829,18 @@ impl ApplicationHandler for PhosphorApp {
                                 #[cfg(not(feature = "webcam"))]
                                 device_index: 0,
                                 #[cfg(feature = "webcam")]
+                                available_modes: app.webcam_modes.clone(),
+                                #[cfg(not(feature = "webcam"))]
+                                available_modes: vec![],
+                                #[cfg(feature = "webcam")]
+                                mode: app.webcam_mode,
+                                #[cfg(not(feature = "webcam"))]
+                                mode: Default::default(),
+                                #[cfg(feature = "webcam")]
+                                fps: app.webcam_capture.as_ref().map_or(0, |c| c.fps()),
+                                #[cfg(not(feature = "webcam"))]
+                                fps: 0,
+                                #[cfg(feature = "webcam")]
                                 capture_running: app
                                     .webcam_capture
                                     .as_ref()
@@ -611,6 +864,7 @@ impl ApplicationHandler for PhosphorApp {
                             output_width: app.ndi.capture_dimensions().0,
                             output_height: app.ndi.capture_dimensions().1,
                             alpha_from_luma: app.ndi.config.alpha_from_luma,
+                            key_output: app.ndi.config.key_output,
                         };
                         ctx.data_mut(|d| {
                             d.insert_temp(egui::Id::new("ndi_info"), ndi_info);
@@ -646,6 +900,7 @@ impl ApplicationHandler for PhosphorApp {
                                 _ => None,
                             },
                             audio_active: app.audio.active,
+                            exporting_loop: app.recording.is_clip(),
                         };
                         ctx.data_mut(|d| {
                             d.insert_temp(egui::Id::new("recording_info"), rec_info);
@@ -659,6 +914,23 @@ impl ApplicationHandler for PhosphorApp {
                             d.insert_temp(egui::Id::new("preset_loading_state"), loading_state);
                         });
                     }
+                    // Monitors for the settings panel's launch monitor picker (#2232)
+                    if app.settings.startup.fullscreen {
+                        let monitors: Vec<String> = app
+                            .window
+                            .available_monitors()
+                            .filter_map(|m| m.name())
+                            .collect();
+                        ctx.data_mut(|d| d.insert_temp(egui::Id::new("monitor_names"), monitors));
+                    }
+                    // Hooks for the settings panel's compositor picker (#2252)
+                    {
+                        let hooks = (
+                            app.composite_hooks.clone(),
+                            app.composite_hook_error.clone(),
+                        );
+                        ctx.data_mut(|d| d.insert_temp(egui::Id::new("composite_hooks"), hooks));
+                    }
 
                     // Sync compile errors into shader editor
                     if app.shader_editor.open {
@@ -675,22 +947,42 @@ impl ApplicationHandler for PhosphorApp {
                     // panel below (which mutates it by &mut) marks the preset
                     // dirty, like the other panels do.
                     let vol_before = (app.volumetric_enabled, app.volumetric_params);
+                    let master_before = app.master;
+                    let freeze_before = app.freeze.params;
+                    let mut post_changed = false;
 
                     // Get active layer's param_store (mutable for MIDI badges)
                     let active_params = app.layer_stack.active_mut();
                     if let Some(layer) = active_params {
                         if !app.shader_editor.open {
+                            // Borrowed alongside param_store, so match the field
+                            // directly rather than through `as_generator_mut`.
+                            let generator = match &mut layer.content {
+                                crate::gpu::layer::LayerContent::Generator(g) => Some(&mut g.def),
+                                _ => None,
+                            };
+                            let generator_before = generator.as_deref().copied();
+                            let automation_before =
+                                (layer.automation.bars, layer.automation.lanes.clone());
+                            let sequences_before = layer.sequencer.sequences.clone();
+                            let mappings_before = layer.audio_mappings.clone();
+                            let post_before = layer.postprocess.clone();
                             crate::ui::panels::draw_panels(
                                 &ctx,
                                 app.egui_overlay.visible,
                                 &mut app.audio,
                                 &mut layer.param_store,
+                                &mut layer.automation,
+                                &mut layer.sequencer,
+                                &mut layer.audio_mappings,
                                 &shader_error,
                                 &app.uniforms,
                                 &app.effect_loader,
                                 &mut layer.postprocess,
                                 &mut app.volumetric_enabled,
                                 &mut app.volumetric_params,
+                                &mut app.master,
+                                &mut app.freeze,
                                 particle_count,
                                 &mut app.midi,
                                 &mut app.osc,
@@ -699,6 +991,7 @@ impl ApplicationHandler for PhosphorApp {
                                 &app.preset_store,
                                 &layer_infos,
                                 active_layer,
+                                generator,
                                 media_info,
                                 webcam_info,
                                 particle_info,
@@ -707,14 +1000,52 @@ impl ApplicationHandler for PhosphorApp {
                                 scene_info,
                                 &app.status_error,
                                 &app.settings,
+                                &app.effect_library,
                             );
+                            if layer.as_generator().map(|g| g.def) != generator_before {
+                                app.preset_store.mark_dirty();
+                            }
+                            let (bars_before, lanes_before) = automation_before;
+                            if layer.automation.bars != bars_before
+                                || layer.automation.lanes != lanes_before
+                                || layer.sequencer.sequences != sequences_before
+                            {
+                                app.preset_store.mark_dirty();
+                            }
+                            if layer.audio_mappings != mappings_before {
+                                layer.modulation.reset();
+                                app.preset_store.mark_dirty();
+                            }
+                            post_changed = layer.postprocess != post_before;
                         }
                         // Sync global postprocess enabled from layer
                         app.post_process.enabled = layer.postprocess.enabled;
                     }
+                    // Post edits land in settings when the chain lives there (#2234)
+                    if post_changed {
+                        app.post_edited();
+                    }
                     if (app.volumetric_enabled, app.volumetric_params) != vol_before {
                         app.preset_store.mark_dirty();
                     }
+                    // Master edits go through the app so they reach settings or
+                    // the preset, whichever owns the grade (#2165).
+                    let master_after = app.master;
+                    if master_after != master_before {
+                        app.master = master_before;
+                        app.master.blackout = master_after.blackout;
+                        app.set_dither(master_after.dither);
+                        app.set_calibration(master_after.calibration);
+                        if master_after.per_preset != master_before.per_preset {
+                            app.set_master_per_preset(master_after.per_preset);
+                        } else {
+                            app.set_master_params(master_after.params);
+                        }
+                    }
+                    if app.freeze.params != freeze_before {
+                        app.settings.freeze = app.freeze.params;
+                        app.settings.save();
+                    }
 
                     // Draw shader editor overlay (on top of everything)
                     crate::ui::panels::shader_editor::draw_shader_editor(
@@ -791,24 +1122,107 @@ impl ApplicationHandler for PhosphorApp {
                         );
                     }
 
-                    // GPU profiler panel
-                    #[cfg(feature = "profiling")]
-                    if app.egui_overlay.visible {
-                        egui::Window::new("GPU Profiler")
-                            .default_pos([10.0, 10.0])
-                            .default_size([250.0, 300.0])
-                            .resizable(true)
-                            .collapsible(true)
-                            .show(&ctx, |ui| {
-                                app.gpu_profiler.ui(ui);
-                            });
+                    // Cue panel window (#2221)
+                    if app.egui_overlay.visible && app.cue_panel_open {
+                        let cue_info = app.scene_info();
+                        crate::ui::panels::cue_panel::draw_cue_panel(
+                            &ctx,
+                            &mut app.cue_panel_open,
+                            &cue_info,
+                        );
+                    }
+
+                    // Uniform inspector (#2228) for the active layer
+                    if app.egui_overlay.visible && app.uniforms_panel_open {
+                        crate::ui::panels::uniforms_panel::draw_uniforms_panel(
+                            &ctx,
+                            &mut app.uniforms_panel_open,
+                            app.layer_stack.active(),
+                        );
+                    }
+
+                    // Debug view (#2229): pick the intermediate texture to show
+                    if app.egui_overlay.visible && app.debug_view.open {
+                        let textures = app.debug_texture_list();
+                        let layer_names: Vec<String> = app
+                            .layer_stack
+                            .layers
+                            .iter()
+                            .map(|l| l.custom_name.clone().unwrap_or_else(|| l.name.clone()))
+                            .collect();
+                        crate::ui::panels::debug_view_panel::draw_debug_view_panel(
+                            &ctx,
+                            &mut app.debug_view,
+                            &textures,
+                            &layer_names,
+                        );
+                    }
+
+                    // Performance panel (#2226), with the GPU profiler's timings
+                    // in profiling builds
+                    if app.egui_overlay.visible && app.perf_panel_open {
+                        #[cfg(feature = "profiling")]
+                        let profiler = &app.gpu_profiler;
+                        crate::ui::panels::perf_panel::draw_perf_panel(
+                            &ctx,
+                            &mut app.perf_panel_open,
+                            &app.resource_monitor,
+                            &app.session_stats,
+                            app.uniforms.delta_time,
+                            |ui| {
+                                #[cfg(feature = "profiling")]
+                                {
+                                    ui.separator();
+                                    profiler.ui(ui);
+                                }
+                                #[cfg(not(feature = "profiling"))]
+                                let _ = ui;
+                            },
+                        );
+                    }
+
+                    // First-run tour (#2242), drawn after the panels it points at
+                    if app.egui_overlay.visible
+                        && !app.shader_editor.open
+                        && let Some(step) = app.tour_step
+                    {
+                        use crate::ui::hints::{self, TourAction};
+                        match hints::draw_tour(&ctx, step) {
+                            Some(TourAction::Next) => {
+                                app.tour_step = Some(step + 1);
+                                hints::focus(&ctx, step + 1);
+                            }
+                            Some(TourAction::Back) => {
+                                app.tour_step = Some(step.saturating_sub(1));
+                                hints::focus(&ctx, step.saturating_sub(1));
+                            }
+                            Some(TourAction::Close) => {
+                                app.tour_step = None;
+                                if !app.settings.tour_seen {
+                                    app.settings.tour_seen = true;
+                                    app.settings.save();
+                                }
+                            }
+                            None => {}
+                        }
+                    }
+
+                    // Built-in file browser (#2244)
+                    if let Some(browser) = &mut self.file_browser
+                        && !browser.show(&ctx)
+                    {
+                        self.browse_dir = Some(browser.dir().to_path_buf());
+                        self.file_browser = None;
                     }
 
                     // Draw depth download confirmation modal
                     crate::ui::panels::obstacle_panel::draw_depth_download_modal(&ctx);
 
-                    // Draw quit confirmation dialog
-                    if app.quit_requested {
+                    // Draw quit confirmation dialog. An installation quits when
+                    // told to (#2233): nobody is there to answer a dialog.
+                    if app.quit_requested && app.settings.installation.enabled {
+                        ctx.data_mut(|d| d.insert_temp(egui::Id::new("confirm_quit"), true));
+                    } else if app.quit_requested {
                         // Track whether dialog was already showing last frame.
                         // On the first frame, the Esc that opened it is still in input state,
                         // so skip Esc-to-cancel until the next frame.
@@ -885,6 +1299,7 @@ impl ApplicationHandler for PhosphorApp {
                     // Flush any global binding edit still inside the 1s debounce
                     // window so it isn't lost on quit.
                     app.binding_bus.flush();
+                    app.save_session(true);
                     event_loop.exit();
                 }
 
@@ -894,27 +1309,30 @@ impl ApplicationHandler for PhosphorApp {
                     .context()
                     .data_mut(|d| d.remove_temp(egui::Id::new("open_shader_editor")));
                 if open_editor.is_some() {
-                    // Resolve active layer's shader path
-                    if let Some(idx) = app.layer_stack.active().and_then(|l| l.effect_index()) {
-                        if let Some(effect) = app.effect_loader.effects.get(idx).cloned() {
-                            let passes = effect.normalized_passes();
-                            if let Some(pass) = passes.first() {
-                                let path = app.effect_loader.resolve_shader_path(&pass.shader);
-                                if let Ok(content) = std::fs::read_to_string(&path) {
-                                    app.shader_editor.open_file(&effect.name, path, content);
-                                    // Load paired .pfx file for tab switching
-                                    if let Some(ref pfx_path) = effect.source_path {
-                                        if let Ok(pfx_content) = std::fs::read_to_string(pfx_path) {
-                                            app.shader_editor
-                                                .load_paired_pfx(pfx_path.clone(), pfx_content);
-                                        }
-                                    }
-                                } else {
-                                    log::error!("Could not read shader: {}", path.display());
-                                }
-                            }
-                        }
-                    }
+                    app.open_shader_editor();
+                }
+                let open_external: Option<String> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("open_external_editor")));
+                if let Some(shader) = open_external {
+                    app.open_in_external_editor(&shader);
+                }
+
+                // OSC address map (#2198)
+                let build_osc_map: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("osc_address_map")));
+                if build_osc_map.is_some() {
+                    app.osc.address_map = Some(app.osc_address_map());
+                }
+                let save_osc_map: Option<crate::osc::address_map::MapFormat> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("osc_address_map_save")));
+                if let Some(format) = save_osc_map {
+                    app.save_osc_address_map(format);
                 }
 
                 let save_editor: Option<bool> = app
@@ -922,39 +1340,23 @@ impl ApplicationHandler for PhosphorApp {
                     .context()
                     .data_mut(|d| d.remove_temp(egui::Id::new("shader_editor_save")));
                 if save_editor.is_some() {
-                    // Save the active tab
-                    if let Some(ref path) = app.shader_editor.file_path {
-                        match std::fs::write(path, &app.shader_editor.code) {
-                            Ok(()) => {
-                                app.shader_editor.disk_content = app.shader_editor.code.clone();
-                                log::info!("Saved shader: {}", path.display());
-                            }
-                            Err(e) => {
-                                log::error!("Failed to save shader: {e}");
-                                app.status_error =
-                                    Some((format!("Save failed: {e}"), std::time::Instant::now()));
-                            }
-                        }
-                    }
-                    // Also save the paired tab if it has unsaved changes
-                    if app.shader_editor.paired_is_dirty() {
-                        if let Some(ref paired_path) = app.shader_editor.paired_path {
-                            match std::fs::write(paired_path, &app.shader_editor.paired_content) {
-                                Ok(()) => {
-                                    app.shader_editor.paired_disk_content =
-                                        app.shader_editor.paired_content.clone();
-                                    log::info!("Saved paired file: {}", paired_path.display());
-                                }
-                                Err(e) => {
-                                    log::error!("Failed to save paired file: {e}");
-                                    app.status_error = Some((
-                                        format!("Save failed: {e}"),
-                                        std::time::Instant::now(),
-                                    ));
-                                }
-                            }
-                        }
-                    }
+                    app.save_shader_editor();
+                }
+                let auto_rollback: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("shader_auto_rollback")));
+                if let Some(on) = auto_rollback {
+                    app.settings.shader_auto_rollback = on;
+                    app.settings.save();
+                }
+                let keymap: Option<crate::ui::panels::editor_keymap::Keymap> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("shader_editor_keymap")));
+                if let Some(keymap) = keymap {
+                    app.settings.shader_editor_keymap = keymap;
+                    app.settings.save();
                 }
 
                 // Handle shader error dismiss from status bar
@@ -1080,6 +1482,87 @@ impl ApplicationHandler for PhosphorApp {
                     app.settings.save();
                 }
 
+                // Venue profiles from settings panel (#2177)
+                let apply_profile: Option<String> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("apply_profile")));
+                if let Some(name) = apply_profile {
+                    app.apply_profile(&name);
+                }
+                let save_profile: Option<String> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("save_profile")));
+                if let Some(name) = save_profile {
+                    let profile = app.capture_profile(&name);
+                    app.settings.active_profile = Some(profile.name.clone());
+                    app.settings.upsert_profile(profile);
+                    app.settings.save();
+                }
+                let delete_profile: Option<String> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("delete_profile")));
+                if let Some(name) = delete_profile {
+                    app.settings.remove_profile(&name);
+                    app.settings.save();
+                }
+
+                // Rig export/import from settings panel (#2203)
+                let export_rig: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("export_rig")));
+                if let Some(with_presets) = export_rig {
+                    app.export_rig(with_presets);
+                }
+                let import_rig: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("import_rig")));
+                if import_rig.is_some() {
+                    app.import_rig_dialog();
+                }
+                app.poll_rig_import();
+
+                // Param group fold (#2181)
+                let toggle_param_group: Option<(String, String)> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("toggle_param_group")));
+                if let Some((effect, group)) = toggle_param_group {
+                    app.settings.toggle_param_group(&effect, &group);
+                    app.settings.save();
+                }
+
+                // Effect library updates (#2180)
+                let library_config: Option<crate::effect::library::LibraryConfig> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_library_config")));
+                if let Some(config) = library_config {
+                    app.settings.effect_library = config;
+                    app.settings.save();
+                }
+                let library_check: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("library_check")));
+                if library_check.is_some() {
+                    app.effect_library.check(&app.settings.effect_library);
+                }
+                let library_install: Option<String> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("library_install")));
+                if let Some(pack) = library_install {
+                    app.effect_library.install(&pack);
+                }
+                if app.effect_library.poll().is_some() {
+                    app.rescan_effects();
+                }
+
                 // Handle particle quality change from settings panel
                 let set_quality: Option<crate::settings::ParticleQuality> = app
                     .egui_overlay
@@ -1109,6 +1592,88 @@ impl ApplicationHandler for PhosphorApp {
                     app.audio.set_band_scale(scale);
                 }
 
+                // Media cache budget from settings panel (#2150)
+                let set_cache_mb: Option<u32> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_media_cache_mb")));
+                if let Some(mb) = set_cache_mb {
+                    app.settings.media_cache_mb = mb;
+                    app.settings.save();
+                    app.media_cache.set_budget_mb(mb);
+                }
+                let set_file_dialog: Option<FileDialogKind> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_file_dialog")));
+                if let Some(kind) = set_file_dialog {
+                    app.settings.file_dialog = kind;
+                    app.settings.save();
+                }
+
+                // Watch folders from settings panel (#2246)
+                let set_watch_folders: Option<Vec<PathBuf>> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_watch_folders")));
+                if let Some(folders) = set_watch_folders {
+                    app.set_watch_folders(folders);
+                }
+
+                // Layer cap from settings panel (#2170); existing layers stay
+                let set_max_layers: Option<usize> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_max_layers")));
+                if let Some(n) = set_max_layers {
+                    app.settings.max_layers = n;
+                    app.settings.save();
+                }
+                let set_composite_hook: Option<Option<String>> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_composite_hook")));
+                if let Some(hook) = set_composite_hook {
+                    app.settings.composite_hook = hook;
+                    app.settings.save();
+                    app.apply_composite_hook();
+                }
+
+                // Deterministic mode (#2174); switching it on starts a fresh run
+                let set_deterministic: Option<crate::gpu::deterministic::DeterministicParams> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_deterministic")));
+                let restart_clock: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("restart_render_clock")));
+                if let Some(det) = set_deterministic {
+                    let switched_on = det.enabled && !app.settings.deterministic.enabled;
+                    app.settings.deterministic = det;
+                    app.settings.save();
+                    if switched_on {
+                        app.restart_render_clock();
+                    }
+                }
+                if restart_clock.is_some() {
+                    app.restart_render_clock();
+                }
+
+                let preload_next: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("preload_next_preset")));
+                if preload_next.is_some() {
+                    app.preload_next_preset();
+                }
+                app.egui_overlay.context().data_mut(|d| {
+                    d.insert_temp(
+                        egui::Id::new("media_preloading"),
+                        app.media_cache.is_preloading(),
+                    );
+                });
+
                 // Handle auto-reconnect toggle from settings panel (A9 #1460)
                 let set_auto_reconnect: Option<bool> = app
                     .egui_overlay
@@ -1117,7 +1682,124 @@ impl ApplicationHandler for PhosphorApp {
                 if let Some(on) = set_auto_reconnect {
                     app.settings.auto_reconnect = on;
                     app.settings.save();
-                    app.audio.set_auto_reconnect(on);
+                    app.audio
+                        .set_auto_reconnect(on || app.settings.installation.enabled);
+                }
+                let set_silence: Option<crate::audio::silence::SilenceConfig> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_silence")));
+                if let Some(silence) = set_silence {
+                    app.settings.silence = silence;
+                    app.settings.save();
+                }
+                let set_attract: Option<crate::preset::attract::AttractConfig> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_attract")));
+                if let Some(attract) = set_attract {
+                    app.settings.attract = attract;
+                    app.settings.save();
+                }
+                let start_tour: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("start_tour")));
+                if start_tour.is_some() {
+                    app.tour_step = Some(0);
+                    crate::ui::hints::focus(&app.egui_overlay.context(), 0);
+                }
+                let set_frame_guard: Option<crate::gpu::frame_guard::FrameGuardConfig> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_frame_guard")));
+                if let Some(frame_guard) = set_frame_guard {
+                    app.settings.frame_guard = frame_guard;
+                    app.settings.save();
+                }
+                let set_watchdog: Option<crate::watchdog::WatchdogConfig> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_watchdog")));
+                if let Some(watchdog) = set_watchdog {
+                    app.settings.watchdog = watchdog;
+                    app.settings.save();
+                }
+                let set_startup: Option<crate::settings::StartupConfig> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_startup")));
+                if let Some(startup) = set_startup {
+                    app.settings.startup = startup;
+                    app.settings.save();
+                    // Start the session file now rather than on the next tick
+                    app.save_session(true);
+                }
+                let set_post_source: Option<crate::effect::post_source::PostSource> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_post_source")));
+                if let Some(source) = set_post_source {
+                    app.set_post_source(source);
+                }
+                let save_post_default: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("save_post_default")));
+                if save_post_default.is_some() {
+                    app.save_post_default();
+                }
+                let set_installation: Option<crate::installation::InstallationConfig> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_installation")));
+                if let Some(installation) = set_installation {
+                    let turned_on = installation.enabled && !app.settings.installation.enabled;
+                    app.settings.installation = installation;
+                    app.settings.save();
+                    if turned_on {
+                        app.installation_unlocked = false;
+                        app.status_error = Some((
+                            "Installation mode on. Ctrl+Shift+D shows the panels again.".into(),
+                            std::time::Instant::now(),
+                        ));
+                    }
+                    app.apply_installation();
+                }
+                let start_snapshot: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("start_analysis_snapshot")));
+                if start_snapshot.is_some() {
+                    let path =
+                        crate::recording::encoder::build_snapshot_path(&app.recording.config);
+                    log::info!("Analysis snapshot: {}", path.display());
+                    app.audio.start_snapshot(path);
+                }
+                let export_set_report: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("export_set_report")));
+                if export_set_report.is_some() {
+                    let path =
+                        crate::recording::encoder::build_set_report_path(&app.recording.config);
+                    match app.preset_store.history.export(&path, &app.session_stats) {
+                        Ok(()) => log::info!("Set report: {}", path.display()),
+                        Err(e) => {
+                            app.status_error = Some((
+                                format!("Set report failed: {e}"),
+                                std::time::Instant::now(),
+                            ));
+                        }
+                    }
+                }
+                let set_external_editor: Option<String> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("set_external_editor")));
+                if let Some(command) = set_external_editor {
+                    app.settings.external_editor = command;
+                    app.settings.save();
                 }
 
                 // Persist A18 structure tuning after a slider release (#1510). The live value
@@ -1150,6 +1832,26 @@ impl ApplicationHandler for PhosphorApp {
                     app.settings.save();
                 }
 
+                // Persist the envelope times (#2219); the followers already run with them.
+                let envelopes_dirty: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("envelopes_dirty")));
+                if envelopes_dirty == Some(true) {
+                    app.settings.envelopes = app.audio.envelopes.config;
+                    app.settings.save();
+                }
+
+                // Persist input channel routing (#2216); the audio panel already applied it.
+                let channels_dirty: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("input_channels_dirty")));
+                if channels_dirty == Some(true) {
+                    app.settings.input_channels = app.audio.channel_maps();
+                    app.settings.save();
+                }
+
                 // Handle FFmpeg webcam toggle from settings panel
                 #[cfg(feature = "webcam")]
                 {
@@ -1207,6 +1909,18 @@ impl ApplicationHandler for PhosphorApp {
                     app.settings.save();
                 }
 
+                // Second input for crowd reactions (#2217); "" turns it off.
+                let switch_mic: Option<String> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("switch_mic_device")));
+                if let Some(device) = switch_mic {
+                    let device = (!device.is_empty()).then_some(device);
+                    app.audio.set_mic_device(device.as_deref());
+                    app.settings.mic_device = device;
+                    app.settings.save();
+                }
+
                 // Handle NDI signals from UI
                 #[cfg(feature = "ndi")]
                 {
@@ -1267,6 +1981,25 @@ impl ApplicationHandler for PhosphorApp {
                         app.ndi.config.save();
                     }
 
+                    let ndi_key: Option<u8> = app
+                        .egui_overlay
+                        .context()
+                        .data_mut(|d| d.remove_temp(egui::Id::new("ndi_key_output")));
+                    if let Some(key_u8) = ndi_key {
+                        app.ndi.config.key_output = crate::ndi::types::KeyOutput::ALL
+                            .get(key_u8 as usize)
+                            .copied()
+                            .unwrap_or_default();
+                        app.ndi.config.save();
+                        // The stream layout changes, so the senders start over
+                        app.ndi.restart(
+                            &app.gpu.device,
+                            app.gpu.format,
+                            app.gpu.surface_config.width,
+                            app.gpu.surface_config.height,
+                        );
+                    }
+
                     let ndi_restart: Option<bool> = app
                         .egui_overlay
                         .context()
@@ -1315,6 +2048,44 @@ impl ApplicationHandler for PhosphorApp {
                         }
                     }
 
+                    let clip_export: Option<bool> = app
+                        .egui_overlay
+                        .context()
+                        .data_mut(|d| d.remove_temp(egui::Id::new("clip_export")));
+                    if clip_export.is_some() {
+                        app.export_loop();
+                    }
+
+                    let clip_cancel: Option<bool> = app
+                        .egui_overlay
+                        .context()
+                        .data_mut(|d| d.remove_temp(egui::Id::new("clip_cancel")));
+                    if clip_cancel.is_some() {
+                        app.recording.cancel_clip();
+                    }
+
+                    let clip_seconds: Option<u32> = app
+                        .egui_overlay
+                        .context()
+                        .data_mut(|d| d.remove_temp(egui::Id::new("rec_clip_seconds_change")));
+                    if let Some(secs) = clip_seconds {
+                        app.recording.config.clip_seconds = secs;
+                        app.recording.config.save();
+                    }
+
+                    let clip_format: Option<u8> = app
+                        .egui_overlay
+                        .context()
+                        .data_mut(|d| d.remove_temp(egui::Id::new("rec_clip_format_change")));
+                    if let Some(idx) = clip_format {
+                        if let Some(&fmt) =
+                            crate::recording::types::ClipFormat::ALL.get(idx as usize)
+                        {
+                            app.recording.config.clip_format = fmt;
+                            app.recording.config.save();
+                        }
+                    }
+
                     let rec_codec: Option<u8> = app
                         .egui_overlay
                         .context()
@@ -1424,8 +2195,87 @@ impl ApplicationHandler for PhosphorApp {
                     .context()
                     .data_mut(|d| d.remove_temp(egui::Id::new("delete_preset")));
                 if let Some(idx) = delete_preset {
-                    if let Err(e) = app.preset_store.delete(idx) {
-                        log::error!("Failed to delete preset: {e}");
+                    let id = app.preset_store.presets.get(idx).map(|(_, p)| p.id);
+                    match app.preset_store.delete(idx) {
+                        Ok(()) => {
+                            // Its direct key and MIDI bindings go with it
+                            if let Some(id) = id {
+                                let before = app.settings.preset_keys.len();
+                                app.settings.preset_keys.retain(|_, v| *v != id);
+                                let mut changed = app.settings.preset_keys.len() != before;
+                                // ...and its end of the crossfade (#2250)
+                                let fade = &mut app.settings.preset_crossfade;
+                                for end in [&mut fade.a, &mut fade.b] {
+                                    if *end == Some(id) {
+                                        *end = None;
+                                        changed = true;
+                                    }
+                                }
+                                if changed {
+                                    app.settings.save();
+                                }
+                                let action = crate::midi::types::TriggerAction::LoadPreset(id);
+                                if app.midi.config.triggers.contains_key(&action) {
+                                    app.midi.clear_trigger_mapping(action);
+                                }
+                            }
+                        }
+                        Err(e) => log::error!("Failed to delete preset: {e}"),
+                    }
+                }
+                let assign_preset_key: Option<(u64, Option<u8>)> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("assign_preset_key")));
+                if let Some((id, digit)) = assign_preset_key {
+                    // One key per preset, one preset per key
+                    app.settings.preset_keys.retain(|_, v| *v != id);
+                    if let Some(digit) = digit {
+                        app.settings.preset_keys.insert(digit, id);
+                    }
+                    app.settings.save();
+                }
+                // Preset crossfade (#2250)
+                let crossfade_ends: Option<(Option<u64>, Option<u64>)> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("preset_crossfade_ends")));
+                if let Some((a, b)) = crossfade_ends {
+                    app.settings.preset_crossfade.a = a;
+                    app.settings.preset_crossfade.b = b;
+                    app.settings.save();
+                }
+                let crossfade_position: Option<f32> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("preset_crossfade_position")));
+                if let Some(position) = crossfade_position {
+                    app.set_preset_crossfade(position);
+                }
+                let rename_preset: Option<(usize, String)> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("rename_preset")));
+                if let Some((idx, new_name)) = rename_preset {
+                    let old_name = app.preset_store.presets.get(idx).map(|(n, _)| n.clone());
+                    match app.preset_store.rename(idx, &new_name) {
+                        Ok(new_idx) => {
+                            // Scenes cue presets by name; keep them pointing at it
+                            let new_name = app.preset_store.presets[new_idx].0.clone();
+                            if let Some(old_name) = old_name {
+                                app.scene_store.rename_preset(&old_name, &new_name);
+                                for cue in &mut app.timeline.cues {
+                                    if cue.preset_name == old_name {
+                                        cue.preset_name = new_name.clone();
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            log::error!("Failed to rename preset: {e}");
+                            app.status_error =
+                                Some((format!("Rename failed: {e}"), std::time::Instant::now()));
+                        }
                     }
                 }
                 let deselect_preset: Option<bool> = app
@@ -1495,6 +2345,7 @@ impl ApplicationHandler for PhosphorApp {
                         app.timeline.stop();
                         app.timeline.loop_mode = false;
                         app.timeline.advance_mode = crate::scene::types::AdvanceMode::Manual;
+                        app.timeline.quantize = crate::scene::types::GoQuantize::Immediate;
                     }
                     let set = crate::scene::types::SceneSet {
                         version: 1,
@@ -1502,6 +2353,7 @@ impl ApplicationHandler for PhosphorApp {
                         cues: app.timeline.cues.clone(),
                         loop_mode: app.timeline.loop_mode,
                         advance_mode: app.timeline.advance_mode,
+                        quantize: app.timeline.quantize,
                     };
                     if let Err(e) = app.scene_store.save(&name, set) {
                         log::error!("Failed to save scene: {e}");
@@ -1577,6 +2429,12 @@ impl ApplicationHandler for PhosphorApp {
                     .data_mut(|d| d.remove_temp(egui::Id::new("scene_add_cue")));
                 let mut scene_dirty = false;
                 if let Some(preset_name) = add_cue {
+                    let preset_id = app
+                        .preset_store
+                        .presets
+                        .iter()
+                        .find(|(n, _)| *n == preset_name)
+                        .map(|(_, p)| p.id);
                     // In Timer mode, default hold_secs so the timer can advance
                     let hold_secs = if matches!(
                         app.timeline.advance_mode,
@@ -1594,6 +2452,9 @@ impl ApplicationHandler for PhosphorApp {
                         label: None,
                         param_overrides: Vec::new(),
                         transition_beats: None,
+                        number: None,
+                        notes: String::new(),
+                        preset_id,
                     };
                     app.timeline.cues.push(cue);
                     scene_dirty = true;
@@ -1693,6 +2554,64 @@ impl ApplicationHandler for PhosphorApp {
                         scene_dirty = true;
                     }
                 }
+                // Cue panel edits (#2221): cue number, notes, Go quantization
+                let set_number: Option<(usize, String)> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("scene_set_cue_number")));
+                if let Some((idx, number)) = set_number {
+                    if let Some(cue) = app.timeline.cues.get_mut(idx) {
+                        let number = number.trim();
+                        cue.number = (!number.is_empty()).then(|| number.to_string());
+                        scene_dirty = true;
+                    }
+                }
+                let set_notes: Option<(usize, String)> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("scene_set_cue_notes")));
+                if let Some((idx, notes)) = set_notes {
+                    if let Some(cue) = app.timeline.cues.get_mut(idx) {
+                        cue.notes = notes;
+                        scene_dirty = true;
+                    }
+                }
+                let set_quantize: Option<crate::scene::types::GoQuantize> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("scene_set_quantize")));
+                if let Some(quantize) = set_quantize {
+                    app.timeline.quantize = quantize;
+                    scene_dirty = true;
+                }
+                let toggle_cue_panel: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("toggle_cue_panel")));
+                if toggle_cue_panel.is_some() {
+                    app.cue_panel_open = !app.cue_panel_open;
+                }
+                let toggle_perf_panel: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("toggle_perf_panel")));
+                if toggle_perf_panel.is_some() {
+                    app.perf_panel_open = !app.perf_panel_open;
+                }
+                let toggle_uniforms_panel: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("toggle_uniforms_panel")));
+                if toggle_uniforms_panel.is_some() {
+                    app.uniforms_panel_open = !app.uniforms_panel_open;
+                }
+                let toggle_debug_view: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("toggle_debug_view")));
+                if toggle_debug_view.is_some() {
+                    app.debug_view.open = !app.debug_view.open;
+                }
 
                 // Auto-save scene after any cue/timeline mutation
                 if scene_dirty {
@@ -1841,6 +2760,9 @@ impl ApplicationHandler for PhosphorApp {
                                                 }
                                             }
                                         }
+                                        ObstacleCommand::UseLayer(i) => {
+                                            ps.set_obstacle_layer(&app.gpu.device, i);
+                                        }
                                         ObstacleCommand::Clear => {
                                             ps.clear_obstacle(&app.gpu.device, &app.gpu.queue);
                                             // Stop depth thread when obstacle cleared
@@ -1861,7 +2783,7 @@ impl ApplicationHandler for PhosphorApp {
                     #[cfg(feature = "webcam")]
                     if obstacle_start_webcam {
                         if app.webcam_capture.is_none() {
-                            match app.start_webcam(app.webcam_device_index, Some((1280, 720))) {
+                            match app.start_webcam(app.webcam_device_index) {
                                 Ok(capture) => {
                                     app.webcam_capture = Some(capture);
                                 }
@@ -1875,7 +2797,7 @@ impl ApplicationHandler for PhosphorApp {
                     if obstacle_start_depth {
                         #[cfg(feature = "webcam")]
                         if app.webcam_capture.is_none() {
-                            match app.start_webcam(app.webcam_device_index, Some((1280, 720))) {
+                            match app.start_webcam(app.webcam_device_index) {
                                 Ok(capture) => {
                                     app.webcam_capture = Some(capture);
                                 }
@@ -1906,31 +2828,11 @@ impl ApplicationHandler for PhosphorApp {
                         d.remove_temp(egui::Id::new("switch_obstacle_webcam_device"))
                     });
                     if let Some(new_idx) = switch_obs_device {
-                        let old_idx = app.webcam_device_index;
-                        app.webcam_capture = None;
-                        match app.start_webcam(new_idx, Some((1280, 720))) {
-                            Ok(capture) => {
-                                app.webcam_capture = Some(capture);
-                                app.webcam_device_index = new_idx;
-                                app.settings.webcam_device = Some(new_idx);
-                                app.settings.save();
-                            }
-                            Err(e) => {
-                                log::error!("Failed to switch obstacle webcam device: {e}");
-                                app.status_error = Some((
-                                    format!("Camera failed: {e}"),
-                                    std::time::Instant::now(),
-                                ));
-                                // Restore previous capture
-                                match app.start_webcam(old_idx, Some((1280, 720))) {
-                                    Ok(capture) => {
-                                        app.webcam_capture = Some(capture);
-                                    }
-                                    Err(e2) => {
-                                        log::error!("Failed to restore previous webcam: {e2}");
-                                    }
-                                }
-                            }
+                        let mode = app.webcam_mode;
+                        if let Err(e) = app.restart_webcam(new_idx, mode) {
+                            log::error!("Failed to switch obstacle webcam device: {e}");
+                            app.status_error =
+                                Some((format!("Camera failed: {e}"), std::time::Instant::now()));
                         }
                     }
                 }
@@ -2029,45 +2931,79 @@ impl ApplicationHandler for PhosphorApp {
                 if add_media.is_some() && self.file_dialog_rx.is_none() {
                     let (tx, rx) = crossbeam_channel::bounded(1);
                     self.file_dialog_rx = Some(rx);
-                    std::thread::Builder::new()
-                        .name("file-dialog".into())
-                        .spawn(move || {
-                            #[allow(unused_mut)]
-                            let mut dialog = rfd::FileDialog::new();
-                            #[cfg(feature = "video")]
-                            {
-                                if crate::media::video::ffmpeg_available() {
-                                    let image_exts: &[&str] =
-                                        &["png", "jpg", "jpeg", "gif", "bmp", "webp"];
-                                    let video_exts = crate::media::decoder::VIDEO_EXTENSIONS;
-                                    let all: Vec<&str> = image_exts
-                                        .iter()
-                                        .copied()
-                                        .chain(video_exts.iter().copied())
-                                        .collect();
-                                    dialog = dialog
-                                        .add_filter("All Media", &all)
-                                        .add_filter("Images", image_exts)
-                                        .add_filter("Video", video_exts);
-                                } else {
-                                    dialog = dialog.add_filter(
-                                        "Images",
-                                        &["png", "jpg", "jpeg", "gif", "bmp", "webp"],
-                                    );
+                    if app.settings.file_dialog == FileDialogKind::BuiltIn {
+                        self.file_browser = Some(
+                            FileBrowser::new(
+                                "Add media layer",
+                                self.browse_dir
+                                    .clone()
+                                    .unwrap_or_else(file_browser::default_dir),
+                                media_extensions(),
+                                false,
+                                move |paths| {
+                                    if let Some(path) = paths.into_iter().next() {
+                                        let _ = tx.send(path);
+                                    }
+                                },
+                            )
+                            .with_thumbnails(self.thumbs.clone()),
+                        );
+                    } else {
+                        std::thread::Builder::new()
+                            .name("file-dialog".into())
+                            .spawn(move || {
+                                if let Some(path) = media_file_dialog().pick_file() {
+                                    let _ = tx.send(path);
                                 }
-                            }
-                            #[cfg(not(feature = "video"))]
-                            {
-                                dialog = dialog.add_filter(
-                                    "Images",
-                                    &["png", "jpg", "jpeg", "gif", "bmp", "webp"],
-                                );
-                            }
-                            if let Some(path) = dialog.pick_file() {
-                                let _ = tx.send(path);
-                            }
-                        })
-                        .ok();
+                            })
+                            .ok();
+                    }
+                }
+
+                // Add a generator layer (#2168)
+                let add_generator: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("add_generator_layer")));
+                if add_generator.is_some() {
+                    app.add_generator_layer();
+                    app.preset_store.mark_dirty();
+                }
+
+                // Add a network stream layer from the URL entry in the layer panel
+                #[cfg(feature = "video")]
+                {
+                    let add_stream: Option<String> = app
+                        .egui_overlay
+                        .context()
+                        .data_mut(|d| d.remove_temp(egui::Id::new("add_stream_layer")));
+                    if let Some(url) = add_stream {
+                        app.add_media_layer(std::path::PathBuf::from(url));
+                        app.preset_store.mark_dirty();
+                    }
+                }
+
+                // Recent media (#2245): a new layer from the "+ Media" menu,
+                // or a swap on the active media layer from its panel
+                let add_recent: Option<String> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("add_recent_media")));
+                if let Some(entry) = add_recent {
+                    app.add_media_layer(std::path::PathBuf::from(entry));
+                    app.preset_store.mark_dirty();
+                }
+                let open_recent: Option<String> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("media_open_recent")));
+                if let Some(entry) = open_recent {
+                    let path = std::path::PathBuf::from(entry);
+                    let active = app.layer_stack.active_layer;
+                    if app.load_media_on_layer(active, path.clone()) {
+                        app.remember_media(&path);
+                        app.preset_store.mark_dirty();
+                    }
                 }
 
                 // Drain file dialog result (non-blocking)
@@ -2088,6 +3024,102 @@ impl ApplicationHandler for PhosphorApp {
                     }
                 }
 
+                // Handle media playlist signals from the media panel
+                let playlist_signal: Option<crate::ui::panels::media_panel::PlaylistSignal> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("media_playlist")));
+                if let Some(signal) = playlist_signal {
+                    use crate::ui::panels::media_panel::PlaylistSignal;
+                    let active = app.layer_stack.active_layer;
+                    match signal {
+                        PlaylistSignal::AddClips => {
+                            if self.playlist_dialog_rx.is_none() {
+                                let (tx, rx) = crossbeam_channel::bounded(1);
+                                self.playlist_dialog_rx = Some(rx);
+                                if app.settings.file_dialog == FileDialogKind::BuiltIn {
+                                    self.file_browser = Some(
+                                        FileBrowser::new(
+                                            "Add clips",
+                                            self.browse_dir
+                                                .clone()
+                                                .unwrap_or_else(file_browser::default_dir),
+                                            media_extensions(),
+                                            true,
+                                            move |paths| {
+                                                let _ = tx.send((active, paths));
+                                            },
+                                        )
+                                        .with_thumbnails(self.thumbs.clone()),
+                                    );
+                                } else {
+                                    std::thread::Builder::new()
+                                        .name("file-dialog".into())
+                                        .spawn(move || {
+                                            if let Some(paths) = media_file_dialog().pick_files() {
+                                                let _ = tx.send((active, paths));
+                                            }
+                                        })
+                                        .ok();
+                                }
+                            }
+                        }
+                        PlaylistSignal::Step(forward) => app.playlist_step(active, forward),
+                        PlaylistSignal::Jump(clip) => app.playlist_jump(active, clip),
+                        PlaylistSignal::Remove(clip) => {
+                            if let Some(layer) = app.layer_stack.active_mut() {
+                                if let Some(pl) = layer.playlist.as_mut() {
+                                    pl.remove_clip(clip);
+                                    if pl.is_empty() {
+                                        layer.playlist = None;
+                                    }
+                                }
+                            }
+                        }
+                        PlaylistSignal::Move(clip, up) => {
+                            if let Some(pl) = app
+                                .layer_stack
+                                .active_mut()
+                                .and_then(|l| l.playlist.as_mut())
+                            {
+                                pl.move_clip(clip, up);
+                            }
+                        }
+                        PlaylistSignal::Shuffle(on) => {
+                            if let Some(pl) = app
+                                .layer_stack
+                                .active_mut()
+                                .and_then(|l| l.playlist.as_mut())
+                            {
+                                pl.set_shuffle(on);
+                            }
+                        }
+                        PlaylistSignal::Advance(mode) => {
+                            if let Some(pl) = app
+                                .layer_stack
+                                .active_mut()
+                                .and_then(|l| l.playlist.as_mut())
+                            {
+                                pl.advance = mode;
+                            }
+                        }
+                    }
+                    app.preset_store.mark_dirty();
+                }
+                if let Some(ref rx) = self.playlist_dialog_rx {
+                    match rx.try_recv() {
+                        Ok((layer_idx, paths)) => {
+                            app.playlist_add_clips(layer_idx, paths);
+                            app.preset_store.mark_dirty();
+                            self.playlist_dialog_rx = None;
+                        }
+                        Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                            self.playlist_dialog_rx = None;
+                        }
+                        Err(crossbeam_channel::TryRecvError::Empty) => {}
+                    }
+                }
+
                 // Handle webcam layer signals
                 #[cfg(feature = "webcam")]
                 {
@@ -2115,45 +3147,27 @@ impl ApplicationHandler for PhosphorApp {
                         .context()
                         .data_mut(|d| d.remove_temp(egui::Id::new("switch_webcam_device")));
                     if let Some(new_idx) = switch_device {
-                        let old_idx = app.webcam_device_index;
-                        // Stop old capture first (release device)
-                        app.webcam_capture = None;
-                        match app.start_webcam(new_idx, Some((1280, 720))) {
-                            Ok(capture) => {
-                                let (w, h) = capture.resolution();
-                                let device_name = capture.device_name().to_string();
-                                app.webcam_capture = Some(capture);
-                                app.webcam_device_index = new_idx;
-                                app.settings.webcam_device = Some(new_idx);
-                                app.settings.save();
-                                // Update active webcam layer
-                                if let Some(layer) = app.layer_stack.active_mut() {
-                                    if let Some(m) = layer.as_media_mut() {
-                                        if m.is_live() {
-                                            m.file_name = device_name;
-                                            m.media_width = w;
-                                            m.media_height = h;
-                                        }
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                log::error!("Failed to switch webcam device: {e}");
-                                app.status_error = Some((
-                                    format!("Camera failed: {e}"),
-                                    std::time::Instant::now(),
-                                ));
-                                // Restore previous capture
-                                match app.start_webcam(old_idx, Some((1280, 720))) {
-                                    Ok(capture) => {
-                                        app.webcam_capture = Some(capture);
-                                    }
-                                    Err(e2) => {
-                                        log::error!("Failed to restore previous webcam: {e2}");
-                                    }
-                                }
-                            }
+                        let mode = app.webcam_mode;
+                        if let Err(e) = app.restart_webcam(new_idx, mode) {
+                            log::error!("Failed to switch webcam device: {e}");
+                            app.status_error =
+                                Some((format!("Camera failed: {e}"), std::time::Instant::now()));
+                        }
+                    }
+
+                    // Switch capture resolution/fps (shared by all webcam layers)
+                    let switch_mode: Option<crate::media::types::WebcamMode> = app
+                        .egui_overlay
+                        .context()
+                        .data_mut(|d| d.remove_temp(egui::Id::new("switch_webcam_mode")));
+                    if let Some(mode) = switch_mode {
+                        let device_idx = app.webcam_device_index;
+                        if let Err(e) = app.restart_webcam(device_idx, mode) {
+                            log::error!("Failed to switch webcam mode: {e}");
+                            app.status_error =
+                                Some((format!("Camera failed: {e}"), std::time::Instant::now()));
                         }
+                        app.preset_store.mark_dirty();
                     }
 
                     let webcam_mirror: Option<bool> = app
@@ -2176,7 +3190,7 @@ impl ApplicationHandler for PhosphorApp {
                         // Stop capture and remove the active webcam layer
                         app.webcam_capture = None;
                         let active = app.layer_stack.active_layer;
-                        app.layer_stack.remove_layer(active);
+                        app.remove_layer(active);
                         app.sync_active_layer();
                         app.preset_store.mark_dirty();
                     }
@@ -2233,6 +3247,30 @@ impl ApplicationHandler for PhosphorApp {
                     }
                 }
 
+                let media_audio: Option<crate::media::types::AudioDrive> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("media_audio_drive")));
+                if let Some(audio) = media_audio {
+                    if let Some(layer) = app.layer_stack.active_mut() {
+                        if let Some(m) = layer.as_media_mut() {
+                            m.transport.audio = audio;
+                        }
+                    }
+                }
+
+                let media_projection: Option<crate::media::types::MediaProjection> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("media_projection")));
+                if let Some(projection) = media_projection {
+                    if let Some(layer) = app.layer_stack.active_mut() {
+                        if let Some(m) = layer.as_media_mut() {
+                            m.set_projection(&app.gpu.queue, projection);
+                        }
+                    }
+                }
+
                 // Handle media seek signal (video scrubber)
                 let media_seek: Option<f64> = app
                     .egui_overlay
@@ -2261,6 +3299,10 @@ impl ApplicationHandler for PhosphorApp {
                         ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_size")));
                     let drag: Option<f32> =
                         ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_drag")));
+                    let trigger_burst: Option<u32> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_trigger_burst")));
+                    let trigger_quantize: Option<bool> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_trigger_quantize")));
 
                     let mut particle_save_info: Option<(usize, gpu::particle::types::ParticleDef)> =
                         None;
@@ -2270,6 +3312,8 @@ impl ApplicationHandler for PhosphorApp {
                         || speed.is_some()
                         || size.is_some()
                         || drag.is_some()
+                        || trigger_burst.is_some()
+                        || trigger_quantize.is_some()
                     {
                         // Panel edit → preset is now unsaved (particle-sim edits
                         // round-trip via LayerPreset.particle_sim).
@@ -2298,6 +3342,12 @@ impl ApplicationHandler for PhosphorApp {
                                     if let Some(v) = drag {
                                         ps.def.drag = v;
                                     }
+                                    if let Some(v) = trigger_burst {
+                                        ps.def.trigger_burst = v;
+                                    }
+                                    if let Some(v) = trigger_quantize {
+                                        ps.def.trigger_burst_quantize = v;
+                                    }
                                     if let Some(idx) = eidx {
                                         particle_save_info = Some((idx, ps.def.clone()));
                                     }
@@ -2306,31 +3356,148 @@ impl ApplicationHandler for PhosphorApp {
                         }
                     }
 
+                    let fire_burst: Option<bool> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_fire_burst")));
+                    if fire_burst.is_some() {
+                        app.particle_burst();
+                    }
+
+                    // Particle count (#2156): live within the buffers; past them
+                    // only on release, since that reloads the effect.
+                    let count: Option<(u32, bool)> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_count")));
+                    if let Some((count, committed)) = count {
+                        let active = app.layer_stack.active_layer;
+                        let capacity = app
+                            .layer_stack
+                            .active()
+                            .and_then(|l| l.as_effect())
+                            .and_then(|e| e.pass_executor.particle_system.as_ref())
+                            .map_or(0, |ps| ps.max_particles);
+                        if committed || count <= capacity {
+                            app.set_particle_count(active, count);
+                        } else {
+                            app.set_particle_count(active, capacity);
+                        }
+                        app.preset_store.mark_dirty();
+                    }
+                    // Trails (#2159): width/fade live, length reallocates the ring.
+                    let trail_length: Option<u32> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_trail_length")));
+                    let trail_width: Option<f32> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_trail_width")));
+                    let trail_fade: Option<f32> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_trail_fade")));
+                    if trail_length.is_some() || trail_width.is_some() || trail_fade.is_some() {
+                        app.preset_store.mark_dirty();
+                        let device = app.gpu.device.clone();
+                        if let Some(layer) = app.layer_stack.active_mut() {
+                            let eidx = layer.effect_index();
+                            if let Some(ps) = layer
+                                .as_effect_mut()
+                                .and_then(|e| e.pass_executor.particle_system.as_mut())
+                            {
+                                ps.set_trails(
+                                    &device,
+                                    crate::gpu::GpuContext::hdr_format(),
+                                    trail_length.unwrap_or(ps.def.trail_length),
+                                    trail_width.unwrap_or(ps.def.trail_width),
+                                    trail_fade.unwrap_or(ps.def.trail_fade),
+                                );
+                                if let Some(idx) = eidx {
+                                    particle_save_info = Some((idx, ps.def.clone()));
+                                }
+                            }
+                        }
+                    }
+                    // Depth sort (#2163): builds the sort passes on first enable.
+                    let depth_sort: Option<bool> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_depth_sort")));
+                    if let Some(on) = depth_sort {
+                        app.preset_store.mark_dirty();
+                        let device = app.gpu.device.clone();
+                        if let Some(layer) = app.layer_stack.active_mut() {
+                            let eidx = layer.effect_index();
+                            if let Some(ps) = layer
+                                .as_effect_mut()
+                                .and_then(|e| e.pass_executor.particle_system.as_mut())
+                            {
+                                ps.set_depth_sort(&device, on);
+                                if let Some(idx) = eidx {
+                                    particle_save_info = Some((idx, ps.def.clone()));
+                                }
+                            }
+                        }
+                    }
+                    let auto_quality: Option<bool> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_auto_quality")));
+                    if let Some(on) = auto_quality {
+                        app.settings.auto_particle_quality = on;
+                        app.settings.save();
+                        if !on {
+                            app.particle_governor.reset();
+                        }
+                    }
+
                     // Persist particle changes to disk for user effects only.
                     // Built-in effects are runtime-only; users should create a
                     // preset or copy the effect to persist changes.
+                    // Written by the persist writer (#2201), which reports failures.
                     if let Some((idx, updated_def)) = particle_save_info {
-                        // Capture failures locally; can't touch app.status_error
-                        // while effect_loader is mutably borrowed.
-                        let mut save_err: Option<String> = None;
                         if let Some(effect) = app.effect_loader.effects.get_mut(idx) {
                             if !EffectLoader::is_builtin(effect) {
                                 effect.particles = Some(updated_def);
                                 if let Some(ref path) = effect.source_path {
                                     if let Ok(json) = serde_json::to_string_pretty(effect) {
-                                        if let Err(e) = std::fs::write(path, json) {
-                                            save_err = Some(e.to_string());
-                                        }
+                                        crate::persist::write(path.clone(), json);
                                     }
                                 }
                             }
                         }
-                        if let Some(e) = save_err {
-                            log::error!("Effect save failed: {e}");
-                            app.status_error = Some((
-                                format!("Effect save failed: {e}"),
-                                std::time::Instant::now(),
-                            ));
+                    }
+                }
+
+                // Write the active layer's edited audio mappings back to
+                // its .pfx (#2186); the override is dropped once on disk.
+                {
+                    let ctx = app.egui_overlay.context();
+                    let save_mappings: Option<usize> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("save_audio_mappings")));
+                    if let Some(idx) = save_mappings {
+                        let mappings = app
+                            .layer_stack
+                            .active()
+                            .and_then(|l| l.audio_mappings.clone());
+                        let mut result: Option<Result<(), String>> = None;
+                        if let (Some(mappings), Some(effect)) =
+                            (mappings, app.effect_loader.effects.get_mut(idx))
+                        {
+                            if !EffectLoader::is_builtin(effect) {
+                                if let Some(ref path) = effect.source_path {
+                                    effect.audio_mappings = mappings;
+                                    result = Some(
+                                        serde_json::to_string_pretty(effect)
+                                            .map(|json| crate::persist::write(path.clone(), json))
+                                            .map_err(|e| e.to_string()),
+                                    );
+                                }
+                            }
+                        }
+                        match result {
+                            Some(Ok(())) => {
+                                if let Some(layer) = app.layer_stack.active_mut() {
+                                    layer.audio_mappings = None;
+                                }
+                                app.preset_store.mark_dirty();
+                            }
+                            Some(Err(e)) => {
+                                log::error!("Effect save failed: {e}");
+                                app.status_error = Some((
+                                    format!("Effect save failed: {e}"),
+                                    std::time::Instant::now(),
+                                ));
+                            }
+                            None => {}
                         }
                     }
                 }
@@ -2358,6 +3525,14 @@ impl ApplicationHandler for PhosphorApp {
                         app.preset_store.mark_dirty();
                     }
 
+                    // Emit a text string (#2161), rendered off-thread like an image
+                    let text_source: Option<String> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_text_source")));
+                    if let Some(text) = text_source {
+                        app.particle_source_loader.load_text(&text);
+                        app.preset_store.mark_dirty();
+                    }
+
                     // Load video as particle source (dialog + decode on background thread)
                     #[cfg(feature = "video")]
                     {
@@ -2462,7 +3637,7 @@ impl ApplicationHandler for PhosphorApp {
                             ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_webcam")));
                         if use_webcam.is_some() {
                             if app.webcam_capture.is_none() {
-                                match app.start_webcam(app.webcam_device_index, Some((1280, 720))) {
+                                match app.start_webcam(app.webcam_device_index) {
                                     Ok(capture) => {
                                         app.webcam_capture = Some(capture);
                                     }
@@ -2487,6 +3662,33 @@ impl ApplicationHandler for PhosphorApp {
                         }
                     }
 
+                    // Emit from a live layer (#2155), or back to the image
+                    let layer_source: Option<Option<usize>> =
+                        ctx.data_mut(|d| d.remove_temp(egui::Id::new("particle_layer_source")));
+                    if let Some(source) = layer_source {
+                        let mut reload = None;
+                        if let Some(ps) = app
+                            .layer_stack
+                            .active_mut()
+                            .and_then(|l| l.as_effect_mut())
+                            .and_then(|e| e.pass_executor.particle_system.as_mut())
+                        {
+                            match source {
+                                Some(index) => ps.set_layer_source(&app.gpu.device, index),
+                                None if ps.image_source.layer_index().is_some() => {
+                                    ps.image_source =
+                                        crate::gpu::particle::ParticleImageSource::Static;
+                                    reload = ps.static_image_path.clone();
+                                }
+                                None => {}
+                            }
+                        }
+                        if let Some(path) = reload {
+                            app.particle_source_loader.load_image(path.into());
+                        }
+                        app.preset_store.mark_dirty();
+                    }
+
                     // Video transport controls
                     #[cfg(feature = "video")]
                     {
@@ -2931,10 +4133,22 @@ impl ApplicationHandler for PhosphorApp {
                                                 ps.video_path = None;
                                                 ps.static_image_path = Some(path.clone());
                                                 // Update emitter image name so UI selector reflects the change
-                                                let filename = std::path::Path::new(&path)
-                                                    .file_name()
-                                                    .map(|f| f.to_string_lossy().to_string())
-                                                    .unwrap_or_default();
+                                                // (a `text:` source keeps its whole string)
+                                                let filename =
+                                                    if crate::gpu::particle::vector_source::text_of(
+                                                        &path,
+                                                    )
+                                                    .is_some()
+                                                    {
+                                                        path.clone()
+                                                    } else {
+                                                        std::path::Path::new(&path)
+                                                            .file_name()
+                                                            .map(|f| {
+                                                                f.to_string_lossy().to_string()
+                                                            })
+                                                            .unwrap_or_default()
+                                                    };
                                                 ps.def.emitter.image = filename;
                                             }
                                             log::info!(
@@ -3073,7 +4287,7 @@ impl ApplicationHandler for PhosphorApp {
                     .context()
                     .data_mut(|d| d.remove_temp(egui::Id::new("remove_layer")));
                 if let Some(idx) = remove_layer {
-                    app.layer_stack.remove_layer(idx);
+                    app.remove_layer(idx);
                     app.sync_active_layer();
                     app.preset_store.mark_dirty();
                     #[cfg(feature = "webcam")]
@@ -3094,6 +4308,23 @@ impl ApplicationHandler for PhosphorApp {
                     app.preset_store.mark_dirty();
                 }
 
+                // Surprise me (#2251)
+                let surprise: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("surprise_me")));
+                if surprise.is_some() {
+                    app.surprise_me();
+                }
+                let surprise_config: Option<crate::preset::surprise::SurpriseConfig> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("surprise_config")));
+                if let Some(config) = surprise_config {
+                    app.settings.surprise = config;
+                    app.settings.save();
+                }
+
                 // Handle layer rename
                 let layer_rename: Option<(usize, Option<String>)> = app
                     .egui_overlay
@@ -3166,12 +4397,61 @@ impl ApplicationHandler for PhosphorApp {
                     }
                 }
 
+                let layer_trail: Option<f32> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("layer_trail")));
+                if let Some(trail) = layer_trail {
+                    if let Some(layer) = app.layer_stack.active_mut() {
+                        if !layer.locked {
+                            layer.trail = trail;
+                            app.preset_store.mark_dirty();
+                        }
+                    }
+                }
+
+                // Opacity envelope (#2249)
+                let layer_envelope: Option<OpacityEnvelope> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("layer_envelope")));
+                if let Some(env) = layer_envelope {
+                    if let Some(layer) = app.layer_stack.active_mut() {
+                        if !layer.locked {
+                            layer.envelope.apply(&env);
+                            app.preset_store.mark_dirty();
+                        }
+                    }
+                }
+                let envelope_restart: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("layer_envelope_restart")));
+                if envelope_restart.is_some() {
+                    if let Some(layer) = app.layer_stack.active_mut() {
+                        layer.envelope.restart();
+                    }
+                }
+
+                let layer_audio_mute: Option<bool> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("layer_audio_mute")));
+                if let Some(muted) = layer_audio_mute {
+                    if let Some(layer) = app.layer_stack.active_mut() {
+                        if !layer.locked {
+                            layer.audio_muted = muted;
+                            app.preset_store.mark_dirty();
+                        }
+                    }
+                }
+
                 let layer_move: Option<(usize, usize)> = app
                     .egui_overlay
                     .context()
                     .data_mut(|d| d.remove_temp(egui::Id::new("layer_move")));
                 if let Some((from, to)) = layer_move {
-                    app.layer_stack.move_layer(from, to);
+                    app.move_layer(from, to);
                     app.sync_active_layer();
                     app.preset_store.mark_dirty();
                 }
@@ -3188,6 +4468,13 @@ impl ApplicationHandler for PhosphorApp {
                         }
                     }
                 }
+                let resume: Option<usize> = app
+                    .egui_overlay
+                    .context()
+                    .data_mut(|d| d.remove_temp(egui::Id::new("layer_resume")));
+                if let Some(layer) = resume.and_then(|i| app.layer_stack.layers.get_mut(i)) {
+                    layer.suspended = None;
+                }
 
                 // Check if active layer params changed (marks preset dirty + schedules .pfx save)
                 if let Some(layer) = app.layer_stack.active_mut() {
@@ -3221,9 +4508,7 @@ impl ApplicationHandler for PhosphorApp {
                                 None
                             }
                         });
-                        // Capture failures locally; can't touch app.status_error
-                        // while effect_loader is mutably borrowed.
-                        let mut save_err: Option<String> = None;
+                        // Written by the persist writer (#2201), which reports failures.
                         if let (Some(values), Some(effect)) =
                             (values, app.effect_loader.effects.get_mut(eidx))
                         {
@@ -3234,9 +4519,7 @@ impl ApplicationHandler for PhosphorApp {
                             }
                             if let Some(ref path) = effect.source_path {
                                 if let Ok(json) = serde_json::to_string_pretty(effect) {
-                                    if let Err(e) = std::fs::write(path, &json) {
-                                        save_err = Some(e.to_string());
-                                    }
+                                    crate::persist::write(path.clone(), json.clone());
                                     // Update editor paired content if showing this .pfx
                                     if app.shader_editor.open {
                                         let pfx_canonical =
@@ -3247,25 +4530,13 @@ impl ApplicationHandler for PhosphorApp {
                                                 .unwrap_or_else(|_| paired.clone());
                                             if paired_canonical == pfx_canonical {
                                                 app.shader_editor.paired_content = json.clone();
-                                                // Only mirror disk content on a successful
-                                                // write, so the editor keeps showing unsaved
-                                                // state (and Ctrl+S retries) after a failure.
-                                                if save_err.is_none() {
-                                                    app.shader_editor.paired_disk_content = json;
-                                                }
+                                                app.shader_editor.paired_disk_content = json;
                                             }
                                         }
                                     }
                                 }
                             }
                         }
-                        if let Some(e) = save_err {
-                            log::error!("Effect save failed: {e}");
-                            app.status_error = Some((
-                                format!("Effect save failed: {e}"),
-                                std::time::Instant::now(),
-                            ));
-                        }
                     }
                 }
 
@@ -3275,13 +4546,17 @@ impl ApplicationHandler for PhosphorApp {
                 triggers.append(&mut app.pending_web_triggers);
                 for trigger in triggers {
                     use crate::midi::types::TriggerAction;
-                    // Build visible (non-hidden) effect indices for cycling
+                    // Followers mirror the ones the state doesn't show (#2204)
+                    app.web.broadcast_trigger(trigger);
+                    // Build visible (non-hidden, runnable) effect indices for cycling
                     let visible: Vec<usize> = app
                         .effect_loader
                         .effects
                         .iter()
                         .enumerate()
-                        .filter(|(_, e)| !e.hidden)
+                        .filter(|(_, e)| {
+                            !e.hidden && app.effect_loader.unsupported_reason(e).is_none()
+                        })
                         .map(|(i, _)| i)
                         .collect();
                     match trigger {
@@ -3311,7 +4586,7 @@ impl ApplicationHandler for PhosphorApp {
                                 layer.postprocess.enabled = app.post_process.enabled;
                             }
                         }
-                        TriggerAction::ToggleOverlay => {
+                        TriggerAction::ToggleOverlay if !app.installation_locked() => {
                             app.egui_overlay.toggle_visible();
                         }
                         TriggerAction::NextPreset if !app.preset_store.presets.is_empty() => {
@@ -3319,6 +4594,48 @@ impl ApplicationHandler for PhosphorApp {
                             let current = app.preset_store.current_preset.unwrap_or(0);
                             app.load_preset((current + 1) % num);
                         }
+                        TriggerAction::PreloadNextPreset => {
+                            app.preload_next_preset();
+                        }
+                        TriggerAction::ExportLoop => {
+                            app.export_loop();
+                        }
+                        TriggerAction::ParticleBurst => {
+                            app.particle_burst();
+                        }
+                        TriggerAction::Blackout => {
+                            app.master.blackout = !app.master.blackout;
+                        }
+                        TriggerAction::FreezeFrame => {
+                            app.freeze.trigger();
+                        }
+                        TriggerAction::ShaderCompile => {
+                            app.save_shader_editor();
+                        }
+                        TriggerAction::ShaderRevert => {
+                            app.shader_editor.revert();
+                        }
+                        TriggerAction::ToggleShaderEditor => {
+                            app.toggle_shader_editor();
+                        }
+                        TriggerAction::ResetNormalization => {
+                            app.audio.reset_normalization();
+                        }
+                        TriggerAction::FreezeNormalization => {
+                            let frozen = app.audio.normalization_frozen();
+                            app.audio.set_normalization_frozen(!frozen);
+                        }
+                        TriggerAction::SurpriseMe => {
+                            app.surprise_me();
+                        }
+                        TriggerAction::Screenshot => {
+                            app.capture_screenshot();
+                        }
+                        TriggerAction::LoadPreset(id) => {
+                            if let Some(idx) = app.preset_store.index_of_id(id) {
+                                app.load_preset(idx);
+                            }
+                        }
                         TriggerAction::PrevPreset if !app.preset_store.presets.is_empty() => {
                             let num = app.preset_store.presets.len();
                             let current = app.preset_store.current_preset.unwrap_or(0);
@@ -3370,10 +4687,16 @@ impl ApplicationHandler for PhosphorApp {
 
                 match app.render() {
                     Ok(()) => {}
+                    // A lost device is retried inside render (#2176); there
+                    // is no surface to reconfigure until that succeeds.
+                    Err(wgpu::SurfaceError::Lost)
+                        if app
+                            .gpu
+                            .device_lost
+                            .load(std::sync::atomic::Ordering::Relaxed) => {}
+                    // A monitor unplugged or changed resolution (#2231)
                     Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
-                        let w = app.gpu.surface_config.width;
-                        let h = app.gpu.surface_config.height;
-                        app.resize(w, h);
+                        app.recover_surface();
                     }
                     Err(wgpu::SurfaceError::OutOfMemory) => {
                         log::error!("Out of GPU memory");
@@ -3384,13 +4707,39 @@ impl ApplicationHandler for PhosphorApp {
                     }
                 }
 
-                app.window.request_redraw();
+                // Outside opening hours (#2233) a frame a second keeps the
+                // schedule and watchdogs running at a fraction of the load.
+                match app.idle_frame_interval() {
+                    Some(wait) => event_loop
+                        .set_control_flow(ControlFlow::WaitUntil(std::time::Instant::now() + wait)),
+                    None => {
+                        event_loop.set_control_flow(ControlFlow::Poll);
+                        app.window.request_redraw();
+                    }
+                }
             }
             _ => {}
         }
     }
 }
 
+/// The number a digit key types, for direct preset keys (#2222).
+fn digit_key(key: KeyCode) -> Option<u8> {
+    Some(match key {
+        KeyCode::Digit0 => 0,
+        KeyCode::Digit1 => 1,
+        KeyCode::Digit2 => 2,
+        KeyCode::Digit3 => 3,
+        KeyCode::Digit4 => 4,
+        KeyCode::Digit5 => 5,
+        KeyCode::Digit6 => 6,
+        KeyCode::Digit7 => 7,
+        KeyCode::Digit8 => 8,
+        KeyCode::Digit9 => 9,
+        _ => return None,
+    })
+}
+
 fn load_window_icon() -> Option<Icon> {
     let png_bytes = include_bytes!("../../../assets/icon/icon_256x256.png");
     let img = image::load_from_memory(png_bytes).ok()?.into_rgba8();
@@ -3398,11 +4747,50 @@ fn load_window_icon() -> Option<Icon> {
     Icon::from_rgba(img.into_raw(), w, h).ok()
 }
 
+/// Native file dialog filtered to the media formats this build can decode.
+fn media_file_dialog() -> rfd::FileDialog {
+    let dialog = rfd::FileDialog::new();
+    let video_exts = video_extensions();
+    if video_exts.is_empty() {
+        return dialog.add_filter("Images", IMAGE_EXTENSIONS);
+    }
+    dialog
+        .add_filter("All Media", &media_extensions())
+        .add_filter("Images", IMAGE_EXTENSIONS)
+        .add_filter("Video", video_exts)
+}
+
+/// `--profile <name>` or `--profile=<name>` (#2177).
+fn profile_arg(args: &[String]) -> Option<String> {
+    value_arg(args, "--profile")
+}
+
+/// `<flag> <value>` or `<flag>=<value>`.
+fn value_arg(args: &[String], flag: &str) -> Option<String> {
+    args.iter().enumerate().find_map(|(i, a)| {
+        if a == flag {
+            args.get(i + 1).cloned()
+        } else {
+            a.strip_prefix(flag)
+                .and_then(|rest| rest.strip_prefix('='))
+                .map(str::to_string)
+        }
+    })
+}
+
 fn main() -> Result<()> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
         .format_timestamp_millis()
         .init();
 
+    // Portable mode (#2178) and --config-dir (#2224) must be settled before
+    // any config is read
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    crate::paths::init(
+        args.iter().any(|a| a == "--portable"),
+        value_arg(&args, "--config-dir").map(std::path::PathBuf::from),
+    );
+
     // Suppress noisy ALSA/JACK C library messages on Linux (missing JACK server, OSS, dsnoop)
     crate::audio::capture::suppress_audio_library_noise();
 
@@ -3420,11 +4808,87 @@ fn main() -> Result<()> {
         }
     }
 
+    // --golden: render the bundled effects headless and compare them with
+    // the reference images (#2175); --bless rewrites the references
+    if std::env::args().any(|a| a == "--golden") {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let result = crate::golden::GoldenOptions::from_args(&args)
+            .and_then(|opts| crate::golden::run(&opts));
+        std::process::exit(match result {
+            Ok(true) => 0,
+            Ok(false) => 1,
+            Err(e) => {
+                eprintln!("--golden: {e:#}");
+                2
+            }
+        });
+    }
+
+    // --benchmark: time every bundled effect headless at a fixed resolution
+    // and write a report, optionally against an earlier one (#2225)
+    if std::env::args().any(|a| a == "--benchmark") {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let result = crate::benchmark::BenchmarkOptions::from_args(&args)
+            .and_then(|opts| crate::benchmark::run(&opts));
+        std::process::exit(match result {
+            Ok(true) => 0,
+            Ok(false) => 1,
+            Err(e) => {
+                eprintln!("--benchmark: {e:#}");
+                2
+            }
+        });
+    }
+
+    // --replay-beats: re-run beat detection over a recorded analysis
+    // snapshot with changed settings and report the difference (#2215)
+    if std::env::args().any(|a| a == "--replay-beats") {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let result = crate::audio::snapshot::ReplayOptions::from_args(&args)
+            .and_then(|opts| crate::audio::snapshot::run_replay(&opts));
+        std::process::exit(match result {
+            Ok(report) => {
+                println!("{report}");
+                0
+            }
+            Err(e) => {
+                eprintln!("--replay-beats: {e:#}");
+                2
+            }
+        });
+    }
+
+    // One instance owns a config dir; a second one sharing it runs without
+    // saving rather than clobbering the first one's files (#2224)
+    let config_dir = crate::paths::config_dir();
+    let (_instance_lock, config_owner) = match crate::paths::lock_instance(&config_dir) {
+        Ok(Some(lock)) => (Some(lock), None),
+        Ok(None) => {
+            let holder = crate::paths::instance_lock_holder(&config_dir);
+            log::warn!(
+                "{} is in use by another instance (pid {}); running read-only",
+                config_dir.display(),
+                holder.map_or_else(|| "?".to_string(), |p| p.to_string())
+            );
+            persist::set_read_only();
+            (None, holder)
+        }
+        Err(e) => {
+            log::warn!("Couldn't lock {}: {e}", config_dir.display());
+            (None, None)
+        }
+    };
+
     let event_loop = EventLoop::new()?;
     event_loop.set_control_flow(winit::event_loop::ControlFlow::Poll);
 
-    let mut app = PhosphorApp::new();
-    event_loop.run_app(&mut app)?;
+    let mut app = PhosphorApp::new(profile_arg(&args));
+    app.config_owner = config_owner;
+    let result = event_loop.run_app(&mut app);
 
+    // Saves still waiting out their debounce reach disk before exit (#2201)
+    drop(app);
+    persist::flush();
+    result?;
     Ok(())
 }
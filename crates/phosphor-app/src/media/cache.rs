@@ -0,0 +1,253 @@
+//! RAM cache of decoded media, keyed by path.
+//!
+//! Decoding is the slow part of a preset switch (a short video clip is
+//! seconds of ffmpeg work). The cache keeps decoded sources around under a
+//! byte budget with least-recently-used eviction, so returning to a look —
+//! or switching to one preloaded in the background — skips the decode.
+//!
+//! Hits hand out a shared handle to the cached frames; `MediaLayer` holds
+//! its source behind the same `Arc`, so a hit costs no copy.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::decoder::{MediaSource, load_media};
+
+/// Default budget when the setting has never been touched.
+pub const DEFAULT_CACHE_MB: u32 = 1024;
+
+/// Budget choices offered in Settings (0 = cache off).
+pub const CACHE_MB_CHOICES: &[u32] = &[0, 512, 1024, 2048, 4096, 8192];
+
+struct Entry {
+    source: Arc<MediaSource>,
+    bytes: usize,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<PathBuf, Entry>,
+    budget_bytes: usize,
+    used_bytes: usize,
+    tick: u64,
+}
+
+impl Inner {
+    fn evict_to(&mut self, target: usize) {
+        while self.used_bytes > target {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(p, _)| p.clone())
+            else {
+                break;
+            };
+            if let Some(e) = self.entries.remove(&oldest) {
+                self.used_bytes -= e.bytes;
+                log::debug!("Media cache evicted '{}'", oldest.display());
+            }
+        }
+    }
+}
+
+/// Shared handle; clones refer to the same cache.
+#[derive(Clone, Default)]
+pub struct MediaCache {
+    inner: Arc<Mutex<Inner>>,
+    /// Background preload jobs still running (for the UI indicator).
+    preloading: Arc<AtomicUsize>,
+}
+
+impl MediaCache {
+    pub fn new(budget_mb: u32) -> Self {
+        let cache = Self::default();
+        cache.set_budget_mb(budget_mb);
+        cache
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Change the budget, evicting least-recently-used entries to fit.
+    pub fn set_budget_mb(&self, mb: u32) {
+        let mut inner = self.lock();
+        inner.budget_bytes = mb as usize * 1024 * 1024;
+        let budget = inner.budget_bytes;
+        inner.evict_to(budget);
+    }
+
+    /// (bytes in use, entry count).
+    pub fn usage(&self) -> (usize, usize) {
+        let inner = self.lock();
+        (inner.used_bytes, inner.entries.len())
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.lock().entries.contains_key(path)
+    }
+
+    /// The cached source for `path`, if present.
+    pub fn get(&self, path: &Path) -> Option<Arc<MediaSource>> {
+        let mut inner = self.lock();
+        inner.tick += 1;
+        let tick = inner.tick;
+        let entry = inner.entries.get_mut(path)?;
+        entry.last_used = tick;
+        Some(entry.source.clone())
+    }
+
+    /// Cache a decoded source. Live and stream sources, and anything larger
+    /// than the whole budget, are not cached.
+    pub fn insert(&self, path: &Path, source: &Arc<MediaSource>) {
+        let Some(bytes) = cacheable_bytes(source) else {
+            return;
+        };
+        if bytes > self.lock().budget_bytes {
+            return;
+        }
+        let mut inner = self.lock();
+        inner.tick += 1;
+        let tick = inner.tick;
+        if let Some(old) = inner.entries.remove(path) {
+            inner.used_bytes -= old.bytes;
+        }
+        let target = inner.budget_bytes.saturating_sub(bytes);
+        inner.evict_to(target);
+        inner.used_bytes += bytes;
+        inner.entries.insert(
+            path.to_path_buf(),
+            Entry {
+                source: source.clone(),
+                bytes,
+                last_used: tick,
+            },
+        );
+    }
+
+    /// Decode `path`, going through the cache. Blocking — call off the
+    /// render thread.
+    pub fn load(&self, path: &Path) -> Result<Arc<MediaSource>, String> {
+        if let Some(source) = self.get(path) {
+            log::debug!("Media cache hit: {}", path.display());
+            return Ok(source);
+        }
+        let source = Arc::new(load_media(path)?);
+        self.insert(path, &source);
+        Ok(source)
+    }
+
+    /// Decode `paths` into the cache on a background thread, skipping ones
+    /// already cached.
+    pub fn preload(&self, paths: Vec<PathBuf>) {
+        let paths: Vec<PathBuf> = paths.into_iter().filter(|p| !self.contains(p)).collect();
+        if paths.is_empty() {
+            return;
+        }
+        let cache = self.clone();
+        self.preloading.fetch_add(1, Ordering::Relaxed);
+        let spawned = std::thread::Builder::new()
+            .name("media-preload".into())
+            .spawn(move || {
+                for path in &paths {
+                    match load_media(path) {
+                        Ok(source) => cache.insert(path, &Arc::new(source)),
+                        Err(e) => log::warn!("Preload of '{}' failed: {e}", path.display()),
+                    }
+                }
+                let (bytes, entries) = cache.usage();
+                log::info!(
+                    "Preloaded {} media file(s); cache holds {entries} ({} MB)",
+                    paths.len(),
+                    bytes / (1024 * 1024)
+                );
+                cache.preloading.fetch_sub(1, Ordering::Relaxed);
+            });
+        if spawned.is_err() {
+            self.preloading.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_preloading(&self) -> bool {
+        self.preloading.load(Ordering::Relaxed) > 0
+    }
+}
+
+/// RAM held by a decoded source, or None for sources that can't be cached.
+fn cacheable_bytes(source: &MediaSource) -> Option<usize> {
+    match source {
+        MediaSource::Static(f) => Some(f.data.len()),
+        MediaSource::Animated { frames, .. } => Some(frames.iter().map(|f| f.data.len()).sum()),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::types::DecodedFrame;
+
+    fn image(bytes: usize) -> Arc<MediaSource> {
+        Arc::new(MediaSource::Static(DecodedFrame {
+            data: vec![0; bytes],
+            width: 1,
+            height: 1,
+        }))
+    }
+
+    fn cache_with_budget_bytes(bytes: usize) -> MediaCache {
+        let cache = MediaCache::default();
+        cache.lock().budget_bytes = bytes;
+        cache
+    }
+
+    #[test]
+    fn hit_shares_cached_source() {
+        let cache = cache_with_budget_bytes(100);
+        let source = image(10);
+        cache.insert(Path::new("a.png"), &source);
+        let hit = cache.get(Path::new("a.png")).unwrap();
+        assert!(Arc::ptr_eq(&hit, &source));
+        assert!(cache.get(Path::new("b.png")).is_none());
+        assert_eq!(cache.usage(), (10, 1));
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = cache_with_budget_bytes(30);
+        cache.insert(Path::new("a.png"), &image(10));
+        cache.insert(Path::new("b.png"), &image(10));
+        cache.insert(Path::new("c.png"), &image(10));
+        // Touch a so b is the oldest
+        cache.get(Path::new("a.png"));
+        cache.insert(Path::new("d.png"), &image(10));
+        assert!(cache.contains(Path::new("a.png")));
+        assert!(!cache.contains(Path::new("b.png")));
+        assert_eq!(cache.usage(), (30, 3));
+    }
+
+    #[test]
+    fn oversized_and_disabled_are_not_cached() {
+        let cache = cache_with_budget_bytes(5);
+        cache.insert(Path::new("big.png"), &image(10));
+        assert_eq!(cache.usage(), (0, 0));
+        let off = MediaCache::new(0);
+        off.insert(Path::new("a.png"), &image(1));
+        assert!(!off.contains(Path::new("a.png")));
+    }
+
+    #[test]
+    fn shrinking_budget_evicts() {
+        let cache = cache_with_budget_bytes(usize::MAX);
+        cache.insert(Path::new("a.png"), &image(1024 * 1024));
+        cache.insert(Path::new("b.png"), &image(1024 * 1024));
+        cache.set_budget_mb(1);
+        assert_eq!(cache.usage().1, 1);
+        assert!(cache.contains(Path::new("b.png")));
+    }
+}
@@ -4,6 +4,7 @@ use super::types::DecodedFrame;
 
 /// Decoded media source: either a static image or animated frames.
 /// Video files are pre-decoded to Animated (same as GIF), enabling instant random access.
+#[derive(Clone)]
 pub enum MediaSource {
     /// Single static image.
     Static(DecodedFrame),
@@ -18,6 +19,10 @@ pub enum MediaSource {
     /// Live webcam feed — frames arrive from capture thread, not stored here.
     #[cfg(feature = "webcam")]
     Live { width: u32, height: u32 },
+    /// Network stream (RTSP/SRT/HLS/HTTP) — frames arrive from the layer's own
+    /// reader thread at `stream::STREAM_SIZE`.
+    #[cfg(feature = "video")]
+    Stream { url: String },
 }
 
 impl MediaSource {
@@ -27,6 +32,8 @@ impl MediaSource {
             MediaSource::Animated { frames, .. } => frames.len(),
             #[cfg(feature = "webcam")]
             MediaSource::Live { .. } => 1,
+            #[cfg(feature = "video")]
+            MediaSource::Stream { .. } => 1,
         }
     }
 
@@ -50,6 +57,14 @@ impl MediaSource {
         false
     }
 
+    pub fn is_stream(&self) -> bool {
+        #[cfg(feature = "video")]
+        if let MediaSource::Stream { .. } = self {
+            return true;
+        }
+        false
+    }
+
     /// Get frame dimensions.
     pub fn dimensions(&self) -> (u32, u32) {
         match self {
@@ -59,16 +74,61 @@ impl MediaSource {
             }
             #[cfg(feature = "webcam")]
             MediaSource::Live { width, height } => (*width, *height),
+            #[cfg(feature = "video")]
+            MediaSource::Stream { .. } => super::stream::STREAM_SIZE,
         }
     }
 }
 
+/// URL schemes opened as network streams rather than files.
+pub const STREAM_SCHEMES: &[&str] = &[
+    "rtsp", "rtsps", "rtmp", "rtmps", "srt", "udp", "rtp", "http", "https",
+];
+
+/// True if `path` is a network stream URL (`rtsp://…`, `srt://…`, …).
+pub fn is_stream_url(path: &str) -> bool {
+    path.split_once("://").is_some_and(|(scheme, _)| {
+        STREAM_SCHEMES
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(scheme))
+    })
+}
+
 /// Video file extensions.
 #[cfg(feature = "video")]
 pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "avi", "mkv", "webm", "m4v", "flv"];
 
+/// Image file extensions.
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp"];
+
+/// Video formats, when this build can decode them.
+pub fn video_extensions() -> &'static [&'static str] {
+    #[cfg(feature = "video")]
+    if crate::media::video::ffmpeg_available() {
+        return VIDEO_EXTENSIONS;
+    }
+    &[]
+}
+
+/// Every media format this build can decode.
+pub fn media_extensions() -> Vec<&'static str> {
+    IMAGE_EXTENSIONS
+        .iter()
+        .chain(video_extensions())
+        .copied()
+        .collect()
+}
+
 /// Load an image or animation from a file path.
 pub fn load_media(path: &Path) -> Result<MediaSource, String> {
+    let path_str = path.to_string_lossy();
+    if is_stream_url(&path_str) {
+        #[cfg(feature = "video")]
+        return load_stream(&path_str);
+        #[cfg(not(feature = "video"))]
+        return Err("Network streams need a build with the `video` feature".to_string());
+    }
+
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
@@ -87,6 +147,18 @@ pub fn load_media(path: &Path) -> Result<MediaSource, String> {
     }
 }
 
+/// Open a network stream. Nothing is fetched here — the layer's reader thread
+/// connects (and reconnects) on its own, so this never blocks on the network.
+#[cfg(feature = "video")]
+fn load_stream(url: &str) -> Result<MediaSource, String> {
+    if !super::video::ffmpeg_available() {
+        return Err("ffmpeg/ffprobe not found on PATH".to_string());
+    }
+    Ok(MediaSource::Stream {
+        url: url.to_string(),
+    })
+}
+
 /// Load a video file by pre-decoding all frames via ffmpeg.
 #[cfg(feature = "video")]
 fn load_video(path: &Path) -> Result<MediaSource, String> {
@@ -299,3 +371,18 @@ fn rgb_to_rgba(rgb: &[u8]) -> Vec<u8> {
     }
     rgba
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_urls_are_detected_by_scheme() {
+        assert!(is_stream_url("rtsp://192.168.1.20:554/stream1"));
+        assert!(is_stream_url("SRT://0.0.0.0:9000?mode=listener"));
+        assert!(is_stream_url("https://cdn.example/live/index.m3u8"));
+        assert!(!is_stream_url("/home/vj/clips/loop.mp4"));
+        assert!(!is_stream_url("C:\\clips\\loop.mp4"));
+        assert!(!is_stream_url("file:///home/vj/loop.mp4"));
+    }
+}
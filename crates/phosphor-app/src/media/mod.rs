@@ -1,13 +1,20 @@
+pub mod cache;
 pub mod decoder;
+pub mod playlist;
+#[cfg(feature = "video")]
+pub mod stream;
+pub mod thumbs;
 pub mod types;
 #[cfg(feature = "video")]
 pub mod video;
+pub mod watch;
 #[cfg(feature = "webcam")]
 pub mod webcam;
 #[cfg(feature = "webcam")]
 pub mod webcam_ffmpeg;
 
 use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Unified webcam backend that wraps either nokhwa (native) or ffmpeg capture.
 #[cfg(feature = "webcam")]
@@ -25,6 +32,14 @@ impl WebcamBackend {
         }
     }
 
+    /// Return a consumed frame's buffer to the capture thread for reuse.
+    pub fn recycle(&self, frame: webcam::WebcamFrame) {
+        match self {
+            Self::Native(c) => c.recycle(frame),
+            Self::Ffmpeg(c) => c.recycle(frame),
+        }
+    }
+
     #[allow(dead_code)]
     pub fn stop(&mut self) {
         match self {
@@ -54,14 +69,27 @@ impl WebcamBackend {
         }
     }
 
+    pub fn fps(&self) -> u32 {
+        match self {
+            Self::Native(c) => c.fps,
+            Self::Ffmpeg(c) => c.fps,
+        }
+    }
+
     /// Start capture using the native (nokhwa) backend.
-    pub fn start_native(device_index: u32, resolution: Option<(u32, u32)>) -> Result<Self, String> {
-        webcam::WebcamCapture::start(device_index, resolution).map(Self::Native)
+    pub fn start_native(
+        device_index: u32,
+        mode: Option<types::WebcamMode>,
+    ) -> Result<Self, String> {
+        webcam::WebcamCapture::start(device_index, mode).map(Self::Native)
     }
 
     /// Start capture using the ffmpeg backend.
-    pub fn start_ffmpeg(device_name: &str, resolution: Option<(u32, u32)>) -> Result<Self, String> {
-        webcam_ffmpeg::FfmpegCapture::start(device_name, resolution).map(Self::Ffmpeg)
+    pub fn start_ffmpeg(
+        device_name: &str,
+        mode: Option<types::WebcamMode>,
+    ) -> Result<Self, String> {
+        webcam_ffmpeg::FfmpegCapture::start(device_name, mode).map(Self::Ffmpeg)
     }
 }
 
@@ -76,8 +104,12 @@ use wgpu::{
 
 use crate::gpu::fullscreen_quad::FULLSCREEN_TRIANGLE_VS_WITH_UV;
 use crate::gpu::render_target::RenderTarget;
+use crate::gpu::resources::{ResourceCounts, Subsystem, Tracked};
 use decoder::MediaSource;
-use types::{PlayDirection, TransportState};
+use types::{
+    AudioCue, AudioPlayhead, AudioStep, MediaProjection, PlayDirection, ProjectionMode,
+    TransportState,
+};
 
 const MEDIA_BLIT_FS: &str = include_str!("../../../../assets/shaders/builtin/media_blit.wgsl");
 
@@ -87,16 +119,26 @@ struct MediaUniforms {
     scale: [f32; 2],
     offset: [f32; 2],
     mirror: u32,
-    _pad: u32,
+    /// 0 = flat, 1 = equirect view, 2 = equirect fisheye (see `ProjectionMode`).
+    projection: u32,
+    /// Radians.
+    yaw: f32,
+    pitch: f32,
+    fov: f32,
+    /// Viewport width / height, for the projected modes' square pixels.
+    aspect: f32,
+    _pad: [u32; 2],
 }
 
 pub struct MediaLayer {
-    pub source: MediaSource,
+    /// Shared with the media cache (#2150).
+    pub source: Arc<MediaSource>,
     pub file_path: PathBuf,
     pub file_name: String,
     pub transport: TransportState,
     pub current_frame: usize,
     frame_elapsed_ms: f64,
+    audio_playhead: AudioPlayhead,
     // GPU resources
     frame_texture: wgpu::Texture,
     frame_view: wgpu::TextureView,
@@ -112,12 +154,20 @@ pub struct MediaLayer {
     needs_upload: bool,
     // PingPong direction for GIF
     pingpong_forward: bool,
-    // Live webcam frame data (set externally by capture thread)
-    #[cfg(feature = "webcam")]
-    live_frame_data: Option<Vec<u8>>,
+    /// Set when playback completes a pass through the clip (wrap or stop);
+    /// consumed by playlist auto-advance via `take_finished`.
+    finished: bool,
+    /// Equirect / fisheye framing; `Flat` is the plain letterboxed blit.
+    pub projection: MediaProjection,
     /// Mirror horizontally (for selfie cameras).
     #[cfg(feature = "webcam")]
     pub mirror: bool,
+    /// Reader thread for `MediaSource::Stream`; dropped (and ffmpeg killed)
+    /// with the layer.
+    #[cfg(feature = "video")]
+    stream: Option<stream::NetworkStream>,
+    /// Its GPU resources, credited to Media (#2226).
+    _tracked: Tracked,
 }
 
 impl MediaLayer {
@@ -127,24 +177,38 @@ impl MediaLayer {
         hdr_format: TextureFormat,
         width: u32,
         height: u32,
-        source: MediaSource,
+        source: Arc<MediaSource>,
         file_path: PathBuf,
     ) -> Self {
+        let before = ResourceCounts::read(device);
         let (media_width, media_height) = source.dimensions();
-        let file_name = file_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-            .to_string();
+        // A URL's last path segment says little ("live", "index.m3u8"); show it whole
+        let file_name = if source.is_stream() {
+            file_path.to_string_lossy().to_string()
+        } else {
+            file_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string()
+        };
 
         let total_frames = source.frame_count();
-        let duration = match &source {
+        let duration = match &*source {
             MediaSource::Animated { delays_ms, .. } => {
                 delays_ms.iter().map(|&d| d as f64).sum::<f64>()
             }
             MediaSource::Static(_) => 0.0,
             #[cfg(feature = "webcam")]
             MediaSource::Live { .. } => 0.0,
+            #[cfg(feature = "video")]
+            MediaSource::Stream { .. } => 0.0,
+        };
+
+        #[cfg(feature = "video")]
+        let stream = match &*source {
+            MediaSource::Stream { url } => Some(stream::NetworkStream::start(url)),
+            _ => None,
         };
 
         let transport = TransportState {
@@ -179,9 +243,9 @@ impl MediaLayer {
         });
 
         // Upload first frame (black for live sources)
-        #[cfg(feature = "webcam")]
+        #[cfg(any(feature = "webcam", feature = "video"))]
         let black_placeholder: Vec<u8>;
-        let first_frame_data: &[u8] = match &source {
+        let first_frame_data: &[u8] = match &*source {
             MediaSource::Static(f) => &f.data,
             MediaSource::Animated { frames, .. } => &frames[0].data,
             #[cfg(feature = "webcam")]
@@ -189,6 +253,11 @@ impl MediaLayer {
                 black_placeholder = vec![0u8; (*width as usize) * (*height as usize) * 4];
                 &black_placeholder
             }
+            #[cfg(feature = "video")]
+            MediaSource::Stream { .. } => {
+                black_placeholder = vec![0u8; (media_width as usize) * (media_height as usize) * 4];
+                &black_placeholder
+            }
         };
         queue.write_texture(
             wgpu::TexelCopyTextureInfo {
@@ -215,7 +284,14 @@ impl MediaLayer {
             RenderTarget::new(device, width, height, hdr_format, 1.0, "media-output");
 
         // Uniform buffer for letterbox transform
-        let uniforms = compute_media_uniforms(media_width, media_height, width, height, false);
+        let uniforms = compute_media_uniforms(
+            media_width,
+            media_height,
+            width,
+            height,
+            false,
+            MediaProjection::default(),
+        );
         let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("media-uniforms"),
             size: std::mem::size_of::<MediaUniforms>() as u64,
@@ -331,6 +407,7 @@ impl MediaLayer {
             transport,
             current_frame: 0,
             frame_elapsed_ms: 0.0,
+            audio_playhead: AudioPlayhead::default(),
             frame_texture,
             frame_view,
             frame_sampler,
@@ -343,10 +420,13 @@ impl MediaLayer {
             media_height,
             needs_upload: false,
             pingpong_forward: true,
-            #[cfg(feature = "webcam")]
-            live_frame_data: None,
+            finished: false,
+            projection: MediaProjection::default(),
             #[cfg(feature = "webcam")]
             mirror: false,
+            #[cfg(feature = "video")]
+            stream,
+            _tracked: Tracked::since(Subsystem::Media, device, before),
         }
     }
 
@@ -373,17 +453,20 @@ impl MediaLayer {
         &self.output_target
     }
 
-    /// Advance media playback by dt seconds. Sets needs_upload if frame changed.
-    pub fn advance(&mut self, dt_secs: f32) {
+    /// Advance media playback by dt seconds, or as the transport's audio
+    /// drive (#2248) says given `cue`. Sets needs_upload if frame changed.
+    pub fn advance(&mut self, dt_secs: f32, cue: Option<&AudioCue>) {
         if !self.transport.playing {
             return;
         }
 
-        let delays_ms = match &self.source {
+        let delays_ms = match &*self.source {
             MediaSource::Animated { delays_ms, .. } => delays_ms,
             MediaSource::Static(_) => return,
             #[cfg(feature = "webcam")]
             MediaSource::Live { .. } => return, // live frames set externally
+            #[cfg(feature = "video")]
+            MediaSource::Stream { .. } => return,
         };
 
         let num_frames = delays_ms.len();
@@ -391,6 +474,24 @@ impl MediaLayer {
             return;
         }
 
+        let dt_secs = match self.audio_playhead.step(
+            &self.transport.audio,
+            cue,
+            self.current_frame,
+            num_frames,
+            dt_secs,
+        ) {
+            AudioStep::Play(dt) => dt,
+            AudioStep::Hold => return,
+            AudioStep::Jump(frame) => {
+                if frame != self.current_frame && frame < num_frames {
+                    self.current_frame = frame;
+                    self.frame_elapsed_ms = 0.0;
+                    self.needs_upload = true;
+                }
+                return;
+            }
+        };
         let dt_ms = dt_secs as f64 * 1000.0 * self.transport.speed as f64;
         self.frame_elapsed_ms += dt_ms;
 
@@ -403,6 +504,7 @@ impl MediaLayer {
                 PlayDirection::Forward => {
                     self.current_frame += 1;
                     if self.current_frame >= num_frames {
+                        self.finished = true;
                         if self.transport.looping {
                             self.current_frame = 0;
                         } else {
@@ -413,6 +515,7 @@ impl MediaLayer {
                 }
                 PlayDirection::Reverse => {
                     if self.current_frame == 0 {
+                        self.finished = true;
                         if self.transport.looping {
                             self.current_frame = num_frames - 1;
                         } else {
@@ -431,6 +534,7 @@ impl MediaLayer {
                         }
                     } else {
                         if self.current_frame == 0 {
+                            self.finished = true;
                             self.pingpong_forward = true;
                             self.current_frame = 1.min(num_frames - 1);
                         } else {
@@ -448,29 +552,35 @@ impl MediaLayer {
 
     /// Upload current frame data to GPU texture if needed.
     pub fn upload_frame(&mut self, queue: &Queue) {
+        // Streams upload whatever the reader thread delivered since last frame
+        #[cfg(feature = "video")]
+        if let Some(ref stream) = self.stream {
+            if let Some(frame) = stream.try_recv_frame() {
+                self.write_frame_texture(queue, &frame);
+                stream.recycle(frame);
+            }
+            return;
+        }
         if !self.needs_upload {
             return;
         }
         self.needs_upload = false;
 
-        // For live sources, use the externally-set frame data
-        #[cfg(feature = "webcam")]
-        let live_data;
-        let frame_data: &[u8] = match &self.source {
+        let frame_data: &[u8] = match &*self.source {
             MediaSource::Static(f) => &f.data,
             MediaSource::Animated { frames, .. } => {
                 &frames[self.current_frame.min(frames.len() - 1)].data
             }
+            // Live frames are uploaded as they arrive, see `upload_live_frame`
             #[cfg(feature = "webcam")]
-            MediaSource::Live { .. } => {
-                live_data = self.live_frame_data.take();
-                match &live_data {
-                    Some(data) => data,
-                    None => return,
-                }
-            }
+            MediaSource::Live { .. } => return,
+            #[cfg(feature = "video")]
+            MediaSource::Stream { .. } => return,
         };
+        self.write_frame_texture(queue, frame_data);
+    }
 
+    fn write_frame_texture(&self, queue: &Queue, frame_data: &[u8]) {
         queue.write_texture(
             wgpu::TexelCopyTextureInfo {
                 texture: &self.frame_texture,
@@ -497,19 +607,7 @@ impl MediaLayer {
         self.output_target.resize(device, width, height);
 
         // Recompute letterbox
-        let mirror = {
-            #[cfg(feature = "webcam")]
-            {
-                self.mirror
-            }
-            #[cfg(not(feature = "webcam"))]
-            {
-                false
-            }
-        };
-        let uniforms =
-            compute_media_uniforms(self.media_width, self.media_height, width, height, mirror);
-        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+        self.write_uniforms(queue);
 
         // Rebuild bind group (output_target view changed but frame texture/sampler/uniform didn't)
         self.bind_group = device.create_bind_group(&BindGroupDescriptor {
@@ -544,31 +642,66 @@ impl MediaLayer {
         self.source.is_live()
     }
 
+    /// Connection state and drop count of a network stream layer.
+    #[cfg(feature = "video")]
+    pub fn stream_status(&self) -> Option<(stream::StreamStatus, u32)> {
+        self.stream.as_ref().map(|s| (s.status(), s.reconnects()))
+    }
+
     /// Update mirror state and re-upload uniforms.
     #[cfg(feature = "webcam")]
     pub fn set_mirror(&mut self, queue: &Queue, mirror: bool) {
         self.mirror = mirror;
+        self.write_uniforms(queue);
+    }
+
+    /// Update projection settings and re-upload uniforms.
+    pub fn set_projection(&mut self, queue: &Queue, projection: MediaProjection) {
+        self.projection = projection.normalized();
+        self.write_uniforms(queue);
+    }
+
+    fn write_uniforms(&self, queue: &Queue) {
+        let mirror = {
+            #[cfg(feature = "webcam")]
+            {
+                self.mirror
+            }
+            #[cfg(not(feature = "webcam"))]
+            {
+                false
+            }
+        };
         let uniforms = compute_media_uniforms(
             self.media_width,
             self.media_height,
             self.output_target.width,
             self.output_target.height,
             mirror,
+            self.projection,
         );
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
     }
 
-    /// Set live frame data from webcam capture thread.
+    /// Upload a webcam frame straight from the capture buffer. Frames whose
+    /// size doesn't match the layer (mid mode-switch) are skipped.
     #[cfg(feature = "webcam")]
-    pub fn set_live_frame(&mut self, data: Vec<u8>) {
-        self.live_frame_data = Some(data);
-        self.needs_upload = true;
+    pub fn upload_live_frame(&self, queue: &Queue, data: &[u8]) {
+        let expected = self.media_width as usize * self.media_height as usize * 4;
+        if data.len() == expected {
+            self.write_frame_texture(queue, data);
+        }
     }
 
     pub fn frame_count(&self) -> usize {
         self.source.frame_count()
     }
 
+    /// True once per completed pass through the clip. Clears the flag.
+    pub fn take_finished(&mut self) -> bool {
+        std::mem::take(&mut self.finished)
+    }
+
     /// Seek to a specific frame index. Instant random access (pre-decoded).
     pub fn seek_to_frame(&mut self, frame: usize) {
         let num_frames = self.source.frame_count();
@@ -585,7 +718,7 @@ impl MediaLayer {
 
     /// Seek to a time position in seconds. Converts to frame index.
     pub fn seek_to_secs(&mut self, secs: f64) {
-        if let MediaSource::Animated { delays_ms, .. } = &self.source {
+        if let MediaSource::Animated { delays_ms, .. } = &*self.source {
             // Walk delays to find frame at this time offset
             let target_ms = secs * 1000.0;
             let mut accum = 0.0;
@@ -603,7 +736,7 @@ impl MediaLayer {
 
     /// Current playback position in seconds (computed from current_frame).
     pub fn position_secs(&self) -> f64 {
-        if let MediaSource::Animated { delays_ms, .. } = &self.source {
+        if let MediaSource::Animated { delays_ms, .. } = &*self.source {
             let ms: f64 = delays_ms
                 .iter()
                 .take(self.current_frame)
@@ -621,13 +754,15 @@ impl MediaLayer {
     }
 }
 
-/// Compute letterbox scale and offset to fit media into viewport (fit mode).
+/// Compute letterbox scale and offset to fit media into viewport (fit mode),
+/// plus the projection camera. The projected modes fill the whole viewport.
 fn compute_media_uniforms(
     media_w: u32,
     media_h: u32,
     viewport_w: u32,
     viewport_h: u32,
     mirror: bool,
+    projection: MediaProjection,
 ) -> MediaUniforms {
     let media_aspect = media_w as f32 / media_h.max(1) as f32;
     let viewport_aspect = viewport_w as f32 / viewport_h.max(1) as f32;
@@ -647,6 +782,63 @@ fn compute_media_uniforms(
         scale: [scale_x, scale_y],
         offset: [offset_x, offset_y],
         mirror: mirror as u32,
-        _pad: 0,
+        projection: match projection.mode {
+            ProjectionMode::Flat => 0,
+            ProjectionMode::EquirectView => 1,
+            ProjectionMode::EquirectFisheye => 2,
+        },
+        yaw: projection.yaw.to_radians(),
+        pitch: projection.pitch.to_radians(),
+        fov: projection.fov.to_radians(),
+        aspect: viewport_aspect,
+        _pad: [0; 2],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn media_blit_shader_validates() {
+        let src = format!("{FULLSCREEN_TRIANGLE_VS_WITH_UV}\n{MEDIA_BLIT_FS}");
+        let module = wgpu::naga::front::wgsl::parse_str(&src).expect("media_blit.wgsl parses");
+        wgpu::naga::valid::Validator::new(
+            wgpu::naga::valid::ValidationFlags::all(),
+            wgpu::naga::valid::Capabilities::empty(),
+        )
+        .validate(&module)
+        .expect("media_blit.wgsl validates");
+    }
+
+    #[test]
+    fn media_uniforms_match_wgsl_layout() {
+        // Multiple of 16 so the uniform binding size is valid on every backend.
+        assert_eq!(std::mem::size_of::<MediaUniforms>(), 48);
+    }
+
+    #[test]
+    fn letterbox_fits_wide_media() {
+        let u = compute_media_uniforms(1920, 1080, 1000, 1000, false, MediaProjection::default());
+        assert_eq!(u.scale[0], 1.0);
+        assert!((u.scale[1] - 1000.0 / 1000.0 / (1920.0 / 1080.0)).abs() < 1e-5);
+        assert!((u.offset[1] - (1.0 - u.scale[1]) * 0.5).abs() < 1e-6);
+        assert_eq!(u.projection, 0);
+    }
+
+    #[test]
+    fn projection_angles_upload_in_radians() {
+        let proj = MediaProjection {
+            mode: ProjectionMode::EquirectFisheye,
+            yaw: 90.0,
+            pitch: -45.0,
+            fov: 180.0,
+        };
+        let u = compute_media_uniforms(4096, 2048, 1920, 1080, false, proj);
+        assert_eq!(u.projection, 2);
+        assert!((u.yaw - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert!((u.pitch + std::f32::consts::FRAC_PI_4).abs() < 1e-6);
+        assert!((u.fov - std::f32::consts::PI).abs() < 1e-6);
+        assert!((u.aspect - 1920.0 / 1080.0).abs() < 1e-6);
     }
 }
@@ -0,0 +1,382 @@
+//! Per-layer media playlist: an ordered reel of clips a media layer steps
+//! through on its own (on clip end or every N beats), with shuffle and
+//! manual next/prev triggers.
+//!
+//! The playlist lives on the `Layer`, not the `MediaLayer` — each clip change
+//! swaps the layer's media content (new dimensions, new GPU texture), while the
+//! reel, its position and the beat counter must survive the swap.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crossbeam_channel::Receiver;
+use serde::{Deserialize, Serialize};
+
+use super::decoder::MediaSource;
+
+/// When a playlist moves on to its next clip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PlaylistAdvance {
+    /// Only on an explicit next/prev trigger (UI button, binding, OSC).
+    #[default]
+    Manual,
+    /// When the current clip reaches its last frame. Static images hold.
+    OnEnd,
+    /// Every `beats` detected beats (MIDI clock when playing, else audio).
+    Beats { beats: u32 },
+}
+
+impl PlaylistAdvance {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PlaylistAdvance::Manual => "Manual",
+            PlaylistAdvance::OnEnd => "On end",
+            PlaylistAdvance::Beats { .. } => "Beats",
+        }
+    }
+}
+
+/// Serialized playlist, saved in `LayerPreset::media_playlist`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlaylistDef {
+    /// Absolute clip paths in reel order.
+    #[serde(default)]
+    pub clips: Vec<String>,
+    #[serde(default)]
+    pub advance: PlaylistAdvance,
+    #[serde(default)]
+    pub shuffle: bool,
+}
+
+/// Result of a background clip decode: (clip path, decoded source or error).
+pub type ClipDecode = (PathBuf, Result<Arc<MediaSource>, String>);
+
+/// Runtime playlist state for one media layer.
+pub struct MediaPlaylist {
+    pub clips: Vec<PathBuf>,
+    pub advance: PlaylistAdvance,
+    shuffle: bool,
+    /// Play order as indices into `clips` (identity unless shuffled).
+    order: Vec<usize>,
+    /// Position within `order` of the clip currently on the layer.
+    position: usize,
+    beat_count: u32,
+    last_beat: bool,
+    rng_state: u32,
+    /// In-flight background decode of the next clip, if any.
+    pub(crate) pending: Option<Receiver<ClipDecode>>,
+}
+
+impl MediaPlaylist {
+    pub fn new(clips: Vec<PathBuf>) -> Self {
+        let order = (0..clips.len()).collect();
+        Self {
+            clips,
+            advance: PlaylistAdvance::default(),
+            shuffle: false,
+            order,
+            position: 0,
+            beat_count: 0,
+            last_beat: false,
+            // xorshift32 must never be seeded with 0.
+            rng_state: crate::gpu::deterministic::clock_entropy() | 1,
+            pending: None,
+        }
+    }
+
+    pub fn from_def(def: &PlaylistDef) -> Self {
+        let mut pl = Self::new(def.clips.iter().map(PathBuf::from).collect());
+        pl.advance = def.advance;
+        pl.set_shuffle(def.shuffle);
+        pl
+    }
+
+    pub fn to_def(&self) -> PlaylistDef {
+        PlaylistDef {
+            clips: self
+                .clips
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+            advance: self.advance,
+            shuffle: self.shuffle,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clips.is_empty()
+    }
+
+    pub fn shuffle(&self) -> bool {
+        self.shuffle
+    }
+
+    /// Index into `clips` of the clip currently on the layer.
+    pub fn current_index(&self) -> Option<usize> {
+        self.order.get(self.position).copied()
+    }
+
+    pub fn current(&self) -> Option<&Path> {
+        self.current_index().map(|i| self.clips[i].as_path())
+    }
+
+    /// Step forward one clip, wrapping (and reshuffling) at the end of the reel.
+    /// Returns the new clip index.
+    pub fn next(&mut self) -> Option<usize> {
+        if self.order.is_empty() {
+            return None;
+        }
+        self.beat_count = 0;
+        self.position += 1;
+        if self.position >= self.order.len() {
+            self.position = 0;
+            if self.shuffle {
+                let last = self.order.last().copied();
+                self.reshuffle();
+                // Never replay the clip that just ended when the reel restarts.
+                if self.order.len() > 1 && self.order.first().copied() == last {
+                    self.order.swap(0, 1);
+                }
+            }
+        }
+        self.current_index()
+    }
+
+    /// Step back one clip, wrapping to the end of the reel.
+    pub fn prev(&mut self) -> Option<usize> {
+        if self.order.is_empty() {
+            return None;
+        }
+        self.beat_count = 0;
+        self.position = self.position.checked_sub(1).unwrap_or(self.order.len() - 1);
+        self.current_index()
+    }
+
+    /// Jump straight to `clip` (an index into `clips`).
+    pub fn jump_to(&mut self, clip: usize) -> Option<usize> {
+        let pos = self.order.iter().position(|&i| i == clip)?;
+        self.position = pos;
+        self.beat_count = 0;
+        self.current_index()
+    }
+
+    pub fn set_shuffle(&mut self, shuffle: bool) {
+        if self.shuffle == shuffle {
+            return;
+        }
+        self.shuffle = shuffle;
+        let current = self.current_index();
+        if shuffle {
+            self.reshuffle();
+        } else {
+            self.order = (0..self.clips.len()).collect();
+        }
+        // Keep the clip on screen where it is; only the upcoming order changes.
+        if let Some(pos) = current.and_then(|c| self.order.iter().position(|&i| i == c)) {
+            self.position = pos;
+        }
+    }
+
+    /// Append clips to the end of the reel (and of the play order).
+    pub fn add_clips(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        for path in paths {
+            self.order.push(self.clips.len());
+            self.clips.push(path);
+        }
+    }
+
+    /// Remove a clip. The clip on screen keeps playing unless it was removed.
+    pub fn remove_clip(&mut self, clip: usize) {
+        if clip >= self.clips.len() {
+            return;
+        }
+        let current = self.current_index();
+        self.clips.remove(clip);
+        self.order.retain(|&i| i != clip);
+        for i in &mut self.order {
+            if *i > clip {
+                *i -= 1;
+            }
+        }
+        self.position = match current {
+            Some(c) if c > clip => self.order.iter().position(|&i| i == c - 1).unwrap_or(0),
+            Some(c) if c < clip => self.order.iter().position(|&i| i == c).unwrap_or(0),
+            _ => self.position.min(self.order.len().saturating_sub(1)),
+        };
+    }
+
+    /// Swap a clip with its neighbour (`up` = towards the start of the reel).
+    pub fn move_clip(&mut self, clip: usize, up: bool) {
+        let other = if up {
+            match clip.checked_sub(1) {
+                Some(o) => o,
+                None => return,
+            }
+        } else {
+            clip + 1
+        };
+        if other >= self.clips.len() || clip >= self.clips.len() {
+            return;
+        }
+        let current = self.current_index();
+        self.clips.swap(clip, other);
+        let remap = |i: usize| {
+            if i == clip {
+                other
+            } else if i == other {
+                clip
+            } else {
+                i
+            }
+        };
+        if self.shuffle {
+            // Shuffled order follows the clips themselves, not their slots.
+            for i in &mut self.order {
+                *i = remap(*i);
+            }
+        } else if let Some(c) = current {
+            // Sequential order is the reel order; the clip on screen moves with it.
+            self.position = remap(c);
+        }
+    }
+
+    /// Feed this frame's beat signal. Returns true when a `Beats` playlist
+    /// should advance (rising edge, every N beats).
+    pub fn feed_beat(&mut self, beat: bool) -> bool {
+        let rising = beat && !self.last_beat;
+        self.last_beat = beat;
+        if !rising {
+            return false;
+        }
+        if let PlaylistAdvance::Beats { beats } = self.advance {
+            self.beat_count += 1;
+            if self.beat_count >= beats.max(1) {
+                self.beat_count = 0;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Fisher–Yates over `order` with a xorshift32 stream.
+    fn reshuffle(&mut self) {
+        for i in (1..self.order.len()).rev() {
+            let mut x = self.rng_state;
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            self.rng_state = x;
+            let j = (x as usize) % (i + 1);
+            self.order.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reel(n: usize) -> MediaPlaylist {
+        MediaPlaylist::new(
+            (0..n)
+                .map(|i| PathBuf::from(format!("clip{i}.gif")))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn next_and_prev_wrap() {
+        let mut pl = reel(3);
+        assert_eq!(pl.current_index(), Some(0));
+        assert_eq!(pl.next(), Some(1));
+        assert_eq!(pl.next(), Some(2));
+        assert_eq!(pl.next(), Some(0));
+        assert_eq!(pl.prev(), Some(2));
+        assert_eq!(pl.prev(), Some(1));
+    }
+
+    #[test]
+    fn empty_playlist_is_inert() {
+        let mut pl = reel(0);
+        assert!(pl.is_empty());
+        assert_eq!(pl.current(), None);
+        assert_eq!(pl.next(), None);
+        assert_eq!(pl.prev(), None);
+    }
+
+    #[test]
+    fn beats_advance_every_n_rising_edges() {
+        let mut pl = reel(2);
+        pl.advance = PlaylistAdvance::Beats { beats: 2 };
+        // A held beat counts once.
+        assert!(!pl.feed_beat(true));
+        assert!(!pl.feed_beat(true));
+        assert!(!pl.feed_beat(false));
+        assert!(pl.feed_beat(true));
+    }
+
+    #[test]
+    fn manual_ignores_beats() {
+        let mut pl = reel(2);
+        for _ in 0..8 {
+            assert!(!pl.feed_beat(true));
+            assert!(!pl.feed_beat(false));
+        }
+    }
+
+    #[test]
+    fn shuffle_is_a_permutation_and_keeps_current_clip() {
+        let mut pl = reel(10);
+        pl.next();
+        pl.next();
+        pl.set_shuffle(true);
+        assert_eq!(pl.current_index(), Some(2));
+        let mut order = pl.order.clone();
+        order.sort_unstable();
+        assert_eq!(order, (0..10).collect::<Vec<_>>());
+        // A full pass from the start of the reel plays every clip once.
+        pl.position = pl.order.len() - 1;
+        let mut seen: Vec<usize> = (0..10).map(|_| pl.next().unwrap()).collect();
+        seen.sort_unstable();
+        assert_eq!(seen, (0..10).collect::<Vec<_>>());
+        pl.set_shuffle(false);
+        let cur = pl.current_index().unwrap();
+        assert_eq!(pl.next(), Some((cur + 1) % 10));
+    }
+
+    #[test]
+    fn remove_clip_keeps_current_clip_playing() {
+        let mut pl = reel(4);
+        pl.jump_to(2);
+        pl.remove_clip(0);
+        assert_eq!(pl.current(), Some(Path::new("clip2.gif")));
+        pl.remove_clip(pl.current_index().unwrap());
+        assert_eq!(pl.clips.len(), 2);
+        assert!(pl.current_index().unwrap() < 2);
+    }
+
+    #[test]
+    fn move_clip_reorders_reel() {
+        let mut pl = reel(3);
+        pl.move_clip(2, true);
+        assert_eq!(pl.clips[1], PathBuf::from("clip2.gif"));
+        assert_eq!(pl.next(), Some(1));
+        assert_eq!(pl.current(), Some(Path::new("clip2.gif")));
+        // Out-of-range moves are no-ops.
+        pl.move_clip(0, true);
+        pl.move_clip(2, false);
+        assert_eq!(pl.clips.len(), 3);
+    }
+
+    #[test]
+    fn def_roundtrip() {
+        let mut pl = reel(2);
+        pl.advance = PlaylistAdvance::Beats { beats: 8 };
+        let json = serde_json::to_string(&pl.to_def()).unwrap();
+        let def: PlaylistDef = serde_json::from_str(&json).unwrap();
+        let pl2 = MediaPlaylist::from_def(&def);
+        assert_eq!(pl2.clips, pl.clips);
+        assert_eq!(pl2.advance, PlaylistAdvance::Beats { beats: 8 });
+        assert!(!pl2.shuffle());
+    }
+}
@@ -0,0 +1,299 @@
+//! Thumbnails and metadata for the file browser (#2247).
+//!
+//! Building a thumbnail means decoding an image or running ffmpeg on a clip,
+//! far too slow for the UI thread with a folder of hundreds. Requests queue
+//! up for a worker thread; the newest request is served first, so the rows
+//! on screen fill in before ones scrolled past. Results are written under
+//! `<config>/thumbnails`, keyed by path, size and modification time, so a
+//! folder opens with its thumbnails after the first visit.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "video")]
+use super::decoder::VIDEO_EXTENSIONS;
+
+/// Longest side of a thumbnail, in pixels.
+pub const THUMB_SIZE: u32 = 96;
+
+/// What the browser shows next to a file's name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaMeta {
+    pub width: u32,
+    pub height: u32,
+    /// Zero for stills.
+    #[serde(default)]
+    pub duration_secs: f64,
+}
+
+impl MediaMeta {
+    /// "1920x1080" for stills, "1920x1080 · 0:42" for clips.
+    pub fn summary(&self) -> String {
+        let size = format!("{}x{}", self.width, self.height);
+        if self.duration_secs <= 0.0 {
+            return size;
+        }
+        let secs = self.duration_secs.round() as u64;
+        format!("{size} \u{b7} {}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+pub struct Thumb {
+    pub meta: MediaMeta,
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+enum Slot {
+    Pending,
+    Ready(Arc<Thumb>),
+    Failed,
+}
+
+#[derive(Default)]
+struct Inner {
+    slots: HashMap<PathBuf, Slot>,
+    /// Waiting for the worker; the last one is built next.
+    queue: Vec<PathBuf>,
+    working: bool,
+}
+
+/// Shared handle; clones refer to the same cache.
+#[derive(Clone)]
+pub struct ThumbCache {
+    inner: Arc<Mutex<Inner>>,
+    dir: PathBuf,
+}
+
+impl ThumbCache {
+    /// Cache thumbnails on disk in `dir`, created when first written.
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            inner: Arc::default(),
+            dir,
+        }
+    }
+
+    /// The thumbnail for `path`, or `None` while it's being built or when it
+    /// can't be. The first call queues it.
+    pub fn get(&self, path: &Path) -> Option<Arc<Thumb>> {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.slots.get(path) {
+            Some(Slot::Ready(thumb)) => return Some(thumb.clone()),
+            Some(_) => return None,
+            None => {}
+        }
+        inner.slots.insert(path.to_path_buf(), Slot::Pending);
+        inner.queue.push(path.to_path_buf());
+        if !inner.working {
+            inner.working = true;
+            drop(inner);
+            self.spawn_worker();
+        }
+        None
+    }
+
+    /// Thumbnails still queued or being built.
+    pub fn pending(&self) -> usize {
+        let inner = self.inner.lock().unwrap();
+        inner.queue.len() + usize::from(inner.working)
+    }
+
+    /// The next path for the worker, clearing `working` when there's none.
+    fn next_job(&self) -> Option<PathBuf> {
+        let mut inner = self.inner.lock().unwrap();
+        let next = inner.queue.pop();
+        inner.working = next.is_some();
+        next
+    }
+
+    fn spawn_worker(&self) {
+        let cache = self.clone();
+        let spawned = std::thread::Builder::new()
+            .name("media-thumbs".into())
+            .spawn(move || {
+                while let Some(path) = cache.next_job() {
+                    let slot = match load_or_build(&cache.dir, &path) {
+                        Ok(thumb) => Slot::Ready(Arc::new(thumb)),
+                        Err(e) => {
+                            log::debug!("No thumbnail for '{}': {e}", path.display());
+                            Slot::Failed
+                        }
+                    };
+                    cache.inner.lock().unwrap().slots.insert(path, slot);
+                }
+            });
+        if spawned.is_err() {
+            let mut inner = self.inner.lock().unwrap();
+            inner.working = false;
+            for path in std::mem::take(&mut inner.queue) {
+                inner.slots.insert(path, Slot::Failed);
+            }
+        }
+    }
+}
+
+/// File name stem for `path`'s cached thumbnail. Changes when the file does.
+fn cache_key(path: &Path) -> Result<String, String> {
+    let meta = std::fs::metadata(path).map_err(|e| e.to_string())?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    // FNV-1a: stable across runs and Rust versions, unlike DefaultHasher
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let text = format!("{}|{}|{modified}", path.display(), meta.len());
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    Ok(format!("{hash:016x}"))
+}
+
+/// `width`x`height` shrunk to fit in a `max` square, at least 1 pixel a side.
+#[cfg_attr(not(feature = "video"), allow(dead_code))]
+fn fit(width: u32, height: u32, max: u32) -> (u32, u32) {
+    let scale = (max as f32 / width.max(height).max(1) as f32).min(1.0);
+    (
+        ((width as f32 * scale).round() as u32).max(1),
+        ((height as f32 * scale).round() as u32).max(1),
+    )
+}
+
+fn load_or_build(dir: &Path, path: &Path) -> Result<Thumb, String> {
+    let key = cache_key(path)?;
+    let meta_path = dir.join(format!("{key}.json"));
+    let image_path = dir.join(format!("{key}.png"));
+    if let (Ok(json), Ok(img)) = (std::fs::read(&meta_path), image::open(&image_path))
+        && let Ok(meta) = serde_json::from_slice(&json)
+    {
+        let rgba = img.to_rgba8();
+        return Ok(Thumb {
+            meta,
+            width: rgba.width(),
+            height: rgba.height(),
+            rgba: rgba.into_raw(),
+        });
+    }
+
+    let thumb = build(path)?;
+    let saved = std::fs::create_dir_all(dir)
+        .map_err(|e| e.to_string())
+        .and_then(|()| {
+            image::save_buffer(
+                &image_path,
+                &thumb.rgba,
+                thumb.width,
+                thumb.height,
+                image::ExtendedColorType::Rgba8,
+            )
+            .map_err(|e| e.to_string())
+        })
+        .and_then(|()| {
+            let json = serde_json::to_vec(&thumb.meta).map_err(|e| e.to_string())?;
+            std::fs::write(&meta_path, json).map_err(|e| e.to_string())
+        });
+    if let Err(e) = saved {
+        log::debug!("Thumbnail for '{}' not saved: {e}", path.display());
+    }
+    Ok(thumb)
+}
+
+fn build(path: &Path) -> Result<Thumb, String> {
+    #[cfg(feature = "video")]
+    {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        if VIDEO_EXTENSIONS.contains(&ext.as_str()) {
+            return build_video(path);
+        }
+    }
+    let img = image::open(path).map_err(|e| e.to_string())?;
+    let meta = MediaMeta {
+        width: img.width(),
+        height: img.height(),
+        duration_secs: 0.0,
+    };
+    let rgba = img.thumbnail(THUMB_SIZE, THUMB_SIZE).to_rgba8();
+    Ok(Thumb {
+        meta,
+        width: rgba.width(),
+        height: rgba.height(),
+        rgba: rgba.into_raw(),
+    })
+}
+
+#[cfg(feature = "video")]
+fn build_video(path: &Path) -> Result<Thumb, String> {
+    use super::video::{ffmpeg_available, grab_frame, probe_video};
+
+    if !ffmpeg_available() {
+        return Err("ffmpeg/ffprobe not found on PATH".to_string());
+    }
+    let probe = probe_video(path)?;
+    let (width, height) = fit(probe.width, probe.height, THUMB_SIZE);
+    // A second in skips fade-ins from black
+    let at = (probe.duration_secs / 2.0).min(1.0);
+    Ok(Thumb {
+        meta: MediaMeta {
+            width: probe.width,
+            height: probe.height,
+            duration_secs: probe.duration_secs,
+        },
+        width,
+        height,
+        rgba: grab_frame(path, at, width, height)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_adds_duration_for_clips() {
+        let mut meta = MediaMeta {
+            width: 1920,
+            height: 1080,
+            duration_secs: 0.0,
+        };
+        assert_eq!(meta.summary(), "1920x1080");
+        meta.duration_secs = 62.4;
+        assert_eq!(meta.summary(), "1920x1080 \u{b7} 1:02");
+    }
+
+    #[test]
+    fn fit_keeps_aspect_and_never_upscales() {
+        assert_eq!(fit(1920, 1080, 96), (96, 54));
+        assert_eq!(fit(100, 4000, 96), (2, 96));
+        assert_eq!(fit(40, 30, 96), (40, 30));
+    }
+
+    #[test]
+    fn thumbnails_are_reused_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().join("thumbnails");
+        let src = dir.path().join("wide.png");
+        image::RgbaImage::from_pixel(300, 100, image::Rgba([255, 0, 0, 255]))
+            .save(&src)
+            .unwrap();
+
+        let built = load_or_build(&cache_dir, &src).unwrap();
+        assert_eq!((built.width, built.height), (96, 32));
+        assert_eq!(built.meta.summary(), "300x100");
+        assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 2);
+
+        let cached = load_or_build(&cache_dir, &src).unwrap();
+        assert_eq!(cached.meta, built.meta);
+        assert_eq!(cached.rgba, built.rgba);
+    }
+}
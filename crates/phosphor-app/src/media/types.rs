@@ -1,10 +1,50 @@
+use crossbeam_channel::{Receiver, Sender};
+use serde::{Deserialize, Serialize};
+
 /// A decoded frame ready for GPU upload.
+#[derive(Clone)]
 pub struct DecodedFrame {
     pub data: Vec<u8>, // RGBA8
     pub width: u32,
     pub height: u32,
 }
 
+/// Frame buffers handed back from the consumer to the capture thread, so a
+/// steady feed decodes into the same few allocations instead of a fresh
+/// 8 MB `Vec` per 1080p frame. Shared by webcam capture and network streams.
+#[derive(Clone)]
+pub struct FramePool {
+    tx: Sender<Vec<u8>>,
+    rx: Receiver<Vec<u8>>,
+}
+
+impl Default for FramePool {
+    fn default() -> Self {
+        // Frames in flight: one being decoded, two queued, one being uploaded.
+        let (tx, rx) = crossbeam_channel::bounded(4);
+        Self { tx, rx }
+    }
+}
+
+impl FramePool {
+    /// A buffer of exactly `len` bytes, reused when one of that size is pooled.
+    /// Contents are stale — callers overwrite the whole buffer.
+    pub fn take(&self, len: usize) -> Vec<u8> {
+        while let Ok(buf) = self.rx.try_recv() {
+            // Buffers from before a resolution change are dropped
+            if buf.len() == len {
+                return buf;
+            }
+        }
+        vec![0; len]
+    }
+
+    /// Return a buffer for reuse. Dropped if the pool is full.
+    pub fn give(&self, buf: Vec<u8>) {
+        let _ = self.tx.try_send(buf);
+    }
+}
+
 /// Playback direction for media layers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlayDirection {
@@ -25,6 +65,8 @@ pub struct TransportState {
     pub position: f64,
     /// Total duration in seconds (for video) or total frames (for GIF as f64).
     pub duration: f64,
+    /// Playback driven by the music (#2248).
+    pub audio: AudioDrive,
 }
 
 impl Default for TransportState {
@@ -36,14 +78,394 @@ impl Default for TransportState {
             direction: PlayDirection::Forward,
             position: 0.0,
             duration: 0.0,
+            audio: AudioDrive::default(),
+        }
+    }
+}
+
+/// How the music moves a clip's playhead (#2248).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AudioDriveMode {
+    /// Plays on its own.
+    #[default]
+    Off,
+    /// Jumps to a random frame on each onset, playing on from there.
+    OnsetJump,
+    /// The bass level sets the position.
+    BassScrub,
+    /// Plays for half a beat after each beat, then holds.
+    BeatGate,
+}
+
+impl AudioDriveMode {
+    pub const ALL: &[AudioDriveMode] = &[
+        AudioDriveMode::Off,
+        AudioDriveMode::OnsetJump,
+        AudioDriveMode::BassScrub,
+        AudioDriveMode::BeatGate,
+    ];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            AudioDriveMode::Off => "Off",
+            AudioDriveMode::OnsetJump => "Jump on onset",
+            AudioDriveMode::BassScrub => "Scrub by bass",
+            AudioDriveMode::BeatGate => "Gate to beats",
+        }
+    }
+}
+
+/// Audio-driven playback, with the part of the clip it uses as fractions of
+/// its length. Saved with the preset.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AudioDrive {
+    pub mode: AudioDriveMode,
+    #[serde(default)]
+    pub from: f32,
+    #[serde(default = "default_drive_to")]
+    pub to: f32,
+}
+
+fn default_drive_to() -> f32 {
+    1.0
+}
+
+impl Default for AudioDrive {
+    fn default() -> Self {
+        Self {
+            mode: AudioDriveMode::Off,
+            from: 0.0,
+            to: default_drive_to(),
+        }
+    }
+}
+
+impl AudioDrive {
+    /// First and last frame of the range in a clip of `num_frames`.
+    fn frames(&self, num_frames: usize) -> (usize, usize) {
+        let last = num_frames.saturating_sub(1) as f32;
+        let from = self.from.clamp(0.0, 1.0);
+        let to = self.to.clamp(from, 1.0);
+        ((from * last).floor() as usize, (to * last).ceil() as usize)
+    }
+}
+
+/// The audio a media layer reacts to this frame.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AudioCue {
+    /// Onset strength, 0-1.
+    pub onset: f32,
+    pub bass: f32,
+    /// A beat landed this frame.
+    pub beat: bool,
+    /// Length of a beat at the current tempo.
+    pub beat_secs: f32,
+}
+
+/// What [`AudioPlayhead::step`] wants done with the playhead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AudioStep {
+    /// Play on by this many seconds.
+    Play(f32),
+    /// Stay on the current frame.
+    Hold,
+    /// Show this frame.
+    Jump(usize),
+}
+
+/// Onset above this counts as a hit, as in the particle bursts.
+const ONSET_HIT: f32 = 0.5;
+
+/// Per-layer state behind [`AudioDrive`].
+#[derive(Debug, Clone)]
+pub struct AudioPlayhead {
+    onset_high: bool,
+    /// Seconds of playback left since the last beat.
+    gate_secs: f32,
+    rng: u32,
+}
+
+impl Default for AudioPlayhead {
+    fn default() -> Self {
+        Self {
+            onset_high: false,
+            gate_secs: 0.0,
+            rng: 0x9e37_79b9,
+        }
+    }
+}
+
+impl AudioPlayhead {
+    /// Decide this frame's move. `cue` is `None` when the layer ignores audio,
+    /// which plays normally like [`AudioDriveMode::Off`].
+    pub fn step(
+        &mut self,
+        drive: &AudioDrive,
+        cue: Option<&AudioCue>,
+        current: usize,
+        num_frames: usize,
+        dt_secs: f32,
+    ) -> AudioStep {
+        let Some(cue) = cue else {
+            return AudioStep::Play(dt_secs);
+        };
+        let (first, last) = drive.frames(num_frames);
+        match drive.mode {
+            AudioDriveMode::Off => AudioStep::Play(dt_secs),
+            AudioDriveMode::OnsetJump => {
+                let hit = cue.onset > ONSET_HIT && !self.onset_high;
+                self.onset_high = cue.onset > ONSET_HIT;
+                if !hit || last <= first {
+                    return AudioStep::Play(dt_secs);
+                }
+                // Never the frame on screen, so every hit shows a cut
+                let span = (last - first) as u32;
+                let mut frame = first + (self.next_random() % span) as usize;
+                if frame >= current {
+                    frame += 1;
+                }
+                AudioStep::Jump(frame.min(last))
+            }
+            AudioDriveMode::BassScrub => {
+                let t = cue.bass.clamp(0.0, 1.0);
+                AudioStep::Jump(first + ((last - first) as f32 * t).round() as usize)
+            }
+            AudioDriveMode::BeatGate => {
+                if cue.beat {
+                    self.gate_secs = cue.beat_secs * 0.5;
+                }
+                if self.gate_secs <= 0.0 {
+                    return AudioStep::Hold;
+                }
+                self.gate_secs -= dt_secs;
+                AudioStep::Play(dt_secs)
+            }
+        }
+    }
+
+    /// xorshift32; the jumps only need to look random.
+    fn next_random(&mut self) -> u32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 17;
+        self.rng ^= self.rng << 5;
+        self.rng
+    }
+}
+
+/// How a media layer maps its frame onto the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ProjectionMode {
+    /// Letterboxed to fit, as-is.
+    #[default]
+    Flat,
+    /// Equirectangular (360°) source seen through a virtual camera.
+    EquirectView,
+    /// Equirectangular source rendered as a fisheye dome master (zenith at centre).
+    EquirectFisheye,
+}
+
+impl ProjectionMode {
+    pub const ALL: &[ProjectionMode] = &[
+        ProjectionMode::Flat,
+        ProjectionMode::EquirectView,
+        ProjectionMode::EquirectFisheye,
+    ];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            ProjectionMode::Flat => "Flat",
+            ProjectionMode::EquirectView => "360° View",
+            ProjectionMode::EquirectFisheye => "Fisheye",
+        }
+    }
+
+    /// Field of view a mode starts at: a natural lens, and a half-sphere dome.
+    pub fn default_fov(self) -> f32 {
+        match self {
+            ProjectionMode::EquirectFisheye => 180.0,
+            _ => 90.0,
+        }
+    }
+}
+
+/// Projection settings for a media layer. Angles are in degrees; `fov` is the
+/// vertical field of view in View mode and the full dome angle in Fisheye mode.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MediaProjection {
+    pub mode: ProjectionMode,
+    #[serde(default)]
+    pub yaw: f32,
+    #[serde(default)]
+    pub pitch: f32,
+    #[serde(default = "default_projection_fov")]
+    pub fov: f32,
+}
+
+fn default_projection_fov() -> f32 {
+    90.0
+}
+
+impl Default for MediaProjection {
+    fn default() -> Self {
+        Self {
+            mode: ProjectionMode::Flat,
+            yaw: 0.0,
+            pitch: 0.0,
+            fov: default_projection_fov(),
+        }
+    }
+}
+
+impl MediaProjection {
+    /// Field-of-view range the panel offers for a mode. A fisheye past 360° just
+    /// wraps the sphere again; a rectilinear view past ~170° is all stretch.
+    pub fn fov_range(mode: ProjectionMode) -> std::ops::RangeInclusive<f32> {
+        match mode {
+            ProjectionMode::EquirectFisheye => 90.0..=360.0,
+            _ => 20.0..=170.0,
+        }
+    }
+
+    /// Clamp angles into range: yaw wraps to -180..180, pitch stops at the poles.
+    pub fn normalized(self) -> Self {
+        let range = Self::fov_range(self.mode);
+        Self {
+            mode: self.mode,
+            yaw: (self.yaw + 180.0).rem_euclid(360.0) - 180.0,
+            pitch: self.pitch.clamp(-90.0, 90.0),
+            fov: self.fov.clamp(*range.start(), *range.end()),
+        }
+    }
+}
+
+/// Requested webcam capture mode. Drivers pick the closest mode they
+/// support, so the running capture may report something different.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WebcamMode {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+}
+
+impl Default for WebcamMode {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            fps: 30,
         }
     }
 }
 
+impl WebcamMode {
+    /// Offered when a device can't be enumerated (busy, or a backend with no
+    /// mode query) — the driver still snaps to its nearest real mode.
+    pub const COMMON: &[WebcamMode] = &[
+        WebcamMode::new(640, 480, 30),
+        WebcamMode::new(1280, 720, 30),
+        WebcamMode::new(1280, 720, 60),
+        WebcamMode::new(1920, 1080, 30),
+        WebcamMode::new(1920, 1080, 60),
+    ];
+
+    pub const fn new(width: u32, height: u32, fps: u32) -> Self {
+        Self { width, height, fps }
+    }
+
+    pub fn resolution(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn resolution_label(&self) -> String {
+        format!("{}x{}", self.width, self.height)
+    }
+
+    /// Sort largest-first and drop duplicates, the order the picker lists them.
+    #[cfg_attr(not(feature = "webcam"), allow(dead_code))]
+    pub fn sort_dedup(modes: &mut Vec<WebcamMode>) {
+        modes.sort_by(|a, b| {
+            (b.width * b.height, b.width, b.fps).cmp(&(a.width * a.height, a.width, a.fps))
+        });
+        modes.dedup();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn frame_pool_reuses_matching_buffers() {
+        let pool = FramePool::default();
+        let mut buf = pool.take(16);
+        buf[0] = 7;
+        pool.give(buf);
+        // Same size comes back as the same allocation
+        assert_eq!(pool.take(16)[0], 7);
+        // A stale size from before a mode switch is discarded
+        pool.give(vec![1; 8]);
+        assert_eq!(pool.take(16), vec![0; 16]);
+    }
+
+    #[test]
+    fn webcam_mode_sort_dedup_largest_first() {
+        let mut modes = vec![
+            WebcamMode::new(640, 480, 30),
+            WebcamMode::new(1920, 1080, 30),
+            WebcamMode::new(1920, 1080, 60),
+            WebcamMode::new(640, 480, 30),
+        ];
+        WebcamMode::sort_dedup(&mut modes);
+        assert_eq!(
+            modes,
+            vec![
+                WebcamMode::new(1920, 1080, 60),
+                WebcamMode::new(1920, 1080, 30),
+                WebcamMode::new(640, 480, 30),
+            ]
+        );
+        assert_eq!(modes[0].resolution_label(), "1920x1080");
+    }
+
+    #[test]
+    fn webcam_mode_default_is_720p30() {
+        let m = WebcamMode::default();
+        assert_eq!(m.resolution(), (1280, 720));
+        assert_eq!(m.fps, 30);
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(serde_json::from_str::<WebcamMode>(&json).unwrap(), m);
+    }
+
+    #[test]
+    fn projection_normalized_wraps_yaw_and_clamps() {
+        let p = MediaProjection {
+            mode: ProjectionMode::EquirectView,
+            yaw: 270.0,
+            pitch: 120.0,
+            fov: 400.0,
+        }
+        .normalized();
+        assert!((p.yaw - -90.0).abs() < 1e-4);
+        assert_eq!(p.pitch, 90.0);
+        assert_eq!(p.fov, 170.0);
+
+        let dome = MediaProjection {
+            mode: ProjectionMode::EquirectFisheye,
+            fov: 400.0,
+            ..Default::default()
+        }
+        .normalized();
+        assert_eq!(dome.fov, 360.0);
+    }
+
+    #[test]
+    fn projection_serde_defaults_angles() {
+        let p: MediaProjection = serde_json::from_str(r#"{"mode":"EquirectFisheye"}"#).unwrap();
+        assert_eq!(p.mode, ProjectionMode::EquirectFisheye);
+        assert_eq!(p.yaw, 0.0);
+        assert_eq!(p.fov, 90.0);
+    }
+
     #[test]
     fn transport_state_defaults() {
         let t = TransportState::default();
@@ -55,6 +477,88 @@ mod tests {
         assert!((t.duration - 0.0).abs() < 1e-10);
     }
 
+    fn cue(onset: f32, bass: f32, beat: bool) -> AudioCue {
+        AudioCue {
+            onset,
+            bass,
+            beat,
+            beat_secs: 0.5,
+        }
+    }
+
+    #[test]
+    fn onset_jump_cuts_once_per_hit_within_range() {
+        let drive = AudioDrive {
+            mode: AudioDriveMode::OnsetJump,
+            from: 0.5,
+            to: 1.0,
+        };
+        let mut head = AudioPlayhead::default();
+        let quiet = cue(0.1, 0.0, false);
+        let hit = cue(0.9, 0.0, false);
+        assert_eq!(
+            head.step(&drive, Some(&quiet), 0, 101, 0.1),
+            AudioStep::Play(0.1)
+        );
+        let mut current = 60;
+        for _ in 0..50 {
+            let AudioStep::Jump(frame) = head.step(&drive, Some(&hit), current, 101, 0.1) else {
+                panic!("an onset should jump");
+            };
+            assert!((50..=100).contains(&frame) && frame != current);
+            // Held high, the same onset doesn't jump again
+            assert_eq!(
+                head.step(&drive, Some(&hit), frame, 101, 0.1),
+                AudioStep::Play(0.1)
+            );
+            head.step(&drive, Some(&quiet), frame, 101, 0.1);
+            current = frame;
+        }
+    }
+
+    #[test]
+    fn bass_scrubs_across_the_range() {
+        let drive = AudioDrive {
+            mode: AudioDriveMode::BassScrub,
+            from: 0.0,
+            to: 0.5,
+        };
+        let mut head = AudioPlayhead::default();
+        assert_eq!(
+            head.step(&drive, Some(&cue(0.0, 0.0, false)), 7, 101, 0.1),
+            AudioStep::Jump(0)
+        );
+        assert_eq!(
+            head.step(&drive, Some(&cue(0.0, 1.0, false)), 7, 101, 0.1),
+            AudioStep::Jump(50)
+        );
+        assert_eq!(head.step(&drive, None, 7, 101, 0.1), AudioStep::Play(0.1));
+    }
+
+    #[test]
+    fn beat_gate_plays_half_a_beat() {
+        let drive = AudioDrive {
+            mode: AudioDriveMode::BeatGate,
+            ..Default::default()
+        };
+        let mut head = AudioPlayhead::default();
+        assert_eq!(
+            head.step(&drive, Some(&cue(0.0, 0.0, false)), 0, 10, 0.1),
+            AudioStep::Hold
+        );
+        assert_eq!(
+            head.step(&drive, Some(&cue(0.0, 0.0, true)), 0, 10, 0.1),
+            AudioStep::Play(0.1)
+        );
+        let steps: Vec<AudioStep> = (0..3)
+            .map(|_| head.step(&drive, Some(&cue(0.0, 0.0, false)), 0, 10, 0.1))
+            .collect();
+        assert_eq!(
+            steps,
+            [AudioStep::Play(0.1), AudioStep::Play(0.1), AudioStep::Hold]
+        );
+    }
+
     #[test]
     fn play_direction_equality() {
         assert_eq!(PlayDirection::Forward, PlayDirection::Forward);
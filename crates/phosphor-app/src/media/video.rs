@@ -171,5 +171,36 @@ pub fn decode_all_frames(
     Ok((frames, delays_ms))
 }
 
+/// One RGBA frame at `at_secs`, scaled to `width`x`height` — a thumbnail
+/// for the file browser (#2247).
+pub fn grab_frame(path: &Path, at_secs: f64, width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let output = Command::new("ffmpeg")
+        .args(["-v", "quiet", "-ss", &format!("{at_secs:.2}"), "-i"])
+        .arg(path)
+        .args([
+            "-frames:v",
+            "1",
+            "-f",
+            "rawvideo",
+            "-pix_fmt",
+            "rgba",
+            "-s",
+            &format!("{width}x{height}"),
+            "pipe:1",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to spawn ffmpeg: {e}"))?;
+    let size = (width as usize) * (height as usize) * 4;
+    if output.stdout.len() < size {
+        return Err("ffmpeg returned no frame".to_string());
+    }
+    let mut rgba = output.stdout;
+    rgba.truncate(size);
+    Ok(rgba)
+}
+
 /// Maximum video duration (seconds) we'll pre-decode. Beyond this, reject.
 pub const MAX_PREDECODE_SECS: f64 = 60.0;
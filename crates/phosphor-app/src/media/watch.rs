@@ -0,0 +1,190 @@
+//! Watch folders (#2246). Media files that appear in a watched folder — a
+//! designer saving over the network, a render farm dropping clips — are
+//! picked up without a file dialog: they land at the top of the recent media
+//! list, ready under "+ Media", with a status toast.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::Result;
+use crossbeam_channel::{Receiver, Sender};
+use notify_debouncer_mini::{DebouncedEventKind, new_debouncer};
+
+use super::decoder::media_extensions;
+
+/// Long enough for most copies to settle before the file is reported.
+const SETTLE: Duration = Duration::from_secs(1);
+
+/// The media files seen so far in the watched folders, so an edit to one
+/// isn't reported as new.
+#[derive(Debug, Default)]
+pub struct FolderIndex {
+    known: HashSet<PathBuf>,
+    extensions: Vec<&'static str>,
+}
+
+impl FolderIndex {
+    /// Index what's already in `folders` (not their subfolders).
+    pub fn scan(folders: &[PathBuf], extensions: Vec<&'static str>) -> Self {
+        let mut index = Self {
+            known: HashSet::new(),
+            extensions,
+        };
+        for folder in folders {
+            let Ok(entries) = std::fs::read_dir(folder) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if index.is_media(&path) {
+                    index.known.insert(path);
+                }
+            }
+        }
+        index
+    }
+
+    fn is_media(&self, path: &Path) -> bool {
+        let hidden = path
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with('.'));
+        !hidden
+            && path.is_file()
+            && path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| self.extensions.contains(&e.to_lowercase().as_str()))
+    }
+
+    /// Take a changed path. True the first time a media file shows up; a
+    /// file that goes away is forgotten, so dropping it again counts.
+    pub fn admit(&mut self, path: &Path) -> bool {
+        if !path.exists() {
+            self.known.remove(path);
+            return false;
+        }
+        self.is_media(path) && self.known.insert(path.to_path_buf())
+    }
+}
+
+/// Watches on its own thread: the initial scan and the checks on each
+/// changed path touch the file system, which can stall on a network share.
+pub struct MediaFolderWatcher {
+    receiver: Receiver<PathBuf>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl MediaFolderWatcher {
+    /// Watch `folders`. Ones that don't exist or can't be watched are skipped
+    /// with a warning.
+    pub fn new(folders: &[PathBuf]) -> Result<Self> {
+        let folders = folders.to_vec();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let shutdown_clone = shutdown.clone();
+        let (new_tx, new_rx) = crossbeam_channel::unbounded();
+
+        std::thread::Builder::new()
+            .name("phosphor-watch-folders".into())
+            .spawn(move || {
+                let (tx, rx): (Sender<PathBuf>, Receiver<PathBuf>) = crossbeam_channel::unbounded();
+                let debouncer = new_debouncer(
+                    SETTLE,
+                    move |res: Result<
+                        Vec<notify_debouncer_mini::DebouncedEvent>,
+                        notify::Error,
+                    >| {
+                        if let Ok(events) = res {
+                            for event in events {
+                                if event.kind == DebouncedEventKind::Any {
+                                    let _ = tx.send(event.path);
+                                }
+                            }
+                        }
+                    },
+                );
+                let mut debouncer = match debouncer {
+                    Ok(d) => d,
+                    Err(e) => {
+                        log::warn!("Watch folders unavailable: {e}");
+                        return;
+                    }
+                };
+                for folder in &folders {
+                    if !folder.is_dir() {
+                        log::warn!("Watch folder {} not found", folder.display());
+                        continue;
+                    }
+                    match debouncer
+                        .watcher()
+                        .watch(folder, notify::RecursiveMode::NonRecursive)
+                    {
+                        Ok(()) => log::info!("Watching {} for new media", folder.display()),
+                        Err(e) => log::warn!("Can't watch {}: {e}", folder.display()),
+                    }
+                }
+                let mut index = FolderIndex::scan(&folders, media_extensions());
+
+                while !shutdown_clone.load(Ordering::Relaxed) {
+                    match rx.recv_timeout(Duration::from_millis(250)) {
+                        Ok(path) => {
+                            if index.admit(&path) && new_tx.send(path).is_err() {
+                                break;
+                            }
+                        }
+                        Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
+                        Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            receiver: new_rx,
+            shutdown,
+        })
+    }
+
+    /// Media files that appeared since the last call, oldest first.
+    pub fn drain_new(&self) -> Vec<PathBuf> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for MediaFolderWatcher {
+    fn drop(&mut self) {
+        // Not joined: the thread notices within one receive timeout
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_new_media_files_are_admitted() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.png");
+        std::fs::write(&old, b"").unwrap();
+        let mut index = FolderIndex::scan(&[dir.path().to_path_buf()], vec!["png", "mp4"]);
+
+        let new = dir.path().join("New.MP4");
+        let notes = dir.path().join("notes.txt");
+        let hidden = dir.path().join(".partial.mp4");
+        for path in [&new, &notes, &hidden] {
+            std::fs::write(path, b"").unwrap();
+        }
+        assert!(!index.admit(&old));
+        assert!(index.admit(&new));
+        assert!(!index.admit(&new), "a second write isn't new");
+        assert!(!index.admit(&notes));
+        assert!(!index.admit(&hidden));
+
+        std::fs::remove_file(&new).unwrap();
+        assert!(!index.admit(&new));
+        std::fs::write(&new, b"").unwrap();
+        assert!(index.admit(&new), "dropped again after removal");
+    }
+}
@@ -4,7 +4,12 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use crossbeam_channel::{Receiver, Sender};
 use nokhwa::Camera;
 use nokhwa::pixel_format::RgbAFormat;
-use nokhwa::utils::{ApiBackend, CameraIndex, RequestedFormat, RequestedFormatType, Resolution};
+use nokhwa::utils::{
+    ApiBackend, CameraFormat, CameraIndex, FrameFormat, RequestedFormat, RequestedFormatType,
+    Resolution,
+};
+
+use super::types::{FramePool, WebcamMode};
 
 /// A single decoded webcam frame (RGBA).
 pub struct WebcamFrame {
@@ -13,33 +18,41 @@ pub struct WebcamFrame {
     pub height: u32,
 }
 
+/// Send a frame without blocking; if the consumer is behind, the frame is
+/// dropped and its buffer goes straight back to the pool.
+pub(crate) fn send_or_recycle(tx: &Sender<WebcamFrame>, pool: &FramePool, frame: WebcamFrame) {
+    if let Err(e) = tx.try_send(frame) {
+        pool.give(e.into_inner().data);
+    }
+}
+
 /// Cross-platform webcam capture running on a dedicated thread.
 pub struct WebcamCapture {
     frame_rx: Receiver<WebcamFrame>,
+    pool: FramePool,
     shutdown: Arc<AtomicBool>,
     thread: Option<std::thread::JoinHandle<()>>,
     pub device_name: String,
     pub resolution: (u32, u32),
+    /// Frame rate the driver actually negotiated.
+    pub fps: u32,
 }
 
-fn requested_format(resolution: Option<(u32, u32)>) -> RequestedFormat<'static> {
-    match resolution {
-        Some((w, h)) => RequestedFormat::new::<RgbAFormat>(RequestedFormatType::Closest(
-            nokhwa::utils::CameraFormat::new(
-                Resolution::new(w, h),
-                nokhwa::utils::FrameFormat::MJPEG,
-                30,
-            ),
+fn requested_format(mode: Option<WebcamMode>, format: FrameFormat) -> RequestedFormat<'static> {
+    match mode {
+        Some(m) => RequestedFormat::new::<RgbAFormat>(RequestedFormatType::Closest(
+            CameraFormat::new(Resolution::new(m.width, m.height), format, m.fps),
         )),
         None => RequestedFormat::new::<RgbAFormat>(RequestedFormatType::AbsoluteHighestResolution),
     }
 }
 
-/// Try to open a camera with MJPEG preference, falling back to any supported format.
-/// Many Windows webcams only support raw formats (YUYV/NV12), not MJPEG.
+/// Try to open a camera in the requested mode as MJPEG, then YUYV, falling back
+/// to any supported format. MJPEG is the only way most USB 2 cameras reach
+/// 1080p60; many Windows webcams only support raw formats (YUYV/NV12).
 fn open_camera_with_fallback(
     device_index: u32,
-    resolution: Option<(u32, u32)>,
+    mode: Option<WebcamMode>,
 ) -> Result<Camera, String> {
     let try_open = |fmt| -> Result<Camera, String> {
         let mut c =
@@ -48,7 +61,13 @@ fn open_camera_with_fallback(
         Ok(c)
     };
 
-    try_open(requested_format(resolution)).or_else(|first_err| {
+    try_open(requested_format(mode, FrameFormat::MJPEG)).or_else(|first_err| {
+        if mode.is_some() {
+            match try_open(requested_format(mode, FrameFormat::YUYV)) {
+                Ok(c) => return Ok(c),
+                Err(e) => log::warn!("Webcam MJPEG ({first_err}) and YUYV ({e}) both failed"),
+            }
+        }
         log::warn!("Preferred webcam format failed ({first_err}), trying fallback...");
         try_open(RequestedFormat::new::<RgbAFormat>(
             RequestedFormatType::AbsoluteHighestResolution,
@@ -58,12 +77,14 @@ fn open_camera_with_fallback(
 }
 
 impl WebcamCapture {
-    /// Start capturing from the given camera index at the requested resolution.
+    /// Start capturing from the given camera index at the requested mode.
     /// Validates the camera can be opened before spawning the capture thread.
-    pub fn start(device_index: u32, resolution: Option<(u32, u32)>) -> Result<Self, String> {
+    pub fn start(device_index: u32, mode: Option<WebcamMode>) -> Result<Self, String> {
         let (frame_tx, frame_rx) = crossbeam_channel::bounded(2);
         let shutdown = Arc::new(AtomicBool::new(false));
         let shutdown_clone = shutdown.clone();
+        let pool = FramePool::default();
+        let pool_clone = pool.clone();
 
         // Probe device name on main thread first
         let devices = list_devices().unwrap_or_default();
@@ -75,17 +96,19 @@ impl WebcamCapture {
 
         // Validate camera access on calling thread (Camera is !Send so we can't move it).
         // Open, check it works, then close so the capture thread can reopen it.
-        let actual_res = {
-            let mut camera = open_camera_with_fallback(device_index, resolution)?;
+        let (actual_res, actual_fps) = {
+            let mut camera = open_camera_with_fallback(device_index, mode)?;
             let r = camera.resolution();
             let res = (r.width(), r.height());
+            let fps = camera.frame_rate();
+            log::info!("Webcam negotiated {} input", camera.frame_format());
             let _ = camera.stop_stream();
             drop(camera);
-            res
+            (res, fps)
         };
 
         log::info!(
-            "Webcam validated: {}x{} on device {device_index}",
+            "Webcam validated: {}x{} @ {actual_fps} fps on device {device_index}",
             actual_res.0,
             actual_res.1
         );
@@ -94,7 +117,7 @@ impl WebcamCapture {
             .name("webcam-capture".into())
             .spawn(move || {
                 match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                    capture_thread(device_index, resolution, frame_tx, shutdown_clone);
+                    capture_thread(device_index, mode, frame_tx, pool_clone, shutdown_clone);
                 })) {
                     Ok(()) => {}
                     Err(e) => {
@@ -113,23 +136,32 @@ impl WebcamCapture {
 
         Ok(Self {
             frame_rx,
+            pool,
             shutdown,
             thread: Some(handle),
             device_name,
             resolution: actual_res,
+            fps: actual_fps,
         })
     }
 
     /// Non-blocking read of the latest frame.
     pub fn try_recv_frame(&self) -> Option<WebcamFrame> {
-        // Drain to get the latest frame (drop old ones)
-        let mut latest = None;
+        // Drain to get the latest frame (recycle older ones)
+        let mut latest: Option<WebcamFrame> = None;
         while let Ok(frame) = self.frame_rx.try_recv() {
-            latest = Some(frame);
+            if let Some(old) = latest.replace(frame) {
+                self.pool.give(old.data);
+            }
         }
         latest
     }
 
+    /// Hand a consumed frame's buffer back to the capture thread.
+    pub fn recycle(&self, frame: WebcamFrame) {
+        self.pool.give(frame.data);
+    }
+
     /// Stop capture and join the thread.
     pub fn stop(&mut self) {
         self.shutdown.store(true, Ordering::Relaxed);
@@ -180,6 +212,27 @@ pub fn list_devices() -> Result<Vec<(u32, String)>, String> {
     Ok(result)
 }
 
+/// List the capture modes a device advertises, largest first.
+///
+/// Opens the device without starting a stream, so call it before (not while)
+/// capturing — some drivers refuse a second handle to a streaming camera.
+pub fn list_modes(device_index: u32) -> Result<Vec<WebcamMode>, String> {
+    let mut camera = Camera::new(
+        CameraIndex::Index(device_index),
+        RequestedFormat::new::<RgbAFormat>(RequestedFormatType::None),
+    )
+    .map_err(|e| camera_error_message(device_index, &e.to_string()))?;
+    let formats = camera
+        .compatible_camera_formats()
+        .map_err(|e| format!("Failed to query camera {device_index} formats: {e}"))?;
+    let mut modes: Vec<WebcamMode> = formats
+        .iter()
+        .map(|f| WebcamMode::new(f.width(), f.height(), f.frame_rate()))
+        .collect();
+    WebcamMode::sort_dedup(&mut modes);
+    Ok(modes)
+}
+
 /// Check if any webcam is available. Cached via OnceLock.
 #[allow(dead_code)]
 pub fn webcam_available() -> bool {
@@ -202,11 +255,12 @@ fn camera_error_message(device_index: u32, err: &str) -> String {
 
 fn capture_thread(
     device_index: u32,
-    resolution: Option<(u32, u32)>,
+    mode: Option<WebcamMode>,
     frame_tx: Sender<WebcamFrame>,
+    pool: FramePool,
     shutdown: Arc<AtomicBool>,
 ) {
-    let mut camera = match open_camera_with_fallback(device_index, resolution) {
+    let mut camera = match open_camera_with_fallback(device_index, mode) {
         Ok(c) => c,
         Err(e) => {
             log::error!("{e}");
@@ -228,23 +282,25 @@ fn capture_thread(
         match camera.frame() {
             Ok(buffer) => {
                 let res = buffer.resolution();
-                // decode_image can panic on corrupted MJPEG frames (libjpeg fatal error).
+                // Decode straight into a recycled buffer (no per-frame allocation).
+                let mut data = pool.take(res.width() as usize * res.height() as usize * 4);
+                // Decoding can panic on corrupted MJPEG frames (libjpeg fatal error).
                 // Catch the panic so one bad frame doesn't kill the capture thread.
                 let decoded = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                    buffer.decode_image::<RgbAFormat>()
+                    buffer.decode_image_to_buffer::<RgbAFormat>(&mut data)
                 }));
                 match decoded {
-                    Ok(Ok(img)) => {
+                    Ok(Ok(())) => {
                         consecutive_panics = 0;
                         let frame = WebcamFrame {
-                            data: img.into_raw(),
+                            data,
                             width: res.width(),
                             height: res.height(),
                         };
-                        // try_send: drop frame if consumer is behind
-                        let _ = frame_tx.try_send(frame);
+                        send_or_recycle(&frame_tx, &pool, frame);
                     }
                     Ok(Err(e)) => {
+                        pool.give(data);
                         log::warn!("Failed to decode webcam frame: {e}");
                     }
                     Err(_) => {
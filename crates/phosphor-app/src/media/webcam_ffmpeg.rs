@@ -5,15 +5,22 @@ use std::sync::atomic::{AtomicBool, Ordering};
 
 use crossbeam_channel::Receiver;
 
-use super::webcam::WebcamFrame;
+use super::types::{FramePool, WebcamMode};
+use super::webcam::{WebcamFrame, send_or_recycle};
+
+/// v4l2 lists frame sizes but not rates; each size is offered at these and
+/// the driver snaps to the nearest rate it supports.
+const V4L2_FPS_CHOICES: &[u32] = &[15, 30, 60];
 
 /// FFmpeg-based webcam capture for DirectShow/virtual cameras.
 pub struct FfmpegCapture {
     frame_rx: Receiver<WebcamFrame>,
+    pool: FramePool,
     shutdown: Arc<AtomicBool>,
     thread: Option<std::thread::JoinHandle<()>>,
     pub device_name: String,
     pub resolution: (u32, u32),
+    pub fps: u32,
 }
 
 /// Check if ffmpeg is available on PATH.
@@ -104,18 +111,22 @@ fn list_devices_linux() -> Vec<(u32, String)> {
 }
 
 impl FfmpegCapture {
-    /// Start capturing from the given device name at the requested resolution.
-    pub fn start(device_name: &str, resolution: Option<(u32, u32)>) -> Result<Self, String> {
+    /// Start capturing from the given device name at the requested mode.
+    pub fn start(device_name: &str, mode: Option<WebcamMode>) -> Result<Self, String> {
         if !ffmpeg_available() {
             return Err(
                 "FFmpeg not found. Install FFmpeg and ensure it is in your PATH.".to_string(),
             );
         }
 
-        let res = resolution.unwrap_or((1280, 720));
+        let mode = mode.unwrap_or_default();
+        let res = mode.resolution();
+        let fps = mode.fps;
         let (frame_tx, frame_rx) = crossbeam_channel::bounded(2);
         let shutdown = Arc::new(AtomicBool::new(false));
         let shutdown_clone = shutdown.clone();
+        let pool = FramePool::default();
+        let pool_clone = pool.clone();
         let name = device_name.to_string();
         let name_clone = name.clone();
 
@@ -125,12 +136,19 @@ impl FfmpegCapture {
         let handle = std::thread::Builder::new()
             .name("ffmpeg-webcam".into())
             .spawn(move || {
-                capture_thread(&name_clone, actual_res, frame_tx, shutdown_clone);
+                capture_thread(
+                    &name_clone,
+                    actual_res,
+                    fps,
+                    frame_tx,
+                    pool_clone,
+                    shutdown_clone,
+                );
             })
             .map_err(|e| format!("Failed to spawn ffmpeg capture thread: {e}"))?;
 
         log::info!(
-            "FFmpeg webcam started: {}x{} on '{}'",
+            "FFmpeg webcam started: {}x{} @ {fps} fps on '{}'",
             actual_res.0,
             actual_res.1,
             name
@@ -138,22 +156,31 @@ impl FfmpegCapture {
 
         Ok(Self {
             frame_rx,
+            pool,
             shutdown,
             thread: Some(handle),
             device_name: name,
             resolution: actual_res,
+            fps,
         })
     }
 
     /// Non-blocking read of the latest frame.
     pub fn try_recv_frame(&self) -> Option<WebcamFrame> {
-        let mut latest = None;
+        let mut latest: Option<WebcamFrame> = None;
         while let Ok(frame) = self.frame_rx.try_recv() {
-            latest = Some(frame);
+            if let Some(old) = latest.replace(frame) {
+                self.pool.give(old.data);
+            }
         }
         latest
     }
 
+    /// Hand a consumed frame's buffer back to the capture thread.
+    pub fn recycle(&self, frame: WebcamFrame) {
+        self.pool.give(frame.data);
+    }
+
     /// Stop capture and join the thread.
     pub fn stop(&mut self) {
         self.shutdown.store(true, Ordering::Relaxed);
@@ -304,14 +331,16 @@ fn read_exact_timeout(
 fn capture_thread(
     device_name: &str,
     resolution: (u32, u32),
+    fps: u32,
     frame_tx: crossbeam_channel::Sender<WebcamFrame>,
+    pool: FramePool,
     shutdown: Arc<AtomicBool>,
 ) {
     let (format_flag, _) = platform_capture_args();
     let input = device_input_arg(device_name);
     let size = format!("{}x{}", resolution.0, resolution.1);
 
-    let mut child = match spawn_ffmpeg(format_flag, &input, &size) {
+    let mut child = match spawn_ffmpeg(format_flag, &input, &size, fps) {
         Ok(c) => c,
         Err(e) => {
             log::error!("Failed to start ffmpeg capture: {e}");
@@ -320,7 +349,6 @@ fn capture_thread(
     };
 
     let frame_bytes = (resolution.0 as usize) * (resolution.1 as usize) * 4;
-    let mut buf = vec![0u8; frame_bytes];
     let mut stdout = match child.stdout.take() {
         Some(s) => s,
         None => {
@@ -338,6 +366,8 @@ fn capture_thread(
     );
 
     while !shutdown.load(Ordering::Relaxed) {
+        // Read straight into a recycled buffer (no per-frame allocation or copy)
+        let mut buf = pool.take(frame_bytes);
         let mut filled = 0;
         let mut failed = false;
         while filled < frame_bytes {
@@ -365,11 +395,11 @@ fn capture_thread(
         }
 
         let frame = WebcamFrame {
-            data: buf.clone(),
+            data: buf,
             width: resolution.0,
             height: resolution.1,
         };
-        let _ = frame_tx.try_send(frame);
+        send_or_recycle(&frame_tx, &pool, frame);
     }
 
     let _ = child.kill();
@@ -377,7 +407,8 @@ fn capture_thread(
     log::info!("FFmpeg capture thread stopped");
 }
 
-fn spawn_ffmpeg(format_flag: &str, input: &str, size: &str) -> Result<Child, String> {
+fn spawn_ffmpeg(format_flag: &str, input: &str, size: &str, fps: u32) -> Result<Child, String> {
+    let fps = fps.to_string();
     Command::new("ffmpeg")
         .args([
             "-f",
@@ -385,7 +416,7 @@ fn spawn_ffmpeg(format_flag: &str, input: &str, size: &str) -> Result<Child, Str
             "-video_size",
             size,
             "-framerate",
-            "30",
+            &fps,
             "-i",
             input,
             "-f",
@@ -402,6 +433,86 @@ fn spawn_ffmpeg(format_flag: &str, input: &str, size: &str) -> Result<Child, Str
         .map_err(|e| format!("Failed to spawn ffmpeg: {e}"))
 }
 
+/// List the capture modes ffmpeg reports for a device, largest first.
+/// Empty when the backend can't enumerate (the picker then offers
+/// `WebcamMode::COMMON`).
+pub fn list_modes(device_name: &str) -> Result<Vec<WebcamMode>, String> {
+    let (format_flag, _) = platform_capture_args();
+    let input = device_input_arg(device_name);
+    let mut cmd = Command::new("ffmpeg");
+    cmd.args(["-hide_banner", "-f", format_flag]);
+    #[cfg(target_os = "windows")]
+    cmd.args(["-list_options", "true"]);
+    #[cfg(target_os = "macos")]
+    // avfoundation has no list flag; an impossible size makes it print
+    // every supported mode in the error.
+    cmd.args(["-video_size", "1x1"]);
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    cmd.args(["-list_formats", "all"]);
+    let output = cmd
+        .args(["-i", &input])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg: {e}"))?;
+    Ok(parse_mode_list(&String::from_utf8_lossy(&output.stderr)))
+}
+
+/// Parse capture modes out of ffmpeg's device-probe stderr. Understands all
+/// three platform dialects:
+/// - v4l2 `-list_formats`: `Compressed: mjpeg : Motion-JPEG : 1280x720 640x480`
+/// - dshow `-list_options`: `vcodec=mjpeg  min s=1280x720 fps=5 max s=1280x720 fps=30`
+/// - avfoundation error: `1280x720@[1.000000 30.000000]fps`
+fn parse_mode_list(stderr: &str) -> Vec<WebcamMode> {
+    fn parse_size(s: &str) -> Option<(u32, u32)> {
+        let (w, h) = s.split_once('x')?;
+        Some((w.trim().parse().ok()?, h.trim().parse().ok()?))
+    }
+    fn parse_fps(s: &str) -> Option<u32> {
+        s.trim().parse::<f64>().ok().map(|f| f.round() as u32)
+    }
+
+    let mut modes = Vec::new();
+    for line in stderr.lines() {
+        if let Some(idx) = line.find("max s=") {
+            // dshow
+            let rest = &line[idx + "max s=".len()..];
+            let mut parts = rest.split_whitespace();
+            let size = parts.next().and_then(parse_size);
+            let fps = parts
+                .next()
+                .and_then(|p| p.strip_prefix("fps="))
+                .and_then(parse_fps);
+            if let (Some((w, h)), Some(fps)) = (size, fps) {
+                modes.push(WebcamMode::new(w, h, fps));
+            }
+        } else if let Some(at) = line.find("@[") {
+            // avfoundation: WxH@[min max]fps — take the max rate
+            let size = line[..at].split_whitespace().last().and_then(parse_size);
+            let fps = line[at + 2..]
+                .split(']')
+                .next()
+                .and_then(|range| range.split_whitespace().last())
+                .and_then(parse_fps);
+            if let (Some((w, h)), Some(fps)) = (size, fps) {
+                modes.push(WebcamMode::new(w, h, fps));
+            }
+        } else if line.contains("Compressed:") || line.contains("Raw") {
+            // v4l2: sizes after the last ':'
+            if let Some((_, sizes)) = line.rsplit_once(':') {
+                for (w, h) in sizes.split_whitespace().filter_map(parse_size) {
+                    for &fps in V4L2_FPS_CHOICES {
+                        modes.push(WebcamMode::new(w, h, fps));
+                    }
+                }
+            }
+        }
+    }
+    WebcamMode::sort_dedup(&mut modes);
+    modes
+}
+
 /// Parse ffmpeg device list output. Platform-specific parsing.
 // `Result` is kept for parity with `list_devices()` (which can fail to spawn
 // ffmpeg); this parser itself never errors.
@@ -465,6 +576,48 @@ mod tests {
         let _ = ffmpeg_available();
     }
 
+    #[test]
+    fn test_parse_v4l2_modes() {
+        let stderr = "[video4linux2,v4l2 @ 0x55] Compressed:       mjpeg :          Motion-JPEG : 1280x720 640x480\n\
+                      [video4linux2,v4l2 @ 0x55] Raw       :     yuyv422 :           YUYV 4:2:2 : 640x480\n";
+        let modes = parse_mode_list(stderr);
+        assert_eq!(modes.len(), 2 * V4L2_FPS_CHOICES.len());
+        assert_eq!(modes[0], WebcamMode::new(1280, 720, 60));
+        assert!(modes.contains(&WebcamMode::new(640, 480, 15)));
+    }
+
+    #[test]
+    fn test_parse_dshow_modes() {
+        let stderr = r#"[dshow @ 0000] DirectShow video device options (from video devices)
+[dshow @ 0000]  Pin "Capture" (alternative pin name "0")
+[dshow @ 0000]   vcodec=mjpeg  min s=1920x1080 fps=5 max s=1920x1080 fps=30
+[dshow @ 0000]   pixel_format=yuyv422  min s=640x480 fps=5 max s=640x480 fps=30.0003
+"#;
+        let modes = parse_mode_list(stderr);
+        assert_eq!(
+            modes,
+            vec![
+                WebcamMode::new(1920, 1080, 30),
+                WebcamMode::new(640, 480, 30)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_avfoundation_modes() {
+        let stderr = "[avfoundation @ 0x7f] Supported modes:\n\
+                      [avfoundation @ 0x7f]   1280x720@[1.000000 30.000000]fps\n\
+                      [avfoundation @ 0x7f]   1920x1080@[1.000000 60.000000]fps\n";
+        let modes = parse_mode_list(stderr);
+        assert_eq!(
+            modes,
+            vec![
+                WebcamMode::new(1920, 1080, 60),
+                WebcamMode::new(1280, 720, 30)
+            ]
+        );
+    }
+
     #[test]
     #[cfg(target_os = "windows")]
     fn test_parse_dshow_devices() {
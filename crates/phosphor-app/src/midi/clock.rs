@@ -116,13 +116,17 @@ impl MidiClock {
     }
 
     /// Total beats counted since Start.
-    #[allow(dead_code)]
     pub fn beat_count(&self) -> u32 {
         self.beat_count
     }
 
+    /// Whether the beat just crossed starts a bar. MIDI clock carries no
+    /// bar of its own, so bars are counted in 4/4 from Start.
+    pub fn on_bar_line(&self, beat_crossed: bool) -> bool {
+        beat_crossed && self.beat_count % 4 == 0
+    }
+
     /// Derived BPM from clock ticks (0 if not enough data).
-    #[allow(dead_code)]
     pub fn bpm(&self) -> f64 {
         self.bpm
     }
@@ -134,7 +138,6 @@ impl MidiClock {
     }
 
     /// Whether we've received enough ticks to report BPM.
-    #[allow(dead_code)]
     pub fn has_bpm(&self) -> bool {
         self.tick_intervals.len() >= 4
     }
@@ -181,6 +184,20 @@ mod tests {
         assert_eq!(clock.beat_count(), 1);
     }
 
+    #[test]
+    fn clock_bar_lines_every_four_beats() {
+        let mut clock = MidiClock::new();
+        clock.process_byte(0xFA);
+        let mut bars = Vec::new();
+        for _ in 0..TICKS_PER_BEAT * 8 {
+            let beat = clock.process_byte(0xF8);
+            if clock.on_bar_line(beat) {
+                bars.push(clock.beat_count());
+            }
+        }
+        assert_eq!(bars, vec![4, 8]);
+    }
+
     #[test]
     fn clock_phase_progresses() {
         let mut clock = MidiClock::new();
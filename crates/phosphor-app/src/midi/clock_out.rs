@@ -0,0 +1,180 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use midir::MidiOutputConnection;
+
+const TICKS_PER_BEAT: u32 = 24;
+const CLOCK: u8 = 0xF8;
+const START: u8 = 0xFA;
+const STOP: u8 = 0xFC;
+/// How hard each tick pulls the clock toward the detected beat phase.
+const PHASE_GAIN: f64 = 0.5;
+/// Most a single tick may be stretched or shortened by that pull.
+const MAX_CORRECTION: f64 = 0.1;
+
+/// The detected tempo as of `at`.
+#[derive(Debug, Clone, Copy)]
+struct Tempo {
+    bpm: f64,
+    beat_phase: f64,
+    at: Instant,
+}
+
+impl Tempo {
+    /// Beat phase extrapolated to now.
+    fn phase_now(&self) -> f64 {
+        (self.beat_phase + self.at.elapsed().as_secs_f64() * self.bpm / 60.0).fract()
+    }
+}
+
+/// Sends MIDI clock (24 ticks per beat) at the detected tempo (#2205), so
+/// lighting desks and DAWs can chase the analysis. Runs on its own thread so
+/// tick timing doesn't follow the frame rate; each tick nudges the next one
+/// toward the detected beat phase, keeping the downbeat where the music's is.
+pub struct MidiClockOut {
+    pub port_name: String,
+    tempo: Arc<Mutex<Option<Tempo>>>,
+    shutdown: Arc<AtomicBool>,
+    _thread: Option<JoinHandle<()>>,
+}
+
+impl MidiClockOut {
+    /// Open an output port by name and start the clock thread. No ticks go
+    /// out until the first tempo arrives.
+    pub fn open(port_name: &str) -> anyhow::Result<Self> {
+        let midi_out = midir::MidiOutput::new("phosphor")?;
+        let ports = midi_out.ports();
+        let port = ports
+            .iter()
+            .find(|p| midi_out.port_name(p).is_ok_and(|n| n == port_name))
+            .ok_or_else(|| anyhow::anyhow!("MIDI output '{port_name}' not found"))?;
+        let connection = midi_out
+            .connect(port, "phosphor-clock")
+            .map_err(|e| anyhow::anyhow!("Failed to open MIDI output: {e}"))?;
+
+        let tempo = Arc::new(Mutex::new(None));
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (shared, flag) = (tempo.clone(), shutdown.clone());
+        let handle = thread::Builder::new()
+            .name("phosphor-midi-clock".into())
+            .spawn(move || run(connection, &shared, &flag))?;
+        log::info!("Sending MIDI clock to {port_name}");
+
+        Ok(Self {
+            port_name: port_name.to_string(),
+            tempo,
+            shutdown,
+            _thread: Some(handle),
+        })
+    }
+
+    /// Latest detected tempo; a BPM of zero holds the clock.
+    pub fn set_tempo(&self, bpm: f64, beat_phase: f64) {
+        let tempo = (bpm > 0.0).then(|| Tempo {
+            bpm,
+            beat_phase,
+            at: Instant::now(),
+        });
+        if let Ok(mut shared) = self.tempo.lock() {
+            *shared = tempo;
+        }
+    }
+
+    /// List all available MIDI output port names.
+    pub fn list_ports() -> Vec<String> {
+        let Ok(midi_out) = midir::MidiOutput::new("phosphor-enumerate") else {
+            return Vec::new();
+        };
+        midi_out
+            .ports()
+            .iter()
+            .filter_map(|p| midi_out.port_name(p).ok())
+            .collect()
+    }
+}
+
+/// The thread sends Stop and closes the port once it sees the flag.
+impl Drop for MidiClockOut {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+fn run(mut connection: MidiOutputConnection, tempo: &Mutex<Option<Tempo>>, shutdown: &AtomicBool) {
+    // Tick within the beat, and when the next one is due; None while stopped
+    let mut clock: Option<(u32, Instant)> = None;
+    while !shutdown.load(Ordering::Relaxed) {
+        let current = tempo.lock().ok().and_then(|t| *t);
+        let Some(current) = current else {
+            if clock.take().is_some() {
+                let _ = connection.send(&[STOP]);
+            }
+            thread::sleep(Duration::from_millis(10));
+            continue;
+        };
+        let (tick, due) = match clock {
+            Some(clock) => clock,
+            None => {
+                // Start on the next detected beat, so receivers count it as one
+                let wait = (1.0 - current.phase_now()) * 60.0 / current.bpm;
+                thread::sleep(Duration::from_secs_f64(wait));
+                if connection.send(&[START]).is_err() {
+                    break;
+                }
+                (0, Instant::now())
+            }
+        };
+        if let Some(wait) = due.checked_duration_since(Instant::now()) {
+            thread::sleep(wait);
+        }
+        if connection.send(&[CLOCK]).is_err() {
+            log::warn!("MIDI clock output failed, stopping it");
+            break;
+        }
+        let interval = tick_interval(
+            current.bpm,
+            f64::from(tick) / f64::from(TICKS_PER_BEAT),
+            current.phase_now(),
+        );
+        clock = Some(((tick + 1) % TICKS_PER_BEAT, due + interval));
+    }
+    if clock.is_some() {
+        let _ = connection.send(&[STOP]);
+    }
+}
+
+/// Time until the next tick: the nominal tick length at `bpm`, shortened
+/// when the clock (at `clock_phase` within the beat) trails the detected
+/// `target_phase` and stretched when it's ahead.
+fn tick_interval(bpm: f64, clock_phase: f64, target_phase: f64) -> Duration {
+    let nominal = 60.0 / (bpm * f64::from(TICKS_PER_BEAT));
+    // Wrapped to [-0.5, 0.5) beats; positive means the clock is behind
+    let error = (target_phase - clock_phase + 0.5).rem_euclid(1.0) - 0.5;
+    let correction = (error * PHASE_GAIN).clamp(-MAX_CORRECTION, MAX_CORRECTION);
+    Duration::from_secs_f64(nominal * (1.0 - correction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks_follow_tempo_and_pull_toward_the_beat() {
+        // 120 BPM: 24 ticks in half a second
+        let nominal = tick_interval(120.0, 0.25, 0.25);
+        assert!((nominal.as_secs_f64() - 0.5 / 24.0).abs() < 1e-9);
+
+        // Behind the detected phase: tick sooner; ahead: later
+        assert!(tick_interval(120.0, 0.25, 0.3) < nominal);
+        assert!(tick_interval(120.0, 0.3, 0.25) > nominal);
+        // Across the beat boundary the short way round
+        assert!(tick_interval(120.0, 0.98, 0.02) < nominal);
+        assert!(tick_interval(120.0, 0.02, 0.98) > nominal);
+
+        // Never more than the cap, however far off
+        let far = tick_interval(120.0, 0.0, 0.49);
+        assert!((far.as_secs_f64() - nominal.as_secs_f64() * 0.9).abs() < 1e-9);
+    }
+}
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 
 use super::types::{MidiMsgType, TriggerAction};
+use crate::schema::Schema;
 
 /// A single MIDI CC/Note → parameter or trigger mapping.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,10 +72,21 @@ pub struct MidiConfig {
     pub triggers: HashMap<TriggerAction, MidiMapping>,
     #[serde(default)]
     pub port_name: Option<String>,
+    /// Send MIDI clock at the detected tempo (#2205).
+    #[serde(default)]
+    pub clock_out_enabled: bool,
+    #[serde(default)]
+    pub clock_out_port: Option<String>,
 }
 
+/// `midi.json` layout history (#2202).
+pub const SCHEMA: Schema = Schema {
+    name: "MIDI config",
+    migrations: &[],
+};
+
 fn default_version() -> u32 {
-    1
+    SCHEMA.version()
 }
 
 fn default_true() -> bool {
@@ -84,11 +96,13 @@ fn default_true() -> bool {
 impl Default for MidiConfig {
     fn default() -> Self {
         Self {
-            version: 1,
+            version: SCHEMA.version(),
             enabled: true,
             params: HashMap::new(),
             triggers: HashMap::new(),
             port_name: None,
+            clock_out_enabled: false,
+            clock_out_port: None,
         }
     }
 }
@@ -96,48 +110,18 @@ impl Default for MidiConfig {
 impl MidiConfig {
     /// Path to the MIDI config file (~/.config/phosphor/midi.json).
     pub fn config_path() -> PathBuf {
-        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-        config_dir.join("phosphor").join("midi.json")
+        crate::paths::config_dir().join("midi.json")
     }
 
     /// Load config from disk, falling back to default on any error.
     pub fn load() -> Self {
-        let path = Self::config_path();
-        match std::fs::read_to_string(&path) {
-            Ok(contents) => match serde_json::from_str(&contents) {
-                Ok(config) => {
-                    log::info!("Loaded MIDI config from {}", path.display());
-                    config
-                }
-                Err(e) => {
-                    log::warn!("Failed to parse MIDI config: {e}");
-                    Self::default()
-                }
-            },
-            Err(_) => {
-                log::info!("No MIDI config found, using defaults");
-                Self::default()
-            }
-        }
+        SCHEMA.load(&Self::config_path()).unwrap_or_default()
     }
 
     /// Save config to disk.
     pub fn save(&self) {
-        let path = Self::config_path();
-        if let Some(parent) = path.parent() {
-            if let Err(e) = std::fs::create_dir_all(parent) {
-                log::error!("Failed to create config dir: {e}");
-                return;
-            }
-        }
         match serde_json::to_string_pretty(self) {
-            Ok(json) => {
-                if let Err(e) = std::fs::write(&path, json) {
-                    log::error!("Failed to write MIDI config: {e}");
-                } else {
-                    log::debug!("Saved MIDI config to {}", path.display());
-                }
-            }
+            Ok(json) => crate::persist::write(Self::config_path(), json),
             Err(e) => log::error!("Failed to serialize MIDI config: {e}"),
         }
     }
@@ -1,4 +1,5 @@
 pub mod clock;
+pub mod clock_out;
 pub mod input;
 pub mod mapping;
 pub mod types;
@@ -8,6 +9,7 @@ use std::time::Instant;
 
 use crossbeam_channel::Receiver;
 
+use self::clock_out::MidiClockOut;
 use self::input::MidiPort;
 use self::mapping::{MidiConfig, MidiMapping};
 use self::types::{LearnTarget, MidiMessage, MidiMsgType, TriggerAction};
@@ -30,6 +32,9 @@ pub struct MidiSystem {
     pub last_message: Option<MidiMessage>,
     last_port_poll: Instant,
     pub available_ports: Vec<String>,
+    /// MIDI clock sent at the detected tempo (#2205).
+    clock_out: Option<MidiClockOut>,
+    pub available_out_ports: Vec<String>,
     /// Last CC values for binding bus: (cc, channel) -> (raw_value, device_name).
     pub last_cc_values: HashMap<(u8, u8), (u8, String)>,
 }
@@ -48,11 +53,15 @@ impl MidiSystem {
             last_message: None,
             last_port_poll: Instant::now(),
             available_ports: Vec::new(),
+            clock_out: None,
+            available_out_ports: Vec::new(),
             last_cc_values: HashMap::default(),
         };
 
         // Initial port scan
         sys.available_ports = MidiPort::list_ports();
+        sys.available_out_ports = MidiClockOut::list_ports();
+        sys.restart_clock_out();
 
         // Auto-connect to saved port or first available
         if let Some(ref saved) = sys.config.port_name.clone() {
@@ -127,6 +136,84 @@ impl MidiSystem {
         beat_crossed
     }
 
+    /// Turn MIDI clock output on or off.
+    pub fn set_clock_out_enabled(&mut self, enabled: bool) {
+        self.config.clock_out_enabled = enabled;
+        self.restart_clock_out();
+        self.config.save();
+    }
+
+    /// Send MIDI clock to another output port.
+    pub fn set_clock_out_port(&mut self, port_name: &str) {
+        self.config.clock_out_port = Some(port_name.to_string());
+        self.restart_clock_out();
+        self.config.save();
+    }
+
+    /// The output port the clock is going to, if it's running.
+    pub fn clock_out_port(&self) -> Option<&str> {
+        self.clock_out.as_ref().map(|c| c.port_name.as_str())
+    }
+
+    /// Reopen the clock output as configured.
+    pub fn restart_clock_out(&mut self) {
+        self.clock_out = None;
+        if !self.config.clock_out_enabled {
+            return;
+        }
+        let Some(port) = self.config.clock_out_port.clone() else {
+            return;
+        };
+        match MidiClockOut::open(&port) {
+            Ok(clock) => self.clock_out = Some(clock),
+            Err(e) => log::error!("Failed to start MIDI clock on '{port}': {e}"),
+        }
+    }
+
+    /// Feed the detected tempo to the clock output, if it's on.
+    pub fn send_tempo(&self, bpm: f32, beat_phase: f32) {
+        if let Some(ref clock) = self.clock_out {
+            clock.set_tempo(f64::from(bpm), f64::from(beat_phase));
+        }
+    }
+
+    /// Rescan input and output ports, dropping vanished ones and reopening
+    /// saved ones that came back.
+    fn poll_ports(&mut self) {
+        self.last_port_poll = Instant::now();
+        self.available_ports = MidiPort::list_ports();
+        self.available_out_ports = MidiClockOut::list_ports();
+
+        if let Some(ref port) = self.connection {
+            // Check if our connected port disappeared
+            if !self.available_ports.contains(&port.port_name) {
+                log::warn!("MIDI port '{}' disconnected", port.port_name);
+                self.disconnect();
+            }
+        } else if let Some(ref saved) = self.config.port_name.clone() {
+            // Try to reconnect to saved port
+            if self.available_ports.contains(saved) {
+                log::info!("MIDI port '{}' reappeared, reconnecting", saved);
+                self.connect(saved);
+            }
+        }
+
+        if let Some(ref clock) = self.clock_out {
+            if !self.available_out_ports.contains(&clock.port_name) {
+                log::warn!("MIDI output '{}' disconnected", clock.port_name);
+                self.clock_out = None;
+            }
+        } else if self.config.clock_out_enabled
+            && self
+                .config
+                .clock_out_port
+                .as_ref()
+                .is_some_and(|p| self.available_out_ports.contains(p))
+        {
+            self.restart_clock_out();
+        }
+    }
+
     /// Start MIDI learn for a parameter or trigger.
     pub fn start_learn(&mut self, target: LearnTarget) {
         self.learn_target = Some(target);
@@ -169,22 +256,7 @@ impl MidiSystem {
 
         // Hot-plug detection: poll ports every 2 seconds
         if self.last_port_poll.elapsed().as_secs() >= 2 {
-            self.last_port_poll = Instant::now();
-            self.available_ports = MidiPort::list_ports();
-
-            if let Some(ref port) = self.connection {
-                // Check if our connected port disappeared
-                if !self.available_ports.contains(&port.port_name) {
-                    log::warn!("MIDI port '{}' disconnected", port.port_name);
-                    self.disconnect();
-                }
-            } else if let Some(ref saved) = self.config.port_name.clone() {
-                // Try to reconnect to saved port
-                if self.available_ports.contains(saved) {
-                    log::info!("MIDI port '{}' reappeared, reconnecting", saved);
-                    self.connect(saved);
-                }
-            }
+            self.poll_ports();
         }
 
         // Drain messages from channel
@@ -299,20 +371,7 @@ impl MidiSystem {
 
         // Hot-plug detection (same as update)
         if self.last_port_poll.elapsed().as_secs() >= 2 {
-            self.last_port_poll = Instant::now();
-            self.available_ports = MidiPort::list_ports();
-
-            if let Some(ref port) = self.connection {
-                if !self.available_ports.contains(&port.port_name) {
-                    log::warn!("MIDI port '{}' disconnected", port.port_name);
-                    self.disconnect();
-                }
-            } else if let Some(ref saved) = self.config.port_name.clone() {
-                if self.available_ports.contains(saved) {
-                    log::info!("MIDI port '{}' reappeared, reconnecting", saved);
-                    self.connect(saved);
-                }
-            }
+            self.poll_ports();
         }
 
         let Some(ref rx) = self.receiver else {
@@ -17,7 +17,11 @@ pub struct MidiMessage {
 }
 
 /// Actions that can be triggered by a MIDI button.
+///
+/// Stored as a string so it can key the JSON trigger maps: the variant name, or
+/// `LoadPreset:<id>` for a direct preset binding.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
 pub enum TriggerAction {
     NextEffect,
     PrevEffect,
@@ -35,10 +39,63 @@ pub enum TriggerAction {
     TempoHalf,
     TempoDouble,
     TempoTap,
+    /// Decode the next preset's media into the RAM cache ahead of a switch.
+    PreloadNextPreset,
+    /// Capture the next few seconds of output as a GIF/WebM loop.
+    ExportLoop,
+    /// Emit each particle layer's trigger burst, optionally on the next beat.
+    ParticleBurst,
+    /// Toggle the output master's hard blackout.
+    Blackout,
+    /// Latch the composite and hold it (or let it decay) over live output.
+    FreezeFrame,
+    /// Live-coding controls (#2188): save and compile the editor's shader,
+    /// drop its unsaved edits, and show or hide it.
+    ShaderCompile,
+    ShaderRevert,
+    ToggleShaderEditor,
+    /// Gain staging (#2213): forget the adaptive normalizer's ranges, and
+    /// hold its current gains.
+    ResetNormalization,
+    FreezeNormalization,
+    /// Replace the stack with random effects, params and blend modes (#2251),
+    /// keeping locked layers.
+    SurpriseMe,
+    /// Save the output as a PNG in the screenshots folder.
+    Screenshot,
+    /// Load one preset directly (#2222), by its stable id. Not in `ALL`: there is
+    /// one per preset, assigned from the preset's context menu.
+    LoadPreset(u64),
+}
+
+impl From<TriggerAction> for String {
+    fn from(action: TriggerAction) -> Self {
+        match action {
+            TriggerAction::LoadPreset(id) => format!("LoadPreset:{id}"),
+            other => format!("{other:?}"),
+        }
+    }
+}
+
+impl TryFrom<String> for TriggerAction {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        if let Some(id) = s.strip_prefix("LoadPreset:") {
+            return id
+                .parse()
+                .map(TriggerAction::LoadPreset)
+                .map_err(|e| format!("bad preset id in trigger '{s}': {e}"));
+        }
+        TriggerAction::ALL
+            .iter()
+            .find(|a| format!("{a:?}") == s)
+            .copied()
+            .ok_or_else(|| format!("unknown trigger action '{s}'"))
+    }
 }
 
 impl TriggerAction {
-    #[allow(dead_code)]
     pub const ALL: &[TriggerAction] = &[
         TriggerAction::NextEffect,
         TriggerAction::PrevEffect,
@@ -54,6 +111,18 @@ impl TriggerAction {
         TriggerAction::TempoHalf,
         TriggerAction::TempoDouble,
         TriggerAction::TempoTap,
+        TriggerAction::PreloadNextPreset,
+        TriggerAction::ExportLoop,
+        TriggerAction::ParticleBurst,
+        TriggerAction::Blackout,
+        TriggerAction::FreezeFrame,
+        TriggerAction::ShaderCompile,
+        TriggerAction::ShaderRevert,
+        TriggerAction::ToggleShaderEditor,
+        TriggerAction::ResetNormalization,
+        TriggerAction::FreezeNormalization,
+        TriggerAction::SurpriseMe,
+        TriggerAction::Screenshot,
     ];
 
     pub fn display_name(&self) -> &'static str {
@@ -72,6 +141,19 @@ impl TriggerAction {
             TriggerAction::TempoHalf => "Tempo Half",
             TriggerAction::TempoDouble => "Tempo Double",
             TriggerAction::TempoTap => "Tap Tempo",
+            TriggerAction::PreloadNextPreset => "Preload Next Preset",
+            TriggerAction::ExportLoop => "Export Loop",
+            TriggerAction::ParticleBurst => "Particle Burst",
+            TriggerAction::Blackout => "Blackout",
+            TriggerAction::FreezeFrame => "Freeze Frame",
+            TriggerAction::ShaderCompile => "Shader Compile",
+            TriggerAction::ShaderRevert => "Shader Revert",
+            TriggerAction::ToggleShaderEditor => "Toggle Shader Editor",
+            TriggerAction::ResetNormalization => "Reset Normalization",
+            TriggerAction::FreezeNormalization => "Freeze Normalization",
+            TriggerAction::SurpriseMe => "Surprise Me",
+            TriggerAction::Screenshot => "Screenshot",
+            TriggerAction::LoadPreset(_) => "Load Preset",
         }
     }
 
@@ -91,6 +173,19 @@ impl TriggerAction {
             TriggerAction::TempoHalf => "Tempo /2",
             TriggerAction::TempoDouble => "Tempo x2",
             TriggerAction::TempoTap => "Tap",
+            TriggerAction::PreloadNextPreset => "Preload",
+            TriggerAction::ExportLoop => "Loop",
+            TriggerAction::ParticleBurst => "Burst",
+            TriggerAction::Blackout => "Blackout",
+            TriggerAction::FreezeFrame => "Freeze",
+            TriggerAction::ShaderCompile => "Compile",
+            TriggerAction::ShaderRevert => "Revert",
+            TriggerAction::ToggleShaderEditor => "Editor",
+            TriggerAction::ResetNormalization => "Norm Rst",
+            TriggerAction::FreezeNormalization => "Norm Frz",
+            TriggerAction::SurpriseMe => "Surprise",
+            TriggerAction::Screenshot => "Shot",
+            TriggerAction::LoadPreset(_) => "Preset",
         }
     }
 }
@@ -108,7 +203,7 @@ mod tests {
 
     #[test]
     fn trigger_action_all_count() {
-        assert_eq!(TriggerAction::ALL.len(), 14);
+        assert_eq!(TriggerAction::ALL.len(), 26);
     }
 
     #[test]
@@ -134,6 +229,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn trigger_action_serializes_as_a_map_key() {
+        let mut map = std::collections::HashMap::new();
+        map.insert(TriggerAction::LoadPreset(42), 1);
+        map.insert(TriggerAction::NextEffect, 2);
+        let json = serde_json::to_string(&map).unwrap();
+        assert!(json.contains("\"LoadPreset:42\""), "{json}");
+        assert!(json.contains("\"NextEffect\""), "{json}");
+        let back: std::collections::HashMap<TriggerAction, i32> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(back, map);
+        assert!(serde_json::from_str::<TriggerAction>("\"Nope\"").is_err());
+    }
+
     #[test]
     fn midi_msg_type_serde_roundtrip() {
         for t in [MidiMsgType::Cc, MidiMsgType::Note] {
@@ -0,0 +1,125 @@
+//! Which monitor the fullscreen output belongs to (#2231). The output is
+//! remembered by monitor name, so when that monitor is unplugged — a loose
+//! HDMI cable, a projector power-cycling — the window can go back to it
+//! fullscreen once it reconnects instead of waiting for a restart.
+
+use std::time::{Duration, Instant};
+
+/// How often the connected monitors are listed.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What changed about the output's monitor since the last check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonitorChange {
+    /// The monitor is gone; the output is wherever the OS put it.
+    Lost(String),
+    /// The monitor is back.
+    Restored(String),
+}
+
+#[derive(Debug, Default)]
+pub struct OutputMonitor {
+    /// Monitor the output went fullscreen on. Kept while it's unplugged.
+    wanted: Option<String>,
+    /// The wanted monitor was missing at the last check.
+    missing: bool,
+    last_check: Option<Instant>,
+}
+
+impl OutputMonitor {
+    /// The output went fullscreen on `monitor` (`None` when it has no name,
+    /// which leaves nothing to find again).
+    pub fn fullscreen_on(&mut self, monitor: Option<String>) {
+        self.wanted = monitor;
+        self.missing = false;
+    }
+
+    /// The output left fullscreen on purpose.
+    pub fn windowed(&mut self) {
+        self.wanted = None;
+        self.missing = false;
+    }
+
+    pub fn wanted(&self) -> Option<&str> {
+        self.wanted.as_deref()
+    }
+
+    /// The wanted monitor is unplugged right now.
+    pub fn is_missing(&self) -> bool {
+        self.missing
+    }
+
+    /// Whether a check is due at `now`.
+    pub fn due(&mut self, now: Instant) -> bool {
+        if self
+            .last_check
+            .is_some_and(|t| now.duration_since(t) < CHECK_INTERVAL)
+        {
+            return false;
+        }
+        self.last_check = Some(now);
+        true
+    }
+
+    /// Compare the wanted monitor against the `connected` monitor names.
+    pub fn check<'a>(
+        &mut self,
+        mut connected: impl Iterator<Item = &'a str>,
+    ) -> Option<MonitorChange> {
+        let wanted = self.wanted.as_deref()?;
+        let present = connected.any(|name| name == wanted);
+        match (self.missing, present) {
+            (false, false) => {
+                self.missing = true;
+                Some(MonitorChange::Lost(wanted.to_string()))
+            }
+            (true, true) => {
+                self.missing = false;
+                Some(MonitorChange::Restored(wanted.to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unplug_and_replug_report_once_each() {
+        let mut out = OutputMonitor::default();
+        out.fullscreen_on(Some("DP-1".into()));
+        assert_eq!(out.check(["DP-1", "HDMI-1"].into_iter()), None);
+        assert_eq!(
+            out.check(["HDMI-1"].into_iter()),
+            Some(MonitorChange::Lost("DP-1".into()))
+        );
+        assert!(out.is_missing());
+        assert_eq!(out.check(["HDMI-1"].into_iter()), None);
+        assert_eq!(
+            out.check(["HDMI-1", "DP-1"].into_iter()),
+            Some(MonitorChange::Restored("DP-1".into()))
+        );
+        assert_eq!(out.check(["HDMI-1", "DP-1"].into_iter()), None);
+    }
+
+    #[test]
+    fn windowed_output_watches_nothing() {
+        let mut out = OutputMonitor::default();
+        assert_eq!(out.check(std::iter::empty()), None);
+        out.fullscreen_on(Some("DP-1".into()));
+        out.windowed();
+        assert_eq!(out.check(std::iter::empty()), None);
+        assert_eq!(out.wanted(), None);
+    }
+
+    #[test]
+    fn checks_are_spaced_out() {
+        let mut out = OutputMonitor::default();
+        let t0 = Instant::now();
+        assert!(out.due(t0));
+        assert!(!out.due(t0 + CHECK_INTERVAL / 2));
+        assert!(out.due(t0 + CHECK_INTERVAL));
+    }
+}
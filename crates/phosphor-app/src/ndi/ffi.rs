@@ -5,15 +5,14 @@ use std::ffi::CString;
 use std::os::raw::{c_char, c_float, c_int};
 use std::sync::OnceLock;
 
+use crate::recording::stamp::FrameStamp;
+
 /// Opaque NDI sender instance handle.
 pub type NdiSendInstance = *mut std::ffi::c_void;
 
 /// FourCC for BGRA pixel format.
 pub const FOURCC_BGRA: u32 = fourcc(b'B', b'G', b'R', b'A');
 
-/// Timecode value that tells NDI to synthesize timing.
-pub const TIMECODE_SYNTHESIZE: i64 = i64::MAX;
-
 /// Progressive frame format.
 pub const FRAME_FORMAT_PROGRESSIVE: u32 = 1;
 
@@ -159,9 +158,12 @@ impl NdiSender {
         })
     }
 
-    /// Send a BGRA video frame.
-    pub fn send_video(&self, data: &[u8], width: u32, height: u32) {
+    /// Send a BGRA video frame, timecoded at its render time with the beat
+    /// it fell on as frame metadata (#2211).
+    pub fn send_video(&self, data: &[u8], width: u32, height: u32, stamp: &FrameStamp) {
         let stride = (width * 4) as c_int;
+        // Built from formatted numbers, so it never holds a NUL
+        let metadata = CString::new(stamp.ndi_metadata()).unwrap_or_default();
         let frame = NdiVideoFrame {
             xres: width as c_int,
             yres: height as c_int,
@@ -170,15 +172,16 @@ impl NdiSender {
             frame_rate_d: 1,
             picture_aspect_ratio: 0.0, // square pixels
             frame_format_type: FRAME_FORMAT_PROGRESSIVE,
-            timecode: TIMECODE_SYNTHESIZE,
+            timecode: stamp.ndi_timecode(),
             p_data: data.as_ptr(),
             line_stride_in_bytes: stride,
-            p_metadata: std::ptr::null(),
+            p_metadata: metadata.as_ptr(),
             timestamp: 0,
         };
         // SAFETY: instance is a valid NDI sender (checked non-null at creation).
         // frame is a repr(C) struct with valid data pointer and correct dimensions.
-        // data.as_ptr() is valid for width * height * 4 bytes (BGRA).
+        // data.as_ptr() is valid for width * height * 4 bytes (BGRA), and
+        // metadata outlives the call, which is all NDI needs it for.
         unsafe { (self.lib.fn_send_video)(self.instance, &frame) };
     }
 }
@@ -0,0 +1,61 @@
+//! Fill and key frames for broadcast keying (#2238), built on the sender
+//! thread from the captured 4-byte pixels. Alpha is the last byte in both
+//! BGRA and RGBA, so neither depends on channel order.
+
+/// The frame with alpha forced opaque. The layers are already composited
+/// over black, so this is the shaped (premultiplied) fill.
+pub fn fill(frame: &[u8]) -> Vec<u8> {
+    let mut out = frame.to_vec();
+    for px in out.chunks_exact_mut(4) {
+        px[3] = 255;
+    }
+    out
+}
+
+/// The frame's alpha as an opaque greyscale image.
+pub fn key(frame: &[u8]) -> Vec<u8> {
+    frame
+        .chunks_exact(4)
+        .flat_map(|px| [px[3], px[3], px[3], 255])
+        .collect()
+}
+
+/// A `2 * width` frame: fill on the left half, key on the right.
+pub fn side_by_side(frame: &[u8], width: u32) -> Vec<u8> {
+    let row = width as usize * 4;
+    let mut out = Vec::with_capacity(frame.len() * 2);
+    for line in frame.chunks_exact(row) {
+        out.extend(
+            line.chunks_exact(4)
+                .flat_map(|px| [px[0], px[1], px[2], 255]),
+        );
+        out.extend(
+            line.chunks_exact(4)
+                .flat_map(|px| [px[3], px[3], px[3], 255]),
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2x1: a half-transparent orange pixel and a transparent one
+    const FRAME: [u8; 8] = [10, 120, 240, 128, 0, 0, 0, 0];
+
+    #[test]
+    fn fill_is_opaque_and_key_is_grey_alpha() {
+        assert_eq!(fill(&FRAME), [10, 120, 240, 255, 0, 0, 0, 255]);
+        assert_eq!(key(&FRAME), [128, 128, 128, 255, 0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn side_by_side_puts_each_row_fill_then_key() {
+        let two_rows = [FRAME, FRAME].concat();
+        let out = side_by_side(&two_rows, 2);
+        assert_eq!(out.len(), two_rows.len() * 2);
+        let row: Vec<u8> = [fill(&FRAME), key(&FRAME)].concat();
+        assert_eq!(out, [row.clone(), row].concat());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod capture;
 pub mod ffi;
+pub mod key;
 pub mod sender;
 pub mod types;
 
@@ -13,8 +14,10 @@ use wgpu::{CommandEncoder, Device, TextureFormat};
 use self::capture::NdiCapture;
 use self::sender::{NdiFrame, spawn_sender_thread};
 use self::types::NdiConfig;
+use crate::gpu::calibration::OutputTarget;
 use crate::gpu::postprocess::PostProcessChain;
 use crate::gpu::render_target::RenderTarget;
+use crate::recording::stamp::FrameStamp;
 
 /// Central NDI output system: owns capture target, sender thread, config.
 pub struct NdiSystem {
@@ -27,6 +30,10 @@ pub struct NdiSystem {
     /// Cached output dimensions (for detecting resolution changes).
     output_width: u32,
     output_height: u32,
+    /// Stamp of the frame in the staging buffer, sent with it next frame.
+    pending_stamp: Option<FrameStamp>,
+    /// Beat of a frame skipped while the readback was busy, carried over.
+    skipped_stamp: Option<FrameStamp>,
 }
 
 impl NdiSystem {
@@ -43,6 +50,8 @@ impl NdiSystem {
             frame_counter,
             output_width: 0,
             output_height: 0,
+            pending_stamp: None,
+            skipped_stamp: None,
         };
 
         if sys.config.enabled {
@@ -68,6 +77,7 @@ impl NdiSystem {
 
         let handle = spawn_sender_thread(
             self.config.source_name.clone(),
+            self.config.key_output,
             rx,
             shutdown.clone(),
             self.frame_counter.clone(),
@@ -92,6 +102,8 @@ impl NdiSystem {
             let _ = handle.join();
         }
         self.capture = None;
+        self.pending_stamp = None;
+        self.skipped_stamp = None;
         self.config.enabled = false;
         log::info!("NDI output stopped");
     }
@@ -114,7 +126,7 @@ impl NdiSystem {
         self.config.save();
     }
 
-    /// Restart with new config (source name or resolution changed).
+    /// Restart with new config (source name, resolution or key output changed).
     pub fn restart(
         &mut self,
         device: &Device,
@@ -162,13 +174,15 @@ impl NdiSystem {
     /// 2. Render the post-process composite to the capture texture.
     /// 3. Copy capture texture → staging buffer.
     /// 4. Request async map on the staging buffer.
-    /// 5. Send the previous frame's data to the NDI thread.
+    /// 5. Send the previous frame's data to the NDI thread, with the stamp
+    ///    it was rendered under.
     pub fn capture_frame(
         &mut self,
         device: &Device,
         encoder: &mut CommandEncoder,
         post_process: &PostProcessChain,
         source: &RenderTarget,
+        stamp: FrameStamp,
     ) {
         let capture = match self.capture.as_mut() {
             Some(c) => c,
@@ -181,11 +195,20 @@ impl NdiSystem {
         // If previous map is still outstanding (GPU readback not ready), skip this frame
         // to avoid submitting commands that reference a still-mapped buffer.
         if capture.is_map_pending() {
+            self.skipped_stamp = Some(stamp.with_beats_from(self.skipped_stamp));
             return;
         }
+        let stamp = stamp.with_beats_from(self.skipped_stamp.take());
+        let prev_stamp = self.pending_stamp.replace(stamp);
 
         // 2. Render composite to capture texture.
-        post_process.render_composite_to(device, encoder, source, capture.view());
+        post_process.render_composite_to(
+            device,
+            encoder,
+            source,
+            capture.view(),
+            OutputTarget::Ndi,
+        );
 
         // 3. Copy to staging.
         capture.copy_to_staging(encoder);
@@ -193,11 +216,12 @@ impl NdiSystem {
         // 4. Will request map after queue.submit() — called from post_submit().
 
         // 5. Send previous frame data to NDI thread.
-        if let (Some(data), Some(tx)) = (prev_data, &self.frame_tx) {
+        if let (Some(data), Some(stamp), Some(tx)) = (prev_data, prev_stamp, &self.frame_tx) {
             let frame = NdiFrame {
                 data,
                 width: capture.width(),
                 height: capture.height(),
+                stamp,
             };
             // try_send: drop frame if NDI thread is behind (VJ performance > NDI latency).
             let _ = tx.try_send(frame);
@@ -6,6 +6,9 @@ use std::time::Duration;
 use crossbeam_channel::Receiver;
 
 use super::ffi::NdiSender;
+use super::key;
+use super::types::KeyOutput;
+use crate::recording::stamp::FrameStamp;
 
 /// Frame data sent from the render thread to the NDI sender thread.
 #[derive(Debug)]
@@ -13,12 +16,14 @@ pub struct NdiFrame {
     pub data: Vec<u8>,
     pub width: u32,
     pub height: u32,
+    pub stamp: FrameStamp,
 }
 
 /// Spawn the NDI sender thread.
 /// Receives frames via crossbeam channel and sends them over NDI.
 pub fn spawn_sender_thread(
     source_name: String,
+    key_output: KeyOutput,
     frame_rx: Receiver<NdiFrame>,
     shutdown: Arc<AtomicBool>,
     frame_counter: Arc<AtomicU64>,
@@ -26,7 +31,13 @@ pub fn spawn_sender_thread(
     std::thread::Builder::new()
         .name("ndi-sender".into())
         .spawn(move || {
-            if let Err(e) = sender_loop(&source_name, &frame_rx, &shutdown, &frame_counter) {
+            if let Err(e) = sender_loop(
+                &source_name,
+                key_output,
+                &frame_rx,
+                &shutdown,
+                &frame_counter,
+            ) {
                 log::error!("NDI sender thread error: {e}");
             }
             log::info!("NDI sender thread exiting");
@@ -43,16 +54,35 @@ pub fn spawn_sender_thread(
 
 fn sender_loop(
     source_name: &str,
+    key_output: KeyOutput,
     frame_rx: &Receiver<NdiFrame>,
     shutdown: &AtomicBool,
     frame_counter: &AtomicU64,
 ) -> Result<(), String> {
-    let sender = NdiSender::new(source_name)?;
+    // Two streams share each frame's timecode, so receivers can pair them.
+    let (sender, key_sender) = if key_output == KeyOutput::SeparateStreams {
+        (
+            NdiSender::new(&format!("{source_name} Fill"))?,
+            Some(NdiSender::new(&format!("{source_name} Key"))?),
+        )
+    } else {
+        (NdiSender::new(source_name)?, None)
+    };
 
     while !shutdown.load(Ordering::Relaxed) {
         match frame_rx.recv_timeout(Duration::from_millis(100)) {
             Ok(frame) => {
-                sender.send_video(&frame.data, frame.width, frame.height);
+                let (w, h, stamp) = (frame.width, frame.height, &frame.stamp);
+                match (key_output, &key_sender) {
+                    (KeyOutput::SideBySide, _) => {
+                        sender.send_video(&key::side_by_side(&frame.data, w), w * 2, h, stamp);
+                    }
+                    (KeyOutput::SeparateStreams, Some(key_sender)) => {
+                        sender.send_video(&key::fill(&frame.data), w, h, stamp);
+                        key_sender.send_video(&key::key(&frame.data), w, h, stamp);
+                    }
+                    _ => sender.send_video(&frame.data, w, h, stamp),
+                }
                 frame_counter.fetch_add(1, Ordering::Relaxed);
             }
             Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
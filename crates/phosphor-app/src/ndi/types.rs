@@ -2,9 +2,39 @@ use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
+use crate::gpu::postprocess::AlphaMode;
 // Re-export OutputResolution from shared gpu::types module.
 pub use crate::gpu::types::OutputResolution;
 
+/// Key and fill for broadcast keying (#2238): the fill with the alpha shown
+/// as a greyscale key, for vision mixers that key on a separate signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum KeyOutput {
+    /// One stream, alpha in the frame.
+    #[default]
+    Off,
+    /// One double-width stream: fill on the left, key on the right.
+    SideBySide,
+    /// Two streams, "<source> Fill" and "<source> Key".
+    SeparateStreams,
+}
+
+impl KeyOutput {
+    pub const ALL: &[KeyOutput] = &[
+        KeyOutput::Off,
+        KeyOutput::SideBySide,
+        KeyOutput::SeparateStreams,
+    ];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            KeyOutput::Off => "Off",
+            KeyOutput::SideBySide => "Side by side",
+            KeyOutput::SeparateStreams => "Two streams",
+        }
+    }
+}
+
 /// Persisted NDI output configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NdiConfig {
@@ -16,6 +46,8 @@ pub struct NdiConfig {
     pub resolution: OutputResolution,
     #[serde(default)]
     pub alpha_from_luma: bool,
+    #[serde(default)]
+    pub key_output: KeyOutput,
 }
 
 fn default_source_name() -> String {
@@ -29,14 +61,26 @@ impl Default for NdiConfig {
             source_name: default_source_name(),
             resolution: OutputResolution::default(),
             alpha_from_luma: false,
+            key_output: KeyOutput::Off,
         }
     }
 }
 
 impl NdiConfig {
+    /// What the composite writes to alpha. A key without "alpha from
+    /// brightness" takes the layers' own alpha.
+    pub fn alpha_mode(&self) -> AlphaMode {
+        if self.alpha_from_luma {
+            AlphaMode::Luma
+        } else if self.key_output != KeyOutput::Off {
+            AlphaMode::Scene
+        } else {
+            AlphaMode::Opaque
+        }
+    }
+
     pub fn config_path() -> PathBuf {
-        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-        config_dir.join("phosphor").join("ndi.json")
+        crate::paths::config_dir().join("ndi.json")
     }
 
     pub fn load() -> Self {
@@ -60,21 +104,8 @@ impl NdiConfig {
     }
 
     pub fn save(&self) {
-        let path = Self::config_path();
-        if let Some(parent) = path.parent() {
-            if let Err(e) = std::fs::create_dir_all(parent) {
-                log::error!("Failed to create config dir: {e}");
-                return;
-            }
-        }
         match serde_json::to_string_pretty(self) {
-            Ok(json) => {
-                if let Err(e) = std::fs::write(&path, json) {
-                    log::error!("Failed to write NDI config: {e}");
-                } else {
-                    log::debug!("Saved NDI config to {}", path.display());
-                }
-            }
+            Ok(json) => crate::persist::write(Self::config_path(), json),
             Err(e) => log::error!("Failed to serialize NDI config: {e}"),
         }
     }
@@ -120,5 +151,16 @@ mod tests {
         assert_eq!(c.source_name, "Custom");
         assert!(!c.enabled);
         assert_eq!(c.resolution, OutputResolution::Match);
+        assert_eq!(c.key_output, KeyOutput::Off);
+    }
+
+    #[test]
+    fn key_output_keys_on_layer_alpha_unless_luma_is_on() {
+        let mut c = NdiConfig::default();
+        assert_eq!(c.alpha_mode(), AlphaMode::Opaque);
+        c.key_output = KeyOutput::SeparateStreams;
+        assert_eq!(c.alpha_mode(), AlphaMode::Scene);
+        c.alpha_from_luma = true;
+        assert_eq!(c.alpha_mode(), AlphaMode::Luma);
     }
 }
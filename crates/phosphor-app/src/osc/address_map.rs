@@ -0,0 +1,379 @@
+//! Every OSC address Fosfora answers right now (#2198): the built-in routes,
+//! expanded for the current layers and their params, plus learned mappings.
+//! Shown in the OSC panel and saved as a Markdown cheat sheet or CSV, for
+//! programming a lighting console without reading the receiver.
+
+use std::fmt::Write as _;
+
+use super::trigger_slug;
+use super::types::OscConfig;
+use crate::gpu::layer::BlendMode;
+use crate::gpu::master::MasterParams;
+use crate::gpu::volumetric::VolumetricParams;
+use crate::midi::types::TriggerAction;
+use crate::params::ParamDef;
+
+const SWITCH: &str = "float, on above 0.5";
+
+/// One address and what sending to it does.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscAddress {
+    pub address: String,
+    /// The argument to send and how it's read.
+    pub value: String,
+    pub action: String,
+    /// Learned in the app rather than built in.
+    pub learned: bool,
+}
+
+/// A layer as the map sees it.
+pub struct MapLayer<'a> {
+    pub name: &'a str,
+    pub params: &'a [ParamDef],
+    /// Particle layers also take the obstacle addresses.
+    pub particles: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapFormat {
+    Markdown,
+    Csv,
+}
+
+impl MapFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// The full map for `layers`, with `active` the layer that the unscoped
+/// `/phosphor/param/...` addresses and learned params act on.
+pub fn address_map(config: &OscConfig, layers: &[MapLayer<'_>], active: usize) -> Vec<OscAddress> {
+    let mut out = Vec::new();
+    let mut add = |address: String, value: &str, action: String| {
+        out.push(OscAddress {
+            address,
+            value: value.to_string(),
+            action,
+            learned: false,
+        });
+    };
+
+    let active_params = layers.get(active).map_or(&[][..], |l| l.params);
+    for def in active_params {
+        if let Some(value) = param_value(def) {
+            add(
+                format!("/phosphor/param/{}", def.name()),
+                &value,
+                format!("{} on the active layer", def.name()),
+            );
+        }
+    }
+
+    let blend = BlendMode::ALL
+        .iter()
+        .map(|m| format!("{} {}", m.as_u32(), m.display_name()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    for (i, layer) in layers.iter().enumerate() {
+        let prefix = format!("/phosphor/layer/{i}");
+        for def in layer.params {
+            if let Some(value) = param_value(def) {
+                add(
+                    format!("{prefix}/param/{}", def.name()),
+                    &value,
+                    format!("{} on {}", def.name(), layer.name),
+                );
+            }
+        }
+        add(
+            format!("{prefix}/opacity"),
+            "float 0–1",
+            format!("Opacity of {}", layer.name),
+        );
+        add(
+            format!("{prefix}/blend"),
+            &format!("int: {blend}"),
+            format!("Blend mode of {}", layer.name),
+        );
+        add(
+            format!("{prefix}/enabled"),
+            SWITCH,
+            format!("Show or hide {}", layer.name),
+        );
+        if layer.particles {
+            add(
+                format!("{prefix}/obstacle/enabled"),
+                SWITCH,
+                format!("Particle obstacle on {}", layer.name),
+            );
+            add(
+                format!("{prefix}/obstacle/mode"),
+                "int: 0 Bounce, 1 Stick, 2 Flow, 3 Contain",
+                format!("Obstacle mode on {}", layer.name),
+            );
+            add(
+                format!("{prefix}/obstacle/threshold"),
+                "float 0–1",
+                format!("Obstacle alpha threshold on {}", layer.name),
+            );
+            add(
+                format!("{prefix}/obstacle/elasticity"),
+                "float 0–1",
+                format!("Obstacle elasticity on {}", layer.name),
+            );
+        }
+    }
+
+    for action in TriggerAction::ALL {
+        add(
+            format!("/phosphor/trigger/{}", trigger_slug(action)),
+            "any message",
+            action.display_name().to_string(),
+        );
+    }
+
+    add(
+        "/phosphor/postprocess/enabled".to_string(),
+        SWITCH,
+        "Post-processing".to_string(),
+    );
+    add(
+        "/phosphor/master/blackout".to_string(),
+        "float, on above 0.5; no argument is on",
+        "Output blackout".to_string(),
+    );
+    for name in MasterParams::PARAM_NAMES {
+        let range = if *name == "gamma" { "0.1–4" } else { "0–4" };
+        add(
+            format!("/phosphor/master/{name}"),
+            &format!("float {range}, 1 unchanged"),
+            format!("Output {name}"),
+        );
+    }
+    add(
+        "/phosphor/volumetric/enabled".to_string(),
+        SWITCH,
+        "Volumetric mode".to_string(),
+    );
+    for name in VolumetricParams::PARAM_NAMES {
+        add(
+            format!("/phosphor/volumetric/{name}"),
+            "float, used as is",
+            format!("Volumetric {}", name.replace('_', " ")),
+        );
+    }
+    add(
+        "/phosphor/scene/goto_cue".to_string(),
+        "int cue index",
+        "Jump to a cue".to_string(),
+    );
+    add(
+        "/phosphor/scene/go".to_string(),
+        "any message",
+        "Go: next cue".to_string(),
+    );
+    add(
+        "/phosphor/scene/back".to_string(),
+        "any message",
+        "Back: previous cue".to_string(),
+    );
+    add(
+        "/phosphor/scene/load".to_string(),
+        "int index or string name",
+        "Load a scene".to_string(),
+    );
+    add(
+        "/phosphor/scene/loop_mode".to_string(),
+        SWITCH,
+        "Loop the scene".to_string(),
+    );
+    add(
+        "/phosphor/scene/advance_mode".to_string(),
+        "int: 0 Manual, 1 Timer, 2 Beat sync",
+        "Scene advance mode".to_string(),
+    );
+    add(
+        "/phosphor/heartbeat".to_string(),
+        "any message",
+        "Controller alive, for the watchdog".to_string(),
+    );
+
+    let mut learned: Vec<OscAddress> = config
+        .params
+        .iter()
+        .map(|(name, mapping)| {
+            let def = active_params.iter().find(|d| d.name() == name);
+            OscAddress {
+                address: mapping.address.clone(),
+                value: def
+                    .and_then(param_value)
+                    .unwrap_or_else(|| "not on the active effect".to_string()),
+                action: format!("{name} on the active layer"),
+                learned: true,
+            }
+        })
+        .chain(config.triggers.iter().map(|(action, mapping)| OscAddress {
+            address: mapping.address.clone(),
+            value: "float, fires above 0.5".to_string(),
+            action: action.display_name().to_string(),
+            learned: true,
+        }))
+        .collect();
+    learned.sort_by(|a, b| a.address.cmp(&b.address).then(a.action.cmp(&b.action)));
+    out.extend(learned);
+    out
+}
+
+/// How a param reads its OSC value, or `None` for kinds OSC can't set.
+fn param_value(def: &ParamDef) -> Option<String> {
+    match def {
+        ParamDef::Float { min, max, .. } => Some(format!("float 0–1, scaled to {min}–{max}")),
+        ParamDef::Bool { .. } => Some(SWITCH.to_string()),
+        _ => None,
+    }
+}
+
+/// A Markdown cheat sheet, grouped into built-in and learned tables.
+pub fn to_markdown(map: &[OscAddress], port: u16) -> String {
+    let mut s = format!("# Fosfora OSC addresses\n\nFosfora listens for OSC on UDP port {port}.\n");
+    for (title, learned) in [("Built in", false), ("Learned", true)] {
+        let rows: Vec<&OscAddress> = map.iter().filter(|a| a.learned == learned).collect();
+        if rows.is_empty() {
+            continue;
+        }
+        let _ = write!(
+            s,
+            "\n## {title}\n\n| Address | Value | Does |\n|---|---|---|\n"
+        );
+        for a in rows {
+            let _ = writeln!(
+                s,
+                "| `{}` | {} | {} |",
+                a.address,
+                a.value.replace('|', "\\|"),
+                a.action.replace('|', "\\|")
+            );
+        }
+    }
+    s
+}
+
+/// CSV with a header row, for spreadsheets and console import tools.
+pub fn to_csv(map: &[OscAddress]) -> String {
+    let mut s = String::from("address,value,action,learned\n");
+    for a in map {
+        let _ = writeln!(
+            s,
+            "{},{},{},{}",
+            csv_field(&a.address),
+            csv_field(&a.value),
+            csv_field(&a.action),
+            a.learned
+        );
+    }
+    s
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::osc::types::OscMapping;
+
+    fn float(name: &str, min: f32, max: f32) -> ParamDef {
+        ParamDef::Float {
+            name: name.to_string(),
+            default: min,
+            min,
+            max,
+            group: None,
+            description: None,
+            unit: None,
+        }
+    }
+
+    #[test]
+    fn map_covers_layers_and_learned_mappings() {
+        let fx = [
+            float("speed", 0.1, 8.0),
+            ParamDef::Color {
+                name: "tint".to_string(),
+                default: [1.0; 4],
+                group: None,
+                description: None,
+            },
+        ];
+        let layers = [
+            MapLayer {
+                name: "Layer 1",
+                params: &fx,
+                particles: false,
+            },
+            MapLayer {
+                name: "Sparks",
+                params: &[],
+                particles: true,
+            },
+        ];
+        let mut config = OscConfig::default();
+        config.params.insert(
+            "speed".to_string(),
+            OscMapping {
+                address: "/console/fader/3".to_string(),
+            },
+        );
+        config.params.insert(
+            "gone".to_string(),
+            OscMapping {
+                address: "/console/fader/4".to_string(),
+            },
+        );
+        let map = address_map(&config, &layers, 0);
+        let find = |addr: &str| map.iter().find(|a| a.address == addr);
+
+        let speed = find("/phosphor/param/speed").unwrap();
+        assert_eq!(speed.value, "float 0–1, scaled to 0.1–8");
+        assert!(find("/phosphor/layer/0/param/speed").is_some());
+        // Colors can't be set over OSC.
+        assert!(find("/phosphor/param/tint").is_none());
+        assert!(find("/phosphor/layer/0/obstacle/mode").is_none());
+        assert!(find("/phosphor/layer/1/obstacle/mode").is_some());
+        assert!(find("/phosphor/trigger/next_effect").is_some());
+
+        let learned: Vec<&OscAddress> = map.iter().filter(|a| a.learned).collect();
+        assert_eq!(learned.len(), 2);
+        assert_eq!(learned[0].address, "/console/fader/3");
+        assert_eq!(learned[0].value, speed.value);
+        assert_eq!(learned[1].value, "not on the active effect");
+    }
+
+    #[test]
+    fn markdown_and_csv_escape_cells() {
+        let map = [OscAddress {
+            address: "/a".to_string(),
+            value: "int: 0 A, 1 B".to_string(),
+            action: "say \"hi\" | bye".to_string(),
+            learned: true,
+        }];
+        let md = to_markdown(&map, 9000);
+        assert!(md.contains("UDP port 9000"));
+        assert!(md.contains("## Learned"));
+        assert!(!md.contains("## Built in"));
+        assert!(md.contains("| `/a` | int: 0 A, 1 B | say \"hi\" \\| bye |"));
+        assert_eq!(
+            to_csv(&map),
+            "address,value,action,learned\n/a,\"int: 0 A, 1 B\",\"say \"\"hi\"\" | bye\",true\n"
+        );
+    }
+}
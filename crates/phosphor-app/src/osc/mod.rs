@@ -1,3 +1,4 @@
+pub mod address_map;
 pub mod receiver;
 pub mod sender;
 pub mod types;
@@ -31,6 +32,8 @@ pub struct OscFrameResult {
     pub postprocess_enabled: Option<bool>,
     pub volumetric_enabled: Option<bool>,
     pub volumetric_params: Vec<(String, f32)>,
+    pub master_blackout: Option<bool>,
+    pub master_params: Vec<(String, f32)>,
     // Scene control
     pub scene_goto_cue: Option<usize>,
     pub scene_load_index: Option<usize>,
@@ -54,6 +57,8 @@ impl OscFrameResult {
             postprocess_enabled: None,
             volumetric_enabled: None,
             volumetric_params: Vec::new(),
+            master_blackout: None,
+            master_params: Vec::new(),
             scene_goto_cue: None,
             scene_load_index: None,
             scene_load_name: None,
@@ -73,9 +78,17 @@ pub struct OscSystem {
     pub learn_target: Option<OscLearnTarget>,
     pub last_activity: Option<Instant>,
     pub last_address: Option<String>,
+    /// Last `/phosphor/heartbeat`, for the watchdog (#2240).
+    pub last_heartbeat: Option<Instant>,
     last_tx_time: Instant,
+    last_tempo_tx: Instant,
+    /// Beat phase last frame, to catch beats as they land.
+    tempo_prev_phase: Option<f32>,
+    beat_count: i32,
     /// Last raw OSC values for binding bus: address -> last value.
     pub last_raw_values: std::collections::HashMap<String, f32>,
+    /// Address map shown in the OSC panel, built on request (#2198).
+    pub address_map: Option<Vec<address_map::OscAddress>>,
 }
 
 impl OscSystem {
@@ -90,8 +103,13 @@ impl OscSystem {
             learn_target: None,
             last_activity: None,
             last_address: None,
+            last_heartbeat: None,
             last_tx_time: Instant::now(),
+            last_tempo_tx: Instant::now(),
+            tempo_prev_phase: None,
+            beat_count: 0,
             last_raw_values: std::collections::HashMap::new(),
+            address_map: None,
         };
 
         // Start receiver if enabled
@@ -100,10 +118,7 @@ impl OscSystem {
         }
 
         // Configure sender if TX enabled
-        if sys.config.tx_enabled {
-            sys.sender
-                .configure(&sys.config.tx_host, sys.config.tx_port);
-        }
+        sys.configure_sender();
 
         sys
     }
@@ -138,6 +153,8 @@ impl OscSystem {
         self.receiver = None;
         self.shutdown = None;
         self.last_address = None;
+        // A controller that can't reach us any more isn't a lost one
+        self.last_heartbeat = None;
     }
 
     /// Restart receiver (e.g., after port change).
@@ -148,6 +165,18 @@ impl OscSystem {
         }
     }
 
+    /// Stamp `last_activity` for a batch of incoming messages. Heartbeats
+    /// only say a controller is connected, not that anyone is using it, so a
+    /// batch of nothing but heartbeats leaves the idle timer running (#2240).
+    fn note_activity(&mut self, messages: &[OscInMessage]) {
+        if messages
+            .iter()
+            .any(|m| !matches!(m, OscInMessage::Heartbeat))
+        {
+            self.last_activity = Some(Instant::now());
+        }
+    }
+
     /// Whether we've received OSC activity within the last 300ms.
     pub fn is_recently_active(&self) -> bool {
         self.last_activity
@@ -191,13 +220,25 @@ impl OscSystem {
     /// Enable or disable TX.
     pub fn set_tx_enabled(&mut self, enabled: bool) {
         self.config.tx_enabled = enabled;
-        if enabled {
+        self.configure_sender();
+        self.config.save();
+    }
+
+    /// Enable or disable the tempo stream (#2205).
+    pub fn set_tempo_tx_enabled(&mut self, enabled: bool) {
+        self.config.tempo_tx_enabled = enabled;
+        self.configure_sender();
+        self.config.save();
+    }
+
+    /// Point the sender at TX host:port while either outbound stream is on.
+    pub fn configure_sender(&mut self) {
+        if self.config.tx_enabled || self.config.tempo_tx_enabled {
             self.sender
                 .configure(&self.config.tx_host, self.config.tx_port);
         } else {
             self.sender.disable();
         }
-        self.config.save();
     }
 
     /// Main per-frame update. Drains OSC messages, applies to active layer params, returns structured results.
@@ -219,7 +260,7 @@ impl OscSystem {
             return result;
         }
 
-        self.last_activity = Some(Instant::now());
+        self.note_activity(&messages);
 
         if !self.config.enabled {
             // Drain but don't process
@@ -230,6 +271,11 @@ impl OscSystem {
         }
 
         for msg in messages {
+            // Pings only feed the watchdog: never learned, never shown
+            if matches!(msg, OscInMessage::Heartbeat) {
+                self.last_heartbeat = Some(Instant::now());
+                continue;
+            }
             let address = msg_address(&msg);
             self.last_address = Some(address.clone());
 
@@ -297,6 +343,12 @@ impl OscSystem {
                 OscInMessage::VolumetricParam { name, value } => {
                     result.volumetric_params.push((name, value));
                 }
+                OscInMessage::MasterBlackout(on) => {
+                    result.master_blackout = Some(on);
+                }
+                OscInMessage::MasterParam { name, value } => {
+                    result.master_params.push((name, value));
+                }
                 OscInMessage::SceneGotoCue(index) => {
                     result.scene_goto_cue = Some(index);
                 }
@@ -346,7 +398,7 @@ impl OscSystem {
             return result;
         }
 
-        self.last_activity = Some(Instant::now());
+        self.note_activity(&messages);
 
         if !self.config.enabled {
             if let Some(msg) = messages.last() {
@@ -356,6 +408,11 @@ impl OscSystem {
         }
 
         for msg in messages {
+            // Pings only feed the watchdog: never learned, never shown
+            if matches!(msg, OscInMessage::Heartbeat) {
+                self.last_heartbeat = Some(Instant::now());
+                continue;
+            }
             let address = msg_address(&msg);
             self.last_address = Some(address.clone());
 
@@ -415,6 +472,14 @@ impl OscSystem {
                 OscInMessage::VolumetricEnabled(enabled) => {
                     result.volumetric_enabled = Some(enabled);
                 }
+                // The output master is global, not the locked layer's: blackout
+                // is the panic button and must always get through.
+                OscInMessage::MasterBlackout(on) => {
+                    result.master_blackout = Some(on);
+                }
+                OscInMessage::MasterParam { name, value } => {
+                    result.master_params.push((name, value));
+                }
                 OscInMessage::SceneGotoCue(index) => {
                     result.scene_goto_cue = Some(index);
                 }
@@ -474,6 +539,42 @@ impl OscSystem {
     }
 }
 
+impl OscSystem {
+    /// Send the tempo stream if it's on (#2205): each beat as it lands,
+    /// BPM and phases at the tempo rate.
+    pub fn send_tempo(&mut self, features: &AudioFeatures) {
+        if !self.config.tempo_tx_enabled {
+            self.tempo_prev_phase = None;
+            return;
+        }
+        let phase = features.beat_phase;
+        if self
+            .tempo_prev_phase
+            .is_some_and(|prev| beat_crossed(prev, phase))
+        {
+            self.beat_count = self.beat_count.wrapping_add(1);
+            // Beats land at whole fractions of the bar, the first at zero
+            let downbeat = features.bar_phase < 0.125 || features.bar_phase > 0.875;
+            self.sender.send_beat(self.beat_count, downbeat);
+        }
+        self.tempo_prev_phase = Some(phase);
+
+        let interval_ms = 1000 / self.config.tempo_tx_rate_hz.max(1);
+        if self.last_tempo_tx.elapsed().as_millis() < interval_ms as u128 {
+            return;
+        }
+        self.last_tempo_tx = Instant::now();
+        self.sender
+            .send_tempo(features.bpm * 300.0, phase, features.bar_phase);
+    }
+}
+
+/// Whether the beat phase wrapped between two frames. Only a drop from
+/// late in one beat to early in the next counts, so jitter doesn't.
+fn beat_crossed(prev: f32, now: f32) -> bool {
+    prev > 0.75 && now < 0.25
+}
+
 impl Drop for OscSystem {
     fn drop(&mut self) {
         self.stop_receiver();
@@ -490,6 +591,7 @@ fn msg_value(msg: &OscInMessage) -> Option<f32> {
         | OscInMessage::LayerObstacleThreshold { value, .. }
         | OscInMessage::LayerObstacleElasticity { value, .. }
         | OscInMessage::VolumetricParam { value, .. }
+        | OscInMessage::MasterParam { value, .. }
         | OscInMessage::Raw { value, .. } => Some(*value),
         OscInMessage::LayerBlend { value, .. } | OscInMessage::LayerObstacleMode { value, .. } => {
             Some(*value as f32)
@@ -498,10 +600,11 @@ fn msg_value(msg: &OscInMessage) -> Option<f32> {
         | OscInMessage::LayerObstacleEnabled { value, .. }
         | OscInMessage::PostProcessEnabled(value)
         | OscInMessage::VolumetricEnabled(value)
+        | OscInMessage::MasterBlackout(value)
         | OscInMessage::SceneLoopMode(value) => Some(if *value { 1.0 } else { 0.0 }),
         OscInMessage::SceneGotoCue(v) | OscInMessage::SceneLoadIndex(v) => Some(*v as f32),
         OscInMessage::SceneAdvanceMode(v) => Some(*v as f32),
-        OscInMessage::Trigger(_) | OscInMessage::SceneLoadName(_) => None,
+        OscInMessage::Trigger(_) | OscInMessage::SceneLoadName(_) | OscInMessage::Heartbeat => None,
     }
 }
 
@@ -530,16 +633,19 @@ fn msg_address(msg: &OscInMessage) -> String {
         OscInMessage::PostProcessEnabled(_) => "/phosphor/postprocess/enabled".to_string(),
         OscInMessage::VolumetricEnabled(_) => "/phosphor/volumetric/enabled".to_string(),
         OscInMessage::VolumetricParam { name, .. } => format!("/phosphor/volumetric/{name}"),
+        OscInMessage::MasterBlackout(_) => "/phosphor/master/blackout".to_string(),
+        OscInMessage::MasterParam { name, .. } => format!("/phosphor/master/{name}"),
         OscInMessage::SceneGotoCue(_) => "/phosphor/scene/goto_cue".to_string(),
         OscInMessage::SceneLoadIndex(_) => "/phosphor/scene/load".to_string(),
         OscInMessage::SceneLoadName(_) => "/phosphor/scene/load".to_string(),
         OscInMessage::SceneLoopMode(_) => "/phosphor/scene/loop_mode".to_string(),
         OscInMessage::SceneAdvanceMode(_) => "/phosphor/scene/advance_mode".to_string(),
+        OscInMessage::Heartbeat => "/phosphor/heartbeat".to_string(),
         OscInMessage::Raw { address, .. } => address.clone(),
     }
 }
 
-fn trigger_slug(action: &TriggerAction) -> &'static str {
+pub(crate) fn trigger_slug(action: &TriggerAction) -> &'static str {
     match action {
         TriggerAction::NextEffect => "next_effect",
         TriggerAction::PrevEffect => "prev_effect",
@@ -555,6 +661,19 @@ fn trigger_slug(action: &TriggerAction) -> &'static str {
         TriggerAction::TempoHalf => "tempo_half",
         TriggerAction::TempoDouble => "tempo_double",
         TriggerAction::TempoTap => "tempo_tap",
+        TriggerAction::PreloadNextPreset => "preload_next_preset",
+        TriggerAction::ExportLoop => "export_loop",
+        TriggerAction::ParticleBurst => "particle_burst",
+        TriggerAction::Blackout => "blackout",
+        TriggerAction::FreezeFrame => "freeze_frame",
+        TriggerAction::ShaderCompile => "shader_compile",
+        TriggerAction::ShaderRevert => "shader_revert",
+        TriggerAction::ToggleShaderEditor => "toggle_shader_editor",
+        TriggerAction::ResetNormalization => "reset_normalization",
+        TriggerAction::FreezeNormalization => "freeze_normalization",
+        TriggerAction::SurpriseMe => "surprise_me",
+        TriggerAction::Screenshot => "screenshot",
+        TriggerAction::LoadPreset(_) => "load_preset",
     }
 }
 
@@ -586,6 +705,15 @@ fn apply_param(
 mod tests {
     use super::*;
 
+    #[test]
+    fn beats_cross_only_on_wrap() {
+        assert!(beat_crossed(0.97, 0.02));
+        assert!(!beat_crossed(0.4, 0.45));
+        assert!(!beat_crossed(0.5, 0.1));
+        // A small backward wobble isn't a beat
+        assert!(!beat_crossed(0.8, 0.78));
+    }
+
     #[test]
     fn trigger_slug_all_actions() {
         let expected = [
@@ -603,6 +731,18 @@ mod tests {
             (TriggerAction::TempoHalf, "tempo_half"),
             (TriggerAction::TempoDouble, "tempo_double"),
             (TriggerAction::TempoTap, "tempo_tap"),
+            (TriggerAction::PreloadNextPreset, "preload_next_preset"),
+            (TriggerAction::ExportLoop, "export_loop"),
+            (TriggerAction::ParticleBurst, "particle_burst"),
+            (TriggerAction::Blackout, "blackout"),
+            (TriggerAction::FreezeFrame, "freeze_frame"),
+            (TriggerAction::ShaderCompile, "shader_compile"),
+            (TriggerAction::ShaderRevert, "shader_revert"),
+            (TriggerAction::ToggleShaderEditor, "toggle_shader_editor"),
+            (TriggerAction::ResetNormalization, "reset_normalization"),
+            (TriggerAction::FreezeNormalization, "freeze_normalization"),
+            (TriggerAction::SurpriseMe, "surprise_me"),
+            (TriggerAction::Screenshot, "screenshot"),
         ];
         for (action, slug) in expected {
             assert_eq!(trigger_slug(&action), slug);
@@ -699,6 +839,17 @@ mod tests {
         assert_eq!(msg_address(&msg), "/phosphor/postprocess/enabled");
     }
 
+    #[test]
+    fn msg_address_master() {
+        let msg = OscInMessage::MasterBlackout(true);
+        assert_eq!(msg_address(&msg), "/phosphor/master/blackout");
+        let msg = OscInMessage::MasterParam {
+            name: "contrast".into(),
+            value: 1.2,
+        };
+        assert_eq!(msg_address(&msg), "/phosphor/master/contrast");
+    }
+
     #[test]
     fn msg_address_raw() {
         let msg = OscInMessage::Raw {
@@ -206,6 +206,18 @@ fn parse_osc_message(msg: &OscMessage) -> Option<OscInMessage> {
                 "tempo_half" => TriggerAction::TempoHalf,
                 "tempo_double" => TriggerAction::TempoDouble,
                 "tempo_tap" => TriggerAction::TempoTap,
+                "preload_next_preset" => TriggerAction::PreloadNextPreset,
+                "export_loop" => TriggerAction::ExportLoop,
+                "particle_burst" => TriggerAction::ParticleBurst,
+                "blackout" => TriggerAction::Blackout,
+                "freeze_frame" => TriggerAction::FreezeFrame,
+                "shader_compile" => TriggerAction::ShaderCompile,
+                "shader_revert" => TriggerAction::ShaderRevert,
+                "toggle_shader_editor" => TriggerAction::ToggleShaderEditor,
+                "reset_normalization" => TriggerAction::ResetNormalization,
+                "freeze_normalization" => TriggerAction::FreezeNormalization,
+                "surprise_me" => TriggerAction::SurpriseMe,
+                "screenshot" => TriggerAction::Screenshot,
                 _ => {
                     let value = first_float(&msg.args).unwrap_or(1.0);
                     return Some(OscInMessage::Raw {
@@ -223,6 +235,24 @@ fn parse_osc_message(msg: &OscMessage) -> Option<OscInMessage> {
             Some(OscInMessage::PostProcessEnabled(value > 0.5))
         }
 
+        // /phosphor/heartbeat
+        "heartbeat" => Some(OscInMessage::Heartbeat),
+
+        // /phosphor/master/blackout  or  /phosphor/master/{brightness,contrast,gamma}
+        "master" if parts.len() >= 4 => {
+            if parts[3] == "blackout" {
+                // A bare message is a panic button: black out.
+                let value = first_float(&msg.args).unwrap_or(1.0);
+                Some(OscInMessage::MasterBlackout(value > 0.5))
+            } else {
+                let value = first_float(&msg.args)?;
+                Some(OscInMessage::MasterParam {
+                    name: parts[3..].join("/"),
+                    value,
+                })
+            }
+        }
+
         // /phosphor/volumetric/enabled  or  /phosphor/volumetric/{param}
         "volumetric" if parts.len() >= 4 => {
             let value = first_float(&msg.args)?;
@@ -244,6 +274,9 @@ fn parse_osc_message(msg: &OscMessage) -> Option<OscInMessage> {
                     let value = first_float(&msg.args)? as usize;
                     Some(OscInMessage::SceneGotoCue(value))
                 }
+                // /phosphor/scene/go, /phosphor/scene/back: the show's Go and Back (#2221)
+                "go" => Some(OscInMessage::Trigger(TriggerAction::SceneGoNext)),
+                "back" => Some(OscInMessage::Trigger(TriggerAction::SceneGoPrev)),
                 // /phosphor/scene/load i (int) or s (string)
                 "load" => {
                     // Try string first, fall back to int
@@ -380,6 +413,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_heartbeat_with_or_without_args() {
+        for args in [vec![], vec![OscType::Int(1)]] {
+            let msg = OscMessage {
+                addr: "/phosphor/heartbeat".into(),
+                args,
+            };
+            match parse_osc_message(&msg) {
+                Some(OscInMessage::Heartbeat) => {}
+                other => panic!("expected Heartbeat, got {:?}", other),
+            }
+        }
+    }
+
     #[test]
     fn parse_postprocess_enabled() {
         let msg = OscMessage {
@@ -392,6 +439,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_master_blackout_and_params() {
+        let bare = OscMessage {
+            addr: "/phosphor/master/blackout".into(),
+            args: vec![],
+        };
+        match parse_osc_message(&bare) {
+            Some(OscInMessage::MasterBlackout(v)) => assert!(v),
+            other => panic!("expected MasterBlackout, got {:?}", other),
+        }
+        let release = OscMessage {
+            addr: "/phosphor/master/blackout".into(),
+            args: vec![OscType::Int(0)],
+        };
+        match parse_osc_message(&release) {
+            Some(OscInMessage::MasterBlackout(v)) => assert!(!v),
+            other => panic!("expected MasterBlackout, got {:?}", other),
+        }
+        let gamma = OscMessage {
+            addr: "/phosphor/master/gamma".into(),
+            args: vec![OscType::Float(1.4)],
+        };
+        match parse_osc_message(&gamma) {
+            Some(OscInMessage::MasterParam { name, value }) => {
+                assert_eq!(name, "gamma");
+                assert!((value - 1.4).abs() < 1e-6);
+            }
+            other => panic!("expected MasterParam, got {:?}", other),
+        }
+    }
+
     #[test]
     fn parse_non_phosphor_returns_raw() {
         let msg = OscMessage {
@@ -635,6 +713,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_scene_go_and_back() {
+        for (addr, expected) in [
+            ("/phosphor/scene/go", TriggerAction::SceneGoNext),
+            ("/phosphor/scene/back", TriggerAction::SceneGoPrev),
+        ] {
+            let msg = OscMessage {
+                addr: addr.into(),
+                args: vec![],
+            };
+            match parse_osc_message(&msg) {
+                Some(OscInMessage::Trigger(action)) => assert_eq!(action, expected),
+                other => panic!("expected Trigger for {addr}, got {:?}", other),
+            }
+        }
+    }
+
     #[test]
     fn parse_scene_load_int() {
         let msg = OscMessage {
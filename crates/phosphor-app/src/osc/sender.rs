@@ -130,6 +130,9 @@ impl OscSender {
         self.send_float("/phosphor/audio/contrast_mean", f.contrast_mean);
         // A16 timbre dynamics (#1467): L2 of the delta-MFCC (coeffs 1..12), adaptively normalized.
         self.send_float("/phosphor/audio/timbre_flux", f.timbre_flux);
+        // Dynamics (#2218): crest factor and the impact envelope.
+        self.send_float("/phosphor/audio/crest", f.crest);
+        self.send_float("/phosphor/audio/impact", f.impact);
     }
 
     /// Send current state (active layer, effect name).
@@ -146,6 +149,22 @@ impl OscSender {
         self.send_float("/phosphor/state/timeline/transition_progress", progress);
     }
 
+    /// Send the tempo stream (#2205): BPM and where we are in the beat and bar.
+    pub fn send_tempo(&self, bpm: f32, beat_phase: f32, bar_phase: f32) {
+        self.send_float("/phosphor/tempo/bpm", bpm);
+        self.send_float("/phosphor/tempo/beat_phase", beat_phase);
+        self.send_float("/phosphor/tempo/bar_phase", bar_phase);
+    }
+
+    /// Mark a beat as it lands, with a running count, and the bar's first
+    /// with a plain 1 trigger.
+    pub fn send_beat(&self, count: i32, downbeat: bool) {
+        self.send_int("/phosphor/tempo/beat", count);
+        if downbeat {
+            self.send_int("/phosphor/tempo/downbeat", 1);
+        }
+    }
+
     fn send_float(&self, addr: &str, value: f32) {
         self.send_packet(addr, vec![OscType::Float(value)]);
     }
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 
 use crate::midi::types::TriggerAction;
+use crate::schema::Schema;
 
 /// Parsed inbound OSC message.
 #[derive(Debug, Clone)]
@@ -38,6 +39,10 @@ pub enum OscInMessage {
     VolumetricEnabled(bool),
     /// Set a volumetric param: /phosphor/volumetric/{name}
     VolumetricParam { name: String, value: f32 },
+    /// Output master blackout on/off: /phosphor/master/blackout
+    MasterBlackout(bool),
+    /// Set an output master param: /phosphor/master/{brightness,contrast,gamma}
+    MasterParam { name: String, value: f32 },
     /// Jump to a specific cue: /phosphor/scene/goto_cue
     SceneGotoCue(usize),
     /// Load scene by index: /phosphor/scene/load (int arg)
@@ -48,6 +53,8 @@ pub enum OscInMessage {
     SceneLoopMode(bool),
     /// Set advance mode: /phosphor/scene/advance_mode (0=Manual, 1=Timer, 2=BeatSync)
     SceneAdvanceMode(u8),
+    /// Controller is alive, for the watchdog (#2240): /phosphor/heartbeat
+    Heartbeat,
     /// Unrecognized address (captured for learn mode)
     Raw { address: String, value: f32 },
 }
@@ -82,14 +89,26 @@ pub struct OscConfig {
     pub tx_enabled: bool,
     #[serde(default = "default_tx_rate")]
     pub tx_rate_hz: u32,
+    /// Send BPM, beats and phase on `/phosphor/tempo/*` (#2205), on their
+    /// own and at their own rate, to the same host and port.
+    #[serde(default)]
+    pub tempo_tx_enabled: bool,
+    #[serde(default = "default_tempo_tx_rate")]
+    pub tempo_tx_rate_hz: u32,
     #[serde(default)]
     pub params: HashMap<String, OscMapping>,
     #[serde(default)]
     pub triggers: HashMap<TriggerAction, OscMapping>,
 }
 
+/// `osc.json` layout history (#2202).
+pub const SCHEMA: Schema = Schema {
+    name: "OSC config",
+    migrations: &[],
+};
+
 fn default_version() -> u32 {
-    1
+    SCHEMA.version()
 }
 fn default_true() -> bool {
     true
@@ -106,17 +125,22 @@ fn default_tx_host() -> String {
 fn default_tx_rate() -> u32 {
     30
 }
+fn default_tempo_tx_rate() -> u32 {
+    60
+}
 
 impl Default for OscConfig {
     fn default() -> Self {
         Self {
-            version: 1,
+            version: SCHEMA.version(),
             enabled: true,
             rx_port: 9000,
             tx_port: 9001,
             tx_host: "127.0.0.1".to_string(),
             tx_enabled: false,
             tx_rate_hz: 30,
+            tempo_tx_enabled: false,
+            tempo_tx_rate_hz: 60,
             params: HashMap::new(),
             triggers: HashMap::new(),
         }
@@ -125,46 +149,16 @@ impl Default for OscConfig {
 
 impl OscConfig {
     pub fn config_path() -> PathBuf {
-        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-        config_dir.join("phosphor").join("osc.json")
+        crate::paths::config_dir().join("osc.json")
     }
 
     pub fn load() -> Self {
-        let path = Self::config_path();
-        match std::fs::read_to_string(&path) {
-            Ok(contents) => match serde_json::from_str(&contents) {
-                Ok(config) => {
-                    log::info!("Loaded OSC config from {}", path.display());
-                    config
-                }
-                Err(e) => {
-                    log::warn!("Failed to parse OSC config: {e}");
-                    Self::default()
-                }
-            },
-            Err(_) => {
-                log::info!("No OSC config found, using defaults");
-                Self::default()
-            }
-        }
+        SCHEMA.load(&Self::config_path()).unwrap_or_default()
     }
 
     pub fn save(&self) {
-        let path = Self::config_path();
-        if let Some(parent) = path.parent() {
-            if let Err(e) = std::fs::create_dir_all(parent) {
-                log::error!("Failed to create config dir: {e}");
-                return;
-            }
-        }
         match serde_json::to_string_pretty(self) {
-            Ok(json) => {
-                if let Err(e) = std::fs::write(&path, json) {
-                    log::error!("Failed to write OSC config: {e}");
-                } else {
-                    log::debug!("Saved OSC config to {}", path.display());
-                }
-            }
+            Ok(json) => crate::persist::write(Self::config_path(), json),
             Err(e) => log::error!("Failed to serialize OSC config: {e}"),
         }
     }
@@ -255,6 +249,8 @@ mod tests {
         assert!(c.enabled); // default true
         assert!(!c.tx_enabled); // default false
         assert_eq!(c.tx_rate_hz, 30); // default
+        assert!(!c.tempo_tx_enabled);
+        assert_eq!(c.tempo_tx_rate_hz, 60);
     }
 
     #[test]
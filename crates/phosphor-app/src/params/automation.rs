@@ -0,0 +1,541 @@
+//! Param automation (#2183): live param moves recorded into per-param lanes
+//! that loop over a few bars and play back. A lane is a list of breakpoints
+//! across the loop; playback interpolates between them.
+//!
+//! Nothing is told where a move came from. Each tick compares the store with
+//! what the last tick left in it: a param that changed since was moved by
+//! someone (UI, MIDI, OSC, web, a binding) and is "touched" for a moment.
+//! Touched params keep their live value, and are written into their lane
+//! while recording is armed. Everything else follows its lane.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ParamDef, ParamValue};
+
+/// Loop lengths offered in the panel.
+pub const BAR_CHOICES: &[u32] = &[1, 2, 4, 8, 16];
+
+/// How long a param stays touched after its last move, so holding a slider
+/// still doesn't hand it straight back to playback.
+const TOUCH_HOLD_SECS: f32 = 0.3;
+
+/// Recorded lanes are thinned to within this fraction of the param range
+/// once a touch ends, leaving breakpoints a person can edit.
+const SIMPLIFY_TOLERANCE: f32 = 0.01;
+
+/// Tempo used when neither the beat tracker nor MIDI clock has one.
+const FALLBACK_BPM: f32 = 120.0;
+
+/// One point on a lane. `pos` is 0..1 across the loop.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Breakpoint {
+    pub pos: f32,
+    pub value: f32,
+}
+
+/// Automation for one Float param, breakpoints sorted by `pos`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutomationLane {
+    pub param: String,
+    pub points: Vec<Breakpoint>,
+}
+
+impl AutomationLane {
+    fn new(param: &str) -> Self {
+        Self {
+            param: param.to_string(),
+            points: Vec::new(),
+        }
+    }
+
+    /// Lane value at `pos`, wrapping from the last point back to the first.
+    pub fn value_at(&self, pos: f32) -> Option<f32> {
+        let first = *self.points.first()?;
+        let last = *self.points.last()?;
+        let (a, b) = match self.points.iter().position(|p| p.pos > pos) {
+            Some(0) => (
+                Breakpoint {
+                    pos: last.pos - 1.0,
+                    ..last
+                },
+                first,
+            ),
+            Some(i) => (self.points[i - 1], self.points[i]),
+            None => (
+                last,
+                Breakpoint {
+                    pos: first.pos + 1.0,
+                    ..first
+                },
+            ),
+        };
+        let span = b.pos - a.pos;
+        if span <= f32::EPSILON {
+            return Some(b.value);
+        }
+        let t = ((pos - a.pos) / span).clamp(0.0, 1.0);
+        Some(a.value + (b.value - a.value) * t)
+    }
+
+    /// Add a point, replacing one already at the same position. Returns its index.
+    pub fn insert(&mut self, point: Breakpoint) -> usize {
+        match self
+            .points
+            .binary_search_by(|p| p.pos.total_cmp(&point.pos))
+        {
+            Ok(i) => {
+                self.points[i] = point;
+                i
+            }
+            Err(i) => {
+                self.points.insert(i, point);
+                i
+            }
+        }
+    }
+
+    /// Move point `index` and keep the lane sorted. Returns its new index.
+    pub fn move_point(&mut self, index: usize, point: Breakpoint) -> usize {
+        if index < self.points.len() {
+            self.points.remove(index);
+        }
+        self.insert(point)
+    }
+
+    pub fn remove_point(&mut self, index: usize) {
+        if index < self.points.len() {
+            self.points.remove(index);
+        }
+    }
+
+    /// Drop points in `(from, to]`, wrapping past the loop end when `to < from`.
+    fn erase(&mut self, from: f32, to: f32) {
+        if to >= from {
+            self.points.retain(|p| p.pos <= from || p.pos > to);
+        } else {
+            self.points.retain(|p| p.pos <= from && p.pos > to);
+        }
+    }
+
+    /// Drop points the lane can do without, keeping the curve within
+    /// `tolerance` of the original (Ramer–Douglas–Peucker on value error).
+    fn simplify(&mut self, tolerance: f32) {
+        let n = self.points.len();
+        if n < 3 {
+            return;
+        }
+        let mut keep = vec![false; n];
+        keep[0] = true;
+        keep[n - 1] = true;
+        let mut spans = vec![(0, n - 1)];
+        while let Some((a, b)) = spans.pop() {
+            let (pa, pb) = (self.points[a], self.points[b]);
+            let mut worst = (0.0, 0);
+            for i in a + 1..b {
+                let p = self.points[i];
+                let t = (p.pos - pa.pos) / (pb.pos - pa.pos).max(f32::EPSILON);
+                let err = (pa.value + (pb.value - pa.value) * t - p.value).abs();
+                if err > worst.0 {
+                    worst = (err, i);
+                }
+            }
+            if worst.0 > tolerance {
+                keep[worst.1] = true;
+                spans.push((a, worst.1));
+                spans.push((worst.1, b));
+            }
+        }
+        let mut i = 0;
+        self.points.retain(|_| {
+            i += 1;
+            keep[i - 1]
+        });
+    }
+}
+
+/// A layer's automation: the loop length, its lanes and the play state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParamAutomation {
+    /// Loop length in 4/4 bars.
+    pub bars: u32,
+    pub lanes: Vec<AutomationLane>,
+    /// Record armed: touched params are written into their lanes.
+    #[serde(skip)]
+    pub recording: bool,
+    /// Playhead, 0..1 across the loop.
+    #[serde(skip)]
+    pub position: f32,
+    /// What each param held when the last tick finished.
+    #[serde(skip)]
+    last: HashMap<String, f32>,
+    /// Seconds of touch left per param.
+    #[serde(skip)]
+    hold: HashMap<String, f32>,
+    /// Whether the last tick was on a bar line, so a trigger held over
+    /// several frames snaps the playhead once.
+    #[serde(skip)]
+    last_bar_line: bool,
+}
+
+impl Default for ParamAutomation {
+    fn default() -> Self {
+        Self {
+            bars: 4,
+            lanes: Vec::new(),
+            recording: false,
+            position: 0.0,
+            last: HashMap::new(),
+            hold: HashMap::new(),
+            last_bar_line: false,
+        }
+    }
+}
+
+impl ParamAutomation {
+    pub fn is_empty(&self) -> bool {
+        self.lanes.iter().all(|l| l.points.is_empty())
+    }
+
+    pub fn lane(&self, param: &str) -> Option<&AutomationLane> {
+        self.lanes.iter().find(|l| l.param == param)
+    }
+
+    pub fn lane_mut(&mut self, param: &str) -> Option<&mut AutomationLane> {
+        self.lanes.iter_mut().find(|l| l.param == param)
+    }
+
+    pub fn remove_lane(&mut self, param: &str) {
+        self.lanes.retain(|l| l.param != param);
+    }
+
+    /// Drop every lane, e.g. when the layer loads another effect.
+    pub fn clear(&mut self) {
+        self.lanes.clear();
+        self.recording = false;
+        self.last.clear();
+        self.hold.clear();
+    }
+
+    /// True while `param` is held by a live move rather than its lane.
+    pub fn is_touched(&self, param: &str) -> bool {
+        self.hold.get(param).is_some_and(|h| *h > 0.0)
+    }
+
+    /// Take the current values of `params` as their own rather than live
+    /// moves. For writers that run after the tick, like step patterns
+    /// (#2184), whose steps would otherwise be recorded into lanes.
+    pub fn settle<'a>(
+        &mut self,
+        values: &HashMap<String, ParamValue>,
+        params: impl IntoIterator<Item = &'a str>,
+    ) {
+        for name in params {
+            if let Some(&ParamValue::Float(value)) = values.get(name) {
+                self.last.insert(name.to_string(), value);
+            }
+        }
+    }
+
+    /// Advance the loop by `bars_elapsed` and play or record every Float
+    /// param. `bar_line` is true on the first beat of a bar: the playhead
+    /// then snaps to the nearest whole bar, so the loop stays on the bar
+    /// grid however the tempo estimate drifts. Writes `values` directly, so
+    /// playback doesn't count as an edit. Returns true when recording
+    /// changed a lane.
+    pub fn tick(
+        &mut self,
+        defs: &[ParamDef],
+        values: &mut HashMap<String, ParamValue>,
+        bars_elapsed: f32,
+        bar_line: bool,
+        dt: f32,
+    ) -> bool {
+        if bar_line && !self.last_bar_line {
+            let bars = self.bars.max(1) as f32;
+            self.position = ((self.position * bars).round() / bars).rem_euclid(1.0);
+        }
+        self.last_bar_line = bar_line;
+        let from = self.position;
+        self.position = (from + bars_elapsed / self.bars.max(1) as f32).rem_euclid(1.0);
+        let to = self.position;
+        let mut recorded = false;
+
+        for def in defs {
+            let ParamDef::Float { name, min, max, .. } = def else {
+                continue;
+            };
+            let Some(&ParamValue::Float(value)) = values.get(name) else {
+                continue;
+            };
+            let moved = self.last.get(name).is_some_and(|l| *l != value);
+            let hold = self.hold.entry(name.clone()).or_insert(0.0);
+            let was_touched = *hold > 0.0;
+            *hold = if moved {
+                TOUCH_HOLD_SECS
+            } else {
+                (*hold - dt).max(0.0)
+            };
+            let touched = *hold > 0.0;
+
+            let mut out = value;
+            if touched {
+                if self.recording {
+                    if self.lane(name).is_none() {
+                        self.lanes.push(AutomationLane::new(name));
+                    }
+                    if let Some(lane) = self.lane_mut(name) {
+                        lane.erase(from, to);
+                        lane.insert(Breakpoint { pos: to, value });
+                    }
+                    recorded = true;
+                }
+            } else {
+                if was_touched && self.recording {
+                    let tolerance = (max - min).abs() * SIMPLIFY_TOLERANCE;
+                    if let Some(lane) = self.lane_mut(name) {
+                        lane.simplify(tolerance);
+                    }
+                }
+                if let Some(v) = self.lane(name).and_then(|l| l.value_at(to)) {
+                    out = v.clamp(*min, *max);
+                    values.insert(name.clone(), ParamValue::Float(out));
+                }
+            }
+            self.last.insert(name.clone(), out);
+        }
+        recorded
+    }
+}
+
+/// Bars elapsed over `dt` seconds at `bpm`, assuming 4/4.
+pub fn bars_for(dt: f32, bpm: f32) -> f32 {
+    let bpm = if bpm >= 30.0 { bpm } else { FALLBACK_BPM };
+    dt * bpm / 60.0 / 4.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speed_def() -> Vec<ParamDef> {
+        vec![ParamDef::Float {
+            name: "speed".into(),
+            default: 0.0,
+            min: 0.0,
+            max: 1.0,
+            group: None,
+            description: None,
+            unit: None,
+        }]
+    }
+
+    fn lane(points: &[(f32, f32)]) -> AutomationLane {
+        AutomationLane {
+            param: "speed".into(),
+            points: points
+                .iter()
+                .map(|&(pos, value)| Breakpoint { pos, value })
+                .collect(),
+        }
+    }
+
+    fn speed(values: &HashMap<String, ParamValue>) -> f32 {
+        match values["speed"] {
+            ParamValue::Float(v) => v,
+            _ => panic!("expected Float"),
+        }
+    }
+
+    #[test]
+    fn value_at_interpolates_and_wraps() {
+        let l = lane(&[(0.25, 0.0), (0.75, 1.0)]);
+        assert_eq!(l.value_at(0.5), Some(0.5));
+        // Past the last point it ramps back toward the first, across the loop end.
+        assert!((l.value_at(0.0).unwrap() - 0.5).abs() < 1e-6);
+        assert!((l.value_at(0.875).unwrap() - 0.75).abs() < 1e-6);
+        assert_eq!(lane(&[(0.3, 0.7)]).value_at(0.9), Some(0.7));
+        assert_eq!(lane(&[]).value_at(0.5), None);
+    }
+
+    #[test]
+    fn insert_keeps_order_and_move_resorts() {
+        let mut l = lane(&[(0.1, 0.0), (0.9, 1.0)]);
+        assert_eq!(
+            l.insert(Breakpoint {
+                pos: 0.5,
+                value: 0.5
+            }),
+            1
+        );
+        assert_eq!(
+            l.insert(Breakpoint {
+                pos: 0.5,
+                value: 0.2
+            }),
+            1
+        );
+        assert_eq!(l.points.len(), 3);
+        let i = l.move_point(
+            1,
+            Breakpoint {
+                pos: 0.95,
+                value: 0.2,
+            },
+        );
+        assert_eq!(i, 2);
+        assert!(l.points.windows(2).all(|w| w[0].pos <= w[1].pos));
+    }
+
+    #[test]
+    fn erase_handles_the_loop_wrap() {
+        let mut l = lane(&[(0.05, 0.0), (0.5, 0.0), (0.95, 0.0)]);
+        l.erase(0.9, 0.1);
+        assert_eq!(l.points.len(), 1);
+        assert_eq!(l.points[0].pos, 0.5);
+    }
+
+    #[test]
+    fn simplify_drops_points_on_a_straight_ramp() {
+        let mut l = lane(
+            &(0..=10)
+                .map(|i| (i as f32 / 10.0, i as f32 / 10.0))
+                .collect::<Vec<_>>(),
+        );
+        l.insert(Breakpoint {
+            pos: 0.55,
+            value: 0.9,
+        });
+        l.simplify(0.01);
+        // Ends, the spike and the points either side of it survive.
+        assert!(l.points.len() <= 5);
+        assert!(l.points.iter().any(|p| p.value == 0.9));
+    }
+
+    #[test]
+    fn untouched_params_follow_their_lane() {
+        let defs = speed_def();
+        let mut values = HashMap::from([("speed".to_string(), ParamValue::Float(0.0))]);
+        let mut a = ParamAutomation {
+            bars: 1,
+            lanes: vec![lane(&[(0.0, 0.0), (0.5, 1.0)])],
+            ..Default::default()
+        };
+        a.tick(&defs, &mut values, 0.25, false, 0.1);
+        assert!((speed(&values) - 0.5).abs() < 1e-6);
+        a.tick(&defs, &mut values, 0.25, false, 0.1);
+        assert!((speed(&values) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn a_live_move_overrides_playback_and_records_when_armed() {
+        let defs = speed_def();
+        let mut values = HashMap::from([("speed".to_string(), ParamValue::Float(0.0))]);
+        let mut a = ParamAutomation {
+            bars: 1,
+            recording: true,
+            ..Default::default()
+        };
+        a.tick(&defs, &mut values, 0.1, false, 0.01);
+        assert!(a.is_empty());
+
+        values.insert("speed".into(), ParamValue::Float(0.8));
+        assert!(a.tick(&defs, &mut values, 0.1, false, 0.01));
+        assert!(a.is_touched("speed"));
+        assert_eq!(speed(&values), 0.8);
+        assert_eq!(a.lane("speed").unwrap().points.len(), 1);
+
+        // Once the touch lapses, playback takes over from the recorded lane.
+        a.recording = false;
+        values.insert("speed".into(), ParamValue::Float(0.1));
+        a.tick(&defs, &mut values, 0.1, false, 1.0);
+        a.tick(&defs, &mut values, 0.1, false, 1.0);
+        assert!(!a.is_touched("speed"));
+        assert!((speed(&values) - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn settled_writes_are_not_recorded() {
+        let defs = speed_def();
+        let mut values = HashMap::from([("speed".to_string(), ParamValue::Float(0.0))]);
+        let mut a = ParamAutomation {
+            bars: 1,
+            recording: true,
+            ..Default::default()
+        };
+        a.tick(&defs, &mut values, 0.1, false, 0.01);
+        values.insert("speed".into(), ParamValue::Float(0.8));
+        a.settle(&values, ["speed"]);
+        assert!(!a.tick(&defs, &mut values, 0.1, false, 0.01));
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn bar_lines_snap_the_playhead_to_whole_bars() {
+        let defs = speed_def();
+        let mut values = HashMap::new();
+        let mut a = ParamAutomation {
+            bars: 4,
+            position: 0.23,
+            ..Default::default()
+        };
+        a.tick(&defs, &mut values, 0.0, true, 0.01);
+        assert_eq!(a.position, 0.25);
+        // A trigger held into the next frame doesn't snap again
+        a.tick(&defs, &mut values, 0.1, true, 0.01);
+        assert!((a.position - 0.275).abs() < 1e-6);
+        // Late in the last bar, the bar line wraps to the loop start
+        a.position = 0.98;
+        a.tick(&defs, &mut values, 0.0, false, 0.01);
+        a.tick(&defs, &mut values, 0.0, true, 0.01);
+        assert_eq!(a.position, 0.0);
+    }
+
+    #[test]
+    fn midi_clock_bar_lines_snap_on_the_frame_they_arrive() {
+        use crate::midi::clock::MidiClock;
+        let defs = speed_def();
+        let mut values = HashMap::new();
+        let mut a = ParamAutomation {
+            bars: 4,
+            position: 0.23,
+            ..Default::default()
+        };
+        let mut clock = MidiClock::new();
+        clock.process_byte(0xFA);
+        // Three beats in, then a frame whose drain carries the fourth
+        for _ in 0..24 * 3 {
+            clock.process_byte(0xF8);
+        }
+        let mut crossed = false;
+        for _ in 0..24 {
+            crossed |= clock.process_byte(0xF8);
+        }
+        a.tick(&defs, &mut values, 0.0, clock.on_bar_line(crossed), 0.01);
+        assert_eq!(a.position, 0.25);
+    }
+
+    #[test]
+    fn bars_for_falls_back_without_a_tempo() {
+        assert!((bars_for(2.0, 120.0) - 1.0).abs() < 1e-6);
+        assert_eq!(bars_for(2.0, 0.0), bars_for(2.0, 120.0));
+    }
+
+    #[test]
+    fn automation_serializes_lanes_only() {
+        let a = ParamAutomation {
+            bars: 8,
+            lanes: vec![lane(&[(0.0, 0.5)])],
+            recording: true,
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&a).unwrap();
+        assert!(!json.contains("recording"));
+        let b: ParamAutomation = serde_json::from_str(&json).unwrap();
+        assert_eq!(b.bars, 8);
+        assert_eq!(b.lanes, a.lanes);
+        assert!(!b.recording);
+    }
+}
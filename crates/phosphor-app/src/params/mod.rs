@@ -1,5 +1,11 @@
+pub mod automation;
+pub mod modulation;
+pub mod sequencer;
 pub mod store;
 pub mod types;
 
+pub use automation::ParamAutomation;
+pub use modulation::AudioModulation;
+pub use sequencer::StepSequencer;
 pub use store::ParamStore;
-pub use types::{ParamDef, ParamValue};
+pub use types::{ParamDef, ParamValue, param_groups};
@@ -0,0 +1,204 @@
+//! Audio mappings driving params (#2186). Each mapping follows its feature
+//! with an attack/release envelope (#2187) and adds it, scaled by `amount`,
+//! on top of where the param was left by hand, MIDI or
+//! automation. The offset is lifted off again before the next frame's
+//! automation and sequencer tick, so they never see it as a hand move.
+
+use std::collections::HashMap;
+
+use super::{ParamDef, ParamValue};
+use crate::effect::format::AudioMapping;
+
+/// Longest attack the mappings panel offers, in seconds.
+pub const MAX_ATTACK_SECS: f32 = 1.0;
+
+/// Longest release the mappings panel offers, in seconds.
+pub const MAX_RELEASE_SECS: f32 = 4.0;
+
+/// One step of an attack/release envelope follower (#2187): `env` chases
+/// `level` with the attack time while rising and the release time while
+/// falling, each covering ~63% of the gap per time constant.
+pub fn follow(env: f32, level: f32, attack: f32, release: f32, dt: f32) -> f32 {
+    let tau = if level > env { attack } else { release };
+    if tau <= 0.0 {
+        return level;
+    }
+    env + (level - env) * (1.0 - (-dt / tau).exp())
+}
+
+/// Per-layer runtime state for its audio mappings. Not saved.
+#[derive(Debug, Clone, Default)]
+pub struct AudioModulation {
+    /// Envelope level per mapping, by index.
+    envelopes: Vec<f32>,
+    /// Param name → (base value, value written over it last frame).
+    applied: HashMap<String, (f32, f32)>,
+}
+
+impl AudioModulation {
+    /// Put every param this wrote last frame back to its base. A param that
+    /// no longer holds what was written has been moved since, and keeps the
+    /// new value.
+    pub fn release(&mut self, values: &mut HashMap<String, ParamValue>) {
+        for (name, (base, written)) in self.applied.drain() {
+            if let Some(ParamValue::Float(v)) = values.get_mut(&name) {
+                if *v == written {
+                    *v = base;
+                }
+            }
+        }
+    }
+
+    /// `values` without this frame's offsets, for saving a preset mid-swing.
+    pub fn unmodulated(&self, values: &HashMap<String, ParamValue>) -> HashMap<String, ParamValue> {
+        let mut out = values.clone();
+        for (name, (base, written)) in &self.applied {
+            if let Some(ParamValue::Float(v)) = out.get_mut(name) {
+                if *v == *written {
+                    *v = *base;
+                }
+            }
+        }
+        out
+    }
+
+    /// Write each driving mapping's offset over its param's current value.
+    /// `feature` resolves a feature name to its current 0..1 level.
+    pub fn apply(
+        &mut self,
+        mappings: &[AudioMapping],
+        defs: &[ParamDef],
+        values: &mut HashMap<String, ParamValue>,
+        feature: impl Fn(&str) -> Option<f32>,
+        dt: f32,
+    ) {
+        self.envelopes.resize(mappings.len(), 0.0);
+        let mut offsets: Vec<(&str, f32)> = Vec::new();
+        for (m, env) in mappings.iter().zip(&mut self.envelopes) {
+            let Some(param) = m.param.as_deref() else {
+                continue;
+            };
+            let Some(level) = feature(&m.feature) else {
+                continue;
+            };
+            *env = follow(
+                *env,
+                level,
+                m.attack.clamp(0.0, MAX_ATTACK_SECS),
+                m.release.clamp(0.0, MAX_RELEASE_SECS),
+                dt,
+            );
+            offsets.push((param, *env * m.amount.clamp(-1.0, 1.0)));
+        }
+
+        for (param, offset) in offsets {
+            let Some(ParamDef::Float { min, max, .. }) = defs.iter().find(|d| d.name() == param)
+            else {
+                continue;
+            };
+            let Some(ParamValue::Float(v)) = values.get_mut(param) else {
+                continue;
+            };
+            // Several mappings on one param stack on the same base.
+            let base = self.applied.get(param).map_or(*v, |(base, _)| *base);
+            let out = (*v + offset * (max - min)).clamp(*min, *max);
+            *v = out;
+            self.applied.insert(param.to_string(), (base, out));
+        }
+    }
+
+    /// Forget envelopes and written values, e.g. after the mapping list or
+    /// the effect changes.
+    pub fn reset(&mut self) {
+        self.envelopes.clear();
+        self.applied.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defs() -> Vec<ParamDef> {
+        vec![ParamDef::Float {
+            name: "warp".into(),
+            default: 0.0,
+            min: 0.0,
+            max: 2.0,
+            group: None,
+            description: None,
+            unit: None,
+        }]
+    }
+
+    fn warp(values: &HashMap<String, ParamValue>) -> f32 {
+        match values["warp"] {
+            ParamValue::Float(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn offset_rides_on_the_base_and_lifts_off() {
+        let defs = defs();
+        let mut values = HashMap::from([("warp".to_string(), ParamValue::Float(0.5))]);
+        let mut mapping = AudioMapping::new("bass", "warp");
+        mapping.amount = 0.5;
+        let mut m = AudioModulation::default();
+
+        m.apply(&[mapping.clone()], &defs, &mut values, |_| Some(1.0), 0.016);
+        assert!((warp(&values) - 1.5).abs() < 1e-6);
+        assert!((warp(&m.unmodulated(&values)) - 0.5).abs() < 1e-6);
+        m.release(&mut values);
+        assert!((warp(&values) - 0.5).abs() < 1e-6);
+
+        // A hand move between frames becomes the new base.
+        m.apply(&[mapping], &defs, &mut values, |_| Some(1.0), 0.016);
+        values.insert("warp".into(), ParamValue::Float(0.2));
+        m.release(&mut values);
+        assert!((warp(&values) - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn stacked_mappings_clamp_to_range() {
+        let defs = defs();
+        let mut values = HashMap::from([("warp".to_string(), ParamValue::Float(1.0))]);
+        let maps = [
+            AudioMapping::new("bass", "warp"),
+            AudioMapping::new("kick", "warp"),
+        ];
+        let mut m = AudioModulation::default();
+        m.apply(&maps, &defs, &mut values, |_| Some(0.4), 0.016);
+        assert!((warp(&values) - 2.0).abs() < 1e-6);
+        m.release(&mut values);
+        assert!((warp(&values) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn attack_glides_and_descriptive_mappings_do_nothing() {
+        let defs = defs();
+        let mut values = HashMap::from([("warp".to_string(), ParamValue::Float(0.0))]);
+        let mut slow = AudioMapping::new("bass", "warp");
+        slow.attack = 1.0;
+        let mut descriptive = AudioMapping::new("kick", "warp");
+        descriptive.param = None;
+        let mut m = AudioModulation::default();
+        m.apply(&[slow, descriptive], &defs, &mut values, |_| Some(1.0), 0.1);
+        let v = warp(&values);
+        assert!(v > 0.0 && v < 0.5, "expected a partial glide, got {v}");
+    }
+
+    #[test]
+    fn envelope_rises_on_attack_and_falls_on_release() {
+        // Instant attack, slow release: a hit lands at once and then eases off.
+        let env = follow(0.0, 1.0, 0.0, 2.0, 0.1);
+        assert_eq!(env, 1.0);
+        let env = follow(env, 0.0, 0.0, 2.0, 0.1);
+        assert!(env > 0.9 && env < 1.0, "release should be slow, got {env}");
+
+        // Slow attack, instant release.
+        let env = follow(0.0, 1.0, 2.0, 0.0, 0.1);
+        assert!(env > 0.0 && env < 0.1, "attack should be slow, got {env}");
+        assert_eq!(follow(env, 0.0, 2.0, 0.0, 0.1), 0.0);
+    }
+}
@@ -0,0 +1,294 @@
+//! Step sequencer (#2184): per-param patterns of 8 or 16 steps advanced by
+//! the beat clock, with swing. A sequenced param is driven by its pattern
+//! while the sequence is on, overriding hand moves and automation lanes.
+//!
+//! Steps follow the tracked beat itself — whole beats counted off
+//! `beat_phase`, realigned to the bar when `beat_in_bar` comes round — so a
+//! pattern lands on the beat rather than drifting from a clock of its own.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ParamDef, ParamValue};
+
+/// Pattern lengths offered in the panel.
+pub const STEP_COUNTS: &[usize] = &[8, 16];
+
+/// Step rates offered in the panel, as (steps per beat, label).
+pub const RATES: &[(f32, &str)] = &[(0.5, "1/2"), (1.0, "1/4"), (2.0, "1/8"), (4.0, "1/16")];
+
+/// Beats a bar realigns the count to, as the automation clock assumes 4/4.
+const BEATS_PER_BAR: u64 = 4;
+
+/// Most swing allowed: the off-beat step starts this far into its pair's
+/// second half (0.5 = a triplet-ish shuffle).
+pub const MAX_SWING: f32 = 0.5;
+
+/// One param's pattern. Step values are 0..1 across the param's range;
+/// Bool params read a step at or above one half as on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StepSequence {
+    pub param: String,
+    pub steps: Vec<f32>,
+    /// Steps per beat.
+    pub rate: f32,
+    /// 0..MAX_SWING; delays every second step.
+    pub swing: f32,
+    pub enabled: bool,
+}
+
+impl Default for StepSequence {
+    fn default() -> Self {
+        Self {
+            param: String::new(),
+            steps: vec![0.0; 16],
+            rate: 4.0,
+            swing: 0.0,
+            enabled: true,
+        }
+    }
+}
+
+impl StepSequence {
+    /// A new pattern that alternates full and empty, so it's audible at once.
+    pub fn new(param: &str) -> Self {
+        Self {
+            param: param.to_string(),
+            steps: (0..16)
+                .map(|i| if i % 2 == 0 { 1.0 } else { 0.0 })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Change the pattern length, repeating the existing steps to fill.
+    pub fn set_length(&mut self, len: usize) {
+        if self.steps.is_empty() {
+            self.steps = vec![0.0; len];
+        } else {
+            self.steps = (0..len).map(|i| self.steps[i % self.steps.len()]).collect();
+        }
+    }
+
+    /// Step playing `beats` into the clock, with swing applied.
+    pub fn step_at(&self, beats: f64) -> usize {
+        if self.steps.is_empty() {
+            return 0;
+        }
+        let step_pos = beats * f64::from(self.rate.max(0.01));
+        let pair = (step_pos / 2.0).floor();
+        let frac = step_pos / 2.0 - pair;
+        let split = 0.5 + f64::from(self.swing.clamp(0.0, MAX_SWING)) * 0.5;
+        let step = pair as usize * 2 + usize::from(frac >= split);
+        step % self.steps.len()
+    }
+}
+
+/// A layer's step sequences and the beat clock they share.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StepSequencer {
+    pub sequences: Vec<StepSequence>,
+    /// Where the patterns are, in beats since the sequencer started.
+    #[serde(skip)]
+    pub beats: f64,
+    /// Whole beats counted so far.
+    #[serde(skip)]
+    whole_beats: u64,
+    /// Last tick's `beat_phase` and `beat_in_bar`, to spot them wrapping.
+    #[serde(skip)]
+    last: (f32, f32),
+}
+
+impl StepSequencer {
+    pub fn is_empty(&self) -> bool {
+        self.sequences.is_empty()
+    }
+
+    pub fn get(&self, param: &str) -> Option<&StepSequence> {
+        self.sequences.iter().find(|s| s.param == param)
+    }
+
+    /// Add a sequence for `param`, or remove the one it has.
+    pub fn toggle(&mut self, param: &str) {
+        if self.get(param).is_some() {
+            self.sequences.retain(|s| s.param != param);
+        } else {
+            self.sequences.push(StepSequence::new(param));
+        }
+    }
+
+    /// Drop every sequence, e.g. when the layer loads another effect.
+    pub fn clear(&mut self) {
+        self.sequences.clear();
+    }
+
+    /// Params driven by an enabled sequence.
+    pub fn driven(&self) -> impl Iterator<Item = &str> {
+        self.sequences
+            .iter()
+            .filter(|s| s.enabled)
+            .map(|s| s.param.as_str())
+    }
+
+    /// Follow the beat to `beat_phase` (0..1 through the beat) and
+    /// `beat_in_bar` (0..1 through the bar), and write each enabled
+    /// sequence's current step into its param. Writes `values` directly, so
+    /// a running pattern doesn't count as an edit.
+    pub fn tick(
+        &mut self,
+        defs: &[ParamDef],
+        values: &mut HashMap<String, ParamValue>,
+        beat_phase: f32,
+        beat_in_bar: f32,
+    ) {
+        let (last_phase, last_in_bar) = self.last;
+        if beat_phase < last_phase - 0.5 {
+            self.whole_beats += 1;
+        }
+        if beat_in_bar < last_in_bar {
+            // A new bar: snap to the nearest bar line, dropping a missed or
+            // doubled beat
+            self.whole_beats =
+                (self.whole_beats + BEATS_PER_BAR / 2) / BEATS_PER_BAR * BEATS_PER_BAR;
+        }
+        self.last = (beat_phase, beat_in_bar);
+        self.beats = self.whole_beats as f64 + f64::from(beat_phase.clamp(0.0, 1.0));
+
+        for seq in self.sequences.iter().filter(|s| s.enabled) {
+            let Some(&step) = seq.steps.get(seq.step_at(self.beats)) else {
+                continue;
+            };
+            let value = match defs.iter().find(|d| d.name() == seq.param) {
+                Some(ParamDef::Float { min, max, .. }) => {
+                    ParamValue::Float(min + (max - min) * step.clamp(0.0, 1.0))
+                }
+                Some(ParamDef::Bool { .. }) => ParamValue::Bool(step >= 0.5),
+                _ => continue,
+            };
+            values.insert(seq.param.clone(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seq(swing: f32) -> StepSequence {
+        StepSequence {
+            param: "level".into(),
+            steps: (0..8).map(|i| i as f32 / 8.0).collect(),
+            rate: 2.0,
+            swing,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn steps_advance_with_the_beat_and_wrap() {
+        let s = seq(0.0);
+        assert_eq!(s.step_at(0.0), 0);
+        assert_eq!(s.step_at(0.5), 1);
+        assert_eq!(s.step_at(1.0), 2);
+        assert_eq!(s.step_at(3.99), 7);
+        assert_eq!(s.step_at(4.0), 0);
+    }
+
+    #[test]
+    fn swing_delays_the_off_step() {
+        let s = seq(0.5);
+        // The pair is one beat long; the second step now starts at 0.75.
+        assert_eq!(s.step_at(0.6), 0);
+        assert_eq!(s.step_at(0.8), 1);
+        assert_eq!(s.step_at(1.0), 2);
+    }
+
+    #[test]
+    fn set_length_repeats_the_pattern() {
+        let mut s = seq(0.0);
+        s.set_length(16);
+        assert_eq!(s.steps.len(), 16);
+        assert_eq!(s.steps[9], s.steps[1]);
+        s.set_length(8);
+        assert_eq!(s.steps, seq(0.0).steps);
+    }
+
+    #[test]
+    fn tick_drives_float_and_bool_params() {
+        let defs = vec![
+            ParamDef::Float {
+                name: "level".into(),
+                default: 0.0,
+                min: 0.0,
+                max: 2.0,
+                group: None,
+                description: None,
+                unit: None,
+            },
+            ParamDef::Bool {
+                name: "strobe".into(),
+                default: false,
+                group: None,
+                description: None,
+            },
+        ];
+        let mut values = HashMap::new();
+        let mut sq = StepSequencer {
+            sequences: vec![
+                seq(0.0),
+                StepSequence {
+                    param: "strobe".into(),
+                    steps: vec![0.0, 1.0],
+                    rate: 2.0,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        sq.tick(&defs, &mut values, 0.5, 0.0);
+        assert!(matches!(values["level"], ParamValue::Float(v) if (v - 0.25).abs() < 1e-6));
+        assert!(matches!(values["strobe"], ParamValue::Bool(true)));
+
+        sq.sequences[0].enabled = false;
+        values.insert("level".into(), ParamValue::Float(1.5));
+        sq.tick(&defs, &mut values, 0.5, 0.0);
+        assert!(matches!(values["level"], ParamValue::Float(v) if v == 1.5));
+    }
+
+    #[test]
+    fn the_clock_counts_beats_and_realigns_on_the_bar() {
+        let mut sq = StepSequencer::default();
+        let mut values = HashMap::new();
+        let mut tick = |phase: f32, in_bar: f32| {
+            sq.tick(&[], &mut values, phase, in_bar);
+            sq.beats
+        };
+        assert_eq!(tick(0.5, 0.0), 0.5);
+        assert_eq!(tick(0.875, 0.0), 0.875);
+        assert_eq!(tick(0.125, 0.25), 1.125);
+        assert_eq!(tick(0.875, 0.25), 1.875);
+        assert_eq!(tick(0.125, 0.5), 2.125);
+        assert_eq!(tick(0.875, 0.75), 2.875);
+        // A missed beat is made up at the bar line
+        assert_eq!(tick(0.125, 0.0), 4.125);
+        // A doubled one is dropped
+        for in_bar in [0.25, 0.5, 0.5, 0.75] {
+            tick(0.875, in_bar);
+            tick(0.125, in_bar);
+        }
+        tick(0.875, 0.75);
+        assert_eq!(tick(0.125, 0.0), 8.125);
+    }
+
+    #[test]
+    fn toggle_adds_and_removes() {
+        let mut sq = StepSequencer::default();
+        sq.toggle("level");
+        assert!(sq.get("level").is_some());
+        sq.toggle("level");
+        assert!(sq.is_empty());
+    }
+}
@@ -74,6 +74,7 @@ impl ParamStore {
     pub fn reset(&mut self, name: &str) {
         if let Some(def) = self.defs.iter().find(|d| d.name() == name) {
             self.values.insert(name.to_string(), def.default_value());
+            self.changed = true;
         }
     }
 
@@ -82,11 +83,24 @@ impl ParamStore {
             self.values
                 .insert(def.name().to_string(), def.default_value());
         }
+        self.changed = true;
     }
 
     /// Pack all param values into a fixed-size f32 array in definition order.
     pub fn pack_to_buffer(&self) -> [f32; 16] {
         let mut buf = [0.0f32; 16];
+        for (def, slots) in self.slots() {
+            if let Some(value) = self.values.get(def.name()) {
+                value.write_to(&mut buf, slots.start);
+            }
+        }
+        buf
+    }
+
+    /// Which `param(i)` slots each input occupies in the packed buffer, in
+    /// definition order (#2228). Inputs that don't fit are left out.
+    pub fn slots(&self) -> Vec<(&ParamDef, std::ops::Range<usize>)> {
+        let mut out = Vec::new();
         let mut offset = 0;
         for def in &self.defs {
             if offset >= 16 {
@@ -95,12 +109,12 @@ impl ParamStore {
             if let Some(value) = self.values.get(def.name()) {
                 let count = value.float_count();
                 if offset + count <= 16 {
-                    value.write_to(&mut buf, offset);
+                    out.push((def, offset..offset + count));
                     offset += count;
                 }
             }
         }
-        buf
+        out
     }
 }
 
@@ -120,14 +134,21 @@ mod tests {
                 default: 0.5,
                 min: 0.0,
                 max: 1.0,
+                group: None,
+                description: None,
+                unit: None,
             },
             ParamDef::Bool {
                 name: "active".into(),
                 default: true,
+                group: None,
+                description: None,
             },
             ParamDef::Color {
                 name: "tint".into(),
                 default: [1.0, 0.0, 0.0, 1.0],
+                group: None,
+                description: None,
             },
         ]
     }
@@ -230,6 +251,8 @@ mod tests {
             .map(|i| ParamDef::Color {
                 name: format!("c{i}"),
                 default: [1.0, 2.0, 3.0, 4.0],
+                group: None,
+                description: None,
             })
             .collect();
         let mut s = ParamStore::new();
@@ -240,6 +263,18 @@ mod tests {
         assert!(approx_eq(buf[15], 4.0, 1e-6)); // c3.a
     }
 
+    #[test]
+    fn slots_follow_the_packing() {
+        let mut s = ParamStore::new();
+        s.load_from_defs(&test_defs());
+        let slots: Vec<(&str, std::ops::Range<usize>)> =
+            s.slots().into_iter().map(|(d, r)| (d.name(), r)).collect();
+        assert_eq!(
+            slots,
+            vec![("speed", 0..1), ("active", 1..2), ("tint", 2..6)]
+        );
+    }
+
     #[test]
     fn pack_to_buffer_empty_store() {
         let s = ParamStore::new();
@@ -259,12 +294,18 @@ mod tests {
                 default: 1.0,
                 min: 0.0,
                 max: 2.0,
+                group: None,
+                description: None,
+                unit: None,
             },
             ParamDef::Point2D {
                 name: "pos".into(),
                 default: [0.3, 0.7],
                 min: [0.0, 0.0],
                 max: [1.0, 1.0],
+                group: None,
+                description: None,
+                unit: None,
             },
         ];
         let mut s = ParamStore::new();
@@ -275,6 +316,18 @@ mod tests {
         assert!(approx_eq(buf[2], 0.7, 1e-6)); // pos.y
     }
 
+    #[test]
+    fn reset_marks_changed() {
+        let mut s = ParamStore::new();
+        s.load_from_defs(&test_defs());
+        s.changed = false;
+        s.reset("speed");
+        assert!(s.changed);
+        s.changed = false;
+        s.reset_all();
+        assert!(s.changed);
+    }
+
     #[test]
     fn reset_unknown_name_is_noop() {
         let mut s = ParamStore::new();
@@ -317,6 +370,9 @@ mod tests {
             default: 0.5,
             min: 0.0,
             max: 1.0,
+            group: None,
+            description: None,
+            unit: None,
         }];
         s.load_from_defs(&initial);
         s.set("speed", ParamValue::Float(0.8));
@@ -327,10 +383,15 @@ mod tests {
                 default: 0.5,
                 min: 0.0,
                 max: 1.0,
+                group: None,
+                description: None,
+                unit: None,
             },
             ParamDef::Bool {
                 name: "glow".into(),
                 default: true,
+                group: None,
+                description: None,
             },
         ];
         s.merge_from_defs(&extended);
@@ -359,6 +420,9 @@ mod tests {
             default: 0.5,
             min: 0.0,
             max: 1.0,
+            group: None,
+            description: None,
+            unit: None,
         }];
         s.merge_from_defs(&reduced);
 
@@ -381,6 +445,9 @@ mod tests {
             default: 0.5,
             min: 0.0,
             max: 1.0,
+            group: None,
+            description: None,
+            unit: None,
         }];
         s.load_from_defs(&initial);
         s.set("val", ParamValue::Float(0.9));
@@ -389,6 +456,8 @@ mod tests {
         let changed = vec![ParamDef::Bool {
             name: "val".into(),
             default: true,
+            group: None,
+            description: None,
         }];
         s.merge_from_defs(&changed);
 
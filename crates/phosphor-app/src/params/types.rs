@@ -8,20 +8,43 @@ pub enum ParamDef {
         default: f32,
         min: f32,
         max: f32,
+        /// Param panel section this input is listed under (#2181).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        group: Option<String>,
+        /// What the input does, shown on hover (#2227).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// Shown after the value, e.g. "Hz" or "°" (#2227).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unit: Option<String>,
     },
     Color {
         name: String,
         default: [f32; 4],
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        group: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
     },
     Bool {
         name: String,
         default: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        group: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
     },
     Point2D {
         name: String,
         default: [f32; 2],
         min: [f32; 2],
         max: [f32; 2],
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        group: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unit: Option<String>,
     },
 }
 
@@ -35,6 +58,39 @@ impl ParamDef {
         }
     }
 
+    /// The param panel group, if the effect declares one.
+    pub fn group(&self) -> Option<&str> {
+        match self {
+            ParamDef::Float { group, .. }
+            | ParamDef::Color { group, .. }
+            | ParamDef::Bool { group, .. }
+            | ParamDef::Point2D { group, .. } => group.as_deref(),
+        }
+    }
+
+    /// The effect author's explanation of the input, if any (#2227).
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            ParamDef::Float { description, .. }
+            | ParamDef::Color { description, .. }
+            | ParamDef::Bool { description, .. }
+            | ParamDef::Point2D { description, .. } => description
+                .as_deref()
+                .map(str::trim)
+                .filter(|d| !d.is_empty()),
+        }
+    }
+
+    /// Unit label for numeric inputs. Colors and toggles have none.
+    pub fn unit(&self) -> Option<&str> {
+        match self {
+            ParamDef::Float { unit, .. } | ParamDef::Point2D { unit, .. } => {
+                unit.as_deref().map(str::trim).filter(|u| !u.is_empty())
+            }
+            ParamDef::Color { .. } | ParamDef::Bool { .. } => None,
+        }
+    }
+
     pub fn default_value(&self) -> ParamValue {
         match self {
             ParamDef::Float { default, .. } => ParamValue::Float(*default),
@@ -106,6 +162,23 @@ impl ParamValue {
     }
 }
 
+/// Split inputs into param panel sections (#2181): ungrouped inputs first,
+/// then each group in the order its first input appears. Indices into `defs`.
+pub fn param_groups(defs: &[ParamDef]) -> Vec<(Option<&str>, Vec<usize>)> {
+    let mut out: Vec<(Option<&str>, Vec<usize>)> = vec![(None, Vec::new())];
+    for (i, def) in defs.iter().enumerate() {
+        let group = def.group().map(str::trim).filter(|g| !g.is_empty());
+        match out.iter_mut().find(|(g, _)| *g == group) {
+            Some((_, indices)) => indices.push(i),
+            None => out.push((group, vec![i])),
+        }
+    }
+    if out[0].1.is_empty() {
+        out.remove(0);
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,16 +194,23 @@ mod tests {
             default: 0.5,
             min: 0.0,
             max: 1.0,
+            group: None,
+            description: None,
+            unit: None,
         };
         assert_eq!(f.name(), "speed");
         let c = ParamDef::Color {
             name: "tint".into(),
             default: [1.0, 0.0, 0.0, 1.0],
+            group: None,
+            description: None,
         };
         assert_eq!(c.name(), "tint");
         let b = ParamDef::Bool {
             name: "active".into(),
             default: true,
+            group: None,
+            description: None,
         };
         assert_eq!(b.name(), "active");
         let p = ParamDef::Point2D {
@@ -138,6 +218,9 @@ mod tests {
             default: [0.0, 0.0],
             min: [-1.0, -1.0],
             max: [1.0, 1.0],
+            group: None,
+            description: None,
+            unit: None,
         };
         assert_eq!(p.name(), "pos");
     }
@@ -149,6 +232,9 @@ mod tests {
             default: 0.75,
             min: 0.0,
             max: 1.0,
+            group: None,
+            description: None,
+            unit: None,
         };
         match f.default_value() {
             ParamValue::Float(v) => assert!(approx_eq(v, 0.75, 1e-6)),
@@ -161,6 +247,8 @@ mod tests {
         let c = ParamDef::Color {
             name: "c".into(),
             default: [0.1, 0.2, 0.3, 0.4],
+            group: None,
+            description: None,
         };
         match c.default_value() {
             ParamValue::Color(v) => {
@@ -176,6 +264,8 @@ mod tests {
         let b = ParamDef::Bool {
             name: "b".into(),
             default: true,
+            group: None,
+            description: None,
         };
         match b.default_value() {
             ParamValue::Bool(v) => assert!(v),
@@ -190,6 +280,9 @@ mod tests {
             default: [0.5, -0.5],
             min: [-1.0, -1.0],
             max: [1.0, 1.0],
+            group: None,
+            description: None,
+            unit: None,
         };
         match p.default_value() {
             ParamValue::Point2D(v) => {
@@ -317,6 +410,9 @@ mod tests {
             default: 0.5,
             min: 0.0,
             max: 1.0,
+            group: None,
+            description: None,
+            unit: None,
         };
         def.set_default(&ParamValue::Float(0.8));
         match def.default_value() {
@@ -330,6 +426,8 @@ mod tests {
         let mut def = ParamDef::Color {
             name: "c".into(),
             default: [0.0; 4],
+            group: None,
+            description: None,
         };
         def.set_default(&ParamValue::Color([0.1, 0.2, 0.3, 0.4]));
         match def.default_value() {
@@ -345,6 +443,9 @@ mod tests {
             default: 0.5,
             min: 0.0,
             max: 1.0,
+            group: None,
+            description: None,
+            unit: None,
         };
         def.set_default(&ParamValue::Bool(true)); // wrong type — should be no-op
         match def.default_value() {
@@ -352,4 +453,66 @@ mod tests {
             _ => panic!("expected Float unchanged"),
         }
     }
+
+    #[test]
+    fn group_parses_and_is_optional() {
+        let defs: Vec<ParamDef> = serde_json::from_str(
+            r#"[
+                { "type": "Float", "name": "speed", "default": 0.5, "min": 0.0, "max": 1.0, "group": "Motion" },
+                { "type": "Bool", "name": "on", "default": true }
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(defs[0].group(), Some("Motion"));
+        assert_eq!(defs[1].group(), None);
+        // Ungrouped inputs serialize exactly as before.
+        assert!(!serde_json::to_string(&defs[1]).unwrap().contains("group"));
+    }
+
+    #[test]
+    fn description_and_unit_parse_and_are_optional() {
+        let defs: Vec<ParamDef> = serde_json::from_str(
+            r#"[
+                { "type": "Float", "name": "rate", "default": 2.0, "min": 0.1, "max": 8.0,
+                  "description": "How often the rings pulse", "unit": "Hz" },
+                { "type": "Color", "name": "tint", "default": [1, 1, 1, 1], "description": "  " },
+                { "type": "Bool", "name": "on", "default": true }
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(defs[0].description(), Some("How often the rings pulse"));
+        assert_eq!(defs[0].unit(), Some("Hz"));
+        // Blank text counts as none.
+        assert_eq!(defs[1].description(), None);
+        assert_eq!(defs[2].unit(), None);
+        let plain = serde_json::to_string(&defs[2]).unwrap();
+        assert!(!plain.contains("description") && !plain.contains("unit"));
+    }
+
+    #[test]
+    fn param_groups_keep_first_appearance_order() {
+        let def = |name: &str, group: Option<&str>| ParamDef::Bool {
+            name: name.into(),
+            default: false,
+            group: group.map(Into::into),
+            description: None,
+        };
+        let defs = vec![
+            def("hue", Some("Color")),
+            def("speed", Some("Motion")),
+            def("mix", None),
+            def("sat", Some("Color")),
+            def("blank", Some(" ")),
+        ];
+        assert_eq!(
+            param_groups(&defs),
+            vec![
+                (None, vec![2, 4]),
+                (Some("Color"), vec![0, 3]),
+                (Some("Motion"), vec![1]),
+            ]
+        );
+        assert_eq!(param_groups(&defs[..2]).len(), 2);
+        assert!(param_groups(&[]).is_empty());
+    }
 }
@@ -0,0 +1,145 @@
+//! Where settings, presets, scenes, mappings and downloads live (#2178).
+//! Normally the platform config dir (`~/.config/phosphor` on Linux). In
+//! portable mode — `--portable`, or a `portable` marker file next to the
+//! executable — everything goes in `phosphor-data/` beside the executable
+//! instead, and bundled assets are taken from there too, so a whole rig
+//! runs from a USB stick without touching the venue machine.
+//!
+//! `--config-dir <path>` points config somewhere else outright, and an
+//! `instance.lock` in the config dir keeps a second instance sharing it from
+//! overwriting the first one's saves (#2224).
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Marker file that turns portable mode on without the flag.
+pub const PORTABLE_MARKER: &str = "portable";
+
+/// Folder beside the executable that holds the portable config.
+pub const PORTABLE_DATA_DIR: &str = "phosphor-data";
+
+/// Lock file held by the instance that owns a config dir.
+pub const INSTANCE_LOCK: &str = "instance.lock";
+
+static PORTABLE_ROOT: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+static CONFIG_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Decide portable mode and any `--config-dir` once, before anything loads
+/// its config. Later calls, and lookups made before it, keep the first decision.
+pub fn init(force_portable: bool, config_override: Option<PathBuf>) {
+    if let Some(dir) = config_override {
+        log::info!("Config dir: {}", dir.display());
+        let _ = CONFIG_OVERRIDE.set(dir);
+    }
+    let root = exe_dir().and_then(|dir| portable_root_for(&dir, force_portable));
+    if let Some(ref dir) = root {
+        log::info!(
+            "Portable mode: config in {}",
+            dir.join(PORTABLE_DATA_DIR).display()
+        );
+    }
+    let _ = PORTABLE_ROOT.set(root);
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    std::env::current_exe()
+        .ok()?
+        .parent()
+        .map(Path::to_path_buf)
+}
+
+fn portable_root_for(exe_dir: &Path, force: bool) -> Option<PathBuf> {
+    (force || exe_dir.join(PORTABLE_MARKER).is_file()).then(|| exe_dir.to_path_buf())
+}
+
+/// The executable's directory when running portable.
+pub fn portable_root() -> Option<&'static Path> {
+    PORTABLE_ROOT
+        .get_or_init(|| exe_dir().and_then(|dir| portable_root_for(&dir, false)))
+        .as_deref()
+}
+
+/// Phosphor's config directory: the `--config-dir` path when given, else
+/// `<platform config>/phosphor`, or `<exe dir>/phosphor-data` in portable mode.
+pub fn config_dir() -> PathBuf {
+    if let Some(dir) = CONFIG_OVERRIDE.get() {
+        return dir.clone();
+    }
+    match portable_root() {
+        Some(root) => root.join(PORTABLE_DATA_DIR),
+        None => dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("phosphor"),
+    }
+}
+
+/// Take the instance lock in `dir`, writing this process id into it. `Ok(None)`
+/// means another running instance holds it; the OS drops the lock when the
+/// holder exits, even on a crash, so a stale file never blocks a restart.
+pub fn lock_instance(dir: &Path) -> std::io::Result<Option<File>> {
+    std::fs::create_dir_all(dir)?;
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(dir.join(INSTANCE_LOCK))?;
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(std::fs::TryLockError::WouldBlock) => return Ok(None),
+        Err(std::fs::TryLockError::Error(e)) => return Err(e),
+    }
+    file.set_len(0)?;
+    file.rewind()?;
+    write!(file, "{}", std::process::id())?;
+    Ok(Some(file))
+}
+
+/// Process id written by the instance holding the lock in `dir`, when readable.
+pub fn instance_lock_holder(dir: &Path) -> Option<u32> {
+    let mut text = String::new();
+    File::open(dir.join(INSTANCE_LOCK))
+        .ok()?
+        .read_to_string(&mut text)
+        .ok()?;
+    text.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marker_file_or_flag_turns_portable_on() {
+        let dir = std::env::temp_dir().join(format!("phosphor-portable-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let _ = std::fs::remove_file(dir.join(PORTABLE_MARKER));
+
+        assert_eq!(portable_root_for(&dir, false), None);
+        assert_eq!(portable_root_for(&dir, true), Some(dir.clone()));
+
+        std::fs::write(dir.join(PORTABLE_MARKER), "").unwrap();
+        assert_eq!(portable_root_for(&dir, false), Some(dir.clone()));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn second_instance_is_refused_until_the_first_exits() {
+        let dir = std::env::temp_dir().join(format!("phosphor-lock-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let first = lock_instance(&dir).unwrap();
+        assert!(first.is_some());
+        assert!(lock_instance(&dir).unwrap().is_none());
+        assert_eq!(instance_lock_holder(&dir), Some(std::process::id()));
+
+        drop(first);
+        assert!(lock_instance(&dir).unwrap().is_some());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
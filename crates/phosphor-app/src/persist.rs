@@ -0,0 +1,315 @@
+//! Config and preset files are written off the render thread (#2201). Saves
+//! are queued to one writer thread, which waits out a short debounce so a
+//! burst of saves to the same file (a learned mapping per fader tweak) lands
+//! as one write of the last contents. Each write goes to a `.tmp` sibling
+//! that is then renamed over the file, so a crash or full disk mid-write
+//! never leaves a truncated config behind. Writes that fail are kept for
+//! [`take_failures`], so the UI can say a save didn't land.
+//!
+//! A second instance sharing a config dir runs read-only (#2224): its saves
+//! are dropped here so they can't overwrite the first instance's files.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+
+/// How long a file waits for further saves before it's written.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+enum Job {
+    Write(PathBuf, Vec<u8>),
+    /// Write the first path, then delete the second once the write has landed.
+    Move(PathBuf, Vec<u8>, PathBuf),
+    Remove(PathBuf),
+    /// Write everything pending now, then answer.
+    Flush(Sender<()>),
+}
+
+static WRITER: OnceLock<Sender<Job>> = OnceLock::new();
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+static FAILURES: Mutex<Vec<(PathBuf, String)>> = Mutex::new(Vec::new());
+
+/// Drop every save from now on; another instance owns the config dir.
+pub fn set_read_only() {
+    READ_ONLY.store(true, Ordering::Relaxed);
+}
+
+/// Whether saves are being dropped.
+pub fn read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+/// Error for direct writes into the config dir while read-only.
+pub fn ensure_writable() -> anyhow::Result<()> {
+    if read_only() {
+        anyhow::bail!("another instance owns this config dir; launch with --config-dir to save");
+    }
+    Ok(())
+}
+
+/// Saves that failed since the last call, oldest first, with the error.
+pub fn take_failures() -> Vec<(PathBuf, String)> {
+    std::mem::take(&mut *FAILURES.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+fn fail(path: &Path, error: String) {
+    log::error!("Failed to write {}: {error}", path.display());
+    FAILURES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push((path.to_path_buf(), error));
+}
+
+fn writer() -> &'static Sender<Job> {
+    WRITER.get_or_init(|| spawn(DEBOUNCE))
+}
+
+/// Queue `contents` to replace the file at `path`, creating its folder.
+/// A later save to the same path before this one is written replaces it.
+pub fn write(path: PathBuf, contents: impl Into<Vec<u8>>) {
+    if read_only() {
+        log::debug!("Read-only, not saving {}", path.display());
+        return;
+    }
+    if let Err(e) = writer().send(Job::Write(path, contents.into()))
+        && let Job::Write(path, _) = e.into_inner()
+    {
+        fail(&path, "the file writer isn't running".to_string());
+    }
+}
+
+/// Queue writing `contents` to `path` in place of the file at `from`, which
+/// is deleted only once the new file is written, so a failed write loses
+/// nothing. Any save still pending for `from` is dropped.
+pub fn replace(path: PathBuf, contents: impl Into<Vec<u8>>, from: PathBuf) {
+    if read_only() {
+        log::debug!(
+            "Read-only, not moving {} to {}",
+            from.display(),
+            path.display()
+        );
+        return;
+    }
+    if let Err(e) = writer().send(Job::Move(path, contents.into(), from))
+        && let Job::Move(path, ..) = e.into_inner()
+    {
+        fail(&path, "the file writer isn't running".to_string());
+    }
+}
+
+/// Queue deleting the file at `path`, dropping any save still pending for it.
+pub fn remove(path: PathBuf) {
+    if read_only() {
+        log::debug!("Read-only, not deleting {}", path.display());
+        return;
+    }
+    let _ = writer().send(Job::Remove(path));
+}
+
+/// Block until every queued save is on disk. Called before exit.
+pub fn flush() {
+    if let Some(tx) = WRITER.get() {
+        flush_on(tx);
+    }
+}
+
+fn flush_on(tx: &Sender<Job>) {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+    if tx.send(Job::Flush(done_tx)).is_ok() {
+        let _ = done_rx.recv();
+    }
+}
+
+fn spawn(debounce: Duration) -> Sender<Job> {
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let spawned = std::thread::Builder::new()
+        .name("phosphor-writer".into())
+        .spawn(move || run(&rx, debounce));
+    if let Err(e) = spawned {
+        log::error!("Failed to start the file writer thread: {e}");
+    }
+    tx
+}
+
+fn run(rx: &Receiver<Job>, debounce: Duration) {
+    let mut pending: Pending = HashMap::new();
+    loop {
+        let job = match pending.values().map(|(_, due)| *due).min() {
+            Some(due) => rx.recv_timeout(due.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match job {
+            Ok(Job::Write(path, contents)) => {
+                // A save over a pending move still deletes the old file
+                let from = pending.remove(&path).and_then(|(_, _, from)| from);
+                pending.insert(path, (contents, Instant::now() + debounce, from));
+            }
+            Ok(Job::Move(path, contents, from)) => {
+                pending.remove(&from);
+                pending.insert(path, (contents, Instant::now() + debounce, Some(from)));
+            }
+            Ok(Job::Remove(path)) => {
+                pending.remove(&path);
+                if let Err(e) = std::fs::remove_file(&path)
+                    && e.kind() != std::io::ErrorKind::NotFound
+                {
+                    log::error!("Failed to delete {}: {e}", path.display());
+                }
+            }
+            Ok(Job::Flush(done)) => {
+                write_due(&mut pending, None);
+                let _ = done.send(());
+            }
+            Err(RecvTimeoutError::Timeout) => write_due(&mut pending, Some(Instant::now())),
+            Err(RecvTimeoutError::Disconnected) => {
+                write_due(&mut pending, None);
+                return;
+            }
+        }
+    }
+}
+
+/// Queued writes: contents, when they're due, and a file to delete after.
+type Pending = HashMap<PathBuf, (Vec<u8>, Instant, Option<PathBuf>)>;
+
+/// Write the pending files due by `now`, or all of them.
+fn write_due(pending: &mut Pending, now: Option<Instant>) {
+    let due: Vec<PathBuf> = pending
+        .iter()
+        .filter(|(_, (_, at, _))| now.is_none_or(|now| *at <= now))
+        .map(|(path, _)| path.clone())
+        .collect();
+    for path in due {
+        if let Some((contents, _, from)) = pending.remove(&path) {
+            match write_atomic(&path, &contents) {
+                Ok(()) => log::debug!("Saved {}", path.display()),
+                Err(e) => {
+                    fail(&path, e.to_string());
+                    continue;
+                }
+            }
+            if let Some(from) = from.filter(|from| *from != path)
+                && let Err(e) = std::fs::remove_file(&from)
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                log::error!("Failed to delete {}: {e}", from.display());
+            }
+        }
+    }
+}
+
+/// Write `contents` to a temp file beside `path` and rename it into place.
+fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_to_one_file_collapse_into_the_last() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sub/osc.json");
+        let tx = spawn(Duration::from_secs(30));
+        tx.send(Job::Write(path.clone(), b"first".to_vec()))
+            .unwrap();
+        tx.send(Job::Write(path.clone(), b"second".to_vec()))
+            .unwrap();
+        // Still inside the debounce window
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!path.exists());
+
+        flush_on(&tx);
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert!(!dir.path().join("sub/osc.json.tmp").exists());
+
+        tx.send(Job::Write(path.clone(), b"third".to_vec()))
+            .unwrap();
+        tx.send(Job::Remove(path.clone())).unwrap();
+        flush_on(&tx);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn a_move_keeps_the_old_file_until_the_new_one_lands() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.json");
+        std::fs::write(&old, b"{}").unwrap();
+        let tx = spawn(Duration::from_secs(30));
+
+        // The new file can't be written: the old one stays
+        std::fs::write(dir.path().join("blocker"), b"").unwrap();
+        let bad = dir.path().join("blocker/new.json");
+        tx.send(Job::Move(bad, b"{}".to_vec(), old.clone()))
+            .unwrap();
+        flush_on(&tx);
+        assert!(old.exists());
+
+        let new = dir.path().join("new.json");
+        tx.send(Job::Move(new.clone(), b"{}".to_vec(), old.clone()))
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(old.exists(), "not deleted before the write");
+        flush_on(&tx);
+        assert!(!old.exists());
+        assert_eq!(std::fs::read(&new).unwrap(), b"{}");
+    }
+
+    #[test]
+    fn a_save_after_a_move_still_deletes_the_old_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.json");
+        std::fs::write(&old, b"{}").unwrap();
+        let new = dir.path().join("new.json");
+        let tx = spawn(Duration::from_secs(30));
+
+        tx.send(Job::Move(new.clone(), b"first".to_vec(), old.clone()))
+            .unwrap();
+        tx.send(Job::Write(new.clone(), b"second".to_vec()))
+            .unwrap();
+        flush_on(&tx);
+        assert!(!old.exists());
+        assert_eq!(std::fs::read(&new).unwrap(), b"second");
+    }
+
+    #[test]
+    fn failed_writes_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        // A file where the parent folder should be
+        std::fs::write(dir.path().join("blocker"), b"").unwrap();
+        let path = dir.path().join("blocker/preset.json");
+        let tx = spawn(Duration::from_secs(30));
+        tx.send(Job::Write(path.clone(), b"{}".to_vec())).unwrap();
+        flush_on(&tx);
+        assert!(take_failures().iter().any(|(p, _)| *p == path));
+    }
+
+    #[test]
+    fn debounced_save_lands_on_its_own() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let tx = spawn(Duration::from_millis(10));
+        tx.send(Job::Write(path.clone(), b"{}".to_vec())).unwrap();
+        let start = Instant::now();
+        while !path.exists() && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(std::fs::read(&path).unwrap(), b"{}");
+    }
+}
@@ -0,0 +1,201 @@
+//! Attract mode (#2239): after minutes with no sound and nobody touching the
+//! controls, slowly cycle a list of ambient presets with long dissolves, and
+//! put the stack back as it was the moment sound or input returns. Meant for
+//! lobbies, where the silence action's single idle preset gets stale.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Saved with the settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AttractConfig {
+    pub enabled: bool,
+    /// Minutes without sound or input before it starts.
+    pub after_mins: f32,
+    /// Preset names to cycle, in order.
+    pub presets: Vec<String>,
+    /// Minutes each preset is shown.
+    pub hold_mins: f32,
+    /// Seconds each dissolve takes.
+    pub fade_secs: f32,
+}
+
+impl Default for AttractConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            after_mins: 5.0,
+            presets: Vec::new(),
+            hold_mins: 3.0,
+            fade_secs: 15.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttractEvent {
+    /// Save the stack and dissolve to the first preset.
+    Started,
+    /// Dissolve to `config.presets[slot]`.
+    Next(usize),
+    /// Put the saved stack back.
+    Stopped,
+}
+
+/// Tracks whether attract mode is running, where it is in the list, and the
+/// dissolve in progress.
+#[derive(Debug, Default)]
+pub struct AttractWatch {
+    active: bool,
+    slot: usize,
+    /// Seconds the current preset has been up.
+    held: f32,
+    /// Seconds into the dissolve, and its length.
+    fade: Option<(f32, f32)>,
+}
+
+impl AttractWatch {
+    /// Advance one frame of `dt` seconds. `idle_for` is how long there has
+    /// been neither sound nor input; `allowed` is false while something else,
+    /// like a playing scene, owns the stack.
+    pub fn update(
+        &mut self,
+        config: &AttractConfig,
+        idle_for: Duration,
+        allowed: bool,
+        dt: f32,
+    ) -> Option<AttractEvent> {
+        if let Some((elapsed, secs)) = &mut self.fade {
+            *elapsed += dt;
+            if *elapsed >= *secs {
+                self.fade = None;
+            }
+        }
+
+        let idle = config.enabled
+            && !config.presets.is_empty()
+            && !idle_for.is_zero()
+            && idle_for.as_secs_f32() >= config.after_mins.max(0.0) * 60.0;
+        if self.active && !idle {
+            self.active = false;
+            self.fade = None;
+            return Some(AttractEvent::Stopped);
+        }
+        if !self.active {
+            if !(idle && allowed) {
+                return None;
+            }
+            self.active = true;
+            self.slot = 0;
+            self.held = 0.0;
+            return Some(AttractEvent::Started);
+        }
+
+        self.held += dt;
+        // A hold never ends before its dissolve does
+        let hold = (config.hold_mins * 60.0).max(config.fade_secs);
+        if self.held < hold || config.presets.len() < 2 {
+            return None;
+        }
+        self.held = 0.0;
+        self.slot = (self.slot + 1) % config.presets.len();
+        Some(AttractEvent::Next(self.slot))
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Index into `config.presets` of the preset up now.
+    pub fn slot(&self) -> usize {
+        self.slot
+    }
+
+    /// Begin a dissolve of `secs` seconds.
+    pub fn start_fade(&mut self, secs: f32) {
+        self.fade = (secs > 0.0).then_some((0.0, secs));
+    }
+
+    /// How far the running dissolve is, 0 to 1, or `None` when there is none.
+    pub fn dissolve_progress(&self) -> Option<f32> {
+        self.fade.map(|(elapsed, secs)| (elapsed / secs).min(1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AttractConfig {
+        AttractConfig {
+            enabled: true,
+            after_mins: 1.0,
+            presets: vec!["a".into(), "b".into()],
+            hold_mins: 1.0,
+            fade_secs: 10.0,
+        }
+    }
+
+    const IDLE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn starts_after_the_wait_cycles_and_stops_on_activity() {
+        let cfg = config();
+        let mut watch = AttractWatch::default();
+        assert_eq!(watch.update(&cfg, Duration::from_secs(59), true, 1.0), None);
+        assert_eq!(
+            watch.update(&cfg, IDLE, true, 1.0),
+            Some(AttractEvent::Started)
+        );
+        assert_eq!(watch.slot(), 0);
+        assert_eq!(watch.update(&cfg, IDLE, true, 59.0), None);
+        assert_eq!(
+            watch.update(&cfg, IDLE, true, 1.0),
+            Some(AttractEvent::Next(1))
+        );
+        assert_eq!(
+            watch.update(&cfg, IDLE, true, 60.0),
+            Some(AttractEvent::Next(0))
+        );
+        assert_eq!(
+            watch.update(&cfg, Duration::ZERO, true, 1.0),
+            Some(AttractEvent::Stopped)
+        );
+        assert!(!watch.is_active());
+    }
+
+    #[test]
+    fn waits_while_not_allowed_or_nothing_to_show() {
+        let mut watch = AttractWatch::default();
+        assert_eq!(watch.update(&config(), IDLE, false, 1.0), None);
+        let empty = AttractConfig {
+            presets: Vec::new(),
+            ..config()
+        };
+        assert_eq!(watch.update(&empty, IDLE, true, 1.0), None);
+        let single = AttractConfig {
+            presets: vec!["a".into()],
+            ..config()
+        };
+        assert_eq!(
+            watch.update(&single, IDLE, true, 1.0),
+            Some(AttractEvent::Started)
+        );
+        assert_eq!(watch.update(&single, IDLE, true, 600.0), None);
+    }
+
+    #[test]
+    fn dissolve_runs_its_length_then_ends() {
+        let cfg = config();
+        let mut watch = AttractWatch::default();
+        watch.update(&cfg, IDLE, true, 0.0);
+        watch.start_fade(10.0);
+        assert_eq!(watch.dissolve_progress(), Some(0.0));
+        watch.update(&cfg, IDLE, true, 5.0);
+        assert_eq!(watch.dissolve_progress(), Some(0.5));
+        watch.update(&cfg, IDLE, true, 5.0);
+        assert_eq!(watch.dissolve_progress(), None);
+    }
+}
@@ -0,0 +1,151 @@
+//! Preset crossfade (#2250): preset A on one end of a fader, preset B on the
+//! other, for DJ-mixer style blends between looks. Opacities and effect
+//! params the two presets share are interpolated along the fader; anything
+//! that can't be — a different effect on a layer, a media file, a toggle —
+//! comes from whichever end the fader is nearer, switching at the middle.
+
+use serde::{Deserialize, Serialize};
+
+use super::store::Preset;
+
+/// Saved with the settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresetCrossfade {
+    /// Preset ids (#2222) at the ends of the fader, so a rename keeps them.
+    pub a: Option<u64>,
+    pub b: Option<u64>,
+    /// Fader position, 0 = A, 1 = B. Starts at A each launch.
+    #[serde(skip)]
+    pub position: f32,
+}
+
+impl PresetCrossfade {
+    /// The end the fader is nearer, whose layout is on screen.
+    pub fn nearer(&self) -> Option<u64> {
+        if self.position < 0.5 { self.a } else { self.b }
+    }
+}
+
+/// The preset on screen at fader position `t`: the nearer end, with the
+/// opacity of each layer both have and the params of each layer running the
+/// same effect in both interpolated between them, as scene morphs do.
+/// `ParamValue::lerp` already switches bools at the middle.
+pub fn blend(a: &Preset, b: &Preset, t: f32) -> Preset {
+    let t = t.clamp(0.0, 1.0);
+    let mut out = if t < 0.5 { a.clone() } else { b.clone() };
+    for (i, layer) in out.layers.iter_mut().enumerate() {
+        let (Some(la), Some(lb)) = (a.layers.get(i), b.layers.get(i)) else {
+            continue;
+        };
+        layer.opacity = la.opacity + (lb.opacity - la.opacity) * t;
+        if la.effect_name != lb.effect_name {
+            continue;
+        }
+        for (name, value) in &mut layer.params {
+            if let (Some(va), Some(vb)) = (la.params.get(name), lb.params.get(name))
+                && std::mem::discriminant(va) == std::mem::discriminant(vb)
+            {
+                *value = va.lerp(vb, t);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::ParamValue;
+    use crate::preset::store::LayerPreset;
+
+    /// Effect name, opacity and params of one layer.
+    type Layer<'a> = (&'a str, f32, &'a [(&'a str, ParamValue)]);
+
+    fn preset(layers: &[Layer<'_>]) -> Preset {
+        let json = serde_json::json!({
+            "layers": layers
+                .iter()
+                .map(|(effect, opacity, _)| serde_json::json!({
+                    "effect_name": effect,
+                    "opacity": opacity,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let mut preset: Preset = serde_json::from_value(json).unwrap();
+        for (layer, (_, _, params)) in preset.layers.iter_mut().zip(layers) {
+            layer.params = params
+                .iter()
+                .map(|(n, v)| (n.to_string(), v.clone()))
+                .collect();
+        }
+        preset
+    }
+
+    /// A param's floats, with a bool as 0 or 1.
+    fn param(layer: &LayerPreset, name: &str) -> Vec<f32> {
+        match &layer.params[name] {
+            ParamValue::Float(v) => vec![*v],
+            ParamValue::Color(c) => c.to_vec(),
+            ParamValue::Bool(b) => vec![f32::from(u8::from(*b))],
+            ParamValue::Point2D(p) => p.to_vec(),
+        }
+    }
+
+    #[test]
+    fn shared_values_follow_the_fader() {
+        let a = preset(&[(
+            "Tunnel",
+            1.0,
+            &[
+                ("speed", ParamValue::Float(0.0)),
+                ("tint", ParamValue::Color([0.0, 0.0, 0.0, 1.0])),
+            ],
+        )]);
+        let b = preset(&[(
+            "Tunnel",
+            0.0,
+            &[
+                ("speed", ParamValue::Float(1.0)),
+                ("tint", ParamValue::Color([1.0, 0.5, 0.0, 1.0])),
+            ],
+        )]);
+        let mid = blend(&a, &b, 0.25);
+        assert_eq!(mid.layers[0].opacity, 0.75);
+        assert_eq!(param(&mid.layers[0], "speed"), [0.25]);
+        assert_eq!(param(&mid.layers[0], "tint"), [0.25, 0.125, 0.0, 1.0]);
+        assert_eq!(param(&blend(&a, &b, 1.0).layers[0], "speed"), [1.0]);
+    }
+
+    #[test]
+    fn everything_else_switches_at_the_middle() {
+        let a = preset(&[
+            ("Tunnel", 1.0, &[("mirror", ParamValue::Bool(false))]),
+            ("Plasma", 1.0, &[("speed", ParamValue::Float(0.0))]),
+        ]);
+        let b = preset(&[
+            ("Tunnel", 1.0, &[("mirror", ParamValue::Bool(true))]),
+            ("Rings", 0.0, &[("speed", ParamValue::Float(1.0))]),
+        ]);
+        let before = blend(&a, &b, 0.4);
+        assert_eq!(param(&before.layers[0], "mirror"), [0.0]);
+        assert_eq!(before.layers[1].effect_name, "Plasma");
+        // Different effects share the layer's opacity but not their params
+        assert!((before.layers[1].opacity - 0.6).abs() < 1e-6);
+        assert_eq!(param(&before.layers[1], "speed"), [0.0]);
+
+        let after = blend(&a, &b, 0.5);
+        assert_eq!(param(&after.layers[0], "mirror"), [1.0]);
+        assert_eq!(after.layers[1].effect_name, "Rings");
+    }
+
+    #[test]
+    fn extra_layers_come_from_the_nearer_end() {
+        let a = preset(&[("Tunnel", 1.0, &[])]);
+        let b = preset(&[("Tunnel", 1.0, &[]), ("Rings", 0.5, &[])]);
+        assert_eq!(blend(&a, &b, 0.3).layers.len(), 1);
+        let near_b = blend(&a, &b, 0.7);
+        assert_eq!(near_b.layers.len(), 2);
+        assert_eq!(near_b.layers[1].opacity, 0.5);
+    }
+}
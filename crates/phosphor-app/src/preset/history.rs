@@ -0,0 +1,241 @@
+//! Set history (#2220): which presets and effects were loaded during the session and
+//! when. Feeds the preset panel's recent strip and the set report written out after a
+//! show.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::session_stats::SessionStats;
+
+/// Oldest entries are dropped past this, so a binding that flips presets on every
+/// beat can't grow the history without bound over a long night.
+const MAX_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryKind {
+    Preset,
+    /// An effect picked by hand on a layer, outside of a preset load.
+    Effect {
+        layer: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// Time since the session started.
+    pub at: Duration,
+    pub kind: HistoryKind,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct SetHistory {
+    started: Instant,
+    entries: VecDeque<HistoryEntry>,
+    /// Outcome of the last report export, shown under the export button.
+    pub last_export: Option<Result<PathBuf, String>>,
+}
+
+impl Default for SetHistory {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            entries: VecDeque::new(),
+            last_export: None,
+        }
+    }
+}
+
+impl SetHistory {
+    pub fn entries(&self) -> &VecDeque<HistoryEntry> {
+        &self.entries
+    }
+
+    pub fn record_preset(&mut self, name: &str) {
+        self.push(self.started.elapsed(), HistoryKind::Preset, name);
+    }
+
+    pub fn record_effect(&mut self, layer: usize, name: &str) {
+        self.push(self.started.elapsed(), HistoryKind::Effect { layer }, name);
+    }
+
+    /// Record a change, unless it reloads what that slot already had.
+    fn push(&mut self, at: Duration, kind: HistoryKind, name: &str) {
+        let previous = self.entries.iter().rev().find(|e| e.kind == kind);
+        if previous.is_some_and(|e| e.name == name) {
+            return;
+        }
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(HistoryEntry {
+            at,
+            kind,
+            name: name.to_string(),
+        });
+    }
+
+    /// Up to `max` distinct presets, most recently used first, leaving out `current`.
+    pub fn recent_presets(&self, current: Option<&str>, max: usize) -> Vec<&str> {
+        let mut recent: Vec<&str> = Vec::new();
+        for entry in self.entries.iter().rev() {
+            if recent.len() >= max {
+                break;
+            }
+            if entry.kind != HistoryKind::Preset
+                || Some(entry.name.as_str()) == current
+                || recent.contains(&entry.name.as_str())
+            {
+                continue;
+            }
+            recent.push(&entry.name);
+        }
+        recent
+    }
+
+    /// The session so far as a markdown set report.
+    pub fn report(&self) -> String {
+        self.report_until(self.started.elapsed())
+    }
+
+    fn report_until(&self, end: Duration) -> String {
+        let presets: Vec<&HistoryEntry> = self
+            .entries
+            .iter()
+            .filter(|e| e.kind == HistoryKind::Preset)
+            .collect();
+        let effects = self.entries.len() - presets.len();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "# Fosfora set report\n");
+        let _ = writeln!(
+            out,
+            "Length {} · {} preset change(s) · {} effect change(s)\n",
+            clock(end),
+            presets.len(),
+            effects
+        );
+        if self.entries.is_empty() {
+            out.push_str("Nothing was loaded.\n");
+            return out;
+        }
+
+        // How long each preset load stayed up: until the next preset, or the end.
+        let held = |i: usize| {
+            let next = presets.get(i + 1).map_or(end, |e| e.at);
+            next.saturating_sub(presets[i].at)
+        };
+
+        out.push_str("| Time | Change | Name | On for |\n|---|---|---|---|\n");
+        let mut preset_i = 0;
+        for entry in &self.entries {
+            let (change, on_for) = match entry.kind {
+                HistoryKind::Preset => {
+                    let on_for = clock(held(preset_i));
+                    preset_i += 1;
+                    ("Preset".to_string(), on_for)
+                }
+                HistoryKind::Effect { layer } => {
+                    (format!("Effect, layer {}", layer + 1), String::new())
+                }
+            };
+            let _ = writeln!(
+                out,
+                "| {} | {change} | {} | {on_for} |",
+                clock(entry.at),
+                entry.name.replace('|', "\\|")
+            );
+        }
+
+        // Time per preset, longest first.
+        let mut totals: Vec<(&str, usize, Duration)> = Vec::new();
+        for (i, entry) in presets.iter().enumerate() {
+            match totals.iter_mut().find(|t| t.0 == entry.name) {
+                Some(total) => {
+                    total.1 += 1;
+                    total.2 += held(i);
+                }
+                None => totals.push((&entry.name, 1, held(i))),
+            }
+        }
+        totals.sort_by_key(|t| std::cmp::Reverse(t.2));
+        if !totals.is_empty() {
+            out.push_str("\n## Time per preset\n\n| Preset | Loads | Total |\n|---|---|---|\n");
+            for (name, loads, total) in totals {
+                let _ = writeln!(
+                    out,
+                    "| {} | {loads} | {} |",
+                    name.replace('|', "\\|"),
+                    clock(total)
+                );
+            }
+        }
+        out
+    }
+
+    /// Write the report, with `stats` after the presets (#2241), to `path` and
+    /// remember the outcome for the panel.
+    pub fn export(&mut self, path: &Path, stats: &SessionStats) -> Result<(), String> {
+        let report = self.report() + &stats.report();
+        let result = std::fs::write(path, report).map_err(|e| e.to_string());
+        self.last_export = Some(result.clone().map(|_| path.to_path_buf()));
+        result
+    }
+}
+
+/// `h:mm:ss`.
+fn clock(d: Duration) -> String {
+    let s = d.as_secs();
+    format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    #[test]
+    fn reloads_are_skipped_and_recent_is_distinct() {
+        let mut h = SetHistory::default();
+        h.push(secs(0), HistoryKind::Preset, "Aurora");
+        h.push(secs(5), HistoryKind::Preset, "Aurora");
+        h.push(secs(10), HistoryKind::Effect { layer: 0 }, "Tide");
+        h.push(secs(20), HistoryKind::Preset, "Ember");
+        h.push(secs(30), HistoryKind::Preset, "Aurora");
+        h.push(secs(40), HistoryKind::Preset, "Nebula");
+        assert_eq!(h.entries().len(), 5);
+        assert_eq!(h.recent_presets(Some("Nebula"), 8), vec!["Aurora", "Ember"]);
+        assert_eq!(h.recent_presets(None, 1), vec!["Nebula"]);
+    }
+
+    #[test]
+    fn report_times_each_preset_until_the_next() {
+        let mut h = SetHistory::default();
+        h.push(secs(0), HistoryKind::Preset, "Aurora");
+        h.push(secs(60), HistoryKind::Effect { layer: 1 }, "Tide");
+        h.push(secs(90), HistoryKind::Preset, "Ember");
+        h.push(secs(150), HistoryKind::Preset, "Aurora");
+        let report = h.report_until(secs(3700));
+
+        assert!(report.contains("Length 1:01:40"), "{report}");
+        assert!(
+            report.contains("| 0:00:00 | Preset | Aurora | 0:01:30 |"),
+            "{report}"
+        );
+        assert!(
+            report.contains("| 0:01:00 | Effect, layer 2 | Tide |  |"),
+            "{report}"
+        );
+        assert!(
+            report.contains("| 0:02:30 | Preset | Aurora | 0:59:10 |"),
+            "{report}"
+        );
+        assert!(report.contains("| Aurora | 2 | 1:00:40 |"), "{report}");
+        assert!(report.contains("| Ember | 1 | 0:01:00 |"), "{report}");
+    }
+}
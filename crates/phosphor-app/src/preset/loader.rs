@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 
 use crossbeam_channel::{Receiver, Sender, TryRecvError, bounded};
 
+use crate::media::cache::MediaCache;
 use crate::media::decoder::MediaSource;
 use crate::preset::Preset;
 
@@ -19,7 +21,7 @@ pub struct PresetDecodeRequest {
 
 /// Result of decoding a single media file.
 pub enum MediaDecodeResult {
-    Ok(MediaSource),
+    Ok(Arc<MediaSource>),
     Err(String),
 }
 
@@ -52,14 +54,15 @@ pub struct PresetLoader {
 }
 
 impl PresetLoader {
-    pub fn new() -> Self {
+    /// Media is decoded through `cache`: hits skip the decode, misses fill it.
+    pub fn new(cache: MediaCache) -> Self {
         let (request_tx, request_rx) = bounded::<PresetDecodeRequest>(1);
         let (result_tx, result_rx) = bounded::<PresetDecodeResult>(1);
 
         let handle = thread::Builder::new()
             .name("phosphor-preset-loader".into())
             .spawn(move || {
-                Self::decode_thread(request_rx, result_tx);
+                Self::decode_thread(request_rx, result_tx, cache);
             })
             .expect("failed to spawn preset loader thread");
 
@@ -141,6 +144,7 @@ impl PresetLoader {
     fn decode_thread(
         request_rx: Receiver<PresetDecodeRequest>,
         result_tx: Sender<PresetDecodeResult>,
+        cache: MediaCache,
     ) {
         loop {
             // Block waiting for next request
@@ -183,7 +187,7 @@ impl PresetLoader {
 
                     let (layer_idx, ref path) = request.media_jobs[job_idx];
                     log::info!("Decoding media for layer {}: {}", layer_idx, path.display());
-                    let result = match crate::media::decoder::load_media(path) {
+                    let result = match cache.load(path) {
                         Ok(source) => MediaDecodeResult::Ok(source),
                         Err(e) => {
                             log::warn!(
@@ -237,13 +241,13 @@ mod tests {
 
     #[test]
     fn loading_state_starts_idle() {
-        let loader = PresetLoader::new();
+        let loader = PresetLoader::new(MediaCache::default());
         assert!(matches!(loader.state, PresetLoadingState::Idle));
     }
 
     #[test]
     fn generation_increments() {
-        let mut loader = PresetLoader::new();
+        let mut loader = PresetLoader::new(MediaCache::default());
         assert_eq!(loader.generation, 0);
 
         let preset = Preset {
@@ -251,6 +255,8 @@ mod tests {
             active_layer: 0,
             postprocess: Default::default(),
             volumetric: None,
+            master: None,
+            id: 0,
         };
 
         loader.request_load(0, preset.clone(), vec![], "Test".into());
@@ -262,12 +268,14 @@ mod tests {
 
     #[test]
     fn request_sets_loading_state() {
-        let mut loader = PresetLoader::new();
+        let mut loader = PresetLoader::new(MediaCache::default());
         let preset = Preset {
             layers: vec![],
             active_layer: 0,
             postprocess: Default::default(),
             volumetric: None,
+            master: None,
+            id: 0,
         };
 
         loader.request_load(3, preset, vec![], "My Preset".into());
@@ -286,12 +294,14 @@ mod tests {
 
     #[test]
     fn empty_media_jobs_returns_result() {
-        let mut loader = PresetLoader::new();
+        let mut loader = PresetLoader::new(MediaCache::default());
         let preset = Preset {
             layers: vec![],
             active_layer: 0,
             postprocess: Default::default(),
             volumetric: None,
+            master: None,
+            id: 0,
         };
 
         loader.request_load(0, preset, vec![], "Empty".into());
@@ -309,12 +319,14 @@ mod tests {
 
     #[test]
     fn missing_file_returns_error() {
-        let mut loader = PresetLoader::new();
+        let mut loader = PresetLoader::new(MediaCache::default());
         let preset = Preset {
             layers: vec![],
             active_layer: 0,
             postprocess: Default::default(),
             volumetric: None,
+            master: None,
+            id: 0,
         };
 
         let jobs = vec![(0, PathBuf::from("/nonexistent/fake_image.png"))];
@@ -334,12 +346,14 @@ mod tests {
 
     #[test]
     fn stale_result_discarded() {
-        let mut loader = PresetLoader::new();
+        let mut loader = PresetLoader::new(MediaCache::default());
         let preset = Preset {
             layers: vec![],
             active_layer: 0,
             postprocess: Default::default(),
             volumetric: None,
+            master: None,
+            id: 0,
         };
 
         // Send first request
@@ -1,4 +1,10 @@
+pub mod attract;
+pub mod crossfade;
+pub mod history;
 pub mod loader;
+pub mod session;
 pub mod store;
+pub mod surprise;
 
-pub use store::{ParticleSimPreset, Preset, PresetStore, VolumetricPreset};
+pub use history::SetHistory;
+pub use store::{ParticleSimPreset, Preset, PresetRef, PresetStore, VolumetricPreset};
@@ -0,0 +1,59 @@
+//! The live stack, kept on disk for the "last session" startup mode (#2232).
+//! Rewritten every few seconds while it changes, so after a power cut an
+//! installation comes back to within seconds of where it was.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::Preset;
+
+/// How often the session is checked for changes and saved.
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// Every layer, postprocess and master, as a preset.
+    pub preset: Preset,
+    /// Id of the saved preset the stack came from.
+    #[serde(default)]
+    pub current: Option<u64>,
+    /// The stack had unsaved edits on top of `current`.
+    #[serde(default)]
+    pub dirty: bool,
+}
+
+pub fn path() -> PathBuf {
+    crate::paths::config_dir().join("session.json")
+}
+
+/// The saved session, if there is a readable one.
+pub fn load() -> Option<Session> {
+    let json = std::fs::read_to_string(path()).ok()?;
+    serde_json::from_str(&json)
+        .inspect_err(|e| log::warn!("Ignoring unreadable session.json: {e}"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_roundtrips_and_tolerates_missing_fields() {
+        let session = Session {
+            preset: serde_json::from_str(r#"{"layers":[]}"#).unwrap(),
+            current: Some(42),
+            dirty: true,
+        };
+        let json = serde_json::to_string(&session).unwrap();
+        let back: Session = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.current, Some(42));
+        assert!(back.dirty);
+
+        let bare: Session = serde_json::from_str(r#"{"preset":{"layers":[]}}"#).unwrap();
+        assert_eq!(bare.current, None);
+        assert!(!bare.dirty);
+    }
+}
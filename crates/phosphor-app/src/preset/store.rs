@@ -4,14 +4,20 @@ use std::path::PathBuf;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
-use crate::effect::format::PostProcessDef;
+use crate::effect::format::{AudioMapping, PostProcessDef};
+use crate::gpu::envelope::OpacityEnvelope;
+use crate::gpu::generator::GeneratorDef;
 use crate::gpu::lattice::LatticeParams;
 use crate::gpu::layer::BlendMode;
+use crate::gpu::master::MasterParams;
 use crate::gpu::particle::types::{
     default_drag, default_emit_rate, default_initial_size, default_initial_speed, default_lifetime,
 };
 use crate::gpu::volumetric::VolumetricParams;
-use crate::params::ParamValue;
+use crate::media::playlist::PlaylistDef;
+use crate::media::types::{AudioDrive, MediaProjection, WebcamMode};
+use crate::params::{ParamAutomation, ParamValue, StepSequencer};
+use crate::schema::Schema;
 
 // Embedded built-in presets
 const BUILTIN_CRUCIBLE: &str = include_str!("../../../../assets/presets/Crucible.json");
@@ -47,8 +53,20 @@ pub struct LayerPreset {
     pub media_speed: Option<f32>,
     #[serde(default)]
     pub media_looping: Option<bool>,
+    /// Audio-driven playback (#2248). `None` = plays on its own.
+    #[serde(default)]
+    pub media_audio: Option<AudioDrive>,
+    /// 360° framing of a media layer. `None` = flat.
+    #[serde(default)]
+    pub media_projection: Option<MediaProjection>,
+    /// Clip reel for a media layer; `media_path` is the clip that was on screen.
+    #[serde(default)]
+    pub media_playlist: Option<PlaylistDef>,
     #[serde(default)]
     pub webcam_device: Option<String>,
+    /// Requested capture mode for a webcam layer. `None` keeps the global choice.
+    #[serde(default)]
+    pub webcam_mode: Option<WebcamMode>,
     /// Absolute path to video file used as particle image source.
     #[serde(default)]
     pub particle_video_path: Option<String>,
@@ -59,6 +77,9 @@ pub struct LayerPreset {
     /// True if particle source is webcam.
     #[serde(default)]
     pub particle_webcam: Option<bool>,
+    /// Layer whose live output feeds the particle emitter (#2155).
+    #[serde(default)]
+    pub particle_layer_source: Option<usize>,
     /// Absolute path to static image used as particle source.
     #[serde(default)]
     pub particle_image_path: Option<String>,
@@ -85,6 +106,10 @@ pub struct LayerPreset {
     /// True if obstacle source is depth estimation.
     #[serde(default)]
     pub obstacle_depth: Option<bool>,
+    /// Layer index whose brightness is the obstacle (#2160). Presets store
+    /// the whole stack, so the index round-trips with it.
+    #[serde(default)]
+    pub obstacle_layer: Option<usize>,
     /// Live Lattice (3D CA) tunables captured from the contextual panel; `None`
     /// for non-lattice effects and old presets. Includes the embedded `render`
     /// camera/palette look, so a Lattice effect round-trips fully.
@@ -94,19 +119,102 @@ pub struct LayerPreset {
     /// `None` for old presets; restored over the `.pfx` defaults on load.
     #[serde(default)]
     pub particle_sim: Option<ParticleSimPreset>,
+    /// Built-in generator layer settings (#2168); `None` for effect and
+    /// media layers. Takes precedence over `effect_name` on load.
+    #[serde(default)]
+    pub generator: Option<GeneratorDef>,
+    /// Layer trail persistence (#2173); 0 (and old presets) = off.
+    #[serde(default)]
+    pub trail: f32,
+    /// Layer ignores the audio (#2206); old presets react as before.
+    #[serde(default)]
+    pub audio_muted: bool,
+    /// Recorded param automation (#2183); `None` when the layer has none.
+    #[serde(default)]
+    pub automation: Option<ParamAutomation>,
+    /// Step sequencer patterns (#2184); `None` when the layer has none.
+    #[serde(default)]
+    pub sequencer: Option<StepSequencer>,
+    /// Beat-synced opacity envelope (#2249); `None` when it's off.
+    #[serde(default)]
+    pub envelope: Option<OpacityEnvelope>,
+    /// Audio mappings edited for this preset (#2186); `None` uses the
+    /// effect's own.
+    #[serde(default)]
+    pub audio_mappings: Option<Vec<AudioMapping>>,
+}
+
+impl LayerPreset {
+    /// A layer showing `effect_name` with its defaults, as a newly added
+    /// layer would (#2251).
+    pub fn for_effect(effect_name: &str) -> Self {
+        Self {
+            effect_name: effect_name.to_string(),
+            params: HashMap::new(),
+            blend_mode: BlendMode::Normal,
+            opacity: default_opacity(),
+            enabled: true,
+            locked: false,
+            pinned: false,
+            custom_name: None,
+            media_path: None,
+            media_speed: None,
+            media_looping: None,
+            media_audio: None,
+            media_projection: None,
+            media_playlist: None,
+            webcam_device: None,
+            webcam_mode: None,
+            particle_video_path: None,
+            particle_video_speed: None,
+            particle_video_looping: None,
+            particle_webcam: None,
+            particle_layer_source: None,
+            particle_image_path: None,
+            splat_scene_path: None,
+            obstacle_image_path: None,
+            obstacle_mode: None,
+            obstacle_fit: None,
+            obstacle_threshold: None,
+            obstacle_elasticity: None,
+            obstacle_depth: None,
+            obstacle_layer: None,
+            lattice: None,
+            particle_sim: None,
+            generator: None,
+            trail: 0.0,
+            audio_muted: false,
+            automation: None,
+            sequencer: None,
+            envelope: None,
+            audio_mappings: None,
+        }
+    }
 }
 
-/// The six particle-sim knobs exposed by the contextual particle panel, grouped
+/// The particle-sim knobs exposed by the contextual particle panel, grouped
 /// so they round-trip through the preset independent of the effect's `.pfx`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ParticleSimPreset {
+    /// Particle count (#2156); 0 = whatever the particle quality setting builds.
+    pub count: u32,
     pub emit_rate: f32,
     pub burst_on_beat: u32,
     pub lifetime: f32,
     pub initial_speed: f32,
     pub initial_size: f32,
     pub drag: f32,
+    /// Trail length / width / fade (#2159); `None` = the effect's `.pfx`
+    /// value, so older presets keep whatever trails the effect ships with.
+    pub trail_length: Option<u32>,
+    pub trail_width: Option<f32>,
+    pub trail_fade: Option<f32>,
+    /// `ParticleBurst` trigger size and beat quantize (#2162); `None` = `.pfx`.
+    pub trigger_burst: Option<u32>,
+    pub trigger_burst_quantize: Option<bool>,
+    /// Alpha depth sort (#2163); `None` = `.pfx`.
+    pub depth_sort: Option<bool>,
 }
 
 impl Default for ParticleSimPreset {
@@ -114,12 +222,19 @@ impl Default for ParticleSimPreset {
         // Mirror ParticleDef's serde defaults so a partial/hand-edited block
         // never yields a dead emitter.
         Self {
+            count: 0,
             emit_rate: default_emit_rate(),
             burst_on_beat: 0,
             lifetime: default_lifetime(),
             initial_speed: default_initial_speed(),
             initial_size: default_initial_size(),
             drag: default_drag(),
+            trail_length: None,
+            trail_width: None,
+            trail_fade: None,
+            trigger_burst: None,
+            trigger_burst_quantize: None,
+            depth_sort: None,
         }
     }
 }
@@ -141,6 +256,13 @@ fn default_true() -> bool {
     true
 }
 
+/// Preset file layout history (#2202). The version is written beside the
+/// preset's fields rather than kept on `Preset`.
+pub const SCHEMA: Schema = Schema {
+    name: "preset",
+    migrations: &[],
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Preset {
     pub layers: Vec<LayerPreset>,
@@ -152,6 +274,37 @@ pub struct Preset {
     /// per-layer property. `None` for old presets.
     #[serde(default)]
     pub volumetric: Option<VolumetricPreset>,
+    /// Output master grade (#2165), present only when the preset opted in;
+    /// otherwise loading it keeps the global master from settings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub master: Option<MasterParams>,
+    /// Stable id (#2222) that direct key and MIDI bindings point at, so they
+    /// survive a rename. Files written before it load as `0`; `scan` fills it in.
+    #[serde(default)]
+    pub id: u64,
+}
+
+/// Ids stay below 2^53 so the web remote's JavaScript numbers hold them exactly.
+const ID_MASK: u64 = (1 << 53) - 1;
+
+/// FNV-1a of the lowercased name: the id a preset gets the first time it's seen,
+/// the same on every launch until it has one written down.
+fn name_hash(name: &str) -> u64 {
+    let hash = name
+        .to_lowercase()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |h, b| {
+            (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        });
+    hash & ID_MASK
+}
+
+/// How a message or a saved show points at a preset (#2223). Ids survive renames
+/// and other presets coming and going; indices are kept for older clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetRef {
+    Index(usize),
+    Id(u64),
 }
 
 #[derive(Default)]
@@ -161,6 +314,8 @@ pub struct PresetStore {
     pub dirty: bool,
     /// Number of built-in presets at the start of the `presets` vec.
     pub builtin_count: usize,
+    /// Presets and effects loaded this session (#2220).
+    pub history: super::SetHistory,
 }
 
 impl PresetStore {
@@ -188,8 +343,7 @@ impl PresetStore {
     }
 
     pub fn presets_dir() -> PathBuf {
-        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-        config_dir.join("phosphor").join("presets")
+        crate::paths::config_dir().join("presets")
     }
 
     /// Returns the set of built-in preset names (lowercase) for shadow detection.
@@ -206,7 +360,7 @@ impl PresetStore {
 
         // 1. Parse embedded built-in presets
         for &(name, json) in BUILTIN_PRESETS {
-            match serde_json::from_str::<Preset>(json) {
+            match SCHEMA.parse::<Preset>(json) {
                 Ok(preset) => {
                     self.presets.push((name.to_string(), preset));
                     self.builtin_count += 1;
@@ -247,7 +401,8 @@ impl PresetStore {
                 .and_then(|s| s.to_str())
                 .unwrap_or("")
                 .to_string();
-            if name.is_empty() {
+            // Binding sidecars (`<preset>.bindings.json`) share the folder
+            if name.is_empty() || name.ends_with(".bindings") {
                 continue;
             }
             // Skip user presets that shadow built-in names
@@ -255,18 +410,8 @@ impl PresetStore {
                 log::info!("Skipping user preset '{}' (shadows built-in)", name);
                 continue;
             }
-            match std::fs::read_to_string(&path) {
-                Ok(contents) => match serde_json::from_str::<Preset>(&contents) {
-                    Ok(preset) => {
-                        user_presets.push((name, preset));
-                    }
-                    Err(e) => {
-                        log::warn!("Failed to parse preset {}: {e}", path.display());
-                    }
-                },
-                Err(e) => {
-                    log::warn!("Failed to read preset {}: {e}", path.display());
-                }
+            if let Some(preset) = SCHEMA.load::<Preset>(&path) {
+                user_presets.push((name, preset));
             }
         }
 
@@ -274,6 +419,15 @@ impl PresetStore {
         user_presets.sort_by(|a, b| a.0.cmp(&b.0));
         self.presets.extend(user_presets);
 
+        // Give presets without an id (built-ins, older files, hand-copied
+        // duplicates) one of their own.
+        for i in 0..self.presets.len() {
+            let id = self.presets[i].1.id;
+            if id == 0 || id > ID_MASK || self.presets[..i].iter().any(|(_, p)| p.id == id) {
+                self.presets[i].1.id = self.fresh_id(&self.presets[i].0);
+            }
+        }
+
         self.current_preset = None;
         self.dirty = false;
 
@@ -287,6 +441,52 @@ impl PresetStore {
         );
     }
 
+    /// Put a user preset where a re-scan would find it, replacing one of the
+    /// same name, without waiting for its queued write to land (#2201).
+    fn insert_user(&mut self, name: String, preset: Preset) -> usize {
+        let start = self.builtin_count;
+        match self.presets[start..].binary_search_by(|(n, _)| n.as_str().cmp(&name)) {
+            Ok(i) => {
+                self.presets[start + i].1 = preset;
+                start + i
+            }
+            Err(i) => {
+                self.presets.insert(start + i, (name, preset));
+                start + i
+            }
+        }
+    }
+
+    /// Index of the preset with this id (#2222).
+    pub fn index_of_id(&self, id: u64) -> Option<usize> {
+        self.presets.iter().position(|(_, p)| p.id == id)
+    }
+
+    /// An id no preset has yet, starting from the name's hash.
+    fn fresh_id(&self, name: &str) -> u64 {
+        let mut id = name_hash(name).max(1);
+        while self.presets.iter().any(|(_, p)| p.id == id) {
+            id = ((id + 1) & ID_MASK).max(1);
+        }
+        id
+    }
+
+    /// Index of the preset a reference points at, if it's still there.
+    pub fn resolve(&self, preset: PresetRef) -> Option<usize> {
+        match preset {
+            PresetRef::Index(i) => (i < self.presets.len()).then_some(i),
+            PresetRef::Id(id) => self.index_of_id(id),
+        }
+    }
+
+    /// The id a save under `name` keeps: the one already there, or a new one.
+    fn id_for(&self, name: &str) -> u64 {
+        self.presets
+            .iter()
+            .find(|(n, _)| n == name)
+            .map_or_else(|| self.fresh_id(name), |(_, p)| p.id)
+    }
+
     fn sanitize_name(name: &str) -> String {
         let sanitized: String = name
             .chars()
@@ -306,6 +506,9 @@ impl PresetStore {
         }
     }
 
+    /// Save a user preset and return its index. The file is queued to the
+    /// persist writer (#2201); a write that fails there is reported through
+    /// `persist::take_failures`, not here.
     pub fn save(
         &mut self,
         name: &str,
@@ -313,6 +516,7 @@ impl PresetStore {
         active_layer: usize,
         postprocess: &PostProcessDef,
         volumetric: Option<VolumetricPreset>,
+        master: Option<MasterParams>,
     ) -> Result<usize> {
         let name = Self::sanitize_name(name);
         if name.is_empty() {
@@ -325,28 +529,21 @@ impl PresetStore {
             anyhow::bail!("Cannot overwrite built-in preset '{}'", name);
         }
 
-        let dir = Self::presets_dir();
-        std::fs::create_dir_all(&dir)?;
-
         let preset = Preset {
             layers,
             active_layer,
             postprocess: postprocess.clone(),
             volumetric,
+            master,
+            id: self.id_for(&name),
         };
 
-        let path = dir.join(format!("{name}.json"));
-        let json = serde_json::to_string_pretty(&preset)?;
-        std::fs::write(&path, json)?;
-        log::info!("Saved preset '{}' to {}", name, path.display());
-
-        self.scan();
+        let path = Self::presets_dir().join(format!("{name}.json"));
+        let json = SCHEMA.to_json(&preset)?;
+        crate::persist::write(path, json);
+        log::info!("Saved preset '{}'", name);
 
-        let idx = self
-            .presets
-            .iter()
-            .position(|(n, _)| n == &name)
-            .unwrap_or(0);
+        let idx = self.insert_user(name, preset);
         self.current_preset = Some(idx);
         self.dirty = false;
         Ok(idx)
@@ -367,18 +564,17 @@ impl PresetStore {
             .get(index)
             .ok_or_else(|| anyhow::anyhow!("Invalid preset index"))?;
 
-        let path = Self::presets_dir().join(format!("{name}.json"));
-        if path.exists() {
-            std::fs::remove_file(&path)?;
-            log::info!("Deleted preset '{}'", name);
-        }
+        crate::persist::remove(Self::presets_dir().join(format!("{name}.json")));
+        log::info!("Deleted preset '{}'", name);
 
-        self.scan();
+        self.presets.remove(index);
+        self.current_preset = None;
+        self.dirty = false;
         Ok(())
     }
 
-    /// Copy a preset to disk as a user preset with the given name.
-    /// Returns the index of the new preset after re-scan.
+    /// Copy a preset to disk as a user preset with the given name, written
+    /// like [`Self::save`]. Returns the index of the new preset.
     pub fn copy_preset(&mut self, source_index: usize, new_name: &str) -> Result<usize> {
         let new_name = Self::sanitize_name(new_name);
         if new_name.is_empty() {
@@ -391,30 +587,74 @@ impl PresetStore {
             anyhow::bail!("Cannot overwrite built-in preset '{}'", new_name);
         }
 
-        let preset = self
+        let mut preset = self
             .presets
             .get(source_index)
             .ok_or_else(|| anyhow::anyhow!("Invalid source preset index"))?
             .1
             .clone();
+        preset.id = self.id_for(&new_name);
 
-        let dir = Self::presets_dir();
-        std::fs::create_dir_all(&dir)?;
-
-        let path = dir.join(format!("{new_name}.json"));
-        let json = serde_json::to_string_pretty(&preset)?;
-        std::fs::write(&path, json)?;
+        let path = Self::presets_dir().join(format!("{new_name}.json"));
+        let json = SCHEMA.to_json(&preset)?;
+        crate::persist::write(path, json);
         log::info!("Copied preset to '{}'", new_name);
 
-        self.scan();
+        let idx = self.insert_user(new_name, preset);
+        self.current_preset = Some(idx);
+        self.dirty = false;
+        Ok(idx)
+    }
 
-        let idx = self
+    /// Rename a user preset (#2222), keeping its id so key and MIDI bindings
+    /// still point at it, and moving its binding sidecar along. Returns the
+    /// new index.
+    pub fn rename(&mut self, index: usize, new_name: &str) -> Result<usize> {
+        if self.is_builtin(index) {
+            anyhow::bail!("Cannot rename built-in preset");
+        }
+        if index >= self.presets.len() {
+            anyhow::bail!("Invalid preset index");
+        }
+        let new_name = Self::sanitize_name(new_name);
+        if new_name.is_empty() {
+            anyhow::bail!("Preset name cannot be empty");
+        }
+        if Self::builtin_names().contains(&new_name.to_lowercase()) {
+            anyhow::bail!("Cannot overwrite built-in preset '{}'", new_name);
+        }
+        if self
             .presets
             .iter()
-            .position(|(n, _)| n == &new_name)
-            .unwrap_or(0);
-        self.current_preset = Some(idx);
-        self.dirty = false;
+            .enumerate()
+            .any(|(i, (n, _))| i != index && *n == new_name)
+        {
+            anyhow::bail!("A preset named '{}' already exists", new_name);
+        }
+        let current_id = self
+            .current_preset
+            .and_then(|i| self.presets.get(i))
+            .map(|(_, p)| p.id);
+        let (old_name, preset) = self.presets.remove(index);
+        let dir = Self::presets_dir();
+        // The old files go only once the new ones are written
+        crate::persist::replace(
+            dir.join(format!("{new_name}.json")),
+            SCHEMA.to_json(&preset)?,
+            dir.join(format!("{old_name}.json")),
+        );
+        let old_sidecar = dir.join(format!("{old_name}.bindings.json"));
+        if let Ok(bindings) = std::fs::read(&old_sidecar) {
+            crate::persist::replace(
+                dir.join(format!("{new_name}.bindings.json")),
+                bindings,
+                old_sidecar,
+            );
+        }
+        log::info!("Renamed preset '{}' to '{}'", old_name, new_name);
+
+        let idx = self.insert_user(new_name, preset);
+        self.current_preset = current_id.and_then(|id| self.index_of_id(id));
         Ok(idx)
     }
 }
@@ -476,6 +716,8 @@ mod tests {
             active_layer: 0,
             postprocess: PostProcessDef::default(),
             volumetric: None,
+            master: None,
+            id: 0,
         };
         s.presets.push(("Test Preset".into(), preset));
         s.current_preset = Some(0);
@@ -579,6 +821,19 @@ mod tests {
         assert!(lp.particle_video_path.is_none());
     }
 
+    #[test]
+    fn layer_preset_particle_layer_source_serde() {
+        let json = r#"{
+            "effect_name": "Raster",
+            "particle_layer_source": 2
+        }"#;
+        let lp: LayerPreset = serde_json::from_str(json).unwrap();
+        assert_eq!(lp.particle_layer_source, Some(2));
+
+        let old: LayerPreset = serde_json::from_str(r#"{"effect_name": "Raster"}"#).unwrap();
+        assert!(old.particle_layer_source.is_none());
+    }
+
     #[test]
     fn preset_serde_roundtrip() {
         let preset = Preset {
@@ -594,11 +849,16 @@ mod tests {
                 media_path: None,
                 media_speed: None,
                 media_looping: None,
+                media_audio: None,
+                media_projection: None,
+                media_playlist: None,
                 webcam_device: None,
+                webcam_mode: None,
                 particle_video_path: None,
                 particle_video_speed: None,
                 particle_video_looping: None,
                 particle_webcam: None,
+                particle_layer_source: None,
                 particle_image_path: None,
                 splat_scene_path: None,
                 obstacle_image_path: None,
@@ -607,12 +867,22 @@ mod tests {
                 obstacle_threshold: None,
                 obstacle_elasticity: None,
                 obstacle_depth: None,
+                obstacle_layer: None,
                 lattice: None,
                 particle_sim: None,
+                generator: None,
+                trail: 0.35,
+                audio_muted: true,
+                automation: None,
+                sequencer: None,
+                envelope: None,
+                audio_mappings: None,
             }],
             active_layer: 0,
             postprocess: PostProcessDef::default(),
             volumetric: None,
+            master: None,
+            id: 0,
         };
         let json = serde_json::to_string(&preset).unwrap();
         let p2: Preset = serde_json::from_str(&json).unwrap();
@@ -621,6 +891,8 @@ mod tests {
         assert_eq!(p2.layers[0].blend_mode, BlendMode::Add);
         assert!((p2.layers[0].opacity - 0.5).abs() < 1e-6);
         assert!(p2.layers[0].pinned);
+        assert!((p2.layers[0].trail - 0.35).abs() < 1e-6);
+        assert!(p2.layers[0].audio_muted);
     }
 
     #[test]
@@ -656,6 +928,20 @@ mod tests {
         assert!(lp.obstacle_threshold.is_none());
         assert!(lp.obstacle_elasticity.is_none());
         assert!(lp.obstacle_depth.is_none());
+        assert!(lp.obstacle_layer.is_none());
+    }
+
+    #[test]
+    fn layer_preset_obstacle_layer_serde() {
+        let json = r#"{
+            "effect_name": "Cascade",
+            "obstacle_layer": 2,
+            "obstacle_mode": 2
+        }"#;
+        let lp: LayerPreset = serde_json::from_str(json).unwrap();
+        assert_eq!(lp.obstacle_layer, Some(2));
+        let lp2: LayerPreset = serde_json::from_str(&serde_json::to_string(&lp).unwrap()).unwrap();
+        assert_eq!(lp2.obstacle_layer, Some(2));
     }
 
     #[test]
@@ -746,11 +1032,16 @@ mod tests {
             media_path: None,
             media_speed: None,
             media_looping: None,
+            media_audio: None,
+            media_projection: None,
+            media_playlist: None,
             webcam_device: None,
+            webcam_mode: None,
             particle_video_path: None,
             particle_video_speed: None,
             particle_video_looping: None,
             particle_webcam: None,
+            particle_layer_source: None,
             particle_image_path: None,
             splat_scene_path: None,
             obstacle_image_path: None,
@@ -759,8 +1050,16 @@ mod tests {
             obstacle_threshold: None,
             obstacle_elasticity: None,
             obstacle_depth: None,
+            obstacle_layer: None,
             lattice: Some(lat),
             particle_sim: None,
+            generator: None,
+            trail: 0.0,
+            audio_muted: false,
+            automation: None,
+            sequencer: None,
+            envelope: None,
+            audio_mappings: None,
         };
         let json = serde_json::to_string(&lp).unwrap();
         let lp2: LayerPreset = serde_json::from_str(&json).unwrap();
@@ -772,30 +1071,64 @@ mod tests {
         assert!((got.render.palette_hue - 0.25).abs() < 1e-6);
     }
 
+    #[test]
+    fn layer_preset_generator_roundtrip() {
+        use crate::gpu::generator::GeneratorKind;
+        let mut lp: LayerPreset = serde_json::from_str(r#"{"effect_name": ""}"#).unwrap();
+        assert!(lp.generator.is_none());
+        lp.generator = Some(GeneratorDef {
+            kind: GeneratorKind::Gradient,
+            rate: 0.5,
+            angle: 45.0,
+            ..Default::default()
+        });
+        let json = serde_json::to_string(&lp).unwrap();
+        assert!(json.contains("\"gradient\""));
+        let lp2: LayerPreset = serde_json::from_str(&json).unwrap();
+        assert_eq!(lp2.generator, lp.generator);
+    }
+
     #[test]
     fn particle_sim_preset_serde_and_defaults() {
         // Full round-trip.
         let sim = ParticleSimPreset {
+            count: 40_000,
             emit_rate: 250.0,
             burst_on_beat: 3,
             lifetime: 1.25,
             initial_speed: 0.7,
             initial_size: 0.03,
             drag: 0.9,
+            trail_length: Some(0),
+            trail_width: Some(0.01),
+            trail_fade: Some(3.0),
+            trigger_burst: Some(5000),
+            trigger_burst_quantize: Some(true),
+            depth_sort: Some(true),
         };
         let json = serde_json::to_string(&sim).unwrap();
         let sim2: ParticleSimPreset = serde_json::from_str(&json).unwrap();
         assert!((sim2.emit_rate - 250.0).abs() < 1e-6);
         assert_eq!(sim2.burst_on_beat, 3);
+        assert_eq!(sim2.count, 40_000);
         assert!((sim2.drag - 0.9).abs() < 1e-6);
+        assert_eq!(sim2.trail_length, Some(0));
+        assert_eq!(sim2.trail_fade, Some(3.0));
+        assert_eq!(sim2.trigger_burst, Some(5000));
+        assert_eq!(sim2.trigger_burst_quantize, Some(true));
+        assert_eq!(sim2.depth_sort, Some(true));
 
         // Partial block fills the rest from ParticleDef's serde defaults (proves
         // container #[serde(default)] — no dead emitter from a partial JSON).
         let partial: ParticleSimPreset = serde_json::from_str(r#"{ "lifetime": 5.0 }"#).unwrap();
         assert!((partial.lifetime - 5.0).abs() < 1e-6);
+        assert_eq!(partial.count, 0);
         assert!((partial.emit_rate - default_emit_rate()).abs() < 1e-6);
         assert!((partial.initial_speed - default_initial_speed()).abs() < 1e-6);
         assert!((partial.drag - default_drag()).abs() < 1e-6);
+        assert!(partial.trail_length.is_none());
+        assert!(partial.trigger_burst.is_none());
+        assert!(partial.depth_sort.is_none());
     }
 
     #[test]
@@ -819,6 +1152,8 @@ mod tests {
                 enabled: true,
                 params,
             }),
+            master: None,
+            id: 0,
         };
         let json = serde_json::to_string(&preset).unwrap();
         let p2: Preset = serde_json::from_str(&json).unwrap();
@@ -828,6 +1163,24 @@ mod tests {
         assert!((vol.params.cam_distance - 6.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn preset_master_is_optional_and_roundtrips() {
+        let json = r#"{ "layers": [], "active_layer": 0 }"#;
+        let p: Preset = serde_json::from_str(json).unwrap();
+        assert!(p.master.is_none());
+        // Not written at all when the preset didn't opt in.
+        assert!(!serde_json::to_string(&p).unwrap().contains("master"));
+
+        let mut graded = p.clone();
+        graded.master = Some(MasterParams {
+            contrast: 1.3,
+            ..Default::default()
+        });
+        let json = serde_json::to_string(&graded).unwrap();
+        let p2: Preset = serde_json::from_str(&json).unwrap();
+        assert_eq!(p2.master.map(|m| m.contrast), Some(1.3));
+    }
+
     #[test]
     fn volumetric_params_partial_serde_fills_defaults() {
         // A partial params block fills the rest from Default (container serde(default)).
@@ -947,6 +1300,8 @@ mod tests {
             active_layer: 0,
             postprocess: PostProcessDef::default(),
             volumetric: None,
+            master: None,
+            id: 0,
         };
         s.presets.push(("Crucible".into(), empty_preset.clone()));
         s.presets
@@ -959,6 +1314,82 @@ mod tests {
         assert!(!s.is_builtin(99));
     }
 
+    #[test]
+    fn insert_user_keeps_scan_order() {
+        let mut s = PresetStore::new();
+        s.builtin_count = 1;
+        let preset = |active_layer| Preset {
+            layers: vec![],
+            active_layer,
+            postprocess: PostProcessDef::default(),
+            volumetric: None,
+            master: None,
+            id: 0,
+        };
+        s.presets.push(("Zed".into(), preset(0)));
+        s.presets.push(("Beta".into(), preset(0)));
+
+        assert_eq!(s.insert_user("Delta".into(), preset(0)), 2);
+        assert_eq!(s.insert_user("Alpha".into(), preset(0)), 1);
+        assert_eq!(s.insert_user("Beta".into(), preset(3)), 2);
+        let names: Vec<&str> = s.presets.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["Zed", "Alpha", "Beta", "Delta"]);
+        assert_eq!(s.presets[2].1.active_layer, 3);
+    }
+
+    #[test]
+    fn ids_are_kept_by_name_and_never_shared() {
+        let mut s = PresetStore::new();
+        let preset = |id| Preset {
+            layers: vec![],
+            active_layer: 0,
+            postprocess: PostProcessDef::default(),
+            volumetric: None,
+            master: None,
+            id,
+        };
+        s.presets
+            .push(("Aurora".into(), preset(name_hash("Ember"))));
+        s.presets.push(("Tide".into(), preset(7)));
+
+        assert_eq!(s.id_for("Tide"), 7);
+        assert_eq!(s.index_of_id(7), Some(1));
+        // The hash of "Ember" is taken by Aurora's id, so Ember moves past it.
+        let ember = s.id_for("Ember");
+        assert_ne!(ember, name_hash("Ember"));
+        assert!(s.index_of_id(ember).is_none());
+        assert_eq!(name_hash("EMBER"), name_hash("ember"));
+        assert!(ember <= ID_MASK);
+
+        assert_eq!(s.resolve(PresetRef::Id(7)), Some(1));
+        assert_eq!(s.resolve(PresetRef::Index(0)), Some(0));
+        assert_eq!(s.resolve(PresetRef::Index(2)), None);
+        assert_eq!(s.resolve(PresetRef::Id(8)), None);
+    }
+
+    #[test]
+    fn rename_rejects_builtins_and_taken_names() {
+        let mut s = PresetStore::new();
+        s.builtin_count = 1;
+        let empty_preset = Preset {
+            layers: vec![],
+            active_layer: 0,
+            postprocess: PostProcessDef::default(),
+            volumetric: None,
+            master: None,
+            id: 0,
+        };
+        s.presets.push(("Crucible".into(), empty_preset.clone()));
+        s.presets.push(("Alpha".into(), empty_preset.clone()));
+        s.presets.push(("Beta".into(), empty_preset));
+
+        assert!(s.rename(0, "Anything").is_err());
+        assert!(s.rename(1, "Beta").is_err());
+        assert!(s.rename(1, "crucible").is_err());
+        assert!(s.rename(1, "  ").is_err());
+        assert_eq!(s.presets.len(), 3);
+    }
+
     #[test]
     fn delete_builtin_returns_error() {
         let mut s = PresetStore::new();
@@ -968,6 +1399,8 @@ mod tests {
             active_layer: 0,
             postprocess: PostProcessDef::default(),
             volumetric: None,
+            master: None,
+            id: 0,
         };
         s.presets.push(("Crucible".into(), empty_preset));
 
@@ -985,6 +1418,8 @@ mod tests {
             active_layer: 0,
             postprocess: PostProcessDef::default(),
             volumetric: None,
+            master: None,
+            id: 0,
         };
         s.presets.push(("Crucible".into(), empty_preset));
 
@@ -0,0 +1,256 @@
+//! Surprise me (#2251): a random but usable stack in one press. Each layer's
+//! effect is drawn from a pool of effect tags, its params are nudged around
+//! their defaults rather than thrown across the whole range, and the layers
+//! above the bottom one alternate lightening and contrast blend modes so
+//! none of them hides or blacks out what is beneath. Locked layers are kept
+//! as they are.
+
+use serde::{Deserialize, Serialize};
+
+use super::store::{LayerPreset, Preset};
+use crate::effect::format::PfxEffect;
+use crate::gpu::layer::BlendMode;
+use crate::params::{ParamDef, ParamValue};
+
+/// Saved with the settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SurpriseConfig {
+    /// Layers in the new stack; more when a locked layer sits higher.
+    pub layers: usize,
+    /// Tag each layer's effect is drawn from, in stack order (top layer
+    /// first, as the layer list shows them). A missing or empty entry, or a
+    /// tag no effect has, draws from every effect.
+    pub pools: Vec<String>,
+    /// How far params may stray from their defaults, as a share of their range.
+    pub spread: f32,
+}
+
+impl Default for SurpriseConfig {
+    fn default() -> Self {
+        Self {
+            layers: 3,
+            pools: Vec::new(),
+            spread: 0.3,
+        }
+    }
+}
+
+/// Modes that only add light, and modes that shape contrast; stacked layers
+/// take turns between them. Multiply and Subtract are left out, as over a
+/// dark effect they leave nothing to see.
+const LIGHTEN: [BlendMode; 2] = [BlendMode::Add, BlendMode::Screen];
+const CONTRAST: [BlendMode; 3] = [
+    BlendMode::Overlay,
+    BlendMode::HardLight,
+    BlendMode::Difference,
+];
+
+/// xorshift32, seeded from the clock by the caller.
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    /// Uniform in 0..1.
+    fn unit(&mut self) -> f32 {
+        (self.next() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.next() as usize % items.len()]
+    }
+}
+
+/// A new stack built over `current`, with effects from `effects` (already
+/// limited to the ones the GPU runs). `None` when there are no effects.
+pub fn surprise(
+    current: &Preset,
+    effects: &[&PfxEffect],
+    config: &SurpriseConfig,
+    seed: u32,
+) -> Option<Preset> {
+    if effects.is_empty() {
+        return None;
+    }
+    // xorshift32 must never be seeded with 0.
+    let mut rng = Rng(seed | 1);
+    let locked = |i: usize| current.layers.get(i).filter(|l| l.locked);
+    let count = config.layers.max(1).max(
+        current
+            .layers
+            .iter()
+            .rposition(|l| l.locked)
+            .map_or(0, |i| i + 1),
+    );
+
+    // Built bottom up, so each layer's blend mode can answer the one beneath
+    // it; index 0 is the top of the stack.
+    let mut layers: Vec<LayerPreset> = Vec::with_capacity(count);
+    let mut used: Vec<&str> = Vec::new();
+    for i in (0..count).rev() {
+        if let Some(layer) = locked(i) {
+            layers.push(layer.clone());
+            continue;
+        }
+        let pool: Vec<&PfxEffect> = match config.pools.get(i).filter(|t| !t.trim().is_empty()) {
+            Some(tag) => effects
+                .iter()
+                .copied()
+                .filter(|e| e.has_tag(tag.trim()))
+                .collect(),
+            None => Vec::new(),
+        };
+        let pool = if pool.is_empty() { effects } else { &pool[..] };
+        // Prefer effects not already in the stack
+        let fresh: Vec<&PfxEffect> = pool
+            .iter()
+            .copied()
+            .filter(|e| !used.contains(&e.name.as_str()))
+            .collect();
+        let effect = rng.pick(if fresh.is_empty() { pool } else { &fresh });
+        used.push(&effect.name);
+
+        let mut layer = LayerPreset::for_effect(&effect.name);
+        layer.params = effect
+            .inputs
+            .iter()
+            .map(|def| (def.name().to_string(), nudge(def, config.spread, &mut rng)))
+            .collect();
+        if let Some(below) = layers.last() {
+            layer.blend_mode = if LIGHTEN.contains(&below.blend_mode) {
+                rng.pick(&CONTRAST)
+            } else {
+                rng.pick(&LIGHTEN)
+            };
+            layer.opacity = 0.6 + 0.4 * rng.unit();
+        }
+        layers.push(layer);
+    }
+    layers.reverse();
+
+    let mut out = current.clone();
+    out.layers = layers;
+    if out.active_layer >= count {
+        out.active_layer = 0;
+    }
+    Some(out)
+}
+
+/// `def`'s default, moved by up to `spread` of its range and kept inside it.
+/// Colors and toggles keep their defaults.
+fn nudge(def: &ParamDef, spread: f32, rng: &mut Rng) -> ParamValue {
+    let mut step = |default: f32, min: f32, max: f32| {
+        let offset = (rng.unit() * 2.0 - 1.0) * spread * (max - min);
+        (default + offset).clamp(min.min(max), max.max(min))
+    };
+    match def {
+        ParamDef::Float {
+            default, min, max, ..
+        } => ParamValue::Float(step(*default, *min, *max)),
+        ParamDef::Point2D {
+            default, min, max, ..
+        } => ParamValue::Point2D([
+            step(default[0], min[0], max[0]),
+            step(default[1], min[1], max[1]),
+        ]),
+        other => other.default_value(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effect(name: &str, tags: &[&str]) -> PfxEffect {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "shader": "main.wgsl",
+            "tags": tags,
+            "inputs": [
+                { "type": "Float", "name": "speed", "default": 0.5, "min": 0.0, "max": 1.0 },
+                { "type": "Bool", "name": "mirror", "default": true },
+            ],
+        }))
+        .unwrap()
+    }
+
+    fn stack(layers: &[(&str, bool)]) -> Preset {
+        let mut preset: Preset =
+            serde_json::from_value(serde_json::json!({ "layers": [] })).unwrap();
+        preset.layers = layers
+            .iter()
+            .map(|(name, locked)| {
+                let mut layer = LayerPreset::for_effect(name);
+                layer.locked = *locked;
+                layer
+            })
+            .collect();
+        preset
+    }
+
+    #[test]
+    fn pools_pick_by_tag_and_params_stay_near_defaults() {
+        let bg = effect("Clouds", &["background"]);
+        let top = effect("Sparks", &["Accent"]);
+        let effects = [&bg, &top];
+        let config = SurpriseConfig {
+            layers: 2,
+            pools: vec!["accent".into(), "background".into()],
+            spread: 0.2,
+        };
+        for seed in 0..50 {
+            let out = surprise(&stack(&[]), &effects, &config, seed).unwrap();
+            assert_eq!(out.layers.len(), 2);
+            // Layer 0 is the top of the stack
+            assert_eq!(out.layers[0].effect_name, "Sparks");
+            assert_ne!(out.layers[0].blend_mode, BlendMode::Normal);
+            assert_eq!(out.layers[1].effect_name, "Clouds");
+            assert_eq!(out.layers[1].blend_mode, BlendMode::Normal);
+            for layer in &out.layers {
+                let ParamValue::Float(speed) = layer.params["speed"] else {
+                    panic!("speed is a float");
+                };
+                assert!((0.3..=0.7).contains(&speed), "speed {speed}");
+                assert!(matches!(layer.params["mirror"], ParamValue::Bool(true)));
+            }
+        }
+    }
+
+    #[test]
+    fn locked_layers_are_kept_in_place() {
+        let effects = [&effect("Clouds", &[]), &effect("Sparks", &[])];
+        let mut current = stack(&[("Tunnel", false), ("Rings", false), ("Mine", true)]);
+        current.layers[2].opacity = 0.25;
+        let config = SurpriseConfig {
+            layers: 1,
+            ..SurpriseConfig::default()
+        };
+        let out = surprise(&current, &effects, &config, 7).unwrap();
+        // The stack grows to reach the locked layer
+        assert_eq!(out.layers.len(), 3);
+        assert_ne!(out.layers[0].effect_name, "Tunnel");
+        assert_eq!(out.layers[2].effect_name, "Mine");
+        assert_eq!(out.layers[2].opacity, 0.25);
+    }
+
+    #[test]
+    fn unknown_tags_fall_back_to_every_effect() {
+        let effects = [&effect("Clouds", &[])];
+        let config = SurpriseConfig {
+            layers: 2,
+            pools: vec!["nothing-has-this".into()],
+            ..SurpriseConfig::default()
+        };
+        let out = surprise(&stack(&[]), &effects, &config, 3).unwrap();
+        assert!(out.layers.iter().all(|l| l.effect_name == "Clouds"));
+        assert!(surprise(&stack(&[]), &[], &config, 3).is_none());
+    }
+}
@@ -10,7 +10,8 @@ use crossbeam_channel::Receiver;
 
 use crate::audio::capture::RingBuffer;
 
-use super::types::{Container, RecordingConfig, VideoCodec};
+use super::stamp::{BeatLog, FrameStamp};
+use super::types::{ClipFormat, Container, RecordingConfig, VideoCodec};
 
 /// Frame data sent from the render thread to the encoder thread.
 pub struct VideoFrame {
@@ -19,6 +20,7 @@ pub struct VideoFrame {
     pub width: u32,
     #[allow(dead_code)]
     pub height: u32,
+    pub stamp: FrameStamp,
 }
 
 /// Audio source info for recording.
@@ -37,6 +39,10 @@ pub struct EncoderInfo {
     pub sw_h264: bool,
     pub sw_hevc: bool,
     pub sw_av1: bool,
+    /// prores_ks (#2251). ProRes has no hardware encoder.
+    pub prores: bool,
+    /// libvpx-vp9, for WebM loop exports. GIF needs no external encoder.
+    pub vp9: bool,
 }
 
 impl EncoderInfo {
@@ -46,6 +52,7 @@ impl EncoderInfo {
             VideoCodec::H264 => self.hw_h264,
             VideoCodec::Hevc => self.hw_hevc,
             VideoCodec::AV1 => self.hw_av1,
+            VideoCodec::ProRes => false,
         }
     }
 
@@ -55,6 +62,7 @@ impl EncoderInfo {
             VideoCodec::H264 => self.hw_h264 || self.sw_h264,
             VideoCodec::Hevc => self.hw_hevc || self.sw_hevc,
             VideoCodec::AV1 => self.hw_av1 || self.sw_av1,
+            VideoCodec::ProRes => self.prores,
         }
     }
 
@@ -68,6 +76,7 @@ impl EncoderInfo {
                 VideoCodec::H264 => self.sw_h264,
                 VideoCodec::Hevc => self.sw_hevc,
                 VideoCodec::AV1 => self.sw_av1,
+                VideoCodec::ProRes => self.prores,
             };
             if has_sw {
                 Some(codec.sw_encoder())
@@ -80,6 +89,14 @@ impl EncoderInfo {
         }
     }
 
+    /// Whether a loop can be exported in the given format.
+    pub fn has_clip(&self, format: ClipFormat) -> bool {
+        match format {
+            ClipFormat::Gif => self.ffmpeg_found,
+            ClipFormat::WebM => self.vp9,
+        }
+    }
+
     /// Display label for a codec's encoder status.
     pub fn encoder_label(&self, codec: VideoCodec) -> &'static str {
         if self.has_hw(codec) {
@@ -89,6 +106,7 @@ impl EncoderInfo {
                 VideoCodec::H264 => self.sw_h264,
                 VideoCodec::Hevc => self.sw_hevc,
                 VideoCodec::AV1 => self.sw_av1,
+                VideoCodec::ProRes => self.prores,
             };
             if has_sw { "SW" } else { "N/A" }
         }
@@ -144,16 +162,24 @@ pub fn probe_encoders() -> EncoderInfo {
         if trimmed.contains("libsvtav1") {
             info.sw_av1 = true;
         }
+        if trimmed.contains("libvpx-vp9") {
+            info.vp9 = true;
+        }
+        if trimmed.contains("prores_ks") {
+            info.prores = true;
+        }
     }
 
     log::info!(
-        "FFmpeg encoders: h264(hw={},sw={}) hevc(hw={},sw={}) av1(hw={},sw={})",
+        "FFmpeg encoders: h264(hw={},sw={}) hevc(hw={},sw={}) av1(hw={},sw={}) prores={} vp9={}",
         info.hw_h264,
         info.sw_h264,
         info.hw_hevc,
         info.sw_hevc,
         info.hw_av1,
-        info.sw_av1
+        info.sw_av1,
+        info.prores,
+        info.vp9
     );
 
     info
@@ -161,11 +187,38 @@ pub fn probe_encoders() -> EncoderInfo {
 
 /// Build the output file path for a new recording.
 pub fn build_output_path(config: &RecordingConfig) -> PathBuf {
+    timestamped_path(config, "phosphor", config.output_container().extension())
+}
+
+/// Build the output file path for a loop export.
+pub fn build_clip_output_path(config: &RecordingConfig) -> PathBuf {
+    timestamped_path(config, "phosphor_loop", config.clip_format.extension())
+}
+
+/// Build the output file path for an analysis snapshot (#2215).
+pub fn build_snapshot_path(config: &RecordingConfig) -> PathBuf {
+    timestamped_path(
+        config,
+        "phosphor_analysis",
+        crate::audio::snapshot::EXTENSION,
+    )
+}
+
+/// Where the set report (#2220) goes: beside the recordings, as markdown.
+pub fn build_set_report_path(config: &RecordingConfig) -> PathBuf {
+    timestamped_path(config, "phosphor_set", "md")
+}
+
+fn timestamped_path(config: &RecordingConfig, prefix: &str, extension: &str) -> PathBuf {
     // Ensure output directory exists
     if let Err(e) = std::fs::create_dir_all(&config.output_dir) {
         log::error!("Failed to create output dir: {e}");
     }
+    timestamped_path_in(&config.output_dir, prefix, extension)
+}
 
+/// `dir/prefix_<local time>.extension`, for outputs kept elsewhere (#2252).
+pub fn timestamped_path_in(dir: &Path, prefix: &str, extension: &str) -> PathBuf {
     // Format timestamp without chrono dependency
     let now = std::time::SystemTime::now();
     let secs = now
@@ -173,8 +226,8 @@ pub fn build_output_path(config: &RecordingConfig) -> PathBuf {
         .unwrap_or_default()
         .as_secs();
     let timestamp = format_local_time(secs);
-    let filename = format!("phosphor_{}.{}", timestamp, config.container.extension());
-    config.output_dir.join(filename)
+    let filename = format!("{prefix}_{timestamp}.{extension}");
+    dir.join(filename)
 }
 
 fn format_local_time(unix_secs: u64) -> String {
@@ -192,6 +245,27 @@ fn format_local_time(unix_secs: u64) -> String {
     format!("{unix_secs}")
 }
 
+/// SMPTE start timecode (`HH:MM:SS:FF`) for a recording starting at
+/// `start`, in local time of day, so clips from one night line up on an
+/// editor's timeline by when they were shot.
+fn start_timecode(start: Duration, fps: u32) -> Option<String> {
+    let output = Command::new("date")
+        .args(["-d", &format!("@{}", start.as_secs()), "+%H:%M:%S"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let hms = String::from_utf8_lossy(&output.stdout);
+    Some(smpte_timecode(hms.trim(), start.subsec_nanos(), fps))
+}
+
+/// `HH:MM:SS` plus the frame the sub-second part falls on.
+fn smpte_timecode(hms: &str, subsec_nanos: u32, fps: u32) -> String {
+    let frame = u64::from(subsec_nanos) * u64::from(fps.max(1)) / 1_000_000_000;
+    format!("{hms}:{frame:02}")
+}
+
 /// Create a named FIFO (pipe) at the given path. Returns the path.
 /// On Linux/macOS this uses mkfifo. The FIFO is cleaned up by the caller.
 pub fn create_audio_fifo() -> Result<PathBuf, String> {
@@ -275,19 +349,44 @@ pub fn spawn_ffmpeg(
         cmd.args(["-crf", &config.quality.to_string(), "-preset", "6"]);
     }
 
-    // Output pixel format
-    cmd.args(["-pix_fmt", "yuv420p"]);
+    // Output pixel format. ProRes 422 HQ (profile 3) is 10-bit 4:2:2, with
+    // PCM audio as editors expect next to it.
+    let prores = encoder_name == "prores_ks";
+    if prores {
+        cmd.args([
+            "-profile:v",
+            "3",
+            "-vendor",
+            "apl0",
+            "-pix_fmt",
+            "yuv422p10le",
+        ]);
+    } else {
+        cmd.args(["-pix_fmt", "yuv420p"]);
+    }
 
     // Audio encoder (if audio input present)
     if audio_fifo.is_some() {
-        cmd.args(["-c:a", "aac", "-b:a", "192k"]);
+        if prores {
+            cmd.args(["-c:a", "pcm_s24le"]);
+        } else {
+            cmd.args(["-c:a", "aac", "-b:a", "192k"]);
+        }
     }
 
     // Container-specific
-    if config.container == Container::Mp4 {
+    if matches!(config.output_container(), Container::Mp4 | Container::Mov) {
         cmd.args(["-movflags", "+faststart"]);
     }
 
+    // Start timecode (#2211); the beat markers go in a sidecar next to the file
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    if let Some(timecode) = start_timecode(now, config.fps) {
+        cmd.args(["-timecode", &timecode]);
+    }
+
     // Shortest: stop when video stops (audio FIFO may have a slight delay)
     if audio_fifo.is_some() {
         cmd.args(["-shortest"]);
@@ -314,24 +413,113 @@ pub fn spawn_ffmpeg(
         .map_err(|e| format!("Failed to spawn ffmpeg: {e}"))
 }
 
+/// ffmpeg filter graph and codec args for a loop export. GIF builds a palette from the
+/// whole clip first (`palettegen` → `paletteuse`) — a fixed 256-colour palette bands every
+/// gradient — and `diff_mode=rectangle` re-encodes only the part of each frame that changed,
+/// which is most of the size win on slow-moving looks.
+pub fn clip_encode_args(format: ClipFormat, clip_width: u32) -> Vec<String> {
+    // -2 keeps the height even, which yuv420p requires.
+    let scale = format!("scale={clip_width}:-2:flags=lanczos");
+    match format {
+        ClipFormat::Gif => vec![
+            "-vf".into(),
+            format!(
+                "fps=20,{scale},split[a][b];[a]palettegen=stats_mode=diff[p];\
+                 [b][p]paletteuse=dither=bayer:bayer_scale=5:diff_mode=rectangle"
+            ),
+            "-loop".into(),
+            "0".into(),
+        ],
+        ClipFormat::WebM => vec![
+            "-vf".into(),
+            scale,
+            "-c:v".into(),
+            "libvpx-vp9".into(),
+            "-crf".into(),
+            "33".into(),
+            "-b:v".into(),
+            "0".into(),
+            "-row-mt".into(),
+            "1".into(),
+            "-deadline".into(),
+            "good".into(),
+            "-cpu-used".into(),
+            "4".into(),
+            "-pix_fmt".into(),
+            "yuv420p".into(),
+        ],
+    }
+}
+
+/// Spawn ffmpeg for a loop export: same raw BGRA stdin as a recording, no audio
+/// (GIF has none, and a shared clip loops where the song doesn't).
+pub fn spawn_ffmpeg_clip(
+    config: &RecordingConfig,
+    width: u32,
+    height: u32,
+    output_path: &Path,
+) -> Result<Child, String> {
+    let mut cmd = Command::new("ffmpeg");
+    cmd.args(["-y", "-hide_banner"]);
+    cmd.args([
+        "-f",
+        "rawvideo",
+        "-pix_fmt",
+        "bgra",
+        "-s",
+        &format!("{width}x{height}"),
+        "-r",
+        &config.fps.to_string(),
+        "-i",
+        "pipe:0",
+    ]);
+    cmd.args(clip_encode_args(config.clip_format, config.clip_width));
+    cmd.arg("-an");
+    cmd.arg(output_path.as_os_str());
+
+    cmd.stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    log::info!(
+        "Spawning ffmpeg loop export: {} {}s {}x{} → {} wide → {}",
+        config.clip_format.display_name(),
+        config.clip_seconds,
+        width,
+        height,
+        config.clip_width,
+        output_path.display()
+    );
+
+    cmd.spawn()
+        .map_err(|e| format!("Failed to spawn ffmpeg: {e}"))
+}
+
 /// Spawn the encoder writer thread. Receives video frames and writes them to ffmpeg's stdin.
+/// With `beats`, the beats among those frames are saved next to that video when it ends.
 pub fn spawn_encoder_thread(
     mut child: Child,
     frame_rx: Receiver<VideoFrame>,
     shutdown: Arc<AtomicBool>,
     frame_counter: Arc<AtomicU64>,
     bytes_written: Arc<AtomicU64>,
+    beats: Option<(PathBuf, u32)>,
 ) -> JoinHandle<Option<String>> {
     std::thread::Builder::new()
         .name("recording-encoder".into())
         .spawn(move || {
+            let mut beat_log = beats.as_ref().map(|(_, fps)| BeatLog::new(*fps));
             let result = encoder_loop(
                 &mut child,
                 &frame_rx,
                 &shutdown,
                 &frame_counter,
                 &bytes_written,
+                beat_log.as_mut(),
             );
+            if let (Some(beat_log), Some((video, _))) = (&beat_log, &beats) {
+                beat_log.save(video);
+            }
 
             // Close stdin to signal ffmpeg to finalize
             drop(child.stdin.take());
@@ -440,6 +628,7 @@ fn encoder_loop(
     shutdown: &AtomicBool,
     frame_counter: &AtomicU64,
     bytes_written: &AtomicU64,
+    mut beat_log: Option<&mut BeatLog>,
 ) -> Option<String> {
     let stdin = match child.stdin.as_mut() {
         Some(s) => s,
@@ -453,7 +642,10 @@ fn encoder_loop(
                 if let Err(e) = stdin.write_all(&frame.data) {
                     return Some(format!("Write to ffmpeg stdin failed: {e}"));
                 }
-                frame_counter.fetch_add(1, Ordering::Relaxed);
+                let index = frame_counter.fetch_add(1, Ordering::Relaxed);
+                if let Some(beats) = beat_log.as_deref_mut() {
+                    beats.record(index, &frame.stamp);
+                }
                 bytes_written.fetch_add(size, Ordering::Relaxed);
             }
             Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
@@ -463,3 +655,15 @@ fn encoder_loop(
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timecode_frame_follows_the_subsecond() {
+        assert_eq!(smpte_timecode("21:30:05", 0, 30), "21:30:05:00");
+        assert_eq!(smpte_timecode("21:30:05", 500_000_000, 30), "21:30:05:15");
+        assert_eq!(smpte_timecode("21:30:05", 999_999_999, 60), "21:30:05:59");
+    }
+}
@@ -1,20 +1,24 @@
 pub mod encoder;
+pub mod screenshot;
+pub mod stamp;
 pub mod types;
 
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::thread::JoinHandle;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::Sender;
 use wgpu::{CommandEncoder, Device, TextureFormat};
 
+use crate::gpu::calibration::OutputTarget;
 use crate::gpu::frame_capture::FrameCapture;
 use crate::gpu::postprocess::PostProcessChain;
 use crate::gpu::render_target::RenderTarget;
 
 use self::encoder::{AudioSource, EncoderInfo, VideoFrame, probe_encoders};
+use self::stamp::FrameStamp;
 use self::types::RecordingConfig;
 
 /// Recording state.
@@ -23,14 +27,61 @@ pub enum RecordingState {
     Idle,
     Recording {
         start_time: Instant,
-        #[allow(dead_code)]
         path: PathBuf,
         encoder_name: String,
         has_audio: bool,
+        /// Set for an "Export loop" capture: stops itself once this much has been captured.
+        clip_length: Option<Duration>,
     },
     Error(String),
 }
 
+/// A stopped capture whose threads are still flushing to disk.
+struct Finishing {
+    path: Option<PathBuf>,
+    clip: bool,
+    /// Cancelled: delete the file once ffmpeg lets go of it.
+    discard: bool,
+    encoder_handle: JoinHandle<Option<String>>,
+    audio_handle: Option<JoinHandle<()>>,
+    audio_fifo_path: Option<PathBuf>,
+}
+
+impl Finishing {
+    fn is_finished(&self) -> bool {
+        self.encoder_handle.is_finished()
+            && self.audio_handle.as_ref().is_none_or(|h| h.is_finished())
+    }
+
+    /// Join the threads and clean up; returns the encoder's error, if any.
+    fn join(self) -> Option<String> {
+        // The audio writer ends with BrokenPipe once ffmpeg closes its input.
+        if let Some(handle) = self.audio_handle {
+            let _ = handle.join();
+        }
+        if let Some(ref path) = self.audio_fifo_path {
+            let _ = std::fs::remove_file(path);
+        }
+        let err = match self.encoder_handle.join() {
+            Ok(err) => err,
+            Err(_) => Some("encoder thread panicked".to_string()),
+        };
+        if self.discard {
+            if let Some(ref path) = self.path {
+                let _ = std::fs::remove_file(path);
+            }
+            return None;
+        }
+        match (&err, &self.path) {
+            (Some(err), _) => log::error!("Recording encoder error: {err}"),
+            (None, Some(path)) if self.clip => log::info!("Loop exported to {}", path.display()),
+            (None, Some(path)) => log::info!("Recording saved to {}", path.display()),
+            (None, None) => {}
+        }
+        err
+    }
+}
+
 /// Central recording system: owns capture target, encoder thread, config.
 pub struct RecordingSystem {
     pub config: RecordingConfig,
@@ -46,6 +97,13 @@ pub struct RecordingSystem {
     pub bytes_written: Arc<AtomicU64>,
     output_width: u32,
     output_height: u32,
+    /// Stamp of the frame waiting in the staging buffer (1-frame latency).
+    pending_stamp: Option<FrameStamp>,
+    /// Stamp of a frame skipped while the staging buffer was busy, so its
+    /// beat isn't lost.
+    skipped_stamp: Option<FrameStamp>,
+    /// Stopped captures still being written, joined by [`Self::reap`].
+    finishing: Vec<Finishing>,
 }
 
 impl RecordingSystem {
@@ -67,6 +125,9 @@ impl RecordingSystem {
             bytes_written: Arc::new(AtomicU64::new(0)),
             output_width: 0,
             output_height: 0,
+            pending_stamp: None,
+            skipped_stamp: None,
+            finishing: Vec::new(),
         }
     }
 
@@ -156,6 +217,7 @@ impl RecordingSystem {
             shutdown.clone(),
             self.frame_counter.clone(),
             self.bytes_written.clone(),
+            Some((output_path.clone(), self.config.fps)),
         );
 
         // Spawn audio writer thread if we have a FIFO
@@ -180,6 +242,7 @@ impl RecordingSystem {
             path: output_path.clone(),
             encoder_name: encoder_name.to_string(),
             has_audio,
+            clip_length: None,
         };
 
         let audio_str = if has_audio { " +audio" } else { "" };
@@ -193,44 +256,148 @@ impl RecordingSystem {
         Ok(output_path)
     }
 
-    /// Stop recording: close ffmpeg stdin, wait for threads to finish.
+    /// Start an "Export loop" capture: `config.clip_seconds` of the composite, written as a
+    /// GIF or WebM scaled to `config.clip_width`. Runs through the same capture path as a
+    /// recording and ends itself — see [`Self::clip_finished`].
+    pub fn start_clip(
+        &mut self,
+        device: &Device,
+        format: TextureFormat,
+        window_w: u32,
+        window_h: u32,
+    ) -> Result<PathBuf, String> {
+        self.stop();
+
+        if !self.encoder_info.has_clip(self.config.clip_format) {
+            let err = if self.encoder_info.ffmpeg_found {
+                format!(
+                    "No encoder available for {}",
+                    self.config.clip_format.display_name()
+                )
+            } else {
+                "ffmpeg not found on PATH".to_string()
+            };
+            self.state = RecordingState::Error(err.clone());
+            return Err(err);
+        }
+
+        let (w, h) = self.config.resolution.dimensions(window_w, window_h);
+        self.output_width = w;
+        self.output_height = h;
+        self.capture = Some(FrameCapture::new(device, w, h, format, "clip-capture"));
+
+        let output_path = encoder::build_clip_output_path(&self.config);
+        let child = encoder::spawn_ffmpeg_clip(&self.config, w, h, &output_path)?;
+
+        let (tx, rx) = crossbeam_channel::bounded(2);
+        let shutdown = Arc::new(AtomicBool::new(false));
+        self.frame_counter.store(0, Ordering::Relaxed);
+        self.bytes_written.store(0, Ordering::Relaxed);
+        let handle = encoder::spawn_encoder_thread(
+            child,
+            rx,
+            shutdown.clone(),
+            self.frame_counter.clone(),
+            self.bytes_written.clone(),
+            None,
+        );
+
+        self.frame_tx = Some(tx);
+        self.shutdown = Some(shutdown);
+        self.encoder_handle = Some(handle);
+        self.state = RecordingState::Recording {
+            start_time: Instant::now(),
+            path: output_path.clone(),
+            encoder_name: self.config.clip_format.display_name().to_string(),
+            has_audio: false,
+            clip_length: Some(Duration::from_secs(u64::from(self.config.clip_seconds))),
+        };
+        Ok(output_path)
+    }
+
+    /// True once a loop export has captured its full length and should be stopped.
+    pub fn clip_finished(&self) -> bool {
+        match &self.state {
+            RecordingState::Recording {
+                start_time,
+                clip_length: Some(len),
+                ..
+            } => start_time.elapsed() >= *len,
+            _ => false,
+        }
+    }
+
+    /// Whether the running capture is a loop export rather than a recording.
+    pub fn is_clip(&self) -> bool {
+        matches!(
+            self.state,
+            RecordingState::Recording {
+                clip_length: Some(_),
+                ..
+            }
+        )
+    }
+
+    /// Stop recording: close ffmpeg stdin and leave its threads to finish
+    /// writing in the background. Never waits on ffmpeg — finalizing a long
+    /// file takes a while — so it's safe on the render thread; call
+    /// [`Self::reap`] each frame to collect the result.
     pub fn stop(&mut self) {
+        self.end_capture(false);
+    }
+
+    /// Abandon a loop export in progress and delete its file.
+    pub fn cancel_clip(&mut self) {
+        if self.is_clip() {
+            self.end_capture(true);
+            log::info!("Loop export cancelled");
+        }
+    }
+
+    fn end_capture(&mut self, discard: bool) {
         if let Some(shutdown) = self.shutdown.take() {
             shutdown.store(true, Ordering::Relaxed);
         }
         // Drop the channel sender so ffmpeg stdin closes.
         self.frame_tx = None;
 
-        // Wait for audio writer thread to finish (it will get BrokenPipe when ffmpeg closes)
-        if let Some(handle) = self.audio_handle.take() {
-            let _ = handle.join();
-        }
-
-        // Clean up FIFO
-        if let Some(ref path) = self.audio_fifo_path.take() {
-            let _ = std::fs::remove_file(path);
-        }
-
-        if let Some(handle) = self.encoder_handle.take() {
-            match handle.join() {
-                Ok(Some(err)) => {
-                    log::error!("Recording encoder error: {err}");
-                    self.state = RecordingState::Error(err);
-                    return;
-                }
-                Ok(None) => {}
-                Err(_) => {
-                    log::error!("Recording encoder thread panicked");
-                }
-            }
+        if let Some(encoder_handle) = self.encoder_handle.take() {
+            self.finishing.push(Finishing {
+                path: self.output_path().cloned(),
+                clip: self.is_clip(),
+                discard,
+                encoder_handle,
+                audio_handle: self.audio_handle.take(),
+                audio_fifo_path: self.audio_fifo_path.take(),
+            });
         }
         self.capture = None;
+        self.pending_stamp = None;
+        self.skipped_stamp = None;
         if matches!(self.state, RecordingState::Recording { .. }) {
             log::info!("Recording stopped");
         }
         self.state = RecordingState::Idle;
     }
 
+    /// Join the threads of stopped captures that are done writing. An
+    /// encoder error shows as [`RecordingState::Error`] unless a new capture
+    /// has started since.
+    pub fn reap(&mut self) {
+        let mut i = 0;
+        while i < self.finishing.len() {
+            if !self.finishing[i].is_finished() {
+                i += 1;
+                continue;
+            }
+            if let Some(err) = self.finishing.swap_remove(i).join()
+                && !self.is_recording()
+            {
+                self.state = RecordingState::Error(err);
+            }
+        }
+    }
+
     pub fn is_recording(&self) -> bool {
         matches!(self.state, RecordingState::Recording { .. })
     }
@@ -244,7 +411,6 @@ impl RecordingSystem {
     }
 
     /// Current output file path (if recording).
-    #[allow(dead_code)]
     pub fn output_path(&self) -> Option<&PathBuf> {
         match &self.state {
             RecordingState::Recording { path, .. } => Some(path),
@@ -268,13 +434,16 @@ impl RecordingSystem {
         (self.output_width, self.output_height)
     }
 
-    /// Run the recording capture pipeline (same pattern as NDI).
+    /// Run the recording capture pipeline (same pattern as NDI). `stamp`
+    /// describes the frame being rendered now; it reaches the encoder with
+    /// that frame's pixels, a frame later.
     pub fn capture_frame(
         &mut self,
         device: &Device,
         encoder: &mut CommandEncoder,
         post_process: &PostProcessChain,
         source: &RenderTarget,
+        stamp: FrameStamp,
     ) {
         let capture = match self.capture.as_mut() {
             Some(c) => c,
@@ -286,21 +455,31 @@ impl RecordingSystem {
 
         // If previous map still outstanding, skip this frame.
         if capture.is_map_pending() {
+            self.skipped_stamp = Some(stamp.with_beats_from(self.skipped_stamp));
             return;
         }
+        let stamp = stamp.with_beats_from(self.skipped_stamp.take());
+        let prev_stamp = self.pending_stamp.replace(stamp);
 
         // Render composite to capture texture.
-        post_process.render_composite_to(device, encoder, source, &capture.view);
+        post_process.render_composite_to(
+            device,
+            encoder,
+            source,
+            &capture.view,
+            OutputTarget::Recording,
+        );
 
         // Copy to staging.
         capture.copy_to_staging(encoder);
 
         // Send previous frame data to encoder thread.
-        if let (Some(data), Some(tx)) = (prev_data, &self.frame_tx) {
+        if let (Some(data), Some(stamp), Some(tx)) = (prev_data, prev_stamp, &self.frame_tx) {
             let frame = VideoFrame {
                 data,
                 width: capture.width,
                 height: capture.height,
+                stamp,
             };
             let _ = tx.try_send(frame);
         }
@@ -313,6 +492,20 @@ impl RecordingSystem {
         }
     }
 
+    /// Rebuild the capture target on a new device after a device loss
+    /// (#2176). The encoder keeps running; it just misses the lost frames.
+    pub fn recreate_capture(&mut self, device: &Device) {
+        if let Some(capture) = self.capture.as_ref() {
+            let label = if self.is_clip() {
+                "clip-capture"
+            } else {
+                "recording-capture"
+            };
+            let (w, h, format) = (capture.width, capture.height, capture.format);
+            self.capture = Some(FrameCapture::new(device, w, h, format, label));
+        }
+    }
+
     pub fn frames_encoded(&self) -> u64 {
         self.frame_counter.load(Ordering::Relaxed)
     }
@@ -325,5 +518,9 @@ impl RecordingSystem {
 impl Drop for RecordingSystem {
     fn drop(&mut self) {
         self.stop();
+        // On exit, wait for the files to be finalized.
+        for finishing in self.finishing.drain(..) {
+            finishing.join();
+        }
     }
 }
@@ -0,0 +1,219 @@
+//! Screenshots (#2252): the F12 key and the `Screenshot` trigger save the
+//! post-processed output, at surface resolution, as a PNG. The frame is
+//! rendered into its own capture target and read back a frame later; the PNG
+//! is encoded on a worker thread so a 4K frame doesn't stall the render.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{Receiver, Sender};
+use wgpu::{CommandEncoder, Device, TextureFormat};
+
+use crate::gpu::calibration::OutputTarget;
+use crate::gpu::frame_capture::FrameCapture;
+use crate::gpu::postprocess::PostProcessChain;
+use crate::gpu::render_target::RenderTarget;
+
+/// A saved file, or why it couldn't be written.
+pub type ScreenshotResult = Result<PathBuf, String>;
+
+/// A readback that hasn't landed by now has failed; the capture is dropped
+/// so the next request can go ahead.
+const READBACK_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct Screenshot {
+    /// Asked for; taken at the next render.
+    requested: bool,
+    /// The frame on its way back from the GPU, and when it was taken.
+    capture: Option<(FrameCapture, Instant)>,
+    done_tx: Sender<ScreenshotResult>,
+    done_rx: Receiver<ScreenshotResult>,
+}
+
+impl Screenshot {
+    pub fn new() -> Self {
+        let (done_tx, done_rx) = crossbeam_channel::unbounded();
+        Self {
+            requested: false,
+            capture: None,
+            done_tx,
+            done_rx,
+        }
+    }
+
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    /// Render `source` through the output chain into a capture target and
+    /// copy it to staging, if a screenshot was asked for and none is in flight.
+    #[allow(clippy::too_many_arguments)]
+    pub fn capture(
+        &mut self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        post_process: &PostProcessChain,
+        source: &RenderTarget,
+        format: TextureFormat,
+        width: u32,
+        height: u32,
+    ) {
+        if !self.requested || self.capture.is_some() {
+            return;
+        }
+        self.requested = false;
+        let capture = FrameCapture::new(device, width, height, format, "screenshot-capture");
+        post_process.render_composite_to(
+            device,
+            encoder,
+            source,
+            &capture.view,
+            OutputTarget::Recording,
+        );
+        capture.copy_to_staging(encoder);
+        self.capture = Some((capture, Instant::now()));
+    }
+
+    /// Called after queue.submit() — request the readback.
+    pub fn post_submit(&mut self) {
+        if let Some((capture, _)) = self.capture.as_mut() {
+            capture.request_map();
+        }
+    }
+
+    /// Hand a finished readback to a worker that writes it into `dir`, and
+    /// return the writes completed since the last call.
+    pub fn poll(&mut self, device: &Device, dir: &Path) -> Vec<ScreenshotResult> {
+        if let Some((capture, taken)) = self.capture.as_mut() {
+            if let Some(data) = capture.take_mapped_data(device) {
+                let (width, height, format) = (capture.width, capture.height, capture.format);
+                self.capture = None;
+                let dir = dir.to_path_buf();
+                let done = self.done_tx.clone();
+                std::thread::spawn(move || {
+                    let _ = done.send(write_png(&dir, data, width, height, format));
+                });
+            } else if taken.elapsed() > READBACK_TIMEOUT {
+                self.capture = None;
+                let _ = self
+                    .done_tx
+                    .send(Err("the frame never came back from the GPU".to_string()));
+            }
+        }
+        self.done_rx.try_iter().collect()
+    }
+
+    /// Drop a capture made on a lost device (#2176); a pending request stays.
+    pub fn cancel(&mut self) {
+        self.capture = None;
+    }
+}
+
+fn write_png(
+    dir: &Path,
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+) -> ScreenshotResult {
+    let rgba = to_rgba(data, format)?;
+    std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    let (path, file) = create_unique(dir)?;
+    let mut out = std::io::BufWriter::new(file);
+    let written = image::write_buffer_with_format(
+        &mut out,
+        &rgba,
+        width,
+        height,
+        image::ExtendedColorType::Rgba8,
+        image::ImageFormat::Png,
+    )
+    .map_err(|e| e.to_string())
+    .and_then(|()| std::io::Write::flush(&mut out).map_err(|e| e.to_string()));
+    drop(out);
+    if let Err(e) = written {
+        // A half-written shot would keep the name and look like a real one
+        let _ = std::fs::remove_file(&path);
+        return Err(format!("{}: {e}", path.display()));
+    }
+    Ok(path)
+}
+
+/// Create `dir/phosphor_<local time>.png`, or `…_2.png`, `…_3.png` for
+/// further shots in the same second, never replacing an existing file.
+fn create_unique(dir: &Path) -> Result<(PathBuf, File), String> {
+    let first = super::encoder::timestamped_path_in(dir, "phosphor", "png");
+    let stem = first
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    for n in 1..1000 {
+        let path = if n == 1 {
+            first.clone()
+        } else {
+            dir.join(format!("{stem}_{n}.png"))
+        };
+        match File::options().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(format!("{}: {e}", path.display())),
+        }
+    }
+    Err(format!(
+        "{}: too many screenshots this second",
+        first.display()
+    ))
+}
+
+/// Capture bytes as opaque RGBA, as the window shows them.
+fn to_rgba(mut data: Vec<u8>, format: TextureFormat) -> Result<Vec<u8>, String> {
+    let bgra = match format {
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+        other => return Err(format!("can't save a {other:?} surface as PNG")),
+    };
+    for px in data.chunks_exact_mut(4) {
+        if bgra {
+            px.swap(0, 2);
+        }
+        px[3] = 255;
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bgra_is_swizzled_and_made_opaque() {
+        let px = vec![10, 20, 30, 0, 1, 2, 3, 128];
+        assert_eq!(
+            to_rgba(px.clone(), TextureFormat::Bgra8UnormSrgb).unwrap(),
+            [30, 20, 10, 255, 3, 2, 1, 255]
+        );
+        assert_eq!(
+            to_rgba(px, TextureFormat::Rgba8Unorm).unwrap(),
+            [10, 20, 30, 255, 1, 2, 3, 255]
+        );
+        assert!(to_rgba(vec![0; 4], TextureFormat::Rgb10a2Unorm).is_err());
+    }
+
+    #[test]
+    fn shots_in_the_same_second_get_their_own_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a, _) = create_unique(dir.path()).unwrap();
+        let (b, _) = create_unique(dir.path()).unwrap();
+        assert_ne!(a, b);
+        assert!(a.exists() && b.exists());
+    }
+
+    #[test]
+    fn failed_encode_leaves_no_file() {
+        let dir = tempfile::tempdir().unwrap();
+        // PNG refuses a zero-width image after the file is created
+        assert!(write_png(dir.path(), Vec::new(), 0, 1, TextureFormat::Rgba8Unorm).is_err());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
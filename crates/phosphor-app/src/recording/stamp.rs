@@ -0,0 +1,203 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::audio::features::AudioFeatures;
+
+/// When an output frame was rendered and where it sat in the music (#2211).
+/// Travels with the frame to the NDI sender (as timecode + metadata) and to
+/// the recording encoder (as a beat marker sidecar), so an editor can cut on
+/// the beat without re-analysing the audio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameStamp {
+    /// Wall-clock render time, since the Unix epoch.
+    pub time: Duration,
+    /// Detected tempo in BPM; zero when there's no audio.
+    pub bpm: f32,
+    pub beat_phase: f32,
+    pub bar_phase: f32,
+    /// A beat landed on this frame.
+    pub beat: bool,
+    /// The beat that landed was the first of a bar.
+    pub downbeat: bool,
+}
+
+impl FrameStamp {
+    /// Stamp for a frame rendered now with the given analysis.
+    pub fn now(features: Option<&AudioFeatures>) -> Self {
+        let time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let f = features.copied().unwrap_or_default();
+        Self {
+            time,
+            bpm: f.bpm * 300.0,
+            beat_phase: f.beat_phase,
+            bar_phase: f.bar_phase,
+            beat: f.beat > 0.5,
+            downbeat: f.downbeat > 0.5,
+        }
+    }
+
+    /// This stamp, also carrying any beat from a frame that was skipped
+    /// before it — a beat on a dropped frame moves to the next one sent.
+    #[must_use]
+    pub fn with_beats_from(self, skipped: Option<FrameStamp>) -> Self {
+        let Some(skipped) = skipped else {
+            return self;
+        };
+        Self {
+            beat: self.beat || skipped.beat,
+            downbeat: self.downbeat || skipped.downbeat,
+            ..self
+        }
+    }
+
+    /// NDI timecode: 100 ns ticks since the Unix epoch, the base NDI itself
+    /// uses when it synthesizes one.
+    pub fn ndi_timecode(&self) -> i64 {
+        i64::try_from(self.time.as_nanos() / 100).unwrap_or(i64::MAX - 1)
+    }
+
+    /// Per-frame NDI metadata: a single XML element receivers can read.
+    pub fn ndi_metadata(&self) -> String {
+        format!(
+            "<phosphor_beat bpm=\"{:.2}\" beat_phase=\"{:.4}\" bar_phase=\"{:.4}\" \
+             beat=\"{}\" downbeat=\"{}\"/>",
+            self.bpm, self.beat_phase, self.bar_phase, self.beat, self.downbeat
+        )
+    }
+}
+
+/// Beats seen by a recording, by their position in the file. The encoder
+/// writes frames at a fixed rate whatever the render rate, so a beat's time
+/// in the video is its frame index over that rate, not its wall-clock time.
+#[derive(Debug, Clone)]
+pub struct BeatLog {
+    fps: u32,
+    /// First frame's render time, for the header.
+    start: Option<Duration>,
+    beats: Vec<(u64, FrameStamp)>,
+}
+
+impl BeatLog {
+    pub fn new(fps: u32) -> Self {
+        Self {
+            fps: fps.max(1),
+            start: None,
+            beats: Vec::new(),
+        }
+    }
+
+    /// Note the stamp of the frame written at `frame` (0-based).
+    pub fn record(&mut self, frame: u64, stamp: &FrameStamp) {
+        self.start.get_or_insert(stamp.time);
+        if stamp.beat {
+            self.beats.push((frame, *stamp));
+        }
+    }
+
+    /// One row per beat: frame, seconds into the file, SMPTE-style
+    /// `HH:MM:SS:FF`, BPM, and whether it was a downbeat.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        if let Some(start) = self.start {
+            let _ = writeln!(out, "# started {:.3} (unix seconds)", start.as_secs_f64());
+        }
+        out.push_str("frame,seconds,timecode,bpm,downbeat\n");
+        let fps = u64::from(self.fps);
+        for (frame, stamp) in &self.beats {
+            let secs = frame / fps;
+            let _ = writeln!(
+                out,
+                "{frame},{:.3},{:02}:{:02}:{:02}:{:02},{:.2},{}",
+                *frame as f64 / fps as f64,
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60,
+                frame % fps,
+                stamp.bpm,
+                u8::from(stamp.downbeat),
+            );
+        }
+        out
+    }
+
+    /// Write the log next to the video it belongs to. Nothing is written
+    /// when no beat was heard.
+    pub fn save(&self, video: &Path) {
+        if self.beats.is_empty() {
+            return;
+        }
+        let path = sidecar_path(video);
+        match std::fs::write(&path, self.to_csv()) {
+            Ok(()) => log::info!("Beat markers saved: {}", path.display()),
+            Err(e) => log::error!("Failed to save beat markers: {e}"),
+        }
+    }
+}
+
+/// `show.mp4` → `show.beats.csv`.
+pub fn sidecar_path(video: &Path) -> PathBuf {
+    video.with_extension("beats.csv")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamp(beat: bool, downbeat: bool) -> FrameStamp {
+        FrameStamp {
+            time: Duration::from_millis(1_700_000_000_250),
+            bpm: 128.0,
+            beat_phase: 0.0,
+            bar_phase: 0.25,
+            beat,
+            downbeat,
+        }
+    }
+
+    #[test]
+    fn ndi_stamp_uses_100ns_ticks_and_xml() {
+        let s = stamp(true, false);
+        assert_eq!(s.ndi_timecode(), 17_000_000_002_500_000);
+        let xml = s.ndi_metadata();
+        assert!(xml.starts_with("<phosphor_beat "));
+        assert!(xml.ends_with("/>"));
+        assert!(xml.contains("bpm=\"128.00\""));
+        assert!(xml.contains("beat=\"true\""));
+        assert!(xml.contains("downbeat=\"false\""));
+    }
+
+    #[test]
+    fn skipped_beats_carry_forward() {
+        let quiet = stamp(false, false);
+        assert_eq!(quiet.with_beats_from(None), quiet);
+        let carried = quiet.with_beats_from(Some(stamp(true, true)));
+        assert!(carried.beat && carried.downbeat);
+        assert_eq!(carried.time, quiet.time);
+    }
+
+    #[test]
+    fn beat_log_places_beats_by_frame() {
+        let mut log = BeatLog::new(30);
+        log.record(0, &stamp(false, false));
+        log.record(15, &stamp(true, true));
+        log.record(16, &stamp(false, false));
+        log.record(3675, &stamp(true, false));
+        let csv = log.to_csv();
+        let rows: Vec<&str> = csv.lines().filter(|l| !l.starts_with('#')).collect();
+        assert_eq!(rows[0], "frame,seconds,timecode,bpm,downbeat");
+        assert_eq!(rows[1], "15,0.500,00:00:00:15,128.00,1");
+        assert_eq!(rows[2], "3675,122.500,00:02:02:15,128.00,0");
+        assert_eq!(rows.len(), 3);
+    }
+
+    #[test]
+    fn sidecar_sits_next_to_the_video() {
+        assert_eq!(
+            sidecar_path(Path::new("/rec/phosphor_x.mp4")),
+            PathBuf::from("/rec/phosphor_x.beats.csv")
+        );
+    }
+}
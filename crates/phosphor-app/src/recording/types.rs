@@ -11,25 +11,35 @@ pub enum VideoCodec {
     H264,
     Hevc,
     AV1,
+    /// ProRes 422 HQ (#2251): near-lossless intra frames for editing a set
+    /// afterwards. Large files, software only, always written as MOV.
+    ProRes,
 }
 
 impl VideoCodec {
-    pub const ALL: &[VideoCodec] = &[VideoCodec::H264, VideoCodec::Hevc, VideoCodec::AV1];
+    pub const ALL: &[VideoCodec] = &[
+        VideoCodec::H264,
+        VideoCodec::Hevc,
+        VideoCodec::AV1,
+        VideoCodec::ProRes,
+    ];
 
     pub fn display_name(self) -> &'static str {
         match self {
             VideoCodec::H264 => "H.264",
             VideoCodec::Hevc => "HEVC",
             VideoCodec::AV1 => "AV1",
+            VideoCodec::ProRes => "ProRes 422 HQ",
         }
     }
 
-    /// Hardware encoder name (NVENC).
+    /// Hardware encoder name (NVENC). ProRes has none; it never gets here.
     pub fn hw_encoder(self) -> &'static str {
         match self {
             VideoCodec::H264 => "h264_nvenc",
             VideoCodec::Hevc => "hevc_nvenc",
             VideoCodec::AV1 => "av1_nvenc",
+            VideoCodec::ProRes => "prores_ks",
         }
     }
 
@@ -39,8 +49,15 @@ impl VideoCodec {
             VideoCodec::H264 => "libx264",
             VideoCodec::Hevc => "libx265",
             VideoCodec::AV1 => "libsvtav1",
+            VideoCodec::ProRes => "prores_ks",
         }
     }
+
+    /// Whether the quality (CQ/CRF) setting applies. ProRes has a fixed
+    /// profile instead.
+    pub fn has_quality(self) -> bool {
+        self != VideoCodec::ProRes
+    }
 }
 
 /// Container format for recording output.
@@ -49,15 +66,17 @@ pub enum Container {
     #[default]
     Mp4,
     Mkv,
+    Mov,
 }
 
 impl Container {
-    pub const ALL: &[Container] = &[Container::Mp4, Container::Mkv];
+    pub const ALL: &[Container] = &[Container::Mp4, Container::Mkv, Container::Mov];
 
     pub fn display_name(self) -> &'static str {
         match self {
             Container::Mp4 => "MP4",
             Container::Mkv => "MKV",
+            Container::Mov => "MOV",
         }
     }
 
@@ -65,6 +84,33 @@ impl Container {
         match self {
             Container::Mp4 => "mp4",
             Container::Mkv => "mkv",
+            Container::Mov => "mov",
+        }
+    }
+}
+
+/// Output format for a short loop export ("Export loop").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ClipFormat {
+    #[default]
+    Gif,
+    WebM,
+}
+
+impl ClipFormat {
+    pub const ALL: &[ClipFormat] = &[ClipFormat::Gif, ClipFormat::WebM];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            ClipFormat::Gif => "GIF",
+            ClipFormat::WebM => "WebM",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ClipFormat::Gif => "gif",
+            ClipFormat::WebM => "webm",
         }
     }
 }
@@ -88,6 +134,18 @@ pub struct RecordingConfig {
     pub use_hw_encoder: bool,
     #[serde(default = "default_true")]
     pub record_audio: bool,
+    /// Length of an "Export loop" capture, in seconds.
+    #[serde(default = "default_clip_seconds")]
+    pub clip_seconds: u32,
+    #[serde(default)]
+    pub clip_format: ClipFormat,
+    /// Width the loop is scaled to (height follows the aspect). Social feeds re-encode
+    /// anything larger, and a full-size GIF runs to tens of MB.
+    #[serde(default = "default_clip_width")]
+    pub clip_width: u32,
+    /// Where screenshots (#2252) are saved.
+    #[serde(default = "default_screenshot_dir")]
+    pub screenshot_dir: PathBuf,
 }
 
 fn default_fps() -> u32 {
@@ -99,6 +157,12 @@ fn default_quality() -> u32 {
 fn default_true() -> bool {
     true
 }
+fn default_clip_seconds() -> u32 {
+    5
+}
+fn default_clip_width() -> u32 {
+    480
+}
 
 fn default_output_dir() -> PathBuf {
     dirs::video_dir()
@@ -106,6 +170,12 @@ fn default_output_dir() -> PathBuf {
         .join("Phosphor")
 }
 
+fn default_screenshot_dir() -> PathBuf {
+    dirs::picture_dir()
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_else(|| PathBuf::from(".")))
+        .join("Phosphor")
+}
+
 impl Default for RecordingConfig {
     fn default() -> Self {
         Self {
@@ -117,14 +187,27 @@ impl Default for RecordingConfig {
             output_dir: default_output_dir(),
             use_hw_encoder: true,
             record_audio: true,
+            clip_seconds: default_clip_seconds(),
+            clip_format: ClipFormat::default(),
+            clip_width: default_clip_width(),
+            screenshot_dir: default_screenshot_dir(),
         }
     }
 }
 
 impl RecordingConfig {
+    /// The container a recording is written in. ProRes doesn't go in MP4,
+    /// so that combination is written as MOV.
+    pub fn output_container(&self) -> Container {
+        if self.codec == VideoCodec::ProRes && self.container == Container::Mp4 {
+            Container::Mov
+        } else {
+            self.container
+        }
+    }
+
     pub fn config_path() -> PathBuf {
-        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-        config_dir.join("phosphor").join("recording.json")
+        crate::paths::config_dir().join("recording.json")
     }
 
     pub fn load() -> Self {
@@ -148,21 +231,8 @@ impl RecordingConfig {
     }
 
     pub fn save(&self) {
-        let path = Self::config_path();
-        if let Some(parent) = path.parent() {
-            if let Err(e) = std::fs::create_dir_all(parent) {
-                log::error!("Failed to create config dir: {e}");
-                return;
-            }
-        }
         match serde_json::to_string_pretty(self) {
-            Ok(json) => {
-                if let Err(e) = std::fs::write(&path, json) {
-                    log::error!("Failed to write recording config: {e}");
-                } else {
-                    log::debug!("Saved recording config to {}", path.display());
-                }
-            }
+            Ok(json) => crate::persist::write(Self::config_path(), json),
             Err(e) => log::error!("Failed to serialize recording config: {e}"),
         }
     }
@@ -197,11 +267,42 @@ mod tests {
         assert_eq!(VideoCodec::H264.display_name(), "H.264");
         assert_eq!(VideoCodec::Hevc.display_name(), "HEVC");
         assert_eq!(VideoCodec::AV1.display_name(), "AV1");
+        assert_eq!(VideoCodec::ProRes.display_name(), "ProRes 422 HQ");
+    }
+
+    #[test]
+    fn prores_is_never_written_as_mp4() {
+        let mut c = RecordingConfig {
+            codec: VideoCodec::ProRes,
+            ..Default::default()
+        };
+        assert_eq!(c.output_container(), Container::Mov);
+        c.container = Container::Mkv;
+        assert_eq!(c.output_container(), Container::Mkv);
+        c.codec = VideoCodec::H264;
+        c.container = Container::Mp4;
+        assert_eq!(c.output_container(), Container::Mp4);
+    }
+
+    #[test]
+    fn clip_fields_default_for_old_configs() {
+        let json = r#"{"codec":"H264","fps":30}"#;
+        let c: RecordingConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(c.clip_seconds, 5);
+        assert_eq!(c.clip_format, ClipFormat::Gif);
+        assert_eq!(c.clip_width, 480);
+    }
+
+    #[test]
+    fn clip_format_extensions() {
+        assert_eq!(ClipFormat::Gif.extension(), "gif");
+        assert_eq!(ClipFormat::WebM.extension(), "webm");
     }
 
     #[test]
     fn container_extensions() {
         assert_eq!(Container::Mp4.extension(), "mp4");
         assert_eq!(Container::Mkv.extension(), "mkv");
+        assert_eq!(Container::Mov.extension(), "mov");
     }
 }
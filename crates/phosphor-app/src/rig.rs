@@ -0,0 +1,167 @@
+//! Rig files (#2203): settings, keymaps, MIDI/OSC/web/NDI/recording config
+//! and global bindings in one JSON file, optionally with the user presets,
+//! so a whole setup can be cloned onto a backup laptop in one step.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::schema::Schema;
+
+/// Rig file layout history.
+pub const SCHEMA: Schema = Schema {
+    name: "rig",
+    migrations: &[],
+};
+
+pub const EXTENSION: &str = "fosfora-rig";
+
+/// Where the setup is saved just before an import replaces it.
+pub const BACKUP_FILE: &str = "before-import.fosfora-rig";
+
+/// Config files a rig carries, relative to the config folder. The shader
+/// editor keymap and venue profiles live in `settings.json`.
+const CONFIG_FILES: &[&str] = &[
+    "settings.json",
+    "midi.json",
+    "osc.json",
+    "web.json",
+    "ndi.json",
+    "recording.json",
+    "global-bindings.json",
+];
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rig {
+    /// File contents by path relative to the config folder.
+    pub files: BTreeMap<String, serde_json::Value>,
+}
+
+impl Rig {
+    /// Gather the config files in `dir`, plus the user presets and their
+    /// binding sidecars with `with_presets`. Missing files are left out.
+    pub fn collect(dir: &Path, with_presets: bool) -> Self {
+        let mut names: Vec<String> = CONFIG_FILES.iter().map(|f| (*f).to_string()).collect();
+        if with_presets && let Ok(entries) = std::fs::read_dir(dir.join("presets")) {
+            names.extend(
+                entries
+                    .flatten()
+                    .filter_map(|e| e.file_name().into_string().ok())
+                    .map(|f| format!("presets/{f}"))
+                    .filter(|name| is_rig_file(name)),
+            );
+        }
+        let files = names
+            .into_iter()
+            .filter_map(|name| {
+                let json = std::fs::read_to_string(dir.join(&name)).ok()?;
+                match serde_json::from_str(&json) {
+                    Ok(value) => Some((name, value)),
+                    Err(e) => {
+                        log::warn!("Leaving {name} out of the rig: {e}");
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self { files }
+    }
+
+    pub fn preset_count(&self) -> usize {
+        self.files
+            .keys()
+            .filter(|f| f.starts_with("presets/") && !f.ends_with(".bindings.json"))
+            .count()
+    }
+
+    /// Queue every file for writing into `dir`, replacing what's there.
+    /// Names that aren't config files or presets are skipped, so a rig can't
+    /// write outside the config folder. Returns how many were queued.
+    pub fn install(&self, dir: &Path) -> usize {
+        let mut written = 0;
+        for (name, value) in &self.files {
+            if !is_rig_file(name) {
+                log::warn!("Skipping {name} in the rig: not a config file");
+                continue;
+            }
+            match serde_json::to_string_pretty(value) {
+                Ok(json) => {
+                    crate::persist::write(dir.join(name), json);
+                    written += 1;
+                }
+                Err(e) => log::warn!("Skipping {name} in the rig: {e}"),
+            }
+        }
+        written
+    }
+}
+
+/// A config file name, or `presets/<name>.json` with a plain file name.
+fn is_rig_file(name: &str) -> bool {
+    if CONFIG_FILES.contains(&name) {
+        return true;
+    }
+    name.strip_prefix("presets/").is_some_and(|file| {
+        Path::new(file).extension().is_some_and(|ext| ext == "json")
+            && !file.starts_with('.')
+            && !file.contains(['/', '\\', ':'])
+            && !file.contains("..")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collect_and_install_round_trip() {
+        let from = tempfile::tempdir().unwrap();
+        std::fs::write(from.path().join("midi.json"), r#"{"version":1}"#).unwrap();
+        std::fs::write(from.path().join("osc.json"), "not json").unwrap();
+        std::fs::create_dir(from.path().join("presets")).unwrap();
+        std::fs::write(from.path().join("presets/Club.json"), r#"{"layers":[]}"#).unwrap();
+        std::fs::write(from.path().join("presets/Club.bindings.json"), "{}").unwrap();
+        std::fs::write(from.path().join("presets/notes.txt"), "x").unwrap();
+
+        let bare = Rig::collect(from.path(), false);
+        assert_eq!(bare.files.keys().collect::<Vec<_>>(), ["midi.json"]);
+
+        let rig = Rig::collect(from.path(), true);
+        assert_eq!(
+            rig.files.keys().collect::<Vec<_>>(),
+            [
+                "midi.json",
+                "presets/Club.bindings.json",
+                "presets/Club.json"
+            ]
+        );
+        assert_eq!(rig.preset_count(), 1);
+
+        let json = SCHEMA.to_json(&rig).unwrap();
+        let back: Rig = SCHEMA.parse(&json).unwrap();
+        assert_eq!(back, rig);
+
+        let to = tempfile::tempdir().unwrap();
+        assert_eq!(rig.install(to.path()), 3);
+        crate::persist::flush();
+        let midi = std::fs::read_to_string(to.path().join("midi.json")).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&midi).unwrap()["version"],
+            1
+        );
+        assert!(to.path().join("presets/Club.json").exists());
+    }
+
+    #[test]
+    fn only_config_files_and_presets_are_installed() {
+        assert!(is_rig_file("settings.json"));
+        assert!(is_rig_file("presets/My Set.json"));
+        assert!(!is_rig_file("pipeline_cache.bin"));
+        assert!(!is_rig_file("../.bashrc"));
+        assert!(!is_rig_file("presets/../../x.json"));
+        assert!(!is_rig_file("presets/a/b.json"));
+        assert!(!is_rig_file("presets/C:\\x.json"));
+        assert!(!is_rig_file("/etc/passwd"));
+    }
+}
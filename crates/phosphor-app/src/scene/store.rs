@@ -19,8 +19,7 @@ impl SceneStore {
     }
 
     pub fn scenes_dir() -> PathBuf {
-        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-        config_dir.join("phosphor").join("scenes")
+        crate::paths::config_dir().join("scenes")
     }
 
     fn sanitize_name(name: &str) -> String {
@@ -97,28 +96,30 @@ impl SceneStore {
         );
     }
 
-    /// Save a scene to disk and re-scan.
+    /// Save a scene through the persist writer (#2201) and add it to the
+    /// list, replacing one of the same name.
     pub fn save(&mut self, name: &str, scene: SceneSet) -> Result<usize> {
         let name = Self::sanitize_name(name);
         if name.is_empty() {
             anyhow::bail!("Scene name cannot be empty");
         }
+        crate::persist::ensure_writable()?;
 
-        let dir = Self::scenes_dir();
-        std::fs::create_dir_all(&dir)?;
-
-        let path = dir.join(format!("{name}.json"));
+        let path = Self::scenes_dir().join(format!("{name}.json"));
         let json = serde_json::to_string_pretty(&scene)?;
-        std::fs::write(&path, json)?;
-        log::info!("Saved scene '{}' to {}", name, path.display());
+        log::info!("Saving scene '{}' to {}", name, path.display());
+        crate::persist::write(path, json);
 
-        self.scan();
-
-        let idx = self
-            .scenes
-            .iter()
-            .position(|(n, _)| n == &name)
-            .unwrap_or(0);
+        let idx = match self.scenes.binary_search_by(|(n, _)| n.cmp(&name)) {
+            Ok(i) => {
+                self.scenes[i].1 = scene;
+                i
+            }
+            Err(i) => {
+                self.scenes.insert(i, (name, scene));
+                i
+            }
+        };
         self.current_scene = Some(idx);
         Ok(idx)
     }
@@ -128,23 +129,44 @@ impl SceneStore {
         self.scenes.get(index).map(|(_, s)| s)
     }
 
-    /// Delete a scene from disk and re-scan.
+    /// Delete a scene, through the persist writer so a save still pending
+    /// for it can't bring it back.
     pub fn delete(&mut self, index: usize) -> Result<()> {
-        let (name, _) = self
-            .scenes
-            .get(index)
-            .ok_or_else(|| anyhow::anyhow!("Invalid scene index"))?;
-
-        let path = Self::scenes_dir().join(format!("{name}.json"));
-        if path.exists() {
-            std::fs::remove_file(&path)?;
-            log::info!("Deleted scene '{}'", name);
+        if index >= self.scenes.len() {
+            anyhow::bail!("Invalid scene index");
         }
+        crate::persist::ensure_writable()?;
 
-        self.scan();
+        let (name, _) = self.scenes.remove(index);
+        crate::persist::remove(Self::scenes_dir().join(format!("{name}.json")));
+        log::info!("Deleted scene '{}'", name);
+        self.current_scene = None;
         Ok(())
     }
 
+    /// Point cues at a renamed preset (#2222) and rewrite the scenes that had
+    /// any. Returns how many cues changed.
+    pub fn rename_preset(&mut self, old: &str, new: &str) -> usize {
+        let dir = Self::scenes_dir();
+        let mut changed = 0;
+        for (name, scene) in &mut self.scenes {
+            let mut hits = 0;
+            for cue in scene.cues.iter_mut().filter(|c| c.preset_name == old) {
+                cue.preset_name = new.to_string();
+                hits += 1;
+            }
+            if hits == 0 {
+                continue;
+            }
+            changed += hits;
+            match serde_json::to_string_pretty(scene) {
+                Ok(json) => crate::persist::write(dir.join(format!("{name}.json")), json),
+                Err(e) => log::warn!("Failed to serialize scene '{}': {e}", name),
+            }
+        }
+        changed
+    }
+
     /// Get the name of the currently loaded scene.
     #[allow(dead_code)]
     pub fn current_name(&self) -> Option<&str> {
@@ -1,4 +1,4 @@
-use super::types::{AdvanceMode, SceneCue, TransitionType};
+use super::types::{AdvanceMode, GoQuantize, SceneCue, TransitionType};
 
 /// Runtime playback state of the timeline.
 #[allow(dead_code)]
@@ -44,6 +44,13 @@ pub enum TimelineEvent {
     TransitionComplete { cue_index: usize },
 }
 
+/// Which way a quantized Go or Back is waiting to move (#2221).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoDirection {
+    Next,
+    Prev,
+}
+
 /// Read-only snapshot for UI (avoids borrow conflicts).
 #[derive(Debug, Clone)]
 pub struct TimelineInfo {
@@ -53,6 +60,9 @@ pub struct TimelineInfo {
     pub state: TimelineInfoState,
     pub loop_mode: bool,
     pub advance_mode: AdvanceMode,
+    pub quantize: GoQuantize,
+    /// A Go or Back waiting for its beat or bar.
+    pub pending_go: Option<GoDirection>,
 }
 
 #[derive(Debug, Clone)]
@@ -79,8 +89,13 @@ pub struct Timeline {
     pub active: bool,
     /// Beat counter for BeatSync mode.
     pub beat_count: u32,
+    /// When a manual Go or Back lands.
+    pub quantize: GoQuantize,
+    /// A Go or Back waiting for its beat or bar.
+    pending_go: Option<GoDirection>,
     /// Last beat state (for rising-edge detection).
     last_beat: bool,
+    last_downbeat: bool,
 }
 
 impl Timeline {
@@ -92,7 +107,10 @@ impl Timeline {
             advance_mode,
             active: false,
             beat_count: 0,
+            quantize: GoQuantize::default(),
+            pending_go: None,
             last_beat: false,
+            last_downbeat: false,
         }
     }
 
@@ -104,6 +122,7 @@ impl Timeline {
         let idx = cue_index.min(self.cues.len() - 1);
         self.active = true;
         self.beat_count = 0;
+        self.pending_go = None;
         self.state = PlaybackState::Holding {
             cue_index: idx,
             elapsed: 0.0,
@@ -153,11 +172,42 @@ impl Timeline {
     /// Stop the timeline.
     pub fn stop(&mut self) {
         self.active = false;
+        self.pending_go = None;
         self.state = PlaybackState::Idle;
     }
 
-    /// Advance to next cue (manual trigger).
+    /// Advance to next cue (manual trigger), on the beat or bar `quantize` asks for.
     pub fn go_next(&mut self) -> TimelineEvent {
+        self.request_go(GoDirection::Next)
+    }
+
+    /// Go to previous cue (manual trigger), on the beat or bar `quantize` asks for.
+    pub fn go_prev(&mut self) -> TimelineEvent {
+        self.request_go(GoDirection::Prev)
+    }
+
+    /// A Go or Back lands now, or waits for its beat or bar. Pressing the same one
+    /// again while it waits lands it at once, for when the music has stopped.
+    fn request_go(&mut self, direction: GoDirection) -> TimelineEvent {
+        if !self.active || self.cues.is_empty() {
+            return TimelineEvent::None;
+        }
+        if self.quantize == GoQuantize::Immediate || self.pending_go == Some(direction) {
+            return self.land(direction);
+        }
+        self.pending_go = Some(direction);
+        TimelineEvent::None
+    }
+
+    fn land(&mut self, direction: GoDirection) -> TimelineEvent {
+        self.pending_go = None;
+        match direction {
+            GoDirection::Next => self.advance(),
+            GoDirection::Prev => self.retreat(),
+        }
+    }
+
+    fn advance(&mut self) -> TimelineEvent {
         if !self.active || self.cues.is_empty() {
             return TimelineEvent::None;
         }
@@ -172,8 +222,7 @@ impl Timeline {
         }
     }
 
-    /// Go to previous cue (manual trigger).
-    pub fn go_prev(&mut self) -> TimelineEvent {
+    fn retreat(&mut self) -> TimelineEvent {
         if !self.active || self.cues.is_empty() {
             return TimelineEvent::None;
         }
@@ -196,6 +245,7 @@ impl Timeline {
         if !self.active {
             return self.start(index);
         }
+        self.pending_go = None;
         let current = self.current_cue_index();
         if current == index {
             return TimelineEvent::None;
@@ -285,17 +335,38 @@ impl Timeline {
             return TimelineEvent::None;
         }
 
+        if let Some(direction) = self.pending_go
+            && self.quantize != GoQuantize::Bar
+        {
+            return self.land(direction);
+        }
+
         if let AdvanceMode::BeatSync { beats_per_cue } = self.advance_mode {
             self.beat_count += 1;
             if self.beat_count >= beats_per_cue {
                 self.beat_count = 0;
-                return self.go_next();
+                return self.advance();
             }
         }
 
         TimelineEvent::None
     }
 
+    /// Feed the downbeat signal (true on the first beat of a bar). Lands a Go or
+    /// Back waiting for the bar.
+    pub fn feed_downbeat(&mut self, downbeat: bool) -> TimelineEvent {
+        if !self.active {
+            return TimelineEvent::None;
+        }
+        let rising = downbeat && !self.last_downbeat;
+        self.last_downbeat = downbeat;
+
+        match self.pending_go {
+            Some(direction) if rising && self.quantize == GoQuantize::Bar => self.land(direction),
+            _ => TimelineEvent::None,
+        }
+    }
+
     /// Get a read-only snapshot for UI.
     pub fn info(&self) -> TimelineInfo {
         let current = self.current_cue_index();
@@ -329,6 +400,8 @@ impl Timeline {
             state,
             loop_mode: self.loop_mode,
             advance_mode: self.advance_mode,
+            quantize: self.quantize,
+            pending_go: self.pending_go,
         }
     }
 
@@ -404,6 +477,9 @@ mod tests {
                 label: None,
                 param_overrides: Vec::new(),
                 transition_beats: None,
+                number: None,
+                notes: String::new(),
+                preset_id: None,
             },
             SceneCue {
                 preset_name: "Preset C".to_string(),
@@ -413,6 +489,9 @@ mod tests {
                 label: None,
                 param_overrides: Vec::new(),
                 transition_beats: None,
+                number: None,
+                notes: String::new(),
+                preset_id: None,
             },
         ]
     }
@@ -585,6 +664,49 @@ mod tests {
         assert!(matches!(ev, TimelineEvent::LoadCue { cue_index: 1 }));
     }
 
+    #[test]
+    fn quantized_go_waits_for_the_beat_and_a_second_press_forces_it() {
+        let cues = vec![SceneCue::new("A"), SceneCue::new("B"), SceneCue::new("C")];
+        let mut tl = Timeline::new(cues, false, AdvanceMode::Manual);
+        tl.quantize = GoQuantize::Beat;
+        tl.start(0);
+
+        assert!(matches!(tl.go_next(), TimelineEvent::None));
+        assert_eq!(tl.info().pending_go, Some(GoDirection::Next));
+        assert!(matches!(
+            tl.feed_beat(true),
+            TimelineEvent::LoadCue { cue_index: 1 }
+        ));
+        assert_eq!(tl.info().pending_go, None);
+
+        // No beat coming: press Go twice.
+        tl.go_next();
+        assert!(matches!(
+            tl.go_next(),
+            TimelineEvent::LoadCue { cue_index: 2 }
+        ));
+    }
+
+    #[test]
+    fn bar_quantized_back_ignores_beats_until_the_downbeat() {
+        let cues = vec![SceneCue::new("A"), SceneCue::new("B")];
+        let mut tl = Timeline::new(cues, false, AdvanceMode::Manual);
+        tl.quantize = GoQuantize::Bar;
+        tl.start(1);
+
+        tl.go_prev();
+        assert!(matches!(tl.feed_beat(true), TimelineEvent::None));
+        assert!(matches!(
+            tl.feed_downbeat(true),
+            TimelineEvent::LoadCue { cue_index: 0 }
+        ));
+
+        // Stopping drops a waiting Go.
+        tl.go_next();
+        tl.stop();
+        assert_eq!(tl.info().pending_go, None);
+    }
+
     #[test]
     fn timeline_info_snapshot() {
         let mut tl = Timeline::new(make_cues(), true, AdvanceMode::Manual);
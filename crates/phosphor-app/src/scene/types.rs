@@ -63,6 +63,31 @@ impl AdvanceMode {
     }
 }
 
+/// When a manual Go or Back lands (#2221). Auto-advance (Timer, Beat Sync) is
+/// already timed and ignores it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum GoQuantize {
+    /// On the press.
+    #[default]
+    Immediate,
+    /// On the next beat.
+    Beat,
+    /// On the next downbeat.
+    Bar,
+}
+
+impl GoQuantize {
+    pub const ALL: &[GoQuantize] = &[GoQuantize::Immediate, GoQuantize::Beat, GoQuantize::Bar];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            GoQuantize::Immediate => "Immediate",
+            GoQuantize::Beat => "Next beat",
+            GoQuantize::Bar => "Next bar",
+        }
+    }
+}
+
 /// A single cue in a scene — references a preset and describes the transition into it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SceneCue {
@@ -86,6 +111,16 @@ pub struct SceneCue {
     /// Optional transition duration in beats (used with BeatSync; overrides transition_secs).
     #[serde(default)]
     pub transition_beats: Option<u32>,
+    /// Cue number as the show calls it ("1", "2.5", "12A"); None numbers by position.
+    #[serde(default)]
+    pub number: Option<String>,
+    /// Operator notes: what happens on stage, what to wait for.
+    #[serde(default)]
+    pub notes: String,
+    /// Stable id of the preset (#2223), looked up before `preset_name` so a
+    /// rename doesn't lose the cue. `None` in scenes saved before ids.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset_id: Option<u64>,
 }
 
 fn default_transition_secs() -> f32 {
@@ -104,9 +139,21 @@ impl SceneCue {
             label: None,
             param_overrides: Vec::new(),
             transition_beats: None,
+            number: None,
+            notes: String::new(),
+            preset_id: None,
         }
     }
 
+    /// Cue number for display: the given one, or the 1-based position `index`.
+    pub fn display_number(&self, index: usize) -> String {
+        self.number
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map_or_else(|| (index + 1).to_string(), str::to_string)
+    }
+
     /// Display label, falling back to preset name.
     #[allow(dead_code)]
     pub fn display_name(&self) -> &str {
@@ -133,6 +180,9 @@ pub struct SceneSet {
     /// How the timeline advances.
     #[serde(default)]
     pub advance_mode: AdvanceMode,
+    /// When a manual Go or Back lands.
+    #[serde(default)]
+    pub quantize: GoQuantize,
 }
 
 fn default_version() -> u32 {
@@ -148,6 +198,7 @@ impl SceneSet {
             cues: Vec::new(),
             loop_mode: false,
             advance_mode: AdvanceMode::default(),
+            quantize: GoQuantize::default(),
         }
     }
 }
@@ -182,6 +233,8 @@ mod tests {
         assert!(cue.hold_secs.is_none());
         assert!(cue.label.is_none());
         assert!(cue.param_overrides.is_empty());
+        assert!(cue.number.is_none());
+        assert!(cue.notes.is_empty());
     }
 
     #[test]
@@ -206,8 +259,12 @@ mod tests {
             label: Some("Build".to_string()),
             param_overrides: Vec::new(),
             transition_beats: None,
+            number: Some("2.5".to_string()),
+            notes: "On the chorus".to_string(),
+            preset_id: Some(42),
         });
         scene.loop_mode = true;
+        scene.quantize = GoQuantize::Bar;
 
         let json = serde_json::to_string_pretty(&scene).unwrap();
         let s2: SceneSet = serde_json::from_str(&json).unwrap();
@@ -216,6 +273,10 @@ mod tests {
         assert_eq!(s2.cues[1].transition, TransitionType::Dissolve);
         assert!((s2.cues[1].transition_secs - 2.0).abs() < 1e-6);
         assert!(s2.loop_mode);
+        assert_eq!(s2.quantize, GoQuantize::Bar);
+        assert_eq!(s2.cues[1].display_number(1), "2.5");
+        assert_eq!(s2.cues[1].notes, "On the chorus");
+        assert_eq!(s2.cues[0].display_number(0), "1");
     }
 
     #[test]
@@ -0,0 +1,236 @@
+//! Schema versions for config and preset files (#2202). Each file type keeps
+//! a list of migrations; a file saved by an older build is upgraded on load
+//! by the ones it missed, after its original is copied aside, and the
+//! upgraded file is queued to be written back. Files from before versioning
+//! count as version 1.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Upgrades a file's JSON by one version, in place.
+pub type Migration = fn(&mut Value);
+
+pub struct Schema {
+    /// What the file is, for log messages.
+    pub name: &'static str,
+    /// `migrations[i]` takes a file from version `i + 1` to `i + 2`, so
+    /// changing a file's layout means appending one here.
+    pub migrations: &'static [Migration],
+}
+
+/// A value saved with a leading `version` field, for types that don't
+/// carry one themselves.
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    version: u32,
+    #[serde(flatten)]
+    body: &'a T,
+}
+
+impl Schema {
+    /// The version this build writes.
+    pub const fn version(&self) -> u32 {
+        self.migrations.len() as u32 + 1
+    }
+
+    /// Upgrade `value` to the current version in place and return the
+    /// version it was saved at. Files from newer builds are left alone.
+    pub fn migrate(&self, value: &mut Value) -> u32 {
+        let found = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .map_or(1, |v| u32::try_from(v).unwrap_or(u32::MAX));
+        if found > self.version() {
+            return found;
+        }
+        for migration in &self.migrations[found.max(1) as usize - 1..] {
+            migration(value);
+        }
+        if let Value::Object(map) = value {
+            map.insert("version".to_string(), self.version().into());
+        }
+        found
+    }
+
+    /// Parse JSON saved at any older version, e.g. a built-in preset.
+    pub fn parse<T: DeserializeOwned>(&self, json: &str) -> serde_json::Result<T> {
+        let mut value: Value = serde_json::from_str(json)?;
+        self.migrate(&mut value);
+        serde_json::from_value(value)
+    }
+
+    /// `value` as pretty JSON with this schema's `version` added.
+    pub fn to_json<T: Serialize>(&self, value: &T) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&Versioned {
+            version: self.version(),
+            body: value,
+        })
+    }
+
+    /// Load the file at `path`, or `None` when it's missing or unreadable.
+    /// Before a file is upgraded, or when it came from a newer build or
+    /// can't be parsed, a copy is kept beside it so the next save can't
+    /// silently drop what this build doesn't understand.
+    pub fn load<T: DeserializeOwned>(&self, path: &Path) -> Option<T> {
+        let Ok(json) = std::fs::read_to_string(path) else {
+            log::info!("No {} at {}, using defaults", self.name, path.display());
+            return None;
+        };
+        let mut value = match serde_json::from_str::<Value>(&json) {
+            Ok(value) => value,
+            Err(e) => {
+                self.unreadable(path, &e);
+                return None;
+            }
+        };
+        let found = self.migrate(&mut value);
+        if found != self.version() {
+            let backup = backup_path(path, &format!("v{found}"));
+            if !backup.exists()
+                && let Err(e) = std::fs::copy(path, &backup)
+            {
+                log::warn!("Failed to back up {}: {e}", path.display());
+            }
+            if found > self.version() {
+                log::warn!(
+                    "{} at {} is from a newer version ({found}, this build reads {}); \
+                     kept a copy at {}",
+                    self.name,
+                    path.display(),
+                    self.version(),
+                    backup.display()
+                );
+                // What this build saves back is its own version
+                if let Value::Object(map) = &mut value {
+                    map.insert("version".to_string(), self.version().into());
+                }
+            }
+        }
+        match serde_json::from_value(value.clone()) {
+            Ok(config) => {
+                if found < self.version() {
+                    log::info!(
+                        "Upgraded {} from version {found} to {}",
+                        self.name,
+                        self.version()
+                    );
+                    if let Ok(json) = serde_json::to_string_pretty(&value) {
+                        crate::persist::write(path.to_path_buf(), json);
+                    }
+                }
+                log::info!("Loaded {} from {}", self.name, path.display());
+                Some(config)
+            }
+            Err(e) => {
+                self.unreadable(path, &e);
+                None
+            }
+        }
+    }
+
+    fn unreadable(&self, path: &Path, e: &serde_json::Error) {
+        let backup = backup_path(path, "unreadable");
+        match std::fs::copy(path, &backup) {
+            Ok(_) => log::warn!(
+                "Failed to parse {}: {e}; kept a copy at {}",
+                self.name,
+                backup.display()
+            ),
+            Err(_) => log::warn!("Failed to parse {}: {e}", self.name),
+        }
+    }
+}
+
+/// `settings.json` → `settings.json.<tag>.bak`.
+fn backup_path(path: &Path, tag: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{tag}.bak"));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Config {
+        version: u32,
+        speed: f32,
+    }
+
+    const SCHEMA: Schema = Schema {
+        name: "test config",
+        migrations: &[
+            // v1 → v2: "rate" was renamed "speed"
+            |v| {
+                if let Some(rate) = v.as_object_mut().and_then(|m| m.remove("rate")) {
+                    v["speed"] = rate;
+                }
+            },
+            // v2 → v3: speed doubled its range
+            |v| v["speed"] = json!(v["speed"].as_f64().unwrap_or(0.0) * 2.0),
+        ],
+    };
+
+    #[test]
+    fn migrations_run_from_the_saved_version() {
+        assert_eq!(SCHEMA.version(), 3);
+
+        // No version field counts as version 1
+        let mut old = json!({ "rate": 1.5 });
+        assert_eq!(SCHEMA.migrate(&mut old), 1);
+        assert_eq!(old, json!({ "version": 3, "speed": 3.0 }));
+
+        let mut v2 = json!({ "version": 2, "speed": 1.0 });
+        assert_eq!(SCHEMA.migrate(&mut v2), 2);
+        assert_eq!(v2["speed"], 2.0);
+
+        let mut newer = json!({ "version": 9, "speed": 1.0 });
+        assert_eq!(SCHEMA.migrate(&mut newer), 9);
+        assert_eq!(newer, json!({ "version": 9, "speed": 1.0 }));
+
+        let current: Config = SCHEMA.parse(r#"{"version":3,"speed":0.5}"#).unwrap();
+        assert_eq!(current.speed, 0.5);
+    }
+
+    #[test]
+    fn load_backs_up_before_upgrading() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.json");
+        std::fs::write(&path, r#"{"rate": 1.0}"#).unwrap();
+        let config: Config = SCHEMA.load(&path).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                version: 3,
+                speed: 2.0
+            }
+        );
+        let backup = dir.path().join("test.json.v1.bak");
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), r#"{"rate": 1.0}"#);
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(SCHEMA.load::<Config>(&path).is_none());
+        assert!(dir.path().join("test.json.unreadable.bak").exists());
+        assert!(
+            SCHEMA
+                .load::<Config>(&dir.path().join("missing.json"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn to_json_puts_version_first() {
+        #[derive(Serialize)]
+        struct Preset {
+            layers: Vec<u32>,
+        }
+        let json = SCHEMA.to_json(&Preset { layers: vec![1] }).unwrap();
+        assert!(json.starts_with("{\n  \"version\": 3,\n  \"layers\""));
+    }
+}
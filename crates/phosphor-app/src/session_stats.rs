@@ -0,0 +1,208 @@
+//! Session statistics (#2241): how the machine held up over a show — frame
+//! rate, dropped frames, peak video memory and audio device changes. Added to
+//! the set report (#2220) beside the presets, for sizing hardware for the next
+//! gig or handing a client a record of the night.
+
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+
+/// A frame this many times the typical interval missed at least one refresh.
+const DROP_FACTOR: f32 = 1.5;
+/// Gaps longer than this are a pause — a dialog, the machine asleep — not a
+/// stretch of dropped frames, and are left out entirely.
+const PAUSE_SECS: f32 = 2.0;
+/// How fast the typical frame interval follows changes, per frame.
+const TYPICAL_RATE: f32 = 0.02;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceChange {
+    /// Time since the session started.
+    pub at: Duration,
+    pub device: String,
+    /// False when the device was lost rather than switched to.
+    pub active: bool,
+}
+
+#[derive(Debug)]
+pub struct SessionStats {
+    started: Instant,
+    frames: u64,
+    frame_secs: f64,
+    /// Slow average of the frame interval, from frames that weren't dropped.
+    typical: Option<f32>,
+    dropped: u64,
+    peak_memory: u64,
+    /// The audio device last seen, and whether it was running.
+    device: Option<(String, bool)>,
+    device_changes: Vec<DeviceChange>,
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            frames: 0,
+            frame_secs: 0.0,
+            typical: None,
+            dropped: 0,
+            peak_memory: 0,
+            device: None,
+            device_changes: Vec::new(),
+        }
+    }
+}
+
+impl SessionStats {
+    /// Count one rendered frame that took `secs` of real time.
+    pub fn frame(&mut self, secs: f32) {
+        if secs <= 0.0 || secs >= PAUSE_SECS {
+            return;
+        }
+        self.frames += 1;
+        self.frame_secs += f64::from(secs);
+        let typical = *self.typical.get_or_insert(secs);
+        if secs > typical * DROP_FACTOR {
+            self.dropped += ((secs / typical).round() as u64).saturating_sub(1).max(1);
+        } else {
+            self.typical = Some(typical + (secs - typical) * TYPICAL_RATE);
+        }
+    }
+
+    /// Note the GPU memory in use now.
+    pub fn memory(&mut self, bytes: u64) {
+        self.peak_memory = self.peak_memory.max(bytes);
+    }
+
+    /// Note the audio input now; a change from last time is recorded.
+    pub fn audio_device(&mut self, name: &str, active: bool) {
+        match &self.device {
+            Some((last, was_active)) if last == name && *was_active == active => return,
+            Some(_) => self.device_changes.push(DeviceChange {
+                at: self.started.elapsed(),
+                device: name.to_string(),
+                active,
+            }),
+            None => {}
+        }
+        self.device = Some((name.to_string(), active));
+    }
+
+    pub fn average_fps(&self) -> Option<f32> {
+        (self.frame_secs > 0.0).then(|| (self.frames as f64 / self.frame_secs) as f32)
+    }
+
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped
+    }
+
+    pub fn peak_memory_bytes(&self) -> u64 {
+        self.peak_memory
+    }
+
+    pub fn device_changes(&self) -> &[DeviceChange] {
+        &self.device_changes
+    }
+
+    /// The statistics as markdown sections for the end of the set report.
+    pub fn report(&self) -> String {
+        let mut out = String::from("\n## Performance\n\n| | |\n|---|---|\n");
+        let fps = self
+            .average_fps()
+            .map_or_else(|| "—".to_string(), |fps| format!("{fps:.1}"));
+        let _ = writeln!(out, "| Average FPS | {fps} |");
+        let share = if self.frames > 0 {
+            format!(
+                " ({:.2}%)",
+                self.dropped as f64 * 100.0 / (self.frames + self.dropped) as f64
+            )
+        } else {
+            String::new()
+        };
+        let _ = writeln!(out, "| Dropped frames | {}{share} |", self.dropped);
+        let memory = if self.peak_memory > 0 {
+            format!("{:.0} MB", self.peak_memory as f64 / (1024.0 * 1024.0))
+        } else {
+            "not reported by this GPU backend".to_string()
+        };
+        let _ = writeln!(out, "| Peak video memory | {memory} |");
+
+        out.push_str("\n## Audio devices\n\n");
+        if self.device_changes.is_empty() {
+            match &self.device {
+                Some((device, _)) => {
+                    let _ = writeln!(out, "{} all session, no changes.", cell(device));
+                }
+                None => out.push_str("No audio input.\n"),
+            }
+            return out;
+        }
+        out.push_str("| Time | Device | |\n|---|---|---|\n");
+        for change in &self.device_changes {
+            let state = if change.active { "switched to" } else { "lost" };
+            let _ = writeln!(
+                out,
+                "| {} | {} | {state} |",
+                clock(change.at),
+                cell(&change.device)
+            );
+        }
+        out
+    }
+}
+
+fn cell(s: &str) -> String {
+    s.replace('|', "\\|")
+}
+
+/// `h:mm:ss`.
+fn clock(d: Duration) -> String {
+    let s = d.as_secs();
+    format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_frames_count_as_the_refreshes_they_missed() {
+        let mut stats = SessionStats::default();
+        for _ in 0..100 {
+            stats.frame(1.0 / 60.0);
+        }
+        assert_eq!(stats.dropped_frames(), 0);
+        stats.frame(3.0 / 60.0);
+        assert_eq!(stats.dropped_frames(), 2);
+        // A pause isn't a drop, and doesn't skew the average
+        stats.frame(30.0);
+        assert_eq!(stats.dropped_frames(), 2);
+        let fps = stats.average_fps().unwrap();
+        assert!((fps - 101.0 / (103.0 / 60.0)).abs() < 0.01, "{fps}");
+    }
+
+    #[test]
+    fn device_changes_skip_the_first_and_repeats() {
+        let mut stats = SessionStats::default();
+        stats.audio_device("Interface", true);
+        stats.audio_device("Interface", true);
+        assert!(stats.report().contains("Interface all session"));
+        stats.audio_device("Interface", false);
+        stats.audio_device("Built-in", true);
+        let changes: Vec<_> = stats
+            .device_changes()
+            .iter()
+            .map(|c| (c.device.as_str(), c.active))
+            .collect();
+        assert_eq!(changes, [("Interface", false), ("Built-in", true)]);
+        let report = stats.report();
+        assert!(report.contains("| Built-in | switched to |"), "{report}");
+    }
+
+    #[test]
+    fn report_keeps_the_peak_memory() {
+        let mut stats = SessionStats::default();
+        stats.memory(512 * 1024 * 1024);
+        stats.memory(256 * 1024 * 1024);
+        assert!(stats.report().contains("| Peak video memory | 512 MB |"));
+    }
+}
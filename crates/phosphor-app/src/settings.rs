@@ -1,7 +1,31 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
+use crate::audio::channels::ChannelMap;
+use crate::audio::envelope::EnvelopeConfig;
+use crate::audio::silence::SilenceConfig;
 use crate::audio::{StructureConfig, TempoConfig};
+use crate::effect::library::LibraryConfig;
+use crate::effect::post_source::PostSettings;
+use crate::gpu::calibration::OutputCalibration;
+use crate::gpu::deterministic::DeterministicParams;
+use crate::gpu::frame_guard::FrameGuardConfig;
+use crate::gpu::freeze::FreezeParams;
+use crate::gpu::layer::DEFAULT_MAX_LAYERS;
+use crate::gpu::master::{DitherParams, MasterParams};
+use crate::installation::InstallationConfig;
+use crate::media::cache::DEFAULT_CACHE_MB;
+use crate::media::types::WebcamMode;
+use crate::preset::attract::AttractConfig;
+use crate::preset::crossfade::PresetCrossfade;
+use crate::preset::surprise::SurpriseConfig;
+use crate::schema::Schema;
+use crate::ui::file_browser::FileDialogKind;
+use crate::ui::panels::editor_keymap::Keymap;
 use crate::ui::theme::ThemeMode;
+use crate::watchdog::WatchdogConfig;
 
 /// How the 7 frequency bands are scaled (A1 #1452).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -66,6 +90,89 @@ impl ParticleQuality {
     }
 }
 
+/// A named machine setup (#2177): the inputs, output window and theme that
+/// change between the home studio and each venue. Switched from Settings or
+/// with `--profile <name>`; everything else in settings stays shared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsProfile {
+    pub name: String,
+    /// `None` is the system default input.
+    pub audio_device: Option<String>,
+    /// Room mic for crowd reactions (#2217); `None` is no mic.
+    pub mic_device: Option<String>,
+    /// `None` leaves MIDI disconnected.
+    pub midi_port: Option<String>,
+    pub osc_rx_port: u16,
+    pub osc_tx_host: String,
+    pub osc_tx_port: u16,
+    /// Monitor name for the output window; `None` leaves the window where it is.
+    pub monitor: Option<String>,
+    /// Window size in physical pixels, used when not fullscreen.
+    pub resolution: Option<[u32; 2]>,
+    pub fullscreen: bool,
+    pub theme: ThemeMode,
+}
+
+impl Default for SettingsProfile {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            audio_device: None,
+            mic_device: None,
+            midi_port: None,
+            osc_rx_port: 9000,
+            osc_tx_host: "127.0.0.1".to_string(),
+            osc_tx_port: 9001,
+            monitor: None,
+            resolution: None,
+            fullscreen: false,
+            theme: ThemeMode::default(),
+        }
+    }
+}
+
+/// What the output shows at launch (#2232).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum StartupMode {
+    /// The default effect on one layer.
+    #[default]
+    Blank,
+    /// The stack as it was when Fosfora last ran, saved edits or not.
+    LastSession,
+    /// A chosen preset.
+    Preset,
+}
+
+impl StartupMode {
+    pub const ALL: &[StartupMode] = &[
+        StartupMode::Blank,
+        StartupMode::LastSession,
+        StartupMode::Preset,
+    ];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::Blank => "Default effect",
+            Self::LastSession => "Last session",
+            Self::Preset => "Preset",
+        }
+    }
+}
+
+/// Launch behaviour for unattended installations (#2232).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct StartupConfig {
+    pub mode: StartupMode,
+    /// Preset id for [`StartupMode::Preset`]; ids survive renames.
+    pub preset: Option<u64>,
+    /// Go fullscreen at launch.
+    pub fullscreen: bool,
+    /// Monitor name to go fullscreen on; `None` is the one the window opens on.
+    pub monitor: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettingsConfig {
     pub version: u32,
@@ -76,8 +183,15 @@ pub struct SettingsConfig {
     pub band_scale: BandScale,
     #[serde(default)]
     pub particle_quality: ParticleQuality,
+    /// Let the quality governor trim particle budgets when frames run long (#2156).
+    /// Opt-in, so the bare `#[serde(default)]` (`false`) is the intended default.
+    #[serde(default)]
+    pub auto_particle_quality: bool,
     #[serde(default)]
     pub webcam_device: Option<u32>,
+    /// Last-picked webcam resolution/fps; presets with a webcam layer override it.
+    #[serde(default)]
+    pub webcam_mode: Option<WebcamMode>,
     #[serde(default)]
     pub use_ffmpeg_webcam: bool,
     /// A18 structure-detector tuning (#1510). `#[serde(default)]` so older settings files
@@ -88,6 +202,9 @@ pub struct SettingsConfig {
     /// load with the built-in defaults (the pre-A7 hardcoded 150 BPM / sigma 1.0).
     #[serde(default)]
     pub tempo: TempoConfig,
+    /// Attack/release of the fast and slow band envelopes (#2219).
+    #[serde(default)]
+    pub envelopes: EnvelopeConfig,
     /// A9 (#1460): reopen the capture device automatically when the watchdog confirms it died.
     ///
     /// `default = "default_true"`, not the bare `#[serde(default)]` every field above uses:
@@ -99,6 +216,108 @@ pub struct SettingsConfig {
     /// Names, not indices — the library re-scans and reorders; names survive it.
     #[serde(default)]
     pub favorite_effects: Vec<String>,
+    /// Effects picked lately (#2245), newest first, by name.
+    #[serde(default)]
+    pub recent_effects: Vec<String>,
+    /// Media files and stream URLs opened lately (#2245), newest first.
+    #[serde(default)]
+    pub recent_media: Vec<String>,
+    /// Folders watched for new media (#2246).
+    #[serde(default)]
+    pub watch_folders: Vec<PathBuf>,
+    /// The first-run tour (#2242) was finished or skipped. Missing reads as
+    /// true, so only a fresh install — no settings file yet — gets the tour.
+    #[serde(default = "default_true")]
+    pub tour_seen: bool,
+    /// RAM budget for decoded preset media (MB, 0 = off). Same `default = …` reasoning as
+    /// `auto_reconnect`: a bare `u32` default would turn the cache off for old settings files.
+    #[serde(default = "default_cache_mb")]
+    pub media_cache_mb: u32,
+    /// Native dialog or the built-in browser for picking media (#2244).
+    #[serde(default)]
+    pub file_dialog: FileDialogKind,
+    /// Global output master grade (#2165); a preset that opts in overrides it.
+    #[serde(default)]
+    pub master: MasterParams,
+    /// Blue-noise output dither (#2236).
+    #[serde(default)]
+    pub dither: DitherParams,
+    /// Per-output colour calibration (#2237).
+    #[serde(default)]
+    pub calibration: OutputCalibration,
+    /// Freeze-frame decay and blend (#2166).
+    #[serde(default)]
+    pub freeze: FreezeParams,
+    /// Layer cap (#2170). Same `default = …` reasoning as `auto_reconnect`:
+    /// a bare `usize` default would leave old settings files with no layers.
+    #[serde(default = "default_max_layers")]
+    pub max_layers: usize,
+    /// Compositor hook file stem (#2252); `None` uses the built-in blend modes.
+    #[serde(default)]
+    pub composite_hook: Option<String>,
+    /// Fixed-step clock and seed for reproducible renders (#2174).
+    #[serde(default)]
+    pub deterministic: DeterministicParams,
+    /// Saved venue profiles (#2177).
+    #[serde(default)]
+    pub profiles: Vec<SettingsProfile>,
+    /// Name of the profile last applied, for the panel.
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Effect pack feed for "Check for new effects" (#2180).
+    #[serde(default)]
+    pub effect_library: LibraryConfig,
+    /// Param panel groups the user folded, by effect name (#2181).
+    #[serde(default)]
+    pub collapsed_param_groups: BTreeMap<String, Vec<String>>,
+    /// Restore the last compiling shader when an editor save breaks it
+    /// (#2188). `default = "default_true"` for the same reason as `auto_reconnect`.
+    #[serde(default = "default_true")]
+    pub shader_auto_rollback: bool,
+    /// Key bindings in the shader editor's code field (#2190).
+    #[serde(default)]
+    pub shader_editor_keymap: Keymap,
+    /// Command that opens a shader in an outside editor (#2191), e.g. `code`;
+    /// `{file}` marks where the path goes. Empty uses the system default app.
+    #[serde(default)]
+    pub external_editor: String,
+    /// What to do when the input goes quiet for a while (#2213).
+    #[serde(default)]
+    pub silence: SilenceConfig,
+    /// Ambient presets to cycle through when nobody's around (#2239).
+    #[serde(default)]
+    pub attract: AttractConfig,
+    /// What to do when the OSC or web controller stops pinging (#2240).
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+    /// GPU time budget per layer before it's suspended (#2243).
+    #[serde(default)]
+    pub frame_guard: FrameGuardConfig,
+    /// Which channels of each input device feed analysis, by device name (#2216).
+    /// Devices left on the default routing are absent.
+    #[serde(default)]
+    pub input_channels: BTreeMap<String, ChannelMap>,
+    /// Second input, analysed for crowd level only (#2217). `None` is off.
+    #[serde(default)]
+    pub mic_device: Option<String>,
+    /// Number keys that load a preset directly (#2222): digit → preset id.
+    #[serde(default)]
+    pub preset_keys: BTreeMap<u8, u64>,
+    /// The two presets on the crossfade fader (#2250).
+    #[serde(default)]
+    pub preset_crossfade: PresetCrossfade,
+    /// Layer count, effect pools and param spread of Surprise me (#2251).
+    #[serde(default)]
+    pub surprise: SurpriseConfig,
+    /// What to show, and where, at launch (#2232).
+    #[serde(default)]
+    pub startup: StartupConfig,
+    /// Unattended installation mode (#2233).
+    #[serde(default)]
+    pub installation: InstallationConfig,
+    /// Default post chain and per-effect post choices (#2234).
+    #[serde(default)]
+    pub post: PostSettings,
 }
 
 /// Serde default for [`SettingsConfig::auto_reconnect`] — see the note on that field.
@@ -106,45 +325,153 @@ fn default_true() -> bool {
     true
 }
 
+fn default_cache_mb() -> u32 {
+    DEFAULT_CACHE_MB
+}
+
+fn default_max_layers() -> usize {
+    DEFAULT_MAX_LAYERS
+}
+
+/// `settings.json` layout history (#2202).
+pub const SCHEMA: Schema = Schema {
+    name: "settings",
+    migrations: &[],
+};
+
 impl Default for SettingsConfig {
     fn default() -> Self {
         Self {
-            version: 1,
+            version: SCHEMA.version(),
             theme: ThemeMode::Dark,
             audio_device: None,
             band_scale: BandScale::default(),
             particle_quality: ParticleQuality::default(),
+            auto_particle_quality: false,
             webcam_device: None,
+            webcam_mode: None,
             use_ffmpeg_webcam: false,
             structure_tuning: StructureConfig::default(),
             tempo: TempoConfig::default(),
+            envelopes: EnvelopeConfig::default(),
             auto_reconnect: true,
             favorite_effects: Vec::new(),
+            recent_effects: Vec::new(),
+            recent_media: Vec::new(),
+            watch_folders: Vec::new(),
+            tour_seen: false,
+            media_cache_mb: DEFAULT_CACHE_MB,
+            file_dialog: FileDialogKind::default(),
+            master: MasterParams::default(),
+            dither: DitherParams::default(),
+            calibration: OutputCalibration::default(),
+            freeze: FreezeParams::default(),
+            max_layers: DEFAULT_MAX_LAYERS,
+            composite_hook: None,
+            deterministic: DeterministicParams::default(),
+            profiles: Vec::new(),
+            active_profile: None,
+            effect_library: LibraryConfig::default(),
+            collapsed_param_groups: BTreeMap::new(),
+            shader_auto_rollback: true,
+            shader_editor_keymap: Keymap::default(),
+            external_editor: String::new(),
+            silence: SilenceConfig::default(),
+            attract: AttractConfig::default(),
+            watchdog: WatchdogConfig::default(),
+            frame_guard: FrameGuardConfig::default(),
+            input_channels: BTreeMap::new(),
+            mic_device: None,
+            preset_keys: BTreeMap::new(),
+            preset_crossfade: PresetCrossfade::default(),
+            surprise: SurpriseConfig::default(),
+            startup: StartupConfig::default(),
+            installation: InstallationConfig::default(),
+            post: PostSettings::default(),
         }
     }
 }
 
+/// Entries kept in each recent list.
+pub const RECENT_MAX: usize = 8;
+
+/// Move `item` to the front of a recent list, dropping the oldest entry past
+/// [`RECENT_MAX`]. Returns false when it was already first, so there's
+/// nothing to save.
+pub fn push_recent(list: &mut Vec<String>, item: &str) -> bool {
+    if list.first().is_some_and(|first| first == item) {
+        return false;
+    }
+    list.retain(|entry| entry != item);
+    list.insert(0, item.to_string());
+    list.truncate(RECENT_MAX);
+    true
+}
+
 impl SettingsConfig {
     pub fn load() -> Self {
-        let Some(config_dir) = dirs::config_dir() else {
-            return Self::default();
-        };
-        let path = config_dir.join("phosphor").join("settings.json");
-        match std::fs::read_to_string(&path) {
-            Ok(json) => serde_json::from_str(&json).unwrap_or_default(),
-            Err(_) => Self::default(),
+        SCHEMA
+            .load(&crate::paths::config_dir().join("settings.json"))
+            .unwrap_or_default()
+    }
+
+    /// A saved profile by name, ignoring case so `--profile` is forgiving.
+    pub fn profile(&self, name: &str) -> Option<&SettingsProfile> {
+        self.profiles
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Add a profile, replacing any with the same name.
+    pub fn upsert_profile(&mut self, profile: SettingsProfile) {
+        if let Some(existing) = self
+            .profiles
+            .iter_mut()
+            .find(|p| p.name.eq_ignore_ascii_case(&profile.name))
+        {
+            *existing = profile;
+        } else {
+            self.profiles.push(profile);
+        }
+    }
+
+    pub fn remove_profile(&mut self, name: &str) {
+        self.profiles.retain(|p| !p.name.eq_ignore_ascii_case(name));
+        if self
+            .active_profile
+            .as_deref()
+            .is_some_and(|a| a.eq_ignore_ascii_case(name))
+        {
+            self.active_profile = None;
+        }
+    }
+
+    pub fn param_group_collapsed(&self, effect: &str, group: &str) -> bool {
+        self.collapsed_param_groups
+            .get(effect)
+            .is_some_and(|groups| groups.iter().any(|g| g == group))
+    }
+
+    /// Fold or unfold one param group of an effect. Effects with every
+    /// group open drop out of the map so settings.json stays small.
+    pub fn toggle_param_group(&mut self, effect: &str, group: &str) {
+        let groups = self
+            .collapsed_param_groups
+            .entry(effect.to_string())
+            .or_default();
+        if let Some(pos) = groups.iter().position(|g| g == group) {
+            groups.remove(pos);
+        } else {
+            groups.push(group.to_string());
+        }
+        if groups.is_empty() {
+            self.collapsed_param_groups.remove(effect);
         }
     }
 
     pub fn save(&self) {
-        let Some(config_dir) = dirs::config_dir() else {
-            return;
-        };
-        let dir = config_dir.join("phosphor");
-        let _ = std::fs::create_dir_all(&dir);
-        let path = dir.join("settings.json");
         if let Ok(json) = serde_json::to_string_pretty(self) {
-            let _ = std::fs::write(path, json);
+            crate::persist::write(crate::paths::config_dir().join("settings.json"), json);
         }
     }
 }
@@ -183,6 +510,14 @@ mod tests {
 
     // ---- Additional tests ----
 
+    #[test]
+    fn unversioned_settings_keep_their_values() {
+        // A file without `version` used to fail to parse and reset everything
+        let c: SettingsConfig = SCHEMA.parse(r#"{"theme":"Light"}"#).unwrap();
+        assert_eq!(c.version, SCHEMA.version());
+        assert_eq!(c.theme, ThemeMode::Light);
+    }
+
     #[test]
     fn particle_quality_serde_roundtrip() {
         for &q in ParticleQuality::ALL {
@@ -199,6 +534,36 @@ mod tests {
         assert_eq!(c.particle_quality, ParticleQuality::High);
     }
 
+    #[test]
+    fn auto_particle_quality_defaults_off() {
+        let json = r#"{"version":1,"theme":"Dark"}"#;
+        let c: SettingsConfig = serde_json::from_str(json).unwrap();
+        assert!(!c.auto_particle_quality);
+    }
+
+    #[test]
+    fn startup_defaults_to_the_default_effect_windowed() {
+        let json = r#"{"version":1,"theme":"Dark"}"#;
+        let c: SettingsConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(c.startup, StartupConfig::default());
+        assert_eq!(c.startup.mode, StartupMode::Blank);
+        assert!(!c.startup.fullscreen);
+
+        let json = r#"{"version":1,"theme":"Dark","startup":{"mode":"Preset","preset":7}}"#;
+        let c: SettingsConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(c.startup.mode, StartupMode::Preset);
+        assert_eq!(c.startup.preset, Some(7));
+        assert_eq!(c.startup.monitor, None);
+    }
+
+    #[test]
+    fn shader_auto_rollback_defaults_on() {
+        let json = r#"{"version":1,"theme":"Dark"}"#;
+        let c: SettingsConfig = serde_json::from_str(json).unwrap();
+        assert!(c.shader_auto_rollback);
+        assert_eq!(c.shader_editor_keymap, Keymap::Standard);
+    }
+
     #[test]
     fn favorite_effects_default_from_missing_field() {
         // Settings files written before favorites existed must load empty, not error.
@@ -218,6 +583,39 @@ mod tests {
         assert_eq!(c2.favorite_effects, c.favorite_effects);
     }
 
+    #[test]
+    fn recent_lists_move_repeats_to_the_front() {
+        let mut list = Vec::new();
+        for item in ["a", "b", "c"] {
+            assert!(push_recent(&mut list, item));
+        }
+        assert!(push_recent(&mut list, "a"));
+        assert_eq!(list, ["a", "c", "b"]);
+        assert!(!push_recent(&mut list, "a"));
+        for i in 0..RECENT_MAX {
+            push_recent(&mut list, &i.to_string());
+        }
+        assert_eq!(list.len(), RECENT_MAX);
+        assert_eq!(list[0], (RECENT_MAX - 1).to_string());
+    }
+
+    #[test]
+    fn param_groups_collapse_per_effect() {
+        let mut c = SettingsConfig::default();
+        c.toggle_param_group("Beam", "Color");
+        assert!(c.param_group_collapsed("Beam", "Color"));
+        assert!(!c.param_group_collapsed("Beam", "Motion"));
+        assert!(!c.param_group_collapsed("Plasma", "Color"));
+
+        let json = serde_json::to_string(&c).unwrap();
+        let c2: SettingsConfig = serde_json::from_str(&json).unwrap();
+        assert!(c2.param_group_collapsed("Beam", "Color"));
+
+        c.toggle_param_group("Beam", "Color");
+        assert!(!c.param_group_collapsed("Beam", "Color"));
+        assert!(c.collapsed_param_groups.is_empty());
+    }
+
     #[test]
     fn settings_config_all_themes_roundtrip() {
         for mode in ThemeMode::ALL {
@@ -283,6 +681,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn max_layers_defaults_when_missing() {
+        let json = r#"{"version":1,"theme":"Dark"}"#;
+        let c: SettingsConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(c.max_layers, DEFAULT_MAX_LAYERS);
+    }
+
+    #[test]
+    fn profiles_default_empty_and_fill_missing_fields() {
+        let json = r#"{"version":1,"theme":"Dark"}"#;
+        let c: SettingsConfig = serde_json::from_str(json).unwrap();
+        assert!(c.profiles.is_empty());
+        assert!(c.active_profile.is_none());
+
+        let p: SettingsProfile = serde_json::from_str(r#"{"name":"Club"}"#).unwrap();
+        assert_eq!(p.osc_rx_port, 9000);
+        assert_eq!(p.osc_tx_port, 9001);
+        assert!(p.resolution.is_none());
+    }
+
+    #[test]
+    fn profile_lookup_upsert_and_remove_ignore_case() {
+        let mut c = SettingsConfig::default();
+        c.upsert_profile(SettingsProfile {
+            name: "Studio".to_string(),
+            ..Default::default()
+        });
+        c.upsert_profile(SettingsProfile {
+            name: "studio".to_string(),
+            osc_rx_port: 8000,
+            ..Default::default()
+        });
+        assert_eq!(c.profiles.len(), 1);
+        assert_eq!(c.profile(" STUDIO ").unwrap().osc_rx_port, 8000);
+
+        c.active_profile = Some("Studio".to_string());
+        c.remove_profile("studio");
+        assert!(c.profile("Studio").is_none());
+        assert!(c.active_profile.is_none());
+    }
+
     #[test]
     fn settings_config_old_cvd_theme_falls_back_to_default() {
         // Users with old CVD theme names in settings.json should fall back to Dark
@@ -0,0 +1,120 @@
+//! Open shaders in the user's own editor (#2191). Saves made there reach the
+//! app through [`super::ShaderWatcher`] like any other write, and the app
+//! rescans the active shaders when its window regains focus, in case the
+//! watcher missed an editor's atomic rename.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Placeholder for the shader path in a configured editor command.
+pub const FILE_PLACEHOLDER: &str = "{file}";
+
+/// Argument list that opens `file` with `command`. The command is split on
+/// whitespace, with double quotes grouping a path that has spaces in it.
+/// `{file}` marks where the path goes; without it the path is appended.
+/// An empty command uses the platform's default opener.
+pub fn command_line(command: &str, file: &Path) -> Vec<String> {
+    let file = file.to_string_lossy();
+    let mut args = split_args(command);
+    if args.is_empty() {
+        args = default_opener();
+    }
+    if args.iter().any(|a| a.contains(FILE_PLACEHOLDER)) {
+        for arg in &mut args {
+            *arg = arg.replace(FILE_PLACEHOLDER, &file);
+        }
+    } else {
+        args.push(file.into_owned());
+    }
+    args
+}
+
+fn default_opener() -> Vec<String> {
+    let args: &[&str] = if cfg!(target_os = "macos") {
+        &["open"]
+    } else if cfg!(windows) {
+        // `start` treats its first quoted argument as a window title.
+        &["cmd", "/C", "start", ""]
+    } else {
+        &["xdg-open"]
+    };
+    args.iter().map(|s| (*s).to_string()).collect()
+}
+
+fn split_args(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let (mut quoted, mut started) = (false, false);
+    for c in command.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    args.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            c => {
+                current.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        args.push(current);
+    }
+    args
+}
+
+/// Launch `command` on `file` without waiting for the editor to close.
+pub fn open(command: &str, file: &Path) -> std::io::Result<()> {
+    let args = command_line(command, file);
+    let Some((program, rest)) = args.split_first() else {
+        return Err(std::io::Error::other("empty editor command"));
+    };
+    let mut child = Command::new(program)
+        .args(rest)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    log::info!("Opened {} with {program}", file.display());
+    // Reap it when it exits so it doesn't linger as a zombie.
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_places_the_file() {
+        let file = Path::new("/fx/my shader.wgsl");
+        assert_eq!(
+            command_line("code -r", file),
+            vec!["code", "-r", "/fx/my shader.wgsl"]
+        );
+        assert_eq!(
+            command_line("subl {file}:1", file),
+            vec!["subl", "/fx/my shader.wgsl:1"]
+        );
+        assert_eq!(
+            command_line(r#""C:\Program Files\Zed\zed.exe" --new"#, file),
+            vec![
+                r"C:\Program Files\Zed\zed.exe",
+                "--new",
+                "/fx/my shader.wgsl"
+            ]
+        );
+        assert_eq!(
+            command_line("  ", file).last().unwrap(),
+            "/fx/my shader.wgsl"
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Warnings and performance hints for a shader that compiles (#2192). naga
+//! only reports hard errors, so these come from its IR and validation info,
+//! plus a look at `for` loop bounds in the source.
+//!
+//! Shaders are linted as compiled, behind the uniform block and library, but
+//! only the user's part is reported on, with lines counted from its start.
+
+use std::ops::Range;
+
+use regex::Regex;
+use wgpu::naga;
+use wgpu::naga::valid::{Capabilities, FunctionInfo, ModuleInfo, ValidationFlags, Validator};
+
+/// Loops that can run this many times per pixel, nested loops multiplied
+/// out, get a hint.
+pub const LOOP_HINT_ITERATIONS: u64 = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Hint,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 1-based line in the user's shader.
+    pub line: usize,
+    pub message: String,
+}
+
+/// Lint `source`, a full shader whose user-written part starts at byte
+/// `user_start`. Returns nothing when it doesn't parse; the compile error
+/// covers that. Sorted by line.
+pub fn lint(source: &str, user_start: usize) -> Vec<Diagnostic> {
+    let Ok(module) = naga::front::wgsl::parse_str(source) else {
+        return Vec::new();
+    };
+    let mut lint = Lint {
+        source,
+        user_start,
+        out: Vec::new(),
+    };
+    let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+        .validate(&module)
+        .ok();
+    if let Some(info) = &info {
+        lint.unused_globals(&module, info);
+        for (handle, function) in module.functions.iter() {
+            lint.block(function, &info[handle], &function.body, false);
+        }
+        for (i, ep) in module.entry_points.iter().enumerate() {
+            lint.block(
+                &ep.function,
+                info.get_entry_point(i),
+                &ep.function.body,
+                false,
+            );
+        }
+    }
+    lint.loops();
+    let mut out = lint.out;
+    out.sort_by_key(|d| (d.line, d.severity));
+    out.dedup();
+    out
+}
+
+struct Lint<'a> {
+    source: &'a str,
+    user_start: usize,
+    out: Vec<Diagnostic>,
+}
+
+impl Lint<'_> {
+    /// Report at byte `offset` of the full source, if it's in user code.
+    fn push(&mut self, offset: usize, severity: Severity, message: String) {
+        if offset < self.user_start {
+            return;
+        }
+        let Some(user) = self.source.get(self.user_start..offset) else {
+            return;
+        };
+        self.out.push(Diagnostic {
+            severity,
+            line: user.matches('\n').count() + 1,
+            message,
+        });
+    }
+
+    fn push_span(&mut self, span: naga::Span, severity: Severity, message: String) {
+        if let Some(range) = span.to_range() {
+            self.push(range.start, severity, message);
+        }
+    }
+
+    /// Module-scope variables no entry point touches. Unused bindings still
+    /// take a slot in the pipeline layout.
+    fn unused_globals(&mut self, module: &naga::Module, info: &ModuleInfo) {
+        for (handle, global) in module.global_variables.iter() {
+            let used =
+                (0..module.entry_points.len()).any(|i| !info.get_entry_point(i)[handle].is_empty());
+            if used {
+                continue;
+            }
+            let name = global.name.as_deref().unwrap_or("_");
+            let message = match &global.binding {
+                Some(b) => format!(
+                    "`{name}` (@group({}) @binding({})) is never used",
+                    b.group, b.binding
+                ),
+                None => format!("`{name}` is never used"),
+            };
+            self.push_span(
+                module.global_variables.get_span(handle),
+                Severity::Warning,
+                message,
+            );
+        }
+    }
+
+    /// Derivatives, and samples that pick their mip level from derivatives,
+    /// inside a branch on a per-pixel value: neighboring pixels that take
+    /// the other path leave them undefined.
+    fn block(
+        &mut self,
+        function: &naga::Function,
+        info: &FunctionInfo,
+        block: &naga::Block,
+        non_uniform: bool,
+    ) {
+        use naga::Statement as S;
+        let varies =
+            |e: naga::Handle<naga::Expression>| info[e].uniformity.non_uniform_result.is_some();
+        for statement in block {
+            match statement {
+                S::Emit(range) if non_uniform => {
+                    for e in range.clone() {
+                        let what = match &function.expressions[e] {
+                            naga::Expression::Derivative { .. } => "Derivative",
+                            naga::Expression::ImageSample {
+                                level: naga::SampleLevel::Auto | naga::SampleLevel::Bias(_),
+                                gather: None,
+                                ..
+                            } => "textureSample",
+                            _ => continue,
+                        };
+                        self.push_span(
+                            function.expressions.get_span(e),
+                            Severity::Warning,
+                            format!(
+                                "{what} inside a per-pixel branch is undefined where \
+                                 neighboring pixels branch differently; move it before \
+                                 the branch or use textureSampleLevel"
+                            ),
+                        );
+                    }
+                }
+                S::Block(inner) => self.block(function, info, inner, non_uniform),
+                S::If {
+                    condition,
+                    accept,
+                    reject,
+                } => {
+                    let branch = non_uniform || varies(*condition);
+                    self.block(function, info, accept, branch);
+                    self.block(function, info, reject, branch);
+                }
+                S::Switch { selector, cases } => {
+                    let branch = non_uniform || varies(*selector);
+                    for case in cases {
+                        self.block(function, info, &case.body, branch);
+                    }
+                }
+                S::Loop {
+                    body, continuing, ..
+                } => {
+                    self.block(function, info, body, non_uniform);
+                    self.block(function, info, continuing, non_uniform);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// `for` loops with a literal bound, multiplied through their nesting.
+    /// Each nest is reported once, at the loop that crosses the limit.
+    fn loops(&mut self) {
+        let user = comments_blanked(&self.source[self.user_start..]);
+        let header =
+            Regex::new(r"\bfor\s*\([^;]*;\s*[A-Za-z_]\w*\s*(<=?)\s*(\d+)[iu]?\s*;").unwrap();
+        // (body byte range, iterations including enclosing loops)
+        let mut open: Vec<(Range<usize>, u64)> = Vec::new();
+        for caps in header.captures_iter(&user) {
+            let (Some(all), Some(op), Some(bound)) = (caps.get(0), caps.get(1), caps.get(2)) else {
+                continue;
+            };
+            let Ok(n) = bound.as_str().parse::<u64>() else {
+                continue;
+            };
+            let n = n + u64::from(op.as_str() == "<=");
+            open.retain(|(body, _)| body.contains(&all.start()));
+            let outer = open.last().map_or(1, |(_, total)| *total);
+            let total = outer.saturating_mul(n);
+            if total >= LOOP_HINT_ITERATIONS && outer < LOOP_HINT_ITERATIONS {
+                let message = if outer > 1 {
+                    format!("Nested loops run up to {total} times per pixel")
+                } else {
+                    format!("Loop runs up to {total} times per pixel")
+                };
+                self.push(
+                    self.user_start + all.start(),
+                    Severity::Hint,
+                    format!("{message}; fewer steps or an early exit keeps it fast"),
+                );
+            }
+            if let Some(body) = loop_body(&user, all.end()) {
+                open.push((body, total));
+            }
+        }
+    }
+}
+
+/// `text` with `//` comments replaced by spaces, so offsets still line up.
+fn comments_blanked(text: &str) -> String {
+    text.lines()
+        .map(|line| match line.find("//") {
+            Some(i) => format!("{}{}", &line[..i], " ".repeat(line.len() - i)),
+            None => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Byte range of the braced body following a `for` header that ends at
+/// `from`, up to its matching `}`.
+fn loop_body(text: &str, from: usize) -> Option<Range<usize>> {
+    let open = from + text[from..].find('{')?;
+    let mut depth = 0usize;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open..open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRELUDE: &str = "@group(0) @binding(0) var<uniform> u: vec4f;\n";
+
+    fn lint_user(user: &str) -> Vec<Diagnostic> {
+        let source = format!("{PRELUDE}{user}");
+        lint(&source, PRELUDE.len())
+    }
+
+    #[test]
+    fn unused_bindings_in_user_code_only() {
+        // `u` from the prelude is unused too, but isn't the author's.
+        let d = lint_user(
+            "@group(0) @binding(1) var t: texture_2d<f32>;\n\
+             @fragment fn fs_main() -> @location(0) vec4f { return vec4f(1.0); }\n",
+        );
+        assert_eq!(d.len(), 1);
+        assert_eq!(d[0].line, 1);
+        assert_eq!(d[0].severity, Severity::Warning);
+        assert!(d[0].message.contains("@binding(1)"));
+    }
+
+    #[test]
+    fn derivative_in_a_per_pixel_branch() {
+        let d = lint_user(
+            "@group(0) @binding(1) var t: texture_2d<f32>;\n\
+             @group(0) @binding(2) var s: sampler;\n\
+             @fragment fn fs_main(@location(0) uv: vec2f) -> @location(0) vec4f {\n\
+             \x20   var c = textureSample(t, s, uv) * u.x;\n\
+             \x20   if (u.y > 0.5) { c += textureSample(t, s, uv); }\n\
+             \x20   if (uv.x > 0.5) {\n\
+             \x20       c += textureSample(t, s, uv);\n\
+             \x20       c += textureSampleLevel(t, s, uv, 0.0);\n\
+             \x20   }\n\
+             \x20   return c;\n\
+             }\n",
+        );
+        let lines: Vec<usize> = d.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![7]);
+        assert!(d[0].message.starts_with("textureSample"));
+    }
+
+    #[test]
+    fn big_and_nested_loops_get_hints() {
+        let d = lint_user(
+            "@fragment fn fs_main() -> @location(0) vec4f {\n\
+             \x20   var a = 0.0;\n\
+             \x20   for (var i = 0; i < 8; i++) { a += u.x; }\n\
+             \x20   for (var i = 0; i < 32; i++) {\n\
+             \x20       for (var j = 0u; j <= 15u; j++) {\n\
+             \x20           for (var k = 0; k < 4; k++) { a += u.y; }\n\
+             \x20       }\n\
+             \x20   }\n\
+             \x20   // for (var i = 0; i < 9999; i++) {}\n\
+             \x20   for (var i = 0; i < 300; i++) { a += u.z; }\n\
+             \x20   return vec4f(a);\n\
+             }\n",
+        );
+        let hints: Vec<(usize, &str)> = d
+            .iter()
+            .filter(|d| d.severity == Severity::Hint)
+            .map(|d| (d.line, d.message.as_str()))
+            .collect();
+        assert_eq!(hints.len(), 2);
+        assert_eq!(hints[0].0, 5);
+        assert!(hints[0].1.starts_with("Nested loops run up to 512"));
+        assert_eq!(hints[1].0, 10);
+        assert!(hints[1].1.starts_with("Loop runs up to 300"));
+    }
+}
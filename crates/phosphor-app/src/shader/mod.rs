@@ -1,3 +1,5 @@
+pub mod external_editor;
 pub mod hot_reload;
+pub mod lint;
 
 pub use hot_reload::ShaderWatcher;
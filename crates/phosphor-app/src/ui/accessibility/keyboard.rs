@@ -15,6 +15,9 @@ impl Shortcuts {
         &[
             ("D", "Toggle UI"),
             ("F", "Fullscreen"),
+            ("P", "Particle burst"),
+            ("K", "Blackout"),
+            ("H", "Freeze frame"),
             ("Esc", "Quit"),
             ("Tab", "Next widget"),
             ("Shift+Tab", "Prev widget"),
@@ -0,0 +1,431 @@
+//! Built-in file browser (#2244), for systems where the native dialog is
+//! unreliable — some Linux desktop portals leave rfd's dialog thread hanging,
+//! and with it any way to add media. Drawn in the app's own egui pass, so it
+//! can't deadlock, and it works the same on every platform.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use egui::{
+    Align, Align2, Color32, Context, Id, Key, Layout, Rect, RichText, ScrollArea, Sense,
+    TextureHandle, Vec2,
+};
+use serde::{Deserialize, Serialize};
+
+use super::theme::colors::theme_colors;
+use super::theme::tokens::*;
+use crate::media::thumbs::ThumbCache;
+
+/// Row height and thumbnail box with thumbnails on (#2247).
+const THUMB_ROW: f32 = 36.0;
+const THUMB_BOX: Vec2 = Vec2::new(48.0, 32.0);
+
+/// Which file dialog picks media, set in Settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum FileDialogKind {
+    /// The operating system's dialog, through rfd.
+    #[default]
+    System,
+    /// [`FileBrowser`], drawn by the app itself.
+    BuiltIn,
+}
+
+impl FileDialogKind {
+    pub const ALL: &[FileDialogKind] = &[FileDialogKind::System, FileDialogKind::BuiltIn];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            FileDialogKind::System => "System",
+            FileDialogKind::BuiltIn => "Built-in",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// `dir`'s subdirectories, then its files with one of `extensions`
+/// (lowercase, without the dot; empty lists every file), each sorted by
+/// name. Hidden entries are left out.
+pub fn list_dir(dir: &Path, extensions: &[&str]) -> io::Result<Vec<DirEntry>> {
+    let mut entries: Vec<DirEntry> = std::fs::read_dir(dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                return None;
+            }
+            let path = entry.path();
+            // Follows symlinks, so a linked folder opens like any other
+            let is_dir = path.is_dir();
+            if !is_dir && !matches_extension(&path, extensions) {
+                return None;
+            }
+            Some(DirEntry { name, path, is_dir })
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    Ok(entries)
+}
+
+fn matches_extension(path: &Path, extensions: &[&str]) -> bool {
+    extensions.is_empty()
+        || path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| extensions.contains(&e.to_lowercase().as_str()))
+}
+
+/// Where the browser opens when nothing was browsed yet this session.
+pub fn default_dir() -> PathBuf {
+    dirs::video_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Called with the chosen paths. Dropped unused when the browser is
+/// cancelled, so a channel sender inside reads as a cancel on the other end.
+type OnPick = Box<dyn FnOnce(Vec<PathBuf>)>;
+
+pub struct FileBrowser {
+    title: &'static str,
+    dir: PathBuf,
+    extensions: Vec<&'static str>,
+    /// Untick "Media only" to list every file.
+    filtered: bool,
+    multiple: bool,
+    entries: Vec<DirEntry>,
+    selected: BTreeSet<PathBuf>,
+    /// The location box, applied with Enter.
+    location: String,
+    error: Option<String>,
+    on_pick: Option<OnPick>,
+    thumbs: Option<ThumbCache>,
+    /// Uploaded thumbnails and their size/duration line, for this folder.
+    textures: HashMap<PathBuf, (TextureHandle, String)>,
+}
+
+impl FileBrowser {
+    pub fn new(
+        title: &'static str,
+        dir: PathBuf,
+        extensions: Vec<&'static str>,
+        multiple: bool,
+        on_pick: impl FnOnce(Vec<PathBuf>) + 'static,
+    ) -> Self {
+        let mut browser = Self {
+            title,
+            dir: PathBuf::new(),
+            extensions,
+            filtered: true,
+            multiple,
+            entries: Vec::new(),
+            selected: BTreeSet::new(),
+            location: String::new(),
+            error: None,
+            on_pick: Some(Box::new(on_pick)),
+            thumbs: None,
+            textures: HashMap::new(),
+        };
+        browser.open(dir);
+        browser
+    }
+
+    /// Show a thumbnail and size/duration for each file, built by `thumbs`.
+    pub fn with_thumbnails(mut self, thumbs: ThumbCache) -> Self {
+        self.thumbs = Some(thumbs);
+        self
+    }
+
+    /// The directory on show, to open the next browser in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn open(&mut self, dir: PathBuf) {
+        let extensions: &[&str] = if self.filtered { &self.extensions } else { &[] };
+        match list_dir(&dir, extensions) {
+            Ok(entries) => {
+                self.entries = entries;
+                self.textures.clear();
+                self.selected.clear();
+                self.error = None;
+                self.location = dir.display().to_string();
+                self.dir = dir;
+            }
+            // Stay where we were, and say why
+            Err(e) => {
+                self.error = Some(format!("Can't open {}: {e}", dir.display()));
+                self.location = self.dir.display().to_string();
+            }
+        }
+    }
+
+    fn pick(&mut self, paths: Vec<PathBuf>) {
+        if let Some(on_pick) = self.on_pick.take() {
+            on_pick(paths);
+        }
+    }
+
+    /// Draw the browser. Returns false once it has closed, picked or not.
+    pub fn show(&mut self, ctx: &Context) -> bool {
+        let tc = theme_colors(ctx);
+        let mut open = true;
+        let mut navigate: Option<PathBuf> = None;
+        let mut chosen: Option<Vec<PathBuf>> = None;
+
+        egui::Window::new(self.title)
+            .id(Id::new("file_browser"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_size([460.0, 360.0])
+            .pivot(Align2::CENTER_CENTER)
+            .default_pos(ctx.content_rect().center())
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let up = self.dir.parent().map(Path::to_path_buf);
+                    if ui
+                        .add_enabled(up.is_some(), egui::Button::new("Up"))
+                        .on_hover_text("Parent folder")
+                        .clicked()
+                    {
+                        navigate = up;
+                    }
+                    if ui.button("Home").clicked() {
+                        navigate = dirs::home_dir();
+                    }
+                    let location = ui.add(
+                        egui::TextEdit::singleline(&mut self.location)
+                            .desired_width(f32::INFINITY)
+                            .font(egui::FontId::proportional(SMALL_SIZE)),
+                    );
+                    if location.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                        navigate = Some(PathBuf::from(self.location.trim()));
+                    }
+                });
+                if let Some(err) = &self.error {
+                    ui.label(RichText::new(err).size(SMALL_SIZE).color(tc.error));
+                }
+                ui.separator();
+
+                let list_height = (ui.available_height() - 36.0).max(120.0);
+                if self.entries.is_empty() {
+                    let empty = if self.filtered {
+                        "No media files here"
+                    } else {
+                        "Empty folder"
+                    };
+                    ui.label(
+                        RichText::new(empty)
+                            .size(SMALL_SIZE)
+                            .color(tc.text_secondary),
+                    );
+                }
+                let row_height = if self.thumbs.is_some() {
+                    THUMB_ROW
+                } else {
+                    ui.spacing().interact_size.y
+                };
+                // Only the rows on screen are drawn, so only they queue thumbnails
+                ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .max_height(list_height)
+                    .show_rows(ui, row_height, self.entries.len(), |ui, rows| {
+                        let toggle = self.multiple && ui.input(|i| i.modifiers.command);
+                        for entry in &self.entries[rows] {
+                            if entry.is_dir {
+                                let r = ui
+                                    .horizontal(|ui| {
+                                        ui.set_height(row_height);
+                                        if self.thumbs.is_some() {
+                                            ui.add_space(THUMB_BOX.x + ui.spacing().item_spacing.x);
+                                        }
+                                        ui.selectable_label(
+                                            false,
+                                            RichText::new(format!("{}/", entry.name))
+                                                .size(SMALL_SIZE)
+                                                .color(tc.text_secondary),
+                                        )
+                                    })
+                                    .inner;
+                                if r.clicked() {
+                                    navigate = Some(entry.path.clone());
+                                }
+                                continue;
+                            }
+                            let thumb = self.thumbs.as_ref().and_then(|thumbs| {
+                                thumb_texture(ctx, thumbs, &mut self.textures, &entry.path)
+                            });
+                            let is_selected = self.selected.contains(&entry.path);
+                            let r = ui
+                                .horizontal(|ui| {
+                                    ui.set_height(row_height);
+                                    if self.thumbs.is_some() {
+                                        let (rect, _) =
+                                            ui.allocate_exact_size(THUMB_BOX, Sense::hover());
+                                        if let Some((texture, _)) = &thumb {
+                                            paint_thumb(ui, rect, texture);
+                                        }
+                                    }
+                                    let r = ui.selectable_label(
+                                        is_selected,
+                                        RichText::new(&entry.name).size(SMALL_SIZE),
+                                    );
+                                    if let Some((_, summary)) = &thumb {
+                                        ui.label(
+                                            RichText::new(summary)
+                                                .size(SMALL_SIZE)
+                                                .color(tc.text_secondary),
+                                        );
+                                    }
+                                    r
+                                })
+                                .inner;
+                            if r.double_clicked() {
+                                chosen = Some(vec![entry.path.clone()]);
+                            } else if r.clicked() {
+                                if !toggle {
+                                    self.selected.clear();
+                                }
+                                if is_selected && toggle {
+                                    self.selected.remove(&entry.path);
+                                } else {
+                                    self.selected.insert(entry.path.clone());
+                                }
+                            }
+                        }
+                    });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let mut filtered = self.filtered;
+                    if ui
+                        .checkbox(&mut filtered, RichText::new("Media only").size(SMALL_SIZE))
+                        .changed()
+                    {
+                        self.filtered = filtered;
+                        navigate = Some(self.dir.clone());
+                    }
+                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                        let label = if self.multiple && self.selected.len() > 1 {
+                            format!("Add {}", self.selected.len())
+                        } else {
+                            "Open".to_string()
+                        };
+                        if ui
+                            .add_enabled(!self.selected.is_empty(), egui::Button::new(label))
+                            .clicked()
+                        {
+                            chosen = Some(self.selected.iter().cloned().collect());
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.on_pick = None;
+                        }
+                        if self.multiple {
+                            ui.label(
+                                RichText::new("Ctrl-click picks several")
+                                    .size(SMALL_SIZE)
+                                    .color(tc.text_secondary),
+                            );
+                        }
+                    });
+                });
+            });
+
+        if self.thumbs.as_ref().is_some_and(|t| t.pending() > 0) {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+        if !open {
+            self.on_pick = None;
+        }
+        if let Some(paths) = chosen {
+            self.pick(paths);
+        }
+        if let Some(dir) = navigate {
+            self.open(dir);
+        }
+        self.on_pick.is_some()
+    }
+}
+
+/// `path`'s thumbnail as a texture, uploaded the first time it's ready.
+fn thumb_texture(
+    ctx: &Context,
+    thumbs: &ThumbCache,
+    textures: &mut HashMap<PathBuf, (TextureHandle, String)>,
+    path: &Path,
+) -> Option<(TextureHandle, String)> {
+    if let Some(uploaded) = textures.get(path) {
+        return Some(uploaded.clone());
+    }
+    let thumb = thumbs.get(path)?;
+    let image = egui::ColorImage::from_rgba_unmultiplied(
+        [thumb.width as usize, thumb.height as usize],
+        &thumb.rgba,
+    );
+    let texture = ctx.load_texture(
+        format!("thumb:{}", path.display()),
+        image,
+        egui::TextureOptions::LINEAR,
+    );
+    let uploaded = (texture, thumb.meta.summary());
+    textures.insert(path.to_path_buf(), uploaded.clone());
+    Some(uploaded)
+}
+
+/// Draw `texture` centered in `rect`, keeping its aspect.
+fn paint_thumb(ui: &egui::Ui, rect: Rect, texture: &TextureHandle) {
+    let size = texture.size_vec2();
+    let scale = (rect.width() / size.x).min(rect.height() / size.y);
+    ui.painter().image(
+        texture.id(),
+        Rect::from_center_size(rect.center(), size * scale),
+        Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+        Color32::WHITE,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_folders_first_then_matching_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b.PNG", "a.mp4", "notes.txt", ".hidden.png"] {
+            std::fs::write(dir.path().join(name), b"").unwrap();
+        }
+        std::fs::create_dir(dir.path().join("Clips")).unwrap();
+
+        let names = |exts: &[&str]| -> Vec<String> {
+            list_dir(dir.path(), exts)
+                .unwrap()
+                .into_iter()
+                .map(|e| e.name)
+                .collect()
+        };
+        assert_eq!(names(&["png", "mp4"]), ["Clips", "a.mp4", "b.PNG"]);
+        assert_eq!(names(&[]), ["Clips", "a.mp4", "b.PNG", "notes.txt"]);
+    }
+
+    #[test]
+    fn a_missing_folder_keeps_the_current_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut browser =
+            FileBrowser::new("Test", dir.path().to_path_buf(), Vec::new(), false, |_| {});
+        browser.open(dir.path().join("gone"));
+        assert_eq!(browser.dir(), dir.path());
+        assert!(browser.error.is_some());
+    }
+}
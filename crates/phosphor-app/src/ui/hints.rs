@@ -0,0 +1,214 @@
+//! First-run tour (#2242): a short run of hints that points at the real
+//! panels. Each step names an anchor, and panels mark where they drew it with
+//! [`mark`] — every [`widgets::section`](super::widgets::section) does so
+//! under its id — so a new feature joins the tour with one more entry in
+//! [`STEPS`] and no layout code. A step whose anchor wasn't drawn this frame
+//! (a hidden panel, the shader editor open) shows its card in the middle.
+
+use egui::{Align, Align2, Color32, Context, Id, Order, Rect, RichText, Stroke, Ui, vec2};
+
+use super::theme::colors::theme_colors;
+use super::theme::tokens::*;
+
+pub struct HintStep {
+    /// Id the panel marks, usually its section's.
+    pub anchor: &'static str,
+    pub title: &'static str,
+    pub body: &'static str,
+}
+
+pub const STEPS: &[HintStep] = &[
+    HintStep {
+        anchor: "sec_effects",
+        title: "Effects",
+        body: "Click an effect to load it on the active layer. Each one reacts to the audio \
+               input; hover it to read what it does.",
+    },
+    HintStep {
+        anchor: "sec_layers",
+        title: "Layers",
+        body: "Stack effects, media and the webcam as layers, each with its own opacity and \
+               blend mode. The highlighted layer is the one the other panels edit.",
+    },
+    HintStep {
+        anchor: "sec_params",
+        title: "Parameters and MIDI learn",
+        body: "Tune the active layer here; right-click a slider to reset it. To play it from a \
+               controller, click the M beside a slider and move a knob or fader.",
+    },
+    HintStep {
+        anchor: "sec_presets",
+        title: "Presets",
+        body: "Save every layer as a preset and bring it back from here, a number key or a \
+               MIDI pad. An amber border means there are unsaved changes.",
+    },
+    HintStep {
+        anchor: "sec_settings",
+        title: "Settings",
+        body: "Audio input, MIDI, OSC, the web remote and outputs live here. Show tour in \
+               Settings runs this again.",
+    },
+];
+
+/// Where the tour card sits from the highlighted panel.
+const GAP: f32 = 12.0;
+const CARD_WIDTH: f32 = 260.0;
+
+/// What the user did with the tour this frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourAction {
+    Back,
+    Next,
+    /// Skipped, or finished from the last step.
+    Close,
+}
+
+fn anchor_id(anchor: &str) -> Id {
+    Id::new(("hint_anchor", anchor))
+}
+
+fn scroll_id() -> Id {
+    Id::new("hint_scroll_to")
+}
+
+/// Record where `anchor` was drawn this frame, and scroll it into view when
+/// the tour has just moved to it.
+pub fn mark(ui: &Ui, anchor: &str, rect: Rect) {
+    let ctx = ui.ctx();
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data_mut(|d| d.insert_temp(anchor_id(anchor), (rect, pass)));
+    let scroll = ctx.data_mut(|d| d.get_temp::<&'static str>(scroll_id()));
+    if scroll == Some(anchor) {
+        ui.scroll_to_rect(rect, Some(Align::Center));
+        ctx.data_mut(|d| d.remove::<&'static str>(scroll_id()));
+    }
+}
+
+/// Bring `step`'s panel into view on the next frame.
+pub fn focus(ctx: &Context, step: usize) {
+    if let Some(s) = STEPS.get(step) {
+        ctx.data_mut(|d| d.insert_temp(scroll_id(), s.anchor));
+    }
+}
+
+/// Where `anchor` was drawn, if it was this frame.
+fn anchor_rect(ctx: &Context, anchor: &str) -> Option<Rect> {
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data(|d| d.get_temp::<(Rect, u64)>(anchor_id(anchor)))
+        .filter(|(_, at)| *at == pass)
+        .map(|(rect, _)| rect)
+}
+
+/// Draw `step`: an outline around its panel and a card beside it. Call after
+/// the panels, so their anchors are this frame's.
+pub fn draw_tour(ctx: &Context, step: usize) -> Option<TourAction> {
+    let hint = STEPS.get(step)?;
+    let tc = theme_colors(ctx);
+    let screen = ctx.content_rect();
+    let target = anchor_rect(ctx, hint.anchor);
+
+    if let Some(rect) = target {
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            Order::Foreground,
+            Id::new("hint_outline"),
+        ));
+        let pulse =
+            ((ctx.input(|i| i.time) * std::f64::consts::TAU / 1.6).sin() * 0.5 + 0.5) as f32;
+        let width = 1.5 + pulse;
+        painter.rect_stroke(
+            rect.expand(2.0),
+            PANEL_ROUNDING,
+            Stroke::new(width, tc.accent),
+            egui::StrokeKind::Outside,
+        );
+        ctx.request_repaint();
+    }
+
+    // Beside the panel on whichever side has room, else centered
+    let (pos, pivot) = match target {
+        Some(rect) if rect.right() + GAP + CARD_WIDTH < screen.right() => {
+            (rect.right_top() + vec2(GAP, 0.0), Align2::LEFT_TOP)
+        }
+        Some(rect) => (rect.left_top() - vec2(GAP, 0.0), Align2::RIGHT_TOP),
+        None => (screen.center(), Align2::CENTER_CENTER),
+    };
+
+    let mut action = None;
+    egui::Area::new(Id::new("hint_card"))
+        .order(Order::Foreground)
+        .pivot(pivot)
+        .fixed_pos(pos)
+        .constrain(true)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style())
+                .stroke(Stroke::new(1.0, tc.accent))
+                .show(ui, |ui| {
+                    ui.set_width(CARD_WIDTH);
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            RichText::new(hint.title)
+                                .size(BODY_SIZE)
+                                .strong()
+                                .color(tc.text_primary),
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(Align::Center), |ui| {
+                            ui.label(
+                                RichText::new(format!("{}/{}", step + 1, STEPS.len()))
+                                    .size(SMALL_SIZE)
+                                    .color(tc.text_secondary),
+                            );
+                        });
+                    });
+                    ui.add_space(4.0);
+                    ui.label(
+                        RichText::new(hint.body)
+                            .size(SMALL_SIZE)
+                            .color(tc.text_primary),
+                    );
+                    ui.add_space(6.0);
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(RichText::new("Skip tour").size(SMALL_SIZE))
+                            .clicked()
+                        {
+                            action = Some(TourAction::Close);
+                        }
+                        ui.with_layout(egui::Layout::right_to_left(Align::Center), |ui| {
+                            let last = step + 1 == STEPS.len();
+                            let next = if last { "Done" } else { "Next" };
+                            let next = RichText::new(next).size(SMALL_SIZE).color(Color32::WHITE);
+                            if ui.add(egui::Button::new(next).fill(tc.accent)).clicked() {
+                                action = Some(if last {
+                                    TourAction::Close
+                                } else {
+                                    TourAction::Next
+                                });
+                            }
+                            if step > 0
+                                && ui.button(RichText::new("Back").size(SMALL_SIZE)).clicked()
+                            {
+                                action = Some(TourAction::Back);
+                            }
+                        });
+                    });
+                });
+        });
+    action
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_point_at_distinct_anchors() {
+        for (i, step) in STEPS.iter().enumerate() {
+            assert!(!step.body.is_empty(), "{}", step.title);
+            assert!(
+                STEPS[..i].iter().all(|s| s.anchor != step.anchor),
+                "{} repeats an anchor",
+                step.title
+            );
+        }
+    }
+}
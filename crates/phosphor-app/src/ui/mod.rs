@@ -1,4 +1,6 @@
 pub mod accessibility;
+pub mod file_browser;
+pub mod hints;
 pub mod overlay;
 pub mod panels;
 pub mod theme;
@@ -127,6 +127,22 @@ impl EguiOverlay {
         }
     }
 
+    /// Rebuild on a new device after a device loss (#2176). The egui context
+    /// goes too, since its font atlas lived on the old device; only the
+    /// overlay's visibility carries over.
+    pub fn recreate(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        window: &Window,
+    ) {
+        let visible = self.visible;
+        *self = Self::new(device, format, window, self.theme);
+        self.visible = visible;
+        self.auto_shown = true;
+        self.user_toggled = true;
+    }
+
     pub fn set_theme(&mut self, theme: ThemeMode) {
         self.theme = theme;
         let ctx = self.state.egui_ctx();
@@ -1,6 +1,12 @@
+//! Effect audio mappings (#2186). Each row names a feature and, optionally,
+//! the param it drives with its amount and attack/release envelope (#2187).
+//! Edits stay on the layer (and its preset) until written back to the `.pfx`.
+
 use egui::{Color32, Margin, RichText, Shape, Stroke, Ui, Vec2, pos2, vec2};
 
 use crate::effect::format::AudioMapping;
+use crate::params::ParamDef;
+use crate::params::modulation::{MAX_ATTACK_SECS, MAX_RELEASE_SECS};
 use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
 
@@ -18,6 +24,27 @@ fn feature_color(feature: &str) -> Color32 {
     }
 }
 
+/// Features offered in the source picker, in display order.
+const FEATURE_CHOICES: &[&str] = &[
+    "sub_bass",
+    "bass",
+    "low_mid",
+    "mid",
+    "upper_mid",
+    "presence",
+    "brilliance",
+    "rms",
+    "kick",
+    "onset",
+    "flux",
+    "centroid",
+    "flatness",
+    "beat",
+    "beat_phase",
+    "bpm",
+    "beat_strength",
+];
+
 /// Human-readable display name for an audio feature.
 fn feature_display_name(feature: &str) -> &str {
     match feature {
@@ -57,7 +84,9 @@ fn draw_arrow_right(ui: &mut Ui, color: Color32) {
         .add(Shape::convex_polygon(points, color, Stroke::NONE));
 }
 
-pub fn draw_audio_mappings(ui: &mut Ui, mappings: &[AudioMapping]) {
+/// Draw the mapping list with its editors. `defs` supplies the Float params
+/// a mapping can drive.
+pub fn draw_audio_mappings(ui: &mut Ui, mappings: &mut Vec<AudioMapping>, defs: &[ParamDef]) {
     let tc = theme_colors(ui.ctx());
 
     if mappings.is_empty() {
@@ -66,11 +95,17 @@ pub fn draw_audio_mappings(ui: &mut Ui, mappings: &[AudioMapping]) {
                 .size(SMALL_SIZE)
                 .color(tc.text_secondary),
         );
-        return;
     }
 
-    for mapping in mappings {
-        let id = ui.id().with(&mapping.feature).with(&mapping.target);
+    let floats: Vec<&str> = defs
+        .iter()
+        .filter(|d| matches!(d, ParamDef::Float { .. }))
+        .map(|d| d.name())
+        .collect();
+
+    let mut remove = None;
+    for (i, mapping) in mappings.iter_mut().enumerate() {
+        let id = ui.id().with(("audio_mapping", i));
         let hovered: bool = ui.ctx().data(|d| d.get_temp(id).unwrap_or(false));
 
         let fill = if hovered {
@@ -85,31 +120,100 @@ pub fn draw_audio_mappings(ui: &mut Ui, mappings: &[AudioMapping]) {
             .inner_margin(Margin::symmetric(4, 4))
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
-                    ui.allocate_ui_with_layout(
-                        vec2(52.0, 16.0),
-                        egui::Layout::left_to_right(egui::Align::Center),
-                        |ui| {
-                            ui.label(
-                                RichText::new(feature_display_name(&mapping.feature))
-                                    .size(SMALL_SIZE)
-                                    .color(feature_color(&mapping.feature))
-                                    .strong(),
-                            );
-                        },
-                    );
+                    ui.spacing_mut().item_spacing.x = 4.0;
+                    egui::ComboBox::from_id_salt(("mapping_feature", i))
+                        .width(72.0)
+                        .selected_text(
+                            RichText::new(feature_display_name(&mapping.feature))
+                                .size(SMALL_SIZE)
+                                .color(feature_color(&mapping.feature))
+                                .strong(),
+                        )
+                        .show_ui(ui, |ui| {
+                            for &f in FEATURE_CHOICES {
+                                ui.selectable_value(
+                                    &mut mapping.feature,
+                                    f.to_string(),
+                                    RichText::new(feature_display_name(f)).color(feature_color(f)),
+                                );
+                            }
+                        });
                     draw_arrow_right(ui, tc.text_secondary);
+                    let param_text = mapping.param.as_deref().unwrap_or("(describe only)");
+                    egui::ComboBox::from_id_salt(("mapping_param", i))
+                        .width(ui.available_width() - 24.0)
+                        .selected_text(RichText::new(param_text).size(SMALL_SIZE))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut mapping.param, None, "(describe only)");
+                            for &name in &floats {
+                                ui.selectable_value(
+                                    &mut mapping.param,
+                                    Some(name.to_string()),
+                                    name,
+                                );
+                            }
+                        });
+                    if ui
+                        .small_button("\u{d7}")
+                        .on_hover_text("Remove this mapping")
+                        .clicked()
+                    {
+                        remove = Some(i);
+                    }
+                });
+                if mapping.param.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing.x = 4.0;
+                        ui.add(
+                            egui::DragValue::new(&mut mapping.amount)
+                                .range(-1.0..=1.0)
+                                .speed(0.01)
+                                .custom_formatter(|v, _| format!("amt {:+.0}%", v * 100.0)),
+                        )
+                        .on_hover_text("Share of the param's range a full-scale feature adds");
+                        ui.add(
+                            egui::DragValue::new(&mut mapping.attack)
+                                .range(0.0..=MAX_ATTACK_SECS)
+                                .speed(0.005)
+                                .custom_formatter(|v, _| format!("atk {v:.2}s")),
+                        )
+                        .on_hover_text("Attack: how fast the envelope rises with the feature");
+                        ui.add(
+                            egui::DragValue::new(&mut mapping.release)
+                                .range(0.0..=MAX_RELEASE_SECS)
+                                .speed(0.01)
+                                .custom_formatter(|v, _| format!("rel {v:.2}s")),
+                        )
+                        .on_hover_text(
+                            "Release: how slowly it falls back; long release = smooth pulses",
+                        );
+                    });
+                }
+                if !mapping.target.is_empty() {
                     ui.add(
                         egui::Label::new(
                             RichText::new(&mapping.target)
                                 .size(SMALL_SIZE)
-                                .color(tc.text_primary),
+                                .color(tc.text_secondary),
                         )
                         .truncate(),
                     );
-                });
+                }
             });
 
         let is_hovered = resp.response.hovered();
         ui.ctx().data_mut(|d| d.insert_temp(id, is_hovered));
     }
+    if let Some(i) = remove {
+        mappings.remove(i);
+    }
+
+    if ui
+        .button(RichText::new("+ Add mapping").size(SMALL_SIZE))
+        .clicked()
+    {
+        let mut mapping = AudioMapping::new("bass", "");
+        mapping.param = floats.first().map(|p| (*p).to_string());
+        mappings.push(mapping);
+    }
 }
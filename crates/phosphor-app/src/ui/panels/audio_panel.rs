@@ -2,6 +2,8 @@ use std::f32::consts::{PI, TAU};
 
 use egui::{Color32, Mesh, Pos2, Rect, RichText, Shape, Stroke, Ui, Vec2, pos2};
 
+use crate::audio::channels::{self, ChannelRoute, ChannelSide};
+use crate::audio::envelope::EnvelopeConfig;
 use crate::audio::{AudioSystem, StructureConfig, TempoCommand, TempoConfig, TempoPreset};
 use crate::gpu::ShaderUniforms;
 use crate::ui::theme::colors::theme_colors;
@@ -215,9 +217,187 @@ fn draw_device_selector(ui: &mut Ui, audio: &mut AudioSystem) {
             });
     });
 
+    draw_mic_selector(ui, audio, &devices);
+
     ui.add_space(4.0);
 }
 
+/// Second input for crowd reactions (#2217): any capture device besides the music, read
+/// for level and onset only (`mic_rms` / `mic_onset`). Opening is handed to `main.rs`,
+/// which also saves the pick.
+fn draw_mic_selector(ui: &mut Ui, audio: &mut AudioSystem, devices: &[String]) {
+    let tc = theme_colors(ui.ctx());
+    let current = audio.mic_device().map(str::to_string);
+    let levels = audio.mic_levels();
+    let selected_text = current
+        .as_deref()
+        .map_or_else(|| "Off".to_string(), |name| truncate_device_name(name, 24));
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new("Mic")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        )
+        .on_hover_text(
+            "A room mic alongside the music. Effects see its level and onsets as \
+                 mic_rms and mic_onset; it doesn't affect the beat or spectrum.",
+        );
+        egui::ComboBox::from_id_salt("audio_mic_combo")
+            .selected_text(RichText::new(&selected_text).size(SMALL_SIZE))
+            .width(ui.available_width() - 4.0)
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(current.is_none(), RichText::new("Off").size(SMALL_SIZE))
+                    .clicked()
+                    && current.is_some()
+                {
+                    ui.ctx().data_mut(|d| {
+                        d.insert_temp(egui::Id::new("switch_mic_device"), String::new());
+                    });
+                }
+                for dev in devices {
+                    let selected = current.as_deref() == Some(dev.as_str());
+                    let label = truncate_device_name(dev, 40);
+                    if ui
+                        .selectable_label(selected, RichText::new(&label).size(SMALL_SIZE))
+                        .clicked()
+                        && !selected
+                    {
+                        ui.ctx().data_mut(|d| {
+                            d.insert_temp(egui::Id::new("switch_mic_device"), dev.clone());
+                        });
+                    }
+                }
+            });
+    });
+
+    if let Some(err) = &audio.mic_error {
+        ui.label(
+            RichText::new(format!("Mic: {err}"))
+                .size(SMALL_SIZE)
+                .color(tc.error),
+        );
+    } else if current.is_some() {
+        ui.label(
+            RichText::new(format!(
+                "level {:.2} \u{00b7} onset {:.2}",
+                levels.rms, levels.onset
+            ))
+            .size(SMALL_SIZE)
+            .color(tc.text_secondary),
+        );
+    }
+}
+
+/// Which channels of a multi-channel device feed analysis (#2216). Each route picks a
+/// channel, the side of the stereo pair it feeds and a trim; no routes is the default
+/// front pair. Edits apply live and are flagged for `main.rs` to save.
+fn draw_channel_rows(ui: &mut Ui, audio: &mut AudioSystem) {
+    let tc = theme_colors(ui.ctx());
+    let mut map = audio.channel_map();
+    let max = audio.max_channel_count.min(channels::MAX_CHANNELS) as u16;
+    let badge = if map.is_default() {
+        "1-2".to_string()
+    } else {
+        let mut list: Vec<u16> = map.routes.iter().map(|r| r.channel + 1).collect();
+        list.sort_unstable();
+        list.dedup();
+        list.iter()
+            .map(u16::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let mut changed = false;
+    widgets::subsection(
+        ui,
+        "sub_audio_channels",
+        "Input channels",
+        Some(&badge),
+        tc.text_secondary,
+        false,
+        |ui| {
+            if map.is_default() {
+                ui.label(
+                    RichText::new(format!(
+                        "Channels 1-2 as left/right ({} available).",
+                        audio.max_channel_count
+                    ))
+                    .size(SMALL_SIZE)
+                    .color(tc.text_secondary),
+                );
+            }
+            let mut remove = None;
+            for (i, route) in map.routes.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    let mut number = route.channel + 1;
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut number)
+                                .range(1..=max)
+                                .prefix("ch "),
+                        )
+                        .changed();
+                    route.channel = number - 1;
+                    egui::ComboBox::from_id_salt(("audio_channel_side", i))
+                        .width(52.0)
+                        .selected_text(RichText::new(route.side.display_name()).size(SMALL_SIZE))
+                        .show_ui(ui, |ui| {
+                            for &side in ChannelSide::ALL {
+                                changed |= ui
+                                    .selectable_value(&mut route.side, side, side.display_name())
+                                    .changed();
+                            }
+                        });
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut route.gain_db)
+                                .range(-24.0..=24.0)
+                                .speed(0.1)
+                                .suffix(" dB"),
+                        )
+                        .on_hover_text("Trim for this channel")
+                        .changed();
+                    if ui
+                        .small_button("\u{2715}")
+                        .on_hover_text("Remove")
+                        .clicked()
+                    {
+                        remove = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = remove {
+                map.routes.remove(i);
+                changed = true;
+            }
+            ui.horizontal(|ui| {
+                if ui
+                    .button("Add channel")
+                    .on_hover_text("Channels feeding the same side are summed.")
+                    .clicked()
+                {
+                    let next = map.routes.last().map_or(0, |r| r.channel + 1);
+                    map.routes.push(ChannelRoute {
+                        channel: next.min(max.saturating_sub(1)),
+                        ..Default::default()
+                    });
+                    changed = true;
+                }
+                if !map.is_default() && ui.button("Default").clicked() {
+                    map.routes.clear();
+                    changed = true;
+                }
+            });
+        },
+    );
+    if changed {
+        audio.set_channel_map(map);
+        ui.ctx()
+            .data_mut(|d| d.insert_temp(egui::Id::new("input_channels_dirty"), true));
+    }
+}
+
 // ── Section header ─────────────────────────────────────────────────────
 
 fn draw_section_header(ui: &mut Ui, label: &str, right: &str) {
@@ -817,6 +997,11 @@ pub fn draw_audio_panel(ui: &mut Ui, audio: &mut AudioSystem, uniforms: &ShaderU
     // Device selector
     draw_device_selector(ui, audio);
 
+    // Channel routing for multi-channel interfaces (#2216).
+    if audio.channel_routing_available() {
+        draw_channel_rows(ui, audio);
+    }
+
     let tc = theme_colors(ui.ctx());
     if !audio.active {
         if let Some(err) = &audio.last_error {
@@ -854,6 +1039,15 @@ pub fn draw_audio_panel(ui: &mut Ui, audio: &mut AudioSystem, uniforms: &ShaderU
     // Tempo prior + octave/tap overrides (A7 #1458) — collapsible, same live-write pattern.
     draw_tempo_rows(ui, audio, uniforms);
 
+    // Normalizer reset / freeze (#2213).
+    draw_gain_rows(ui, audio);
+
+    // Fast/slow band envelope times (#2219).
+    draw_envelope_rows(ui, audio);
+
+    // Analysis snapshot for offline beat tuning (#2215).
+    draw_snapshot_rows(ui, audio);
+
     // Chroma
     draw_section_header(ui, "CHROMA", "12 pitch classes");
     ui.vertical_centered(|ui| {
@@ -989,9 +1183,10 @@ fn draw_tuning_rows(ui: &mut Ui, audio: &mut AudioSystem) {
     }
 }
 
-/// Tempo prior + manual overrides (A7 #1458). Genre presets and the prior sliders write the
-/// shared `TempoControl` config directly (the audio thread reads it next hop — no pipeline
-/// rebuild); the half/double and tap controls go through the same struct's command mailbox.
+/// Tempo prior, detector knobs (#2214) + manual overrides (A7 #1458). Genre presets and the
+/// sliders write the shared `TempoControl` config directly (the audio thread reads it next
+/// hop — no pipeline rebuild); the half/double and tap controls go through the same
+/// struct's command mailbox.
 /// Collapsed by default to keep the monitor panel uncluttered.
 fn draw_tempo_rows(ui: &mut Ui, audio: &mut AudioSystem, uniforms: &ShaderUniforms) {
     let tc = theme_colors(ui.ctx());
@@ -1000,7 +1195,7 @@ fn draw_tempo_rows(ui: &mut Ui, audio: &mut AudioSystem, uniforms: &ShaderUnifor
     widgets::subsection(
         ui,
         "sub_audio_tempo",
-        "Tempo \u{00b7} prior & octave",
+        "Tempo \u{00b7} beat detection",
         None,
         tc.text_secondary,
         false,
@@ -1021,9 +1216,10 @@ fn draw_tempo_rows(ui: &mut Ui, audio: &mut AudioSystem, uniforms: &ShaderUnifor
                         )
                         .clicked()
                     {
-                        let (center, sigma) = p.values();
-                        ctl.config.prior_center_bpm = center;
-                        ctl.config.prior_sigma = sigma;
+                        ctl.config = TempoConfig {
+                            auto_prior: ctl.config.auto_prior,
+                            ..p.config()
+                        };
                         committed = true;
                     }
                 }
@@ -1060,6 +1256,37 @@ fn draw_tempo_rows(ui: &mut Ui, audio: &mut AudioSystem, uniforms: &ShaderUnifor
                 committed = true;
             }
 
+            // Detector knobs (#2214), below the prior they share the presets with.
+            ui.add_space(2.0);
+            rows::group_label(ui, "Detector");
+            committed |= rows::ParamRow::new("Onset")
+                .tooltip(
+                    "How far the flux must jump to count as a hit. Lower catches soft attacks; \
+                     higher ignores busy hats and ghost notes.",
+                )
+                .show_slider(ui, &mut ctl.config.onset_threshold, 0.5..=6.0)
+                .committed;
+            committed |= rows::ParamRow::new("Min BPM")
+                .tooltip("Slowest tempo the detector will report.")
+                .show_slider(ui, &mut ctl.config.bpm_min, 40.0..=290.0)
+                .committed;
+            committed |= rows::ParamRow::new("Max BPM")
+                .tooltip("Fastest tempo the detector will report.")
+                .show_slider(ui, &mut ctl.config.bpm_max, 50.0..=300.0)
+                .committed;
+            // Keep the window open: dragging one end past the other pushes it along.
+            if ctl.config.bpm_max < ctl.config.bpm_min + 10.0 {
+                ctl.config.bpm_max = ctl.config.bpm_min + 10.0;
+            }
+            committed |= rows::ParamRow::new("Tracking")
+                .logarithmic(true)
+                .tooltip(
+                    "How eagerly the tempo follows changes. Higher catches a DJ's pitch ride \
+                     sooner; lower holds steady through fills and breakdowns.",
+                )
+                .show_slider(ui, &mut ctl.config.kalman_aggressiveness, 0.1..=10.0)
+                .committed;
+
             ui.add_space(2.0);
             let bpm = uniforms.bpm * 300.0;
             if bpm > 1.0 {
@@ -1112,3 +1339,164 @@ fn draw_tempo_rows(ui: &mut Ui, audio: &mut AudioSystem, uniforms: &ShaderUnifor
             .data_mut(|d| d.insert_temp(egui::Id::new("tempo_config_dirty"), true));
     }
 }
+
+/// Gain staging (#2213). The features are normalized against the range each one has
+/// covered recently; resetting forgets that range (after a loud soundcheck, say) and
+/// freezing holds the current gains so a quiet passage stays quiet on screen.
+fn draw_gain_rows(ui: &mut Ui, audio: &mut AudioSystem) {
+    let tc = theme_colors(ui.ctx());
+    let frozen = audio.normalization_frozen();
+    widgets::subsection(
+        ui,
+        "sub_audio_gain",
+        "Gain \u{00b7} normalization",
+        frozen.then_some("frozen"),
+        tc.text_secondary,
+        false,
+        |ui| {
+            let mut hold = frozen;
+            if rows::checkbox_row(
+                ui,
+                &mut hold,
+                "Freeze gains",
+                Some("Stop adapting to the input level (also mappable to MIDI/OSC)."),
+            )
+            .changed()
+            {
+                audio.set_normalization_frozen(hold);
+            }
+            ui.add_space(2.0);
+            if ui
+                .button("Reset normalization")
+                .on_hover_text(
+                    "Forget the learned feature ranges and start adapting afresh. Also \
+                     unfreezes.",
+                )
+                .clicked()
+            {
+                audio.reset_normalization();
+            }
+        },
+    );
+}
+
+/// Attack/release of the `env_*` band envelopes (#2219). The followers run on the render
+/// thread, so sliders edit their times in place; on release the change is flagged for
+/// `main.rs` to save.
+fn draw_envelope_rows(ui: &mut Ui, audio: &mut AudioSystem) {
+    let tc = theme_colors(ui.ctx());
+    let mut committed = false;
+    widgets::subsection(
+        ui,
+        "sub_audio_envelopes",
+        "Envelopes \u{00b7} fast/slow",
+        None,
+        tc.text_secondary,
+        false,
+        |ui| {
+            let cfg = &mut audio.envelopes.config;
+            let row = |ui: &mut Ui, v: &mut f32, max: f32, label: &str, tip: &str| -> bool {
+                rows::ParamRow::new(label)
+                    .tooltip(tip)
+                    .logarithmic(true)
+                    .formatter(|v| format!("{v:.0} ms"))
+                    .show_slider(ui, v, 0.0..=max)
+                    .committed
+            };
+            let names = ["Bass", "Mid", "High"];
+            for (name, band) in names.into_iter().zip(cfg.bands_mut()) {
+                rows::group_label(ui, name);
+                committed |= row(
+                    ui,
+                    &mut band.fast.attack_ms,
+                    500.0,
+                    "Fast rise",
+                    "How quickly the fast envelope climbs on a hit.",
+                );
+                committed |= row(
+                    ui,
+                    &mut band.fast.release_ms,
+                    2000.0,
+                    "Fast fall",
+                    "How quickly the fast envelope lets go.",
+                );
+                committed |= row(
+                    ui,
+                    &mut band.slow.attack_ms,
+                    2000.0,
+                    "Slow rise",
+                    "How quickly the slow envelope climbs.",
+                );
+                committed |= row(
+                    ui,
+                    &mut band.slow.release_ms,
+                    5000.0,
+                    "Slow fall",
+                    "How quickly the slow envelope lets go.",
+                );
+                ui.add_space(2.0);
+            }
+            if ui.button("Reset to defaults").clicked() {
+                *cfg = EnvelopeConfig::default();
+                committed = true;
+            }
+        },
+    );
+
+    if committed {
+        ui.ctx()
+            .data_mut(|d| d.insert_temp(egui::Id::new("envelopes_dirty"), true));
+    }
+}
+
+/// Analysis snapshot (#2215): records what the beat detector heard and decided, so its
+/// settings can be tuned afterwards with `--replay-beats` instead of at the gig. The path
+/// comes from the recording settings, so starting is handed to `main.rs`.
+fn draw_snapshot_rows(ui: &mut Ui, audio: &mut AudioSystem) {
+    let tc = theme_colors(ui.ctx());
+    let (target, frames, error) = audio.snapshot_status();
+    let secs = frames as f32 * crate::audio::ANALYSIS_HOP as f32 / audio.sample_rate.max(1) as f32;
+    let badge = target.as_ref().map(|_| format!("rec {secs:.0}s"));
+    if target.is_some() {
+        ui.ctx().request_repaint();
+    }
+    widgets::subsection(
+        ui,
+        "sub_audio_snapshot",
+        "Analysis snapshot",
+        badge.as_deref(),
+        tc.error,
+        false,
+        |ui| {
+            if let Some(path) = &target {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                ui.label(
+                    RichText::new(format!("{name} \u{00b7} {frames} hops, {secs:.1}s"))
+                        .size(SMALL_SIZE)
+                        .color(tc.text_secondary),
+                )
+                .on_hover_text(path.display().to_string());
+                if ui.button("Stop snapshot").clicked() {
+                    audio.stop_snapshot();
+                }
+            } else {
+                if ui
+                    .button("Record snapshot")
+                    .on_hover_text(
+                        "Save the beat detector's input and decisions to the recording folder \
+                         (about 3 MB a minute). Replay it with `--replay-beats <file>` to try \
+                         other detector settings.",
+                    )
+                    .clicked()
+                {
+                    ui.ctx().data_mut(|d| {
+                        d.insert_temp(egui::Id::new("start_analysis_snapshot"), true)
+                    });
+                }
+                if let Some(err) = &error {
+                    ui.label(RichText::new(err).size(SMALL_SIZE).color(tc.error));
+                }
+            }
+        },
+    );
+}
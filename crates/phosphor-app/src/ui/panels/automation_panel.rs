@@ -0,0 +1,189 @@
+//! Param automation lanes (#2183): record arm, loop length, and a small
+//! breakpoint editor per lane. Drag a point to move it, double-click to add
+//! one, right-click a point to delete it.
+
+use egui::{Color32, RichText, Sense, Stroke, Ui, Vec2};
+
+use crate::params::ParamDef;
+use crate::params::automation::{AutomationLane, BAR_CHOICES, Breakpoint, ParamAutomation};
+use crate::ui::theme::colors::theme_colors;
+use crate::ui::theme::tokens::*;
+
+const LANE_HEIGHT: f32 = 36.0;
+/// How close (px) the pointer must be to grab or delete a breakpoint.
+const GRAB_RADIUS: f32 = 8.0;
+/// Curve samples drawn across the lane.
+const CURVE_STEPS: usize = 64;
+
+pub fn draw_automation_panel(ui: &mut Ui, automation: &mut ParamAutomation, defs: &[ParamDef]) {
+    let tc = theme_colors(ui.ctx());
+
+    ui.horizontal(|ui| {
+        let rec = if automation.recording {
+            RichText::new("\u{25cf} Rec")
+                .size(SMALL_SIZE)
+                .color(tc.error)
+        } else {
+            RichText::new("\u{25cb} Rec").size(SMALL_SIZE)
+        };
+        if ui
+            .add(egui::Button::new(rec).selected(automation.recording))
+            .on_hover_text("Record: params you move are written into their lanes")
+            .clicked()
+        {
+            automation.recording = !automation.recording;
+        }
+        egui::ComboBox::from_id_salt("automation_bars")
+            .width(64.0)
+            .selected_text(RichText::new(format!("{} bars", automation.bars)).size(SMALL_SIZE))
+            .show_ui(ui, |ui| {
+                for &bars in BAR_CHOICES {
+                    ui.selectable_value(&mut automation.bars, bars, format!("{bars} bars"));
+                }
+            });
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui
+                .add_enabled(
+                    !automation.lanes.is_empty(),
+                    egui::Button::new(RichText::new("Clear").size(SMALL_SIZE)),
+                )
+                .clicked()
+            {
+                automation.lanes.clear();
+            }
+        });
+    });
+
+    if automation.lanes.is_empty() {
+        ui.label(
+            RichText::new("Arm Rec and move a param to record a lane")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        );
+        return;
+    }
+
+    let position = automation.position;
+    let mut remove = None;
+    for i in 0..automation.lanes.len() {
+        let name = automation.lanes[i].param.clone();
+        let Some((min, max)) = defs.iter().find_map(|d| match d {
+            ParamDef::Float {
+                name: n, min, max, ..
+            } if *n == name => Some((*min, *max)),
+            _ => None,
+        }) else {
+            continue;
+        };
+        let touched = automation.is_touched(&name);
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            let color = if touched {
+                tc.warning
+            } else {
+                tc.text_secondary
+            };
+            ui.label(RichText::new(&name).size(SMALL_SIZE).color(color));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .small_button("\u{d7}")
+                    .on_hover_text("Delete this lane")
+                    .clicked()
+                {
+                    remove = Some(name.clone());
+                }
+            });
+        });
+        let accent = if touched { tc.warning } else { tc.accent };
+        draw_lane(ui, &mut automation.lanes[i], min, max, position, accent);
+    }
+    if let Some(name) = remove {
+        automation.remove_lane(&name);
+    }
+}
+
+fn draw_lane(
+    ui: &mut Ui,
+    lane: &mut AutomationLane,
+    min: f32,
+    max: f32,
+    position: f32,
+    accent: Color32,
+) {
+    let tc = theme_colors(ui.ctx());
+    let (rect, resp) = ui.allocate_exact_size(
+        Vec2::new(ui.available_width(), LANE_HEIGHT),
+        Sense::click_and_drag(),
+    );
+    let span = (max - min).max(f32::EPSILON);
+    let to_screen = |p: Breakpoint| {
+        egui::pos2(
+            rect.x_range().lerp(p.pos),
+            rect.bottom() - (p.value - min) / span * rect.height(),
+        )
+    };
+    let from_screen = |pt: egui::Pos2| Breakpoint {
+        pos: ((pt.x - rect.left()) / rect.width()).clamp(0.0, 1.0),
+        value: (min + (rect.bottom() - pt.y) / rect.height() * span).clamp(min, max),
+    };
+    let nearest = |lane: &AutomationLane, pt: egui::Pos2| {
+        lane.points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, to_screen(*p).distance(pt)))
+            .filter(|(_, d)| *d <= GRAB_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    };
+
+    // The dragged point lives in egui memory between frames.
+    let drag_id = resp.id.with("drag_point");
+    if let Some(pt) = resp.interact_pointer_pos() {
+        if resp.drag_started() {
+            let grabbed = nearest(lane, pt);
+            ui.data_mut(|d| d.insert_temp(drag_id, grabbed));
+        } else if resp.dragged() {
+            let grabbed: Option<usize> = ui.data(|d| d.get_temp(drag_id)).flatten();
+            if let Some(i) = grabbed {
+                let i = lane.move_point(i, from_screen(pt));
+                ui.data_mut(|d| d.insert_temp(drag_id, Some(i)));
+            }
+        }
+        if resp.double_clicked() {
+            lane.insert(from_screen(pt));
+        }
+        if resp.secondary_clicked() {
+            if let Some(i) = nearest(lane, pt) {
+                lane.remove_point(i);
+            }
+        }
+    }
+    if resp.drag_stopped() {
+        ui.data_mut(|d| d.remove_temp::<Option<usize>>(drag_id));
+    }
+
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, tc.widget_bg);
+    let curve: Vec<egui::Pos2> = (0..=CURVE_STEPS)
+        .filter_map(|s| {
+            let pos = s as f32 / CURVE_STEPS as f32;
+            lane.value_at(pos)
+                .map(|value| to_screen(Breakpoint { pos, value }))
+        })
+        .collect();
+    painter.add(egui::Shape::line(curve, Stroke::new(1.0, accent)));
+    for p in &lane.points {
+        painter.circle_filled(to_screen(*p), 2.5, accent);
+    }
+    let x = rect.x_range().lerp(position);
+    painter.line_segment(
+        [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+        Stroke::new(1.0, tc.text_dim),
+    );
+    if lane.points.is_empty() {
+        resp.on_hover_text("Double-click to add a point");
+    } else {
+        resp.on_hover_text("Drag a point to move it, double-click to add, right-click to delete");
+    }
+}
@@ -92,6 +92,8 @@ pub fn build_target_options(info: &BindingPanelInfo) -> Vec<TargetOption> {
             ("opacity", "opacity"),
             ("blend", "blend"),
             ("enabled", "enabled"),
+            ("playlist_next", "playlist next"),
+            ("playlist_prev", "playlist prev"),
         ] {
             targets.push(TargetOption {
                 id: format!("layer.{i}.{suffix}"),
@@ -168,6 +170,11 @@ pub fn build_target_options(info: &BindingPanelInfo) -> Vec<TargetOption> {
         label: "Master opacity".into(),
         group: "Global".into(),
     });
+    targets.push(TargetOption {
+        id: "global.preset_crossfade".into(),
+        label: "Preset crossfade".into(),
+        group: "Global".into(),
+    });
 
     targets
 }
@@ -216,6 +223,7 @@ const EXTENDED_SOURCES: &[(&str, &str, &str)] = &[
     ("loudness_m", "Momentary Loudness", "Loudness"),
     ("loudness_s", "Short-term Loudness", "Loudness"),
     ("loudness_trend", "Loudness Trend", "Loudness"),
+    ("crest", "Crest Factor", "Loudness"),
     ("contrast_0", "Contrast 200 Hz", "Timbre"),
     ("contrast_1", "Contrast 400 Hz", "Timbre"),
     ("contrast_2", "Contrast 800 Hz", "Timbre"),
@@ -230,6 +238,7 @@ const EXTENDED_SOURCES: &[(&str, &str, &str)] = &[
     ("section_novelty", "Section Novelty", "Structure"),
     ("buildup", "Build-up", "Structure"),
     ("drop", "Drop", "Structure"),
+    ("impact", "Impact", "Structure"),
     ("percussive_energy", "Percussive Energy", "Harmonic"),
     ("harmonic_energy", "Harmonic Energy", "Harmonic"),
     ("harmonic_ratio", "Harmonic Ratio", "Harmonic"),
@@ -468,6 +477,7 @@ pub const AUDIO_SOURCE_ORDER: &[&str] = &[
     "audio.loudness_m",
     "audio.loudness_s",
     "audio.loudness_trend",
+    "audio.crest",
     // Features
     "audio.kick",
     "audio.centroid",
@@ -498,6 +508,7 @@ pub const AUDIO_SOURCE_ORDER: &[&str] = &[
     "audio.section_novelty",
     "audio.buildup",
     "audio.drop",
+    "audio.impact",
     // Harmonic
     "audio.percussive_energy",
     "audio.harmonic_energy",
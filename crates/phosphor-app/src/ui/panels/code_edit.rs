@@ -0,0 +1,334 @@
+//! Text operations behind the shader editor's find/replace, go-to-line,
+//! block indent and comment toggle (#2190). Positions are char indices, as
+//! egui's text cursor counts them, unless a name says bytes.
+
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+/// One indent step for block indent and Vim's `>>`.
+pub const INDENT: &str = "    ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FindOptions {
+    /// Treat the query as a regular expression; replacements may use `$1`.
+    pub regex: bool,
+    pub case_sensitive: bool,
+}
+
+fn pattern(query: &str, opts: FindOptions) -> Result<Regex, String> {
+    let source = if opts.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    RegexBuilder::new(&source)
+        .case_insensitive(!opts.case_sensitive)
+        .multi_line(true)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Byte index of char index `pos`, clamped to the end.
+pub fn byte_index(text: &str, pos: usize) -> usize {
+    text.char_indices().nth(pos).map_or(text.len(), |(b, _)| b)
+}
+
+/// Byte ranges of every non-empty match of `query`.
+pub fn find_bytes(text: &str, query: &str, opts: FindOptions) -> Result<Vec<Range<usize>>, String> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(pattern(query, opts)?
+        .find_iter(text)
+        .filter(|m| !m.is_empty())
+        .map(|m| m.range())
+        .collect())
+}
+
+/// Char ranges of every non-empty match of `query`.
+pub fn find_all(text: &str, query: &str, opts: FindOptions) -> Result<Vec<Range<usize>>, String> {
+    let mut out = Vec::new();
+    let (mut byte, mut chars) = (0, 0);
+    for m in find_bytes(text, query, opts)? {
+        chars += text[byte..m.start].chars().count();
+        let len = text[m.clone()].chars().count();
+        out.push(chars..chars + len);
+        chars += len;
+        byte = m.end;
+    }
+    Ok(out)
+}
+
+/// The match after `from` going forward, or before it going back, wrapping
+/// around the ends.
+pub fn next_match(matches: &[Range<usize>], from: usize, forward: bool) -> Option<Range<usize>> {
+    if forward {
+        matches.iter().find(|m| m.start >= from).or(matches.first())
+    } else {
+        matches
+            .iter()
+            .rev()
+            .find(|m| m.start < from)
+            .or(matches.last())
+    }
+    .cloned()
+}
+
+/// Replace the match at char range `at`, expanding `$1`-style groups in
+/// regex mode. Returns the replacement's char range, or `None` when `at` is
+/// no longer a match (the text changed under it).
+pub fn replace_at(
+    text: &mut String,
+    at: Range<usize>,
+    query: &str,
+    replacement: &str,
+    opts: FindOptions,
+) -> Result<Option<Range<usize>>, String> {
+    if query.is_empty() {
+        return Ok(None);
+    }
+    let re = pattern(query, opts)?;
+    let start = byte_index(text, at.start);
+    let Some(caps) = re.captures_at(text, start) else {
+        return Ok(None);
+    };
+    let Some(m) = caps.get(0) else {
+        return Ok(None);
+    };
+    if m.start() != start || m.as_str().chars().count() != at.len() {
+        return Ok(None);
+    }
+    let mut with = String::new();
+    if opts.regex {
+        caps.expand(replacement, &mut with);
+    } else {
+        with.push_str(replacement);
+    }
+    let end = m.end();
+    text.replace_range(start..end, &with);
+    Ok(Some(at.start..at.start + with.chars().count()))
+}
+
+/// Replace every non-empty match. Returns how many were replaced.
+pub fn replace_all(
+    text: &mut String,
+    query: &str,
+    replacement: &str,
+    opts: FindOptions,
+) -> Result<usize, String> {
+    if query.is_empty() {
+        return Ok(0);
+    }
+    let re = pattern(query, opts)?;
+    let mut out = String::with_capacity(text.len());
+    let (mut last, mut count) = (0, 0);
+    for caps in re.captures_iter(text) {
+        let Some(m) = caps.get(0).filter(|m| !m.is_empty()) else {
+            continue;
+        };
+        out.push_str(&text[last..m.start()]);
+        if opts.regex {
+            caps.expand(replacement, &mut out);
+        } else {
+            out.push_str(replacement);
+        }
+        last = m.end();
+        count += 1;
+    }
+    if count > 0 {
+        out.push_str(&text[last..]);
+        *text = out;
+    }
+    Ok(count)
+}
+
+/// Char index where 1-based `line` starts, clamped to the last line.
+pub fn line_start(text: &str, line: usize) -> usize {
+    let total = text.chars().count();
+    let mut pos = 0;
+    for (i, l) in text.split('\n').enumerate() {
+        let len = l.chars().count();
+        if i + 1 >= line || pos + len == total {
+            return pos;
+        }
+        pos += len + 1;
+    }
+    pos
+}
+
+/// 1-based line holding char index `pos`.
+pub fn line_of(text: &str, pos: usize) -> usize {
+    text.chars().take(pos).filter(|&c| c == '\n').count() + 1
+}
+
+/// 0-based lines touched by char range `sel`. A selection ending at the
+/// very start of a line leaves that line out.
+fn touched_lines(text: &str, sel: &Range<usize>) -> Range<usize> {
+    let first = line_of(text, sel.start) - 1;
+    let mut last = line_of(text, sel.end) - 1;
+    if last > first && text.chars().nth(sel.end.saturating_sub(1)) == Some('\n') {
+        last -= 1;
+    }
+    first..last + 1
+}
+
+/// Apply `f` to each line `sel` touches; returns the char range now
+/// covering those lines.
+fn edit_lines(
+    text: &mut String,
+    sel: &Range<usize>,
+    mut f: impl FnMut(&str) -> String,
+) -> Range<usize> {
+    let lines = touched_lines(text, sel);
+    let mut all: Vec<String> = text.split('\n').map(str::to_string).collect();
+    for line in &mut all[lines.clone()] {
+        *line = f(line);
+    }
+    *text = all.join("\n");
+    let start = line_start(text, lines.start + 1);
+    let len: usize = all[lines.clone()]
+        .iter()
+        .map(|l| l.chars().count() + 1)
+        .sum();
+    start..start + len - 1
+}
+
+/// Indent (or outdent) every line `sel` touches by one [`INDENT`]. Blank
+/// lines aren't indented. Returns the char range of the edited lines.
+pub fn indent_lines(text: &mut String, sel: &Range<usize>, outdent: bool) -> Range<usize> {
+    edit_lines(text, sel, |line| {
+        if outdent {
+            let strip = line
+                .chars()
+                .take(INDENT.len())
+                .take_while(|&c| c == ' ')
+                .count();
+            let strip = if strip == 0 && line.starts_with('\t') {
+                1
+            } else {
+                strip
+            };
+            line[strip..].to_string()
+        } else if line.trim().is_empty() {
+            line.to_string()
+        } else {
+            format!("{INDENT}{line}")
+        }
+    })
+}
+
+/// Bytes of leading spaces and tabs.
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+/// Comment out the lines `sel` touches with `//`, or uncomment them if
+/// every non-blank one already is. Returns the char range of the lines.
+pub fn toggle_comment(text: &mut String, sel: &Range<usize>) -> Range<usize> {
+    let lines = touched_lines(text, sel);
+    let body: Vec<&str> = text
+        .split('\n')
+        .skip(lines.start)
+        .take(lines.len())
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    let uncomment = !body.is_empty() && body.iter().all(|l| l[indent_of(l)..].starts_with("//"));
+    // Comment markers line up at the shallowest indent.
+    let column = body.iter().map(|l| indent_of(l)).min().unwrap_or(0);
+    edit_lines(text, sel, |line| {
+        if line.trim().is_empty() {
+            line.to_string()
+        } else if uncomment {
+            let indent = indent_of(line);
+            let rest = &line[indent + 2..];
+            let rest = rest.strip_prefix(' ').unwrap_or(rest);
+            format!("{}{rest}", &line[..indent])
+        } else {
+            format!("{}// {}", &line[..column], &line[column..])
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAIN: FindOptions = FindOptions {
+        regex: false,
+        case_sensitive: false,
+    };
+
+    #[test]
+    fn find_counts_chars_and_wraps() {
+        let text = "é time; Time(time)";
+        let m = find_all(text, "time", PLAIN).unwrap();
+        assert_eq!(m, vec![2..6, 8..12, 13..17]);
+        assert_eq!(next_match(&m, 7, true), Some(8..12));
+        assert_eq!(next_match(&m, 14, true), Some(2..6));
+        assert_eq!(next_match(&m, 2, false), Some(13..17));
+        let cased = FindOptions {
+            case_sensitive: true,
+            ..PLAIN
+        };
+        assert_eq!(find_all(text, "Time", cased).unwrap(), vec![8..12]);
+        assert!(
+            find_all(
+                text,
+                "(",
+                FindOptions {
+                    regex: true,
+                    ..PLAIN
+                }
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn regex_replace_expands_groups() {
+        let re = FindOptions {
+            regex: true,
+            case_sensitive: true,
+        };
+        let mut text = "vec3f(a) vec3f(b)".to_string();
+        let at = find_all(&text, r"vec3f\((\w)\)", re).unwrap()[1].clone();
+        let done = replace_at(&mut text, at, r"vec3f\((\w)\)", "v($1)", re).unwrap();
+        assert_eq!(text, "vec3f(a) v(b)");
+        assert_eq!(done, Some(9..13));
+
+        assert_eq!(replace_all(&mut text, "(a)", "[a]", PLAIN).unwrap(), 1);
+        assert_eq!(text, "vec3f[a] v(b)");
+        // A stale range no longer matches.
+        assert_eq!(replace_at(&mut text, 0..3, "zzz", "", PLAIN).unwrap(), None);
+    }
+
+    #[test]
+    fn lines_count_from_one() {
+        let text = "a\nbb\nccc";
+        assert_eq!(line_start(text, 1), 0);
+        assert_eq!(line_start(text, 3), 5);
+        assert_eq!(line_start(text, 99), 5);
+        assert_eq!(line_of(text, 6), 3);
+    }
+
+    #[test]
+    fn block_indent_and_outdent() {
+        let mut text = "fn a() {\nx;\n\n  y;\n}".to_string();
+        // Selection from line 2 to the start of line 5 covers lines 2–4.
+        let sel = indent_lines(&mut text, &(9..18), false);
+        assert_eq!(text, "fn a() {\n    x;\n\n      y;\n}");
+        assert_eq!(sel, 9..25);
+        indent_lines(&mut text, &sel, true);
+        assert_eq!(text, "fn a() {\nx;\n\n  y;\n}");
+    }
+
+    #[test]
+    fn comment_toggles_at_the_shallowest_indent() {
+        let mut text = "  a;\n    b;\n".to_string();
+        let sel = toggle_comment(&mut text, &(0..8));
+        assert_eq!(text, "  // a;\n  //   b;\n");
+        toggle_comment(&mut text, &sel);
+        assert_eq!(text, "  a;\n    b;\n");
+    }
+}
@@ -0,0 +1,316 @@
+//! Cue panel (#2221): the running view of a scene as a show. The cue standing by
+//! and the one after it with their notes, a big GO and BACK, when a Go lands, and
+//! the cue list with editable numbers and notes. Toggled with C or from the
+//! Scenes panel.
+
+use egui::{Color32, Context, CornerRadius, RichText, Stroke, Ui, Vec2};
+
+use crate::scene::timeline::{GoDirection, TimelineInfoState};
+use crate::scene::types::GoQuantize;
+use crate::ui::theme::colors::{ThemeColors, theme_colors};
+use crate::ui::theme::tokens::*;
+
+use super::scene_panel::{CueDisplayInfo, SceneInfo};
+
+const GO_HEIGHT: f32 = 44.0;
+
+pub fn draw_cue_panel(ctx: &Context, open: &mut bool, info: &SceneInfo) {
+    let tc = theme_colors(ctx);
+    let title = info
+        .current_scene
+        .and_then(|i| info.scene_store_names.get(i))
+        .map_or_else(|| "Cues".to_string(), |name| format!("Cues · {name}"));
+
+    egui::Window::new(title)
+        .id(egui::Id::new("cue_panel_window"))
+        .open(open)
+        .default_size([420.0, 460.0])
+        .resizable(true)
+        .collapsible(true)
+        .show(ctx, |ui| {
+            let Some(tl) = info.timeline.as_ref() else {
+                ui.label(
+                    RichText::new("Load a scene in the Scenes panel to run its cues.")
+                        .size(BODY_SIZE)
+                        .color(tc.text_secondary),
+                );
+                return;
+            };
+            if info.cue_list.is_empty() {
+                ui.label(
+                    RichText::new("This scene has no cues yet.")
+                        .size(BODY_SIZE)
+                        .color(tc.text_secondary),
+                );
+                return;
+            }
+
+            let current = tl.active.then_some(tl.current_cue);
+            let next = match current {
+                None => Some(0),
+                Some(i) if i + 1 < info.cue_list.len() => Some(i + 1),
+                Some(_) if tl.loop_mode => Some(0),
+                Some(_) => None,
+            };
+
+            draw_cue_card(ui, "LIVE", current.map(|i| &info.cue_list[i]), &tc, true);
+            ui.add_space(4.0);
+            draw_cue_card(ui, "NEXT", next.map(|i| &info.cue_list[i]), &tc, false);
+            ui.add_space(6.0);
+
+            draw_transport(ui, tl.active, tl.pending_go, tl.quantize, &tc);
+            if let TimelineInfoState::Transitioning { progress, .. } = tl.state {
+                ui.add(egui::ProgressBar::new(progress).desired_height(4.0));
+            }
+
+            ui.add_space(6.0);
+            ui.separator();
+            draw_cue_table(ui, info, current, &tc);
+        });
+
+    if *open {
+        ctx.request_repaint_after(std::time::Duration::from_millis(50));
+    }
+}
+
+/// One cue as a card: number and name large, notes underneath.
+fn draw_cue_card(
+    ui: &mut Ui,
+    heading: &str,
+    cue: Option<&CueDisplayInfo>,
+    tc: &ThemeColors,
+    live: bool,
+) {
+    let (fill, stroke) = if live && cue.is_some() {
+        (tc.success.linear_multiply(0.12), tc.success)
+    } else {
+        (tc.card_bg, tc.card_border)
+    };
+    egui::Frame::new()
+        .fill(fill)
+        .stroke(Stroke::new(1.0_f32, stroke))
+        .corner_radius(CornerRadius::same(WIDGET_ROUNDING))
+        .inner_margin(egui::Margin::symmetric(8, 6))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.label(
+                RichText::new(heading)
+                    .size(SMALL_SIZE)
+                    .color(tc.text_secondary)
+                    .strong(),
+            );
+            let Some(cue) = cue else {
+                let text = if live { "Standing by" } else { "End of show" };
+                ui.label(RichText::new(text).size(BODY_SIZE).color(tc.text_secondary));
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(&cue.number)
+                        .size(BODY_SIZE + 6.0)
+                        .color(if live { tc.success } else { tc.accent })
+                        .strong(),
+                );
+                ui.label(
+                    RichText::new(&cue.preset_name)
+                        .size(BODY_SIZE + 4.0)
+                        .color(tc.text_primary),
+                );
+            });
+            if !cue.notes.is_empty() {
+                ui.label(
+                    RichText::new(&cue.notes)
+                        .size(BODY_SIZE)
+                        .color(tc.text_primary),
+                );
+            }
+        });
+}
+
+/// BACK, GO and the quantization a Go waits for.
+fn draw_transport(
+    ui: &mut Ui,
+    active: bool,
+    pending: Option<GoDirection>,
+    quantize: GoQuantize,
+    tc: &ThemeColors,
+) {
+    ui.horizontal(|ui| {
+        let back_label = if pending == Some(GoDirection::Prev) {
+            "BACK…"
+        } else {
+            "BACK"
+        };
+        let back = egui::Button::new(
+            RichText::new(back_label)
+                .size(BODY_SIZE)
+                .color(tc.text_primary),
+        )
+        .fill(Color32::TRANSPARENT)
+        .stroke(Stroke::new(1.0_f32, tc.card_border))
+        .corner_radius(CornerRadius::same(WIDGET_ROUNDING));
+        if ui
+            .add_enabled(active, back)
+            .on_hover_text("Previous cue")
+            .clicked()
+        {
+            ui.ctx()
+                .data_mut(|d| d.insert_temp(egui::Id::new("scene_go_prev"), true));
+        }
+
+        let go_label = match (active, pending) {
+            (false, _) => "GO (start)",
+            (true, Some(GoDirection::Next)) => "GO…",
+            (true, _) => "GO",
+        };
+        let go = egui::Button::new(
+            RichText::new(go_label)
+                .size(BODY_SIZE + 6.0)
+                .color(Color32::WHITE)
+                .strong(),
+        )
+        .fill(tc.success)
+        .corner_radius(CornerRadius::same(WIDGET_ROUNDING));
+        let go_w = ui.available_width();
+        if ui
+            .add_sized(Vec2::new(go_w, GO_HEIGHT), go)
+            .on_hover_text("Space, or the Scene Next trigger over MIDI / OSC")
+            .clicked()
+        {
+            let id = if active {
+                "scene_go_next"
+            } else {
+                "scene_toggle_play"
+            };
+            ui.ctx()
+                .data_mut(|d| d.insert_temp(egui::Id::new(id), true));
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new("Go lands:")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        );
+        let mut selected = quantize;
+        egui::ComboBox::from_id_salt("cue_quantize_combo")
+            .width(90.0)
+            .selected_text(selected.display_name())
+            .show_ui(ui, |ui| {
+                for q in GoQuantize::ALL {
+                    ui.selectable_value(&mut selected, *q, q.display_name());
+                }
+            });
+        if selected != quantize {
+            ui.ctx()
+                .data_mut(|d| d.insert_temp(egui::Id::new("scene_set_quantize"), selected));
+        }
+        if pending.is_some() {
+            ui.label(
+                RichText::new("waiting · press again to go now")
+                    .size(SMALL_SIZE)
+                    .color(tc.warning),
+            );
+        }
+    });
+}
+
+/// Every cue with an editable number and notes; click a name to jump.
+fn draw_cue_table(ui: &mut Ui, info: &SceneInfo, current: Option<usize>, tc: &ThemeColors) {
+    egui::ScrollArea::vertical()
+        .id_salt("cue_panel_table")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            for (idx, cue) in info.cue_list.iter().enumerate() {
+                ui.push_id(idx, |ui| {
+                    ui.horizontal(|ui| {
+                        let number_id = egui::Id::new("cue_number_edit").with(idx);
+                        let mut number: String = ui
+                            .ctx()
+                            .data_mut(|d| d.get_temp(number_id))
+                            .unwrap_or_else(|| cue.number.clone());
+                        let resp = ui.add(
+                            egui::TextEdit::singleline(&mut number)
+                                .desired_width(36.0)
+                                .font(egui::FontId::proportional(SMALL_SIZE)),
+                        );
+                        commit_on_focus_loss(
+                            ui,
+                            &resp,
+                            number_id,
+                            number,
+                            &cue.number,
+                            idx,
+                            "scene_set_cue_number",
+                        );
+
+                        let name_color = if current == Some(idx) {
+                            tc.success
+                        } else {
+                            tc.text_primary
+                        };
+                        let name = egui::Button::new(
+                            RichText::new(&cue.preset_name)
+                                .size(SMALL_SIZE)
+                                .color(name_color),
+                        )
+                        .frame(false);
+                        if ui
+                            .add_sized(Vec2::new(110.0, MIN_INTERACT_HEIGHT), name)
+                            .on_hover_text("Jump to this cue")
+                            .clicked()
+                        {
+                            ui.ctx().data_mut(|d| {
+                                d.insert_temp(egui::Id::new("scene_jump_to_cue"), idx);
+                            });
+                        }
+
+                        let notes_id = egui::Id::new("cue_notes_edit").with(idx);
+                        let mut notes: String = ui
+                            .ctx()
+                            .data_mut(|d| d.get_temp(notes_id))
+                            .unwrap_or_else(|| cue.notes.clone());
+                        let resp = ui.add(
+                            egui::TextEdit::singleline(&mut notes)
+                                .hint_text("Notes…")
+                                .desired_width(ui.available_width())
+                                .font(egui::FontId::proportional(SMALL_SIZE)),
+                        );
+                        commit_on_focus_loss(
+                            ui,
+                            &resp,
+                            notes_id,
+                            notes,
+                            &cue.notes,
+                            idx,
+                            "scene_set_cue_notes",
+                        );
+                    });
+                });
+            }
+        });
+}
+
+/// Keep a field's text in temp data while it's edited and send it once on focus
+/// loss, so typing doesn't rewrite the scene file on every key.
+fn commit_on_focus_loss(
+    ui: &Ui,
+    resp: &egui::Response,
+    buffer_id: egui::Id,
+    text: String,
+    original: &str,
+    idx: usize,
+    signal: &str,
+) {
+    if resp.lost_focus() {
+        ui.ctx().data_mut(|d| {
+            d.remove_temp::<String>(buffer_id);
+            if text != original {
+                d.insert_temp(egui::Id::new(signal), (idx, text));
+            }
+        });
+    } else if resp.has_focus() {
+        ui.ctx().data_mut(|d| d.insert_temp(buffer_id, text));
+    }
+}
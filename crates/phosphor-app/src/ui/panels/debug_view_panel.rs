@@ -0,0 +1,125 @@
+//! Debug view panel (#2229): pick an intermediate texture — a pass output, a
+//! feedback buffer, a particle aux texture — to draw over the output. The view
+//! only shows while this window is open. Toggled with V or from the Uniforms
+//! window.
+
+use egui::{Context, RichText};
+
+use crate::gpu::debug_view::{
+    DebugChannel, DebugLayout, DebugSelection, DebugTextureInfo, DebugViewState,
+};
+use crate::ui::theme::colors::theme_colors;
+use crate::ui::theme::tokens::*;
+
+pub fn draw_debug_view_panel(
+    ctx: &Context,
+    state: &mut DebugViewState,
+    textures: &[DebugTextureInfo],
+    layer_names: &[String],
+) {
+    let tc = theme_colors(ctx);
+    let source_name = |layer: Option<usize>, label: &str| match layer {
+        Some(i) => format!(
+            "{} · {label}",
+            layer_names.get(i).map_or("Layer", String::as_str)
+        ),
+        None => label.to_string(),
+    };
+
+    let mut open = state.open;
+    egui::Window::new("Debug View")
+        .id(egui::Id::new("debug_view_window"))
+        .open(&mut open)
+        .default_size([320.0, 200.0])
+        .resizable(true)
+        .collapsible(true)
+        .show(ctx, |ui| {
+            let current = state.selection.as_ref().and_then(|sel| {
+                textures
+                    .iter()
+                    .find(|t| t.layer == sel.layer && t.label == sel.label)
+            });
+            let selected_text = match (&state.selection, current) {
+                (None, _) => "Off".to_string(),
+                (Some(sel), _) => source_name(sel.layer, &sel.label),
+            };
+            egui::ComboBox::from_id_salt("debug_view_source")
+                .selected_text(RichText::new(selected_text).size(SMALL_SIZE))
+                .width(ui.available_width())
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut state.selection, None, "Off");
+                    for t in textures {
+                        let pick = Some(DebugSelection {
+                            layer: t.layer,
+                            label: t.label.clone(),
+                        });
+                        ui.selectable_value(
+                            &mut state.selection,
+                            pick,
+                            source_name(t.layer, &t.label),
+                        )
+                        .on_hover_text(format!("{} x {} · {:?}", t.width, t.height, t.format));
+                    }
+                });
+
+            match (&state.selection, current) {
+                (Some(_), Some(t)) => {
+                    ui.label(
+                        RichText::new(format!("{} x {} · {:?}", t.width, t.height, t.format))
+                            .size(SMALL_SIZE)
+                            .color(tc.text_secondary),
+                    );
+                }
+                (Some(_), None) => {
+                    ui.label(
+                        RichText::new("Not rendered right now: the layer or pass is gone.")
+                            .size(SMALL_SIZE)
+                            .color(tc.warning),
+                    );
+                }
+                (None, _) => {}
+            }
+            ui.add_space(4.0);
+
+            ui.horizontal(|ui| {
+                ui.selectable_value(
+                    &mut state.layout,
+                    DebugLayout::PictureInPicture,
+                    RichText::new("Picture-in-picture").size(SMALL_SIZE),
+                );
+                ui.selectable_value(
+                    &mut state.layout,
+                    DebugLayout::Fullscreen,
+                    RichText::new("Fullscreen").size(SMALL_SIZE),
+                );
+            });
+            ui.horizontal(|ui| {
+                for channel in DebugChannel::ALL {
+                    ui.selectable_value(
+                        &mut state.channel,
+                        channel,
+                        RichText::new(channel.display_name()).size(SMALL_SIZE),
+                    );
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("Exposure").size(SMALL_SIZE));
+                ui.add(
+                    egui::Slider::new(&mut state.exposure, 0.01..=64.0)
+                        .logarithmic(true)
+                        .max_decimals(2),
+                )
+                .on_hover_text("Scale values before clamping, to read HDR or faint buffers");
+            });
+            ui.add_space(4.0);
+            ui.label(
+                RichText::new(
+                    "Drawn in this window only while the panel is open. NDI and recordings \
+                     are unaffected.",
+                )
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+            );
+        });
+    state.open = open;
+}
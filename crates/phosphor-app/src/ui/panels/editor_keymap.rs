@@ -0,0 +1,567 @@
+//! Optional Vim and Emacs keybindings for the shader editor (#2190). The
+//! editor turns each frame's key events into [`KeyInput`]s and runs them
+//! through [`KeymapState::handle`] before egui's text field sees them;
+//! anything answered with [`KeyResult::Pass`] reaches the field as usual.
+//!
+//! Positions are char indices, as in [`super::code_edit`].
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use super::code_edit;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum Keymap {
+    #[default]
+    Standard,
+    Vim,
+    Emacs,
+}
+
+impl Keymap {
+    pub const ALL: &[Keymap] = &[Keymap::Standard, Keymap::Vim, Keymap::Emacs];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::Standard => "Standard",
+            Self::Vim => "Vim",
+            Self::Emacs => "Emacs",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyInput {
+    /// Typed text, one char at a time.
+    Char(char),
+    /// Ctrl plus a letter, lowercase.
+    Ctrl(char),
+    /// Alt (Meta) plus a letter, lowercase.
+    Alt(char),
+    Escape,
+    Enter,
+    Backspace,
+}
+
+/// Work a key asks of the editor beyond moving the cursor or editing text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorCommand {
+    Save,
+    /// Close, dropping unsaved edits.
+    Close,
+    /// Save, then hide the editor.
+    SaveAndHide,
+    Find,
+    FindNext,
+    FindPrev,
+    GotoLine,
+    Undo,
+    Redo,
+    /// Close the find or go-to-line bar.
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyResult {
+    /// Not a binding here; let the text field have the key.
+    Pass,
+    /// Handled: the text and cursor are updated.
+    Done,
+    Command(EditorCommand),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KeymapState {
+    /// Vim: typing inserts text. The editor opens in normal mode.
+    pub insert: bool,
+    /// Vim: the `:` command being typed.
+    pub command_line: Option<String>,
+    /// First key of a two-key command: Vim's `dd`, `yy`, `gg`, `>>`, `<<`,
+    /// or Emacs' `C-x`.
+    pub pending: Option<char>,
+    /// Last deleted, killed or yanked text.
+    register: String,
+    /// The register holds whole lines (Vim `dd`/`yy`), pasted as lines.
+    linewise: bool,
+    /// Emacs: the previous key was `C-k`, so the next kill appends.
+    killing: bool,
+}
+
+impl KeymapState {
+    /// Back to normal mode with nothing half-typed. The register survives.
+    pub fn reset(&mut self) {
+        self.insert = false;
+        self.command_line = None;
+        self.pending = None;
+        self.killing = false;
+    }
+
+    pub fn handle(
+        &mut self,
+        keymap: Keymap,
+        text: &mut String,
+        cursor: &mut usize,
+        input: KeyInput,
+    ) -> KeyResult {
+        match keymap {
+            Keymap::Standard => KeyResult::Pass,
+            Keymap::Vim => self.vim(text, cursor, input),
+            Keymap::Emacs => self.emacs(text, cursor, input),
+        }
+    }
+
+    fn vim(&mut self, text: &mut String, cursor: &mut usize, input: KeyInput) -> KeyResult {
+        if self.insert {
+            if input != KeyInput::Escape {
+                return KeyResult::Pass;
+            }
+            self.insert = false;
+            let chars: Vec<char> = text.chars().collect();
+            if *cursor > line_bounds(&chars, *cursor).start {
+                *cursor -= 1;
+            }
+            return KeyResult::Done;
+        }
+        if self.command_line.is_some() {
+            return self.vim_command_line(text, cursor, input);
+        }
+        let c = match input {
+            KeyInput::Char(c) => c,
+            KeyInput::Ctrl('r') => return KeyResult::Command(EditorCommand::Redo),
+            KeyInput::Ctrl(_) | KeyInput::Alt(_) => return KeyResult::Pass,
+            KeyInput::Escape => {
+                self.pending = None;
+                return KeyResult::Done;
+            }
+            KeyInput::Enter => '+',
+            KeyInput::Backspace => 'h',
+        };
+        if let Some(first) = self.pending.take() {
+            self.vim_pair(text, cursor, first, c);
+            return KeyResult::Done;
+        }
+
+        let chars: Vec<char> = text.chars().collect();
+        let line = line_bounds(&chars, *cursor);
+        let pos = *cursor;
+        match c {
+            'h' => *cursor = pos.saturating_sub(1).max(line.start),
+            'l' => *cursor = (pos + 1).min(last_col(&line)),
+            'j' => *cursor = vertical(&chars, pos, true, true),
+            'k' => *cursor = vertical(&chars, pos, false, true),
+            '+' if line.end < chars.len() => *cursor = first_non_blank(&chars, line.end + 1),
+            '0' => *cursor = line.start,
+            '^' => *cursor = first_non_blank(&chars, pos),
+            '$' => *cursor = last_col(&line),
+            'w' => *cursor = word_forward(&chars, pos),
+            'b' => *cursor = word_back(&chars, pos),
+            'G' => *cursor = first_non_blank(&chars, chars.len()),
+            'x' if pos < line.end => {
+                self.register = chars[pos].to_string();
+                self.linewise = false;
+                splice(text, pos..pos + 1, "");
+                *cursor = pos.min(last_col(&(line.start..line.end - 1)));
+            }
+            'D' if pos < line.end => {
+                self.register = chars[pos..line.end].iter().collect();
+                self.linewise = false;
+                splice(text, pos..line.end, "");
+                *cursor = last_col(&(line.start..pos));
+            }
+            'i' => self.insert = true,
+            'a' => {
+                *cursor = (pos + 1).min(line.end);
+                self.insert = true;
+            }
+            'I' => {
+                *cursor = first_non_blank(&chars, pos);
+                self.insert = true;
+            }
+            'A' => {
+                *cursor = line.end;
+                self.insert = true;
+            }
+            'o' | 'O' => {
+                let indent: String = chars[line.start..first_non_blank(&chars, pos)]
+                    .iter()
+                    .collect();
+                if c == 'o' {
+                    splice(text, line.end..line.end, &format!("\n{indent}"));
+                    *cursor = line.end + 1 + indent.chars().count();
+                } else {
+                    splice(text, line.start..line.start, &format!("{indent}\n"));
+                    *cursor = line.start + indent.chars().count();
+                }
+                self.insert = true;
+            }
+            'p' | 'P' => self.vim_paste(text, cursor, c == 'p'),
+            'u' => return KeyResult::Command(EditorCommand::Undo),
+            '/' => return KeyResult::Command(EditorCommand::Find),
+            'n' => return KeyResult::Command(EditorCommand::FindNext),
+            'N' => return KeyResult::Command(EditorCommand::FindPrev),
+            ':' => self.command_line = Some(String::new()),
+            'd' | 'y' | 'g' | '>' | '<' => self.pending = Some(c),
+            // Anything else is swallowed: normal mode never types.
+            _ => {}
+        }
+        KeyResult::Done
+    }
+
+    fn vim_pair(&mut self, text: &mut String, cursor: &mut usize, first: char, second: char) {
+        let chars: Vec<char> = text.chars().collect();
+        let line = line_bounds(&chars, *cursor);
+        match (first, second) {
+            ('d', 'd') => {
+                self.register = chars[line.clone()].iter().collect::<String>() + "\n";
+                self.linewise = true;
+                // Take the line's newline, or the one before it on the last line.
+                let del = if line.end < chars.len() {
+                    line.start..line.end + 1
+                } else {
+                    line.start.saturating_sub(1)..line.end
+                };
+                splice(text, del.clone(), "");
+                let chars: Vec<char> = text.chars().collect();
+                *cursor = first_non_blank(&chars, del.start.min(chars.len()));
+            }
+            ('y', 'y') => {
+                self.register = chars[line].iter().collect::<String>() + "\n";
+                self.linewise = true;
+            }
+            ('g', 'g') => *cursor = first_non_blank(&chars, 0),
+            ('>', '>') | ('<', '<') => {
+                code_edit::indent_lines(text, &(line.start..line.start), first == '<');
+                let chars: Vec<char> = text.chars().collect();
+                *cursor = first_non_blank(&chars, line.start);
+            }
+            _ => {}
+        }
+    }
+
+    fn vim_paste(&mut self, text: &mut String, cursor: &mut usize, after: bool) {
+        if self.register.is_empty() {
+            return;
+        }
+        let chars: Vec<char> = text.chars().collect();
+        let line = line_bounds(&chars, *cursor);
+        if self.linewise {
+            let at = if !after {
+                splice(text, line.start..line.start, &self.register);
+                line.start
+            } else if line.end < chars.len() {
+                splice(text, line.end + 1..line.end + 1, &self.register);
+                line.end + 1
+            } else {
+                // Last line without a newline: lead with one instead.
+                let body = self.register.strip_suffix('\n').unwrap_or(&self.register);
+                splice(text, line.end..line.end, &format!("\n{body}"));
+                line.end + 1
+            };
+            let chars: Vec<char> = text.chars().collect();
+            *cursor = first_non_blank(&chars, at);
+        } else {
+            let at = if after && line.start < line.end {
+                (*cursor + 1).min(line.end)
+            } else {
+                *cursor
+            };
+            splice(text, at..at, &self.register);
+            *cursor = at + self.register.chars().count() - 1;
+        }
+    }
+
+    fn vim_command_line(&mut self, text: &str, cursor: &mut usize, input: KeyInput) -> KeyResult {
+        let Some(line) = &mut self.command_line else {
+            return KeyResult::Pass;
+        };
+        match input {
+            KeyInput::Char(c) => line.push(c),
+            KeyInput::Backspace => {
+                if line.pop().is_none() {
+                    self.command_line = None;
+                }
+            }
+            KeyInput::Escape => self.command_line = None,
+            KeyInput::Ctrl(_) | KeyInput::Alt(_) => return KeyResult::Pass,
+            KeyInput::Enter => {
+                let command = self.command_line.take().unwrap_or_default();
+                match command.trim() {
+                    "w" => return KeyResult::Command(EditorCommand::Save),
+                    "q" | "q!" => return KeyResult::Command(EditorCommand::Close),
+                    "wq" | "x" => return KeyResult::Command(EditorCommand::SaveAndHide),
+                    n => {
+                        if let Ok(n) = n.parse::<usize>() {
+                            let chars: Vec<char> = text.chars().collect();
+                            *cursor = first_non_blank(&chars, code_edit::line_start(text, n));
+                        }
+                    }
+                }
+            }
+        }
+        KeyResult::Done
+    }
+
+    fn emacs(&mut self, text: &mut String, cursor: &mut usize, input: KeyInput) -> KeyResult {
+        let killing = std::mem::take(&mut self.killing);
+        if self.pending.take() == Some('x') {
+            return match input {
+                KeyInput::Ctrl('s') => KeyResult::Command(EditorCommand::Save),
+                KeyInput::Ctrl('c') => KeyResult::Command(EditorCommand::Close),
+                _ => KeyResult::Done,
+            };
+        }
+        let chars: Vec<char> = text.chars().collect();
+        let line = line_bounds(&chars, *cursor);
+        let pos = *cursor;
+        match input {
+            KeyInput::Ctrl('a') => *cursor = line.start,
+            KeyInput::Ctrl('e') => *cursor = line.end,
+            KeyInput::Ctrl('f') => *cursor = (pos + 1).min(chars.len()),
+            KeyInput::Ctrl('b') => *cursor = pos.saturating_sub(1),
+            KeyInput::Ctrl('n') => *cursor = vertical(&chars, pos, true, false),
+            KeyInput::Ctrl('p') => *cursor = vertical(&chars, pos, false, false),
+            KeyInput::Alt('f') => *cursor = word_forward(&chars, pos),
+            KeyInput::Alt('b') => *cursor = word_back(&chars, pos),
+            KeyInput::Ctrl('d') => {
+                if pos < chars.len() {
+                    splice(text, pos..pos + 1, "");
+                }
+            }
+            KeyInput::Ctrl('k') => {
+                // Kill to the end of the line, or the newline when already there.
+                let end = if pos < line.end {
+                    line.end
+                } else {
+                    (line.end + 1).min(chars.len())
+                };
+                let killed: String = chars[pos..end].iter().collect();
+                if killing {
+                    self.register.push_str(&killed);
+                } else {
+                    self.register = killed;
+                }
+                self.linewise = false;
+                self.killing = true;
+                splice(text, pos..end, "");
+            }
+            KeyInput::Ctrl('y') => {
+                splice(text, pos..pos, &self.register);
+                *cursor = pos + self.register.chars().count();
+            }
+            KeyInput::Ctrl('x') => self.pending = Some('x'),
+            KeyInput::Ctrl('s') => return KeyResult::Command(EditorCommand::Find),
+            KeyInput::Ctrl('r') => return KeyResult::Command(EditorCommand::FindPrev),
+            KeyInput::Ctrl('g') => return KeyResult::Command(EditorCommand::Cancel),
+            KeyInput::Alt('g') => return KeyResult::Command(EditorCommand::GotoLine),
+            _ => return KeyResult::Pass,
+        }
+        KeyResult::Done
+    }
+}
+
+/// Replace char range `range` of `text` with `with`.
+fn splice(text: &mut String, range: Range<usize>, with: &str) {
+    let start = code_edit::byte_index(text, range.start);
+    let end = code_edit::byte_index(text, range.end);
+    text.replace_range(start..end, with);
+}
+
+/// The line holding `pos`, without its newline.
+fn line_bounds(chars: &[char], pos: usize) -> Range<usize> {
+    let pos = pos.min(chars.len());
+    let start = chars[..pos]
+        .iter()
+        .rposition(|&c| c == '\n')
+        .map_or(0, |i| i + 1);
+    let end = chars[pos..]
+        .iter()
+        .position(|&c| c == '\n')
+        .map_or(chars.len(), |i| pos + i);
+    start..end
+}
+
+/// Last char Vim's normal-mode cursor may sit on.
+fn last_col(line: &Range<usize>) -> usize {
+    line.end.saturating_sub(1).max(line.start)
+}
+
+/// First non-blank char on the line holding `pos`.
+fn first_non_blank(chars: &[char], pos: usize) -> usize {
+    let line = line_bounds(chars, pos);
+    (line.start..line.end)
+        .find(|&i| !matches!(chars[i], ' ' | '\t'))
+        .unwrap_or_else(|| last_col(&line))
+}
+
+/// Same column on the next or previous line, clamped to its length.
+fn vertical(chars: &[char], pos: usize, down: bool, vim: bool) -> usize {
+    let line = line_bounds(chars, pos);
+    let target = if down {
+        if line.end >= chars.len() {
+            return pos;
+        }
+        line_bounds(chars, line.end + 1)
+    } else {
+        if line.start == 0 {
+            return pos;
+        }
+        line_bounds(chars, line.start - 1)
+    };
+    let limit = if vim { last_col(&target) } else { target.end };
+    (target.start + pos - line.start).min(limit)
+}
+
+/// 0 blank, 1 word, 2 punctuation, for word motions.
+fn class(c: char) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+/// Start of the next word.
+fn word_forward(chars: &[char], pos: usize) -> usize {
+    let mut i = pos;
+    if let Some(&c) = chars.get(i) {
+        let k = class(c);
+        while k != 0 && chars.get(i).is_some_and(|&c| class(c) == k) {
+            i += 1;
+        }
+    }
+    while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+        i += 1;
+    }
+    i.min(chars.len().saturating_sub(1))
+}
+
+/// Start of this word, or of the previous one when already there.
+fn word_back(chars: &[char], pos: usize) -> usize {
+    let mut i = pos.min(chars.len());
+    while i > 0 && chars[i - 1].is_whitespace() {
+        i -= 1;
+    }
+    if i > 0 {
+        let k = class(chars[i - 1]);
+        while i > 0 && class(chars[i - 1]) == k {
+            i -= 1;
+        }
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(
+        state: &mut KeymapState,
+        keymap: Keymap,
+        text: &mut String,
+        cursor: &mut usize,
+        typed: &str,
+    ) -> Vec<KeyResult> {
+        typed
+            .chars()
+            .map(|c| state.handle(keymap, text, cursor, KeyInput::Char(c)))
+            .collect()
+    }
+
+    #[test]
+    fn vim_motions_stay_on_the_line() {
+        let mut s = KeymapState::default();
+        let mut text = "let a = 1;\n  b\n".to_string();
+        let mut cur = 0;
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, "w");
+        assert_eq!(cur, 4);
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, "$");
+        assert_eq!(cur, 9);
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, "j");
+        assert_eq!(cur, 13);
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, "0^");
+        assert_eq!(cur, 13);
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, "gg");
+        assert_eq!(cur, 0);
+        // Normal mode swallows text it doesn't know.
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, "Q");
+        assert_eq!(text, "let a = 1;\n  b\n");
+    }
+
+    #[test]
+    fn vim_delete_and_paste_lines() {
+        let mut s = KeymapState::default();
+        let mut text = "one\ntwo\nthree".to_string();
+        let mut cur = 0;
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, "ddp");
+        assert_eq!(text, "two\none\nthree");
+        assert_eq!(cur, 4);
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, "Gyyp");
+        assert_eq!(text, "two\none\nthree\nthree");
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, "dd");
+        assert_eq!(text, "two\none\nthree");
+        assert_eq!(cur, 8);
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, "x");
+        assert_eq!(text, "two\none\nhree");
+    }
+
+    #[test]
+    fn vim_insert_and_escape() {
+        let mut s = KeymapState::default();
+        let mut text = "ab".to_string();
+        let mut cur = 0;
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, "A");
+        assert!(s.insert);
+        assert_eq!(cur, 2);
+        // Insert mode leaves typing to the text field.
+        assert_eq!(
+            keys(&mut s, Keymap::Vim, &mut text, &mut cur, "c"),
+            vec![KeyResult::Pass]
+        );
+        s.handle(Keymap::Vim, &mut text, &mut cur, KeyInput::Escape);
+        assert!(!s.insert);
+        assert_eq!(cur, 1);
+    }
+
+    #[test]
+    fn vim_command_line() {
+        let mut s = KeymapState::default();
+        let mut text = "a\nb\n  c".to_string();
+        let mut cur = 0;
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, ":3");
+        assert_eq!(s.command_line.as_deref(), Some("3"));
+        s.handle(Keymap::Vim, &mut text, &mut cur, KeyInput::Enter);
+        assert_eq!(cur, 6);
+        keys(&mut s, Keymap::Vim, &mut text, &mut cur, ":w");
+        assert_eq!(
+            s.handle(Keymap::Vim, &mut text, &mut cur, KeyInput::Enter),
+            KeyResult::Command(EditorCommand::Save)
+        );
+    }
+
+    #[test]
+    fn emacs_kills_append_and_yank() {
+        let mut s = KeymapState::default();
+        let mut text = "ab\ncd\nef".to_string();
+        let mut cur = 0;
+        for _ in 0..3 {
+            s.handle(Keymap::Emacs, &mut text, &mut cur, KeyInput::Ctrl('k'));
+        }
+        assert_eq!(text, "\nef");
+        s.handle(Keymap::Emacs, &mut text, &mut cur, KeyInput::Ctrl('e'));
+        s.handle(Keymap::Emacs, &mut text, &mut cur, KeyInput::Ctrl('y'));
+        assert_eq!(text, "ab\ncd\nef");
+        assert_eq!(cur, 5);
+        assert_eq!(
+            s.handle(Keymap::Emacs, &mut text, &mut cur, KeyInput::Char('z')),
+            KeyResult::Pass
+        );
+        s.handle(Keymap::Emacs, &mut text, &mut cur, KeyInput::Ctrl('x'));
+        assert_eq!(
+            s.handle(Keymap::Emacs, &mut text, &mut cur, KeyInput::Ctrl('s')),
+            KeyResult::Command(EditorCommand::Save)
+        );
+    }
+}
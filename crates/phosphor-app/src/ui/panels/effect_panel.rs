@@ -42,12 +42,17 @@ struct GridCtx<'a> {
     favorites: &'a [String],
     btn_height: f32,
     gap: f32,
-    /// Right-click two-stage delete (user section only; favorites row never deletes).
+    /// Right-click two-stage delete (user section only; favorites and recent rows never delete).
     allow_delete: bool,
     warning_color: Color32,
 }
 
-pub fn draw_effect_panel(ui: &mut Ui, loader: &EffectLoader, favorites: &[String]) {
+pub fn draw_effect_panel(
+    ui: &mut Ui,
+    loader: &EffectLoader,
+    favorites: &[String],
+    recent: &[String],
+) {
     let tc = theme_colors(ui.ctx());
 
     if loader.effects.is_empty() {
@@ -114,18 +119,22 @@ pub fn draw_effect_panel(ui: &mut Ui, loader: &EffectLoader, favorites: &[String
     };
     let filtering = !(q.is_empty() && types_on.0 && types_on.1 && types_on.2);
 
-    // ── Partition: favorites / built-in / user, filter applied ───────
-    let fav: Vec<(usize, &PfxEffect)> = favorites
-        .iter()
-        .filter_map(|name| {
-            loader
-                .effects
-                .iter()
-                .position(|e| &e.name == name && !e.hidden)
-                .map(|i| (i, &loader.effects[i]))
-        })
-        .filter(|(_, e)| matches(e))
-        .collect();
+    // ── Partition: favorites / recent / built-in / user, filter applied
+    let by_name = |names: &[String]| -> Vec<(usize, &PfxEffect)> {
+        names
+            .iter()
+            .filter_map(|name| {
+                loader
+                    .effects
+                    .iter()
+                    .position(|e| &e.name == name && !e.hidden)
+                    .map(|i| (i, &loader.effects[i]))
+            })
+            .filter(|(_, e)| matches(e))
+            .collect()
+    };
+    let fav = by_name(favorites);
+    let recent = by_name(recent);
     let builtin_all: Vec<(usize, &PfxEffect)> = loader
         .effects
         .iter()
@@ -174,6 +183,13 @@ pub fn draw_effect_panel(ui: &mut Ui, loader: &EffectLoader, favorites: &[String
         ui.add_space(2.0);
     }
 
+    // ── Recent row (#2245), newest first ─────────────────────────────
+    if !recent.is_empty() {
+        rows::group_label(ui, "Recent");
+        draw_effect_grid(ui, &recent, &ctx, pending_delete, &mut new_pending);
+        ui.add_space(2.0);
+    }
+
     // ── Built-in section ─────────────────────────────────────────────
     if !builtin.is_empty() {
         egui::CollapsingHeader::new(
@@ -283,6 +299,53 @@ pub fn draw_effect_panel(ui: &mut Ui, loader: &EffectLoader, favorites: &[String
                 .data_mut(|d| d.insert_temp(egui::Id::new("open_shader_editor"), true));
         }
 
+        // Open in an outside editor (#2191): one entry per shader file
+        let shaders = current
+            .filter(|_| is_user)
+            .map(shader_files)
+            .unwrap_or_default();
+        let button = egui::Button::new(RichText::new("\u{2197}").size(SMALL_SIZE).color(
+            if shaders.is_empty() {
+                tc.text_secondary
+            } else {
+                tc.text_primary
+            },
+        ))
+        .fill(tc.card_bg)
+        .stroke(Stroke::new(1.0_f32, tc.card_border))
+        .corner_radius(CornerRadius::same(4));
+        let mut open = None;
+        ui.add_enabled_ui(!shaders.is_empty(), |ui| {
+            if let [(_, shader)] = shaders.as_slice() {
+                if ui
+                    .add(button)
+                    .on_hover_text("Open the shader in your editor (Settings \u{2192} Ext. editor)")
+                    .clicked()
+                {
+                    open = Some(shader.clone());
+                }
+            } else {
+                egui::containers::menu::MenuButton::from_button(button)
+                    .ui(ui, |ui| {
+                        for (label, shader) in &shaders {
+                            if ui
+                                .button(RichText::new(label).size(SMALL_SIZE))
+                                .on_hover_text(shader)
+                                .clicked()
+                            {
+                                open = Some(shader.clone());
+                            }
+                        }
+                    })
+                    .0
+                    .on_hover_text("Open a pass in your editor (Settings \u{2192} Ext. editor)");
+            }
+        });
+        if let Some(shader) = open {
+            ui.ctx()
+                .data_mut(|d| d.insert_temp(egui::Id::new("open_external_editor"), shader));
+        }
+
         if ui
             .add(
                 egui::Button::new(
@@ -307,6 +370,27 @@ pub fn draw_effect_panel(ui: &mut Ui, loader: &EffectLoader, favorites: &[String
     draw_footer(ui, &builtin_all, &user_all, filtering.then_some(shown), &tc);
 }
 
+/// (label, shader path) for each pass and the compute shader, without
+/// repeating a file two passes share.
+fn shader_files(effect: &PfxEffect) -> Vec<(String, String)> {
+    let mut files: Vec<(String, String)> = Vec::new();
+    let passes = effect
+        .normalized_passes()
+        .into_iter()
+        .map(|p| (p.name, p.shader));
+    let compute = effect
+        .particles
+        .as_ref()
+        .filter(|p| !p.compute_shader.is_empty())
+        .map(|p| ("compute".to_string(), p.compute_shader.clone()));
+    for (name, shader) in passes.chain(compute) {
+        if !files.iter().any(|(_, s)| *s == shader) {
+            files.push((format!("{name} \u{2014} {shader}"), shader));
+        }
+    }
+    files
+}
+
 // ── Type filter chips ────────────────────────────────────────────────
 
 fn type_tooltip(et: EffectType) -> &'static str {
@@ -410,6 +494,7 @@ fn draw_effect_grid(
                 let is_armed =
                     ctx.allow_delete && pending_delete.map_or(false, |(idx, _)| idx == i);
                 let is_fav = ctx.favorites.iter().any(|f| f == &effect.name);
+                let unsupported = ctx.loader.unsupported_reason(effect);
 
                 let et = effect.effect_type();
                 let et_color = type_color(et);
@@ -419,11 +504,13 @@ fn draw_effect_grid(
                 } else if is_current {
                     (tc.accent, Color32::WHITE, Stroke::NONE)
                 } else {
-                    (
-                        tc.card_bg,
-                        tc.text_primary,
-                        Stroke::new(1.0_f32, tc.card_border),
-                    )
+                    // Effects this GPU can't run are dimmed (#2195).
+                    let text = if unsupported.is_some() {
+                        tc.text_secondary
+                    } else {
+                        tc.text_primary
+                    };
+                    (tc.card_bg, text, Stroke::new(1.0_f32, tc.card_border))
                 };
 
                 let btn = egui::Button::new(
@@ -550,6 +637,9 @@ fn draw_effect_grid(
                     if let Some(s) = &particle_suffix {
                         text.push_str(s);
                     }
+                    if let Some(reason) = &unsupported {
+                        text = format!("\u{26a0} Unsupported on this GPU. {reason}.\n{text}");
+                    }
                     text.push_str(&type_suffix);
                     if ctx.allow_delete {
                         text.push_str(" — right-click to delete");
@@ -0,0 +1,63 @@
+//! Generator layer controls (#2168). Edits land in the layer's
+//! `GeneratorDef` in place; main.rs diffs it to mark the preset dirty.
+
+use egui::Ui;
+
+use crate::gpu::generator::{BEAT_RATES, GeneratorDef, GeneratorKind};
+use crate::ui::widgets::rows;
+
+pub fn draw_generator_panel(ui: &mut Ui, def: &mut GeneratorDef) {
+    rows::combo_row(
+        ui,
+        "generator_kind",
+        "Type",
+        None,
+        def.kind.display_name(),
+        |ui| {
+            for kind in GeneratorKind::ALL {
+                ui.selectable_value(&mut def.kind, *kind, kind.display_name());
+            }
+        },
+    );
+
+    let gradient = def.kind == GeneratorKind::Gradient;
+    rows::custom_row(ui, "Color", None, |ui| {
+        ui.color_edit_button_rgb(&mut def.color);
+        if gradient {
+            ui.color_edit_button_rgb(&mut def.color_b);
+        }
+    });
+
+    ui.add_space(4.0);
+    rows::checkbox_row(
+        ui,
+        &mut def.beat_sync,
+        "Beat sync",
+        Some("Lock flashes to the detected (or tapped) tempo"),
+    );
+    if def.beat_sync {
+        let selected = BEAT_RATES
+            .iter()
+            .find(|(r, _)| (*r - def.rate).abs() < 1e-3)
+            .map_or("custom", |(_, name)| *name);
+        rows::combo_row(ui, "generator_rate", "Every", None, selected, |ui| {
+            for (rate, name) in BEAT_RATES {
+                ui.selectable_value(&mut def.rate, *rate, *name);
+            }
+        });
+    } else {
+        rows::ParamRow::new("Rate (Hz)").show_slider(ui, &mut def.rate, 0.5..=30.0);
+    }
+    rows::ParamRow::new("Duty")
+        .tooltip("Lit part of each cycle; for washes, how long the flash takes to fade")
+        .show_slider(ui, &mut def.duty, 0.0..=1.0);
+    rows::ParamRow::new("Intensity").show_slider(ui, &mut def.intensity, 0.0..=2.0);
+
+    if gradient {
+        ui.add_space(4.0);
+        rows::ParamRow::new("Angle").show_slider(ui, &mut def.angle, 0.0..=360.0);
+        rows::ParamRow::new("Scroll")
+            .tooltip("Cycles per second; negative scrolls the other way")
+            .show_slider(ui, &mut def.scroll, -2.0..=2.0);
+    }
+}
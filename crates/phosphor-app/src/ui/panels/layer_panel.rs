@@ -2,7 +2,10 @@ use std::sync::Arc;
 
 use egui::{Color32, CornerRadius, Rect, RichText, Stroke, StrokeKind, Ui, Vec2};
 
+use crate::gpu::envelope::{EnvelopeShape, OpacityEnvelope};
 use crate::gpu::layer::{BlendMode, LayerInfo};
+use crate::preset::surprise::SurpriseConfig;
+use crate::ui::panels::media_panel;
 use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
 
@@ -10,9 +13,12 @@ use crate::ui::theme::tokens::*;
 const TYPE_COLOR_EFFECT: Color32 = Color32::from_rgb(0x77, 0x66, 0xEE); // purple (same as shader)
 const TYPE_COLOR_MEDIA: Color32 = Color32::from_rgb(0xFF, 0x88, 0x33); // orange
 const TYPE_COLOR_WEBCAM: Color32 = Color32::from_rgb(0x33, 0xCC, 0xAA); // teal
+const TYPE_COLOR_GENERATOR: Color32 = Color32::from_rgb(0xEE, 0xCC, 0x33); // yellow
 
 fn layer_type_color(layer: &LayerInfo) -> Color32 {
-    if layer.media_is_live {
+    if layer.generator.is_some() {
+        TYPE_COLOR_GENERATOR
+    } else if layer.media_is_live {
         TYPE_COLOR_WEBCAM
     } else if layer.is_media {
         TYPE_COLOR_MEDIA
@@ -22,7 +28,9 @@ fn layer_type_color(layer: &LayerInfo) -> Color32 {
 }
 
 fn layer_type_label(layer: &LayerInfo) -> &'static str {
-    if layer.media_is_live {
+    if layer.generator.is_some() {
+        "GN"
+    } else if layer.media_is_live {
         "WC"
     } else if layer.is_media {
         "MD"
@@ -199,9 +207,15 @@ fn with_alpha(c: Color32, a: f32) -> Color32 {
 }
 
 /// Draw the layer management panel.
-pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize) {
+pub fn draw_layer_panel(
+    ui: &mut Ui,
+    layers: &[LayerInfo],
+    active_layer: usize,
+    max_layers: usize,
+    recent_media: &[String],
+    surprise: &SurpriseConfig,
+) {
     let tc = theme_colors(ui.ctx());
-    let max_layers = 8;
     let num_layers = layers.len();
     let ctx = ui.ctx().clone();
 
@@ -300,7 +314,7 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
                     };
                     let _header_resp = egui::Frame::new()
                         .fill(header_fill)
-                        .corner_radius(if is_active && num_layers > 1 {
+                        .corner_radius(if is_active {
                             CornerRadius {
                                 nw: 4,
                                 ne: 4,
@@ -490,7 +504,9 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
                                         d.insert_temp(egui::Id::new("layer_rename_text"), text);
                                     });
                                 } else {
-                                    let effect_display = if layer.is_media {
+                                    let effect_display = if let Some(kind) = layer.generator {
+                                        kind
+                                    } else if layer.is_media {
                                         layer.media_file_name.as_deref().unwrap_or("media")
                                     } else {
                                         layer.effect_name.as_deref().unwrap_or("(empty)")
@@ -545,8 +561,32 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
                         });
                     header_center_y = _header_resp.response.rect.center().y;
 
-                    // Blend mode + opacity shown BELOW the active layer header
-                    if is_active && num_layers > 1 {
+                    // Frame-time guard (#2243): say why the layer stopped
+                    if let Some(reason) = &layer.suspended {
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                RichText::new("Suspended")
+                                    .size(SMALL_SIZE)
+                                    .color(tc.warning),
+                            )
+                            .on_hover_text(reason);
+                            let resume = ui
+                                .small_button(RichText::new("Resume").size(SMALL_SIZE))
+                                .on_hover_text(
+                                    "Run the layer again. It is suspended again if it \
+                                     stays over the budget.",
+                                );
+                            if resume.clicked() {
+                                ui.ctx().data_mut(|d| {
+                                    d.insert_temp(egui::Id::new("layer_resume"), i);
+                                });
+                            }
+                        });
+                    }
+
+                    // Blend mode + opacity (with 2+ layers) and trail shown BELOW
+                    // the active layer header
+                    if is_active {
                         egui::Frame::new()
                             .fill(tc.widget_bg)
                             .corner_radius(CornerRadius {
@@ -558,80 +598,88 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
                             .inner_margin(egui::Margin::symmetric(6, 4))
                             .show(ui, |ui| {
                                 ui.add_enabled_ui(!layer.locked, |ui| {
-                                    ui.horizontal(|ui| {
-                                        ui.label(
-                                            RichText::new("Blend")
-                                                .size(SMALL_SIZE)
-                                                .color(tc.text_secondary),
-                                        );
-                                        let help_id = egui::Id::new(format!("blend_help_{i}"));
-                                        let current_mode = layer.blend_mode;
-                                        let combo_width = ui.available_width() - 22.0;
-                                        egui::ComboBox::from_id_salt(format!("blend_mode_{i}"))
-                                            .selected_text(
-                                                RichText::new(current_mode.display_name())
-                                                    .size(SMALL_SIZE),
-                                            )
-                                            .width(combo_width)
-                                            .show_ui(ui, |ui| {
-                                                for &mode in BlendMode::ALL {
-                                                    let r = ui.selectable_label(
-                                                        mode == current_mode,
-                                                        RichText::new(mode.display_name())
-                                                            .size(SMALL_SIZE),
-                                                    );
-                                                    if r.clicked() && mode != current_mode {
-                                                        ui.ctx().data_mut(|d| {
-                                                            d.insert_temp(
-                                                                egui::Id::new("layer_blend"),
-                                                                mode.as_u32(),
-                                                            );
-                                                        });
-                                                    }
-                                                }
-                                            });
-                                        // Info button — painted as circled "i"
-                                        let btn_size = Vec2::splat(16.0);
-                                        let (rect, help_btn) =
-                                            ui.allocate_exact_size(btn_size, egui::Sense::click());
-                                        if ui.is_rect_visible(rect) {
-                                            let center = rect.center();
-                                            let radius = 7.0;
-                                            let color = if help_btn.hovered() {
-                                                tc.text_primary
-                                            } else {
-                                                tc.text_secondary
-                                            };
-                                            ui.painter().circle_stroke(
-                                                center,
-                                                radius,
-                                                Stroke::new(1.0_f32, color),
+                                    if num_layers > 1 {
+                                        ui.horizontal(|ui| {
+                                            ui.label(
+                                                RichText::new("Blend")
+                                                    .size(SMALL_SIZE)
+                                                    .color(tc.text_secondary),
                                             );
-                                            ui.painter().text(
-                                                center,
-                                                egui::Align2::CENTER_CENTER,
-                                                "i",
-                                                egui::FontId::proportional(SMALL_SIZE),
-                                                color,
+                                            let help_id = egui::Id::new(format!("blend_help_{i}"));
+                                            let current_mode = layer.blend_mode;
+                                            let combo_width = ui.available_width() - 22.0;
+                                            egui::ComboBox::from_id_salt(format!("blend_mode_{i}"))
+                                                .selected_text(
+                                                    RichText::new(current_mode.display_name())
+                                                        .size(SMALL_SIZE),
+                                                )
+                                                .width(combo_width)
+                                                .show_ui(ui, |ui| {
+                                                    for &mode in BlendMode::ALL {
+                                                        let r = ui.selectable_label(
+                                                            mode == current_mode,
+                                                            RichText::new(mode.display_name())
+                                                                .size(SMALL_SIZE),
+                                                        );
+                                                        if r.clicked() && mode != current_mode {
+                                                            ui.ctx().data_mut(|d| {
+                                                                d.insert_temp(
+                                                                    egui::Id::new("layer_blend"),
+                                                                    mode.as_u32(),
+                                                                );
+                                                            });
+                                                        }
+                                                    }
+                                                });
+                                            // Info button — painted as circled "i"
+                                            let btn_size = Vec2::splat(16.0);
+                                            let (rect, help_btn) = ui.allocate_exact_size(
+                                                btn_size,
+                                                egui::Sense::click(),
                                             );
-                                        }
-                                        if help_btn.clicked() {
-                                            let open: bool = ui
+                                            if ui.is_rect_visible(rect) {
+                                                let center = rect.center();
+                                                let radius = 7.0;
+                                                let color = if help_btn.hovered() {
+                                                    tc.text_primary
+                                                } else {
+                                                    tc.text_secondary
+                                                };
+                                                ui.painter().circle_stroke(
+                                                    center,
+                                                    radius,
+                                                    Stroke::new(1.0_f32, color),
+                                                );
+                                                ui.painter().text(
+                                                    center,
+                                                    egui::Align2::CENTER_CENTER,
+                                                    "i",
+                                                    egui::FontId::proportional(SMALL_SIZE),
+                                                    color,
+                                                );
+                                            }
+                                            if help_btn.clicked() {
+                                                let open: bool = ui
+                                                    .ctx()
+                                                    .data(|d| d.get_temp(help_id).unwrap_or(false));
+                                                ui.ctx()
+                                                    .data_mut(|d| d.insert_temp(help_id, !open));
+                                            }
+                                            let blend_help_open: bool = ui
                                                 .ctx()
                                                 .data(|d| d.get_temp(help_id).unwrap_or(false));
-                                            ui.ctx().data_mut(|d| d.insert_temp(help_id, !open));
-                                        }
-                                        let blend_help_open: bool =
-                                            ui.ctx().data(|d| d.get_temp(help_id).unwrap_or(false));
-                                        if blend_help_open {
-                                            let area_resp = egui::Area::new(help_id.with("popup"))
-                                                .order(egui::Order::Foreground)
-                                                .fixed_pos(help_btn.rect.right_bottom())
-                                                .show(ui.ctx(), |ui| {
-                                                    egui::Frame::popup(ui.style()).show(ui, |ui| {
-                                                        for &mode in BlendMode::ALL {
-                                                            ui.horizontal(|ui| {
-                                                                ui.label(
+                                            if blend_help_open {
+                                                let area_resp =
+                                                    egui::Area::new(help_id.with("popup"))
+                                                        .order(egui::Order::Foreground)
+                                                        .fixed_pos(help_btn.rect.right_bottom())
+                                                        .show(ui.ctx(), |ui| {
+                                                            egui::Frame::popup(ui.style()).show(
+                                                                ui,
+                                                                |ui| {
+                                                                    for &mode in BlendMode::ALL {
+                                                                        ui.horizontal(|ui| {
+                                                                            ui.label(
                                                                     RichText::new(
                                                                         mode.display_name(),
                                                                     )
@@ -639,55 +687,111 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
                                                                     .strong()
                                                                     .color(tc.text_primary),
                                                                 );
-                                                                ui.label(
+                                                                            ui.label(
                                                                     RichText::new(
                                                                         mode.description(),
                                                                     )
                                                                     .size(SMALL_SIZE)
                                                                     .color(tc.text_secondary),
                                                                 );
-                                                            });
-                                                        }
+                                                                        });
+                                                                    }
+                                                                },
+                                                            );
+                                                        });
+                                                // Close on click outside popup
+                                                if ui.input(|i| i.pointer.any_click())
+                                                    && !area_resp.response.hovered()
+                                                    && !help_btn.hovered()
+                                                {
+                                                    ui.ctx().data_mut(|d| {
+                                                        d.insert_temp(help_id, false)
                                                     });
+                                                }
+                                            }
+                                        });
+
+                                        ui.horizontal(|ui| {
+                                            ui.label(
+                                                RichText::new("Opacity")
+                                                    .size(SMALL_SIZE)
+                                                    .color(tc.text_secondary),
+                                            );
+                                            let saved_bg = ui.visuals().widgets.inactive.bg_fill;
+                                            ui.visuals_mut().widgets.inactive.bg_fill = tc.meter_bg;
+                                            let mut opacity = layer.opacity;
+                                            let slider = ui.add(
+                                                egui::Slider::new(&mut opacity, 0.0..=1.0)
+                                                    .show_value(true)
+                                                    .custom_formatter(|v, _| {
+                                                        format!("{:.0}%", v * 100.0)
+                                                    })
+                                                    .text(""),
+                                            );
+                                            ui.visuals_mut().widgets.inactive.bg_fill = saved_bg;
+                                            if slider.changed() {
+                                                ui.ctx().data_mut(|d| {
+                                                    d.insert_temp(
+                                                        egui::Id::new("layer_opacity"),
+                                                        opacity,
+                                                    );
                                                 });
-                                            // Close on click outside popup
-                                            if ui.input(|i| i.pointer.any_click())
-                                                && !area_resp.response.hovered()
-                                                && !help_btn.hovered()
-                                            {
-                                                ui.ctx()
-                                                    .data_mut(|d| d.insert_temp(help_id, false));
                                             }
-                                        }
-                                    });
+                                        });
+                                    }
 
+                                    // Trail (#2173): previous frames linger on any layer type
                                     ui.horizontal(|ui| {
                                         ui.label(
-                                            RichText::new("Opacity")
+                                            RichText::new("Trail")
                                                 .size(SMALL_SIZE)
                                                 .color(tc.text_secondary),
                                         );
                                         let saved_bg = ui.visuals().widgets.inactive.bg_fill;
                                         ui.visuals_mut().widgets.inactive.bg_fill = tc.meter_bg;
-                                        let mut opacity = layer.opacity;
+                                        let mut trail = layer.trail;
                                         let slider = ui.add(
-                                            egui::Slider::new(&mut opacity, 0.0..=1.0)
+                                            egui::Slider::new(&mut trail, 0.0..=0.98)
                                                 .show_value(true)
                                                 .custom_formatter(|v, _| {
-                                                    format!("{:.0}%", v * 100.0)
+                                                    if v <= 0.0 {
+                                                        "Off".to_string()
+                                                    } else {
+                                                        format!("{:.0}%", v * 100.0)
+                                                    }
                                                 })
                                                 .text(""),
                                         );
                                         ui.visuals_mut().widgets.inactive.bg_fill = saved_bg;
                                         if slider.changed() {
                                             ui.ctx().data_mut(|d| {
-                                                d.insert_temp(
-                                                    egui::Id::new("layer_opacity"),
-                                                    opacity,
-                                                );
+                                                d.insert_temp(egui::Id::new("layer_trail"), trail);
                                             });
                                         }
+                                        slider.on_hover_text(
+                                            "How much of the previous frame stays behind. \
+                                             Works on effects, media and webcam alike.",
+                                        );
                                     });
+
+                                    draw_envelope(ui, i, &layer.envelope, layer.envelope_gain);
+
+                                    // Audio mute (#2206): hold this layer steady
+                                    // while the others react
+                                    let mut muted = layer.audio_muted;
+                                    let cb = ui.checkbox(
+                                        &mut muted,
+                                        RichText::new("Ignore audio").size(SMALL_SIZE),
+                                    );
+                                    if cb.changed() {
+                                        ui.ctx().data_mut(|d| {
+                                            d.insert_temp(egui::Id::new("layer_audio_mute"), muted);
+                                        });
+                                    }
+                                    cb.on_hover_text(
+                                        "This layer sees silence: audio uniforms, particles \
+                                         and audio mappings stop following the music",
+                                    );
                                 });
                             });
                     }
@@ -698,7 +802,7 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
 
             // Left type color strip (3px)
             let strip_alpha = if layer.enabled { 1.0 } else { 0.5 };
-            let strip_sw = if is_active && num_layers > 1 { 0 } else { 4 };
+            let strip_sw = if is_active { 0 } else { 4 };
             let strip_rect =
                 Rect::from_min_size(card_rect.left_top(), Vec2::new(3.0, card_rect.height()));
             ui.painter().rect_filled(
@@ -822,6 +926,7 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
     // Add Layer / Add Media buttons
     ui.add_space(4.0);
     let can_add = num_layers < max_layers;
+    let full_tip = format!("Maximum {max_layers} layers reached");
 
     // Type-colored button helper
     let type_btn = |ui: &mut Ui,
@@ -854,10 +959,13 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
     };
 
     ui.horizontal(|ui| {
-        #[cfg(feature = "webcam")]
-        let btn_count = 3.0_f32;
-        #[cfg(not(feature = "webcam"))]
-        let btn_count = 2.0_f32;
+        let mut btn_count = 3.0_f32;
+        if cfg!(feature = "webcam") {
+            btn_count += 1.0;
+        }
+        if cfg!(feature = "video") {
+            btn_count += 1.0;
+        }
         let spacing = ui.spacing().item_spacing.x;
         let btn_width =
             ((ui.available_width() - spacing * (btn_count - 1.0)) / btn_count).max(30.0);
@@ -868,9 +976,9 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
                 .data_mut(|d| d.insert_temp(egui::Id::new("add_layer"), true));
         }
         if can_add {
-            add_btn.on_hover_text("Add an effect layer (max 8)");
+            add_btn.on_hover_text(format!("Add an effect layer (max {max_layers})"));
         } else {
-            add_btn.on_hover_text("Maximum 8 layers reached");
+            add_btn.on_hover_text(full_tip.as_str());
         }
 
         let media_btn = type_btn(ui, "+ Media", TYPE_COLOR_MEDIA, can_add, btn_width);
@@ -878,10 +986,39 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
             ui.ctx()
                 .data_mut(|d| d.insert_temp(egui::Id::new("add_media_layer"), true));
         }
+        if can_add && !recent_media.is_empty() {
+            // Right-click reopens a recent file as a new layer (#2245)
+            media_btn.context_menu(|ui| {
+                for entry in recent_media {
+                    let label = RichText::new(media_panel::recent_label(entry)).size(SMALL_SIZE);
+                    if ui.button(label).on_hover_text(entry.as_str()).clicked() {
+                        ui.ctx().data_mut(|d| {
+                            d.insert_temp(egui::Id::new("add_recent_media"), entry.clone())
+                        });
+                        ui.close();
+                    }
+                }
+            });
+            media_btn.on_hover_text(format!(
+                "Add an image/GIF layer (max {max_layers}); right-click for recent files"
+            ));
+        } else if can_add {
+            media_btn.on_hover_text(format!("Add an image/GIF layer (max {max_layers})"));
+        } else {
+            media_btn.on_hover_text(full_tip.as_str());
+        }
+
+        let gen_btn = type_btn(ui, "+ Gen", TYPE_COLOR_GENERATOR, can_add, btn_width);
+        if gen_btn.clicked() {
+            ui.ctx()
+                .data_mut(|d| d.insert_temp(egui::Id::new("add_generator_layer"), true));
+        }
         if can_add {
-            media_btn.on_hover_text("Add an image/GIF layer (max 8)");
+            gen_btn.on_hover_text(format!(
+                "Add a strobe / color wash layer (max {max_layers})"
+            ));
         } else {
-            media_btn.on_hover_text("Maximum 8 layers reached");
+            gen_btn.on_hover_text(full_tip.as_str());
         }
 
         #[cfg(feature = "webcam")]
@@ -897,13 +1034,36 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
                     .data_mut(|d| d.insert_temp(egui::Id::new("add_webcam_layer"), device_idx));
             }
             if can_add {
-                webcam_btn.on_hover_text("Add a live webcam layer (max 8)");
+                webcam_btn.on_hover_text(format!("Add a live webcam layer (max {max_layers})"));
             } else {
-                webcam_btn.on_hover_text("Maximum 8 layers reached");
+                webcam_btn.on_hover_text(full_tip.as_str());
+            }
+        }
+
+        #[cfg(feature = "video")]
+        {
+            let stream_btn = type_btn(ui, "+ Stream", TYPE_COLOR_MEDIA, can_add, btn_width);
+            if stream_btn.clicked() {
+                ui.ctx().data_mut(|d| {
+                    let open = d.get_temp_mut_or_default::<bool>(egui::Id::new("stream_url_open"));
+                    *open = !*open;
+                });
+            }
+            if can_add {
+                stream_btn.on_hover_text(format!(
+                    "Add a network stream layer: RTSP, SRT, HLS, HTTP (max {max_layers})"
+                ));
+            } else {
+                stream_btn.on_hover_text(full_tip.as_str());
             }
         }
     });
 
+    #[cfg(feature = "video")]
+    draw_stream_url_row(ui, can_add);
+
+    draw_surprise(ui, surprise, max_layers);
+
     // Clear All — subtle text link with 2-second armed confirmation
     if num_layers > 1 {
         ui.add_space(2.0);
@@ -972,8 +1132,9 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
     // Footer: type breakdown counts
     let fx_count = layers
         .iter()
-        .filter(|l| !l.is_media && !l.media_is_live)
+        .filter(|l| !l.is_media && !l.media_is_live && l.generator.is_none())
         .count();
+    let gen_count = layers.iter().filter(|l| l.generator.is_some()).count();
     let media_count = layers
         .iter()
         .filter(|l| l.is_media && !l.media_is_live)
@@ -984,9 +1145,198 @@ pub fn draw_layer_panel(ui: &mut Ui, layers: &[LayerInfo], active_layer: usize)
     ui.separator();
     ui.label(
         RichText::new(format!(
-            "{fx_count} effect · {media_count} media · {webcam_count} webcam"
+            "{fx_count} effect · {media_count} media · {webcam_count} webcam · {gen_count} gen"
         ))
         .size(7.0)
         .color(tc.text_secondary),
     );
 }
+
+/// Opacity envelope (#2249): shape, then the bars/depth it uses, with a
+/// restart button so a fade can be fired again at a drop.
+fn draw_envelope(ui: &mut Ui, i: usize, current: &OpacityEnvelope, gain: f32) {
+    let tc = theme_colors(ui.ctx());
+    let mut env = current.clone();
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new("Envelope")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        )
+        .on_hover_text(
+            "Shape the layer's opacity on the beat clock: fade in or out over a \
+             number of bars, pulse on every beat, or swell and dip once per period. \
+             Scales the opacity slider; saved with the preset.",
+        );
+        egui::ComboBox::from_id_salt(format!("layer_envelope_{i}"))
+            .selected_text(RichText::new(env.shape.display_name()).size(SMALL_SIZE))
+            .show_ui(ui, |ui| {
+                for &shape in EnvelopeShape::ALL {
+                    ui.selectable_value(
+                        &mut env.shape,
+                        shape,
+                        RichText::new(shape.display_name()).size(SMALL_SIZE),
+                    );
+                }
+            });
+        if !current.is_off() {
+            ui.label(
+                RichText::new(format!("{:.0}%", gain * 100.0))
+                    .size(SMALL_SIZE)
+                    .color(tc.text_secondary),
+            );
+            if ui
+                .small_button("\u{21ba}")
+                .on_hover_text("Restart the envelope")
+                .clicked()
+            {
+                ui.ctx().data_mut(|d| {
+                    d.insert_temp(egui::Id::new("layer_envelope_restart"), true);
+                });
+            }
+        }
+    });
+
+    let slider = |ui: &mut Ui,
+                  label: &str,
+                  value: &mut f32,
+                  range: std::ops::RangeInclusive<f32>,
+                  fmt: fn(f64) -> String| {
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(label)
+                    .size(SMALL_SIZE)
+                    .color(tc.text_secondary),
+            );
+            ui.add(
+                egui::Slider::new(value, range)
+                    .custom_formatter(move |v, _| fmt(v))
+                    .text(""),
+            );
+        });
+    };
+    let bars = |v: f64| format!("{v:.2} bars");
+    if current.shape.uses_bars() {
+        let label = if current.shape == EnvelopeShape::Lfo {
+            "Period"
+        } else {
+            "Length"
+        };
+        slider(ui, label, &mut env.bars, 0.25..=32.0, bars);
+        if current.shape != EnvelopeShape::Lfo {
+            slider(ui, "After", &mut env.after_bars, 0.0..=64.0, bars);
+        }
+    }
+    if current.shape.uses_depth() {
+        slider(ui, "Depth", &mut env.depth, 0.0..=1.0, |v| {
+            format!("{:.0}%", v * 100.0)
+        });
+    }
+
+    if env != *current {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("layer_envelope"), env);
+        });
+    }
+}
+
+/// Surprise me (#2251): one click for a random stack; right-click sets how
+/// many layers, which effect tag each is drawn from, and how far params stray.
+fn draw_surprise(ui: &mut Ui, config: &SurpriseConfig, max_layers: usize) {
+    ui.add_space(2.0);
+    let resp = ui.add_sized(
+        Vec2::new(ui.available_width(), MIN_INTERACT_HEIGHT),
+        egui::Button::new(RichText::new("Surprise me").size(SMALL_SIZE)),
+    );
+    if resp.clicked() {
+        ui.ctx()
+            .data_mut(|d| d.insert_temp(egui::Id::new("surprise_me"), true));
+    }
+    let mut edited = config.clone();
+    resp.context_menu(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("Layers").size(SMALL_SIZE));
+            ui.add(egui::DragValue::new(&mut edited.layers).range(1..=max_layers));
+        });
+        ui.add(
+            egui::Slider::new(&mut edited.spread, 0.0..=1.0)
+                .text(RichText::new("Spread").size(SMALL_SIZE)),
+        )
+        .on_hover_text("How far params may stray from their defaults, as a share of their range");
+        ui.label(RichText::new("Effect tag per layer (blank = any)").size(SMALL_SIZE));
+        edited
+            .pools
+            .resize(edited.layers.max(edited.pools.len()), String::new());
+        for (i, tag) in edited.pools.iter_mut().take(edited.layers).enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("{i}")).size(SMALL_SIZE).monospace());
+                ui.add(
+                    egui::TextEdit::singleline(tag)
+                        .hint_text("shader, particle, feedback or a .pfx tag")
+                        .font(egui::TextStyle::Small)
+                        .desired_width(180.0),
+                );
+            });
+        }
+        // Trailing blanks carry nothing
+        while edited.pools.last().is_some_and(|t| t.trim().is_empty()) {
+            edited.pools.pop();
+        }
+    });
+    resp.on_hover_text(
+        "Random effects, params and blend modes in place of the current stack. \
+         Locked layers stay. Right-click to set layers, tags and spread.",
+    );
+    if edited != *config {
+        ui.ctx()
+            .data_mut(|d| d.insert_temp(egui::Id::new("surprise_config"), edited));
+    }
+}
+
+/// Inline URL entry toggled by "+ Stream". Enter or "Open" adds the layer.
+#[cfg(feature = "video")]
+fn draw_stream_url_row(ui: &mut Ui, can_add: bool) {
+    let open_id = egui::Id::new("stream_url_open");
+    let text_id = egui::Id::new("stream_url_text");
+    let open: bool = ui.ctx().data(|d| d.get_temp(open_id)).unwrap_or(false);
+    if !open {
+        return;
+    }
+    let mut url: String = ui.ctx().data(|d| d.get_temp(text_id)).unwrap_or_default();
+
+    ui.add_space(2.0);
+    let mut submit = false;
+    ui.horizontal(|ui| {
+        let open_w = 44.0;
+        let resp = ui.add(
+            egui::TextEdit::singleline(&mut url)
+                .hint_text("rtsp://, srt://, https://\u{2026}")
+                .font(egui::TextStyle::Small)
+                .desired_width(ui.available_width() - open_w - 6.0),
+        );
+        if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            submit = true;
+        }
+        let valid = crate::media::decoder::is_stream_url(url.trim());
+        if ui
+            .add_enabled(
+                can_add && valid,
+                egui::Button::new(RichText::new("Open").size(SMALL_SIZE)),
+            )
+            .clicked()
+        {
+            submit = true;
+        }
+    });
+
+    let trimmed = url.trim().to_string();
+    if submit && can_add && crate::media::decoder::is_stream_url(&trimmed) {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("add_stream_layer"), trimmed);
+            d.insert_temp(open_id, false);
+        });
+    } else {
+        ui.ctx().data_mut(|d| d.insert_temp(text_id, url));
+    }
+}
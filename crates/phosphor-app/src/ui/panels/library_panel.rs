@@ -0,0 +1,122 @@
+//! Effect library updates (#2180): the feed settings, a check button and the
+//! packs the last check found. Actions go to main.rs through egui temp data.
+
+use egui::{RichText, Ui};
+
+use crate::effect::library::{LibraryConfig, LibraryState, LibraryUpdater, PackState};
+use crate::ui::theme::colors::theme_colors;
+use crate::ui::theme::tokens::*;
+use crate::ui::widgets::rows;
+
+pub fn draw_library_panel(ui: &mut Ui, updater: &LibraryUpdater, config: &LibraryConfig) {
+    let tc = theme_colors(ui.ctx());
+
+    let mut edited = config.clone();
+    for (label, value, tip) in [
+        (
+            "Manifest URL",
+            &mut edited.manifest_url,
+            "Where the effect pack list is published",
+        ),
+        (
+            "Public key",
+            &mut edited.public_key,
+            "Hex Ed25519 key the list must be signed with; unsigned or altered lists are refused",
+        ),
+    ] {
+        rows::custom_row(ui, label, Some(tip), |ui| {
+            ui.add(
+                egui::TextEdit::singleline(value)
+                    .desired_width(ui.available_width().max(60.0))
+                    .font(egui::TextStyle::Small),
+            );
+        });
+    }
+    if edited != *config {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("set_library_config"), edited);
+        });
+    }
+
+    ui.horizontal(|ui| {
+        let check = ui.add_enabled(
+            config.is_configured() && !updater.is_busy(),
+            egui::Button::new(RichText::new("Check for new effects").size(SMALL_SIZE)),
+        );
+        if check.clicked() {
+            ui.ctx().data_mut(|d| {
+                d.insert_temp(egui::Id::new("library_check"), true);
+            });
+        }
+        if updater.is_busy() {
+            ui.spinner();
+        }
+    });
+
+    let (list, installing) = match &updater.state {
+        LibraryState::Idle => return,
+        LibraryState::Checking => {
+            ui.label(
+                RichText::new("Checking\u{2026}")
+                    .size(SMALL_SIZE)
+                    .color(tc.text_secondary),
+            );
+            return;
+        }
+        LibraryState::Error(e) => {
+            ui.label(RichText::new(e).size(SMALL_SIZE).color(tc.error));
+            return;
+        }
+        LibraryState::Ready(list) => (list, None),
+        LibraryState::Installing(name, list) => (list, Some(name.as_str())),
+    };
+
+    if list.is_empty() {
+        ui.label(
+            RichText::new("No packs published")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        );
+        return;
+    }
+    for pack in list {
+        let entry = &pack.entry;
+        ui.horizontal(|ui| {
+            let title = if entry.version.is_empty() {
+                entry.name.clone()
+            } else {
+                format!("{} {}", entry.name, entry.version)
+            };
+            let label = ui.label(RichText::new(title).size(SMALL_SIZE).strong());
+            if !entry.description.is_empty() {
+                label.on_hover_text(entry.description.as_str());
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if installing == Some(entry.name.as_str()) {
+                    ui.spinner();
+                } else if pack.state == PackState::Installed {
+                    ui.label(
+                        RichText::new(pack.state.label())
+                            .size(SMALL_SIZE)
+                            .color(tc.text_secondary),
+                    );
+                } else {
+                    let verb = if pack.state == PackState::New {
+                        "Install"
+                    } else {
+                        "Update"
+                    };
+                    let btn = ui.add_enabled(
+                        installing.is_none(),
+                        egui::Button::new(RichText::new(verb).size(SMALL_SIZE)),
+                    );
+                    if btn.clicked() {
+                        ui.ctx().data_mut(|d| {
+                            d.insert_temp(egui::Id::new("library_install"), entry.name.clone());
+                        });
+                    }
+                }
+            });
+        });
+    }
+}
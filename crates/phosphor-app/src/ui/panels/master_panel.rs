@@ -0,0 +1,157 @@
+//! Output master controls (#2165): blackout plus the final brightness /
+//! contrast / gamma grade. Edits land in `MasterState` in place; main.rs
+//! diffs it afterwards to route them to settings or the current preset.
+//! The freeze-frame controls (#2166) sit here too, as the other output-wide
+//! performance switch, and the output dither (#2236) and per-output
+//! calibration (#2237).
+
+use egui::Ui;
+
+use crate::gpu::calibration::{Calibration, OutputTarget};
+use crate::gpu::freeze::FreezeFrame;
+use crate::gpu::master::{MasterParams, MasterState};
+use crate::ui::theme::colors::theme_colors;
+use crate::ui::widgets::{self, rows};
+
+pub fn draw_master_panel(ui: &mut Ui, master: &mut MasterState, freeze: &mut FreezeFrame) {
+    let tc = theme_colors(ui.ctx());
+
+    let (label, fill) = if master.blackout {
+        ("BLACKOUT", tc.error)
+    } else {
+        ("Blackout", tc.widget_bg)
+    };
+    let width = ui.available_width();
+    if ui
+        .add(
+            egui::Button::new(egui::RichText::new(label).size(11.0).strong())
+                .fill(fill)
+                .min_size(egui::vec2(width, 24.0)),
+        )
+        .on_hover_text("Cut the output to black (K)")
+        .clicked()
+    {
+        master.blackout = !master.blackout;
+    }
+    ui.add_space(4.0);
+
+    let p = &mut master.params;
+    rows::ParamRow::new("Brightness").show_slider(ui, &mut p.brightness, 0.0..=2.0);
+    rows::ParamRow::new("Contrast").show_slider(ui, &mut p.contrast, 0.0..=2.0);
+    rows::ParamRow::new("Gamma")
+        .tooltip("Above 1 lifts the shadows")
+        .show_slider(ui, &mut p.gamma, 0.5..=2.5);
+
+    ui.add_space(4.0);
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut master.per_preset, "Save with preset")
+            .on_hover_text("Off: one grade for every preset, kept in settings");
+        if ui.button("Reset").clicked() {
+            master.params = MasterParams::default();
+        }
+    });
+
+    ui.add_space(4.0);
+    let dither = &mut master.dither;
+    ui.checkbox(&mut dither.enabled, "Dither output")
+        .on_hover_text(
+            "Blue-noise grain that breaks up banding in dark gradients and fades on \
+         8-bit projectors. Applies to NDI and recordings too; kept in settings.",
+        );
+    rows::ParamRow::new("Dither strength")
+        .tooltip("1 spreads each pixel across one 8-bit step")
+        .enabled(dither.enabled)
+        .show_slider(ui, &mut dither.strength, 0.0..=2.0);
+
+    draw_calibration(ui, master);
+
+    ui.add_space(4.0);
+    let frozen = freeze.is_active();
+    widgets::subsection(
+        ui,
+        "master_freeze",
+        "Freeze Frame",
+        frozen.then_some("HELD"),
+        tc.accent,
+        true,
+        |ui| {
+            // With decay on, a press re-latches instead of releasing.
+            let releases = frozen && freeze.params.decay <= 0.0;
+            let label = if releases { "Release" } else { "Freeze" };
+            let fill = if frozen { tc.accent } else { tc.widget_bg };
+            let width = ui.available_width();
+            if ui
+                .add(
+                    egui::Button::new(egui::RichText::new(label).size(11.0).strong())
+                        .fill(fill)
+                        .min_size(egui::vec2(width, 22.0)),
+                )
+                .on_hover_text("Hold the current frame (H)")
+                .clicked()
+            {
+                freeze.trigger();
+            }
+            rows::ParamRow::new("Decay (s)")
+                .tooltip("Fade back to live over this long; 0 holds until released")
+                .show_slider(ui, &mut freeze.params.decay, 0.0..=8.0);
+            ui.checkbox(&mut freeze.params.under, "Under live output")
+                .on_hover_text("Live output stays on top; the held frame shows in its dark areas");
+        },
+    );
+}
+
+/// Trims for the window or the NDI feed. Recordings are never calibrated, so
+/// they keep the reference image.
+fn draw_calibration(ui: &mut Ui, master: &mut MasterState) {
+    let tc = theme_colors(ui.ctx());
+    let cal = &mut master.calibration;
+    let active = !cal.window.is_identity() || !cal.ndi.is_identity();
+    widgets::subsection(
+        ui,
+        "master_calibration",
+        "Output Calibration",
+        active.then_some("ON"),
+        tc.accent,
+        false,
+        |ui| {
+            let target_id = egui::Id::new("calibration_target");
+            let ndi = ui.data(|d| d.get_temp::<bool>(target_id)).unwrap_or(false);
+            let mut target = if ndi {
+                OutputTarget::Ndi
+            } else {
+                OutputTarget::Window
+            };
+            if cfg!(feature = "ndi") {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut target, OutputTarget::Window, "Window");
+                    ui.selectable_value(&mut target, OutputTarget::Ndi, "NDI");
+                });
+                ui.data_mut(|d| d.insert_temp(target_id, target == OutputTarget::Ndi));
+            }
+            let k = match target {
+                OutputTarget::Ndi => &mut cal.ndi,
+                _ => &mut cal.window,
+            };
+
+            rows::ParamRow::new("Gamma")
+                .tooltip("Above 1 lifts the shadows on this output only")
+                .show_slider(ui, &mut k.gamma, 0.5..=2.5);
+            rows::ParamRow::new("Saturation").show_slider(ui, &mut k.saturation, 0.0..=2.0);
+            for (i, name) in ["Red", "Green", "Blue"].into_iter().enumerate() {
+                rows::ParamRow::new(&format!("{name} gain")).show_slider(
+                    ui,
+                    &mut k.gain[i],
+                    0.0..=2.0,
+                );
+            }
+            for (i, name) in ["Red", "Green", "Blue"].into_iter().enumerate() {
+                rows::ParamRow::new(&format!("{name} offset"))
+                    .tooltip("Lifts or lowers this channel; fades out with blackout")
+                    .show_slider(ui, &mut k.offset[i], -0.2..=0.2);
+            }
+            if ui.button("Reset").clicked() {
+                *k = Calibration::default();
+            }
+        },
+    );
+}
@@ -1,6 +1,12 @@
+use std::path::Path;
+
 use egui::{RichText, Ui};
 
-use crate::media::types::PlayDirection;
+use crate::media::decoder::is_stream_url;
+use crate::media::playlist::PlaylistAdvance;
+use crate::media::types::{
+    AudioDrive, AudioDriveMode, MediaProjection, PlayDirection, ProjectionMode,
+};
 use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
 
@@ -19,23 +25,109 @@ pub struct MediaInfo {
     pub current_frame: usize,
     pub video_position_secs: f64,
     pub video_duration_secs: f64,
+    pub projection: MediaProjection,
+    pub audio: AudioDrive,
+    pub playlist: Option<PlaylistInfo>,
+    /// Set for network stream layers.
+    pub stream: Option<StreamInfo>,
+}
+
+/// Connection state of a network stream layer.
+#[cfg_attr(not(feature = "video"), allow(dead_code))]
+pub struct StreamInfo {
+    pub status: &'static str,
+    /// Frames are arriving (false while connecting or reconnecting).
+    pub live: bool,
+    pub reconnects: u32,
+}
+
+/// Snapshot of the active layer's playlist for the media panel.
+pub struct PlaylistInfo {
+    pub clip_names: Vec<String>,
+    pub current: Option<usize>,
+    pub advance: PlaylistAdvance,
+    pub shuffle: bool,
+    /// A clip is decoding in the background.
+    pub loading: bool,
 }
 
-pub fn draw_media_panel(ui: &mut Ui, info: &MediaInfo) {
+/// How a recent media entry (#2245) reads in a list: stream URLs as they
+/// are, files by name.
+pub fn recent_label(entry: &str) -> String {
+    if is_stream_url(entry) {
+        return entry.to_string();
+    }
+    Path::new(entry)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| entry.to_string())
+}
+
+pub fn draw_media_panel(ui: &mut Ui, info: &MediaInfo, recent: &[String]) {
     let tc = theme_colors(ui.ctx());
 
-    // File info
-    ui.label(
-        RichText::new(&info.file_name)
-            .size(BODY_SIZE)
-            .color(tc.text_primary),
-    );
+    // File info, with the recent list one click away
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new(&info.file_name)
+                .size(BODY_SIZE)
+                .color(tc.text_primary),
+        );
+        if recent.is_empty() {
+            return;
+        }
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            egui::ComboBox::from_id_salt("media_open_recent")
+                .selected_text(RichText::new("Recent").size(SMALL_SIZE))
+                .width(70.0)
+                .show_ui(ui, |ui| {
+                    for entry in recent {
+                        let label = RichText::new(recent_label(entry)).size(SMALL_SIZE);
+                        if ui
+                            .selectable_label(false, label)
+                            .on_hover_text(entry.as_str())
+                            .clicked()
+                        {
+                            ui.ctx().data_mut(|d| {
+                                d.insert_temp(egui::Id::new("media_open_recent"), entry.clone())
+                            });
+                        }
+                    }
+                })
+                .response
+                .on_hover_text("Replace this layer's media with a recent file");
+        });
+    });
     ui.label(
         RichText::new(format!("{}x{}", info.media_width, info.media_height))
             .size(SMALL_SIZE)
             .color(tc.text_secondary),
     );
 
+    if let Some(ref stream) = info.stream {
+        ui.horizontal(|ui| {
+            if stream.live {
+                ui.label(RichText::new("\u{25cf}").size(SMALL_SIZE).color(tc.success));
+            } else {
+                // Buffering indicator
+                ui.add(egui::Spinner::new().size(SMALL_SIZE));
+            }
+            let color = if stream.live {
+                tc.text_secondary
+            } else {
+                tc.warning
+            };
+            ui.label(RichText::new(stream.status).size(SMALL_SIZE).color(color));
+            if stream.reconnects > 0 {
+                ui.label(
+                    RichText::new(format!("({} drops)", stream.reconnects))
+                        .size(SMALL_SIZE)
+                        .color(tc.text_secondary),
+                );
+            }
+        });
+    }
+
     if info.is_video {
         // While seek slider is being dragged, show drag position in time display
         let seek_id = egui::Id::new("media_seek_drag");
@@ -214,6 +306,273 @@ pub fn draw_media_panel(ui: &mut Ui, info: &MediaInfo) {
                 .color(tc.text_secondary),
         );
     }
+
+    if info.frame_count > 1 {
+        ui.add_space(8.0);
+        draw_audio_drive(ui, info.audio);
+    }
+
+    ui.add_space(8.0);
+    draw_projection(ui, info.projection);
+
+    ui.add_space(8.0);
+    draw_playlist(ui, info.playlist.as_ref());
+}
+
+/// Audio-driven playback (#2248): mode, then the part of the clip it uses.
+fn draw_audio_drive(ui: &mut Ui, current: AudioDrive) {
+    let tc = theme_colors(ui.ctx());
+    let mut drive = current;
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new("Audio")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        )
+        .on_hover_text(
+            "Let the music move the playhead: jump to a random frame on each onset, \
+             set the position from the bass level, or play only for half a beat after \
+             each beat. Ignored while the layer's audio is muted.",
+        );
+        egui::ComboBox::from_id_salt("media_audio_drive")
+            .selected_text(RichText::new(drive.mode.display_name()).size(SMALL_SIZE))
+            .show_ui(ui, |ui| {
+                for &mode in AudioDriveMode::ALL {
+                    ui.selectable_value(
+                        &mut drive.mode,
+                        mode,
+                        RichText::new(mode.display_name()).size(SMALL_SIZE),
+                    );
+                }
+            });
+    });
+
+    if matches!(
+        current.mode,
+        AudioDriveMode::OnsetJump | AudioDriveMode::BassScrub
+    ) {
+        let slider = |ui: &mut Ui, label: &str, value: &mut f32| {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(label)
+                        .size(SMALL_SIZE)
+                        .color(tc.text_secondary),
+                );
+                ui.add(
+                    egui::Slider::new(value, 0.0..=1.0)
+                        .custom_formatter(|v, _| format!("{:.0}%", v * 100.0))
+                        .text(""),
+                );
+            });
+        };
+        slider(ui, "From", &mut drive.from);
+        slider(ui, "To", &mut drive.to);
+        drive.to = drive.to.max(drive.from);
+    }
+
+    if drive != current {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("media_audio_drive"), drive);
+        });
+    }
+}
+
+/// 360° framing: mode selector, then yaw/pitch/fov for the equirect modes.
+fn draw_projection(ui: &mut Ui, current: MediaProjection) {
+    let tc = theme_colors(ui.ctx());
+    let mut proj = current;
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new("Projection")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        );
+        for &mode in ProjectionMode::ALL {
+            let selected = proj.mode == mode;
+            if ui
+                .selectable_label(
+                    selected,
+                    RichText::new(mode.display_name()).size(SMALL_SIZE),
+                )
+                .clicked()
+                && !selected
+            {
+                proj.mode = mode;
+                proj.fov = mode.default_fov();
+            }
+        }
+    });
+
+    if current.mode != ProjectionMode::Flat {
+        let slider = |ui: &mut Ui, label: &str, value: &mut f32, range| {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(label)
+                        .size(SMALL_SIZE)
+                        .color(tc.text_secondary),
+                );
+                ui.add(egui::Slider::new(value, range).suffix("°").text(""));
+            });
+        };
+        slider(ui, "Yaw", &mut proj.yaw, -180.0..=180.0);
+        slider(ui, "Pitch", &mut proj.pitch, -90.0..=90.0);
+        let fov_label = if current.mode == ProjectionMode::EquirectFisheye {
+            "Dome"
+        } else {
+            "FOV"
+        };
+        slider(
+            ui,
+            fov_label,
+            &mut proj.fov,
+            MediaProjection::fov_range(current.mode),
+        );
+    }
+
+    if proj != current {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("media_projection"), proj);
+        });
+    }
+}
+
+/// Playlist edits requested by the media panel, drained by the main loop.
+/// (`Default` is only required by egui's temp-data `remove_temp`.)
+#[derive(Debug, Clone, Copy, Default)]
+pub enum PlaylistSignal {
+    #[default]
+    AddClips,
+    Step(bool),
+    Jump(usize),
+    Remove(usize),
+    /// (clip, up)
+    Move(usize, bool),
+    Shuffle(bool),
+    Advance(PlaylistAdvance),
+}
+
+fn draw_playlist(ui: &mut Ui, info: Option<&PlaylistInfo>) {
+    let tc = theme_colors(ui.ctx());
+    let signal = |ui: &Ui, value: PlaylistSignal| {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("media_playlist"), value);
+        });
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new("Playlist")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        );
+        if info.is_some_and(|p| p.loading) {
+            ui.spinner();
+        }
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui
+                .button(RichText::new("+ Clips").size(SMALL_SIZE))
+                .on_hover_text("Add clips to this layer's playlist")
+                .clicked()
+            {
+                signal(ui, PlaylistSignal::AddClips);
+            }
+        });
+    });
+
+    let Some(pl) = info else {
+        return;
+    };
+
+    // Prev / Next + Shuffle
+    ui.horizontal(|ui| {
+        if ui.button(RichText::new("Prev").size(SMALL_SIZE)).clicked() {
+            signal(ui, PlaylistSignal::Step(false));
+        }
+        if ui.button(RichText::new("Next").size(SMALL_SIZE)).clicked() {
+            signal(ui, PlaylistSignal::Step(true));
+        }
+        let mut shuffle = pl.shuffle;
+        if ui.checkbox(&mut shuffle, "Shuffle").changed() {
+            signal(ui, PlaylistSignal::Shuffle(shuffle));
+        }
+    });
+
+    // Auto-advance
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new("Advance")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        );
+        let beats = match pl.advance {
+            PlaylistAdvance::Beats { beats } => beats,
+            _ => 8,
+        };
+        for mode in [
+            PlaylistAdvance::Manual,
+            PlaylistAdvance::OnEnd,
+            PlaylistAdvance::Beats { beats },
+        ] {
+            let selected = std::mem::discriminant(&mode) == std::mem::discriminant(&pl.advance);
+            if ui
+                .selectable_label(
+                    selected,
+                    RichText::new(mode.display_name()).size(SMALL_SIZE),
+                )
+                .clicked()
+                && !selected
+            {
+                signal(ui, PlaylistSignal::Advance(mode));
+            }
+        }
+    });
+    if let PlaylistAdvance::Beats { beats } = pl.advance {
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new("Every")
+                    .size(SMALL_SIZE)
+                    .color(tc.text_secondary),
+            );
+            let mut b = beats;
+            if ui
+                .add(egui::DragValue::new(&mut b).range(1..=64).suffix(" beats"))
+                .changed()
+            {
+                signal(
+                    ui,
+                    PlaylistSignal::Advance(PlaylistAdvance::Beats { beats: b }),
+                );
+            }
+        });
+    }
+
+    // Clip list — click to jump, arrows to reorder
+    for (i, name) in pl.clip_names.iter().enumerate() {
+        ui.horizontal(|ui| {
+            let current = pl.current == Some(i);
+            let label = RichText::new(format!("{}. {}", i + 1, name)).size(SMALL_SIZE);
+            if ui.selectable_label(current, label).clicked() && !current {
+                signal(ui, PlaylistSignal::Jump(i));
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .small_button("x")
+                    .on_hover_text("Remove from playlist")
+                    .clicked()
+                {
+                    signal(ui, PlaylistSignal::Remove(i));
+                }
+                if i + 1 < pl.clip_names.len() && ui.small_button("v").clicked() {
+                    signal(ui, PlaylistSignal::Move(i, false));
+                }
+                if i > 0 && ui.small_button("^").clicked() {
+                    signal(ui, PlaylistSignal::Move(i, true));
+                }
+            });
+        });
+    }
 }
 
 fn format_time(secs: f64) -> String {
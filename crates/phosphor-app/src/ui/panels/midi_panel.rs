@@ -1,6 +1,7 @@
 use egui::{Color32, RichText, Ui};
 
 use crate::midi::MidiSystem;
+use crate::midi::mapping::MidiMapping;
 use crate::midi::types::{LearnTarget, MidiMsgType, TriggerAction};
 use crate::ui::theme::tokens::*;
 
@@ -49,6 +50,8 @@ pub fn draw_midi_panel(ui: &mut Ui, midi: &mut MidiSystem) {
             }
         });
 
+    draw_clock_out(ui, midi);
+
     // Learn status (conditional)
     if let Some(ref learn_target) = midi.learn_target {
         let label = match learn_target {
@@ -63,6 +66,64 @@ pub fn draw_midi_panel(ui: &mut Ui, midi: &mut MidiSystem) {
     }
 }
 
+/// MIDI clock out (#2205): toggle plus output port.
+fn draw_clock_out(ui: &mut Ui, midi: &mut MidiSystem) {
+    let mut enabled = midi.config.clock_out_enabled;
+    if ui
+        .checkbox(
+            &mut enabled,
+            RichText::new("Send MIDI clock").size(SMALL_SIZE),
+        )
+        .on_hover_text(
+            "Send MIDI clock at the detected tempo, locked to the beat, so \
+             lighting desks and DAWs can follow the music",
+        )
+        .changed()
+    {
+        midi.set_clock_out_enabled(enabled);
+    }
+    if !midi.config.clock_out_enabled {
+        return;
+    }
+
+    let current_label = midi
+        .clock_out_port()
+        .or(midi.config.clock_out_port.as_deref())
+        .unwrap_or("Choose output")
+        .to_string();
+    egui::ComboBox::from_id_salt("midi_clock_port")
+        .selected_text(RichText::new(&current_label).size(SMALL_SIZE))
+        .width((ui.available_width() - 8.0).max(1.0))
+        .show_ui(ui, |ui| {
+            let mut chosen = None;
+            for port in &midi.available_out_ports {
+                let is_selected = midi.clock_out_port() == Some(port.as_str());
+                if ui.selectable_label(is_selected, port).clicked() && !is_selected {
+                    chosen = Some(port.clone());
+                }
+            }
+            if let Some(port) = chosen {
+                midi.set_clock_out_port(&port);
+            }
+            if midi.available_out_ports.is_empty() {
+                ui.label(
+                    RichText::new("No MIDI outputs")
+                        .weak()
+                        .italics()
+                        .size(SMALL_SIZE),
+                );
+            }
+        });
+}
+
+/// "CCn" or "Nn" for a mapping.
+pub(crate) fn mapping_label(mapping: &MidiMapping) -> String {
+    match mapping.msg_type {
+        MidiMsgType::Cc => format!("CC{}", mapping.cc),
+        MidiMsgType::Note => format!("N{}", mapping.cc),
+    }
+}
+
 /// One MIDI mapping badge for `action` — unmapped "M" / learning ".." / mapped
 /// "CCn"/"Nn". Shared with the unified Triggers table.
 pub(crate) fn draw_trigger_badge(ui: &mut Ui, midi: &mut MidiSystem, action: TriggerAction) {
@@ -82,11 +143,7 @@ pub(crate) fn draw_trigger_badge(ui: &mut Ui, midi: &mut MidiSystem, action: Tri
         }
         ui.ctx().request_repaint();
     } else if is_mapped {
-        let mapping = &midi.config.triggers[&action];
-        let label = match mapping.msg_type {
-            MidiMsgType::Cc => format!("CC{}", mapping.cc),
-            MidiMsgType::Note => format!("N{}", mapping.cc),
-        };
+        let label = mapping_label(&midi.config.triggers[&action]);
         let resp = ui
             .button(RichText::new(&label).color(MIDI_BLUE).size(SMALL_SIZE))
             .on_hover_text("Click to re-learn, right-click to clear");
@@ -1,11 +1,19 @@
 pub mod audio_mappings_panel;
 pub mod audio_panel;
+pub mod automation_panel;
 pub mod binding_helpers;
 pub mod binding_matrix;
 pub mod bindings_panel;
+pub mod code_edit;
+pub mod cue_panel;
+pub mod debug_view_panel;
+pub mod editor_keymap;
 pub mod effect_panel;
+pub mod generator_panel;
 pub mod lattice_panel;
 pub mod layer_panel;
+pub mod library_panel;
+pub mod master_panel;
 pub mod media_panel;
 pub mod midi_panel;
 #[cfg(feature = "ndi")]
@@ -14,15 +22,18 @@ pub mod obstacle_panel;
 pub mod osc_panel;
 pub mod param_panel;
 pub mod particle_panel;
+pub mod perf_panel;
 pub mod postfx_panel;
 pub mod preset_panel;
 pub mod recording_panel;
 pub mod scene_panel;
+pub mod sequencer_panel;
 pub mod settings_panel;
 pub mod shader_editor;
 pub mod status_bar;
 pub mod timeline_bar;
 pub mod triggers_panel;
+pub mod uniforms_panel;
 pub mod volumetric_panel;
 pub mod web_panel;
 pub mod webcam_panel;
@@ -32,13 +43,17 @@ use egui::{Context, Frame, Margin, ScrollArea};
 use crate::audio::AudioSystem;
 use crate::bindings::bus::BindingBus;
 use crate::effect::EffectLoader;
-use crate::effect::format::PostProcessDef;
+use crate::effect::format::{AudioMapping, PostProcessDef};
+use crate::effect::library::LibraryUpdater;
 use crate::gpu::ShaderUniforms;
+use crate::gpu::freeze::FreezeFrame;
+use crate::gpu::generator::GeneratorDef;
 use crate::gpu::layer::LayerInfo;
+use crate::gpu::master::MasterState;
 use crate::gpu::volumetric::VolumetricParams;
 use crate::midi::MidiSystem;
 use crate::osc::OscSystem;
-use crate::params::ParamStore;
+use crate::params::{ParamAutomation, ParamStore, StepSequencer};
 use crate::preset::PresetStore;
 use crate::settings::SettingsConfig;
 use crate::ui::theme::colors::theme_colors;
@@ -51,12 +66,17 @@ pub fn draw_panels(
     visible: bool,
     audio: &mut AudioSystem,
     params: &mut ParamStore,
+    automation: &mut ParamAutomation,
+    sequencer: &mut StepSequencer,
+    audio_mappings: &mut Option<Vec<AudioMapping>>,
     shader_error: &Option<String>,
     uniforms: &ShaderUniforms,
     effect_loader: &EffectLoader,
     postprocess: &mut PostProcessDef,
     volumetric_enabled: &mut bool,
     volumetric_params: &mut VolumetricParams,
+    master: &mut MasterState,
+    freeze: &mut FreezeFrame,
     particle_count: Option<u32>,
     midi: &mut MidiSystem,
     osc: &mut OscSystem,
@@ -65,6 +85,7 @@ pub fn draw_panels(
     preset_store: &PresetStore,
     layers: &[LayerInfo],
     active_layer: usize,
+    generator: Option<&mut GeneratorDef>,
     media_info: Option<media_panel::MediaInfo>,
     webcam_info: Option<webcam_panel::WebcamInfo>,
     particle_info: Option<particle_panel::ParticleInfo>,
@@ -73,6 +94,7 @@ pub fn draw_panels(
     scene_info: Option<scene_panel::SceneInfo>,
     status_error: &Option<(String, std::time::Instant)>,
     settings: &SettingsConfig,
+    effect_library: &LibraryUpdater,
 ) {
     if !visible {
         return;
@@ -128,6 +150,7 @@ pub fn draw_panels(
             status_error,
             preset_loading.as_deref(),
             audio.indicator(),
+            audio.input_level(),
         );
     });
 
@@ -218,17 +241,53 @@ pub fn draw_panels(
                 // Effects section
                 let fx_badge = format!("{}", effect_loader.effects.len());
                 widgets::section(ui, "sec_effects", "Effects", Some(&fx_badge), true, |ui| {
-                    effect_panel::draw_effect_panel(ui, effect_loader, &settings.favorite_effects);
+                    effect_panel::draw_effect_panel(
+                        ui,
+                        effect_loader,
+                        &settings.favorite_effects,
+                        &settings.recent_effects,
+                    );
+                    widgets::subsection(
+                        ui,
+                        "sub_effect_library",
+                        "Library updates",
+                        None,
+                        tc.text_secondary,
+                        false,
+                        |ui| {
+                            library_panel::draw_library_panel(
+                                ui,
+                                effect_library,
+                                &settings.effect_library,
+                            );
+                        },
+                    );
                 });
 
                 // Layers section
-                let layer_badge = format!("{}/{}", layers.len(), 8);
+                let max_layers = settings.max_layers.max(1);
+                let layer_badge = format!("{}/{max_layers}", layers.len());
                 widgets::section(ui, "sec_layers", "Layers", Some(&layer_badge), true, |ui| {
-                    layer_panel::draw_layer_panel(ui, layers, active_layer);
+                    layer_panel::draw_layer_panel(
+                        ui,
+                        layers,
+                        active_layer,
+                        max_layers,
+                        &settings.recent_media,
+                        &settings.surprise,
+                    );
                 });
 
                 // Presets section
-                preset_panel::draw_preset_section(ui, preset_store);
+                preset_panel::draw_preset_section(
+                    ui,
+                    preset_store,
+                    &mut preset_panel::PresetBindings {
+                        midi: &mut *midi,
+                        keys: &settings.preset_keys,
+                        crossfade: &settings.preset_crossfade,
+                    },
+                );
 
                 // Scenes section (default collapsed)
                 if let Some(ref scene) = scene_info {
@@ -350,7 +409,9 @@ pub fn draw_panels(
 
                         // Triggers subsection — one table for both protocols
                         // (the per-protocol grids used to repeat the same list).
-                        let mapped = midi.config.triggers.len() + osc.config.triggers.len();
+                        let mapped = midi.config.triggers.len()
+                            + osc.config.triggers.len()
+                            + settings.preset_keys.len();
                         let trig_badge = (mapped > 0).then(|| format!("{mapped}"));
                         widgets::subsection(
                             ui,
@@ -361,6 +422,12 @@ pub fn draw_panels(
                             false,
                             |ui| {
                                 triggers_panel::draw_triggers_table(ui, midi, osc);
+                                triggers_panel::draw_preset_triggers(
+                                    ui,
+                                    midi,
+                                    preset_store,
+                                    &settings.preset_keys,
+                                );
                             },
                         );
 
@@ -445,6 +512,22 @@ pub fn draw_panels(
                                 settings.band_scale,
                                 settings.use_ffmpeg_webcam,
                                 settings.auto_reconnect,
+                                &settings.silence,
+                                &settings.attract,
+                                &settings.startup,
+                                &settings.installation,
+                                &settings.watchdog,
+                                preset_store,
+                                settings.media_cache_mb,
+                                settings.file_dialog,
+                                &settings.watch_folders,
+                                settings.max_layers,
+                                settings.composite_hook.as_deref(),
+                                &settings.frame_guard,
+                                settings.deterministic,
+                                &settings.external_editor,
+                                &settings.profiles,
+                                settings.active_profile.as_deref(),
                             );
                         });
                     },
@@ -458,7 +541,12 @@ pub fn draw_panels(
         .frame(panel_frame)
         .show(ctx, |ui| {
             ScrollArea::vertical().show(ui, |ui| {
-                if let Some(ref info) = webcam_info {
+                if let Some(def) = generator {
+                    // Generator layer: its settings replace the effect params
+                    widgets::section(ui, "sec_generator", "Generator", None, true, |ui| {
+                        generator_panel::draw_generator_panel(ui, def);
+                    });
+                } else if let Some(ref info) = webcam_info {
                     // Webcam layer: show webcam controls
                     widgets::section(ui, "sec_webcam", "Webcam", None, true, |ui| {
                         webcam_panel::draw_webcam_panel(ui, info);
@@ -466,12 +554,59 @@ pub fn draw_panels(
                 } else if let Some(ref info) = media_info {
                     // Media layer: show media controls instead of params
                     widgets::section(ui, "sec_media", "Media", None, true, |ui| {
-                        media_panel::draw_media_panel(ui, info);
+                        media_panel::draw_media_panel(ui, info, &settings.recent_media);
                     });
                 } else {
                     // Effect layer: show parameters
                     widgets::section(ui, "sec_params", "Parameters", None, true, |ui| {
-                        param_panel::draw_param_panel(ui, params, midi, osc);
+                        let effect_name = layers
+                            .get(active_layer)
+                            .and_then(|l| l.effect_name.as_deref())
+                            .unwrap_or_default();
+                        param_panel::draw_param_panel(
+                            ui,
+                            params,
+                            sequencer,
+                            midi,
+                            osc,
+                            effect_name,
+                            settings,
+                        );
+
+                        let lanes = automation.lanes.len();
+                        let (badge, badge_color) = if automation.recording {
+                            (Some("REC".to_string()), tc.error)
+                        } else {
+                            ((lanes > 0).then(|| lanes.to_string()), tc.accent)
+                        };
+                        widgets::subsection(
+                            ui,
+                            "sub_automation",
+                            "Automation",
+                            badge.as_deref(),
+                            badge_color,
+                            false,
+                            |ui| {
+                                automation_panel::draw_automation_panel(
+                                    ui,
+                                    automation,
+                                    &params.defs,
+                                );
+                            },
+                        );
+
+                        let sequences = sequencer.sequences.len();
+                        widgets::subsection(
+                            ui,
+                            "sub_sequencer",
+                            "Step sequencer",
+                            (sequences > 0).then(|| sequences.to_string()).as_deref(),
+                            tc.accent,
+                            false,
+                            |ui| {
+                                sequencer_panel::draw_sequencer_panel(ui, sequencer, &params.defs);
+                            },
+                        );
                     });
 
                     // Particle section (shows when active layer has particles)
@@ -517,15 +652,24 @@ pub fn draw_panels(
                         });
                     }
 
-                    // Audio Reactivity section (default collapsed)
-                    let active_info = layers.get(active_layer);
-                    let mappings = active_info
+                    // Audio Reactivity section (default collapsed). Edits land
+                    // on the layer as a preset override until saved (#2186).
+                    let active_effect = layers
+                        .get(active_layer)
                         .and_then(|info| info.effect_index)
-                        .and_then(|idx| effect_loader.effects.get(idx))
-                        .map(|fx| fx.audio_mappings.as_slice())
-                        .unwrap_or(&[]);
-                    if !mappings.is_empty() {
-                        let mapping_badge = format!("{}", mappings.len());
+                        .and_then(|idx| effect_loader.effects.get(idx).map(|fx| (idx, fx)));
+                    if let Some((effect_idx, fx)) = active_effect {
+                        let edited = audio_mappings.is_some();
+                        let mut working = audio_mappings
+                            .clone()
+                            .unwrap_or_else(|| fx.audio_mappings.clone());
+                        let before = working.clone();
+                        let mapping_badge = if edited {
+                            format!("{} edited", working.len())
+                        } else {
+                            format!("{}", working.len())
+                        };
+                        let mut revert = false;
                         widgets::section(
                             ui,
                             "sec_audio_react",
@@ -533,9 +677,49 @@ pub fn draw_panels(
                             Some(&mapping_badge),
                             false,
                             |ui| {
-                                audio_mappings_panel::draw_audio_mappings(ui, mappings);
+                                audio_mappings_panel::draw_audio_mappings(
+                                    ui,
+                                    &mut working,
+                                    &params.defs,
+                                );
+                                if edited {
+                                    ui.horizontal(|ui| {
+                                        let builtin = EffectLoader::is_builtin(fx);
+                                        let save = ui
+                                            .add_enabled(
+                                                !builtin && fx.source_path.is_some(),
+                                                egui::Button::new("Save to .pfx"),
+                                            )
+                                            .on_hover_text(
+                                                "Write these mappings into the effect file",
+                                            )
+                                            .on_disabled_hover_text(
+                                                "Built-in effects keep edits in the preset",
+                                            );
+                                        if save.clicked() {
+                                            ui.data_mut(|d| {
+                                                d.insert_temp(
+                                                    egui::Id::new("save_audio_mappings"),
+                                                    effect_idx,
+                                                );
+                                            });
+                                        }
+                                        if ui
+                                            .button("Revert")
+                                            .on_hover_text("Go back to the effect's own mappings")
+                                            .clicked()
+                                        {
+                                            revert = true;
+                                        }
+                                    });
+                                }
                             },
                         );
+                        if revert {
+                            *audio_mappings = None;
+                        } else if working != before {
+                            *audio_mappings = Some(working);
+                        }
                     }
                 }
 
@@ -551,8 +735,29 @@ pub fn draw_panels(
 
                 // Post-Processing section
                 widgets::section(ui, "sec_postprocess", "Post-Processing", None, true, |ui| {
-                    postfx_panel::draw_postfx_panel(ui, postprocess);
+                    let post_source = layer_infos
+                        .get(active_layer)
+                        .and_then(|l| l.effect_name.as_deref())
+                        .map(|name| settings.post.source(name));
+                    postfx_panel::draw_postfx_panel(ui, postprocess, post_source);
                 });
+
+                // Master output section — final grade + blackout (#2165)
+                let master_badge = if master.blackout {
+                    Some("BLACKOUT")
+                } else {
+                    freeze.is_active().then_some("HELD")
+                };
+                widgets::section(
+                    ui,
+                    "sec_master",
+                    "Master Output",
+                    master_badge,
+                    true,
+                    |ui| {
+                        master_panel::draw_master_panel(ui, master, freeze);
+                    },
+                );
             });
         });
 }
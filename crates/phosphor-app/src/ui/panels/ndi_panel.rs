@@ -1,7 +1,7 @@
 use egui::{RichText, Ui};
 
 use crate::ndi::ffi::ndi_search_diagnostics;
-use crate::ndi::types::OutputResolution;
+use crate::ndi::types::{KeyOutput, OutputResolution};
 use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
 
@@ -18,6 +18,7 @@ pub struct NdiInfo {
     pub output_width: u32,
     pub output_height: u32,
     pub alpha_from_luma: bool,
+    pub key_output: KeyOutput,
 }
 
 pub fn draw_ndi_panel(ui: &mut Ui, info: &NdiInfo) {
@@ -126,15 +127,43 @@ pub fn draw_ndi_panel(ui: &mut Ui, info: &NdiInfo) {
         }
     });
 
+    // Key and fill for vision mixers (#2238)
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Key + fill").size(SMALL_SIZE))
+            .on_hover_text(
+                "Send the alpha as a greyscale key beside the fill, for mixers that key on a \
+                 separate signal. The key is the layers' alpha, or brightness with the box above.",
+            );
+        let current = info.key_output;
+        egui::ComboBox::from_id_salt("ndi_key_output")
+            .selected_text(current.display_name())
+            .width(120.0)
+            .show_ui(ui, |ui| {
+                for (i, &mode) in KeyOutput::ALL.iter().enumerate() {
+                    if ui
+                        .selectable_label(current == mode, mode.display_name())
+                        .clicked()
+                        && mode != current
+                    {
+                        ui.ctx().data_mut(|d| {
+                            d.insert_temp(egui::Id::new("ndi_key_output"), i as u8);
+                        });
+                    }
+                }
+            });
+    });
+
     // Show output dimensions when running
     if info.running && info.output_width > 0 {
+        let (width, layout) = match info.key_output {
+            KeyOutput::Off => (info.output_width, ""),
+            KeyOutput::SideBySide => (info.output_width * 2, " (fill | key)"),
+            KeyOutput::SeparateStreams => (info.output_width, " ×2 (fill, key)"),
+        };
         ui.label(
-            RichText::new(format!(
-                "Output: {}x{}",
-                info.output_width, info.output_height
-            ))
-            .size(SMALL_SIZE)
-            .color(tc.text_secondary),
+            RichText::new(format!("Output: {}x{}{layout}", width, info.output_height))
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
         );
     }
 
@@ -13,7 +13,7 @@ pub struct ObstacleInfo {
     pub fit: ObstacleFit,
     pub threshold: f32,
     pub elasticity: f32,
-    /// "image", "webcam", "depth", or "" (none)
+    /// "image", "video", "webcam", "depth", "layer", or "" (none)
     pub source: String,
     pub image_path: Option<String>,
     pub has_particles: bool,
@@ -26,6 +26,10 @@ pub struct ObstacleInfo {
     pub depth_download_error: Option<String>,
     pub webcam_devices: Vec<(u32, String)>,
     pub webcam_device_index: u32,
+    /// Layer whose brightness is the obstacle (#2160), and the layers that
+    /// can be picked: (index, display name).
+    pub layer: Option<usize>,
+    pub layer_choices: Vec<(usize, String)>,
 }
 
 /// UI commands emitted by the obstacle panel.
@@ -42,6 +46,7 @@ pub enum ObstacleCommand {
     LoadVideo,
     UseWebcam,
     UseDepth,
+    UseLayer(usize),
     DownloadDepthModel,
     Clear,
 }
@@ -106,6 +111,7 @@ pub fn draw_obstacle_panel(ui: &mut Ui, info: &ObstacleInfo) {
             }
             "webcam" => "Webcam".to_string(),
             "depth" => "Depth (MiDaS)".to_string(),
+            "layer" => "Layer brightness".to_string(),
             _ => "None".to_string(),
         };
         ui.label(
@@ -239,6 +245,19 @@ pub fn draw_obstacle_panel(ui: &mut Ui, info: &ObstacleInfo) {
                 }
             }
         }
+        if let Some(&(first, _)) = info.layer_choices.first() {
+            if tab_btn(ui, "Layer", info.source == "layer")
+                .on_hover_text("Collide with the bright areas of another layer, live")
+                .clicked()
+            {
+                ui.ctx().data_mut(|d| {
+                    d.insert_temp(
+                        egui::Id::new("obstacle_cmd"),
+                        ObstacleCommand::UseLayer(info.layer.unwrap_or(first)),
+                    );
+                });
+            }
+        }
         if !info.source.is_empty() {
             if tab_btn(ui, "Clear", false)
                 .on_hover_text("Remove obstacle and stop capture")
@@ -251,6 +270,32 @@ pub fn draw_obstacle_panel(ui: &mut Ui, info: &ObstacleInfo) {
         }
     });
 
+    // Which layer feeds a live layer obstacle (#2160)
+    if info.source == "layer" && !info.layer_choices.is_empty() {
+        let mut choice = info.layer;
+        let selected = choice
+            .and_then(|i| info.layer_choices.iter().find(|(c, _)| *c == i))
+            .map(|(_, name)| name.as_str())
+            .unwrap_or("Layer");
+        combo_row(
+            ui,
+            "obstacle_layer",
+            "Layer",
+            Some("Bright areas of this layer become the obstacle"),
+            selected,
+            |ui| {
+                for (i, name) in &info.layer_choices {
+                    ui.selectable_value(&mut choice, Some(*i), name);
+                }
+            },
+        );
+        if let Some(i) = choice.filter(|&i| Some(i) != info.layer) {
+            ui.ctx().data_mut(|d| {
+                d.insert_temp(egui::Id::new("obstacle_cmd"), ObstacleCommand::UseLayer(i));
+            });
+        }
+    }
+
     // Show download error if any
     if let Some(ref err) = info.depth_download_error {
         ui.label(
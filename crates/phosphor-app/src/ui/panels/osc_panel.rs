@@ -2,6 +2,7 @@ use egui::{Color32, RichText, Ui};
 
 use crate::midi::types::TriggerAction;
 use crate::osc::OscSystem;
+use crate::osc::address_map::MapFormat;
 use crate::osc::types::OscLearnTarget;
 use crate::ui::theme::tokens::*;
 use crate::ui::widgets::rows;
@@ -65,10 +66,7 @@ pub fn draw_osc_panel(ui: &mut Ui, osc: &mut OscSystem) {
             if resp.changed() {
                 osc.config.tx_host = host;
                 osc.config.save();
-                if osc.config.tx_enabled {
-                    osc.sender
-                        .configure(&osc.config.tx_host, osc.config.tx_port);
-                }
+                osc.configure_sender();
             }
         });
 
@@ -83,10 +81,7 @@ pub fn draw_osc_panel(ui: &mut Ui, osc: &mut OscSystem) {
             if resp.changed() {
                 osc.config.tx_port = port;
                 osc.config.save();
-                if osc.config.tx_enabled {
-                    osc.sender
-                        .configure(&osc.config.tx_host, osc.config.tx_port);
-                }
+                osc.configure_sender();
             }
             ui.add_space(4.0);
             let mut rate = osc.config.tx_rate_hz;
@@ -101,6 +96,34 @@ pub fn draw_osc_panel(ui: &mut Ui, osc: &mut OscSystem) {
                 osc.config.save();
             }
         });
+
+        // Tempo stream for lighting desks, sent whether or not TX is on
+        let mut tempo = osc.config.tempo_tx_enabled;
+        if ui
+            .checkbox(&mut tempo, RichText::new("Tempo out").size(SMALL_SIZE))
+            .on_hover_text(
+                "Send BPM, beat and bar phase to /phosphor/tempo/*, plus \
+                 /phosphor/tempo/beat on every beat, so lighting software can chase it",
+            )
+            .changed()
+        {
+            osc.set_tempo_tx_enabled(tempo);
+        }
+        if osc.config.tempo_tx_enabled {
+            rows::custom_row(ui, "Tempo rate", None, |ui| {
+                let mut rate = osc.config.tempo_tx_rate_hz;
+                let resp = ui.add(
+                    egui::DragValue::new(&mut rate)
+                        .range(1..=120)
+                        .speed(1.0)
+                        .suffix(" Hz"),
+                );
+                if resp.changed() {
+                    osc.config.tempo_tx_rate_hz = rate;
+                    osc.config.save();
+                }
+            });
+        }
     });
 
     // Learn status (conditional)
@@ -118,6 +141,71 @@ pub fn draw_osc_panel(ui: &mut Ui, osc: &mut OscSystem) {
         ui.label(RichText::new(label).size(SMALL_SIZE).color(color));
         ui.ctx().request_repaint();
     }
+
+    ui.add_space(4.0);
+    draw_address_map(ui, osc);
+}
+
+/// Every address Fosfora answers, for programming a console (#2198).
+/// main.rs builds the map and saves it; this lists it.
+fn draw_address_map(ui: &mut Ui, osc: &mut OscSystem) {
+    ui.horizontal(|ui| {
+        let label = if osc.address_map.is_some() {
+            "Refresh map"
+        } else {
+            "Address map"
+        };
+        if ui
+            .button(RichText::new(label).size(SMALL_SIZE))
+            .on_hover_text("List every OSC address Fosfora answers right now")
+            .clicked()
+        {
+            ui.ctx()
+                .data_mut(|d| d.insert_temp(egui::Id::new("osc_address_map"), true));
+        }
+        if osc.address_map.is_none() {
+            return;
+        }
+        for (text, format) in [
+            ("Save .md", MapFormat::Markdown),
+            ("Save .csv", MapFormat::Csv),
+        ] {
+            if ui
+                .button(RichText::new(text).size(SMALL_SIZE))
+                .on_hover_text("Save the map as a cheat sheet")
+                .clicked()
+            {
+                ui.ctx()
+                    .data_mut(|d| d.insert_temp(egui::Id::new("osc_address_map_save"), format));
+            }
+        }
+        if ui.button(RichText::new("Close").size(SMALL_SIZE)).clicked() {
+            osc.address_map = None;
+        }
+    });
+
+    let Some(map) = &osc.address_map else {
+        return;
+    };
+    egui::ScrollArea::vertical()
+        .id_salt("osc_address_map")
+        .max_height(240.0)
+        .show(ui, |ui| {
+            for entry in map {
+                let color = if entry.learned {
+                    OSC_GREEN
+                } else {
+                    ui.visuals().text_color()
+                };
+                ui.label(
+                    RichText::new(&entry.address)
+                        .monospace()
+                        .size(SMALL_SIZE)
+                        .color(color),
+                )
+                .on_hover_text(format!("{}\n{}", entry.action, entry.value));
+            }
+        });
 }
 
 /// One OSC mapping badge for `action` — unmapped "O" / learning ".." / mapped
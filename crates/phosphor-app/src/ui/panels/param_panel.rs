@@ -3,13 +3,21 @@ use egui::{Color32, RichText, Ui};
 use crate::midi::MidiSystem;
 use crate::midi::types::{LearnTarget, MidiMsgType};
 use crate::osc::OscSystem;
-use crate::params::{ParamDef, ParamStore, ParamValue};
+use crate::params::{ParamDef, ParamStore, ParamValue, StepSequencer, param_groups};
+use crate::settings::SettingsConfig;
 use crate::ui::panels::osc_panel;
 use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
+use crate::ui::widgets;
 
 const MIDI_BLUE: Color32 = Color32::from_rgb(0x60, 0xA0, 0xE0);
 
+/// Shift-drag moves a slider this many times slower (#2182).
+const FINE_DRAG_DIVISOR: f32 = 10.0;
+
+/// Hover hint shared by every param slider.
+const SLIDER_HINT: &str = "Right-click: reset to default \u{b7} Shift-drag: fine adjust";
+
 /// Draw a compact MIDI mapping badge for a parameter.
 fn draw_midi_badge(ui: &mut Ui, midi: &mut MidiSystem, param_name: &str) {
     let is_learning = midi.learn_target == Some(LearnTarget::Param(param_name.to_string()));
@@ -61,16 +69,110 @@ fn format_mapping_label(msg_type: MidiMsgType, cc: u8) -> String {
     }
 }
 
+/// A param slider with a tick at the default value and Shift-drag fine
+/// adjustment (#2182). `current` is the stored value, so a fine drag steps
+/// from it rather than from where the pointer landed. Returns true when the
+/// slider was right-clicked, asking for a reset to default.
+fn param_slider(
+    ui: &mut Ui,
+    val: &mut f32,
+    current: f32,
+    default: f32,
+    range: std::ops::RangeInclusive<f32>,
+    tick_color: Color32,
+) -> bool {
+    let (min, max) = (*range.start(), *range.end());
+    let resp = ui
+        .add(
+            egui::Slider::new(val, range)
+                .clamping(egui::SliderClamping::Always)
+                .show_value(false),
+        )
+        .interact(egui::Sense::click())
+        .on_hover_text(SLIDER_HINT);
+
+    if resp.dragged() && ui.input(|i| i.modifiers.shift) {
+        *val = fine_step(current, resp.drag_delta().x, resp.rect.width(), min, max);
+    }
+
+    if max > min {
+        let t = ((default - min) / (max - min)).clamp(0.0, 1.0);
+        let x = resp.rect.x_range().lerp(t);
+        ui.painter().line_segment(
+            [
+                egui::pos2(x, resp.rect.bottom() - 3.0),
+                egui::pos2(x, resp.rect.bottom()),
+            ],
+            egui::Stroke::new(1.0, tick_color),
+        );
+    }
+
+    resp.secondary_clicked()
+}
+
+/// Value after a fine drag of `delta_px` across a slider `width_px` wide.
+fn fine_step(current: f32, delta_px: f32, width_px: f32, min: f32, max: f32) -> f32 {
+    let per_px = (max - min) / width_px.max(1.0) / FINE_DRAG_DIVISOR;
+    (current + delta_px * per_px).clamp(min, max)
+}
+
+/// What the param context menu asked for.
+enum ParamMenu {
+    Reset,
+    ToggleSequence,
+}
+
+/// Right-click menu on a param's name: reset, and step sequence on/off (#2184).
+fn param_menu(resp: &egui::Response, sequenced: bool) -> Option<ParamMenu> {
+    let mut action = None;
+    resp.context_menu(|ui| {
+        if ui.button("Reset to default").clicked() {
+            action = Some(ParamMenu::Reset);
+            ui.close();
+        }
+        let label = if sequenced {
+            "Remove step sequence"
+        } else {
+            "Step sequence"
+        };
+        if ui.button(label).clicked() {
+            action = Some(ParamMenu::ToggleSequence);
+            ui.close();
+        }
+    });
+    action
+}
+
 /// Shared compact float formatting, f32-flavored for this panel's values.
 fn fmt_val(v: f32) -> String {
     crate::ui::widgets::fmt_val(f64::from(v))
 }
 
+/// A value with the input's unit after it, when the effect gives one (#2227).
+fn with_unit(text: String, unit: Option<&str>) -> String {
+    match unit {
+        Some(u) => format!("{text} {u}"),
+        None => text,
+    }
+}
+
+/// Name hover: the effect's description of the input above `hint`.
+fn name_hover(def: &ParamDef, hint: &str) -> String {
+    match def.description() {
+        Some(d) if hint.is_empty() => d.to_string(),
+        Some(d) => format!("{d}\n\n{hint}"),
+        None => hint.to_string(),
+    }
+}
+
 pub fn draw_param_panel(
     ui: &mut Ui,
     store: &mut ParamStore,
     midi: &mut MidiSystem,
     osc: &mut OscSystem,
+    sequencer: &mut StepSequencer,
+    effect_name: &str,
+    settings: &SettingsConfig,
 ) {
     let tc = theme_colors(ui.ctx());
 
@@ -85,123 +187,259 @@ pub fn draw_param_panel(
 
     let defs = store.defs.clone();
 
-    for def in &defs {
-        match def {
-            ParamDef::Float { name, min, max, .. } => {
-                let current = match store.get(name) {
-                    Some(ParamValue::Float(v)) => *v,
-                    _ => *min,
-                };
-                let mut val = current;
+    // Inputs with a `group` get a foldable section each (#2181); the folds
+    // are kept per effect in settings, so main.rs does the toggling.
+    for (group, indices) in param_groups(&defs) {
+        if let Some(group) = group {
+            let open = !settings.param_group_collapsed(effect_name, group);
+            if widgets::subsection_header(ui, group, None, tc.text_secondary, open) {
+                ui.ctx().data_mut(|d| {
+                    d.insert_temp(
+                        egui::Id::new("toggle_param_group"),
+                        (effect_name.to_string(), group.to_string()),
+                    );
+                });
+            }
+            if !open {
+                continue;
+            }
+            ui.add_space(4.0);
+        }
+        for i in indices {
+            draw_param(ui, &defs[i], store, sequencer, midi, osc);
+            ui.add_space(2.0);
+        }
+    }
 
-                // Single compact row: [name left] [slider fills | value | M | O right]
-                ui.horizontal(|ui| {
+    ui.add_space(4.0);
+    ui.horizontal(|ui| {
+        if ui
+            .add(
+                egui::Button::new(RichText::new("Reset All").size(8.0))
+                    .min_size(egui::vec2(0.0, 20.0)),
+            )
+            .on_hover_text("Reset every param of this effect to its default")
+            .clicked()
+        {
+            store.reset_all();
+        }
+        if ui
+            .add(
+                egui::Button::new(RichText::new("Uniforms").size(8.0))
+                    .min_size(egui::vec2(0.0, 20.0)),
+            )
+            .on_hover_text("Inspect the values the shader receives, param(i) slots included (U)")
+            .clicked()
+        {
+            ui.ctx()
+                .data_mut(|d| d.insert_temp(egui::Id::new("toggle_uniforms_panel"), true));
+        }
+    });
+}
+
+fn draw_param(
+    ui: &mut Ui,
+    def: &ParamDef,
+    store: &mut ParamStore,
+    sequencer: &mut StepSequencer,
+    midi: &mut MidiSystem,
+    osc: &mut OscSystem,
+) {
+    let tc = theme_colors(ui.ctx());
+    let sequenced = sequencer.get(def.name()).is_some();
+    // Sequenced params show their name in the accent color.
+    let name_color = if sequenced {
+        tc.accent
+    } else {
+        tc.text_secondary
+    };
+    let mut menu = None;
+    match def {
+        ParamDef::Float {
+            name,
+            default,
+            min,
+            max,
+            ..
+        } => {
+            let current = match store.get(name) {
+                Some(ParamValue::Float(v)) => *v,
+                _ => *min,
+            };
+            let mut val = current;
+            let mut reset = false;
+
+            // Single compact row: [name left] [slider fills | value | M | O right]
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 4.0;
+                let label = ui
+                    .add(
+                        egui::Label::new(RichText::new(name).size(9.0).color(name_color))
+                            .sense(egui::Sense::click()),
+                    )
+                    .on_hover_text(name_hover(def, "Right-click for options"));
+                menu = param_menu(&label, sequenced);
+                // Right-to-left: badges rightmost, then value, slider fills the rest
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     ui.spacing_mut().item_spacing.x = 4.0;
-                    ui.label(RichText::new(name).size(9.0).color(tc.text_secondary));
-                    // Right-to-left: badges rightmost, then value, slider fills the rest
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.spacing_mut().item_spacing.x = 4.0;
-                        osc_panel::draw_osc_badge(ui, osc, name);
-                        draw_midi_badge(ui, midi, name);
-                        ui.label(
-                            RichText::new(fmt_val(val))
-                                .size(9.0)
-                                .color(tc.text_secondary),
-                        );
-                        ui.spacing_mut().slider_width = ui.available_width();
-                        ui.add(
-                            egui::Slider::new(&mut val, *min..=*max)
-                                .clamping(egui::SliderClamping::Always)
-                                .show_value(false),
-                        );
-                    });
+                    osc_panel::draw_osc_badge(ui, osc, name);
+                    draw_midi_badge(ui, midi, name);
+                    ui.label(
+                        RichText::new(with_unit(fmt_val(val), def.unit()))
+                            .size(9.0)
+                            .color(tc.text_secondary),
+                    );
+                    ui.spacing_mut().slider_width = ui.available_width();
+                    reset = param_slider(ui, &mut val, current, *default, *min..=*max, tc.text_dim);
                 });
+            });
 
-                if val != current {
-                    store.set(name, ParamValue::Float(val));
-                }
+            if reset {
+                store.reset(name);
+            } else if val != current {
+                store.set(name, ParamValue::Float(val));
             }
-            ParamDef::Color { name, .. } => {
-                let current = match store.get(name) {
-                    Some(ParamValue::Color(c)) => *c,
-                    _ => [1.0, 1.0, 1.0, 1.0],
-                };
-                let mut color = current;
-
-                ui.horizontal(|ui| {
-                    ui.label(RichText::new(name).size(SMALL_SIZE));
-                    ui.color_edit_button_rgba_unmultiplied(&mut color);
-                    if ui.small_button("R").on_hover_text("Reset").clicked() {
-                        store.reset(name);
-                    }
-                });
+        }
+        ParamDef::Color { name, .. } => {
+            let current = match store.get(name) {
+                Some(ParamValue::Color(c)) => *c,
+                _ => [1.0, 1.0, 1.0, 1.0],
+            };
+            let mut color = current;
 
-                if color != current {
-                    store.set(name, ParamValue::Color(color));
+            ui.horizontal(|ui| {
+                let label = ui.label(RichText::new(name).size(SMALL_SIZE));
+                if def.description().is_some() {
+                    label.on_hover_text(name_hover(def, ""));
+                }
+                ui.color_edit_button_rgba_unmultiplied(&mut color);
+                if ui.small_button("R").on_hover_text("Reset").clicked() {
+                    store.reset(name);
                 }
+            });
+
+            if color != current {
+                store.set(name, ParamValue::Color(color));
             }
-            ParamDef::Bool { name, .. } => {
-                let current = match store.get(name) {
-                    Some(ParamValue::Bool(b)) => *b,
-                    _ => false,
-                };
-                let mut val = current;
+        }
+        ParamDef::Bool { name, .. } => {
+            let current = match store.get(name) {
+                Some(ParamValue::Bool(b)) => *b,
+                _ => false,
+            };
+            let mut val = current;
 
-                ui.horizontal(|ui| {
-                    ui.checkbox(&mut val, RichText::new(name).size(SMALL_SIZE));
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        osc_panel::draw_osc_badge(ui, osc, name);
-                        draw_midi_badge(ui, midi, name);
-                    });
+            ui.horizontal(|ui| {
+                let check = ui
+                    .checkbox(
+                        &mut val,
+                        RichText::new(name).size(SMALL_SIZE).color(name_color),
+                    )
+                    .on_hover_text(name_hover(def, "Right-click for options"));
+                menu = param_menu(&check, sequenced);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    osc_panel::draw_osc_badge(ui, osc, name);
+                    draw_midi_badge(ui, midi, name);
                 });
+            });
 
-                if val != current {
-                    store.set(name, ParamValue::Bool(val));
-                }
+            if val != current {
+                store.set(name, ParamValue::Bool(val));
             }
-            ParamDef::Point2D { name, min, max, .. } => {
-                let current = match store.get(name) {
-                    Some(ParamValue::Point2D(p)) => *p,
-                    _ => *min,
-                };
-                let mut val = current;
+        }
+        ParamDef::Point2D {
+            name,
+            default,
+            min,
+            max,
+            ..
+        } => {
+            let current = match store.get(name) {
+                Some(ParamValue::Point2D(p)) => *p,
+                _ => *min,
+            };
+            let mut val = current;
+            let mut reset = false;
 
-                ui.horizontal(|ui| {
-                    ui.label(RichText::new(name).size(SMALL_SIZE).strong());
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.label(
-                            RichText::new(format!("{}, {}", fmt_val(val[0]), fmt_val(val[1])))
-                                .size(SMALL_SIZE)
-                                .color(tc.text_secondary),
-                        );
-                    });
-                });
-                ui.horizontal(|ui| {
-                    ui.spacing_mut().item_spacing.x = 4.0;
-                    ui.label(RichText::new("X").size(MONO_SIZE).color(tc.text_secondary));
-                    ui.add(egui::Slider::new(&mut val[0], min[0]..=max[0]).show_value(false));
+            ui.horizontal(|ui| {
+                let label = ui.label(RichText::new(name).size(SMALL_SIZE).strong());
+                if def.description().is_some() {
+                    label.on_hover_text(name_hover(def, ""));
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let xy = format!("{}, {}", fmt_val(val[0]), fmt_val(val[1]));
+                    ui.label(
+                        RichText::new(with_unit(xy, def.unit()))
+                            .size(SMALL_SIZE)
+                            .color(tc.text_secondary),
+                    );
                 });
+            });
+            for (axis, label) in ["X", "Y"].into_iter().enumerate() {
                 ui.horizontal(|ui| {
                     ui.spacing_mut().item_spacing.x = 4.0;
-                    ui.label(RichText::new("Y").size(MONO_SIZE).color(tc.text_secondary));
-                    ui.add(egui::Slider::new(&mut val[1], min[1]..=max[1]).show_value(false));
+                    ui.label(
+                        RichText::new(label)
+                            .size(MONO_SIZE)
+                            .color(tc.text_secondary),
+                    );
+                    reset |= param_slider(
+                        ui,
+                        &mut val[axis],
+                        current[axis],
+                        default[axis],
+                        min[axis]..=max[axis],
+                        tc.text_dim,
+                    );
                 });
+            }
 
-                if val != current {
-                    store.set(name, ParamValue::Point2D(val));
-                }
+            if reset {
+                store.reset(name);
+            } else if val != current {
+                store.set(name, ParamValue::Point2D(val));
             }
         }
-        ui.add_space(2.0);
     }
 
-    ui.add_space(4.0);
-    if ui
-        .add(
-            egui::Button::new(RichText::new("Reset All").size(8.0)).min_size(egui::vec2(0.0, 20.0)),
-        )
-        .clicked()
-    {
-        store.reset_all();
+    match menu {
+        Some(ParamMenu::Reset) => store.reset(def.name()),
+        Some(ParamMenu::ToggleSequence) => sequencer.toggle(def.name()),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_hover_puts_the_description_above_the_hint() {
+        let mut def = ParamDef::Bool {
+            name: "on".into(),
+            default: true,
+            group: None,
+            description: None,
+        };
+        assert_eq!(name_hover(&def, "hint"), "hint");
+        if let ParamDef::Bool { description, .. } = &mut def {
+            *description = Some("Strobe on beats".into());
+        }
+        assert_eq!(name_hover(&def, "hint"), "Strobe on beats\n\nhint");
+        assert_eq!(name_hover(&def, ""), "Strobe on beats");
+        assert_eq!(with_unit("2".into(), Some("Hz")), "2 Hz");
+    }
+
+    #[test]
+    fn fine_step_is_a_tenth_of_a_normal_drag() {
+        // 100 px slider over 0..1: a normal 10 px drag moves 0.1, a fine one 0.01.
+        assert!((fine_step(0.5, 10.0, 100.0, 0.0, 1.0) - 0.51).abs() < 1e-6);
+        assert!((fine_step(0.5, -10.0, 100.0, 0.0, 1.0) - 0.49).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fine_step_clamps_to_range() {
+        assert_eq!(fine_step(0.99, 1000.0, 100.0, 0.0, 1.0), 1.0);
+        assert_eq!(fine_step(0.0, -5.0, 0.0, 0.0, 1.0), 0.0);
     }
 }
@@ -9,9 +9,22 @@ use crate::ui::widgets::rows;
 pub struct ParticleInfo {
     pub alive_count: u32,
     pub max_count: u32,
+    /// Live particle budget (#2156) and this frame's count after the governor.
+    pub particle_budget: u32,
+    pub active_count: u32,
+    /// Top of the Count slider; past `max_count` the effect reallocates.
+    pub count_ceiling: u32,
+    /// Splat/morph layouts keep every slot — no Count slider.
+    pub budget_locked: bool,
+    pub auto_quality: bool,
+    /// Governor scale in `[0.1, 1]`; 1 when auto quality is off.
+    pub quality_scale: f32,
     pub max_scaled_count: u32,
     pub emit_rate: f32,
     pub burst_on_beat: u32,
+    /// `ParticleBurst` trigger size and beat quantize (#2162).
+    pub trigger_burst: u32,
+    pub trigger_burst_quantize: bool,
     pub lifetime: f32,
     pub initial_speed: f32,
     pub initial_size: f32,
@@ -20,16 +33,27 @@ pub struct ParticleInfo {
     pub drag: f32,
     #[allow(dead_code)]
     pub attraction_strength: f32,
+    /// Settings driven by an effect input (#2164); their sliders are locked.
+    pub bound_params: Vec<String>,
     pub blend_mode: String,
     pub has_flow_field: bool,
     pub has_trails: bool,
     pub trail_length: u32,
+    /// Ribbon width and fade exponent (#2159).
+    pub trail_width: f32,
+    pub trail_fade: f32,
+    /// False for splat effects, which own the trail bind group.
+    pub trails_supported: bool,
+    /// Alpha sprites drawn back-to-front (#2163).
+    pub depth_sort: bool,
+    /// System is under the depth sort's particle cap.
+    pub depth_sort_supported: bool,
     pub has_interaction: bool,
     pub has_sprite: bool,
     pub is_compute_raster: bool,
     // Image source info
     pub has_image_source: bool,
-    /// "static", "video", or "webcam"
+    /// "static", "video", "webcam", or "layer"
     pub source_type: String,
     /// Source filename or device name
     #[allow(dead_code)]
@@ -45,6 +69,10 @@ pub struct ParticleInfo {
     pub source_loading_name: String,
     /// Built-in image names (e.g. "skull", "phoenix") available for quick select.
     pub builtin_images: Vec<String>,
+    /// Layer the emitter samples live (#2155), `None` for the image/video source.
+    pub layer_source: Option<usize>,
+    /// Other layers that can feed the emitter: (index, display name).
+    pub layer_choices: Vec<(usize, String)>,
     // Splat scene state (#1800)
     pub has_splat: bool,
     /// Sorted alpha-over path active (vs weighted-average OIT). Drives the badge.
@@ -82,9 +110,9 @@ pub struct ParticleInfo {
 pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
     let tc = theme_colors(ui.ctx());
 
-    // Alive / max count with utilization bar
-    let util = if info.max_count > 0 {
-        info.alive_count as f32 / info.max_count as f32
+    // Alive / simulated count with utilization bar
+    let util = if info.active_count > 0 {
+        info.alive_count as f32 / info.active_count as f32
     } else {
         0.0
     };
@@ -99,7 +127,7 @@ pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
         ui.label(
             RichText::new(format!(
                 "/ {} alive ({:.0}%)",
-                format_count(info.max_count),
+                format_count(info.active_count),
                 util * 100.0,
             ))
             .size(SMALL_SIZE)
@@ -133,7 +161,7 @@ pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
         ui.spacing_mut().item_spacing.x = 4.0;
         // Blend mode — a sorted splat effect bypasses the OIT framebuffer, so show
         // the true active path ("sorted") rather than the pfx blend string ("oit").
-        if info.splat_sorted {
+        if info.splat_sorted || info.depth_sort {
             feature_badge(ui, "sorted", tc.accent);
         } else {
             feature_badge(ui, &info.blend_mode, tc.text_secondary);
@@ -161,6 +189,14 @@ pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
                 ),
             );
         }
+        if info.quality_scale < 1.0 {
+            feature_badge_with_tooltip(
+                ui,
+                &format!("auto {:.0}%", info.quality_scale * 100.0),
+                egui::Color32::from_rgb(0xC0, 0x90, 0x30),
+                "Auto quality is trimming particles to hold the frame rate",
+            );
+        }
         if info.has_flow_field {
             feature_badge(ui, "flow field", tc.accent);
         }
@@ -288,6 +324,85 @@ pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
             });
         }
 
+        // Emit from: the image/video source, or another layer's live output
+        if !info.layer_choices.is_empty() || info.layer_source.is_some() {
+            let selected = info
+                .layer_source
+                .and_then(|i| info.layer_choices.iter().find(|(c, _)| *c == i))
+                .map(|(_, name)| name.as_str())
+                .unwrap_or("Image");
+            let mut choice = info.layer_source;
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new("Emit from")
+                        .size(SMALL_SIZE)
+                        .color(tc.text_secondary),
+                );
+                egui::ComboBox::from_id_salt("particle_layer_source")
+                    .selected_text(RichText::new(selected).size(SMALL_SIZE))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut choice, None, "Image");
+                        for (i, name) in &info.layer_choices {
+                            ui.selectable_value(&mut choice, Some(*i), name);
+                        }
+                    });
+            });
+            if choice != info.layer_source {
+                ui.ctx().data_mut(|d| {
+                    d.insert_temp(egui::Id::new("particle_layer_source"), choice);
+                });
+            }
+        }
+
+        // Text or SVG logo as the emitter image (#2161)
+        if info.layer_source.is_none() {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 3.0;
+                let text_id = egui::Id::new("particle_text_input_buf");
+                let mut text_buf: String = ui
+                    .ctx()
+                    .data_mut(|d| d.get_persisted_mut_or_default::<String>(text_id).clone());
+                let te = ui.add(
+                    egui::TextEdit::singleline(&mut text_buf)
+                        .desired_width(ui.available_width() - 84.0)
+                        .hint_text("Text...")
+                        .font(egui::TextStyle::Small),
+                );
+                if te.changed() {
+                    let buf = text_buf.clone();
+                    ui.ctx().data_mut(|d| {
+                        *d.get_persisted_mut_or_default::<String>(text_id) = buf;
+                    });
+                }
+                let enter_pressed =
+                    te.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let set_clicked = ui
+                    .add(
+                        egui::Button::new(RichText::new("Set").size(SMALL_SIZE - 1.0))
+                            .min_size(egui::vec2(0.0, 20.0)),
+                    )
+                    .on_hover_text("Particles form this text")
+                    .clicked();
+                if (set_clicked || enter_pressed) && !text_buf.trim().is_empty() {
+                    let text = text_buf.trim().to_string();
+                    ui.ctx().data_mut(|d| {
+                        d.insert_temp(egui::Id::new("particle_text_source"), text);
+                    });
+                }
+                if ui
+                    .add(
+                        egui::Button::new(RichText::new("Image...").size(SMALL_SIZE - 1.0))
+                            .min_size(egui::vec2(0.0, 20.0)),
+                    )
+                    .on_hover_text("Load an image or SVG logo")
+                    .clicked()
+                {
+                    ui.ctx()
+                        .data_mut(|d| d.insert_temp(egui::Id::new("particle_load_image"), true));
+                }
+            });
+        }
+
         // Video transport controls (only when video source is active)
         if info.source_type == "video" {
             ui.add_space(2.0);
@@ -782,6 +897,8 @@ pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
     // clamped by the slider, which would corrupt ps.def every frame.
     let emit_max = emit_rate.max(info.max_count as f32 * 0.1).max(5000.0);
     let burst_max = burst.max(2000);
+    let mut trigger_burst = info.trigger_burst;
+    let trigger_max = trigger_burst.max(info.max_count / 10).max(10_000);
     let life_max = lifetime.max(30.0);
     let speed_min = speed.min(0.0);
     let speed_max = speed.max(2.0);
@@ -791,6 +908,37 @@ pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
     let drag_min = drag.min(0.8);
 
     // Shared aligned rows; each edit is posted via temp data for main.rs to apply.
+    // Count (#2156): live while dragging within the allocated buffers; a larger
+    // count is only applied on release, since it reloads the effect.
+    if !info.budget_locked {
+        let mut count = info.particle_budget;
+        let count_min = 1_000.min(count).max(1);
+        let row = rows::ParamRow::new("Count")
+            .logarithmic(true)
+            .tooltip(
+                "Particles simulated. Up to the allocated buffers this applies live; \
+                 more reloads the effect with larger buffers.",
+            )
+            .formatter(|v| format_count(v as u32))
+            .show_slider(ui, &mut count, count_min..=info.count_ceiling.max(count));
+        if (row.changed || row.committed) && count != info.particle_budget {
+            ui.ctx().data_mut(|d| {
+                d.insert_temp(egui::Id::new("particle_count"), (count, row.committed));
+            });
+        }
+    }
+    let mut auto_quality = info.auto_quality;
+    if rows::checkbox_row(
+        ui,
+        &mut auto_quality,
+        "Auto quality",
+        Some("Trim particle counts on every layer when frames run over budget"),
+    )
+    .changed()
+    {
+        ui.ctx()
+            .data_mut(|d| d.insert_temp(egui::Id::new("particle_auto_quality"), auto_quality));
+    }
     if rows::ParamRow::new("Emit rate")
         .logarithmic(true)
         .formatter(|v| format!("{v:.0}/s"))
@@ -807,7 +955,44 @@ pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
         ui.ctx()
             .data_mut(|d| d.insert_temp(egui::Id::new("particle_burst"), burst));
     }
-    if rows::ParamRow::new("Lifetime")
+    // Triggered burst (#2162): what the Particle Burst trigger emits
+    if rows::ParamRow::new("Trigger burst")
+        .logarithmic(true)
+        .tooltip("Particles emitted by the Particle Burst trigger (P, MIDI, OSC or web)")
+        .formatter(|v| format_count(v as u32))
+        .show_slider(ui, &mut trigger_burst, 0..=trigger_max)
+        .changed
+    {
+        ui.ctx()
+            .data_mut(|d| d.insert_temp(egui::Id::new("particle_trigger_burst"), trigger_burst));
+    }
+    let mut quantize = info.trigger_burst_quantize;
+    ui.horizontal(|ui| {
+        if ui
+            .checkbox(
+                &mut quantize,
+                RichText::new("On next beat").size(SMALL_SIZE),
+            )
+            .on_hover_text("Hold a triggered burst until the next detected beat")
+            .changed()
+        {
+            ui.ctx()
+                .data_mut(|d| d.insert_temp(egui::Id::new("particle_trigger_quantize"), quantize));
+        }
+        if ui
+            .add_enabled(
+                info.trigger_burst > 0,
+                egui::Button::new(RichText::new("Fire").size(SMALL_SIZE - 1.0))
+                    .min_size(egui::vec2(0.0, 20.0)),
+            )
+            .on_hover_text("Fire the Particle Burst trigger")
+            .clicked()
+        {
+            ui.ctx()
+                .data_mut(|d| d.insert_temp(egui::Id::new("particle_fire_burst"), true));
+        }
+    });
+    if lock_if_bound(rows::ParamRow::new("Lifetime"), info, "lifetime")
         .formatter(|v| format!("{v:.1}s"))
         .show_slider(ui, &mut lifetime, 0.5..=life_max)
         .changed
@@ -815,7 +1000,7 @@ pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
         ui.ctx()
             .data_mut(|d| d.insert_temp(egui::Id::new("particle_lifetime"), lifetime));
     }
-    if rows::ParamRow::new("Speed")
+    if lock_if_bound(rows::ParamRow::new("Speed"), info, "initial_speed")
         .logarithmic(speed_log)
         .formatter(|v| format!("{v:.3}"))
         .show_slider(ui, &mut speed, speed_min..=speed_max)
@@ -824,7 +1009,7 @@ pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
         ui.ctx()
             .data_mut(|d| d.insert_temp(egui::Id::new("particle_speed"), speed));
     }
-    if rows::ParamRow::new("Size")
+    if lock_if_bound(rows::ParamRow::new("Size"), info, "initial_size")
         .logarithmic(true)
         .formatter(|v| format!("{v:.4}"))
         .show_slider(ui, &mut size, size_min..=size_max)
@@ -833,7 +1018,7 @@ pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
         ui.ctx()
             .data_mut(|d| d.insert_temp(egui::Id::new("particle_size"), size));
     }
-    if rows::ParamRow::new("Drag")
+    if lock_if_bound(rows::ParamRow::new("Drag"), info, "drag")
         .formatter(|v| format!("{v:.3}"))
         .show_slider(ui, &mut drag, drag_min..=1.0)
         .changed
@@ -841,6 +1026,96 @@ pub fn draw_particle_panel(ui: &mut Ui, info: &ParticleInfo) {
         ui.ctx()
             .data_mut(|d| d.insert_temp(egui::Id::new("particle_drag"), drag));
     }
+
+    // Trails (#2159): width and fade are live; a new length reallocates the
+    // ring, so the slider holds its drag locally and sends on release.
+    if info.trails_supported {
+        let drag_id = egui::Id::new("particle_trail_length_drag");
+        let mut trail_length = ui
+            .ctx()
+            .data(|d| d.get_temp::<u32>(drag_id))
+            .unwrap_or(info.trail_length);
+        let row = rows::ParamRow::new("Trail")
+            .tooltip("Ribbon length in frames; below 2 turns trails off")
+            .formatter(|v| {
+                if v < 2.0 {
+                    "off".to_string()
+                } else {
+                    format!("{v:.0} pt")
+                }
+            })
+            .show_slider(ui, &mut trail_length, 0..=64u32.max(info.trail_length));
+        // A click or arrow key changes it without a drag: send that at once.
+        if row.committed || (row.changed && !row.response.dragged()) {
+            ui.ctx().data_mut(|d| {
+                d.remove::<u32>(drag_id);
+                if trail_length != info.trail_length {
+                    d.insert_temp(egui::Id::new("particle_trail_length"), trail_length);
+                }
+            });
+        } else if row.changed {
+            ui.ctx().data_mut(|d| d.insert_temp(drag_id, trail_length));
+        }
+        if info.has_trails {
+            let mut width = info.trail_width;
+            let mut fade = info.trail_fade;
+            if rows::ParamRow::new("Trail width")
+                .logarithmic(true)
+                .formatter(|v| format!("{v:.4}"))
+                .show_slider(ui, &mut width, 0.0005..=width.max(0.05))
+                .changed
+            {
+                ui.ctx()
+                    .data_mut(|d| d.insert_temp(egui::Id::new("particle_trail_width"), width));
+            }
+            if rows::ParamRow::new("Trail fade")
+                .tooltip("How quickly a ribbon fades toward its tail: 1 = linear, higher = shorter")
+                .formatter(|v| format!("{v:.2}"))
+                .show_slider(ui, &mut fade, 0.25..=fade.max(6.0))
+                .changed
+            {
+                ui.ctx()
+                    .data_mut(|d| d.insert_temp(egui::Id::new("particle_trail_fade"), fade));
+            }
+        }
+    }
+
+    // Depth sort (#2163): only alpha blending depends on draw order
+    if info.blend_mode == "alpha" && !info.is_compute_raster && !info.has_splat {
+        let mut sort = info.depth_sort;
+        let hover = if info.depth_sort_supported {
+            "Draw particles back-to-front so overlapping alpha sprites stop flickering"
+        } else {
+            "Depth sort is limited to 65K particles — lower the count to enable it"
+        };
+        if ui
+            .add_enabled(
+                info.depth_sort_supported,
+                egui::Checkbox::new(&mut sort, RichText::new("Depth sort").size(SMALL_SIZE)),
+            )
+            .on_hover_text(hover)
+            .on_disabled_hover_text(hover)
+            .changed()
+        {
+            ui.ctx()
+                .data_mut(|d| d.insert_temp(egui::Id::new("particle_depth_sort"), sort));
+        }
+    }
+}
+
+/// A `.pfx` `params` entry (#2164) overrides the slider every frame, so lock
+/// it and point at the input that drives it.
+fn lock_if_bound<'a>(
+    row: rows::ParamRow<'a>,
+    info: &ParticleInfo,
+    setting: &str,
+) -> rows::ParamRow<'a> {
+    if info.bound_params.iter().any(|s| s == setting) {
+        row.enabled(false)
+            .tooltip("Driven by an effect input — adjust it in the Parameters panel")
+    } else {
+        row
+    }
 }
 
 fn feature_badge_with_tooltip(ui: &mut Ui, text: &str, color: egui::Color32, tooltip: &str) {
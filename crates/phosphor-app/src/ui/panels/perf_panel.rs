@@ -0,0 +1,226 @@
+//! Performance panel (#2226): live GPU resource counts, who holds them, and a
+//! warning when a kind keeps growing across minutes of use. Opened by clicking
+//! the FPS readout in the status bar. Profiling builds show the GPU timings here.
+
+use egui::{Context, RichText, Ui};
+
+use crate::gpu::resources::{self, ResourceCounts, ResourceMonitor};
+use crate::session_stats::SessionStats;
+use crate::ui::theme::colors::theme_colors;
+use crate::ui::theme::tokens::*;
+
+const NEEDED_HISTORY_SECS: f64 = 240.0;
+
+pub fn draw_perf_panel(
+    ctx: &Context,
+    open: &mut bool,
+    monitor: &ResourceMonitor,
+    stats: &SessionStats,
+    delta_time: f32,
+    extra: impl FnOnce(&mut Ui),
+) {
+    let tc = theme_colors(ctx);
+
+    egui::Window::new("Performance")
+        .id(egui::Id::new("perf_panel_window"))
+        .open(open)
+        .default_pos([10.0, 10.0])
+        .default_size([360.0, 380.0])
+        .resizable(true)
+        .collapsible(true)
+        .show(ctx, |ui| {
+            ui.label(
+                RichText::new(format!("Frame {:.1} ms", delta_time * 1000.0))
+                    .size(BODY_SIZE)
+                    .color(tc.text_primary),
+            );
+            draw_session(ui, stats);
+            ui.add_space(6.0);
+
+            let (Some(live), Some(delta)) = (monitor.live(), monitor.since_start()) else {
+                return;
+            };
+            if !monitor.has_counters() {
+                ui.label(
+                    RichText::new("This GPU backend doesn't report resource counts.")
+                        .size(BODY_SIZE)
+                        .color(tc.text_secondary),
+                );
+                extra(ui);
+                return;
+            }
+
+            let growing = monitor.growing();
+            ui.label(
+                RichText::new("GPU RESOURCES")
+                    .size(SMALL_SIZE)
+                    .color(tc.text_secondary)
+                    .strong(),
+            );
+            egui::Grid::new("perf_resources_grid")
+                .num_columns(4)
+                .spacing([12.0, 2.0])
+                .show(ui, |ui| {
+                    for ((kind, now), (change, grows)) in ResourceCounts::KINDS
+                        .iter()
+                        .zip(live.values())
+                        .zip(delta.values().into_iter().zip(growing))
+                    {
+                        let color = if grows { tc.warning } else { tc.text_primary };
+                        ui.label(RichText::new(*kind).size(SMALL_SIZE).color(color));
+                        ui.label(RichText::new(now.to_string()).size(SMALL_SIZE).color(color));
+                        ui.label(
+                            RichText::new(format!("{change:+}"))
+                                .size(SMALL_SIZE)
+                                .color(tc.text_secondary),
+                        )
+                        .on_hover_text("Change since startup");
+                        if grows {
+                            ui.label(RichText::new("growing").size(SMALL_SIZE).color(tc.warning));
+                        } else {
+                            ui.label("");
+                        }
+                        ui.end_row();
+                    }
+                    if monitor.memory_bytes > 0 {
+                        ui.label(
+                            RichText::new("Memory")
+                                .size(SMALL_SIZE)
+                                .color(tc.text_primary),
+                        );
+                        ui.label(
+                            RichText::new(format!(
+                                "{:.1} MB",
+                                monitor.memory_bytes as f64 / (1024.0 * 1024.0)
+                            ))
+                            .size(SMALL_SIZE)
+                            .color(tc.text_primary),
+                        );
+                        ui.end_row();
+                    }
+                });
+
+            if growing.contains(&true) {
+                ui.add_space(4.0);
+                ui.label(
+                    RichText::new(
+                        "Still climbing after four minutes: something isn't freeing what it \
+                         creates. The subsystem table shows who holds it.",
+                    )
+                    .size(SMALL_SIZE)
+                    .color(tc.warning),
+                );
+            } else if monitor.history_secs() < NEEDED_HISTORY_SECS {
+                ui.add_space(4.0);
+                ui.label(
+                    RichText::new(format!(
+                        "Growth check starts after four minutes ({:.0}s so far).",
+                        monitor.history_secs()
+                    ))
+                    .size(SMALL_SIZE)
+                    .color(tc.text_secondary),
+                );
+            }
+
+            ui.add_space(8.0);
+            ui.label(
+                RichText::new("BY SUBSYSTEM")
+                    .size(SMALL_SIZE)
+                    .color(tc.text_secondary)
+                    .strong(),
+            );
+            let usage = resources::usage();
+            egui::Grid::new("perf_subsystem_grid")
+                .num_columns(6)
+                .spacing([10.0, 2.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("");
+                    ui.label(
+                        RichText::new("Objects")
+                            .size(SMALL_SIZE)
+                            .color(tc.text_secondary),
+                    );
+                    for kind in ResourceCounts::KINDS {
+                        ui.label(
+                            RichText::new(kind)
+                                .size(SMALL_SIZE)
+                                .color(tc.text_secondary),
+                        );
+                    }
+                    ui.end_row();
+
+                    let mut attributed = [0i64; 4];
+                    for u in &usage {
+                        let held = u.held().values();
+                        let row = ui.label(
+                            RichText::new(u.subsystem.display_name())
+                                .size(SMALL_SIZE)
+                                .color(tc.text_primary),
+                        );
+                        row.on_hover_text(format!(
+                            "Created {} · freed {}",
+                            summary(&u.created),
+                            summary(&u.released)
+                        ));
+                        ui.label(RichText::new(u.owners.to_string()).size(SMALL_SIZE));
+                        for (sum, n) in attributed.iter_mut().zip(held) {
+                            *sum += n;
+                            ui.label(RichText::new(n.to_string()).size(SMALL_SIZE));
+                        }
+                        ui.end_row();
+                    }
+
+                    // Fonts, the compositor, egui and anything created outside a
+                    // tracked constructor
+                    ui.label(
+                        RichText::new("Other")
+                            .size(SMALL_SIZE)
+                            .color(tc.text_secondary),
+                    )
+                    .on_hover_text("Compositor, UI and anything not owned by a subsystem");
+                    ui.label("");
+                    for (now, sum) in live.values().into_iter().zip(attributed) {
+                        ui.label(RichText::new((now - sum).max(0).to_string()).size(SMALL_SIZE));
+                    }
+                    ui.end_row();
+                });
+
+            extra(ui);
+        });
+}
+
+/// This session's numbers so far (#2241), as the set report will give them.
+fn draw_session(ui: &mut Ui, stats: &SessionStats) {
+    let tc = theme_colors(ui.ctx());
+    let mut line = match stats.average_fps() {
+        Some(fps) => format!("Session: {fps:.0} FPS average"),
+        None => "Session".to_string(),
+    };
+    line += &format!(" · {} dropped", stats.dropped_frames());
+    if stats.peak_memory_bytes() > 0 {
+        line += &format!(
+            " · {:.0} MB peak",
+            stats.peak_memory_bytes() as f64 / (1024.0 * 1024.0)
+        );
+    }
+    let changes = stats.device_changes().len();
+    if changes > 0 {
+        line += &format!(" · {changes} audio device change(s)");
+    }
+    ui.label(
+        RichText::new(line)
+            .size(SMALL_SIZE)
+            .color(tc.text_secondary),
+    )
+    .on_hover_text("Written to the set report by Export set in the Presets panel");
+}
+
+fn summary(counts: &ResourceCounts) -> String {
+    ResourceCounts::KINDS
+        .iter()
+        .zip(counts.values())
+        .map(|(kind, n)| format!("{n} {}", kind.to_lowercase()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -2,12 +2,23 @@
 //! film grain). Extracted from the `draw_panels` inline block; the per-effect
 //! checkbox + indented-params structure is kept, with sliders on shared rows.
 
-use egui::Ui;
+use egui::{RichText, Ui};
 
-use crate::effect::format::PostProcessDef;
+use crate::effect::format::{PostProcessDef, TONEMAPPERS};
+use crate::effect::post_source::PostSource;
+use crate::ui::theme::tokens::*;
 use crate::ui::widgets::rows;
 
-pub fn draw_postfx_panel(ui: &mut Ui, postprocess: &mut PostProcessDef) {
+/// `source` is where the active effect takes its chain from (#2234); `None`
+/// when the active layer isn't an effect.
+pub fn draw_postfx_panel(
+    ui: &mut Ui,
+    postprocess: &mut PostProcessDef,
+    source: Option<PostSource>,
+) {
+    if let Some(source) = source {
+        draw_source(ui, source);
+    }
     ui.checkbox(&mut postprocess.enabled, "Enable");
     let global_on = postprocess.enabled;
 
@@ -75,4 +86,85 @@ pub fn draw_postfx_panel(ui: &mut Ui, postprocess: &mut PostProcessDef) {
             );
         });
     });
+
+    ui.add_space(4.0);
+
+    // Tonemapping (#2235): how HDR brightness is squeezed into the display's range
+    ui.add_enabled_ui(global_on, |ui| {
+        let current = TONEMAPPERS
+            .iter()
+            .find(|(id, _)| *id == postprocess.tonemap)
+            .map_or("ACES", |(_, name)| name);
+        rows::combo_row(
+            ui,
+            "tonemap_selector",
+            "Tonemap",
+            Some(
+                "ACES: punchy filmic highlights. Reinhard: gentle, keeps colour in bright \
+                 particles. Hable: soft filmic shoulder. Linear: clips at white.",
+            ),
+            current,
+            |ui| {
+                for (id, name) in TONEMAPPERS {
+                    ui.selectable_value(
+                        &mut postprocess.tonemap,
+                        id.to_string(),
+                        RichText::new(*name).size(SMALL_SIZE),
+                    );
+                }
+            },
+        );
+        rows::ParamRow::new("Exposure")
+            .tooltip("Brightness in stops before tonemapping; +1 doubles it")
+            .show_slider(ui, &mut postprocess.exposure, -4.0..=4.0);
+        rows::ParamRow::new("White point")
+            .tooltip(
+                "Brightness that becomes full white. Lower it to brighten highlights, \
+                 raise it to keep detail in hot particle cores.",
+            )
+            .enabled(postprocess.tonemap != "linear")
+            .show_slider(ui, &mut postprocess.white_point, 1.0..=20.0);
+    });
+}
+
+/// Where the chain comes from, and saving it as the default. Applied by
+/// main.rs through the `set_post_source` and `save_post_default` temps.
+fn draw_source(ui: &mut Ui, source: PostSource) {
+    let mut picked = source;
+    rows::combo_row(
+        ui,
+        "post_source_selector",
+        "Source",
+        Some(
+            "Effect's post: the effect's own chain, or the preset's. My default: your \
+             default chain. Custom: a chain kept for this effect. The last two are saved \
+             in your settings and override presets.",
+        ),
+        source.display_name(),
+        |ui| {
+            for &s in PostSource::ALL {
+                ui.selectable_value(
+                    &mut picked,
+                    s,
+                    RichText::new(s.display_name()).size(SMALL_SIZE),
+                );
+            }
+        },
+    );
+    if picked != source {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("set_post_source"), picked);
+        });
+    }
+    if source != PostSource::Default
+        && ui
+            .small_button("Save as my default")
+            .on_hover_text("Use this chain for effects set to My default, and effects without one")
+            .clicked()
+    {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("save_post_default"), true);
+        });
+    }
+    ui.add_space(4.0);
 }
@@ -1,9 +1,16 @@
+use std::collections::BTreeMap;
+
 use egui::{
     Color32, CornerRadius, Frame, Margin, RichText, Stroke, Ui, Vec2,
     collapsing_header::CollapsingState,
 };
 
+use crate::midi::MidiSystem;
+use crate::midi::types::{LearnTarget, TriggerAction};
 use crate::preset::PresetStore;
+use crate::preset::crossfade::PresetCrossfade;
+use crate::ui::hints;
+use crate::ui::panels::midi_panel;
 use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
 use crate::ui::widgets;
@@ -43,8 +50,43 @@ fn draw_pulse_dot(ui: &mut Ui, time: f64) {
     ui.painter().circle(center, 3.0, AMBER, Stroke::NONE);
 }
 
+/// Direct key and MIDI bindings (#2222), shown on the tiles and assigned from
+/// their context menus.
+pub struct PresetBindings<'a> {
+    pub midi: &'a mut MidiSystem,
+    /// Digit → preset id, from settings.
+    pub keys: &'a BTreeMap<u8, u64>,
+    /// The presets on the crossfade fader (#2250), and where it sits.
+    pub crossfade: &'a PresetCrossfade,
+}
+
+impl PresetBindings<'_> {
+    fn key_of(&self, id: u64) -> Option<u8> {
+        self.keys.iter().find(|(_, v)| **v == id).map(|(d, _)| *d)
+    }
+
+    /// "3", "N36", "3 · N36", or ".." while learning a pad for it.
+    fn badge(&self, id: u64) -> Option<String> {
+        let action = TriggerAction::LoadPreset(id);
+        let midi = if self.midi.learn_target == Some(LearnTarget::Trigger(action)) {
+            Some("..".to_string())
+        } else {
+            self.midi
+                .config
+                .triggers
+                .get(&action)
+                .map(midi_panel::mapping_label)
+        };
+        match (self.key_of(id), midi) {
+            (Some(d), Some(m)) => Some(format!("{d} · {m}")),
+            (Some(d), None) => Some(d.to_string()),
+            (None, m) => m,
+        }
+    }
+}
+
 /// Top-level preset section with custom header (replaces widgets::section for presets).
-pub fn draw_preset_section(ui: &mut Ui, store: &PresetStore) {
+pub fn draw_preset_section(ui: &mut Ui, store: &PresetStore, bindings: &mut PresetBindings) {
     let tc = theme_colors(ui.ctx());
     let dirty = store.dirty;
     let time = ui.input(|i| i.time);
@@ -71,7 +113,7 @@ pub fn draw_preset_section(ui: &mut Ui, store: &PresetStore) {
             Color32::from_rgba_unmultiplied(AMBER.r(), AMBER.g(), AMBER.b(), alpha as u8),
         );
     }
-    frame.show(ui, |ui| {
+    let card = frame.show(ui, |ui| {
         let full_width = ui.available_width();
 
         // Header row
@@ -126,12 +168,13 @@ pub fn draw_preset_section(ui: &mut Ui, store: &PresetStore) {
         // Body
         if state.is_open() {
             ui.add_space(4.0);
-            draw_preset_panel(ui, store);
+            draw_preset_panel(ui, store, bindings);
         }
     });
+    hints::mark(ui, "sec_presets", card.response.rect);
 }
 
-fn draw_preset_panel(ui: &mut Ui, store: &PresetStore) {
+fn draw_preset_panel(ui: &mut Ui, store: &PresetStore, bindings: &mut PresetBindings) {
     let tc = theme_colors(ui.ctx());
     let time = ui.input(|i| i.time);
 
@@ -243,6 +286,8 @@ fn draw_preset_panel(ui: &mut Ui, store: &PresetStore) {
         }
     }
 
+    draw_recent_strip(ui, store, &tc);
+
     // Compact save row
     ui.label(
         RichText::new("Save current state as preset:")
@@ -344,6 +389,7 @@ fn draw_preset_panel(ui: &mut Ui, store: &PresetStore) {
                 pending_delete,
                 &mut new_pending,
                 loading_index,
+                bindings,
             );
         });
     }
@@ -375,6 +421,7 @@ fn draw_preset_panel(ui: &mut Ui, store: &PresetStore) {
                 pending_delete,
                 &mut new_pending,
                 loading_index,
+                bindings,
             );
         }
     });
@@ -464,7 +511,182 @@ fn draw_preset_panel(ui: &mut Ui, store: &PresetStore) {
                 });
             }
         }
+
+        // Decode the next preset's media into the RAM cache ahead of time
+        if store.presets.len() > 1 {
+            let preloading: bool = ui
+                .ctx()
+                .data(|d| d.get_temp(egui::Id::new("media_preloading")))
+                .unwrap_or(false);
+            let label = if preloading {
+                "Preloading\u{2026}"
+            } else {
+                "Preload next"
+            };
+            if ui
+                .add_enabled(
+                    !preloading,
+                    egui::Button::new(RichText::new(label).size(SMALL_SIZE).color(tc.text_primary))
+                        .fill(tc.card_bg)
+                        .stroke(Stroke::new(1.0_f32, tc.card_border))
+                        .corner_radius(CornerRadius::same(4)),
+                )
+                .on_hover_text("Decode the next preset's media into RAM so it switches instantly")
+                .clicked()
+            {
+                ui.ctx()
+                    .data_mut(|d| d.insert_temp(egui::Id::new("preload_next_preset"), true));
+            }
+        }
+
+        if ui
+            .add_enabled(
+                !store.history.entries().is_empty(),
+                egui::Button::new(
+                    RichText::new("Export set")
+                        .size(SMALL_SIZE)
+                        .color(tc.text_primary),
+                )
+                .fill(tc.card_bg)
+                .stroke(Stroke::new(1.0_f32, tc.card_border))
+                .corner_radius(CornerRadius::same(4)),
+            )
+            .on_hover_text(
+                "Write this session's presets and effects, with times, and how the machine \
+                 held up as a set report",
+            )
+            .clicked()
+        {
+            ui.ctx()
+                .data_mut(|d| d.insert_temp(egui::Id::new("export_set_report"), true));
+        }
+    });
+
+    if let Some(export) = &store.history.last_export {
+        let (text, color) = match export {
+            Ok(path) => (
+                format!(
+                    "Saved {}",
+                    path.file_name().unwrap_or_default().to_string_lossy()
+                ),
+                tc.text_secondary,
+            ),
+            Err(e) => (format!("Export failed: {e}"), tc.error),
+        };
+        ui.label(RichText::new(text).size(SMALL_SIZE - 1.0).color(color));
+    }
+
+    draw_crossfade(ui, store, bindings.crossfade, &tc);
+}
+
+/// Preset crossfade (#2250): pick A and B, then blend with the fader. The
+/// same fader is the "Preset crossfade" binding target, for a MIDI fader.
+fn draw_crossfade(
+    ui: &mut Ui,
+    store: &PresetStore,
+    fade: &PresetCrossfade,
+    tc: &crate::ui::theme::colors::ThemeColors,
+) {
+    ui.add_space(6.0);
+    ui.label(
+        RichText::new("Crossfade")
+            .size(SMALL_SIZE)
+            .color(tc.text_secondary),
+    )
+    .on_hover_text(
+        "Blend between two presets with one fader. Opacities and the params of \
+         effects both presets run are mixed; everything else switches at the middle. \
+         Bind a MIDI fader to Global \u{2192} Preset crossfade to play it live.",
+    );
+
+    let name_of = |id: Option<u64>| {
+        id.and_then(|id| store.index_of_id(id))
+            .map_or("\u{2014}", |i| store.presets[i].0.as_str())
+    };
+    let mut ends = (fade.a, fade.b);
+    ui.horizontal(|ui| {
+        let pick_w = (ui.available_width() - ui.spacing().item_spacing.x) / 2.0;
+        for (label, end) in [("A", &mut ends.0), ("B", &mut ends.1)] {
+            egui::ComboBox::from_id_salt(format!("preset_crossfade_{label}"))
+                .width(pick_w - 24.0)
+                .selected_text(
+                    RichText::new(format!("{label}: {}", truncate_name(name_of(*end), 12)))
+                        .size(SMALL_SIZE),
+                )
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(end, None, RichText::new("None").size(SMALL_SIZE));
+                    for (name, preset) in &store.presets {
+                        ui.selectable_value(
+                            end,
+                            Some(preset.id),
+                            RichText::new(name).size(SMALL_SIZE),
+                        );
+                    }
+                });
+        }
+    });
+    if ends != (fade.a, fade.b) {
+        ui.ctx()
+            .data_mut(|d| d.insert_temp(egui::Id::new("preset_crossfade_ends"), ends));
+    }
+
+    let ready = [fade.a, fade.b]
+        .into_iter()
+        .all(|id| id.and_then(|id| store.index_of_id(id)).is_some());
+    let mut position = fade.position;
+    let saved_bg = ui.visuals().widgets.inactive.bg_fill;
+    ui.visuals_mut().widgets.inactive.bg_fill = tc.meter_bg;
+    let slider = ui.add_enabled(
+        ready,
+        egui::Slider::new(&mut position, 0.0..=1.0)
+            .show_value(false)
+            .text(""),
+    );
+    ui.visuals_mut().widgets.inactive.bg_fill = saved_bg;
+    if slider.changed() {
+        ui.ctx()
+            .data_mut(|d| d.insert_temp(egui::Id::new("preset_crossfade_position"), position));
+    }
+}
+
+/// Recently used presets, newest first, one click back (#2220).
+fn draw_recent_strip(ui: &mut Ui, store: &PresetStore, tc: &crate::ui::theme::colors::ThemeColors) {
+    let recent = store.history.recent_presets(store.current_name(), 6);
+    if recent.is_empty() {
+        return;
+    }
+    ui.label(
+        RichText::new("Recent")
+            .size(SMALL_SIZE)
+            .color(tc.text_secondary),
+    );
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing = Vec2::new(4.0, 4.0);
+        for name in recent {
+            // Presets deleted since they were used drop out of the strip.
+            let Some(index) = store.presets.iter().position(|(n, _)| n == name) else {
+                continue;
+            };
+            if ui
+                .add(
+                    egui::Button::new(
+                        RichText::new(truncate_name(name, 12))
+                            .size(SMALL_SIZE - 1.0)
+                            .color(tc.text_primary),
+                    )
+                    .fill(tc.card_bg)
+                    .stroke(Stroke::new(1.0_f32, tc.card_border))
+                    .corner_radius(CornerRadius::same(3)),
+                )
+                .on_hover_text(name)
+                .clicked()
+            {
+                ui.ctx()
+                    .data_mut(|d| d.insert_temp(egui::Id::new("pending_preset"), index));
+            }
+        }
     });
+    ui.add_space(6.0);
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -479,6 +701,7 @@ fn draw_preset_grid(
     pending_delete: Option<(usize, f64)>,
     new_pending: &mut Option<(usize, f64)>,
     loading_index: Option<usize>,
+    bindings: &mut PresetBindings,
 ) {
     let warning_color = Color32::from_rgb(0xE0, 0x60, 0x40);
     let now = ui.input(|i| i.time);
@@ -489,7 +712,7 @@ fn draw_preset_grid(
     for row in presets.chunks(COLS) {
         ui.horizontal(|ui| {
             ui.spacing_mut().item_spacing.x = gap;
-            for &(i, (pname, _)) in row {
+            for &(i, (pname, preset)) in row {
                 let is_current = store.current_preset == Some(i);
                 let is_loading = loading_index == Some(i);
                 let is_armed =
@@ -573,6 +796,15 @@ fn draw_preset_grid(
                 .corner_radius(CornerRadius::same(4));
 
                 let response = ui.add_sized(Vec2::new(btn_width, btn_height), btn);
+                if let Some(badge) = bindings.badge(preset.id) {
+                    ui.painter().text(
+                        response.rect.right_top() + Vec2::new(-3.0, 2.0),
+                        egui::Align2::RIGHT_TOP,
+                        badge,
+                        egui::FontId::proportional(SMALL_SIZE - 2.0),
+                        tc.text_secondary,
+                    );
+                }
 
                 // Left click: load/reload preset (also clears pending delete)
                 if response.clicked() {
@@ -581,8 +813,25 @@ fn draw_preset_grid(
                         .data_mut(|d| d.insert_temp(egui::Id::new("pending_preset"), i));
                 }
 
+                // Shift+right-click: key, MIDI pad and rename (#2222). Plain
+                // right-click keeps deleting and deselecting.
+                let shift = ui.input(|inp| inp.modifiers.shift);
+                let open_menu = if response.secondary_clicked() && shift {
+                    Some(egui::SetOpenCommand::Bool(true))
+                } else if response.clicked() {
+                    Some(egui::SetOpenCommand::Bool(false))
+                } else {
+                    None
+                };
+                egui::Popup::context_menu(&response)
+                    .open_memory(open_menu)
+                    .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+                    .show(|ui| {
+                        draw_tile_menu(ui, i, pname, preset.id, is_builtin_section, bindings);
+                    });
+
                 // Right click: two-stage delete for user presets only
-                if !is_builtin_section && response.secondary_clicked() {
+                if !is_builtin_section && response.secondary_clicked() && !shift {
                     if is_armed {
                         // Second right-click: confirm delete
                         ui.ctx()
@@ -601,7 +850,7 @@ fn draw_preset_grid(
                 }
 
                 // Right-click on built-in: deselect if current + clean
-                if is_builtin_section && response.secondary_clicked() {
+                if is_builtin_section && response.secondary_clicked() && !shift {
                     if is_current && !store.dirty {
                         ui.ctx().data_mut(|d| {
                             d.insert_temp(egui::Id::new("deselect_preset"), true);
@@ -612,13 +861,13 @@ fn draw_preset_grid(
                 let hover_text = if is_armed {
                     "Right-click again to DELETE".to_string()
                 } else if is_builtin_section {
-                    pname.clone()
+                    format!("{pname} (shift+right-click to bind a key or pad)")
                 } else if is_current && store.dirty {
                     format!("{pname} — click to reload, right-click to delete")
                 } else if is_current {
                     format!("{pname} — click to reload, right-click to deselect")
                 } else {
-                    format!("{pname} (right-click to delete)")
+                    format!("{pname} (right-click to delete, shift+right-click for more)")
                 };
                 response.on_hover_text(hover_text);
             }
@@ -626,6 +875,97 @@ fn draw_preset_grid(
     }
 }
 
+/// A tile's context menu: the number key and MIDI pad that load it, and rename
+/// for user presets.
+fn draw_tile_menu(
+    ui: &mut Ui,
+    index: usize,
+    name: &str,
+    id: u64,
+    is_builtin: bool,
+    bindings: &mut PresetBindings,
+) {
+    ui.label(RichText::new(name).size(SMALL_SIZE).strong());
+    ui.separator();
+
+    let key = bindings.key_of(id);
+    let key_label = key.map_or_else(|| "Key: none".to_string(), |d| format!("Key: {d}"));
+    ui.menu_button(key_label, |ui| {
+        for digit in [1, 2, 3, 4, 5, 6, 7, 8, 9, 0] {
+            let taken_by_other = bindings.keys.get(&digit).is_some_and(|v| *v != id);
+            let text = if taken_by_other {
+                format!("{digit} (reassign)")
+            } else {
+                digit.to_string()
+            };
+            if ui.selectable_label(key == Some(digit), text).clicked() {
+                ui.ctx().data_mut(|d| {
+                    d.insert_temp(egui::Id::new("assign_preset_key"), (id, Some(digit)));
+                });
+                ui.close();
+            }
+        }
+        if key.is_some() && ui.button("Clear").clicked() {
+            ui.ctx().data_mut(|d| {
+                d.insert_temp(egui::Id::new("assign_preset_key"), (id, None::<u8>));
+            });
+            ui.close();
+        }
+    });
+
+    let action = TriggerAction::LoadPreset(id);
+    match bindings.midi.config.triggers.get(&action) {
+        Some(mapping) => {
+            let label = format!("MIDI: {} · re-learn", midi_panel::mapping_label(mapping));
+            if ui.button(label).clicked() {
+                bindings.midi.start_learn(LearnTarget::Trigger(action));
+                ui.close();
+            }
+            if ui.button("Clear MIDI").clicked() {
+                bindings.midi.clear_trigger_mapping(action);
+                ui.close();
+            }
+        }
+        None => {
+            if ui
+                .button("Learn MIDI pad")
+                .on_hover_text("Then press the pad or key on your controller")
+                .clicked()
+            {
+                bindings.midi.start_learn(LearnTarget::Trigger(action));
+                ui.close();
+            }
+        }
+    }
+
+    if is_builtin {
+        return;
+    }
+    ui.separator();
+    let buffer_id = egui::Id::new("preset_rename_buffer").with(index);
+    let mut new_name: String = ui
+        .ctx()
+        .data_mut(|d| d.get_temp(buffer_id))
+        .unwrap_or_else(|| name.to_string());
+    let resp = ui.add(
+        egui::TextEdit::singleline(&mut new_name)
+            .hint_text("Rename…")
+            .desired_width(140.0),
+    );
+    // Enter renames; clicking away drops the edit.
+    if resp.lost_focus() {
+        ui.ctx().data_mut(|d| d.remove_temp::<String>(buffer_id));
+        if ui.input(|i| i.key_pressed(egui::Key::Enter)) && new_name.trim() != name {
+            ui.ctx().data_mut(|d| {
+                d.insert_temp(egui::Id::new("rename_preset"), (index, new_name));
+            });
+            ui.close();
+        }
+    } else if resp.has_focus() {
+        ui.ctx().data_mut(|d| d.insert_temp(buffer_id, new_name));
+    }
+}
+
 fn truncate_name(name: &str, max_len: usize) -> String {
     if name.len() <= max_len {
         name.to_string()
@@ -2,7 +2,7 @@ use egui::{RichText, Ui};
 
 use crate::gpu::types::OutputResolution;
 use crate::recording::encoder::EncoderInfo;
-use crate::recording::types::{Container, RecordingConfig, VideoCodec};
+use crate::recording::types::{ClipFormat, Container, RecordingConfig, VideoCodec};
 use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
 
@@ -22,6 +22,8 @@ pub struct RecordingInfo {
     pub encoder_name: String,
     pub error: Option<String>,
     pub audio_active: bool,
+    /// The running capture is an "Export loop", not a recording.
+    pub exporting_loop: bool,
 }
 
 impl Default for RecordingInfo {
@@ -40,6 +42,7 @@ impl Default for RecordingInfo {
             encoder_name: String::new(),
             error: None,
             audio_active: false,
+            exporting_loop: false,
         }
     }
 }
@@ -56,6 +59,31 @@ pub fn draw_recording_panel(ui: &mut Ui, info: &RecordingInfo) {
         return;
     }
 
+    if info.exporting_loop {
+        let remaining = (f64::from(info.config.clip_seconds) - info.duration_secs).max(0.0);
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(format!(
+                    "Exporting {} loop… {:.1}s",
+                    info.config.clip_format.display_name(),
+                    remaining
+                ))
+                .size(SMALL_SIZE)
+                .color(egui::Color32::from_rgb(0xE0, 0x60, 0x60)),
+            );
+            if ui
+                .button(RichText::new("Cancel").size(SMALL_SIZE))
+                .on_hover_text("Stop the export and discard the clip")
+                .clicked()
+            {
+                ui.ctx().data_mut(|d| {
+                    d.insert_temp(egui::Id::new("clip_cancel"), true);
+                });
+            }
+        });
+        return;
+    }
+
     // Record button
     let (btn_text, btn_color) = if info.recording {
         (
@@ -81,7 +109,7 @@ pub fn draw_recording_panel(ui: &mut Ui, info: &RecordingInfo) {
         btn
     };
 
-    if ui.add(btn).clicked() {
+    if ui.add(btn).on_hover_text("Shortcut: R").clicked() {
         ui.ctx().data_mut(|d| {
             d.insert_temp(egui::Id::new("recording_toggle"), true);
         });
@@ -210,28 +238,35 @@ pub fn draw_recording_panel(ui: &mut Ui, info: &RecordingInfo) {
     });
 
     // Quality slider
-    ui.horizontal(|ui| {
-        ui.label(RichText::new("Quality").size(SMALL_SIZE));
-        let mut quality = info.config.quality;
-        let resp = ui.add(
-            egui::Slider::new(&mut quality, 15..=35)
-                .show_value(true)
-                .text(RichText::new("CQ").size(SMALL_SIZE - 1.0)),
-        );
-        if resp.changed() {
-            ui.ctx().data_mut(|d| {
-                d.insert_temp(egui::Id::new("rec_quality_change"), quality);
-            });
-        }
-    });
+    if info.config.codec.has_quality() {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("Quality").size(SMALL_SIZE));
+            let mut quality = info.config.quality;
+            let resp = ui.add(
+                egui::Slider::new(&mut quality, 15..=35)
+                    .show_value(true)
+                    .text(RichText::new("CQ").size(SMALL_SIZE - 1.0)),
+            );
+            if resp.changed() {
+                ui.ctx().data_mut(|d| {
+                    d.insert_temp(egui::Id::new("rec_quality_change"), quality);
+                });
+            }
+        });
+    }
 
-    // Container toggle
+    // Container toggle. ProRes is written as MOV when MP4 is picked.
     ui.horizontal(|ui| {
         ui.label(RichText::new("Container").size(SMALL_SIZE));
-        let current = info.config.container;
+        let current = info.config.output_container();
+        let prores = info.config.codec == VideoCodec::ProRes;
         for (i, &cont) in Container::ALL.iter().enumerate() {
             if ui
-                .selectable_label(current == cont, cont.display_name())
+                .add_enabled(
+                    !(prores && cont == Container::Mp4),
+                    egui::Button::new(cont.display_name()).selected(current == cont),
+                )
+                .on_disabled_hover_text("ProRes doesn't fit in MP4")
                 .clicked()
             {
                 ui.ctx().data_mut(|d| {
@@ -278,6 +313,47 @@ pub fn draw_recording_panel(ui: &mut Ui, info: &RecordingInfo) {
         }
     });
 
+    ui.add_space(4.0);
+    ui.separator();
+
+    // Export loop: a short GIF/WebM of the composite for sharing
+    ui.horizontal(|ui| {
+        let btn = egui::Button::new(RichText::new("Export loop").size(SMALL_SIZE))
+            .min_size(egui::vec2(90.0, 20.0));
+        let available = info.encoder_info.has_clip(info.config.clip_format);
+        if ui
+            .add_enabled(available, btn)
+            .on_hover_text("Capture the next few seconds of output as a looping clip")
+            .on_disabled_hover_text("ffmpeg has no VP9 encoder — pick GIF")
+            .clicked()
+        {
+            ui.ctx().data_mut(|d| {
+                d.insert_temp(egui::Id::new("clip_export"), true);
+            });
+        }
+        let current = info.config.clip_format;
+        for (i, &fmt) in ClipFormat::ALL.iter().enumerate() {
+            if ui
+                .selectable_label(current == fmt, fmt.display_name())
+                .clicked()
+            {
+                ui.ctx().data_mut(|d| {
+                    d.insert_temp(egui::Id::new("rec_clip_format_change"), i as u8);
+                });
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Length").size(SMALL_SIZE));
+        let mut secs = info.config.clip_seconds;
+        let resp = ui.add(egui::Slider::new(&mut secs, 1..=15).suffix(" s"));
+        if resp.changed() {
+            ui.ctx().data_mut(|d| {
+                d.insert_temp(egui::Id::new("rec_clip_seconds_change"), secs);
+            });
+        }
+    });
+
     // Output directory
     ui.horizontal(|ui| {
         ui.label(RichText::new("Output").size(SMALL_SIZE));
@@ -1,6 +1,6 @@
 use egui::{Color32, CornerRadius, Rect, RichText, Stroke, Ui, Vec2};
 
-use crate::scene::timeline::{TimelineInfo, TimelineInfoState};
+use crate::scene::timeline::{GoDirection, TimelineInfo, TimelineInfoState};
 use crate::scene::types::{AdvanceMode, TransitionType};
 use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
@@ -23,6 +23,9 @@ pub struct CueDisplayInfo {
     pub transition: TransitionType,
     pub transition_secs: f32,
     pub hold_secs: Option<f32>,
+    /// Cue number as shown: the cue's own, or its position.
+    pub number: String,
+    pub notes: String,
 }
 
 pub fn draw_scene_panel(ui: &mut Ui, info: &SceneInfo) {
@@ -170,12 +173,24 @@ pub fn draw_scene_panel(ui: &mut Ui, info: &SceneInfo) {
         ui.add_space(4.0);
         ui.separator();
         ui.add_space(2.0);
-        ui.label(
-            RichText::new("TRANSPORT")
-                .size(HEADING_SIZE)
-                .color(tc.text_secondary)
-                .strong(),
-        );
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new("TRANSPORT")
+                    .size(HEADING_SIZE)
+                    .color(tc.text_secondary)
+                    .strong(),
+            );
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .small_button(RichText::new("Cue panel").size(SMALL_SIZE))
+                    .on_hover_text("Show view: live and next cue with notes, GO / BACK (C)")
+                    .clicked()
+                {
+                    ui.ctx()
+                        .data_mut(|d| d.insert_temp(egui::Id::new("toggle_cue_panel"), true));
+                }
+            });
+        });
         ui.add_space(2.0);
 
         // Transport controls
@@ -237,8 +252,13 @@ pub fn draw_scene_panel(ui: &mut Ui, info: &SceneInfo) {
                     }
 
                     let go_w = ui.available_width();
+                    let go_label = if tl.pending_go == Some(GoDirection::Next) {
+                        "GO…"
+                    } else {
+                        "GO"
+                    };
                     let go_btn = egui::Button::new(
-                        RichText::new("GO")
+                        RichText::new(go_label)
                             .size(BODY_SIZE)
                             .color(Color32::WHITE)
                             .strong(),
@@ -593,16 +613,16 @@ fn draw_cue_row(
 
                 // ── Top row: cue number + name + hover-reveal × ──
                 ui.horizontal(|ui| {
-                    // Cue number (right-aligned in 14px)
+                    // Cue number (right-aligned in 20px)
                     let num_color = if is_current {
                         tc.accent
                     } else {
                         tc.text_secondary
                     };
                     ui.add_sized(
-                        Vec2::new(14.0, MIN_INTERACT_HEIGHT),
+                        Vec2::new(20.0, MIN_INTERACT_HEIGHT),
                         egui::Label::new(
-                            RichText::new(format!("{}.", idx + 1))
+                            RichText::new(format!("{}.", cue.number))
                                 .size(SMALL_SIZE)
                                 .color(num_color),
                         ),
@@ -655,7 +675,7 @@ fn draw_cue_row(
 
                 // ── Bottom row: transition badge + duration | Hold: duration ──
                 ui.horizontal(|ui| {
-                    ui.add_space(18.0); // align under name (past cue number)
+                    ui.add_space(24.0); // align under name (past cue number)
 
                     // Transition badge (colored, click-to-cycle)
                     let trans_color = match cue.transition {
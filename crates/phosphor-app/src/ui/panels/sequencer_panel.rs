@@ -0,0 +1,150 @@
+//! Step sequencer patterns (#2184). Sequences are added from a param's
+//! right-click menu; here each one gets its length, rate, swing and a step
+//! grid. Click or drag in a step to set its height.
+
+use egui::{RichText, Sense, Stroke, Ui, Vec2};
+
+use crate::params::ParamDef;
+use crate::params::sequencer::{MAX_SWING, RATES, STEP_COUNTS, StepSequence, StepSequencer};
+use crate::ui::theme::colors::theme_colors;
+use crate::ui::theme::tokens::*;
+
+const GRID_HEIGHT: f32 = 28.0;
+
+pub fn draw_sequencer_panel(ui: &mut Ui, sequencer: &mut StepSequencer, defs: &[ParamDef]) {
+    let tc = theme_colors(ui.ctx());
+
+    if sequencer.sequences.is_empty() {
+        ui.label(
+            RichText::new("Right-click a param name and pick Step sequence")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        );
+        return;
+    }
+
+    let beats = sequencer.beats;
+    let mut remove = None;
+    for seq in &mut sequencer.sequences {
+        let is_bool = defs
+            .iter()
+            .any(|d| matches!(d, ParamDef::Bool { name, .. } if *name == seq.param));
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 4.0;
+            ui.checkbox(&mut seq.enabled, RichText::new(&seq.param).size(SMALL_SIZE));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .small_button("\u{d7}")
+                    .on_hover_text("Remove this sequence")
+                    .clicked()
+                {
+                    remove = Some(seq.param.clone());
+                }
+                draw_settings(ui, seq);
+            });
+        });
+        draw_grid(ui, seq, beats, is_bool);
+    }
+    if let Some(param) = remove {
+        sequencer.toggle(&param);
+    }
+}
+
+fn draw_settings(ui: &mut Ui, seq: &mut StepSequence) {
+    ui.add(
+        egui::DragValue::new(&mut seq.swing)
+            .range(0.0..=MAX_SWING)
+            .speed(0.01)
+            .custom_formatter(|v, _| format!("sw {:.0}%", v * 100.0)),
+    )
+    .on_hover_text("Swing: delays every second step");
+
+    let rate_label = RATES
+        .iter()
+        .find(|(r, _)| *r == seq.rate)
+        .map_or("?", |(_, l)| l);
+    egui::ComboBox::from_id_salt(("seq_rate", &seq.param))
+        .width(44.0)
+        .selected_text(RichText::new(rate_label).size(SMALL_SIZE))
+        .show_ui(ui, |ui| {
+            for &(rate, label) in RATES {
+                ui.selectable_value(&mut seq.rate, rate, label);
+            }
+        });
+
+    let mut len = seq.steps.len();
+    egui::ComboBox::from_id_salt(("seq_len", &seq.param))
+        .width(36.0)
+        .selected_text(RichText::new(len.to_string()).size(SMALL_SIZE))
+        .show_ui(ui, |ui| {
+            for &n in STEP_COUNTS {
+                ui.selectable_value(&mut len, n, n.to_string());
+            }
+        });
+    if len != seq.steps.len() {
+        seq.set_length(len);
+    }
+}
+
+fn draw_grid(ui: &mut Ui, seq: &mut StepSequence, beats: f64, is_bool: bool) {
+    let tc = theme_colors(ui.ctx());
+    let (rect, resp) = ui.allocate_exact_size(
+        Vec2::new(ui.available_width(), GRID_HEIGHT),
+        Sense::click_and_drag(),
+    );
+    let n = seq.steps.len().max(1);
+    let cell_w = rect.width() / n as f32;
+
+    if let Some(pt) = resp.interact_pointer_pos() {
+        if (resp.clicked() || resp.dragged()) && rect.contains(pt) {
+            let i = (((pt.x - rect.left()) / cell_w) as usize).min(n - 1);
+            let v = ((rect.bottom() - pt.y) / rect.height()).clamp(0.0, 1.0);
+            if let Some(step) = seq.steps.get_mut(i) {
+                *step = if is_bool {
+                    if v >= 0.5 { 1.0 } else { 0.0 }
+                } else {
+                    v
+                };
+            }
+        }
+    }
+
+    let current = seq.enabled.then(|| seq.step_at(beats));
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, tc.widget_bg);
+    for (i, &v) in seq.steps.iter().enumerate() {
+        let x0 = rect.left() + i as f32 * cell_w;
+        let cell = egui::Rect::from_min_max(
+            egui::pos2(x0 + 1.0, rect.top()),
+            egui::pos2(x0 + cell_w - 1.0, rect.bottom()),
+        );
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(
+                cell.left(),
+                cell.bottom() - v.clamp(0.0, 1.0) * cell.height(),
+            ),
+            cell.max,
+        );
+        let color = if current == Some(i) {
+            tc.warning
+        } else {
+            tc.accent
+        };
+        painter.rect_filled(
+            bar,
+            1.0,
+            color.gamma_multiply(if i.is_multiple_of(4) { 1.0 } else { 0.7 }),
+        );
+        if current == Some(i) {
+            painter.rect_stroke(
+                cell,
+                1.0,
+                Stroke::new(1.0, tc.warning),
+                egui::StrokeKind::Inside,
+            );
+        }
+    }
+    resp.on_hover_text("Click or drag to set step heights");
+}
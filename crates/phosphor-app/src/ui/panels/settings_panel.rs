@@ -1,9 +1,22 @@
+use std::path::PathBuf;
+
 use egui::{RichText, Ui};
 
-use crate::settings::{BandScale, ParticleQuality};
+use crate::audio::silence::{SilenceAction, SilenceConfig};
+use crate::gpu::deterministic::{DeterministicParams, FPS_CHOICES};
+use crate::gpu::frame_guard::FrameGuardConfig;
+use crate::gpu::layer::MAX_LAYERS_CHOICES;
+use crate::installation::{InstallationConfig, format_minute};
+use crate::media::cache::CACHE_MB_CHOICES;
+use crate::preset::PresetStore;
+use crate::preset::attract::AttractConfig;
+use crate::settings::{BandScale, ParticleQuality, SettingsProfile, StartupConfig, StartupMode};
+use crate::ui::file_browser::FileDialogKind;
 use crate::ui::theme::ThemeMode;
+use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
 use crate::ui::widgets::rows;
+use crate::watchdog::{WatchdogAction, WatchdogConfig};
 
 pub fn draw_settings_panel(
     ui: &mut Ui,
@@ -12,7 +25,41 @@ pub fn draw_settings_panel(
     current_band_scale: BandScale,
     use_ffmpeg_webcam: bool,
     auto_reconnect: bool,
+    silence: &SilenceConfig,
+    attract: &AttractConfig,
+    startup: &StartupConfig,
+    installation: &InstallationConfig,
+    watchdog: &WatchdogConfig,
+    preset_store: &PresetStore,
+    media_cache_mb: u32,
+    file_dialog: FileDialogKind,
+    watch_folders: &[PathBuf],
+    max_layers: usize,
+    composite_hook: Option<&str>,
+    frame_guard: &FrameGuardConfig,
+    deterministic: DeterministicParams,
+    external_editor: &str,
+    profiles: &[SettingsProfile],
+    active_profile: Option<&str>,
 ) {
+    draw_profiles(ui, profiles, active_profile);
+    draw_rig(ui);
+    rows::custom_row(
+        ui,
+        "Tour",
+        Some("Walk through the main panels again, as on first launch"),
+        |ui| {
+            if ui
+                .button(RichText::new("Show tour").size(SMALL_SIZE))
+                .clicked()
+            {
+                ui.ctx()
+                    .data_mut(|d| d.insert_temp(egui::Id::new("start_tour"), true));
+            }
+        },
+    );
+    ui.add_space(4.0);
+
     rows::combo_row(
         ui,
         "theme_selector",
@@ -79,6 +126,87 @@ pub fn draw_settings_panel(
         },
     );
 
+    let cache_label = |mb: u32| match mb {
+        0 => "Off".to_string(),
+        mb if mb >= 1024 => format!("{} GB", mb / 1024),
+        mb => format!("{mb} MB"),
+    };
+    rows::combo_row(
+        ui,
+        "media_cache_selector",
+        "Media cache",
+        Some(
+            "RAM kept for decoded images and clips, so switching back to a preset (or to \
+             one preloaded from the Presets panel) skips decoding. Least recently used \
+             media is dropped first.",
+        ),
+        &cache_label(media_cache_mb),
+        |ui| {
+            for &mb in CACHE_MB_CHOICES {
+                let r = ui.selectable_label(
+                    mb == media_cache_mb,
+                    RichText::new(cache_label(mb)).size(SMALL_SIZE),
+                );
+                if r.clicked() && mb != media_cache_mb {
+                    ui.ctx().data_mut(|d| {
+                        d.insert_temp(egui::Id::new("set_media_cache_mb"), mb);
+                    });
+                }
+            }
+        },
+    );
+
+    rows::combo_row(
+        ui,
+        "file_dialog_selector",
+        "File dialog",
+        Some(
+            "How media is picked. Built-in is drawn by Fosfora itself, for desktops where \
+             the system dialog hangs or never opens.",
+        ),
+        file_dialog.display_name(),
+        |ui| {
+            for &kind in FileDialogKind::ALL {
+                let r = ui.selectable_label(
+                    kind == file_dialog,
+                    RichText::new(kind.display_name()).size(SMALL_SIZE),
+                );
+                if r.clicked() && kind != file_dialog {
+                    ui.ctx().data_mut(|d| {
+                        d.insert_temp(egui::Id::new("set_file_dialog"), kind);
+                    });
+                }
+            }
+        },
+    );
+    draw_watch_folders(ui, watch_folders);
+
+    rows::combo_row(
+        ui,
+        "max_layers_selector",
+        "Max layers",
+        Some(
+            "How many layers a stack may hold. Layers at zero opacity that nothing \
+             samples are skipped, so large stacks mostly cost VRAM.",
+        ),
+        &max_layers.to_string(),
+        |ui| {
+            for &n in MAX_LAYERS_CHOICES {
+                let r = ui.selectable_label(
+                    n == max_layers,
+                    RichText::new(n.to_string()).size(SMALL_SIZE),
+                );
+                if r.clicked() && n != max_layers {
+                    ui.ctx().data_mut(|d| {
+                        d.insert_temp(egui::Id::new("set_max_layers"), n);
+                    });
+                }
+            }
+        },
+    );
+    draw_composite_hook(ui, composite_hook);
+    draw_frame_guard(ui, frame_guard);
+
     // A9 (#1460): auto-reconnect the capture device after a confirmed loss.
     let mut reconnect = auto_reconnect;
     let resp = rows::checkbox_row(
@@ -96,6 +224,61 @@ pub fn draw_settings_panel(
             d.insert_temp(egui::Id::new("set_auto_reconnect"), reconnect);
         });
     }
+    draw_silence(ui, silence, preset_store);
+    draw_attract(ui, attract, preset_store);
+    draw_startup(ui, startup, preset_store);
+    draw_installation(ui, installation);
+    draw_watchdog(ui, watchdog, preset_store);
+
+    // Deterministic mode (#2174): fixed-step clock and a fixed seed.
+    let mut det = deterministic;
+    rows::checkbox_row(
+        ui,
+        &mut det.enabled,
+        "Deterministic",
+        Some(
+            "Step time by a fixed 1/fps and use a fixed seed, so a recording or A/B \
+             comparison repeats frame for frame. Turning it on restarts the current \
+             preset. Live audio and video playback still vary between runs.",
+        ),
+    );
+    if det.enabled {
+        rows::custom_row(ui, "Seed", None, |ui| {
+            ui.add(egui::DragValue::new(&mut det.seed).range(0..=65535));
+        });
+        rows::combo_row(
+            ui,
+            "deterministic_fps_selector",
+            "Frame rate",
+            Some("Simulated frames per second; match the recording's frame rate"),
+            &det.fps.to_string(),
+            |ui| {
+                for &fps in FPS_CHOICES {
+                    ui.selectable_value(
+                        &mut det.fps,
+                        fps,
+                        RichText::new(fps.to_string()).size(SMALL_SIZE),
+                    );
+                }
+            },
+        );
+        if ui
+            .button(RichText::new("Restart").size(SMALL_SIZE))
+            .on_hover_text("Back to frame 0 with the current preset reloaded")
+            .clicked()
+        {
+            ui.ctx().data_mut(|d| {
+                d.insert_temp(egui::Id::new("restart_render_clock"), true);
+            });
+        }
+    }
+    if det != deterministic {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("set_deterministic"), det);
+        });
+    }
+
+    draw_external_editor(ui, external_editor);
 
     // FFmpeg webcam backend (webcam feature only)
     #[cfg(feature = "webcam")]
@@ -119,3 +302,613 @@ pub fn draw_settings_panel(
     #[cfg(not(feature = "webcam"))]
     let _ = use_ffmpeg_webcam;
 }
+
+/// What to do when the input goes quiet for a while (#2213).
+fn draw_silence(ui: &mut Ui, silence: &SilenceConfig, preset_store: &PresetStore) {
+    let mut cfg = silence.clone();
+    rows::combo_row(
+        ui,
+        "silence_action_selector",
+        "On silence",
+        Some(
+            "After the input has been silent for a while, fade the output to black or \
+             switch to an idle preset. Undone as soon as sound returns.",
+        ),
+        cfg.action.display_name(),
+        |ui| {
+            for &action in SilenceAction::ALL {
+                ui.selectable_value(
+                    &mut cfg.action,
+                    action,
+                    RichText::new(action.display_name()).size(SMALL_SIZE),
+                );
+            }
+        },
+    );
+    if cfg.action != SilenceAction::Off {
+        rows::ParamRow::new("After secs")
+            .tooltip("Seconds of silence before it kicks in")
+            .show_slider(ui, &mut cfg.after_secs, 2.0..=120.0);
+    }
+    if cfg.action == SilenceAction::IdlePreset {
+        let selected = if cfg.idle_preset.is_empty() {
+            "Choose preset"
+        } else {
+            cfg.idle_preset.as_str()
+        };
+        let mut chosen = None;
+        rows::combo_row(
+            ui,
+            "silence_preset_selector",
+            "Idle preset",
+            None,
+            selected,
+            |ui| {
+                for (name, _) in &preset_store.presets {
+                    let r = ui.selectable_label(
+                        *name == cfg.idle_preset,
+                        RichText::new(name).size(SMALL_SIZE),
+                    );
+                    if r.clicked() {
+                        chosen = Some(name.clone());
+                    }
+                }
+            },
+        );
+        if let Some(name) = chosen {
+            cfg.idle_preset = name;
+        }
+    }
+    if cfg != *silence {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("set_silence"), cfg);
+        });
+    }
+}
+
+/// Attract mode (#2239): which presets to cycle when nobody's around.
+fn draw_attract(ui: &mut Ui, attract: &AttractConfig, preset_store: &PresetStore) {
+    let mut cfg = attract.clone();
+    ui.checkbox(
+        &mut cfg.enabled,
+        RichText::new("Attract mode").size(SMALL_SIZE),
+    )
+    .on_hover_text(
+        "With no sound and nobody at the controls, slowly cycle the presets \
+         ticked below. The stack comes back as it was on any sound or input.",
+    );
+    if cfg.enabled {
+        rows::ParamRow::new("After mins")
+            .tooltip("Minutes without sound or input before it starts")
+            .show_slider(ui, &mut cfg.after_mins, 1.0..=60.0);
+        rows::ParamRow::new("Hold mins")
+            .tooltip("Minutes each preset stays up")
+            .show_slider(ui, &mut cfg.hold_mins, 0.5..=30.0);
+        rows::ParamRow::new("Fade secs")
+            .tooltip("Length of the dissolve between presets")
+            .show_slider(ui, &mut cfg.fade_secs, 1.0..=60.0);
+        for (name, _) in &preset_store.presets {
+            let mut on = cfg.presets.contains(name);
+            if ui
+                .checkbox(&mut on, RichText::new(name).size(SMALL_SIZE))
+                .changed()
+            {
+                if on {
+                    cfg.presets.push(name.clone());
+                } else {
+                    cfg.presets.retain(|n| n != name);
+                }
+            }
+        }
+        if cfg.presets.is_empty() {
+            ui.label(
+                RichText::new("Tick at least one preset")
+                    .size(SMALL_SIZE)
+                    .weak(),
+            );
+        }
+    }
+    if cfg != *attract {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("set_attract"), cfg);
+        });
+    }
+}
+
+/// Compositor hook picker (#2252). The hooks on disk and the current error
+/// come from main.rs through the `composite_hooks` temp.
+fn draw_composite_hook(ui: &mut Ui, current: Option<&str>) {
+    let (hooks, error): (Vec<String>, Option<String>) = ui
+        .ctx()
+        .data(|d| d.get_temp(egui::Id::new("composite_hooks")))
+        .unwrap_or_default();
+    rows::combo_row(
+        ui,
+        "composite_hook_selector",
+        "Compositor",
+        Some(
+            "Built-in blend modes, or a WGSL hook from assets/shaders/composite that \
+             lays out each layer itself: split-screens, mirrors, mosaics. Edits reload live.",
+        ),
+        current.unwrap_or("Built-in blend modes"),
+        |ui| {
+            let choices = std::iter::once(None).chain(hooks.iter().map(|h| Some(h.as_str())));
+            for choice in choices {
+                let label = choice.unwrap_or("Built-in blend modes");
+                let r =
+                    ui.selectable_label(choice == current, RichText::new(label).size(SMALL_SIZE));
+                if r.clicked() && choice != current {
+                    let choice = choice.map(str::to_string);
+                    ui.ctx().data_mut(|d| {
+                        d.insert_temp(egui::Id::new("set_composite_hook"), choice);
+                    });
+                }
+            }
+        },
+    );
+    if let Some(error) = error {
+        ui.label(
+            RichText::new(error)
+                .size(SMALL_SIZE)
+                .color(theme_colors(ui.ctx()).error),
+        );
+    }
+}
+
+/// What the output shows at launch, and where (#2232). Monitor names come
+/// from main.rs through the `monitor_names` temp.
+fn draw_startup(ui: &mut Ui, startup: &StartupConfig, preset_store: &PresetStore) {
+    let mut cfg = startup.clone();
+    rows::combo_row(
+        ui,
+        "startup_mode_selector",
+        "On launch",
+        Some(
+            "What to show when Fosfora starts. Last session brings back the layers as \
+             they were, saved every few seconds so a power cut loses little.",
+        ),
+        cfg.mode.display_name(),
+        |ui| {
+            for &mode in StartupMode::ALL {
+                ui.selectable_value(
+                    &mut cfg.mode,
+                    mode,
+                    RichText::new(mode.display_name()).size(SMALL_SIZE),
+                );
+            }
+        },
+    );
+    if cfg.mode == StartupMode::Preset {
+        let selected = cfg
+            .preset
+            .and_then(|id| preset_store.presets.iter().find(|(_, p)| p.id == id))
+            .map_or("Choose preset", |(name, _)| name.as_str());
+        rows::combo_row(
+            ui,
+            "startup_preset_selector",
+            "Launch preset",
+            None,
+            selected,
+            |ui| {
+                for (name, p) in &preset_store.presets {
+                    ui.selectable_value(
+                        &mut cfg.preset,
+                        Some(p.id),
+                        RichText::new(name).size(SMALL_SIZE),
+                    );
+                }
+            },
+        );
+    }
+    rows::checkbox_row(
+        ui,
+        &mut cfg.fullscreen,
+        "Launch fullscreen",
+        Some("Go fullscreen at launch, on the monitor below"),
+    );
+    if cfg.fullscreen {
+        let monitors: Vec<String> = ui
+            .ctx()
+            .data(|d| d.get_temp(egui::Id::new("monitor_names")))
+            .unwrap_or_default();
+        let selected = cfg.monitor.as_deref().unwrap_or("Current");
+        rows::combo_row(
+            ui,
+            "startup_monitor_selector",
+            "Monitor",
+            Some(
+                "A monitor that isn't connected at launch, like a projector still \
+                 warming up, is picked up as soon as it appears",
+            ),
+            selected,
+            |ui| {
+                ui.selectable_value(
+                    &mut cfg.monitor,
+                    None,
+                    RichText::new("Current").size(SMALL_SIZE),
+                );
+                // Keep a saved monitor listed while it's unplugged
+                let saved = cfg.monitor.clone().filter(|m| !monitors.contains(m));
+                for name in monitors.iter().chain(saved.iter()) {
+                    ui.selectable_value(
+                        &mut cfg.monitor,
+                        Some(name.clone()),
+                        RichText::new(name).size(SMALL_SIZE),
+                    );
+                }
+            },
+        );
+    }
+    if cfg != *startup {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("set_startup"), cfg);
+        });
+    }
+}
+
+/// Installation mode and its opening hours (#2233).
+fn draw_installation(ui: &mut Ui, installation: &InstallationConfig) {
+    let mut cfg = installation.clone();
+    rows::checkbox_row(
+        ui,
+        &mut cfg.enabled,
+        "Installation",
+        Some(
+            "For unattended shows: hides the panels, cursor and shortcuts, quits without \
+             asking, and keeps retrying a lost audio device or webcam. Ctrl+Shift+D \
+             unlocks the panels until the next launch.",
+        ),
+    );
+    if cfg.enabled {
+        rows::checkbox_row(
+            ui,
+            &mut cfg.schedule,
+            "Opening hours",
+            Some("Fade to black outside these hours and render one frame a second"),
+        );
+        if cfg.schedule {
+            minute_row(ui, "Opens at", &mut cfg.open_at);
+            minute_row(ui, "Closes at", &mut cfg.close_at);
+        }
+    }
+    if cfg != *installation {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("set_installation"), cfg);
+        });
+    }
+}
+
+/// A local time of day, as minutes after midnight, shown and typed as `HH:MM`.
+fn minute_row(ui: &mut Ui, label: &str, minute: &mut u16) {
+    rows::custom_row(ui, label, None, |ui| {
+        ui.add(
+            egui::DragValue::new(minute)
+                .range(0..=24 * 60 - 1)
+                .speed(2.0)
+                .custom_formatter(|m, _| format_minute(m as u16))
+                .custom_parser(|text| {
+                    let (h, m) = text.trim().split_once(':')?;
+                    let (h, m) = (h.parse::<f64>().ok()?, m.parse::<f64>().ok()?);
+                    (h < 24.0 && m < 60.0).then_some(h * 60.0 + m)
+                }),
+        );
+    });
+}
+
+/// Watch folders (#2246): new media saved into one shows up under "+ Media".
+/// A path typed in the box is added with Enter or the Add button.
+fn draw_watch_folders(ui: &mut Ui, watch_folders: &[PathBuf]) {
+    let buffer = egui::Id::new("watch_folder_edit");
+    let mut path = ui
+        .ctx()
+        .data_mut(|d| d.get_temp::<String>(buffer))
+        .unwrap_or_default();
+    let mut folders = watch_folders.to_vec();
+    rows::custom_row(
+        ui,
+        "Watch folders",
+        Some(
+            "Media files saved into these folders, e.g. a share a designer drops renders \
+             into, go to the top of the recent list — right-click + Media to add one. \
+             Subfolders aren't watched.",
+        ),
+        |ui| {
+            ui.vertical(|ui| {
+                for folder in watch_folders {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .small_button("\u{2715}")
+                            .on_hover_text("Stop watching")
+                            .clicked()
+                        {
+                            folders.retain(|f| f != folder);
+                        }
+                        ui.label(
+                            RichText::new(folder.display().to_string())
+                                .size(SMALL_SIZE)
+                                .weak(),
+                        );
+                    });
+                }
+                ui.horizontal(|ui| {
+                    let resp = ui.add(
+                        egui::TextEdit::singleline(&mut path)
+                            .desired_width(ui.available_width() - 40.0)
+                            .hint_text("Folder path")
+                            .font(egui::FontId::proportional(SMALL_SIZE)),
+                    );
+                    let entered =
+                        resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    let add = ui.add_enabled(!path.trim().is_empty(), egui::Button::new("Add"));
+                    if (entered || add.clicked()) && !path.trim().is_empty() {
+                        let folder = PathBuf::from(path.trim());
+                        if !folders.contains(&folder) {
+                            folders.push(folder);
+                        }
+                        path.clear();
+                    }
+                });
+            });
+        },
+    );
+    ui.ctx().data_mut(|d| {
+        d.insert_temp(buffer, path);
+        if folders != watch_folders {
+            d.insert_temp(egui::Id::new("set_watch_folders"), folders);
+        }
+    });
+}
+
+/// Command for the effect panel's external editor button (#2191). Edited in
+/// a temp buffer and applied when the field loses focus.
+fn draw_external_editor(ui: &mut Ui, external_editor: &str) {
+    let buffer = egui::Id::new("external_editor_edit");
+    let mut command = ui
+        .ctx()
+        .data_mut(|d| d.get_temp::<String>(buffer))
+        .unwrap_or_else(|| external_editor.to_string());
+    let mut done = false;
+    rows::custom_row(
+        ui,
+        "Ext. editor",
+        Some(
+            "Command that opens a shader from the Effects panel's \u{2197} button, e.g. \
+             `code` or `subl {file}`. Empty uses your system's default app for .wgsl files. \
+             Saves there recompile live, and again when you switch back to Fosfora.",
+        ),
+        |ui| {
+            let resp = ui.add(
+                egui::TextEdit::singleline(&mut command)
+                    .desired_width(ui.available_width())
+                    .hint_text("System default")
+                    .font(egui::FontId::proportional(SMALL_SIZE)),
+            );
+            done = resp.lost_focus();
+        },
+    );
+    if done {
+        ui.ctx().data_mut(|d| {
+            d.remove_temp::<String>(buffer);
+            if command.trim() != external_editor {
+                d.insert_temp(
+                    egui::Id::new("set_external_editor"),
+                    command.trim().to_string(),
+                );
+            }
+        });
+    } else {
+        ui.ctx().data_mut(|d| d.insert_temp(buffer, command));
+    }
+}
+
+/// Rig files (#2203): the whole setup in one file, for a backup laptop.
+fn draw_rig(ui: &mut Ui) {
+    let with_presets_id = egui::Id::new("rig_with_presets");
+    let mut with_presets = ui
+        .ctx()
+        .data_mut(|d| d.get_temp::<bool>(with_presets_id))
+        .unwrap_or(true);
+    rows::custom_row(
+        ui,
+        "Rig",
+        Some(
+            "Settings, keymaps, MIDI/OSC/web/NDI/recording setup and global bindings in one \
+             file, to clone this setup onto another machine. Importing replaces them; the \
+             setup it replaces is kept as before-import.fosfora-rig in the config folder.",
+        ),
+        |ui| {
+            if ui
+                .button(RichText::new("Export...").size(SMALL_SIZE))
+                .on_hover_text("Save this setup as a rig file")
+                .clicked()
+            {
+                ui.ctx()
+                    .data_mut(|d| d.insert_temp(egui::Id::new("export_rig"), with_presets));
+            }
+            if ui
+                .button(RichText::new("Import...").size(SMALL_SIZE))
+                .on_hover_text("Replace this setup with a rig file")
+                .clicked()
+            {
+                ui.ctx()
+                    .data_mut(|d| d.insert_temp(egui::Id::new("import_rig"), true));
+            }
+            ui.checkbox(&mut with_presets, RichText::new("Presets").size(SMALL_SIZE))
+                .on_hover_text("Include your presets in exported rigs");
+        },
+    );
+    ui.ctx()
+        .data_mut(|d| d.insert_temp(with_presets_id, with_presets));
+}
+
+/// Venue profiles (#2177): switch, save the current setup, delete.
+fn draw_profiles(ui: &mut Ui, profiles: &[SettingsProfile], active_profile: Option<&str>) {
+    rows::combo_row(
+        ui,
+        "profile_selector",
+        "Profile",
+        Some(
+            "Audio input, MIDI port, OSC ports, output monitor, window size and theme, \
+             saved per venue. Launch with --profile <name> to start in one.",
+        ),
+        active_profile.unwrap_or("None"),
+        |ui| {
+            for p in profiles {
+                let r = ui.selectable_label(
+                    Some(p.name.as_str()) == active_profile,
+                    RichText::new(&p.name).size(SMALL_SIZE),
+                );
+                if r.clicked() {
+                    ui.ctx().data_mut(|d| {
+                        d.insert_temp(egui::Id::new("apply_profile"), p.name.clone());
+                        d.insert_temp(egui::Id::new("profile_save_name"), p.name.clone());
+                    });
+                }
+            }
+            if profiles.is_empty() {
+                ui.label(
+                    RichText::new("No profiles")
+                        .weak()
+                        .italics()
+                        .size(SMALL_SIZE),
+                );
+            }
+        },
+    );
+
+    let mut name = ui
+        .ctx()
+        .data_mut(|d| d.get_temp::<String>(egui::Id::new("profile_save_name")))
+        .unwrap_or_else(|| active_profile.unwrap_or_default().to_string());
+    rows::custom_row(ui, "", None, |ui| {
+        let buttons = if active_profile.is_some() { 2.0 } else { 1.0 };
+        let text_width = (ui.available_width() - 44.0 * buttons).max(1.0);
+        ui.add(
+            egui::TextEdit::singleline(&mut name)
+                .desired_width(text_width)
+                .hint_text("Name...")
+                .font(egui::FontId::proportional(SMALL_SIZE)),
+        );
+        if ui
+            .add_enabled(
+                !name.trim().is_empty(),
+                egui::Button::new(RichText::new("Save").size(SMALL_SIZE)),
+            )
+            .on_hover_text("Save the current setup under this name")
+            .clicked()
+        {
+            ui.ctx().data_mut(|d| {
+                d.insert_temp(egui::Id::new("save_profile"), name.trim().to_string());
+            });
+        }
+        if let Some(active) = active_profile {
+            if ui
+                .button(RichText::new("Delete").size(SMALL_SIZE))
+                .on_hover_text(format!("Delete profile '{active}'"))
+                .clicked()
+            {
+                ui.ctx().data_mut(|d| {
+                    d.insert_temp(egui::Id::new("delete_profile"), active.to_string());
+                });
+                name.clear();
+            }
+        }
+    });
+    ui.ctx()
+        .data_mut(|d| d.insert_temp(egui::Id::new("profile_save_name"), name));
+}
+
+/// Frame-time guard (#2243): suspend a layer that keeps running over budget.
+fn draw_frame_guard(ui: &mut Ui, frame_guard: &FrameGuardConfig) {
+    let mut cfg = frame_guard.clone();
+    rows::checkbox_row(
+        ui,
+        &mut cfg.enabled,
+        "Frame guard",
+        Some(
+            "Suspend a layer whose GPU time stays over the budget, so one runaway \
+             shader can't stall the show. Needs a GPU with timestamp queries.",
+        ),
+    );
+    if cfg.enabled {
+        rows::ParamRow::new("Budget ms")
+            .tooltip("GPU milliseconds one layer may take each frame")
+            .show_slider(ui, &mut cfg.budget_ms, 5.0..=100.0);
+        rows::ParamRow::new("Frames over")
+            .tooltip("Frames in a row over the budget before the layer is suspended")
+            .show_slider(ui, &mut cfg.frames, 1..=120);
+    }
+    if cfg != *frame_guard {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("set_frame_guard"), cfg);
+        });
+    }
+}
+
+/// Controller watchdog (#2240): the fallback when heartbeats stop.
+fn draw_watchdog(ui: &mut Ui, watchdog: &WatchdogConfig, preset_store: &PresetStore) {
+    let mut cfg = watchdog.clone();
+    ui.checkbox(
+        &mut cfg.enabled,
+        RichText::new("Controller watchdog").size(SMALL_SIZE),
+    )
+    .on_hover_text(
+        "Once a controller has sent /phosphor/heartbeat over OSC or a heartbeat \
+         message over the web remote, fall back when it goes quiet for too long.",
+    );
+    if cfg.enabled {
+        rows::ParamRow::new("Timeout secs")
+            .tooltip("Seconds without a heartbeat before the fallback")
+            .show_slider(ui, &mut cfg.timeout_secs, 1.0..=60.0);
+        rows::combo_row(
+            ui,
+            "watchdog_action_selector",
+            "Fallback",
+            None,
+            cfg.action.display_name(),
+            |ui| {
+                for &action in WatchdogAction::ALL {
+                    ui.selectable_value(
+                        &mut cfg.action,
+                        action,
+                        RichText::new(action.display_name()).size(SMALL_SIZE),
+                    );
+                }
+            },
+        );
+        if cfg.action == WatchdogAction::SafePreset {
+            let selected = if cfg.safe_preset.is_empty() {
+                "Choose preset"
+            } else {
+                cfg.safe_preset.as_str()
+            };
+            let mut chosen = None;
+            rows::combo_row(
+                ui,
+                "watchdog_preset_selector",
+                "Safe preset",
+                None,
+                selected,
+                |ui| {
+                    for (name, _) in &preset_store.presets {
+                        let r = ui.selectable_label(
+                            *name == cfg.safe_preset,
+                            RichText::new(name).size(SMALL_SIZE),
+                        );
+                        if r.clicked() {
+                            chosen = Some(name.clone());
+                        }
+                    }
+                },
+            );
+            if let Some(name) = chosen {
+                cfg.safe_preset = name;
+            }
+        }
+    }
+    if cfg != *watchdog {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("set_watchdog"), cfg);
+        });
+    }
+}
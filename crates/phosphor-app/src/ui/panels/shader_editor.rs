@@ -1,4 +1,5 @@
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 use egui::{
     Color32, CornerRadius, Frame, Id, Key, Margin, Modifiers, Order, Rect, RichText, Stroke,
@@ -6,8 +7,12 @@ use egui::{
 };
 use egui_code_editor::{ColorTheme, Syntax, Token, TokenType};
 
+use crate::effect::history::{self, DiffLine, Snapshot};
+use crate::shader::lint::{Diagnostic, Severity};
+use crate::ui::panels::code_edit::{self, FindOptions};
+use crate::ui::panels::editor_keymap::{EditorCommand, KeyInput, KeyResult, Keymap, KeymapState};
 use crate::ui::theme::ThemeMode;
-use crate::ui::theme::colors::theme_colors;
+use crate::ui::theme::colors::{ThemeColors, theme_colors};
 
 /// Which file type is currently active in the editor.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -16,6 +21,23 @@ pub enum EditorFileType {
     Pfx,
 }
 
+/// Find/replace bar state (#2190).
+#[derive(Debug, Clone, Default)]
+pub struct FindBar {
+    pub open: bool,
+    /// Show the replace row too.
+    pub replace: bool,
+    pub query: String,
+    pub replacement: String,
+    pub opts: FindOptions,
+    /// Match last jumped to, as a char range.
+    pub current: Option<Range<usize>>,
+    /// A bad regex, or how many a Replace All changed.
+    pub note: Option<String>,
+    /// Focus the query (or go-to-line) field on its next frame.
+    pub focus: bool,
+}
+
 /// State for the shader editor overlay.
 pub struct ShaderEditorState {
     pub open: bool,
@@ -40,6 +62,41 @@ pub struct ShaderEditorState {
     pub paired_disk_content: String,
     /// File name of the paired file.
     pub paired_file_name: String,
+    /// Write the last compiling shader back when an editor save breaks it
+    /// (#2188). Mirrors the setting of the same name.
+    pub auto_rollback: bool,
+    /// WGSL path and content last known to compile.
+    pub last_good: Option<(PathBuf, String)>,
+    /// WGSL content saved from the editor and not yet compiled.
+    pub pending_compile: Option<String>,
+    /// Shown in the error bar after a rollback, until the next save.
+    pub rollback_note: Option<String>,
+    /// Snapshots of the open shader, newest first (#2189). Filled by the app.
+    pub history: Vec<Snapshot>,
+    /// Snapshot being diffed against the buffer, with its content. Shown in
+    /// place of the code while set.
+    pub history_view: Option<(Snapshot, String)>,
+    /// The diff last drawn for `history_view`, and what it was drawn from.
+    /// `line_diff` is quadratic, so it's redone only when that changes.
+    history_diff: Option<(DiffKey, egui::text::LayoutJob)>,
+    pub find: FindBar,
+    /// Go-to-line field text, shown while `Some`.
+    pub goto_line: Option<String>,
+    /// Key bindings in the code field (#2190). Mirrors the setting of the
+    /// same name.
+    pub keymap: Keymap,
+    pub keys: KeymapState,
+    /// Last caret position in the code field, as a char index.
+    pub cursor: usize,
+    /// Selection to put in the code field on its next frame, scrolled into
+    /// view.
+    pub select: Option<Range<usize>>,
+    /// Give the code field keyboard focus on its next frame.
+    pub focus_code: bool,
+    /// Warnings and performance hints for the saved WGSL (#2192).
+    pub diagnostics: Vec<Diagnostic>,
+    /// Diagnostics list expanded under the code.
+    pub show_diagnostics: bool,
 }
 
 impl Default for ShaderEditorState {
@@ -62,6 +119,22 @@ impl Default for ShaderEditorState {
             paired_content: String::new(),
             paired_disk_content: String::new(),
             paired_file_name: String::new(),
+            auto_rollback: true,
+            last_good: None,
+            pending_compile: None,
+            rollback_note: None,
+            history: Vec::new(),
+            history_view: None,
+            history_diff: None,
+            find: FindBar::default(),
+            goto_line: None,
+            keymap: Keymap::default(),
+            keys: KeymapState::default(),
+            cursor: 0,
+            select: None,
+            focus_code: false,
+            diagnostics: Vec::new(),
+            show_diagnostics: true,
         }
     }
 }
@@ -79,6 +152,13 @@ impl ShaderEditorState {
             .map(|f| f.to_string_lossy().to_string())
             .unwrap_or_default();
         self.effect_name = effect_name.to_string();
+        self.last_good = Some((path.clone(), content.clone()));
+        self.pending_compile = None;
+        self.rollback_note = None;
+        self.history.clear();
+        self.history_view = None;
+        self.reset_navigation();
+        self.diagnostics.clear();
         self.file_path = Some(path);
         self.code = content.clone();
         self.disk_content = content;
@@ -102,6 +182,7 @@ impl ShaderEditorState {
         if target == self.file_type {
             return;
         }
+        self.history_view = None;
         // Swap current ↔ paired
         std::mem::swap(&mut self.code, &mut self.paired_content);
         std::mem::swap(&mut self.disk_content, &mut self.paired_disk_content);
@@ -121,6 +202,196 @@ impl ShaderEditorState {
         self.paired_disk_content.clear();
         self.paired_file_name.clear();
         self.file_type = EditorFileType::Wgsl;
+        self.last_good = None;
+        self.pending_compile = None;
+        self.rollback_note = None;
+        self.history.clear();
+        self.history_view = None;
+        self.history_diff = None;
+        self.reset_navigation();
+        self.diagnostics.clear();
+    }
+
+    /// Drop find, go-to-line and keymap state tied to the old buffer. The
+    /// find query itself is kept for the next file.
+    fn reset_navigation(&mut self) {
+        self.find.open = false;
+        self.find.current = None;
+        self.find.note = None;
+        self.goto_line = None;
+        self.keys.reset();
+        self.cursor = 0;
+        self.select = None;
+    }
+
+    /// Discard unsaved edits in both tabs.
+    pub fn revert(&mut self) {
+        self.code = self.disk_content.clone();
+        self.paired_content = self.paired_disk_content.clone();
+    }
+
+    /// The WGSL buffer and its on-disk copy, whichever tab holds them.
+    fn wgsl_buffers(&mut self) -> (&mut String, &mut String) {
+        match self.file_type {
+            EditorFileType::Wgsl => (&mut self.code, &mut self.disk_content),
+            EditorFileType::Pfx => (&mut self.paired_content, &mut self.paired_disk_content),
+        }
+    }
+
+    /// Path of the WGSL file, whichever tab holds it.
+    pub fn wgsl_path(&self) -> Option<&Path> {
+        match self.file_type {
+            EditorFileType::Wgsl => self.file_path.as_deref(),
+            EditorFileType::Pfx => self.paired_path.as_deref(),
+        }
+    }
+
+    /// The WGSL buffer and its file name, whichever tab holds them.
+    pub fn wgsl_code(&self) -> (&str, &str) {
+        match self.file_type {
+            EditorFileType::Wgsl => (&self.code, &self.file_name),
+            EditorFileType::Pfx => (&self.paired_content, &self.paired_file_name),
+        }
+    }
+
+    pub fn wgsl_code_mut(&mut self) -> &mut String {
+        match self.file_type {
+            EditorFileType::Wgsl => &mut self.code,
+            EditorFileType::Pfx => &mut self.paired_content,
+        }
+    }
+
+    /// Open a snapshot's diff against the WGSL buffer.
+    pub fn view_snapshot(&mut self, snapshot: &Snapshot) {
+        match snapshot.read() {
+            Ok(content) => self.history_view = Some((snapshot.clone(), content)),
+            Err(e) => self.compile_error = Some(format!("Snapshot read error: {e}")),
+        }
+    }
+
+    /// Put the viewed snapshot into the WGSL buffer, unsaved, so it goes
+    /// live on the next save.
+    pub fn restore_snapshot(&mut self) {
+        if let Some((_, content)) = self.history_view.take() {
+            *self.wgsl_buffers().0 = content;
+        }
+    }
+
+    /// Open the find bar, seeded with `seed` when it's a one-line selection.
+    pub fn open_find(&mut self, replace: bool, seed: Option<String>) {
+        self.find.open = true;
+        self.find.replace |= replace;
+        self.find.focus = true;
+        self.goto_line = None;
+        if let Some(seed) = seed.filter(|s| !s.is_empty() && !s.contains('\n')) {
+            self.find.query = if self.find.opts.regex {
+                regex::escape(&seed)
+            } else {
+                seed
+            };
+        }
+    }
+
+    /// Select the first match at or after char index `from` (before it,
+    /// going back).
+    pub fn find_step(&mut self, from: usize, forward: bool) {
+        match code_edit::find_all(&self.code, &self.find.query, self.find.opts) {
+            Ok(matches) => {
+                self.find.current = code_edit::next_match(&matches, from, forward);
+                self.find.note = None;
+                self.select.clone_from(&self.find.current);
+            }
+            Err(e) => {
+                self.find.current = None;
+                self.find.note = Some(e);
+            }
+        }
+    }
+
+    /// Step past the current match, or search from char index `cursor`
+    /// when the caret has left it.
+    pub fn find_next(&mut self, cursor: usize, forward: bool) {
+        let from = match &self.find.current {
+            Some(r) if (r.start..=r.end).contains(&cursor) => {
+                if forward {
+                    r.end
+                } else {
+                    r.start
+                }
+            }
+            _ => cursor,
+        };
+        self.find_step(from, forward);
+    }
+
+    /// Replace the current match and move on to the next.
+    pub fn replace_current(&mut self) {
+        let f = &self.find;
+        let replaced = match f.current.clone() {
+            Some(at) => code_edit::replace_at(&mut self.code, at, &f.query, &f.replacement, f.opts),
+            None => Ok(None),
+        };
+        match replaced {
+            Ok(Some(r)) => self.find_step(r.end, true),
+            Ok(None) => self.find_next(self.cursor, true),
+            Err(e) => self.find.note = Some(e),
+        }
+    }
+
+    pub fn replace_every(&mut self) {
+        let f = &self.find;
+        match code_edit::replace_all(&mut self.code, &f.query, &f.replacement, f.opts) {
+            Ok(n) => {
+                self.find.current = None;
+                self.find.note = Some(format!("{n} replaced"));
+            }
+            Err(e) => self.find.note = Some(e),
+        }
+    }
+
+    /// Put the caret at the start of 1-based `line` and close the go-to bar.
+    pub fn goto(&mut self, line: usize) {
+        let pos = code_edit::line_start(&self.code, line);
+        self.select = Some(pos..pos);
+        self.goto_line = None;
+        self.focus_code = true;
+    }
+
+    /// Record that the WGSL tab was just written to disk, so the next
+    /// compile result decides whether it becomes the last good version.
+    pub fn wgsl_saved(&mut self) {
+        let saved = self.wgsl_buffers().1.clone();
+        self.rollback_note = None;
+        // Unchanged source won't recompile, so there is no result to wait for.
+        if self
+            .last_good
+            .as_ref()
+            .is_some_and(|(_, good)| *good == saved)
+        {
+            return;
+        }
+        self.pending_compile = Some(saved);
+    }
+
+    /// Handle the compile result for a save made here. On success the saved
+    /// source becomes the last good one. On failure with rollback on, the
+    /// last good (path, source) is returned for the caller to write back;
+    /// the broken code stays in the buffer, now unsaved, so it can be fixed.
+    pub fn compile_finished(&mut self, error: Option<&str>) -> Option<(PathBuf, String)> {
+        let saved = self.pending_compile.take()?;
+        let Some(error) = error else {
+            if let Some((_, good)) = &mut self.last_good {
+                *good = saved;
+            }
+            return None;
+        };
+        if !self.auto_rollback {
+            return None;
+        }
+        let (path, good) = self.last_good.clone()?;
+        *self.wgsl_buffers().1 = good.clone();
+        self.rollback_note = Some(format!("Rolled back to the last working shader: {error}"));
+        Some((path, good))
     }
 
     /// Check if the paired file has unsaved changes.
@@ -277,13 +548,23 @@ pub fn draw_shader_editor(
     let color_theme = editor_color_theme(theme);
     let fontsize = 13.0f32;
 
-    // Header bar height + toolbar + separators
-    let header_height = 60.0;
-    let error_height = if state.compile_error.is_some() {
+    // Header bar height + toolbar + separators, plus find/go-to rows
+    let bar_rows = usize::from(state.find.open)
+        + usize::from(state.find.open && state.find.replace)
+        + usize::from(state.goto_line.is_some());
+    let header_height = 60.0 + bar_rows as f32 * 26.0;
+    let mut error_height = if state.compile_error.is_some() || state.rollback_note.is_some() {
         28.0
     } else {
         0.0
     };
+    let show_diagnostics = state.file_type == EditorFileType::Wgsl && !state.diagnostics.is_empty();
+    if show_diagnostics {
+        error_height += 28.0;
+        if state.show_diagnostics {
+            error_height += state.diagnostics.len().min(DIAGNOSTIC_ROWS) as f32 * 18.0;
+        }
+    }
 
     // Minimized shows ~5 lines, normal uses 80% of screen height
     let code_height = if state.minimized {
@@ -503,7 +784,19 @@ pub fn draw_shader_editor(
                         state.code = state.disk_content.clone();
                     }
 
-                    // Right-aligned opacity slider
+                    if state.file_type == EditorFileType::Wgsl && !state.history.is_empty() {
+                        draw_history_combo(ui, state);
+                    }
+                    if let Some(status) = keymap_status(state) {
+                        ui.label(
+                            RichText::new(status)
+                                .size(12.0)
+                                .monospace()
+                                .color(tc.accent),
+                        );
+                    }
+
+                    // Right-aligned opacity slider and rollback toggle
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         ui.add_space(10.0);
                         ui.spacing_mut().slider_width = 80.0;
@@ -514,8 +807,47 @@ pub fn draw_shader_editor(
                             "Background opacity: {:.0}%",
                             state.editor_opacity * 100.0
                         ));
+                        if ui
+                            .checkbox(
+                                &mut state.auto_rollback,
+                                RichText::new("Rollback")
+                                    .size(12.0)
+                                    .color(tc.text_secondary),
+                            )
+                            .on_hover_text(
+                                "If a save fails to compile, put the last working shader back \
+                                 on disk and keep your edits unsaved",
+                            )
+                            .changed()
+                        {
+                            ctx.data_mut(|d| {
+                                d.insert_temp(Id::new("shader_auto_rollback"), state.auto_rollback);
+                            });
+                        }
+                        let mut keymap = state.keymap;
+                        egui::ComboBox::from_id_salt("shader_keymap")
+                            .width(80.0)
+                            .selected_text(RichText::new(keymap.display_name()).size(12.0))
+                            .show_ui(ui, |ui| {
+                                for &k in Keymap::ALL {
+                                    ui.selectable_value(&mut keymap, k, k.display_name());
+                                }
+                            })
+                            .response
+                            .on_hover_text("Key bindings in the code");
+                        if keymap != state.keymap {
+                            state.keymap = keymap;
+                            state.keys.reset();
+                            ctx.data_mut(|d| {
+                                d.insert_temp(Id::new("shader_editor_keymap"), keymap);
+                            });
+                        }
                     });
                 });
+                if state.find.open || state.goto_line.is_some() {
+                    ui.separator();
+                    draw_find_bar(ui, state);
+                }
                 ui.add_space(2.0);
             });
 
@@ -560,96 +892,152 @@ pub fn draw_shader_editor(
                 } else {
                     format!("full_{file_salt}")
                 };
-                egui::ScrollArea::vertical()
-                    .id_salt(format!("shader_scroll_{mode_salt}"))
-                    .min_scrolled_height(code_height)
-                    .max_height(code_height)
-                    .show(ui, |ui| {
-                        ui.horizontal_top(|ui| {
-                            // Line numbers
-                            let text = &state.code;
-                            let line_count = if text.ends_with('\n') || text.is_empty() {
-                                text.lines().count() + 1
-                            } else {
-                                text.lines().count()
-                            }
-                            .max(5);
-                            let max_digits = line_count.to_string().len();
-                            let mut nums = (1..=line_count)
-                                .map(|i| {
-                                    let label = i.to_string();
-                                    format!(
-                                        "{}{label}",
-                                        " ".repeat(max_digits.saturating_sub(label.len()))
-                                    )
-                                })
-                                .collect::<Vec<_>>()
-                                .join("\n");
-
-                            #[allow(clippy::cast_precision_loss)]
-                            let num_width = max_digits as f32 * fontsize * 0.5;
-
-                            let mut num_layouter =
-                                |ui: &egui::Ui, buf: &dyn TextBuffer, _wrap: f32| {
-                                    let job = egui::text::LayoutJob::single_section(
-                                        buf.as_str().to_string(),
-                                        egui::text::TextFormat::simple(
-                                            egui::FontId::monospace(fontsize),
-                                            num_color,
-                                        ),
-                                    );
-                                    ui.fonts_mut(|f| f.layout_job(job))
-                                };
-
-                            ui.add(
-                                egui::TextEdit::multiline(&mut nums)
-                                    .id_source(format!("shader_numlines_{mode_salt}"))
-                                    .interactive(false)
-                                    .frame(false)
-                                    .desired_rows(5)
-                                    .desired_width(num_width)
-                                    .layouter(&mut num_layouter),
-                            );
-
-                            // Code editor with syntax highlighting
-                            egui::ScrollArea::horizontal()
-                                .id_salt(format!("shader_hscroll_{mode_salt}"))
-                                .show(ui, |ui| {
-                                    let mut code_layouter =
-                                        |ui: &egui::Ui, buf: &dyn TextBuffer, _wrap: f32| {
-                                            let mut token = Token::default();
-                                            let tokens = token.tokens(&syntax, buf.as_str());
-                                            let mut job = egui::text::LayoutJob::default();
-                                            for t in tokens {
-                                                if !t.buffer().is_empty() {
-                                                    job.append(
-                                                        t.buffer(),
-                                                        0.0,
-                                                        egui_code_editor::format_token(
-                                                            &color_theme,
-                                                            fontsize,
-                                                            t.ty(),
-                                                        ),
+                let action = if let Some((snap, old)) = &state.history_view {
+                    draw_snapshot_diff(
+                        ui,
+                        snap,
+                        old,
+                        &state.code,
+                        &mut state.history_diff,
+                        code_height,
+                        fontsize,
+                    )
+                } else {
+                    egui::ScrollArea::vertical()
+                        .id_salt(format!("shader_scroll_{mode_salt}"))
+                        .min_scrolled_height(code_height)
+                        .max_height(code_height)
+                        .show(ui, |ui| {
+                            ui.horizontal_top(|ui| {
+                                // Line numbers
+                                let text = &state.code;
+                                let line_count = if text.ends_with('\n') || text.is_empty() {
+                                    text.lines().count() + 1
+                                } else {
+                                    text.lines().count()
+                                }
+                                .max(5);
+                                let max_digits = line_count.to_string().len();
+                                let mut nums = (1..=line_count)
+                                    .map(|i| {
+                                        let label = i.to_string();
+                                        format!(
+                                            "{}{label}",
+                                            " ".repeat(max_digits.saturating_sub(label.len()))
+                                        )
+                                    })
+                                    .collect::<Vec<_>>()
+                                    .join("\n");
+
+                                #[allow(clippy::cast_precision_loss)]
+                                let num_width = max_digits as f32 * fontsize * 0.5;
+
+                                let mut num_layouter =
+                                    |ui: &egui::Ui, buf: &dyn TextBuffer, _wrap: f32| {
+                                        let job = egui::text::LayoutJob::single_section(
+                                            buf.as_str().to_string(),
+                                            egui::text::TextFormat::simple(
+                                                egui::FontId::monospace(fontsize),
+                                                num_color,
+                                            ),
+                                        );
+                                        ui.fonts_mut(|f| f.layout_job(job))
+                                    };
+
+                                ui.add(
+                                    egui::TextEdit::multiline(&mut nums)
+                                        .id_source(format!("shader_numlines_{mode_salt}"))
+                                        .interactive(false)
+                                        .frame(false)
+                                        .desired_rows(5)
+                                        .desired_width(num_width)
+                                        .layouter(&mut num_layouter),
+                                );
+
+                                // Code editor with syntax highlighting
+                                egui::ScrollArea::horizontal()
+                                    .id_salt(format!("shader_hscroll_{mode_salt}"))
+                                    .show(ui, |ui| {
+                                        let code_id = ui
+                                            .make_persistent_id(format!("shader_code_{mode_salt}"));
+                                        let scroll_to = handle_code_keys(ui, state, code_id);
+                                        let highlight = state.find.open.then(|| {
+                                            (
+                                                state.find.query.clone(),
+                                                state.find.opts,
+                                                state.find.current.clone(),
+                                            )
+                                        });
+                                        let mut code_layouter =
+                                            |ui: &egui::Ui, buf: &dyn TextBuffer, _wrap: f32| {
+                                                let mut token = Token::default();
+                                                let tokens = token.tokens(&syntax, buf.as_str());
+                                                let mut job = egui::text::LayoutJob::default();
+                                                for t in tokens {
+                                                    if !t.buffer().is_empty() {
+                                                        job.append(
+                                                            t.buffer(),
+                                                            0.0,
+                                                            egui_code_editor::format_token(
+                                                                &color_theme,
+                                                                fontsize,
+                                                                t.ty(),
+                                                            ),
+                                                        );
+                                                    }
+                                                }
+                                                if let Some((query, opts, current)) = &highlight {
+                                                    highlight_matches(
+                                                        &mut job,
+                                                        query,
+                                                        *opts,
+                                                        current.as_ref(),
+                                                        &tc,
                                                     );
                                                 }
-                                            }
-                                            ui.fonts_mut(|f| f.layout_job(job))
-                                        };
-
-                                    egui::TextEdit::multiline(&mut state.code)
-                                        .id_source(format!("shader_code_{mode_salt}"))
-                                        .lock_focus(true)
-                                        .desired_rows(60)
-                                        .frame(true)
-                                        .desired_width(f32::INFINITY)
-                                        .layouter(&mut code_layouter)
-                                        .show(ui);
-                                });
+                                                ui.fonts_mut(|f| f.layout_job(job))
+                                            };
+
+                                        let output = egui::TextEdit::multiline(&mut state.code)
+                                            .id(code_id)
+                                            .lock_focus(true)
+                                            .desired_rows(60)
+                                            .frame(true)
+                                            .desired_width(f32::INFINITY)
+                                            .layouter(&mut code_layouter)
+                                            .show(ui);
+                                        if let Some(range) = output.cursor_range {
+                                            state.cursor = range.primary.index;
+                                        }
+                                        if let Some(pos) = scroll_to {
+                                            let rect = output
+                                                .galley
+                                                .pos_from_cursor(egui::text::CCursor::new(pos))
+                                                .translate(output.galley_pos.to_vec2());
+                                            ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                                        }
+                                    });
+                            });
                         });
-                    });
+                    None
+                };
+                match action {
+                    Some(SnapshotAction::Restore) => state.restore_snapshot(),
+                    Some(SnapshotAction::Close) => state.history_view = None,
+                    None => {}
+                }
 
-                // Compile error bar
-                if let Some(ref error) = state.compile_error {
+                if show_diagnostics {
+                    ui.separator();
+                    draw_diagnostics(ui, state);
+                }
+
+                // Compile error bar; a rollback note explains itself first
+                if let Some(error) = state
+                    .rollback_note
+                    .as_ref()
+                    .or(state.compile_error.as_ref())
+                {
                     ui.separator();
                     ui.horizontal(|ui| {
                         ui.add_space(10.0);
@@ -666,21 +1054,644 @@ pub fn draw_shader_editor(
     // Handle Ctrl+S
     let ctrl_s =
         ctx.input(|i| i.key_pressed(Key::S) && i.modifiers.matches_exact(Modifiers::COMMAND));
-    if ctrl_s && (state.is_dirty() || state.paired_is_dirty()) {
+    // Emacs saves with C-x C-s and searches with C-s.
+    if ctrl_s && state.keymap != Keymap::Emacs && (state.is_dirty() || state.paired_is_dirty()) {
         ctx.data_mut(|d| {
             d.insert_temp(Id::new("shader_editor_save"), true);
         });
     }
 
-    // Handle Esc to close
+    // Esc closes the find bar first, then the editor; Vim keeps Esc for
+    // itself and closes with :q
     let esc = ctx.input(|i| i.key_pressed(Key::Escape));
     if esc {
-        state.close();
+        if state.find.open || state.goto_line.is_some() {
+            state.find.open = false;
+            state.goto_line = None;
+            state.focus_code = true;
+        } else if state.keymap != Keymap::Vim {
+            state.close();
+        }
     }
 
     true
 }
 
+/// Diagnostics rows shown before the list scrolls.
+const DIAGNOSTIC_ROWS: usize = 6;
+
+/// Warnings and performance hints under the code (#2192). Click one to jump
+/// to its line.
+fn draw_diagnostics(ui: &mut egui::Ui, state: &mut ShaderEditorState) {
+    let tc = theme_colors(ui.ctx());
+    let warnings = state
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Warning)
+        .count();
+    let hints = state.diagnostics.len() - warnings;
+    let count = |n: usize, what: &str| match n {
+        0 => None,
+        1 => Some(format!("1 {what}")),
+        n => Some(format!("{n} {what}s")),
+    };
+    let summary: Vec<String> = [count(warnings, "warning"), count(hints, "hint")]
+        .into_iter()
+        .flatten()
+        .collect();
+    let arrow = if state.show_diagnostics {
+        "\u{25be}"
+    } else {
+        "\u{25b8}"
+    };
+    ui.horizontal(|ui| {
+        ui.add_space(10.0);
+        let label = RichText::new(format!("{arrow} {}", summary.join(", ")))
+            .size(11.0)
+            .color(if warnings > 0 {
+                tc.warning
+            } else {
+                tc.text_secondary
+            });
+        if ui
+            .add(egui::Button::new(label).frame(false))
+            .on_hover_text("Show or hide warnings and performance hints")
+            .clicked()
+        {
+            state.show_diagnostics = !state.show_diagnostics;
+        }
+    });
+    if !state.show_diagnostics {
+        return;
+    }
+
+    let mut jump = None;
+    egui::ScrollArea::vertical()
+        .id_salt("shader_diagnostics")
+        .max_height(DIAGNOSTIC_ROWS as f32 * 18.0)
+        .show(ui, |ui| {
+            for d in &state.diagnostics {
+                let (kind, color) = match d.severity {
+                    Severity::Warning => ("warning", tc.warning),
+                    Severity::Hint => ("hint", tc.text_secondary),
+                };
+                ui.horizontal(|ui| {
+                    ui.add_space(10.0);
+                    let text = RichText::new(format!("Line {} {kind}: {}", d.line, d.message))
+                        .size(11.0)
+                        .color(color);
+                    if ui
+                        .add(
+                            egui::Label::new(text)
+                                .sense(egui::Sense::click())
+                                .truncate(),
+                        )
+                        .on_hover_text(&d.message)
+                        .clicked()
+                    {
+                        jump = Some(d.line);
+                    }
+                });
+            }
+        });
+    if let Some(line) = jump {
+        state.goto(line);
+    }
+}
+
+/// Find/replace and go-to-line rows under the toolbar (#2190).
+fn draw_find_bar(ui: &mut egui::Ui, state: &mut ShaderEditorState) {
+    let tc = theme_colors(ui.ctx());
+    let focus = std::mem::take(&mut state.find.focus);
+
+    if let Some(line) = &mut state.goto_line {
+        let mut go = None;
+        ui.horizontal(|ui| {
+            ui.add_space(10.0);
+            ui.label(
+                RichText::new("Go to line")
+                    .size(12.0)
+                    .color(tc.text_secondary),
+            );
+            let resp = ui.add(
+                egui::TextEdit::singleline(line)
+                    .id_salt("shader_goto_line")
+                    .desired_width(60.0),
+            );
+            if focus {
+                resp.request_focus();
+            }
+            if resp.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                go = Some(line.trim().parse::<usize>().ok());
+            }
+        });
+        match go {
+            Some(Some(n)) => state.goto(n),
+            Some(None) => state.goto_line = None,
+            None => {}
+        }
+    }
+    if !state.find.open {
+        return;
+    }
+
+    let matches = code_edit::find_all(&state.code, &state.find.query, state.find.opts);
+    let emacs = state.keymap == Keymap::Emacs;
+    let (mut requery, mut step, mut close) = (false, None, false);
+    ui.horizontal(|ui| {
+        ui.add_space(10.0);
+        let resp = ui.add(
+            egui::TextEdit::singleline(&mut state.find.query)
+                .id_salt("shader_find")
+                .hint_text("Find")
+                .desired_width(200.0),
+        );
+        if focus {
+            resp.request_focus();
+        }
+        requery |= resp.changed();
+        if resp.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+            step = Some(!ui.input(|i| i.modifiers.shift));
+            resp.request_focus();
+        }
+        if emacs && resp.has_focus() {
+            if ui.input_mut(|i| i.consume_key(Modifiers::CTRL, Key::S)) {
+                step = Some(true);
+            }
+            if ui.input_mut(|i| i.consume_key(Modifiers::CTRL, Key::R)) {
+                step = Some(false);
+            }
+        }
+        requery |= ui
+            .toggle_value(&mut state.find.opts.regex, RichText::new(".*").size(12.0))
+            .on_hover_text("Regular expression; replacements can use $1")
+            .changed();
+        requery |= ui
+            .toggle_value(
+                &mut state.find.opts.case_sensitive,
+                RichText::new("Aa").size(12.0),
+            )
+            .on_hover_text("Match case")
+            .changed();
+        if ui
+            .small_button("\u{25b2}")
+            .on_hover_text("Previous match (Shift+Enter)")
+            .clicked()
+        {
+            step = Some(false);
+        }
+        if ui
+            .small_button("\u{25bc}")
+            .on_hover_text("Next match (Enter)")
+            .clicked()
+        {
+            step = Some(true);
+        }
+        let (status, color) = match (&matches, &state.find.note) {
+            (Err(e), _) => (truncate_error(e, 80).to_string(), tc.error),
+            (Ok(_), Some(note)) => (note.clone(), tc.text_secondary),
+            (Ok(m), None) => {
+                let at = state
+                    .find
+                    .current
+                    .as_ref()
+                    .and_then(|c| m.iter().position(|r| r == c));
+                let text = match at {
+                    _ if state.find.query.is_empty() => String::new(),
+                    _ if m.is_empty() => "No matches".to_string(),
+                    Some(i) => format!("{} of {}", i + 1, m.len()),
+                    None => format!("{} matches", m.len()),
+                };
+                (text, tc.text_secondary)
+            }
+        };
+        ui.label(RichText::new(status).size(11.0).color(color));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.add_space(10.0);
+            if close_icon(ui, "find_close", tc.text_secondary)
+                .on_hover_text("Close (Esc)")
+                .clicked()
+            {
+                close = true;
+            }
+            ui.toggle_value(&mut state.find.replace, RichText::new("Replace").size(12.0));
+        });
+    });
+
+    let (mut one, mut all) = (false, false);
+    if state.find.replace {
+        ui.horizontal(|ui| {
+            ui.add_space(10.0);
+            ui.add(
+                egui::TextEdit::singleline(&mut state.find.replacement)
+                    .id_salt("shader_replace")
+                    .hint_text("Replace with")
+                    .desired_width(200.0),
+            );
+            one = ui
+                .button(RichText::new("Replace").size(12.0))
+                .on_hover_text("Replace this match and go to the next")
+                .clicked();
+            all = ui
+                .button(RichText::new("All").size(12.0))
+                .on_hover_text("Replace every match")
+                .clicked();
+        });
+    }
+
+    if requery {
+        let from = state
+            .find
+            .current
+            .as_ref()
+            .map_or(state.cursor, |r| r.start);
+        state.find_step(from, true);
+    }
+    if let Some(forward) = step {
+        state.find_next(state.cursor, forward);
+    }
+    if one {
+        state.replace_current();
+    }
+    if all {
+        state.replace_every();
+    }
+    if close {
+        state.find.open = false;
+        state.focus_code = true;
+    }
+}
+
+/// Vim's mode line, or Emacs' pending prefix, for the toolbar.
+fn keymap_status(state: &ShaderEditorState) -> Option<String> {
+    match state.keymap {
+        Keymap::Standard => None,
+        Keymap::Vim => Some(match (&state.keys.command_line, state.keys.insert) {
+            (Some(line), _) => format!(":{line}"),
+            (None, true) => "-- INSERT --".to_string(),
+            (None, false) => "-- NORMAL --".to_string(),
+        }),
+        Keymap::Emacs => (state.keys.pending == Some('x')).then(|| "C-x-".to_string()),
+    }
+}
+
+/// A key event as keymap input, if it can be one.
+fn key_input(key: Key, modifiers: Modifiers) -> Option<KeyInput> {
+    let name = key.name();
+    let letter = name
+        .chars()
+        .next()
+        .filter(|c| name.len() == 1 && c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_lowercase());
+    match key {
+        Key::Escape => Some(KeyInput::Escape),
+        Key::Enter if modifiers.is_none() => Some(KeyInput::Enter),
+        Key::Backspace if modifiers.is_none() => Some(KeyInput::Backspace),
+        _ if modifiers.ctrl && !modifiers.alt => letter.map(KeyInput::Ctrl),
+        _ if modifiers.alt && !modifiers.ctrl => letter.map(KeyInput::Alt),
+        _ => None,
+    }
+}
+
+/// Apply a pending selection, then run this frame's keys through the editor
+/// shortcuts and the keymap (#2190) before the code field sees them.
+/// Returns a char index to scroll into view once the field is laid out.
+fn handle_code_keys(ui: &egui::Ui, state: &mut ShaderEditorState, id: Id) -> Option<usize> {
+    use egui::text::{CCursor, CCursorRange};
+
+    let ctx = ui.ctx();
+    let mut te = egui::text_edit::TextEditState::load(ctx, id).unwrap_or_default();
+    let mut scroll = None;
+    if std::mem::take(&mut state.focus_code) {
+        ctx.memory_mut(|m| m.request_focus(id));
+    }
+    if let Some(r) = state.select.take() {
+        te.cursor.set_char_range(Some(CCursorRange::two(
+            CCursor::new(r.start),
+            CCursor::new(r.end),
+        )));
+        te.clone().store(ctx, id);
+        scroll = Some(r.end);
+    }
+    if !ctx.memory(|m| m.has_focus(id)) {
+        return scroll;
+    }
+
+    let range = te.cursor.char_range();
+    let mut sel = range.map_or(0..0, |r| r.as_sorted_char_range());
+    let mut cursor = range.map_or(0, |r| r.primary.index);
+    let wgsl = state.file_type == EditorFileType::Wgsl;
+    let (mut moved, mut close) = (false, false);
+    let events = ctx.input_mut(|i| std::mem::take(&mut i.events));
+    let mut kept = Vec::with_capacity(events.len());
+    for event in events {
+        // Editor shortcuts, the same under every keymap. Emacs keeps C-f,
+        // C-h and C-g, and finds with C-s instead.
+        if let egui::Event::Key {
+            key,
+            pressed: true,
+            modifiers,
+            ..
+        } = &event
+        {
+            let command = modifiers.command && !modifiers.alt && !modifiers.shift;
+            let bars = state.keymap != Keymap::Emacs;
+            let selected: String = state.code.chars().skip(sel.start).take(sel.len()).collect();
+            match key {
+                Key::F | Key::H if command && bars => {
+                    state.open_find(*key == Key::H, Some(selected));
+                    continue;
+                }
+                Key::G if command && bars => {
+                    state.goto_line = Some(String::new());
+                    state.find.focus = true;
+                    continue;
+                }
+                Key::Slash if command && wgsl => {
+                    sel = code_edit::toggle_comment(&mut state.code, &sel);
+                    cursor = sel.end;
+                    moved = true;
+                    continue;
+                }
+                Key::Tab if modifiers.is_none() && selected.contains('\n') => {
+                    sel = code_edit::indent_lines(&mut state.code, &sel, false);
+                    cursor = sel.end;
+                    moved = true;
+                    continue;
+                }
+                Key::Tab if modifiers.shift_only() => {
+                    sel = code_edit::indent_lines(&mut state.code, &sel, true);
+                    cursor = sel.end;
+                    moved = true;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        let inputs: Vec<KeyInput> = match &event {
+            egui::Event::Text(t) => t.chars().map(KeyInput::Char).collect(),
+            egui::Event::Key {
+                key,
+                pressed: true,
+                modifiers,
+                ..
+            } => key_input(*key, *modifiers).into_iter().collect(),
+            _ => Vec::new(),
+        };
+        if inputs.is_empty() {
+            // Vim's normal mode never edits through the field.
+            let normal = state.keymap == Keymap::Vim && !state.keys.insert;
+            let edits = matches!(
+                event,
+                egui::Event::Paste(_)
+                    | egui::Event::Cut
+                    | egui::Event::Key {
+                        key: Key::Tab | Key::Delete,
+                        ..
+                    }
+            );
+            if !(normal && edits) {
+                kept.push(event);
+            }
+            continue;
+        }
+
+        let mut handled = false;
+        for input in inputs {
+            let result = state
+                .keys
+                .handle(state.keymap, &mut state.code, &mut cursor, input);
+            let command = match result {
+                KeyResult::Pass => continue,
+                KeyResult::Done => None,
+                KeyResult::Command(c) => Some(c),
+            };
+            handled = true;
+            let undo = |modifiers| egui::Event::Key {
+                key: Key::Z,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers,
+            };
+            match command {
+                Some(EditorCommand::Save) => {
+                    ctx.data_mut(|d| d.insert_temp(Id::new("shader_editor_save"), true));
+                }
+                Some(EditorCommand::Close) => close = true,
+                Some(EditorCommand::SaveAndHide) => {
+                    ctx.data_mut(|d| d.insert_temp(Id::new("shader_editor_save"), true));
+                    state.open = false;
+                }
+                Some(EditorCommand::Find) => state.open_find(false, None),
+                Some(EditorCommand::FindNext) => state.find_next(cursor, true),
+                Some(EditorCommand::FindPrev) => state.find_next(cursor, false),
+                Some(EditorCommand::GotoLine) => {
+                    state.goto_line = Some(String::new());
+                    state.find.focus = true;
+                }
+                Some(EditorCommand::Undo) => kept.push(undo(Modifiers::COMMAND)),
+                Some(EditorCommand::Redo) => kept.push(undo(Modifiers::COMMAND | Modifiers::SHIFT)),
+                Some(EditorCommand::Cancel) => {
+                    state.find.open = false;
+                    state.goto_line = None;
+                }
+                None => {}
+            }
+        }
+        if handled {
+            // A find command selects its match; anything else collapses.
+            sel = state.select.take().unwrap_or(cursor..cursor);
+            cursor = sel.end;
+            moved = true;
+        } else {
+            kept.push(event);
+        }
+    }
+    ctx.input_mut(|i| i.events = kept);
+
+    if moved {
+        te.cursor.set_char_range(Some(CCursorRange::two(
+            CCursor::new(sel.start),
+            CCursor::new(sel.end.max(cursor)),
+        )));
+        te.store(ctx, id);
+        scroll = Some(cursor);
+    }
+    if close {
+        state.close();
+    }
+    scroll
+}
+
+/// Give the parts of `job` matching the find query a background (#2190);
+/// `current`, a char range, gets the stronger one.
+fn highlight_matches(
+    job: &mut egui::text::LayoutJob,
+    query: &str,
+    opts: FindOptions,
+    current: Option<&Range<usize>>,
+    tc: &ThemeColors,
+) {
+    let Ok(ranges) = code_edit::find_bytes(&job.text, query, opts) else {
+        return;
+    };
+    if ranges.is_empty() {
+        return;
+    }
+    let current = current.map(|r| {
+        code_edit::byte_index(&job.text, r.start)..code_edit::byte_index(&job.text, r.end)
+    });
+    let mut sections = Vec::with_capacity(job.sections.len() + 2 * ranges.len());
+    let mut next = 0;
+    for section in std::mem::take(&mut job.sections) {
+        let (mut start, end) = (section.byte_range.start, section.byte_range.end);
+        while ranges.get(next).is_some_and(|r| r.end <= start) {
+            next += 1;
+        }
+        let mut i = next;
+        while start < end {
+            let (cut, lit) = match ranges.get(i) {
+                Some(r) if r.start <= start => (r.end.min(end), Some(r)),
+                Some(r) if r.start < end => (r.start, None),
+                _ => (end, None),
+            };
+            let mut part = section.clone();
+            if start != section.byte_range.start {
+                part.leading_space = 0.0;
+            }
+            part.byte_range = start..cut;
+            if let Some(r) = lit {
+                part.format.background = if current.as_ref() == Some(r) {
+                    tc.accent.gamma_multiply(0.6)
+                } else {
+                    tc.warning.gamma_multiply(0.3)
+                };
+                if cut == r.end {
+                    i += 1;
+                }
+            }
+            sections.push(part);
+            start = cut;
+        }
+    }
+    job.sections = sections;
+}
+
+/// Snapshot picker for the toolbar (#2189); picking one opens its diff.
+fn draw_history_combo(ui: &mut egui::Ui, state: &mut ShaderEditorState) {
+    let now = history::now_ms();
+    let mut picked = None;
+    egui::ComboBox::from_id_salt("shader_history")
+        .width(96.0)
+        .selected_text(RichText::new(format!("History ({})", state.history.len())).size(12.0))
+        .show_ui(ui, |ui| {
+            for snap in &state.history {
+                let mut label = history::age_label(snap.taken_ms, now);
+                if snap.compiled {
+                    label.push_str("  \u{2713} compiled");
+                }
+                let viewing = state.history_view.as_ref().is_some_and(|(v, _)| v == snap);
+                if ui.selectable_label(viewing, label).clicked() {
+                    picked = Some(snap.clone());
+                }
+            }
+        })
+        .response
+        .on_hover_text("Snapshots taken on every save and successful compile");
+    if let Some(snap) = picked {
+        state.view_snapshot(&snap);
+    }
+}
+
+/// Snapshot path, editor buffer, font size and line colours a diff was
+/// laid out for.
+type DiffKey = (PathBuf, String, f32, [Color32; 3]);
+
+enum SnapshotAction {
+    Restore,
+    Close,
+}
+
+/// Line diff from a snapshot to the current buffer, drawn in place of the
+/// code: removed lines in red, added lines in green. Laid out into `cache`
+/// when anything it depends on changed.
+fn draw_snapshot_diff(
+    ui: &mut egui::Ui,
+    snap: &Snapshot,
+    old: &str,
+    current: &str,
+    cache: &mut Option<(DiffKey, egui::text::LayoutJob)>,
+    height: f32,
+    fontsize: f32,
+) -> Option<SnapshotAction> {
+    let tc = theme_colors(ui.ctx());
+    let mut action = None;
+    ui.horizontal(|ui| {
+        ui.add_space(10.0);
+        ui.label(
+            RichText::new(format!(
+                "Snapshot from {} \u{2192} editor",
+                history::age_label(snap.taken_ms, history::now_ms())
+            ))
+            .size(12.0)
+            .color(tc.text_secondary),
+        );
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.add_space(10.0);
+            if ui.button(RichText::new("Close").size(12.0)).clicked() {
+                action = Some(SnapshotAction::Close);
+            }
+            if ui
+                .button(RichText::new("Restore").size(12.0))
+                .on_hover_text("Load this snapshot into the editor; save to apply it")
+                .clicked()
+            {
+                action = Some(SnapshotAction::Restore);
+            }
+        });
+    });
+
+    let colors = [tc.text_dim, tc.error, tc.success];
+    let fresh = cache.as_ref().is_some_and(|((path, text, size, cols), _)| {
+        *path == snap.path && text == current && *size == fontsize && *cols == colors
+    });
+    if !fresh {
+        let font = egui::FontId::monospace(fontsize);
+        let mut job = egui::text::LayoutJob::default();
+        for line in history::line_diff(old, current) {
+            let (prefix, text, color) = match line {
+                DiffLine::Same(t) => ("  ", t, tc.text_dim),
+                DiffLine::Removed(t) => ("- ", t, tc.error),
+                DiffLine::Added(t) => ("+ ", t, tc.success),
+            };
+            job.append(
+                &format!("{prefix}{text}\n"),
+                0.0,
+                egui::text::TextFormat::simple(font.clone(), color),
+            );
+        }
+        let key = (snap.path.clone(), current.to_string(), fontsize, colors);
+        *cache = Some((key, job));
+    }
+    let job = cache
+        .as_ref()
+        .map(|(_, job)| job.clone())
+        .unwrap_or_default();
+    egui::ScrollArea::both()
+        .id_salt("shader_history_diff")
+        .min_scrolled_height(height - 24.0)
+        .max_height(height - 24.0)
+        .show(ui, |ui| {
+            ui.horizontal_top(|ui| {
+                ui.add_space(10.0);
+                ui.label(job);
+            });
+        });
+    action
+}
+
 /// Draw the "New Effect" name prompt as a small centered window.
 pub fn draw_new_effect_prompt(ctx: &egui::Context, state: &mut ShaderEditorState) {
     if !state.new_effect_prompt {
@@ -764,3 +1775,122 @@ fn truncate_error(s: &str, max_len: usize) -> &str {
         &s[..end]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor() -> ShaderEditorState {
+        let mut ed = ShaderEditorState::default();
+        ed.open_file("fx", PathBuf::from("fx.wgsl"), "good".into());
+        ed
+    }
+
+    #[test]
+    fn successful_save_becomes_last_good() {
+        let mut ed = editor();
+        ed.code = "better".into();
+        ed.disk_content = ed.code.clone();
+        ed.wgsl_saved();
+        assert_eq!(ed.compile_finished(None), None);
+        assert_eq!(ed.last_good.as_ref().unwrap().1, "better");
+    }
+
+    #[test]
+    fn broken_save_rolls_back_and_keeps_the_edit() {
+        let mut ed = editor();
+        ed.code = "broken".into();
+        ed.disk_content = ed.code.clone();
+        ed.wgsl_saved();
+        let rollback = ed.compile_finished(Some("syntax error"));
+        assert_eq!(rollback, Some((PathBuf::from("fx.wgsl"), "good".into())));
+        assert_eq!(ed.code, "broken");
+        assert!(ed.is_dirty());
+        assert!(ed.rollback_note.is_some());
+        // Results not caused by an editor save are left alone.
+        assert_eq!(ed.compile_finished(Some("again")), None);
+    }
+
+    #[test]
+    fn rollback_off_or_from_the_effect_tab() {
+        let mut ed = editor();
+        ed.auto_rollback = false;
+        ed.code = "broken".into();
+        ed.disk_content = ed.code.clone();
+        ed.wgsl_saved();
+        assert_eq!(ed.compile_finished(Some("err")), None);
+
+        // With the .pfx tab active the shader lives in the paired buffers.
+        let mut ed = editor();
+        ed.load_paired_pfx(PathBuf::from("fx.pfx"), "{}".into());
+        ed.switch_tab(EditorFileType::Pfx);
+        ed.paired_content = "broken".into();
+        ed.paired_disk_content = ed.paired_content.clone();
+        ed.wgsl_saved();
+        assert!(ed.compile_finished(Some("err")).is_some());
+        assert_eq!(ed.paired_disk_content, "good");
+        assert_eq!(ed.code, "{}");
+    }
+
+    #[test]
+    fn restoring_a_snapshot_leaves_it_unsaved() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("1-compiled.wgsl");
+        std::fs::write(&path, "old").unwrap();
+        let snap = Snapshot {
+            path,
+            taken_ms: 1,
+            compiled: true,
+        };
+
+        let mut ed = editor();
+        ed.view_snapshot(&snap);
+        assert_eq!(ed.history_view.as_ref().unwrap().1, "old");
+        ed.restore_snapshot();
+        assert!(ed.history_view.is_none());
+        assert_eq!(ed.code, "old");
+        assert!(ed.is_dirty());
+    }
+
+    #[test]
+    fn find_steps_and_replaces() {
+        let mut ed = editor();
+        ed.code = "a = t; b = t; c = t;".into();
+        ed.find.query = "t".into();
+        ed.find_next(0, true);
+        assert_eq!(ed.find.current, Some(4..5));
+        assert_eq!(ed.select, Some(4..5));
+        // Stepping from inside the current match moves past it, and wraps.
+        ed.find_next(5, true);
+        assert_eq!(ed.find.current, Some(11..12));
+        ed.find_next(11, false);
+        assert_eq!(ed.find.current, Some(4..5));
+        ed.find_next(4, false);
+        assert_eq!(ed.find.current, Some(18..19));
+
+        ed.find.replacement = "x".into();
+        ed.replace_current();
+        assert_eq!(ed.code, "a = t; b = t; c = x;");
+        assert_eq!(ed.find.current, Some(4..5));
+        ed.replace_every();
+        assert_eq!(ed.code, "a = x; b = x; c = x;");
+        assert_eq!(ed.find.note.as_deref(), Some("2 replaced"));
+
+        ed.find.opts.regex = true;
+        ed.find.query = "(".into();
+        ed.find_step(0, true);
+        assert!(ed.find.note.is_some());
+        assert_eq!(ed.find.current, None);
+    }
+
+    #[test]
+    fn goto_line_selects_its_start() {
+        let mut ed = editor();
+        ed.code = "one\ntwo\nthree".into();
+        ed.goto_line = Some("3".into());
+        ed.goto(3);
+        assert_eq!(ed.select, Some(8..8));
+        assert!(ed.goto_line.is_none());
+        assert!(ed.focus_code);
+    }
+}
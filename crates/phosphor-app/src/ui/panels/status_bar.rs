@@ -1,8 +1,8 @@
-use egui::{Color32, RichText, Ui, Vec2};
+use egui::{Color32, RichText, Stroke, StrokeKind, Ui, Vec2};
 
-use crate::audio::AudioIndicator;
+use crate::audio::{AudioIndicator, InputLevel, to_dbfs};
 use crate::gpu::ShaderUniforms;
-use crate::ui::theme::colors::theme_colors;
+use crate::ui::theme::colors::{ThemeColors, theme_colors};
 use crate::ui::theme::tokens::*;
 
 const OFF: Color32 = Color32::from_rgb(0x33, 0x33, 0x33);
@@ -41,6 +41,67 @@ fn fixed_value(ui: &mut Ui, text: &str, width: f32, color: Color32) {
 /// Duration to show status errors before auto-clearing.
 const ERROR_DISPLAY_SECS: f64 = 6.0;
 
+/// Bottom of the input meter's scale; anything quieter draws empty.
+const METER_FLOOR_DB: f32 = -60.0;
+/// How long the meter's peak tick holds before it falls back.
+const PEAK_HOLD_SECS: f64 = 1.5;
+/// How long the meter stays red after the input clips.
+const CLIP_HOLD_SECS: f64 = 3.0;
+
+/// Input level meter (#2212): an rms bar with a held peak tick, outlined red
+/// for a few seconds after the input clips so a single over isn't missed.
+fn input_meter(ui: &mut Ui, input: InputLevel, tc: &ThemeColors) {
+    let now = ui.input(|i| i.time);
+    let id = ui.id().with("input_meter");
+    // (held peak dB, when it was taken, last clip)
+    let (mut held, mut held_at, mut clip_at): (f32, f64, f64) = ui.ctx().data_mut(|d| {
+        d.get_temp(id)
+            .unwrap_or((METER_FLOOR_DB, 0.0, f64::NEG_INFINITY))
+    });
+    let peak_db = to_dbfs(input.peak);
+    if peak_db >= held || now - held_at > PEAK_HOLD_SECS {
+        held = peak_db;
+        held_at = now;
+    }
+    if input.clipped() {
+        clip_at = now;
+    }
+    ui.ctx()
+        .data_mut(|d| d.insert_temp(id, (held, held_at, clip_at)));
+    let clipping = now - clip_at < CLIP_HOLD_SECS;
+
+    let (rect, resp) = ui.allocate_exact_size(Vec2::new(36.0, 6.0), egui::Sense::hover());
+    let fraction = |db: f32| ((db - METER_FLOOR_DB) / -METER_FLOOR_DB).clamp(0.0, 1.0);
+    let rms_db = to_dbfs(input.rms);
+    let color = if clipping {
+        tc.error
+    } else if held > -6.0 {
+        tc.warning
+    } else {
+        tc.success
+    };
+    let painter = ui.painter();
+    painter.rect_filled(rect, 1.0, OFF);
+    let mut bar = rect;
+    bar.set_width(rect.width() * fraction(rms_db));
+    painter.rect_filled(bar, 1.0, color);
+    if held > METER_FLOOR_DB {
+        let x = rect.left() + rect.width() * fraction(held);
+        painter.vline(x, rect.y_range(), Stroke::new(1.0, tc.text_primary));
+    }
+    if clipping {
+        painter.rect_stroke(rect, 1.0, Stroke::new(1.0, tc.error), StrokeKind::Outside);
+    }
+
+    let mut tip = format!("Input peak {peak_db:.1} dBFS · RMS {rms_db:.1} dBFS");
+    if clipping {
+        tip.push_str("\nClipping: turn the source or input gain down");
+    } else if held <= METER_FLOOR_DB {
+        tip.push_str("\nNo signal: check the audio input and routing");
+    }
+    resp.on_hover_text(tip);
+}
+
 // UI status bar function requires multiple bool flags for indicator states
 #[allow(clippy::fn_params_excessive_bools)]
 pub fn draw_status_bar(
@@ -60,6 +121,7 @@ pub fn draw_status_bar(
     status_error: &Option<(String, std::time::Instant)>,
     preset_loading: Option<&str>,
     audio: AudioIndicator,
+    input: InputLevel,
 ) {
     let tc = theme_colors(ui.ctx());
 
@@ -130,7 +192,16 @@ pub fn draw_status_bar(
             ui.ctx().data_mut(|d| d.insert_temp(fps_id, smoothed));
             let fps = smoothed as u32;
             fixed_value(ui, &format!("{fps}"), 24.0, tc.text_secondary);
-            label(ui, "FPS");
+            let fps_label = ui
+                .add(
+                    egui::Label::new(RichText::new("FPS").size(MONO_SIZE).color(LABEL_COLOR))
+                        .sense(egui::Sense::click()),
+                )
+                .on_hover_text("Performance panel: GPU resources and growth");
+            if fps_label.clicked() {
+                ui.ctx()
+                    .data_mut(|d| d.insert_temp(egui::Id::new("toggle_perf_panel"), true));
+            }
 
             ui.add_space(6.0);
 
@@ -184,6 +255,9 @@ pub fn draw_status_bar(
             label(ui, "MIDI");
             ui.add_space(6.0);
 
+            // Input level, right of the AUD dot
+            input_meter(ui, input, &tc);
+
             // Audio capture health (A9 #1460) — the only dot here that can report a fault, so
             // it shows an attempt counter rather than just a colour.
             {
@@ -2,11 +2,14 @@
 //! OSC column. Replaces the two per-protocol trigger grids that repeated the
 //! same ten actions (and their layout code) once per protocol.
 
+use std::collections::BTreeMap;
+
 use egui::{RichText, Ui};
 
 use crate::midi::MidiSystem;
 use crate::midi::types::TriggerAction;
 use crate::osc::OscSystem;
+use crate::preset::PresetStore;
 use crate::ui::panels::{midi_panel, osc_panel};
 use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
@@ -68,3 +71,81 @@ pub fn draw_triggers_table(ui: &mut Ui, midi: &mut MidiSystem, osc: &mut OscSyst
             }
         });
 }
+
+/// Presets bound directly to a MIDI pad or a number key (#2222), one row each.
+/// Assigned from the preset tile's shift+right-click menu; the MIDI badge here
+/// re-learns or clears like the ones above.
+pub fn draw_preset_triggers(
+    ui: &mut Ui,
+    midi: &mut MidiSystem,
+    presets: &PresetStore,
+    keys: &BTreeMap<u8, u64>,
+) {
+    let mut bound: Vec<usize> = midi
+        .config
+        .triggers
+        .keys()
+        .filter_map(|a| match a {
+            TriggerAction::LoadPreset(id) => Some(*id),
+            _ => None,
+        })
+        .chain(keys.values().copied())
+        .filter_map(|id| presets.index_of_id(id))
+        .collect();
+    bound.sort_unstable();
+    bound.dedup();
+    if bound.is_empty() {
+        return;
+    }
+
+    let tc = theme_colors(ui.ctx());
+    let label_w = 64.0;
+    let badge_w = ((ui.available_width() - label_w - 12.0) / 2.0).max(40.0);
+
+    ui.add_space(6.0);
+    egui::Grid::new("preset_triggers_table")
+        .num_columns(3)
+        .min_col_width(0.0)
+        .spacing([4.0, 3.0])
+        .show(ui, |ui| {
+            ui.add_sized(
+                [label_w, 12.0],
+                egui::Label::new(RichText::new("Presets").size(8.0).color(tc.text_secondary)),
+            );
+            for heading in ["MIDI", "Key"] {
+                ui.add_sized(
+                    [badge_w, 12.0],
+                    egui::Label::new(RichText::new(heading).size(8.0).color(tc.text_secondary)),
+                );
+            }
+            ui.end_row();
+
+            for idx in bound {
+                let (name, preset) = &presets.presets[idx];
+                let action = TriggerAction::LoadPreset(preset.id);
+                ui.add_sized(
+                    [label_w, MIN_INTERACT_HEIGHT],
+                    egui::Label::new(
+                        RichText::new(name)
+                            .size(SMALL_SIZE)
+                            .color(tc.text_secondary),
+                    )
+                    .truncate(),
+                )
+                .on_hover_text(name);
+                ui.add_sized([badge_w, MIN_INTERACT_HEIGHT], |ui: &mut Ui| {
+                    ui.horizontal(|ui| midi_panel::draw_trigger_badge(ui, midi, action))
+                        .response
+                });
+                let key = keys
+                    .iter()
+                    .find(|(_, id)| **id == preset.id)
+                    .map_or_else(|| "—".to_string(), |(d, _)| d.to_string());
+                ui.add_sized(
+                    [badge_w, MIN_INTERACT_HEIGHT],
+                    egui::Label::new(RichText::new(key).size(SMALL_SIZE).color(tc.text_primary)),
+                );
+                ui.end_row();
+            }
+        });
+}
@@ -0,0 +1,282 @@
+//! Uniform inspector (#2228): the `ShaderUniforms` the active layer's shader
+//! reads this frame. Params come first, slot by slot with the input that fills
+//! each `param(i)`, then the clock and every audio field with its byte offset.
+//! Toggled with U or from the param panel; its Textures button opens the
+//! debug view.
+
+use std::mem::offset_of;
+
+use egui::{Color32, Context, RichText, Ui};
+
+use crate::gpu::ShaderUniforms;
+use crate::gpu::layer::{Layer, LayerContent};
+use crate::params::ParamDef;
+use crate::ui::theme::colors::{ThemeColors, theme_colors};
+use crate::ui::theme::tokens::*;
+
+/// Name, byte offset and value of each listed scalar field.
+macro_rules! fields {
+    ($u:expr; $($f:ident),* $(,)?) => {
+        [$((stringify!($f), offset_of!(ShaderUniforms, $f), $u.$f)),*]
+    };
+}
+
+pub fn draw_uniforms_panel(ctx: &Context, open: &mut bool, layer: Option<&Layer>) {
+    let tc = theme_colors(ctx);
+    let hold_id = egui::Id::new("uniforms_panel_hold");
+
+    egui::Window::new("Uniforms")
+        .id(egui::Id::new("uniforms_panel_window"))
+        .open(open)
+        .default_size([340.0, 520.0])
+        .resizable(true)
+        .collapsible(true)
+        .show(ctx, |ui| {
+            let Some(layer) = layer else {
+                return;
+            };
+            let LayerContent::Effect(ref effect) = layer.content else {
+                ui.label(
+                    RichText::new(
+                        "The active layer isn't an effect, so it has no shader uniforms.",
+                    )
+                    .size(BODY_SIZE)
+                    .color(tc.text_secondary),
+                );
+                return;
+            };
+
+            // Hold keeps a copy so a fast-moving value can be read
+            let mut held: Option<ShaderUniforms> = ctx.data_mut(|d| d.get_temp(hold_id));
+            ui.horizontal(|ui| {
+                let name = layer.custom_name.as_deref().unwrap_or(&layer.name);
+                ui.label(RichText::new(name).size(BODY_SIZE).strong());
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .small_button("Textures")
+                        .on_hover_text("Debug view of this layer's passes (V)")
+                        .clicked()
+                    {
+                        ctx.data_mut(|d| d.insert_temp(egui::Id::new("toggle_debug_view"), true));
+                    }
+                    let mut hold = held.is_some();
+                    if ui
+                        .checkbox(&mut hold, RichText::new("Hold").size(SMALL_SIZE))
+                        .on_hover_text("Freeze the readout on this frame")
+                        .changed()
+                    {
+                        held = hold.then_some(effect.uniforms);
+                        ctx.data_mut(|d| match held {
+                            Some(u) => d.insert_temp(hold_id, u),
+                            None => d.remove::<ShaderUniforms>(hold_id),
+                        });
+                    }
+                });
+            });
+            if layer.audio_muted {
+                ui.label(
+                    RichText::new("Audio muted: this layer's audio fields read 0.")
+                        .size(SMALL_SIZE)
+                        .color(tc.warning),
+                );
+            }
+            let u = held.unwrap_or(effect.uniforms);
+            ui.add_space(4.0);
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                draw_params(ui, &u, &layer.param_store.slots(), &tc);
+                draw_fields(ui, &u, &tc);
+            });
+        });
+}
+
+/// One row per `param(i)`, naming the input (and component) packed there.
+fn draw_params(
+    ui: &mut Ui,
+    u: &ShaderUniforms,
+    slots: &[(&ParamDef, std::ops::Range<usize>)],
+    tc: &ThemeColors,
+) {
+    egui::CollapsingHeader::new(RichText::new("Params").size(SMALL_SIZE).strong())
+        .id_salt("uniforms_params")
+        .default_open(true)
+        .show(ui, |ui| {
+            egui::Grid::new("uniforms_params_grid")
+                .num_columns(3)
+                .spacing([12.0, 1.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    for (i, v) in u.params.iter().enumerate() {
+                        let owner = slots
+                            .iter()
+                            .find(|(_, r)| r.contains(&i))
+                            .map(|(def, r)| slot_label(def, i - r.start));
+                        let color = if owner.is_some() {
+                            tc.text_primary
+                        } else {
+                            tc.text_dim
+                        };
+                        mono(ui, &format!("param({i})"), color);
+                        mono(ui, &format!("{v:.4}"), color);
+                        mono(ui, owner.as_deref().unwrap_or("unused"), tc.text_secondary);
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// "speed", or "tint.g" for one component of a multi-slot input.
+fn slot_label(def: &ParamDef, component: usize) -> String {
+    let names: &[&str] = match def {
+        ParamDef::Color { .. } => &["r", "g", "b", "a"],
+        ParamDef::Point2D { .. } => &["x", "y"],
+        ParamDef::Float { .. } | ParamDef::Bool { .. } => return def.name().to_string(),
+    };
+    format!("{}.{}", def.name(), names[component])
+}
+
+fn draw_fields(ui: &mut Ui, u: &ShaderUniforms, tc: &ThemeColors) {
+    let clock = fields!(u; time, delta_time, frame_index, feedback_decay, seed);
+    section(ui, "Clock", true, |ui| {
+        rows(ui, &clock, tc);
+        mono(ui, "resolution", tc.text_primary);
+        mono(
+            ui,
+            &format!("{} x {}", u.resolution[0], u.resolution[1]),
+            tc.text_primary,
+        );
+        offset(ui, offset_of!(ShaderUniforms, resolution), tc);
+        ui.end_row();
+    });
+
+    let bands = fields!(u; sub_bass, bass, low_mid, mid, upper_mid, presence, brilliance, rms);
+    section(ui, "Bands", false, |ui| rows(ui, &bands, tc));
+
+    let features = fields!(u;
+        kick, centroid, flux, flatness, rolloff, bandwidth, zcr, onset, beat, beat_phase, bpm,
+        beat_strength, dominant_chroma, scroll_phase,
+    );
+    section(ui, "Features", false, |ui| rows(ui, &features, tc));
+
+    let music = fields!(u;
+        loudness_m, loudness_s, loudness_trend, key_class, key_is_minor, key_confidence,
+        downbeat, bar_phase, beat_in_bar, pan, stereo_width, stereo_corr, section_novelty,
+        buildup, drop,
+    );
+    section(ui, "Loudness, key, bar, stereo", false, |ui| {
+        rows(ui, &music, tc)
+    });
+
+    let timbre = fields!(u;
+        percussive_energy, harmonic_energy, harmonic_ratio, pitch, pitch_confidence,
+        contrast_0, contrast_1, contrast_2, contrast_3, contrast_4, contrast_5, contrast_mean,
+        timbre_flux,
+    );
+    section(ui, "Timbre and pitch", false, |ui| rows(ui, &timbre, tc));
+
+    let dynamics = fields!(u;
+        crest, impact, env_bass_fast, env_bass_slow, env_mid_fast, env_mid_slow, env_high_fast,
+        env_high_slow, mic_rms, mic_onset,
+    );
+    section(ui, "Dynamics, envelopes, mic", false, |ui| {
+        rows(ui, &dynamics, tc)
+    });
+
+    let motion = fields!(u; motion, motion_x, motion_y, frame_diff);
+    section(ui, "Motion", false, |ui| rows(ui, &motion, tc));
+
+    section(ui, "Arrays", false, |ui| {
+        array_rows(
+            ui,
+            "mfcc",
+            &u.mfcc[..13],
+            offset_of!(ShaderUniforms, mfcc),
+            tc,
+        );
+        array_rows(
+            ui,
+            "chroma",
+            &u.chroma,
+            offset_of!(ShaderUniforms, chroma),
+            tc,
+        );
+        array_rows(
+            ui,
+            "band_pan",
+            &u.band_pan[..7],
+            offset_of!(ShaderUniforms, band_pan),
+            tc,
+        );
+    });
+}
+
+fn section(ui: &mut Ui, title: &str, open: bool, body: impl FnOnce(&mut Ui)) {
+    egui::CollapsingHeader::new(RichText::new(title).size(SMALL_SIZE).strong())
+        .id_salt(("uniforms_section", title))
+        .default_open(open)
+        .show(ui, |ui| {
+            egui::Grid::new(("uniforms_grid", title))
+                .num_columns(3)
+                .spacing([12.0, 1.0])
+                .striped(true)
+                .show(ui, body);
+        });
+}
+
+fn rows(ui: &mut Ui, fields: &[(&str, usize, f32)], tc: &ThemeColors) {
+    for (name, at, v) in fields {
+        mono(ui, name, tc.text_primary);
+        mono(ui, &format!("{v:.4}"), tc.text_primary);
+        offset(ui, *at, tc);
+        ui.end_row();
+    }
+}
+
+fn array_rows(ui: &mut Ui, name: &str, values: &[f32], base: usize, tc: &ThemeColors) {
+    for (i, v) in values.iter().enumerate() {
+        mono(ui, &format!("{name}[{i}]"), tc.text_primary);
+        mono(ui, &format!("{v:.4}"), tc.text_primary);
+        offset(ui, base + i * 4, tc);
+        ui.end_row();
+    }
+}
+
+fn offset(ui: &mut Ui, at: usize, tc: &ThemeColors) {
+    mono(ui, &format!("+{at}"), tc.text_dim).on_hover_text("Byte offset in the uniform buffer");
+}
+
+fn mono(ui: &mut Ui, text: &str, color: Color32) -> egui::Response {
+    ui.label(RichText::new(text).monospace().size(MONO_SIZE).color(color))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_labels_name_components() {
+        let tint = ParamDef::Color {
+            name: "tint".into(),
+            default: [1.0; 4],
+            group: None,
+            description: None,
+        };
+        let on = ParamDef::Bool {
+            name: "on".into(),
+            default: true,
+            group: None,
+            description: None,
+        };
+        assert_eq!(slot_label(&tint, 2), "tint.b");
+        assert_eq!(slot_label(&on, 0), "on");
+    }
+
+    #[test]
+    fn field_offsets_match_the_wgsl_layout() {
+        let u: ShaderUniforms = bytemuck::Zeroable::zeroed();
+        let [(name, at, _)] = fields!(u; kick);
+        assert_eq!((name, at), ("kick", 48));
+        assert_eq!(offset_of!(ShaderUniforms, params), 96);
+        assert_eq!(offset_of!(ShaderUniforms, env_high_slow), 500);
+    }
+}
@@ -3,6 +3,7 @@ use egui::{Color32, RichText, Ui};
 use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
 use crate::web::WebSystem;
+use crate::web::follower::FollowStatus;
 
 const WEB_BLUE: Color32 = Color32::from_rgb(0x50, 0x90, 0xE0);
 
@@ -75,6 +76,61 @@ pub fn draw_web_panel(ui: &mut Ui, web: &mut WebSystem) {
             });
         }
     }
+
+    draw_follow(ui, web);
+}
+
+/// Hot standby (#2204): mirror another instance's web server.
+fn draw_follow(ui: &mut Ui, web: &mut WebSystem) {
+    let tc = theme_colors(ui.ctx());
+    ui.separator();
+
+    let mut follow = web.config.follow;
+    if ui
+        .checkbox(
+            &mut follow,
+            RichText::new("Follow primary").size(SMALL_SIZE),
+        )
+        .on_hover_text(
+            "Run as a hot standby: mirror the preset, layers, params and triggers \
+             of the Fosfora at this address, so this output can take over",
+        )
+        .changed()
+    {
+        web.set_following(follow);
+    }
+
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Primary").size(SMALL_SIZE));
+        let resp = ui.add(
+            egui::TextEdit::singleline(&mut web.config.follow_address)
+                .hint_text("192.168.1.20:9002")
+                .desired_width(ui.available_width().max(60.0))
+                .font(egui::TextStyle::Small),
+        );
+        if resp.changed() {
+            web.config.save();
+        }
+        // Reconnect once editing is done, not on every keystroke
+        if resp.lost_focus() && web.config.follow {
+            web.restart_follower();
+        }
+    });
+
+    if let Some(status) = web.follow_status() {
+        let (text, color) = match status {
+            FollowStatus::Connecting => ("Connecting…".to_string(), tc.text_secondary),
+            FollowStatus::Connected => ("Following".to_string(), tc.success),
+            FollowStatus::Lost(why) => (format!("Lost primary: {why}"), tc.warning),
+        };
+        ui.label(RichText::new(text).size(SMALL_SIZE).color(color));
+    } else if web.config.follow {
+        ui.label(
+            RichText::new("Enter the primary's address")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        );
+    }
 }
 
 /// Try to find a LAN IP address (non-loopback IPv4).
@@ -1,5 +1,6 @@
 use egui::{RichText, Ui};
 
+use crate::media::types::WebcamMode;
 use crate::ui::theme::colors::theme_colors;
 use crate::ui::theme::tokens::*;
 
@@ -12,6 +13,12 @@ pub struct WebcamInfo {
     pub mirror: bool,
     pub available_devices: Vec<(u32, String)>,
     pub device_index: u32,
+    /// Modes the device advertises (empty = unknown, offer common modes).
+    pub available_modes: Vec<WebcamMode>,
+    /// Requested capture mode.
+    pub mode: WebcamMode,
+    /// Frame rate the device actually delivers (0 = unknown).
+    pub fps: u32,
     pub capture_running: bool,
 }
 
@@ -57,12 +64,20 @@ pub fn draw_webcam_panel(ui: &mut Ui, info: &WebcamInfo) {
     }
 
     ui.label(RichText::new(&info.device_name).size(BODY_SIZE).strong());
+    let actual = if info.fps > 0 {
+        format!("{}x{} @ {} fps", info.width, info.height, info.fps)
+    } else {
+        format!("{}x{}", info.width, info.height)
+    };
     ui.label(
-        RichText::new(format!("{}x{}", info.width, info.height))
+        RichText::new(actual)
             .size(SMALL_SIZE)
             .color(tc.text_secondary),
     );
 
+    ui.add_space(4.0);
+    draw_mode_pickers(ui, info);
+
     if !info.capture_running {
         ui.add_space(4.0);
         ui.label(
@@ -102,6 +117,95 @@ pub fn draw_webcam_panel(ui: &mut Ui, info: &WebcamInfo) {
     }
 }
 
+/// Resolution + FPS combos. Picking a resolution keeps the current fps if the
+/// device offers it there, else the closest one.
+fn draw_mode_pickers(ui: &mut Ui, info: &WebcamInfo) {
+    let tc = theme_colors(ui.ctx());
+    let modes: &[WebcamMode] = if info.available_modes.is_empty() {
+        WebcamMode::COMMON
+    } else {
+        &info.available_modes
+    };
+
+    let mut resolutions: Vec<(u32, u32)> = Vec::new();
+    for m in modes {
+        if !resolutions.contains(&m.resolution()) {
+            resolutions.push(m.resolution());
+        }
+    }
+    let mut rates: Vec<u32> = modes
+        .iter()
+        .filter(|m| m.resolution() == info.mode.resolution())
+        .map(|m| m.fps)
+        .collect();
+    rates.sort_unstable_by(|a, b| b.cmp(a));
+    rates.dedup();
+
+    let mut picked: Option<WebcamMode> = None;
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new("Resolution")
+                .size(SMALL_SIZE)
+                .color(tc.text_secondary),
+        );
+        egui::ComboBox::from_id_salt("webcam_resolution_combo")
+            .selected_text(RichText::new(info.mode.resolution_label()).size(SMALL_SIZE))
+            .show_ui(ui, |ui| {
+                for &(w, h) in &resolutions {
+                    let selected = (w, h) == info.mode.resolution();
+                    if ui
+                        .selectable_label(
+                            selected,
+                            RichText::new(format!("{w}x{h}")).size(SMALL_SIZE),
+                        )
+                        .clicked()
+                        && !selected
+                    {
+                        let fps = modes
+                            .iter()
+                            .filter(|m| m.resolution() == (w, h))
+                            .map(|m| m.fps)
+                            .min_by_key(|f| f.abs_diff(info.mode.fps))
+                            .unwrap_or(info.mode.fps);
+                        picked = Some(WebcamMode::new(w, h, fps));
+                    }
+                }
+            });
+    });
+    if !rates.is_empty() {
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new("FPS")
+                    .size(SMALL_SIZE)
+                    .color(tc.text_secondary),
+            );
+            egui::ComboBox::from_id_salt("webcam_fps_combo")
+                .selected_text(RichText::new(info.mode.fps.to_string()).size(SMALL_SIZE))
+                .show_ui(ui, |ui| {
+                    for &fps in &rates {
+                        let selected = fps == info.mode.fps;
+                        if ui
+                            .selectable_label(
+                                selected,
+                                RichText::new(fps.to_string()).size(SMALL_SIZE),
+                            )
+                            .clicked()
+                            && !selected
+                        {
+                            picked = Some(WebcamMode { fps, ..info.mode });
+                        }
+                    }
+                });
+        });
+    }
+
+    if let Some(mode) = picked {
+        ui.ctx().data_mut(|d| {
+            d.insert_temp(egui::Id::new("switch_webcam_mode"), mode);
+        });
+    }
+}
+
 fn truncate_name(name: &str, max: usize) -> String {
     if name.len() <= max {
         name.to_string()
@@ -5,6 +5,7 @@ use egui::{
     collapsing_header::CollapsingState, pos2,
 };
 
+use super::hints;
 use super::theme::colors::theme_colors;
 use super::theme::tokens::*;
 
@@ -55,10 +56,11 @@ pub fn section(
     add_body: impl FnOnce(&mut Ui),
 ) {
     let tc = theme_colors(ui.ctx());
+    let anchor = id;
     let id = ui.make_persistent_id(id);
     let state = CollapsingState::load_with_default_open(ui.ctx(), id, default_open);
 
-    card_frame(ui).show(ui, |ui| {
+    let card = card_frame(ui).show(ui, |ui| {
         let full_width = ui.available_width();
 
         // Header row — always full width
@@ -95,6 +97,8 @@ pub fn section(
             add_body(ui);
         }
     });
+    // Sections are where the first-run tour points (#2242)
+    hints::mark(ui, anchor, card.response.rect);
 }
 
 /// Draw a solid triangle indicator for collapsible sections.
@@ -136,10 +140,11 @@ pub fn section_with_header(
     add_body: impl FnOnce(&mut Ui),
 ) {
     let tc = theme_colors(ui.ctx());
+    let anchor = id;
     let id = ui.make_persistent_id(id);
     let state = CollapsingState::load_with_default_open(ui.ctx(), id, default_open);
 
-    card_frame(ui).show(ui, |ui| {
+    let card = card_frame(ui).show(ui, |ui| {
         let full_width = ui.available_width();
 
         let header_response = ui.horizontal(|ui| {
@@ -170,6 +175,7 @@ pub fn section_with_header(
             add_body(ui);
         }
     });
+    hints::mark(ui, anchor, card.response.rect);
 }
 
 /// Subsection font size — smaller than parent section heading (JSX: 9px vs 11px).
@@ -190,10 +196,33 @@ pub fn subsection(
     default_open: bool,
     add_body: impl FnOnce(&mut Ui),
 ) {
-    let tc = theme_colors(ui.ctx());
     let id = ui.make_persistent_id(id);
     let state = CollapsingState::load_with_default_open(ui.ctx(), id, default_open);
 
+    if subsection_header(ui, title, badge_text, badge_color, state.is_open()) {
+        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, default_open);
+        state.toggle(ui);
+        state.store(ui.ctx());
+    }
+
+    // JSX: marginBottom 6 — gap between header and body content
+    if state.is_open() {
+        ui.add_space(6.0);
+        add_body(ui);
+    }
+}
+
+/// The [`subsection`] header alone, for callers that keep the open state
+/// themselves (#2181). Returns true when the header was clicked.
+pub fn subsection_header(
+    ui: &mut Ui,
+    title: &str,
+    badge_text: Option<&str>,
+    badge_color: Color32,
+    open: bool,
+) -> bool {
+    let tc = theme_colors(ui.ctx());
+
     // JSX: marginTop 10 on every SectionLabel
     ui.add_space(10.0);
 
@@ -202,7 +231,7 @@ pub fn subsection(
         ui.set_min_width(full_width);
         ui.spacing_mut().item_spacing.x = 5.0;
         // Smaller arrow than parent section (JSX font-size 8 vs 11)
-        draw_section_arrow_sized(ui, state.is_open(), tc.text_secondary, SUBSECTION_ARROW);
+        draw_section_arrow_sized(ui, open, tc.text_secondary, SUBSECTION_ARROW);
         ui.label(
             RichText::new(title.to_uppercase())
                 .size(SUBSECTION_SIZE)
@@ -220,21 +249,10 @@ pub fn subsection(
         });
     });
 
-    if header_response
+    header_response
         .response
         .interact(egui::Sense::click())
         .clicked()
-    {
-        let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, default_open);
-        state.toggle(ui);
-        state.store(ui.ctx());
-    }
-
-    // JSX: marginBottom 6 — gap between header and body content
-    if state.is_open() {
-        ui.add_space(6.0);
-        add_body(ui);
-    }
 }
 
 /// Badge label in accent color at small size.
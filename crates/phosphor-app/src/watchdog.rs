@@ -0,0 +1,163 @@
+//! Controller heartbeat watchdog (#2240). A show controller that pings
+//! `/phosphor/heartbeat` over OSC, or sends `heartbeat` over the WebSocket,
+//! arms it; if the pings then stop for longer than the timeout — the
+//! controller crashed, or its network went — the output falls back to black
+//! or a safe preset until they resume. Nothing happens before the first ping,
+//! so a rig with no controller isn't blacked out at launch.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Seconds the output takes to fade to black once the watchdog trips.
+const FADE_OUT_SECS: f32 = 1.0;
+/// Seconds to come back once pings resume.
+const FADE_IN_SECS: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum WatchdogAction {
+    /// Fade the master output to black until pings resume.
+    #[default]
+    Blackout,
+    /// Load the safe preset, and leave it up for the controller to replace.
+    SafePreset,
+}
+
+impl WatchdogAction {
+    pub const ALL: &[WatchdogAction] = &[WatchdogAction::Blackout, WatchdogAction::SafePreset];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            WatchdogAction::Blackout => "Blackout",
+            WatchdogAction::SafePreset => "Safe preset",
+        }
+    }
+}
+
+/// Saved with the settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    /// Seconds without a ping before the action fires.
+    pub timeout_secs: f32,
+    pub action: WatchdogAction,
+    /// Preset name for [`WatchdogAction::SafePreset`].
+    pub safe_preset: String,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout_secs: 10.0,
+            action: WatchdogAction::Blackout,
+            safe_preset: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogEvent {
+    /// The pings stopped: run the action.
+    Tripped,
+    /// They're back.
+    Recovered,
+}
+
+/// Whether the watchdog has tripped, and the blackout fade.
+#[derive(Debug, Default)]
+pub struct Watchdog {
+    tripped: bool,
+    fade: f32,
+}
+
+impl Watchdog {
+    /// Advance one frame of `dt` seconds. `since_ping` is the age of the
+    /// newest heartbeat from any controller, `None` if none has pinged yet.
+    pub fn update(
+        &mut self,
+        config: &WatchdogConfig,
+        since_ping: Option<Duration>,
+        dt: f32,
+    ) -> Option<WatchdogEvent> {
+        let lost = config.enabled
+            && since_ping.is_some_and(|age| age.as_secs_f32() > config.timeout_secs.max(0.0));
+        let event = match (self.tripped, lost) {
+            (false, true) => Some(WatchdogEvent::Tripped),
+            (true, false) => Some(WatchdogEvent::Recovered),
+            _ => None,
+        };
+        self.tripped = lost;
+
+        let dark = lost && config.action == WatchdogAction::Blackout;
+        self.fade = if dark {
+            (self.fade + dt / FADE_OUT_SECS).min(1.0)
+        } else {
+            (self.fade - dt / FADE_IN_SECS).max(0.0)
+        };
+        event
+    }
+
+    /// How far the output is faded out, 0 to 1.
+    pub fn fade(&self) -> f32 {
+        self.fade
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(action: WatchdogAction) -> WatchdogConfig {
+        WatchdogConfig {
+            enabled: true,
+            timeout_secs: 5.0,
+            action,
+            safe_preset: String::new(),
+        }
+    }
+
+    const FRESH: Option<Duration> = Some(Duration::from_secs(1));
+    const STALE: Option<Duration> = Some(Duration::from_secs(6));
+
+    #[test]
+    fn waits_for_the_first_ping() {
+        let mut dog = Watchdog::default();
+        let cfg = config(WatchdogAction::Blackout);
+        assert_eq!(dog.update(&cfg, None, 60.0), None);
+        assert_eq!(dog.fade(), 0.0);
+    }
+
+    #[test]
+    fn trips_when_pings_stop_and_recovers_when_they_resume() {
+        let mut dog = Watchdog::default();
+        let cfg = config(WatchdogAction::Blackout);
+        assert_eq!(dog.update(&cfg, FRESH, 0.1), None);
+        assert_eq!(dog.update(&cfg, STALE, 0.5), Some(WatchdogEvent::Tripped));
+        assert_eq!(dog.update(&cfg, STALE, 0.5), None);
+        assert_eq!(dog.fade(), 1.0);
+        assert_eq!(
+            dog.update(&cfg, FRESH, 0.25),
+            Some(WatchdogEvent::Recovered)
+        );
+        assert_eq!(dog.fade(), 0.5);
+        assert_eq!(dog.update(&cfg, FRESH, 0.25), None);
+        assert_eq!(dog.fade(), 0.0);
+    }
+
+    #[test]
+    fn safe_preset_and_disabled_leave_the_output_up() {
+        let mut dog = Watchdog::default();
+        let cfg = config(WatchdogAction::SafePreset);
+        assert_eq!(dog.update(&cfg, STALE, 1.0), Some(WatchdogEvent::Tripped));
+        assert_eq!(dog.fade(), 0.0);
+
+        let mut dog = Watchdog::default();
+        let off = WatchdogConfig {
+            enabled: false,
+            ..config(WatchdogAction::Blackout)
+        };
+        assert_eq!(dog.update(&off, STALE, 1.0), None);
+    }
+}
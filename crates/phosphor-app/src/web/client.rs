@@ -6,8 +6,9 @@ use crossbeam_channel::{Receiver, Sender};
 use tungstenite::WebSocket;
 use tungstenite::protocol::Message;
 
-use super::types::{SourceFieldInfo, WsInMessage};
+use super::types::{RpcError, SourceFieldInfo, WebAction, WebQuery, WebRequest, WsInMessage};
 use crate::midi::types::TriggerAction;
+use crate::preset::PresetRef;
 
 /// Run the per-client read/write loop.
 /// Reads JSON from the client, sends outbound messages from the broadcast channel.
@@ -15,6 +16,7 @@ pub fn run_client<S: Read + Write>(
     mut ws: WebSocket<S>,
     inbound_tx: Sender<WsInMessage>,
     outbound_rx: Receiver<String>,
+    reply_tx: Sender<String>,
     initial_state: String,
     shutdown: Arc<AtomicBool>,
     client_id: usize,
@@ -40,8 +42,12 @@ pub fn run_client<S: Read + Write>(
         // Try to read a message (50ms timeout)
         match ws.read() {
             Ok(Message::Text(text)) => {
-                if let Some(msg) = parse_client_message(text.as_ref()) {
-                    let _ = inbound_tx.try_send(msg);
+                if let Some(msg) = read_text(text.as_ref(), &reply_tx) {
+                    if let Err(crossbeam_channel::TrySendError::Full(WsInMessage::Request(req))) =
+                        inbound_tx.try_send(msg)
+                    {
+                        req.respond(Err(RpcError::failed("busy, try again")));
+                    }
                 }
             }
             Ok(Message::Close(_)) => {
@@ -96,9 +102,38 @@ pub fn run_client<S: Read + Write>(
     log::info!("WebSocket client {} disconnected", client_id);
 }
 
-/// Parse a JSON message from the client into a WsInMessage.
-fn parse_client_message(text: &str) -> Option<WsInMessage> {
+/// Parse a text frame (#2197). Messages carrying an `id`, and queries,
+/// become requests answered to this client alone; one that doesn't parse
+/// gets its error reply here.
+fn read_text(text: &str, reply_tx: &Sender<String>) -> Option<WsInMessage> {
     let v: serde_json::Value = serde_json::from_str(text).ok()?;
+    let msg = parse_client_value(&v);
+    let id = v
+        .get("id")
+        .filter(|id| id.is_string() || id.is_number())
+        .cloned();
+    let id = match id {
+        Some(id) => id,
+        None if matches!(msg, Some(WsInMessage::Query(_))) => serde_json::Value::Null,
+        None => return msg,
+    };
+    let Some(message) = msg else {
+        let error = RpcError::new(
+            RpcError::INVALID_REQUEST,
+            "unknown message type or missing fields",
+        );
+        let _ = reply_tx.try_send(super::state::build_reply(&id, Err(error)));
+        return None;
+    };
+    Some(WsInMessage::Request(WebRequest {
+        id,
+        message: Box::new(message),
+        reply_to: reply_tx.clone(),
+    }))
+}
+
+/// Parse a JSON message from the client into a WsInMessage.
+pub(super) fn parse_client_value(v: &serde_json::Value) -> Option<WsInMessage> {
     let msg_type = v.get("type")?.as_str()?;
 
     match msg_type {
@@ -159,13 +194,28 @@ fn parse_client_message(text: &str) -> Option<WsInMessage> {
                 "tempo_half" => TriggerAction::TempoHalf,
                 "tempo_double" => TriggerAction::TempoDouble,
                 "tempo_tap" => TriggerAction::TempoTap,
+                "preload_next_preset" => TriggerAction::PreloadNextPreset,
+                "export_loop" => TriggerAction::ExportLoop,
+                "particle_burst" => TriggerAction::ParticleBurst,
+                "blackout" => TriggerAction::Blackout,
+                "freeze_frame" => TriggerAction::FreezeFrame,
+                "shader_compile" => TriggerAction::ShaderCompile,
+                "shader_revert" => TriggerAction::ShaderRevert,
+                "toggle_shader_editor" => TriggerAction::ToggleShaderEditor,
+                "reset_normalization" => TriggerAction::ResetNormalization,
+                "freeze_normalization" => TriggerAction::FreezeNormalization,
+                "surprise_me" => TriggerAction::SurpriseMe,
+                "screenshot" => TriggerAction::Screenshot,
                 _ => return None,
             };
             Some(WsInMessage::Trigger(action))
         }
         "load_preset" => {
-            let index = v.get("index")?.as_u64()? as usize;
-            Some(WsInMessage::LoadPreset { index })
+            let preset = match v.get("preset_id").and_then(serde_json::Value::as_u64) {
+                Some(id) => PresetRef::Id(id),
+                None => PresetRef::Index(v.get("index")?.as_u64()? as usize),
+            };
+            Some(WsInMessage::LoadPreset { preset })
         }
         "set_postprocess_enabled" => {
             let value = v
@@ -174,6 +224,63 @@ fn parse_client_message(text: &str) -> Option<WsInMessage> {
                 .or_else(|| v.get("value")?.as_f64().map(|f| f > 0.5))?;
             Some(WsInMessage::PostProcessEnabled(value))
         }
+        "set_media_playing" => Some(WsInMessage::Action(WebAction::MediaPlaying(flag(v)?))),
+        "media_seek" => {
+            let seconds = v.get("seconds")?.as_f64()?;
+            Some(WsInMessage::Action(WebAction::MediaSeek(seconds.max(0.0))))
+        }
+        "set_media_speed" => {
+            let value = v.get("value")?.as_f64()? as f32;
+            Some(WsInMessage::Action(WebAction::MediaSpeed(
+                value.clamp(0.1, 4.0),
+            )))
+        }
+        "set_media_loop" => Some(WsInMessage::Action(WebAction::MediaLoop(flag(v)?))),
+        "set_media_direction" => {
+            let value = v.get("value")?.as_u64()?;
+            Some(WsInMessage::Action(WebAction::MediaDirection(
+                value.min(2) as u8
+            )))
+        }
+        "set_webcam_mirror" => Some(WsInMessage::Action(WebAction::WebcamMirror(flag(v)?))),
+        "save_preset" => {
+            let name = v.get("name")?.as_str()?.trim();
+            if name.is_empty() {
+                return None;
+            }
+            Some(WsInMessage::Action(WebAction::SavePreset(name.to_string())))
+        }
+        "add_layer" => Some(WsInMessage::Action(WebAction::AddLayer)),
+        "remove_layer" => {
+            let layer = v.get("layer")?.as_u64()? as usize;
+            Some(WsInMessage::Action(WebAction::RemoveLayer(layer)))
+        }
+        "move_layer" => {
+            let from = v.get("from")?.as_u64()? as usize;
+            let to = v.get("to")?.as_u64()? as usize;
+            Some(WsInMessage::Action(WebAction::MoveLayer { from, to }))
+        }
+        "set_postprocess_param" => {
+            let name = v.get("name")?.as_str()?.to_string();
+            let value =
+                v.get("value")?
+                    .as_f64()
+                    .or_else(|| v.get("value")?.as_bool().map(f64::from))? as f32;
+            Some(WsInMessage::Action(WebAction::PostProcessParam {
+                name,
+                value,
+            }))
+        }
+        "set_shader_source" => {
+            let source = v.get("source")?.as_str()?.to_string();
+            Some(WsInMessage::Action(WebAction::ShaderSource(source)))
+        }
+        "get_shader_source" => Some(WsInMessage::Action(WebAction::GetShaderSource)),
+        "heartbeat" => Some(WsInMessage::Heartbeat),
+        "list_effects" => Some(WsInMessage::Query(WebQuery::Effects)),
+        "list_presets" => Some(WsInMessage::Query(WebQuery::Presets)),
+        "list_layers" => Some(WsInMessage::Query(WebQuery::Layers)),
+        "get_state" => Some(WsInMessage::Query(WebQuery::State)),
         "data" => {
             let source = v.get("source")?.as_str()?.to_string();
             let fields_obj = v.get("fields")?.as_object()?;
@@ -218,10 +325,20 @@ fn parse_binary_preview(data: &[u8]) -> Option<WsInMessage> {
     Some(WsInMessage::BindPreview { source, jpeg_data })
 }
 
+/// A `value` given as a bool, or as a number that's on above 0.5.
+fn flag(v: &serde_json::Value) -> Option<bool> {
+    let value = v.get("value")?;
+    value.as_bool().or_else(|| value.as_f64().map(|f| f > 0.5))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn parse_client_message(text: &str) -> Option<WsInMessage> {
+        parse_client_value(&serde_json::from_str(text).ok()?)
+    }
+
     #[test]
     fn parse_set_param() {
         let json = r#"{"type":"set_param","name":"speed","value":0.75}"#;
@@ -330,6 +447,18 @@ mod tests {
             ("tempo_half", TriggerAction::TempoHalf),
             ("tempo_double", TriggerAction::TempoDouble),
             ("tempo_tap", TriggerAction::TempoTap),
+            ("preload_next_preset", TriggerAction::PreloadNextPreset),
+            ("export_loop", TriggerAction::ExportLoop),
+            ("particle_burst", TriggerAction::ParticleBurst),
+            ("blackout", TriggerAction::Blackout),
+            ("freeze_frame", TriggerAction::FreezeFrame),
+            ("shader_compile", TriggerAction::ShaderCompile),
+            ("shader_revert", TriggerAction::ShaderRevert),
+            ("toggle_shader_editor", TriggerAction::ToggleShaderEditor),
+            ("reset_normalization", TriggerAction::ResetNormalization),
+            ("freeze_normalization", TriggerAction::FreezeNormalization),
+            ("surprise_me", TriggerAction::SurpriseMe),
+            ("screenshot", TriggerAction::Screenshot),
         ] {
             let json = format!(r#"{{"type":"trigger","action":"{action_str}"}}"#);
             match parse_client_message(&json) {
@@ -343,7 +472,13 @@ mod tests {
     fn parse_load_preset() {
         let json = r#"{"type":"load_preset","index":5}"#;
         match parse_client_message(json) {
-            Some(WsInMessage::LoadPreset { index }) => assert_eq!(index, 5),
+            Some(WsInMessage::LoadPreset { preset }) => assert_eq!(preset, PresetRef::Index(5)),
+            other => panic!("expected LoadPreset, got {:?}", other),
+        }
+        // An id wins over an index sent alongside it.
+        let json = r#"{"type":"load_preset","preset_id":77,"index":5}"#;
+        match parse_client_message(json) {
+            Some(WsInMessage::LoadPreset { preset }) => assert_eq!(preset, PresetRef::Id(77)),
             other => panic!("expected LoadPreset, got {:?}", other),
         }
     }
@@ -439,4 +574,131 @@ mod tests {
     fn parse_binary_preview_bad_jpeg() {
         assert!(parse_binary_preview(b"src\x00notjpeg").is_none());
     }
+
+    fn action(json: &str) -> WebAction {
+        match parse_client_message(json) {
+            Some(WsInMessage::Action(a)) => a,
+            other => panic!("expected Action, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_media_transport() {
+        assert_eq!(
+            action(r#"{"type":"set_media_playing","value":false}"#),
+            WebAction::MediaPlaying(false)
+        );
+        assert_eq!(
+            action(r#"{"type":"media_seek","seconds":-2.0}"#),
+            WebAction::MediaSeek(0.0)
+        );
+        assert_eq!(
+            action(r#"{"type":"set_media_speed","value":9.0}"#),
+            WebAction::MediaSpeed(4.0)
+        );
+        assert_eq!(
+            action(r#"{"type":"set_media_loop","value":1.0}"#),
+            WebAction::MediaLoop(true)
+        );
+        assert_eq!(
+            action(r#"{"type":"set_media_direction","value":7}"#),
+            WebAction::MediaDirection(2)
+        );
+        assert_eq!(
+            action(r#"{"type":"set_webcam_mirror","value":true}"#),
+            WebAction::WebcamMirror(true)
+        );
+    }
+
+    #[test]
+    fn parse_layer_and_preset_actions() {
+        assert_eq!(action(r#"{"type":"add_layer"}"#), WebAction::AddLayer);
+        assert_eq!(
+            action(r#"{"type":"remove_layer","layer":2}"#),
+            WebAction::RemoveLayer(2)
+        );
+        assert_eq!(
+            action(r#"{"type":"move_layer","from":0,"to":3}"#),
+            WebAction::MoveLayer { from: 0, to: 3 }
+        );
+        assert_eq!(
+            action(r#"{"type":"save_preset","name":" Night "}"#),
+            WebAction::SavePreset("Night".to_string())
+        );
+        assert!(parse_client_message(r#"{"type":"save_preset","name":"  "}"#).is_none());
+    }
+
+    #[test]
+    fn parse_heartbeat() {
+        assert!(matches!(
+            parse_client_message(r#"{"type":"heartbeat"}"#),
+            Some(WsInMessage::Heartbeat)
+        ));
+    }
+
+    #[test]
+    fn parse_postprocess_and_shader_actions() {
+        assert_eq!(
+            action(r#"{"type":"set_postprocess_param","name":"bloom_enabled","value":false}"#),
+            WebAction::PostProcessParam {
+                name: "bloom_enabled".to_string(),
+                value: 0.0
+            }
+        );
+        assert_eq!(
+            action(r#"{"type":"set_shader_source","source":"fn f() {}"}"#),
+            WebAction::ShaderSource("fn f() {}".to_string())
+        );
+        assert_eq!(
+            action(r#"{"type":"get_shader_source"}"#),
+            WebAction::GetShaderSource
+        );
+    }
+
+    #[test]
+    fn requests_carry_id_and_reply_channel() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let msg = read_text(r#"{"id":7,"type":"add_layer"}"#, &tx);
+        match msg {
+            Some(WsInMessage::Request(req)) => {
+                assert_eq!(req.id, 7);
+                assert!(matches!(
+                    *req.message,
+                    WsInMessage::Action(WebAction::AddLayer)
+                ));
+                req.respond(Ok(serde_json::json!({ "index": 1 })));
+            }
+            other => panic!("expected Request, got {other:?}"),
+        }
+        let reply: serde_json::Value = serde_json::from_str(&rx.try_recv().unwrap()).unwrap();
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["result"]["index"], 1);
+
+        // Without an id it stays fire-and-forget, except for queries.
+        assert!(matches!(
+            read_text(r#"{"type":"add_layer"}"#, &tx),
+            Some(WsInMessage::Action(WebAction::AddLayer))
+        ));
+        match read_text(r#"{"type":"list_effects"}"#, &tx) {
+            Some(WsInMessage::Request(req)) => assert!(req.id.is_null()),
+            other => panic!("expected Request, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn bad_request_is_answered_with_an_error() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        assert!(read_text(r#"{"id":"x","type":"remove_layer"}"#, &tx).is_none());
+        assert!(read_text(r#"{"id":"y","type":"no_such_thing"}"#, &tx).is_none());
+        // No id, no reply.
+        assert!(read_text(r#"{"type":"no_such_thing"}"#, &tx).is_none());
+        let replies: Vec<serde_json::Value> = rx
+            .try_iter()
+            .map(|r| serde_json::from_str(&r).unwrap())
+            .collect();
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0]["id"], "x");
+        assert_eq!(replies[1]["type"], "error");
+        assert_eq!(replies[1]["error"]["code"], RpcError::INVALID_REQUEST);
+    }
 }
@@ -0,0 +1,279 @@
+//! Hot standby (#2204): a backup machine follows the primary's web server as
+//! an ordinary client. The `state` and `state_patch` stream carries presets,
+//! layers, params and blackout; one-shot triggers the state can't show are
+//! sent on as `trigger` messages. Both machines then render the same thing,
+//! so the backup's output can be switched to the moment the primary fails.
+
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, anyhow, bail};
+use crossbeam_channel::{Receiver, Sender};
+use serde_json::Value;
+use tungstenite::protocol::Message;
+
+use super::state::apply_state_patch;
+use super::types::WsInMessage;
+use crate::midi::types::TriggerAction;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// Read timeout, so the shutdown flag is seen promptly.
+const READ_TIMEOUT: Duration = Duration::from_millis(200);
+/// The primary sends a full state every few seconds, so this much silence
+/// means it's gone even if the connection hasn't closed.
+const SILENCE_LIMIT: Duration = Duration::from_secs(10);
+const MIN_RETRY: Duration = Duration::from_secs(1);
+const MAX_RETRY: Duration = Duration::from_secs(8);
+
+/// Triggers whose effect doesn't show in the state, so a primary sends them
+/// on to followers as they fire.
+pub fn mirrors(action: TriggerAction) -> bool {
+    matches!(
+        action,
+        TriggerAction::ParticleBurst
+            | TriggerAction::FreezeFrame
+            | TriggerAction::TempoHalf
+            | TriggerAction::TempoDouble
+            | TriggerAction::TempoTap
+    )
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FollowStatus {
+    Connecting,
+    Connected,
+    /// Connection failed or dropped, with why; retried with backoff.
+    Lost(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Status(FollowStatus),
+    State(Value),
+    Trigger(TriggerAction),
+}
+
+/// The follower connection thread and what it has received.
+pub struct Follower {
+    pub status: FollowStatus,
+    rx: Receiver<Event>,
+    shutdown: Arc<AtomicBool>,
+    _thread: Option<JoinHandle<()>>,
+}
+
+impl Follower {
+    /// Start following the primary at `address` (`host` or `host:port`).
+    pub fn start(address: &str) -> Self {
+        let (tx, rx) = crossbeam_channel::bounded(256);
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let host = host_port(address);
+        let flag = shutdown.clone();
+        let spawned = thread::Builder::new()
+            .name("phosphor-web-follower".into())
+            .spawn(move || run(&host, &tx, &flag));
+        let handle = match spawned {
+            Ok(handle) => Some(handle),
+            Err(e) => {
+                log::error!("Failed to start the follower thread: {e}");
+                None
+            }
+        };
+        Self {
+            status: FollowStatus::Connecting,
+            rx,
+            shutdown,
+            _thread: handle,
+        }
+    }
+
+    /// What arrived since the last call: the newest state, if there is one,
+    /// and the triggers in the order they fired.
+    pub fn poll(&mut self) -> (Option<Value>, Vec<TriggerAction>) {
+        let mut state = None;
+        let mut triggers = Vec::new();
+        for event in self.rx.try_iter() {
+            match event {
+                Event::Status(status) => self.status = status,
+                Event::State(value) => state = Some(value),
+                Event::Trigger(action) => triggers.push(action),
+            }
+        }
+        (state, triggers)
+    }
+}
+
+/// Not joined: a thread mid-connect can take a few seconds to notice, and
+/// nothing waits on it.
+impl Drop for Follower {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+/// `host:port` from what the user typed, which may be the primary's control
+/// page URL. The port defaults to the web server's.
+fn host_port(address: &str) -> String {
+    let address = address.trim();
+    let address = ["ws://", "http://"]
+        .iter()
+        .find_map(|scheme| address.strip_prefix(scheme))
+        .unwrap_or(address);
+    let host = address.split('/').next().unwrap_or_default();
+    if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{host}:{}", super::types::WebConfig::default().port)
+    }
+}
+
+fn run(host: &str, tx: &Sender<Event>, shutdown: &AtomicBool) {
+    let mut retry = MIN_RETRY;
+    while !shutdown.load(Ordering::Relaxed) {
+        let _ = tx.try_send(Event::Status(FollowStatus::Connecting));
+        let mut connected = false;
+        match follow(host, tx, shutdown, &mut connected) {
+            Ok(()) => break,
+            Err(e) => {
+                log::warn!("Following {host}: {e:#}");
+                let _ = tx.try_send(Event::Status(FollowStatus::Lost(format!("{e:#}"))));
+            }
+        }
+        if connected {
+            retry = MIN_RETRY;
+        }
+        let wake = Instant::now() + retry;
+        while Instant::now() < wake && !shutdown.load(Ordering::Relaxed) {
+            thread::sleep(READ_TIMEOUT);
+        }
+        retry = (retry * 2).min(MAX_RETRY);
+    }
+}
+
+/// Connect and mirror until shutdown, or until the primary goes away.
+fn follow(
+    host: &str,
+    tx: &Sender<Event>,
+    shutdown: &AtomicBool,
+    connected: &mut bool,
+) -> anyhow::Result<()> {
+    let addr = host
+        .to_socket_addrs()
+        .with_context(|| format!("can't resolve {host}"))?
+        .next()
+        .ok_or_else(|| anyhow!("can't resolve {host}"))?;
+    let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+    let (mut ws, _) = tungstenite::client(format!("ws://{host}/ws"), stream)
+        .map_err(|e| anyhow!("handshake failed: {e}"))?;
+    ws.get_ref().set_read_timeout(Some(READ_TIMEOUT))?;
+    log::info!("Following the primary at {host}");
+    *connected = true;
+    let _ = tx.try_send(Event::Status(FollowStatus::Connected));
+
+    let mut state = None;
+    let mut last_message = Instant::now();
+    while !shutdown.load(Ordering::Relaxed) {
+        match ws.read() {
+            Ok(Message::Text(text)) => {
+                last_message = Instant::now();
+                if let Some(event) = read_message(&mut state, text.as_ref()) {
+                    let _ = tx.try_send(event);
+                }
+            }
+            Ok(Message::Close(_)) => bail!("the primary closed the connection"),
+            Ok(_) => last_message = Instant::now(),
+            Err(tungstenite::Error::Io(ref e))
+                if e.kind() == std::io::ErrorKind::WouldBlock
+                    || e.kind() == std::io::ErrorKind::TimedOut => {}
+            Err(e) => return Err(e.into()),
+        }
+        if last_message.elapsed() > SILENCE_LIMIT {
+            bail!("the primary stopped sending");
+        }
+    }
+    let _ = ws.close(None);
+    Ok(())
+}
+
+/// Fold one message from the primary into `state` and return what the app
+/// should act on. A patch that doesn't fit drops the state until the next
+/// full one arrives.
+fn read_message(state: &mut Option<Value>, text: &str) -> Option<Event> {
+    let v: Value = serde_json::from_str(text).ok()?;
+    match v.get("type")?.as_str()? {
+        "state" => {
+            *state = Some(v);
+            state.clone().map(Event::State)
+        }
+        "state_patch" => {
+            let current = state.as_mut()?;
+            let ops = v.get("patch")?.as_array()?;
+            if apply_state_patch(current, ops) {
+                Some(Event::State(current.clone()))
+            } else {
+                *state = None;
+                None
+            }
+        }
+        "trigger" => match super::client::parse_client_value(&v)? {
+            WsInMessage::Trigger(action) => Some(Event::Trigger(action)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn address_accepts_a_control_page_url() {
+        assert_eq!(host_port("192.168.1.20"), "192.168.1.20:9002");
+        assert_eq!(host_port(" stage-pc:8080 "), "stage-pc:8080");
+        assert_eq!(host_port("http://10.0.0.5:9002/"), "10.0.0.5:9002");
+        assert_eq!(host_port("ws://10.0.0.5/ws"), "10.0.0.5:9002");
+    }
+
+    #[test]
+    fn messages_keep_the_mirrored_state_current() {
+        let mut state = None;
+        let patch = json!({
+            "type": "state_patch",
+            "patch": [{ "op": "replace", "path": "/active_layer", "value": 1 }],
+        })
+        .to_string();
+        // Patches before the first full state are ignored
+        assert_eq!(read_message(&mut state, &patch), None);
+
+        let full = json!({ "type": "state", "active_layer": 0, "layers": [] });
+        assert_eq!(
+            read_message(&mut state, &full.to_string()),
+            Some(Event::State(full))
+        );
+        let Some(Event::State(patched)) = read_message(&mut state, &patch) else {
+            panic!("patch not applied");
+        };
+        assert_eq!(patched["active_layer"], 1);
+
+        let bad = json!({
+            "type": "state_patch",
+            "patch": [{ "op": "replace", "path": "/layers/4/opacity", "value": 1 }],
+        });
+        assert_eq!(read_message(&mut state, &bad.to_string()), None);
+        assert!(state.is_none());
+
+        assert_eq!(
+            read_message(&mut state, r#"{"type":"trigger","action":"tempo_tap"}"#),
+            Some(Event::Trigger(TriggerAction::TempoTap))
+        );
+        assert_eq!(
+            read_message(&mut state, r#"{"type":"audio","rms":0.5}"#),
+            None
+        );
+    }
+}
@@ -1,4 +1,5 @@
 pub mod client;
+pub mod follower;
 pub mod server;
 #[allow(dead_code)]
 pub mod state;
@@ -7,16 +8,23 @@ pub mod types;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::{Receiver, Sender};
 
+use self::follower::{FollowStatus, Follower};
 use self::types::{WebConfig, WebFrameResult, WsInMessage};
 use crate::audio::features::AudioFeatures;
+use crate::midi::types::TriggerAction;
 use std::collections::HashMap;
 
 use crate::params::{ParamDef, ParamValue};
 
+/// How often connected clients get state changes.
+const STATE_INTERVAL: Duration = Duration::from_millis(100);
+/// How often they get the whole state regardless.
+const FULL_STATE_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Central WebSocket system: owns accept thread, client channels, config.
 pub struct WebSystem {
     inbound_rx: Option<Receiver<WsInMessage>>,
@@ -28,12 +36,19 @@ pub struct WebSystem {
     pub config: WebConfig,
     pub client_count: usize,
     pub last_activity: Option<Instant>,
+    /// Last `heartbeat` from any client, for the watchdog (#2240).
+    pub last_heartbeat: Option<Instant>,
     last_audio_broadcast: Instant,
     last_state_broadcast: Instant,
+    /// State as clients last got it; patches are diffed against it (#2199).
+    sent_state: Option<serde_json::Value>,
+    last_full_state: Instant,
     /// Accumulated binding data values from WebSocket clients.
     pub bind_values: std::collections::HashMap<String, f32>,
     /// Preview thumbnail JPEG data from bridge sources.
     pub preview_images: std::collections::HashMap<String, Vec<u8>>,
+    /// Connection to the primary while this instance is its standby (#2204).
+    follower: Option<Follower>,
 }
 
 impl WebSystem {
@@ -51,15 +66,20 @@ impl WebSystem {
             config,
             client_count: 0,
             last_activity: None,
+            last_heartbeat: None,
             last_audio_broadcast: Instant::now(),
             last_state_broadcast: Instant::now(),
+            sent_state: None,
+            last_full_state: Instant::now(),
             bind_values: std::collections::HashMap::new(),
             preview_images: std::collections::HashMap::new(),
+            follower: None,
         };
 
         if sys.config.enabled {
             sys.start_server();
         }
+        sys.restart_follower();
 
         sys
     }
@@ -108,6 +128,8 @@ impl WebSystem {
             clients.clear();
         }
         self.client_count = 0;
+        self.last_heartbeat = None;
+        self.forget_state();
     }
 
     /// Restart server (e.g., after port change).
@@ -129,6 +151,17 @@ impl WebSystem {
         self.config.save();
     }
 
+    /// Stamp `last_activity` unless the batch is only heartbeats, which
+    /// mustn't hold off the idle timer (see `OscSystem::note_activity`).
+    fn note_activity(&mut self, messages: &[WsInMessage]) {
+        if messages
+            .iter()
+            .any(|m| !matches!(m, WsInMessage::Heartbeat))
+        {
+            self.last_activity = Some(Instant::now());
+        }
+    }
+
     /// Whether the server is running.
     pub fn is_running(&self) -> bool {
         self.shutdown
@@ -136,6 +169,43 @@ impl WebSystem {
             .map_or(false, |s| !s.load(Ordering::Relaxed))
     }
 
+    /// Start or stop following the primary at `config.follow_address`.
+    pub fn set_following(&mut self, follow: bool) {
+        self.config.follow = follow;
+        self.restart_follower();
+        self.config.save();
+    }
+
+    /// Reconnect to the configured primary, e.g. after the address changed.
+    pub fn restart_follower(&mut self) {
+        self.follower = None;
+        let address = self.config.follow_address.trim();
+        if self.config.follow && !address.is_empty() {
+            self.follower = Some(Follower::start(address));
+        }
+    }
+
+    /// How following the primary is going, when this instance is a standby.
+    pub fn follow_status(&self) -> Option<&FollowStatus> {
+        self.follower.as_ref().map(|f| &f.status)
+    }
+
+    /// The primary's newest state and the triggers it sent since last frame.
+    pub fn poll_follower(&mut self) -> (Option<serde_json::Value>, Vec<TriggerAction>) {
+        self.follower
+            .as_mut()
+            .map_or((None, Vec::new()), Follower::poll)
+    }
+
+    /// Send a trigger that just fired on to any followers, when it's one
+    /// the state doesn't show.
+    pub fn broadcast_trigger(&self, action: TriggerAction) {
+        if self.client_count > 0 && follower::mirrors(action) {
+            let slug = crate::osc::trigger_slug(&action);
+            self.broadcast_json(&state::build_trigger(slug));
+        }
+    }
+
     /// Main per-frame update. Drains WS messages, returns structured results.
     /// Accepts split-borrowed ParamStore fields to avoid cloning defs.
     pub fn update(
@@ -157,9 +227,10 @@ impl WebSystem {
             return result;
         }
 
-        self.last_activity = Some(Instant::now());
+        self.note_activity(&messages);
 
         for msg in messages {
+            let msg = settle_bind_request(msg);
             match msg {
                 WsInMessage::SetParam { name, value } => {
                     apply_param(param_values, param_changed, param_defs, &name, value);
@@ -185,8 +256,8 @@ impl WebSystem {
                 WsInMessage::Trigger(action) => {
                     result.triggers.push(action);
                 }
-                WsInMessage::LoadPreset { index } => {
-                    result.preset_loads.push(index);
+                WsInMessage::LoadPreset { preset } => {
+                    result.preset_loads.push(preset);
                 }
                 WsInMessage::PostProcessEnabled(enabled) => {
                     result.postprocess_enabled = Some(enabled);
@@ -202,6 +273,17 @@ impl WebSystem {
                 WsInMessage::BindPreview { source, jpeg_data } => {
                     self.preview_images.insert(source, jpeg_data);
                 }
+                WsInMessage::Action(action) => {
+                    result.actions.push(action);
+                }
+                WsInMessage::Heartbeat => {
+                    self.last_heartbeat = Some(Instant::now());
+                }
+                WsInMessage::Request(req) => {
+                    result.requests.push(req);
+                }
+                // Queries only arrive wrapped in a request.
+                WsInMessage::Query(_) => {}
             }
         }
 
@@ -223,9 +305,10 @@ impl WebSystem {
             return result;
         }
 
-        self.last_activity = Some(Instant::now());
+        self.note_activity(&messages);
 
         for msg in messages {
+            let msg = settle_bind_request(msg);
             match msg {
                 WsInMessage::Trigger(action) => {
                     result.triggers.push(action);
@@ -251,12 +334,21 @@ impl WebSystem {
                 WsInMessage::SelectLayer { index } => {
                     result.select_layer = Some(index);
                 }
-                WsInMessage::LoadPreset { index } => {
-                    result.preset_loads.push(index);
+                WsInMessage::LoadPreset { preset } => {
+                    result.preset_loads.push(preset);
                 }
                 WsInMessage::BindPreview { source, jpeg_data } => {
                     self.preview_images.insert(source, jpeg_data);
                 }
+                WsInMessage::Action(action) => {
+                    result.actions.push(action);
+                }
+                WsInMessage::Heartbeat => {
+                    self.last_heartbeat = Some(Instant::now());
+                }
+                WsInMessage::Request(req) => {
+                    result.requests.push(req);
+                }
                 _ => {} // Skip active-layer param application
             }
         }
@@ -292,18 +384,50 @@ impl WebSystem {
         self.broadcast_json(&json);
     }
 
-    /// Update the latest full state and broadcast to clients at 10Hz.
-    /// Stores state for initial sync on new connections, and periodically
-    /// broadcasts to existing clients so MIDI/OSC/egui changes are reflected.
-    pub fn update_latest_state(&mut self, state_json: &str) {
-        if let Ok(mut state) = self.latest_state.lock() {
-            *state = state_json.to_string();
-        }
-        // Broadcast state at 10Hz so external changes (MIDI/OSC/egui) reach web clients
-        if self.client_count > 0 && self.last_state_broadcast.elapsed().as_millis() >= 100 {
-            self.last_state_broadcast = Instant::now();
-            self.broadcast_json(state_json);
+    /// Whether the 10Hz state tick is due. Nothing is built while no client
+    /// is connected (#2200); the first one to arrive gets a full state on the
+    /// next tick.
+    pub fn state_due(&self) -> bool {
+        self.client_count > 0 && self.last_state_broadcast.elapsed() >= STATE_INTERVAL
+    }
+
+    /// Store the latest full state for new clients' initial sync and send
+    /// connected clients what changed since the last tick as a `state_patch`,
+    /// so MIDI/OSC/egui changes reach them. A full `state` still goes out
+    /// every few seconds to resync any client that dropped a patch (#2199).
+    pub fn update_latest_state(&mut self, state: serde_json::Value) {
+        self.publish_state(state, false);
+    }
+
+    /// Send the full state to every client now, e.g. after a preset load or a
+    /// layer list change.
+    pub fn broadcast_state(&mut self, state: serde_json::Value) {
+        self.publish_state(state, true);
+    }
+
+    fn publish_state(&mut self, state: serde_json::Value, full: bool) {
+        self.last_state_broadcast = Instant::now();
+        let full = full
+            || self.sent_state.is_none()
+            || self.last_full_state.elapsed() >= FULL_STATE_INTERVAL;
+        // Held while sending so a client connecting meanwhile gets either the
+        // state before this change and then the patch, or the state after it.
+        let mut latest = self.latest_state.lock().unwrap_or_else(|e| e.into_inner());
+        if full {
+            let json = state.to_string();
+            self.broadcast_json(&json);
+            *latest = json;
+            self.last_full_state = Instant::now();
+        } else if let Some(prev) = &self.sent_state {
+            let patch = state::diff_state(prev, &state);
+            if patch.is_empty() {
+                return;
+            }
+            self.broadcast_json(&state::build_state_patch(patch));
+            *latest = state.to_string();
         }
+        drop(latest);
+        self.sent_state = Some(state);
     }
 
     /// Update client count after broadcast_json prunes disconnected senders.
@@ -311,6 +435,20 @@ impl WebSystem {
         if let Ok(clients) = self.clients.lock() {
             self.client_count = clients.len();
         }
+        if self.client_count == 0 {
+            self.forget_state();
+        }
+    }
+
+    /// Drop the stored state once it stops being kept current, so a client
+    /// connecting later isn't greeted with a stale one; it gets a full state
+    /// on the next tick instead.
+    fn forget_state(&mut self) {
+        if self.sent_state.take().is_some()
+            && let Ok(mut latest) = self.latest_state.lock()
+        {
+            latest.clear();
+        }
     }
 }
 
@@ -321,25 +459,47 @@ impl Drop for WebSystem {
 }
 
 /// Apply a normalized (0-1) float value to a param, scaling to its defined range.
-fn apply_param(
+/// Returns false when there's no float or bool param by that name.
+pub fn apply_param(
     values: &mut HashMap<String, ParamValue>,
     changed: &mut bool,
     defs: &[ParamDef],
     name: &str,
     value: f32,
-) {
-    if let Some(def) = defs.iter().find(|d| d.name() == name) {
-        match def {
-            ParamDef::Float { min, max, .. } => {
-                let val = min + (max - min) * value.clamp(0.0, 1.0);
-                values.insert(name.to_string(), ParamValue::Float(val));
-                *changed = true;
-            }
-            ParamDef::Bool { .. } => {
-                values.insert(name.to_string(), ParamValue::Bool(value > 0.5));
-                *changed = true;
-            }
-            _ => {}
+) -> bool {
+    let Some(def) = defs.iter().find(|d| d.name() == name) else {
+        return false;
+    };
+    match def {
+        ParamDef::Float { min, max, .. } => {
+            let val = min + (max - min) * value.clamp(0.0, 1.0);
+            values.insert(name.to_string(), ParamValue::Float(val));
+        }
+        ParamDef::Bool { .. } => {
+            values.insert(name.to_string(), ParamValue::Bool(value > 0.5));
+        }
+        _ => return false,
+    }
+    *changed = true;
+    true
+}
+
+/// Bridge data and heartbeats sent as a request are acknowledged here and
+/// applied like any other; everything else asked as a request is the app's to answer (#2197).
+fn settle_bind_request(msg: WsInMessage) -> WsInMessage {
+    match msg {
+        WsInMessage::Request(req)
+            if matches!(
+                *req.message,
+                WsInMessage::BindData { .. }
+                    | WsInMessage::BindSchema { .. }
+                    | WsInMessage::BindPreview { .. }
+                    | WsInMessage::Heartbeat
+            ) =>
+        {
+            req.respond(Ok(serde_json::Value::Null));
+            *req.message
         }
+        msg => msg,
     }
 }
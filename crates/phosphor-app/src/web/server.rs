@@ -109,16 +109,18 @@ fn handle_connection(
 
                 // Get latest state for initial sync — recover from poisoned mutex
                 // rather than panicking the accept thread during a live performance.
-                let state = latest_state
-                    .lock()
-                    .unwrap_or_else(|e| e.into_inner())
-                    .clone();
+                // Held until the client is registered, so no state patch
+                // lands between the snapshot and its first broadcast (#2199).
+                let latest = latest_state.lock().unwrap_or_else(|e| e.into_inner());
+                let state = latest.clone();
 
-                // Register client
+                // Register client; requests are answered on the same queue
+                let reply_tx = outbound_tx.clone();
                 clients
                     .lock()
                     .unwrap_or_else(|e| e.into_inner())
                     .push(outbound_tx);
+                drop(latest);
 
                 let tx = inbound_tx.clone();
                 let flag = shutdown.clone();
@@ -126,7 +128,7 @@ fn handle_connection(
                 thread::Builder::new()
                     .name(format!("phosphor-web-client-{client_id}"))
                     .spawn(move || {
-                        client::run_client(ws, tx, outbound_rx, state, flag, client_id);
+                        client::run_client(ws, tx, outbound_rx, reply_tx, state, flag, client_id);
                     })
                     .ok();
             }
@@ -16,7 +16,12 @@ pub struct FullState {
     pub active_layer: usize,
     pub presets: Vec<PresetInfo>,
     pub current_preset: Option<usize>,
+    /// Stable id of the current preset (#2223); the index moves when presets
+    /// are added, renamed or deleted.
+    pub current_preset_id: Option<u64>,
     pub postprocess_enabled: bool,
+    /// Output blackout, so followers match it (#2204).
+    pub blackout: bool,
 }
 
 #[derive(Serialize)]
@@ -36,6 +41,7 @@ pub struct LayerState {
     pub opacity: f32,
     pub enabled: bool,
     pub locked: bool,
+    pub audio_muted: bool,
     pub params: Vec<ParamInfo>,
 }
 
@@ -49,11 +55,18 @@ pub struct ParamInfo {
     pub min: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max: Option<f64>,
+    /// The effect's own explanation of the input (#2227).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
 }
 
 #[derive(Serialize)]
 pub struct PresetInfo {
     pub index: usize,
+    /// What `load_preset` should send (#2223).
+    pub id: u64,
     pub name: String,
     pub builtin: bool,
 }
@@ -130,6 +143,16 @@ pub struct PresetsChanged {
     pub current: Option<usize>,
 }
 
+/// Shader editor text, sent in reply to `get_shader_source` (#2196).
+#[derive(Serialize)]
+pub struct ShaderSource<'a> {
+    #[serde(rename = "type")]
+    pub msg_type: &'static str,
+    pub effect_name: &'a str,
+    pub file_name: &'a str,
+    pub source: &'a str,
+}
+
 // -- Builders --
 
 /// Per-layer tuple: (params, effect_index, blend_mode, opacity, enabled, locked).
@@ -142,7 +165,8 @@ pub fn build_full_state(
     layers: &[LayerTuple<'_>],
     preset_store: &PresetStore,
     postprocess_enabled: bool,
-) -> String {
+    blackout: bool,
+) -> serde_json::Value {
     let effect_list: Vec<EffectInfo> = effects
         .iter()
         .enumerate()
@@ -180,6 +204,7 @@ pub fn build_full_state(
                 opacity: info.opacity,
                 enabled: info.enabled,
                 locked: info.locked,
+                audio_muted: info.audio_muted,
                 params,
             }
         })
@@ -189,8 +214,9 @@ pub fn build_full_state(
         .presets
         .iter()
         .enumerate()
-        .map(|(i, (name, _))| PresetInfo {
+        .map(|(i, (name, preset))| PresetInfo {
             index: i,
+            id: preset.id,
             name: name.clone(),
             builtin: preset_store.is_builtin(i),
         })
@@ -203,10 +229,132 @@ pub fn build_full_state(
         active_layer,
         presets,
         current_preset: preset_store.current_preset,
+        current_preset_id: preset_store
+            .current_preset
+            .and_then(|i| preset_store.presets.get(i))
+            .map(|(_, p)| p.id),
         postprocess_enabled,
+        blackout,
     };
 
-    serde_json::to_string(&state).unwrap_or_default()
+    serde_json::to_value(&state).unwrap_or_default()
+}
+
+/// The changes taking `prev` to `next` as RFC 6902 JSON Patch operations
+/// (#2199). Objects are compared key by key and same-length arrays index by
+/// index; anything else that differs is replaced whole. Empty when equal.
+pub fn diff_state(prev: &serde_json::Value, next: &serde_json::Value) -> Vec<serde_json::Value> {
+    let mut ops = Vec::new();
+    diff_into(prev, next, &mut String::new(), &mut ops);
+    ops
+}
+
+fn diff_into(
+    prev: &serde_json::Value,
+    next: &serde_json::Value,
+    path: &mut String,
+    ops: &mut Vec<serde_json::Value>,
+) {
+    use serde_json::{Value, json};
+    if prev == next {
+        return;
+    }
+    let len = path.len();
+    match (prev, next) {
+        (Value::Object(a), Value::Object(b)) => {
+            for key in a.keys().filter(|k| !b.contains_key(*k)) {
+                push_token(path, key);
+                ops.push(json!({ "op": "remove", "path": path }));
+                path.truncate(len);
+            }
+            for (key, value) in b {
+                push_token(path, key);
+                match a.get(key) {
+                    Some(old) => diff_into(old, value, path, ops),
+                    None => ops.push(json!({ "op": "add", "path": path, "value": value })),
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (i, (old, value)) in a.iter().zip(b).enumerate() {
+                push_token(path, &i.to_string());
+                diff_into(old, value, path, ops);
+                path.truncate(len);
+            }
+        }
+        _ => ops.push(json!({ "op": "replace", "path": path, "value": next })),
+    }
+}
+
+/// Append `/token` to a JSON Pointer, escaping `~` and `/`.
+fn push_token(path: &mut String, token: &str) {
+    path.push('/');
+    path.push_str(&token.replace('~', "~0").replace('/', "~1"));
+}
+
+/// Apply `diff_state` operations to `state` in place (#2204). Returns false,
+/// leaving `state` partly patched, when a path doesn't exist in it; the
+/// caller should wait for the next full state.
+pub fn apply_state_patch(state: &mut serde_json::Value, ops: &[serde_json::Value]) -> bool {
+    use serde_json::Value;
+    for op in ops {
+        let (Some(kind), Some(path)) = (
+            op.get("op").and_then(Value::as_str),
+            op.get("path").and_then(Value::as_str),
+        ) else {
+            return false;
+        };
+        let (parent, token) = match path.rfind('/') {
+            Some(i) => (
+                &path[..i],
+                path[i + 1..].replace("~1", "/").replace("~0", "~"),
+            ),
+            // The root itself
+            None => {
+                let Some(value) = op.get("value") else {
+                    return false;
+                };
+                *state = value.clone();
+                continue;
+            }
+        };
+        let Some(target) = state.pointer_mut(parent) else {
+            return false;
+        };
+        let applied = match (kind, target) {
+            ("remove", Value::Object(map)) => map.remove(&token).is_some(),
+            ("add" | "replace", Value::Object(map)) => op
+                .get("value")
+                .map(|v| map.insert(token, v.clone()))
+                .is_some(),
+            ("replace", Value::Array(items)) => {
+                match (token.parse::<usize>().ok(), op.get("value")) {
+                    (Some(i), Some(v)) if i < items.len() => {
+                        items[i] = v.clone();
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        };
+        if !applied {
+            return false;
+        }
+    }
+    true
+}
+
+/// A `state_patch` message carrying `diff_state` operations.
+pub fn build_state_patch(ops: Vec<serde_json::Value>) -> String {
+    serde_json::json!({ "type": "state_patch", "patch": ops }).to_string()
+}
+
+/// A trigger fired on this machine, sent on to followers (#2204) in the
+/// same form clients send triggers.
+pub fn build_trigger(slug: &str) -> String {
+    serde_json::json!({ "type": "trigger", "action": slug }).to_string()
 }
 
 pub fn build_audio_snapshot(f: &AudioFeatures) -> String {
@@ -237,6 +385,33 @@ pub fn build_active_layer_changed(index: usize) -> String {
     .unwrap_or_default()
 }
 
+pub fn build_shader_source(effect_name: &str, file_name: &str, source: &str) -> String {
+    serde_json::to_string(&ShaderSource {
+        msg_type: "shader_source",
+        effect_name,
+        file_name,
+        source,
+    })
+    .unwrap_or_default()
+}
+
+/// Reply to a request (#2197): `{"type":"response","id","result"}` on
+/// success, `{"type":"error","id","error":{"code","message"}}` otherwise.
+pub fn build_reply(
+    id: &serde_json::Value,
+    outcome: Result<serde_json::Value, super::types::RpcError>,
+) -> String {
+    let reply = match outcome {
+        Ok(result) => serde_json::json!({ "type": "response", "id": id, "result": result }),
+        Err(e) => serde_json::json!({
+            "type": "error",
+            "id": id,
+            "error": { "code": e.code, "message": e.message },
+        }),
+    };
+    reply.to_string()
+}
+
 pub fn build_layer_changed(info: &LayerInfo, index: usize) -> String {
     serde_json::to_string(&LayerChanged {
         msg_type: "layer_changed",
@@ -272,8 +447,9 @@ pub fn build_presets_changed(preset_store: &PresetStore) -> String {
         .presets
         .iter()
         .enumerate()
-        .map(|(i, (name, _))| PresetInfo {
+        .map(|(i, (name, preset))| PresetInfo {
             index: i,
+            id: preset.id,
             name: name.clone(),
             builtin: preset_store.is_builtin(i),
         })
@@ -292,6 +468,8 @@ fn build_params(store: &ParamStore) -> Vec<ParamInfo> {
         .iter()
         .map(|def| {
             let value = store.values.get(def.name());
+            let description = def.description().map(str::to_string);
+            let unit = def.unit().map(str::to_string);
             match def {
                 ParamDef::Float { name, min, max, .. } => {
                     let v = match value {
@@ -311,6 +489,8 @@ fn build_params(store: &ParamStore) -> Vec<ParamInfo> {
                         value: normalized as f64,
                         min: Some(*min as f64),
                         max: Some(*max as f64),
+                        description,
+                        unit,
                     }
                 }
                 ParamDef::Bool { name, .. } => {
@@ -324,6 +504,8 @@ fn build_params(store: &ParamStore) -> Vec<ParamInfo> {
                         value: v,
                         min: None,
                         max: None,
+                        description,
+                        unit,
                     }
                 }
                 ParamDef::Color { name, .. } => ParamInfo {
@@ -332,6 +514,8 @@ fn build_params(store: &ParamStore) -> Vec<ParamInfo> {
                     value: 0.0,
                     min: None,
                     max: None,
+                    description,
+                    unit,
                 },
                 ParamDef::Point2D { name, .. } => ParamInfo {
                     name: name.clone(),
@@ -339,6 +523,8 @@ fn build_params(store: &ParamStore) -> Vec<ParamInfo> {
                     value: 0.0,
                     min: None,
                     max: None,
+                    description,
+                    unit,
                 },
             }
         })
@@ -378,6 +564,90 @@ mod tests {
         assert_eq!(v["index"], 3);
     }
 
+    #[test]
+    fn shader_source_message() {
+        let json = build_shader_source("Aurora", "aurora.wgsl", "fn f() {}\n");
+        let v: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(v["type"], "shader_source");
+        assert_eq!(v["file_name"], "aurora.wgsl");
+        assert_eq!(v["source"], "fn f() {}\n");
+    }
+
+    #[test]
+    fn reply_messages() {
+        use super::super::types::RpcError;
+        let id = serde_json::json!("a1");
+        let ok: serde_json::Value =
+            serde_json::from_str(&build_reply(&id, Ok(serde_json::json!({ "index": 4 })))).unwrap();
+        assert_eq!(ok["type"], "response");
+        assert_eq!(ok["id"], "a1");
+        assert_eq!(ok["result"]["index"], 4);
+
+        let err: serde_json::Value = serde_json::from_str(&build_reply(
+            &serde_json::json!(9),
+            Err(RpcError::locked(2)),
+        ))
+        .unwrap();
+        assert_eq!(err["type"], "error");
+        assert_eq!(err["id"], 9);
+        assert_eq!(err["error"]["code"], RpcError::LOCKED);
+        assert_eq!(err["error"]["message"], "layer 2 is locked");
+    }
+
+    #[test]
+    fn state_diff_is_a_json_patch() {
+        use serde_json::json;
+        let prev = json!({
+            "active_layer": 0,
+            "layers": [{ "opacity": 1.0, "params": [{ "name": "a/b", "value": 0.5 }] }],
+            "presets": ["x"],
+            "gone": true,
+        });
+        assert!(diff_state(&prev, &prev).is_empty());
+
+        let next = json!({
+            "active_layer": 1,
+            "layers": [{ "opacity": 1.0, "params": [{ "name": "a/b", "value": 0.25 }] }],
+            "presets": ["x", "y"],
+            "new~": 3,
+        });
+        assert_eq!(
+            diff_state(&prev, &next),
+            vec![
+                json!({ "op": "remove", "path": "/gone" }),
+                json!({ "op": "replace", "path": "/active_layer", "value": 1 }),
+                json!({ "op": "replace", "path": "/layers/0/params/0/value", "value": 0.25 }),
+                json!({ "op": "add", "path": "/new~0", "value": 3 }),
+                json!({ "op": "replace", "path": "/presets", "value": ["x", "y"] }),
+            ]
+        );
+
+        let v: serde_json::Value = serde_json::from_str(&build_state_patch(vec![])).unwrap();
+        assert_eq!(v["type"], "state_patch");
+        assert_eq!(v["patch"], json!([]));
+    }
+
+    #[test]
+    fn state_patch_applies_back() {
+        use serde_json::json;
+        let prev = json!({
+            "active_layer": 0,
+            "gone": true,
+            "layers": [{ "opacity": 1.0, "params": [{ "name": "a/b", "value": 0.5 }] }],
+        });
+        let next = json!({
+            "active_layer": 1,
+            "layers": [{ "opacity": 0.5, "params": [{ "name": "a/b", "value": 0.25 }] }],
+            "new~": [1, 2],
+        });
+        let mut state = prev.clone();
+        assert!(apply_state_patch(&mut state, &diff_state(&prev, &next)));
+        assert_eq!(state, next);
+
+        let missing = [json!({ "op": "replace", "path": "/layers/3/opacity", "value": 1 })];
+        assert!(!apply_state_patch(&mut state, &missing));
+    }
+
     #[test]
     fn build_params_float_normalized() {
         let mut store = ParamStore::new();
@@ -386,6 +656,9 @@ mod tests {
             default: 0.5,
             min: 0.0,
             max: 1.0,
+            group: None,
+            description: None,
+            unit: None,
         }];
         store.load_from_defs(&defs);
         store.set("x", ParamValue::Float(0.75));
@@ -396,12 +669,32 @@ mod tests {
         assert!((params[0].value - 0.75).abs() < 0.01);
     }
 
+    #[test]
+    fn build_params_carry_description_and_unit() {
+        let mut store = ParamStore::new();
+        let defs = vec![ParamDef::Float {
+            name: "rate".into(),
+            default: 2.0,
+            min: 0.0,
+            max: 8.0,
+            group: None,
+            description: Some("Pulses per second".into()),
+            unit: Some("Hz".into()),
+        }];
+        store.load_from_defs(&defs);
+        let json = serde_json::to_value(build_params(&store)).unwrap();
+        assert_eq!(json[0]["description"], "Pulses per second");
+        assert_eq!(json[0]["unit"], "Hz");
+    }
+
     #[test]
     fn build_params_bool() {
         let mut store = ParamStore::new();
         let defs = vec![ParamDef::Bool {
             name: "flag".into(),
             default: false,
+            group: None,
+            description: None,
         }];
         store.load_from_defs(&defs);
         store.set("flag", ParamValue::Bool(true));
@@ -418,6 +711,8 @@ mod tests {
         let defs = vec![ParamDef::Color {
             name: "tint".into(),
             default: [1.0, 0.0, 0.0, 1.0],
+            group: None,
+            description: None,
         }];
         store.load_from_defs(&defs);
         let params = build_params(&store);
@@ -435,6 +730,9 @@ mod tests {
             default: [0.5, 0.5],
             min: [0.0, 0.0],
             max: [1.0, 1.0],
+            group: None,
+            description: None,
+            unit: None,
         }];
         store.load_from_defs(&defs);
         let params = build_params(&store);
@@ -450,6 +748,9 @@ mod tests {
             default: 5.0,
             min: 5.0,
             max: 5.0,
+            group: None,
+            description: None,
+            unit: None,
         }];
         store.load_from_defs(&defs);
         let params = build_params(&store);
@@ -476,6 +777,12 @@ mod tests {
             media_is_animated: false,
             media_is_video: false,
             media_is_live: false,
+            generator: None,
+            trail: 0.0,
+            audio_muted: false,
+            suspended: None,
+            envelope: Default::default(),
+            envelope_gain: 1.0,
         };
         let json = build_layer_changed(&info, 2);
         let v: serde_json::Value = serde_json::from_str(&json).unwrap();
@@ -507,6 +814,8 @@ mod tests {
             active_layer: 0,
             postprocess: PostProcessDef::default(),
             volumetric: None,
+            master: None,
+            id: 0,
         };
         store.presets.push(("Crucible".into(), empty.clone()));
         store.presets.push(("My Preset".into(), empty));
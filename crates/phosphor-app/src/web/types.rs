@@ -1,8 +1,10 @@
 use std::path::PathBuf;
 
+use crossbeam_channel::Sender;
 use serde::{Deserialize, Serialize};
 
 use crate::midi::types::TriggerAction;
+use crate::preset::PresetRef;
 
 /// Inbound message from a WebSocket client.
 #[derive(Debug, Clone)]
@@ -27,8 +29,8 @@ pub enum WsInMessage {
     SetLayerEnabled { layer: usize, value: bool },
     /// Fire a trigger action.
     Trigger(TriggerAction),
-    /// Load a preset by index.
-    LoadPreset { index: usize },
+    /// Load a preset by id, or by index for older clients (#2223).
+    LoadPreset { preset: PresetRef },
     /// Toggle post-processing.
     PostProcessEnabled(bool),
     /// Binding data: source name + field values.
@@ -44,6 +46,127 @@ pub enum WsInMessage {
     },
     /// Preview thumbnail image (JPEG) from a bridge source.
     BindPreview { source: String, jpeg_data: Vec<u8> },
+    /// Controller is alive, for the watchdog (#2240).
+    Heartbeat,
+    /// Something the app applies itself, in arrival order.
+    Action(WebAction),
+    /// Read-only question, always answered as a request.
+    Query(WebQuery),
+    /// A message sent with an `id`, answered on its own to the sender.
+    Request(WebRequest),
+}
+
+/// What a client can ask about without changing anything (#2197).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebQuery {
+    Effects,
+    Presets,
+    Layers,
+    State,
+}
+
+/// A message the client wants a reply to (#2197). Requests skip the
+/// per-frame batching and are applied one by one, so each reply reports
+/// what that message did, or why it was refused.
+#[derive(Debug, Clone)]
+pub struct WebRequest {
+    /// Echoed back as given: a JSON string or number, or null.
+    pub id: serde_json::Value,
+    pub message: Box<WsInMessage>,
+    /// The sending client's outbound queue.
+    pub reply_to: Sender<String>,
+}
+
+impl WebRequest {
+    /// Send the outcome back as a `response` or `error` message.
+    pub fn respond(&self, outcome: Result<serde_json::Value, RpcError>) {
+        let json = super::state::build_reply(&self.id, outcome);
+        let _ = self.reply_to.try_send(json);
+    }
+}
+
+/// Why a request was refused. Codes below zero follow JSON-RPC 2.0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl RpcError {
+    /// Unknown `type`, or a missing or mistyped field.
+    pub const INVALID_REQUEST: i32 = -32600;
+    /// An index or name that doesn't exist.
+    pub const NOT_FOUND: i32 = 1;
+    /// The target layer is locked.
+    pub const LOCKED: i32 = 2;
+    /// Understood, but the app couldn't do it.
+    pub const FAILED: i32 = 3;
+
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(Self::NOT_FOUND, message)
+    }
+
+    pub fn locked(layer: usize) -> Self {
+        Self::new(Self::LOCKED, format!("layer {layer} is locked"))
+    }
+
+    pub fn failed(message: impl Into<String>) -> Self {
+        Self::new(Self::FAILED, message)
+    }
+}
+
+/// Media, layer, preset, post-processing and shader editor messages (#2196),
+/// giving web clients the same reach as the egui panels. Kept in order
+/// because layer adds, removes and moves shift the indices that follow.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebAction {
+    /// Play or pause the active media layer.
+    MediaPlaying(bool),
+    /// Seek the active media layer to a time in seconds.
+    MediaSeek(f64),
+    /// Active media layer playback speed (0.1-4.0).
+    MediaSpeed(f32),
+    MediaLoop(bool),
+    /// Active media layer direction: 0 forward, 1 reverse, 2 ping-pong.
+    MediaDirection(u8),
+    /// Mirror the active webcam layer.
+    WebcamMirror(bool),
+    /// Save the current layers as a preset under this name.
+    SavePreset(String),
+    AddLayer,
+    RemoveLayer(usize),
+    MoveLayer {
+        from: usize,
+        to: usize,
+    },
+    /// Set a post-processing setting on the active layer, by field name.
+    PostProcessParam {
+        name: String,
+        value: f32,
+    },
+    /// Replace the text in the shader editor's open tab, opening the active
+    /// effect's shader first if the editor is closed. `shader_compile`
+    /// saves it.
+    ShaderSource(String),
+    /// Reply with the shader editor's text as a `shader_source` message.
+    GetShaderSource,
+}
+
+impl WebAction {
+    /// Whether clients need the full state again afterwards.
+    pub fn changes_layers(&self) -> bool {
+        matches!(
+            self,
+            Self::AddLayer | Self::RemoveLayer(_) | Self::MoveLayer { .. } | Self::SavePreset(_)
+        )
+    }
 }
 
 /// Metadata for a WebSocket source field.
@@ -71,7 +194,9 @@ pub struct WebFrameResult {
     pub postprocess_enabled: Option<bool>,
     pub effect_loads: Vec<usize>,
     pub select_layer: Option<usize>,
-    pub preset_loads: Vec<usize>,
+    pub preset_loads: Vec<PresetRef>,
+    pub actions: Vec<WebAction>,
+    pub requests: Vec<WebRequest>,
 }
 
 impl WebFrameResult {
@@ -86,6 +211,8 @@ impl WebFrameResult {
             effect_loads: Vec::new(),
             select_layer: None,
             preset_loads: Vec::new(),
+            actions: Vec::new(),
+            requests: Vec::new(),
         }
     }
 }
@@ -97,6 +224,12 @@ pub struct WebConfig {
     pub enabled: bool,
     #[serde(default = "default_port")]
     pub port: u16,
+    /// Mirror another instance as its hot standby (#2204).
+    #[serde(default)]
+    pub follow: bool,
+    /// The primary to follow: `host` or `host:port`.
+    #[serde(default)]
+    pub follow_address: String,
 }
 
 fn default_true() -> bool {
@@ -111,14 +244,15 @@ impl Default for WebConfig {
         Self {
             enabled: true,
             port: 9002,
+            follow: false,
+            follow_address: String::new(),
         }
     }
 }
 
 impl WebConfig {
     pub fn config_path() -> PathBuf {
-        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-        config_dir.join("phosphor").join("web.json")
+        crate::paths::config_dir().join("web.json")
     }
 
     pub fn load() -> Self {
@@ -142,21 +276,8 @@ impl WebConfig {
     }
 
     pub fn save(&self) {
-        let path = Self::config_path();
-        if let Some(parent) = path.parent() {
-            if let Err(e) = std::fs::create_dir_all(parent) {
-                log::error!("Failed to create config dir: {e}");
-                return;
-            }
-        }
         match serde_json::to_string_pretty(self) {
-            Ok(json) => {
-                if let Err(e) = std::fs::write(&path, json) {
-                    log::error!("Failed to write web config: {e}");
-                } else {
-                    log::debug!("Saved web config to {}", path.display());
-                }
-            }
+            Ok(json) => crate::persist::write(Self::config_path(), json),
             Err(e) => log::error!("Failed to serialize web config: {e}"),
         }
     }
@@ -194,6 +315,8 @@ mod tests {
         assert!(r.effect_loads.is_empty());
         assert!(r.select_layer.is_none());
         assert!(r.preset_loads.is_empty());
+        assert!(r.actions.is_empty());
+        assert!(r.requests.is_empty());
     }
 
     // ---- Additional tests ----
@@ -203,6 +326,7 @@ mod tests {
         let c = WebConfig {
             enabled: false,
             port: 8080,
+            ..WebConfig::default()
         };
         let json = serde_json::to_string(&c).unwrap();
         let c2: WebConfig = serde_json::from_str(&json).unwrap();
@@ -216,5 +340,6 @@ mod tests {
         let c: WebConfig = serde_json::from_str(json).unwrap();
         assert_eq!(c.port, 3000);
         assert!(c.enabled); // default true
+        assert!(!c.follow);
     }
 }